@@ -1,4 +1,5 @@
 use auto_update::{AutoUpdateStatus, AutoUpdater, DismissErrorMessage};
+use client::ClientSettings;
 use editor::Editor;
 use extension::ExtensionStore;
 use futures::StreamExt;
@@ -9,6 +10,7 @@ use gpui::{
 };
 use language::{LanguageRegistry, LanguageServerBinaryStatus, LanguageServerName};
 use project::{LanguageServerProgress, Project};
+use settings::{Settings, SettingsStore};
 use smallvec::SmallVec;
 use std::{cmp::Reverse, fmt::Write, sync::Arc};
 use ui::prelude::*;
@@ -18,6 +20,7 @@ actions!(activity_indicator, [ShowErrorMessage]);
 
 const DOWNLOAD_ICON: &str = "icons/download.svg";
 const WARNING_ICON: &str = "icons/warning.svg";
+const OFFLINE_ICON: &str = "icons/disconnected.svg";
 
 pub enum Event {
     ShowError { lsp_name: Arc<str>, error: String },
@@ -69,6 +72,8 @@ impl ActivityIndicator {
             })
             .detach();
             cx.observe(&project, |_, _, cx| cx.notify()).detach();
+            cx.observe_global::<SettingsStore>(|_, cx| cx.notify())
+                .detach();
 
             if let Some(auto_updater) = auto_updater.as_ref() {
                 cx.observe(auto_updater, |_, _, cx| cx.notify()).detach();
@@ -263,6 +268,16 @@ impl ActivityIndicator {
             };
         }
 
+        if ClientSettings::get_global(cx).offline {
+            return Content {
+                icon: Some(OFFLINE_ICON),
+                message: "Offline".to_string(),
+                on_click: Some(Arc::new(|_, cx| {
+                    cx.dispatch_action(Box::new(zed_actions::ToggleOfflineMode));
+                })),
+            };
+        }
+
         // Show any application auto-update info.
         if let Some(updater) = &self.auto_updater {
             return match &updater.read(cx).status() {
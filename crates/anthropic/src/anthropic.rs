@@ -88,6 +88,8 @@ pub struct Request {
     pub stream: bool,
     pub system: String,
     pub max_tokens: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq)]
@@ -6,7 +6,9 @@ use std::time::Duration;
 use anyhow::{anyhow, Result};
 use fs::Fs;
 use gpui::{AsyncAppContext, ModelContext, Task, WeakModel};
+use language::language_settings::AllLanguageSettings;
 use project::{Project, ProjectPath};
+use settings::{Settings, SettingsLocation};
 use util::ResultExt;
 
 use crate::ambient_context::ContextUpdated;
@@ -158,8 +160,17 @@ impl CurrentProjectContext {
                     .ok_or_else(|| anyhow!("no worktree"))
             })??;
 
-            let path_to_cargo_toml = worktree.update(cx, |worktree, _cx| {
+            let path_to_cargo_toml = worktree.update(cx, |worktree, cx| {
                 let cargo_toml = worktree.entry_for_path("Cargo.toml")?;
+                let location = SettingsLocation {
+                    worktree_id: worktree.id().to_usize(),
+                    path: &cargo_toml.path,
+                };
+                if !AllLanguageSettings::get(Some(location), cx)
+                    .assistant_context_enabled_for_path(&cargo_toml.path)
+                {
+                    return None;
+                }
                 Some(ProjectPath {
                     worktree_id: worktree.id(),
                     path: cargo_toml.path.clone(),
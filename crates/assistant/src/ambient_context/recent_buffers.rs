@@ -1,6 +1,6 @@
 use crate::{assistant_panel::Conversation, LanguageModelRequestMessage, Role};
 use gpui::{ModelContext, Subscription, Task, WeakModel};
-use language::{Buffer, BufferSnapshot, Rope};
+use language::{language_settings::assistant_context_enabled, Buffer, BufferSnapshot, Rope};
 use std::{fmt::Write, path::PathBuf, time::Duration};
 
 use super::ContextUpdated;
@@ -34,15 +34,18 @@ impl RecentBuffersContext {
             .buffers
             .iter()
             .filter_map(|recent| {
+                // Buffers whose contents mustn't be sent to the assistant are left out.
                 let (full_path, snapshot) = recent
                     .buffer
                     .read_with(cx, |buffer, cx| {
-                        (
-                            buffer.file().map(|file| file.full_path(cx)),
-                            buffer.snapshot(),
-                        )
+                        assistant_context_enabled(buffer.language(), buffer.file(), cx).then(|| {
+                            (
+                                buffer.file().map(|file| file.full_path(cx)),
+                                buffer.snapshot(),
+                            )
+                        })
                     })
-                    .ok()?;
+                    .ok()??;
                 Some(SourceBufferSnapshot {
                     full_path,
                     model: recent.buffer.clone(),
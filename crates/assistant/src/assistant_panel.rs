@@ -34,8 +34,9 @@ use gpui::{
     WeakModel, WeakView, WhiteSpace, WindowContext,
 };
 use language::{
-    language_settings::SoftWrap, AutoindentMode, Buffer, BufferSnapshot, LanguageRegistry,
-    OffsetRangeExt as _, Point, ToOffset as _,
+    language_settings::{assistant_context_enabled, SoftWrap},
+    AutoindentMode, Buffer, BufferSnapshot, LanguageRegistry, OffsetRangeExt as _, Point,
+    ToOffset as _,
 };
 use multi_buffer::MultiBufferRow;
 use parking_lot::Mutex;
@@ -302,6 +303,10 @@ impl AssistantPanel {
         } else {
             return;
         };
+        // Only the editors of the workspace, unlike those of conversations, can show files.
+        if show_include_conversation && !selection_context_enabled(&active_editor, workspace, cx) {
+            return;
+        }
         let project = workspace.project().clone();
 
         if assistant.update(cx, |assistant, cx| assistant.is_authenticated(cx)) {
@@ -2844,6 +2849,9 @@ impl ConversationEditor {
         else {
             return;
         };
+        if !selection_context_enabled(&editor, workspace, cx) {
+            return;
+        }
 
         let editor = editor.read(cx);
         let range = editor.selections.newest::<usize>(cx).range();
@@ -3470,6 +3478,38 @@ struct PendingInlineAssist {
     project: WeakModel<Project>,
 }
 
+/// Returns whether the buffers of the editor's newest selection may be attached to the
+/// assistant's context, telling the user when they can't.
+fn selection_context_enabled(
+    editor: &View<Editor>,
+    workspace: &mut Workspace,
+    cx: &mut ViewContext<Workspace>,
+) -> bool {
+    let editor = editor.read(cx);
+    let range = editor.selections.newest::<usize>(cx).range();
+    let enabled = editor
+        .buffer()
+        .read(cx)
+        .range_to_buffer_ranges(range, cx)
+        .into_iter()
+        .all(|(buffer, _, _)| {
+            let buffer = buffer.read(cx);
+            assistant_context_enabled(buffer.language(), buffer.file(), cx)
+        });
+
+    if !enabled {
+        struct AssistantContextExcluded;
+        workspace.show_toast(
+            Toast::new(
+                NotificationId::unique::<AssistantContextExcluded>(),
+                "This file is excluded from the assistant's context by your settings.",
+            ),
+            cx,
+        );
+    }
+    enabled
+}
+
 fn merge_ranges(ranges: &mut Vec<Range<Anchor>>, buffer: &MultiBufferSnapshot) {
     ranges.sort_unstable_by(|a, b| {
         a.start
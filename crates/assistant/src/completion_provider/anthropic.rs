@@ -212,6 +212,7 @@ impl AnthropicCompletionProvider {
             stream: true,
             system: system_message,
             max_tokens: 4092,
+            temperature: None,
         }
     }
 }
@@ -1,8 +1,12 @@
 mod assistant_settings;
 mod attachments;
 mod completion_provider;
+mod context_budget;
+mod inline_assist;
+mod prompt_library;
 mod saved_conversation;
 mod saved_conversations;
+mod slash_commands;
 mod tools;
 pub mod ui;
 
@@ -11,34 +15,47 @@ use crate::ui::UserOrAssistant;
 use ::ui::{div, prelude::*, Color, Tooltip, ViewContext};
 use anyhow::{Context, Result};
 use assistant_tooling::{
-    AttachmentRegistry, ProjectContext, ToolFunctionCall, ToolRegistry, UserAttachment,
+    AttachmentRegistry, LanguageModelAttachment, ProjectContext, ToolFunctionCall, ToolRegistry,
+    UserAttachment,
+};
+use attachments::{
+    ActiveEditorAttachment, ActiveEditorAttachmentTool, DiagnosticsAttachmentTool,
+    SelectionAttachmentTool, SlashCommandAttachmentTool, TerminalOutputAttachmentTool,
 };
-use attachments::ActiveEditorAttachmentTool;
 use client::{proto, Client, UserStore};
 use collections::HashMap;
 use completion_provider::*;
-use editor::Editor;
+use editor::{Editor, EditorEvent};
 use feature_flags::FeatureFlagAppExt as _;
 use file_icons::FileIcons;
 use fs::Fs;
 use futures::{future::join_all, StreamExt};
 use gpui::{
-    list, AnyElement, AppContext, AsyncWindowContext, ClickEvent, EventEmitter, FocusHandle,
-    FocusableView, ListAlignment, ListState, Model, ReadGlobal, Render, Task, UpdateGlobal, View,
-    WeakView,
+    list, AnyElement, AppContext, AsyncWindowContext, ClickEvent, DismissEvent, EventEmitter,
+    FocusHandle, FocusableView, ListAlignment, ListState, Model, ReadGlobal, Render, Subscription,
+    Task, UpdateGlobal, View, WeakView,
 };
+use inline_assist::InlineAssistant;
 use language::{language_settings::SoftWrap, LanguageRegistry};
 use markdown::{Markdown, MarkdownStyle};
 use open_ai::{FunctionContent, ToolCall, ToolCallContent};
+use picker::Picker;
+use prompt_library::{PromptContext, PromptTemplate};
 use saved_conversation::{SavedAssistantMessagePart, SavedChatMessage, SavedConversation};
-use saved_conversations::SavedConversations;
+use saved_conversations::{SavedConversationEvent, SavedConversations};
 use semantic_index::{CloudEmbeddingProvider, ProjectIndex, ProjectIndexDebugView, SemanticIndex};
 use serde::{Deserialize, Serialize};
 use settings::Settings;
-use std::sync::Arc;
-use tools::{AnnotationTool, CreateBufferTool, ProjectIndexTool};
-use ui::{ActiveFileButton, Composer, ProjectIndexButton};
-use util::paths::CONVERSATIONS_DIR;
+use slash_commands::{SlashCommandCompletionProvider, SlashCommandInvocation};
+use std::{path::PathBuf, sync::Arc, time::Duration};
+use tools::{
+    AnnotationTool, CreateBufferTool, DiagnosticsTool, ListDirectoryTool, ProjectIndexTool,
+    ReadFileTool, RunTerminalCommandTool, SearchProjectTool,
+};
+use ui::{
+    ActiveFileButton, AddContextMenu, Composer, FileCandidate, FileContextPickerDelegate,
+    ProjectIndexButton,
+};
 use util::{maybe, paths::EMBEDDINGS_DIR, ResultExt};
 use workspace::{
     dock::{DockPosition, Panel, PanelEvent},
@@ -49,6 +66,10 @@ pub use assistant_settings::AssistantSettings;
 
 const MAX_COMPLETION_CALLS_PER_SUBMISSION: usize = 5;
 
+/// How long to wait after the conversation or the message being composed changes before
+/// counting its tokens again, so that typing is counted once.
+const TOKEN_COUNT_DELAY: Duration = Duration::from_millis(300);
+
 #[derive(Eq, PartialEq, Copy, Clone, Deserialize)]
 pub struct Submit(SubmitMode);
 
@@ -63,7 +84,16 @@ pub enum SubmitMode {
     Codebase,
 }
 
-gpui::actions!(assistant2, [Cancel, ToggleFocus, DebugProjectIndex,]);
+gpui::actions!(
+    assistant2,
+    [
+        Cancel,
+        CancelGeneration,
+        ToggleFocus,
+        DebugProjectIndex,
+        InlineAssist,
+    ]
+);
 gpui::impl_actions!(assistant2, [Submit]);
 
 pub fn init(client: Arc<Client>, cx: &mut AppContext) {
@@ -84,15 +114,14 @@ pub fn init(client: Arc<Client>, cx: &mut AppContext) {
     })
     .detach();
 
-    cx.set_global(CompletionProvider::new(CloudCompletionProvider::new(
-        client,
-    )));
+    cx.set_global(CompletionProvider::new(client));
 
     cx.observe_new_views(
         |workspace: &mut Workspace, _cx: &mut ViewContext<Workspace>| {
             workspace.register_action(|workspace, _: &ToggleFocus, cx| {
                 workspace.toggle_panel_focus::<AssistantPanel>(cx);
             });
+            workspace.register_action(InlineAssistant::deploy);
             workspace.register_action(|workspace, _: &DebugProjectIndex, cx| {
                 if let Some(panel) = workspace.panel::<AssistantPanel>(cx) {
                     let index = panel.read(cx).chat.read(cx).project_index.clone();
@@ -145,10 +174,40 @@ impl AssistantPanel {
                 tool_registry
                     .register(AnnotationTool::new(workspace.clone(), project.clone()))
                     .unwrap();
+                tool_registry
+                    .register(SearchProjectTool::new(project.clone()))
+                    .unwrap();
+                tool_registry
+                    .register(ReadFileTool::new(project.clone()))
+                    .unwrap();
+                tool_registry
+                    .register(ListDirectoryTool::new(project.clone()))
+                    .unwrap();
+                tool_registry
+                    .register(DiagnosticsTool::new(project.clone()))
+                    .unwrap();
+                tool_registry
+                    .register(RunTerminalCommandTool::new(
+                        workspace.clone(),
+                        project.clone(),
+                    ))
+                    .unwrap();
 
                 let mut attachment_registry = AttachmentRegistry::new();
                 attachment_registry
                     .register(ActiveEditorAttachmentTool::new(workspace.clone(), cx));
+                attachment_registry.register(SelectionAttachmentTool::new(workspace.clone(), cx));
+                attachment_registry.register(DiagnosticsAttachmentTool::new(project.clone(), cx));
+                attachment_registry
+                    .register(TerminalOutputAttachmentTool::new(workspace.clone(), cx));
+                attachment_registry.register(SlashCommandAttachmentTool);
+                // These are only attached when they're added to a message from the composer.
+                attachment_registry.set_attachment_tool_enabled::<SelectionAttachmentTool>(false);
+                attachment_registry.set_attachment_tool_enabled::<DiagnosticsAttachmentTool>(false);
+                attachment_registry
+                    .set_attachment_tool_enabled::<TerminalOutputAttachmentTool>(false);
+                attachment_registry
+                    .set_attachment_tool_enabled::<SlashCommandAttachmentTool>(false);
 
                 Self::new(
                     project.read(cx).fs().clone(),
@@ -251,7 +310,7 @@ impl FocusableView for AssistantPanel {
 }
 
 pub struct AssistantChat {
-    model: String,
+    model: LanguageModel,
     messages: Vec<ChatMessage>,
     list_state: ListState,
     fs: Arc<dyn Fs>,
@@ -259,17 +318,31 @@ pub struct AssistantChat {
     composer_editor: View<Editor>,
     saved_conversations: View<SavedConversations>,
     saved_conversations_open: bool,
+    /// Where the conversation is saved, once it has been, or where it was loaded from.
+    conversation_path: Option<PathBuf>,
+    /// The title the conversation was given, which is otherwise its first message.
+    conversation_title: Option<String>,
+    /// Identifies the conversation that's shown, so that saving or loading one that's since been
+    /// replaced doesn't affect the new one.
+    conversation_id: usize,
     project_index_button: View<ProjectIndexButton>,
     active_file_button: Option<View<ActiveFileButton>>,
     user_store: Model<UserStore>,
+    workspace: WeakView<Workspace>,
     next_message_id: MessageId,
     collapsed_messages: HashMap<MessageId, bool>,
     editing_message: Option<EditingMessage>,
     pending_completion: Option<Task<()>>,
+    /// The tokens that the conversation would take up if the message being composed were sent.
+    token_count: Option<usize>,
+    count_tokens_task: Option<Task<()>>,
     tool_registry: Arc<ToolRegistry>,
     attachment_registry: Arc<AttachmentRegistry>,
+    /// The context that the user has added to the message they're composing.
+    pending_attachments: Vec<UserAttachment>,
     project_index: Model<ProjectIndex>,
     markdown_style: MarkdownStyle,
+    _subscriptions: Vec<Subscription>,
 }
 
 struct EditingMessage {
@@ -289,7 +362,7 @@ impl AssistantChat {
         workspace: WeakView<Workspace>,
         cx: &mut ViewContext<Self>,
     ) -> Self {
-        let model = CompletionProvider::global(cx).default_model();
+        let model = CompletionProvider::global(cx).default_model(cx);
         let view = cx.view().downgrade();
         let list_state = ListState::new(
             0,
@@ -314,37 +387,41 @@ impl AssistantChat {
             _ => None,
         };
 
-        let saved_conversations = cx.new_view(|cx| SavedConversations::new(cx));
-        cx.spawn({
-            let fs = fs.clone();
-            let saved_conversations = saved_conversations.downgrade();
-            |_assistant_chat, mut cx| async move {
-                let saved_conversation_metadata = SavedConversationMetadata::list(fs).await?;
-
-                cx.update(|cx| {
-                    saved_conversations.update(cx, |this, cx| {
-                        this.init(saved_conversation_metadata, cx);
-                    })
-                })??;
-
-                anyhow::Ok(())
-            }
-        })
-        .detach_and_log_err(cx);
+        let composer_editor = cx.new_view(|cx| {
+            let mut editor = Editor::auto_height(80, cx);
+            editor.set_soft_wrap_mode(SoftWrap::EditorWidth, cx);
+            editor.set_placeholder_text("Send a message, or type / for a command…", cx);
+            editor.set_completion_provider(Box::new(SlashCommandCompletionProvider::new(
+                project_index.read(cx).project(),
+            )));
+            editor
+        });
+        let saved_conversations = cx.new_view(|cx| SavedConversations::new(fs.clone(), cx));
+        let subscriptions = vec![
+            cx.subscribe(&composer_editor, |this, _, event: &EditorEvent, cx| {
+                if let EditorEvent::BufferEdited = event {
+                    this.count_tokens(cx);
+                }
+            }),
+            cx.subscribe(&saved_conversations, Self::handle_saved_conversation_event),
+            cx.subscribe(&saved_conversations, |this, _, _: &DismissEvent, cx| {
+                this.saved_conversations_open = false;
+                cx.notify();
+            }),
+        ];
 
-        Self {
+        let this = Self {
             model,
             messages: Vec::new(),
-            composer_editor: cx.new_view(|cx| {
-                let mut editor = Editor::auto_height(80, cx);
-                editor.set_soft_wrap_mode(SoftWrap::EditorWidth, cx);
-                editor.set_placeholder_text("Send a message…", cx);
-                editor
-            }),
+            composer_editor,
             saved_conversations,
             saved_conversations_open: false,
+            conversation_path: None,
+            conversation_title: None,
+            conversation_id: 0,
             list_state,
             user_store,
+            workspace,
             fs,
             language_registry,
             project_index_button,
@@ -354,7 +431,10 @@ impl AssistantChat {
             editing_message: None,
             collapsed_messages: HashMap::default(),
             pending_completion: None,
+            token_count: None,
+            count_tokens_task: None,
             attachment_registry,
+            pending_attachments: Vec::new(),
             tool_registry,
             markdown_style: MarkdownStyle {
                 code_block: gpui::TextStyleRefinement {
@@ -392,7 +472,60 @@ impl AssistantChat {
                     selection
                 },
             },
+            _subscriptions: subscriptions,
+        };
+        this.refresh_saved_conversations(cx);
+        this
+    }
+
+    fn set_model(&mut self, model: LanguageModel, cx: &mut ViewContext<Self>) {
+        self.model = model;
+        self.count_tokens(cx);
+        cx.notify();
+    }
+
+    /// Counts the tokens that the conversation would take up if the message being composed were
+    /// sent with the context attached to it, after a short delay.
+    fn count_tokens(&mut self, cx: &mut ViewContext<Self>) {
+        self.count_tokens_task = Some(cx.spawn(|this, mut cx| async move {
+            cx.background_executor().timer(TOKEN_COUNT_DELAY).await;
+            let token_count = this.update(&mut cx, |this, cx| {
+                let messages = this.completion_messages(cx);
+                let composed_messages = this.composed_messages(cx);
+                let model = this.model.clone();
+                cx.spawn(|_, mut cx| async move {
+                    let mut messages = messages.await?;
+                    messages.extend(composed_messages);
+                    cx.update(|cx| {
+                        CompletionProvider::global(cx).count_tokens(&model, messages, cx)
+                    })?
+                    .await
+                })
+            })?;
+            let token_count = token_count.await.log_err();
+            this.update(&mut cx, |this, cx| {
+                this.token_count = token_count;
+                cx.notify();
+            })
+        }));
+    }
+
+    /// The messages that sending the message being composed would add, which are its attached
+    /// context followed by its text.
+    fn composed_messages(&self, cx: &mut WindowContext) -> Vec<CompletionMessage> {
+        let project_index = self.project_index.read(cx);
+        let mut project_context = ProjectContext::new(project_index.project(), project_index.fs());
+        let mut messages = self
+            .pending_attachments
+            .iter()
+            .filter_map(|attachment| attachment.generate(&mut project_context, cx))
+            .map(|content| CompletionMessage::System { content })
+            .collect::<Vec<_>>();
+        let text = self.composer_editor.read(cx).text(cx);
+        if !text.is_empty() {
+            messages.push(CompletionMessage::User { content: text });
         }
+        messages
     }
 
     fn message_for_id(&self, id: MessageId) -> Option<&ChatMessage> {
@@ -402,8 +535,53 @@ impl AssistantChat {
         })
     }
 
-    fn toggle_saved_conversations(&mut self) {
+    fn toggle_saved_conversations(&mut self, cx: &mut ViewContext<Self>) {
         self.saved_conversations_open = !self.saved_conversations_open;
+        if self.saved_conversations_open {
+            self.saved_conversations.focus_handle(cx).focus(cx);
+        } else {
+            self.composer_editor.focus_handle(cx).focus(cx);
+        }
+        cx.notify();
+    }
+
+    fn refresh_saved_conversations(&self, cx: &mut ViewContext<Self>) {
+        let fs = self.fs.clone();
+        let saved_conversations = self.saved_conversations.downgrade();
+        cx.spawn(|_, mut cx| async move {
+            let saved_conversation_metadata = SavedConversationMetadata::list(fs).await?;
+            saved_conversations.update(&mut cx, |this, cx| {
+                this.set_conversations(saved_conversation_metadata, cx);
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn handle_saved_conversation_event(
+        &mut self,
+        _: View<SavedConversations>,
+        event: &SavedConversationEvent,
+        cx: &mut ViewContext<Self>,
+    ) {
+        match event {
+            SavedConversationEvent::Open(path) => self.open_conversation(path.clone(), cx),
+            SavedConversationEvent::Renamed {
+                old_path,
+                new_path,
+                title,
+            } => {
+                if self.conversation_path.as_ref() == Some(old_path) {
+                    self.conversation_path = Some(new_path.clone());
+                    self.conversation_title = Some(title.clone());
+                }
+            }
+            SavedConversationEvent::Deleted(path) => {
+                if self.conversation_path.as_ref() == Some(path) {
+                    self.stop_generation(cx);
+                    self.reset(cx);
+                }
+            }
+        }
     }
 
     fn cancel(&mut self, _: &Cancel, cx: &mut ViewContext<Self>) {
@@ -413,28 +591,51 @@ impl AssistantChat {
             return;
         }
 
-        if self.pending_completion.take().is_some() {
-            if let Some(ChatMessage::Assistant(grouping)) = self.messages.last() {
-                if grouping.messages.is_empty() {
-                    self.pop_message(cx);
-                }
-            }
+        if self.stop_generation(cx) {
+            self.save_conversation(cx).detach_and_log_err(cx);
             return;
         }
 
         cx.propagate();
     }
 
+    fn cancel_generation(&mut self, _: &CancelGeneration, cx: &mut ViewContext<Self>) {
+        if self.stop_generation(cx) {
+            self.save_conversation(cx).detach_and_log_err(cx);
+        } else {
+            cx.propagate();
+        }
+    }
+
+    /// Stops the response that's being generated, if there is one, keeping what's been received
+    /// of it. Dropping the pending completion drops its stream, which aborts the request.
+    fn stop_generation(&mut self, cx: &mut ViewContext<Self>) -> bool {
+        if self.pending_completion.take().is_none() {
+            return false;
+        }
+        if let Some(ChatMessage::Assistant(grouping)) = self.messages.last() {
+            let is_empty = grouping.messages.iter().all(|message| {
+                message.body.read(cx).source().is_empty() && message.tool_calls.is_empty()
+            });
+            if is_empty {
+                self.pop_message(cx);
+            }
+        }
+        cx.notify();
+        true
+    }
+
     fn submit(&mut self, Submit(mode): &Submit, cx: &mut ViewContext<Self>) {
+        let mut pending_attachments = Vec::new();
+        let mut invocations = Vec::new();
         if self.composer_editor.focus_handle(cx).is_focused(cx) {
-            // Don't allow multiple concurrent completions.
-            if self.pending_completion.is_some() {
-                cx.propagate();
-                return;
-            }
+            // Don't allow multiple concurrent completions, so a message that's sent while a
+            // response is generated stops it.
+            self.stop_generation(cx);
 
             let message = self.composer_editor.update(cx, |composer_editor, cx| {
-                let text = composer_editor.text(cx);
+                let (text, commands) = slash_commands::parse_message(&composer_editor.text(cx));
+                invocations = commands;
                 let id = self.next_message_id.post_inc();
                 let body = cx.new_view(|cx| {
                     Markdown::new(
@@ -453,6 +654,7 @@ impl AssistantChat {
                 })
             });
             self.push_message(message, cx);
+            pending_attachments = std::mem::take(&mut self.pending_attachments);
         } else if let Some(editing_message) = self.editing_message.as_ref() {
             let focus_handle = editing_message.body.focus_handle(cx);
             if focus_handle.contains_focused(cx) {
@@ -480,20 +682,34 @@ impl AssistantChat {
 
         let mode = *mode;
         self.pending_completion = Some(cx.spawn(move |this, mut cx| async move {
+            let slash_command_tasks = this.update(&mut cx, |this, cx| {
+                this.resolve_slash_commands(&invocations, cx)
+            });
             let attachments_task = this.update(&mut cx, |this, cx| {
                 let attachment_registry = this.attachment_registry.clone();
                 attachment_registry.call_all_attachment_tools(cx)
             });
 
-            let attachments = maybe!(async {
-                let attachments_task = attachments_task?;
-                let attachments = attachments_task.await?;
+            let mut attachments = pending_attachments;
+            if let Ok(slash_command_tasks) = slash_command_tasks {
+                attachments.extend(
+                    join_all(slash_command_tasks)
+                        .await
+                        .into_iter()
+                        .filter_map(|attachment| attachment.log_err()),
+                );
+            }
+            attachments.extend(
+                maybe!(async {
+                    let attachments_task = attachments_task?;
+                    let attachments = attachments_task.await?;
 
-                anyhow::Ok(attachments)
-            })
-            .await
-            .log_err()
-            .unwrap_or_default();
+                    anyhow::Ok(attachments)
+                })
+                .await
+                .log_err()
+                .unwrap_or_default(),
+            );
 
             // Set the attachments to the _last_ user message
             this.update(&mut cx, |this, _cx| {
@@ -512,14 +728,37 @@ impl AssistantChat {
             .await
             .log_err();
 
-            this.update(&mut cx, |this, _cx| {
+            this.update(&mut cx, |this, cx| {
                 this.pending_completion = None;
+                this.count_tokens(cx);
+                this.save_conversation(cx).detach_and_log_err(cx);
             })
             .context("Failed to push new user message")
             .log_err();
         }));
     }
 
+    fn resolve_slash_commands(
+        &self,
+        invocations: &[SlashCommandInvocation],
+        cx: &mut ViewContext<Self>,
+    ) -> Vec<Task<Result<UserAttachment>>> {
+        let Some(project) = self.project_index.read(cx).project().upgrade() else {
+            return Vec::new();
+        };
+        invocations
+            .iter()
+            .map(|invocation| {
+                invocation.resolve(
+                    project.clone(),
+                    self.workspace.clone(),
+                    self.attachment_registry.clone(),
+                    cx,
+                )
+            })
+            .collect()
+    }
+
     async fn request_completion(
         this: WeakView<Self>,
         mode: SubmitMode,
@@ -529,34 +768,43 @@ impl AssistantChat {
         let mut call_count = 0;
         loop {
             let complete = async {
-                let (tool_definitions, model_name, messages) = this.update(cx, |this, cx| {
-                    this.push_new_assistant_message(cx);
-
-                    let definitions = if call_count < limit
-                        && matches!(mode, SubmitMode::Codebase | SubmitMode::Simple)
-                    {
-                        this.tool_registry.definitions()
-                    } else {
-                        Vec::new()
-                    };
-                    call_count += 1;
+                let (tool_definitions, model, truncation, messages) =
+                    this.update(cx, |this, cx| {
+                        this.push_new_assistant_message(cx);
 
-                    (
-                        definitions,
-                        this.model.clone(),
-                        this.completion_messages(cx),
-                    )
-                })?;
+                        let definitions = if call_count < limit
+                            && matches!(mode, SubmitMode::Codebase | SubmitMode::Simple)
+                        {
+                            this.tool_registry.definitions()
+                        } else {
+                            Vec::new()
+                        };
+                        call_count += 1;
+
+                        (
+                            definitions,
+                            this.model.clone(),
+                            AssistantSettings::get_global(cx).truncation,
+                            this.completion_messages(cx),
+                        )
+                    })?;
 
                 let messages = messages.await?;
+                let messages =
+                    context_budget::fit_to_context_window(messages, &model, truncation, cx).await?;
 
                 let completion = cx.update(|cx| {
-                    CompletionProvider::global(cx).complete(
-                        model_name,
-                        messages,
-                        Vec::new(),
-                        1.0,
-                        tool_definitions,
+                    CompletionProvider::global(cx).clone().complete(
+                        &model,
+                        CompletionRequest {
+                            model: model.name.clone(),
+                            messages,
+                            stop: Vec::new(),
+                            temperature: 1.0,
+                            max_tokens: None,
+                            tools: tool_definitions,
+                        },
+                        cx,
                     )
                 });
 
@@ -690,6 +938,7 @@ impl AssistantChat {
                 tool_calls: Vec::new(),
             }],
             error: None,
+            model: Some(self.model.clone()),
         });
         self.push_message(message, cx);
     }
@@ -700,6 +949,7 @@ impl AssistantChat {
         self.messages.push(message);
         self.list_state
             .splice_focusable(old_len..old_len, focus_handle);
+        self.count_tokens(cx);
         cx.notify();
     }
 
@@ -721,6 +971,7 @@ impl AssistantChat {
         }) {
             self.list_state.splice(index + 1..self.messages.len(), 0);
             self.messages.truncate(index + 1);
+            self.count_tokens(cx);
             cx.notify();
         }
     }
@@ -734,59 +985,300 @@ impl AssistantChat {
         *entry = !*entry;
     }
 
-    fn reset(&mut self) {
+    fn reset(&mut self, cx: &mut ViewContext<Self>) {
         self.messages.clear();
         self.list_state.reset(0);
         self.editing_message.take();
         self.collapsed_messages.clear();
+        self.conversation_path = None;
+        self.conversation_title = None;
+        self.conversation_id += 1;
+        self.count_tokens(cx);
+        cx.notify();
     }
 
     fn new_conversation(&mut self, cx: &mut ViewContext<Self>) {
-        let messages = std::mem::take(&mut self.messages)
-            .into_iter()
-            .map(|message| self.serialize_message(message, cx))
-            .collect::<Vec<_>>();
+        self.stop_generation(cx);
+        self.save_conversation(cx).detach_and_log_err(cx);
+        self.reset(cx);
+    }
 
-        self.reset();
+    fn title(&self, cx: &AppContext) -> String {
+        if let Some(title) = self.conversation_title.as_ref() {
+            return title.clone();
+        }
 
-        let title = messages
+        self.messages
             .first()
             .map(|message| match message {
-                SavedChatMessage::User { body, .. } => body.clone(),
-                SavedChatMessage::Assistant { messages, .. } => messages
+                ChatMessage::User(message) => message.body.read(cx).source().to_string(),
+                ChatMessage::Assistant(message) => message
+                    .messages
                     .first()
-                    .map(|message| message.body.to_string())
+                    .map(|message| message.body.read(cx).source().to_string())
                     .unwrap_or_default(),
             })
-            .unwrap_or_else(|| "A conversation with the assistant.".to_string());
+            .unwrap_or_else(|| "A conversation with the assistant.".to_string())
+    }
+
+    /// Saves the conversation where it was last saved or loaded from, or at a new path when it
+    /// hasn't been, then lists the saved conversations again. Empty conversations aren't saved.
+    fn save_conversation(&mut self, cx: &mut ViewContext<Self>) -> Task<Result<()>> {
+        if self.messages.is_empty() {
+            return Task::ready(Ok(()));
+        }
 
         let saved_conversation = SavedConversation {
             version: "0.3.0".to_string(),
-            title,
-            messages,
+            title: self.title(cx),
+            model: Some(self.model.clone()),
+            messages: self
+                .messages
+                .iter()
+                .map(|message| self.serialize_message(message, cx))
+                .collect(),
         };
+        let path = self.conversation_path.clone();
+        let conversation_id = self.conversation_id;
+        let fs = self.fs.clone();
+        cx.spawn(|this, mut cx| async move {
+            let path = saved_conversation.save(fs.as_ref(), path).await?;
+            this.update(&mut cx, |this, cx| {
+                if this.conversation_id == conversation_id {
+                    this.conversation_path = Some(path);
+                }
+                this.refresh_saved_conversations(cx);
+            })
+        })
+    }
 
-        let discriminant = 1;
+    /// Replaces the conversation with a saved one, which is only read from disk once it's opened.
+    fn open_conversation(&mut self, path: PathBuf, cx: &mut ViewContext<Self>) {
+        self.saved_conversations_open = false;
+        self.composer_editor.focus_handle(cx).focus(cx);
+        cx.notify();
+        if self.conversation_path.as_ref() == Some(&path) {
+            return;
+        }
 
-        let path = CONVERSATIONS_DIR.join(&format!(
-            "{title} - {discriminant}.zed.{version}.json",
-            title = saved_conversation.title,
-            version = saved_conversation.version
-        ));
+        self.new_conversation(cx);
+        let conversation_id = self.conversation_id;
+        let fs = self.fs.clone();
+        cx.spawn(|this, mut cx| async move {
+            let saved_conversation = SavedConversation::load(fs.as_ref(), &path).await?;
+            this.update(&mut cx, |this, cx| {
+                if this.conversation_id == conversation_id {
+                    this.load_conversation(path, saved_conversation, cx);
+                }
+            })
+        })
+        .detach_and_log_err(cx);
+    }
 
-        cx.spawn({
-            let fs = self.fs.clone();
-            |_this, _cx| async move {
-                fs.create_dir(CONVERSATIONS_DIR.as_ref()).await?;
-                fs.atomic_write(path, serde_json::to_string(&saved_conversation)?)
-                    .await?;
+    fn load_conversation(
+        &mut self,
+        path: PathBuf,
+        saved_conversation: SavedConversation,
+        cx: &mut ViewContext<Self>,
+    ) {
+        for message in saved_conversation.messages {
+            let message = match message {
+                SavedChatMessage::User {
+                    id,
+                    body,
+                    attachments,
+                } => ChatMessage::User(UserMessage {
+                    id,
+                    body: self.new_markdown(body, cx),
+                    attachments: attachments
+                        .into_iter()
+                        .filter_map(|attachment| {
+                            self.attachment_registry
+                                .deserialize_user_attachment(attachment, cx)
+                                .log_err()
+                        })
+                        .collect(),
+                }),
+                SavedChatMessage::Assistant {
+                    id,
+                    messages,
+                    error,
+                    model,
+                } => ChatMessage::Assistant(AssistantMessage {
+                    id,
+                    messages: messages
+                        .into_iter()
+                        .map(|message| AssistantMessagePart {
+                            body: self.new_markdown(message.body.to_string(), cx),
+                            tool_calls: message
+                                .tool_calls
+                                .iter()
+                                .filter_map(|tool_call| {
+                                    self.tool_registry
+                                        .deserialize_tool_call(tool_call, cx)
+                                        .log_err()
+                                })
+                                .collect(),
+                        })
+                        .collect(),
+                    error,
+                    model,
+                }),
+            };
+            let id = match &message {
+                ChatMessage::User(message) => message.id,
+                ChatMessage::Assistant(message) => message.id,
+            };
+            self.next_message_id = self.next_message_id.max(MessageId(id.0 + 1));
+            self.push_message(message, cx);
+        }
 
-                anyhow::Ok(())
+        if let Some(model) = saved_conversation.model {
+            if CompletionProvider::global(cx)
+                .provider(&model.provider)
+                .is_some()
+            {
+                self.set_model(model, cx);
             }
+        }
+        self.conversation_path = Some(path);
+        self.conversation_title = Some(saved_conversation.title);
+    }
+
+    /// Inserts a prompt from the library into the composer, with its variables expanded from
+    /// the active editor's selection.
+    fn insert_prompt(&mut self, prompt: PromptTemplate, cx: &mut ViewContext<Self>) {
+        let fs = self.fs.clone();
+        let workspace = self.workspace.clone();
+        cx.spawn(|this, mut cx| async move {
+            let template = prompt.load(fs.as_ref()).await?;
+            let context = workspace.update(&mut cx, |workspace, cx| {
+                PromptContext::for_workspace(workspace, cx)
+            })?;
+            this.update(&mut cx, |this, cx| {
+                this.composer_editor.update(cx, |editor, cx| {
+                    editor.insert(&context.expand(&template), cx);
+                });
+                this.composer_editor.focus_handle(cx).focus(cx);
+            })
         })
         .detach_and_log_err(cx);
     }
 
+    fn edit_prompt(&mut self, path: PathBuf, cx: &mut ViewContext<Self>) {
+        self.workspace
+            .update(cx, |workspace, cx| {
+                workspace
+                    .open_abs_path(path, true, cx)
+                    .detach_and_log_err(cx);
+            })
+            .log_err();
+    }
+
+    fn new_prompt(&mut self, cx: &mut ViewContext<Self>) {
+        let fs = self.fs.clone();
+        cx.spawn(|this, mut cx| async move {
+            let path = PromptTemplate::create(fs.as_ref()).await?;
+            this.update(&mut cx, |this, cx| this.edit_prompt(path, cx))
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Runs an attachment tool for the message that's being composed, which is sent with its
+    /// output unless it's removed first.
+    fn add_context<A: LanguageModelAttachment + 'static>(&mut self, cx: &mut ViewContext<Self>) {
+        let attachment = self.attachment_registry.call::<A>(cx);
+        cx.spawn(|this, mut cx| async move {
+            let attachment = attachment.await?;
+            this.update(&mut cx, |this, cx| {
+                this.pending_attachments.push(attachment);
+                this.count_tokens(cx);
+                cx.notify();
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Lets the user pick a file of the project to attach to the message that's being composed.
+    fn pick_file(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        let project = workspace.read(cx).project().read(cx);
+        let mut files = Vec::new();
+        for worktree in project.visible_worktrees(cx) {
+            let worktree = worktree.read(cx);
+            let root_name = worktree.root_name();
+            let abs_path = worktree.abs_path();
+            for entry in worktree.files(false, 0) {
+                files.push(FileCandidate {
+                    display_path: format!("{root_name}/{}", entry.path.display()),
+                    abs_path: abs_path.join(&entry.path),
+                });
+            }
+        }
+
+        let assistant_chat = cx.view().downgrade();
+        workspace.update(cx, |workspace, cx| {
+            workspace.toggle_modal(cx, |cx| {
+                let delegate = FileContextPickerDelegate::new(assistant_chat, files);
+                Picker::uniform_list(delegate, cx)
+            });
+        });
+    }
+
+    fn attach_file(&mut self, abs_path: PathBuf, cx: &mut ViewContext<Self>) {
+        if let Some(attachment) = self
+            .attachment_registry
+            .attach::<ActiveEditorAttachmentTool>(ActiveEditorAttachment::for_path(abs_path), cx)
+            .log_err()
+        {
+            self.pending_attachments.push(attachment);
+            self.composer_editor.focus_handle(cx).focus(cx);
+            self.count_tokens(cx);
+            cx.notify();
+        }
+    }
+
+    fn remove_pending_attachment(&mut self, ix: usize, cx: &mut ViewContext<Self>) {
+        if ix < self.pending_attachments.len() {
+            self.pending_attachments.remove(ix);
+            self.count_tokens(cx);
+            cx.notify();
+        }
+    }
+
+    fn render_pending_attachments(&self, cx: &mut ViewContext<Self>) -> Vec<AnyElement> {
+        self.pending_attachments
+            .iter()
+            .enumerate()
+            .map(|(ix, attachment)| {
+                h_flex()
+                    .child(attachment.view.clone())
+                    .child(
+                        IconButton::new(("remove-attachment", ix), IconName::Close)
+                            .icon_size(IconSize::XSmall)
+                            .tooltip(|cx| Tooltip::text("Remove", cx))
+                            .on_click(cx.listener(move |this, _, cx| {
+                                this.remove_pending_attachment(ix, cx)
+                            })),
+                    )
+                    .into_any_element()
+            })
+            .collect()
+    }
+
+    fn new_markdown(&self, text: String, cx: &mut ViewContext<Self>) -> View<Markdown> {
+        cx.new_view(|cx| {
+            Markdown::new(
+                text,
+                self.markdown_style.clone(),
+                Some(self.language_registry.clone()),
+                cx,
+            )
+        })
+    }
+
     fn render_error(
         &self,
         error: Option<SharedString>,
@@ -835,11 +1327,11 @@ impl AssistantChat {
                                 editing_message.body.clone(),
                                 self.project_index_button.clone(),
                                 self.active_file_button.clone(),
-                                crate::ui::ModelSelector::new(
-                                    cx.view().downgrade(),
-                                    self.model.clone(),
-                                )
-                                .into_any_element(),
+                                AddContextMenu::new(cx.view().downgrade()).into_any_element(),
+                                Vec::new(),
+                                crate::ui::PromptSelector::new(cx.view().downgrade())
+                                    .into_any_element(),
+                                None,
                             ));
                         }
                     }
@@ -898,7 +1390,7 @@ impl AssistantChat {
                 id,
                 messages,
                 error,
-                ..
+                model,
             }) => {
                 let mut message_elements = Vec::new();
 
@@ -937,6 +1429,7 @@ impl AssistantChat {
                                 }
                             })),
                         )
+                        .model(model.as_ref().map(|model| model.name.clone().into()))
                         // TODO: Wire up selections.
                         .selected(is_last),
                     )
@@ -1025,7 +1518,7 @@ impl AssistantChat {
 
     fn serialize_message(
         &self,
-        message: ChatMessage,
+        message: &ChatMessage,
         cx: &mut ViewContext<AssistantChat>,
     ) -> SavedChatMessage {
         match message {
@@ -1043,7 +1536,8 @@ impl AssistantChat {
             },
             ChatMessage::Assistant(message) => SavedChatMessage::Assistant {
                 id: message.id,
-                error: message.error,
+                error: message.error.clone(),
+                model: message.model.clone(),
                 messages: message
                     .messages
                     .iter()
@@ -1076,6 +1570,7 @@ impl Render for AssistantChat {
             .key_context("AssistantChat")
             .on_action(cx.listener(Self::submit))
             .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::cancel_generation))
             .text_color(Color::Default.color(cx))
             .child(list(self.list_state.clone()).flex_1().pt(header_height))
             .child(
@@ -1095,14 +1590,18 @@ impl Render for AssistantChat {
                                 IconName::ChevronLeft
                             },
                         )
-                        .on_click(cx.listener(|this, _event, _cx| {
-                            this.toggle_saved_conversations();
+                        .on_click(cx.listener(|this, _event, cx| {
+                            this.toggle_saved_conversations(cx);
                         }))
                         .tooltip(move |cx| Tooltip::text("Switch Conversations", cx)),
                     )
                     .child(
                         h_flex()
                             .gap(Spacing::Large.rems(cx))
+                            .child(crate::ui::ModelSelector::new(
+                                cx.view().downgrade(),
+                                self.model.clone(),
+                            ))
                             .child(
                                 IconButton::new("new-conversation", IconName::Plus)
                                     .on_click(cx.listener(move |this, _event, cx| {
@@ -1131,12 +1630,37 @@ impl Render for AssistantChat {
                         .child(self.saved_conversations.clone()),
                 )
             })
+            .when(self.pending_completion.is_some(), |element| {
+                element.child(
+                    h_flex().w_full().justify_center().child(
+                        Button::new("stop-generation", "Stop Generating")
+                            .icon(IconName::XCircle)
+                            .icon_position(IconPosition::Start)
+                            .icon_size(IconSize::Small)
+                            .style(ButtonStyle::Filled)
+                            .on_click(cx.listener(|this, _event, cx| {
+                                this.cancel_generation(&CancelGeneration, cx);
+                            })),
+                    ),
+                )
+            })
             .child(Composer::new(
                 self.composer_editor.clone(),
                 self.project_index_button.clone(),
                 self.active_file_button.clone(),
-                crate::ui::ModelSelector::new(cx.view().downgrade(), self.model.clone())
-                    .into_any_element(),
+                AddContextMenu::new(cx.view().downgrade()).into_any_element(),
+                self.render_pending_attachments(cx),
+                crate::ui::PromptSelector::new(cx.view().downgrade()).into_any_element(),
+                self.token_count
+                    .zip(CompletionProvider::global(cx).max_token_count(&self.model))
+                    .map(|(token_count, max_token_count)| {
+                        crate::ui::TokenCount::new(
+                            token_count,
+                            max_token_count,
+                            AssistantSettings::get_global(cx).truncation,
+                        )
+                        .into_any_element()
+                    }),
             ))
     }
 }
@@ -1181,4 +1705,357 @@ struct AssistantMessage {
     pub id: MessageId,
     pub messages: Vec<AssistantMessagePart>,
     pub error: Option<SharedString>,
+    /// The model that produced the message, which messages saved before it was recorded don't
+    /// have.
+    pub model: Option<LanguageModel>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assistant_settings::ModelParameters;
+    use futures::future::{self, BoxFuture, FutureExt};
+    use gpui::{TestAppContext, VisualTestContext};
+    use language::Point;
+    use project::Project;
+    use semantic_index::{Embedding, EmbeddingProvider, TextToEmbed};
+    use settings::SettingsStore;
+    use std::path::Path;
+    use workspace::AppState;
+
+    #[gpui::test]
+    async fn test_stop_generation(cx: &mut TestAppContext) {
+        let (app_state, provider) = init_test(cx);
+        let index_dir = tempfile::tempdir().unwrap();
+        let (chat, cx) = build_chat(&app_state, index_dir.path(), cx).await;
+
+        // Stopping a response keeps what's been received of it, and stops reading the rest.
+        send_message("Hello", &chat, cx);
+        provider.send_last_completion_chunk("Hi, ");
+        cx.run_until_parked();
+        chat.update(cx, |chat, cx| chat.cancel_generation(&CancelGeneration, cx));
+        cx.run_until_parked();
+        assert!(!provider.pending_completions()[0].is_streaming());
+        chat.update(cx, |chat, cx| {
+            assert!(chat.pending_completion.is_none());
+            assert_eq!(message_texts(chat, cx), ["Hello", "Hi, "]);
+        });
+
+        // A response that's stopped before any of it is received is removed.
+        send_message("Are you there?", &chat, cx);
+        chat.update(cx, |chat, cx| {
+            assert_eq!(
+                message_texts(chat, cx),
+                ["Hello", "Hi, ", "Are you there?", ""]
+            );
+            chat.cancel_generation(&CancelGeneration, cx);
+        });
+        cx.run_until_parked();
+        assert!(!provider.pending_completions()[1].is_streaming());
+        chat.update(cx, |chat, cx| {
+            assert!(chat.pending_completion.is_none());
+            assert_eq!(message_texts(chat, cx), ["Hello", "Hi, ", "Are you there?"]);
+        });
+
+        // Sending a message while a response is generated stops it.
+        send_message("Tell me a story", &chat, cx);
+        provider.send_last_completion_chunk("Once upon");
+        cx.run_until_parked();
+        send_message("A short one", &chat, cx);
+        assert_eq!(provider.pending_completions().len(), 4);
+        assert!(!provider.pending_completions()[2].is_streaming());
+        provider.send_last_completion_chunk("The end.");
+        provider.finish_last_completion();
+        cx.run_until_parked();
+        chat.update(cx, |chat, cx| {
+            assert!(chat.pending_completion.is_none());
+            assert_eq!(
+                message_texts(chat, cx),
+                [
+                    "Hello",
+                    "Hi, ",
+                    "Are you there?",
+                    "Tell me a story",
+                    "Once upon",
+                    "A short one",
+                    "The end."
+                ]
+            );
+        });
+    }
+
+    #[gpui::test]
+    async fn test_attach_context(cx: &mut TestAppContext) {
+        let (app_state, provider) = init_test(cx);
+        let index_dir = tempfile::tempdir().unwrap();
+        let (chat, cx) = build_chat(&app_state, index_dir.path(), cx).await;
+
+        let editor = cx.new_view(|cx| {
+            let mut editor = Editor::multi_line(cx);
+            editor.set_text("one\ntwo\nthree\nfour", cx);
+            editor
+        });
+        let workspace = chat.update(cx, |chat, _| chat.workspace.upgrade().unwrap());
+        workspace.update(cx, |workspace, cx| {
+            workspace.add_item_to_active_pane(Box::new(editor.clone()), None, cx);
+        });
+        editor.update(cx, |editor, cx| {
+            editor.change_selections(None, cx, |selections| {
+                selections.select_ranges([Point::new(1, 0)..Point::new(2, 5)])
+            });
+        });
+
+        // Context that's added and then removed isn't sent.
+        chat.update(cx, |chat, cx| {
+            chat.add_context::<SelectionAttachmentTool>(cx);
+            chat.add_context::<SelectionAttachmentTool>(cx);
+        });
+        cx.run_until_parked();
+        chat.update(cx, |chat, cx| {
+            assert_eq!(chat.pending_attachments.len(), 2);
+            chat.remove_pending_attachment(1, cx);
+            assert_eq!(chat.pending_attachments.len(), 1);
+        });
+
+        // The context that's been added is sent with the next message, before it.
+        send_message("What does this do?", &chat, cx);
+        chat.update(cx, |chat, _| assert!(chat.pending_attachments.is_empty()));
+        let selection = CompletionMessage::System {
+            content: "selected text in untitled:2-3:\n```\ntwo\nthree\n```".into(),
+        };
+        {
+            let pending_completions = provider.pending_completions();
+            let messages = &pending_completions[0].request.messages;
+            assert_eq!(
+                messages[messages.len() - 2..],
+                [
+                    selection.clone(),
+                    CompletionMessage::User {
+                        content: "What does this do?".into()
+                    }
+                ]
+            );
+        }
+        provider.send_last_completion_chunk("It counts.");
+        provider.finish_last_completion();
+        cx.run_until_parked();
+
+        // It's kept with the message it was sent with, but isn't attached to the messages after.
+        send_message("Thanks", &chat, cx);
+        let pending_completions = provider.pending_completions();
+        let messages = &pending_completions[1].request.messages;
+        assert_eq!(
+            messages
+                .iter()
+                .filter(|message| **message == selection)
+                .count(),
+            1
+        );
+        assert_eq!(
+            messages.last(),
+            Some(&CompletionMessage::User {
+                content: "Thanks".into()
+            })
+        );
+    }
+
+    #[gpui::test]
+    async fn test_switch_models(cx: &mut TestAppContext) {
+        let (app_state, provider) = init_test(cx);
+        cx.update(|cx| {
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings::<AssistantSettings>(cx, |settings| {
+                    settings.models = Some(HashMap::from_iter([(
+                        "other-fake-model".to_string(),
+                        ModelParameters {
+                            temperature: Some(0.2),
+                            max_tokens: Some(256),
+                        },
+                    )]));
+                });
+            });
+        });
+        let index_dir = tempfile::tempdir().unwrap();
+        let (chat, cx) = build_chat(&app_state, index_dir.path(), cx).await;
+        let fake_model = LanguageModel {
+            provider: "fake".into(),
+            name: "fake-model".into(),
+        };
+        let other_fake_model = LanguageModel {
+            provider: "fake".into(),
+            name: "other-fake-model".into(),
+        };
+        let message_models = |chat: &AssistantChat| {
+            chat.messages
+                .iter()
+                .filter_map(|message| match message {
+                    ChatMessage::User(_) => None,
+                    ChatMessage::Assistant(message) => Some(message.model.clone()),
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // Models without parameters in the settings are requested with those of the chat.
+        send_message("Hello", &chat, cx);
+        provider.send_last_completion_chunk("Hi");
+        provider.finish_last_completion();
+        cx.run_until_parked();
+
+        // The model can be switched mid-conversation, and its parameters take the place of the
+        // chat's.
+        chat.update(cx, |chat, cx| chat.set_model(other_fake_model.clone(), cx));
+        send_message("Hello again", &chat, cx);
+        provider.send_last_completion_chunk("Hi again");
+        provider.finish_last_completion();
+        cx.run_until_parked();
+        {
+            let pending_completions = provider.pending_completions();
+            let requests = pending_completions
+                .iter()
+                .map(|completion| {
+                    let request = &completion.request;
+                    (
+                        request.model.as_str(),
+                        request.temperature,
+                        request.max_tokens,
+                    )
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(
+                requests,
+                [
+                    ("fake-model", 1.0, None),
+                    ("other-fake-model", 0.2, Some(256))
+                ]
+            );
+        }
+
+        // Each response records the model that produced it, which is kept when it's saved.
+        let saved_conversation = chat.update(cx, |chat, cx| {
+            assert_eq!(
+                message_models(chat),
+                [Some(fake_model.clone()), Some(other_fake_model.clone())]
+            );
+            SavedConversation {
+                version: "0.3.0".into(),
+                title: chat.title(cx),
+                model: Some(fake_model.clone()),
+                messages: chat
+                    .messages
+                    .iter()
+                    .map(|message| chat.serialize_message(message, cx))
+                    .collect(),
+            }
+        });
+        chat.update(cx, |chat, cx| {
+            chat.new_conversation(cx);
+            chat.load_conversation("/conversations/hello.json".into(), saved_conversation, cx);
+            assert_eq!(chat.model, fake_model);
+            assert_eq!(
+                message_models(chat),
+                [Some(fake_model.clone()), Some(other_fake_model.clone())]
+            );
+        });
+        cx.run_until_parked();
+    }
+
+    struct FakeEmbeddingProvider;
+
+    impl EmbeddingProvider for FakeEmbeddingProvider {
+        fn embed<'a>(
+            &'a self,
+            texts: &'a [TextToEmbed<'a>],
+        ) -> BoxFuture<'a, Result<Vec<Embedding>>> {
+            let embeddings = texts
+                .iter()
+                .map(|_| Embedding::new(vec![0.0, 1.0]))
+                .collect();
+            future::ready(Ok(embeddings)).boxed()
+        }
+
+        fn batch_size(&self) -> usize {
+            16
+        }
+    }
+
+    fn init_test(cx: &mut TestAppContext) -> (Arc<AppState>, Arc<FakeLanguageModelProvider>) {
+        cx.update(|cx| {
+            let app_state = AppState::test(cx);
+            language::init(cx);
+            editor::init(cx);
+            Project::init_settings(cx);
+            AssistantSettings::register(cx);
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings::<AssistantSettings>(cx, |settings| {
+                    settings.provider = Some("fake".into());
+                });
+            });
+
+            let provider = Arc::new(FakeLanguageModelProvider::default());
+            cx.set_global(CompletionProvider::fake(provider.clone()));
+            (app_state, provider)
+        })
+    }
+
+    async fn build_chat<'a>(
+        app_state: &Arc<AppState>,
+        index_dir: &Path,
+        cx: &'a mut TestAppContext,
+    ) -> (View<AssistantChat>, &'a mut VisualTestContext) {
+        let project = Project::test(app_state.fs.clone(), [], cx).await;
+        let mut semantic_index = SemanticIndex::new(
+            index_dir.into(),
+            Arc::new(FakeEmbeddingProvider),
+            &mut cx.to_async(),
+        )
+        .await
+        .unwrap();
+        let project_index = cx.update(|cx| semantic_index.project_index(project.clone(), cx));
+
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+        let attachment_registry = cx.update(|cx| {
+            let mut attachment_registry = AttachmentRegistry::new();
+            attachment_registry.register(SelectionAttachmentTool::new(workspace.downgrade(), cx));
+            attachment_registry.set_attachment_tool_enabled::<SelectionAttachmentTool>(false);
+            Arc::new(attachment_registry)
+        });
+        let chat = cx.new_view(|cx| {
+            AssistantChat::new(
+                app_state.fs.clone(),
+                app_state.languages.clone(),
+                Arc::new(ToolRegistry::new()),
+                attachment_registry,
+                app_state.user_store.clone(),
+                project_index,
+                workspace.downgrade(),
+                cx,
+            )
+        });
+        (chat, cx)
+    }
+
+    fn send_message(text: &str, chat: &View<AssistantChat>, cx: &mut VisualTestContext) {
+        chat.update(cx, |chat, cx| {
+            chat.composer_editor
+                .update(cx, |editor, cx| editor.set_text(text, cx));
+            chat.composer_editor.focus_handle(cx).focus(cx);
+            chat.submit(&Submit(SubmitMode::Simple), cx);
+        });
+        cx.run_until_parked();
+    }
+
+    /// The text of each message, with the parts of the assistant's joined together.
+    fn message_texts(chat: &AssistantChat, cx: &AppContext) -> Vec<String> {
+        chat.messages
+            .iter()
+            .map(|message| match message {
+                ChatMessage::User(message) => message.body.read(cx).source().to_string(),
+                ChatMessage::Assistant(message) => message
+                    .messages
+                    .iter()
+                    .map(|part| part.body.read(cx).source())
+                    .collect(),
+            })
+            .collect()
+    }
 }
@@ -1,15 +1,191 @@
+use collections::HashMap;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use settings::{Settings, SettingsSources};
 
-#[derive(Default, Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(default)]
 pub struct AssistantSettings {
     pub enabled: bool,
+    pub provider: String,
+    pub model: Option<String>,
+    pub truncation: TruncationStrategy,
+    pub models: HashMap<String, ModelParameters>,
+    pub terminal_command_allowlist: Vec<String>,
+    pub openai: OpenAiSettings,
+    pub anthropic: AnthropicSettings,
+    pub azure_openai: AzureOpenAiSettings,
+    pub ollama: OllamaSettings,
+}
+
+impl Default for AssistantSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            provider: "zed.dev".into(),
+            model: None,
+            truncation: TruncationStrategy::default(),
+            models: HashMap::default(),
+            terminal_command_allowlist: Vec::new(),
+            openai: Default::default(),
+            anthropic: Default::default(),
+            azure_openai: Default::default(),
+            ollama: Default::default(),
+        }
+    }
 }
 
 #[derive(Default, Debug, Deserialize, Serialize, Clone, JsonSchema)]
 pub struct AssistantSettingsContent {
     pub enabled: Option<bool>,
+    /// The provider that new conversations request completions from: "zed.dev", "openai",
+    /// "anthropic", "azure_openai" or "ollama".
+    ///
+    /// Default: "zed.dev"
+    pub provider: Option<String>,
+    /// The model that new conversations use, as it's named by the provider.
+    ///
+    /// Default: the provider's first model
+    pub model: Option<String>,
+    /// How conversations that no longer fit in the model's context window are shortened:
+    /// "drop_oldest" or "summarize".
+    ///
+    /// Default: "drop_oldest"
+    pub truncation: Option<TruncationStrategy>,
+    /// The parameters that completions are requested from each model with, keyed by the name
+    /// of the model. For example:
+    ///
+    /// "models": { "gpt-4o": { "temperature": 0.2, "max_tokens": 2048 } }
+    ///
+    /// Default: {}
+    pub models: Option<HashMap<String, ModelParameters>>,
+    /// The commands that the assistant runs in the terminal without asking first. A command is
+    /// allowed when it's one of these, or one of these followed by arguments, and doesn't chain
+    /// or redirect to other commands. Every other command has to be confirmed.
+    ///
+    /// "terminal_command_allowlist": ["cargo check", "git status"]
+    ///
+    /// Default: []
+    pub terminal_command_allowlist: Option<Vec<String>>,
+    pub openai: Option<OpenAiSettings>,
+    pub anthropic: Option<AnthropicSettings>,
+    pub azure_openai: Option<AzureOpenAiSettings>,
+    pub ollama: Option<OllamaSettings>,
+}
+
+/// How a conversation is shortened when it no longer fits in the model's context window. The
+/// latest message and the responses to it are always kept.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TruncationStrategy {
+    /// Leave out the oldest messages.
+    #[default]
+    DropOldest,
+    /// Replace the oldest messages with a summary of them, which the model is asked to write.
+    Summarize,
+}
+
+impl AssistantSettings {
+    /// The parameters in the settings for the model with the given name.
+    pub fn model_parameters(&self, model: &str) -> ModelParameters {
+        self.models.get(model).copied().unwrap_or_default()
+    }
+}
+
+/// The parameters that completions are requested from a model with, which take the place of
+/// those that each feature of the assistant requests by default.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Deserialize, Serialize, JsonSchema)]
+pub struct ModelParameters {
+    /// How random the model's responses are, from 0 to 2.
+    pub temperature: Option<f32>,
+    /// The most tokens that the model responds with.
+    pub max_tokens: Option<u32>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, JsonSchema)]
+#[serde(default)]
+pub struct OpenAiSettings {
+    /// Default: "https://api.openai.com/v1"
+    pub api_url: String,
+    /// Default: ["gpt-4o", "gpt-4-turbo", "gpt-3.5-turbo"]
+    pub models: Vec<String>,
+}
+
+impl Default for OpenAiSettings {
+    fn default() -> Self {
+        Self {
+            api_url: open_ai::OPEN_AI_API_URL.into(),
+            models: vec![
+                "gpt-4o".into(),
+                "gpt-4-turbo".into(),
+                "gpt-3.5-turbo".into(),
+            ],
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, JsonSchema)]
+#[serde(default)]
+pub struct AnthropicSettings {
+    /// Default: "https://api.anthropic.com"
+    pub api_url: String,
+    /// Default: ["claude-3-opus-20240229", "claude-3-sonnet-20240229", "claude-3-haiku-20240307"]
+    pub models: Vec<String>,
+}
+
+impl Default for AnthropicSettings {
+    fn default() -> Self {
+        Self {
+            api_url: anthropic::ANTHROPIC_API_URL.into(),
+            models: vec![
+                "claude-3-opus-20240229".into(),
+                "claude-3-sonnet-20240229".into(),
+                "claude-3-haiku-20240307".into(),
+            ],
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, JsonSchema)]
+#[serde(default)]
+pub struct AzureOpenAiSettings {
+    /// The endpoint of the Azure OpenAI resource, like "https://my-resource.openai.azure.com".
+    pub endpoint: String,
+    /// Default: "2024-02-01"
+    pub api_version: String,
+    /// The names of the resource's deployments, which are chosen as its models.
+    pub deployments: Vec<String>,
+}
+
+impl Default for AzureOpenAiSettings {
+    fn default() -> Self {
+        Self {
+            endpoint: String::new(),
+            api_version: "2024-02-01".into(),
+            deployments: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, JsonSchema)]
+#[serde(default)]
+pub struct OllamaSettings {
+    /// Default: "http://localhost:11434"
+    pub api_url: String,
+    /// The models that are listed before Ollama is connected to, after which the models it has
+    /// pulled are.
+    ///
+    /// Default: ["llama3"]
+    pub models: Vec<String>,
+}
+
+impl Default for OllamaSettings {
+    fn default() -> Self {
+        Self {
+            api_url: "http://localhost:11434".into(),
+            models: vec!["llama3".into()],
+        }
+    }
 }
 
 impl Settings for AssistantSettings {
@@ -1,3 +1,11 @@
 mod active_file;
+mod diagnostics;
+mod selection;
+mod slash_command;
+mod terminal_output;
 
 pub use active_file::*;
+pub use diagnostics::*;
+pub use selection::*;
+pub use slash_command::*;
+pub use terminal_output::*;
@@ -4,7 +4,7 @@ use anyhow::{anyhow, Result};
 use assistant_tooling::{AttachmentOutput, LanguageModelAttachment, ProjectContext};
 use editor::Editor;
 use gpui::{Render, Task, View, WeakModel, WeakView};
-use language::Buffer;
+use language::{language_settings::assistant_context_enabled, Buffer};
 use project::ProjectPath;
 use serde::{Deserialize, Serialize};
 use ui::{prelude::*, ButtonLike, Tooltip, WindowContext};
@@ -18,6 +18,16 @@ pub struct ActiveEditorAttachment {
     path: Option<PathBuf>,
 }
 
+impl ActiveEditorAttachment {
+    /// Attaches a file that the user picked rather than the one that's active.
+    pub fn for_path(path: PathBuf) -> Self {
+        Self {
+            buffer: None,
+            path: Some(path),
+        }
+    }
+}
+
 pub struct FileAttachmentView {
     project_path: Option<ProjectPath>,
     buffer: Option<WeakModel<Buffer>>,
@@ -60,7 +70,12 @@ impl AttachmentOutput for FileAttachmentView {
         }
 
         if let Some(buffer) = self.buffer.as_ref().and_then(|buffer| buffer.upgrade()) {
-            return format!("current untitled buffer text:\n{}", buffer.read(cx).text());
+            let buffer = buffer.read(cx);
+            if !assistant_context_enabled(buffer.language(), buffer.file(), cx) {
+                return "current untitled buffer: excluded from the context by the user"
+                    .to_string();
+            }
+            return format!("current untitled buffer text:\n{}", buffer.text());
         }
 
         String::new()
@@ -0,0 +1,98 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use assistant_tooling::{AttachmentOutput, LanguageModelAttachment, ProjectContext};
+use gpui::{Model, Render, Task, View};
+use project::Project;
+use serde::{Deserialize, Serialize};
+use ui::{prelude::*, ButtonLike, Tooltip, WindowContext};
+
+use crate::tools::{collect_diagnostics, format_diagnostics, FileDiagnostic};
+
+#[derive(Serialize, Deserialize)]
+pub struct DiagnosticsAttachment {
+    diagnostics: Vec<FileDiagnostic>,
+}
+
+pub struct DiagnosticsAttachmentView {
+    diagnostics: Result<DiagnosticsAttachment>,
+}
+
+impl Render for DiagnosticsAttachmentView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let diagnostics = match &self.diagnostics {
+            Ok(diagnostics) => &diagnostics.diagnostics,
+            Err(error) => return div().child(error.to_string()).into_any_element(),
+        };
+
+        let error_count = diagnostics.iter().filter(|d| d.is_error()).count();
+        let warning_count = diagnostics.len() - error_count;
+        let summary: SharedString =
+            format!("{error_count} errors, {warning_count} warnings").into();
+
+        ButtonLike::new("diagnostics-attachment")
+            .child(
+                h_flex()
+                    .gap_1()
+                    .bg(cx.theme().colors().editor_background)
+                    .rounded_md()
+                    .child(ui::Icon::new(IconName::ExclamationTriangle))
+                    .child("Diagnostics"),
+            )
+            .tooltip(move |cx| {
+                Tooltip::with_meta("Diagnostics Attached", None, summary.clone(), cx)
+            })
+            .into_any_element()
+    }
+}
+
+impl AttachmentOutput for DiagnosticsAttachmentView {
+    fn generate(&self, _project: &mut ProjectContext, _cx: &mut WindowContext) -> String {
+        match &self.diagnostics {
+            Ok(diagnostics) => format!(
+                "current diagnostics:\n{}",
+                format_diagnostics(&diagnostics.diagnostics)
+            ),
+            Err(_) => String::new(),
+        }
+    }
+}
+
+/// Attaches the errors and warnings that are reported for the project.
+pub struct DiagnosticsAttachmentTool {
+    project: Model<Project>,
+}
+
+impl DiagnosticsAttachmentTool {
+    pub fn new(project: Model<Project>, _cx: &mut WindowContext) -> Self {
+        Self { project }
+    }
+}
+
+impl LanguageModelAttachment for DiagnosticsAttachmentTool {
+    type Output = DiagnosticsAttachment;
+    type View = DiagnosticsAttachmentView;
+
+    fn name(&self) -> Arc<str> {
+        "diagnostics-attachment".into()
+    }
+
+    fn run(&self, cx: &mut WindowContext) -> Task<Result<DiagnosticsAttachment>> {
+        let diagnostics = collect_diagnostics(&self.project, None, cx);
+        cx.background_executor().spawn(async move {
+            Ok(DiagnosticsAttachment {
+                diagnostics: diagnostics.await?,
+            })
+        })
+    }
+
+    fn view(
+        &self,
+        output: Result<DiagnosticsAttachment>,
+        cx: &mut WindowContext,
+    ) -> View<Self::View> {
+        cx.new_view(|_cx| DiagnosticsAttachmentView {
+            diagnostics: output,
+        })
+    }
+}
@@ -0,0 +1,158 @@
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use assistant_tooling::{AttachmentOutput, LanguageModelAttachment, ProjectContext};
+use editor::Editor;
+use gpui::{Render, Task, View, WeakView};
+use language::language_settings::assistant_context_enabled;
+use serde::{Deserialize, Serialize};
+use ui::{prelude::*, ButtonLike, Tooltip, WindowContext};
+use util::maybe;
+use workspace::Workspace;
+
+#[derive(Serialize, Deserialize)]
+pub struct SelectionAttachment {
+    path: Option<String>,
+    language: Option<String>,
+    /// The one-based lines that the selection starts and ends on.
+    start_line: u32,
+    end_line: u32,
+    text: String,
+}
+
+impl SelectionAttachment {
+    fn location(&self, path: &str) -> String {
+        if self.start_line == self.end_line {
+            format!("{path}:{}", self.start_line)
+        } else {
+            format!("{path}:{}-{}", self.start_line, self.end_line)
+        }
+    }
+}
+
+pub struct SelectionAttachmentView {
+    selection: Result<SelectionAttachment>,
+}
+
+impl Render for SelectionAttachmentView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let selection = match &self.selection {
+            Ok(selection) => selection,
+            Err(error) => return div().child(error.to_string()).into_any_element(),
+        };
+
+        let path = selection.path.as_deref().unwrap_or("untitled");
+        let filename = path.rsplit('/').next().unwrap_or(path);
+        let label = selection.location(filename);
+        let location: SharedString = selection.location(path).into();
+
+        ButtonLike::new("selection-attachment")
+            .child(
+                h_flex()
+                    .gap_1()
+                    .bg(cx.theme().colors().editor_background)
+                    .rounded_md()
+                    .child(ui::Icon::new(IconName::Quote))
+                    .child(label),
+            )
+            .tooltip(move |cx| Tooltip::with_meta("Selection Attached", None, location.clone(), cx))
+            .into_any_element()
+    }
+}
+
+impl AttachmentOutput for SelectionAttachmentView {
+    fn generate(&self, _project: &mut ProjectContext, _cx: &mut WindowContext) -> String {
+        let Ok(selection) = &self.selection else {
+            return String::new();
+        };
+
+        format!(
+            "selected text in {}:\n```{}\n{}\n```",
+            selection.location(selection.path.as_deref().unwrap_or("untitled")),
+            selection.language.as_deref().unwrap_or(""),
+            selection.text
+        )
+    }
+}
+
+/// Attaches the text that's selected in the active editor.
+pub struct SelectionAttachmentTool {
+    workspace: WeakView<Workspace>,
+}
+
+impl SelectionAttachmentTool {
+    pub fn new(workspace: WeakView<Workspace>, _cx: &mut WindowContext) -> Self {
+        Self { workspace }
+    }
+}
+
+impl LanguageModelAttachment for SelectionAttachmentTool {
+    type Output = SelectionAttachment;
+    type View = SelectionAttachmentView;
+
+    fn name(&self) -> Arc<str> {
+        "selection-attachment".into()
+    }
+
+    fn run(&self, cx: &mut WindowContext) -> Task<Result<SelectionAttachment>> {
+        Task::ready(maybe!({
+            let editor = self
+                .workspace
+                .update(cx, |workspace, cx| {
+                    workspace
+                        .active_item(cx)
+                        .and_then(|item| item.act_as::<Editor>(cx))
+                })?
+                .ok_or_else(|| anyhow!("no active editor"))?;
+
+            let editor = editor.read(cx);
+            let range = editor.selections.newest::<usize>(cx).range();
+            if range.is_empty() {
+                return Err(anyhow!("no text is selected"));
+            }
+            let excluded = editor
+                .buffer()
+                .read(cx)
+                .range_to_buffer_ranges(range.clone(), cx)
+                .into_iter()
+                .any(|(buffer, _, _)| {
+                    let buffer = buffer.read(cx);
+                    !assistant_context_enabled(buffer.language(), buffer.file(), cx)
+                });
+            if excluded {
+                return Err(anyhow!(
+                    "the selection is excluded from the assistant's context by your settings"
+                ));
+            }
+
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            let text = snapshot.text_for_range(range.clone()).collect::<String>();
+            let (buffer, start) = snapshot
+                .point_to_buffer_offset(range.start)
+                .ok_or_else(|| anyhow!("no text is selected"))?;
+            let (_, end) = snapshot
+                .point_to_buffer_offset(range.end)
+                .ok_or_else(|| anyhow!("no text is selected"))?;
+
+            Ok(SelectionAttachment {
+                path: buffer
+                    .file()
+                    .map(|file| file.full_path(cx).to_string_lossy().to_string()),
+                language: buffer
+                    .language()
+                    .map(|language| language.code_fence_block_name().to_string()),
+                start_line: buffer.offset_to_point(start).row + 1,
+                end_line: buffer.offset_to_point(end).row + 1,
+                text,
+            })
+        }))
+    }
+
+    fn view(
+        &self,
+        output: Result<SelectionAttachment>,
+        cx: &mut WindowContext,
+    ) -> View<Self::View> {
+        cx.new_view(|_cx| SelectionAttachmentView { selection: output })
+    }
+}
@@ -0,0 +1,89 @@
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use assistant_tooling::{AttachmentOutput, LanguageModelAttachment, ProjectContext};
+use gpui::{Render, Task, View};
+use serde::{Deserialize, Serialize};
+use ui::{prelude::*, ButtonLike, Tooltip, WindowContext};
+
+/// The context that a slash command typed in the composer expanded into, or why it couldn't be.
+#[derive(Serialize, Deserialize)]
+pub struct SlashCommandOutput {
+    /// The command as it was typed, like "/search parse_args".
+    pub command: String,
+    pub output: Result<String, String>,
+}
+
+pub struct SlashCommandAttachmentView {
+    output: Result<SlashCommandOutput>,
+}
+
+impl Render for SlashCommandAttachmentView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let output = match &self.output {
+            Ok(output) => output,
+            Err(error) => return div().child(error.to_string()).into_any_element(),
+        };
+
+        let command: SharedString = output.command.clone().into();
+        let (icon, color, tooltip): (_, _, SharedString) = match &output.output {
+            Ok(_) => (
+                IconName::Code,
+                Color::Default,
+                "Command Output Attached".into(),
+            ),
+            Err(error) => (
+                IconName::ExclamationTriangle,
+                Color::Error,
+                error.clone().into(),
+            ),
+        };
+
+        ButtonLike::new("slash-command-attachment")
+            .child(
+                h_flex()
+                    .gap_1()
+                    .bg(cx.theme().colors().editor_background)
+                    .rounded_md()
+                    .child(ui::Icon::new(icon).color(color))
+                    .child(Label::new(command.clone()).color(color)),
+            )
+            .tooltip(move |cx| Tooltip::with_meta(tooltip.clone(), None, command.clone(), cx))
+            .into_any_element()
+    }
+}
+
+impl AttachmentOutput for SlashCommandAttachmentView {
+    fn generate(&self, _project: &mut ProjectContext, _cx: &mut WindowContext) -> String {
+        match &self.output {
+            Ok(SlashCommandOutput {
+                command,
+                output: Ok(output),
+            }) => format!("output of `{command}`:\n{output}"),
+            _ => String::new(),
+        }
+    }
+}
+
+/// Attaches the output of the slash commands that expand into text of their own. It's never run,
+/// since its outputs are only attached when a command is sent.
+pub struct SlashCommandAttachmentTool;
+
+impl LanguageModelAttachment for SlashCommandAttachmentTool {
+    type Output = SlashCommandOutput;
+    type View = SlashCommandAttachmentView;
+
+    fn name(&self) -> Arc<str> {
+        "slash-command-attachment".into()
+    }
+
+    fn run(&self, _cx: &mut WindowContext) -> Task<Result<SlashCommandOutput>> {
+        Task::ready(Err(anyhow!(
+            "slash commands are only attached when they're sent"
+        )))
+    }
+
+    fn view(&self, output: Result<SlashCommandOutput>, cx: &mut WindowContext) -> View<Self::View> {
+        cx.new_view(|_cx| SlashCommandAttachmentView { output })
+    }
+}
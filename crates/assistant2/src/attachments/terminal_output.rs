@@ -0,0 +1,114 @@
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use assistant_tooling::{AttachmentOutput, LanguageModelAttachment, ProjectContext};
+use gpui::{Render, Task, View, WeakView};
+use serde::{Deserialize, Serialize};
+use terminal_view::{terminal_panel::TerminalPanel, TerminalView};
+use ui::{prelude::*, ButtonLike, Tooltip, WindowContext};
+use util::maybe;
+use workspace::Workspace;
+
+/// The most lines of a terminal's output that are attached, so it doesn't overwhelm the context.
+const MAX_LINES: usize = 100;
+
+#[derive(Serialize, Deserialize)]
+pub struct TerminalOutputAttachment {
+    title: String,
+    output: String,
+}
+
+pub struct TerminalOutputAttachmentView {
+    output: Result<TerminalOutputAttachment>,
+}
+
+impl Render for TerminalOutputAttachmentView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let output = match &self.output {
+            Ok(output) => output,
+            Err(error) => return div().child(error.to_string()).into_any_element(),
+        };
+
+        let title: SharedString = output.title.clone().into();
+
+        ButtonLike::new("terminal-output-attachment")
+            .child(
+                h_flex()
+                    .gap_1()
+                    .bg(cx.theme().colors().editor_background)
+                    .rounded_md()
+                    .child(ui::Icon::new(IconName::Terminal))
+                    .child("Terminal Output"),
+            )
+            .tooltip(move |cx| {
+                Tooltip::with_meta("Terminal Output Attached", None, title.clone(), cx)
+            })
+            .into_any_element()
+    }
+}
+
+impl AttachmentOutput for TerminalOutputAttachmentView {
+    fn generate(&self, _project: &mut ProjectContext, _cx: &mut WindowContext) -> String {
+        match &self.output {
+            Ok(output) => format!(
+                "recent output of the terminal `{}`:\n```\n{}\n```",
+                output.title, output.output
+            ),
+            Err(_) => String::new(),
+        }
+    }
+}
+
+/// Attaches the most recent output of the active terminal, which is the one in the center of the
+/// workspace if it's focused there, or otherwise the one in the terminal panel.
+pub struct TerminalOutputAttachmentTool {
+    workspace: WeakView<Workspace>,
+}
+
+impl TerminalOutputAttachmentTool {
+    pub fn new(workspace: WeakView<Workspace>, _cx: &mut WindowContext) -> Self {
+        Self { workspace }
+    }
+}
+
+impl LanguageModelAttachment for TerminalOutputAttachmentTool {
+    type Output = TerminalOutputAttachment;
+    type View = TerminalOutputAttachmentView;
+
+    fn name(&self) -> Arc<str> {
+        "terminal-output-attachment".into()
+    }
+
+    fn run(&self, cx: &mut WindowContext) -> Task<Result<TerminalOutputAttachment>> {
+        Task::ready(maybe!({
+            let terminal_view = self
+                .workspace
+                .update(cx, |workspace, cx| {
+                    workspace.active_item_as::<TerminalView>(cx).or_else(|| {
+                        let panel = workspace.panel::<TerminalPanel>(cx)?;
+                        let pane = panel.read(cx).pane().read(cx);
+                        pane.active_item()?.downcast::<TerminalView>()
+                    })
+                })?
+                .ok_or_else(|| anyhow!("no terminal is open"))?;
+
+            let terminal = terminal_view.read(cx).terminal().read(cx);
+            let text = terminal.text();
+            let lines = text.trim_end().lines().collect::<Vec<_>>();
+            let output = lines[lines.len().saturating_sub(MAX_LINES)..].join("\n");
+
+            Ok(TerminalOutputAttachment {
+                title: terminal.title(true),
+                output,
+            })
+        }))
+    }
+
+    fn view(
+        &self,
+        output: Result<TerminalOutputAttachment>,
+        cx: &mut WindowContext,
+    ) -> View<Self::View> {
+        cx.new_view(|_cx| TerminalOutputAttachmentView { output })
+    }
+}
@@ -1,179 +1,268 @@
-use anyhow::Result;
+mod anthropic;
+mod azure_open_ai;
+mod cloud;
+#[cfg(test)]
+mod fake;
+mod ollama;
+mod open_ai;
+
+use crate::AssistantSettings;
+use anyhow::{anyhow, Result};
 use assistant_tooling::ToolFunctionDefinition;
 use client::{proto, Client};
-use futures::{future::BoxFuture, stream::BoxStream, FutureExt, StreamExt};
-use gpui::Global;
-use std::sync::Arc;
-
-pub use open_ai::RequestMessage as CompletionMessage;
-
-#[derive(Clone)]
-pub struct CompletionProvider(Arc<dyn CompletionProviderBackend>);
+use futures::{
+    future::{self, BoxFuture},
+    stream::BoxStream,
+    FutureExt, StreamExt,
+};
+use gpui::{AppContext, BackgroundExecutor, Global, Task};
+use serde::{Deserialize, Serialize};
+use settings::Settings;
+use std::{env, sync::Arc};
 
-impl CompletionProvider {
-    pub fn new(backend: impl CompletionProviderBackend) -> Self {
-        Self(Arc::new(backend))
-    }
-
-    pub fn default_model(&self) -> String {
-        self.0.default_model()
-    }
+pub use ::open_ai::RequestMessage as CompletionMessage;
+pub use anthropic::AnthropicCompletionProvider;
+pub use azure_open_ai::AzureOpenAiCompletionProvider;
+pub use cloud::CloudCompletionProvider;
+#[cfg(test)]
+pub use fake::FakeLanguageModelProvider;
+pub use ollama::OllamaCompletionProvider;
+pub use open_ai::OpenAiCompletionProvider;
 
-    pub fn available_models(&self) -> Vec<String> {
-        self.0.available_models()
-    }
-
-    pub fn complete(
+/// A service that the assistant can request completions from, like zed.dev or a model that's
+/// running locally.
+pub trait LanguageModelProvider: 'static + Send + Sync {
+    /// The name of the provider in the settings, like `"openai"`.
+    fn id(&self) -> &'static str;
+    fn display_name(&self) -> &'static str;
+    /// The models of the provider that can be chosen, as they're named in its API.
+    fn available_models(&self, cx: &AppContext) -> Vec<String>;
+    /// Whether the provider's models can call the assistant's tools. The tools are left out of
+    /// the requests to providers whose models can't.
+    fn supports_tools(&self) -> bool;
+    /// The most tokens that the model's context window holds, including its response.
+    fn max_token_count(&self, model: &str) -> usize;
+    /// Counts the tokens that the messages take up for the model. By default they're counted
+    /// with OpenAI's tokenizers, which approximate the counts of other providers' models.
+    fn count_tokens(
         &self,
-        model: String,
+        model: &str,
         messages: Vec<CompletionMessage>,
-        stop: Vec<String>,
-        temperature: f32,
-        tools: Vec<ToolFunctionDefinition>,
-    ) -> BoxFuture<'static, Result<BoxStream<'static, Result<proto::LanguageModelResponseMessage>>>>
-    {
-        self.0.complete(model, messages, stop, temperature, tools)
+        cx: &AppContext,
+    ) -> BoxFuture<'static, Result<usize>> {
+        count_open_ai_tokens(model, messages, cx.background_executor())
     }
-}
-
-impl Global for CompletionProvider {}
-
-pub trait CompletionProviderBackend: 'static {
-    fn default_model(&self) -> String;
-    fn available_models(&self) -> Vec<String>;
+    fn is_authenticated(&self, cx: &AppContext) -> bool;
+    fn authenticate(&self, cx: &mut AppContext) -> Task<Result<()>>;
     fn complete(
         &self,
-        model: String,
-        messages: Vec<CompletionMessage>,
-        stop: Vec<String>,
-        temperature: f32,
-        tools: Vec<ToolFunctionDefinition>,
+        request: CompletionRequest,
+        cx: &AppContext,
     ) -> BoxFuture<'static, Result<BoxStream<'static, Result<proto::LanguageModelResponseMessage>>>>;
 }
 
-pub struct CloudCompletionProvider {
-    client: Arc<Client>,
+pub struct CompletionRequest {
+    pub model: String,
+    pub messages: Vec<CompletionMessage>,
+    pub stop: Vec<String>,
+    pub temperature: f32,
+    /// The most tokens that the model may respond with, which is otherwise up to its provider.
+    pub max_tokens: Option<u32>,
+    pub tools: Vec<ToolFunctionDefinition>,
+}
+
+/// A model, along with the provider it's requested from.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LanguageModel {
+    pub provider: String,
+    pub name: String,
+}
+
+/// The providers that completions can be requested from.
+#[derive(Clone)]
+pub struct CompletionProvider {
+    providers: Vec<Arc<dyn LanguageModelProvider>>,
 }
 
-impl CloudCompletionProvider {
+impl CompletionProvider {
     pub fn new(client: Arc<Client>) -> Self {
-        Self { client }
+        let http_client = client.http_client();
+        Self {
+            providers: vec![
+                Arc::new(CloudCompletionProvider::new(client)),
+                Arc::new(OpenAiCompletionProvider::new(http_client.clone())),
+                Arc::new(AnthropicCompletionProvider::new(http_client.clone())),
+                Arc::new(AzureOpenAiCompletionProvider::new(http_client.clone())),
+                Arc::new(OllamaCompletionProvider::new(http_client)),
+            ],
+        }
     }
-}
 
-impl CompletionProviderBackend for CloudCompletionProvider {
-    fn default_model(&self) -> String {
-        "gpt-4-turbo".into()
+    #[cfg(test)]
+    pub fn fake(provider: Arc<FakeLanguageModelProvider>) -> Self {
+        Self {
+            providers: vec![provider],
+        }
     }
 
-    fn available_models(&self) -> Vec<String> {
-        vec!["gpt-4-turbo".into(), "gpt-4".into(), "gpt-3.5-turbo".into()]
+    pub fn providers(&self) -> &[Arc<dyn LanguageModelProvider>] {
+        &self.providers
     }
 
-    fn complete(
+    pub fn provider(&self, id: &str) -> Option<&Arc<dyn LanguageModelProvider>> {
+        self.providers.iter().find(|provider| provider.id() == id)
+    }
+
+    /// The model that new conversations use, which is the one in the settings or, when it isn't
+    /// set, the first model of the provider in the settings.
+    pub fn default_model(&self, cx: &AppContext) -> LanguageModel {
+        let settings = AssistantSettings::get_global(cx);
+        let name = settings.model.clone().or_else(|| {
+            self.provider(&settings.provider)?
+                .available_models(cx)
+                .into_iter()
+                .next()
+        });
+        LanguageModel {
+            provider: settings.provider.clone(),
+            name: name.unwrap_or_default(),
+        }
+    }
+
+    /// The most tokens that the model's context window holds, if its provider is known.
+    pub fn max_token_count(&self, model: &LanguageModel) -> Option<usize> {
+        Some(self.provider(&model.provider)?.max_token_count(&model.name))
+    }
+
+    /// Counts the tokens that the messages take up for the model.
+    pub fn count_tokens(
         &self,
-        model: String,
+        model: &LanguageModel,
         messages: Vec<CompletionMessage>,
-        stop: Vec<String>,
-        temperature: f32,
-        tools: Vec<ToolFunctionDefinition>,
-    ) -> BoxFuture<'static, Result<BoxStream<'static, Result<proto::LanguageModelResponseMessage>>>>
-    {
-        let client = self.client.clone();
-        let tools: Vec<proto::ChatCompletionTool> = tools
-            .iter()
-            .filter_map(|tool| {
-                Some(proto::ChatCompletionTool {
-                    variant: Some(proto::chat_completion_tool::Variant::Function(
-                        proto::chat_completion_tool::FunctionObject {
-                            name: tool.name.clone(),
-                            description: Some(tool.description.clone()),
-                            parameters: Some(serde_json::to_string(&tool.parameters).ok()?),
-                        },
-                    )),
-                })
-            })
-            .collect();
+        cx: &AppContext,
+    ) -> BoxFuture<'static, Result<usize>> {
+        match self.provider(&model.provider) {
+            Some(provider) => provider.count_tokens(&model.name, messages, cx),
+            None => future::ready(Err(anyhow!(
+                "unknown language model provider {:?}",
+                model.provider
+            )))
+            .boxed(),
+        }
+    }
 
-        let tool_choice = match tools.is_empty() {
-            true => None,
-            false => Some("auto".into()),
+    /// Requests a completion from the model's provider, authenticating with it first if needed.
+    /// The model's parameters in the settings take the place of the request's.
+    pub fn complete(
+        &self,
+        model: &LanguageModel,
+        mut request: CompletionRequest,
+        cx: &mut AppContext,
+    ) -> Task<Result<BoxStream<'static, Result<proto::LanguageModelResponseMessage>>>> {
+        let Some(provider) = self.provider(&model.provider).cloned() else {
+            return Task::ready(Err(anyhow!(
+                "unknown language model provider {:?}",
+                model.provider
+            )));
         };
+        request.model.clone_from(&model.name);
+        let parameters = AssistantSettings::get_global(cx).model_parameters(&model.name);
+        if let Some(temperature) = parameters.temperature {
+            request.temperature = temperature;
+        }
+        if parameters.max_tokens.is_some() {
+            request.max_tokens = parameters.max_tokens;
+        }
+        if !provider.supports_tools() {
+            request.tools.clear();
+        }
 
-        async move {
-            let stream = client
-                .request_stream(proto::CompleteWithLanguageModel {
-                    model,
-                    messages: messages
-                        .into_iter()
-                        .map(|message| match message {
-                            CompletionMessage::Assistant {
-                                content,
-                                tool_calls,
-                            } => proto::LanguageModelRequestMessage {
-                                role: proto::LanguageModelRole::LanguageModelAssistant as i32,
-                                content: content.unwrap_or_default(),
-                                tool_call_id: None,
-                                tool_calls: tool_calls
-                                    .into_iter()
-                                    .map(|tool_call| match tool_call.content {
-                                        open_ai::ToolCallContent::Function { function } => {
-                                            proto::ToolCall {
-                                                id: tool_call.id,
-                                                variant: Some(proto::tool_call::Variant::Function(
-                                                    proto::tool_call::FunctionCall {
-                                                        name: function.name,
-                                                        arguments: function.arguments,
-                                                    },
-                                                )),
-                                            }
-                                        }
-                                    })
-                                    .collect(),
-                            },
-                            CompletionMessage::User { content } => {
-                                proto::LanguageModelRequestMessage {
-                                    role: proto::LanguageModelRole::LanguageModelUser as i32,
-                                    content,
-                                    tool_call_id: None,
-                                    tool_calls: Vec::new(),
-                                }
-                            }
-                            CompletionMessage::System { content } => {
-                                proto::LanguageModelRequestMessage {
-                                    role: proto::LanguageModelRole::LanguageModelSystem as i32,
-                                    content,
-                                    tool_calls: Vec::new(),
-                                    tool_call_id: None,
-                                }
-                            }
-                            CompletionMessage::Tool {
-                                content,
-                                tool_call_id,
-                            } => proto::LanguageModelRequestMessage {
-                                role: proto::LanguageModelRole::LanguageModelTool as i32,
-                                content,
-                                tool_call_id: Some(tool_call_id),
-                                tool_calls: Vec::new(),
-                            },
-                        })
-                        .collect(),
-                    stop,
-                    temperature,
-                    tool_choice,
-                    tools,
-                })
-                .await?;
+        let authenticate = if provider.is_authenticated(cx) {
+            Task::ready(Ok(()))
+        } else {
+            provider.authenticate(cx)
+        };
+        cx.spawn(|cx| async move {
+            authenticate.await?;
+            cx.update(|cx| provider.complete(request, cx))?.await
+        })
+    }
+}
+
+impl Global for CompletionProvider {}
+
+/// Reads the provider's API key from the environment variable or, if it isn't set, from the
+/// credentials that are stored for the API's URL.
+fn read_api_key(env_var: &'static str, api_url: String, cx: &AppContext) -> Task<Result<String>> {
+    if let Ok(api_key) = env::var(env_var) {
+        return Task::ready(Ok(api_key));
+    }
+    let read_credentials = cx.read_credentials(&api_url);
+    cx.spawn(|_| async move {
+        let (_, api_key) = read_credentials
+            .await?
+            .ok_or_else(|| anyhow!("no API key for {api_url}, set {env_var} to provide one"))?;
+        Ok(String::from_utf8(api_key)?)
+    })
+}
 
-            Ok(stream
-                .filter_map(|response| async move {
-                    match response {
-                        Ok(mut response) => Some(Ok(response.choices.pop()?.delta?)),
-                        Err(error) => Some(Err(error)),
+/// Counts the tokens of the messages with OpenAI's tokenizer for the model or, when it isn't one
+/// of OpenAI's, with GPT-4's.
+fn count_open_ai_tokens(
+    model: &str,
+    messages: Vec<CompletionMessage>,
+    executor: &BackgroundExecutor,
+) -> BoxFuture<'static, Result<usize>> {
+    let model = model.to_string();
+    executor
+        .spawn(async move {
+            let messages = messages
+                .into_iter()
+                .map(|message| {
+                    let (role, content) = match message {
+                        CompletionMessage::System { content } => ("system", content),
+                        CompletionMessage::User { content } => ("user", content),
+                        CompletionMessage::Tool { content, .. } => ("tool", content),
+                        CompletionMessage::Assistant {
+                            content,
+                            tool_calls,
+                        } => {
+                            // The calls' names and arguments take up tokens too.
+                            let mut content = content.unwrap_or_default();
+                            for tool_call in tool_calls {
+                                let ::open_ai::ToolCallContent::Function { function } =
+                                    tool_call.content;
+                                content.push_str(&function.name);
+                                content.push_str(&function.arguments);
+                            }
+                            ("assistant", content)
+                        }
+                    };
+                    tiktoken_rs::ChatCompletionRequestMessage {
+                        role: role.into(),
+                        content: Some(content),
+                        name: None,
+                        function_call: None,
                     }
                 })
-                .boxed())
-        }
+                .collect::<Vec<_>>();
+            tiktoken_rs::num_tokens_from_messages(&model, &messages)
+                .or_else(|_| tiktoken_rs::num_tokens_from_messages("gpt-4", &messages))
+        })
+        .boxed()
+}
+
+/// A stream of the response's content, for providers that don't stream tool calls.
+fn content_stream(
+    content: BoxStream<'static, Result<String>>,
+) -> BoxStream<'static, Result<proto::LanguageModelResponseMessage>> {
+    content
+        .map(|content| {
+            Ok(proto::LanguageModelResponseMessage {
+                role: None,
+                content: Some(content?),
+                tool_calls: Vec::new(),
+            })
+        })
         .boxed()
-    }
 }
@@ -0,0 +1,201 @@
+use super::{
+    content_stream, read_api_key, CompletionMessage, CompletionRequest, LanguageModelProvider,
+};
+use crate::AssistantSettings;
+use anthropic::{ContentBlock, Model, Request, RequestMessage, ResponseEvent, Role, TextDelta};
+use anyhow::{anyhow, Result};
+use client::proto;
+use futures::{future::BoxFuture, stream::BoxStream, FutureExt, StreamExt};
+use gpui::{AppContext, Task};
+use http::HttpClient;
+use parking_lot::Mutex;
+use settings::Settings;
+use std::sync::Arc;
+
+/// The most tokens that Anthropic's models are asked to respond with.
+const MAX_TOKENS: u32 = 4096;
+
+pub struct AnthropicCompletionProvider {
+    http_client: Arc<dyn HttpClient>,
+    api_key: Arc<Mutex<Option<String>>>,
+}
+
+impl AnthropicCompletionProvider {
+    pub fn new(http_client: Arc<dyn HttpClient>) -> Self {
+        Self {
+            http_client,
+            api_key: Default::default(),
+        }
+    }
+}
+
+impl LanguageModelProvider for AnthropicCompletionProvider {
+    fn id(&self) -> &'static str {
+        "anthropic"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "Anthropic"
+    }
+
+    fn available_models(&self, cx: &AppContext) -> Vec<String> {
+        AssistantSettings::get_global(cx).anthropic.models.clone()
+    }
+
+    fn supports_tools(&self) -> bool {
+        false
+    }
+
+    fn max_token_count(&self, model: &str) -> usize {
+        Model::from_id(model)
+            .unwrap_or(Model::Claude3Haiku)
+            .max_token_count()
+    }
+
+    fn is_authenticated(&self, _: &AppContext) -> bool {
+        self.api_key.lock().is_some()
+    }
+
+    fn authenticate(&self, cx: &mut AppContext) -> Task<Result<()>> {
+        let api_url = AssistantSettings::get_global(cx).anthropic.api_url.clone();
+        let read_api_key = read_api_key("ANTHROPIC_API_KEY", api_url, cx);
+        let api_key = self.api_key.clone();
+        cx.background_executor().spawn(async move {
+            *api_key.lock() = Some(read_api_key.await?);
+            Ok(())
+        })
+    }
+
+    fn complete(
+        &self,
+        request: CompletionRequest,
+        cx: &AppContext,
+    ) -> BoxFuture<'static, Result<BoxStream<'static, Result<proto::LanguageModelResponseMessage>>>>
+    {
+        let api_url = AssistantSettings::get_global(cx).anthropic.api_url.clone();
+        let api_key = self.api_key.lock().clone();
+        let http_client = self.http_client.clone();
+        async move {
+            let api_key = api_key.ok_or_else(|| anyhow!("missing Anthropic API key"))?;
+            let request = to_anthropic_request(request)?;
+            let events = anthropic::stream_completion(
+                http_client.as_ref(),
+                &api_url,
+                &api_key,
+                request,
+                None,
+            )
+            .await?;
+            let content = events
+                .filter_map(|event| async move {
+                    match event {
+                        Ok(ResponseEvent::ContentBlockStart {
+                            content_block: ContentBlock::Text { text },
+                            ..
+                        })
+                        | Ok(ResponseEvent::ContentBlockDelta {
+                            delta: TextDelta::TextDelta { text },
+                            ..
+                        }) => Some(Ok(text)),
+                        Ok(_) => None,
+                        Err(error) => Some(Err(error)),
+                    }
+                })
+                .boxed();
+            Ok(content_stream(content))
+        }
+        .boxed()
+    }
+}
+
+/// Anthropic's API takes the system prompt separately from the messages, which have to alternate
+/// between the user and the assistant, so consecutive messages from either are joined. The
+/// results of tools are sent as the user's, since the models are never offered tools.
+fn to_anthropic_request(request: CompletionRequest) -> Result<Request> {
+    let mut system = String::new();
+    let mut messages: Vec<RequestMessage> = Vec::new();
+    for message in request.messages {
+        let (role, content) = match message {
+            CompletionMessage::System { content } => {
+                if !system.is_empty() {
+                    system.push_str("\n\n");
+                }
+                system.push_str(&content);
+                continue;
+            }
+            CompletionMessage::User { content } | CompletionMessage::Tool { content, .. } => {
+                (Role::User, content)
+            }
+            CompletionMessage::Assistant { content, .. } => {
+                (Role::Assistant, content.unwrap_or_default())
+            }
+        };
+        if content.is_empty() {
+            continue;
+        }
+        match messages.last_mut() {
+            Some(last_message) if last_message.role == role => {
+                last_message.content.push_str("\n\n");
+                last_message.content.push_str(&content);
+            }
+            _ => messages.push(RequestMessage { role, content }),
+        }
+    }
+
+    Ok(Request {
+        model: Model::from_id(&request.model)?,
+        messages,
+        stream: true,
+        system,
+        max_tokens: request.max_tokens.unwrap_or(MAX_TOKENS),
+        temperature: Some(request.temperature),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_anthropic_request() {
+        let request = to_anthropic_request(CompletionRequest {
+            model: "claude-3-haiku-20240307".into(),
+            messages: vec![
+                CompletionMessage::System {
+                    content: "Be brief.".into(),
+                },
+                CompletionMessage::User {
+                    content: "What's in main.rs?".into(),
+                },
+                CompletionMessage::Tool {
+                    content: "fn main() {}".into(),
+                    tool_call_id: "1".into(),
+                },
+                CompletionMessage::Assistant {
+                    content: Some("An empty main function.".into()),
+                    tool_calls: Vec::new(),
+                },
+            ],
+            stop: Vec::new(),
+            temperature: 1.0,
+            max_tokens: None,
+            tools: Vec::new(),
+        })
+        .unwrap();
+        assert_eq!(request.model, Model::Claude3Haiku);
+        assert_eq!(request.system, "Be brief.");
+        assert_eq!(
+            request.messages,
+            vec![
+                RequestMessage {
+                    role: Role::User,
+                    content: "What's in main.rs?\n\nfn main() {}".into(),
+                },
+                RequestMessage {
+                    role: Role::Assistant,
+                    content: "An empty main function.".into(),
+                },
+            ]
+        );
+    }
+}
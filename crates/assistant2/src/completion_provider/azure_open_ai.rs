@@ -0,0 +1,103 @@
+use super::{
+    open_ai::{open_ai_max_token_count, stream_completion},
+    read_api_key, CompletionRequest, LanguageModelProvider,
+};
+use crate::AssistantSettings;
+use anyhow::{anyhow, Result};
+use client::proto;
+use futures::{future::BoxFuture, stream::BoxStream, FutureExt};
+use gpui::{AppContext, Task};
+use http::HttpClient;
+use parking_lot::Mutex;
+use settings::Settings;
+use std::sync::Arc;
+
+/// Requests completions from the deployments of an Azure OpenAI resource, which are its models.
+pub struct AzureOpenAiCompletionProvider {
+    http_client: Arc<dyn HttpClient>,
+    api_key: Arc<Mutex<Option<String>>>,
+}
+
+impl AzureOpenAiCompletionProvider {
+    pub fn new(http_client: Arc<dyn HttpClient>) -> Self {
+        Self {
+            http_client,
+            api_key: Default::default(),
+        }
+    }
+}
+
+impl LanguageModelProvider for AzureOpenAiCompletionProvider {
+    fn id(&self) -> &'static str {
+        "azure_openai"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "Azure OpenAI"
+    }
+
+    fn available_models(&self, cx: &AppContext) -> Vec<String> {
+        AssistantSettings::get_global(cx)
+            .azure_openai
+            .deployments
+            .clone()
+    }
+
+    fn supports_tools(&self) -> bool {
+        true
+    }
+
+    fn max_token_count(&self, deployment: &str) -> usize {
+        open_ai_max_token_count(deployment)
+    }
+
+    fn is_authenticated(&self, _: &AppContext) -> bool {
+        self.api_key.lock().is_some()
+    }
+
+    fn authenticate(&self, cx: &mut AppContext) -> Task<Result<()>> {
+        let endpoint = AssistantSettings::get_global(cx)
+            .azure_openai
+            .endpoint
+            .clone();
+        if endpoint.is_empty() {
+            return Task::ready(Err(anyhow!(
+                "set assistant_v2.azure_openai.endpoint to use Azure OpenAI"
+            )));
+        }
+        let read_api_key = read_api_key("AZURE_OPENAI_API_KEY", endpoint, cx);
+        let api_key = self.api_key.clone();
+        cx.background_executor().spawn(async move {
+            *api_key.lock() = Some(read_api_key.await?);
+            Ok(())
+        })
+    }
+
+    fn complete(
+        &self,
+        request: CompletionRequest,
+        cx: &AppContext,
+    ) -> BoxFuture<'static, Result<BoxStream<'static, Result<proto::LanguageModelResponseMessage>>>>
+    {
+        let settings = &AssistantSettings::get_global(cx).azure_openai;
+        let uri = format!(
+            "{}/openai/deployments/{}/chat/completions?api-version={}",
+            settings.endpoint.trim_end_matches('/'),
+            request.model,
+            settings.api_version
+        );
+        let api_key = self.api_key.lock().clone();
+        let http_client = self.http_client.clone();
+        async move {
+            let api_key = api_key.ok_or_else(|| anyhow!("missing Azure OpenAI API key"))?;
+            stream_completion(
+                http_client.as_ref(),
+                &uri,
+                Some(("api-key", &api_key)),
+                request,
+            )
+            .await
+        }
+        .boxed()
+    }
+}
@@ -0,0 +1,162 @@
+use super::{
+    open_ai::open_ai_max_token_count, CompletionMessage, CompletionRequest, LanguageModelProvider,
+};
+use anyhow::Result;
+use client::{proto, Client};
+use futures::{future::BoxFuture, stream::BoxStream, FutureExt, StreamExt};
+use gpui::{AppContext, Task};
+use std::sync::Arc;
+
+pub struct CloudCompletionProvider {
+    client: Arc<Client>,
+}
+
+impl CloudCompletionProvider {
+    pub fn new(client: Arc<Client>) -> Self {
+        Self { client }
+    }
+}
+
+impl LanguageModelProvider for CloudCompletionProvider {
+    fn id(&self) -> &'static str {
+        "zed.dev"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "Zed"
+    }
+
+    fn available_models(&self, _: &AppContext) -> Vec<String> {
+        vec!["gpt-4-turbo".into(), "gpt-4".into(), "gpt-3.5-turbo".into()]
+    }
+
+    fn supports_tools(&self) -> bool {
+        true
+    }
+
+    fn max_token_count(&self, model: &str) -> usize {
+        open_ai_max_token_count(model)
+    }
+
+    fn is_authenticated(&self, _: &AppContext) -> bool {
+        self.client.status().borrow().is_connected()
+    }
+
+    fn authenticate(&self, cx: &mut AppContext) -> Task<Result<()>> {
+        let client = self.client.clone();
+        cx.spawn(move |cx| async move { client.authenticate_and_connect(true, &cx).await })
+    }
+
+    fn complete(
+        &self,
+        request: CompletionRequest,
+        _: &AppContext,
+    ) -> BoxFuture<'static, Result<BoxStream<'static, Result<proto::LanguageModelResponseMessage>>>>
+    {
+        let CompletionRequest {
+            model,
+            messages,
+            stop,
+            temperature,
+            // zed.dev doesn't take a limit on the tokens of the response.
+            max_tokens: _,
+            tools,
+        } = request;
+        let client = self.client.clone();
+        let tools: Vec<proto::ChatCompletionTool> = tools
+            .iter()
+            .filter_map(|tool| {
+                Some(proto::ChatCompletionTool {
+                    variant: Some(proto::chat_completion_tool::Variant::Function(
+                        proto::chat_completion_tool::FunctionObject {
+                            name: tool.name.clone(),
+                            description: Some(tool.description.clone()),
+                            parameters: Some(serde_json::to_string(&tool.parameters).ok()?),
+                        },
+                    )),
+                })
+            })
+            .collect();
+
+        let tool_choice = match tools.is_empty() {
+            true => None,
+            false => Some("auto".into()),
+        };
+
+        async move {
+            let stream = client
+                .request_stream(proto::CompleteWithLanguageModel {
+                    model,
+                    messages: messages
+                        .into_iter()
+                        .map(|message| match message {
+                            CompletionMessage::Assistant {
+                                content,
+                                tool_calls,
+                            } => proto::LanguageModelRequestMessage {
+                                role: proto::LanguageModelRole::LanguageModelAssistant as i32,
+                                content: content.unwrap_or_default(),
+                                tool_call_id: None,
+                                tool_calls: tool_calls
+                                    .into_iter()
+                                    .map(|tool_call| match tool_call.content {
+                                        open_ai::ToolCallContent::Function { function } => {
+                                            proto::ToolCall {
+                                                id: tool_call.id,
+                                                variant: Some(proto::tool_call::Variant::Function(
+                                                    proto::tool_call::FunctionCall {
+                                                        name: function.name,
+                                                        arguments: function.arguments,
+                                                    },
+                                                )),
+                                            }
+                                        }
+                                    })
+                                    .collect(),
+                            },
+                            CompletionMessage::User { content } => {
+                                proto::LanguageModelRequestMessage {
+                                    role: proto::LanguageModelRole::LanguageModelUser as i32,
+                                    content,
+                                    tool_call_id: None,
+                                    tool_calls: Vec::new(),
+                                }
+                            }
+                            CompletionMessage::System { content } => {
+                                proto::LanguageModelRequestMessage {
+                                    role: proto::LanguageModelRole::LanguageModelSystem as i32,
+                                    content,
+                                    tool_calls: Vec::new(),
+                                    tool_call_id: None,
+                                }
+                            }
+                            CompletionMessage::Tool {
+                                content,
+                                tool_call_id,
+                            } => proto::LanguageModelRequestMessage {
+                                role: proto::LanguageModelRole::LanguageModelTool as i32,
+                                content,
+                                tool_call_id: Some(tool_call_id),
+                                tool_calls: Vec::new(),
+                            },
+                        })
+                        .collect(),
+                    stop,
+                    temperature,
+                    tool_choice,
+                    tools,
+                })
+                .await?;
+
+            Ok(stream
+                .filter_map(|response| async move {
+                    match response {
+                        Ok(mut response) => Some(Ok(response.choices.pop()?.delta?)),
+                        Err(error) => Some(Err(error)),
+                    }
+                })
+                .boxed())
+        }
+        .boxed()
+    }
+}
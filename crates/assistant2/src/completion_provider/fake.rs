@@ -0,0 +1,109 @@
+use super::{CompletionMessage, CompletionRequest, LanguageModelProvider};
+use anyhow::Result;
+use client::proto;
+use futures::{
+    channel::mpsc,
+    future::{self, BoxFuture},
+    stream::BoxStream,
+    FutureExt, StreamExt,
+};
+use gpui::{AppContext, Task};
+use parking_lot::{Mutex, MutexGuard};
+
+/// A provider whose responses are streamed by tests, chunk by chunk.
+#[derive(Default)]
+pub struct FakeLanguageModelProvider {
+    pending_completions: Mutex<Vec<PendingCompletion>>,
+}
+
+pub struct PendingCompletion {
+    pub request: CompletionRequest,
+    tx: mpsc::UnboundedSender<Result<proto::LanguageModelResponseMessage>>,
+}
+
+impl PendingCompletion {
+    /// Whether the response is still open, which it stops being once it's finished or dropped.
+    pub fn is_streaming(&self) -> bool {
+        !self.tx.is_closed()
+    }
+}
+
+impl FakeLanguageModelProvider {
+    /// The completions that have been requested, oldest first.
+    pub fn pending_completions(&self) -> MutexGuard<Vec<PendingCompletion>> {
+        self.pending_completions.lock()
+    }
+
+    /// Sends a chunk of the response to the last request.
+    pub fn send_last_completion_chunk(&self, content: &str) {
+        let pending_completions = self.pending_completions.lock();
+        let pending_completion = pending_completions.last().expect("no pending completion");
+        pending_completion
+            .tx
+            .unbounded_send(Ok(proto::LanguageModelResponseMessage {
+                role: Some(proto::LanguageModelRole::LanguageModelAssistant as i32),
+                content: Some(content.to_string()),
+                tool_calls: Vec::new(),
+            }))
+            .ok();
+    }
+
+    /// Ends the response to the last request.
+    pub fn finish_last_completion(&self) {
+        let pending_completions = self.pending_completions.lock();
+        let pending_completion = pending_completions.last().expect("no pending completion");
+        pending_completion.tx.close_channel();
+    }
+}
+
+impl LanguageModelProvider for FakeLanguageModelProvider {
+    fn id(&self) -> &'static str {
+        "fake"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "Fake"
+    }
+
+    fn available_models(&self, _: &AppContext) -> Vec<String> {
+        vec!["fake-model".into(), "other-fake-model".into()]
+    }
+
+    fn supports_tools(&self) -> bool {
+        false
+    }
+
+    fn max_token_count(&self, _: &str) -> usize {
+        100_000
+    }
+
+    fn count_tokens(
+        &self,
+        _: &str,
+        messages: Vec<CompletionMessage>,
+        _: &AppContext,
+    ) -> BoxFuture<'static, Result<usize>> {
+        future::ready(Ok(messages.len())).boxed()
+    }
+
+    fn is_authenticated(&self, _: &AppContext) -> bool {
+        true
+    }
+
+    fn authenticate(&self, _: &mut AppContext) -> Task<Result<()>> {
+        Task::ready(Ok(()))
+    }
+
+    fn complete(
+        &self,
+        request: CompletionRequest,
+        _: &AppContext,
+    ) -> BoxFuture<'static, Result<BoxStream<'static, Result<proto::LanguageModelResponseMessage>>>>
+    {
+        let (tx, rx) = mpsc::unbounded();
+        self.pending_completions
+            .lock()
+            .push(PendingCompletion { request, tx });
+        future::ready(Ok(rx.boxed())).boxed()
+    }
+}
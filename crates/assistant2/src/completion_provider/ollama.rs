@@ -0,0 +1,111 @@
+use super::{open_ai::stream_completion, CompletionRequest, LanguageModelProvider};
+use crate::AssistantSettings;
+use anyhow::{anyhow, Result};
+use client::proto;
+use futures::{future::BoxFuture, stream::BoxStream, AsyncReadExt, FutureExt};
+use gpui::{AppContext, Task};
+use http::{AsyncBody, HttpClient};
+use parking_lot::Mutex;
+use serde::Deserialize;
+use settings::Settings;
+use std::sync::Arc;
+
+/// The context length that Ollama runs models with unless they're configured otherwise.
+const CONTEXT_LENGTH: usize = 2048;
+
+/// Requests completions from the models that Ollama serves locally, which don't need an API key.
+pub struct OllamaCompletionProvider {
+    http_client: Arc<dyn HttpClient>,
+    /// The models that Ollama has pulled, which are listed when authenticating, and until then
+    /// are the ones in the settings.
+    models: Arc<Mutex<Option<Vec<String>>>>,
+}
+
+#[derive(Deserialize)]
+struct Tags {
+    models: Vec<Tag>,
+}
+
+#[derive(Deserialize)]
+struct Tag {
+    name: String,
+}
+
+impl OllamaCompletionProvider {
+    pub fn new(http_client: Arc<dyn HttpClient>) -> Self {
+        Self {
+            http_client,
+            models: Default::default(),
+        }
+    }
+}
+
+impl LanguageModelProvider for OllamaCompletionProvider {
+    fn id(&self) -> &'static str {
+        "ollama"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "Ollama"
+    }
+
+    fn available_models(&self, cx: &AppContext) -> Vec<String> {
+        self.models
+            .lock()
+            .clone()
+            .unwrap_or_else(|| AssistantSettings::get_global(cx).ollama.models.clone())
+    }
+
+    fn supports_tools(&self) -> bool {
+        false
+    }
+
+    fn max_token_count(&self, _: &str) -> usize {
+        CONTEXT_LENGTH
+    }
+
+    fn is_authenticated(&self, _: &AppContext) -> bool {
+        self.models.lock().is_some()
+    }
+
+    fn authenticate(&self, cx: &mut AppContext) -> Task<Result<()>> {
+        let uri = format!(
+            "{}/api/tags",
+            AssistantSettings::get_global(cx).ollama.api_url
+        );
+        let http_client = self.http_client.clone();
+        let models = self.models.clone();
+        cx.background_executor().spawn(async move {
+            let mut response = http_client
+                .get(&uri, AsyncBody::default(), false)
+                .await
+                .map_err(|error| anyhow!("failed to connect to Ollama at {uri}: {error}"))?;
+            let mut body = Vec::new();
+            response.body_mut().read_to_end(&mut body).await?;
+            if !response.status().is_success() {
+                return Err(anyhow!(
+                    "failed to list Ollama's models, status: {}, body: {}",
+                    response.status(),
+                    String::from_utf8_lossy(&body)
+                ));
+            }
+            let tags: Tags = serde_json::from_slice(&body)?;
+            *models.lock() = Some(tags.models.into_iter().map(|tag| tag.name).collect());
+            Ok(())
+        })
+    }
+
+    fn complete(
+        &self,
+        request: CompletionRequest,
+        cx: &AppContext,
+    ) -> BoxFuture<'static, Result<BoxStream<'static, Result<proto::LanguageModelResponseMessage>>>>
+    {
+        let uri = format!(
+            "{}/v1/chat/completions",
+            AssistantSettings::get_global(cx).ollama.api_url
+        );
+        let http_client = self.http_client.clone();
+        async move { stream_completion(http_client.as_ref(), &uri, None, request).await }.boxed()
+    }
+}
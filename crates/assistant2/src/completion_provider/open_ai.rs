@@ -0,0 +1,187 @@
+use super::{read_api_key, CompletionMessage, CompletionRequest, LanguageModelProvider};
+use crate::AssistantSettings;
+use anyhow::{anyhow, Result};
+use client::proto;
+use futures::{future::BoxFuture, stream::BoxStream, FutureExt, StreamExt};
+use gpui::{AppContext, Task};
+use http::HttpClient;
+use open_ai::{FunctionDefinition, ResponseStreamEvent, ToolDefinition};
+use parking_lot::Mutex;
+use serde::Serialize;
+use settings::Settings;
+use std::sync::Arc;
+
+pub struct OpenAiCompletionProvider {
+    http_client: Arc<dyn HttpClient>,
+    api_key: Arc<Mutex<Option<String>>>,
+}
+
+impl OpenAiCompletionProvider {
+    pub fn new(http_client: Arc<dyn HttpClient>) -> Self {
+        Self {
+            http_client,
+            api_key: Default::default(),
+        }
+    }
+}
+
+impl LanguageModelProvider for OpenAiCompletionProvider {
+    fn id(&self) -> &'static str {
+        "openai"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "OpenAI"
+    }
+
+    fn available_models(&self, cx: &AppContext) -> Vec<String> {
+        AssistantSettings::get_global(cx).openai.models.clone()
+    }
+
+    fn supports_tools(&self) -> bool {
+        true
+    }
+
+    fn max_token_count(&self, model: &str) -> usize {
+        open_ai_max_token_count(model)
+    }
+
+    fn is_authenticated(&self, _: &AppContext) -> bool {
+        self.api_key.lock().is_some()
+    }
+
+    fn authenticate(&self, cx: &mut AppContext) -> Task<Result<()>> {
+        let api_url = AssistantSettings::get_global(cx).openai.api_url.clone();
+        let read_api_key = read_api_key("OPENAI_API_KEY", api_url, cx);
+        let api_key = self.api_key.clone();
+        cx.background_executor().spawn(async move {
+            *api_key.lock() = Some(read_api_key.await?);
+            Ok(())
+        })
+    }
+
+    fn complete(
+        &self,
+        request: CompletionRequest,
+        cx: &AppContext,
+    ) -> BoxFuture<'static, Result<BoxStream<'static, Result<proto::LanguageModelResponseMessage>>>>
+    {
+        let uri = format!(
+            "{}/chat/completions",
+            AssistantSettings::get_global(cx).openai.api_url
+        );
+        let api_key = self.api_key.lock().clone();
+        let http_client = self.http_client.clone();
+        async move {
+            let api_key = api_key.ok_or_else(|| anyhow!("missing OpenAI API key"))?;
+            stream_completion(
+                http_client.as_ref(),
+                &uri,
+                Some(("Authorization", &format!("Bearer {api_key}"))),
+                request,
+            )
+            .await
+        }
+        .boxed()
+    }
+}
+
+/// The size of the context window of OpenAI's model with the given name, which is also how
+/// models that are served under other names, like Azure OpenAI's deployments, are treated.
+pub(super) fn open_ai_max_token_count(model: &str) -> usize {
+    if model.starts_with("gpt-4o") || model.starts_with("gpt-4-turbo") {
+        128_000
+    } else if model.starts_with("gpt-4-32k") {
+        32_768
+    } else if model.starts_with("gpt-3.5-turbo") {
+        16_385
+    } else {
+        8_192
+    }
+}
+
+/// A request to an API that's compatible with OpenAI's, whose models aren't limited to OpenAI's.
+#[derive(Serialize)]
+struct Request {
+    model: String,
+    messages: Vec<CompletionMessage>,
+    stream: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    stop: Vec<String>,
+    temperature: f32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_choice: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tools: Vec<ToolDefinition>,
+}
+
+/// Streams a completion from an API that's compatible with OpenAI's, which OpenAI, Azure OpenAI
+/// and Ollama all serve.
+pub(super) async fn stream_completion(
+    http_client: &dyn HttpClient,
+    uri: &str,
+    auth_header: Option<(&str, &str)>,
+    request: CompletionRequest,
+) -> Result<BoxStream<'static, Result<proto::LanguageModelResponseMessage>>> {
+    let tools = request
+        .tools
+        .into_iter()
+        .map(|tool| ToolDefinition::Function {
+            function: FunctionDefinition {
+                name: tool.name,
+                description: Some(tool.description),
+                parameters: match serde_json::to_value(&tool.parameters) {
+                    Ok(serde_json::Value::Object(parameters)) => Some(parameters),
+                    _ => None,
+                },
+            },
+        })
+        .collect::<Vec<_>>();
+    let request = Request {
+        model: request.model,
+        messages: request.messages,
+        stream: true,
+        stop: request.stop,
+        temperature: request.temperature,
+        max_tokens: request.max_tokens,
+        tool_choice: (!tools.is_empty()).then(|| "auto".into()),
+        tools,
+    };
+    let events =
+        open_ai::stream_compatible_completion(http_client, uri, auth_header, &request, None)
+            .await?;
+    Ok(events
+        .filter_map(|event| async move {
+            match event {
+                Ok(event) => Some(Ok(response_message(event)?)),
+                Err(error) => Some(Err(error)),
+            }
+        })
+        .boxed())
+}
+
+fn response_message(mut event: ResponseStreamEvent) -> Option<proto::LanguageModelResponseMessage> {
+    let delta = event.choices.pop()?.delta;
+    Some(proto::LanguageModelResponseMessage {
+        role: None,
+        content: delta.content,
+        tool_calls: delta
+            .tool_calls
+            .into_iter()
+            .map(|tool_call| proto::ToolCallDelta {
+                index: tool_call.index as u32,
+                id: tool_call.id,
+                variant: tool_call.function.map(|function| {
+                    proto::tool_call_delta::Variant::Function(
+                        proto::tool_call_delta::FunctionCallDelta {
+                            name: function.name,
+                            arguments: function.arguments,
+                        },
+                    )
+                }),
+            })
+            .collect(),
+    })
+}
@@ -0,0 +1,226 @@
+use crate::{
+    assistant_settings::TruncationStrategy, CompletionMessage, CompletionProvider,
+    CompletionRequest, LanguageModel,
+};
+use anyhow::Result;
+use futures::StreamExt;
+use gpui::{AsyncWindowContext, ReadGlobal};
+use std::ops::Range;
+
+/// The most tokens that are kept free in the context window for the model's response.
+const RESPONSE_TOKENS: usize = 1024;
+
+const SUMMARY_PROMPT: &str = "Summarize the following conversation between a user and an \
+    assistant in a few paragraphs. Keep the facts, decisions and code that later messages may \
+    refer to, and leave out pleasantries.";
+
+/// The tokens that a conversation can take up in the model's context window, which leaves room
+/// for the model's response.
+pub(crate) fn token_budget(max_token_count: usize) -> usize {
+    max_token_count.saturating_sub(RESPONSE_TOKENS.min(max_token_count / 4))
+}
+
+/// Shortens the messages when they don't fit in the model's context window, by leaving out or
+/// summarizing the oldest turns of the conversation. The system prompt and the latest turn are
+/// always kept.
+pub(crate) async fn fit_to_context_window(
+    messages: Vec<CompletionMessage>,
+    model: &LanguageModel,
+    strategy: TruncationStrategy,
+    cx: &mut AsyncWindowContext,
+) -> Result<Vec<CompletionMessage>> {
+    let (max_token_count, token_count) = cx.update(|cx| {
+        let provider = CompletionProvider::global(cx);
+        (
+            provider.max_token_count(model),
+            provider.count_tokens(model, messages.clone(), cx),
+        )
+    })?;
+    let Some(max_token_count) = max_token_count else {
+        return Ok(messages);
+    };
+    let budget = token_budget(max_token_count);
+    let token_count = token_count.await?;
+    if token_count <= budget {
+        return Ok(messages);
+    }
+
+    let turns = turns(&messages);
+    let mut turn_token_counts = Vec::new();
+    for turn in turns.iter().take(turns.len().saturating_sub(1)) {
+        let count = cx.update(|cx| {
+            CompletionProvider::global(cx).count_tokens(model, messages[turn.clone()].to_vec(), cx)
+        })?;
+        turn_token_counts.push(count.await?);
+    }
+
+    let dropped_turns = turns_to_drop(token_count, &turn_token_counts, budget);
+    if dropped_turns == 0 {
+        return Ok(messages);
+    }
+
+    let dropped_range = turns[0].start..turns[dropped_turns - 1].end;
+    let mut messages = messages;
+    let dropped_messages = messages.drain(dropped_range.clone()).collect::<Vec<_>>();
+    if strategy == TruncationStrategy::Summarize {
+        let summary = summarize(dropped_messages, model, cx).await?;
+        messages.insert(
+            dropped_range.start,
+            CompletionMessage::System {
+                content: format!("A summary of the earlier conversation:\n\n{summary}"),
+            },
+        );
+    }
+    Ok(messages)
+}
+
+/// Splits the messages after the system prompt into turns, each of which is a message of the
+/// user's along with the context that was attached to it and the responses to it, including the
+/// results of the tools the model called.
+fn turns(messages: &[CompletionMessage]) -> Vec<Range<usize>> {
+    let mut turns: Vec<Range<usize>> = Vec::new();
+    let mut previous_was_response = true;
+    for (ix, message) in messages.iter().enumerate().skip(1) {
+        let is_response = matches!(
+            message,
+            CompletionMessage::Assistant { .. } | CompletionMessage::Tool { .. }
+        );
+        match turns.last_mut() {
+            Some(turn) if is_response || !previous_was_response => turn.end = ix + 1,
+            _ => turns.push(ix..ix + 1),
+        }
+        previous_was_response = is_response;
+    }
+    turns
+}
+
+/// How many of the oldest turns have to be left out for the conversation to fit in the budget,
+/// given the token counts of every turn but the latest, which is never left out.
+fn turns_to_drop(token_count: usize, turn_token_counts: &[usize], budget: usize) -> usize {
+    let mut token_count = token_count;
+    let mut dropped_turns = 0;
+    for turn_token_count in turn_token_counts {
+        if token_count <= budget {
+            break;
+        }
+        token_count = token_count.saturating_sub(*turn_token_count);
+        dropped_turns += 1;
+    }
+    dropped_turns
+}
+
+/// Asks the model to summarize the messages of the user and the assistant.
+async fn summarize(
+    messages: Vec<CompletionMessage>,
+    model: &LanguageModel,
+    cx: &mut AsyncWindowContext,
+) -> Result<String> {
+    let transcript = messages
+        .into_iter()
+        .filter_map(|message| match message {
+            CompletionMessage::User { content } => Some(format!("User: {content}")),
+            CompletionMessage::Assistant {
+                content: Some(content),
+                ..
+            } if !content.is_empty() => Some(format!("Assistant: {content}")),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+    let completion = cx.update(|cx| {
+        CompletionProvider::global(cx).clone().complete(
+            model,
+            CompletionRequest {
+                model: model.name.clone(),
+                messages: vec![
+                    CompletionMessage::System {
+                        content: SUMMARY_PROMPT.into(),
+                    },
+                    CompletionMessage::User {
+                        content: transcript,
+                    },
+                ],
+                stop: Vec::new(),
+                temperature: 1.0,
+                max_tokens: None,
+                tools: Vec::new(),
+            },
+            cx,
+        )
+    })?;
+
+    let mut stream = completion.await?;
+    let mut summary = String::new();
+    while let Some(message) = stream.next().await {
+        if let Some(content) = message?.content {
+            summary.push_str(&content);
+        }
+    }
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(content: &str) -> CompletionMessage {
+        CompletionMessage::User {
+            content: content.into(),
+        }
+    }
+
+    fn system(content: &str) -> CompletionMessage {
+        CompletionMessage::System {
+            content: content.into(),
+        }
+    }
+
+    fn assistant(content: &str) -> CompletionMessage {
+        CompletionMessage::Assistant {
+            content: Some(content.into()),
+            tool_calls: Vec::new(),
+        }
+    }
+
+    fn tool(content: &str) -> CompletionMessage {
+        CompletionMessage::Tool {
+            content: content.into(),
+            tool_call_id: "1".into(),
+        }
+    }
+
+    #[test]
+    fn test_turns() {
+        let messages = vec![
+            system("You are an assistant."),
+            user("What's in main.rs?"),
+            assistant(""),
+            tool("fn main() {}"),
+            assistant("An empty main function."),
+            system("The selected text is `main`."),
+            user("Rename it."),
+            assistant("Sure."),
+            user("Thanks!"),
+        ];
+        assert_eq!(turns(&messages), vec![1..5, 5..8, 8..9]);
+        assert_eq!(turns(&messages[..1]), Vec::<Range<usize>>::new());
+    }
+
+    #[test]
+    fn test_turns_to_drop() {
+        // The conversation already fits.
+        assert_eq!(turns_to_drop(100, &[30, 30], 100), 0);
+        // Leaving out the oldest turn is enough.
+        assert_eq!(turns_to_drop(120, &[30, 30], 100), 1);
+        assert_eq!(turns_to_drop(160, &[30, 30], 100), 2);
+        // The latest turn is kept even when it doesn't fit on its own.
+        assert_eq!(turns_to_drop(500, &[30, 30], 100), 2);
+    }
+
+    #[test]
+    fn test_token_budget() {
+        assert_eq!(token_budget(128_000), 128_000 - 1024);
+        assert_eq!(token_budget(2048), 2048 - 512);
+    }
+}
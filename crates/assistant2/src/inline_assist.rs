@@ -0,0 +1,503 @@
+use std::mem;
+use std::ops::{Range, RangeInclusive};
+
+use collections::HashSet;
+use editor::{
+    actions::Cancel,
+    display_map::{BlockContext, BlockDisposition, BlockId, BlockProperties, BlockStyle},
+    scroll::Autoscroll,
+    Anchor, Bias, Editor, EditorElement, EditorStyle, ToPoint, ViewBlock,
+};
+use futures::StreamExt;
+use gpui::{
+    FocusHandle, FocusableView, FontStyle, FontWeight, Hsla, ReadGlobal, Task, TextStyle, View,
+    WeakView, WhiteSpace,
+};
+use language::{language_settings::assistant_context_enabled, Point};
+use settings::Settings;
+use similar::{DiffTag, TextDiff};
+use theme::ThemeSettings;
+use ui::{prelude::*, Tooltip};
+use workspace::Workspace;
+
+use crate::{
+    AssistantPanel, CompletionMessage, CompletionProvider, CompletionRequest, InlineAssist,
+    LanguageModel,
+};
+
+/// How many lines around the rewritten ones are sent to the model, so it knows what they're for.
+const CONTEXT_LINES: u32 = 30;
+
+const SYSTEM_PROMPT: &str = "\
+You rewrite code as you're instructed to. The code to rewrite is between <rewrite> and \
+</rewrite>, and when there's nothing between them, you write the code to insert there. Respond \
+with only the code that replaces them, keeping the indentation of the code around it, without \
+explanations or Markdown code blocks.";
+
+/// Rewrites the selected lines, or generates code at the cursor, following an instruction that's
+/// typed above them. The rewrite is shown as a diff, with the lines it removes highlighted and the
+/// lines it adds shown below them, and only edits the buffer once it's accepted.
+pub struct InlineAssistant {
+    editor: WeakView<Editor>,
+    /// The lines that are rewritten, which is empty when code is generated at the cursor.
+    range: Range<Anchor>,
+    model: LanguageModel,
+    prompt_editor: View<Editor>,
+    prompt_block: Option<BlockId>,
+    /// The text of the lines when the rewrite was requested.
+    original: String,
+    /// The model's response, which is streamed in.
+    rewrite: String,
+    /// The instruction that the rewrite was requested with, once it has been.
+    instruction: Option<String>,
+    error: Option<SharedString>,
+    pending_completion: Option<Task<()>>,
+    deleted_rows: Vec<RangeInclusive<Anchor>>,
+    inserted_blocks: HashSet<BlockId>,
+}
+
+/// The highlight of the lines that a rewrite removes.
+enum InlineAssistDeletion {}
+
+impl InlineAssistant {
+    pub fn deploy(workspace: &mut Workspace, _: &InlineAssist, cx: &mut ViewContext<Workspace>) {
+        let Some(editor) = workspace
+            .active_item(cx)
+            .and_then(|item| item.act_as::<Editor>(cx))
+        else {
+            return;
+        };
+        let model = workspace
+            .panel::<AssistantPanel>(cx)
+            .map(|panel| panel.read(cx).chat.read(cx).model.clone())
+            .unwrap_or_else(|| CompletionProvider::global(cx).default_model(cx));
+
+        let selection = editor.read(cx).selections.newest_anchor().clone();
+        if selection.start.excerpt_id != selection.end.excerpt_id {
+            return;
+        }
+
+        // Whole lines are rewritten, though a line that's selected up to its start isn't.
+        let snapshot = editor.read(cx).buffer().read(cx).snapshot(cx);
+        let mut start = selection.start.to_point(&snapshot);
+        let mut end = selection.end.to_point(&snapshot);
+        if end > start {
+            start.column = 0;
+            if end.column == 0 {
+                end.row -= 1;
+            }
+            end = snapshot.clip_point(Point::new(end.row, u32::MAX), Bias::Left);
+        }
+        let range = snapshot.anchor_before(start)..snapshot.anchor_after(end);
+
+        let inline_assistant = cx.new_view(|cx| {
+            let prompt_editor = cx.new_view(|cx| {
+                let mut editor = Editor::single_line(cx);
+                editor.set_placeholder_text(
+                    if start == end {
+                        "Describe the code to generate…"
+                    } else {
+                        "Describe how to rewrite the selection…"
+                    },
+                    cx,
+                );
+                editor
+            });
+            Self {
+                editor: editor.downgrade(),
+                range: range.clone(),
+                model,
+                prompt_editor,
+                prompt_block: None,
+                original: String::new(),
+                rewrite: String::new(),
+                instruction: None,
+                error: None,
+                pending_completion: None,
+                deleted_rows: Vec::new(),
+                inserted_blocks: HashSet::default(),
+            }
+        });
+
+        let focus_handle = inline_assistant.focus_handle(cx);
+        editor.update(cx, |editor, cx| {
+            editor.change_selections(None, cx, |selections| {
+                selections.select_anchor_ranges([selection.head()..selection.head()])
+            });
+            let block_id = editor.insert_view_blocks(
+                [ViewBlock {
+                    position: snapshot.anchor_before(Point::new(start.row, 0)),
+                    disposition: BlockDisposition::Above,
+                    style: BlockStyle::Sticky,
+                    view: inline_assistant.clone().into(),
+                    focus_handle: Some(focus_handle.clone()),
+                }],
+                Some(Autoscroll::newest()),
+                cx,
+            )[0];
+            inline_assistant.update(cx, |inline_assistant, _| {
+                inline_assistant.prompt_block = Some(block_id)
+            });
+        });
+        cx.focus(&focus_handle);
+    }
+
+    /// Requests a rewrite with the instruction in the prompt, replacing any that was proposed.
+    fn generate(&mut self, _: &menu::Confirm, cx: &mut ViewContext<Self>) {
+        let instruction = self.prompt_editor.read(cx).text(cx);
+        if instruction.trim().is_empty() {
+            return;
+        }
+        let Some(editor) = self.editor.upgrade() else {
+            return;
+        };
+
+        let snapshot = editor.read(cx).buffer().read(cx).snapshot(cx);
+        let start = snapshot.point_to_buffer_offset(self.range.start);
+        let end = snapshot.point_to_buffer_offset(self.range.end);
+        let Some(((buffer, start), (_, end))) = start.zip(end) else {
+            return;
+        };
+        if !assistant_context_enabled(buffer.language(), buffer.file(), cx) {
+            self.error =
+                Some("This file is excluded from the assistant's context by your settings.".into());
+            cx.notify();
+            return;
+        }
+        self.original = buffer.text_for_range(start..end).collect();
+
+        let start_row = buffer.offset_to_point(start).row;
+        let end_row = buffer.offset_to_point(end).row;
+        let context_start = Point::new(start_row.saturating_sub(CONTEXT_LINES), 0);
+        let context_end = Point::new(end_row + CONTEXT_LINES + 1, 0).min(buffer.max_point());
+        let mut prompt = String::new();
+        if let Some(file) = buffer.file() {
+            prompt.push_str(&format!("This is part of {}", file.path().display()));
+        } else {
+            prompt.push_str("This is part of an untitled buffer");
+        }
+        if let Some(language) = buffer.language_at(start) {
+            prompt.push_str(&format!(", which is written in {}", language.name()));
+        }
+        prompt.push_str(":\n\n");
+        prompt.extend(buffer.text_for_range(context_start..buffer.offset_to_point(start)));
+        prompt.push_str("<rewrite>");
+        prompt.push_str(&self.original);
+        prompt.push_str("</rewrite>");
+        prompt.extend(buffer.text_for_range(buffer.offset_to_point(end)..context_end));
+        prompt.push_str("\n\n");
+        prompt.push_str(&instruction);
+
+        let completion = CompletionProvider::global(cx).clone().complete(
+            &self.model,
+            CompletionRequest {
+                model: self.model.name.clone(),
+                messages: vec![
+                    CompletionMessage::System {
+                        content: SYSTEM_PROMPT.into(),
+                    },
+                    CompletionMessage::User { content: prompt },
+                ],
+                stop: Vec::new(),
+                temperature: 0.5,
+                max_tokens: None,
+                tools: Vec::new(),
+            },
+            cx,
+        );
+
+        self.instruction = Some(instruction);
+        self.rewrite.clear();
+        self.error = None;
+        self.update_diff(cx);
+        self.pending_completion = Some(cx.spawn(|this, mut cx| async move {
+            let result = async {
+                let mut stream = completion.await?;
+                while let Some(delta) = stream.next().await {
+                    if let Some(content) = delta?.content {
+                        this.update(&mut cx, |this, cx| {
+                            this.rewrite.push_str(&content);
+                            this.update_diff(cx);
+                        })?;
+                    }
+                }
+                anyhow::Ok(())
+            }
+            .await;
+
+            this.update(&mut cx, |this, cx| {
+                this.pending_completion = None;
+                if let Err(error) = result {
+                    this.error = Some(error.to_string().into());
+                }
+                cx.notify();
+            })
+            .ok();
+        }));
+        cx.notify();
+    }
+
+    /// Replaces the lines with the rewrite.
+    fn accept(&mut self, _: &menu::SecondaryConfirm, cx: &mut ViewContext<Self>) {
+        if self.pending_completion.is_some() || self.instruction.is_none() {
+            return;
+        }
+
+        let rewrite = strip_code_block(&self.rewrite).to_string();
+        if let Some(editor) = self.editor.upgrade() {
+            editor.update(cx, |editor, cx| {
+                editor.edit([(self.range.clone(), rewrite)], cx);
+            });
+        }
+        self.dismiss(cx);
+    }
+
+    fn reject(&mut self, _: &Cancel, cx: &mut ViewContext<Self>) {
+        self.dismiss(cx);
+    }
+
+    fn dismiss(&mut self, cx: &mut ViewContext<Self>) {
+        self.pending_completion = None;
+        self.rewrite.clear();
+        self.instruction = None;
+        self.update_diff(cx);
+
+        let Some(editor) = self.editor.upgrade() else {
+            return;
+        };
+        editor.update(cx, |editor, cx| {
+            if let Some(block_id) = self.prompt_block.take() {
+                editor.remove_blocks(HashSet::from_iter([block_id]), None, cx);
+            }
+            editor.focus(cx);
+        });
+    }
+
+    /// Shows the diff between the lines and the rewrite, as far as it has been streamed.
+    fn update_diff(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(editor) = self.editor.upgrade() else {
+            return;
+        };
+
+        let deleted_color = deleted_color(cx);
+        let inserted_color = inserted_color(cx);
+        let original = if self.instruction.is_some() {
+            self.original.as_str()
+        } else {
+            ""
+        };
+        let rewrite = strip_code_block(&self.rewrite);
+        let diff = TextDiff::from_lines(original, rewrite);
+
+        editor.update(cx, |editor, cx| {
+            for rows in self.deleted_rows.drain(..) {
+                editor.highlight_rows::<InlineAssistDeletion>(rows, None, false, cx);
+            }
+            editor.remove_blocks(mem::take(&mut self.inserted_blocks), None, cx);
+
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            let start = self.range.start.to_point(&snapshot);
+            let inserts_at_cursor = self.range.start == self.range.end;
+            let mut blocks = Vec::new();
+            for op in diff.ops() {
+                let (tag, old_range, new_range) = op.as_tag_tuple();
+                if matches!(tag, DiffTag::Delete | DiffTag::Replace) {
+                    let first_row = start.row + old_range.start as u32;
+                    let last_row = start.row + old_range.end as u32 - 1;
+                    let rows = snapshot.anchor_before(Point::new(first_row, 0))
+                        ..=snapshot.anchor_before(Point::new(last_row, 0));
+                    editor.highlight_rows::<InlineAssistDeletion>(
+                        rows.clone(),
+                        Some(deleted_color),
+                        false,
+                        cx,
+                    );
+                    self.deleted_rows.push(rows);
+                }
+                if matches!(tag, DiffTag::Insert | DiffTag::Replace) {
+                    let lines = diff.new_slices()[new_range]
+                        .iter()
+                        .map(|line| SharedString::from(line.trim_end_matches('\n').to_string()))
+                        .collect::<Vec<_>>();
+                    let (row, disposition) = if old_range.end > 0 {
+                        (
+                            start.row + old_range.end as u32 - 1,
+                            BlockDisposition::Below,
+                        )
+                    } else if inserts_at_cursor {
+                        (start.row, BlockDisposition::Below)
+                    } else {
+                        (start.row, BlockDisposition::Above)
+                    };
+                    blocks.push(BlockProperties {
+                        position: snapshot.anchor_before(Point::new(row, 0)),
+                        height: lines.len().min(u8::MAX as usize) as u8,
+                        style: BlockStyle::Flex,
+                        render: Box::new(move |cx| {
+                            render_inserted_lines(&lines, inserted_color, cx)
+                        }),
+                        disposition,
+                    });
+                }
+            }
+            self.inserted_blocks = editor.insert_blocks(blocks, None, cx).into_iter().collect();
+        });
+        cx.notify();
+    }
+
+    fn render_prompt_editor(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let settings = ThemeSettings::get_global(cx);
+        let text_style = TextStyle {
+            color: cx.theme().colors().text,
+            font_family: settings.ui_font.family.clone(),
+            font_features: settings.ui_font.features.clone(),
+            font_size: rems(0.875).into(),
+            font_weight: FontWeight::NORMAL,
+            font_style: FontStyle::Normal,
+            line_height: relative(1.3),
+            background_color: None,
+            underline: None,
+            strikethrough: None,
+            white_space: WhiteSpace::Normal,
+        };
+        EditorElement::new(
+            &self.prompt_editor,
+            EditorStyle {
+                background: cx.theme().colors().editor_background,
+                local_player: cx.theme().players().local(),
+                text: text_style,
+                ..Default::default()
+            },
+        )
+    }
+}
+
+impl Render for InlineAssistant {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let is_generating = self.pending_completion.is_some();
+        let has_rewrite = self.instruction.is_some() && !is_generating;
+
+        h_flex()
+            .key_context("InlineAssistant")
+            .w_full()
+            .py_2()
+            .gap_2()
+            .border_y_1()
+            .border_color(cx.theme().colors().border)
+            .on_action(cx.listener(Self::generate))
+            .on_action(cx.listener(Self::accept))
+            .on_action(cx.listener(Self::reject))
+            .children(self.error.clone().map(|error| {
+                div()
+                    .id("inline-assist-error")
+                    .tooltip(move |cx| Tooltip::text(error.clone(), cx))
+                    .child(Icon::new(IconName::XCircle).color(Color::Error))
+            }))
+            .child(div().flex_1().child(self.render_prompt_editor(cx)))
+            .child(
+                h_flex()
+                    .gap_1()
+                    .pr_2()
+                    .when(is_generating, |element| {
+                        element.child(
+                            Label::new("Generating…")
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                    })
+                    .child(
+                        IconButton::new("regenerate", IconName::Update)
+                            .icon_size(IconSize::Small)
+                            .disabled(!has_rewrite)
+                            .on_click(cx.listener(|this, _, cx| this.generate(&menu::Confirm, cx)))
+                            .tooltip(|cx| Tooltip::for_action("Regenerate", &menu::Confirm, cx)),
+                    )
+                    .child(
+                        IconButton::new("accept", IconName::Check)
+                            .icon_size(IconSize::Small)
+                            .disabled(!has_rewrite)
+                            .on_click(
+                                cx.listener(|this, _, cx| this.accept(&menu::SecondaryConfirm, cx)),
+                            )
+                            .tooltip(|cx| {
+                                Tooltip::for_action("Accept", &menu::SecondaryConfirm, cx)
+                            }),
+                    )
+                    .child(
+                        IconButton::new("reject", IconName::Close)
+                            .icon_size(IconSize::Small)
+                            .on_click(cx.listener(|this, _, cx| this.reject(&Cancel, cx)))
+                            .tooltip(|cx| Tooltip::for_action("Reject", &Cancel, cx)),
+                    ),
+            )
+    }
+}
+
+impl FocusableView for InlineAssistant {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.prompt_editor.focus_handle(cx)
+    }
+}
+
+fn render_inserted_lines(lines: &[SharedString], color: Hsla, cx: &mut BlockContext) -> AnyElement {
+    let text_style = &cx.editor_style.text;
+    v_flex()
+        .w_full()
+        .pl(cx.gutter_dimensions.width + cx.gutter_dimensions.margin)
+        .bg(color)
+        .font_family(text_style.font_family.clone())
+        .text_size(text_style.font_size)
+        .children(lines.iter().map(|line| {
+            div()
+                .h(cx.line_height)
+                .whitespace_nowrap()
+                .overflow_hidden()
+                .child(line.clone())
+        }))
+        .into_any_element()
+}
+
+fn deleted_color(cx: &AppContext) -> Hsla {
+    let mut color = cx.theme().status().deleted;
+    color.fade_out(0.7);
+    color
+}
+
+fn inserted_color(cx: &AppContext) -> Hsla {
+    let mut color = cx.theme().status().created;
+    color.fade_out(0.7);
+    color
+}
+
+/// Models sometimes wrap their response in a Markdown code block despite being asked not to, so
+/// its fences are left out of the rewrite, including the opening one while it's still streamed.
+fn strip_code_block(text: &str) -> &str {
+    let mut text = text;
+    if text.starts_with("```") {
+        text = match text.find('\n') {
+            Some(newline) => &text[newline + 1..],
+            None => "",
+        };
+        let trimmed = text.trim_end();
+        if let Some(stripped) = trimmed.strip_suffix("```") {
+            text = stripped.strip_suffix('\n').unwrap_or(stripped);
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_code_block() {
+        assert_eq!(strip_code_block("let a = 1;"), "let a = 1;");
+        assert_eq!(strip_code_block("```"), "");
+        assert_eq!(strip_code_block("```rust\nlet a"), "let a");
+        assert_eq!(
+            strip_code_block("```rust\nlet a = 1;\nlet b = 2;\n```\n"),
+            "let a = 1;\nlet b = 2;"
+        );
+    }
+}
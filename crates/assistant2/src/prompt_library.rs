@@ -0,0 +1,181 @@
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::Result;
+use editor::Editor;
+use fs::Fs;
+use futures::StreamExt;
+use gpui::AppContext;
+use util::paths::PROMPTS_DIR;
+use workspace::Workspace;
+
+/// The extension of the prompts in the library, whose file names are their titles.
+const PROMPT_EXTENSION: &str = "md";
+
+/// What a new prompt starts out as, which documents the variables it can use.
+const NEW_PROMPT_TEMPLATE: &str = "\
+Review the following {language} code from `{file_path}`:
+
+```
+{selection}
+```
+";
+
+/// A reusable prompt that's stored as a Markdown file in the prompts directory, so it can be
+/// edited like any other file and shared by copying it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PromptTemplate {
+    pub title: String,
+    pub path: PathBuf,
+}
+
+impl PromptTemplate {
+    pub async fn list(fs: Arc<dyn Fs>) -> Result<Vec<Self>> {
+        fs.create_dir(&PROMPTS_DIR).await?;
+
+        let mut paths = fs.read_dir(&PROMPTS_DIR).await?;
+        let mut prompts = Vec::new();
+        while let Some(path) = paths.next().await {
+            let path = path?;
+            if path.extension() != Some(OsStr::new(PROMPT_EXTENSION)) {
+                continue;
+            }
+            if let Some(title) = path.file_stem().and_then(OsStr::to_str) {
+                prompts.push(Self {
+                    title: title.to_string(),
+                    path,
+                });
+            }
+        }
+        prompts.sort_unstable_by(|a, b| a.title.cmp(&b.title));
+
+        Ok(prompts)
+    }
+
+    pub async fn load(&self, fs: &dyn Fs) -> Result<String> {
+        fs.load(&self.path).await
+    }
+
+    /// Creates a prompt with an example of the variables it can use, returning its path.
+    pub async fn create(fs: &dyn Fs) -> Result<PathBuf> {
+        fs.create_dir(&PROMPTS_DIR).await?;
+
+        let path = new_prompt_path(&PROMPTS_DIR, fs).await;
+        fs.atomic_write(path.clone(), NEW_PROMPT_TEMPLATE.to_string())
+            .await?;
+        Ok(path)
+    }
+}
+
+async fn new_prompt_path(dir: &Path, fs: &dyn Fs) -> PathBuf {
+    let mut discriminant = 1;
+    loop {
+        let title = if discriminant == 1 {
+            "New Prompt".to_string()
+        } else {
+            format!("New Prompt {discriminant}")
+        };
+        let path = dir.join(format!("{title}.{PROMPT_EXTENSION}"));
+        if !fs.is_file(&path).await {
+            return path;
+        }
+        discriminant += 1;
+    }
+}
+
+/// The values of the variables that prompts are expanded with, which come from the active editor.
+#[derive(Default, Debug)]
+pub struct PromptContext {
+    pub selection: Option<String>,
+    pub file_path: Option<String>,
+    pub language: Option<String>,
+}
+
+impl PromptContext {
+    pub fn for_workspace(workspace: &Workspace, cx: &AppContext) -> Self {
+        let Some(editor) = workspace
+            .active_item(cx)
+            .and_then(|item| item.act_as::<Editor>(cx))
+        else {
+            return Self::default();
+        };
+
+        let editor = editor.read(cx);
+        let snapshot = editor.buffer().read(cx).snapshot(cx);
+        let selection = editor.selections.newest::<usize>(cx);
+        let selected_text = snapshot
+            .text_for_range(selection.range())
+            .collect::<String>();
+        let file_path = snapshot
+            .point_to_buffer_offset(selection.head())
+            .and_then(|(buffer, _)| Some(buffer.file()?.path().to_string_lossy().to_string()));
+        let language = snapshot
+            .language_at(selection.head())
+            .map(|language| language.name().to_string());
+
+        Self {
+            selection: Some(selected_text).filter(|text| !text.is_empty()),
+            file_path,
+            language,
+        }
+    }
+
+    /// Replaces the `{selection}`, `{file_path}` and `{language}` variables in the prompt. Any
+    /// other braces are left as they are, as are variables that have no value.
+    pub fn expand(&self, prompt: &str) -> String {
+        let mut expanded = String::with_capacity(prompt.len());
+        let mut rest = prompt;
+        while let Some(start) = rest.find('{') {
+            expanded.push_str(&rest[..start]);
+            rest = &rest[start..];
+
+            let value = rest.find('}').and_then(|end| {
+                let value = match &rest[1..end] {
+                    "selection" => self.selection.as_deref(),
+                    "file_path" => self.file_path.as_deref(),
+                    "language" => self.language.as_deref(),
+                    _ => None,
+                }?;
+                Some((value, end))
+            });
+            match value {
+                Some((value, end)) => {
+                    expanded.push_str(value);
+                    rest = &rest[end + 1..];
+                }
+                None => {
+                    expanded.push('{');
+                    rest = &rest[1..];
+                }
+            }
+        }
+        expanded.push_str(rest);
+        expanded
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_prompt() {
+        let context = PromptContext {
+            selection: Some("fn main() {}".into()),
+            file_path: Some("src/main.rs".into()),
+            language: Some("Rust".into()),
+        };
+        assert_eq!(
+            context.expand("Refactor this {language} from {file_path}:\n{selection}"),
+            "Refactor this Rust from src/main.rs:\nfn main() {}"
+        );
+        assert_eq!(
+            context.expand("Keep {braces}, {{language}} and {"),
+            "Keep {braces}, {Rust} and {"
+        );
+
+        let context = PromptContext::default();
+        assert_eq!(context.expand("Explain {selection}"), "Explain {selection}");
+    }
+}
@@ -1,18 +1,18 @@
 use std::cmp::Reverse;
 use std::ffi::OsStr;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use anyhow::Result;
 use assistant_tooling::{SavedToolFunctionCall, SavedUserAttachment};
-use fs::Fs;
+use fs::{Fs, RemoveOptions};
 use futures::StreamExt;
 use gpui::SharedString;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use util::paths::CONVERSATIONS_DIR;
 
-use crate::MessageId;
+use crate::{LanguageModel, MessageId};
 
 #[derive(Serialize, Deserialize)]
 pub struct SavedConversation {
@@ -20,6 +20,10 @@ pub struct SavedConversation {
     pub version: String,
     /// The title of the conversation, generated by the Assistant.
     pub title: String,
+    /// The model the conversation was last had with, which conversations saved before it was
+    /// recorded don't have.
+    #[serde(default)]
+    pub model: Option<LanguageModel>,
     pub messages: Vec<SavedChatMessage>,
 }
 
@@ -34,6 +38,10 @@ pub enum SavedChatMessage {
         id: MessageId,
         messages: Vec<SavedAssistantMessagePart>,
         error: Option<SharedString>,
+        /// The model that produced the message, which messages saved before it was recorded
+        /// don't have.
+        #[serde(default)]
+        model: Option<LanguageModel>,
     },
 }
 
@@ -43,6 +51,55 @@ pub struct SavedAssistantMessagePart {
     pub tool_calls: Vec<SavedToolFunctionCall>,
 }
 
+/// The longest title that's used in the file name of a conversation.
+const MAX_FILE_NAME_TITLE_LEN: usize = 60;
+
+impl SavedConversation {
+    pub async fn load(fs: &dyn Fs, path: &Path) -> Result<Self> {
+        let text = fs.load(path).await?;
+        Ok(serde_json::from_str(&text)?)
+    }
+
+    /// Saves the conversation at the given path or, when it hasn't been saved before, at a new
+    /// path named after its title, returning the path it's saved at.
+    pub async fn save(&self, fs: &dyn Fs, path: Option<PathBuf>) -> Result<PathBuf> {
+        fs.create_dir(&CONVERSATIONS_DIR).await?;
+        let path = match path {
+            Some(path) => path,
+            None => self.new_path(fs).await,
+        };
+        fs.atomic_write(path.clone(), serde_json::to_string(self)?)
+            .await?;
+        Ok(path)
+    }
+
+    async fn new_path(&self, fs: &dyn Fs) -> PathBuf {
+        let title = self
+            .title
+            .chars()
+            .map(|c| match c {
+                // Characters that aren't allowed in file names on some platforms.
+                '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' | '\n' | '\r' => ' ',
+                c => c,
+            })
+            .take(MAX_FILE_NAME_TITLE_LEN)
+            .collect::<String>();
+        let title = title.trim();
+        let mut discriminant = 1;
+        loop {
+            let path = CONVERSATIONS_DIR.join(format!(
+                "{title} - {discriminant}.zed.{version}.json",
+                version = self.version
+            ));
+            if !fs.is_file(&path).await {
+                return path;
+            }
+            discriminant += 1;
+        }
+    }
+}
+
+#[derive(Clone)]
 pub struct SavedConversationMetadata {
     pub title: String,
     pub path: PathBuf,
@@ -87,4 +144,117 @@ impl SavedConversationMetadata {
 
         Ok(conversations)
     }
+
+    /// Changes the title of the conversation, which moves it to a path named after the new
+    /// title, returning that path.
+    pub async fn rename(&self, fs: &dyn Fs, title: String) -> Result<PathBuf> {
+        let mut conversation = SavedConversation::load(fs, &self.path).await?;
+        conversation.title = title;
+        let path = conversation.save(fs, None).await?;
+        fs.remove_file(&self.path, RemoveOptions::default()).await?;
+        Ok(path)
+    }
+
+    pub async fn delete(&self, fs: &dyn Fs) -> Result<()> {
+        fs.remove_file(
+            &self.path,
+            RemoveOptions {
+                recursive: false,
+                ignore_if_not_exists: true,
+            },
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fs::FakeFs;
+    use gpui::TestAppContext;
+
+    #[test]
+    fn test_load_without_models() {
+        let conversation: SavedConversation = serde_json::from_str(
+            r#"{
+                "version": "0.2.0",
+                "title": "Hello",
+                "messages": [
+                    { "User": { "id": 0, "body": "Hello", "attachments": [] } },
+                    { "Assistant": { "id": 1, "messages": [], "error": null } }
+                ]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(conversation.model, None);
+        assert!(matches!(
+            conversation.messages[1],
+            SavedChatMessage::Assistant { model: None, .. }
+        ));
+    }
+
+    #[gpui::test]
+    async fn test_rename_and_delete(cx: &mut TestAppContext) {
+        let fs = FakeFs::new(cx.executor());
+        let conversation = SavedConversation {
+            version: "0.3.0".into(),
+            title: "Is a/b <c> | \"d\"?".into(),
+            model: None,
+            messages: Vec::new(),
+        };
+        let path = conversation.save(fs.as_ref(), None).await.unwrap();
+        assert_eq!(
+            path,
+            CONVERSATIONS_DIR.join("Is a b  c     d - 1.zed.0.3.0.json")
+        );
+        let other_path = conversation.save(fs.as_ref(), None).await.unwrap();
+        assert_eq!(
+            other_path,
+            CONVERSATIONS_DIR.join("Is a b  c     d - 2.zed.0.3.0.json")
+        );
+
+        // Renaming a conversation moves it to a path named after its new title.
+        let conversations = SavedConversationMetadata::list(fs.clone()).await.unwrap();
+        let metadata = conversations
+            .iter()
+            .find(|conversation| conversation.path == path)
+            .unwrap();
+        let renamed_path = metadata
+            .rename(fs.as_ref(), "Renamed: once".into())
+            .await
+            .unwrap();
+        assert_eq!(
+            renamed_path,
+            CONVERSATIONS_DIR.join("Renamed  once - 1.zed.0.3.0.json")
+        );
+        assert!(!fs.is_file(&path).await);
+        let renamed = SavedConversation::load(fs.as_ref(), &renamed_path)
+            .await
+            .unwrap();
+        assert_eq!(renamed.title, "Renamed: once");
+        assert_eq!(
+            titles(fs.clone()).await,
+            ["Renamed  once", "Is a b  c     d"]
+        );
+
+        // Deleting a conversation removes it, even when it's already been removed.
+        let conversations = SavedConversationMetadata::list(fs.clone()).await.unwrap();
+        let metadata = conversations
+            .iter()
+            .find(|conversation| conversation.path == renamed_path)
+            .unwrap();
+        metadata.delete(fs.as_ref()).await.unwrap();
+        metadata.delete(fs.as_ref()).await.unwrap();
+        assert!(!fs.is_file(&renamed_path).await);
+        assert_eq!(titles(fs.clone()).await, ["Is a b  c     d"]);
+    }
+
+    async fn titles(fs: Arc<FakeFs>) -> Vec<String> {
+        SavedConversationMetadata::list(fs)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|conversation| conversation.title)
+            .collect()
+    }
 }
@@ -1,19 +1,33 @@
+use std::path::PathBuf;
 use std::sync::Arc;
 
+use fs::Fs;
 use fuzzy::{match_strings, StringMatch, StringMatchCandidate};
 use gpui::{AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, View, WeakView};
 use picker::{Picker, PickerDelegate};
-use ui::{prelude::*, HighlightedLabel, ListItem, ListItemSpacing};
+use ui::{prelude::*, HighlightedLabel, ListItem, ListItemSpacing, Tooltip};
 use util::ResultExt;
 
 use crate::saved_conversation::SavedConversationMetadata;
 
+pub enum SavedConversationEvent {
+    Open(PathBuf),
+    Renamed {
+        old_path: PathBuf,
+        new_path: PathBuf,
+        title: String,
+    },
+    Deleted(PathBuf),
+}
+
 pub struct SavedConversations {
+    fs: Arc<dyn Fs>,
     focus_handle: FocusHandle,
     picker: Option<View<Picker<SavedConversationPickerDelegate>>>,
 }
 
 impl EventEmitter<DismissEvent> for SavedConversations {}
+impl EventEmitter<SavedConversationEvent> for SavedConversations {}
 
 impl FocusableView for SavedConversations {
     fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
@@ -26,20 +40,33 @@ impl FocusableView for SavedConversations {
 }
 
 impl SavedConversations {
-    pub fn new(cx: &mut ViewContext<Self>) -> Self {
+    pub fn new(fs: Arc<dyn Fs>, cx: &mut ViewContext<Self>) -> Self {
         Self {
+            fs,
             focus_handle: cx.focus_handle(),
             picker: None,
         }
     }
 
-    pub fn init(
+    /// Lists the conversations, which are listed again whenever one is saved.
+    pub fn set_conversations(
         &mut self,
         saved_conversations: Vec<SavedConversationMetadata>,
         cx: &mut ViewContext<Self>,
     ) {
-        let delegate =
-            SavedConversationPickerDelegate::new(cx.view().downgrade(), saved_conversations);
+        if let Some(picker) = self.picker.as_ref() {
+            picker.update(cx, |picker, cx| {
+                picker.delegate.saved_conversations = saved_conversations;
+                picker.refresh(cx);
+            });
+            return;
+        }
+
+        let delegate = SavedConversationPickerDelegate::new(
+            cx.view().downgrade(),
+            self.fs.clone(),
+            saved_conversations,
+        );
         self.picker = Some(cx.new_view(|cx| Picker::uniform_list(delegate, cx).modal(false)));
     }
 }
@@ -55,20 +82,26 @@ impl Render for SavedConversations {
 
 pub struct SavedConversationPickerDelegate {
     view: WeakView<SavedConversations>,
+    fs: Arc<dyn Fs>,
     saved_conversations: Vec<SavedConversationMetadata>,
     selected_index: usize,
     matches: Vec<StringMatch>,
+    query: String,
+    /// The conversation that's being renamed, whose new title is typed in the query editor.
+    renaming: Option<usize>,
 }
 
 impl SavedConversationPickerDelegate {
     pub fn new(
         weak_view: WeakView<SavedConversations>,
+        fs: Arc<dyn Fs>,
         saved_conversations: Vec<SavedConversationMetadata>,
     ) -> Self {
         let matches = saved_conversations
             .iter()
-            .map(|conversation| StringMatch {
-                candidate_id: 0,
+            .enumerate()
+            .map(|(candidate_id, conversation)| StringMatch {
+                candidate_id,
                 score: 0.0,
                 positions: Default::default(),
                 string: conversation.title.clone(),
@@ -77,10 +110,61 @@ impl SavedConversationPickerDelegate {
 
         Self {
             view: weak_view,
+            fs,
             saved_conversations,
             selected_index: 0,
             matches,
+            query: String::new(),
+            renaming: None,
+        }
+    }
+
+    fn start_renaming(&mut self, ix: usize) -> Option<String> {
+        let conversation = self.saved_conversations.get(ix)?;
+        self.renaming = Some(ix);
+        Some(conversation.title.clone())
+    }
+
+    fn rename(&mut self, ix: usize, title: String, cx: &mut ViewContext<Picker<Self>>) {
+        let Some(conversation) = self.saved_conversations.get(ix).cloned() else {
+            return;
+        };
+        let fs = self.fs.clone();
+        let view = self.view.clone();
+        cx.spawn(|picker, mut cx| async move {
+            let new_path = conversation.rename(fs.as_ref(), title.clone()).await?;
+            let old_path = conversation.path;
+            let saved_conversations = SavedConversationMetadata::list(fs).await?;
+            picker.update(&mut cx, |picker, cx| {
+                picker.delegate.saved_conversations = saved_conversations;
+                picker.set_query("", cx);
+            })?;
+            view.update(&mut cx, |_, cx| {
+                cx.emit(SavedConversationEvent::Renamed {
+                    old_path,
+                    new_path,
+                    title,
+                })
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn delete(&mut self, ix: usize, cx: &mut ViewContext<Picker<Self>>) {
+        if ix >= self.saved_conversations.len() {
+            return;
         }
+        let conversation = self.saved_conversations.remove(ix);
+        let fs = self.fs.clone();
+        let view = self.view.clone();
+        cx.spawn(|picker, mut cx| async move {
+            conversation.delete(fs.as_ref()).await?;
+            picker.update(&mut cx, |picker, cx| picker.refresh(cx))?;
+            view.update(&mut cx, |_, cx| {
+                cx.emit(SavedConversationEvent::Deleted(conversation.path))
+            })
+        })
+        .detach_and_log_err(cx);
     }
 }
 
@@ -88,7 +172,7 @@ impl PickerDelegate for SavedConversationPickerDelegate {
     type ListItem = ui::ListItem;
 
     fn placeholder_text(&self, _cx: &mut WindowContext) -> Arc<str> {
-        "Select saved conversation...".into()
+        "Search conversations...".into()
     }
 
     fn match_count(&self) -> usize {
@@ -108,6 +192,24 @@ impl PickerDelegate for SavedConversationPickerDelegate {
         query: String,
         cx: &mut ViewContext<Picker<Self>>,
     ) -> gpui::Task<()> {
+        self.query.clone_from(&query);
+        // While a conversation is renamed, the query is its new title, so it's the only one shown.
+        if let Some(ix) = self.renaming {
+            self.selected_index = 0;
+            self.matches = self
+                .saved_conversations
+                .get(ix)
+                .map(|conversation| StringMatch {
+                    candidate_id: ix,
+                    string: conversation.title.clone(),
+                    positions: Vec::new(),
+                    score: 0.0,
+                })
+                .into_iter()
+                .collect();
+            return gpui::Task::ready(());
+        }
+
         let background_executor = cx.background_executor().clone();
         let candidates = self
             .saved_conversations
@@ -160,15 +262,35 @@ impl PickerDelegate for SavedConversationPickerDelegate {
     }
 
     fn confirm(&mut self, _secondary: bool, cx: &mut ViewContext<Picker<Self>>) {
-        if self.matches.is_empty() {
-            self.dismissed(cx);
+        if let Some(ix) = self.renaming.take() {
+            let title = self.query.trim().to_string();
+            if title.is_empty() {
+                cx.defer(|picker, cx| picker.set_query("", cx));
+            } else {
+                self.rename(ix, title, cx);
+            }
             return;
         }
 
-        // TODO: Implement selecting a saved conversation.
+        let Some(conversation_match) = self.matches.get(self.selected_index) else {
+            self.dismissed(cx);
+            return;
+        };
+        let path = self.saved_conversations[conversation_match.candidate_id]
+            .path
+            .clone();
+        self.view
+            .update(cx, |_, cx| cx.emit(SavedConversationEvent::Open(path)))
+            .log_err();
     }
 
     fn dismissed(&mut self, cx: &mut ui::prelude::ViewContext<Picker<Self>>) {
+        // Dismissing the picker while renaming a conversation only stops renaming it.
+        if self.renaming.take().is_some() {
+            cx.defer(|picker, cx| picker.set_query("", cx));
+            return;
+        }
+
         self.view
             .update(cx, |_, cx| cx.emit(DismissEvent))
             .log_err();
@@ -178,19 +300,50 @@ impl PickerDelegate for SavedConversationPickerDelegate {
         &self,
         ix: usize,
         selected: bool,
-        _cx: &mut ViewContext<Picker<Self>>,
+        cx: &mut ViewContext<Picker<Self>>,
     ) -> Option<Self::ListItem> {
         let conversation_match = &self.matches[ix];
-        let _conversation = &self.saved_conversations[conversation_match.candidate_id];
+        let conversation_ix = conversation_match.candidate_id;
+        let label = if self.renaming.is_some() {
+            HighlightedLabel::new(self.query.clone(), Vec::new())
+        } else {
+            HighlightedLabel::new(
+                conversation_match.string.clone(),
+                conversation_match.positions.clone(),
+            )
+        };
 
         Some(
             ListItem::new(ix)
                 .spacing(ListItemSpacing::Sparse)
                 .selected(selected)
-                .child(HighlightedLabel::new(
-                    conversation_match.string.clone(),
-                    conversation_match.positions.clone(),
-                )),
+                .child(label)
+                .when(self.renaming.is_none(), |item| {
+                    item.end_hover_slot(
+                        h_flex()
+                            .gap_1()
+                            .child(
+                                IconButton::new(("rename-conversation", ix), IconName::Pencil)
+                                    .icon_size(IconSize::Small)
+                                    .on_click(cx.listener(move |picker, _, cx| {
+                                        if let Some(title) =
+                                            picker.delegate.start_renaming(conversation_ix)
+                                        {
+                                            picker.set_query(title, cx);
+                                        }
+                                    }))
+                                    .tooltip(|cx| Tooltip::text("Rename Conversation", cx)),
+                            )
+                            .child(
+                                IconButton::new(("delete-conversation", ix), IconName::Trash)
+                                    .icon_size(IconSize::Small)
+                                    .on_click(cx.listener(move |picker, _, cx| {
+                                        picker.delegate.delete(conversation_ix, cx);
+                                    }))
+                                    .tooltip(|cx| Tooltip::text("Delete Conversation", cx)),
+                            ),
+                    )
+                }),
         )
     }
 }
@@ -0,0 +1,530 @@
+use crate::{
+    attachments::{
+        ActiveEditorAttachment, ActiveEditorAttachmentTool, DiagnosticsAttachmentTool,
+        SlashCommandAttachmentTool, SlashCommandOutput, TerminalOutputAttachmentTool,
+    },
+    tools::{display_project_path, resolve_project_path},
+};
+use anyhow::{anyhow, Result};
+use assistant_tooling::{AttachmentRegistry, UserAttachment};
+use editor::{CompletionProvider as EditorCompletionProvider, Editor};
+use futures::StreamExt;
+use fuzzy::StringMatchCandidate;
+use gpui::{Model, Task, ViewContext, WeakModel, WeakView, WindowContext};
+use language::{
+    language_settings::assistant_context_enabled, Anchor, Buffer, CodeLabel, Documentation,
+    LanguageServerId, Point, ToPoint as _,
+};
+use parking_lot::RwLock;
+use project::{search::SearchQuery, Completion, Project, ProjectPath, SearchResult};
+use std::{
+    fmt::Write as _,
+    path::Path,
+    sync::{atomic::AtomicBool, Arc},
+};
+use util::maybe;
+use workspace::Workspace;
+
+/// The most lines that `/search` and `/symbols` attach, and the most paths that are offered when
+/// completing `/file`.
+const MAX_RESULTS: usize = 50;
+
+/// A command that's typed on a line of its own in the composer, which expands into context that's
+/// attached to the message when it's sent.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum SlashCommand {
+    File,
+    Search,
+    Diagnostics,
+    Terminal,
+    Symbols,
+}
+
+impl SlashCommand {
+    const ALL: [Self; 5] = [
+        Self::File,
+        Self::Search,
+        Self::Diagnostics,
+        Self::Terminal,
+        Self::Symbols,
+    ];
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|command| command.name() == name)
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::File => "file",
+            Self::Search => "search",
+            Self::Diagnostics => "diagnostics",
+            Self::Terminal => "terminal",
+            Self::Symbols => "symbols",
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            Self::File => "Attach a file of the project",
+            Self::Search => "Attach the lines of the project that contain the text",
+            Self::Diagnostics => "Attach the project's errors and warnings",
+            Self::Terminal => "Attach the recent output of the active terminal",
+            Self::Symbols => {
+                "Attach the outline of the active file, or the project's symbols that match the query"
+            }
+        }
+    }
+
+    /// How the command's argument is written in the completion menu, if it takes one.
+    fn argument(self) -> Option<&'static str> {
+        match self {
+            Self::File => Some("<path>"),
+            Self::Search => Some("<query>"),
+            Self::Symbols => Some("[query]"),
+            Self::Diagnostics | Self::Terminal => None,
+        }
+    }
+
+    fn requires_argument(self) -> bool {
+        matches!(self, Self::File | Self::Search)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct SlashCommandInvocation {
+    pub command: SlashCommand,
+    pub argument: String,
+}
+
+impl SlashCommandInvocation {
+    /// The command as it's typed.
+    fn source(&self) -> String {
+        if self.argument.is_empty() {
+            format!("/{}", self.command.name())
+        } else {
+            format!("/{} {}", self.command.name(), self.argument)
+        }
+    }
+
+    /// Expands the command into the context that's attached to the message it's sent with. A
+    /// command that fails is attached with its error, which is shown instead of being sent.
+    pub(crate) fn resolve(
+        &self,
+        project: Model<Project>,
+        workspace: WeakView<Workspace>,
+        attachment_registry: Arc<AttachmentRegistry>,
+        cx: &mut WindowContext,
+    ) -> Task<Result<UserAttachment>> {
+        let output = match self.command {
+            SlashCommand::File => {
+                let abs_path = {
+                    let project = project.read(cx);
+                    resolve_project_path(project, &self.argument, cx)
+                        .and_then(|project_path| project.absolute_path(&project_path, cx))
+                };
+                match abs_path {
+                    Some(abs_path) => {
+                        return Task::ready(
+                            attachment_registry.attach::<ActiveEditorAttachmentTool>(
+                                ActiveEditorAttachment::for_path(abs_path),
+                                cx,
+                            ),
+                        );
+                    }
+                    None => Task::ready(Err(anyhow!(
+                        "{:?} isn't a file of the project",
+                        self.argument
+                    ))),
+                }
+            }
+            SlashCommand::Diagnostics => {
+                return attachment_registry.call::<DiagnosticsAttachmentTool>(cx);
+            }
+            SlashCommand::Terminal => {
+                return attachment_registry.call::<TerminalOutputAttachmentTool>(cx);
+            }
+            SlashCommand::Search => search(project, &self.argument, cx),
+            SlashCommand::Symbols if self.argument.is_empty() => {
+                Task::ready(active_file_outline(workspace, cx))
+            }
+            SlashCommand::Symbols => symbols(project, &self.argument, cx),
+        };
+
+        let command = self.source();
+        cx.spawn(|mut cx| async move {
+            let output = output.await.map_err(|error| error.to_string());
+            cx.update(|cx| {
+                attachment_registry.attach::<SlashCommandAttachmentTool>(
+                    SlashCommandOutput { command, output },
+                    cx,
+                )
+            })?
+        })
+    }
+}
+
+/// Splits the message into its text and the slash commands that are on lines of their own,
+/// outside of code blocks.
+pub(crate) fn parse_message(text: &str) -> (String, Vec<SlashCommandInvocation>) {
+    let mut lines = Vec::new();
+    let mut invocations = Vec::new();
+    let mut in_code_block = false;
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("```") {
+            in_code_block = !in_code_block;
+        } else if !in_code_block {
+            if let Some(invocation) = parse_invocation(trimmed) {
+                invocations.push(invocation);
+                continue;
+            }
+        }
+        lines.push(line);
+    }
+    (lines.join("\n").trim().to_string(), invocations)
+}
+
+fn parse_invocation(line: &str) -> Option<SlashCommandInvocation> {
+    let line = line.strip_prefix('/')?;
+    let (name, argument) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    let command = SlashCommand::from_name(name)?;
+    let argument = argument.trim();
+    if argument.is_empty() && command.requires_argument() {
+        return None;
+    }
+    Some(SlashCommandInvocation {
+        command,
+        argument: argument.to_string(),
+    })
+}
+
+/// The lines of the project that contain the query, each with its path and line number.
+fn search(project: Model<Project>, query: &str, cx: &mut WindowContext) -> Task<Result<String>> {
+    let query = match SearchQuery::text(query, false, false, false, Vec::new(), Vec::new()) {
+        Ok(query) => query,
+        Err(error) => return Task::ready(Err(error)),
+    };
+    let mut results = project.update(cx, |project, cx| project.search(query, cx));
+    cx.spawn(|cx| async move {
+        let mut lines = Vec::new();
+        let mut limit_reached = false;
+        while let Some(result) = results.next().await {
+            let (buffer, ranges) = match result {
+                SearchResult::Buffer { buffer, ranges } => (buffer, ranges),
+                SearchResult::LimitReached => {
+                    limit_reached = true;
+                    break;
+                }
+                SearchResult::SkippedFiles(_) => continue,
+            };
+
+            buffer.read_with(&cx, |buffer, cx| {
+                // The matches in files that are excluded from the context are left out.
+                if !assistant_context_enabled(buffer.language(), buffer.file(), cx) {
+                    return;
+                }
+                let path = project::File::from_dyn(buffer.file())
+                    .map(|file| {
+                        let project_path = ProjectPath {
+                            worktree_id: file.worktree_id(cx),
+                            path: file.path.clone(),
+                        };
+                        display_project_path(project.read(cx), &project_path, cx)
+                    })
+                    .unwrap_or_else(|| "untitled".to_string());
+                let mut last_row = None;
+                for range in ranges {
+                    let row = range.start.to_point(buffer).row;
+                    if last_row.replace(row) == Some(row) {
+                        continue;
+                    }
+                    let line = buffer
+                        .text_for_range(Point::new(row, 0)..Point::new(row, buffer.line_len(row)))
+                        .collect::<String>();
+                    lines.push(format!("{path}:{}: {}", row + 1, line.trim()));
+                }
+            })?;
+
+            if lines.len() >= MAX_RESULTS {
+                limit_reached = lines.len() > MAX_RESULTS;
+                lines.truncate(MAX_RESULTS);
+                break;
+            }
+        }
+
+        if lines.is_empty() {
+            return Ok("no matches".to_string());
+        }
+        let mut output = format!("```\n{}\n```", lines.join("\n"));
+        if limit_reached {
+            output.push_str("\nthere are more matches, which were left out");
+        }
+        Ok(output)
+    })
+}
+
+/// The symbols of the file that's open in the active editor, indented by how they're nested.
+fn active_file_outline(workspace: WeakView<Workspace>, cx: &mut WindowContext) -> Result<String> {
+    maybe!({
+        let editor = workspace
+            .update(cx, |workspace, cx| workspace.active_item_as::<Editor>(cx))?
+            .ok_or_else(|| anyhow!("no file is open"))?;
+        let buffer = editor
+            .read(cx)
+            .buffer()
+            .read(cx)
+            .as_singleton()
+            .ok_or_else(|| anyhow!("no file is open"))?;
+        let buffer = buffer.read(cx);
+        if !assistant_context_enabled(buffer.language(), buffer.file(), cx) {
+            return Err(anyhow!(
+                "the active file is excluded from the assistant's context by your settings"
+            ));
+        }
+        let snapshot = buffer.snapshot();
+        let outline = snapshot
+            .outline(None)
+            .ok_or_else(|| anyhow!("the active file has no symbols"))?;
+
+        let path = buffer
+            .file()
+            .map(|file| file.path().to_string_lossy().to_string())
+            .unwrap_or_else(|| "untitled".to_string());
+        let mut output = format!("symbols of {path}:\n");
+        for item in outline.items {
+            let row = item.range.start.to_point(&snapshot).row;
+            writeln!(
+                output,
+                "{}{} (line {})",
+                "  ".repeat(item.depth),
+                item.text,
+                row + 1
+            )?;
+        }
+        Ok(output)
+    })
+}
+
+/// The symbols that the project's language servers find for the query.
+fn symbols(project: Model<Project>, query: &str, cx: &mut WindowContext) -> Task<Result<String>> {
+    let symbols = project.update(cx, |project, cx| project.symbols(query, cx));
+    cx.spawn(|cx| async move {
+        let symbols = symbols.await?;
+        if symbols.is_empty() {
+            return Ok("no symbols found".to_string());
+        }
+        project.read_with(&cx, |project, cx| {
+            let mut output = String::new();
+            for symbol in symbols.iter().take(MAX_RESULTS) {
+                writeln!(
+                    output,
+                    "{} in {}:{}",
+                    symbol.label.text,
+                    display_project_path(project, &symbol.path, cx),
+                    symbol.range.start.0.row + 1
+                )?;
+            }
+            Ok(output)
+        })?
+    })
+}
+
+/// Completes the names of slash commands that are typed at the start of a line in the composer,
+/// and the paths of the project's files for `/file`.
+pub(crate) struct SlashCommandCompletionProvider {
+    project: WeakModel<Project>,
+}
+
+impl SlashCommandCompletionProvider {
+    pub(crate) fn new(project: WeakModel<Project>) -> Self {
+        Self { project }
+    }
+
+    fn command_completions(&self, range: std::ops::Range<Anchor>) -> Vec<Completion> {
+        SlashCommand::ALL
+            .into_iter()
+            .map(|command| {
+                let name = command.name();
+                let (text, new_text) = match command.argument() {
+                    Some(argument) => (format!("/{name} {argument}"), format!("/{name} ")),
+                    None => (format!("/{name}"), format!("/{name}")),
+                };
+                Completion {
+                    old_range: range.clone(),
+                    new_text,
+                    label: CodeLabel {
+                        filter_range: 1..name.len() + 1,
+                        text,
+                        runs: Vec::new(),
+                    },
+                    documentation: Some(Documentation::SingleLine(
+                        command.description().to_string(),
+                    )),
+                    server_id: LanguageServerId(0),
+                    lsp_completion: Default::default(),
+                }
+            })
+            .collect()
+    }
+
+    fn path_completions(
+        &self,
+        query: String,
+        range: std::ops::Range<Anchor>,
+        cx: &mut ViewContext<Editor>,
+    ) -> Task<Result<Vec<Completion>>> {
+        let Some(project) = self.project.upgrade() else {
+            return Task::ready(Ok(Vec::new()));
+        };
+        let mut candidates = Vec::new();
+        for worktree in project.read(cx).visible_worktrees(cx) {
+            let worktree = worktree.read(cx);
+            let root_name = worktree.root_name();
+            for entry in worktree.files(false, 0) {
+                candidates.push(StringMatchCandidate::new(
+                    candidates.len(),
+                    Path::new(root_name)
+                        .join(&entry.path)
+                        .to_string_lossy()
+                        .to_string(),
+                ));
+            }
+        }
+
+        let executor = cx.background_executor().clone();
+        cx.background_executor().spawn(async move {
+            let matches = fuzzy::match_strings(
+                &candidates,
+                &query,
+                false,
+                MAX_RESULTS,
+                &AtomicBool::default(),
+                executor,
+            )
+            .await;
+            Ok(matches
+                .into_iter()
+                .map(|path_match| Completion {
+                    old_range: range.clone(),
+                    new_text: path_match.string.clone(),
+                    label: CodeLabel {
+                        filter_range: 0..path_match.string.len(),
+                        text: path_match.string,
+                        runs: Vec::new(),
+                    },
+                    documentation: None,
+                    server_id: LanguageServerId(0),
+                    lsp_completion: Default::default(),
+                })
+                .collect())
+        })
+    }
+}
+
+impl EditorCompletionProvider for SlashCommandCompletionProvider {
+    fn completions(
+        &self,
+        buffer: &Model<Buffer>,
+        buffer_position: Anchor,
+        cx: &mut ViewContext<Editor>,
+    ) -> Task<Result<Vec<Completion>>> {
+        let buffer = buffer.read(cx);
+        let position = buffer_position.to_point(buffer);
+        let line = buffer
+            .text_for_range(Point::new(position.row, 0)..position)
+            .collect::<String>();
+        let Some(typed) = line.trim_start().strip_prefix('/') else {
+            return Task::ready(Ok(Vec::new()));
+        };
+
+        match typed.split_once(' ') {
+            None => {
+                let start = Point::new(position.row, position.column - typed.len() as u32 - 1);
+                Task::ready(Ok(
+                    self.command_completions(buffer.anchor_before(start)..buffer_position)
+                ))
+            }
+            Some((name, argument)) if SlashCommand::from_name(name) == Some(SlashCommand::File) => {
+                let start = Point::new(position.row, position.column - argument.len() as u32);
+                let range = buffer.anchor_before(start)..buffer_position;
+                let query = argument.to_string();
+                self.path_completions(query, range, cx)
+            }
+            Some(_) => Task::ready(Ok(Vec::new())),
+        }
+    }
+
+    fn resolve_completions(
+        &self,
+        _buffer: Model<Buffer>,
+        _completion_indices: Vec<usize>,
+        _completions: Arc<RwLock<Box<[Completion]>>>,
+        _cx: &mut ViewContext<Editor>,
+    ) -> Task<Result<bool>> {
+        Task::ready(Ok(false))
+    }
+
+    fn apply_additional_edits_for_completion(
+        &self,
+        _buffer: Model<Buffer>,
+        _completion: Completion,
+        _push_to_history: bool,
+        _cx: &mut ViewContext<Editor>,
+    ) -> Task<Result<Option<language::Transaction>>> {
+        Task::ready(Ok(None))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn invocation(command: SlashCommand, argument: &str) -> SlashCommandInvocation {
+        SlashCommandInvocation {
+            command,
+            argument: argument.into(),
+        }
+    }
+
+    #[test]
+    fn test_parse_message() {
+        let (text, invocations) = parse_message(
+            "/file src/main.rs\nWhy doesn't this build?\n  /search  fn parse_args \n/diagnostics",
+        );
+        assert_eq!(text, "Why doesn't this build?");
+        assert_eq!(
+            invocations,
+            vec![
+                invocation(SlashCommand::File, "src/main.rs"),
+                invocation(SlashCommand::Search, "fn parse_args"),
+                invocation(SlashCommand::Diagnostics, ""),
+            ]
+        );
+
+        // Unknown commands, commands that are missing their argument, commands that don't start
+        // their line and commands in code blocks are left in the message.
+        let message = "/unknown\n/file\nRun /terminal\n```\n/symbols\n```";
+        let (text, invocations) = parse_message(message);
+        assert_eq!(text, message);
+        assert!(invocations.is_empty());
+
+        let (text, invocations) = parse_message("/symbols\n/terminal");
+        assert_eq!(text, "");
+        assert_eq!(
+            invocations,
+            vec![
+                invocation(SlashCommand::Symbols, ""),
+                invocation(SlashCommand::Terminal, ""),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_invocation_source() {
+        assert_eq!(invocation(SlashCommand::Terminal, "").source(), "/terminal");
+        assert_eq!(
+            invocation(SlashCommand::Search, "parse_args").source(),
+            "/search parse_args"
+        );
+    }
+}
@@ -1,7 +1,142 @@
 mod annotate_code;
 mod create_buffer;
+mod diagnostics;
+mod list_directory;
 mod project_index;
+mod read_file;
+mod run_terminal_command;
+mod search_project;
 
 pub use annotate_code::*;
 pub use create_buffer::*;
+pub use diagnostics::*;
+pub use list_directory::*;
 pub use project_index::*;
+pub use read_file::*;
+pub use run_terminal_command::*;
+pub use search_project::*;
+
+use std::path::{Component, Path};
+
+use gpui::AppContext;
+use project::{Project, ProjectPath};
+
+/// Finds the project path that the model refers to, which is either absolute or relative to a
+/// worktree, optionally starting with the name of that worktree's root.
+pub(crate) fn resolve_project_path(
+    project: &Project,
+    path: &str,
+    cx: &AppContext,
+) -> Option<ProjectPath> {
+    let path = Path::new(path.trim_start_matches("./"));
+    // Paths that go up a directory could leave the worktree, even when they're absolute.
+    if path
+        .components()
+        .any(|component| component == Component::ParentDir)
+    {
+        return None;
+    }
+    if path.is_absolute() {
+        return project.project_path_for_absolute_path(path, cx);
+    }
+
+    let worktrees = project.visible_worktrees(cx).collect::<Vec<_>>();
+    let rooted_path = worktrees.iter().find_map(|worktree| {
+        let worktree = worktree.read(cx);
+        let path = path.strip_prefix(worktree.root_name()).ok()?;
+        worktree.entry_for_path(path)?;
+        Some(ProjectPath {
+            worktree_id: worktree.id(),
+            path: path.into(),
+        })
+    });
+    rooted_path.or_else(|| {
+        worktrees.iter().find_map(|worktree| {
+            let worktree = worktree.read(cx);
+            let path = if path == Path::new(".") {
+                Path::new("")
+            } else {
+                path
+            };
+            worktree.entry_for_path(path)?;
+            Some(ProjectPath {
+                worktree_id: worktree.id(),
+                path: path.into(),
+            })
+        })
+    })
+}
+
+/// Formats a project path the way the model is asked to refer to it, starting with the name of its
+/// worktree's root.
+pub(crate) fn display_project_path(
+    project: &Project,
+    project_path: &ProjectPath,
+    cx: &AppContext,
+) -> String {
+    match project.worktree_for_id(project_path.worktree_id, cx) {
+        Some(worktree) => Path::new(worktree.read(cx).root_name())
+            .join(&project_path.path)
+            .to_string_lossy()
+            .to_string(),
+        None => project_path.path.to_string_lossy().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use project::FakeFs;
+    use serde_json::json;
+    use settings::SettingsStore;
+
+    #[gpui::test]
+    async fn test_resolve_project_path(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            Project::init_settings(cx);
+        });
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/code",
+            json!({
+                "project": {
+                    "src": {
+                        "main.rs": "",
+                    },
+                    "README.md": "",
+                },
+                "secrets.txt": "",
+            }),
+        )
+        .await;
+        let project = Project::test(fs, ["/code/project".as_ref()], cx).await;
+
+        project.read_with(cx, |project, cx| {
+            let resolve = |path| {
+                resolve_project_path(project, path, cx)
+                    .map(|project_path| project_path.path.to_string_lossy().to_string())
+            };
+            assert_eq!(
+                resolve("project/src/main.rs").as_deref(),
+                Some("src/main.rs")
+            );
+            assert_eq!(resolve("src/main.rs").as_deref(), Some("src/main.rs"));
+            assert_eq!(resolve("./README.md").as_deref(), Some("README.md"));
+            assert_eq!(resolve(".").as_deref(), Some(""));
+            assert_eq!(
+                resolve("/code/project/src/main.rs").as_deref(),
+                Some("src/main.rs")
+            );
+            assert_eq!(resolve("src/missing.rs"), None);
+
+            // Paths outside of the worktree aren't part of the project.
+            assert_eq!(resolve("/code/secrets.txt"), None);
+            assert_eq!(resolve("../secrets.txt"), None);
+            assert_eq!(resolve("src/../../secrets.txt"), None);
+            assert_eq!(resolve("/code/project/../secrets.txt"), None);
+        });
+    }
+}
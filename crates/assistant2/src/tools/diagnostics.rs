@@ -0,0 +1,296 @@
+use anyhow::{anyhow, Result};
+use assistant_tooling::{LanguageModelTool, ProjectContext, ToolView};
+use futures::future::join_all;
+use gpui::{prelude::*, Model, Task, View};
+use language::{language_settings::assistant_context_enabled, DiagnosticSeverity, ToPoint as _};
+use project::{Project, ProjectPath};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use ui::prelude::*;
+
+use super::resolve_project_path;
+
+/// The most files whose diagnostics are reported at once, so they don't overwhelm the context.
+const MAX_FILES: usize = 20;
+
+pub struct DiagnosticsTool {
+    project: Model<Project>,
+}
+
+impl DiagnosticsTool {
+    pub fn new(project: Model<Project>) -> Self {
+        Self { project }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, JsonSchema)]
+pub struct DiagnosticsInput {
+    /// A file or directory to limit the diagnostics to, relative to the root of the project.
+    ///
+    /// This should start with the name of the project's root directory, like "zed/src". Leave
+    /// it out to get the diagnostics of the whole project.
+    path: Option<String>,
+}
+
+impl LanguageModelTool for DiagnosticsTool {
+    type View = DiagnosticsView;
+
+    fn name(&self) -> String {
+        "get_diagnostics".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Get the errors and warnings that language servers report for files in the project."
+            .to_string()
+    }
+
+    fn view(&self, cx: &mut WindowContext) -> View<Self::View> {
+        cx.new_view(|_cx| DiagnosticsView {
+            project: self.project.clone(),
+            input: DiagnosticsInput::default(),
+            state: DiagnosticsState::CollectingInput,
+        })
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct FileDiagnostic {
+    path: String,
+    /// The one-based line that the diagnostic starts on.
+    line: u32,
+    is_error: bool,
+    message: String,
+}
+
+impl FileDiagnostic {
+    pub fn is_error(&self) -> bool {
+        self.is_error
+    }
+}
+
+enum DiagnosticsState {
+    CollectingInput,
+    Fetching,
+    Error(anyhow::Error),
+    Finished(Vec<FileDiagnostic>),
+}
+
+pub struct DiagnosticsView {
+    project: Model<Project>,
+    input: DiagnosticsInput,
+    state: DiagnosticsState,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SerializedDiagnostics {
+    error_message: Option<String>,
+    diagnostics: Vec<FileDiagnostic>,
+}
+
+impl Render for DiagnosticsView {
+    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let label = match &self.state {
+            DiagnosticsState::CollectingInput | DiagnosticsState::Fetching => {
+                Label::new("Fetching diagnostics...")
+            }
+            DiagnosticsState::Error(error) => {
+                Label::new(format!("Failed to fetch diagnostics: {error}")).color(Color::Error)
+            }
+            DiagnosticsState::Finished(diagnostics) => {
+                let error_count = diagnostics.iter().filter(|d| d.is_error).count();
+                let warning_count = diagnostics.len() - error_count;
+                Label::new(format!(
+                    "Found {error_count} {} and {warning_count} {}{}",
+                    if error_count == 1 { "error" } else { "errors" },
+                    if warning_count == 1 {
+                        "warning"
+                    } else {
+                        "warnings"
+                    },
+                    match &self.input.path {
+                        Some(path) => format!(" in `{path}`"),
+                        None => String::new(),
+                    }
+                ))
+            }
+        };
+
+        h_flex()
+            .gap_2()
+            .child(Icon::new(IconName::ExclamationTriangle))
+            .child(label)
+    }
+}
+
+impl ToolView for DiagnosticsView {
+    type Input = DiagnosticsInput;
+
+    type SerializedState = SerializedDiagnostics;
+
+    fn generate(&self, _context: &mut ProjectContext, _cx: &mut ViewContext<Self>) -> String {
+        match &self.state {
+            DiagnosticsState::CollectingInput | DiagnosticsState::Fetching => String::new(),
+            DiagnosticsState::Error(error) => format!("Failed to fetch diagnostics: {error}"),
+            DiagnosticsState::Finished(diagnostics) => format_diagnostics(diagnostics),
+        }
+    }
+
+    fn set_input(&mut self, input: Self::Input, cx: &mut ViewContext<Self>) {
+        self.input = input;
+        cx.notify();
+    }
+
+    fn execute(&mut self, cx: &mut ViewContext<Self>) -> Task<Result<()>> {
+        let project = self.project.read(cx);
+        let filter = match &self.input.path {
+            Some(path) => match resolve_project_path(project, path, cx) {
+                Some(project_path) => Some(project_path),
+                None => {
+                    self.state = DiagnosticsState::Error(anyhow!("no such path {path}"));
+                    cx.notify();
+                    return Task::ready(Ok(()));
+                }
+            },
+            None => None,
+        };
+
+        let diagnostics = collect_diagnostics(&self.project, filter, cx);
+        self.state = DiagnosticsState::Fetching;
+        cx.notify();
+
+        cx.spawn(|this, mut cx| async move {
+            let diagnostics = diagnostics.await;
+            this.update(&mut cx, |this, cx| {
+                this.state = match diagnostics {
+                    Ok(diagnostics) => DiagnosticsState::Finished(diagnostics),
+                    Err(error) => DiagnosticsState::Error(error),
+                };
+                cx.notify();
+            })
+        })
+    }
+
+    fn serialize(&self, _cx: &mut ViewContext<Self>) -> Self::SerializedState {
+        match &self.state {
+            DiagnosticsState::Error(error) => SerializedDiagnostics {
+                error_message: Some(error.to_string()),
+                diagnostics: Vec::new(),
+            },
+            DiagnosticsState::Finished(diagnostics) => SerializedDiagnostics {
+                error_message: None,
+                diagnostics: diagnostics.clone(),
+            },
+            DiagnosticsState::CollectingInput | DiagnosticsState::Fetching => {
+                SerializedDiagnostics {
+                    error_message: None,
+                    diagnostics: Vec::new(),
+                }
+            }
+        }
+    }
+
+    fn deserialize(
+        &mut self,
+        output: Self::SerializedState,
+        cx: &mut ViewContext<Self>,
+    ) -> Result<()> {
+        self.state = match output.error_message {
+            Some(error) => DiagnosticsState::Error(anyhow!(error)),
+            None => DiagnosticsState::Finished(output.diagnostics),
+        };
+        cx.notify();
+        Ok(())
+    }
+}
+
+/// Collects the errors and warnings of the files in the project, or of the ones in `filter` if
+/// it's given, sorted by path and line.
+pub(crate) fn collect_diagnostics(
+    project: &Model<Project>,
+    filter: Option<ProjectPath>,
+    cx: &mut WindowContext,
+) -> Task<Result<Vec<FileDiagnostic>>> {
+    let mut paths = project
+        .read(cx)
+        .diagnostic_summaries(false, cx)
+        .filter(|(project_path, _, summary)| {
+            let is_in_filter = filter.as_ref().map_or(true, |filter| {
+                filter.worktree_id == project_path.worktree_id
+                    && project_path.path.starts_with(&filter.path)
+            });
+            is_in_filter && (summary.error_count > 0 || summary.warning_count > 0)
+        })
+        .map(|(project_path, _, _)| project_path)
+        .collect::<Vec<_>>();
+    paths.dedup();
+    paths.truncate(MAX_FILES);
+
+    let buffers = project.update(cx, |project, cx| {
+        paths
+            .into_iter()
+            .map(|project_path| project.open_buffer(project_path, cx))
+            .collect::<Vec<_>>()
+    });
+
+    cx.spawn(|mut cx| async move {
+        let buffers = join_all(buffers).await;
+        cx.update(|cx| {
+            let mut diagnostics = Vec::new();
+            for buffer in buffers {
+                let buffer = buffer?.read(cx).snapshot();
+                if !assistant_context_enabled(buffer.language(), buffer.file(), cx) {
+                    continue;
+                }
+                let Some(path) = buffer
+                    .file()
+                    .map(|file| file.full_path(cx).to_string_lossy().to_string())
+                else {
+                    continue;
+                };
+
+                for (_, group) in buffer.diagnostic_groups(None) {
+                    let entry = &group.entries[group.primary_ix];
+                    let is_error = match entry.diagnostic.severity {
+                        DiagnosticSeverity::ERROR => true,
+                        DiagnosticSeverity::WARNING => false,
+                        _ => continue,
+                    };
+                    let start = entry.range.start.to_point(&buffer);
+                    diagnostics.push(FileDiagnostic {
+                        path: path.clone(),
+                        line: start.row + 1,
+                        is_error,
+                        message: entry.diagnostic.message.clone(),
+                    });
+                }
+            }
+            diagnostics.sort_by(|a, b| (&a.path, a.line).cmp(&(&b.path, b.line)));
+            Ok(diagnostics)
+        })?
+    })
+}
+
+pub(crate) fn format_diagnostics(diagnostics: &[FileDiagnostic]) -> String {
+    if diagnostics.is_empty() {
+        return "No errors or warnings".to_string();
+    }
+
+    let mut body = String::new();
+    for diagnostic in diagnostics {
+        writeln!(
+            &mut body,
+            "* {}:{}: {}: {}",
+            diagnostic.path,
+            diagnostic.line,
+            if diagnostic.is_error {
+                "error"
+            } else {
+                "warning"
+            },
+            diagnostic.message
+        )
+        .unwrap();
+    }
+    body
+}
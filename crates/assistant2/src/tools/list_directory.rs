@@ -0,0 +1,191 @@
+use anyhow::{anyhow, Result};
+use assistant_tooling::{LanguageModelTool, ProjectContext, ToolView};
+use gpui::{prelude::*, AppContext, Model, Task, View};
+use project::Project;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use ui::prelude::*;
+
+use super::resolve_project_path;
+
+pub struct ListDirectoryTool {
+    project: Model<Project>,
+}
+
+impl ListDirectoryTool {
+    pub fn new(project: Model<Project>) -> Self {
+        Self { project }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, JsonSchema)]
+pub struct ListDirectoryInput {
+    /// The path of the directory, relative to the root of the project.
+    ///
+    /// This should start with the name of the project's root directory, like "zed/src". Use the
+    /// name of the root directory alone to list the root of the project.
+    path: String,
+}
+
+impl LanguageModelTool for ListDirectoryTool {
+    type View = ListDirectoryView;
+
+    fn name(&self) -> String {
+        "list_directory".to_string()
+    }
+
+    fn description(&self) -> String {
+        "List the files and directories directly inside a directory of the project. Directories end with a slash.".to_string()
+    }
+
+    fn view(&self, cx: &mut WindowContext) -> View<Self::View> {
+        cx.new_view(|_cx| ListDirectoryView {
+            project: self.project.clone(),
+            input: ListDirectoryInput::default(),
+            state: ListDirectoryState::CollectingPath,
+        })
+    }
+}
+
+enum ListDirectoryState {
+    CollectingPath,
+    Error(anyhow::Error),
+    Finished(Vec<String>),
+}
+
+pub struct ListDirectoryView {
+    project: Model<Project>,
+    input: ListDirectoryInput,
+    state: ListDirectoryState,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SerializedListDirectory {
+    error_message: Option<String>,
+    entries: Vec<String>,
+}
+
+impl ListDirectoryView {
+    fn list(&self, cx: &AppContext) -> Result<Vec<String>> {
+        let project = self.project.read(cx);
+        let project_path = resolve_project_path(project, &self.input.path, cx)
+            .ok_or_else(|| anyhow!("no such path {}", self.input.path))?;
+        let worktree = project
+            .worktree_for_id(project_path.worktree_id, cx)
+            .ok_or_else(|| anyhow!("no such path {}", self.input.path))?
+            .read(cx);
+        let entry = worktree
+            .entry_for_path(&project_path.path)
+            .ok_or_else(|| anyhow!("no such path {}", self.input.path))?;
+        if !entry.is_dir() {
+            return Err(anyhow!("{} is not a directory", self.input.path));
+        }
+
+        Ok(worktree
+            .child_entries(&project_path.path)
+            .filter_map(|entry| {
+                let name = entry.path.file_name()?.to_string_lossy();
+                Some(if entry.is_dir() {
+                    format!("{name}/")
+                } else {
+                    name.to_string()
+                })
+            })
+            .collect())
+    }
+}
+
+impl Render for ListDirectoryView {
+    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let label = match &self.state {
+            ListDirectoryState::CollectingPath => Label::new("Listing directory..."),
+            ListDirectoryState::Error(error) => {
+                Label::new(format!("Failed to list {}: {error}", self.input.path))
+                    .color(Color::Error)
+            }
+            ListDirectoryState::Finished(entries) => Label::new(format!(
+                "Listed `{}` ({} {})",
+                self.input.path,
+                entries.len(),
+                if entries.len() == 1 {
+                    "entry"
+                } else {
+                    "entries"
+                }
+            )),
+        };
+
+        h_flex()
+            .gap_2()
+            .child(Icon::new(IconName::Folder))
+            .child(label)
+    }
+}
+
+impl ToolView for ListDirectoryView {
+    type Input = ListDirectoryInput;
+
+    type SerializedState = SerializedListDirectory;
+
+    fn generate(&self, _context: &mut ProjectContext, _cx: &mut ViewContext<Self>) -> String {
+        match &self.state {
+            ListDirectoryState::CollectingPath => String::new(),
+            ListDirectoryState::Error(error) => format!("Failed to list directory: {error}"),
+            ListDirectoryState::Finished(entries) if entries.is_empty() => {
+                format!("{} is empty", self.input.path)
+            }
+            ListDirectoryState::Finished(entries) => {
+                let mut body = format!("{} contains:\n", self.input.path);
+                for entry in entries {
+                    writeln!(&mut body, "* {entry}").unwrap();
+                }
+                body
+            }
+        }
+    }
+
+    fn set_input(&mut self, input: Self::Input, cx: &mut ViewContext<Self>) {
+        self.input = input;
+        cx.notify();
+    }
+
+    fn execute(&mut self, cx: &mut ViewContext<Self>) -> Task<Result<()>> {
+        self.state = match self.list(cx) {
+            Ok(entries) => ListDirectoryState::Finished(entries),
+            Err(error) => ListDirectoryState::Error(error),
+        };
+        cx.notify();
+        Task::ready(Ok(()))
+    }
+
+    fn serialize(&self, _cx: &mut ViewContext<Self>) -> Self::SerializedState {
+        match &self.state {
+            ListDirectoryState::Error(error) => SerializedListDirectory {
+                error_message: Some(error.to_string()),
+                entries: Vec::new(),
+            },
+            ListDirectoryState::Finished(entries) => SerializedListDirectory {
+                error_message: None,
+                entries: entries.clone(),
+            },
+            ListDirectoryState::CollectingPath => SerializedListDirectory {
+                error_message: None,
+                entries: Vec::new(),
+            },
+        }
+    }
+
+    fn deserialize(
+        &mut self,
+        output: Self::SerializedState,
+        cx: &mut ViewContext<Self>,
+    ) -> Result<()> {
+        self.state = match output.error_message {
+            Some(error) => ListDirectoryState::Error(anyhow!(error)),
+            None => ListDirectoryState::Finished(output.entries),
+        };
+        cx.notify();
+        Ok(())
+    }
+}
@@ -0,0 +1,157 @@
+use anyhow::{anyhow, Result};
+use assistant_tooling::{LanguageModelTool, ProjectContext, ToolView};
+use gpui::{prelude::*, AppContext, Model, Task, View};
+use project::{Project, ProjectPath};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use ui::prelude::*;
+
+use super::{display_project_path, resolve_project_path};
+
+pub struct ReadFileTool {
+    project: Model<Project>,
+}
+
+impl ReadFileTool {
+    pub fn new(project: Model<Project>) -> Self {
+        Self { project }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, JsonSchema)]
+pub struct ReadFileInput {
+    /// The path of the file, relative to the root of the project.
+    ///
+    /// This should start with the name of the project's root directory, like "zed/src/main.rs".
+    path: String,
+}
+
+impl LanguageModelTool for ReadFileTool {
+    type View = ReadFileView;
+
+    fn name(&self) -> String {
+        "read_file".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Read the entire contents of a file in the project.".to_string()
+    }
+
+    fn view(&self, cx: &mut WindowContext) -> View<Self::View> {
+        cx.new_view(|_cx| ReadFileView {
+            project: self.project.clone(),
+            input: ReadFileInput::default(),
+            state: ReadFileState::CollectingPath,
+        })
+    }
+}
+
+enum ReadFileState {
+    CollectingPath,
+    Error(anyhow::Error),
+    Finished(ProjectPath),
+}
+
+pub struct ReadFileView {
+    project: Model<Project>,
+    input: ReadFileInput,
+    state: ReadFileState,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SerializedReadFile {
+    error_message: Option<String>,
+}
+
+impl ReadFileView {
+    fn resolve_file(&self, cx: &AppContext) -> Result<ProjectPath> {
+        let project = self.project.read(cx);
+        let project_path = resolve_project_path(project, &self.input.path, cx)
+            .ok_or_else(|| anyhow!("no such path {}", self.input.path))?;
+        let entry = project
+            .entry_for_path(&project_path, cx)
+            .ok_or_else(|| anyhow!("no such path {}", self.input.path))?;
+        if !entry.is_file() {
+            return Err(anyhow!("{} is not a file", self.input.path));
+        }
+        Ok(project_path)
+    }
+}
+
+impl Render for ReadFileView {
+    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let label = match &self.state {
+            ReadFileState::CollectingPath => Label::new("Reading file..."),
+            ReadFileState::Error(error) => {
+                Label::new(format!("Failed to read {}: {error}", self.input.path))
+                    .color(Color::Error)
+            }
+            ReadFileState::Finished(_) => Label::new(format!("Read `{}`", self.input.path)),
+        };
+
+        h_flex()
+            .gap_2()
+            .child(Icon::new(IconName::File))
+            .child(label)
+    }
+}
+
+impl ToolView for ReadFileView {
+    type Input = ReadFileInput;
+
+    type SerializedState = SerializedReadFile;
+
+    fn generate(&self, context: &mut ProjectContext, cx: &mut ViewContext<Self>) -> String {
+        match &self.state {
+            ReadFileState::CollectingPath => String::new(),
+            ReadFileState::Error(error) => format!("Failed to read file: {error}"),
+            ReadFileState::Finished(project_path) => {
+                context.add_file(project_path.clone());
+                format!(
+                    "The contents of {} are included in the project context.",
+                    display_project_path(self.project.read(cx), project_path, cx)
+                )
+            }
+        }
+    }
+
+    fn set_input(&mut self, input: Self::Input, cx: &mut ViewContext<Self>) {
+        self.input = input;
+        cx.notify();
+    }
+
+    fn execute(&mut self, cx: &mut ViewContext<Self>) -> Task<Result<()>> {
+        self.state = match self.resolve_file(cx) {
+            Ok(project_path) => ReadFileState::Finished(project_path),
+            Err(error) => ReadFileState::Error(error),
+        };
+        cx.notify();
+        Task::ready(Ok(()))
+    }
+
+    fn serialize(&self, _cx: &mut ViewContext<Self>) -> Self::SerializedState {
+        SerializedReadFile {
+            error_message: match &self.state {
+                ReadFileState::Error(error) => Some(error.to_string()),
+                _ => None,
+            },
+        }
+    }
+
+    fn deserialize(
+        &mut self,
+        output: Self::SerializedState,
+        cx: &mut ViewContext<Self>,
+    ) -> Result<()> {
+        // The file is read again when the conversation continues, so only its path is resolved.
+        self.state = match output.error_message {
+            Some(error) => ReadFileState::Error(anyhow!(error)),
+            None => match self.resolve_file(cx) {
+                Ok(project_path) => ReadFileState::Finished(project_path),
+                Err(error) => ReadFileState::Error(error),
+            },
+        };
+        cx.notify();
+        Ok(())
+    }
+}
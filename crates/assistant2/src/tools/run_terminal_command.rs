@@ -0,0 +1,403 @@
+use crate::AssistantSettings;
+use anyhow::{anyhow, Result};
+use assistant_tooling::{LanguageModelTool, ProjectContext, ToolView};
+use futures::channel::oneshot;
+use gpui::{prelude::*, Model, PromptLevel, Task, View, WeakView};
+use project::Project;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use settings::Settings;
+use std::path::PathBuf;
+use task::{RevealStrategy, SpawnInTerminal, TaskId};
+use terminal::TaskStatus;
+use terminal_view::terminal_panel::TerminalPanel;
+use ui::prelude::*;
+use util::ResultExt;
+use workspace::Workspace;
+
+use super::resolve_project_path;
+
+/// The most lines of a command's output that are returned, so it doesn't overwhelm the context.
+const MAX_OUTPUT_LINES: usize = 200;
+
+pub struct RunTerminalCommandTool {
+    workspace: WeakView<Workspace>,
+    project: Model<Project>,
+}
+
+impl RunTerminalCommandTool {
+    pub fn new(workspace: WeakView<Workspace>, project: Model<Project>) -> Self {
+        Self { workspace, project }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, JsonSchema)]
+pub struct RunTerminalCommandInput {
+    /// The shell command to run, like "cargo test -p editor".
+    command: String,
+
+    /// The directory to run the command in, relative to the root of the project.
+    ///
+    /// This should start with the name of the project's root directory, like "zed/crates". Leave
+    /// it out to run the command in the project's root directory.
+    working_directory: Option<String>,
+}
+
+impl LanguageModelTool for RunTerminalCommandTool {
+    type View = RunTerminalCommandView;
+
+    fn name(&self) -> String {
+        "run_terminal_command".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Run a shell command in the user's terminal and get its output. The user is shown the command and asked to confirm it before it runs, and may decline to run it.".to_string()
+    }
+
+    fn view(&self, cx: &mut WindowContext) -> View<Self::View> {
+        cx.new_view(|_cx| RunTerminalCommandView {
+            workspace: self.workspace.clone(),
+            project: self.project.clone(),
+            input: RunTerminalCommandInput::default(),
+            working_directory: None,
+            state: RunTerminalCommandState::CollectingInput,
+            confirmation_tx: None,
+        })
+    }
+}
+
+enum RunTerminalCommandState {
+    CollectingInput,
+    AwaitingConfirmation,
+    Running,
+    Declined,
+    Finished { output: String, success: bool },
+    Error(anyhow::Error),
+}
+
+pub struct RunTerminalCommandView {
+    workspace: WeakView<Workspace>,
+    project: Model<Project>,
+    input: RunTerminalCommandInput,
+    working_directory: Option<PathBuf>,
+    state: RunTerminalCommandState,
+    /// Tells the pending execution whether the user chose to run the command.
+    confirmation_tx: Option<oneshot::Sender<bool>>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub enum SerializedCommandRun {
+    Declined,
+    Finished { output: String, success: bool },
+    Error(String),
+}
+
+impl RunTerminalCommandView {
+    /// Asks the user to confirm the exact command and the directory it runs in.
+    fn confirm(&mut self, cx: &mut ViewContext<Self>) {
+        let working_directory = self
+            .working_directory
+            .as_ref()
+            .map(|path| path.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let answer = cx.prompt(
+            PromptLevel::Warning,
+            "Run this command in the terminal?",
+            Some(&format!("{}\n\nin {working_directory}", self.input.command)),
+            &["Run", "Cancel"],
+        );
+        cx.spawn(|this, mut cx| async move {
+            if answer.await? == 0 {
+                this.update(&mut cx, |this, _| this.respond(true))?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn respond(&mut self, run: bool) {
+        if let Some(confirmation_tx) = self.confirmation_tx.take() {
+            confirmation_tx.send(run).ok();
+        }
+    }
+
+    fn run(&mut self, cx: &mut ViewContext<Self>) -> Task<Result<()>> {
+        let command = self.input.command.clone();
+        let spawn_in_terminal = SpawnInTerminal {
+            id: TaskId(format!("assistant-command-{}", cx.entity_id().as_u64())),
+            full_label: command.clone(),
+            label: command.clone(),
+            command: command.clone(),
+            args: Vec::new(),
+            command_label: command,
+            cwd: self.working_directory.clone(),
+            env: Default::default(),
+            use_new_terminal: true,
+            allow_concurrent_runs: true,
+            reveal: RevealStrategy::Always,
+        };
+        let terminal = self.workspace.update(cx, |workspace, cx| {
+            let panel = workspace
+                .panel::<TerminalPanel>(cx)
+                .ok_or_else(|| anyhow!("no terminal panel"))?;
+            anyhow::Ok(panel.update(cx, |panel, cx| panel.run_command(&spawn_in_terminal, cx)))
+        });
+        let terminal = match terminal.and_then(|terminal| terminal) {
+            Ok(terminal) => terminal,
+            Err(error) => {
+                self.state = RunTerminalCommandState::Error(error);
+                cx.notify();
+                return Task::ready(Ok(()));
+            }
+        };
+        self.state = RunTerminalCommandState::Running;
+        cx.notify();
+
+        cx.spawn(|this, mut cx| async move {
+            let result = async {
+                let terminal = terminal.await?;
+                terminal
+                    .update(&mut cx, |terminal, cx| terminal.wait_for_completed_task(cx))?
+                    .await;
+                terminal.read_with(&cx, |terminal, _| {
+                    let text = terminal.text();
+                    let lines = text.trim_end().lines().collect::<Vec<_>>();
+                    let output = lines[lines.len().saturating_sub(MAX_OUTPUT_LINES)..].join("\n");
+                    let success = terminal.task().map_or(false, |task| {
+                        task.status == TaskStatus::Completed { success: true }
+                    });
+                    (output, success)
+                })
+            }
+            .await;
+
+            this.update(&mut cx, |this, cx| {
+                this.state = match result {
+                    Ok((output, success)) => RunTerminalCommandState::Finished { output, success },
+                    Err(error) => RunTerminalCommandState::Error(error),
+                };
+                cx.notify();
+            })
+        })
+    }
+}
+
+impl Render for RunTerminalCommandView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let status = match &self.state {
+            RunTerminalCommandState::CollectingInput => None,
+            RunTerminalCommandState::AwaitingConfirmation => Some(
+                h_flex()
+                    .gap_2()
+                    .child(
+                        Button::new("run-in-terminal", "Run in Terminal")
+                            .icon(IconName::Play)
+                            .icon_position(IconPosition::Start)
+                            .icon_size(IconSize::Small)
+                            .style(ButtonStyle::Filled)
+                            .on_click(cx.listener(|this, _, cx| this.confirm(cx))),
+                    )
+                    .child(
+                        Button::new("decline-command", "Don't Run")
+                            .on_click(cx.listener(|this, _, _| this.respond(false))),
+                    )
+                    .into_any_element(),
+            ),
+            RunTerminalCommandState::Running => Some(
+                Label::new("Running…")
+                    .color(Color::Muted)
+                    .into_any_element(),
+            ),
+            RunTerminalCommandState::Declined => {
+                Some(Label::new("Not run").color(Color::Muted).into_any_element())
+            }
+            RunTerminalCommandState::Finished { success, .. } => Some(
+                if *success {
+                    Label::new("Finished, output sent to the assistant").color(Color::Muted)
+                } else {
+                    Label::new("Failed, output sent to the assistant").color(Color::Error)
+                }
+                .into_any_element(),
+            ),
+            RunTerminalCommandState::Error(error) => Some(
+                Label::new(format!("Failed to run the command: {error}"))
+                    .color(Color::Error)
+                    .into_any_element(),
+            ),
+        };
+
+        v_flex()
+            .gap_1()
+            .child(
+                h_flex().gap_2().child(Icon::new(IconName::Terminal)).child(
+                    div()
+                        .flex_1()
+                        .px_2()
+                        .py_1()
+                        .rounded_md()
+                        .bg(cx.theme().colors().editor_background)
+                        .font_family("Zed Mono")
+                        .child(self.input.command.clone()),
+                ),
+            )
+            .children(self.working_directory.as_ref().map(|working_directory| {
+                Label::new(format!("in {}", working_directory.to_string_lossy()))
+                    .size(LabelSize::Small)
+                    .color(Color::Muted)
+            }))
+            .children(status)
+    }
+}
+
+impl ToolView for RunTerminalCommandView {
+    type Input = RunTerminalCommandInput;
+
+    type SerializedState = SerializedCommandRun;
+
+    fn generate(&self, _context: &mut ProjectContext, _cx: &mut ViewContext<Self>) -> String {
+        let command = &self.input.command;
+        match &self.state {
+            RunTerminalCommandState::CollectingInput
+            | RunTerminalCommandState::AwaitingConfirmation
+            | RunTerminalCommandState::Running => String::new(),
+            RunTerminalCommandState::Declined => {
+                format!("The user chose not to run `{command}`.")
+            }
+            RunTerminalCommandState::Finished { output, success } => format!(
+                "`{command}` {}. The end of its output:\n```\n{output}\n```",
+                if *success { "succeeded" } else { "failed" }
+            ),
+            RunTerminalCommandState::Error(error) => {
+                format!("Failed to run `{command}`: {error}")
+            }
+        }
+    }
+
+    fn set_input(&mut self, input: Self::Input, cx: &mut ViewContext<Self>) {
+        self.input = input;
+        cx.notify();
+    }
+
+    fn execute(&mut self, cx: &mut ViewContext<Self>) -> Task<Result<()>> {
+        let project = self.project.read(cx);
+        let working_directory = match &self.input.working_directory {
+            Some(path) => resolve_project_path(project, path, cx)
+                .and_then(|project_path| project.absolute_path(&project_path, cx)),
+            None => project
+                .visible_worktrees(cx)
+                .next()
+                .map(|worktree| worktree.read(cx).abs_path().to_path_buf()),
+        };
+        let Some(working_directory) = working_directory else {
+            self.state = RunTerminalCommandState::Error(anyhow!(
+                "no such directory {}",
+                self.input.working_directory.as_deref().unwrap_or(".")
+            ));
+            cx.notify();
+            return Task::ready(Ok(()));
+        };
+        self.working_directory = Some(working_directory);
+
+        let allowlist = &AssistantSettings::get_global(cx).terminal_command_allowlist;
+        if is_allowlisted(allowlist, &self.input.command) {
+            return self.run(cx);
+        }
+
+        let (confirmation_tx, confirmation_rx) = oneshot::channel();
+        self.confirmation_tx = Some(confirmation_tx);
+        self.state = RunTerminalCommandState::AwaitingConfirmation;
+        cx.notify();
+
+        cx.spawn(|this, mut cx| async move {
+            let run = confirmation_rx.await.unwrap_or(false);
+            let run_task = this.update(&mut cx, |this, cx| {
+                if run {
+                    Some(this.run(cx))
+                } else {
+                    this.state = RunTerminalCommandState::Declined;
+                    cx.notify();
+                    None
+                }
+            })?;
+            if let Some(run_task) = run_task {
+                run_task.await.log_err();
+            }
+            Ok(())
+        })
+    }
+
+    fn serialize(&self, _cx: &mut ViewContext<Self>) -> Self::SerializedState {
+        match &self.state {
+            RunTerminalCommandState::Finished { output, success } => {
+                SerializedCommandRun::Finished {
+                    output: output.clone(),
+                    success: *success,
+                }
+            }
+            RunTerminalCommandState::Error(error) => SerializedCommandRun::Error(error.to_string()),
+            RunTerminalCommandState::CollectingInput
+            | RunTerminalCommandState::AwaitingConfirmation
+            | RunTerminalCommandState::Running
+            | RunTerminalCommandState::Declined => SerializedCommandRun::Declined,
+        }
+    }
+
+    fn deserialize(
+        &mut self,
+        output: Self::SerializedState,
+        cx: &mut ViewContext<Self>,
+    ) -> Result<()> {
+        self.state = match output {
+            SerializedCommandRun::Declined => RunTerminalCommandState::Declined,
+            SerializedCommandRun::Finished { output, success } => {
+                RunTerminalCommandState::Finished { output, success }
+            }
+            SerializedCommandRun::Error(error) => RunTerminalCommandState::Error(anyhow!(error)),
+        };
+        cx.notify();
+        Ok(())
+    }
+}
+
+/// Whether the command can run without being confirmed, because it's one of the allowed commands
+/// or starts with one of them, followed by its arguments. Commands that chain or redirect to other
+/// commands always need to be confirmed.
+fn is_allowlisted(allowlist: &[String], command: &str) -> bool {
+    const SHELL_OPERATORS: &[&str] = &[";", "&", "|", "`", "$(", ">", "<", "\n"];
+
+    let command = command.trim();
+    if SHELL_OPERATORS
+        .iter()
+        .any(|operator| command.contains(operator))
+    {
+        return false;
+    }
+    allowlist.iter().any(|allowed| {
+        let allowed = allowed.trim();
+        !allowed.is_empty()
+            && command
+                .strip_prefix(allowed)
+                .map_or(false, |rest| rest.is_empty() || rest.starts_with(' '))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_allowlisted() {
+        let allowlist = vec!["cargo check".to_string(), "git status".to_string()];
+        assert!(is_allowlisted(&allowlist, "cargo check"));
+        assert!(is_allowlisted(&allowlist, "  cargo check -p editor "));
+        assert!(is_allowlisted(&allowlist, "git status --short"));
+
+        assert!(!is_allowlisted(&allowlist, "cargo checkout"));
+        assert!(!is_allowlisted(&allowlist, "cargo test"));
+        assert!(!is_allowlisted(&allowlist, "cargo check && rm -rf target"));
+        assert!(!is_allowlisted(&allowlist, "git status; curl example.com"));
+        assert!(!is_allowlisted(&allowlist, "git status > status.txt"));
+        assert!(!is_allowlisted(&allowlist, "cargo check $(whoami)"));
+        assert!(!is_allowlisted(&[], "cargo check"));
+    }
+}
@@ -0,0 +1,301 @@
+use anyhow::{anyhow, Result};
+use assistant_tooling::{LanguageModelTool, ProjectContext, ToolView};
+use collections::BTreeMap;
+use futures::StreamExt as _;
+use gpui::{prelude::*, Model, Task, View};
+use language::{Point, ToOffset as _, ToPoint as _};
+use project::{search::SearchQuery, Project, ProjectPath, SearchResult};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::{fmt::Write as _, ops::Range, path::Path, sync::Arc};
+use ui::prelude::*;
+use util::paths::PathMatcher;
+
+use super::display_project_path;
+
+/// The most matches that are reported for a search, so they don't overwhelm the context.
+const MAX_MATCHES: usize = 50;
+
+pub struct SearchProjectTool {
+    project: Model<Project>,
+}
+
+impl SearchProjectTool {
+    pub fn new(project: Model<Project>) -> Self {
+        Self { project }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, JsonSchema)]
+pub struct SearchProjectInput {
+    /// The text to search for.
+    query: String,
+    /// Whether the query is a regular expression.
+    #[serde(default)]
+    regex: bool,
+    /// Whether the search is case sensitive.
+    #[serde(default)]
+    case_sensitive: bool,
+    /// A glob that the paths of the files to search must match, like "**/*.rs".
+    include: Option<String>,
+}
+
+impl LanguageModelTool for SearchProjectTool {
+    type View = SearchProjectView;
+
+    fn name(&self) -> String {
+        "search_project".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Search the files of the project for text or a regular expression, returning the lines that match. Use this to find exact identifiers and strings; use semantic search to find code by what it does.".to_string()
+    }
+
+    fn view(&self, cx: &mut WindowContext) -> View<Self::View> {
+        cx.new_view(|_cx| SearchProjectView {
+            project: self.project.clone(),
+            input: SearchProjectInput::default(),
+            state: SearchProjectState::CollectingQuery,
+        })
+    }
+}
+
+enum SearchProjectState {
+    CollectingQuery,
+    Searching,
+    Error(anyhow::Error),
+    Finished {
+        /// The lines that matched in each file, expanded to whole lines.
+        excerpts: BTreeMap<ProjectPath, Vec<Range<usize>>>,
+        limit_reached: bool,
+    },
+}
+
+pub struct SearchProjectView {
+    project: Model<Project>,
+    input: SearchProjectInput,
+    state: SearchProjectState,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SerializedSearchProject {
+    error_message: Option<String>,
+    limit_reached: bool,
+    worktrees: BTreeMap<Arc<Path>, BTreeMap<Arc<Path>, Vec<Range<usize>>>>,
+}
+
+impl Render for SearchProjectView {
+    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let label = match &self.state {
+            SearchProjectState::CollectingQuery | SearchProjectState::Searching => {
+                Label::new(format!("Searching for `{}`...", self.input.query))
+            }
+            SearchProjectState::Error(error) => {
+                Label::new(format!("Failed to search: {error}")).color(Color::Error)
+            }
+            SearchProjectState::Finished { excerpts, .. } => {
+                let file_count = excerpts.len();
+                Label::new(format!(
+                    "Searched for `{}` and found it in {file_count} {}",
+                    self.input.query,
+                    if file_count == 1 { "file" } else { "files" }
+                ))
+            }
+        };
+
+        h_flex()
+            .gap_2()
+            .child(Icon::new(IconName::MagnifyingGlass))
+            .child(label)
+    }
+}
+
+impl ToolView for SearchProjectView {
+    type Input = SearchProjectInput;
+
+    type SerializedState = SerializedSearchProject;
+
+    fn generate(&self, context: &mut ProjectContext, cx: &mut ViewContext<Self>) -> String {
+        match &self.state {
+            SearchProjectState::CollectingQuery | SearchProjectState::Searching => String::new(),
+            SearchProjectState::Error(error) => format!("Failed to search: {error}"),
+            SearchProjectState::Finished { excerpts, .. } if excerpts.is_empty() => {
+                "No results found".to_string()
+            }
+            SearchProjectState::Finished {
+                excerpts,
+                limit_reached,
+            } => {
+                let mut body = "Found matches in the following paths:\n".to_string();
+                for (project_path, ranges) in excerpts {
+                    context.add_excerpts(project_path.clone(), ranges);
+                    writeln!(
+                        &mut body,
+                        "* {}",
+                        display_project_path(self.project.read(cx), project_path, cx)
+                    )
+                    .unwrap();
+                }
+                if *limit_reached {
+                    body.push_str("There are more matches, so narrow the search to see them.\n");
+                }
+                body
+            }
+        }
+    }
+
+    fn set_input(&mut self, input: Self::Input, cx: &mut ViewContext<Self>) {
+        self.input = input;
+        cx.notify();
+    }
+
+    fn execute(&mut self, cx: &mut ViewContext<Self>) -> Task<Result<()>> {
+        let query = match build_query(&self.input) {
+            Ok(query) => query,
+            Err(error) => {
+                self.state = SearchProjectState::Error(error);
+                cx.notify();
+                return Task::ready(Ok(()));
+            }
+        };
+
+        let mut results = self
+            .project
+            .update(cx, |project, cx| project.search(query, cx));
+        self.state = SearchProjectState::Searching;
+        cx.notify();
+
+        cx.spawn(|this, mut cx| async move {
+            let mut excerpts = BTreeMap::<ProjectPath, Vec<Range<usize>>>::new();
+            let mut match_count = 0;
+            let mut limit_reached = false;
+            while let Some(result) = results.next().await {
+                let (buffer, ranges) = match result {
+                    SearchResult::Buffer { buffer, ranges } => (buffer, ranges),
+                    SearchResult::LimitReached => {
+                        limit_reached = true;
+                        break;
+                    }
+                    SearchResult::SkippedFiles(_) => continue,
+                };
+
+                buffer.read_with(&cx, |buffer, cx| {
+                    let Some(file) = project::File::from_dyn(buffer.file()) else {
+                        return;
+                    };
+                    let project_path = ProjectPath {
+                        worktree_id: file.worktree_id(cx),
+                        path: file.path.clone(),
+                    };
+                    let line_ranges = excerpts.entry(project_path).or_default();
+                    for range in ranges {
+                        let start = range.start.to_point(buffer);
+                        let end = range.end.to_point(buffer);
+                        let start = Point::new(start.row, 0).to_offset(buffer);
+                        let end = Point::new(end.row, buffer.line_len(end.row)).to_offset(buffer);
+                        line_ranges.push(start..end);
+                        match_count += 1;
+                    }
+                })?;
+
+                if match_count >= MAX_MATCHES {
+                    limit_reached = true;
+                    break;
+                }
+            }
+
+            this.update(&mut cx, |this, cx| {
+                this.state = SearchProjectState::Finished {
+                    excerpts,
+                    limit_reached,
+                };
+                cx.notify();
+            })
+        })
+    }
+
+    fn serialize(&self, cx: &mut ViewContext<Self>) -> Self::SerializedState {
+        let mut serialized = SerializedSearchProject {
+            error_message: None,
+            limit_reached: false,
+            worktrees: BTreeMap::default(),
+        };
+        match &self.state {
+            SearchProjectState::Error(error) => serialized.error_message = Some(error.to_string()),
+            SearchProjectState::Finished {
+                excerpts,
+                limit_reached,
+            } => {
+                serialized.limit_reached = *limit_reached;
+                let project = self.project.read(cx);
+                for (project_path, ranges) in excerpts {
+                    if let Some(worktree) = project.worktree_for_id(project_path.worktree_id, cx) {
+                        serialized
+                            .worktrees
+                            .entry(worktree.read(cx).abs_path())
+                            .or_default()
+                            .insert(project_path.path.clone(), ranges.clone());
+                    }
+                }
+            }
+            SearchProjectState::CollectingQuery | SearchProjectState::Searching => {}
+        }
+        serialized
+    }
+
+    fn deserialize(
+        &mut self,
+        serialized: Self::SerializedState,
+        cx: &mut ViewContext<Self>,
+    ) -> Result<()> {
+        if let Some(error) = serialized.error_message {
+            self.state = SearchProjectState::Error(anyhow!(error));
+        } else {
+            let mut excerpts = BTreeMap::<ProjectPath, Vec<Range<usize>>>::new();
+            let project = self.project.read(cx);
+            for (worktree_path, paths) in serialized.worktrees {
+                if let Some(worktree) = project
+                    .worktrees()
+                    .find(|worktree| worktree.read(cx).abs_path() == worktree_path)
+                {
+                    let worktree_id = worktree.read(cx).id();
+                    for (path, ranges) in paths {
+                        excerpts.insert(ProjectPath { worktree_id, path }, ranges);
+                    }
+                }
+            }
+            self.state = SearchProjectState::Finished {
+                excerpts,
+                limit_reached: serialized.limit_reached,
+            };
+        }
+        cx.notify();
+        Ok(())
+    }
+}
+
+fn build_query(input: &SearchProjectInput) -> Result<SearchQuery> {
+    let files_to_include = match &input.include {
+        Some(glob) => vec![PathMatcher::new(glob)?],
+        None => Vec::new(),
+    };
+    if input.regex {
+        SearchQuery::regex(
+            &input.query,
+            false,
+            input.case_sensitive,
+            false,
+            files_to_include,
+            Vec::new(),
+        )
+    } else {
+        SearchQuery::text(
+            &input.query,
+            false,
+            input.case_sensitive,
+            false,
+            files_to_include,
+            Vec::new(),
+        )
+    }
+}
@@ -1,17 +1,23 @@
 mod active_file_button;
+mod add_context_menu;
 mod chat_message;
 mod chat_notice;
 mod composer;
+mod model_selector;
 mod project_index_button;
+mod prompt_selector;
 
 #[cfg(feature = "stories")]
 mod stories;
 
 pub use active_file_button::*;
+pub use add_context_menu::*;
 pub use chat_message::*;
 pub use chat_notice::*;
 pub use composer::*;
+pub use model_selector::*;
 pub use project_index_button::*;
+pub use prompt_selector::*;
 
 #[cfg(feature = "stories")]
 pub use stories::*;
@@ -0,0 +1,220 @@
+use std::{path::PathBuf, sync::Arc};
+
+use fuzzy::{match_strings, StringMatch, StringMatchCandidate};
+use gpui::{DismissEvent, WeakView};
+use picker::{Picker, PickerDelegate};
+use ui::{
+    popover_menu, prelude::*, ContextMenu, HighlightedLabel, ListItem, ListItemSpacing, Tooltip,
+};
+use util::ResultExt;
+
+use crate::{
+    attachments::{
+        ActiveEditorAttachmentTool, DiagnosticsAttachmentTool, SelectionAttachmentTool,
+        TerminalOutputAttachmentTool,
+    },
+    AssistantChat,
+};
+
+/// Lists the kinds of context that can be attached to the next message.
+#[derive(IntoElement)]
+pub struct AddContextMenu {
+    assistant_chat: WeakView<AssistantChat>,
+}
+
+impl AddContextMenu {
+    pub fn new(assistant_chat: WeakView<AssistantChat>) -> Self {
+        Self { assistant_chat }
+    }
+}
+
+impl RenderOnce for AddContextMenu {
+    fn render(self, _cx: &mut WindowContext) -> impl IntoElement {
+        popover_menu("add-context")
+            .menu(move |cx| {
+                let assistant_chat = self.assistant_chat.clone();
+                Some(ContextMenu::build(cx, |menu, _cx| {
+                    menu.entry("Selection", None, {
+                        let assistant_chat = assistant_chat.clone();
+                        move |cx| {
+                            assistant_chat
+                                .update(cx, |assistant_chat, cx| {
+                                    assistant_chat.add_context::<SelectionAttachmentTool>(cx)
+                                })
+                                .log_err();
+                        }
+                    })
+                    .entry("Active File", None, {
+                        let assistant_chat = assistant_chat.clone();
+                        move |cx| {
+                            assistant_chat
+                                .update(cx, |assistant_chat, cx| {
+                                    assistant_chat.add_context::<ActiveEditorAttachmentTool>(cx)
+                                })
+                                .log_err();
+                        }
+                    })
+                    .entry("File…", None, {
+                        let assistant_chat = assistant_chat.clone();
+                        move |cx| {
+                            assistant_chat
+                                .update(cx, |assistant_chat, cx| assistant_chat.pick_file(cx))
+                                .log_err();
+                        }
+                    })
+                    .entry("Diagnostics", None, {
+                        let assistant_chat = assistant_chat.clone();
+                        move |cx| {
+                            assistant_chat
+                                .update(cx, |assistant_chat, cx| {
+                                    assistant_chat.add_context::<DiagnosticsAttachmentTool>(cx)
+                                })
+                                .log_err();
+                        }
+                    })
+                    .entry("Terminal Output", None, move |cx| {
+                        assistant_chat
+                            .update(cx, |assistant_chat, cx| {
+                                assistant_chat.add_context::<TerminalOutputAttachmentTool>(cx)
+                            })
+                            .log_err();
+                    })
+                }))
+            })
+            .trigger(
+                IconButton::new("add-context-button", IconName::Plus)
+                    .icon_size(IconSize::XSmall)
+                    .tooltip(|cx| Tooltip::text("Add Context", cx)),
+            )
+            .anchor(gpui::AnchorCorner::BottomLeft)
+    }
+}
+
+/// A file in the project that can be attached, with its path shown relative to the project.
+pub struct FileCandidate {
+    pub display_path: String,
+    pub abs_path: PathBuf,
+}
+
+/// Lists the files in the project, attaching the one that's picked to the next message.
+pub struct FileContextPickerDelegate {
+    assistant_chat: WeakView<AssistantChat>,
+    files: Vec<FileCandidate>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl FileContextPickerDelegate {
+    pub fn new(assistant_chat: WeakView<AssistantChat>, files: Vec<FileCandidate>) -> Self {
+        Self {
+            assistant_chat,
+            files,
+            matches: Vec::new(),
+            selected_index: 0,
+        }
+    }
+}
+
+impl PickerDelegate for FileContextPickerDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _cx: &mut WindowContext) -> Arc<str> {
+        "Search files to attach...".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _cx: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        cx: &mut ViewContext<Picker<Self>>,
+    ) -> gpui::Task<()> {
+        let background_executor = cx.background_executor().clone();
+        let candidates = self
+            .files
+            .iter()
+            .enumerate()
+            .map(|(id, file)| StringMatchCandidate {
+                id,
+                char_bag: file.display_path.as_str().into(),
+                string: file.display_path.clone(),
+            })
+            .collect::<Vec<_>>();
+
+        cx.spawn(move |this, mut cx| async move {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .take(100)
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    100,
+                    &Default::default(),
+                    background_executor,
+                )
+                .await
+            };
+
+            this.update(&mut cx, |this, _cx| {
+                this.delegate.matches = matches;
+                this.delegate.selected_index = this
+                    .delegate
+                    .selected_index
+                    .min(this.delegate.matches.len().saturating_sub(1));
+            })
+            .log_err();
+        })
+    }
+
+    fn confirm(&mut self, _secondary: bool, cx: &mut ViewContext<Picker<Self>>) {
+        if let Some(file_match) = self.matches.get(self.selected_index) {
+            let abs_path = self.files[file_match.candidate_id].abs_path.clone();
+            self.assistant_chat
+                .update(cx, |assistant_chat, cx| {
+                    assistant_chat.attach_file(abs_path, cx)
+                })
+                .log_err();
+        }
+        cx.emit(DismissEvent);
+    }
+
+    fn dismissed(&mut self, _cx: &mut ViewContext<Picker<Self>>) {}
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _cx: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let file_match = &self.matches[ix];
+        Some(
+            ListItem::new(ix)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .child(HighlightedLabel::new(
+                    file_match.string.clone(),
+                    file_match.positions.clone(),
+                )),
+        )
+    }
+}
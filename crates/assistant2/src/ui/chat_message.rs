@@ -15,6 +15,8 @@ pub enum UserOrAssistant {
 pub struct ChatMessage {
     id: MessageId,
     player: UserOrAssistant,
+    /// The model that produced the message, when it's the assistant's.
+    model: Option<SharedString>,
     messages: Vec<AnyElement>,
     selected: bool,
     collapsed: bool,
@@ -32,12 +34,18 @@ impl ChatMessage {
         Self {
             id,
             player,
+            model: None,
             messages,
             selected: false,
             collapsed,
             on_collapse_handle_click,
         }
     }
+
+    pub fn model(mut self, model: Option<SharedString>) -> Self {
+        self.model = model;
+        self
+    }
 }
 
 impl Selectable for ChatMessage {
@@ -97,7 +105,10 @@ impl RenderOnce for ChatMessage {
                                     this.child(div().size(avatar_size))
                                 }
                             })
-                            .child(Label::new(username).color(Color::Muted)),
+                            .child(Label::new(username).color(Color::Muted))
+                            .children(self.model.map(|model| {
+                                Label::new(model).size(LabelSize::Small).color(Color::Muted)
+                            })),
                     )
                     .child(
                         h_flex().visible_on_hover(message_group).child(
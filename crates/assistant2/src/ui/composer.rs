@@ -1,19 +1,24 @@
 use crate::{
+    assistant_settings::TruncationStrategy,
+    context_budget::token_budget,
     ui::{ActiveFileButton, ProjectIndexButton},
-    AssistantChat, CompletionProvider,
 };
 use editor::{Editor, EditorElement, EditorStyle};
-use gpui::{AnyElement, FontStyle, FontWeight, ReadGlobal, TextStyle, View, WeakView, WhiteSpace};
+use gpui::{AnyElement, FontStyle, FontWeight, TextStyle, View, WhiteSpace};
 use settings::Settings;
 use theme::ThemeSettings;
-use ui::{popover_menu, prelude::*, ButtonLike, ContextMenu, Divider, TextSize, Tooltip};
+use ui::{prelude::*, Divider, TextSize, Tooltip};
 
 #[derive(IntoElement)]
 pub struct Composer {
     editor: View<Editor>,
     project_index_button: View<ProjectIndexButton>,
     active_file_button: Option<View<ActiveFileButton>>,
-    model_selector: AnyElement,
+    add_context_menu: Option<AnyElement>,
+    /// The context that's been attached to the message, each with a button to remove it.
+    attachments: Vec<AnyElement>,
+    prompt_selector: AnyElement,
+    token_count: Option<AnyElement>,
 }
 
 impl Composer {
@@ -21,13 +26,19 @@ impl Composer {
         editor: View<Editor>,
         project_index_button: View<ProjectIndexButton>,
         active_file_button: Option<View<ActiveFileButton>>,
-        model_selector: AnyElement,
+        add_context_menu: AnyElement,
+        attachments: Vec<AnyElement>,
+        prompt_selector: AnyElement,
+        token_count: Option<AnyElement>,
     ) -> Self {
         Self {
             editor,
             project_index_button,
             active_file_button,
-            model_selector,
+            add_context_menu: Some(add_context_menu),
+            attachments,
+            prompt_selector,
+            token_count,
         }
     }
 
@@ -36,11 +47,13 @@ impl Composer {
     }
 
     fn render_attachment_tools(&mut self, _cx: &mut WindowContext) -> impl IntoElement {
-        h_flex().children(
-            self.active_file_button
-                .clone()
-                .map(|view| view.into_any_element()),
-        )
+        h_flex()
+            .children(
+                self.active_file_button
+                    .clone()
+                    .map(|view| view.into_any_element()),
+            )
+            .children(self.add_context_menu.take())
     }
 }
 
@@ -54,6 +67,8 @@ impl RenderOnce for Composer {
         // Remove the extra 1px added by the border
         let padding = Spacing::XLarge.rems(cx) - rems_from_px(1.);
 
+        let attachments = std::mem::take(&mut self.attachments);
+
         h_flex()
             .p(Spacing::Small.rems(cx))
             .w_full()
@@ -71,6 +86,9 @@ impl RenderOnce for Composer {
                             .justify_between()
                             .w_full()
                             .gap_2()
+                            .when(!attachments.is_empty(), |element| {
+                                element.child(h_flex().flex_wrap().gap_1().children(attachments))
+                            })
                             .child({
                                 let settings = ThemeSettings::get_global(cx);
                                 let text_style = TextStyle {
@@ -112,82 +130,88 @@ impl RenderOnce for Composer {
                                                 .child(self.render_attachment_tools(cx)),
                                         ),
                                     )
-                                    .child(h_flex().gap_1().child(self.model_selector)),
+                                    .child(
+                                        h_flex()
+                                            .gap_1()
+                                            .children(self.token_count)
+                                            .child(self.prompt_selector),
+                                    ),
                             ),
                     ),
             )
     }
 }
 
+/// The share of the context window at which the token count warns that the conversation is
+/// about to be shortened.
+const TOKEN_COUNT_WARNING_THRESHOLD: f32 = 0.9;
+
+/// The tokens that the conversation would take up, out of those that the model's context window
+/// leaves for it.
 #[derive(IntoElement)]
-pub struct ModelSelector {
-    assistant_chat: WeakView<AssistantChat>,
-    model: String,
+pub struct TokenCount {
+    token_count: usize,
+    max_token_count: usize,
+    truncation: TruncationStrategy,
 }
 
-impl ModelSelector {
-    pub fn new(assistant_chat: WeakView<AssistantChat>, model: String) -> Self {
+impl TokenCount {
+    pub fn new(token_count: usize, max_token_count: usize, truncation: TruncationStrategy) -> Self {
         Self {
-            assistant_chat,
-            model,
+            token_count,
+            max_token_count,
+            truncation,
         }
     }
 }
 
-impl RenderOnce for ModelSelector {
+impl RenderOnce for TokenCount {
     fn render(self, _cx: &mut WindowContext) -> impl IntoElement {
-        popover_menu("model-switcher")
-            .menu(move |cx| {
-                ContextMenu::build(cx, |mut menu, cx| {
-                    for model in CompletionProvider::global(cx).available_models() {
-                        menu = menu.custom_entry(
-                            {
-                                let model = model.clone();
-                                move |_| Label::new(model.clone()).into_any_element()
-                            },
-                            {
-                                let assistant_chat = self.assistant_chat.clone();
-                                move |cx| {
-                                    _ = assistant_chat.update(cx, |assistant_chat, cx| {
-                                        assistant_chat.model.clone_from(&model);
-                                        cx.notify();
-                                    });
-                                }
-                            },
-                        );
-                    }
-                    menu
-                })
-                .into()
-            })
-            .trigger(
-                ButtonLike::new("active-model")
-                    .child(
-                        h_flex()
-                            .w_full()
-                            .gap_0p5()
-                            .child(
-                                div()
-                                    .overflow_x_hidden()
-                                    .flex_grow()
-                                    .whitespace_nowrap()
-                                    .child(
-                                        Label::new(self.model)
-                                            .size(LabelSize::Small)
-                                            .color(Color::Muted),
-                                    ),
-                            )
-                            .child(
-                                div().child(
-                                    Icon::new(IconName::ChevronDown)
-                                        .color(Color::Muted)
-                                        .size(IconSize::XSmall),
-                                ),
-                            ),
-                    )
-                    .style(ButtonStyle::Subtle)
-                    .tooltip(move |cx| Tooltip::text("Change Model", cx)),
+        let budget = token_budget(self.max_token_count);
+        let (color, tooltip): (_, SharedString) = if self.token_count > budget {
+            let shortening = match self.truncation {
+                TruncationStrategy::DropOldest => "left out",
+                TruncationStrategy::Summarize => "summarized",
+            };
+            (
+                Color::Error,
+                format!(
+                    "The conversation doesn't fit in the model's context window, so its oldest \
+                    messages will be {shortening}"
+                )
+                .into(),
             )
-            .anchor(gpui::AnchorCorner::BottomRight)
+        } else if self.token_count as f32 >= budget as f32 * TOKEN_COUNT_WARNING_THRESHOLD {
+            (
+                Color::Warning,
+                "The conversation almost fills the model's context window".into(),
+            )
+        } else {
+            (
+                Color::Muted,
+                "Tokens used of the model's context window".into(),
+            )
+        };
+
+        div()
+            .id("token-count")
+            .child(
+                Label::new(format!(
+                    "{} / {}",
+                    format_token_count(self.token_count),
+                    format_token_count(budget)
+                ))
+                .size(LabelSize::Small)
+                .color(color),
+            )
+            .tooltip(move |cx| Tooltip::text(tooltip.clone(), cx))
+    }
+}
+
+fn format_token_count(token_count: usize) -> String {
+    if token_count >= 1000 {
+        format!("{:.1}k", token_count as f32 / 1000.)
+    } else {
+        token_count.to_string()
     }
 }
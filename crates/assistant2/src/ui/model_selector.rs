@@ -0,0 +1,104 @@
+use crate::{AssistantChat, CompletionProvider, LanguageModel};
+use gpui::{ReadGlobal, WeakView};
+use ui::{popover_menu, prelude::*, ButtonLike, ContextMenu, Tooltip};
+
+/// A dropdown of every provider's models, which switches the model that the rest of the
+/// conversation is had with.
+#[derive(IntoElement)]
+pub struct ModelSelector {
+    assistant_chat: WeakView<AssistantChat>,
+    model: LanguageModel,
+}
+
+impl ModelSelector {
+    pub fn new(assistant_chat: WeakView<AssistantChat>, model: LanguageModel) -> Self {
+        Self {
+            assistant_chat,
+            model,
+        }
+    }
+}
+
+impl RenderOnce for ModelSelector {
+    fn render(self, _cx: &mut WindowContext) -> impl IntoElement {
+        let active_model = self.model.clone();
+        popover_menu("model-switcher")
+            .menu(move |cx| {
+                ContextMenu::build(cx, |mut menu, cx| {
+                    for provider in CompletionProvider::global(cx).providers() {
+                        let models = provider.available_models(cx);
+                        if models.is_empty() {
+                            continue;
+                        }
+                        menu = menu.header(provider.display_name());
+                        for name in models {
+                            let model = LanguageModel {
+                                provider: provider.id().to_string(),
+                                name,
+                            };
+                            let is_active = model == active_model;
+                            menu = menu.custom_entry(
+                                {
+                                    let name = model.name.clone();
+                                    move |_| {
+                                        h_flex()
+                                            .w_full()
+                                            .justify_between()
+                                            .gap_2()
+                                            .child(Label::new(name.clone()))
+                                            .when(is_active, |this| {
+                                                this.child(
+                                                    Icon::new(IconName::Check)
+                                                        .size(IconSize::Small)
+                                                        .color(Color::Accent),
+                                                )
+                                            })
+                                            .into_any_element()
+                                    }
+                                },
+                                {
+                                    let assistant_chat = self.assistant_chat.clone();
+                                    move |cx| {
+                                        _ = assistant_chat.update(cx, |assistant_chat, cx| {
+                                            assistant_chat.set_model(model.clone(), cx);
+                                        });
+                                    }
+                                },
+                            );
+                        }
+                    }
+                    menu
+                })
+                .into()
+            })
+            .trigger(
+                ButtonLike::new("active-model")
+                    .child(
+                        h_flex()
+                            .w_full()
+                            .gap_0p5()
+                            .child(
+                                div()
+                                    .overflow_x_hidden()
+                                    .flex_grow()
+                                    .whitespace_nowrap()
+                                    .child(
+                                        Label::new(self.model.name)
+                                            .size(LabelSize::Small)
+                                            .color(Color::Muted),
+                                    ),
+                            )
+                            .child(
+                                div().child(
+                                    Icon::new(IconName::ChevronDown)
+                                        .color(Color::Muted)
+                                        .size(IconSize::XSmall),
+                                ),
+                            ),
+                    )
+                    .style(ButtonStyle::Subtle)
+                    .tooltip(move |cx| Tooltip::text("Change Model", cx)),
+            )
+            .anchor(gpui::AnchorCorner::TopRight)
+    }
+}
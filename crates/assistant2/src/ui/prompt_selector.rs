@@ -0,0 +1,205 @@
+use std::sync::Arc;
+
+use fuzzy::{match_strings, StringMatch, StringMatchCandidate};
+use gpui::{AnyElement, DismissEvent, WeakView};
+use picker::{Picker, PickerDelegate};
+use ui::{popover_menu, prelude::*, HighlightedLabel, ListItem, ListItemSpacing, Tooltip};
+use util::ResultExt;
+
+use crate::{prompt_library::PromptTemplate, AssistantChat};
+
+/// Lists the prompts in the library, inserting the one that's picked into the composer.
+#[derive(IntoElement)]
+pub struct PromptSelector {
+    assistant_chat: WeakView<AssistantChat>,
+}
+
+impl PromptSelector {
+    pub fn new(assistant_chat: WeakView<AssistantChat>) -> Self {
+        Self { assistant_chat }
+    }
+}
+
+impl RenderOnce for PromptSelector {
+    fn render(self, _cx: &mut WindowContext) -> impl IntoElement {
+        popover_menu("prompt-library")
+            .menu(move |cx| {
+                let assistant_chat = self.assistant_chat.clone();
+                let fs = assistant_chat.upgrade()?.read(cx).fs.clone();
+                Some(cx.new_view(|cx| {
+                    cx.spawn(|picker, mut cx| async move {
+                        let prompts = PromptTemplate::list(fs).await?;
+                        picker.update(&mut cx, |picker: &mut Picker<PromptPickerDelegate>, cx| {
+                            picker.delegate.prompts = prompts;
+                            picker.refresh(cx);
+                        })
+                    })
+                    .detach_and_log_err(cx);
+
+                    let delegate = PromptPickerDelegate {
+                        assistant_chat,
+                        prompts: Vec::new(),
+                        matches: Vec::new(),
+                        selected_index: 0,
+                    };
+                    Picker::uniform_list(delegate, cx).width(rems(20.))
+                }))
+            })
+            .trigger(
+                IconButton::new("prompt-library-button", IconName::Library)
+                    .icon_size(IconSize::Small)
+                    .tooltip(|cx| Tooltip::text("Insert Prompt", cx)),
+            )
+            .anchor(gpui::AnchorCorner::BottomLeft)
+    }
+}
+
+pub struct PromptPickerDelegate {
+    assistant_chat: WeakView<AssistantChat>,
+    prompts: Vec<PromptTemplate>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl PickerDelegate for PromptPickerDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _cx: &mut WindowContext) -> Arc<str> {
+        "Search prompts...".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _cx: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        cx: &mut ViewContext<Picker<Self>>,
+    ) -> gpui::Task<()> {
+        let background_executor = cx.background_executor().clone();
+        let candidates = self
+            .prompts
+            .iter()
+            .enumerate()
+            .map(|(id, prompt)| StringMatchCandidate {
+                id,
+                char_bag: prompt.title.as_str().into(),
+                string: prompt.title.clone(),
+            })
+            .collect::<Vec<_>>();
+
+        cx.spawn(move |this, mut cx| async move {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    100,
+                    &Default::default(),
+                    background_executor,
+                )
+                .await
+            };
+
+            this.update(&mut cx, |this, _cx| {
+                this.delegate.matches = matches;
+                this.delegate.selected_index = this
+                    .delegate
+                    .selected_index
+                    .min(this.delegate.matches.len().saturating_sub(1));
+            })
+            .log_err();
+        })
+    }
+
+    fn confirm(&mut self, _secondary: bool, cx: &mut ViewContext<Picker<Self>>) {
+        if let Some(prompt_match) = self.matches.get(self.selected_index) {
+            let prompt = self.prompts[prompt_match.candidate_id].clone();
+            self.assistant_chat
+                .update(cx, |assistant_chat, cx| {
+                    assistant_chat.insert_prompt(prompt, cx)
+                })
+                .log_err();
+        }
+        cx.emit(DismissEvent);
+    }
+
+    fn dismissed(&mut self, _cx: &mut ViewContext<Picker<Self>>) {}
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        cx: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let prompt_match = &self.matches[ix];
+        let path = self.prompts[prompt_match.candidate_id].path.clone();
+        let assistant_chat = self.assistant_chat.clone();
+
+        Some(
+            ListItem::new(ix)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .child(HighlightedLabel::new(
+                    prompt_match.string.clone(),
+                    prompt_match.positions.clone(),
+                ))
+                .end_hover_slot(
+                    IconButton::new(("edit-prompt", ix), IconName::Pencil)
+                        .icon_size(IconSize::Small)
+                        .on_click(cx.listener(move |_, _, cx| {
+                            assistant_chat
+                                .update(cx, |assistant_chat, cx| {
+                                    assistant_chat.edit_prompt(path.clone(), cx)
+                                })
+                                .log_err();
+                            cx.emit(DismissEvent);
+                        }))
+                        .tooltip(|cx| Tooltip::text("Edit Prompt", cx)),
+                ),
+        )
+    }
+
+    fn render_footer(&self, cx: &mut ViewContext<Picker<Self>>) -> Option<AnyElement> {
+        let assistant_chat = self.assistant_chat.clone();
+        Some(
+            h_flex()
+                .w_full()
+                .p_1()
+                .border_t_1()
+                .border_color(cx.theme().colors().border_variant)
+                .child(
+                    Button::new("new-prompt", "New Prompt")
+                        .icon(IconName::Plus)
+                        .icon_position(IconPosition::Start)
+                        .icon_size(IconSize::Small)
+                        .on_click(cx.listener(move |_, _, cx| {
+                            assistant_chat
+                                .update(cx, |assistant_chat, cx| assistant_chat.new_prompt(cx))
+                                .log_err();
+                            cx.emit(DismissEvent);
+                        })),
+                )
+                .into_any_element(),
+        )
+    }
+}
@@ -164,6 +164,24 @@ impl AttachmentRegistry {
         (attachment.call)(cx)
     }
 
+    /// Attaches an output of the attachment tool without running it, for attachments that depend
+    /// on what the user chose rather than on the state of the workspace.
+    pub fn attach<A: LanguageModelAttachment + 'static>(
+        &self,
+        output: A::Output,
+        cx: &mut WindowContext,
+    ) -> Result<UserAttachment> {
+        let Some(attachment) = self.registered_attachments.get(&TypeId::of::<A>()) else {
+            return Err(anyhow!("no attachment tool"));
+        };
+
+        let saved_attachment = SavedUserAttachment {
+            name: attachment.name.clone(),
+            serialized_output: Ok(RawValue::from_string(serde_json::to_string(&output)?)?),
+        };
+        (attachment.deserialize)(&saved_attachment, cx)
+    }
+
     pub fn call_all_attachment_tools(
         self: Arc<Self>,
         cx: &mut WindowContext<'_>,
@@ -1,6 +1,8 @@
 use anyhow::{anyhow, Result};
 use gpui::{AppContext, Model, Task, WeakModel};
+use language::language_settings::AllLanguageSettings;
 use project::{Fs, Project, ProjectPath, Worktree};
+use settings::{Settings, SettingsLocation};
 use std::{cmp::Ordering, fmt::Write as _, ops::Range, sync::Arc};
 use sum_tree::TreeMap;
 
@@ -93,6 +95,7 @@ impl ProjectContext {
         let fs = self.fs.clone();
         cx.spawn(|cx| async move {
             let project = project?;
+            let languages = project.read_with(&cx, |project, _| project.languages().clone())?;
             let mut result = "project structure:\n".to_string();
 
             let mut last_worktree: Option<Model<Worktree>> = None;
@@ -122,6 +125,23 @@ impl ProjectContext {
                 let path = &project_path.path;
                 writeln!(&mut result, "## {}", path.display()).unwrap();
 
+                // The contents of the files that the settings exclude are never loaded.
+                if !matches!(path_state, PathState::PathOnly) {
+                    let language = languages.language_for_file_path(path).await.ok();
+                    let enabled = cx.update(|cx| {
+                        let location = SettingsLocation {
+                            worktree_id: project_path.worktree_id.to_usize(),
+                            path,
+                        };
+                        AllLanguageSettings::get(Some(location), cx)
+                            .assistant_context_enabled(language.as_ref(), Some(path.as_ref()))
+                    })?;
+                    if !enabled {
+                        writeln!(&mut result, "(excluded from the context by the user)").unwrap();
+                        continue;
+                    }
+                }
+
                 match path_state {
                     PathState::PathOnly => {}
                     PathState::EntireFile => {
@@ -173,7 +193,7 @@ mod tests {
     use std::path::Path;
 
     use super::*;
-    use gpui::TestAppContext;
+    use gpui::{TestAppContext, UpdateGlobal};
     use project::FakeFs;
     use serde_json::json;
     use settings::SettingsStore;
@@ -286,10 +306,70 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_excluded_files(cx: &mut TestAppContext) {
+        init_test(cx);
+        cx.update(|cx| {
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings::<AllLanguageSettings>(cx, |settings| {
+                    settings.assistant_context_exclusions = Some(vec!["secrets/**".into()]);
+                });
+            });
+        });
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/code",
+            json!({
+                "root1": {
+                    "lib.rs": "mod secrets;",
+                    "secrets": {
+                        "keys.rs": "const KEY: &str = \"hunter2\";",
+                    },
+                },
+            }),
+        )
+        .await;
+        let project = Project::test(fs.clone(), ["/code/root1".as_ref()], cx).await;
+        let worktree_id = project.read_with(cx, |project, cx| {
+            project.worktrees().next().unwrap().read(cx).id()
+        });
+
+        let mut ax = ProjectContext::new(project.downgrade(), fs);
+        ax.add_file(ProjectPath {
+            worktree_id,
+            path: Path::new("lib.rs").into(),
+        });
+        ax.add_file(ProjectPath {
+            worktree_id,
+            path: Path::new("secrets/keys.rs").into(),
+        });
+
+        let message = cx
+            .update(|cx| ax.generate_system_message(cx))
+            .await
+            .unwrap();
+        assert_eq!(
+            r#"
+            project structure:
+            # root1
+            ## lib.rs
+            ~~~
+            mod secrets;
+            ~~~
+            ## secrets/keys.rs
+            (excluded from the context by the user)
+            "#
+            .unindent(),
+            message
+        );
+    }
+
     fn init_test(cx: &mut TestAppContext) {
         cx.update(|cx| {
             let settings_store = SettingsStore::test(cx);
             cx.set_global(settings_store);
+            language::init(cx);
             Project::init_settings(cx);
         });
     }
@@ -12,7 +12,7 @@ use std::{
     mem,
     sync::atomic::{AtomicBool, Ordering::SeqCst},
 };
-use ui::ViewContext;
+use ui::{LabelCommon as _, ViewContext};
 
 pub struct ToolRegistry {
     registered_tools: HashMap<String, RegisteredTool>,
@@ -31,6 +31,8 @@ enum ToolFunctionCallState {
     #[default]
     Initializing,
     NoSuchTool,
+    /// The arguments the model called the tool with don't match the tool's input schema.
+    InvalidArguments(String),
     KnownTool(Box<dyn InternalToolView>),
     ExecutedTool(Box<dyn InternalToolView>),
 }
@@ -39,6 +41,7 @@ trait InternalToolView {
     fn view(&self) -> AnyView;
     fn generate(&self, project: &mut ProjectContext, cx: &mut WindowContext) -> String;
     fn try_set_input(&self, input: &str, cx: &mut WindowContext);
+    fn set_validated_input(&self, input: &str, cx: &mut WindowContext) -> Result<()>;
     fn execute(&self, cx: &mut WindowContext) -> Task<Result<()>>;
     fn serialize_output(&self, cx: &mut WindowContext) -> Result<Box<RawValue>>;
     fn deserialize_output(&self, raw_value: &RawValue, cx: &mut WindowContext) -> Result<()>;
@@ -57,6 +60,7 @@ enum SavedToolFunctionCallState {
     #[default]
     Initializing,
     NoSuchTool,
+    InvalidArguments(String),
     KnownTool,
     ExecutedTool(Box<RawValue>),
 }
@@ -187,17 +191,40 @@ impl ToolRegistry {
         }
     }
 
+    /// Executes the tool once the model has finished calling it.
+    ///
+    /// Calls to tools that don't exist, or whose arguments don't match the tool's input schema,
+    /// aren't executed, but still complete so that the error is reported back to the model.
     pub fn execute_tool_call(
         &self,
         tool_call: &mut ToolFunctionCall,
         cx: &mut WindowContext,
     ) -> Option<Task<Result<()>>> {
-        if let ToolFunctionCallState::KnownTool(view) = mem::take(&mut tool_call.state) {
-            let task = view.execute(cx);
-            tool_call.state = ToolFunctionCallState::ExecutedTool(view);
-            Some(task)
-        } else {
-            None
+        match mem::take(&mut tool_call.state) {
+            ToolFunctionCallState::KnownTool(view) => {
+                // Tools without parameters may be called without any arguments at all.
+                let arguments = if tool_call.arguments.trim().is_empty() {
+                    "{}"
+                } else {
+                    tool_call.arguments.as_str()
+                };
+                if let Err(error) = view.set_validated_input(arguments, cx) {
+                    tool_call.state = ToolFunctionCallState::InvalidArguments(error.to_string());
+                    return Some(Task::ready(Ok(())));
+                }
+
+                let task = view.execute(cx);
+                tool_call.state = ToolFunctionCallState::ExecutedTool(view);
+                Some(task)
+            }
+            ToolFunctionCallState::NoSuchTool => {
+                tool_call.state = ToolFunctionCallState::NoSuchTool;
+                Some(Task::ready(Ok(())))
+            }
+            state => {
+                tool_call.state = state;
+                None
+            }
         }
     }
 
@@ -210,6 +237,11 @@ impl ToolRegistry {
             ToolFunctionCallState::NoSuchTool => {
                 Some(ui::Label::new("No such tool").into_any_element())
             }
+            ToolFunctionCallState::InvalidArguments(error) => Some(
+                ui::Label::new(format!("Invalid arguments for {}: {error}", tool_call.name))
+                    .color(ui::Color::Error)
+                    .into_any_element(),
+            ),
             ToolFunctionCallState::Initializing => None,
             ToolFunctionCallState::KnownTool(view) | ToolFunctionCallState::ExecutedTool(view) => {
                 Some(view.view().into_any_element())
@@ -228,6 +260,10 @@ impl ToolRegistry {
             ToolFunctionCallState::NoSuchTool => {
                 format!("No such tool: {}", tool_call.name)
             }
+            ToolFunctionCallState::InvalidArguments(error) => format!(
+                "Invalid arguments for {}: {error}. Call it again with arguments that match its parameters.",
+                tool_call.name
+            ),
             ToolFunctionCallState::KnownTool(view) | ToolFunctionCallState::ExecutedTool(view) => {
                 view.generate(project_context, cx)
             }
@@ -246,6 +282,9 @@ impl ToolRegistry {
             state: match &call.state {
                 ToolFunctionCallState::Initializing => SavedToolFunctionCallState::Initializing,
                 ToolFunctionCallState::NoSuchTool => SavedToolFunctionCallState::NoSuchTool,
+                ToolFunctionCallState::InvalidArguments(error) => {
+                    SavedToolFunctionCallState::InvalidArguments(error.clone())
+                }
                 ToolFunctionCallState::KnownTool(_) => SavedToolFunctionCallState::KnownTool,
                 ToolFunctionCallState::ExecutedTool(view) => {
                     SavedToolFunctionCallState::ExecutedTool(view.serialize_output(cx)?)
@@ -259,8 +298,12 @@ impl ToolRegistry {
         call: &SavedToolFunctionCall,
         cx: &mut WindowContext,
     ) -> Result<ToolFunctionCall> {
-        let Some(tool) = self.registered_tools.get(&call.name) else {
-            return Err(anyhow!("no such tool {}", call.name));
+        // Calls to tools that don't exist are saved too, so the tool is only needed to restore the
+        // views of those that do.
+        let tool = || {
+            self.registered_tools
+                .get(&call.name)
+                .ok_or_else(|| anyhow!("no such tool {}", call.name))
         };
 
         Ok(ToolFunctionCall {
@@ -270,14 +313,17 @@ impl ToolRegistry {
             state: match &call.state {
                 SavedToolFunctionCallState::Initializing => ToolFunctionCallState::Initializing,
                 SavedToolFunctionCallState::NoSuchTool => ToolFunctionCallState::NoSuchTool,
+                SavedToolFunctionCallState::InvalidArguments(error) => {
+                    ToolFunctionCallState::InvalidArguments(error.clone())
+                }
                 SavedToolFunctionCallState::KnownTool => {
                     log::error!("Deserialized tool that had not executed");
-                    let view = (tool.build_view)(cx);
+                    let view = (tool()?.build_view)(cx);
                     view.try_set_input(&call.arguments, cx);
                     ToolFunctionCallState::KnownTool(view)
                 }
                 SavedToolFunctionCallState::ExecutedTool(output) => {
-                    let view = (tool.build_view)(cx);
+                    let view = (tool()?.build_view)(cx);
                     view.try_set_input(&call.arguments, cx);
                     view.deserialize_output(output, cx)?;
                     ToolFunctionCallState::ExecutedTool(view)
@@ -322,6 +368,15 @@ impl<T: ToolView> InternalToolView for View<T> {
         }
     }
 
+    fn set_validated_input(&self, input: &str, cx: &mut WindowContext) -> Result<()> {
+        let input = serde_json::from_str::<T::Input>(input)?;
+        self.update(cx, |view, cx| {
+            view.set_input(input, cx);
+            cx.notify();
+        });
+        Ok(())
+    }
+
     fn execute(&self, cx: &mut WindowContext) -> Task<Result<()>> {
         self.update(cx, |view, cx| view.execute(cx))
     }
@@ -353,9 +408,11 @@ mod test {
     use super::*;
     use gpui::{div, prelude::*, Render, TestAppContext};
     use gpui::{EmptyView, View};
+    use project::{FakeFs, Project};
     use schemars::JsonSchema;
     use serde::{Deserialize, Serialize};
     use serde_json::json;
+    use settings::SettingsStore;
 
     #[derive(Deserialize, Serialize, JsonSchema)]
     struct WeatherQuery {
@@ -523,4 +580,112 @@ mod test {
             _ => panic!(),
         }
     }
+
+    #[gpui::test]
+    async fn test_invalid_tool_arguments(cx: &mut TestAppContext) {
+        let (_, cx) = cx.add_window_view(|_cx| EmptyView);
+
+        let mut registry = ToolRegistry::new();
+        registry
+            .register(WeatherTool {
+                current_weather: WeatherResult {
+                    location: "San Francisco".to_string(),
+                    temperature: 21.0,
+                    unit: "Celsius".to_string(),
+                },
+            })
+            .unwrap();
+
+        let mut call = ToolFunctionCall {
+            id: "the-id".to_string(),
+            ..Default::default()
+        };
+
+        let task = cx.update(|cx| {
+            registry.update_tool_call(
+                &mut call,
+                Some("get_current_weather"),
+                Some(r#"{"location": 42}"#),
+                cx,
+            );
+            registry.execute_tool_call(&mut call, cx).unwrap()
+        });
+        task.await.unwrap();
+
+        match &call.state {
+            ToolFunctionCallState::InvalidArguments(error) => {
+                assert!(error.contains("invalid type"), "{error}");
+            }
+            _ => panic!(),
+        }
+    }
+
+    #[gpui::test]
+    async fn test_tool_call_errors_round_trip(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            Project::init_settings(cx);
+        });
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs.clone(), [], cx).await;
+        let (_, cx) = cx.add_window_view(|_cx| EmptyView);
+
+        let mut registry = ToolRegistry::new();
+        registry
+            .register(WeatherTool {
+                current_weather: WeatherResult {
+                    location: "San Francisco".to_string(),
+                    temperature: 21.0,
+                    unit: "Celsius".to_string(),
+                },
+            })
+            .unwrap();
+
+        let mut no_such_tool = ToolFunctionCall {
+            id: "call-1".to_string(),
+            ..Default::default()
+        };
+        let mut invalid_arguments = ToolFunctionCall {
+            id: "call-2".to_string(),
+            ..Default::default()
+        };
+        let tasks = cx.update(|cx| {
+            registry.update_tool_call(&mut no_such_tool, Some("get_forecast"), Some("{}"), cx);
+            registry.update_tool_call(
+                &mut invalid_arguments,
+                Some("get_current_weather"),
+                Some(r#"{"location": "San Francisco"}"#),
+                cx,
+            );
+            [
+                registry.execute_tool_call(&mut no_such_tool, cx).unwrap(),
+                registry
+                    .execute_tool_call(&mut invalid_arguments, cx)
+                    .unwrap(),
+            ]
+        });
+        for task in tasks {
+            task.await.unwrap();
+        }
+
+        // The errors are still reported to the model once the calls have been saved and loaded.
+        cx.update(|cx| {
+            let mut project_context = ProjectContext::new(project.downgrade(), fs.clone());
+            let contents = [&no_such_tool, &invalid_arguments].map(|call| {
+                let saved_call = registry.serialize_tool_call(call, cx).unwrap();
+                let saved_call = serde_json::to_string(&saved_call).unwrap();
+                let saved_call = serde_json::from_str(&saved_call).unwrap();
+                let call = registry.deserialize_tool_call(&saved_call, cx).unwrap();
+                registry.content_for_tool_call(&call, &mut project_context, cx)
+            });
+            assert_eq!(contents[0], "No such tool: get_forecast");
+            assert!(
+                contents[1]
+                    .starts_with("Invalid arguments for get_current_weather: missing field `unit`"),
+                "{}",
+                contents[1]
+            );
+        });
+    }
 }
@@ -310,7 +310,8 @@ impl AutoUpdater {
     }
 
     pub fn poll(&mut self, cx: &mut ModelContext<Self>) {
-        if self.pending_poll.is_some() || self.status.is_updated() {
+        if self.pending_poll.is_some() || self.status.is_updated() || self.http_client.is_offline()
+        {
             return;
         }
 
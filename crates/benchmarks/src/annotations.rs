@@ -0,0 +1,150 @@
+use crate::output::{format_change, format_duration};
+use collections::BTreeMap;
+
+/// The latest result of a benchmark, along with the result of the run before it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LatestResult {
+    pub id: String,
+    pub nanos: f64,
+    pub previous_nanos: Option<f64>,
+}
+
+/// Finds the benchmarks defined in the given Rust source, returning the row on which each
+/// of them is defined along with the text describing their latest results.
+///
+/// Benchmarks run by the built-in harness are found by their function's name, while
+/// criterion benchmarks are found by the string literal naming them.
+pub fn annotations(text: &str, results: &[LatestResult]) -> Vec<(u32, String)> {
+    let lines = text.lines().collect::<Vec<_>>();
+    let mut results_by_row = BTreeMap::<u32, Vec<&LatestResult>>::new();
+    for result in results {
+        if let Some(row) = definition_row(&lines, &result.id) {
+            results_by_row.entry(row).or_default().push(result);
+        }
+    }
+
+    results_by_row
+        .into_iter()
+        .map(|(row, results)| {
+            let descriptions = results
+                .iter()
+                .map(|result| {
+                    let description = describe(result);
+                    if results.len() > 1 {
+                        format!("{}: {description}", short_name(&result.id))
+                    } else {
+                        description
+                    }
+                })
+                .collect::<Vec<_>>();
+            (row, format!("  ⏱ {}", descriptions.join(", ")))
+        })
+        .collect()
+}
+
+fn describe(result: &LatestResult) -> String {
+    let duration = format_duration(result.nanos);
+    match result.previous_nanos {
+        Some(previous_nanos) => format!(
+            "{duration} ({})",
+            format_change(previous_nanos, result.nanos)
+        ),
+        None => duration,
+    }
+}
+
+fn short_name(id: &str) -> &str {
+    id.rsplit(['/', ':']).next().unwrap_or(id)
+}
+
+fn definition_row(lines: &[&str], id: &str) -> Option<u32> {
+    // Criterion IDs are made up of the group name, the function name and the parameter.
+    // The function name is the most specific of these likely to appear as a literal.
+    let mut parts = id.split('/');
+    let group = parts.next()?;
+    let function = parts.next();
+    let literals = function.into_iter().chain([group]);
+
+    let function_name = id.rsplit("::").next()?;
+
+    literals
+        .filter_map(|literal| {
+            let literal = format!("\"{literal}\"");
+            lines.iter().position(|line| line.contains(&literal))
+        })
+        .next()
+        .or_else(|| {
+            lines
+                .iter()
+                .position(|line| defines_function(line, function_name))
+        })
+        .map(|row| row as u32)
+}
+
+fn defines_function(line: &str, name: &str) -> bool {
+    let pattern = format!("fn {name}");
+    line.match_indices(&pattern).any(|(ix, _)| {
+        let preceded_by_boundary = line[..ix]
+            .chars()
+            .next_back()
+            .map_or(true, char::is_whitespace);
+        let followed_by_signature = line[ix + pattern.len()..]
+            .trim_start()
+            .starts_with(['(', '<']);
+        preceded_by_boundary && followed_by_signature
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    fn result(id: &str, nanos: f64, previous_nanos: Option<f64>) -> LatestResult {
+        LatestResult {
+            id: id.into(),
+            nanos,
+            previous_nanos,
+        }
+    }
+
+    #[test]
+    fn test_annotations() {
+        let text = indoc! {r#"
+            #[bench]
+            fn bench_fib(b: &mut Bencher) {
+                b.iter(|| fib(20));
+            }
+
+            fn criterion_benchmark(c: &mut Criterion) {
+                c.bench_function("fib 20", |b| b.iter(|| fib(20)));
+                let mut group = c.benchmark_group("parsing");
+                group.bench_function("json", |b| b.iter(|| parse_json()));
+                group.bench_function("toml", |b| b.iter(|| parse_toml()));
+                for size in [10, 100] {
+                    group.bench_with_input(BenchmarkId::from_parameter(size), &size, run);
+                }
+            }
+        "#};
+
+        assert_eq!(
+            annotations(
+                text,
+                &[
+                    result("tests::bench_fib", 1234., Some(1200.)),
+                    result("fib 20", 26251., None),
+                    result("parsing/json", 1_210_100., Some(1_210_000.)),
+                    result("parsing/10", 500., None),
+                    result("parsing/100", 5000., Some(6000.)),
+                    result("tests::bench_missing", 1., None),
+                ],
+            ),
+            [
+                (1, "  ⏱ 1.23 µs (+2.8%)".to_string()),
+                (6, "  ⏱ 26.25 µs".to_string()),
+                (7, "  ⏱ 10: 500.00 ns, 100: 5.00 µs (-16.7%)".to_string()),
+                (8, "  ⏱ 1.21 ms (±0.0%)".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,340 @@
+mod annotations;
+mod output;
+
+use anyhow::Result;
+use collections::{BTreeMap, HashMap};
+use db::kvp::KEY_VALUE_STORE;
+use editor::{Editor, EditorMode};
+use gpui::{
+    actions, AppContext, Context, EventEmitter, Global, Model, ModelContext, Subscription,
+    ViewContext, WeakModel,
+};
+use language::{Point, ToPoint};
+use project::Project;
+use serde_derive::{Deserialize, Serialize};
+use std::{
+    path::Path,
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use terminal_view::TerminalView;
+use util::ResultExt;
+use workspace::Workspace;
+
+pub use annotations::{annotations, LatestResult};
+pub use output::{format_change, format_duration, parse_bench_output, BenchmarkResult};
+
+actions!(benchmarks, [ClearHistory]);
+
+/// The number of runs kept in the history of each project.
+const MAX_RUNS: usize = 20;
+
+pub fn init(cx: &mut AppContext) {
+    let store = cx.new_model(|_| BenchmarkStore::default());
+    cx.set_global(GlobalBenchmarkStore(store));
+
+    cx.observe_new_views(|workspace: &mut Workspace, cx| {
+        let project = workspace.project().clone();
+        BenchmarkStore::global(cx).update(cx, |store, cx| store.add_project(project, cx));
+
+        workspace.register_action(|workspace, _: &ClearHistory, cx| {
+            let roots = worktree_roots(workspace.project(), cx);
+            BenchmarkStore::global(cx).update(cx, |store, cx| store.clear_history(&roots, cx));
+        });
+    })
+    .detach();
+    cx.observe_new_views(register_terminal_view).detach();
+    cx.observe_new_views(register_editor).detach();
+}
+
+struct GlobalBenchmarkStore(Model<BenchmarkStore>);
+
+impl Global for GlobalBenchmarkStore {}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Event {
+    HistoryChanged,
+}
+
+/// A single run of `cargo bench`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct BenchmarkRun {
+    /// When the run finished, in seconds since the Unix epoch.
+    pub timestamp: u64,
+    /// The time taken by each iteration of each benchmark in nanoseconds, keyed by the
+    /// benchmark's ID.
+    pub results: BTreeMap<String, f64>,
+}
+
+/// The most recent runs of the benchmarks in a project, oldest first.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct BenchmarkHistory {
+    pub runs: Vec<BenchmarkRun>,
+}
+
+impl BenchmarkHistory {
+    /// The latest result of every benchmark in the history. Runs often only include some of
+    /// the benchmarks, so each result is compared to the previous run that included it.
+    pub fn latest_results(&self) -> Vec<LatestResult> {
+        let mut results = BTreeMap::<&str, LatestResult>::new();
+        for run in &self.runs {
+            for (id, nanos) in &run.results {
+                let previous_nanos = results.get(id.as_str()).map(|result| result.nanos);
+                results.insert(
+                    id.as_str(),
+                    LatestResult {
+                        id: id.clone(),
+                        nanos: *nanos,
+                        previous_nanos,
+                    },
+                );
+            }
+        }
+        results.into_values().collect()
+    }
+
+    fn push(&mut self, run: BenchmarkRun) {
+        self.runs.push(run);
+        let excess = self.runs.len().saturating_sub(MAX_RUNS);
+        self.runs.drain(..excess);
+    }
+}
+
+/// Records the results of the benchmarks run as tasks, keeping a history of previous runs
+/// for each worktree in the database.
+#[derive(Default)]
+pub struct BenchmarkStore {
+    histories: HashMap<Arc<Path>, BenchmarkHistory>,
+    projects: Vec<(WeakModel<Project>, Subscription)>,
+}
+
+impl EventEmitter<Event> for BenchmarkStore {}
+
+impl BenchmarkStore {
+    pub fn global(cx: &AppContext) -> Model<Self> {
+        cx.global::<GlobalBenchmarkStore>().0.clone()
+    }
+
+    pub fn try_global(cx: &AppContext) -> Option<Model<Self>> {
+        cx.try_global::<GlobalBenchmarkStore>()
+            .map(|store| store.0.clone())
+    }
+
+    /// The history of the worktree containing the given path, if any.
+    pub fn history_for_path(&self, abs_path: &Path) -> Option<&BenchmarkHistory> {
+        self.histories
+            .iter()
+            .filter(|(root, _)| abs_path.starts_with(root))
+            .max_by_key(|(root, _)| root.as_os_str().len())
+            .map(|(_, history)| history)
+    }
+
+    /// Adds a run containing the given results to the history of each of the given worktrees.
+    pub fn record_run(
+        &mut self,
+        roots: &[Arc<Path>],
+        results: Vec<BenchmarkResult>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let run = BenchmarkRun {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |duration| duration.as_secs()),
+            results: results
+                .into_iter()
+                .map(|result| (result.id, result.nanos))
+                .collect(),
+        };
+
+        for root in roots {
+            let history = self.histories.entry(root.clone()).or_default();
+            history.push(run.clone());
+            if let Some(json) = serde_json::to_string(history).log_err() {
+                let key = history_key(root);
+                db::write_and_log(cx, move || KEY_VALUE_STORE.write_kvp(key, json));
+            }
+        }
+        cx.emit(Event::HistoryChanged);
+    }
+
+    pub fn clear_history(&mut self, roots: &[Arc<Path>], cx: &mut ModelContext<Self>) {
+        for root in roots {
+            self.histories
+                .insert(root.clone(), BenchmarkHistory::default());
+            let key = history_key(root);
+            db::write_and_log(cx, move || KEY_VALUE_STORE.delete_kvp(key));
+        }
+        cx.emit(Event::HistoryChanged);
+    }
+
+    fn add_project(&mut self, project: Model<Project>, cx: &mut ModelContext<Self>) {
+        if !project.read(cx).is_local() {
+            return;
+        }
+
+        let subscription = cx.subscribe(&project, |this, project, event, cx| {
+            if let project::Event::WorktreeAdded = event {
+                this.load_histories(&project, cx);
+            }
+        });
+        self.projects.push((project.downgrade(), subscription));
+        self.projects
+            .retain(|(project, _)| project.upgrade().is_some());
+        self.load_histories(&project, cx);
+    }
+
+    fn load_histories(&mut self, project: &Model<Project>, cx: &mut ModelContext<Self>) {
+        for root in worktree_roots(project, cx) {
+            if self.histories.contains_key(&root) {
+                continue;
+            }
+
+            self.histories
+                .insert(root.clone(), BenchmarkHistory::default());
+            let key = history_key(&root);
+            let load = cx
+                .background_executor()
+                .spawn(async move { KEY_VALUE_STORE.read_kvp(&key) });
+            cx.spawn(|this, mut cx| async move {
+                let Some(json) = load.await? else {
+                    return Ok(());
+                };
+                let mut loaded = serde_json::from_str::<BenchmarkHistory>(&json)?;
+                this.update(&mut cx, |this, cx| {
+                    // Runs may have finished while the history was loading.
+                    let history = this.histories.entry(root).or_default();
+                    for run in history.runs.drain(..) {
+                        loaded.push(run);
+                    }
+                    *history = loaded;
+                    cx.emit(Event::HistoryChanged);
+                })
+            })
+            .detach_and_log_err(cx);
+        }
+    }
+}
+
+fn history_key(root: &Path) -> String {
+    format!("benchmark_history_{}", root.to_string_lossy())
+}
+
+fn worktree_roots(project: &Model<Project>, cx: &AppContext) -> Vec<Arc<Path>> {
+    project
+        .read(cx)
+        .visible_worktrees(cx)
+        .map(|worktree| worktree.read(cx).abs_path())
+        .collect()
+}
+
+fn register_terminal_view(_: &mut TerminalView, cx: &mut ViewContext<TerminalView>) {
+    cx.subscribe(
+        &cx.view().clone(),
+        |terminal_view, _, event: &terminal::Event, cx| {
+            if let terminal::Event::TaskFinished = event {
+                record_task_results(terminal_view, cx).log_err();
+            }
+        },
+    )
+    .detach();
+}
+
+fn record_task_results(
+    terminal_view: &mut TerminalView,
+    cx: &mut ViewContext<TerminalView>,
+) -> Result<()> {
+    let terminal = terminal_view.terminal().read(cx);
+    let Some(task) = terminal.task() else {
+        return Ok(());
+    };
+    let is_benchmark = task
+        .command_label
+        .split_whitespace()
+        .any(|arg| arg == "bench" || arg == "criterion");
+    if !is_benchmark {
+        return Ok(());
+    }
+
+    let results = parse_bench_output(&terminal.text());
+    if results.is_empty() {
+        return Ok(());
+    }
+
+    let workspace = terminal_view.workspace().upgrade();
+    let Some(project) = workspace.map(|workspace| workspace.read(cx).project().clone()) else {
+        return Ok(());
+    };
+    let roots = worktree_roots(&project, cx);
+    BenchmarkStore::global(cx).update(cx, |store, cx| store.record_run(&roots, results, cx));
+    Ok(())
+}
+
+/// Marks the inlays showing the results of benchmarks.
+struct BenchmarkAnnotations;
+
+fn register_editor(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+    if editor.mode() != EditorMode::Full {
+        return;
+    }
+    let Some(store) = BenchmarkStore::try_global(cx) else {
+        return;
+    };
+
+    cx.subscribe(&store, |editor, _, _: &Event, cx| {
+        update_annotations(editor, cx)
+    })
+    .detach();
+    cx.subscribe(&editor.buffer().clone(), |editor, _, event, cx| {
+        if matches!(
+            event,
+            multi_buffer::Event::ExcerptsAdded { .. }
+                | multi_buffer::Event::FileHandleChanged
+                | multi_buffer::Event::LanguageChanged
+                | multi_buffer::Event::Reloaded
+                | multi_buffer::Event::Saved
+        ) {
+            update_annotations(editor, cx);
+        }
+    })
+    .detach();
+
+    update_annotations(editor, cx);
+}
+
+fn update_annotations(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+    let store = BenchmarkStore::global(cx);
+    let snapshot = editor.buffer().read(cx).snapshot(cx);
+    let mut inlays = Vec::new();
+    for (excerpt_id, buffer, excerpt_range) in snapshot.excerpts() {
+        let is_rust = buffer
+            .language()
+            .map_or(false, |language| language.name().as_ref() == "Rust");
+        let Some(file) = buffer.file().and_then(|file| file.as_local()) else {
+            continue;
+        };
+        if !is_rust {
+            continue;
+        }
+        let Some(history) = store.read(cx).history_for_path(&file.abs_path(cx)) else {
+            continue;
+        };
+        let results = history.latest_results();
+        if results.is_empty() {
+            continue;
+        }
+
+        let excerpt_start_row = excerpt_range.context.start.to_point(buffer).row;
+        let excerpt_end_row = excerpt_range.context.end.to_point(buffer).row;
+        for (row, text) in annotations(&buffer.text(), &results) {
+            if row < excerpt_start_row || row > excerpt_end_row {
+                continue;
+            }
+            let position = buffer.anchor_after(Point::new(row, buffer.line_len(row)));
+            if let Some(position) = snapshot.anchor_in_excerpt(excerpt_id, position) {
+                inlays.push((position, text));
+            }
+        }
+    }
+
+    editor.set_inlay_annotations::<BenchmarkAnnotations>(inlays, cx);
+}
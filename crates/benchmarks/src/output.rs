@@ -0,0 +1,181 @@
+/// The time taken by a single benchmark, as reported by `cargo bench`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BenchmarkResult {
+    /// The benchmark's ID, such as `tests::bench_parse` for the built-in harness, or
+    /// `parsing/json/large` for criterion.
+    pub id: String,
+    /// The estimated time taken by each iteration, in nanoseconds.
+    pub nanos: f64,
+}
+
+/// Extracts the benchmark results from the output of `cargo bench`, which may have been
+/// produced by either criterion or the built-in benchmark harness.
+pub fn parse_bench_output(output: &str) -> Vec<BenchmarkResult> {
+    let mut results = Vec::new();
+    let mut previous_line = "";
+    for line in output.lines() {
+        if let Some(result) =
+            parse_libtest_line(line).or_else(|| parse_criterion_line(line, previous_line))
+        {
+            results.push(result);
+        }
+        if !line.trim().is_empty() {
+            previous_line = line;
+        }
+    }
+    results
+}
+
+/// Parses a line such as `test bench_fib ... bench:   1,234 ns/iter (+/- 56)`.
+fn parse_libtest_line(line: &str) -> Option<BenchmarkResult> {
+    let line = line.trim().strip_prefix("test ")?;
+    let (id, measurement) = line.split_once(" ... bench:")?;
+    let (nanos, _) = measurement.trim_start().split_once(" ns/iter")?;
+    Some(BenchmarkResult {
+        id: id.trim().to_string(),
+        nanos: nanos.replace(',', "").parse().ok()?,
+    })
+}
+
+/// Parses a line such as `fib 20    time:   [26.029 µs 26.251 µs 26.505 µs]`. Criterion
+/// prints long IDs on their own line, in which case the ID is taken from the previous line.
+fn parse_criterion_line(line: &str, previous_line: &str) -> Option<BenchmarkResult> {
+    let (id, measurement) = line.split_once("time:")?;
+    let measurement = measurement.trim().strip_prefix('[')?;
+    let (measurement, _) = measurement.split_once(']')?;
+
+    // The estimate is the middle of the confidence interval. Change reports use the same
+    // layout, with percentages rather than units, and fail to parse here.
+    let mut parts = measurement.split_whitespace().skip(2);
+    let value = parts.next()?.parse::<f64>().ok()?;
+    let nanos = value * nanos_per_unit(parts.next()?)?;
+
+    let id = if id.trim().is_empty() {
+        if previous_line.starts_with(char::is_whitespace) {
+            return None;
+        }
+        previous_line.trim()
+    } else {
+        id.trim()
+    };
+    if id.is_empty() || id.starts_with("Benchmarking ") {
+        return None;
+    }
+
+    Some(BenchmarkResult {
+        id: id.to_string(),
+        nanos,
+    })
+}
+
+fn nanos_per_unit(unit: &str) -> Option<f64> {
+    match unit {
+        "ps" => Some(1e-3),
+        "ns" => Some(1.),
+        "us" | "µs" | "μs" => Some(1e3),
+        "ms" => Some(1e6),
+        "s" => Some(1e9),
+        _ => None,
+    }
+}
+
+/// Formats a duration in nanoseconds using the largest unit in which it's at least 1.
+pub fn format_duration(nanos: f64) -> String {
+    let (value, unit) = if nanos >= 1e9 {
+        (nanos / 1e9, "s")
+    } else if nanos >= 1e6 {
+        (nanos / 1e6, "ms")
+    } else if nanos >= 1e3 {
+        (nanos / 1e3, "µs")
+    } else {
+        (nanos, "ns")
+    };
+    format!("{value:.2} {unit}")
+}
+
+/// Formats the relative change from `previous` to `current`, such as `+2.4%`.
+pub fn format_change(previous: f64, current: f64) -> String {
+    let change = (current - previous) / previous * 100.;
+    if change.abs() < 0.05 {
+        "±0.0%".to_string()
+    } else {
+        format!("{change:+.1}%")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    #[test]
+    fn test_parse_libtest_output() {
+        let output = indoc! {"
+            running 3 tests
+            test tests::bench_fib    ... bench:       1,234 ns/iter (+/- 56)
+            test tests::bench_parse  ... bench:      98,765.50 ns/iter (+/- 1,020.10)
+            test tests::test_parse ... ignored
+
+            test result: ok. 0 passed; 0 failed; 1 ignored; 0 measured; 2 filtered out
+        "};
+        assert_eq!(
+            parse_bench_output(output),
+            [
+                BenchmarkResult {
+                    id: "tests::bench_fib".into(),
+                    nanos: 1234.,
+                },
+                BenchmarkResult {
+                    id: "tests::bench_parse".into(),
+                    nanos: 98765.5,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_criterion_output() {
+        let output = indoc! {"
+            Benchmarking fib 20: Analyzing
+            fib 20                  time:   [26.029 µs 26.251 µs 26.505 µs]
+                                    change: [-2.3019% -0.8965% +0.6043%] (p = 0.23 > 0.05)
+                                    No change in performance detected.
+            Found 3 outliers among 100 measurements (3.00%)
+              3 (3.00%) high mild
+            parsing/json/very_large_document
+                                    time:   [1.2045 ms 1.2101 ms 1.2160 ms]
+                                    thrpt:  [80.186 MiB/s 80.575 MiB/s 80.947 MiB/s]
+                             change:
+                                    time:   [-1.0% +0.1% +1.1%] (p = 0.84 > 0.05)
+            tiny                    time:   [512.10 ps 515.00 ps 519.90 ps]
+        "};
+        assert_eq!(
+            parse_bench_output(output),
+            [
+                BenchmarkResult {
+                    id: "fib 20".into(),
+                    nanos: 26251.,
+                },
+                BenchmarkResult {
+                    id: "parsing/json/very_large_document".into(),
+                    nanos: 1210100.,
+                },
+                BenchmarkResult {
+                    id: "tiny".into(),
+                    nanos: 0.515,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_format() {
+        assert_eq!(format_duration(0.515), "0.52 ns");
+        assert_eq!(format_duration(26251.), "26.25 µs");
+        assert_eq!(format_duration(1_210_100.), "1.21 ms");
+        assert_eq!(format_duration(3.5e9), "3.50 s");
+        assert_eq!(format_change(100., 102.4), "+2.4%");
+        assert_eq!(format_change(100., 90.), "-10.0%");
+        assert_eq!(format_change(100., 100.01), "±0.0%");
+    }
+}
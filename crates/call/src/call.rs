@@ -18,6 +18,7 @@ use room::Event;
 use settings::Settings;
 use std::sync::Arc;
 
+pub use live_kit_client::AudioDevice;
 pub use participant::ParticipantLocation;
 pub use room::Room;
 
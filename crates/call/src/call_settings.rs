@@ -1,5 +1,6 @@
 use anyhow::Result;
 use gpui::AppContext;
+use live_kit_client::ScreenCaptureOptions;
 use schemars::JsonSchema;
 use serde_derive::{Deserialize, Serialize};
 use settings::{Settings, SettingsSources};
@@ -8,6 +9,57 @@ use settings::{Settings, SettingsSources};
 pub struct CallSettings {
     pub mute_on_join: bool,
     pub share_on_join: bool,
+    pub push_to_talk: bool,
+    pub audio_input_device: Option<String>,
+    pub screen_share_resolution: ScreenShareResolution,
+    pub screen_share_frame_rate: u32,
+}
+
+impl CallSettings {
+    /// The limits to apply to the video captured when sharing a screen.
+    pub fn screen_capture_options(&self) -> ScreenCaptureOptions {
+        let (max_width, max_height) = self.screen_share_resolution.dimensions();
+        ScreenCaptureOptions {
+            max_width,
+            max_height,
+            max_frame_rate: self.screen_share_frame_rate.max(1),
+        }
+    }
+}
+
+/// The maximum resolution at which screens are shared.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub enum ScreenShareResolution {
+    #[serde(rename = "720p")]
+    P720,
+    #[serde(rename = "1080p")]
+    P1080,
+    #[serde(rename = "1440p")]
+    P1440,
+    #[serde(rename = "2160p")]
+    P2160,
+}
+
+impl ScreenShareResolution {
+    pub const ALL: [Self; 4] = [Self::P720, Self::P1080, Self::P1440, Self::P2160];
+
+    pub fn dimensions(&self) -> (u32, u32) {
+        match self {
+            Self::P720 => (1280, 720),
+            Self::P1080 => (1920, 1080),
+            Self::P1440 => (2560, 1440),
+            Self::P2160 => (3840, 2160),
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::P720 => "720p",
+            Self::P1080 => "1080p",
+            Self::P1440 => "1440p",
+            Self::P2160 => "4K",
+        }
+    }
 }
 
 /// Configuration of voice calls in Zed.
@@ -22,6 +74,28 @@ pub struct CallSettingsContent {
     ///
     /// Default: true
     pub share_on_join: Option<bool>,
+
+    /// Whether the microphone should only be live while the `collab::PushToTalk`
+    /// key binding is held down.
+    ///
+    /// Default: false
+    pub push_to_talk: Option<bool>,
+
+    /// The name of the audio device used as a microphone, or null to use the
+    /// system default.
+    ///
+    /// Default: null
+    pub audio_input_device: Option<String>,
+
+    /// The maximum resolution at which your screen is shared.
+    ///
+    /// Default: 1080p
+    pub screen_share_resolution: Option<ScreenShareResolution>,
+
+    /// The maximum number of frames per second captured when sharing your screen.
+    ///
+    /// Default: 15
+    pub screen_share_frame_rate: Option<u32>,
 }
 
 impl Settings for CallSettings {
@@ -15,7 +15,9 @@ use gpui::{
     AppContext, AsyncAppContext, Context, EventEmitter, Model, ModelContext, Task, WeakModel,
 };
 use language::LanguageRegistry;
-use live_kit_client::{LocalAudioTrack, LocalTrackPublication, LocalVideoTrack, RoomUpdate};
+use live_kit_client::{
+    AudioDevice, LocalAudioTrack, LocalTrackPublication, LocalVideoTrack, RoomUpdate,
+};
 use postage::{sink::Sink, stream::Stream, watch};
 use project::Project;
 use settings::Settings as _;
@@ -158,7 +160,10 @@ impl Room {
                 this.update(&mut cx, |this, cx| {
                     if this.can_use_microphone() {
                         if let Some(live_kit) = &this.live_kit {
-                            if !live_kit.muted_by_user && !live_kit.deafened {
+                            // With push-to-talk, publish the microphone muted up front so that
+                            // holding the key down takes effect immediately.
+                            let push_to_talk = CallSettings::get_global(cx).push_to_talk;
+                            if (!live_kit.muted_by_user || push_to_talk) && !live_kit.deafened {
                                 return this.share_microphone(cx);
                             }
                         }
@@ -177,6 +182,7 @@ impl Room {
                 muted_by_user: Self::mute_on_join(cx),
                 deafened: false,
                 speaking: false,
+                push_to_talk_active: false,
                 _maintain_room,
                 _handle_updates,
             })
@@ -328,7 +334,8 @@ impl Room {
     }
 
     pub fn mute_on_join(cx: &AppContext) -> bool {
-        CallSettings::get_global(cx).mute_on_join || client::IMPERSONATE_LOGIN.is_some()
+        let settings = CallSettings::get_global(cx);
+        settings.mute_on_join || settings.push_to_talk || client::IMPERSONATE_LOGIN.is_some()
     }
 
     fn from_join_response(
@@ -1335,10 +1342,75 @@ impl Room {
         self.live_kit.as_ref().map(|live_kit| live_kit.deafened)
     }
 
+    pub fn is_push_to_talk_active(&self) -> bool {
+        self.live_kit
+            .as_ref()
+            .map_or(false, |live_kit| live_kit.push_to_talk_active)
+    }
+
+    /// The level of the audio captured by the microphone, between 0 and 1.
+    pub fn local_audio_level(&self) -> f32 {
+        if self.is_muted() {
+            return 0.;
+        }
+        self.live_kit
+            .as_ref()
+            .map_or(0., |live_kit| live_kit.room.local_audio_level())
+    }
+
+    pub fn audio_input_devices(&self) -> Vec<AudioDevice> {
+        self.live_kit
+            .as_ref()
+            .map_or(Vec::new(), |live_kit| live_kit.room.audio_input_devices())
+    }
+
+    /// The audio device currently used as a microphone.
+    pub fn audio_input_device(&self) -> Option<AudioDevice> {
+        let live_kit = self.live_kit.as_ref()?;
+        let id = live_kit.room.audio_input_device()?;
+        live_kit
+            .room
+            .audio_input_devices()
+            .into_iter()
+            .find(|device| device.id == id)
+    }
+
+    pub fn set_audio_input_device(
+        &mut self,
+        device: &AudioDevice,
+        cx: &mut ModelContext<Self>,
+    ) -> Result<()> {
+        let live_kit = self
+            .live_kit
+            .as_ref()
+            .ok_or_else(|| anyhow!("live-kit was not initialized"))?;
+        live_kit.room.set_audio_input_device(&device.id)?;
+        cx.notify();
+        Ok(())
+    }
+
+    /// Selects the microphone configured in the settings, if it is available.
+    fn apply_audio_input_device_setting(&self, cx: &AppContext) {
+        let Some(live_kit) = self.live_kit.as_ref() else {
+            return;
+        };
+        let Some(name) = CallSettings::get_global(cx).audio_input_device.as_ref() else {
+            return;
+        };
+        if let Some(device) = live_kit
+            .room
+            .audio_input_devices()
+            .into_iter()
+            .find(|device| &device.name == name)
+        {
+            live_kit.room.set_audio_input_device(&device.id).log_err();
+        }
+    }
+
     pub fn can_use_microphone(&self) -> bool {
         use proto::ChannelRole::*;
         match self.local_participant.role {
-            Admin | Member | Talker => true,
+            Admin | Member | Commenter | Talker => true,
             Guest | Banned => false,
         }
     }
@@ -1347,7 +1419,7 @@ impl Room {
         use proto::ChannelRole::*;
         match self.local_participant.role {
             Admin | Member => true,
-            Guest | Banned | Talker => false,
+            Guest | Banned | Commenter | Talker => false,
         }
     }
 
@@ -1357,6 +1429,7 @@ impl Room {
             return Task::ready(Err(anyhow!("room is offline")));
         }
 
+        self.apply_audio_input_device_setting(cx);
         let publish_id = if let Some(live_kit) = self.live_kit.as_mut() {
             let publish_id = post_inc(&mut live_kit.next_publish_id);
             live_kit.microphone_track = LocalTrack::Pending { publish_id };
@@ -1435,6 +1508,7 @@ impl Room {
             return Task::ready(Err(anyhow!("screen was already shared")));
         }
 
+        let options = CallSettings::get_global(cx).screen_capture_options();
         let (displays, publish_id) = if let Some(live_kit) = self.live_kit.as_mut() {
             let publish_id = post_inc(&mut live_kit.next_publish_id);
             live_kit.screen_track = LocalTrack::Pending { publish_id };
@@ -1450,7 +1524,7 @@ impl Room {
                 let display = displays
                     .first()
                     .ok_or_else(|| anyhow!("no display found"))?;
-                let track = LocalVideoTrack::screen_share_for_display(display);
+                let track = LocalVideoTrack::screen_share_for_display(display, options);
                 this.upgrade()
                     .ok_or_else(|| anyhow!("room was dropped"))?
                     .update(&mut cx, |this, _| {
@@ -1537,6 +1611,22 @@ impl Room {
         }
     }
 
+    /// Unmutes the microphone while push-to-talk is active, muting it again afterwards.
+    pub fn set_push_to_talk_active(&mut self, active: bool, cx: &mut ModelContext<Self>) {
+        let Some(live_kit) = self.live_kit.as_mut() else {
+            return;
+        };
+        if live_kit.push_to_talk_active == active || live_kit.deafened {
+            return;
+        }
+
+        live_kit.push_to_talk_active = active;
+        live_kit.muted_by_user = !active;
+        if let Some(task) = self.set_mute(!active, cx) {
+            task.detach_and_log_err(cx);
+        }
+    }
+
     pub fn toggle_deafen(&mut self, cx: &mut ModelContext<Self>) {
         if let Some(live_kit) = self.live_kit.as_mut() {
             // When deafening, mute the microphone if it was not already muted.
@@ -1666,6 +1756,8 @@ struct LiveKitRoom {
     muted_by_user: bool,
     deafened: bool,
     speaking: bool,
+    /// Whether the push-to-talk key binding is currently held down.
+    push_to_talk_active: bool,
     next_publish_id: usize,
     _maintain_room: Task<()>,
     _handle_updates: Task<()>,
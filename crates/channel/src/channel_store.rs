@@ -75,6 +75,8 @@ pub struct Channel {
     pub name: SharedString,
     pub visibility: proto::ChannelVisibility,
     pub parent_path: Vec<ChannelId>,
+    /// The role of those who join the channel while it's public, without having been invited.
+    pub default_role: proto::ChannelRole,
 }
 
 #[derive(Default, Debug)]
@@ -113,6 +115,13 @@ impl Channel {
         self.parent_path.first().copied().unwrap_or(self.id)
     }
 
+    pub(crate) fn default_role_from_proto(channel: &proto::Channel) -> proto::ChannelRole {
+        channel
+            .default_role
+            .and_then(proto::ChannelRole::from_i32)
+            .unwrap_or(proto::ChannelRole::Guest)
+    }
+
     pub fn slug(str: &str) -> String {
         let slug: String = str
             .chars()
@@ -136,8 +145,9 @@ impl ChannelMembership {
                 proto::ChannelRole::Admin => 0,
                 proto::ChannelRole::Member => 1,
                 proto::ChannelRole::Banned => 2,
-                proto::ChannelRole::Talker => 3,
-                proto::ChannelRole::Guest => 4,
+                proto::ChannelRole::Commenter => 3,
+                proto::ChannelRole::Talker => 4,
+                proto::ChannelRole::Guest => 5,
             },
             kind_order: match self.kind {
                 proto::channel_member::Kind::Member => 0,
@@ -565,11 +575,29 @@ impl ChannelStore {
 
     pub fn channel_capability(&self, channel_id: ChannelId) -> Capability {
         match self.channel_role(channel_id) {
-            ChannelRole::Admin | ChannelRole::Member => Capability::ReadWrite,
+            ChannelRole::Admin | ChannelRole::Member | ChannelRole::Commenter => {
+                Capability::ReadWrite
+            }
             _ => Capability::ReadOnly,
         }
     }
 
+    /// Lets the notes of channels whose role changed be written, or stops them from being.
+    fn update_channel_buffer_capabilities(&self, cx: &mut ModelContext<Self>) {
+        for (channel_id, buffer) in &self.opened_buffers {
+            if let OpenedModelHandle::Open(buffer) = buffer {
+                if let Some(buffer) = buffer.upgrade() {
+                    let capability = self.channel_capability(*channel_id);
+                    buffer.update(cx, |buffer, cx| {
+                        buffer
+                            .buffer()
+                            .update(cx, |buffer, cx| buffer.set_capability(capability, cx))
+                    });
+                }
+            }
+        }
+    }
+
     pub fn channel_role(&self, channel_id: ChannelId) -> proto::ChannelRole {
         maybe!({
             let mut channel = self.channel_for_id(channel_id)?;
@@ -649,6 +677,25 @@ impl ChannelStore {
         })
     }
 
+    pub fn set_channel_default_role(
+        &mut self,
+        channel_id: ChannelId,
+        role: proto::ChannelRole,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let client = self.client.clone();
+        cx.spawn(move |_, _| async move {
+            let _ = client
+                .request(proto::SetChannelDefaultRole {
+                    channel_id: channel_id.0,
+                    role: role.into(),
+                })
+                .await?;
+
+            Ok(())
+        })
+    }
+
     pub fn set_channel_visibility(
         &mut self,
         channel_id: ChannelId,
@@ -915,6 +962,7 @@ impl ChannelStore {
                         .set_role(role)
                 }
             }
+            this.update_channel_buffer_capabilities(cx);
         })
     }
 
@@ -1080,6 +1128,7 @@ impl ChannelStore {
                     Arc::new(Channel {
                         id: ChannelId(channel.id),
                         visibility: channel.visibility(),
+                        default_role: Channel::default_role_from_proto(&channel),
                         name: channel.name.into(),
                         parent_path: channel
                             .parent_path
@@ -59,11 +59,14 @@ impl<'a> ChannelPathsInsertGuard<'a> {
         if let Some(existing_channel) = self.channels_by_id.get_mut(&ChannelId(channel_proto.id)) {
             let existing_channel = Arc::make_mut(existing_channel);
 
+            let default_role = Channel::default_role_from_proto(&channel_proto);
             ret = existing_channel.visibility != channel_proto.visibility()
                 || existing_channel.name != channel_proto.name
-                || existing_channel.parent_path != parent_path;
+                || existing_channel.parent_path != parent_path
+                || existing_channel.default_role != default_role;
 
             existing_channel.visibility = channel_proto.visibility();
+            existing_channel.default_role = default_role;
             existing_channel.name = channel_proto.name.into();
             existing_channel.parent_path = parent_path;
         } else {
@@ -72,6 +75,7 @@ impl<'a> ChannelPathsInsertGuard<'a> {
                 Arc::new(Channel {
                     id: ChannelId(channel_proto.id),
                     visibility: channel_proto.visibility(),
+                    default_role: Channel::default_role_from_proto(&channel_proto),
                     name: channel_proto.name.into(),
                     parent_path,
                 }),
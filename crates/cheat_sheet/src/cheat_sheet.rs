@@ -0,0 +1,173 @@
+//! A cheat sheet of the key bindings that apply where the focus is, a training mode that
+//! suggests the key binding of an action whenever it's run without one, and insights into which
+//! commands are run and how.
+
+mod insights;
+mod insights_settings;
+mod training;
+
+use collections::BTreeMap;
+use command_palette::humanize_action_name;
+use gpui::{
+    actions, Action, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView,
+    KeyContext, Render, SharedString, ViewContext, WindowContext,
+};
+use itertools::Itertools;
+use std::cmp::Reverse;
+use ui::{prelude::*, Headline, HeadlineSize, KeyBinding};
+use workspace::{ModalView, Workspace};
+
+actions!(
+    cheat_sheet,
+    [
+        Toggle,
+        ToggleTrainingMode,
+        ShowCommandInsights,
+        ClearCommandUsage
+    ]
+);
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(CheatSheet::register).detach();
+    cx.observe_new_views(training::register).detach();
+    cx.observe_new_views(insights::register).detach();
+    training::init(cx);
+    insights::init(cx);
+}
+
+/// The key bindings of the actions in a namespace, such as the editor's.
+struct Section {
+    title: SharedString,
+    bindings: Vec<(SharedString, gpui::KeyBinding)>,
+}
+
+pub struct CheatSheet {
+    sections: Vec<Section>,
+    focus_handle: FocusHandle,
+}
+
+impl ModalView for CheatSheet {}
+
+impl EventEmitter<DismissEvent> for CheatSheet {}
+
+impl FocusableView for CheatSheet {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl CheatSheet {
+    fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+        workspace.register_action(|workspace, _: &Toggle, cx| {
+            // The bindings are those of where the focus is before the cheat sheet takes it.
+            let sections = sections(cx);
+            workspace.toggle_modal(cx, |cx| CheatSheet {
+                sections,
+                focus_handle: cx.focus_handle(),
+            });
+        });
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, cx: &mut ViewContext<Self>) {
+        cx.emit(DismissEvent);
+    }
+}
+
+impl Render for CheatSheet {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex()
+            .id("cheat-sheet")
+            .key_context("CheatSheet")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::cancel))
+            .elevation_3(cx)
+            .w(rems(40.))
+            .max_h(rems(32.))
+            .overflow_y_scroll()
+            .p_3()
+            .gap_3()
+            .child(Headline::new("Key Bindings").size(HeadlineSize::Small))
+            .when(self.sections.is_empty(), |this| {
+                this.child(Label::new("No key bindings apply here").color(Color::Muted))
+            })
+            .children(self.sections.iter().map(|section| {
+                v_flex()
+                    .gap_1()
+                    .child(
+                        Label::new(section.title.clone())
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .children(section.bindings.iter().map(|(name, binding)| {
+                        h_flex()
+                            .justify_between()
+                            .child(Label::new(name.clone()))
+                            .child(KeyBinding::new(binding.clone()))
+                    }))
+            }))
+    }
+}
+
+/// The key binding that runs the action where the focus is. When several would, it's the one
+/// that takes precedence.
+pub(crate) fn focused_key_binding(
+    action: &dyn Action,
+    cx: &WindowContext,
+) -> Option<gpui::KeyBinding> {
+    let bindings = match cx.focused() {
+        Some(focus_handle) => cx.bindings_for_action_in(action, &focus_handle),
+        None => cx.bindings_for_action(action),
+    };
+    bindings.last().cloned()
+}
+
+/// The key bindings of the actions available where the focus is, in a section for each
+/// namespace. The sections of what's nearest the focus, like the editor rather than the workspace
+/// around it, come first.
+fn sections(cx: &WindowContext) -> Vec<Section> {
+    let context_stack = cx.context_stack();
+    let mut namespaces = BTreeMap::<String, Vec<(SharedString, gpui::KeyBinding)>>::default();
+    for action in cx.available_actions() {
+        let Some(binding) = focused_key_binding(action.as_ref(), cx) else {
+            continue;
+        };
+        let (namespace, name) = action
+            .name()
+            .split_once("::")
+            .unwrap_or(("", action.name()));
+        namespaces.entry(namespace.to_string()).or_default().push((
+            humanize_action_name(name).trim().to_string().into(),
+            binding,
+        ));
+    }
+
+    namespaces
+        .into_iter()
+        .sorted_by_key(|(namespace, _)| Reverse(focus_depth(namespace, &context_stack)))
+        .map(|(namespace, mut bindings)| {
+            bindings.sort_by(|(a, _), (b, _)| a.cmp(b));
+            Section {
+                title: humanize_action_name(&namespace).into(),
+                bindings,
+            }
+        })
+        .collect()
+}
+
+/// How deep the key context that's named after the namespace is in the focus, as "ProjectPanel"
+/// is for the "project_panel" namespace, if it's there at all.
+fn focus_depth(namespace: &str, context_stack: &[KeyContext]) -> Option<usize> {
+    let context = namespace
+        .split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect::<String>();
+    context_stack
+        .iter()
+        .rposition(|key_context| key_context.contains(&context))
+}
@@ -0,0 +1,456 @@
+//! Command insights, which count how often each action is run from the command palette and with
+//! its key binding, to suggest the key bindings worth learning and point out the ones that go
+//! unused. Counting is off unless it's enabled in the settings, and the counts are only ever kept
+//! in the local database.
+
+use collections::HashMap;
+use command_palette::humanize_action_name;
+use command_palette_hooks::CommandPaletteObserver;
+use db::kvp::KEY_VALUE_STORE;
+use gpui::{
+    Action, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Global, Render,
+    SharedString, Subscription, Task, ViewContext, WindowContext,
+};
+use itertools::Itertools;
+use serde_derive::{Deserialize, Serialize};
+use settings::{Settings, SettingsStore};
+use std::{rc::Rc, time::Duration};
+use ui::{prelude::*, Headline, HeadlineSize, KeyBinding};
+use util::ResultExt;
+use workspace::{notifications::NotificationId, ModalView, Toast, Workspace};
+
+use crate::{focused_key_binding, insights_settings::CommandInsightsSettings};
+use crate::{ClearCommandUsage, ShowCommandInsights};
+
+/// The key under which the counts are stored in the database.
+const COMMAND_USAGE_KEY: &str = "command_usage";
+
+/// How long to wait after an action is counted before saving the counts, so that they aren't
+/// written on every keystroke.
+const SAVE_DEBOUNCE: Duration = Duration::from_secs(10);
+
+/// How many times an action is run from the command palette before its key binding is suggested.
+const MIN_PALETTE_RUNS: usize = 3;
+
+/// How many actions are listed in each section of the insights.
+const MAX_LISTED_ACTIONS: usize = 10;
+
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct ActionUsage {
+    /// How many times the action was run from the command palette.
+    palette: usize,
+    /// How many times the action was run with a key binding.
+    keystroke: usize,
+}
+
+enum Invocation {
+    Palette,
+    Keystroke,
+}
+
+#[derive(Default)]
+struct CommandUsage {
+    /// How many times each action was run, by the action's name.
+    counts: HashMap<String, ActionUsage>,
+    /// The observer of keystrokes, while the usage of commands is being counted.
+    subscription: Option<Subscription>,
+    save_task: Option<Task<()>>,
+}
+
+impl Global for CommandUsage {}
+
+pub(crate) fn init(cx: &mut AppContext) {
+    CommandInsightsSettings::register(cx);
+    let counts = KEY_VALUE_STORE
+        .read_kvp(COMMAND_USAGE_KEY)
+        .log_err()
+        .flatten()
+        .and_then(|counts| serde_json::from_str(&counts).log_err())
+        .unwrap_or_default();
+    cx.set_global(CommandUsage {
+        counts,
+        ..Default::default()
+    });
+    update_counting(cx);
+    cx.observe_global::<SettingsStore>(update_counting).detach();
+}
+
+pub(crate) fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+    workspace.register_action(|workspace, _: &ShowCommandInsights, cx| {
+        let insights = CommandInsights::new(cx);
+        workspace.toggle_modal(cx, |_| insights);
+    });
+    workspace.register_action(|workspace, _: &ClearCommandUsage, cx| {
+        clear(cx);
+        workspace.show_toast(
+            Toast::new(
+                NotificationId::unique::<CommandUsage>(),
+                "Cleared the command usage counts.",
+            ),
+            cx,
+        );
+    });
+}
+
+/// Starts or stops counting the usage of commands, according to the settings.
+fn update_counting(cx: &mut AppContext) {
+    let enabled = CommandInsightsSettings::get_global(cx).enabled;
+    if enabled == cx.global::<CommandUsage>().subscription.is_some() {
+        return;
+    }
+
+    if enabled {
+        let subscription = cx.observe_keystrokes(|event, cx| {
+            if let Some(action) = &event.action {
+                count(action.as_ref(), Invocation::Keystroke, cx);
+            }
+        });
+        cx.global_mut::<CommandUsage>().subscription = Some(subscription);
+        CommandPaletteObserver::update_global(cx, |observer, _| {
+            observer.set(Rc::new(|action, cx| count(action, Invocation::Palette, cx)))
+        });
+    } else {
+        cx.global_mut::<CommandUsage>().subscription = None;
+        CommandPaletteObserver::update_global(cx, |observer, _| observer.clear());
+    }
+}
+
+fn count(action: &dyn Action, invocation: Invocation, cx: &mut AppContext) {
+    // Menus and pickers are driven with these actions, so they'd only crowd out the others.
+    if action.name().starts_with("menu::") {
+        return;
+    }
+    let usage = cx.global_mut::<CommandUsage>();
+    let counts = usage.counts.entry(action.name().to_string()).or_default();
+    match invocation {
+        Invocation::Palette => counts.palette += 1,
+        Invocation::Keystroke => counts.keystroke += 1,
+    }
+
+    if usage.save_task.is_none() {
+        let save_task = cx.spawn(|cx| async move {
+            cx.background_executor().timer(SAVE_DEBOUNCE).await;
+            let Ok(Some(counts)) = cx.update(|cx| {
+                let usage = cx.global_mut::<CommandUsage>();
+                usage.save_task = None;
+                serde_json::to_string(&usage.counts).log_err()
+            }) else {
+                return;
+            };
+            KEY_VALUE_STORE
+                .write_kvp(COMMAND_USAGE_KEY.to_string(), counts)
+                .await
+                .log_err();
+        });
+        cx.global_mut::<CommandUsage>().save_task = Some(save_task);
+    }
+}
+
+fn clear(cx: &mut AppContext) {
+    let usage = cx.global_mut::<CommandUsage>();
+    usage.counts.clear();
+    usage.save_task = None;
+    db::write_and_log(cx, || {
+        KEY_VALUE_STORE.delete_kvp(COMMAND_USAGE_KEY.to_string())
+    });
+}
+
+/// The actions that are run from the command palette often, and more often than with a key
+/// binding, with how many times they were. The most run come first.
+fn frequent_palette_actions(counts: &HashMap<String, ActionUsage>) -> Vec<(&str, usize)> {
+    counts
+        .iter()
+        .filter(|(_, usage)| usage.palette >= MIN_PALETTE_RUNS && usage.palette > usage.keystroke)
+        .map(|(name, usage)| (name.as_str(), usage.palette))
+        .sorted_by(|(a_name, a_runs), (b_name, b_runs)| {
+            b_runs.cmp(a_runs).then_with(|| a_name.cmp(b_name))
+        })
+        .collect()
+}
+
+/// An action that's run from the command palette often, with the key binding that would run it
+/// where the focus is, if it has one.
+struct FrequentAction {
+    name: SharedString,
+    palette_runs: usize,
+    binding: Option<gpui::KeyBinding>,
+}
+
+pub struct CommandInsights {
+    /// Whether the usage of commands is being counted.
+    enabled: bool,
+    frequent: Vec<FrequentAction>,
+    /// The actions that have key bindings where the focus is, but were never run with them.
+    unused: Vec<(SharedString, gpui::KeyBinding)>,
+    focus_handle: FocusHandle,
+}
+
+impl ModalView for CommandInsights {}
+
+impl EventEmitter<DismissEvent> for CommandInsights {}
+
+impl FocusableView for CommandInsights {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl CommandInsights {
+    /// The insights into the usage of commands, with the key bindings of where the focus is
+    /// before the insights take it.
+    fn new(cx: &mut WindowContext) -> Self {
+        let counts = &cx.global::<CommandUsage>().counts;
+        let frequent = frequent_palette_actions(counts)
+            .into_iter()
+            .take(MAX_LISTED_ACTIONS)
+            .map(|(name, palette_runs)| FrequentAction {
+                name: humanize_action_name(name).into(),
+                palette_runs,
+                binding: cx
+                    .build_action(name, None)
+                    .ok()
+                    .and_then(|action| focused_key_binding(action.as_ref(), cx)),
+            })
+            .collect();
+
+        // Until something is counted, every binding would be listed as unused.
+        let unused = if counts.is_empty() {
+            Vec::new()
+        } else {
+            cx.available_actions()
+                .into_iter()
+                .filter(|action| {
+                    !action.name().starts_with("menu::")
+                        && !counts
+                            .get(action.name())
+                            .is_some_and(|usage| usage.keystroke > 0)
+                })
+                .filter_map(|action| {
+                    let binding = focused_key_binding(action.as_ref(), cx)?;
+                    Some((
+                        SharedString::from(humanize_action_name(action.name())),
+                        binding,
+                    ))
+                })
+                .sorted_by(|(a, _), (b, _)| a.cmp(b))
+                .take(MAX_LISTED_ACTIONS)
+                .collect()
+        };
+
+        Self {
+            enabled: CommandInsightsSettings::get_global(cx).enabled,
+            frequent,
+            unused,
+            focus_handle: cx.focus_handle(),
+        }
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, cx: &mut ViewContext<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn clear(&mut self, cx: &mut ViewContext<Self>) {
+        clear(cx);
+        self.frequent.clear();
+        self.unused.clear();
+        cx.notify();
+    }
+}
+
+impl Render for CommandInsights {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let section_title =
+            |title: &'static str| Label::new(title).size(LabelSize::Small).color(Color::Muted);
+
+        v_flex()
+            .id("command-insights")
+            .key_context("CommandInsights")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::cancel))
+            .elevation_3(cx)
+            .w(rems(40.))
+            .max_h(rems(32.))
+            .overflow_y_scroll()
+            .p_3()
+            .gap_3()
+            .child(
+                h_flex()
+                    .justify_between()
+                    .child(Headline::new("Command Insights").size(HeadlineSize::Small))
+                    .child(
+                        Button::new("clear-command-usage", "Clear")
+                            .style(ButtonStyle::Subtle)
+                            .color(Color::Muted)
+                            .on_click(cx.listener(|this, _, cx| this.clear(cx))),
+                    ),
+            )
+            .when(!self.enabled, |this| {
+                this.child(
+                    Label::new(
+                        "Command usage isn't being counted. Enable \"command_insights\" in your \
+                         settings to count it on this machine.",
+                    )
+                    .color(Color::Muted),
+                )
+            })
+            .when(
+                self.enabled && self.frequent.is_empty() && self.unused.is_empty(),
+                |this| {
+                    this.child(
+                        Label::new("Nothing to suggest yet. Keep working and check back later.")
+                            .color(Color::Muted),
+                    )
+                },
+            )
+            .when(!self.frequent.is_empty(), |this| {
+                this.child(
+                    v_flex()
+                        .gap_1()
+                        .child(section_title("Often Run from the Command Palette"))
+                        .children(self.frequent.iter().map(|action| {
+                            h_flex()
+                                .justify_between()
+                                .child(Label::new(format!(
+                                    "{} ({} runs)",
+                                    action.name, action.palette_runs
+                                )))
+                                .child(match &action.binding {
+                                    Some(binding) => {
+                                        KeyBinding::new(binding.clone()).into_any_element()
+                                    }
+                                    None => Label::new("Worth binding to a key")
+                                        .color(Color::Muted)
+                                        .into_any_element(),
+                                })
+                        })),
+                )
+            })
+            .when(!self.unused.is_empty(), |this| {
+                this.child(
+                    v_flex()
+                        .gap_1()
+                        .child(section_title("Key Bindings Never Used Here"))
+                        .children(self.unused.iter().map(|(name, binding)| {
+                            h_flex()
+                                .justify_between()
+                                .child(Label::new(name.clone()))
+                                .child(KeyBinding::new(binding.clone()))
+                        })),
+                )
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{actions, KeyBinding, TestAppContext, UpdateGlobal, VisualTestContext};
+    use project::{FakeFs, Project};
+
+    actions!(test, [Bound, Unbound]);
+
+    fn usage(action: &str, cx: &mut VisualTestContext) -> Option<ActionUsage> {
+        cx.update(|cx| cx.global::<CommandUsage>().counts.get(action).copied())
+    }
+
+    #[test]
+    fn test_frequent_palette_actions() {
+        let counts = HashMap::from_iter([
+            (
+                "a::Rare".to_string(),
+                ActionUsage {
+                    palette: 2,
+                    keystroke: 0,
+                },
+            ),
+            (
+                "a::Learned".to_string(),
+                ActionUsage {
+                    palette: 5,
+                    keystroke: 9,
+                },
+            ),
+            (
+                "a::Often".to_string(),
+                ActionUsage {
+                    palette: 4,
+                    keystroke: 1,
+                },
+            ),
+            (
+                "a::Oftener".to_string(),
+                ActionUsage {
+                    palette: 8,
+                    keystroke: 0,
+                },
+            ),
+        ]);
+        assert_eq!(
+            frequent_palette_actions(&counts),
+            [("a::Oftener", 8), ("a::Often", 4)]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_command_usage(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            workspace::init_settings(cx);
+            Project::init_settings(cx);
+            command_palette_hooks::init(cx);
+            crate::init(cx);
+            cx.on_action(|_: &Bound, _| {});
+            cx.on_action(|_: &Unbound, _| {});
+            cx.bind_keys([KeyBinding::new("ctrl-b", Bound, None)]);
+        });
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (_workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+
+        // nothing is counted until it's enabled
+        cx.simulate_keystrokes("ctrl-b");
+        cx.update(|cx| CommandPaletteObserver::observe(&Unbound, cx));
+        assert_eq!(usage("test::Bound", cx), None);
+        assert_eq!(usage("test::Unbound", cx), None);
+
+        cx.update(|cx| {
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings::<CommandInsightsSettings>(cx, |settings| {
+                    settings.enabled = true
+                });
+            })
+        });
+        cx.simulate_keystrokes("ctrl-b ctrl-b");
+        cx.update(|cx| CommandPaletteObserver::observe(&Bound, cx));
+        cx.update(|cx| CommandPaletteObserver::observe(&Unbound, cx));
+        assert_eq!(
+            usage("test::Bound", cx),
+            Some(ActionUsage {
+                palette: 1,
+                keystroke: 2
+            })
+        );
+        assert_eq!(
+            usage("test::Unbound", cx),
+            Some(ActionUsage {
+                palette: 1,
+                keystroke: 0
+            })
+        );
+
+        cx.update(|cx| clear(cx));
+        assert_eq!(usage("test::Bound", cx), None);
+
+        cx.update(|cx| {
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings::<CommandInsightsSettings>(cx, |settings| {
+                    settings.enabled = false
+                });
+            })
+        });
+        cx.simulate_keystrokes("ctrl-b");
+        assert_eq!(usage("test::Bound", cx), None);
+    }
+}
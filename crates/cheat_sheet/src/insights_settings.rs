@@ -0,0 +1,25 @@
+use anyhow::Result;
+use gpui::AppContext;
+use schemars::JsonSchema;
+use serde_derive::{Deserialize, Serialize};
+use settings::{Settings, SettingsSources};
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, Debug)]
+#[serde(default)]
+pub struct CommandInsightsSettings {
+    /// Whether to count how often each command is run from the command palette and with its key
+    /// binding, for the command insights. The counts are only ever stored on this machine.
+    ///
+    /// Default: false
+    pub enabled: bool,
+}
+
+impl Settings for CommandInsightsSettings {
+    const KEY: Option<&'static str> = Some("command_insights");
+
+    type FileContent = Self;
+
+    fn load(sources: SettingsSources<Self::FileContent>, _: &mut AppContext) -> Result<Self> {
+        sources.json_merge()
+    }
+}
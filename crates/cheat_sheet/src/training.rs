@@ -0,0 +1,154 @@
+//! Training mode, in which running an action that has a key binding some other way, as by
+//! clicking a button or picking it from a menu, shows a toast suggesting the key binding.
+
+use collections::HashMap;
+use command_palette::humanize_action_name;
+use gpui::{Action, AppContext, Global, Subscription, ViewContext, WindowContext};
+use itertools::Itertools;
+use std::time::Duration;
+use workspace::{notifications::NotificationId, Toast, Workspace};
+
+use crate::{focused_key_binding, ToggleTrainingMode};
+
+/// How many times the key binding of an action is suggested, so that it isn't every time the
+/// action is run.
+const MAX_SUGGESTIONS_PER_ACTION: usize = 3;
+
+/// How long a suggestion is shown for.
+const SUGGESTION_DURATION: Duration = Duration::from_secs(5);
+
+#[derive(Default)]
+struct TrainingMode {
+    /// The observer of dispatched actions, while training mode is on.
+    subscription: Option<Subscription>,
+    /// How many times the key binding of each action has been suggested.
+    suggestions: HashMap<String, usize>,
+}
+
+impl Global for TrainingMode {}
+
+/// The toast that suggests a key binding.
+enum KeyBindingSuggestion {}
+
+pub(crate) fn init(cx: &mut AppContext) {
+    cx.set_global(TrainingMode::default());
+}
+
+pub(crate) fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+    workspace.register_action(|workspace, _: &ToggleTrainingMode, cx| {
+        let message = if toggle(cx) {
+            "Training mode is on. Key bindings will be suggested for commands run without them."
+        } else {
+            "Training mode is off."
+        };
+        workspace.show_toast(
+            Toast::new(NotificationId::unique::<TrainingMode>(), message),
+            cx,
+        );
+    });
+}
+
+/// Turns training mode on or off, returning whether it's on.
+fn toggle(cx: &mut AppContext) -> bool {
+    let enabled = cx.global::<TrainingMode>().subscription.is_none();
+    let subscription = enabled.then(|| cx.observe_dispatched_actions(suggest_key_binding));
+    cx.global_mut::<TrainingMode>().subscription = subscription;
+    enabled
+}
+
+fn suggest_key_binding(action: &dyn Action, cx: &mut WindowContext) {
+    // Menus and pickers are driven with these actions whether or not keys are used.
+    if action.name().starts_with("menu::") {
+        return;
+    }
+    let Some(binding) = focused_key_binding(action, cx) else {
+        return;
+    };
+    let suggestions = cx
+        .global_mut::<TrainingMode>()
+        .suggestions
+        .entry(action.name().to_string())
+        .or_default();
+    if *suggestions >= MAX_SUGGESTIONS_PER_ACTION {
+        return;
+    }
+    *suggestions += 1;
+
+    let Some(workspace) = cx.window_handle().downcast::<Workspace>() else {
+        return;
+    };
+    let message = format!(
+        "Tip: {} runs \"{}\"",
+        binding.keystrokes().iter().join(" "),
+        humanize_action_name(action.name())
+    );
+    let id = NotificationId::unique::<KeyBindingSuggestion>();
+    workspace
+        .update(cx, |workspace, cx| {
+            workspace.show_toast(Toast::new(id.clone(), message), cx);
+            cx.spawn(|workspace, mut cx| async move {
+                cx.background_executor().timer(SUGGESTION_DURATION).await;
+                workspace
+                    .update(&mut cx, |workspace, cx| workspace.dismiss_toast(&id, cx))
+                    .ok();
+            })
+            .detach();
+        })
+        .ok();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{actions, KeyBinding, TestAppContext, VisualTestContext};
+    use project::{FakeFs, Project};
+    use settings::SettingsStore;
+
+    actions!(test, [Suggested, Unbound]);
+
+    fn suggestions(action: &str, cx: &mut VisualTestContext) -> Option<usize> {
+        cx.update(|cx| cx.global::<TrainingMode>().suggestions.get(action).copied())
+    }
+
+    #[gpui::test]
+    async fn test_training_mode(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            workspace::init_settings(cx);
+            Project::init_settings(cx);
+            crate::init(cx);
+            cx.on_action(|_: &Suggested, _| {});
+            cx.on_action(|_: &Unbound, _| {});
+            cx.bind_keys([KeyBinding::new("ctrl-t", Suggested, None)]);
+        });
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (_workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+
+        // nothing is suggested until training mode is on
+        cx.dispatch_action(Suggested);
+        assert_eq!(suggestions("test::Suggested", cx), None);
+
+        cx.update(|cx| toggle(cx));
+        for _ in 0..MAX_SUGGESTIONS_PER_ACTION + 2 {
+            cx.dispatch_action(Suggested);
+        }
+        cx.dispatch_action(Unbound);
+        assert_eq!(
+            suggestions("test::Suggested", cx),
+            Some(MAX_SUGGESTIONS_PER_ACTION)
+        );
+        assert_eq!(suggestions("test::Unbound", cx), None);
+
+        // actions run with their key binding aren't suggested
+        cx.update(|cx| cx.global_mut::<TrainingMode>().suggestions.clear());
+        cx.simulate_keystrokes("ctrl-t");
+        assert_eq!(suggestions("test::Suggested", cx), None);
+
+        cx.update(|cx| toggle(cx));
+        cx.dispatch_action(Suggested);
+        assert_eq!(suggestions("test::Suggested", cx), None);
+    }
+}
@@ -28,3 +28,6 @@ pub enum CliResponse {
 /// When Zed started not as an *.app but as a binary (e.g. local development),
 /// there's a possibility to tell it to behave "regularly".
 pub const FORCE_CLI_MODE_ENV_VAR_NAME: &str = "ZED_FORCE_CLI_MODE";
+
+/// Set by the CLI when it launches Zed with `--software-rendering`.
+pub const SOFTWARE_RENDERING_ENV_VAR_NAME: &str = "ZED_SOFTWARE_RENDERING";
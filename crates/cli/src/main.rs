@@ -17,6 +17,7 @@ trait InstalledApp {
     fn zed_version_string(&self) -> String;
     fn launch(&self, ipc_url: String) -> anyhow::Result<()>;
     fn run_foreground(&self, ipc_url: String) -> io::Result<ExitStatus>;
+    fn run_headless(&self, command: Vec<String>) -> io::Result<ExitStatus>;
 }
 
 #[derive(Parser, Debug)]
@@ -49,6 +50,14 @@ struct Args {
     /// Run zed in dev-server mode
     #[arg(long)]
     dev_server_token: Option<String>,
+    /// Render with a software rasterizer instead of the GPU, when Zed isn't running yet, for
+    /// machines whose GPU drivers are broken. Only supported on Linux.
+    #[arg(long)]
+    software_rendering: bool,
+    /// Run a command without opening any windows, and exit with its status.
+    /// For example `--headless run-script <SCRIPT> [PATHS]...`
+    #[arg(long, num_args = 1.., value_name = "COMMAND")]
+    headless: Option<Vec<String>>,
 }
 
 fn parse_path_with_position(
@@ -79,6 +88,15 @@ fn main() -> Result<()> {
         return Ok(());
     }
 
+    if args.software_rendering {
+        env::set_var(cli::SOFTWARE_RENDERING_ENV_VAR_NAME, "");
+    }
+
+    if let Some(command) = args.headless {
+        let status = app.run_headless(command)?;
+        std::process::exit(status.code().unwrap_or(1));
+    }
+
     let curdir = env::current_dir()?;
     let mut paths = vec![];
     for path in args.paths_with_position {
@@ -230,6 +248,13 @@ mod linux {
                 .arg(ipc_url)
                 .status()
         }
+
+        fn run_headless(&self, command: Vec<String>) -> io::Result<ExitStatus> {
+            std::process::Command::new(self.0.clone())
+                .arg("--headless")
+                .args(command)
+                .status()
+        }
     }
 
     impl App {
@@ -294,6 +319,9 @@ mod windows {
         fn run_foreground(&self, _ipc_url: String) -> io::Result<ExitStatus> {
             unimplemented!()
         }
+        fn run_headless(&self, _command: Vec<String>) -> io::Result<ExitStatus> {
+            unimplemented!()
+        }
     }
 
     impl Detect {
@@ -471,12 +499,16 @@ mod mac_os {
         }
 
         fn run_foreground(&self, ipc_url: String) -> io::Result<ExitStatus> {
-            let path = match self {
-                Bundle::App { app_bundle, .. } => app_bundle.join("Contents/MacOS/zed"),
-                Bundle::LocalPath { executable, .. } => executable.clone(),
-            };
+            std::process::Command::new(self.executable())
+                .arg(ipc_url)
+                .status()
+        }
 
-            std::process::Command::new(path).arg(ipc_url).status()
+        fn run_headless(&self, command: Vec<String>) -> io::Result<ExitStatus> {
+            std::process::Command::new(self.executable())
+                .arg("--headless")
+                .args(command)
+                .status()
         }
     }
 
@@ -494,6 +526,13 @@ mod mac_os {
                 Self::LocalPath { executable, .. } => executable,
             }
         }
+
+        fn executable(&self) -> PathBuf {
+            match self {
+                Self::App { app_bundle, .. } => app_bundle.join("Contents/MacOS/zed"),
+                Self::LocalPath { executable, .. } => executable.clone(),
+            }
+        }
     }
 
     pub(super) fn spawn_channel_cli(
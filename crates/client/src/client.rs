@@ -1,6 +1,7 @@
 #[cfg(any(test, feature = "test-support"))]
 pub mod test;
 
+mod proxy;
 pub mod telemetry;
 pub mod user;
 
@@ -45,6 +46,7 @@ use std::{
     time::{Duration, Instant},
 };
 use telemetry::Telemetry;
+use telemetry_events::EventCategory;
 use thiserror::Error;
 use url::Url;
 use util::{ResultExt, TryFutureExt};
@@ -92,11 +94,15 @@ actions!(client, [SignIn, SignOut, Reconnect]);
 #[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct ClientSettingsContent {
     server_url: Option<String>,
+    pub offline: Option<bool>,
 }
 
 #[derive(Deserialize)]
 pub struct ClientSettings {
     pub server_url: String,
+    /// Whether Zed stays off the network: it doesn't connect to the collaboration server, check
+    /// for updates, query the extension registry, or download language servers.
+    pub offline: bool,
 }
 
 impl Settings for ClientSettings {
@@ -116,11 +122,16 @@ impl Settings for ClientSettings {
 #[derive(Default, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ProxySettingsContent {
     proxy: Option<String>,
+    ca_certificates: Option<PathBuf>,
 }
 
 #[derive(Deserialize, Default)]
 pub struct ProxySettings {
     pub proxy: Option<String>,
+    /// A file of PEM certificates of the certificate authorities to trust, such as those of a
+    /// corporate network that intercepts TLS. HTTP requests trust them instead of the system's,
+    /// while the collaboration server's connection trusts them in addition to the system's.
+    pub ca_certificates: Option<PathBuf>,
 }
 
 impl Settings for ProxySettings {
@@ -134,6 +145,10 @@ impl Settings for ProxySettings {
                 .user
                 .and_then(|value| value.proxy.clone())
                 .or(sources.default.proxy.clone()),
+            ca_certificates: sources
+                .user
+                .and_then(|value| value.ca_certificates.clone())
+                .or(sources.default.ca_certificates.clone()),
         })
     }
 }
@@ -454,10 +469,19 @@ impl<T: 'static> Drop for PendingEntitySubscription<T> {
     }
 }
 
-#[derive(Copy, Clone)]
+#[derive(Clone)]
 pub struct TelemetrySettings {
     pub diagnostics: bool,
     pub metrics: bool,
+    pub disabled_categories: Vec<EventCategory>,
+}
+
+impl TelemetrySettings {
+    /// Whether events of the category are sent, which they are unless metrics are off or the
+    /// category was opted out of.
+    pub fn sends(&self, category: EventCategory) -> bool {
+        self.metrics && !self.disabled_categories.contains(&category)
+    }
 }
 
 /// Control what info is collected by Zed.
@@ -471,6 +495,10 @@ pub struct TelemetrySettingsContent {
     ///
     /// Default: true
     pub metrics: Option<bool>,
+    /// Kinds of usage data not to send, even though metrics are sent.
+    ///
+    /// Default: []
+    pub disabled_categories: Option<Vec<EventCategory>>,
 }
 
 impl settings::Settings for TelemetrySettings {
@@ -491,6 +519,12 @@ impl settings::Settings for TelemetrySettings {
                 .as_ref()
                 .and_then(|v| v.metrics)
                 .unwrap_or(sources.default.metrics.ok_or_else(Self::missing_default)?),
+            disabled_categories: sources
+                .user
+                .as_ref()
+                .and_then(|v| v.disabled_categories.clone())
+                .or_else(|| sources.default.disabled_categories.clone())
+                .unwrap_or_default(),
         })
     }
 }
@@ -533,10 +567,14 @@ impl Client {
 
     pub fn production(cx: &mut AppContext) -> Arc<Self> {
         let clock = Arc::new(clock::RealSystemClock);
+        let proxy_settings = ProxySettings::get_global(cx);
+        let client_settings = ClientSettings::get_global(cx);
         let http = Arc::new(HttpClientWithUrl::new(
-            &ClientSettings::get_global(cx).server_url,
-            ProxySettings::get_global(cx).proxy.clone(),
+            &client_settings.server_url,
+            proxy_settings.proxy.clone(),
+            proxy_settings.ca_certificates.clone(),
         ));
+        http.set_offline(client_settings.offline);
         Self::new(clock, http.clone(), cx)
     }
 
@@ -856,6 +894,10 @@ impl Client {
         try_provider: bool,
         cx: &AsyncAppContext,
     ) -> anyhow::Result<()> {
+        if self.http.is_offline() {
+            return Err(anyhow!("not connecting in offline mode"));
+        }
+
         let was_disconnected = match *self.status().borrow() {
             Status::SignedOut => true,
             Status::ConnectionError
@@ -1138,12 +1180,13 @@ impl Client {
 
         let http = self.http.clone();
         cx.background_executor().spawn(async move {
-            let mut rpc_url = Self::get_rpc_url(http, release_channel).await?;
-            let rpc_host = rpc_url
+            let mut rpc_url = Self::get_rpc_url(http.clone(), release_channel).await?;
+            let (rpc_host, rpc_port) = rpc_url
                 .host_str()
                 .zip(rpc_url.port_or_known_default())
                 .ok_or_else(|| anyhow!("missing host in rpc url"))?;
-            let stream = smol::net::TcpStream::connect(rpc_host).await?;
+            // The connection goes through the same proxy as HTTP requests.
+            let stream = proxy::connect(http.proxy(), rpc_host, rpc_port).await?;
 
             log::info!("connected to rpc endpoint {}", rpc_url);
 
@@ -1151,8 +1194,12 @@ impl Client {
                 "https" => {
                     rpc_url.set_scheme("wss").unwrap();
                     let request = request.uri(rpc_url.as_str()).body(())?;
+                    let connector = proxy::tls_connector(http.ca_certificates())?;
                     let (stream, _) =
-                        async_tungstenite::async_std::client_async_tls(request, stream).await?;
+                        async_tungstenite::async_std::client_async_tls_with_connector(
+                            request, stream, connector,
+                        )
+                        .await?;
                     Ok(Connection::new(
                         stream
                             .map_err(|error| anyhow!(error))
@@ -0,0 +1,242 @@
+//! Connecting to the collaboration server through the proxy that's set for HTTP requests, which
+//! the websocket connection doesn't go through otherwise, and with the CA certificates they trust.
+
+use anyhow::{anyhow, bail, Context as _, Result};
+use smol::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+};
+use std::path::Path;
+use url::Url;
+
+/// The longest response to a `CONNECT` request that's read, to not read forever from a server
+/// that isn't a proxy.
+const MAX_CONNECT_RESPONSE_LEN: usize = 16 * 1024;
+
+/// Opens a connection to the host, through the proxy if there is one.
+pub(crate) async fn connect(proxy: Option<&str>, host: &str, port: u16) -> Result<TcpStream> {
+    let Some(proxy) = proxy else {
+        return Ok(TcpStream::connect((host, port)).await?);
+    };
+    let proxy = if proxy.contains("://") {
+        Url::parse(proxy)
+    } else {
+        Url::parse(&format!("http://{proxy}"))
+    }
+    .with_context(|| format!("invalid proxy {proxy:?}"))?;
+    let proxy_host = proxy
+        .host_str()
+        .ok_or_else(|| anyhow!("missing host in proxy {proxy}"))?;
+    let proxy_port = proxy
+        .port_or_known_default()
+        .or_else(|| proxy.scheme().starts_with("socks").then_some(1080))
+        .ok_or_else(|| anyhow!("missing port in proxy {proxy}"))?;
+    let stream = TcpStream::connect((proxy_host, proxy_port))
+        .await
+        .with_context(|| format!("failed to connect to proxy {proxy_host}:{proxy_port}"))?;
+
+    match proxy.scheme() {
+        "http" => http_connect(stream, &proxy, host, port).await,
+        "socks5" | "socks5h" => socks5_connect(stream, &proxy, host, port).await,
+        scheme => bail!("{scheme} proxies aren't supported for collaboration"),
+    }
+}
+
+/// Asks an HTTP proxy to open a tunnel to the host, with a `CONNECT` request.
+async fn http_connect(
+    mut stream: TcpStream,
+    proxy: &Url,
+    host: &str,
+    port: u16,
+) -> Result<TcpStream> {
+    let mut request = format!("CONNECT {host}:{port} HTTP/1.1\r\nHost: {host}:{port}\r\n");
+    if !proxy.username().is_empty() {
+        let credentials = base64::encode(
+            [
+                percent_decode(proxy.username()),
+                percent_decode(proxy.password().unwrap_or_default()),
+            ]
+            .join(&b':'),
+        );
+        request.push_str(&format!("Proxy-Authorization: Basic {credentials}\r\n"));
+    }
+    request.push_str("\r\n");
+    stream.write_all(request.as_bytes()).await?;
+
+    // The response is read a byte at a time, so that none of what the host sends after it
+    // through the tunnel is consumed.
+    let mut response = Vec::new();
+    let mut byte = [0];
+    while !response.ends_with(b"\r\n\r\n") {
+        if stream.read(&mut byte).await? == 0 {
+            bail!("proxy closed the connection");
+        }
+        response.push(byte[0]);
+        if response.len() > MAX_CONNECT_RESPONSE_LEN {
+            bail!("proxy response is too long");
+        }
+    }
+    let response = String::from_utf8_lossy(&response);
+    let status_line = response.lines().next().unwrap_or_default();
+    if status_line.split_whitespace().nth(1) != Some("200") {
+        bail!("proxy refused to connect to {host}: {status_line}");
+    }
+    Ok(stream)
+}
+
+/// Asks a SOCKS5 proxy to connect to the host, as in RFC 1928, authenticating with the username
+/// and password of the proxy's URL, as in RFC 1929, if it has them.
+async fn socks5_connect(
+    mut stream: TcpStream,
+    proxy: &Url,
+    host: &str,
+    port: u16,
+) -> Result<TcpStream> {
+    const VERSION: u8 = 5;
+    const NO_AUTHENTICATION: u8 = 0;
+    const PASSWORD_AUTHENTICATION: u8 = 2;
+
+    let username = percent_decode(proxy.username());
+    let password = percent_decode(proxy.password().unwrap_or_default());
+    if username.is_empty() {
+        stream.write_all(&[VERSION, 1, NO_AUTHENTICATION]).await?;
+    } else {
+        stream
+            .write_all(&[VERSION, 2, NO_AUTHENTICATION, PASSWORD_AUTHENTICATION])
+            .await?;
+    }
+    let mut reply = [0; 2];
+    stream.read_exact(&mut reply).await?;
+    if reply[0] != VERSION {
+        bail!("proxy isn't a SOCKS5 proxy");
+    }
+    match reply[1] {
+        NO_AUTHENTICATION => {}
+        PASSWORD_AUTHENTICATION if !username.is_empty() => {
+            let mut request = vec![1, username.len().try_into()?];
+            request.extend_from_slice(&username);
+            request.push(password.len().try_into()?);
+            request.extend_from_slice(&password);
+            stream.write_all(&request).await?;
+            stream.read_exact(&mut reply).await?;
+            if reply[1] != 0 {
+                bail!("proxy rejected the username and password");
+            }
+        }
+        _ => bail!("proxy requires an unsupported authentication method"),
+    }
+
+    // Connect to the host by name, so that the proxy resolves it.
+    let mut request = vec![VERSION, 1, 0, 3, host.len().try_into()?];
+    request.extend_from_slice(host.as_bytes());
+    request.extend_from_slice(&port.to_be_bytes());
+    stream.write_all(&request).await?;
+    let mut reply = [0; 4];
+    stream.read_exact(&mut reply).await?;
+    if reply[1] != 0 {
+        bail!("proxy failed to connect to {host}, with error {}", reply[1]);
+    }
+    // The reply ends with the address the proxy bound, which isn't needed.
+    let address_len = match reply[3] {
+        1 => 4,
+        4 => 16,
+        3 => {
+            let mut len = [0];
+            stream.read_exact(&mut len).await?;
+            len[0] as usize
+        }
+        address_type => bail!("proxy replied with an unknown address type {address_type}"),
+    };
+    let mut address = vec![0; address_len + 2];
+    stream.read_exact(&mut address).await?;
+    Ok(stream)
+}
+
+/// Decodes the `%` escapes of a URL's username or password, which the URL keeps encoded.
+fn percent_decode(text: &str) -> Vec<u8> {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut ix = 0;
+    while ix < bytes.len() {
+        let escaped = bytes
+            .get(ix + 1..ix + 3)
+            .filter(|hex| bytes[ix] == b'%' && hex.iter().all(u8::is_ascii_hexdigit))
+            .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                ix += 3;
+            }
+            None => {
+                decoded.push(bytes[ix]);
+                ix += 1;
+            }
+        }
+    }
+    decoded
+}
+
+/// A TLS connector that trusts the CA certificates in the file, which holds one or more PEM
+/// certificates, as well as the system's, or `None` to only trust the system's.
+pub(crate) fn tls_connector(
+    ca_certificates: Option<&Path>,
+) -> Result<Option<async_native_tls::TlsConnector>> {
+    let Some(path) = ca_certificates else {
+        return Ok(None);
+    };
+    let pem = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read CA certificates from {path:?}"))?;
+    let mut connector = async_native_tls::TlsConnector::new();
+    for certificate in pem
+        .split_inclusive("-----END CERTIFICATE-----")
+        .filter(|certificate| certificate.contains("-----BEGIN CERTIFICATE-----"))
+    {
+        let certificate = async_native_tls::Certificate::from_pem(certificate.as_bytes())
+            .with_context(|| format!("invalid CA certificate in {path:?}"))?;
+        connector = connector.add_root_certificate(certificate);
+    }
+    Ok(Some(connector))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use smol::net::TcpListener;
+
+    #[test]
+    fn test_http_connect() {
+        smol::block_on(async {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let proxy = format!("http://us%20er:p%40ss@{}", listener.local_addr().unwrap());
+            let server = smol::spawn(async move {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut request = Vec::new();
+                let mut byte = [0];
+                while !request.ends_with(b"\r\n\r\n") {
+                    stream.read_exact(&mut byte).await.unwrap();
+                    request.push(byte[0]);
+                }
+                stream
+                    .write_all(b"HTTP/1.1 200 Connection established\r\n\r\nhello")
+                    .await
+                    .unwrap();
+                String::from_utf8(request).unwrap()
+            });
+
+            let mut stream = connect(Some(&proxy), "collab.example.com", 443)
+                .await
+                .unwrap();
+            let request = server.await;
+            assert!(request.starts_with("CONNECT collab.example.com:443 HTTP/1.1\r\n"));
+            assert!(request.contains(&format!(
+                "Proxy-Authorization: Basic {}\r\n",
+                base64::encode("us er:p@ss")
+            )));
+
+            // What's sent through the tunnel after the proxy's response is left to be read.
+            let mut tunneled = String::new();
+            stream.read_to_string(&mut tunneled).await.unwrap();
+            assert_eq!(tunneled, "hello");
+        });
+    }
+}
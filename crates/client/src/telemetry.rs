@@ -3,7 +3,7 @@ mod event_coalescer;
 use crate::{ChannelId, TelemetrySettings};
 use chrono::{DateTime, Utc};
 use clock::SystemClock;
-use futures::Future;
+use futures::{channel::mpsc, Future};
 use gpui::{AppContext, AppMetadata, BackgroundExecutor, Task};
 use http::{self, HttpClient, HttpClientWithUrl, Method};
 use once_cell::sync::Lazy;
@@ -16,8 +16,8 @@ use std::{env, mem, path::PathBuf, sync::Arc, time::Duration};
 use sysinfo::{CpuRefreshKind, Pid, ProcessRefreshKind, RefreshKind, System};
 use telemetry_events::{
     ActionEvent, AppEvent, AssistantEvent, AssistantKind, CallEvent, CpuEvent, EditEvent,
-    EditorEvent, Event, EventRequestBody, EventWrapper, ExtensionEvent, InlineCompletionEvent,
-    MemoryEvent, SettingEvent,
+    EditorEvent, Event, EventCategory, EventRequestBody, EventWrapper, ExtensionEvent,
+    InlineCompletionEvent, MemoryEvent, SettingEvent,
 };
 use tempfile::NamedTempFile;
 #[cfg(not(debug_assertions))]
@@ -48,6 +48,16 @@ struct TelemetryState {
     first_event_date_time: Option<DateTime<Utc>>,
     event_coalescer: EventCoalescer,
     max_queue_size: usize,
+    event_observers: Vec<mpsc::UnboundedSender<ReportedEvent>>,
+}
+
+/// An event as it was reported, for inspecting what telemetry is sent.
+#[derive(Clone, Debug)]
+pub struct ReportedEvent {
+    pub event: Event,
+    /// Whether the event is sent, rather than dropped because metrics are off or its category
+    /// was opted out of.
+    pub sent: bool,
 }
 
 #[cfg(debug_assertions)]
@@ -83,7 +93,7 @@ impl Telemetry {
         TelemetrySettings::register(cx);
 
         let state = Arc::new(Mutex::new(TelemetryState {
-            settings: *TelemetrySettings::get_global(cx),
+            settings: TelemetrySettings::get_global(cx).clone(),
             app_metadata: cx.app_metadata(),
             architecture: env::consts::ARCH,
             release_channel,
@@ -97,6 +107,7 @@ impl Telemetry {
             first_event_date_time: None,
             event_coalescer: EventCoalescer::new(clock.clone()),
             max_queue_size: MAX_QUEUE_LEN,
+            event_observers: Vec::new(),
         }));
 
         #[cfg(not(debug_assertions))]
@@ -118,7 +129,7 @@ impl Telemetry {
 
             move |cx| {
                 let mut state = state.lock();
-                state.settings = *TelemetrySettings::get_global(cx);
+                state.settings = TelemetrySettings::get_global(cx).clone();
             }
         })
         .detach();
@@ -360,10 +371,26 @@ impl Telemetry {
         self.report_event(event)
     }
 
+    /// Every event reported from now on, whether or not it's sent.
+    pub fn observe_events(self: &Arc<Self>) -> mpsc::UnboundedReceiver<ReportedEvent> {
+        let (tx, rx) = mpsc::unbounded();
+        self.state.lock().event_observers.push(tx);
+        rx
+    }
+
     fn report_event(self: &Arc<Self>, event: Event) {
         let mut state = self.state.lock();
 
-        if !state.settings.metrics {
+        let sent = state.settings.sends(event.category());
+        state.event_observers.retain(|observer| {
+            observer
+                .unbounded_send(ReportedEvent {
+                    event: event.clone(),
+                    sent,
+                })
+                .is_ok()
+        });
+        if !sent {
             return;
         }
 
@@ -636,6 +663,38 @@ mod tests {
         });
     }
 
+    #[gpui::test]
+    fn test_telemetry_disabled_categories(cx: &mut TestAppContext) {
+        init_test(cx);
+        let clock = Arc::new(FakeSystemClock::new(
+            Utc.with_ymd_and_hms(1990, 4, 12, 12, 0, 0).unwrap(),
+        ));
+        let http = FakeHttpClient::with_200_response();
+        let installation_id = Some("installation_id".to_string());
+        let session_id = "session_id".to_string();
+
+        cx.update(|cx| {
+            let telemetry = Telemetry::new(clock.clone(), http, cx);
+            telemetry.start(installation_id, session_id, cx);
+            telemetry.state.lock().settings.disabled_categories = vec![EventCategory::Setting];
+            let mut events = telemetry.observe_events();
+
+            telemetry.report_setting_event("theme", "One Dark".to_string());
+            assert!(telemetry.state.lock().events_queue.is_empty());
+            telemetry.report_app_event("open".to_string());
+            assert_eq!(telemetry.state.lock().events_queue.len(), 1);
+
+            // Events that aren't sent are still shown to observers.
+            let reported = std::iter::from_fn(|| events.try_next().ok().flatten())
+                .map(|reported| (reported.event.category(), reported.sent))
+                .collect::<Vec<_>>();
+            assert_eq!(
+                reported,
+                [(EventCategory::Setting, false), (EventCategory::App, true)]
+            );
+        });
+    }
+
     // TODO:
     // Test settings
     // Update FakeHTTPClient to keep track of the number of requests and assert on it
@@ -200,7 +200,8 @@ CREATE TABLE "channels" (
     "created_at" TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
     "visibility" VARCHAR NOT NULL,
     "parent_path" TEXT NOT NULL,
-    "requires_zed_cla" BOOLEAN NOT NULL DEFAULT FALSE
+    "requires_zed_cla" BOOLEAN NOT NULL DEFAULT FALSE,
+    "default_role" VARCHAR NOT NULL DEFAULT 'guest'
 );
 
 CREATE INDEX "index_channels_on_parent_path" ON "channels" ("parent_path");
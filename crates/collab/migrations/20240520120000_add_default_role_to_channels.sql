@@ -0,0 +1 @@
+ALTER TABLE "channels" ADD COLUMN "default_role" VARCHAR NOT NULL DEFAULT 'guest';
@@ -609,6 +609,7 @@ pub struct Channel {
     pub visibility: ChannelVisibility,
     /// parent_path is the channel ids from the root to this one (not including this one)
     pub parent_path: Vec<ChannelId>,
+    pub default_role: ChannelRole,
 }
 
 impl Channel {
@@ -618,6 +619,7 @@ impl Channel {
             visibility: value.visibility,
             name: value.clone().name,
             parent_path: value.ancestors().collect(),
+            default_role: value.default_role,
         }
     }
 
@@ -627,6 +629,7 @@ impl Channel {
             name: self.name.clone(),
             visibility: self.visibility.into(),
             parent_path: self.parent_path.iter().map(|c| c.to_proto()).collect(),
+            default_role: Some(self.default_role.into()),
         }
     }
 }
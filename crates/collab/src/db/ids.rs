@@ -105,6 +105,10 @@ pub enum ChannelRole {
     #[sea_orm(string_value = "member")]
     #[default]
     Member,
+    /// Commenter can read, and write in the channel notes, but not in projects.
+    /// They can use microphones and the channel chat
+    #[sea_orm(string_value = "commenter")]
+    Commenter,
     /// Talker can read, but not write.
     /// They can use microphones and the channel chat
     #[sea_orm(string_value = "talker")]
@@ -123,8 +127,9 @@ impl ChannelRole {
     pub fn should_override(&self, other: Self) -> bool {
         use ChannelRole::*;
         match self {
-            Admin => matches!(other, Member | Banned | Talker | Guest),
-            Member => matches!(other, Banned | Talker | Guest),
+            Admin => matches!(other, Member | Banned | Commenter | Talker | Guest),
+            Member => matches!(other, Banned | Commenter | Talker | Guest),
+            Commenter => matches!(other, Talker | Guest),
             Talker => matches!(other, Guest),
             Banned => matches!(other, Guest),
             Guest => false,
@@ -144,7 +149,7 @@ impl ChannelRole {
         use ChannelRole::*;
         match self {
             Admin | Member => true,
-            Guest | Talker => visibility == ChannelVisibility::Public,
+            Guest | Talker | Commenter => visibility == ChannelVisibility::Public,
             Banned => false,
         }
     }
@@ -154,7 +159,7 @@ impl ChannelRole {
         use ChannelRole::*;
         match self {
             Admin | Member => true,
-            Guest | Talker | Banned => false,
+            Guest | Talker | Commenter | Banned => false,
         }
     }
 
@@ -162,7 +167,7 @@ impl ChannelRole {
     pub fn can_only_see_public_descendants(&self) -> bool {
         use ChannelRole::*;
         match self {
-            Guest | Talker => true,
+            Guest | Talker | Commenter => true,
             Admin | Member | Banned => false,
         }
     }
@@ -171,7 +176,7 @@ impl ChannelRole {
     pub fn can_use_microphone(&self) -> bool {
         use ChannelRole::*;
         match self {
-            Admin | Member | Talker => true,
+            Admin | Member | Commenter | Talker => true,
             Guest | Banned => false,
         }
     }
@@ -181,6 +186,15 @@ impl ChannelRole {
         use ChannelRole::*;
         match self {
             Admin | Member => true,
+            Commenter | Talker | Guest | Banned => false,
+        }
+    }
+
+    /// True if the role can write in the channel notes.
+    pub fn can_edit_channel_notes(&self) -> bool {
+        use ChannelRole::*;
+        match self {
+            Admin | Member | Commenter => true,
             Talker | Guest | Banned => false,
         }
     }
@@ -189,7 +203,7 @@ impl ChannelRole {
     pub fn can_read_projects(&self) -> bool {
         use ChannelRole::*;
         match self {
-            Admin | Member | Guest | Talker => true,
+            Admin | Member | Commenter | Guest | Talker => true,
             Banned => false,
         }
     }
@@ -198,7 +212,16 @@ impl ChannelRole {
         use ChannelRole::*;
         match self {
             Admin | Member => true,
-            Banned | Guest | Talker => false,
+            Banned | Guest | Talker | Commenter => false,
+        }
+    }
+
+    /// True if the role can be given to everyone who joins a public channel.
+    pub fn can_be_default(&self) -> bool {
+        use ChannelRole::*;
+        match self {
+            Guest | Talker | Commenter => true,
+            Admin | Member | Banned => false,
         }
     }
 }
@@ -208,6 +231,7 @@ impl From<proto::ChannelRole> for ChannelRole {
         match value {
             proto::ChannelRole::Admin => ChannelRole::Admin,
             proto::ChannelRole::Member => ChannelRole::Member,
+            proto::ChannelRole::Commenter => ChannelRole::Commenter,
             proto::ChannelRole::Talker => ChannelRole::Talker,
             proto::ChannelRole::Guest => ChannelRole::Guest,
             proto::ChannelRole::Banned => ChannelRole::Banned,
@@ -220,6 +244,7 @@ impl Into<proto::ChannelRole> for ChannelRole {
         match self {
             ChannelRole::Admin => proto::ChannelRole::Admin,
             ChannelRole::Member => proto::ChannelRole::Member,
+            ChannelRole::Commenter => proto::ChannelRole::Commenter,
             ChannelRole::Talker => proto::ChannelRole::Talker,
             ChannelRole::Guest => proto::ChannelRole::Guest,
             ChannelRole::Banned => proto::ChannelRole::Banned,
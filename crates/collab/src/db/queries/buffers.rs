@@ -451,12 +451,11 @@ impl Database {
                     Some(_) => requires_write_permission = true,
                 }
             }
-            if requires_write_permission {
-                self.check_user_is_channel_member(&channel, user, &tx)
-                    .await?;
-            } else {
-                self.check_user_is_channel_participant(&channel, user, &tx)
-                    .await?;
+            let role = self
+                .check_user_is_channel_participant(&channel, user, &tx)
+                .await?;
+            if requires_write_permission && !role.can_edit_channel_notes() {
+                Err(anyhow!("user cannot edit the channel notes"))?;
             }
 
             let buffer = buffer::Entity::find()
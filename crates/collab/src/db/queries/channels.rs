@@ -68,6 +68,7 @@ impl Database {
                         .map_or(String::new(), |parent| parent.path()),
                 ),
                 requires_zed_cla: ActiveValue::NotSet,
+                default_role: ActiveValue::NotSet,
             }
             .insert(&*tx)
             .await?;
@@ -127,13 +128,14 @@ impl Database {
                         self.channel_role_for_user(&channel, user_id, &tx).await? == role
                     );
                 } else if channel.visibility == ChannelVisibility::Public {
-                    role = Some(ChannelRole::Guest);
+                    let default_role = self.default_role_for_channel(&channel, &tx).await?;
+                    role = Some(default_role);
                     channel_member::Entity::insert(channel_member::ActiveModel {
                         id: ActiveValue::NotSet,
                         channel_id: ActiveValue::Set(channel.root_id()),
                         user_id: ActiveValue::Set(user_id),
                         accepted: ActiveValue::Set(true),
-                        role: ActiveValue::Set(ChannelRole::Guest),
+                        role: ActiveValue::Set(default_role),
                     })
                     .exec(&*tx)
                     .await?;
@@ -210,6 +212,49 @@ impl Database {
         .await
     }
 
+    /// Sets the role of those who join the given channel while it's public, without having been
+    /// invited. Memberships are held in root channels, so only they have a default role.
+    pub async fn set_channel_default_role(
+        &self,
+        channel_id: ChannelId,
+        role: ChannelRole,
+        admin_id: UserId,
+    ) -> Result<channel::Model> {
+        self.transaction(move |tx| async move {
+            let channel = self.get_channel_internal(channel_id, &tx).await?;
+            self.check_user_is_channel_admin(&channel, admin_id, &tx)
+                .await?;
+
+            if !channel.is_root() {
+                Err(anyhow!("only root channels have a default role"))?;
+            }
+            if !role.can_be_default() {
+                Err(anyhow!("{role:?} can't be the default role of a channel"))?;
+            }
+
+            let mut model = channel.into_active_model();
+            model.default_role = ActiveValue::Set(role);
+            let channel = model.update(&*tx).await?;
+
+            Ok(channel)
+        })
+        .await
+    }
+
+    /// Returns the role of those who join the given channel while it's public, without having
+    /// been invited, which is the default role of its root channel.
+    pub(crate) async fn default_role_for_channel(
+        &self,
+        channel: &channel::Model,
+        tx: &DatabaseTransaction,
+    ) -> Result<ChannelRole> {
+        if channel.is_root() {
+            return Ok(channel.default_role);
+        }
+        let root = self.get_channel_internal(channel.root_id(), tx).await?;
+        Ok(root.default_role)
+    }
+
     #[cfg(test)]
     pub async fn set_channel_requires_zed_cla(
         &self,
@@ -410,7 +455,7 @@ impl Database {
         .await
     }
 
-    async fn calculate_membership_updated(
+    pub(crate) async fn calculate_membership_updated(
         &self,
         channel: &channel::Model,
         user_id: UserId,
@@ -780,6 +825,7 @@ impl Database {
         match role {
             Some(ChannelRole::Admin) => Ok(role.unwrap()),
             Some(ChannelRole::Member)
+            | Some(ChannelRole::Commenter)
             | Some(ChannelRole::Talker)
             | Some(ChannelRole::Banned)
             | Some(ChannelRole::Guest)
@@ -802,6 +848,7 @@ impl Database {
             Some(ChannelRole::Banned)
             | Some(ChannelRole::Guest)
             | Some(ChannelRole::Talker)
+            | Some(ChannelRole::Commenter)
             | None => Err(anyhow!(
                 "user is not a channel member or channel does not exist"
             ))?,
@@ -820,7 +867,8 @@ impl Database {
             Some(ChannelRole::Admin)
             | Some(ChannelRole::Member)
             | Some(ChannelRole::Guest)
-            | Some(ChannelRole::Talker) => Ok(role.unwrap()),
+            | Some(ChannelRole::Talker)
+            | Some(ChannelRole::Commenter) => Ok(role.unwrap()),
             Some(ChannelRole::Banned) | None => Err(anyhow!(
                 "user is not a channel participant or channel does not exist"
             ))?,
@@ -163,7 +163,9 @@ impl Database {
 
             let called_user_role = match caller.role.unwrap_or(ChannelRole::Member) {
                 ChannelRole::Admin | ChannelRole::Member => ChannelRole::Member,
-                ChannelRole::Guest | ChannelRole::Talker => ChannelRole::Guest,
+                ChannelRole::Guest | ChannelRole::Talker | ChannelRole::Commenter => {
+                    ChannelRole::Guest
+                }
                 ChannelRole::Banned => return Err(anyhow!("banned users cannot invite").into()),
             };
 
@@ -1043,7 +1045,7 @@ impl Database {
         room_id: RoomId,
         user_id: UserId,
         role: ChannelRole,
-    ) -> Result<TransactionGuard<proto::Room>> {
+    ) -> Result<TransactionGuard<(proto::Room, Option<MembershipUpdated>)>> {
         self.room_transaction(room_id, |tx| async move {
             room_participant::Entity::find()
                 .filter(
@@ -1077,11 +1079,65 @@ impl Database {
             if result.rows_affected != 1 {
                 Err(anyhow!("could not update room participant role"))?;
             }
-            self.get_room(room_id, &tx).await
+            let membership_updated = self
+                .update_guest_membership_for_room_role(room_id, user_id, role, &tx)
+                .await?;
+            Ok((self.get_room(room_id, &tx).await?, membership_updated))
         })
         .await
     }
 
+    /// Guests of a channel can only write in its notes while they can comment in its room, so
+    /// their membership follows their role there. Members keep the membership they were given.
+    async fn update_guest_membership_for_room_role(
+        &self,
+        room_id: RoomId,
+        user_id: UserId,
+        role: ChannelRole,
+        tx: &DatabaseTransaction,
+    ) -> Result<Option<MembershipUpdated>> {
+        let Some(channel) = room::Entity::find_by_id(room_id)
+            .one(tx)
+            .await?
+            .ok_or_else(|| anyhow!("could not find room"))?
+            .find_related(channel::Entity)
+            .one(tx)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        let membership_role = match role {
+            ChannelRole::Guest | ChannelRole::Talker | ChannelRole::Commenter => role,
+            ChannelRole::Admin | ChannelRole::Member => ChannelRole::Commenter,
+            ChannelRole::Banned => return Ok(None),
+        };
+        let Some(membership) = channel_member::Entity::find()
+            .filter(
+                channel_member::Column::ChannelId
+                    .eq(channel.root_id())
+                    .and(channel_member::Column::UserId.eq(user_id))
+                    .and(channel_member::Column::Accepted.eq(true)),
+            )
+            .one(tx)
+            .await?
+        else {
+            return Ok(None);
+        };
+        if !membership.role.can_be_default() || membership.role == membership_role {
+            return Ok(None);
+        }
+
+        let mut membership = membership.into_active_model();
+        membership.role = ActiveValue::Set(membership_role);
+        channel_member::Entity::update(membership).exec(tx).await?;
+
+        Ok(Some(
+            self.calculate_membership_updated(&channel, user_id, tx)
+                .await?,
+        ))
+    }
+
     async fn check_user_has_signed_cla(
         &self,
         user_id: UserId,
@@ -1,4 +1,4 @@
-use crate::db::{ChannelId, ChannelVisibility};
+use crate::db::{ChannelId, ChannelRole, ChannelVisibility};
 use sea_orm::entity::prelude::*;
 
 #[derive(Clone, Debug, Default, PartialEq, Eq, DeriveEntityModel)]
@@ -10,6 +10,8 @@ pub struct Model {
     pub visibility: ChannelVisibility,
     pub parent_path: String,
     pub requires_zed_cla: bool,
+    /// The role of those who join the channel while it's public, without having been invited.
+    pub default_role: ChannelRole,
 }
 
 impl Model {
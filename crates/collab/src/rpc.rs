@@ -557,6 +557,7 @@ impl Server {
             .add_request_handler(user_handler(remove_channel_member))
             .add_request_handler(user_handler(set_channel_member_role))
             .add_request_handler(user_handler(set_channel_visibility))
+            .add_request_handler(user_handler(set_channel_default_role))
             .add_request_handler(user_handler(rename_channel))
             .add_request_handler(user_handler(join_channel_buffer))
             .add_request_handler(user_handler(leave_channel_buffer))
@@ -1790,7 +1791,7 @@ async fn set_room_participant_role(
     let role = ChannelRole::from(request.role());
 
     let (live_kit_room, can_publish) = {
-        let room = session
+        let mut result = session
             .db()
             .await
             .set_room_participant_role(
@@ -1800,10 +1801,21 @@ async fn set_room_participant_role(
                 role,
             )
             .await?;
+        let membership_updated = result.1.take();
+        let room = &result.0;
 
         let live_kit_room = room.live_kit_room.clone();
         let can_publish = ChannelRole::from(request.role()).can_use_microphone();
-        room_updated(&room, &session.peer);
+        room_updated(room, &session.peer);
+        if let Some(membership_updated) = membership_updated {
+            let mut connection_pool = session.connection_pool().await;
+            notify_membership_updated(
+                &mut connection_pool,
+                membership_updated,
+                user_id,
+                &session.peer,
+            );
+        }
         (live_kit_room, can_publish)
     };
 
@@ -3557,6 +3569,34 @@ async fn set_channel_visibility(
     Ok(())
 }
 
+/// Set the role of those who join a public channel without having been invited.
+async fn set_channel_default_role(
+    request: proto::SetChannelDefaultRole,
+    response: Response<proto::SetChannelDefaultRole>,
+    session: UserSession,
+) -> Result<()> {
+    let db = session.db().await;
+    let channel_id = ChannelId::from_proto(request.channel_id);
+    let channel_model = db
+        .set_channel_default_role(channel_id, request.role().into(), session.user_id())
+        .await?;
+    let channel = Channel::from_model(channel_model);
+
+    let connection_pool = session.connection_pool().await;
+    let update = proto::UpdateChannels {
+        channels: vec![channel.to_proto()],
+        ..Default::default()
+    };
+    for (connection_id, role) in connection_pool.channel_connection_ids(channel.id) {
+        if role.can_see_channel(channel.visibility) {
+            session.peer.send(connection_id, update.clone())?;
+        }
+    }
+
+    response.send(proto::Ack {})?;
+    Ok(())
+}
+
 /// Alter the role for a user in the channel.
 async fn set_channel_member_role(
     request: proto::SetChannelMemberRole,
@@ -4514,6 +4554,7 @@ async fn complete_with_anthropic(
             stream: true,
             system: system_message,
             max_tokens: 4092,
+            temperature: None,
         },
         None,
     )
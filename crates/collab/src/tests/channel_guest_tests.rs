@@ -160,6 +160,83 @@ async fn test_channel_guest_promotion(cx_a: &mut TestAppContext, cx_b: &mut Test
         .is_err());
 }
 
+#[gpui::test]
+async fn test_channel_member_demoted_to_talker(
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(cx_a.executor()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+
+    let channel_id = server
+        .make_public_channel("the-channel", &client_a, cx_a)
+        .await;
+
+    let project_a = client_a.build_test_project(cx_a).await;
+    cx_a.update(|cx| workspace::join_channel(channel_id, client_a.app_state.clone(), None, cx))
+        .await
+        .unwrap();
+    active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    cx_a.run_until_parked();
+
+    // Client B joins the channel and is given edit access
+    cx_b.update(|cx| workspace::join_channel(channel_id, client_b.app_state.clone(), None, cx))
+        .await
+        .unwrap();
+    cx_a.run_until_parked();
+    active_call_a
+        .update(cx_a, |call, cx| {
+            call.room().unwrap().update(cx, |room, cx| {
+                room.set_participant_role(
+                    client_b.user_id().unwrap(),
+                    proto::ChannelRole::Member,
+                    cx,
+                )
+            })
+        })
+        .await
+        .unwrap();
+    cx_a.run_until_parked();
+
+    let (workspace_b, cx_b) = client_b.active_workspace(cx_b);
+    let room_b = cx_b
+        .read(ActiveCall::global)
+        .update(cx_b, |call, _| call.room().unwrap().clone());
+    cx_b.simulate_keystrokes("cmd-p 1 enter");
+    let (project_b, editor_b) = workspace_b.update(cx_b, |workspace, cx| {
+        (
+            workspace.project().clone(),
+            workspace.active_item_as::<Editor>(cx).unwrap(),
+        )
+    });
+    assert!(project_b.read_with(cx_b, |project, _| !project.is_read_only()));
+    assert!(editor_b.update(cx_b, |editor, cx| !editor.read_only(cx)));
+
+    // B's write access is revoked mid-session, while keeping their microphone
+    active_call_a
+        .update(cx_a, |call, cx| {
+            call.room().unwrap().update(cx, |room, cx| {
+                room.set_participant_role(
+                    client_b.user_id().unwrap(),
+                    proto::ChannelRole::Talker,
+                    cx,
+                )
+            })
+        })
+        .await
+        .unwrap();
+    cx_a.run_until_parked();
+
+    assert!(project_b.read_with(cx_b, |project, _| project.is_read_only()));
+    assert!(editor_b.update(cx_b, |editor, cx| editor.read_only(cx)));
+    assert!(room_b.read_with(cx_b, |room, _| room.can_use_microphone()));
+}
+
 #[gpui::test]
 async fn test_channel_requires_zed_cla(cx_a: &mut TestAppContext, cx_b: &mut TestAppContext) {
     let mut server = TestServer::start(cx_a.executor()).await;
@@ -286,3 +363,79 @@ async fn test_channel_requires_zed_cla(cx_a: &mut TestAppContext, cx_b: &mut Tes
     assert!(room_b.read_with(cx_b, |room, _| room.can_share_projects()));
     assert!(room_b.read_with(cx_b, |room, _| room.can_use_microphone()));
 }
+
+#[gpui::test]
+async fn test_channel_default_role_commenter(cx_a: &mut TestAppContext, cx_b: &mut TestAppContext) {
+    let mut server = TestServer::start(cx_a.executor()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+
+    let channel_id = server
+        .make_public_channel("the-channel", &client_a, cx_a)
+        .await;
+    client_a
+        .channel_store()
+        .update(cx_a, |store, cx| {
+            store.set_channel_default_role(channel_id, proto::ChannelRole::Commenter, cx)
+        })
+        .await
+        .unwrap();
+
+    let project_a = client_a.build_test_project(cx_a).await;
+    cx_a.update(|cx| workspace::join_channel(channel_id, client_a.app_state.clone(), None, cx))
+        .await
+        .unwrap();
+    active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    cx_a.run_until_parked();
+
+    // Client B joins the channel with the channel's default role
+    cx_b.update(|cx| workspace::join_channel(channel_id, client_b.app_state.clone(), None, cx))
+        .await
+        .unwrap();
+    cx_a.run_until_parked();
+    client_b.channel_store().read_with(cx_b, |store, _| {
+        assert_eq!(
+            store.channel_role(channel_id),
+            proto::ChannelRole::Commenter
+        )
+    });
+
+    // B can talk, but not edit the shared project
+    let (workspace_b, cx_b) = client_b.active_workspace(cx_b);
+    let room_b = cx_b
+        .read(ActiveCall::global)
+        .update(cx_b, |call, _| call.room().unwrap().clone());
+    let project_b = workspace_b.update(cx_b, |workspace, _| workspace.project().clone());
+    assert!(project_b.read_with(cx_b, |project, _| project.is_read_only()));
+    assert!(room_b.read_with(cx_b, |room, _| room.can_use_microphone()));
+
+    // B can write in the channel notes
+    let channel_buffer_b = client_b
+        .channel_store()
+        .update(cx_b, |store, cx| store.open_channel_buffer(channel_id, cx))
+        .await
+        .unwrap();
+    assert!(channel_buffer_b.read_with(cx_b, |buffer, cx| !buffer.buffer().read(cx).read_only()));
+
+    // B is demoted to a guest, and loses access to the channel notes
+    active_call_a
+        .update(cx_a, |call, cx| {
+            call.room().unwrap().update(cx, |room, cx| {
+                room.set_participant_role(
+                    client_b.user_id().unwrap(),
+                    proto::ChannelRole::Guest,
+                    cx,
+                )
+            })
+        })
+        .await
+        .unwrap();
+    cx_a.run_until_parked();
+
+    assert!(room_b.read_with(cx_b, |room, _| !room.can_use_microphone()));
+    assert!(channel_buffer_b.read_with(cx_b, |buffer, cx| buffer.buffer().read(cx).read_only()));
+}
@@ -1526,6 +1526,8 @@ async fn test_mutual_editor_inlay_hint_cache_update(
                     show_type_hints: true,
                     show_parameter_hints: false,
                     show_other_hints: true,
+                    show_padding: true,
+                    max_length: None,
                 })
             });
         });
@@ -1540,6 +1542,8 @@ async fn test_mutual_editor_inlay_hint_cache_update(
                     show_type_hints: true,
                     show_parameter_hints: false,
                     show_other_hints: true,
+                    show_padding: true,
+                    max_length: None,
                 })
             });
         });
@@ -1788,6 +1792,8 @@ async fn test_inlay_hint_refresh_is_forwarded(
                     show_type_hints: false,
                     show_parameter_hints: false,
                     show_other_hints: false,
+                    show_padding: true,
+                    max_length: None,
                 })
             });
         });
@@ -1802,6 +1808,8 @@ async fn test_inlay_hint_refresh_is_forwarded(
                     show_type_hints: true,
                     show_parameter_hints: true,
                     show_other_hints: true,
+                    show_padding: true,
+                    max_length: None,
                 })
             });
         });
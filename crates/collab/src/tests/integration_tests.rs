@@ -6,7 +6,10 @@ use crate::{
     },
 };
 use anyhow::{anyhow, Result};
-use call::{room, ActiveCall, ParticipantLocation, Room};
+use call::{
+    call_settings::{CallSettings, ScreenShareResolution},
+    room, ActiveCall, ParticipantLocation, Room,
+};
 use client::{User, RECEIVE_TIMEOUT};
 use collections::{HashMap, HashSet};
 use fs::{FakeFs, Fs as _, RemoveOptions};
@@ -21,7 +24,7 @@ use language::{
     tree_sitter_rust, Diagnostic, DiagnosticEntry, FakeLspAdapter, Language, LanguageConfig,
     LanguageMatcher, LineEnding, OffsetRangeExt, Point, Rope,
 };
-use live_kit_client::MacOSDisplay;
+use live_kit_client::{MacOSDisplay, ScreenCaptureOptions};
 use lsp::LanguageServerId;
 use parking_lot::Mutex;
 use project::{
@@ -2085,6 +2088,141 @@ async fn test_mute_deafen(
     }
 }
 
+#[gpui::test]
+async fn test_push_to_talk(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    server
+        .make_contacts(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+
+    cx_a.update(|cx| {
+        SettingsStore::update_global(cx, |store, cx| {
+            store.update_user_settings::<CallSettings>(cx, |settings| {
+                settings.push_to_talk = Some(true);
+            });
+        });
+    });
+
+    let active_call_a = cx_a.read(ActiveCall::global);
+    let active_call_b = cx_b.read(ActiveCall::global);
+    active_call_a
+        .update(cx_a, |call, cx| {
+            call.invite(client_b.user_id().unwrap(), None, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    active_call_b
+        .update(cx_b, |call, cx| call.accept_incoming(cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    let room_a = active_call_a.read_with(cx_a, |call, _| call.room().unwrap().clone());
+    let room_b = active_call_b.read_with(cx_b, |call, _| call.room().unwrap().clone());
+    let user_a_muted = |cx: &mut TestAppContext| {
+        room_b.read_with(cx, |room, _| {
+            room.remote_participants()
+                .get(&client_a.user_id().unwrap())
+                .unwrap()
+                .muted
+        })
+    };
+
+    // User A's microphone is published, but muted until push-to-talk is active.
+    room_a.read_with(cx_a, |room, _| {
+        assert!(room.is_sharing_mic());
+        assert!(room.is_muted());
+    });
+    assert!(user_a_muted(cx_b));
+
+    room_a.update(cx_a, |room, cx| room.set_push_to_talk_active(true, cx));
+    executor.run_until_parked();
+    room_a.read_with(cx_a, |room, _| assert!(!room.is_muted()));
+    assert!(!user_a_muted(cx_b));
+
+    room_a.update(cx_a, |room, cx| room.set_push_to_talk_active(false, cx));
+    executor.run_until_parked();
+    room_a.read_with(cx_a, |room, _| assert!(room.is_muted()));
+    assert!(user_a_muted(cx_b));
+}
+
+#[gpui::test]
+async fn test_screen_share_quality_settings(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    server
+        .make_contacts(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+
+    cx_a.update(|cx| {
+        SettingsStore::update_global(cx, |store, cx| {
+            store.update_user_settings::<CallSettings>(cx, |settings| {
+                settings.screen_share_resolution = Some(ScreenShareResolution::P720);
+                settings.screen_share_frame_rate = Some(30);
+            });
+        });
+    });
+
+    let active_call_a = cx_a.read(ActiveCall::global);
+    let active_call_b = cx_b.read(ActiveCall::global);
+    active_call_a
+        .update(cx_a, |call, cx| {
+            call.invite(client_b.user_id().unwrap(), None, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    active_call_b
+        .update(cx_b, |call, cx| call.accept_incoming(cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    let display = MacOSDisplay::new();
+    active_call_a
+        .update(cx_a, |call, cx| {
+            call.room().unwrap().update(cx, |room, cx| {
+                room.set_display_sources(vec![display.clone()]);
+                room.share_screen(cx)
+            })
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    let room_b = active_call_b.read_with(cx_b, |call, _| call.room().unwrap().clone());
+    room_b.read_with(cx_b, |room, _| {
+        let video_tracks = &room
+            .remote_participants()
+            .get(&client_a.user_id().unwrap())
+            .unwrap()
+            .video_tracks;
+        assert_eq!(
+            video_tracks
+                .values()
+                .map(|track| track.capture_options())
+                .collect::<Vec<_>>(),
+            [ScreenCaptureOptions {
+                max_width: 1280,
+                max_height: 720,
+                max_frame_rate: 30,
+            }]
+        );
+    });
+}
+
 #[gpui::test(iterations = 10)]
 async fn test_room_location(
     executor: BackgroundExecutor,
@@ -4908,6 +5046,7 @@ async fn test_project_search(
             SearchResult::Buffer { buffer, ranges } => {
                 results.entry(buffer).or_insert(ranges);
             }
+            SearchResult::SkippedFiles(_) => {}
             SearchResult::LimitReached => {
                 panic!("Unexpectedly reached search limit in tests. If you do want to assert limit-reached, change this panic call.")
             }
@@ -868,6 +868,10 @@ impl CollabPanel {
                 Label::new("Mic only")
                     .color(Color::Muted)
                     .into_any_element()
+            } else if role == proto::ChannelRole::Commenter {
+                Label::new("Can comment")
+                    .color(Color::Muted)
+                    .into_any_element()
             } else {
                 div().into_any_element()
             })
@@ -1075,6 +1079,24 @@ impl CollabPanel {
         })
     }
 
+    fn set_participant_role(user_id: u64, role: proto::ChannelRole, cx: &mut WindowContext) {
+        ActiveCall::global(cx)
+            .update(cx, |call, cx| {
+                let Some(room) = call.room() else {
+                    return Task::ready(Ok(()));
+                };
+                room.update(cx, |room, cx| room.set_participant_role(user_id, role, cx))
+            })
+            .detach_and_prompt_err("Failed to change permissions", cx, |e, _| {
+                match e.error_code() {
+                    ErrorCode::NeedsCla => {
+                        Some("This user has not yet signed the CLA at https://zed.dev/cla.".into())
+                    }
+                    _ => None,
+                }
+            })
+    }
+
     fn deploy_participant_context_menu(
         &mut self,
         position: Point<Pixels>,
@@ -1085,89 +1107,31 @@ impl CollabPanel {
         let this = cx.view().clone();
         if !(role == proto::ChannelRole::Guest
             || role == proto::ChannelRole::Talker
+            || role == proto::ChannelRole::Commenter
             || role == proto::ChannelRole::Member)
         {
             return;
         }
 
         let context_menu = ContextMenu::build(cx, |mut context_menu, cx| {
-            if role == proto::ChannelRole::Guest {
-                context_menu = context_menu.entry(
-                    "Grant Mic Access",
-                    None,
-                    cx.handler_for(&this, move |_, cx| {
-                        ActiveCall::global(cx)
-                            .update(cx, |call, cx| {
-                                let Some(room) = call.room() else {
-                                    return Task::ready(Ok(()));
-                                };
-                                room.update(cx, |room, cx| {
-                                    room.set_participant_role(
-                                        user_id,
-                                        proto::ChannelRole::Talker,
-                                        cx,
-                                    )
-                                })
-                            })
-                            .detach_and_prompt_err("Failed to grant mic access", cx, |_, _| None)
-                    }),
-                );
-            }
-            if role == proto::ChannelRole::Guest || role == proto::ChannelRole::Talker {
-                context_menu = context_menu.entry(
-                    "Grant Write Access",
-                    None,
-                    cx.handler_for(&this, move |_, cx| {
-                        ActiveCall::global(cx)
-                            .update(cx, |call, cx| {
-                                let Some(room) = call.room() else {
-                                    return Task::ready(Ok(()));
-                                };
-                                room.update(cx, |room, cx| {
-                                    room.set_participant_role(
-                                        user_id,
-                                        proto::ChannelRole::Member,
-                                        cx,
-                                    )
-                                })
-                            })
-                            .detach_and_prompt_err("Failed to grant write access", cx, |e, _| {
-                                match e.error_code() {
-                                    ErrorCode::NeedsCla => Some("This user has not yet signed the CLA at https://zed.dev/cla.".into()),
-                                    _ => None,
-                                }
-                            })
-                    }),
-                );
-            }
-            if role == proto::ChannelRole::Member || role == proto::ChannelRole::Talker {
-                let label = if role == proto::ChannelRole::Talker {
-                    "Mute"
-                } else {
-                    "Revoke Access"
-                };
-                context_menu = context_menu.entry(
+            context_menu = context_menu.header("Permissions");
+            for (label, level) in [
+                ("Can View", proto::ChannelRole::Guest),
+                ("Can Talk and Chat", proto::ChannelRole::Talker),
+                ("Can Comment", proto::ChannelRole::Commenter),
+                ("Can Edit", proto::ChannelRole::Member),
+            ] {
+                context_menu = context_menu.toggleable_entry(
                     label,
+                    role == level,
                     None,
                     cx.handler_for(&this, move |_, cx| {
-                        ActiveCall::global(cx)
-                            .update(cx, |call, cx| {
-                                let Some(room) = call.room() else {
-                                    return Task::ready(Ok(()));
-                                };
-                                room.update(cx, |room, cx| {
-                                    room.set_participant_role(
-                                        user_id,
-                                        proto::ChannelRole::Guest,
-                                        cx,
-                                    )
-                                })
-                            })
-                            .detach_and_prompt_err("Failed to revoke access", cx, |_, _| None)
+                        if role != level {
+                            Self::set_participant_role(user_id, level, cx);
+                        }
                     }),
                 );
             }
-
             context_menu
         });
 
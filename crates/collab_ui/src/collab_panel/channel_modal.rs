@@ -11,7 +11,9 @@ use gpui::{
 };
 use picker::{Picker, PickerDelegate};
 use std::sync::Arc;
-use ui::{prelude::*, Avatar, CheckboxWithLabel, ContextMenu, ListItem, ListItemSpacing};
+use ui::{
+    popover_menu, prelude::*, Avatar, CheckboxWithLabel, ContextMenu, ListItem, ListItemSpacing,
+};
 use util::TryFutureExt;
 use workspace::{notifications::DetachAndPromptErr, ModalView};
 
@@ -108,6 +110,51 @@ impl ChannelModal {
     fn dismiss(&mut self, _: &menu::Cancel, cx: &mut ViewContext<Self>) {
         cx.emit(DismissEvent);
     }
+
+    /// The permissions given to those who join the public channel without having been invited.
+    fn render_default_role_menu(&self, default_role: ChannelRole) -> impl IntoElement {
+        let channel_store = self.channel_store.clone();
+        let channel_id = self.channel_id;
+        let label = match default_role {
+            ChannelRole::Talker => "Joiners Can Talk",
+            ChannelRole::Commenter => "Joiners Can Comment",
+            _ => "Joiners Can View",
+        };
+        popover_menu("default-role")
+            .menu(move |cx| {
+                let channel_store = channel_store.clone();
+                Some(ContextMenu::build(cx, move |mut menu, _| {
+                    menu = menu.header("Those Who Join");
+                    for (label, role) in [
+                        ("Can View", ChannelRole::Guest),
+                        ("Can Talk and Chat", ChannelRole::Talker),
+                        ("Can Comment", ChannelRole::Commenter),
+                    ] {
+                        let channel_store = channel_store.clone();
+                        menu =
+                            menu.toggleable_entry(label, role == default_role, None, move |cx| {
+                                channel_store
+                                    .update(cx, |channel_store, cx| {
+                                        channel_store.set_channel_default_role(channel_id, role, cx)
+                                    })
+                                    .detach_and_prompt_err(
+                                        "Failed to change permissions",
+                                        cx,
+                                        |_, _| None,
+                                    );
+                            });
+                    }
+                    menu
+                }))
+            })
+            .trigger(
+                Button::new("default-role", label)
+                    .label_size(LabelSize::Small)
+                    .icon(IconName::ChevronDown)
+                    .icon_size(IconSize::XSmall)
+                    .icon_position(IconPosition::End),
+            )
+    }
 }
 
 impl EventEmitter<DismissEvent> for ChannelModal {}
@@ -128,6 +175,7 @@ impl Render for ChannelModal {
         let channel_name = channel.name.clone();
         let channel_id = channel.id;
         let visibility = channel.visibility;
+        let default_role = channel.default_role;
         let mode = self.picker.read(cx).delegate.mode;
 
         v_flex()
@@ -165,6 +213,9 @@ impl Render for ChannelModal {
                                 },
                                 cx.listener(Self::set_channel_visibility),
                             ))
+                            .when(visibility == ChannelVisibility::Public, |this| {
+                                this.child(self.render_default_role_menu(default_role))
+                            })
                             .children(
                                 Some(
                                     Button::new("copy-link", "Copy Link")
@@ -400,6 +451,7 @@ impl PickerDelegate for ChannelModalDelegate {
                             )
                             .children(match membership.map(|m| m.role) {
                                 Some(ChannelRole::Admin) => Some(Label::new("Admin")),
+                                Some(ChannelRole::Commenter) => Some(Label::new("Commenter")),
                                 Some(ChannelRole::Guest) => Some(Label::new("Guest")),
                                 _ => None,
                             })
@@ -570,9 +622,10 @@ impl ChannelModalDelegate {
                 });
             }
 
-            if role == ChannelRole::Admin || role == ChannelRole::Guest {
+            let is_guest = role == ChannelRole::Guest || role == ChannelRole::Commenter;
+            if role == ChannelRole::Admin || is_guest {
                 let picker = picker.clone();
-                let label = if role == ChannelRole::Guest {
+                let label = if is_guest {
                     "Promote to Member"
                 } else {
                     "Demote to Member"
@@ -587,7 +640,7 @@ impl ChannelModalDelegate {
                 });
             }
 
-            if role == ChannelRole::Member || role == ChannelRole::Guest {
+            if role == ChannelRole::Member || is_guest {
                 let picker = picker.clone();
                 menu = menu.entry("Promote to Admin", None, move |cx| {
                     picker.update(cx, |picker, cx| {
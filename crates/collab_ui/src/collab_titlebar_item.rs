@@ -1,16 +1,21 @@
 use crate::face_pile::FacePile;
 use auto_update::AutoUpdateStatus;
-use call::{ActiveCall, ParticipantLocation, Room};
+use call::{
+    call_settings::{CallSettings, ScreenShareResolution},
+    ActiveCall, AudioDevice, ParticipantLocation, Room,
+};
 use client::{proto::PeerId, Client, User, UserStore};
 use gpui::{
     actions, canvas, div, point, px, Action, AnyElement, AppContext, Element, Hsla,
-    InteractiveElement, IntoElement, Model, ParentElement, Path, Render,
-    StatefulInteractiveElement, Styled, Subscription, View, ViewContext, VisualContext, WeakView,
+    InteractiveElement, IntoElement, KeyUpEvent, Model, ParentElement, Path, Render,
+    StatefulInteractiveElement, Styled, Subscription, Task, View, ViewContext, VisualContext,
+    WeakView, WindowContext,
 };
-use project::{Project, RepositoryEntry};
+use project::{Fs, Project, RepositoryEntry};
 use recent_projects::RecentProjects;
 use rpc::proto::{self, DevServerStatus};
-use std::sync::Arc;
+use settings::Settings;
+use std::{sync::Arc, time::Duration};
 use theme::ActiveTheme;
 use ui::{
     h_flex, popover_menu, prelude::*, Avatar, AvatarAudioStatusIndicator, Button, ButtonLike,
@@ -22,6 +27,7 @@ use workspace::{notifications::NotifyResultExt, Workspace};
 
 const MAX_PROJECT_NAME_LENGTH: usize = 40;
 const MAX_BRANCH_NAME_LENGTH: usize = 40;
+const SCREEN_SHARE_FRAME_RATES: [u32; 4] = [5, 15, 30, 60];
 
 actions!(
     collab,
@@ -37,7 +43,9 @@ actions!(
 pub fn init(cx: &mut AppContext) {
     cx.observe_new_views(|workspace: &mut Workspace, cx| {
         let titlebar_item = cx.new_view(|cx| CollabTitlebarItem::new(workspace, cx));
-        workspace.set_titlebar_item(titlebar_item.into(), cx)
+        workspace.set_titlebar_item(titlebar_item.into(), cx);
+        workspace
+            .register_action(|_, action: &crate::PushToTalk, cx| crate::push_to_talk(action, cx));
     })
     .detach();
 }
@@ -46,7 +54,10 @@ pub struct CollabTitlebarItem {
     project: Model<Project>,
     user_store: Model<UserStore>,
     client: Arc<Client>,
+    fs: Arc<dyn Fs>,
     workspace: WeakView<Workspace>,
+    /// The key that activated push-to-talk, which deactivates it once released.
+    push_to_talk_key: Option<String>,
     _subscriptions: Vec<Subscription>,
 }
 
@@ -170,8 +181,8 @@ impl Render for CollabTitlebarItem {
                     .gap_1()
                     .pr_1()
                     .on_mouse_move(|_, cx| cx.stop_propagation())
-                    .when_some(room, |this, room| {
-                        let room = room.read(cx);
+                    .when_some(room, |this, room_handle| {
+                        let room = room_handle.read(cx);
                         let project = self.project.read(cx);
                         let is_local = project.is_local();
                         let is_dev_server_project = project.dev_server_project_id().is_some();
@@ -181,6 +192,10 @@ impl Render for CollabTitlebarItem {
                         let is_screen_sharing = room.is_screen_sharing();
                         let can_use_microphone = room.can_use_microphone();
                         let can_share_projects = room.can_share_projects();
+                        let push_to_talk_key = self
+                            .push_to_talk_key
+                            .clone()
+                            .filter(|_| room.is_push_to_talk_active());
 
                         this.when(
                             (is_local || is_dev_server_project) && can_share_projects,
@@ -257,6 +272,21 @@ impl Render for CollabTitlebarItem {
                                 .selected_style(ButtonStyle::Tinted(TintColor::Negative))
                                 .on_click(move |_, cx| crate::toggle_mute(&Default::default(), cx)),
                             )
+                            .child(render_microphone_menu(room_handle.clone(), self.fs.clone()))
+                        })
+                        .when_some(push_to_talk_key, |this, push_to_talk_key| {
+                            // Key releases aren't bound to actions, so listen for the release of
+                            // the key that activated push-to-talk directly.
+                            this.child(canvas(
+                                |_, _| {},
+                                move |_, _, cx| {
+                                    cx.on_key_event(move |event: &KeyUpEvent, _, cx| {
+                                        if event.keystroke.key == push_to_talk_key {
+                                            crate::release_push_to_talk(cx);
+                                        }
+                                    })
+                                },
+                            ))
                         })
                         .child(
                             IconButton::new(
@@ -306,6 +336,7 @@ impl Render for CollabTitlebarItem {
                                         crate::toggle_screen_sharing(&Default::default(), cx)
                                     }),
                             )
+                            .child(render_screen_share_menu(self.fs.clone()))
                         })
                         .child(div().pr_2())
                     })
@@ -324,6 +355,161 @@ impl Render for CollabTitlebarItem {
     }
 }
 
+fn render_microphone_menu(room: Model<Room>, fs: Arc<dyn Fs>) -> impl IntoElement {
+    popover_menu("microphone-menu")
+        .menu(move |cx| {
+            let room = room.clone();
+            let fs = fs.clone();
+            Some(ContextMenu::build(cx, move |mut menu, cx| {
+                let level_meter = cx.new_view(|cx| AudioLevelMeter::new(room.clone(), cx));
+                let current_device = room.read(cx).audio_input_device();
+                menu = menu.header("Microphone");
+                for device in room.read(cx).audio_input_devices() {
+                    let room = room.clone();
+                    let fs = fs.clone();
+                    menu = menu.toggleable_entry(
+                        device.name.clone(),
+                        current_device.as_ref() == Some(&device),
+                        None,
+                        move |cx| select_audio_input_device(&room, &device, fs.clone(), cx),
+                    );
+                }
+                menu.separator()
+                    .custom_entry(move |_| level_meter.clone().into_any_element(), |_| {})
+            }))
+        })
+        .trigger(
+            IconButton::new("microphone-menu", IconName::ChevronDown)
+                .style(ButtonStyle::Subtle)
+                .icon_size(IconSize::XSmall)
+                .tooltip(|cx| Tooltip::text("Microphone Settings", cx)),
+        )
+        .anchor(gpui::AnchorCorner::TopRight)
+}
+
+fn select_audio_input_device(
+    room: &Model<Room>,
+    device: &AudioDevice,
+    fs: Arc<dyn Fs>,
+    cx: &mut WindowContext,
+) {
+    room.update(cx, |room, cx| room.set_audio_input_device(device, cx))
+        .log_err();
+    let name = device.name.clone();
+    settings::update_settings_file::<CallSettings>(fs, cx, move |settings| {
+        settings.audio_input_device = Some(name);
+    });
+}
+
+fn render_screen_share_menu(fs: Arc<dyn Fs>) -> impl IntoElement {
+    popover_menu("screen-share-menu")
+        .menu(move |cx| {
+            let fs = fs.clone();
+            Some(ContextMenu::build(cx, move |mut menu, cx| {
+                let call_settings = CallSettings::get_global(cx);
+                let current_resolution = call_settings.screen_share_resolution;
+                let current_frame_rate = call_settings.screen_share_frame_rate;
+
+                menu = menu.header("Maximum Resolution");
+                for resolution in ScreenShareResolution::ALL {
+                    let fs = fs.clone();
+                    menu = menu.toggleable_entry(
+                        resolution.label(),
+                        resolution == current_resolution,
+                        None,
+                        move |cx| {
+                            settings::update_settings_file::<CallSettings>(
+                                fs.clone(),
+                                cx,
+                                move |settings| settings.screen_share_resolution = Some(resolution),
+                            );
+                        },
+                    );
+                }
+
+                menu = menu.separator().header("Maximum Frame Rate");
+                for frame_rate in SCREEN_SHARE_FRAME_RATES {
+                    let fs = fs.clone();
+                    menu = menu.toggleable_entry(
+                        format!("{frame_rate} fps"),
+                        frame_rate == current_frame_rate,
+                        None,
+                        move |cx| {
+                            settings::update_settings_file::<CallSettings>(
+                                fs.clone(),
+                                cx,
+                                move |settings| settings.screen_share_frame_rate = Some(frame_rate),
+                            );
+                        },
+                    );
+                }
+                menu
+            }))
+        })
+        .trigger(
+            IconButton::new("screen-share-menu", IconName::ChevronDown)
+                .style(ButtonStyle::Subtle)
+                .icon_size(IconSize::XSmall)
+                .tooltip(|cx| {
+                    Tooltip::with_meta(
+                        "Screen Share Quality",
+                        None,
+                        "Applies the next time you share your screen",
+                        cx,
+                    )
+                }),
+        )
+        .anchor(gpui::AnchorCorner::TopRight)
+}
+
+/// Displays the level of the audio captured by the microphone.
+struct AudioLevelMeter {
+    room: Model<Room>,
+    _refresh: Task<()>,
+}
+
+impl AudioLevelMeter {
+    const SEGMENT_COUNT: usize = 12;
+    const REFRESH_INTERVAL: Duration = Duration::from_millis(100);
+
+    fn new(room: Model<Room>, cx: &mut ViewContext<Self>) -> Self {
+        let _refresh = cx.spawn(|this, mut cx| async move {
+            loop {
+                cx.background_executor().timer(Self::REFRESH_INTERVAL).await;
+                if this.update(&mut cx, |_, cx| cx.notify()).is_err() {
+                    break;
+                }
+            }
+        });
+        Self { room, _refresh }
+    }
+}
+
+impl Render for AudioLevelMeter {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let level = self.room.read(cx).local_audio_level().clamp(0., 1.);
+        let lit_segment_count = (level * Self::SEGMENT_COUNT as f32).round() as usize;
+        let lit_color = cx.theme().status().success;
+        let unlit_color = cx.theme().colors().element_background;
+
+        h_flex()
+            .px_2()
+            .py_1()
+            .gap_0p5()
+            .children((0..Self::SEGMENT_COUNT).map(|ix| {
+                div()
+                    .w_2()
+                    .h_1p5()
+                    .rounded_sm()
+                    .bg(if ix < lit_segment_count {
+                        lit_color
+                    } else {
+                        unlit_color
+                    })
+            }))
+    }
+}
+
 fn render_color_ribbon(color: Hsla) -> impl Element {
     canvas(
         move |_, _| {},
@@ -365,12 +551,28 @@ impl CollabTitlebarItem {
         subscriptions.push(cx.observe(&active_call, |this, _, cx| this.active_call_changed(cx)));
         subscriptions.push(cx.observe_window_activation(Self::window_activation_changed));
         subscriptions.push(cx.observe(&user_store, |_, _, cx| cx.notify()));
+        let this = cx.view().downgrade();
+        subscriptions.push(cx.observe_keystrokes(move |event, cx| {
+            let is_push_to_talk = event
+                .action
+                .as_ref()
+                .map_or(false, |action| action.as_any().is::<crate::PushToTalk>());
+            if is_push_to_talk {
+                this.update(cx, |this, cx| {
+                    this.push_to_talk_key = Some(event.keystroke.key.clone());
+                    cx.notify();
+                })
+                .ok();
+            }
+        }));
 
         Self {
             workspace: workspace.weak_handle(),
             project,
             user_store,
             client,
+            fs: workspace.app_state().fs.clone(),
+            push_to_talk_key: None,
             _subscriptions: subscriptions,
         }
     }
@@ -614,6 +816,11 @@ impl CollabTitlebarItem {
     }
 
     fn window_activation_changed(&mut self, cx: &mut ViewContext<Self>) {
+        // Key releases aren't received by inactive windows.
+        if !cx.is_window_active() && self.push_to_talk_key.take().is_some() {
+            crate::release_push_to_talk(cx);
+        }
+
         if cx.is_window_active() {
             ActiveCall::global(cx)
                 .update(cx, |call, cx| call.set_location(Some(&self.project), cx))
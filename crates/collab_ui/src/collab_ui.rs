@@ -9,7 +9,7 @@ mod panel_settings;
 
 use std::{rc::Rc, sync::Arc};
 
-use call::{report_call_event_for_room, ActiveCall};
+use call::{call_settings::CallSettings, report_call_event_for_room, ActiveCall};
 pub use collab_panel::CollabPanel;
 pub use collab_titlebar_item::CollabTitlebarItem;
 use gpui::{
@@ -26,7 +26,13 @@ use workspace::{notifications::DetachAndPromptErr, AppState};
 
 actions!(
     collab,
-    [ToggleScreenSharing, ToggleMute, ToggleDeafen, LeaveCall]
+    [
+        ToggleScreenSharing,
+        ToggleMute,
+        ToggleDeafen,
+        LeaveCall,
+        PushToTalk
+    ]
 );
 
 pub fn init(app_state: &Arc<AppState>, cx: &mut AppContext) {
@@ -94,6 +100,21 @@ pub fn toggle_deafen(_: &ToggleDeafen, cx: &mut AppContext) {
     }
 }
 
+pub fn push_to_talk(_: &PushToTalk, cx: &mut AppContext) {
+    if !CallSettings::get_global(cx).push_to_talk {
+        return;
+    }
+    if let Some(room) = ActiveCall::global(cx).read(cx).room().cloned() {
+        room.update(cx, |room, cx| room.set_push_to_talk_active(true, cx));
+    }
+}
+
+fn release_push_to_talk(cx: &mut AppContext) {
+    if let Some(room) = ActiveCall::global(cx).read(cx).room().cloned() {
+        room.update(cx, |room, cx| room.set_push_to_talk_active(false, cx));
+    }
+}
+
 fn notification_window_options(
     screen: Rc<dyn PlatformDisplay>,
     window_size: Size<Pixels>,
@@ -45,8 +45,8 @@ pub fn init(app_state: &Arc<AppState>, cx: &mut AppContext) {
                                     )
                                 })
                             })
-                            .unwrap();
-                        notification_windows.push(window);
+                            .log_err();
+                        notification_windows.extend(window);
                     }
                 }
             }
@@ -8,6 +8,7 @@ use settings::Settings;
 use std::sync::{Arc, Weak};
 use theme::ThemeSettings;
 use ui::{prelude::*, Button, Label};
+use util::ResultExt;
 use workspace::AppState;
 
 pub fn init(app_state: &Arc<AppState>, cx: &mut AppContext) {
@@ -27,20 +28,24 @@ pub fn init(app_state: &Arc<AppState>, cx: &mut AppContext) {
 
             for screen in cx.displays() {
                 let options = notification_window_options(screen, window_size, cx);
-                let window = cx.open_window(options, |cx| {
-                    cx.new_view(|_| {
-                        ProjectSharedNotification::new(
-                            owner.clone(),
-                            *project_id,
-                            worktree_root_names.clone(),
-                            app_state.clone(),
-                        )
+                let window = cx
+                    .open_window(options, |cx| {
+                        cx.new_view(|_| {
+                            ProjectSharedNotification::new(
+                                owner.clone(),
+                                *project_id,
+                                worktree_root_names.clone(),
+                                app_state.clone(),
+                            )
+                        })
                     })
-                });
-                notification_windows
-                    .entry(*project_id)
-                    .or_insert(Vec::new())
-                    .push(window);
+                    .log_err();
+                if let Some(window) = window {
+                    notification_windows
+                        .entry(*project_id)
+                        .or_insert(Vec::new())
+                        .push(window);
+                }
             }
         }
 
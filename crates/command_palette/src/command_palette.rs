@@ -7,12 +7,14 @@ use std::{
 use client::{parse_zed_link, telemetry::Telemetry};
 use collections::HashMap;
 use command_palette_hooks::{
-    CommandInterceptResult, CommandPaletteFilter, CommandPaletteInterceptor,
+    CommandCompletion, CommandInterceptResult, CommandPaletteCompleter, CommandPaletteFilter,
+    CommandPaletteInterceptor, CommandPaletteObserver,
 };
 use fuzzy::{StringMatch, StringMatchCandidate};
 use gpui::{
-    actions, Action, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Global,
-    ParentElement, Render, Styled, Task, UpdateGlobal, View, ViewContext, VisualContext, WeakView,
+    actions, Action, AnyElement, AppContext, DismissEvent, EventEmitter, FocusHandle,
+    FocusableView, Global, KeyContext, ParentElement, Render, Styled, Task, UpdateGlobal, View,
+    ViewContext, VisualContext, WeakView,
 };
 use picker::{Picker, PickerDelegate};
 
@@ -22,11 +24,25 @@ use util::ResultExt;
 use workspace::{ModalView, Workspace};
 use zed_actions::OpenZedUrl;
 
-actions!(command_palette, [Toggle]);
+actions!(
+    command_palette,
+    [
+        Toggle,
+        ToggleCommandLine,
+        NextCompletion,
+        PreviousCompletion,
+        PreviousHistoryQuery,
+        NextHistoryQuery
+    ]
+);
+
+/// The most queries the query history keeps, as in vim.
+const MAX_HISTORY: usize = 50;
 
 pub fn init(cx: &mut AppContext) {
     client::init_settings(cx);
     cx.set_global(HitCounts::default());
+    cx.set_global(QueryHistory::default());
     command_palette_hooks::init(cx);
     cx.observe_new_views(CommandPalette::register).detach();
 }
@@ -57,20 +73,48 @@ fn trim_consecutive_whitespaces(input: &str) -> String {
 
 impl CommandPalette {
     fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
-        workspace.register_action(|workspace, _: &Toggle, cx| {
-            let Some(previous_focus_handle) = cx.focused() else {
-                return;
-            };
-            let telemetry = workspace.client().telemetry().clone();
-            workspace.toggle_modal(cx, move |cx| {
-                CommandPalette::new(previous_focus_handle, telemetry, cx)
-            });
+        workspace.register_action(|workspace, _: &Toggle, cx| Self::toggle(workspace, "", cx));
+        workspace.register_action(|workspace, _: &ToggleCommandLine, cx| {
+            Self::toggle_command_line(workspace, "", cx)
+        });
+    }
+
+    /// Opens the command palette with the given query already typed, or closes it.
+    pub fn toggle(workspace: &mut Workspace, query: &str, cx: &mut ViewContext<Workspace>) {
+        Self::toggle_modal(workspace, query, false, cx);
+    }
+
+    /// Like [`CommandPalette::toggle`], but opens the palette as a command line, whose
+    /// queries are kept in a history that up and down move through.
+    pub fn toggle_command_line(
+        workspace: &mut Workspace,
+        query: &str,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        Self::toggle_modal(workspace, query, true, cx);
+    }
+
+    fn toggle_modal(
+        workspace: &mut Workspace,
+        query: &str,
+        command_line: bool,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let Some(previous_focus_handle) = cx.focused() else {
+            return;
+        };
+        let telemetry = workspace.client().telemetry().clone();
+        let query = query.to_string();
+        workspace.toggle_modal(cx, move |cx| {
+            CommandPalette::new(previous_focus_handle, telemetry, &query, command_line, cx)
         });
     }
 
     fn new(
         previous_focus_handle: FocusHandle,
         telemetry: Arc<Telemetry>,
+        query: &str,
+        command_line: bool,
         cx: &mut ViewContext<Self>,
     ) -> Self {
         let filter = CommandPaletteFilter::try_global(cx);
@@ -95,11 +139,55 @@ impl CommandPalette {
             commands,
             telemetry,
             previous_focus_handle,
+            command_line,
         );
 
-        let picker = cx.new_view(|cx| Picker::uniform_list(delegate, cx));
+        let picker = cx.new_view(|cx| {
+            let picker = Picker::uniform_list(delegate, cx);
+            if !query.is_empty() {
+                picker.set_query(query, cx);
+            }
+            picker
+        });
         Self { picker }
     }
+
+    fn next_completion(&mut self, _: &NextCompletion, cx: &mut ViewContext<Self>) {
+        self.select_completion(true, cx);
+    }
+
+    fn previous_completion(&mut self, _: &PreviousCompletion, cx: &mut ViewContext<Self>) {
+        self.select_completion(false, cx);
+    }
+
+    fn select_completion(&mut self, forward: bool, cx: &mut ViewContext<Self>) {
+        self.picker.update(cx, |picker, cx| {
+            let query = picker.query(cx);
+            picker.delegate.update_completions(&query, cx);
+            if let Some(query) = picker.delegate.completion_query(forward) {
+                picker.set_query(query, cx);
+            }
+        });
+    }
+
+    fn previous_history_query(&mut self, _: &PreviousHistoryQuery, cx: &mut ViewContext<Self>) {
+        self.select_history_query(true, cx);
+    }
+
+    fn next_history_query(&mut self, _: &NextHistoryQuery, cx: &mut ViewContext<Self>) {
+        self.select_history_query(false, cx);
+    }
+
+    fn select_history_query(&mut self, older: bool, cx: &mut ViewContext<Self>) {
+        self.picker.update(cx, |picker, cx| {
+            let query = picker.query(cx);
+            match picker.delegate.history_query(older, &query, cx) {
+                Some(query) => picker.set_query(query, cx),
+                None if older => picker.select_prev(&menu::SelectPrev, cx),
+                None => picker.select_next(&menu::SelectNext, cx),
+            }
+        });
+    }
 }
 
 impl EventEmitter<DismissEvent> for CommandPalette {}
@@ -111,8 +199,20 @@ impl FocusableView for CommandPalette {
 }
 
 impl Render for CommandPalette {
-    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
-        v_flex().w(rems(34.)).child(self.picker.clone())
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let mut key_context = KeyContext::new_with_defaults();
+        key_context.add("CommandPalette");
+        if self.picker.read(cx).delegate.command_line {
+            key_context.add("command_line");
+        }
+        v_flex()
+            .key_context(key_context)
+            .on_action(cx.listener(Self::next_completion))
+            .on_action(cx.listener(Self::previous_completion))
+            .on_action(cx.listener(Self::previous_history_query))
+            .on_action(cx.listener(Self::next_history_query))
+            .w(rems(34.))
+            .child(self.picker.clone())
     }
 }
 
@@ -128,6 +228,17 @@ pub struct CommandPaletteDelegate {
         Task<()>,
         postage::dispatch::Receiver<(Vec<Command>, Vec<StringMatch>)>,
     )>,
+    /// The query that the matches are for.
+    latest_query: String,
+    /// The completions of a query, from the [`CommandPaletteCompleter`].
+    completions: Vec<CommandCompletion>,
+    completions_query: String,
+    /// The completion whose query is in the palette, once tab has been pressed.
+    selected_completion: Option<usize>,
+    /// Whether the palette was opened as a command line, which keeps a [`QueryHistory`].
+    command_line: bool,
+    /// The entry of the [`QueryHistory`] whose query is in the palette.
+    history_position: Option<usize>,
 }
 
 struct Command {
@@ -152,12 +263,19 @@ struct HitCounts(HashMap<String, usize>);
 
 impl Global for HitCounts {}
 
+/// The queries run from the command palette while it acts as a command line, oldest first.
+#[derive(Default)]
+struct QueryHistory(Vec<String>);
+
+impl Global for QueryHistory {}
+
 impl CommandPaletteDelegate {
     fn new(
         command_palette: WeakView<CommandPalette>,
         commands: Vec<Command>,
         telemetry: Arc<Telemetry>,
         previous_focus_handle: FocusHandle,
+        command_line: bool,
     ) -> Self {
         Self {
             command_palette,
@@ -168,7 +286,70 @@ impl CommandPaletteDelegate {
             telemetry,
             previous_focus_handle,
             updating_matches: None,
+            latest_query: String::new(),
+            completions: Vec::new(),
+            completions_query: String::new(),
+            selected_completion: None,
+            command_line,
+            history_position: None,
+        }
+    }
+
+    /// Finds the completions of the query, unless it's the query they're for or the query of
+    /// the selected completion, which keeps tab cycling through the same completions.
+    fn update_completions(&mut self, query: &str, cx: &AppContext) {
+        let is_selected = self
+            .selected_completion
+            .and_then(|ix| self.completions.get(ix))
+            .map_or(false, |completion| completion.query == query);
+        if is_selected || query == self.completions_query {
+            return;
+        }
+        self.completions = CommandPaletteCompleter::try_global(cx)
+            .map(|completer| completer.complete(query, cx))
+            .unwrap_or_default();
+        self.completions_query = query.to_string();
+        self.selected_completion = None;
+    }
+
+    /// The query of the next or previous completion, cycling around at either end.
+    fn completion_query(&mut self, forward: bool) -> Option<String> {
+        let count = self.completions.len();
+        if count == 0 {
+            return None;
         }
+        let ix = match (self.selected_completion, forward) {
+            (None, true) => 0,
+            (None, false) => count - 1,
+            (Some(ix), true) => (ix + 1) % count,
+            (Some(ix), false) => (ix + count - 1) % count,
+        };
+        self.selected_completion = Some(ix);
+        Some(self.completions[ix].query.clone())
+    }
+
+    /// The query of an older or newer entry of the query history, when the palette acts as a
+    /// command line and its query is empty or was taken from the history.
+    fn history_query(&mut self, older: bool, query: &str, cx: &AppContext) -> Option<String> {
+        if !self.command_line {
+            return None;
+        }
+        let history = &cx.global::<QueryHistory>().0;
+        let position = match self.history_position.take() {
+            Some(ix) if history.get(ix).map_or(false, |entry| entry == query) => Some(ix),
+            _ if query.is_empty() => None,
+            _ => return None,
+        };
+        let position = match (position, older) {
+            (None, true) => history.len().checked_sub(1)?,
+            (None, false) => return None,
+            (Some(ix), true) => ix.saturating_sub(1),
+            (Some(ix), false) if ix + 1 < history.len() => ix + 1,
+            // Moving past the newest entry goes back to an empty query.
+            (Some(_), false) => return Some(String::new()),
+        };
+        self.history_position = Some(position);
+        Some(history[position].clone())
     }
 
     fn matches_updated(
@@ -179,6 +360,7 @@ impl CommandPaletteDelegate {
         cx: &mut ViewContext<Picker<Self>>,
     ) {
         self.updating_matches.take();
+        self.update_completions(&query, cx);
 
         let mut intercept_result = CommandPaletteInterceptor::try_global(cx)
             .and_then(|interceptor| interceptor.intercept(&query, cx));
@@ -219,6 +401,7 @@ impl CommandPaletteDelegate {
         }
         self.commands = commands;
         self.matches = matches;
+        self.latest_query = query;
         if self.matches.is_empty() {
             self.selected_ix = 0;
         } else {
@@ -365,7 +548,19 @@ impl PickerDelegate for CommandPaletteDelegate {
         HitCounts::update_global(cx, |hit_counts, _cx| {
             *hit_counts.0.entry(command.name).or_default() += 1;
         });
+        let query = self.latest_query.trim();
+        if !query.is_empty() && self.command_line {
+            let query = query.to_string();
+            QueryHistory::update_global(cx, |history, _| {
+                history.0.retain(|entry| *entry != query);
+                history.0.push(query);
+                if history.0.len() > MAX_HISTORY {
+                    history.0.remove(0);
+                }
+            });
+        }
         let action = command.action;
+        CommandPaletteObserver::observe(action.as_ref(), cx);
         cx.focus(&self.previous_focus_handle);
         self.dismissed(cx);
         cx.dispatch_action(action);
@@ -401,9 +596,36 @@ impl PickerDelegate for CommandPaletteDelegate {
                 ),
         )
     }
+
+    fn render_footer(&self, cx: &mut ViewContext<Picker<Self>>) -> Option<AnyElement> {
+        if self.completions.is_empty() {
+            return None;
+        }
+        Some(
+            h_flex()
+                .w_full()
+                .flex_wrap()
+                .gap_2()
+                .p_2()
+                .border_t_1()
+                .border_color(cx.theme().colors().border_variant)
+                .children(self.completions.iter().enumerate().map(|(ix, completion)| {
+                    let color = if self.selected_completion == Some(ix) {
+                        Color::Accent
+                    } else {
+                        Color::Muted
+                    };
+                    Label::new(completion.label.clone())
+                        .size(LabelSize::Small)
+                        .color(color)
+                }))
+                .into_any_element(),
+        )
+    }
 }
 
-fn humanize_action_name(name: &str) -> String {
+/// Turns an action's name into a label for it, like "editor: move up" for "editor::MoveUp".
+pub fn humanize_action_name(name: &str) -> String {
     let capacity = name.len() + name.chars().filter(|c| c.is_uppercase()).count();
     let mut result = String::with_capacity(capacity);
     for char in name.chars() {
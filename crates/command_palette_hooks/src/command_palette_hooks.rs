@@ -2,7 +2,7 @@
 
 #![deny(missing_docs)]
 
-use std::any::TypeId;
+use std::{any::TypeId, rc::Rc};
 
 use collections::HashSet;
 use derive_more::{Deref, DerefMut};
@@ -12,6 +12,8 @@ use gpui::{Action, AppContext, BorrowAppContext, Global};
 pub fn init(cx: &mut AppContext) {
     cx.set_global(GlobalCommandPaletteFilter::default());
     cx.set_global(GlobalCommandPaletteInterceptor::default());
+    cx.set_global(GlobalCommandPaletteCompleter::default());
+    cx.set_global(GlobalCommandPaletteObserver::default());
 }
 
 /// A filter for the command palette.
@@ -140,3 +142,103 @@ impl CommandPaletteInterceptor {
         self.0 = Some(handler);
     }
 }
+
+/// A completion of the command palette's query, such as a file name for a vim `:e` command.
+pub struct CommandCompletion {
+    /// The text shown for the completion.
+    pub label: String,
+    /// The query that replaces the palette's query when the completion is chosen.
+    pub query: String,
+}
+
+/// A completer for the command palette, which makes it act as a command line: tab cycles
+/// through the completions of the query, and up and down go through the queries run before.
+#[derive(Default)]
+pub struct CommandPaletteCompleter(
+    Option<Box<dyn Fn(&str, &AppContext) -> Vec<CommandCompletion>>>,
+);
+
+#[derive(Default)]
+struct GlobalCommandPaletteCompleter(CommandPaletteCompleter);
+
+impl Global for GlobalCommandPaletteCompleter {}
+
+impl CommandPaletteCompleter {
+    /// Returns the global [`CommandPaletteCompleter`], if one is set.
+    pub fn try_global(cx: &AppContext) -> Option<&CommandPaletteCompleter> {
+        cx.try_global::<GlobalCommandPaletteCompleter>()
+            .map(|completer| &completer.0)
+            .filter(|completer| completer.0.is_some())
+    }
+
+    /// Updates the global [`CommandPaletteCompleter`] using the given closure.
+    pub fn update_global<F, R>(cx: &mut AppContext, update: F) -> R
+    where
+        F: FnOnce(&mut Self, &mut AppContext) -> R,
+    {
+        cx.update_global(|this: &mut GlobalCommandPaletteCompleter, cx| update(&mut this.0, cx))
+    }
+
+    /// Returns the completions of the given query from the command palette.
+    pub fn complete(&self, query: &str, cx: &AppContext) -> Vec<CommandCompletion> {
+        let Some(handler) = self.0.as_ref() else {
+            return Vec::new();
+        };
+
+        (handler)(query, cx)
+    }
+
+    /// Clears the global completer.
+    pub fn clear(&mut self) {
+        self.0 = None;
+    }
+
+    /// Sets the global completer.
+    ///
+    /// This will override the previous completer, if it exists.
+    pub fn set(&mut self, handler: Box<dyn Fn(&str, &AppContext) -> Vec<CommandCompletion>>) {
+        self.0 = Some(handler);
+    }
+}
+
+/// An observer of the actions that are run from the command palette.
+#[derive(Default)]
+pub struct CommandPaletteObserver(Option<Rc<dyn Fn(&dyn Action, &mut AppContext)>>);
+
+#[derive(Default)]
+struct GlobalCommandPaletteObserver(CommandPaletteObserver);
+
+impl Global for GlobalCommandPaletteObserver {}
+
+impl CommandPaletteObserver {
+    /// Updates the global [`CommandPaletteObserver`] using the given closure.
+    pub fn update_global<F, R>(cx: &mut AppContext, update: F) -> R
+    where
+        F: FnOnce(&mut Self, &mut AppContext) -> R,
+    {
+        cx.update_global(|this: &mut GlobalCommandPaletteObserver, cx| update(&mut this.0, cx))
+    }
+
+    /// Tells the global observer, if one is set, that the given action was run from the command
+    /// palette.
+    pub fn observe(action: &dyn Action, cx: &mut AppContext) {
+        let handler = cx
+            .try_global::<GlobalCommandPaletteObserver>()
+            .and_then(|observer| observer.0 .0.clone());
+        if let Some(handler) = handler {
+            (handler)(action, cx);
+        }
+    }
+
+    /// Clears the global observer.
+    pub fn clear(&mut self) {
+        self.0 = None;
+    }
+
+    /// Sets the global observer.
+    ///
+    /// This will override the previous observer, if it exists.
+    pub fn set(&mut self, handler: Rc<dyn Fn(&dyn Action, &mut AppContext)>) {
+        self.0 = Some(handler);
+    }
+}
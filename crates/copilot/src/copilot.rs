@@ -15,7 +15,7 @@ use gpui::{
 use http::github::latest_github_release;
 use http::HttpClient;
 use language::{
-    language_settings::{all_language_settings, language_settings, InlineCompletionProvider},
+    language_settings::{all_language_settings, language_settings},
     point_from_lsp, point_to_lsp, Anchor, Bias, Buffer, BufferSnapshot, Language, PointUtf16,
     ToPointUtf16,
 };
@@ -359,8 +359,9 @@ impl Copilot {
         let server_id = self.server_id;
         let http = self.http.clone();
         let node_runtime = self.node_runtime.clone();
-        if all_language_settings(None, cx).inline_completions.provider
-            == InlineCompletionProvider::Copilot
+        if all_language_settings(None, cx)
+            .inline_completions
+            .is_provider_enabled("copilot")
         {
             if matches!(self.server, CopilotServer::Disabled) {
                 let start_task = cx
@@ -637,7 +638,9 @@ impl Copilot {
             ..
         }) = &mut self.server
         {
-            if !matches!(status, SignInStatus::Authorized { .. }) {
+            if !matches!(status, SignInStatus::Authorized { .. })
+                || !inline_completions_enabled_for_buffer(buffer.read(cx), cx)
+            {
                 return;
             }
 
@@ -686,6 +689,13 @@ impl Copilot {
         event: &language::Event,
         cx: &mut ModelContext<Self>,
     ) -> Result<()> {
+        // Stop sending a buffer's changes once it's renamed, or the settings change, so that
+        // inline completions are disabled for it.
+        if !inline_completions_enabled_for_buffer(buffer.read(cx), cx) {
+            self.unregister_buffer(&buffer.downgrade());
+            return Ok(());
+        }
+
         if let Ok(server) = self.server.as_running() {
             if let Some(registered_buffer) = server.registered_buffers.get_mut(&buffer.entity_id())
             {
@@ -840,6 +850,12 @@ impl Copilot {
             >,
         T: ToPointUtf16,
     {
+        if !inline_completions_enabled_for_buffer(buffer.read(cx), cx) {
+            self.unregister_buffer(&buffer.downgrade());
+            return Task::ready(Err(anyhow!(
+                "inline completions are disabled for this buffer"
+            )));
+        }
         self.register_buffer(buffer, cx);
 
         let server = match self.server.as_authenticated() {
@@ -953,6 +969,14 @@ impl Copilot {
     }
 }
 
+/// Whether the buffer's contents may be sent to Copilot, which they never are when inline
+/// completions are disabled for its language or path.
+fn inline_completions_enabled_for_buffer(buffer: &Buffer, cx: &AppContext) -> bool {
+    let file = buffer.file();
+    all_language_settings(file, cx)
+        .inline_completions_enabled(buffer.language(), file.map(|file| file.path().as_ref()))
+}
+
 fn id_for_language(language: Option<&Arc<Language>>) -> String {
     language
         .map(|language| language.lsp_id())
@@ -1043,11 +1067,12 @@ async fn get_copilot_lsp(http: Arc<dyn HttpClient>) -> anyhow::Result<PathBuf> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use gpui::TestAppContext;
-    use language::BufferId;
+    use gpui::{TestAppContext, UpdateGlobal};
+    use language::{language_settings::AllLanguageSettings, BufferId};
 
     #[gpui::test(iterations = 10)]
     async fn test_buffer_management(cx: &mut TestAppContext) {
+        init_test(cx);
         let (copilot, mut lsp) = Copilot::fake(cx);
 
         let buffer_1 = cx.new_model(|cx| Buffer::local("Hello", cx));
@@ -1203,6 +1228,74 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_disabled_buffers_are_not_sent(cx: &mut TestAppContext) {
+        init_test(cx);
+        let (copilot, mut lsp) = Copilot::fake(cx);
+
+        let buffer_with_path = |text: &str, path: &str, cx: &mut TestAppContext| {
+            let buffer = cx.new_model(|cx| Buffer::local(text, cx));
+            buffer.update(cx, |buffer, cx| {
+                buffer.file_updated(
+                    Arc::new(File {
+                        abs_path: Path::new("/root").join(path),
+                        path: Path::new(path).into(),
+                    }),
+                    cx,
+                )
+            });
+            buffer
+        };
+        let env_buffer = buffer_with_path("SECRET=1", ".env", cx);
+        let buffer = buffer_with_path("Hello", "hello.txt", cx);
+        let buffer_uri = lsp::Url::from_file_path("/root/hello.txt").unwrap();
+
+        // Buffers matching the disabled globs are never opened.
+        copilot.update(cx, |copilot, cx| {
+            copilot.register_buffer(&env_buffer, cx);
+            copilot.register_buffer(&buffer, cx);
+        });
+        assert_eq!(
+            lsp.receive_notification::<lsp::notification::DidOpenTextDocument>()
+                .await
+                .text_document
+                .uri,
+            buffer_uri
+        );
+
+        // Buffers whose inline completions become disabled are closed rather than updated.
+        cx.update(|cx| {
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings::<AllLanguageSettings>(cx, |settings| {
+                    settings
+                        .inline_completions
+                        .get_or_insert_with(Default::default)
+                        .disabled_globs = Some(vec!["*.txt".into()]);
+                });
+            })
+        });
+        buffer.update(cx, |buffer, cx| buffer.edit([(5..5, " world")], None, cx));
+        assert_eq!(
+            lsp.receive_notification::<lsp::notification::DidCloseTextDocument>()
+                .await,
+            lsp::DidCloseTextDocumentParams {
+                text_document: lsp::TextDocumentIdentifier::new(buffer_uri),
+            }
+        );
+        assert!(copilot
+            .update(cx, |copilot, cx| copilot.completions(&buffer, 0usize, cx))
+            .await
+            .is_err());
+    }
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let store = SettingsStore::test(cx);
+            cx.set_global(store);
+            language::init(cx);
+        });
+    }
+
     struct File {
         abs_path: PathBuf,
         path: Arc<Path>,
@@ -0,0 +1,421 @@
+mod coverage_settings;
+mod coverage_summary_view;
+mod report;
+
+use collections::HashMap;
+use editor::{Anchor, Editor, EditorMode, EditorSnapshot, GutterColumn};
+use fs::Fs;
+use futures::StreamExt;
+use gpui::{
+    actions, div, px, AnyElement, AppContext, Context, EventEmitter, Global, IntoElement, Model,
+    ModelContext, PathPromptOptions, Styled, Subscription, Task, ViewContext, WeakModel,
+    WindowContext,
+};
+use language::{Point, ToPoint};
+use multi_buffer::{MultiBufferRow, ToPoint as _};
+use project::Project;
+use settings::{Settings, SettingsStore};
+use std::{
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+use theme::ActiveTheme;
+use util::ResultExt;
+use workspace::Workspace;
+
+pub use coverage_settings::CoverageSettings;
+pub use coverage_summary_view::CoverageSummaryView;
+pub use report::{Coverage, CoverageSummary, FileCoverage, LineCoverage, LineStatus};
+
+actions!(coverage, [LoadReport, ClearCoverage, OpenSummary]);
+
+const REPORT_WATCH_LATENCY: Duration = Duration::from_millis(100);
+
+pub fn init(fs: Arc<dyn Fs>, cx: &mut AppContext) {
+    CoverageSettings::register(cx);
+
+    let store = cx.new_model(|cx| CoverageStore::new(fs, cx));
+    cx.set_global(GlobalCoverageStore(store));
+
+    cx.observe_new_views(|workspace: &mut Workspace, cx| {
+        let project = workspace.project().clone();
+        CoverageStore::global(cx).update(cx, |store, cx| store.add_project(project, cx));
+
+        workspace
+            .register_action(load_report)
+            .register_action(|_, _: &ClearCoverage, cx| {
+                CoverageStore::global(cx).update(cx, |store, cx| store.clear(cx));
+            })
+            .register_action(CoverageSummaryView::deploy);
+    })
+    .detach();
+    cx.observe_new_views(register_editor).detach();
+}
+
+struct GlobalCoverageStore(Model<CoverageStore>);
+
+impl Global for GlobalCoverageStore {}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Event {
+    CoverageUpdated,
+}
+
+/// Loads test coverage reports, reloading them whenever they're rewritten, and merges them
+/// into the coverage for every source file.
+pub struct CoverageStore {
+    fs: Arc<dyn Fs>,
+    projects: Vec<(WeakModel<Project>, Subscription)>,
+    reports: HashMap<PathBuf, Report>,
+    coverage: Arc<Coverage>,
+    _settings_subscription: Subscription,
+}
+
+struct Report {
+    /// Whether the report was loaded explicitly, rather than via the `report_paths` setting.
+    explicit: bool,
+    coverage: Option<Coverage>,
+    _watch: Task<()>,
+}
+
+impl EventEmitter<Event> for CoverageStore {}
+
+impl CoverageStore {
+    fn new(fs: Arc<dyn Fs>, cx: &mut ModelContext<Self>) -> Self {
+        Self {
+            fs,
+            projects: Vec::new(),
+            reports: HashMap::default(),
+            coverage: Arc::default(),
+            _settings_subscription: cx
+                .observe_global::<SettingsStore>(|this, cx| this.update_watched_reports(cx)),
+        }
+    }
+
+    pub fn global(cx: &AppContext) -> Model<Self> {
+        cx.global::<GlobalCoverageStore>().0.clone()
+    }
+
+    pub fn try_global(cx: &AppContext) -> Option<Model<Self>> {
+        cx.try_global::<GlobalCoverageStore>()
+            .map(|store| store.0.clone())
+    }
+
+    /// The coverage merged from every loaded report.
+    pub fn coverage(&self) -> &Arc<Coverage> {
+        &self.coverage
+    }
+
+    pub fn file_coverage(&self, abs_path: &Path) -> Option<&FileCoverage> {
+        self.coverage.files.get(abs_path)
+    }
+
+    /// Loads the coverage report at the given path, and keeps reloading it as it changes.
+    /// Relative source paths in the report are resolved against `root`.
+    pub fn load_report(&mut self, path: PathBuf, root: PathBuf, cx: &mut ModelContext<Self>) {
+        self.watch_report(path, root, true, cx);
+    }
+
+    /// Replaces the coverage previously provided for the given source, which allows test
+    /// runners to stream coverage without writing a report to disk.
+    pub fn set_coverage(
+        &mut self,
+        source: PathBuf,
+        coverage: Coverage,
+        cx: &mut ModelContext<Self>,
+    ) {
+        self.reports.insert(
+            source,
+            Report {
+                explicit: true,
+                coverage: Some(coverage),
+                _watch: Task::ready(()),
+            },
+        );
+        self.update_coverage(cx);
+    }
+
+    /// Discards all coverage. Reports configured via the `report_paths` setting are loaded
+    /// again the next time they're rewritten.
+    pub fn clear(&mut self, cx: &mut ModelContext<Self>) {
+        self.reports.retain(|_, report| !report.explicit);
+        for report in self.reports.values_mut() {
+            report.coverage = None;
+        }
+        self.update_coverage(cx);
+    }
+
+    fn add_project(&mut self, project: Model<Project>, cx: &mut ModelContext<Self>) {
+        if !project.read(cx).is_local() {
+            return;
+        }
+
+        let subscription = cx.subscribe(&project, |this, _, event, cx| {
+            if matches!(
+                event,
+                project::Event::WorktreeAdded | project::Event::WorktreeRemoved(_)
+            ) {
+                this.update_watched_reports(cx);
+            }
+        });
+        self.projects.push((project.downgrade(), subscription));
+        self.update_watched_reports(cx);
+    }
+
+    /// Starts watching the reports configured for each worktree, and stops watching the
+    /// reports of worktrees that have since been closed.
+    fn update_watched_reports(&mut self, cx: &mut ModelContext<Self>) {
+        self.projects
+            .retain(|(project, _)| project.upgrade().is_some());
+
+        let report_paths = &CoverageSettings::get_global(cx).report_paths;
+        let mut configured_reports = HashMap::default();
+        for (project, _) in &self.projects {
+            let Some(project) = project.upgrade() else {
+                continue;
+            };
+            for worktree in project.read(cx).visible_worktrees(cx) {
+                let root = worktree.read(cx).abs_path();
+                for report_path in report_paths {
+                    configured_reports.insert(root.join(report_path), root.to_path_buf());
+                }
+            }
+        }
+
+        let report_count = self.reports.len();
+        self.reports
+            .retain(|path, report| report.explicit || configured_reports.contains_key(path));
+        let removed_reports = report_count != self.reports.len();
+
+        for (path, root) in configured_reports {
+            if !self.reports.contains_key(&path) {
+                self.watch_report(path, root, false, cx);
+            }
+        }
+
+        if removed_reports {
+            self.update_coverage(cx);
+        }
+    }
+
+    fn watch_report(
+        &mut self,
+        path: PathBuf,
+        root: PathBuf,
+        explicit: bool,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let fs = self.fs.clone();
+        let watch = cx.spawn({
+            let path = path.clone();
+            |this, mut cx| async move {
+                let events = fs.watch(&path, REPORT_WATCH_LATENCY).await;
+                futures::pin_mut!(events);
+
+                loop {
+                    let coverage = if fs.is_file(&path).await {
+                        fs.load(&path)
+                            .await
+                            .and_then(|text| Coverage::parse(&text, &root))
+                            .log_err()
+                    } else {
+                        None
+                    };
+
+                    let updated = this.update(&mut cx, |this, cx| {
+                        if let Some(report) = this.reports.get_mut(&path) {
+                            report.coverage = coverage;
+                            this.update_coverage(cx);
+                        }
+                    });
+                    if updated.is_err() || events.next().await.is_none() {
+                        break;
+                    }
+                }
+            }
+        });
+
+        self.reports.insert(
+            path,
+            Report {
+                explicit,
+                coverage: None,
+                _watch: watch,
+            },
+        );
+    }
+
+    fn update_coverage(&mut self, cx: &mut ModelContext<Self>) {
+        let mut coverage = Coverage::default();
+        for report in self.reports.values() {
+            if let Some(report_coverage) = &report.coverage {
+                coverage.merge(report_coverage);
+            }
+        }
+
+        if coverage != *self.coverage {
+            self.coverage = Arc::new(coverage);
+            cx.emit(Event::CoverageUpdated);
+            cx.notify();
+        }
+    }
+}
+
+fn load_report(workspace: &mut Workspace, _: &LoadReport, cx: &mut ViewContext<Workspace>) {
+    let paths = cx.prompt_for_paths(PathPromptOptions {
+        files: true,
+        directories: false,
+        multiple: true,
+    });
+    let project = workspace.project().downgrade();
+
+    cx.spawn(|_, mut cx| async move {
+        let Some(paths) = paths.await.log_err().flatten() else {
+            return;
+        };
+
+        cx.update(|cx| {
+            let roots = project
+                .upgrade()
+                .map(|project| {
+                    project
+                        .read(cx)
+                        .visible_worktrees(cx)
+                        .map(|worktree| worktree.read(cx).abs_path())
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default();
+
+            CoverageStore::global(cx).update(cx, |store, cx| {
+                for path in paths {
+                    // Source paths are usually relative to the directory the tests ran in,
+                    // which is most likely the root of the project containing the report.
+                    let root = roots
+                        .iter()
+                        .find(|root| path.starts_with(root))
+                        .map(|root| root.to_path_buf())
+                        .or_else(|| path.parent().map(Path::to_path_buf))
+                        .unwrap_or_default();
+                    store.load_report(path, root, cx);
+                }
+            });
+        })
+        .log_err();
+    })
+    .detach();
+}
+
+/// The gutter column showing test coverage, as a stripe alongside each row with coverage.
+struct CoverageColumn {
+    /// Sorted, non-overlapping ranges of rows and their coverage.
+    stripes: Vec<(Range<Anchor>, LineStatus)>,
+}
+
+impl CoverageColumn {
+    const NAME: &'static str = "coverage";
+}
+
+impl GutterColumn for CoverageColumn {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    fn width(&self) -> f32 {
+        0.5
+    }
+
+    fn render_row(
+        &self,
+        row: MultiBufferRow,
+        snapshot: &EditorSnapshot,
+        cx: &mut WindowContext,
+    ) -> Option<AnyElement> {
+        let buffer = &snapshot.buffer_snapshot;
+        let ix = self
+            .stripes
+            .partition_point(|(range, _)| range.end.to_point(buffer).row < row.0);
+        let (range, status) = self.stripes.get(ix)?;
+        if range.start.to_point(buffer).row > row.0 {
+            return None;
+        }
+
+        let colors = cx.theme().status();
+        let color = match status {
+            LineStatus::Covered => colors.success,
+            LineStatus::Partial => colors.warning,
+            LineStatus::Uncovered => colors.error,
+        };
+        Some(div().w(px(3.)).h_full().bg(color).into_any_element())
+    }
+}
+
+fn register_editor(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+    if editor.mode() != EditorMode::Full {
+        return;
+    }
+    let Some(store) = CoverageStore::try_global(cx) else {
+        return;
+    };
+
+    cx.subscribe(&store, |editor, _, _: &Event, cx| {
+        update_coverage_column(editor, cx)
+    })
+    .detach();
+    cx.subscribe(&editor.buffer().clone(), |editor, _, event, cx| {
+        if matches!(
+            event,
+            multi_buffer::Event::ExcerptsAdded { .. }
+                | multi_buffer::Event::FileHandleChanged
+                | multi_buffer::Event::Reloaded
+        ) {
+            update_coverage_column(editor, cx);
+        }
+    })
+    .detach();
+    cx.observe_global::<SettingsStore>(update_coverage_column)
+        .detach();
+
+    update_coverage_column(editor, cx);
+}
+
+fn update_coverage_column(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+    let coverage = CoverageStore::global(cx).read(cx).coverage().clone();
+    if !CoverageSettings::get_global(cx).gutter || coverage.files.is_empty() {
+        editor.unregister_gutter_column(CoverageColumn::NAME, cx);
+        return;
+    }
+
+    let snapshot = editor.buffer().read(cx).snapshot(cx);
+    let mut stripes = Vec::new();
+    for (excerpt_id, buffer, excerpt_range) in snapshot.excerpts() {
+        let Some(file) = buffer.file().and_then(|file| file.as_local()) else {
+            continue;
+        };
+        let Some(file_coverage) = coverage.files.get(&file.abs_path(cx)) else {
+            continue;
+        };
+
+        let excerpt_start_row = excerpt_range.context.start.to_point(buffer).row;
+        let excerpt_end_row = excerpt_range.context.end.to_point(buffer).row;
+        for (rows, status) in file_coverage.row_ranges() {
+            let start_row = (*rows.start()).max(excerpt_start_row);
+            let end_row = (*rows.end()).min(excerpt_end_row);
+            if start_row > end_row {
+                continue;
+            }
+
+            let start = buffer.anchor_before(Point::new(start_row, 0));
+            let end = buffer.anchor_after(Point::new(end_row, buffer.line_len(end_row)));
+            if let Some((start, end)) = snapshot
+                .anchor_in_excerpt(excerpt_id, start)
+                .zip(snapshot.anchor_in_excerpt(excerpt_id, end))
+            {
+                stripes.push((start..end, status));
+            }
+        }
+    }
+
+    editor.register_gutter_column(CoverageColumn { stripes }, cx);
+}
@@ -0,0 +1,36 @@
+use anyhow::Result;
+use gpui::AppContext;
+use schemars::JsonSchema;
+use serde_derive::{Deserialize, Serialize};
+use settings::{Settings, SettingsSources};
+
+#[derive(Deserialize, Debug)]
+pub struct CoverageSettings {
+    pub gutter: bool,
+    pub report_paths: Vec<String>,
+}
+
+/// Configuration of test coverage in Zed.
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, Debug)]
+pub struct CoverageSettingsContent {
+    /// Whether to show which lines were covered by tests in the gutter.
+    ///
+    /// Default: true
+    pub gutter: Option<bool>,
+
+    /// The LCOV or Cobertura reports to load, relative to the root of each worktree.
+    /// Reports are reloaded whenever they're rewritten, such as by a test runner.
+    ///
+    /// Default: ["coverage/lcov.info", "lcov.info", "coverage/cobertura-coverage.xml", "coverage.xml"]
+    pub report_paths: Option<Vec<String>>,
+}
+
+impl Settings for CoverageSettings {
+    const KEY: Option<&'static str> = Some("coverage");
+
+    type FileContent = CoverageSettingsContent;
+
+    fn load(sources: SettingsSources<Self::FileContent>, _: &mut AppContext) -> Result<Self> {
+        sources.json_merge()
+    }
+}
@@ -0,0 +1,347 @@
+use crate::{ClearCoverage, CoverageStore, CoverageSummary, LineStatus, LoadReport, OpenSummary};
+use collections::HashSet;
+use editor::{scroll::Autoscroll, Editor};
+use gpui::{
+    uniform_list, AnyElement, AppContext, EventEmitter, FocusHandle, FocusableView, Model,
+    Subscription, UniformListScrollHandle, View, WeakView,
+};
+use language::Point;
+use std::{
+    ops::{Range, RangeInclusive},
+    path::PathBuf,
+};
+use ui::{prelude::*, ListItem, Tooltip};
+use workspace::{
+    item::{Item, TabContentParams},
+    Workspace,
+};
+
+/// Lists the coverage of every file in the loaded reports, and the lines that weren't covered
+/// within each of them.
+pub struct CoverageSummaryView {
+    workspace: WeakView<Workspace>,
+    store: Model<CoverageStore>,
+    summary: CoverageSummary,
+    rows: Vec<Row>,
+    expanded_files: HashSet<PathBuf>,
+    focus_handle: FocusHandle,
+    list_scroll_handle: UniformListScrollHandle,
+    _subscription: Subscription,
+}
+
+enum Row {
+    File {
+        path: PathBuf,
+        label: SharedString,
+        summary: CoverageSummary,
+        expanded: bool,
+    },
+    Lines {
+        path: PathBuf,
+        rows: RangeInclusive<u32>,
+        status: LineStatus,
+    },
+}
+
+impl CoverageSummaryView {
+    pub(crate) fn deploy(
+        workspace: &mut Workspace,
+        _: &OpenSummary,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        if let Some(existing) = workspace.item_of_type::<CoverageSummaryView>(cx) {
+            workspace.activate_item(&existing, cx);
+        } else {
+            let workspace_handle = cx.view().downgrade();
+            let view = cx.new_view(|cx| CoverageSummaryView::new(workspace_handle, cx));
+            workspace.add_item_to_active_pane(Box::new(view), None, cx);
+        }
+    }
+
+    fn new(workspace: WeakView<Workspace>, cx: &mut ViewContext<Self>) -> Self {
+        let store = CoverageStore::global(cx);
+        let mut this = Self {
+            workspace,
+            summary: CoverageSummary::default(),
+            rows: Vec::new(),
+            expanded_files: HashSet::default(),
+            focus_handle: cx.focus_handle(),
+            list_scroll_handle: UniformListScrollHandle::new(),
+            _subscription: cx.observe(&store, |this, _, cx| this.update_rows(cx)),
+            store,
+        };
+        this.update_rows(cx);
+        this
+    }
+
+    fn update_rows(&mut self, cx: &mut ViewContext<Self>) {
+        let coverage = self.store.read(cx).coverage().clone();
+        let roots = self
+            .workspace
+            .upgrade()
+            .map(|workspace| {
+                workspace
+                    .read(cx)
+                    .visible_worktrees(cx)
+                    .map(|worktree| worktree.read(cx).abs_path())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        self.expanded_files
+            .retain(|path| coverage.files.contains_key(path));
+        self.summary = coverage.summary();
+        self.rows.clear();
+        for (path, file_coverage) in &coverage.files {
+            let label = roots
+                .iter()
+                .find_map(|root| path.strip_prefix(root.parent().unwrap_or(root)).ok())
+                .unwrap_or(path);
+            let expanded = self.expanded_files.contains(path);
+            self.rows.push(Row::File {
+                path: path.clone(),
+                label: label.to_string_lossy().to_string().into(),
+                summary: file_coverage.summary(),
+                expanded,
+            });
+
+            if expanded {
+                self.rows.extend(
+                    file_coverage
+                        .row_ranges()
+                        .into_iter()
+                        .filter(|(_, status)| *status != LineStatus::Covered)
+                        .map(|(rows, status)| Row::Lines {
+                            path: path.clone(),
+                            rows,
+                            status,
+                        }),
+                );
+            }
+        }
+        cx.notify();
+    }
+
+    fn toggle_expanded(&mut self, path: &PathBuf, cx: &mut ViewContext<Self>) {
+        if !self.expanded_files.remove(path) {
+            self.expanded_files.insert(path.clone());
+        }
+        self.update_rows(cx);
+    }
+
+    fn open_path(&mut self, path: PathBuf, row: Option<u32>, cx: &mut ViewContext<Self>) {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        let open_task =
+            workspace.update(cx, |workspace, cx| workspace.open_abs_path(path, true, cx));
+        cx.spawn(|_, mut cx| async move {
+            let item = open_task.await?;
+            if let Some((editor, row)) = item.downcast::<Editor>().zip(row) {
+                editor.update(&mut cx, |editor, cx| {
+                    let point = Point::new(row, 0);
+                    editor.change_selections(Some(Autoscroll::center()), cx, |selections| {
+                        selections.select_ranges([point..point])
+                    });
+                })?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn render_rows(&mut self, range: Range<usize>, cx: &mut ViewContext<Self>) -> Vec<AnyElement> {
+        range
+            .filter_map(|ix| Some((ix, self.rows.get(ix)?)))
+            .map(|(ix, row)| match row {
+                Row::File {
+                    path,
+                    label,
+                    summary,
+                    expanded,
+                } => ListItem::new(ix)
+                    .toggle((summary.uncovered + summary.partial > 0).then_some(*expanded))
+                    .on_toggle(cx.listener({
+                        let path = path.clone();
+                        move |this, _, cx| this.toggle_expanded(&path, cx)
+                    }))
+                    .on_click(cx.listener({
+                        let path = path.clone();
+                        move |this, _, cx| this.open_path(path.clone(), None, cx)
+                    }))
+                    .child(Label::new(label.clone()))
+                    .end_slot(render_summary(summary, cx))
+                    .into_any_element(),
+                Row::Lines { path, rows, status } => {
+                    let description = match (rows.start() == rows.end(), status) {
+                        (true, LineStatus::Partial) => {
+                            format!("Line {} partially covered", rows.start() + 1)
+                        }
+                        (true, _) => format!("Line {} not covered", rows.start() + 1),
+                        (false, LineStatus::Partial) => format!(
+                            "Lines {}–{} partially covered",
+                            rows.start() + 1,
+                            rows.end() + 1
+                        ),
+                        (false, _) => {
+                            format!("Lines {}–{} not covered", rows.start() + 1, rows.end() + 1)
+                        }
+                    };
+                    let color = match status {
+                        LineStatus::Partial => Color::Warning,
+                        _ => Color::Error,
+                    };
+
+                    ListItem::new(ix)
+                        .indent_level(2)
+                        .on_click(cx.listener({
+                            let path = path.clone();
+                            let row = *rows.start();
+                            move |this, _, cx| this.open_path(path.clone(), Some(row), cx)
+                        }))
+                        .child(Label::new(description).color(color))
+                        .into_any_element()
+                }
+            })
+            .collect()
+    }
+}
+
+fn render_summary(summary: &CoverageSummary, cx: &WindowContext) -> impl IntoElement {
+    let percentage = summary.percentage();
+    let colors = cx.theme().status();
+    let bar_color = if summary.uncovered == 0 {
+        colors.success
+    } else if percentage >= 50. {
+        colors.warning
+    } else {
+        colors.error
+    };
+
+    h_flex()
+        .gap_2()
+        .child(
+            div()
+                .w_24()
+                .h_1p5()
+                .rounded_sm()
+                .bg(cx.theme().colors().element_background)
+                .child(
+                    div()
+                        .h_full()
+                        .rounded_sm()
+                        .w(relative(percentage / 100.))
+                        .bg(bar_color),
+                ),
+        )
+        .child(
+            div()
+                .w_16()
+                .flex()
+                .justify_end()
+                .child(Label::new(format!("{percentage:.1}%"))),
+        )
+        .child(
+            Label::new(format!(
+                "{}/{}",
+                summary.covered + summary.partial,
+                summary.total()
+            ))
+            .color(Color::Muted),
+        )
+}
+
+impl Render for CoverageSummaryView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let header = h_flex()
+            .p_2()
+            .gap_2()
+            .justify_between()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(Label::new("Test Coverage"))
+                    .when(!self.rows.is_empty(), |this| {
+                        this.child(render_summary(&self.summary, cx))
+                    }),
+            )
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(
+                        IconButton::new("load-report", IconName::FileDoc)
+                            .tooltip(|cx| Tooltip::for_action("Load Report", &LoadReport, cx))
+                            .on_click(|_, cx| cx.dispatch_action(Box::new(LoadReport))),
+                    )
+                    .child(
+                        IconButton::new("clear-coverage", IconName::Trash)
+                            .disabled(self.rows.is_empty())
+                            .tooltip(|cx| Tooltip::for_action("Clear Coverage", &ClearCoverage, cx))
+                            .on_click(|_, cx| cx.dispatch_action(Box::new(ClearCoverage))),
+                    ),
+            );
+
+        v_flex()
+            .size_full()
+            .track_focus(&self.focus_handle)
+            .bg(cx.theme().colors().editor_background)
+            .child(header)
+            .child(if self.rows.is_empty() {
+                v_flex()
+                    .flex_1()
+                    .items_center()
+                    .justify_center()
+                    .child(Label::new("No coverage reports loaded").color(Color::Muted))
+                    .into_any_element()
+            } else {
+                uniform_list(
+                    cx.view().clone(),
+                    "coverage-summary",
+                    self.rows.len(),
+                    Self::render_rows,
+                )
+                .track_scroll(self.list_scroll_handle.clone())
+                .flex_1()
+                .into_any_element()
+            })
+    }
+}
+
+impl EventEmitter<()> for CoverageSummaryView {}
+
+impl Item for CoverageSummaryView {
+    type Event = ();
+
+    fn tab_content(&self, params: TabContentParams, _: &WindowContext) -> AnyElement {
+        Label::new("Coverage")
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("coverage summary opened")
+    }
+
+    fn clone_on_split(
+        &self,
+        _: workspace::WorkspaceId,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<View<Self>>
+    where
+        Self: Sized,
+    {
+        Some(cx.new_view(|cx| Self::new(self.workspace.clone(), cx)))
+    }
+}
+
+impl FocusableView for CoverageSummaryView {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
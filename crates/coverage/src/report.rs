@@ -0,0 +1,466 @@
+use anyhow::{anyhow, Context as _, Result};
+use collections::BTreeMap;
+use std::{
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+};
+
+/// Line coverage for a set of source files, as read from one or more coverage reports.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Coverage {
+    /// Coverage for each source file, keyed by its absolute path.
+    pub files: BTreeMap<PathBuf, FileCoverage>,
+}
+
+/// Coverage for the instrumented lines of a single source file.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FileCoverage {
+    /// Coverage for each instrumented line, keyed by its zero-based row.
+    pub lines: BTreeMap<u32, LineCoverage>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LineCoverage {
+    /// The number of times the line was executed.
+    pub hits: u64,
+    /// The number of branches on this line that were taken, if the line contains any branches.
+    pub branches_covered: u32,
+    /// The number of branches on this line.
+    pub branches_total: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LineStatus {
+    Covered,
+    /// The line was executed, but not all of its branches were taken.
+    Partial,
+    Uncovered,
+}
+
+/// Totals for the instrumented lines of one or more files.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CoverageSummary {
+    pub covered: usize,
+    pub partial: usize,
+    pub uncovered: usize,
+}
+
+impl LineCoverage {
+    pub fn status(&self) -> LineStatus {
+        if self.hits == 0 {
+            LineStatus::Uncovered
+        } else if self.branches_covered < self.branches_total {
+            LineStatus::Partial
+        } else {
+            LineStatus::Covered
+        }
+    }
+
+    fn merge(&mut self, other: &LineCoverage) {
+        self.hits += other.hits;
+        self.branches_covered = self.branches_covered.max(other.branches_covered);
+        self.branches_total = self.branches_total.max(other.branches_total);
+    }
+}
+
+impl FileCoverage {
+    pub fn summary(&self) -> CoverageSummary {
+        let mut summary = CoverageSummary::default();
+        for line in self.lines.values() {
+            summary.add(line.status());
+        }
+        summary
+    }
+
+    /// Returns the ranges of adjacent instrumented rows sharing the same status.
+    pub fn row_ranges(&self) -> Vec<(RangeInclusive<u32>, LineStatus)> {
+        let mut ranges = Vec::<(RangeInclusive<u32>, LineStatus)>::new();
+        for (row, line) in &self.lines {
+            let status = line.status();
+            if let Some((range, last_status)) = ranges.last_mut() {
+                if *last_status == status && *range.end() + 1 == *row {
+                    *range = *range.start()..=*row;
+                    continue;
+                }
+            }
+            ranges.push((*row..=*row, status));
+        }
+        ranges
+    }
+
+    fn merge(&mut self, other: &FileCoverage) {
+        for (row, line) in &other.lines {
+            self.lines.entry(*row).or_default().merge(line);
+        }
+    }
+}
+
+impl CoverageSummary {
+    pub fn add(&mut self, status: LineStatus) {
+        match status {
+            LineStatus::Covered => self.covered += 1,
+            LineStatus::Partial => self.partial += 1,
+            LineStatus::Uncovered => self.uncovered += 1,
+        }
+    }
+
+    pub fn merge(&mut self, other: CoverageSummary) {
+        self.covered += other.covered;
+        self.partial += other.partial;
+        self.uncovered += other.uncovered;
+    }
+
+    pub fn total(&self) -> usize {
+        self.covered + self.partial + self.uncovered
+    }
+
+    /// The percentage of instrumented lines that were executed at least once.
+    pub fn percentage(&self) -> f32 {
+        if self.total() == 0 {
+            100.
+        } else {
+            (self.covered + self.partial) as f32 * 100. / self.total() as f32
+        }
+    }
+}
+
+impl Coverage {
+    /// Parses a coverage report in either the LCOV or Cobertura XML format. Relative source
+    /// paths are resolved against `root`.
+    pub fn parse(text: &str, root: &Path) -> Result<Self> {
+        if text.trim_start().starts_with('<') {
+            Self::parse_cobertura(text, root)
+        } else {
+            Self::parse_lcov(text, root)
+        }
+    }
+
+    pub fn parse_lcov(text: &str, root: &Path) -> Result<Self> {
+        let mut coverage = Coverage::default();
+        let mut current_file = None;
+        for (ix, line) in text.lines().enumerate() {
+            let line = line.trim();
+            let (kind, value) = line.split_once(':').unwrap_or((line, ""));
+            let result = match kind {
+                "SF" => {
+                    current_file = Some((root.join(value), FileCoverage::default()));
+                    Ok(())
+                }
+                "DA" => current_file
+                    .as_mut()
+                    .context("line data outside of a source file record")
+                    .and_then(|(_, file)| parse_lcov_line_data(value, file)),
+                "BRDA" => current_file
+                    .as_mut()
+                    .context("branch data outside of a source file record")
+                    .and_then(|(_, file)| parse_lcov_branch_data(value, file)),
+                "end_of_record" => {
+                    if let Some((path, file)) = current_file.take() {
+                        coverage.add_file(path, file);
+                    }
+                    Ok(())
+                }
+                _ => Ok(()),
+            };
+            result.with_context(|| format!("invalid LCOV record on line {}", ix + 1))?;
+        }
+        if let Some((path, file)) = current_file.take() {
+            coverage.add_file(path, file);
+        }
+        Ok(coverage)
+    }
+
+    pub fn parse_cobertura(text: &str, root: &Path) -> Result<Self> {
+        let mut coverage = Coverage::default();
+        let mut sources = Vec::new();
+        let mut current_file = None;
+        let mut in_method = false;
+
+        let mut remaining = text;
+        while let Some(tag_start) = remaining.find('<') {
+            let tag_text = &remaining[tag_start + 1..];
+            let tag_end = tag_text
+                .find('>')
+                .ok_or_else(|| anyhow!("unterminated XML tag"))?;
+            let tag = &tag_text[..tag_end];
+            remaining = &tag_text[tag_end + 1..];
+
+            if tag.starts_with('?') || tag.starts_with('!') {
+                continue;
+            }
+            if let Some(name) = tag.strip_prefix('/') {
+                match name.trim() {
+                    "method" => in_method = false,
+                    "class" => {
+                        if let Some((path, file)) = current_file.take() {
+                            coverage.add_file(path, file);
+                        }
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+
+            let name_len = tag
+                .find(|c: char| c.is_whitespace() || c == '/')
+                .unwrap_or(tag.len());
+            let attributes = &tag[name_len..];
+            match &tag[..name_len] {
+                "source" => {
+                    let text_end = remaining.find('<').unwrap_or(remaining.len());
+                    let source = unescape_xml(remaining[..text_end].trim());
+                    sources.push(root.join(source));
+                }
+                "class" => {
+                    let filename = xml_attribute(attributes, "filename")
+                        .context("class element without a filename")?;
+                    let path = sources
+                        .first()
+                        .unwrap_or(&root.to_path_buf())
+                        .join(filename);
+                    current_file = Some((path, FileCoverage::default()));
+                    if attributes.trim_end().ends_with('/') {
+                        if let Some((path, file)) = current_file.take() {
+                            coverage.add_file(path, file);
+                        }
+                    }
+                }
+                "method" => in_method = !attributes.trim_end().ends_with('/'),
+                // Lines listed within methods duplicate those listed for their class.
+                "line" if !in_method => {
+                    if let Some((_, file)) = current_file.as_mut() {
+                        parse_cobertura_line(attributes, file)?;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(coverage)
+    }
+
+    /// Adds the coverage from another report, summing the hits for lines present in both.
+    pub fn merge(&mut self, other: &Coverage) {
+        for (path, file) in &other.files {
+            self.files.entry(path.clone()).or_default().merge(file);
+        }
+    }
+
+    pub fn summary(&self) -> CoverageSummary {
+        let mut summary = CoverageSummary::default();
+        for file in self.files.values() {
+            summary.merge(file.summary());
+        }
+        summary
+    }
+
+    fn add_file(&mut self, path: PathBuf, file: FileCoverage) {
+        self.files.entry(path).or_default().merge(&file);
+    }
+}
+
+/// Parses the `<line>,<hits>[,<checksum>]` value of an LCOV `DA` record.
+fn parse_lcov_line_data(value: &str, file: &mut FileCoverage) -> Result<()> {
+    let mut fields = value.split(',');
+    let line_number = parse_line_number(fields.next())?;
+    let hits = fields
+        .next()
+        .context("missing hit count")?
+        .parse::<i64>()
+        .context("invalid hit count")?;
+    file.lines.entry(line_number).or_default().hits += hits.max(0) as u64;
+    Ok(())
+}
+
+/// Parses the `<line>,<block>,<branch>,<taken>` value of an LCOV `BRDA` record, where `taken`
+/// is `-` if the line was never executed.
+fn parse_lcov_branch_data(value: &str, file: &mut FileCoverage) -> Result<()> {
+    let mut fields = value.split(',');
+    let line_number = parse_line_number(fields.next())?;
+    let taken = fields.nth(2).context("missing branch hit count")?;
+    let line = file.lines.entry(line_number).or_default();
+    line.branches_total += 1;
+    if taken != "-" && taken.parse::<u64>().context("invalid branch hit count")? > 0 {
+        line.branches_covered += 1;
+    }
+    Ok(())
+}
+
+/// Parses the attributes of a Cobertura `<line>` element, such as
+/// `number="3" hits="1" branch="true" condition-coverage="50% (1/2)"`.
+fn parse_cobertura_line(attributes: &str, file: &mut FileCoverage) -> Result<()> {
+    let line_number = parse_line_number(xml_attribute(attributes, "number").as_deref())?;
+    let hits = xml_attribute(attributes, "hits")
+        .context("line element without hits")?
+        .parse::<u64>()
+        .context("invalid hit count")?;
+
+    let mut line = LineCoverage {
+        hits,
+        ..Default::default()
+    };
+    if xml_attribute(attributes, "branch").as_deref() == Some("true") {
+        if let Some(condition_coverage) = xml_attribute(attributes, "condition-coverage") {
+            let (covered, total) = condition_coverage
+                .split_once('(')
+                .and_then(|(_, rest)| rest.trim_end_matches(')').split_once('/'))
+                .context("invalid condition coverage")?;
+            line.branches_covered = covered
+                .trim()
+                .parse()
+                .context("invalid condition coverage")?;
+            line.branches_total = total.trim().parse().context("invalid condition coverage")?;
+        }
+    }
+
+    let existing = file.lines.entry(line_number).or_default();
+    existing.hits = existing.hits.max(line.hits);
+    existing.branches_covered = existing.branches_covered.max(line.branches_covered);
+    existing.branches_total = existing.branches_total.max(line.branches_total);
+    Ok(())
+}
+
+/// Converts a one-based line number into a zero-based row.
+fn parse_line_number(value: Option<&str>) -> Result<u32> {
+    let line_number = value
+        .context("missing line number")?
+        .trim()
+        .parse::<u32>()
+        .context("invalid line number")?;
+    Ok(line_number.saturating_sub(1))
+}
+
+fn xml_attribute(attributes: &str, name: &str) -> Option<String> {
+    let mut remaining = attributes;
+    loop {
+        let (key, rest) = remaining.split_once('=')?;
+        let rest = rest.trim_start();
+        let quote = rest.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+        let (value, rest) = rest[1..].split_once(quote)?;
+        if key.trim() == name {
+            return Some(unescape_xml(value));
+        }
+        remaining = rest;
+    }
+}
+
+fn unescape_xml(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    #[test]
+    fn test_parse_lcov() {
+        let report = indoc! {"
+            TN:
+            SF:src/lib.rs
+            DA:1,3
+            DA:2,0
+            DA:4,1
+            BRDA:4,0,0,1
+            BRDA:4,0,1,-
+            LF:3
+            LH:2
+            end_of_record
+            SF:/abs/main.rs
+            DA:1,1
+            end_of_record
+            SF:src/lib.rs
+            DA:2,2
+            end_of_record
+        "};
+        let coverage = Coverage::parse(report, Path::new("/root")).unwrap();
+        assert_eq!(
+            coverage.files.keys().collect::<Vec<_>>(),
+            [Path::new("/abs/main.rs"), Path::new("/root/src/lib.rs")]
+        );
+
+        let lib = &coverage.files[Path::new("/root/src/lib.rs")];
+        assert_eq!(
+            lib.lines.values().map(|line| line.hits).collect::<Vec<_>>(),
+            [3, 2, 1]
+        );
+        assert_eq!(
+            lib.row_ranges(),
+            [(0..=1, LineStatus::Covered), (3..=3, LineStatus::Partial)]
+        );
+        assert_eq!(
+            coverage.summary(),
+            CoverageSummary {
+                covered: 3,
+                partial: 1,
+                uncovered: 0,
+            }
+        );
+
+        assert!(Coverage::parse("SF:a.rs\nDA:x,1\n", Path::new("/root")).is_err());
+    }
+
+    #[test]
+    fn test_parse_cobertura() {
+        let report = indoc! {r#"
+            <?xml version="1.0" ?>
+            <!DOCTYPE coverage SYSTEM "http://cobertura.sourceforge.net/xml/coverage-04.dtd">
+            <coverage line-rate="0.5" branch-rate="0.5" version="1.9">
+                <sources>
+                    <source>/project</source>
+                </sources>
+                <packages>
+                    <package name="app">
+                        <classes>
+                            <class name="a.py" filename="app/a.py">
+                                <methods>
+                                    <method name="f" signature="()">
+                                        <lines>
+                                            <line number="2" hits="9"/>
+                                        </lines>
+                                    </method>
+                                </methods>
+                                <lines>
+                                    <line number="1" hits="1"/>
+                                    <line number="2" hits="0"/>
+                                    <line number="3" hits="2" branch="true" condition-coverage="50% (1/2)"/>
+                                </lines>
+                            </class>
+                            <class name="empty.py" filename="app/empty.py"/>
+                        </classes>
+                    </package>
+                </packages>
+            </coverage>
+        "#};
+        let coverage = Coverage::parse(report, Path::new("/root")).unwrap();
+        let file = &coverage.files[Path::new("/project/app/a.py")];
+        assert_eq!(
+            file.row_ranges(),
+            [
+                (0..=0, LineStatus::Covered),
+                (1..=1, LineStatus::Uncovered),
+                (2..=2, LineStatus::Partial),
+            ]
+        );
+        assert_eq!(
+            coverage.files[Path::new("/project/app/empty.py")],
+            FileCoverage::default()
+        );
+    }
+
+    #[test]
+    fn test_merge_coverage() {
+        let mut coverage = Coverage::parse("SF:a.rs\nDA:1,0\nDA:2,1\n", Path::new("/")).unwrap();
+        let other = Coverage::parse("SF:a.rs\nDA:1,2\nDA:3,0\n", Path::new("/")).unwrap();
+        coverage.merge(&other);
+
+        let summary = coverage.summary();
+        assert_eq!(summary.total(), 3);
+        assert_eq!(summary.uncovered, 1);
+        assert_eq!(format!("{:.1}", summary.percentage()), "66.7");
+    }
+}
@@ -1,3 +1,4 @@
+mod export;
 pub mod items;
 mod project_diagnostics_settings;
 mod toolbar_controls;
@@ -52,6 +53,7 @@ pub fn init(cx: &mut AppContext) {
     ProjectDiagnosticsSettings::register(cx);
     cx.observe_new_views(ProjectDiagnosticsEditor::register)
         .detach();
+    cx.observe_new_views(export::register).detach();
 }
 
 struct ProjectDiagnosticsEditor {
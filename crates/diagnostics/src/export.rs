@@ -0,0 +1,48 @@
+use std::time::Duration;
+
+use gpui::{actions, ViewContext};
+use project::DiagnosticsExportFormat;
+use workspace::{notifications::DetachAndPromptErr, Workspace};
+
+actions!(diagnostics, [ExportDiagnostics]);
+
+/// How long to wait for language servers to finish reporting diagnostics before exporting them.
+const EXPORT_TIMEOUT: Duration = Duration::from_secs(30);
+
+pub(crate) fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+    workspace.register_action(export_diagnostics);
+}
+
+/// Writes the diagnostics of every file in the project to a file, as SARIF when its name ends
+/// with `.sarif` and as JSON otherwise.
+fn export_diagnostics(
+    workspace: &mut Workspace,
+    _: &ExportDiagnostics,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let project = workspace.project().clone();
+    let fs = workspace.app_state().fs.clone();
+    let directory = project
+        .read(cx)
+        .visible_worktrees(cx)
+        .next()
+        .and_then(|worktree| Some(worktree.read(cx).as_local()?.abs_path().to_path_buf()))
+        .unwrap_or_default();
+    let abs_path = cx.prompt_for_new_path(&directory);
+
+    cx.spawn(|_, mut cx| async move {
+        let Some(abs_path) = abs_path.await? else {
+            return Ok(());
+        };
+        project
+            .update(&mut cx, |project, cx| {
+                project.diagnostics_settled(EXPORT_TIMEOUT, cx)
+            })?
+            .await;
+        let format = DiagnosticsExportFormat::for_path(&abs_path);
+        let exported =
+            project.read_with(&cx, |project, cx| project.export_diagnostics(format, cx))??;
+        fs.atomic_write(abs_path, exported).await
+    })
+    .detach_and_prompt_err("Failed to export diagnostics", cx, |_, _| None);
+}
@@ -80,6 +80,11 @@ pub struct FoldAt {
     pub buffer_row: MultiBufferRow,
 }
 
+#[derive(PartialEq, Clone, Deserialize, Default)]
+pub struct FoldAtLevel {
+    pub level: u32,
+}
+
 #[derive(PartialEq, Clone, Deserialize, Default)]
 pub struct UnfoldAt {
     pub buffer_row: MultiBufferRow,
@@ -121,6 +126,7 @@ impl_actions!(
         ConfirmCompletion,
         ExpandExcerpts,
         FoldAt,
+        FoldAtLevel,
         MoveDownByLines,
         MovePageDown,
         MovePageUp,
@@ -142,6 +148,7 @@ impl_actions!(
 gpui::actions!(
     editor,
     [
+        AcceptInlineCompletionLine,
         AcceptPartialCopilotSuggestion,
         AcceptPartialInlineCompletion,
         AddSelectionAbove,
@@ -168,6 +175,8 @@ gpui::actions!(
         CopyRelativePath,
         Cut,
         CutToEndOfLine,
+        CycleHeadingVisibility,
+        DecrementNumber,
         Delete,
         DeleteLine,
         DeleteToBeginningOfLine,
@@ -176,21 +185,30 @@ gpui::actions!(
         DeleteToNextWordEnd,
         DeleteToPreviousSubwordStart,
         DeleteToPreviousWordStart,
+        DemoteHeading,
         DisplayCursorNames,
         DuplicateLineDown,
         DuplicateLineUp,
         ExpandAllHunkDiffs,
         ExpandMacroRecursively,
+        ExportCellsAsHtml,
+        ExportCellsAsMarkdown,
         FindAllReferences,
+        FindBacklinks,
         Fold,
+        FoldAll,
+        FoldRecursive,
         FoldSelectedRanges,
         Format,
+        FormatTables,
         GoToDefinition,
         GoToDefinitionSplit,
         GoToDiagnostic,
         GoToHunk,
         GoToImplementation,
         GoToImplementationSplit,
+        GoToNextCell,
+        GoToPrevCell,
         GoToPrevDiagnostic,
         GoToPrevHunk,
         GoToTypeDefinition,
@@ -198,6 +216,7 @@ gpui::actions!(
         HalfPageDown,
         HalfPageUp,
         Hover,
+        IncrementNumber,
         Indent,
         JoinLines,
         LineDown,
@@ -231,6 +250,7 @@ gpui::actions!(
         PageUp,
         Paste,
         PreviousInlineCompletion,
+        PromoteHeading,
         Redo,
         RedoSelection,
         Rename,
@@ -238,6 +258,9 @@ gpui::actions!(
         RevealInFinder,
         ReverseLines,
         RevertSelectedHunks,
+        RunAllAbove,
+        RunAllBelow,
+        RunCell,
         ScrollCursorBottom,
         ScrollCursorCenter,
         ScrollCursorTop,
@@ -267,15 +290,19 @@ gpui::actions!(
         SplitSelectionIntoLines,
         Tab,
         TabPrev,
+        ToggleBlockComments,
+        ToggleCheckbox,
         ToggleGitBlame,
         ToggleGitBlameInline,
         ToggleHunkDiff,
         ToggleInlayHints,
         ToggleLineNumbers,
+        ToggleRedactedValues,
         ToggleSoftWrap,
         Transpose,
         Undo,
         UndoSelection,
+        UnfoldAll,
         UnfoldLines,
         UniqueLinesCaseInsensitive,
         UniqueLinesCaseSensitive,
@@ -4,6 +4,7 @@ use settings::Settings;
 use settings::SettingsStore;
 use smol::Timer;
 use std::time::Duration;
+use workspace::ActivityTracker;
 
 pub struct BlinkManager {
     blink_interval: Duration,
@@ -12,6 +13,8 @@ pub struct BlinkManager {
     blinking_paused: bool,
     visible: bool,
     enabled: bool,
+    /// Whether blinking is stopped because the app is idle or in the background.
+    throttled: bool,
 }
 
 impl BlinkManager {
@@ -22,6 +25,24 @@ impl BlinkManager {
         })
         .detach();
 
+        // Stop blinking while the app isn't being used, and resume as soon as it is
+        let throttled = ActivityTracker::global(cx).map_or(false, |tracker| {
+            cx.observe(&tracker, |this, tracker, cx| {
+                let throttled = tracker.read(cx).is_throttled();
+                if throttled != this.throttled {
+                    this.throttled = throttled;
+                    if throttled {
+                        this.show_cursor(cx);
+                    } else if this.enabled && !this.blinking_paused {
+                        this.visible = false;
+                        this.blink_cursors(this.blink_epoch, cx);
+                    }
+                }
+            })
+            .detach();
+            tracker.read(cx).is_throttled()
+        });
+
         Self {
             blink_interval,
 
@@ -29,6 +50,7 @@ impl BlinkManager {
             blinking_paused: false,
             visible: true,
             enabled: false,
+            throttled,
         }
     }
 
@@ -57,7 +79,7 @@ impl BlinkManager {
     }
 
     fn blink_cursors(&mut self, epoch: usize, cx: &mut ModelContext<Self>) {
-        if EditorSettings::get_global(cx).cursor_blink {
+        if EditorSettings::get_global(cx).cursor_blink && !self.throttled {
             if epoch == self.blink_epoch && self.enabled && !self.blinking_paused {
                 self.visible = !self.visible;
                 cx.notify();
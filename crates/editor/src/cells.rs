@@ -0,0 +1,353 @@
+//! Cells of code in ordinary source files, which start at lines with a `# %%` or `// %%` marker,
+//! as in Jupytext's percent format. Cells can be navigated between, and run in a REPL by whoever
+//! handles [`RunCell`](crate::actions::RunCell) and the other run actions.
+
+use std::{
+    cell::RefCell,
+    ops::{Range, RangeInclusive},
+    sync::Arc,
+};
+
+use gpui::{AppContext, ViewContext};
+use language::Language;
+use multi_buffer::{MultiBufferRow, MultiBufferSnapshot};
+use text::{BufferId, Edit, Point};
+use theme::ActiveTheme;
+use ui::{Color, IconName};
+
+use crate::{
+    actions::{GoToNextCell, GoToPrevCell},
+    decorations::{Decoration, DecorationProvider, DecorationStyle, GutterIcon},
+    scroll::Autoscroll,
+    Editor, EditorMode,
+};
+
+/// The markers that a cell's first line starts with, after any indentation.
+const CELL_MARKERS: &[&str] = &["# %%", "#%%", "// %%", "//%%"];
+
+/// What follows the marker of a cell of Markdown, whose lines are comments, as in
+/// `# %% [markdown]`.
+const MARKDOWN_CELL_TAG: &str = "[markdown]";
+
+/// The decorations of cell markers are registered for this type.
+pub enum CellMarkers {}
+
+/// A cell of code, which runs from its marker line to the next one. Code before the first marker
+/// is a cell without a marker.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Cell {
+    pub marker_row: Option<u32>,
+    /// The rows of the cell's code, after its marker.
+    pub rows: Range<u32>,
+}
+
+impl Cell {
+    fn start_row(&self) -> u32 {
+        self.marker_row.unwrap_or(self.rows.start)
+    }
+
+    /// The cell's code, without its marker or the blank lines at its end.
+    pub fn code(&self, buffer: &MultiBufferSnapshot) -> String {
+        let end = Point::new(self.rows.end, 0).min(buffer.max_point());
+        let start = Point::new(self.rows.start, 0).min(end);
+        let code = buffer.text_for_range(start..end).collect::<String>();
+        code.trim_end().to_string()
+    }
+
+    /// The prefix of the comments that a cell of Markdown is written in, or `None` if the cell
+    /// is code.
+    fn markdown_comment_prefix(&self, buffer: &MultiBufferSnapshot) -> Option<&'static str> {
+        let row = self.marker_row?;
+        let range = Point::new(row, 0)..Point::new(row, buffer.line_len(MultiBufferRow(row)));
+        let marker = buffer.text_for_range(range).collect::<String>();
+        let marker = marker.trim_start();
+        if !marker.contains(MARKDOWN_CELL_TAG) {
+            return None;
+        }
+        Some(if marker.starts_with("//") { "//" } else { "#" })
+    }
+
+    /// The text of a cell of Markdown, without the comment prefix of its lines, or `None` if the
+    /// cell is code.
+    pub fn markdown(&self, buffer: &MultiBufferSnapshot) -> Option<String> {
+        let prefix = self.markdown_comment_prefix(buffer)?;
+        Some(uncomment(&self.code(buffer), prefix))
+    }
+}
+
+/// The cells to run, relative to the one with the newest cursor.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CellsToRun {
+    Current,
+    /// The cells before the current one.
+    Above,
+    /// The current cell and those after it.
+    Below,
+}
+
+pub fn is_cell_marker(line: &str) -> bool {
+    let line = line.trim_start();
+    CELL_MARKERS.iter().any(|marker| line.starts_with(marker))
+}
+
+/// Whether cells can be marked in the language, which is when its line comments start the way
+/// cell markers do.
+pub fn language_has_cells(language: &Arc<Language>) -> bool {
+    language
+        .default_scope()
+        .line_comment_prefixes()
+        .iter()
+        .any(|prefix| matches!(prefix.trim_end(), "#" | "//"))
+}
+
+/// The rows in the range that are cell markers.
+fn marker_rows<'a>(
+    buffer: &'a MultiBufferSnapshot,
+    rows: RangeInclusive<u32>,
+) -> impl 'a + Iterator<Item = u32> {
+    rows.filter(|row| {
+        // Only the start of a line is needed to tell whether it's a marker.
+        let start = buffer
+            .chars_at(Point::new(*row, 0))
+            .take_while(|c| *c != '\n')
+            .skip_while(|c| c.is_whitespace())
+            .take(5)
+            .collect::<String>();
+        is_cell_marker(&start)
+    })
+}
+
+/// The cells of the buffer, or none if it has no cell markers.
+pub fn cells(buffer: &MultiBufferSnapshot) -> Vec<Cell> {
+    let max_row = buffer.max_point().row;
+    let marker_rows = marker_rows(buffer, 0..=max_row).collect::<Vec<_>>();
+    let Some(&first_marker_row) = marker_rows.first() else {
+        return Vec::new();
+    };
+
+    let mut cells = Vec::new();
+    let preamble = Cell {
+        marker_row: None,
+        rows: 0..first_marker_row,
+    };
+    if !preamble.code(buffer).trim().is_empty() {
+        cells.push(preamble);
+    }
+    for (ix, &marker_row) in marker_rows.iter().enumerate() {
+        let end = marker_rows.get(ix + 1).copied().unwrap_or(max_row + 1);
+        cells.push(Cell {
+            marker_row: Some(marker_row),
+            rows: marker_row + 1..end,
+        });
+    }
+    cells
+}
+
+/// The lines of the text, without the comment prefix they start with.
+pub fn uncomment(text: &str, prefix: &str) -> String {
+    text.lines()
+        .map(|line| {
+            let line = line.trim_start();
+            let line = line.strip_prefix(prefix).unwrap_or(line);
+            line.strip_prefix(' ').unwrap_or(line)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The index of the cell that contains the row, if it's in one.
+fn cell_at(cells: &[Cell], row: u32) -> Option<usize> {
+    cells.iter().rposition(|cell| cell.start_row() <= row)
+}
+
+/// Marks the first line of each cell with a background and an icon in the gutter. The marker
+/// rows are kept between queries, so that only the rows edited since are scanned again.
+#[derive(Default)]
+struct CellMarkerDecorations {
+    scanned: RefCell<Option<ScannedMarkers>>,
+}
+
+struct ScannedMarkers {
+    buffer_id: BufferId,
+    version: clock::Global,
+    rows: Vec<u32>,
+}
+
+impl DecorationProvider for CellMarkerDecorations {
+    fn decorations(&self, buffer: &MultiBufferSnapshot, cx: &AppContext) -> Vec<Decoration> {
+        let Some((_, buffer_id, text)) = buffer.as_singleton() else {
+            return Vec::new();
+        };
+        let mut scanned = self.scanned.borrow_mut();
+        let rows = match scanned.take() {
+            Some(scanned) if scanned.buffer_id == buffer_id => update_marker_rows(
+                &scanned.rows,
+                text.edits_since::<Point>(&scanned.version),
+                buffer,
+            ),
+            _ => marker_rows(buffer, 0..=buffer.max_point().row).collect(),
+        };
+
+        let background = cx.theme().colors().editor_subheader_background;
+        let decorations = rows
+            .iter()
+            .map(|&row| {
+                let range =
+                    Point::new(row, 0)..Point::new(row, buffer.line_len(MultiBufferRow(row)));
+                Decoration {
+                    range: buffer.anchor_before(range.start)..buffer.anchor_after(range.end),
+                    style: DecorationStyle {
+                        background: Some(background),
+                        gutter_icon: Some(GutterIcon {
+                            icon: IconName::Play,
+                            color: Color::Muted,
+                        }),
+                        ..Default::default()
+                    },
+                }
+            })
+            .collect();
+        *scanned = Some(ScannedMarkers {
+            buffer_id,
+            version: text.version().clone(),
+            rows,
+        });
+        decorations
+    }
+}
+
+/// The marker rows after the edits, given those before them. Markers outside of the edited rows
+/// are moved by the rows inserted or deleted before them, and the edited rows are scanned again.
+fn update_marker_rows(
+    old_rows: &[u32],
+    edits: impl IntoIterator<Item = Edit<Point>>,
+    buffer: &MultiBufferSnapshot,
+) -> Vec<u32> {
+    // Edits on the same row rescan it more than once, so a row is only added after the last.
+    fn push(rows: &mut Vec<u32>, row: u32) {
+        if rows.last().map_or(true, |last| row > *last) {
+            rows.push(row);
+        }
+    }
+
+    let mut rows = Vec::with_capacity(old_rows.len());
+    let mut old_rows = old_rows.iter().copied().peekable();
+    let mut row_delta = 0_i64;
+    for edit in edits {
+        while let Some(row) = old_rows.next_if(|row| *row < edit.old.start.row) {
+            push(&mut rows, (row as i64 + row_delta) as u32);
+        }
+        while old_rows.next_if(|row| *row <= edit.old.end.row).is_some() {}
+        for row in marker_rows(buffer, edit.new.start.row..=edit.new.end.row) {
+            push(&mut rows, row);
+        }
+        row_delta = edit.new.end.row as i64 - edit.old.end.row as i64;
+    }
+    for row in old_rows {
+        push(&mut rows, (row as i64 + row_delta) as u32);
+    }
+    rows
+}
+
+impl Editor {
+    /// Shows the cell markers of full editors while their buffer's language can have cells.
+    pub(crate) fn refresh_cell_marker_decorations(&mut self, cx: &mut ViewContext<Self>) {
+        let has_cells = self.mode == EditorMode::Full
+            && self
+                .buffer
+                .read(cx)
+                .as_singleton()
+                .and_then(|buffer| buffer.read(cx).language().cloned())
+                .map_or(false, |language| language_has_cells(&language));
+        if has_cells == self.decorations::<CellMarkers>().is_some() {
+            return;
+        }
+
+        if has_cells {
+            self.register_decoration_provider::<CellMarkers>(CellMarkerDecorations::default(), cx);
+        } else {
+            self.unregister_decoration_provider::<CellMarkers>(cx);
+        }
+    }
+
+    pub fn go_to_next_cell(&mut self, _: &GoToNextCell, cx: &mut ViewContext<Self>) {
+        let buffer = self.buffer.read(cx).snapshot(cx);
+        let cells = cells(&buffer);
+        let row = self.selections.newest::<Point>(cx).head().row;
+        let next = cell_at(&cells, row).map_or(0, |ix| ix + 1);
+        if let Some(cell) = cells.get(next) {
+            self.go_to_cell(cell, &buffer, cx);
+        }
+    }
+
+    pub fn go_to_prev_cell(&mut self, _: &GoToPrevCell, cx: &mut ViewContext<Self>) {
+        let buffer = self.buffer.read(cx).snapshot(cx);
+        let cells = cells(&buffer);
+        let row = self.selections.newest::<Point>(cx).head().row;
+        let prev = cell_at(&cells, row).and_then(|ix| ix.checked_sub(1));
+        if let Some(cell) = prev.and_then(|ix| cells.get(ix)) {
+            self.go_to_cell(cell, &buffer, cx);
+        }
+    }
+
+    /// Moves the cursor to the start of the cell's code.
+    fn go_to_cell(
+        &mut self,
+        cell: &Cell,
+        buffer: &MultiBufferSnapshot,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let point = Point::new(cell.rows.start, 0).min(Point::new(buffer.max_point().row, 0));
+        self.change_selections(Some(Autoscroll::fit()), cx, |s| {
+            s.select_ranges([point..point])
+        });
+    }
+
+    /// The code of the cells to run for where the newest cursor is, one cell after another, or
+    /// `None` if the buffer has no cells or there's no code in them.
+    pub fn cell_code(&self, cells_to_run: CellsToRun, cx: &AppContext) -> Option<String> {
+        let buffer = self.buffer.read(cx).snapshot(cx);
+        let cells = cells(&buffer);
+        let row = self.selections.newest::<Point>(cx).head().row;
+        let current = cell_at(&cells, row).unwrap_or(0);
+        let cells = match cells_to_run {
+            CellsToRun::Current => cells.get(current..=current)?,
+            CellsToRun::Above => &cells[..current],
+            CellsToRun::Below => &cells[current..],
+        };
+        let code = cells
+            .iter()
+            .map(|cell| cell.code(&buffer))
+            .filter(|code| !code.trim().is_empty())
+            .collect::<Vec<_>>();
+        (!code.is_empty()).then(|| code.join("\n"))
+    }
+
+    /// The buffer's cells as Markdown, with the code of each in a fenced code block, and cells
+    /// of Markdown as their text, or `None` if the buffer has no cells.
+    pub fn cells_markdown(&self, cx: &AppContext) -> Option<String> {
+        let buffer = self.buffer.read(cx).snapshot(cx);
+        let cells = cells(&buffer);
+        if cells.is_empty() {
+            return None;
+        }
+        let fence_name = buffer
+            .language_at(0)
+            .map(|language| language.code_fence_block_name().to_string())
+            .unwrap_or_default();
+        let blocks = cells
+            .iter()
+            .filter_map(|cell| {
+                let code = cell.code(&buffer);
+                if code.trim().is_empty() {
+                    return None;
+                }
+                Some(
+                    cell.markdown(&buffer)
+                        .unwrap_or_else(|| format!("```{fence_name}\n{code}\n```")),
+                )
+            })
+            .collect::<Vec<_>>();
+        Some(blocks.join("\n\n") + "\n")
+    }
+}
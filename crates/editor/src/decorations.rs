@@ -0,0 +1,265 @@
+use crate::{
+    display_map::{DisplaySnapshot, ToDisplayPoint},
+    DisplayPoint, Editor, EditorSnapshot, GutterColumn,
+};
+use gpui::{
+    AnyElement, AppContext, Hsla, IntoElement, SharedString, UnderlineStyle, ViewContext,
+    WindowContext,
+};
+use multi_buffer::{Anchor, MultiBufferRow, MultiBufferSnapshot, ToPoint};
+use std::{any::TypeId, ops::Range, sync::Arc, time::Duration};
+use ui::{Color, Icon, IconName, IconSize};
+
+/// How long to wait after the buffer changes before querying the decoration providers again.
+/// Decorations are anchored, so they follow edits in the meantime.
+pub(crate) const DECORATION_REFRESH_DEBOUNCE: Duration = Duration::from_millis(50);
+
+/// Supplies decorations for the contents of an editor, such as to mark up the results of
+/// an external tool. Providers are registered with [`Editor::register_decoration_provider`]
+/// and are queried again whenever the editor's buffer changes.
+pub trait DecorationProvider: 'static {
+    fn decorations(&self, buffer: &MultiBufferSnapshot, cx: &AppContext) -> Vec<Decoration>;
+}
+
+impl<F> DecorationProvider for F
+where
+    F: 'static + Fn(&MultiBufferSnapshot, &AppContext) -> Vec<Decoration>,
+{
+    fn decorations(&self, buffer: &MultiBufferSnapshot, cx: &AppContext) -> Vec<Decoration> {
+        self(buffer, cx)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Decoration {
+    pub range: Range<Anchor>,
+    pub style: DecorationStyle,
+}
+
+/// How a decorated range is displayed. Any combination of the decorations may be used.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DecorationStyle {
+    /// Underlines the text in the range. Underlines without a color use the text's color.
+    pub underline: Option<UnderlineStyle>,
+    /// Fills the background of the text in the range.
+    pub background: Option<Hsla>,
+    /// Shows an icon in the `decorations` gutter column on the first row of the range. Only
+    /// the first icon is shown on each row.
+    pub gutter_icon: Option<GutterIcon>,
+    /// Shows the given text inline after the range, styled like an inlay hint.
+    pub inlay: Option<SharedString>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GutterIcon {
+    pub icon: IconName,
+    pub color: Color,
+}
+
+/// The gutter column showing the icons of decorations, which is registered while any
+/// decoration has one.
+struct DecorationIconColumn {
+    /// The position of each icon, sorted by row, with at most one icon per row.
+    icons: Vec<(Anchor, GutterIcon)>,
+}
+
+impl DecorationIconColumn {
+    const NAME: &'static str = "decorations";
+}
+
+impl GutterColumn for DecorationIconColumn {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    fn width(&self) -> f32 {
+        2.
+    }
+
+    fn render_row(
+        &self,
+        row: MultiBufferRow,
+        snapshot: &EditorSnapshot,
+        _: &mut WindowContext,
+    ) -> Option<AnyElement> {
+        let buffer = &snapshot.buffer_snapshot;
+        let ix = self
+            .icons
+            .partition_point(|(position, _)| position.to_point(buffer).row < row.0);
+        let (position, icon) = self.icons.get(ix)?;
+        if position.to_point(buffer).row != row.0 {
+            return None;
+        }
+        Some(
+            Icon::new(icon.icon)
+                .size(IconSize::XSmall)
+                .color(icon.color)
+                .into_any_element(),
+        )
+    }
+}
+
+pub(crate) struct DecorationLayer {
+    provider: Box<dyn DecorationProvider>,
+    /// The provider's latest decorations, sorted by the start of their range.
+    decorations: Arc<[Decoration]>,
+}
+
+impl Editor {
+    /// Registers a provider of decorations for this editor, replacing any provider previously
+    /// registered for `T`. Inlays are shown using the inlay annotations of `T`, see
+    /// [`Editor::set_inlay_annotations`].
+    pub fn register_decoration_provider<T: 'static>(
+        &mut self,
+        provider: impl DecorationProvider,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.decoration_layers.insert(
+            TypeId::of::<T>(),
+            DecorationLayer {
+                provider: Box::new(provider),
+                decorations: Arc::from([]),
+            },
+        );
+        self.refresh_decoration_layer(TypeId::of::<T>(), cx);
+    }
+
+    pub fn unregister_decoration_provider<T: 'static>(&mut self, cx: &mut ViewContext<Self>) {
+        if self.decoration_layers.remove(&TypeId::of::<T>()).is_some() {
+            self.splice_inlay_annotations(TypeId::of::<T>(), Vec::new(), cx);
+            self.refresh_decoration_icon_column(cx);
+            cx.notify();
+        }
+    }
+
+    /// Queries the provider registered for `T` again, such as when the information it
+    /// decorates the buffer with has changed.
+    pub fn refresh_decorations<T: 'static>(&mut self, cx: &mut ViewContext<Self>) {
+        self.refresh_decoration_layer(TypeId::of::<T>(), cx);
+    }
+
+    /// The latest decorations supplied by the provider registered for `T`.
+    pub fn decorations<T: 'static>(&self) -> Option<&[Decoration]> {
+        self.decoration_layers
+            .get(&TypeId::of::<T>())
+            .map(|layer| layer.decorations.as_ref())
+    }
+
+    pub(crate) fn schedule_decorations_refresh(&mut self, cx: &mut ViewContext<Self>) {
+        if self.decoration_layers.is_empty() {
+            return;
+        }
+
+        self.refresh_decorations_task = Some(cx.spawn(|editor, mut cx| async move {
+            cx.background_executor()
+                .timer(DECORATION_REFRESH_DEBOUNCE)
+                .await;
+            editor
+                .update(&mut cx, |editor, cx| {
+                    editor.refresh_decorations_task.take();
+                    let type_ids = editor.decoration_layers.keys().copied().collect::<Vec<_>>();
+                    for type_id in type_ids {
+                        editor.refresh_decoration_layer(type_id, cx);
+                    }
+                })
+                .ok();
+        }));
+    }
+
+    fn refresh_decoration_layer(&mut self, type_id: TypeId, cx: &mut ViewContext<Self>) {
+        let buffer = self.buffer.read(cx).snapshot(cx);
+        let Some(layer) = self.decoration_layers.get_mut(&type_id) else {
+            return;
+        };
+
+        let mut decorations = layer.provider.decorations(&buffer, cx);
+        decorations.sort_by(|a, b| a.range.start.cmp(&b.range.start, &buffer));
+        if *layer.decorations == *decorations {
+            return;
+        }
+
+        let inlays = decorations
+            .iter()
+            .filter_map(|decoration| {
+                let text = decoration.style.inlay.as_ref()?;
+                Some((decoration.range.end, text.to_string()))
+            })
+            .collect();
+        layer.decorations = Arc::from(decorations);
+        self.splice_inlay_annotations(type_id, inlays, cx);
+        self.refresh_decoration_icon_column(cx);
+        cx.notify();
+    }
+
+    fn refresh_decoration_icon_column(&mut self, cx: &mut ViewContext<Self>) {
+        let buffer = self.buffer.read(cx).snapshot(cx);
+        let mut icons = self
+            .decoration_layers
+            .values()
+            .flat_map(|layer| layer.decorations.iter())
+            .filter_map(|decoration| {
+                let icon = decoration.style.gutter_icon?;
+                Some((decoration.range.start, icon))
+            })
+            .collect::<Vec<_>>();
+        if icons.is_empty() {
+            self.unregister_gutter_column(DecorationIconColumn::NAME, cx);
+            return;
+        }
+
+        icons.sort_by(|(a, _), (b, _)| a.cmp(b, &buffer));
+        icons.dedup_by_key(|(position, _)| position.to_point(&buffer).row);
+        self.register_gutter_column(DecorationIconColumn { icons }, cx);
+    }
+
+    fn decorations_in_range<'a>(
+        &'a self,
+        search_range: Range<Anchor>,
+        buffer: &'a MultiBufferSnapshot,
+    ) -> impl 'a + Iterator<Item = &'a Decoration> {
+        self.decoration_layers.values().flat_map(move |layer| {
+            let (start, end) = (search_range.start, search_range.end);
+            layer
+                .decorations
+                .iter()
+                .take_while(move |decoration| decoration.range.start.cmp(&end, buffer).is_lt())
+                .filter(move |decoration| decoration.range.end.cmp(&start, buffer).is_ge())
+        })
+    }
+
+    pub(crate) fn decoration_backgrounds_in_range(
+        &self,
+        search_range: Range<Anchor>,
+        display_snapshot: &DisplaySnapshot,
+    ) -> Vec<(Range<DisplayPoint>, Hsla)> {
+        self.decorations_in_range(search_range, &display_snapshot.buffer_snapshot)
+            .filter_map(|decoration| {
+                let background = decoration.style.background?;
+                Some((
+                    to_display_range(&decoration.range, display_snapshot),
+                    background,
+                ))
+            })
+            .collect()
+    }
+
+    pub(crate) fn decoration_underlines_in_range(
+        &self,
+        search_range: Range<Anchor>,
+        display_snapshot: &DisplaySnapshot,
+    ) -> Vec<(Range<DisplayPoint>, UnderlineStyle)> {
+        self.decorations_in_range(search_range, &display_snapshot.buffer_snapshot)
+            .filter_map(|decoration| {
+                let underline = decoration.style.underline?;
+                Some((
+                    to_display_range(&decoration.range, display_snapshot),
+                    underline,
+                ))
+            })
+            .collect()
+    }
+}
+
+fn to_display_range(range: &Range<Anchor>, snapshot: &DisplaySnapshot) -> Range<DisplayPoint> {
+    range.start.to_display_point(snapshot)..range.end.to_display_point(snapshot)
+}
@@ -39,7 +39,14 @@ use multi_buffer::{
     ToOffset, ToPoint,
 };
 use serde::Deserialize;
-use std::{any::TypeId, borrow::Cow, fmt::Debug, num::NonZeroU32, ops::Range, sync::Arc};
+use std::{
+    any::TypeId,
+    borrow::Cow,
+    fmt::Debug,
+    num::NonZeroU32,
+    ops::Range,
+    sync::{Arc, OnceLock},
+};
 use sum_tree::{Bias, TreeMap};
 use tab_map::TabMap;
 
@@ -53,7 +60,7 @@ pub use block_map::{
 use self::block_map::BlockRow;
 pub use self::fold_map::{Fold, FoldId, FoldPoint};
 pub use self::inlay_map::{InlayOffset, InlayPoint};
-pub(crate) use inlay_map::Inlay;
+pub(crate) use inlay_map::{HintDisplayOptions, Inlay};
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum FoldStatus {
@@ -61,6 +68,21 @@ pub enum FoldStatus {
     Foldable,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RegionMarker {
+    Start,
+    End,
+}
+
+/// The ranges folded by `#region` markers, keyed by the row of their start marker. They're
+/// computed at most once for each version of the buffer, when first needed.
+#[derive(Default)]
+struct RegionFoldRanges {
+    /// The buffer's edit and parse counts when the ranges were computed.
+    version: (usize, usize),
+    ranges: OnceLock<HashMap<u32, Range<Point>>>,
+}
+
 const UNNECESSARY_CODE_FADE: f32 = 0.3;
 
 pub trait ToDisplayPoint {
@@ -92,6 +114,8 @@ pub struct DisplayMap {
     text_highlights: TextHighlights,
     /// Regions of inlays that should be highlighted.
     inlay_highlights: InlayHighlights,
+    /// Ranges that can be folded by `#region` markers.
+    region_fold_ranges: Arc<RegionFoldRanges>,
     pub clip_at_line_ends: bool,
 }
 
@@ -124,12 +148,20 @@ impl DisplayMap {
             block_map,
             text_highlights: Default::default(),
             inlay_highlights: Default::default(),
+            region_fold_ranges: Default::default(),
             clip_at_line_ends: false,
         }
     }
 
     pub fn snapshot(&mut self, cx: &mut ModelContext<Self>) -> DisplaySnapshot {
         let buffer_snapshot = self.buffer.read(cx).snapshot(cx);
+        let version = (buffer_snapshot.edit_count(), buffer_snapshot.parse_count());
+        if self.region_fold_ranges.version != version {
+            self.region_fold_ranges = Arc::new(RegionFoldRanges {
+                version,
+                ranges: OnceLock::new(),
+            });
+        }
         let edits = self.buffer_subscription.consume().into_inner();
         let (inlay_snapshot, edits) = self.inlay_map.sync(buffer_snapshot, edits);
         let (fold_snapshot, edits) = self.fold_map.read(inlay_snapshot.clone(), edits);
@@ -149,6 +181,7 @@ impl DisplayMap {
             block_snapshot,
             text_highlights: self.text_highlights.clone(),
             inlay_highlights: self.inlay_highlights.clone(),
+            region_fold_ranges: self.region_fold_ranges.clone(),
             clip_at_line_ends: self.clip_at_line_ends,
         }
     }
@@ -231,6 +264,20 @@ impl DisplayMap {
         self.block_map.replace(styles);
     }
 
+    pub fn resize_blocks(&mut self, heights: HashMap<BlockId, u8>, cx: &mut ModelContext<Self>) {
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let edits = self.buffer_subscription.consume().into_inner();
+        let tab_size = Self::tab_size(&self.buffer, cx);
+        let (snapshot, edits) = self.inlay_map.sync(snapshot, edits);
+        let (snapshot, edits) = self.fold_map.read(snapshot, edits);
+        let (snapshot, edits) = self.tab_map.sync(snapshot, edits, tab_size);
+        let (snapshot, edits) = self
+            .wrap_map
+            .update(cx, |map, cx| map.sync(snapshot, edits, cx));
+        let mut block_map = self.block_map.write(snapshot, edits);
+        block_map.resize(heights);
+    }
+
     pub fn remove_blocks(&mut self, ids: HashSet<BlockId>, cx: &mut ModelContext<Self>) {
         let snapshot = self.buffer.read(cx).snapshot(cx);
         let edits = self.buffer_subscription.consume().into_inner();
@@ -374,6 +421,7 @@ pub struct DisplaySnapshot {
     block_snapshot: block_map::BlockSnapshot,
     text_highlights: TextHighlights,
     inlay_highlights: InlayHighlights,
+    region_fold_ranges: Arc<RegionFoldRanges>,
     clip_at_line_ends: bool,
 }
 
@@ -844,6 +892,94 @@ impl DisplaySnapshot {
     }
 
     pub fn is_foldable(&self, buffer_row: MultiBufferRow) -> bool {
+        self.is_indent_foldable(buffer_row)
+            || self.region_fold_range(buffer_row).is_some()
+            || self.heading_fold_range(buffer_row).is_some()
+            || self.syntax_fold_range(buffer_row).is_some()
+    }
+
+    /// Returns the range that would be folded at the given row, which is determined by
+    /// `#region` markers, then by headings, then by the brackets in the syntax tree, and finally
+    /// by indentation.
+    pub fn foldable_range(&self, buffer_row: MultiBufferRow) -> Option<Range<Point>> {
+        if self.is_line_folded(buffer_row) {
+            return None;
+        }
+
+        self.region_fold_range(buffer_row)
+            .or_else(|| self.heading_fold_range(buffer_row))
+            .or_else(|| self.syntax_fold_range(buffer_row))
+            .or_else(|| self.indent_fold_range(buffer_row))
+    }
+
+    /// Returns the level of the heading on the given row, in a language with headings like
+    /// Markdown's, where `### Heading` is at level 3. Lines in code blocks aren't headings.
+    pub fn heading_level(&self, buffer_row: MultiBufferRow) -> Option<u32> {
+        let line_start = Point::new(buffer_row.0, 0);
+        let mut chars = self.buffer_snapshot.chars_at(line_start).peekable();
+        let marker = *chars.peek()?;
+        if marker.is_alphanumeric() || marker.is_whitespace() {
+            return None;
+        }
+        let scope = self.buffer_snapshot.language_scope_at(line_start)?;
+        if scope.heading_marker() != Some(marker) || scope.override_name().is_some() {
+            return None;
+        }
+
+        let mut level = 0;
+        while chars.next_if_eq(&marker).is_some() {
+            level += 1;
+        }
+        match chars.next() {
+            None | Some(' ' | '\t' | '\n') => Some(level),
+            Some(_) => None,
+        }
+    }
+
+    /// Returns the rows of the headings, along with their levels.
+    pub fn headings(&self) -> Vec<(MultiBufferRow, u32)> {
+        (0..=self.buffer_snapshot.max_buffer_row().0)
+            .filter_map(|row| {
+                let row = MultiBufferRow(row);
+                Some((row, self.heading_level(row)?))
+            })
+            .collect()
+    }
+
+    /// Returns the range of the section below the heading on the given row, up to the row of the
+    /// heading that ends it, leaving out the blank lines before that heading.
+    pub fn heading_section_range(
+        &self,
+        heading_row: MultiBufferRow,
+        end_row: Option<MultiBufferRow>,
+    ) -> Option<Range<Point>> {
+        let end_row = end_row.map_or(self.buffer_snapshot.max_buffer_row().0, |row| row.0 - 1);
+        let last_row = (heading_row.0 + 1..=end_row)
+            .rev()
+            .find(|row| !self.buffer_snapshot.is_line_blank(MultiBufferRow(*row)))?;
+        Some(
+            Point::new(heading_row.0, self.buffer_snapshot.line_len(heading_row))
+                ..Point::new(
+                    last_row,
+                    self.buffer_snapshot.line_len(MultiBufferRow(last_row)),
+                ),
+        )
+    }
+
+    /// Returns the range of the section below the heading on the given row, which ends at the
+    /// next heading of the same or a higher level, so that subsections are folded with it.
+    fn heading_fold_range(&self, buffer_row: MultiBufferRow) -> Option<Range<Point>> {
+        let level = self.heading_level(buffer_row)?;
+        let end_row = (buffer_row.0 + 1..=self.buffer_snapshot.max_buffer_row().0)
+            .map(MultiBufferRow)
+            .find(|row| {
+                self.heading_level(*row)
+                    .map_or(false, |row_level| row_level <= level)
+            });
+        self.heading_section_range(buffer_row, end_row)
+    }
+
+    fn is_indent_foldable(&self, buffer_row: MultiBufferRow) -> bool {
         let max_row = self.buffer_snapshot.max_buffer_row();
         if buffer_row >= max_row {
             return false;
@@ -867,28 +1003,151 @@ impl DisplaySnapshot {
         false
     }
 
-    pub fn foldable_range(&self, buffer_row: MultiBufferRow) -> Option<Range<Point>> {
-        let start = MultiBufferPoint::new(buffer_row.0, self.buffer_snapshot.line_len(buffer_row));
-        if self.is_foldable(MultiBufferRow(start.row))
-            && !self.is_line_folded(MultiBufferRow(start.row))
+    fn indent_fold_range(&self, buffer_row: MultiBufferRow) -> Option<Range<Point>> {
+        if !self.is_indent_foldable(buffer_row) {
+            return None;
+        }
+
+        let start = Point::new(buffer_row.0, self.buffer_snapshot.line_len(buffer_row));
+        let (start_indent, _) = self.line_indent_for_buffer_row(buffer_row);
+        let max_point = self.buffer_snapshot.max_point();
+        let mut end = None;
+
+        for row in (buffer_row.0 + 1)..=max_point.row {
+            let (indent, is_blank) = self.line_indent_for_buffer_row(MultiBufferRow(row));
+            if !is_blank && indent <= start_indent {
+                let prev_row = row - 1;
+                end = Some(Point::new(
+                    prev_row,
+                    self.buffer_snapshot.line_len(MultiBufferRow(prev_row)),
+                ));
+                break;
+            }
+        }
+        let end = end.unwrap_or(max_point);
+        Some(start..end)
+    }
+
+    /// Returns the range between the bracket ending the given row and its matching bracket,
+    /// provided that the closing bracket starts a later line.
+    fn syntax_fold_range(&self, buffer_row: MultiBufferRow) -> Option<Range<Point>> {
+        let line_len = self.buffer_snapshot.line_len(buffer_row);
+        let line_end = self
+            .buffer_snapshot
+            .point_to_offset(Point::new(buffer_row.0, line_len));
+        let trailing_whitespace_len = self
+            .buffer_snapshot
+            .reversed_chars_at(line_end)
+            .take_while(|c| *c == ' ' || *c == '\t')
+            .count();
+        let bracket_end = line_end - trailing_whitespace_len;
+        if !matches!(
+            self.buffer_snapshot.reversed_chars_at(bracket_end).next(),
+            Some('{' | '[' | '(')
+        ) {
+            return None;
+        }
+        let bracket_start = bracket_end - 1;
+
+        let (_, close_range) = self
+            .buffer_snapshot
+            .bracket_ranges(bracket_start..bracket_end)?
+            .find(|(open_range, _)| open_range.start == bracket_start)?;
+        let close_start = close_range.start.to_point(&self.buffer_snapshot);
+        if close_start.row <= buffer_row.0 + 1 {
+            return None;
+        }
+        let (close_indent, _) = self.line_indent_for_buffer_row(MultiBufferRow(close_start.row));
+        if close_start.column != close_indent {
+            return None;
+        }
+
+        let end_row = MultiBufferRow(close_start.row - 1);
+        Some(
+            Point::new(buffer_row.0, line_len)
+                ..Point::new(end_row.0, self.buffer_snapshot.line_len(end_row)),
+        )
+    }
+
+    /// Returns the range between a `#region` marker on the given row and its matching
+    /// `#endregion` marker, including the latter.
+    fn region_fold_range(&self, buffer_row: MultiBufferRow) -> Option<Range<Point>> {
+        self.region_fold_ranges
+            .ranges
+            .get_or_init(|| self.compute_region_fold_ranges())
+            .get(&buffer_row.0)
+            .cloned()
+    }
+
+    /// Matches the `#region` and `#endregion` markers in the buffer in a single pass.
+    fn compute_region_fold_ranges(&self) -> HashMap<u32, Range<Point>> {
+        let mut ranges = HashMap::default();
+        let mut open_regions = Vec::new();
+        let mut visit_line =
+            |row: u32, line: &str| match self.region_marker(MultiBufferRow(row), line) {
+                Some(RegionMarker::Start) => open_regions.push(row),
+                Some(RegionMarker::End) => {
+                    if let Some(start_row) = open_regions.pop() {
+                        let start_len = self.buffer_snapshot.line_len(MultiBufferRow(start_row));
+                        ranges.insert(
+                            start_row,
+                            Point::new(start_row, start_len)..Point::new(row, line.len() as u32),
+                        );
+                    }
+                }
+                None => {}
+            };
+
+        let mut row = 0;
+        let mut line = String::new();
+        for chunk in self
+            .buffer_snapshot
+            .text_for_range(0..self.buffer_snapshot.len())
         {
-            let (start_indent, _) = self.line_indent_for_buffer_row(buffer_row);
-            let max_point = self.buffer_snapshot.max_point();
-            let mut end = None;
-
-            for row in (buffer_row.0 + 1)..=max_point.row {
-                let (indent, is_blank) = self.line_indent_for_buffer_row(MultiBufferRow(row));
-                if !is_blank && indent <= start_indent {
-                    let prev_row = row - 1;
-                    end = Some(Point::new(
-                        prev_row,
-                        self.buffer_snapshot.line_len(MultiBufferRow(prev_row)),
-                    ));
-                    break;
+            for (ix, part) in chunk.split('\n').enumerate() {
+                if ix > 0 {
+                    visit_line(row, &line);
+                    line.clear();
+                    row += 1;
                 }
+                line.push_str(part);
             }
-            let end = end.unwrap_or(max_point);
-            Some(start..end)
+        }
+        visit_line(row, &line);
+        ranges
+    }
+
+    fn region_marker(&self, buffer_row: MultiBufferRow, line: &str) -> Option<RegionMarker> {
+        let line_start = Point::new(buffer_row.0, 0);
+        let line = line.trim_start();
+        if !line.contains("#region") && !line.contains("#endregion") {
+            return None;
+        }
+
+        let scope = self.buffer_snapshot.language_scope_at(line_start)?;
+        let comment_prefixes = scope
+            .line_comment_prefixes()
+            .iter()
+            .map(|prefix| prefix.as_ref())
+            .chain(
+                scope
+                    .block_comment_delimiters()
+                    .map(|(start, _)| start.as_ref()),
+            );
+        let marker = comment_prefixes
+            .filter_map(|prefix| line.strip_prefix(prefix.trim_end()))
+            .map(str::trim_start)
+            .next()?;
+
+        let is_marker = |name: &str| {
+            marker.strip_prefix(name).map_or(false, |rest| {
+                rest.chars().next().map_or(true, char::is_whitespace)
+            })
+        };
+        if is_marker("#region") {
+            Some(RegionMarker::Start)
+        } else if is_marker("#endregion") {
+            Some(RegionMarker::End)
         } else {
             None
         }
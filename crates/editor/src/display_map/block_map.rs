@@ -62,7 +62,7 @@ pub struct Block {
     position: Anchor,
     height: u8,
     style: BlockStyle,
-    render: Mutex<RenderBlock>,
+    render: Arc<Mutex<RenderBlock>>,
     disposition: BlockDisposition,
 }
 
@@ -529,7 +529,7 @@ impl<'a> BlockMapWriter<'a> {
                     id,
                     position,
                     height: block.height,
-                    render: Mutex::new(block.render),
+                    render: Arc::new(Mutex::new(block.render)),
                     disposition: block.disposition,
                     style: block.style,
                 }),
@@ -574,6 +574,49 @@ impl<'a> BlockMapWriter<'a> {
         });
         self.0.sync(wrap_snapshot, edits);
     }
+
+    /// Changes the height of the given blocks, such as when their contents were measured at
+    /// a different height than they were inserted with.
+    pub fn resize(&mut self, mut heights: HashMap<BlockId, u8>) {
+        let wrap_snapshot = &*self.0.wrap_snapshot.borrow();
+        let buffer = wrap_snapshot.buffer_snapshot();
+        let mut edits = Patch::default();
+        let mut last_block_buffer_row = None;
+        for block in &mut self.0.blocks {
+            let Some(new_height) = heights.remove(&block.id) else {
+                continue;
+            };
+            if block.height == new_height {
+                continue;
+            }
+
+            *block = Arc::new(Block {
+                id: block.id,
+                position: block.position,
+                height: new_height,
+                style: block.style,
+                render: block.render.clone(),
+                disposition: block.disposition,
+            });
+
+            let buffer_row = block.position.to_point(buffer).row;
+            if last_block_buffer_row != Some(buffer_row) {
+                last_block_buffer_row = Some(buffer_row);
+                let wrap_row = wrap_snapshot
+                    .make_wrap_point(Point::new(buffer_row, 0), Bias::Left)
+                    .row();
+                let start_row = wrap_snapshot.prev_row_boundary(WrapPoint::new(wrap_row, 0));
+                let end_row = wrap_snapshot
+                    .next_row_boundary(WrapPoint::new(wrap_row, 0))
+                    .unwrap_or(wrap_snapshot.max_point().row() + 1);
+                edits.push(Edit {
+                    old: start_row..end_row,
+                    new: start_row..end_row,
+                });
+            }
+        }
+        self.0.sync(wrap_snapshot, edits);
+    }
 }
 
 impl BlockSnapshot {
@@ -953,6 +996,10 @@ impl DerefMut for BlockContext<'_, '_> {
 }
 
 impl Block {
+    pub fn id(&self) -> BlockId {
+        self.id
+    }
+
     pub fn render(&self, cx: &mut BlockContext) -> AnyElement {
         self.render.lock()(cx)
     }
@@ -1190,6 +1237,56 @@ mod tests {
         assert_eq!(snapshot.text(), "aaa\n\nb!!!\n\n\nbb\nccc\nddd\n\n\n");
     }
 
+    #[gpui::test]
+    fn test_resize_blocks(cx: &mut gpui::TestAppContext) {
+        cx.update(|cx| init_test(cx));
+
+        let text = "aaa\nbbb\nccc\nddd";
+
+        let buffer = cx.update(|cx| MultiBuffer::build_simple(text, cx));
+        let buffer_snapshot = cx.update(|cx| buffer.read(cx).snapshot(cx));
+        let (_, inlay_snapshot) = InlayMap::new(buffer_snapshot.clone());
+        let (_, fold_snapshot) = FoldMap::new(inlay_snapshot);
+        let (_, tab_snapshot) = TabMap::new(fold_snapshot, 1.try_into().unwrap());
+        let (_, wraps_snapshot) =
+            cx.update(|cx| WrapMap::new(tab_snapshot, font("Helvetica"), px(14.0), None, cx));
+        let mut block_map = BlockMap::new(wraps_snapshot.clone(), 1, 1);
+
+        let mut writer = block_map.write(wraps_snapshot.clone(), Default::default());
+        let block_ids = writer.insert(vec![
+            BlockProperties {
+                style: BlockStyle::Fixed,
+                position: buffer_snapshot.anchor_after(Point::new(1, 0)),
+                height: 1,
+                disposition: BlockDisposition::Above,
+                render: Box::new(|_| div().into_any()),
+            },
+            BlockProperties {
+                style: BlockStyle::Fixed,
+                position: buffer_snapshot.anchor_after(Point::new(3, 3)),
+                height: 1,
+                disposition: BlockDisposition::Below,
+                render: Box::new(|_| div().into_any()),
+            },
+        ]);
+        let snapshot = block_map.read(wraps_snapshot.clone(), Default::default());
+        assert_eq!(snapshot.text(), "aaa\n\nbbb\nccc\nddd\n");
+
+        let mut writer = block_map.write(wraps_snapshot.clone(), Default::default());
+        writer.resize(HashMap::from_iter([(block_ids[0], 3), (block_ids[1], 2)]));
+        let snapshot = block_map.read(wraps_snapshot.clone(), Default::default());
+        assert_eq!(snapshot.text(), "aaa\n\n\n\nbbb\nccc\nddd\n\n");
+        assert_eq!(
+            snapshot.to_block_point(WrapPoint::new(1, 0)),
+            BlockPoint::new(4, 0)
+        );
+
+        let mut writer = block_map.write(wraps_snapshot.clone(), Default::default());
+        writer.resize(HashMap::from_iter([(block_ids[0], 1)]));
+        let snapshot = block_map.read(wraps_snapshot, Default::default());
+        assert_eq!(snapshot.text(), "aaa\n\nbbb\nccc\nddd\n\n");
+    }
+
     #[gpui::test]
     fn test_blocks_on_wrapped_lines(cx: &mut gpui::TestAppContext) {
         cx.update(|cx| init_test(cx));
@@ -1,7 +1,7 @@
 use crate::{HighlightStyles, InlayId};
 use collections::{BTreeMap, BTreeSet};
 use gpui::HighlightStyle;
-use language::{Chunk, Edit, Point, TextSummary};
+use language::{language_settings::InlayHintSettings, Chunk, Edit, Point, TextSummary};
 use multi_buffer::{
     Anchor, MultiBufferChunks, MultiBufferRow, MultiBufferRows, MultiBufferSnapshot, ToOffset,
 };
@@ -46,14 +46,62 @@ pub(crate) struct Inlay {
     pub text: text::Rope,
 }
 
+/// How inlay hints are displayed, as configured in the inlay hint settings.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct HintDisplayOptions {
+    pub show_padding: bool,
+    pub max_length: Option<usize>,
+}
+
+impl Default for HintDisplayOptions {
+    fn default() -> Self {
+        Self {
+            show_padding: true,
+            max_length: None,
+        }
+    }
+}
+
+impl From<&InlayHintSettings> for HintDisplayOptions {
+    fn from(settings: &InlayHintSettings) -> Self {
+        Self {
+            show_padding: settings.show_padding,
+            max_length: settings.max_length,
+        }
+    }
+}
+
+impl HintDisplayOptions {
+    pub fn truncates(&self, hint: &project::InlayHint) -> bool {
+        self.max_length
+            .map_or(false, |max_length| hint.text().chars().count() > max_length)
+    }
+}
+
 impl Inlay {
-    pub fn hint(id: usize, position: Anchor, hint: &project::InlayHint) -> Self {
+    pub fn hint(
+        id: usize,
+        position: Anchor,
+        hint: &project::InlayHint,
+        options: HintDisplayOptions,
+    ) -> Self {
         let mut text = hint.text();
-        if hint.padding_right && !text.ends_with(' ') {
-            text.push(' ');
+        if let Some(max_length) = options.max_length {
+            if text.chars().count() > max_length {
+                text = text
+                    .chars()
+                    .take(max_length.saturating_sub(1))
+                    .chain(['…'])
+                    .collect();
+            }
         }
-        if hint.padding_left && !text.starts_with(' ') {
-            text.insert(0, ' ');
+        if options.show_padding {
+            if hint.padding_right && !text.ends_with(' ') {
+                text.push(' ');
+            }
+            if hint.padding_left && !text.starts_with(' ') {
+                text.insert(0, ' ');
+            }
         }
         Self {
             id: InlayId::Hint(id),
@@ -69,6 +117,14 @@ impl Inlay {
             text: text.into(),
         }
     }
+
+    pub fn annotation<T: Into<Rope>>(id: usize, position: Anchor, text: T) -> Self {
+        Self {
+            id: InlayId::Annotation(id),
+            position,
+            text: text.into(),
+        }
+    }
 }
 
 impl sum_tree::Item for Transform {
@@ -309,7 +365,7 @@ impl<'a> Iterator for InlayChunks<'a> {
 
                 let mut highlight_style = match inlay.id {
                     InlayId::Suggestion(_) => self.highlight_styles.suggestion,
-                    InlayId::Hint(_) => self.highlight_styles.inlay_hint,
+                    InlayId::Hint(_) | InlayId::Annotation(_) => self.highlight_styles.inlay_hint,
                 };
                 let next_inlay_highlight_endpoint;
                 let offset_in_inlay = self.output_offset - self.transforms.start().0;
@@ -1197,6 +1253,7 @@ mod tests {
                     kind: None,
                     resolve_state: ResolveState::Resolved,
                 },
+                HintDisplayOptions::default(),
             )
             .text
             .to_string(),
@@ -1217,6 +1274,7 @@ mod tests {
                     kind: None,
                     resolve_state: ResolveState::Resolved,
                 },
+                HintDisplayOptions::default(),
             )
             .text
             .to_string(),
@@ -1237,6 +1295,7 @@ mod tests {
                     kind: None,
                     resolve_state: ResolveState::Resolved,
                 },
+                HintDisplayOptions::default(),
             )
             .text
             .to_string(),
@@ -1257,6 +1316,7 @@ mod tests {
                     kind: None,
                     resolve_state: ResolveState::Resolved,
                 },
+                HintDisplayOptions::default(),
             )
             .text
             .to_string(),
@@ -1265,6 +1325,56 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_inlay_properties_display_options() {
+        let hint = InlayHint {
+            label: InlayHintLabel::String("Vec<String>".to_string()),
+            position: text::Anchor::default(),
+            padding_left: true,
+            padding_right: false,
+            tooltip: None,
+            kind: None,
+            resolve_state: ResolveState::Resolved,
+        };
+        let hint_text = |options| {
+            Inlay::hint(0, Anchor::min(), &hint, options)
+                .text
+                .to_string()
+        };
+
+        assert_eq!(
+            hint_text(HintDisplayOptions {
+                show_padding: false,
+                max_length: None,
+            }),
+            "Vec<String>",
+            "Should not pad label when padding is disabled"
+        );
+        assert_eq!(
+            hint_text(HintDisplayOptions {
+                show_padding: true,
+                max_length: Some(6),
+            }),
+            " Vec<S…",
+            "Should truncate label before padding it"
+        );
+        assert_eq!(
+            hint_text(HintDisplayOptions {
+                show_padding: true,
+                max_length: Some(11),
+            }),
+            " Vec<String>",
+            "Should not truncate label that fits"
+        );
+
+        let options = HintDisplayOptions {
+            show_padding: true,
+            max_length: Some(6),
+        };
+        assert!(options.truncates(&hint));
+        assert!(!HintDisplayOptions::default().truncates(&hint));
+    }
+
     #[gpui::test]
     fn test_basic_inlays(cx: &mut AppContext) {
         let buffer = MultiBuffer::build_simple("abcdefghi", cx);
@@ -0,0 +1,641 @@
+//! Links between the documents of languages like Markdown, where `[text](notes/todo.md#today)`
+//! and `[[todo#today]]` link to another document and one of its headings. Links are followed
+//! with cmd-click, their targets are completed, and `FindBacklinks` lists the links to a document.
+
+use crate::{Editor, FindBacklinks};
+use anyhow::Result;
+use futures::StreamExt as _;
+use gpui::{AppContext, Model, Task, ViewContext};
+use language::{Buffer, BufferSnapshot, CodeLabel, Documentation, Point, ToOffset};
+use lsp::LanguageServerId;
+use project::{
+    search::SearchQuery, Completion, File, Location, LocationLink, Project, ProjectPath,
+    SearchResult,
+};
+use std::{
+    ffi::OsStr,
+    ops::Range,
+    path::{Component, Path, PathBuf},
+    sync::Arc,
+};
+use util::{paths::PathMatcher, ResultExt};
+
+/// Stands in for a language server id on the completions of link targets.
+pub(crate) const DOCUMENT_LINK_COMPLETION_SERVER_ID: LanguageServerId =
+    LanguageServerId(usize::MAX - 1);
+
+/// Matches the parts of links that the search for backlinks looks for, which are then parsed.
+const LINK_SEARCH_PATTERN: &str = r"\[\[[^\]\n]+\]\]|\]\([^)\n]+\)";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum DocumentLinkKind {
+    /// A link like `[text](path#heading)`, whose path is relative to the document's directory.
+    Inline,
+    /// A link like `[[name#heading|text]]`, whose name is a document's path without its
+    /// extension, or just its file name.
+    Wiki,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct DocumentLink {
+    /// The range of the whole link within its line.
+    pub range: Range<usize>,
+    pub kind: DocumentLinkKind,
+    /// The path of the document that's linked to, which is empty for links to a heading of the
+    /// document they're in.
+    pub path: String,
+    pub heading: Option<String>,
+}
+
+impl Editor {
+    /// Completions for the target of the link that's being typed before the cursor: the paths
+    /// of the worktree's files or, after a `#`, the document's headings.
+    pub(crate) fn document_link_completions(
+        &self,
+        buffer: &Model<Buffer>,
+        position: text::Anchor,
+        cx: &AppContext,
+    ) -> Vec<Completion> {
+        let buffer = buffer.read(cx);
+        let snapshot = buffer.snapshot();
+        let cursor = position.to_offset(&snapshot);
+        let Some((target_start, kind)) = link_target_start(&snapshot, cursor) else {
+            return Vec::new();
+        };
+        let typed_target = snapshot
+            .text_for_range(target_start..cursor)
+            .collect::<String>();
+
+        let mut targets = Vec::new();
+        if typed_target.starts_with('#') {
+            for (_, heading) in headings(&snapshot) {
+                let target = match kind {
+                    DocumentLinkKind::Inline => format!("#{}", heading_slug(&heading)),
+                    DocumentLinkKind::Wiki => format!("#{heading}"),
+                };
+                targets.push((target, heading));
+            }
+        } else if let Some(file) = File::from_dyn(buffer.file()) {
+            let worktree = file.worktree.read(cx);
+            let dir = file.path.parent().unwrap_or(Path::new(""));
+            let path_suffixes = buffer
+                .language()
+                .map(|language| language.path_suffixes())
+                .unwrap_or_default();
+            for entry in worktree.files(false, 0) {
+                if entry.path == file.path {
+                    continue;
+                }
+                let target = match kind {
+                    DocumentLinkKind::Inline => relative_path(dir, &entry.path).replace(' ', "%20"),
+                    DocumentLinkKind::Wiki => {
+                        let is_document = entry
+                            .path
+                            .extension()
+                            .and_then(OsStr::to_str)
+                            .map_or(false, |extension| {
+                                path_suffixes.iter().any(|suffix| suffix == extension)
+                            });
+                        let Some(file_stem) = entry.path.file_stem().filter(|_| is_document) else {
+                            continue;
+                        };
+                        file_stem.to_string_lossy().into_owned()
+                    }
+                };
+                targets.push((target, entry.path.to_string_lossy().into_owned()));
+            }
+        }
+
+        let old_range = snapshot.anchor_before(target_start)..snapshot.anchor_after(cursor);
+        targets
+            .into_iter()
+            .map(|(target, documentation)| Completion {
+                old_range: old_range.clone(),
+                new_text: target.clone(),
+                label: CodeLabel::plain(target.clone(), None),
+                server_id: DOCUMENT_LINK_COMPLETION_SERVER_ID,
+                documentation: Some(Documentation::SingleLine(documentation)),
+                lsp_completion: lsp::CompletionItem {
+                    label: target,
+                    kind: Some(lsp::CompletionItemKind::REFERENCE),
+                    ..Default::default()
+                },
+            })
+            .collect()
+    }
+
+    /// Lists the links to the document in the worktree's other documents, in a multibuffer.
+    pub fn find_backlinks(
+        &mut self,
+        _: &FindBacklinks,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<Task<Result<()>>> {
+        let head = self.selections.newest_anchor().head();
+        let (buffer, head) = self.buffer.read(cx).text_anchor_for_position(head, cx)?;
+        let snapshot = buffer.read(cx).snapshot();
+        if !snapshot
+            .language_scope_at(head)
+            .map_or(false, |scope| scope.document_links())
+        {
+            return None;
+        }
+        let file = File::from_dyn(buffer.read(cx).file())?;
+        let target_path = ProjectPath {
+            worktree_id: file.worktree.read(cx).id(),
+            path: file.path.clone(),
+        };
+        let title = format!("Backlinks to {}", file.path.file_name()?.to_string_lossy());
+
+        let files_to_include = snapshot
+            .language()?
+            .path_suffixes()
+            .iter()
+            .filter_map(|suffix| PathMatcher::new(&format!("**/*.{suffix}")).log_err())
+            .collect();
+        let query = SearchQuery::regex(
+            LINK_SEARCH_PATTERN,
+            false,
+            true,
+            false,
+            files_to_include,
+            Vec::new(),
+        )
+        .log_err()?;
+
+        let replica_id = self.replica_id(cx);
+        let workspace = self.workspace()?;
+        let project = workspace.read(cx).project().clone();
+        let mut results = project.update(cx, |project, cx| project.search(query, cx));
+        Some(cx.spawn(|_, mut cx| async move {
+            let mut locations = Vec::new();
+            while let Some(result) = results.next().await {
+                let SearchResult::Buffer { buffer, ranges } = result else {
+                    continue;
+                };
+                cx.update(|cx| {
+                    let snapshot = buffer.read(cx).snapshot();
+                    let Some(file) = File::from_dyn(buffer.read(cx).file()) else {
+                        return;
+                    };
+                    for range in ranges {
+                        let Some((link_range, link)) =
+                            link_at(&snapshot, range.start.to_offset(&snapshot))
+                        else {
+                            continue;
+                        };
+                        if !link.path.is_empty()
+                            && resolve_link_path(&link, file, cx).as_ref() == Some(&target_path)
+                        {
+                            locations.push(Location {
+                                buffer: buffer.clone(),
+                                range: snapshot.anchor_after(link_range.start)
+                                    ..snapshot.anchor_before(link_range.end),
+                            });
+                        }
+                    }
+                })?;
+            }
+
+            if locations.is_empty() {
+                return Ok(());
+            }
+            workspace.update(&mut cx, |workspace, cx| {
+                Editor::open_locations_in_multibuffer(
+                    workspace, locations, replica_id, title, false, cx,
+                );
+            })
+        }))
+    }
+}
+
+/// Resolves the link at the given position, if there is one, to the start of the document or
+/// heading that it's to, opening the document's buffer.
+pub(crate) fn link_definition(
+    project: &Model<Project>,
+    buffer: &Model<Buffer>,
+    position: text::Anchor,
+    cx: &mut AppContext,
+) -> Option<Task<Result<LocationLink>>> {
+    let snapshot = buffer.read(cx).snapshot();
+    let (link_range, link) = link_at(&snapshot, position.to_offset(&snapshot))?;
+    let file = File::from_dyn(buffer.read(cx).file())?;
+    let target_path = resolve_link_path(&link, file, cx)?;
+    let open_buffer = project.update(cx, |project, cx| project.open_buffer(target_path, cx));
+    let origin = Location {
+        buffer: buffer.clone(),
+        range: snapshot.anchor_after(link_range.start)..snapshot.anchor_before(link_range.end),
+    };
+    Some(cx.spawn(|mut cx| async move {
+        let target_buffer = open_buffer.await?;
+        let target_snapshot = target_buffer.update(&mut cx, |buffer, _| buffer.snapshot())?;
+        let row = link
+            .heading
+            .as_deref()
+            .and_then(|heading| {
+                let slug = heading_slug(heading);
+                headings(&target_snapshot)
+                    .into_iter()
+                    .find(|(_, text)| heading_slug(text) == slug)
+            })
+            .map_or(0, |(row, _)| row);
+        let start = target_snapshot.anchor_before(Point::new(row, 0));
+        Ok(LocationLink {
+            origin: Some(origin),
+            target: Location {
+                buffer: target_buffer,
+                range: start..start,
+            },
+        })
+    }))
+}
+
+/// Returns the link around the given offset, with its range in the buffer.
+pub(crate) fn link_at(
+    snapshot: &BufferSnapshot,
+    offset: usize,
+) -> Option<(Range<usize>, DocumentLink)> {
+    let scope = snapshot.language_scope_at(offset)?;
+    if !scope.document_links() || scope.override_name().is_some() {
+        return None;
+    }
+
+    let row = snapshot.offset_to_point(offset).row;
+    let line_start = snapshot.point_to_offset(Point::new(row, 0));
+    let line = snapshot
+        .text_for_range(Point::new(row, 0)..Point::new(row, snapshot.line_len(row)))
+        .collect::<String>();
+    let column = offset - line_start;
+    links_in_line(&line)
+        .into_iter()
+        .find(|link| link.range.contains(&column))
+        .map(|link| {
+            (
+                line_start + link.range.start..line_start + link.range.end,
+                link,
+            )
+        })
+}
+
+/// Returns where the target of the link that's being typed before the given offset starts.
+fn link_target_start(
+    snapshot: &BufferSnapshot,
+    offset: usize,
+) -> Option<(usize, DocumentLinkKind)> {
+    let scope = snapshot.language_scope_at(offset)?;
+    if !scope.document_links() || scope.override_name().is_some() {
+        return None;
+    }
+
+    let line_start = snapshot.point_to_offset(Point::new(snapshot.offset_to_point(offset).row, 0));
+    let before = snapshot
+        .text_for_range(line_start..offset)
+        .collect::<String>();
+    let wiki = before
+        .rfind("[[")
+        .filter(|ix| !before[*ix..].contains("]]"))
+        .map(|ix| (ix + 2, DocumentLinkKind::Wiki));
+    let inline = before
+        .rfind("](")
+        .filter(|ix| !before[*ix..].contains(')'))
+        .map(|ix| (ix + 2, DocumentLinkKind::Inline));
+    let (start, kind) = match (wiki, inline) {
+        (Some(wiki), Some(inline)) => {
+            if wiki.0 > inline.0 {
+                wiki
+            } else {
+                inline
+            }
+        }
+        (wiki, inline) => wiki.or(inline)?,
+    };
+    Some((line_start + start, kind))
+}
+
+/// Resolves the path of the document that a link in the given file is to.
+fn resolve_link_path(link: &DocumentLink, file: &File, cx: &AppContext) -> Option<ProjectPath> {
+    let worktree = file.worktree.read(cx);
+    let project_path = |path: PathBuf| ProjectPath {
+        worktree_id: worktree.id(),
+        path: Arc::from(path),
+    };
+    if link.path.is_empty() {
+        return Some(project_path(file.path.to_path_buf()));
+    }
+
+    let is_file = |path: &Path| {
+        worktree
+            .entry_for_path(path)
+            .map_or(false, |entry| entry.is_file())
+    };
+    let dir = file.path.parent().unwrap_or(Path::new(""));
+    let path = match link.path.strip_prefix('/') {
+        Some(path_from_root) => normalize_path(Path::new(path_from_root)),
+        None => normalize_path(&dir.join(&link.path)),
+    };
+    if let Some(path) = path.filter(|path| is_file(path)) {
+        return Some(project_path(path));
+    }
+
+    // Wiki-links leave out the extension, and can name a document in any directory.
+    if link.kind == DocumentLinkKind::Wiki {
+        let extension = file.path.extension()?.to_string_lossy();
+        let file_path = PathBuf::from(format!("{}.{extension}", link.path));
+        if let Some(path) = normalize_path(&dir.join(&file_path)).filter(|path| is_file(path)) {
+            return Some(project_path(path));
+        }
+        let entry = worktree
+            .files(false, 0)
+            .find(|entry| entry.path.ends_with(&file_path))?;
+        return Some(project_path(entry.path.to_path_buf()));
+    }
+
+    None
+}
+
+/// Returns the links in a line of a document.
+fn links_in_line(line: &str) -> Vec<DocumentLink> {
+    let mut links = Vec::new();
+    let mut ix = 0;
+    while let Some(start) = line[ix..].find('[').map(|offset| ix + offset) {
+        let link = if line[start..].starts_with("[[") {
+            wiki_link(line, start)
+        } else {
+            inline_link(line, start)
+        };
+        match link {
+            Some(link) => {
+                ix = link.range.end;
+                links.push(link);
+            }
+            None => ix = start + 1,
+        }
+    }
+    links
+}
+
+fn wiki_link(line: &str, start: usize) -> Option<DocumentLink> {
+    let content_start = start + 2;
+    let content_end = content_start + line[content_start..].find("]]")?;
+    let content = &line[content_start..content_end];
+    if content.contains('[') {
+        return None;
+    }
+    let target = content.split('|').next()?.trim();
+    if target.is_empty() {
+        return None;
+    }
+
+    let (path, heading) = split_heading(target);
+    Some(DocumentLink {
+        range: start..content_end + 2,
+        kind: DocumentLinkKind::Wiki,
+        path: path.to_string(),
+        heading: heading.map(str::to_string),
+    })
+}
+
+fn inline_link(line: &str, start: usize) -> Option<DocumentLink> {
+    // The text of the link can contain brackets, as long as they're balanced.
+    let mut depth = 0;
+    let mut text_end = None;
+    for (offset, c) in line[start..].char_indices() {
+        match c {
+            '[' => depth += 1,
+            ']' => {
+                depth -= 1;
+                if depth == 0 {
+                    text_end = Some(start + offset);
+                    break;
+                }
+            }
+            _ => {}
+        }
+    }
+    let text_end = text_end?;
+    if !line[text_end..].starts_with("](") {
+        return None;
+    }
+
+    // The destination can be in angle brackets, so that it can contain spaces and parentheses,
+    // and can be followed by a title.
+    let destination_start = text_end + 2;
+    let destination = &line[destination_start..];
+    let (target, destination_end) = match destination.strip_prefix('<') {
+        Some(angled_destination) => {
+            let target_len = angled_destination.find('>')?;
+            let target_end = destination_start + 1 + target_len;
+            (
+                &angled_destination[..target_len],
+                target_end + line[target_end..].find(')')?,
+            )
+        }
+        None => {
+            let destination_len = destination.find(')')?;
+            (
+                destination[..destination_len].split_whitespace().next()?,
+                destination_start + destination_len,
+            )
+        }
+    };
+    // Links to URLs are followed as those.
+    if target.contains("://") || target.starts_with("mailto:") {
+        return None;
+    }
+
+    let (path, heading) = split_heading(target);
+    Some(DocumentLink {
+        range: start..destination_end + 1,
+        kind: DocumentLinkKind::Inline,
+        path: percent_decode(path),
+        heading: heading.map(percent_decode),
+    })
+}
+
+fn split_heading(target: &str) -> (&str, Option<&str>) {
+    match target.split_once('#') {
+        Some((path, heading)) => (path, Some(heading).filter(|heading| !heading.is_empty())),
+        None => (target, None),
+    }
+}
+
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut ix = 0;
+    while ix < bytes.len() {
+        if bytes[ix] == b'%' {
+            if let Some(byte) = text
+                .get(ix + 1..ix + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            {
+                decoded.push(byte);
+                ix += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[ix]);
+        ix += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Turns the text of a heading into the anchor that links to it, the way GitHub does: in lower
+/// case, with hyphens for spaces and without other punctuation.
+pub(crate) fn heading_slug(text: &str) -> String {
+    text.trim()
+        .chars()
+        .filter_map(|c| match c {
+            ' ' => Some('-'),
+            c if c.is_alphanumeric() || c == '-' || c == '_' => Some(c),
+            _ => None,
+        })
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Returns the rows of the document's headings, along with their text.
+fn headings(snapshot: &BufferSnapshot) -> Vec<(u32, String)> {
+    let mut headings = Vec::new();
+    for row in 0..=snapshot.max_point().row {
+        let line_start = Point::new(row, 0);
+        let mut chars = snapshot.chars_at(line_start);
+        let Some(marker) = chars.next() else {
+            continue;
+        };
+        if marker.is_alphanumeric() || marker.is_whitespace() {
+            continue;
+        }
+        let Some(scope) = snapshot.language_scope_at(line_start) else {
+            continue;
+        };
+        if scope.heading_marker() != Some(marker) || scope.override_name().is_some() {
+            continue;
+        }
+
+        let line = snapshot
+            .text_for_range(line_start..Point::new(row, snapshot.line_len(row)))
+            .collect::<String>();
+        let text = line.trim_start_matches(marker);
+        if !text.starts_with(char::is_whitespace) {
+            continue;
+        }
+        let text = text.trim().trim_end_matches(marker).trim();
+        headings.push((row, text.to_string()));
+    }
+    headings
+}
+
+/// Resolves the `.` and `..` components of a path within a worktree, returning `None` if it
+/// leaves the worktree.
+fn normalize_path(path: &Path) -> Option<PathBuf> {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    return None;
+                }
+            }
+            Component::Normal(name) => normalized.push(name),
+            Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    Some(normalized)
+}
+
+/// Returns the path that links from a document in one directory to another file, which is
+/// always separated by slashes.
+fn relative_path(from_dir: &Path, to: &Path) -> String {
+    let common_len = from_dir
+        .components()
+        .zip(to.components())
+        .take_while(|(a, b)| a == b)
+        .count();
+    from_dir
+        .components()
+        .skip(common_len)
+        .map(|_| "..".to_string())
+        .chain(
+            to.components()
+                .skip(common_len)
+                .map(|component| component.as_os_str().to_string_lossy().into_owned()),
+        )
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_links_in_line() {
+        let line =
+            "See [the plan](../plans/Q3%20plan.md#next-steps \"Plan\"), [[Todo#Today|today]] \
+            and [this section](#usage), but not [the site](https://zed.dev) or [[]].";
+        let links = links_in_line(line);
+        assert_eq!(
+            links
+                .iter()
+                .map(|link| (
+                    &line[link.range.clone()],
+                    link.kind,
+                    link.path.as_str(),
+                    link.heading.as_deref()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "[the plan](../plans/Q3%20plan.md#next-steps \"Plan\")",
+                    DocumentLinkKind::Inline,
+                    "../plans/Q3 plan.md",
+                    Some("next-steps"),
+                ),
+                (
+                    "[[Todo#Today|today]]",
+                    DocumentLinkKind::Wiki,
+                    "Todo",
+                    Some("Today"),
+                ),
+                (
+                    "[this section](#usage)",
+                    DocumentLinkKind::Inline,
+                    "",
+                    Some("usage"),
+                ),
+            ]
+        );
+
+        let links = links_in_line("![a [nested] image](<images/a (1).png>)");
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].path, "images/a (1).png");
+    }
+
+    #[test]
+    fn test_heading_slug() {
+        assert_eq!(heading_slug("Next Steps"), "next-steps");
+        assert_eq!(heading_slug(" What's new in v1.2? "), "whats-new-in-v12");
+        assert_eq!(
+            heading_slug("snake_case and kebab-case"),
+            "snake_case-and-kebab-case"
+        );
+    }
+
+    #[test]
+    fn test_paths() {
+        assert_eq!(
+            normalize_path(Path::new("notes/daily/../plans/./q3.md")),
+            Some(PathBuf::from("notes/plans/q3.md"))
+        );
+        assert_eq!(normalize_path(Path::new("../outside.md")), None);
+        assert_eq!(
+            relative_path(Path::new("notes/daily"), Path::new("notes/plans/q3.md")),
+            "../plans/q3.md"
+        );
+        assert_eq!(
+            relative_path(Path::new(""), Path::new("notes/todo.md")),
+            "notes/todo.md"
+        );
+    }
+}
@@ -15,19 +15,26 @@
 pub mod actions;
 mod blame_entry_tooltip;
 mod blink_manager;
+pub mod cells;
 pub mod display_map;
 mod editor_settings;
 mod element;
+mod event_stream;
 mod hunk_diff;
+pub mod increment;
 mod inlay_hint_cache;
 
 mod debounced_delay;
+mod decorations;
+mod document_links;
 mod git;
+mod gutter_columns;
 mod highlight_matching_bracket;
 mod hover_links;
 mod hover_popover;
 mod inline_completion_provider;
 pub mod items;
+mod linked_tags;
 mod mouse_context_menu;
 pub mod movement;
 mod persistence;
@@ -35,6 +42,8 @@ mod rust_analyzer_ext;
 pub mod scroll;
 mod selections_collection;
 pub mod tasks;
+mod view_blocks;
+mod word_completions;
 
 #[cfg(test)]
 mod editor_tests;
@@ -51,6 +60,8 @@ use clock::ReplicaId;
 use collections::{BTreeMap, Bound, HashMap, HashSet, VecDeque};
 use convert_case::{Case, Casing};
 use debounced_delay::DebouncedDelay;
+use decorations::DecorationLayer;
+pub use decorations::{Decoration, DecorationProvider, DecorationStyle, GutterIcon};
 pub use display_map::DisplayPoint;
 use display_map::*;
 use editor_settings::CurrentLineHighlight;
@@ -59,6 +70,7 @@ use element::LineWithInvisibles;
 pub use element::{
     CursorLayout, EditorElement, HighlightedRange, HighlightedRangeLine, PointForPosition,
 };
+pub use event_stream::{EditorEventStream, EditorStreamEvent, StreamBuffer, StreamEdit};
 use futures::FutureExt;
 use fuzzy::{StringMatch, StringMatchCandidate};
 use git::blame::GitBlame;
@@ -68,28 +80,30 @@ use gpui::{
     AppContext, AsyncWindowContext, AvailableSpace, BackgroundExecutor, Bounds, ClipboardItem,
     Context, DispatchPhase, ElementId, EventEmitter, FocusHandle, FocusableView, FontId, FontStyle,
     FontWeight, HighlightStyle, Hsla, InteractiveText, KeyContext, Model, MouseButton, PaintQuad,
-    ParentElement, Pixels, Render, SharedString, Size, StrikethroughStyle, Styled, StyledText,
-    Subscription, Task, TextStyle, UnderlineStyle, UniformListScrollHandle, View, ViewContext,
-    ViewInputHandler, VisualContext, WeakView, WhiteSpace, WindowContext,
+    ParentElement, Pixels, PromptLevel, Render, SharedString, Size, StrikethroughStyle, Styled,
+    StyledText, Subscription, Task, TextStyle, UnderlineStyle, UniformListScrollHandle, View,
+    ViewContext, ViewInputHandler, VisualContext, WeakView, WhiteSpace, WindowContext,
 };
+pub use gutter_columns::GutterColumn;
 use highlight_matching_bracket::refresh_matching_bracket_highlights;
 use hover_popover::{hide_hover, HoverState};
 use hunk_diff::ExpandedHunks;
 pub(crate) use hunk_diff::HunkToExpand;
 use inlay_hint_cache::{InlayHintCache, InlaySplice, InvalidationStrategy};
 pub use inline_completion_provider::*;
-pub use items::MAX_TAB_TITLE_LEN;
+pub use items::{recently_edited_files, RecentlyEditedFile, MAX_TAB_TITLE_LEN};
 use itertools::Itertools;
 use language::{
     char_kind,
     language_settings::{self, all_language_settings, InlayHintSettings},
     markdown, point_from_lsp, AutoindentMode, BracketPair, Buffer, Capability, CharKind, CodeLabel,
-    CursorShape, Diagnostic, Documentation, IndentKind, IndentSize, Language, OffsetRangeExt,
-    Point, Selection, SelectionGoal, TransactionId,
+    CursorShape, Diagnostic, Documentation, IndentKind, IndentSize, Language, LanguageScope,
+    OffsetRangeExt, Point, Selection, SelectionGoal, TransactionId,
 };
 use language::{BufferRow, Runnable, RunnableRange};
 use task::{ResolvedTask, TaskTemplate, TaskVariables};
 
+use document_links::DOCUMENT_LINK_COMPLETION_SERVER_ID;
 use hover_links::{HoverLink, HoveredLinkState, InlayHighlight};
 use lsp::{DiagnosticSeverity, LanguageServerId};
 use mouse_context_menu::MouseContextMenu;
@@ -138,6 +152,9 @@ use ui::{
     Tooltip,
 };
 use util::{defer, maybe, post_inc, RangeExt, ResultExt, TryFutureExt};
+pub use view_blocks::ViewBlock;
+use view_blocks::ViewBlockState;
+use word_completions::WORD_COMPLETION_SERVER_ID;
 use workspace::item::{ItemHandle, PreviewTabsSettings};
 use workspace::notifications::{DetachAndPromptErr, NotificationId};
 use workspace::{
@@ -227,6 +244,7 @@ pub fn render_parsed_markdown(
 pub(crate) enum InlayId {
     Suggestion(usize),
     Hint(usize),
+    Annotation(usize),
 }
 
 impl InlayId {
@@ -234,6 +252,7 @@ impl InlayId {
         match self {
             Self::Suggestion(id) => *id,
             Self::Hint(id) => *id,
+            Self::Annotation(id) => *id,
         }
     }
 }
@@ -255,6 +274,7 @@ pub fn init_settings(cx: &mut AppContext) {
 
 pub fn init(cx: &mut AppContext) {
     init_settings(cx);
+    EditorEventStream::init(cx);
 
     workspace::register_project_item::<Editor>(cx);
     workspace::register_followable_item::<Editor>(cx);
@@ -449,11 +469,20 @@ pub struct Editor {
     show_breadcrumbs: bool,
     show_gutter: bool,
     show_wrap_guides: Option<bool>,
+    /// Whether the values of private files are shown, even though they're redacted.
+    private_values_revealed: bool,
     placeholder_text: Option<Arc<str>>,
     highlight_order: usize,
     highlighted_rows: HashMap<TypeId, Vec<RowHighlight>>,
     background_highlights: TreeMap<TypeId, BackgroundHighlight>,
+    inlay_annotations: HashMap<TypeId, Vec<InlayId>>,
+    decoration_layers: BTreeMap<TypeId, DecorationLayer>,
+    refresh_decorations_task: Option<Task<()>>,
+    view_blocks: HashMap<BlockId, ViewBlockState>,
+    gutter_columns: Vec<Arc<dyn GutterColumn>>,
+    streamed_buffer_versions: HashMap<BufferId, clock::Global>,
     scrollbar_marker_state: ScrollbarMarkerState,
+    heading_visibility: HeadingVisibility,
     nav_history: Option<ItemNavHistory>,
     context_menu: RwLock<Option<ContextMenu>>,
     mouse_context_menu: Option<MouseContextMenu>,
@@ -471,6 +500,14 @@ pub struct Editor {
     collapse_matches: bool,
     autoindent_mode: Option<AutoindentMode>,
     workspace: Option<(WeakView<Workspace>, WorkspaceId)>,
+    serialize_file_state_task: Option<Task<()>>,
+    record_edit_task: Option<Task<()>>,
+    /// The version of the buffer when the editor's pending transaction started, which the edits
+    /// it makes are found from.
+    transaction_start_version: Option<clock::Global>,
+    /// Where the editor last edited its buffer, which is recorded as where its file was last
+    /// edited. Edits made elsewhere, like reloads or collaborators' edits, don't change it.
+    last_edit: Option<text::Anchor>,
     keymap_context_layers: BTreeMap<TypeId, KeyContext>,
     input_enabled: bool,
     use_modal_editing: bool,
@@ -480,9 +517,12 @@ pub struct Editor {
     hover_state: HoverState,
     gutter_hovered: bool,
     hovered_link_state: Option<HoveredLinkState>,
-    inline_completion_provider: Option<RegisteredInlineCompletionProvider>,
+    inline_completion_providers: Vec<RegisteredInlineCompletionProvider>,
     active_inline_completion: Option<Inlay>,
+    active_inline_completion_provider: Option<Arc<dyn InlineCompletionProviderHandle>>,
     show_inline_completions: bool,
+    /// Labels shown in the headers of buffers' excerpts, in place of their files' paths.
+    buffer_header_labels: HashMap<BufferId, SharedString>,
     inlay_hint_cache: InlayHintCache,
     expanded_hunks: ExpandedHunks,
     next_inlay_id: usize,
@@ -508,6 +548,7 @@ pub struct Editor {
     >,
     last_bounds: Option<Bounds<Pixels>>,
     expect_bounds_change: Option<Bounds<Pixels>>,
+    last_search: Option<items::LastSearch>,
     tasks: HashMap<(BufferId, BufferRow), (usize, RunnableTasks)>,
     tasks_update_task: Option<Task<()>>,
 }
@@ -517,6 +558,8 @@ pub struct EditorSnapshot {
     pub mode: EditorMode,
     show_gutter: bool,
     render_git_blame_gutter: bool,
+    /// The total width of the visible gutter columns, in multiples of the em width.
+    gutter_columns_width: f32,
     pub display_snapshot: DisplaySnapshot,
     pub placeholder_text: Option<Arc<str>>,
     is_focused: bool,
@@ -534,6 +577,8 @@ pub struct GutterDimensions {
     pub width: Pixels,
     pub margin: Pixels,
     pub git_blame_entries_width: Option<Pixels>,
+    /// The width of the columns registered with [`Editor::register_gutter_column`].
+    pub columns_width: Pixels,
 }
 
 impl Default for GutterDimensions {
@@ -544,6 +589,7 @@ impl Default for GutterDimensions {
             width: Pixels::ZERO,
             margin: Pixels::ZERO,
             git_blame_entries_width: None,
+            columns_width: Pixels::ZERO,
         }
     }
 }
@@ -573,6 +619,16 @@ enum SelectionHistoryMode {
     Redoing,
 }
 
+/// What's shown of a document with headings, which `CycleHeadingVisibility` steps through.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum HeadingVisibility {
+    /// Only the top-level headings, with everything below them folded.
+    TopLevel,
+    /// All the headings, with the text below each folded.
+    Headings,
+    All,
+}
+
 #[derive(Clone, PartialEq, Eq, Hash)]
 struct HoveredCursor {
     replica_id: u16,
@@ -1632,11 +1688,19 @@ impl Editor {
             show_breadcrumbs: EditorSettings::get_global(cx).toolbar.breadcrumbs,
             show_gutter: mode == EditorMode::Full,
             show_wrap_guides: None,
+            private_values_revealed: false,
             placeholder_text: None,
             highlight_order: 0,
             highlighted_rows: HashMap::default(),
             background_highlights: Default::default(),
+            inlay_annotations: HashMap::default(),
+            decoration_layers: BTreeMap::default(),
+            refresh_decorations_task: None,
+            view_blocks: HashMap::default(),
+            gutter_columns: Vec::new(),
+            streamed_buffer_versions: HashMap::default(),
             scrollbar_marker_state: ScrollbarMarkerState::default(),
+            heading_visibility: HeadingVisibility::All,
             nav_history: None,
             context_menu: RwLock::new(None),
             mouse_context_menu: None,
@@ -1655,6 +1719,10 @@ impl Editor {
             autoindent_mode: Some(AutoindentMode::EachLine),
             collapse_matches: false,
             workspace: None,
+            serialize_file_state_task: None,
+            record_edit_task: None,
+            transaction_start_version: None,
+            last_edit: None,
             keymap_context_layers: Default::default(),
             input_enabled: true,
             use_modal_editing: mode == EditorMode::Full,
@@ -1665,14 +1733,16 @@ impl Editor {
             remote_id: None,
             hover_state: Default::default(),
             hovered_link_state: Default::default(),
-            inline_completion_provider: None,
+            inline_completion_providers: Vec::new(),
             active_inline_completion: None,
+            active_inline_completion_provider: None,
             inlay_hint_cache: InlayHintCache::new(inlay_hint_settings),
             expanded_hunks: ExpandedHunks::default(),
             gutter_hovered: false,
             pixel_position_of_newest_cursor: None,
             last_bounds: None,
             expect_bounds_change: None,
+            last_search: None,
             gutter_dimensions: GutterDimensions::default(),
             style: None,
             show_cursor_names: false,
@@ -1680,6 +1750,7 @@ impl Editor {
             editor_actions: Default::default(),
             vim_replace_map: Default::default(),
             show_inline_completions: mode == EditorMode::Full,
+            buffer_header_labels: HashMap::default(),
             custom_context_menu: None,
             show_git_blame_gutter: false,
             show_git_blame_inline: false,
@@ -1723,9 +1794,12 @@ impl Editor {
                 this.git_blame_inline_enabled = true;
                 this.start_git_blame_inline(false, cx);
             }
+
+            this.refresh_cell_marker_decorations(cx);
         }
 
         this.report_editor_event("open", None, cx);
+        this.stream_buffer_events(cx);
         this
     }
 
@@ -1867,6 +1941,11 @@ impl Editor {
             mode: self.mode,
             show_gutter: self.show_gutter,
             render_git_blame_gutter: self.render_git_blame_gutter(cx),
+            gutter_columns_width: self
+                .visible_gutter_columns(cx)
+                .iter()
+                .map(|column| column.width())
+                .sum(),
             display_snapshot: self.display_map.update(cx, |map, cx| map.snapshot(cx)),
             scroll_anchor: self.scroll_manager.anchor(),
             ongoing_scroll: self.scroll_manager.ongoing_scroll(),
@@ -1921,6 +2000,7 @@ impl Editor {
         self.completion_provider = Some(hub);
     }
 
+    /// Replaces all of the editor's inline completion providers with the given one.
     pub fn set_inline_completion_provider<T>(
         &mut self,
         provider: Option<Model<T>>,
@@ -1928,8 +2008,29 @@ impl Editor {
     ) where
         T: InlineCompletionProvider,
     {
-        self.inline_completion_provider =
-            provider.map(|provider| RegisteredInlineCompletionProvider {
+        self.clear_inline_completion_providers(cx);
+        if let Some(provider) = provider {
+            self.register_inline_completion_provider(provider, cx);
+        }
+    }
+
+    pub fn clear_inline_completion_providers(&mut self, cx: &mut ViewContext<Self>) {
+        self.discard_inline_completion(false, cx);
+        self.inline_completion_providers.clear();
+    }
+
+    /// Adds an inline completion provider, replacing any provider with the same name.
+    pub fn register_inline_completion_provider<T>(
+        &mut self,
+        provider: Model<T>,
+        cx: &mut ViewContext<Self>,
+    ) where
+        T: InlineCompletionProvider,
+    {
+        self.inline_completion_providers
+            .retain(|registered| registered.provider.name() != T::name());
+        self.inline_completion_providers
+            .push(RegisteredInlineCompletionProvider {
                 _subscription: cx.observe(&provider, |this, _, cx| {
                     if this.focus_handle.is_focused(cx) {
                         this.update_visible_inline_completion(cx);
@@ -1940,6 +2041,31 @@ impl Editor {
         self.refresh_inline_completion(false, cx);
     }
 
+    pub fn unregister_inline_completion_provider(
+        &mut self,
+        name: &str,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(ix) = self
+            .inline_completion_providers
+            .iter()
+            .position(|registered| registered.provider.name() == name)
+        else {
+            return;
+        };
+        let registered = self.inline_completion_providers.remove(ix);
+        registered.provider.discard(false, cx);
+        if self
+            .active_inline_completion_provider
+            .as_ref()
+            .map_or(false, |provider| provider.name() == name)
+        {
+            self.take_active_inline_completion(cx);
+            self.active_inline_completion_provider = None;
+        }
+        self.update_visible_inline_completion(cx);
+    }
+
     pub fn placeholder_text(&self, _cx: &mut WindowContext) -> Option<&str> {
         self.placeholder_text.as_deref()
     }
@@ -2030,6 +2156,24 @@ impl Editor {
         self.show_inline_completions = show_inline_completions;
     }
 
+    /// Shows the label in the headers of the buffer's excerpts, instead of the buffer's path.
+    pub fn set_buffer_header_label(
+        &mut self,
+        buffer_id: BufferId,
+        label: Option<SharedString>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        match label {
+            Some(label) => self.buffer_header_labels.insert(buffer_id, label),
+            None => self.buffer_header_labels.remove(&buffer_id),
+        };
+        cx.notify();
+    }
+
+    pub(crate) fn buffer_header_label(&self, buffer_id: BufferId) -> Option<SharedString> {
+        self.buffer_header_labels.get(&buffer_id).cloned()
+    }
+
     pub fn set_use_modal_editing(&mut self, to: bool) {
         self.use_modal_editing = to;
     }
@@ -2172,6 +2316,7 @@ impl Editor {
         }
 
         self.blink_manager.update(cx, BlinkManager::pause_blinking);
+        self.stream_selections_changed(cx);
         cx.emit(EditorEvent::SelectionsChanged { local });
 
         if self.selections.disjoint_anchors().len() == 1 {
@@ -2664,6 +2809,18 @@ impl Editor {
         for (selection, autoclose_region) in
             self.selections_with_autoclose_regions(selections, &snapshot)
         {
+            let mut closing_tag = None;
+            if selection.is_empty()
+                && text.as_ref() == ">"
+                && self.use_autoclose
+                && snapshot.settings_at(selection.start, cx).use_autoclose
+            {
+                closing_tag = linked_tags::closing_tag_for_input(
+                    &snapshot,
+                    selection.start.to_offset(&snapshot),
+                );
+            }
+
             if let Some(scope) = snapshot.language_scope_at(selection.head()) {
                 // Determine if the inserted text matches the opening or closing
                 // bracket of any of this language's bracket pairs.
@@ -2745,6 +2902,12 @@ impl Editor {
                                 let anchor = snapshot.anchor_after(selection.end);
                                 new_selections
                                     .push((selection.map(|_| anchor), region.pair.end.len()));
+                                // If skipping the closing bracket completes an opening tag,
+                                // then insert the corresponding closing tag after it.
+                                if let Some(closing_tag) = closing_tag {
+                                    let end = selection.end + Point::new(0, text.len() as u32);
+                                    edits.push((end..end, closing_tag.into()));
+                                }
                                 continue;
                             }
                         }
@@ -2829,6 +2992,15 @@ impl Editor {
                 }
             }
 
+            // If the inserted text completes an opening tag, then insert the
+            // corresponding closing tag after it.
+            if let Some(closing_tag) = closing_tag {
+                let anchor = snapshot.anchor_before(selection.end);
+                new_selections.push((selection.map(|_| anchor), text.len()));
+                edits.push((selection.range(), format!("{text}{closing_tag}").into()));
+                continue;
+            }
+
             // If not handling any auto-close operation, then just replace the selected
             // text with the given input and move the selection to the end of the
             // newly inserted text.
@@ -2837,6 +3009,18 @@ impl Editor {
             edits.push((selection.start..selection.end, text.clone()));
         }
 
+        // Keep the names of matching tags in sync when editing either of them.
+        let linked_edits = linked_tags::linked_tag_edits(&snapshot, &edits);
+        if !linked_edits.is_empty() {
+            edits.extend(linked_edits.into_iter().map(|(range, text)| {
+                (
+                    range.start.to_point(&snapshot)..range.end.to_point(&snapshot),
+                    text,
+                )
+            }));
+            edits.sort_by_key(|(range, _)| range.start);
+        }
+
         drop(snapshot);
         self.transact(cx, |this, cx| {
             this.buffer.update(cx, |buffer, cx| {
@@ -2964,6 +3148,10 @@ impl Editor {
                 selections
                     .iter()
                     .map(|selection| {
+                        // Block comment continuations are aligned with the comment's opening
+                        // delimiter, and list continuations with the list item's markers, which
+                        // auto-indentation would undo.
+                        let mut keeps_indent = false;
                         let start_point = selection.start.to_point(&buffer);
                         let mut indent =
                             buffer.indent_size_for_line(MultiBufferRow(start_point.row));
@@ -3011,7 +3199,7 @@ impl Editor {
                                     return None;
                                 }
 
-                                if !multi_buffer.settings_at(0, cx).extend_comment_on_newline {
+                                if !buffer.settings_at(start, cx).extend_comment_on_newline {
                                     return None;
                                 }
 
@@ -3044,12 +3232,57 @@ impl Editor {
                                 } else {
                                     None
                                 }
+                            })
+                            .or_else(|| {
+                                if !selection_is_empty
+                                    || !buffer.settings_at(start, cx).extend_comment_on_newline
+                                {
+                                    return None;
+                                }
+                                let continuation =
+                                    block_comment_continuation(&buffer, start_point, language)?;
+                                keeps_indent = true;
+                                Some(continuation)
                             });
                             (comment_delimiter, insert_extra_newline)
                         } else {
                             (None, false)
                         };
 
+                        // Continue Markdown list items and block quotes, or end them when typing
+                        // a newline in an empty one.
+                        let mut list_markers_start = None;
+                        let comment_delimiter = comment_delimiter.or_else(|| {
+                            if !selection_is_empty || !language_scope.as_ref()?.continue_lists() {
+                                return None;
+                            }
+                            let row = MultiBufferRow(start_point.row);
+                            let text_before_cursor = buffer
+                                .text_for_range(Point::new(row.0, 0)..start_point)
+                                .collect::<String>();
+                            let markers = markdown::line_markers(&text_before_cursor)?;
+                            keeps_indent = true;
+                            let line_end = Point::new(row.0, buffer.line_len(row));
+                            if text_before_cursor[markers.len..].trim().is_empty()
+                                && buffer
+                                    .text_for_range(start_point..line_end)
+                                    .all(|chunk| chunk.trim().is_empty())
+                            {
+                                list_markers_start =
+                                    Some(buffer.point_to_offset(Point::new(row.0, indent.len)));
+                                None
+                            } else {
+                                Some(markers.continuation.into())
+                            }
+                        });
+                        if let Some(markers_start) = list_markers_start {
+                            let anchor = buffer.anchor_after(end);
+                            return (
+                                ((markers_start..end, String::new()), keeps_indent),
+                                (false, selection.map(|_| anchor)),
+                            );
+                        }
+
                         let capacity_for_delimiter = comment_delimiter
                             .as_deref()
                             .map(str::len)
@@ -3068,14 +3301,31 @@ impl Editor {
                         let anchor = buffer.anchor_after(end);
                         let new_selection = selection.map(|_| anchor);
                         (
-                            (start..end, new_text),
+                            ((start..end, new_text), keeps_indent),
                             (insert_extra_newline, new_selection),
                         )
                     })
                     .unzip()
             };
 
-            this.edit_with_autoindent(edits, cx);
+            // The edits that keep their indentation are made first, since a later edit would
+            // cancel the auto-indentation, so the others are anchored to still apply to the
+            // right ranges after them.
+            let (unindented_edits, indented_edits): (Vec<_>, Vec<_>) = edits
+                .into_iter()
+                .partition(|(_, keeps_indent)| *keeps_indent);
+            let buffer = this.buffer.read(cx).snapshot(cx);
+            let indented_edits = indented_edits
+                .into_iter()
+                .map(|((range, text), _)| {
+                    (
+                        buffer.anchor_before(range.start)..buffer.anchor_after(range.end),
+                        text,
+                    )
+                })
+                .collect::<Vec<_>>();
+            this.edit(unindented_edits.into_iter().map(|(edit, _)| edit), cx);
+            this.edit_with_autoindent(indented_edits, cx);
             let buffer = this.buffer.read(cx).snapshot(cx);
             let new_selections = selection_fixup_info
                 .into_iter()
@@ -3215,13 +3465,20 @@ impl Editor {
         let autoindent = text.is_empty().not().then(|| AutoindentMode::Block {
             original_indent_columns: Vec::new(),
         });
-        self.insert_with_autoindent_mode(text, autoindent, cx);
+        self.insert_with_autoindent_mode(text, autoindent, false, cx);
+    }
+
+    /// Deletes the selected text in response to the user typing, keeping the names of
+    /// matching tags in sync.
+    fn delete_typed(&mut self, cx: &mut ViewContext<Self>) {
+        self.insert_with_autoindent_mode("", None, true, cx);
     }
 
     fn insert_with_autoindent_mode(
         &mut self,
         text: &str,
         autoindent_mode: Option<AutoindentMode>,
+        edit_linked_tags: bool,
         cx: &mut ViewContext<Self>,
     ) {
         if self.read_only(cx) {
@@ -3232,23 +3489,32 @@ impl Editor {
         self.transact(cx, |this, cx| {
             let old_selections = this.selections.all_adjusted(cx);
             let selection_anchors = this.buffer.update(cx, |buffer, cx| {
-                let anchors = {
+                let (anchors, edits) = {
                     let snapshot = buffer.read(cx);
-                    old_selections
+                    let anchors = old_selections
                         .iter()
                         .map(|s| {
                             let anchor = snapshot.anchor_after(s.head());
                             s.map(|_| anchor)
                         })
-                        .collect::<Vec<_>>()
-                };
-                buffer.edit(
-                    old_selections
+                        .collect::<Vec<_>>();
+                    let mut edits = old_selections
                         .iter()
-                        .map(|s| (s.start..s.end, text.clone())),
-                    autoindent_mode,
-                    cx,
-                );
+                        .map(|s| {
+                            (
+                                s.start.to_offset(&snapshot)..s.end.to_offset(&snapshot),
+                                text.clone(),
+                            )
+                        })
+                        .collect::<Vec<_>>();
+                    if edit_linked_tags {
+                        let linked_edits = linked_tags::linked_tag_edits(&snapshot, &edits);
+                        edits.extend(linked_edits);
+                        edits.sort_by_key(|(range, _)| range.start);
+                    }
+                    (anchors, edits)
+                };
+                buffer.edit(edits, autoindent_mode, cx);
                 anchors
             });
 
@@ -3661,11 +3927,23 @@ impl Editor {
 
         let query = Self::completion_query(&self.buffer.read(cx).read(cx), position);
         let completions = provider.completions(&buffer, buffer_position, cx);
+        let link_completions = self.document_link_completions(&buffer, buffer_position, cx);
 
         let id = post_inc(&mut self.next_completion_id);
         let task = cx.spawn(|this, mut cx| {
             async move {
-                let completions = completions.await.log_err();
+                let mut completions = completions.await.log_err();
+                if !link_completions.is_empty() {
+                    let mut all_completions = link_completions;
+                    all_completions.extend(completions.unwrap_or_default());
+                    completions = Some(all_completions);
+                }
+                if query.is_some() && completions.as_ref().map_or(true, |c| c.is_empty()) {
+                    let word_completions = this.update(&mut cx, |editor, cx| {
+                        editor.word_completions(&buffer, buffer_position, cx)
+                    })?;
+                    completions = Some(word_completions.await);
+                }
                 let menu = if let Some(completions) = completions {
                     let mut menu = CompletionsMenu {
                         id,
@@ -3804,53 +4082,70 @@ impl Editor {
             return None;
         }
 
+        // The completion replaces the text before and after the newest cursor that the language
+        // server asked to replace, and the same text around each other cursor. Where a cursor
+        // doesn't have that text before it, the completion replaces the word it's typing instead.
         let lookbehind = newest_selection
             .start
             .text_anchor
             .to_offset(buffer)
-            .saturating_sub(old_range.start);
+            .saturating_sub(old_range.start)
+            .min(old_text.len());
         let lookahead = old_range
             .end
-            .saturating_sub(newest_selection.end.text_anchor.to_offset(buffer));
-        let mut common_prefix_len = old_text
-            .bytes()
-            .zip(text.bytes())
-            .take_while(|(a, b)| a == b)
-            .count();
+            .saturating_sub(newest_selection.end.text_anchor.to_offset(buffer))
+            .min(old_text.len() - lookbehind);
+        let prefix = &old_text[..lookbehind];
+        let suffix = &old_text[old_text.len() - lookahead..];
 
         let snapshot = self.buffer.read(cx).snapshot(cx);
-        let mut range_to_replace: Option<Range<isize>> = None;
-        let mut ranges = Vec::new();
+        let mut ranges = Vec::<Range<usize>>::with_capacity(selections.len());
         for selection in &selections {
-            if snapshot.contains_str_at(selection.start.saturating_sub(lookbehind), &old_text) {
-                let start = selection.start.saturating_sub(lookbehind);
-                let end = selection.end + lookahead;
-                if selection.id == newest_selection.id {
-                    range_to_replace = Some(
-                        ((start + common_prefix_len) as isize - selection.start as isize)
-                            ..(end as isize - selection.start as isize),
-                    );
+            let start = if selection.start >= lookbehind
+                && snapshot.contains_str_at(selection.start - lookbehind, prefix)
+            {
+                selection.start - lookbehind
+            } else {
+                match snapshot.surrounding_word(selection.start) {
+                    (word_range, Some(CharKind::Word)) => word_range.start.min(selection.start),
+                    _ => selection.start,
                 }
-                ranges.push(start + common_prefix_len..end);
+            };
+            let end = if snapshot.contains_str_at(selection.end, suffix) {
+                selection.end + suffix.len()
             } else {
-                common_prefix_len = 0;
-                ranges.clear();
-                ranges.extend(selections.iter().map(|s| {
-                    if s.id == newest_selection.id {
-                        range_to_replace = Some(
-                            old_range.start.to_offset_utf16(&snapshot).0 as isize
-                                - selection.start as isize
-                                ..old_range.end.to_offset_utf16(&snapshot).0 as isize
-                                    - selection.start as isize,
-                        );
-                        old_range.clone()
-                    } else {
-                        s.start..s.end
-                    }
-                }));
-                break;
-            }
+                selection.end
+            };
+            // Don't overlap the range replaced at the previous cursor.
+            let previous_end = ranges.last().map_or(0, |range| range.end);
+            ranges.push(start.max(previous_end)..end.max(previous_end));
         }
+
+        // Leave the text that each range already starts with in place.
+        let common_prefix_len = ranges
+            .iter()
+            .map(|range| {
+                snapshot
+                    .text_for_range(range.clone())
+                    .flat_map(str::chars)
+                    .zip(text.chars())
+                    .take_while(|(a, b)| a == b)
+                    .map(|(c, _)| c.len_utf8())
+                    .sum::<usize>()
+            })
+            .min()
+            .unwrap_or(0);
+        for range in &mut ranges {
+            range.start += common_prefix_len;
+        }
+        let range_to_replace = selections
+            .iter()
+            .zip(&ranges)
+            .find(|(selection, _)| selection.id == newest_selection.id)
+            .map(|(selection, range)| {
+                (range.start as isize - selection.start as isize)
+                    ..(range.end as isize - selection.start as isize)
+            });
         let text = &text[common_prefix_len..];
 
         cx.emit(EditorEvent::InputHandled {
@@ -3880,6 +4175,14 @@ impl Editor {
             this.refresh_inline_completion(true, cx);
         });
 
+        if completion.server_id == WORD_COMPLETION_SERVER_ID {
+            word_completions::record_word_completion(&completion.new_text, cx);
+            return Some(Task::ready(Ok(())));
+        }
+        if completion.server_id == DOCUMENT_LINK_COMPLETION_SERVER_ID {
+            return Some(Task::ready(Ok(())));
+        }
+
         let provider = self.completion_provider.as_ref()?;
         let apply_edits = provider.apply_additional_edits_for_completion(
             buffer_handle,
@@ -4345,19 +4648,23 @@ impl Editor {
         debounce: bool,
         cx: &mut ViewContext<Self>,
     ) -> Option<()> {
-        let provider = self.inline_completion_provider()?;
         let cursor = self.selections.newest_anchor().head();
         let (buffer, cursor_buffer_position) =
             self.buffer.read(cx).text_anchor_for_position(cursor, cx)?;
-        if !self.show_inline_completions
-            || !provider.is_enabled(&buffer, cursor_buffer_position, cx)
-        {
+        let providers = self
+            .inline_completion_providers(cx)
+            .into_iter()
+            .filter(|provider| provider.is_enabled(&buffer, cursor_buffer_position, cx))
+            .collect::<Vec<_>>();
+        if !self.show_inline_completions || providers.is_empty() {
             self.discard_inline_completion(false, cx);
             return None;
         }
 
         self.update_visible_inline_completion(cx);
-        provider.refresh(buffer, cursor_buffer_position, debounce, cx);
+        for provider in providers {
+            provider.refresh(buffer.clone(), cursor_buffer_position, debounce, cx);
+        }
         Some(())
     }
 
@@ -4366,7 +4673,10 @@ impl Editor {
         direction: Direction,
         cx: &mut ViewContext<Self>,
     ) -> Option<()> {
-        let provider = self.inline_completion_provider()?;
+        let provider = self
+            .active_inline_completion_provider
+            .clone()
+            .or_else(|| self.inline_completion_providers(cx).into_iter().next())?;
         let cursor = self.selections.newest_anchor().head();
         let (buffer, cursor_buffer_position) =
             self.buffer.read(cx).text_anchor_for_position(cursor, cx)?;
@@ -4437,7 +4747,7 @@ impl Editor {
 
     fn accept_inline_completion(&mut self, cx: &mut ViewContext<Self>) -> bool {
         if let Some(completion) = self.take_active_inline_completion(cx) {
-            if let Some(provider) = self.inline_completion_provider() {
+            if let Some(provider) = self.active_inline_completion_provider.take() {
                 provider.accept(cx);
             }
 
@@ -4445,7 +4755,7 @@ impl Editor {
                 utf16_range_to_replace: None,
                 text: completion.text.to_string().into(),
             });
-            self.insert_with_autoindent_mode(&completion.text.to_string(), None, cx);
+            self.insert_with_autoindent_mode(&completion.text.to_string(), None, false, cx);
             self.refresh_inline_completion(true, cx);
             cx.notify();
             true
@@ -4480,21 +4790,58 @@ impl Editor {
                     utf16_range_to_replace: None,
                     text: partial_completion.clone().into(),
                 });
-                self.insert_with_autoindent_mode(&partial_completion, None, cx);
+                self.insert_with_autoindent_mode(&partial_completion, None, false, cx);
                 self.refresh_inline_completion(true, cx);
                 cx.notify();
             }
         }
     }
 
+    pub fn accept_inline_completion_line(
+        &mut self,
+        _: &AcceptInlineCompletionLine,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if self.selections.count() != 1 || !self.has_active_inline_completion(cx) {
+            return;
+        }
+        let Some(completion) = self.active_inline_completion.as_ref() else {
+            return;
+        };
+        let text = completion.text.to_string();
+        // A completion starting on a new line is accepted up to the end of that line.
+        let line_end = text
+            .char_indices()
+            .skip_while(|(_, c)| *c == '\n')
+            .find(|(_, c)| *c == '\n')
+            .map(|(ix, _)| ix);
+        let Some(line_end) = line_end else {
+            self.accept_inline_completion(cx);
+            return;
+        };
+
+        self.take_active_inline_completion(cx);
+        let partial_completion = &text[..line_end];
+        cx.emit(EditorEvent::InputHandled {
+            utf16_range_to_replace: None,
+            text: partial_completion.to_string().into(),
+        });
+        self.insert_with_autoindent_mode(partial_completion, None, false, cx);
+        self.refresh_inline_completion(true, cx);
+        cx.notify();
+    }
+
     fn discard_inline_completion(
         &mut self,
         should_report_inline_completion_event: bool,
         cx: &mut ViewContext<Self>,
     ) -> bool {
-        if let Some(provider) = self.inline_completion_provider() {
-            provider.discard(should_report_inline_completion_event, cx);
+        for registered in &self.inline_completion_providers {
+            registered
+                .provider
+                .discard(should_report_inline_completion_event, cx);
         }
+        self.active_inline_completion_provider = None;
 
         self.take_active_inline_completion(cx).is_some()
     }
@@ -4530,10 +4877,11 @@ impl Editor {
             && self.completion_tasks.is_empty()
             && selection.start == selection.end
         {
-            if let Some(provider) = self.inline_completion_provider() {
-                if let Some((buffer, cursor_buffer_position)) =
-                    self.buffer.read(cx).text_anchor_for_position(cursor, cx)
-                {
+            if let Some((buffer, cursor_buffer_position)) =
+                self.buffer.read(cx).text_anchor_for_position(cursor, cx)
+            {
+                // Show the suggestion of the highest-priority provider that has one.
+                for provider in self.inline_completion_providers(cx) {
                     if let Some(text) =
                         provider.active_completion_text(&buffer, cursor_buffer_position, cx)
                     {
@@ -4546,6 +4894,7 @@ impl Editor {
                         let completion_inlay =
                             Inlay::suggestion(post_inc(&mut self.next_inlay_id), cursor, text);
                         self.active_inline_completion = Some(completion_inlay.clone());
+                        self.active_inline_completion_provider = Some(provider);
                         self.display_map.update(cx, move |map, cx| {
                             map.splice_inlays(to_remove, vec![completion_inlay], cx)
                         });
@@ -4559,8 +4908,27 @@ impl Editor {
         self.discard_inline_completion(false, cx);
     }
 
-    fn inline_completion_provider(&self) -> Option<Arc<dyn InlineCompletionProviderHandle>> {
-        Some(self.inline_completion_provider.as_ref()?.provider.clone())
+    /// The registered inline completion providers that aren't disabled in the settings,
+    /// highest priority first.
+    fn inline_completion_providers(
+        &self,
+        cx: &AppContext,
+    ) -> Vec<Arc<dyn InlineCompletionProviderHandle>> {
+        let settings = &all_language_settings(None, cx).inline_completions;
+        let mut providers = self
+            .inline_completion_providers
+            .iter()
+            .map(|registered| registered.provider.clone())
+            .filter(|provider| {
+                settings
+                    .providers
+                    .get(provider.name())
+                    .and_then(|provider_settings| provider_settings.enabled)
+                    != Some(false)
+            })
+            .collect::<Vec<_>>();
+        providers.sort_by_key(|provider| Reverse(settings.provider_priority(provider.name())));
+        providers
     }
 
     fn render_code_actions_indicator(
@@ -4711,6 +5079,15 @@ impl Editor {
         }
 
         let tabstops = self.buffer.update(cx, |buffer, cx| {
+            // The starts of the insertions are anchored, so that the tabstops are found at each
+            // one even after the ones before it have been reindented.
+            let insertion_starts = {
+                let snapshot = buffer.read(cx);
+                insertion_ranges
+                    .iter()
+                    .map(|range| snapshot.anchor_before(range.start))
+                    .collect::<Vec<_>>()
+            };
             let snippet_text: Arc<str> = snippet.text.clone().into();
             buffer.edit(
                 insertion_ranges
@@ -4733,12 +5110,8 @@ impl Editor {
                     let mut tabstop_ranges = tabstop
                         .iter()
                         .flat_map(|tabstop_range| {
-                            let mut delta = 0_isize;
-                            insertion_ranges.iter().map(move |insertion_range| {
-                                let insertion_start = insertion_range.start as isize + delta;
-                                delta +=
-                                    snippet.text.len() as isize - insertion_range.len() as isize;
-
+                            insertion_starts.iter().map(move |insertion_start| {
+                                let insertion_start = insertion_start.to_offset(snapshot) as isize;
                                 let start = ((insertion_start + tabstop_range.start) as usize)
                                     .min(snapshot.len());
                                 let end = ((insertion_start + tabstop_range.end) as usize)
@@ -4906,7 +5279,7 @@ impl Editor {
             }
 
             this.change_selections(Some(Autoscroll::fit()), cx, |s| s.select(selections));
-            this.insert("", cx);
+            this.delete_typed(cx);
             this.refresh_inline_completion(true, cx);
         });
     }
@@ -4924,7 +5297,7 @@ impl Editor {
                     }
                 })
             });
-            this.insert("", cx);
+            this.delete_typed(cx);
             this.refresh_inline_completion(true, cx);
         });
     }
@@ -5377,6 +5750,69 @@ impl Editor {
         })
     }
 
+    /// Removes every selected line for which `predicate` returns false, as a single transaction.
+    /// When no selection is non-empty, the whole buffer is filtered.
+    pub fn retain_lines(
+        &mut self,
+        mut predicate: impl FnMut(&str) -> bool,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.transact(cx, |this, cx| {
+            if this
+                .selections
+                .all::<Point>(cx)
+                .iter()
+                .all(|selection| selection.is_empty())
+            {
+                this.select_all(&SelectAll, cx);
+            }
+            this.manipulate_lines(cx, |lines| lines.retain(|line| predicate(line)));
+        });
+    }
+
+    /// Returns how many of the lines that [`Editor::retain_lines`] would consider satisfy `predicate`,
+    /// along with the total number of lines considered.
+    pub fn count_retained_lines(
+        &mut self,
+        mut predicate: impl FnMut(&str) -> bool,
+        cx: &mut ViewContext<Self>,
+    ) -> (usize, usize) {
+        let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
+        let buffer = self.buffer.read(cx).snapshot(cx);
+
+        let mut selections = self.selections.all::<Point>(cx);
+        if selections.iter().all(|selection| selection.is_empty()) {
+            selections.truncate(1);
+            selections[0].start = Point::zero();
+            selections[0].end = buffer.max_point();
+        }
+
+        let mut selections = selections.iter().peekable();
+        let mut contiguous_row_selections = Vec::new();
+        let mut retained = 0;
+        let mut total = 0;
+        while let Some(selection) = selections.next() {
+            let (start_row, end_row) = consume_contiguous_rows(
+                &mut contiguous_row_selections,
+                selection,
+                &display_map,
+                &mut selections,
+            );
+            for row in start_row.0..end_row.0 {
+                let line_start = Point::new(row, 0);
+                let line_end = Point::new(row, buffer.line_len(MultiBufferRow(row)));
+                let line = buffer
+                    .text_for_range(line_start..line_end)
+                    .collect::<String>();
+                total += 1;
+                if predicate(&line) {
+                    retained += 1;
+                }
+            }
+        }
+        (retained, total)
+    }
+
     pub fn revert_selected_hunks(&mut self, _: &RevertSelectedHunks, cx: &mut ViewContext<Self>) {
         let revert_changes = self.gather_revert_changes(&self.selections.disjoint_anchors(), cx);
         if !revert_changes.is_empty() {
@@ -5507,24 +5943,46 @@ impl Editor {
             callback(&mut lines);
             let lines_after = lines.len();
 
-            edits.push((start_point..end_point, lines.join("\n")));
+            // When every line is removed, remove the rows themselves along with one of their
+            // newlines, rather than leaving an empty row behind.
+            let rows_after = if lines.is_empty() {
+                if end_row.0 <= buffer.max_point().row {
+                    edits.push((start_point..Point::new(end_row.0, 0), String::new()));
+                    0
+                } else if start_row.0 > 0 {
+                    let previous_row = start_row.previous_row();
+                    let previous_line_end =
+                        Point::new(previous_row.0, buffer.line_len(previous_row));
+                    edits.push((previous_line_end..end_point, String::new()));
+                    0
+                } else {
+                    edits.push((start_point..end_point, String::new()));
+                    1
+                }
+            } else {
+                edits.push((start_point..end_point, lines.join("\n")));
+                lines_after
+            };
 
             // Selections must change based on added and removed line count
             let start_row =
                 MultiBufferRow(start_point.row + added_lines as u32 - removed_lines as u32);
             let end_row = MultiBufferRow(start_row.0 + lines_after.saturating_sub(1) as u32);
-            new_selections.push(Selection {
-                id: selection.id,
-                start: start_row,
-                end: end_row,
-                goal: SelectionGoal::None,
-                reversed: selection.reversed,
-            });
+            new_selections.push((
+                Selection {
+                    id: selection.id,
+                    start: start_row,
+                    end: end_row,
+                    goal: SelectionGoal::None,
+                    reversed: selection.reversed,
+                },
+                lines.is_empty(),
+            ));
 
-            if lines_after > lines_before {
-                added_lines += lines_after - lines_before;
-            } else if lines_before > lines_after {
-                removed_lines += lines_before - lines_after;
+            if rows_after > lines_before {
+                added_lines += rows_after - lines_before;
+            } else if lines_before > rows_after {
+                removed_lines += lines_before - rows_after;
             }
         }
 
@@ -5537,9 +5995,13 @@ impl Editor {
             // Recalculate offsets on newly edited buffer
             let new_selections = new_selections
                 .iter()
-                .map(|s| {
-                    let start_point = Point::new(s.start.0, 0);
-                    let end_point = Point::new(s.end.0, buffer.line_len(s.end));
+                .map(|(s, emptied)| {
+                    let start_point = Point::new(s.start.0, 0).min(buffer.max_point());
+                    let end_point = if *emptied {
+                        start_point
+                    } else {
+                        Point::new(s.end.0, buffer.line_len(s.end))
+                    };
                     Selection {
                         id: s.id,
                         start: buffer.point_to_offset(start_point),
@@ -5991,85 +6453,109 @@ impl Editor {
     }
 
     pub fn cut(&mut self, _: &Cut, cx: &mut ViewContext<Self>) {
-        let mut text = String::new();
+        let (item, ranges) = self.clipboard_item_for_selections(cx);
         let buffer = self.buffer.read(cx).snapshot(cx);
-        let mut selections = self.selections.all::<Point>(cx);
-        let mut clipboard_selections = Vec::with_capacity(selections.len());
-        {
-            let max_point = buffer.max_point();
-            let mut is_first = true;
-            for selection in &mut selections {
-                let is_entire_line = selection.is_empty() || self.selections.line_mode;
-                if is_entire_line {
-                    selection.start = Point::new(selection.start.row, 0);
-                    selection.end = cmp::min(max_point, Point::new(selection.end.row + 1, 0));
-                    selection.goal = SelectionGoal::None;
-                }
-                if is_first {
-                    is_first = false;
-                } else {
-                    text += "\n";
-                }
-                let mut len = 0;
-                for chunk in buffer.text_for_range(selection.start..selection.end) {
-                    text.push_str(chunk);
-                    len += chunk.len();
-                }
-                clipboard_selections.push(ClipboardSelection {
-                    len,
-                    is_entire_line,
-                    first_line_indent: buffer
-                        .indent_size_for_line(MultiBufferRow(selection.start.row))
-                        .len,
+        let anchor_ranges = ranges
+            .iter()
+            .map(|range| buffer.anchor_after(range.start)..buffer.anchor_before(range.end))
+            .collect::<Vec<_>>();
+        self.confirm_copying_redacted_values(
+            &ranges,
+            move |this, cx| {
+                this.transact(cx, |this, cx| {
+                    this.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                        s.select_anchor_ranges(anchor_ranges);
+                    });
+                    this.insert("", cx);
+                    cx.write_to_clipboard(item);
                 });
-            }
+            },
+            cx,
+        );
+    }
+
+    pub fn copy(&mut self, _: &Copy, cx: &mut ViewContext<Self>) {
+        let (item, ranges) = self.clipboard_item_for_selections(cx);
+        self.confirm_copying_redacted_values(&ranges, move |_, cx| cx.write_to_clipboard(item), cx);
+    }
+
+    /// Runs `copy` right away if the ranges contain no redacted values, or otherwise once the
+    /// user confirms that they want them copied. Whatever is copied should be read from the
+    /// buffer before calling this, since the buffer may change while the user is asked.
+    pub fn confirm_copying_redacted_values(
+        &mut self,
+        ranges: &[Range<Point>],
+        copy: impl 'static + FnOnce(&mut Self, &mut ViewContext<Self>),
+        cx: &mut ViewContext<Self>,
+    ) {
+        if !self.ranges_contain_redacted_values(ranges, cx) {
+            copy(self, cx);
+            return;
         }
 
-        self.transact(cx, |this, cx| {
-            this.change_selections(Some(Autoscroll::fit()), cx, |s| {
-                s.select(selections);
-            });
-            this.insert("", cx);
-            cx.write_to_clipboard(ClipboardItem::new(text).with_metadata(clipboard_selections));
-        });
+        let answer = cx.prompt(
+            PromptLevel::Warning,
+            "Copy private values?",
+            Some("The selection contains values that are hidden because the file is private."),
+            &["Copy", "Cancel"],
+        );
+        cx.spawn(|editor, mut cx| async move {
+            if answer.await == Ok(0) {
+                editor.update(&mut cx, |editor, cx| copy(editor, cx)).ok();
+            }
+        })
+        .detach();
     }
 
-    pub fn copy(&mut self, _: &Copy, cx: &mut ViewContext<Self>) {
+    fn ranges_contain_redacted_values(&self, ranges: &[Range<Point>], cx: &AppContext) -> bool {
+        if self.private_values_revealed {
+            return false;
+        }
+        let buffer = self.buffer.read(cx).snapshot(cx);
+        ranges.iter().any(|range| {
+            let range = range.start.to_offset(&buffer)..range.end.to_offset(&buffer);
+            buffer
+                .redacted_ranges(range.clone(), |file| redaction_enabled(file, cx))
+                .any(|redacted| redacted.start < range.end && redacted.end > range.start)
+        })
+    }
+
+    /// What copying the selections puts on the clipboard, along with the ranges that are
+    /// copied, which are whole lines for empty selections and in line mode.
+    fn clipboard_item_for_selections(&self, cx: &AppContext) -> (ClipboardItem, Vec<Range<Point>>) {
         let selections = self.selections.all::<Point>(cx);
-        let buffer = self.buffer.read(cx).read(cx);
+        let buffer = self.buffer.read(cx).snapshot(cx);
+        let max_point = buffer.max_point();
         let mut text = String::new();
-
+        let mut ranges = Vec::with_capacity(selections.len());
         let mut clipboard_selections = Vec::with_capacity(selections.len());
-        {
-            let max_point = buffer.max_point();
-            let mut is_first = true;
-            for selection in selections.iter() {
-                let mut start = selection.start;
-                let mut end = selection.end;
-                let is_entire_line = selection.is_empty() || self.selections.line_mode;
-                if is_entire_line {
-                    start = Point::new(start.row, 0);
-                    end = cmp::min(max_point, Point::new(end.row + 1, 0));
-                }
-                if is_first {
-                    is_first = false;
-                } else {
-                    text += "\n";
-                }
-                let mut len = 0;
-                for chunk in buffer.text_for_range(start..end) {
-                    text.push_str(chunk);
-                    len += chunk.len();
-                }
-                clipboard_selections.push(ClipboardSelection {
-                    len,
-                    is_entire_line,
-                    first_line_indent: buffer.indent_size_for_line(MultiBufferRow(start.row)).len,
-                });
-            }
+        for selection in &selections {
+            let mut start = selection.start;
+            let mut end = selection.end;
+            let is_entire_line = selection.is_empty() || self.selections.line_mode;
+            if is_entire_line {
+                start = Point::new(start.row, 0);
+                end = cmp::min(max_point, Point::new(end.row + 1, 0));
+            }
+            if !ranges.is_empty() {
+                text += "\n";
+            }
+            let mut len = 0;
+            for chunk in buffer.text_for_range(start..end) {
+                text.push_str(chunk);
+                len += chunk.len();
+            }
+            clipboard_selections.push(ClipboardSelection {
+                len,
+                is_entire_line,
+                first_line_indent: buffer.indent_size_for_line(MultiBufferRow(start.row)).len,
+            });
+            ranges.push(start..end);
         }
-
-        cx.write_to_clipboard(ClipboardItem::new(text).with_metadata(clipboard_selections));
+        (
+            ClipboardItem::new(text).with_metadata(clipboard_selections),
+            ranges,
+        )
     }
 
     pub fn paste(&mut self, _: &Paste, cx: &mut ViewContext<Self>) {
@@ -6153,7 +6639,9 @@ impl Editor {
             return;
         }
 
+        let version = self.singleton_buffer_version(cx);
         if let Some(tx_id) = self.buffer.update(cx, |buffer, cx| buffer.undo(cx)) {
+            self.remember_last_edit(version, cx);
             if let Some((selections, _)) = self.selection_history.transaction(tx_id).cloned() {
                 self.change_selections(None, cx, |s| {
                     s.select_anchors(selections.to_vec());
@@ -6174,7 +6662,9 @@ impl Editor {
             return;
         }
 
+        let version = self.singleton_buffer_version(cx);
         if let Some(tx_id) = self.buffer.update(cx, |buffer, cx| buffer.redo(cx)) {
+            self.remember_last_edit(version, cx);
             if let Some((_, Some(selections))) = self.selection_history.transaction(tx_id).cloned()
             {
                 self.change_selections(None, cx, |s| {
@@ -7497,7 +7987,72 @@ impl Editor {
         Ok(())
     }
 
+    /// Checks the unchecked task list items on the selected lines, and unchecks the checked ones.
+    pub fn toggle_checkbox(&mut self, _: &ToggleCheckbox, cx: &mut ViewContext<Self>) {
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let mut edits = Vec::new();
+        let mut last_toggled_row = None;
+        for selection in self.selections.all::<Point>(cx) {
+            let mut end_row = selection.end.row;
+            if end_row > selection.start.row && selection.end.column == 0 {
+                end_row -= 1;
+            }
+            for row in selection.start.row..=end_row {
+                if last_toggled_row.map_or(false, |last_row| row <= last_row) {
+                    continue;
+                }
+                last_toggled_row = Some(row);
+
+                let line_end = Point::new(row, snapshot.line_len(MultiBufferRow(row)));
+                let line = snapshot
+                    .text_for_range(Point::new(row, 0)..line_end)
+                    .collect::<String>();
+                if let Some(checkbox) = markdown::line_markers(&line).and_then(|m| m.checkbox) {
+                    let is_checked = !line[checkbox..].starts_with(' ');
+                    let checkbox = Point::new(row, checkbox as u32);
+                    edits.push((
+                        checkbox..checkbox + Point::new(0, 1),
+                        if is_checked { " " } else { "x" },
+                    ));
+                }
+            }
+        }
+        if !edits.is_empty() {
+            self.transact(cx, |this, cx| this.edit(edits, cx));
+        }
+    }
+
+    /// Aligns the columns of the Markdown tables that the selections are in.
+    pub fn format_tables(&mut self, _: &FormatTables, cx: &mut ViewContext<Self>) {
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let selections = self.selections.all::<usize>(cx);
+        let edits = markdown::align_tables(&snapshot.text())
+            .into_iter()
+            .filter(|(range, _)| {
+                selections
+                    .iter()
+                    .any(|selection| selection.start <= range.end && range.start <= selection.end)
+            })
+            .collect::<Vec<_>>();
+        if !edits.is_empty() {
+            self.transact(cx, |this, cx| this.edit(edits, cx));
+        }
+    }
+
     pub fn toggle_comments(&mut self, action: &ToggleComments, cx: &mut ViewContext<Self>) {
+        self.toggle_comments_impl(action.advance_downwards, false, cx);
+    }
+
+    pub fn toggle_block_comments(&mut self, _: &ToggleBlockComments, cx: &mut ViewContext<Self>) {
+        self.toggle_comments_impl(false, true, cx);
+    }
+
+    fn toggle_comments_impl(
+        &mut self,
+        advance_downwards: bool,
+        prefer_block_comments: bool,
+        cx: &mut ViewContext<Self>,
+    ) {
         let text_layout_details = &self.text_layout_details(cx);
         self.transact(cx, |this, cx| {
             let mut selections = this.selections.all::<MultiBufferPoint>(cx);
@@ -7609,9 +8164,46 @@ impl Editor {
                     continue;
                 }
 
-                // If the language has line comments, toggle those.
+                // Find any block comment that already wraps the selected rows, so that it can
+                // be removed even when the language prefers line comments.
+                let block_comment = language.block_comment_delimiters().map(
+                    |(full_comment_prefix, comment_suffix)| {
+                        let comment_prefix = full_comment_prefix.trim_end_matches(' ');
+                        let comment_prefix_whitespace =
+                            &full_comment_prefix[comment_prefix.len()..];
+                        let prefix_range = comment_prefix_range(
+                            snapshot.deref(),
+                            start_row,
+                            comment_prefix,
+                            comment_prefix_whitespace,
+                        );
+                        let suffix_range = comment_suffix_range(
+                            snapshot.deref(),
+                            end_row,
+                            comment_suffix.trim_start_matches(' '),
+                            comment_suffix.starts_with(' '),
+                        );
+                        (
+                            full_comment_prefix.clone(),
+                            comment_suffix.clone(),
+                            prefix_range,
+                            suffix_range,
+                        )
+                    },
+                );
+                let is_block_commented =
+                    block_comment
+                        .as_ref()
+                        .map_or(false, |(_, _, prefix_range, suffix_range)| {
+                            !prefix_range.is_empty() && !suffix_range.is_empty()
+                        });
+
+                // If the language has line comments, toggle those, unless block comments were
+                // requested or the rows are already wrapped in a block comment.
                 let full_comment_prefixes = language.line_comment_prefixes();
-                if !full_comment_prefixes.is_empty() {
+                let use_block_comment =
+                    block_comment.is_some() && (prefer_block_comments || is_block_commented);
+                if !full_comment_prefixes.is_empty() && !use_block_comment {
                     let first_prefix = full_comment_prefixes
                         .first()
                         .expect("prefixes is non-empty");
@@ -7667,31 +8259,17 @@ impl Editor {
                             (position..position, first_prefix.clone())
                         }));
                     }
-                } else if let Some((full_comment_prefix, comment_suffix)) =
-                    language.block_comment_delimiters()
+                } else if let Some((
+                    full_comment_prefix,
+                    comment_suffix,
+                    prefix_range,
+                    suffix_range,
+                )) = block_comment
                 {
-                    let comment_prefix = full_comment_prefix.trim_end_matches(' ');
-                    let comment_prefix_whitespace = &full_comment_prefix[comment_prefix.len()..];
-                    let prefix_range = comment_prefix_range(
-                        snapshot.deref(),
-                        start_row,
-                        comment_prefix,
-                        comment_prefix_whitespace,
-                    );
-                    let suffix_range = comment_suffix_range(
-                        snapshot.deref(),
-                        end_row,
-                        comment_suffix.trim_start_matches(' '),
-                        comment_suffix.starts_with(' '),
-                    );
-
-                    if prefix_range.is_empty() || suffix_range.is_empty() {
-                        edits.push((
-                            prefix_range.start..prefix_range.start,
-                            full_comment_prefix.clone(),
-                        ));
-                        edits.push((suffix_range.end..suffix_range.end, comment_suffix.clone()));
+                    if !is_block_commented {
+                        edits.push((prefix_range.start..prefix_range.start, full_comment_prefix));
                         suffixes_inserted.push((end_row, comment_suffix.len()));
+                        edits.push((suffix_range.end..suffix_range.end, comment_suffix));
                     } else {
                         edits.push((prefix_range, empty_str.clone()));
                         edits.push((suffix_range, empty_str.clone()));
@@ -7744,7 +8322,7 @@ impl Editor {
             let selections_selecting = selections
                 .iter()
                 .any(|selection| selection.start != selection.end);
-            let advance_downwards = action.advance_downwards
+            let advance_downwards = advance_downwards
                 && selections_on_single_row
                 && !selections_selecting
                 && this.mode != EditorMode::SingleLine;
@@ -9038,13 +9616,29 @@ impl Editor {
         cx: &mut ViewContext<Self>,
     ) -> Task<Result<()>> {
         let buffer = self.buffer().clone();
-        let mut buffers = buffer.read(cx).all_buffers();
-        if trigger == FormatTrigger::Save {
-            buffers.retain(|buffer| buffer.read(cx).is_dirty());
-        }
-
         let mut timeout = cx.background_executor().timer(FORMAT_TIMEOUT).fuse();
-        let format = project.update(cx, |project, cx| project.format(buffers, true, trigger, cx));
+        let format = if trigger == FormatTrigger::Manual && !buffer.read(cx).is_singleton() {
+            // Only the parts of the buffers that the multibuffer shows are formatted.
+            let mut buffer_ranges = HashMap::<_, Vec<_>>::default();
+            for buffer_handle in buffer.read(cx).all_buffers() {
+                let ranges = buffer
+                    .read(cx)
+                    .excerpts_for_buffer(&buffer_handle, cx)
+                    .into_iter()
+                    .map(|(_, excerpt_range)| excerpt_range.context)
+                    .collect();
+                buffer_ranges.insert(buffer_handle, ranges);
+            }
+            project.update(cx, |project, cx| {
+                project.format_ranges(buffer_ranges, true, cx)
+            })
+        } else {
+            let mut buffers = buffer.read(cx).all_buffers();
+            if trigger == FormatTrigger::Save {
+                buffers.retain(|buffer| buffer.read(cx).is_dirty());
+            }
+            project.update(cx, |project, cx| project.format(buffers, true, trigger, cx))
+        };
 
         cx.spawn(|_, mut cx| async move {
             let transaction = futures::select_biased! {
@@ -9222,12 +9816,14 @@ impl Editor {
         self.end_transaction_at(Instant::now(), cx)
     }
 
-    fn start_transaction_at(&mut self, now: Instant, cx: &mut ViewContext<Self>) {
+    pub fn start_transaction_at(&mut self, now: Instant, cx: &mut ViewContext<Self>) {
         self.end_selection(cx);
+        let version = self.singleton_buffer_version(cx);
         if let Some(tx_id) = self
             .buffer
             .update(cx, |buffer, cx| buffer.start_transaction_at(now, cx))
         {
+            self.transaction_start_version = version;
             self.selection_history
                 .insert_transaction(tx_id, self.selections.disjoint_anchors());
             cx.emit(EditorEvent::TransactionBegun {
@@ -9236,7 +9832,7 @@ impl Editor {
         }
     }
 
-    fn end_transaction_at(
+    pub fn end_transaction_at(
         &mut self,
         now: Instant,
         cx: &mut ViewContext<Self>,
@@ -9251,6 +9847,8 @@ impl Editor {
                 log::error!("unexpectedly ended a transaction that wasn't started by this editor");
             }
 
+            let version = self.transaction_start_version.take();
+            self.remember_last_edit(version, cx);
             cx.emit(EditorEvent::Edited);
             Some(tx_id)
         } else {
@@ -9258,6 +9856,23 @@ impl Editor {
         }
     }
 
+    fn singleton_buffer_version(&self, cx: &AppContext) -> Option<clock::Global> {
+        let buffer = self.buffer.read(cx).as_singleton()?;
+        Some(buffer.read(cx).version())
+    }
+
+    /// Remembers where the editor edited its buffer since the given version, which it does
+    /// synchronously, so no one else's edits are among them.
+    fn remember_last_edit(&mut self, since: Option<clock::Global>, cx: &AppContext) {
+        let Some((since, buffer)) = since.zip(self.buffer.read(cx).as_singleton()) else {
+            return;
+        };
+        let buffer = buffer.read(cx);
+        if let Some(edit) = buffer.edits_since::<usize>(&since).last() {
+            self.last_edit = Some(buffer.anchor_before(edit.new.start));
+        }
+    }
+
     pub fn fold(&mut self, _: &actions::Fold, cx: &mut ViewContext<Self>) {
         let mut fold_ranges = Vec::new();
 
@@ -9285,6 +9900,212 @@ impl Editor {
         self.fold_ranges(fold_ranges, true, cx);
     }
 
+    pub fn fold_all(&mut self, _: &actions::FoldAll, cx: &mut ViewContext<Self>) {
+        let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
+        let fold_ranges = Self::outermost_foldable_ranges(&display_map, |_| true);
+        self.fold_ranges(fold_ranges, true, cx);
+    }
+
+    pub fn fold_recursive(&mut self, _: &actions::FoldRecursive, cx: &mut ViewContext<Self>) {
+        let mut fold_ranges = Vec::new();
+
+        let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
+
+        let selections = self.selections.all_adjusted(cx);
+        for selection in selections {
+            let range = selection.range().sorted();
+            let Some((start_row, enclosing_range)) = (0..=range.start.row)
+                .rev()
+                .filter_map(|row| {
+                    let fold_range = display_map.foldable_range(MultiBufferRow(row))?;
+                    Some((row, fold_range))
+                })
+                .find(|(_, fold_range)| fold_range.end.row >= range.end.row)
+            else {
+                continue;
+            };
+
+            for row in start_row..=enclosing_range.end.row {
+                if let Some(fold_range) = display_map.foldable_range(MultiBufferRow(row)) {
+                    if fold_range.end <= enclosing_range.end {
+                        fold_ranges.push(fold_range);
+                    }
+                }
+            }
+        }
+
+        self.fold_ranges(fold_ranges, true, cx);
+    }
+
+    pub fn fold_at_level(&mut self, fold_at_level: &FoldAtLevel, cx: &mut ViewContext<Self>) {
+        let level = fold_at_level.level;
+        if level == 0 {
+            return;
+        }
+
+        let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
+        let fold_ranges = Self::outermost_foldable_ranges(&display_map, |depth| depth == level);
+        self.fold_ranges(fold_ranges, true, cx);
+    }
+
+    /// Returns the foldable ranges whose nesting depth, starting at 1 for top-level ranges,
+    /// satisfies `include`, skipping any ranges nested within them.
+    fn outermost_foldable_ranges(
+        display_map: &DisplaySnapshot,
+        include: impl Fn(u32) -> bool,
+    ) -> Vec<Range<Point>> {
+        let mut fold_ranges = Vec::new();
+        let mut enclosing_ranges = Vec::<Range<Point>>::new();
+        let max_row = display_map.buffer_snapshot.max_buffer_row();
+
+        let mut row = 0;
+        while row <= max_row.0 {
+            while enclosing_ranges
+                .last()
+                .map_or(false, |range| range.end.row < row)
+            {
+                enclosing_ranges.pop();
+            }
+
+            let Some(fold_range) = display_map.foldable_range(MultiBufferRow(row)) else {
+                row += 1;
+                continue;
+            };
+
+            let depth = enclosing_ranges.len() as u32 + 1;
+            if include(depth) {
+                row = fold_range.end.row + 1;
+                fold_ranges.push(fold_range);
+            } else {
+                row += 1;
+                enclosing_ranges.push(fold_range);
+            }
+        }
+
+        fold_ranges
+    }
+
+    /// Folds everything below the top-level headings, then only the text below each heading,
+    /// then unfolds everything, as Org mode's global cycling does.
+    pub fn cycle_heading_visibility(
+        &mut self,
+        _: &CycleHeadingVisibility,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
+        let headings = display_map.headings();
+        if headings.is_empty() {
+            return;
+        }
+
+        self.heading_visibility = match self.heading_visibility {
+            HeadingVisibility::All => HeadingVisibility::TopLevel,
+            HeadingVisibility::TopLevel => HeadingVisibility::Headings,
+            HeadingVisibility::Headings => HeadingVisibility::All,
+        };
+        let fold_ranges = match self.heading_visibility {
+            HeadingVisibility::TopLevel => {
+                let top_level = headings.iter().map(|(_, level)| *level).min().unwrap_or(1);
+                headings
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, (_, level))| *level == top_level)
+                    .filter_map(|(ix, (row, _))| {
+                        let end_row = headings[ix + 1..]
+                            .iter()
+                            .find(|(_, level)| *level <= top_level)
+                            .map(|(row, _)| *row);
+                        display_map.heading_section_range(*row, end_row)
+                    })
+                    .collect()
+            }
+            HeadingVisibility::Headings => headings
+                .iter()
+                .enumerate()
+                .filter_map(|(ix, (row, _))| {
+                    let end_row = headings.get(ix + 1).map(|(row, _)| *row);
+                    display_map.heading_section_range(*row, end_row)
+                })
+                .collect(),
+            HeadingVisibility::All => Vec::new(),
+        };
+
+        self.unfold_ranges(
+            [Point::zero()..display_map.buffer_snapshot.max_point()],
+            true,
+            false,
+            cx,
+        );
+        self.fold_ranges(fold_ranges, true, cx);
+    }
+
+    /// Raises the headings that the selections are in by a level, along with their subheadings.
+    pub fn promote_heading(&mut self, _: &PromoteHeading, cx: &mut ViewContext<Self>) {
+        self.change_heading_levels(true, cx);
+    }
+
+    /// Lowers the headings that the selections are in by a level, along with their subheadings.
+    pub fn demote_heading(&mut self, _: &DemoteHeading, cx: &mut ViewContext<Self>) {
+        self.change_heading_levels(false, cx);
+    }
+
+    fn change_heading_levels(&mut self, promote: bool, cx: &mut ViewContext<Self>) {
+        let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
+        let headings = display_map.headings();
+        let mut changed_headings = Vec::new();
+        for selection in self.selections.all::<Point>(cx) {
+            // The heading of the section that the selection starts in, the ones it contains, and
+            // their subheadings.
+            let Some(start_ix) = headings
+                .iter()
+                .rposition(|(row, _)| row.0 <= selection.start.row)
+            else {
+                continue;
+            };
+            let mut level = headings[start_ix].1;
+            let mut end_ix = start_ix + 1;
+            while let Some((row, row_level)) = headings.get(end_ix) {
+                if row.0 <= selection.end.row {
+                    level = level.min(*row_level);
+                } else if *row_level <= level {
+                    break;
+                }
+                end_ix += 1;
+            }
+            changed_headings.extend(
+                start_ix.max(
+                    changed_headings
+                        .last()
+                        .map_or(0, |last_ix: &usize| last_ix + 1),
+                )..end_ix,
+            );
+        }
+
+        // Top-level headings can't be raised, so neither can their sections.
+        if changed_headings.is_empty()
+            || promote && changed_headings.iter().any(|ix| headings[*ix].1 <= 1)
+        {
+            return;
+        }
+        let buffer = &display_map.buffer_snapshot;
+        let edits = changed_headings
+            .into_iter()
+            .filter_map(|ix| {
+                let line_start = Point::new(headings[ix].0 .0, 0);
+                let marker = buffer.chars_at(line_start).next()?;
+                Some(if promote {
+                    (
+                        line_start..line_start + Point::new(0, marker.len_utf8() as u32),
+                        String::new(),
+                    )
+                } else {
+                    (line_start..line_start, marker.to_string())
+                })
+            })
+            .collect::<Vec<_>>();
+        self.transact(cx, |this, cx| this.edit(edits, cx));
+    }
+
     pub fn fold_at(&mut self, fold_at: &FoldAt, cx: &mut ViewContext<Self>) {
         let buffer_row = fold_at.buffer_row;
         let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
@@ -9319,6 +10140,16 @@ impl Editor {
         self.unfold_ranges(ranges, true, true, cx);
     }
 
+    pub fn unfold_all(&mut self, _: &actions::UnfoldAll, cx: &mut ViewContext<Self>) {
+        let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
+        self.unfold_ranges(
+            [Point::zero()..display_map.buffer_snapshot.max_point()],
+            true,
+            true,
+            cx,
+        );
+    }
+
     pub fn unfold_at(&mut self, unfold_at: &UnfoldAt, cx: &mut ViewContext<Self>) {
         let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
 
@@ -9401,6 +10232,7 @@ impl Editor {
             }
 
             self.scrollbar_marker_state.dirty = true;
+            self.schedule_file_state_serialization(cx);
         }
     }
 
@@ -9435,6 +10267,7 @@ impl Editor {
 
             cx.notify();
             self.scrollbar_marker_state.dirty = true;
+            self.schedule_file_state_serialization(cx);
         }
     }
 
@@ -9473,12 +10306,26 @@ impl Editor {
         }
     }
 
+    pub fn resize_blocks(
+        &mut self,
+        heights: HashMap<BlockId, u8>,
+        autoscroll: Option<Autoscroll>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.display_map
+            .update(cx, |display_map, cx| display_map.resize_blocks(heights, cx));
+        if let Some(autoscroll) = autoscroll {
+            self.request_autoscroll(autoscroll, cx);
+        }
+    }
+
     pub fn remove_blocks(
         &mut self,
         block_ids: HashSet<BlockId>,
         autoscroll: Option<Autoscroll>,
         cx: &mut ViewContext<Self>,
     ) {
+        self.view_blocks.retain(|id, _| !block_ids.contains(id));
         self.display_map.update(cx, |display_map, cx| {
             display_map.remove_blocks(block_ids, cx)
         });
@@ -9616,6 +10463,12 @@ impl Editor {
         EditorSettings::override_global(editor_settings, cx);
     }
 
+    /// Shows the values that are redacted in private files, or hides them again.
+    pub fn toggle_redacted_values(&mut self, _: &ToggleRedactedValues, cx: &mut ViewContext<Self>) {
+        self.private_values_revealed = !self.private_values_revealed;
+        cx.notify();
+    }
+
     pub fn set_show_gutter(&mut self, show_gutter: bool, cx: &mut ViewContext<Self>) {
         self.show_gutter = show_gutter;
         cx.notify();
@@ -10060,6 +10913,44 @@ impl Editor {
             .map_or(false, |(_, highlights)| !highlights.is_empty())
     }
 
+    /// Replaces the annotations of type `T` with the given text, which is displayed inline,
+    /// styled like an inlay hint, at each of the given positions.
+    pub fn set_inlay_annotations<T: 'static>(
+        &mut self,
+        annotations: impl IntoIterator<Item = (Anchor, String)>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.splice_inlay_annotations(TypeId::of::<T>(), annotations, cx);
+    }
+
+    pub fn clear_inlay_annotations<T: 'static>(&mut self, cx: &mut ViewContext<Self>) {
+        self.set_inlay_annotations::<T>(std::iter::empty(), cx);
+    }
+
+    fn splice_inlay_annotations(
+        &mut self,
+        type_id: TypeId,
+        annotations: impl IntoIterator<Item = (Anchor, String)>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let to_remove = self.inlay_annotations.remove(&type_id).unwrap_or_default();
+        let to_insert = annotations
+            .into_iter()
+            .map(|(position, text)| {
+                Inlay::annotation(post_inc(&mut self.next_inlay_id), position, text)
+            })
+            .collect::<Vec<_>>();
+        if to_remove.is_empty() && to_insert.is_empty() {
+            return;
+        }
+
+        if !to_insert.is_empty() {
+            self.inlay_annotations
+                .insert(type_id, to_insert.iter().map(|inlay| inlay.id).collect());
+        }
+        self.splice_inlays(to_remove, to_insert, cx);
+    }
+
     pub fn background_highlights_in_range(
         &self,
         search_range: Range<Anchor>,
@@ -10173,23 +11064,20 @@ impl Editor {
         results
     }
 
-    /// Get the text ranges corresponding to the redaction query
+    /// Get the text ranges corresponding to the redaction query, unless the redacted values
+    /// have been revealed.
     pub fn redacted_ranges(
         &self,
         search_range: Range<Anchor>,
         display_snapshot: &DisplaySnapshot,
         cx: &WindowContext,
     ) -> Vec<Range<DisplayPoint>> {
+        if self.private_values_revealed {
+            return Vec::new();
+        }
         display_snapshot
             .buffer_snapshot
-            .redacted_ranges(search_range, |file| {
-                if let Some(file) = file {
-                    file.is_private()
-                        && EditorSettings::get(Some(file.as_ref().into()), cx).redact_private_values
-                } else {
-                    false
-                }
-            })
+            .redacted_ranges(search_range, |file| redaction_enabled(file, cx))
             .map(|range| {
                 range.start.to_display_point(display_snapshot)
                     ..range.end.to_display_point(display_snapshot)
@@ -10259,6 +11147,8 @@ impl Editor {
                 self.scrollbar_marker_state.dirty = true;
                 self.refresh_active_diagnostics(cx);
                 self.refresh_code_actions(cx);
+                self.schedule_decorations_refresh(cx);
+                self.stream_buffer_events(cx);
                 if self.has_active_inline_completion(cx) {
                     self.update_visible_inline_completion(cx);
                 }
@@ -10304,6 +11194,8 @@ impl Editor {
                 excerpts,
             } => {
                 self.tasks_update_task = Some(self.refresh_runnables(cx));
+                self.schedule_decorations_refresh(cx);
+                self.stream_buffer_events(cx);
                 cx.emit(EditorEvent::ExcerptsAdded {
                     buffer: buffer.clone(),
                     predecessor: *predecessor,
@@ -10321,6 +11213,8 @@ impl Editor {
                 cx.emit(EditorEvent::Reparsed);
             }
             multi_buffer::Event::LanguageChanged => {
+                self.refresh_cell_marker_decorations(cx);
+                self.schedule_decorations_refresh(cx);
                 cx.emit(EditorEvent::Reparsed);
                 cx.notify();
             }
@@ -10565,8 +11459,9 @@ impl Editor {
             .get("vim_mode")
             == Some(&serde_json::Value::Bool(true));
 
-        let copilot_enabled = all_language_settings(file, cx).inline_completions.provider
-            == language::language_settings::InlineCompletionProvider::Copilot;
+        let copilot_enabled = all_language_settings(file, cx)
+            .inline_completions
+            .is_provider_enabled("copilot");
         let copilot_enabled_for_language = self
             .buffer
             .read(cx)
@@ -10932,6 +11827,15 @@ impl CompletionProvider for Model<Project> {
     }
 }
 
+/// Whether the values in the file are redacted, as they are in private files when the
+/// `redact_private_values` setting is on.
+fn redaction_enabled(file: Option<&Arc<dyn language::File>>, cx: &AppContext) -> bool {
+    file.map_or(false, |file| {
+        file.is_private()
+            && EditorSettings::get(Some(file.as_ref().into()), cx).redact_private_values
+    })
+}
+
 fn inlay_hint_settings(
     location: Anchor,
     snapshot: &MultiBufferSnapshot,
@@ -10945,6 +11849,47 @@ fn inlay_hint_settings(
         .inlay_hints
 }
 
+/// Returns the text to insert after the indentation of a new line that is typed at `cursor`,
+/// if the cursor is within a block comment of a language configured to continue those.
+fn block_comment_continuation(
+    buffer: &MultiBufferSnapshot,
+    cursor: Point,
+    language: &LanguageScope,
+) -> Option<Arc<str>> {
+    if language.override_name() != Some("comment") {
+        return None;
+    }
+    let continuation = language.block_comment_continuation()?;
+    let (comment_start, comment_end) = language.block_comment_delimiters()?;
+    let comment_start = comment_start.trim_end();
+    let comment_end = comment_end.trim_start();
+    let continuation_marker = continuation.trim_end();
+
+    let line_start = Point::new(cursor.row, 0);
+    let text_before_cursor = buffer
+        .text_for_range(line_start..cursor)
+        .collect::<String>();
+    let text_before_cursor = text_before_cursor.trim_start();
+
+    if let Some(comment_text) = text_before_cursor.strip_prefix(comment_start) {
+        // Align the continuation marker under the comment's opening delimiter, e.g. ` * ` for `/*`.
+        if comment_text.contains(comment_end) {
+            return None;
+        }
+        let padding = comment_start
+            .len()
+            .saturating_sub(continuation_marker.len());
+        Some(format!("{}{}", " ".repeat(padding), continuation).into())
+    } else if !continuation_marker.is_empty()
+        && text_before_cursor.starts_with(continuation_marker)
+        && !text_before_cursor.contains(comment_end)
+    {
+        Some(continuation.clone())
+    } else {
+        None
+    }
+}
+
 fn consume_contiguous_rows(
     contiguous_row_selections: &mut Vec<Selection<Point>>,
     selection: &Selection<Point>,
@@ -11039,7 +11984,7 @@ impl EditorSnapshot {
             ProjectSettings::get_global(cx).git.git_gutter,
             Some(GitGutterSetting::TrackedFiles)
         );
-        let gutter_settings = EditorSettings::get_global(cx).gutter;
+        let gutter_settings = &EditorSettings::get_global(cx).gutter;
         let gutter_lines_enabled = gutter_settings.line_numbers;
         let line_gutter_width = if gutter_lines_enabled {
             // Avoid flicker-like gutter resizes when the line number gains another digit and only resize the gutter on files with N*10^5 lines.
@@ -11053,7 +11998,9 @@ impl EditorSnapshot {
             .render_git_blame_gutter
             .then_some(em_width * GIT_BLAME_GUTTER_WIDTH_CHARS);
 
-        let mut left_padding = git_blame_entries_width.unwrap_or(Pixels::ZERO);
+        let columns_width = em_width * self.gutter_columns_width;
+
+        let mut left_padding = git_blame_entries_width.unwrap_or(Pixels::ZERO) + columns_width;
         left_padding += if gutter_settings.code_actions {
             em_width * 3.0
         } else if show_git_gutter && gutter_lines_enabled {
@@ -11080,6 +12027,7 @@ impl EditorSnapshot {
             width: line_gutter_width + left_padding + right_padding,
             margin: -descent,
             git_blame_entries_width,
+            columns_width,
         }
     }
 }
@@ -1,3 +1,4 @@
+use collections::HashMap;
 use gpui::AppContext;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -10,15 +11,18 @@ pub struct EditorSettings {
     pub hover_popover_enabled: bool,
     pub show_completions_on_input: bool,
     pub show_completion_documentation: bool,
+    pub word_completions: bool,
     pub completion_documentation_secondary_query_debounce: u64,
     pub use_on_type_format: bool,
     pub toolbar: Toolbar,
     pub scrollbar: Scrollbar,
     pub gutter: Gutter,
+    pub event_stream: EventStream,
     pub vertical_scroll_margin: f32,
     pub scroll_sensitivity: f32,
     pub relative_line_numbers: bool,
     pub seed_search_query_from_cursor: SeedQuerySetting,
+    pub search_wrap: bool,
     pub multi_cursor_modifier: MultiCursorModifier,
     pub redact_private_values: bool,
     #[serde(default)]
@@ -78,11 +82,20 @@ pub struct Scrollbar {
     pub cursors: bool,
 }
 
-#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 pub struct Gutter {
     pub line_numbers: bool,
     pub code_actions: bool,
     pub folds: bool,
+    pub columns: HashMap<String, bool>,
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct EventStream {
+    pub enabled: bool,
+    pub include_paths: bool,
+    pub include_text: bool,
+    pub interval_ms: u64,
 }
 
 /// When to show the scrollbar in the editor.
@@ -138,6 +151,11 @@ pub struct EditorSettingsContent {
     ///
     /// Default: true
     pub show_completion_documentation: Option<bool>,
+    /// Whether to offer completions made from the words of open buffers when
+    /// the language server has none, or there is no language server.
+    ///
+    /// Default: true
+    pub word_completions: Option<bool>,
     /// The debounce delay before re-querying the language server for completion
     /// documentation when not included in original completion list.
     ///
@@ -154,6 +172,8 @@ pub struct EditorSettingsContent {
     pub scrollbar: Option<ScrollbarContent>,
     /// Gutter related settings
     pub gutter: Option<GutterContent>,
+    /// Editor event stream related settings
+    pub event_stream: Option<EventStreamContent>,
     /// The number of lines to keep above/below the cursor when auto-scrolling.
     ///
     /// Default: 3.
@@ -171,13 +191,20 @@ pub struct EditorSettingsContent {
     ///
     /// Default: always
     pub seed_search_query_from_cursor: Option<SeedQuerySetting>,
+    /// Whether moving to the next or previous search match wraps around the
+    /// start and end of the buffer.
+    ///
+    /// Default: true
+    pub search_wrap: Option<bool>,
     /// The key to use for adding multiple cursors
     ///
     /// Default: alt
     pub multi_cursor_modifier: Option<MultiCursorModifier>,
     /// Hide the values of variables in `private` files, as defined by the
     /// private_files setting. This only changes the visual representation,
-    /// the values are still present in the file and can be selected / copied / pasted
+    /// the values are still present in the file and can be selected / copied / pasted.
+    /// Copying hidden values asks for confirmation first, and `editor::ToggleRedactedValues`
+    /// shows them in an editor until it's toggled again.
     ///
     /// Default: false
     pub redact_private_values: Option<bool>,
@@ -232,7 +259,7 @@ pub struct ScrollbarContent {
 }
 
 /// Gutter related settings
-#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 pub struct GutterContent {
     /// Whether to show line numbers in the gutter.
     ///
@@ -246,6 +273,32 @@ pub struct GutterContent {
     ///
     /// Default: true
     pub folds: Option<bool>,
+    /// Whether to show each of the columns that features add to the gutter, by name.
+    /// Columns that aren't listed are shown.
+    ///
+    /// Default: {}
+    pub columns: Option<HashMap<String, bool>>,
+}
+
+/// Editor event stream related settings
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct EventStreamContent {
+    /// Whether to report editor events to extensions and scripts that subscribe to them.
+    ///
+    /// Default: false
+    pub enabled: Option<bool>,
+    /// Whether reported events include the paths of buffers.
+    ///
+    /// Default: false
+    pub include_paths: Option<bool>,
+    /// Whether reported edits include the inserted text.
+    ///
+    /// Default: false
+    pub include_text: Option<bool>,
+    /// The minimum number of milliseconds between batches of reported events.
+    ///
+    /// Default: 1000
+    pub interval_ms: Option<u64>,
 }
 
 impl Settings for EditorSettings {
@@ -1,5 +1,7 @@
 use super::*;
 use crate::{
+    cells::{cells, CellMarkers, CellsToRun},
+    editor_settings::{EventStreamContent, GutterContent},
     scroll::scroll_amount::ScrollAmount,
     test::{
         assert_text_with_selections, build_editor, editor_hunks,
@@ -9,7 +11,9 @@ use crate::{
     JoinLines,
 };
 use futures::StreamExt;
-use gpui::{div, TestAppContext, UpdateGlobal, VisualTestContext, WindowBounds, WindowOptions};
+use gpui::{
+    div, ModelContext, TestAppContext, UpdateGlobal, VisualTestContext, WindowBounds, WindowOptions,
+};
 use indoc::indoc;
 use language::{
     language_settings::{
@@ -505,6 +509,7 @@ fn test_clone(cx: &mut TestAppContext) {
     let cloned_editor = editor
         .update(cx, |editor, cx| {
             cx.open_window(Default::default(), |cx| cx.new_view(|cx| editor.clone(cx)))
+                .unwrap()
         })
         .unwrap();
 
@@ -810,6 +815,248 @@ fn test_fold_action(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+fn test_fold_all_and_fold_at_level(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let view = cx.add_window(|cx| {
+        let buffer = MultiBuffer::build_simple(
+            &"
+                impl Foo {
+                    // Hello!
+
+                    fn a() {
+                        1
+                    }
+
+                    fn b() {
+                        2
+                    }
+
+                    fn c() {
+                        3
+                    }
+                }
+            "
+            .unindent(),
+            cx,
+        );
+        build_editor(buffer.clone(), cx)
+    });
+
+    _ = view.update(cx, |view, cx| {
+        view.fold_all(&FoldAll, cx);
+        assert_eq!(
+            view.display_text(cx),
+            "
+                impl Foo {⋯
+                }
+            "
+            .unindent(),
+        );
+
+        view.unfold_all(&UnfoldAll, cx);
+        assert_eq!(view.display_text(cx), view.buffer.read(cx).read(cx).text());
+
+        view.fold_at_level(&FoldAtLevel { level: 2 }, cx);
+        assert_eq!(
+            view.display_text(cx),
+            "
+                impl Foo {
+                    // Hello!
+
+                    fn a() {⋯
+                    }
+
+                    fn b() {⋯
+                    }
+
+                    fn c() {⋯
+                    }
+                }
+            "
+            .unindent(),
+        );
+
+        view.unfold_all(&UnfoldAll, cx);
+        view.change_selections(None, cx, |s| {
+            s.select_display_ranges([
+                DisplayPoint::new(DisplayRow(0), 0)..DisplayPoint::new(DisplayRow(0), 0)
+            ]);
+        });
+        view.fold_recursive(&FoldRecursive, cx);
+        assert_eq!(
+            view.display_text(cx),
+            "
+                impl Foo {⋯
+                }
+            "
+            .unindent(),
+        );
+
+        // Unfolding the outer range reveals the nested ranges, which remain folded.
+        view.unfold_lines(&UnfoldLines, cx);
+        assert_eq!(
+            view.display_text(cx),
+            "
+                impl Foo {
+                    // Hello!
+
+                    fn a() {⋯
+                    }
+
+                    fn b() {⋯
+                    }
+
+                    fn c() {⋯
+                    }
+                }
+            "
+            .unindent(),
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_fold_region_markers(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    let language = Arc::new(Language::new(
+        LanguageConfig {
+            line_comments: vec!["// ".into()],
+            ..Default::default()
+        },
+        None,
+    ));
+    cx.update_buffer(|buffer, cx| buffer.set_language(Some(language), cx));
+
+    cx.set_state(indoc! {"
+        ˇ// #region Helpers
+        fn a() {}
+
+        // #region Nested
+        fn b() {}
+        // #endregion
+        // #endregion
+        fn c() {}
+    "});
+
+    cx.update_editor(|editor, cx| {
+        editor.fold(&Fold, cx);
+        assert_eq!(
+            editor.display_text(cx),
+            indoc! {"
+                // #region Helpers⋯
+                fn c() {}
+            "}
+        );
+
+        editor.unfold_all(&UnfoldAll, cx);
+        editor.fold_at_level(&FoldAtLevel { level: 2 }, cx);
+        assert_eq!(
+            editor.display_text(cx),
+            indoc! {"
+                // #region Helpers
+                fn a() {}
+
+                // #region Nested⋯
+                // #endregion
+                fn c() {}
+            "}
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_heading_folding(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    let language = Arc::new(Language::new(
+        LanguageConfig {
+            heading_marker: Some('#'),
+            ..Default::default()
+        },
+        None,
+    ));
+    cx.update_buffer(|buffer, cx| buffer.set_language(Some(language), cx));
+
+    cx.set_state(indoc! {"
+        ˇ# A
+        a
+        ## B
+        #b
+
+        # C
+        c
+    "});
+    cx.update_editor(|editor, cx| {
+        editor.cycle_heading_visibility(&CycleHeadingVisibility, cx);
+        assert_eq!(editor.display_text(cx), "# A⋯\n\n# C⋯\n");
+
+        editor.cycle_heading_visibility(&CycleHeadingVisibility, cx);
+        assert_eq!(editor.display_text(cx), "# A⋯\n## B⋯\n\n# C⋯\n");
+
+        editor.cycle_heading_visibility(&CycleHeadingVisibility, cx);
+        assert_eq!(editor.display_text(cx), "# A\na\n## B\n#b\n\n# C\nc\n");
+    });
+
+    // Headings are demoted along with their subheadings
+    cx.set_state("# A\nˇa\n## B\n\n# C\n");
+    cx.update_editor(|editor, cx| editor.demote_heading(&DemoteHeading, cx));
+    cx.assert_editor_state("## A\nˇa\n### B\n\n# C\n");
+    cx.update_editor(|editor, cx| editor.promote_heading(&PromoteHeading, cx));
+    cx.assert_editor_state("# A\nˇa\n## B\n\n# C\n");
+
+    // Top-level headings can't be promoted
+    cx.update_editor(|editor, cx| editor.promote_heading(&PromoteHeading, cx));
+    cx.assert_editor_state("# A\nˇa\n## B\n\n# C\n");
+}
+
+#[gpui::test]
+async fn test_fold_syntax_ranges(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    let language = Arc::new(
+        Language::new(
+            LanguageConfig::default(),
+            Some(tree_sitter_rust::language()),
+        )
+        .with_brackets_query(indoc! {r#"
+                ("{" @open "}" @close)
+                ("[" @open "]" @close)
+            "#})
+        .unwrap(),
+    );
+    cx.update_buffer(|buffer, cx| buffer.set_language(Some(language), cx));
+
+    // The array isn't indented, so only its brackets determine the folded range.
+    cx.set_state(indoc! {"
+        fn main() {
+        let x = [ˇ
+        1,
+        2,
+        ];
+        }
+    "});
+    cx.executor().run_until_parked();
+
+    cx.update_editor(|editor, cx| {
+        editor.fold(&Fold, cx);
+        assert_eq!(
+            editor.display_text(cx),
+            indoc! {"
+                fn main() {
+                let x = [⋯
+                ];
+                }
+            "}
+        );
+    });
+}
+
 #[gpui::test]
 fn test_move_cursor(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -3092,6 +3339,89 @@ async fn test_unique_lines_single_selection(cx: &mut TestAppContext) {
     "});
 }
 
+#[gpui::test]
+async fn test_retain_lines(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+
+    // Only the selected lines are filtered
+    cx.set_state(indoc! {"
+        foo
+        «bar
+        baz
+        quxˇ»
+        bar
+    "});
+    cx.update_editor(|e, cx| {
+        assert_eq!(
+            e.count_retained_lines(|line| line.starts_with("ba"), cx),
+            (2, 3)
+        );
+        e.retain_lines(|line| line.starts_with("ba"), cx);
+    });
+    cx.assert_editor_state(indoc! {"
+        foo
+        «bar
+        bazˇ»
+        bar
+    "});
+
+    // The whole buffer is filtered when nothing is selected
+    cx.set_state(indoc! {"
+        foo
+        barˇ
+        baz
+        qux
+    "});
+    cx.update_editor(|e, cx| {
+        assert_eq!(
+            e.count_retained_lines(|line| !line.contains('a'), cx),
+            (2, 4)
+        );
+        e.retain_lines(|line| !line.contains('a'), cx);
+    });
+    cx.assert_editor_state(indoc! {"
+        «foo
+        quxˇ»
+    "});
+
+    // Filtering is undone in a single step
+    cx.update_editor(|e, cx| e.undo(&Undo, cx));
+    cx.assert_editor_state(indoc! {"
+        foo
+        barˇ
+        baz
+        qux
+    "});
+
+    // A block whose lines are all removed takes its rows with it
+    cx.set_state(indoc! {"
+        foo
+        «bax
+        bayˇ»
+        qux
+        «bar
+        quuxˇ»
+        end
+    "});
+    cx.update_editor(|e, cx| e.retain_lines(|line| !line.starts_with("ba"), cx));
+    cx.assert_editor_state(indoc! {"
+        foo
+        ˇqux
+        «quuxˇ»
+        end
+    "});
+
+    // Including when the block is at the end of the buffer
+    cx.set_state(indoc! {"
+        foo
+        «bar
+        bazˇ»"});
+    cx.update_editor(|e, cx| e.retain_lines(|line| !line.starts_with("ba"), cx));
+    cx.assert_editor_state("fooˇ");
+}
+
 #[gpui::test]
 async fn test_manipulate_lines_with_multi_selection(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -3294,49 +3624,296 @@ async fn test_manipulate_text(cx: &mut TestAppContext) {
 }
 
 #[gpui::test]
-fn test_duplicate_line(cx: &mut TestAppContext) {
+async fn test_increment_number(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
 
-    let view = cx.add_window(|cx| {
-        let buffer = MultiBuffer::build_simple("abc\ndef\nghi\n", cx);
-        build_editor(buffer, cx)
-    });
-    _ = view.update(cx, |view, cx| {
-        view.change_selections(None, cx, |s| {
-            s.select_display_ranges([
-                DisplayPoint::new(DisplayRow(0), 0)..DisplayPoint::new(DisplayRow(0), 1),
-                DisplayPoint::new(DisplayRow(0), 2)..DisplayPoint::new(DisplayRow(0), 2),
-                DisplayPoint::new(DisplayRow(1), 0)..DisplayPoint::new(DisplayRow(1), 0),
-                DisplayPoint::new(DisplayRow(3), 0)..DisplayPoint::new(DisplayRow(3), 0),
-            ])
-        });
-        view.duplicate_line_down(&DuplicateLineDown, cx);
-        assert_eq!(view.display_text(cx), "abc\nabc\ndef\ndef\nghi\n\n");
-        assert_eq!(
-            view.selections.display_ranges(cx),
-            vec![
-                DisplayPoint::new(DisplayRow(1), 0)..DisplayPoint::new(DisplayRow(1), 1),
-                DisplayPoint::new(DisplayRow(1), 2)..DisplayPoint::new(DisplayRow(1), 2),
-                DisplayPoint::new(DisplayRow(3), 0)..DisplayPoint::new(DisplayRow(3), 0),
-                DisplayPoint::new(DisplayRow(6), 0)..DisplayPoint::new(DisplayRow(6), 0),
-            ]
-        );
-    });
+    let mut cx = EditorTestContext::new(cx).await;
 
-    let view = cx.add_window(|cx| {
-        let buffer = MultiBuffer::build_simple("abc\ndef\nghi\n", cx);
-        build_editor(buffer, cx)
-    });
-    _ = view.update(cx, |view, cx| {
-        view.change_selections(None, cx, |s| {
-            s.select_display_ranges([
-                DisplayPoint::new(DisplayRow(0), 1)..DisplayPoint::new(DisplayRow(1), 1),
-                DisplayPoint::new(DisplayRow(1), 2)..DisplayPoint::new(DisplayRow(2), 1),
-            ])
-        });
-        view.duplicate_line_down(&DuplicateLineDown, cx);
-        assert_eq!(view.display_text(cx), "abc\ndef\nghi\nabc\ndef\nghi\n");
-        assert_eq!(
+    cx.set_state("ˇlet x = 9;");
+    cx.update_editor(|e, cx| e.increment_number(&IncrementNumber, cx));
+    cx.assert_editor_state("let x = ˇ10;");
+    cx.update_editor(|e, cx| e.decrement_number(&DecrementNumber, cx));
+    cx.update_editor(|e, cx| e.decrement_number(&DecrementNumber, cx));
+    cx.assert_editor_state("let x = ˇ8;");
+
+    // Binary, octal and hexadecimal numbers keep their width and case.
+    cx.set_state("ˇ0x0f 0b11 0o7\n0XFF ˇ0b11 0o7\n0xFF 0b11 ˇ0o7");
+    cx.update_editor(|e, cx| e.increment_number(&IncrementNumber, cx));
+    cx.assert_editor_state("0xˇ10 0b11 0o7\n0XFF 0bˇ100 0o7\n0xFF 0b11 0oˇ10");
+    cx.set_state("ˇ0xFF");
+    cx.update_editor(|e, cx| e.increment_number(&IncrementNumber, cx));
+    cx.assert_editor_state("0xˇ100");
+
+    // Every number in a selection changes.
+    cx.set_state(indoc! {"
+        «1, 2, -3
+        a4bˇ»5
+    "});
+    cx.update_editor(|e, cx| e.increment_number(&IncrementNumber, cx));
+    cx.assert_editor_state(indoc! {"
+        «2, 3, -2
+        a5bˇ»5
+    "});
+
+    // Numbers joined by characters that could be part of a number are all found.
+    cx.set_state("«2024-10-16ˇ» «3x4ˇ»");
+    cx.update_editor(|e, cx| e.increment_number(&IncrementNumber, cx));
+    cx.assert_editor_state("«2025-11-17ˇ» «4x5ˇ»");
+}
+
+#[gpui::test]
+async fn test_cells(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.set_state(indoc! {"
+        import ˇos
+
+        # %% first
+        a = 1
+
+        // %%
+        b = 2
+        #%%
+    "});
+
+    cx.update_editor(|e, cx| e.go_to_next_cell(&GoToNextCell, cx));
+    cx.assert_editor_state(indoc! {"
+        import os
+
+        # %% first
+        ˇa = 1
+
+        // %%
+        b = 2
+        #%%
+    "});
+    cx.update_editor(|e, cx| e.go_to_next_cell(&GoToNextCell, cx));
+    cx.update_editor(|e, cx| e.go_to_next_cell(&GoToNextCell, cx));
+    cx.update_editor(|e, cx| e.go_to_next_cell(&GoToNextCell, cx));
+    cx.assert_editor_state(indoc! {"
+        import os
+
+        # %% first
+        a = 1
+
+        // %%
+        b = 2
+        #%%
+        ˇ"});
+    cx.update_editor(|e, cx| e.go_to_prev_cell(&GoToPrevCell, cx));
+    cx.update_editor(|e, cx| e.go_to_prev_cell(&GoToPrevCell, cx));
+    cx.assert_editor_state(indoc! {"
+        import os
+
+        # %% first
+        ˇa = 1
+
+        // %%
+        b = 2
+        #%%
+    "});
+
+    // Running cells leaves out their markers and empty cells.
+    cx.update_editor(|e, cx| {
+        assert_eq!(
+            e.cell_code(CellsToRun::Current, cx).as_deref(),
+            Some("a = 1")
+        );
+        assert_eq!(
+            e.cell_code(CellsToRun::Above, cx).as_deref(),
+            Some("import os")
+        );
+        assert_eq!(
+            e.cell_code(CellsToRun::Below, cx).as_deref(),
+            Some("a = 1\nb = 2")
+        );
+    });
+
+    cx.set_state("ˇa = 1\n#% not a marker\n");
+    cx.update_editor(|e, cx| assert_eq!(e.cell_code(CellsToRun::Current, cx), None));
+}
+
+#[gpui::test]
+async fn test_cells_markdown(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.set_state(indoc! {"
+        ˇ# %% [markdown]
+        # # Results
+        #
+        # The *first* value:
+
+        # %%
+        a = 1
+
+        # %%
+        #%% [markdown]
+        #Done.
+    "});
+
+    cx.update_editor(|e, cx| {
+        assert_eq!(
+            e.cells_markdown(cx).as_deref(),
+            Some(indoc! {"
+                # Results
+
+                The *first* value:
+
+                ```
+                a = 1
+                ```
+
+                Done.
+            "})
+        );
+    });
+
+    cx.set_state("ˇa = 1\n");
+    cx.update_editor(|e, cx| assert_eq!(e.cells_markdown(cx), None));
+}
+
+#[gpui::test]
+async fn test_cell_marker_decorations(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.set_state(indoc! {"
+        ˇimport os
+        # %%
+        a = 1
+    "});
+
+    // Buffers whose language can't have cells aren't scanned for markers.
+    cx.update_editor(|e, _| assert_eq!(e.decorations::<CellMarkers>(), None));
+
+    let python = Arc::new(Language::new(
+        LanguageConfig {
+            name: "Python".into(),
+            line_comments: vec!["# ".into()],
+            ..Default::default()
+        },
+        None,
+    ));
+    cx.update_buffer(|buffer, cx| buffer.set_language(Some(python), cx));
+    assert_eq!(cell_marker_rows(&mut cx), [1]);
+
+    // Markers move with the rows inserted above them.
+    cx.update_editor(|e, cx| e.insert("# %%\n", cx));
+    assert_eq!(cell_marker_rows(&mut cx), [0, 2]);
+
+    // Edits at several cursors are each scanned.
+    cx.update_editor(|e, cx| {
+        e.change_selections(None, cx, |s| {
+            s.select_ranges([
+                Point::new(1, 0)..Point::new(1, 0),
+                Point::new(3, 0)..Point::new(3, 0),
+            ])
+        });
+        e.insert("#%%\n", cx);
+    });
+    cx.assert_editor_state(indoc! {"
+        # %%
+        #%%
+        ˇimport os
+        # %%
+        #%%
+        ˇa = 1
+    "});
+    assert_eq!(cell_marker_rows(&mut cx), [0, 1, 3, 4]);
+
+    // Markers go away when their row is deleted or stops starting with a marker.
+    cx.update_editor(|e, cx| {
+        e.change_selections(None, cx, |s| {
+            s.select_ranges([Point::new(0, 0)..Point::new(0, 0)])
+        });
+        e.delete_line(&DeleteLine, cx);
+        e.change_selections(None, cx, |s| {
+            s.select_ranges([Point::new(2, 0)..Point::new(2, 0)])
+        });
+        e.insert("x", cx);
+    });
+    assert_eq!(cell_marker_rows(&mut cx), [0, 3]);
+
+    let markdown = Arc::new(Language::new(
+        LanguageConfig {
+            name: "Markdown".into(),
+            ..Default::default()
+        },
+        None,
+    ));
+    cx.update_buffer(|buffer, cx| buffer.set_language(Some(markdown), cx));
+    cx.update_editor(|e, _| assert_eq!(e.decorations::<CellMarkers>(), None));
+}
+
+/// The rows of the cell markers that are decorated once the decorations are refreshed, which are
+/// checked against those found by scanning the whole buffer.
+fn cell_marker_rows(cx: &mut EditorTestContext) -> Vec<u32> {
+    cx.executor()
+        .advance_clock(crate::decorations::DECORATION_REFRESH_DEBOUNCE);
+    cx.run_until_parked();
+    cx.update_editor(|e, cx| {
+        let buffer = e.buffer.read(cx).snapshot(cx);
+        let rows = e
+            .decorations::<CellMarkers>()
+            .unwrap()
+            .iter()
+            .map(|decoration| decoration.range.start.to_point(&buffer).row)
+            .collect::<Vec<_>>();
+        let scanned_rows = cells(&buffer)
+            .iter()
+            .filter_map(|cell| cell.marker_row)
+            .collect::<Vec<_>>();
+        assert_eq!(rows, scanned_rows);
+        rows
+    })
+}
+
+#[gpui::test]
+fn test_duplicate_line(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let view = cx.add_window(|cx| {
+        let buffer = MultiBuffer::build_simple("abc\ndef\nghi\n", cx);
+        build_editor(buffer, cx)
+    });
+    _ = view.update(cx, |view, cx| {
+        view.change_selections(None, cx, |s| {
+            s.select_display_ranges([
+                DisplayPoint::new(DisplayRow(0), 0)..DisplayPoint::new(DisplayRow(0), 1),
+                DisplayPoint::new(DisplayRow(0), 2)..DisplayPoint::new(DisplayRow(0), 2),
+                DisplayPoint::new(DisplayRow(1), 0)..DisplayPoint::new(DisplayRow(1), 0),
+                DisplayPoint::new(DisplayRow(3), 0)..DisplayPoint::new(DisplayRow(3), 0),
+            ])
+        });
+        view.duplicate_line_down(&DuplicateLineDown, cx);
+        assert_eq!(view.display_text(cx), "abc\nabc\ndef\ndef\nghi\n\n");
+        assert_eq!(
+            view.selections.display_ranges(cx),
+            vec![
+                DisplayPoint::new(DisplayRow(1), 0)..DisplayPoint::new(DisplayRow(1), 1),
+                DisplayPoint::new(DisplayRow(1), 2)..DisplayPoint::new(DisplayRow(1), 2),
+                DisplayPoint::new(DisplayRow(3), 0)..DisplayPoint::new(DisplayRow(3), 0),
+                DisplayPoint::new(DisplayRow(6), 0)..DisplayPoint::new(DisplayRow(6), 0),
+            ]
+        );
+    });
+
+    let view = cx.add_window(|cx| {
+        let buffer = MultiBuffer::build_simple("abc\ndef\nghi\n", cx);
+        build_editor(buffer, cx)
+    });
+    _ = view.update(cx, |view, cx| {
+        view.change_selections(None, cx, |s| {
+            s.select_display_ranges([
+                DisplayPoint::new(DisplayRow(0), 1)..DisplayPoint::new(DisplayRow(1), 1),
+                DisplayPoint::new(DisplayRow(1), 2)..DisplayPoint::new(DisplayRow(2), 1),
+            ])
+        });
+        view.duplicate_line_down(&DuplicateLineDown, cx);
+        assert_eq!(view.display_text(cx), "abc\ndef\nghi\nabc\ndef\nghi\n");
+        assert_eq!(
             view.selections.display_ranges(cx),
             vec![
                 DisplayPoint::new(DisplayRow(3), 1)..DisplayPoint::new(DisplayRow(4), 1),
@@ -3702,6 +4279,117 @@ async fn test_clipboard(cx: &mut gpui::TestAppContext) {
         tˇhe lazy dog"});
 }
 
+#[gpui::test]
+async fn test_copy_redacted_values(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+    cx.update(|cx| {
+        SettingsStore::update_global(cx, |store, cx| {
+            store.update_user_settings::<EditorSettings>(cx, |settings| {
+                settings.redact_private_values = Some(true);
+            });
+        });
+    });
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({ ".env": "let token = \"secret\";\nlet name = 1;\n" }),
+    )
+    .await;
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+    let buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/.env", cx))
+        .await
+        .unwrap();
+    let language = Language::new(
+        LanguageConfig {
+            name: "Rust".into(),
+            ..Default::default()
+        },
+        Some(tree_sitter_rust::language()),
+    )
+    .with_redaction_query("(string_literal) @redact")
+    .unwrap();
+    buffer.update(cx, |buffer, cx| {
+        buffer.set_language(Some(Arc::new(language)), cx)
+    });
+    let buffer = cx.new_model(|cx| MultiBuffer::singleton(buffer, cx));
+    let (editor, cx) = cx.add_window_view(|cx| build_editor(buffer, cx));
+    cx.run_until_parked();
+
+    let clipboard_text =
+        |cx: &mut VisualTestContext| cx.read_from_clipboard().map(|item| item.text().to_string());
+    let select = |editor: &mut Editor, range: Range<Point>, cx: &mut ViewContext<Editor>| {
+        editor.change_selections(None, cx, |s| s.select_ranges([range]));
+    };
+
+    // Text without redacted values is copied right away.
+    editor.update(cx, |editor, cx| {
+        select(editor, Point::new(1, 0)..Point::new(1, 8), cx);
+        editor.copy(&Copy, cx);
+    });
+    assert!(!cx.has_pending_prompt());
+    assert_eq!(clipboard_text(cx).as_deref(), Some("let name"));
+
+    // Redacted values are copied as they were when copying, once that's confirmed.
+    editor.update(cx, |editor, cx| {
+        select(editor, Point::new(0, 0)..Point::new(0, 21), cx);
+        editor.copy(&Copy, cx);
+        select(editor, Point::new(1, 0)..Point::new(1, 0), cx);
+        editor.insert("// ", cx);
+    });
+    assert!(cx.has_pending_prompt());
+    cx.simulate_prompt_answer(0);
+    cx.run_until_parked();
+    assert_eq!(
+        clipboard_text(cx).as_deref(),
+        Some("let token = \"secret\";")
+    );
+
+    // Cutting them can be cancelled, which leaves the text alone.
+    editor.update(cx, |editor, cx| {
+        select(editor, Point::new(0, 4)..Point::new(0, 4), cx);
+        editor.cut(&Cut, cx);
+    });
+    cx.simulate_prompt_answer(1);
+    cx.run_until_parked();
+    editor.update(cx, |editor, cx| {
+        assert_eq!(
+            editor.text(cx),
+            "let token = \"secret\";\n// let name = 1;\n"
+        );
+    });
+    assert_eq!(
+        clipboard_text(cx).as_deref(),
+        Some("let token = \"secret\";")
+    );
+
+    // Once confirmed, the line that was selected is cut, even though the cursor has moved.
+    editor.update(cx, |editor, cx| {
+        editor.cut(&Cut, cx);
+        select(editor, Point::new(1, 0)..Point::new(1, 0), cx);
+    });
+    cx.simulate_prompt_answer(0);
+    cx.run_until_parked();
+    editor.update(cx, |editor, cx| {
+        assert_eq!(editor.text(cx), "// let name = 1;\n");
+    });
+    assert_eq!(
+        clipboard_text(cx).as_deref(),
+        Some("let token = \"secret\";\n")
+    );
+
+    // Nothing is asked once the values are revealed.
+    editor.update(cx, |editor, cx| {
+        editor.undo(&Undo, cx);
+        editor.toggle_redacted_values(&ToggleRedactedValues, cx);
+        select(editor, Point::new(0, 12)..Point::new(0, 20), cx);
+        editor.copy(&Copy, cx);
+    });
+    assert!(!cx.has_pending_prompt());
+    assert_eq!(clipboard_text(cx).as_deref(), Some("\"secret\""));
+}
+
 #[gpui::test]
 async fn test_paste_multiline(cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
@@ -6342,35 +7030,215 @@ async fn test_document_format_manual_trigger(cx: &mut gpui::TestAppContext) {
 }
 
 #[gpui::test]
-async fn test_concurrent_format_requests(cx: &mut gpui::TestAppContext) {
-    init_test(cx, |_| {});
+async fn test_multibuffer_format_manual_trigger(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |settings| {
+        settings.defaults.formatter = Some(language_settings::Formatter::LanguageServer)
+    });
 
-    let mut cx = EditorLspTestContext::new_rust(
-        lsp::ServerCapabilities {
-            document_formatting_provider: Some(lsp::OneOf::Left(true)),
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/a", json!({ "main.rs": sample_text(10, 4, 'a') }))
+        .await;
+
+    let project = Project::test(fs, ["/a".as_ref()], cx).await;
+    let workspace = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
+    let cx = &mut VisualTestContext::from_window(*workspace.deref(), cx);
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp_adapter(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                document_range_formatting_provider: Some(lsp::OneOf::Left(true)),
+                ..Default::default()
+            },
             ..Default::default()
         },
-        cx,
-    )
-    .await;
-
-    cx.set_state(indoc! {"
-        one.twoˇ
-    "});
+    );
 
-    // The format request takes a long time. When it completes, it inserts
-    // a newline and an indent before the `.`
-    cx.lsp
-        .handle_request::<lsp::request::Formatting, _, _>(move |_, cx| {
-            let executor = cx.background_executor().clone();
-            async move {
-                executor.timer(Duration::from_millis(100)).await;
-                Ok(Some(vec![lsp::TextEdit {
-                    range: lsp::Range::new(lsp::Position::new(0, 3), lsp::Position::new(0, 3)),
-                    new_text: "\n    ".into(),
-                }]))
-            }
-        });
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/a/main.rs", cx)
+        })
+        .await
+        .unwrap();
+
+    cx.executor().start_waiting();
+    let fake_server = fake_servers.next().await.unwrap();
+
+    let multi_buffer = cx.new_model(|cx| {
+        let mut multi_buffer = MultiBuffer::new(0, ReadWrite);
+        multi_buffer.push_excerpts(
+            buffer.clone(),
+            [
+                ExcerptRange {
+                    context: Point::new(0, 0)..Point::new(2, 0),
+                    primary: None,
+                },
+                ExcerptRange {
+                    context: Point::new(5, 0)..Point::new(7, 0),
+                    primary: None,
+                },
+            ],
+            cx,
+        );
+        multi_buffer
+    });
+    let editor =
+        cx.new_view(|cx| Editor::new(EditorMode::Full, multi_buffer, Some(project.clone()), cx));
+
+    // Only the excerpted ranges are formatted, each with its own request.
+    let format = editor
+        .update(cx, |editor, cx| {
+            editor.perform_format(project.clone(), FormatTrigger::Manual, cx)
+        })
+        .unwrap();
+    let mut requests = fake_server.handle_request::<lsp::request::RangeFormatting, _, _>(
+        move |params, _| async move {
+            assert_eq!(
+                params.text_document.uri,
+                lsp::Url::from_file_path("/a/main.rs").unwrap()
+            );
+            Ok(Some(vec![lsp::TextEdit::new(
+                lsp::Range::new(params.range.start, params.range.start),
+                "// formatted\n".to_string(),
+            )]))
+        },
+    );
+    requests.next().await;
+    requests.next().await;
+    cx.executor().start_waiting();
+    format.await;
+
+    assert_eq!(
+        buffer.read_with(cx, |buffer, _| buffer.text()),
+        "// formatted\naaaa\nbbbb\ncccc\ndddd\neeee\n// formatted\nffff\ngggg\nhhhh\niiii\njjjj"
+    );
+    assert_eq!(
+        editor.update(cx, |editor, cx| editor.text(cx)),
+        "// formatted\naaaa\nbbbb\n\n// formatted\nffff\ngggg\n"
+    );
+}
+
+#[gpui::test]
+async fn test_multibuffer_format_overlapping_edits(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |settings| {
+        settings.defaults.formatter = Some(language_settings::Formatter::LanguageServer)
+    });
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/a", json!({ "main.rs": sample_text(10, 4, 'a') }))
+        .await;
+
+    let project = Project::test(fs, ["/a".as_ref()], cx).await;
+    let workspace = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
+    let cx = &mut VisualTestContext::from_window(*workspace.deref(), cx);
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp_adapter(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                document_range_formatting_provider: Some(lsp::OneOf::Left(true)),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/a/main.rs", cx)
+        })
+        .await
+        .unwrap();
+
+    cx.executor().start_waiting();
+    let fake_server = fake_servers.next().await.unwrap();
+
+    let multi_buffer = cx.new_model(|cx| {
+        let mut multi_buffer = MultiBuffer::new(0, ReadWrite);
+        multi_buffer.push_excerpts(
+            buffer.clone(),
+            [
+                ExcerptRange {
+                    context: Point::new(0, 0)..Point::new(2, 0),
+                    primary: None,
+                },
+                ExcerptRange {
+                    context: Point::new(5, 0)..Point::new(7, 0),
+                    primary: None,
+                },
+            ],
+            cx,
+        );
+        multi_buffer
+    });
+    let editor =
+        cx.new_view(|cx| Editor::new(EditorMode::Full, multi_buffer, Some(project.clone()), cx));
+
+    // Both responses contain the same edit outside of the requested range, which is applied once.
+    let format = editor
+        .update(cx, |editor, cx| {
+            editor.perform_format(project.clone(), FormatTrigger::Manual, cx)
+        })
+        .unwrap();
+    let mut requests = fake_server.handle_request::<lsp::request::RangeFormatting, _, _>(
+        move |params, _| async move {
+            Ok(Some(vec![
+                lsp::TextEdit::new(
+                    lsp::Range::new(lsp::Position::new(3, 0), lsp::Position::new(3, 4)),
+                    "DDDD".to_string(),
+                ),
+                lsp::TextEdit::new(
+                    lsp::Range::new(params.range.start, params.range.start),
+                    "// formatted\n".to_string(),
+                ),
+            ]))
+        },
+    );
+    requests.next().await;
+    requests.next().await;
+    cx.executor().start_waiting();
+    format.await;
+
+    assert_eq!(
+        buffer.read_with(cx, |buffer, _| buffer.text()),
+        "// formatted\naaaa\nbbbb\ncccc\nDDDD\neeee\n// formatted\nffff\ngggg\nhhhh\niiii\njjjj"
+    );
+}
+
+#[gpui::test]
+async fn test_concurrent_format_requests(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorLspTestContext::new_rust(
+        lsp::ServerCapabilities {
+            document_formatting_provider: Some(lsp::OneOf::Left(true)),
+            ..Default::default()
+        },
+        cx,
+    )
+    .await;
+
+    cx.set_state(indoc! {"
+        one.twoˇ
+    "});
+
+    // The format request takes a long time. When it completes, it inserts
+    // a newline and an indent before the `.`
+    cx.lsp
+        .handle_request::<lsp::request::Formatting, _, _>(move |_, cx| {
+            let executor = cx.background_executor().clone();
+            async move {
+                executor.timer(Duration::from_millis(100)).await;
+                Ok(Some(vec![lsp::TextEdit {
+                    range: lsp::Range::new(lsp::Position::new(0, 3), lsp::Position::new(0, 3)),
+                    new_text: "\n    ".into(),
+                }]))
+            }
+        });
 
     // Submit a format request.
     let format_1 = cx
@@ -6703,6 +7571,194 @@ async fn test_completion(cx: &mut gpui::TestAppContext) {
     apply_additional_edits.await.unwrap();
 }
 
+#[gpui::test]
+async fn test_completion_with_multiple_cursors(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorLspTestContext::new_rust(
+        lsp::ServerCapabilities {
+            completion_provider: Some(lsp::CompletionOptions {
+                trigger_characters: Some(vec![".".to_string()]),
+                resolve_provider: Some(true),
+                ..Default::default()
+            }),
+            ..Default::default()
+        },
+        cx,
+    )
+    .await;
+    let counter = Arc::new(AtomicUsize::new(0));
+
+    // Each cursor's own prefix is replaced, even where it differs from the newest cursor's.
+    cx.set_state(indoc! {"
+        let a = foo.baˇ;
+        let b = baz.qˇ;
+        let c = qux.bˇ;
+    "});
+    cx.update_editor(|editor, cx| editor.show_completions(&ShowCompletions, cx));
+    handle_completion_request(
+        &mut cx,
+        indoc! {"
+            let a = foo.ba;
+            let b = baz.q;
+            let c = qux.<b|>;
+        "},
+        vec!["bar"],
+        counter.clone(),
+    )
+    .await;
+    cx.condition(|editor, _| editor.context_menu_visible())
+        .await;
+    let apply_additional_edits = cx.update_editor(|editor, cx| {
+        editor
+            .confirm_completion(&ConfirmCompletion::default(), cx)
+            .unwrap()
+    });
+    cx.assert_editor_state(indoc! {"
+        let a = foo.barˇ;
+        let b = baz.barˇ;
+        let c = qux.barˇ;
+    "});
+    handle_resolve_completion_request(&mut cx, None).await;
+    apply_additional_edits.await.unwrap();
+}
+
+#[gpui::test]
+async fn test_word_completions(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+    let mut cx = EditorTestContext::new(cx).await;
+
+    let completion_labels = |cx: &mut EditorTestContext| {
+        cx.update_editor(|editor, _| {
+            if let Some(ContextMenu::Completions(menu)) = editor.context_menu.read().as_ref() {
+                menu.matches
+                    .iter()
+                    .map(|m| m.string.clone())
+                    .collect::<Vec<_>>()
+            } else {
+                panic!("expected completion menu to be open");
+            }
+        })
+    };
+
+    // Without a language server, words of the buffer are offered, nearest first.
+    cx.set_state(indoc! {"
+        needle
+        other words 1234
+        nested
+        neˇ"});
+    cx.update_editor(|editor, cx| editor.show_completions(&ShowCompletions, cx));
+    cx.executor().run_until_parked();
+    assert_eq!(completion_labels(&mut cx), &["nested", "needle"]);
+
+    cx.update_editor(|editor, cx| {
+        editor.context_menu_next(&Default::default(), cx);
+        editor
+            .confirm_completion(&ConfirmCompletion::default(), cx)
+            .unwrap()
+    })
+    .await
+    .unwrap();
+    cx.assert_editor_state(indoc! {"
+        needle
+        other words 1234
+        nested
+        needleˇ"});
+
+    // Recently completed words come first.
+    cx.set_state(indoc! {"
+        needle
+        other words 1234
+        nested
+        neˇ"});
+    cx.update_editor(|editor, cx| editor.show_completions(&ShowCompletions, cx));
+    cx.executor().run_until_parked();
+    assert_eq!(completion_labels(&mut cx), &["needle", "nested"]);
+    cx.update_editor(|editor, cx| editor.cancel(&Cancel, cx));
+
+    _ = cx.update(|cx| {
+        cx.update_global::<SettingsStore, _>(|settings, cx| {
+            settings.update_user_settings::<EditorSettings>(cx, |settings| {
+                settings.word_completions = Some(false);
+            });
+        })
+    });
+    cx.update_editor(|editor, cx| editor.show_completions(&ShowCompletions, cx));
+    cx.executor().run_until_parked();
+    assert!(cx.editor(|e, _| e.context_menu.read().is_none()));
+}
+
+#[gpui::test]
+fn test_editor_event_stream(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+    _ = cx.update(|cx| {
+        cx.update_global::<SettingsStore, _>(|settings, cx| {
+            settings.update_user_settings::<EditorSettings>(cx, |settings| {
+                settings.event_stream = Some(EventStreamContent {
+                    enabled: Some(true),
+                    interval_ms: Some(100),
+                    ..Default::default()
+                });
+            });
+        })
+    });
+
+    let events = Rc::new(RefCell::new(Vec::new()));
+    _ = cx.update(|cx| {
+        let stream = EditorEventStream::global(cx).unwrap();
+        let events = events.clone();
+        cx.subscribe(&stream, move |_, event, _| {
+            events.borrow_mut().push(event.clone())
+        })
+        .detach();
+    });
+
+    let buffer = cx.update(|cx| MultiBuffer::build_simple("one two", cx));
+    let editor = cx.add_window(|cx| build_editor(buffer, cx));
+    _ = editor.update(cx, |editor, cx| {
+        editor.change_selections(None, cx, |s| s.select_ranges([3..3]));
+        editor.change_selections(None, cx, |s| s.select_ranges([7..7]));
+        editor.handle_input(" three", cx);
+    });
+    cx.executor().run_until_parked();
+    assert!(events.borrow().is_empty());
+
+    cx.executor().advance_clock(Duration::from_millis(100));
+    cx.executor().run_until_parked();
+    let events = events.borrow();
+    let opened = events
+        .iter()
+        .filter_map(|event| match event {
+            EditorStreamEvent::BufferOpened { buffer, .. } => Some(buffer.path.clone()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(opened, [None]);
+    let edits = events
+        .iter()
+        .filter_map(|event| match event {
+            EditorStreamEvent::Edited { edits, .. } => Some(edits.clone()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        edits,
+        [vec![StreamEdit {
+            range: Point::new(0, 7)..Point::new(0, 13),
+            new_text: None,
+        }]]
+    );
+    // Selection changes within a batch are coalesced into the latest one.
+    let selections = events
+        .iter()
+        .filter_map(|event| match event {
+            EditorStreamEvent::SelectionsChanged { selections, .. } => Some(selections.clone()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(selections, [vec![Point::new(0, 13)..Point::new(0, 13)]]);
+}
+
 #[gpui::test]
 async fn test_no_duplicated_completion_requests(cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
@@ -6800,6 +7856,112 @@ async fn test_no_duplicated_completion_requests(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_inline_completion_provider_priority(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.set_state("let x = ˇ");
+
+    let first = cx.new_model(|_| FakeInlineCompletionProvider::<true> {
+        completion: Some("first()\nnext_line()".into()),
+    });
+    let second = cx.new_model(|_| FakeInlineCompletionProvider::<false> {
+        completion: Some("second()".into()),
+    });
+    cx.update_editor(|editor, cx| {
+        editor.register_inline_completion_provider(first, cx);
+        editor.register_inline_completion_provider(second, cx);
+    });
+    // Providers with the same priority are preferred in the order they were registered.
+    cx.update_editor(|editor, cx| {
+        assert_eq!(editor.display_text(cx), "let x = first()\nnext_line()");
+    });
+
+    let set_provider_settings = |cx: &mut EditorTestContext, enabled, priority| {
+        cx.update(|cx| {
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings::<AllLanguageSettings>(cx, |settings| {
+                    settings
+                        .inline_completions
+                        .get_or_insert_with(Default::default)
+                        .providers = Some(HashMap::from_iter([(
+                        "second".to_string(),
+                        language::language_settings::InlineCompletionProviderSettings {
+                            enabled,
+                            priority,
+                        },
+                    )]));
+                });
+            });
+        });
+        cx.update_editor(|editor, cx| editor.show_inline_completion(&Default::default(), cx));
+    };
+
+    set_provider_settings(&mut cx, None, Some(1));
+    cx.update_editor(|editor, cx| {
+        assert_eq!(editor.display_text(cx), "let x = second()");
+    });
+
+    set_provider_settings(&mut cx, Some(false), Some(1));
+    cx.update_editor(|editor, cx| {
+        assert_eq!(editor.display_text(cx), "let x = first()\nnext_line()");
+
+        // Accepting a line leaves the rest of the suggestion to be accepted later.
+        editor.accept_inline_completion_line(&Default::default(), cx);
+        assert_eq!(editor.text(cx), "let x = first()");
+    });
+}
+
+#[derive(Default)]
+struct FakeInlineCompletionProvider<const FIRST: bool> {
+    completion: Option<String>,
+}
+
+impl<const FIRST: bool> InlineCompletionProvider for FakeInlineCompletionProvider<FIRST> {
+    fn name() -> &'static str {
+        if FIRST {
+            "first"
+        } else {
+            "second"
+        }
+    }
+
+    fn is_enabled(&self, _: &Model<Buffer>, _: language::Anchor, _: &AppContext) -> bool {
+        true
+    }
+
+    fn refresh(
+        &mut self,
+        _: Model<Buffer>,
+        _: language::Anchor,
+        _: bool,
+        _: &mut ModelContext<Self>,
+    ) {
+    }
+
+    fn cycle(
+        &mut self,
+        _: Model<Buffer>,
+        _: language::Anchor,
+        _: Direction,
+        _: &mut ModelContext<Self>,
+    ) {
+    }
+
+    fn accept(&mut self, _: &mut ModelContext<Self>) {}
+
+    fn discard(&mut self, _: bool, _: &mut ModelContext<Self>) {}
+
+    fn active_completion_text<'a>(
+        &'a self,
+        _: &Model<Buffer>,
+        _: language::Anchor,
+        _: &'a AppContext,
+    ) -> Option<&'a str> {
+        self.completion.as_deref()
+    }
+}
+
 #[gpui::test]
 async fn test_toggle_comment(cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
@@ -7175,6 +8337,236 @@ async fn test_toggle_block_comment(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_toggle_block_comments_with_line_comments(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    let language = Arc::new(Language::new(
+        LanguageConfig {
+            line_comments: vec!["// ".into()],
+            block_comment: Some(("/* ".into(), " */".into())),
+            ..Default::default()
+        },
+        Some(tree_sitter_rust::language()),
+    ));
+    cx.update_buffer(|buffer, cx| buffer.set_language(Some(language), cx));
+
+    // Block comments are used when requested explicitly
+    cx.set_state("let a = 1;ˇ\nlet b = 2;\n");
+    cx.update_editor(|editor, cx| editor.toggle_block_comments(&ToggleBlockComments, cx));
+    cx.assert_editor_state("/* let a = 1;ˇ */\nlet b = 2;\n");
+
+    // An existing block comment is removed, even though the language prefers line comments
+    cx.update_editor(|editor, cx| editor.toggle_comments(&ToggleComments::default(), cx));
+    cx.assert_editor_state("let a = 1;ˇ\nlet b = 2;\n");
+
+    // Otherwise, line comments are used
+    cx.update_editor(|editor, cx| editor.toggle_comments(&ToggleComments::default(), cx));
+    cx.assert_editor_state("// let a = 1;ˇ\nlet b = 2;\n");
+}
+
+#[gpui::test]
+async fn test_newline_block_comments(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |settings| {
+        settings.defaults.tab_size = NonZeroU32::new(4)
+    });
+
+    let mut cx = EditorTestContext::new(cx).await;
+    let language = Arc::new(
+        Language::new(
+            LanguageConfig {
+                line_comments: vec!["// ".into()],
+                block_comment: Some(("/* ".into(), " */".into())),
+                block_comment_continuation: Some("* ".into()),
+                ..Default::default()
+            },
+            Some(tree_sitter_rust::language()),
+        )
+        .with_override_query("[(line_comment) (block_comment)] @comment")
+        .unwrap()
+        .with_indents_query(r#"(_ "{" "}" @end) @indent"#)
+        .unwrap(),
+    );
+    cx.update_buffer(|buffer, cx| buffer.set_language(Some(language), cx));
+
+    // The continuation is aligned with the comment's opening delimiter
+    cx.set_state("/**ˇ */\n");
+    cx.update_editor(|e, cx| e.newline(&Newline, cx));
+    cx.assert_editor_state("/**\n * ˇ */\n");
+
+    // Subsequent lines keep the continuation
+    cx.set_state("/**\n * fooˇ\n */\n");
+    cx.update_editor(|e, cx| e.newline(&Newline, cx));
+    cx.assert_editor_state("/**\n * foo\n * ˇ\n */\n");
+
+    // Lines that only look like a continuation outside of comments are left alone
+    cx.set_state("fn f(x: &mut i32) {\n    *x = 1;ˇ\n}\n");
+    cx.update_editor(|e, cx| e.newline(&Newline, cx));
+    cx.assert_editor_state("fn f(x: &mut i32) {\n    *x = 1;\n    ˇ\n}\n");
+
+    // Closed comments are not continued
+    cx.set_state("/* foo */ˇ\n");
+    cx.update_editor(|e, cx| e.newline(&Newline, cx));
+    cx.assert_editor_state("/* foo */\nˇ\n");
+
+    // Other cursors are still auto-indented
+    cx.set_state("/**ˇ */\nfn f() {ˇ\n}\n");
+    cx.update_editor(|e, cx| e.newline(&Newline, cx));
+    cx.assert_editor_state("/**\n * ˇ */\nfn f() {\n    ˇ\n}\n");
+}
+
+#[gpui::test]
+async fn test_markdown_editing(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    let language = Arc::new(Language::new(
+        LanguageConfig {
+            continue_lists: true,
+            ..Default::default()
+        },
+        None,
+    ));
+    cx.update_buffer(|buffer, cx| buffer.set_language(Some(language), cx));
+
+    // List items and block quotes are continued
+    cx.set_state("  - fooˇ\n");
+    cx.update_editor(|e, cx| e.newline(&Newline, cx));
+    cx.assert_editor_state("  - foo\n  - ˇ\n");
+
+    cx.set_state("> 1. [x] fooˇ\n");
+    cx.update_editor(|e, cx| e.newline(&Newline, cx));
+    cx.assert_editor_state("> 1. [x] foo\n> 2. [ ] ˇ\n");
+
+    // A newline in an empty item ends the list
+    cx.set_state("- foo\n  - ˇ\n");
+    cx.update_editor(|e, cx| e.newline(&Newline, cx));
+    cx.assert_editor_state("- foo\n  ˇ\n");
+
+    // Each cursor continues or ends its own list item
+    cx.set_state(indoc! {"
+        - fooˇ
+        1. barˇ
+          - ˇ
+        plainˇ
+    "});
+    cx.update_editor(|e, cx| e.newline(&Newline, cx));
+    cx.assert_editor_state(indoc! {"
+        - foo
+        - ˇ
+        1. bar
+        2. ˇ
+          ˇ
+        plain
+        ˇ
+    "});
+
+    // Task list items on the selected lines are toggled
+    cx.set_state("- [ ] «foo\n- bar\n* [x] bazˇ»\n");
+    cx.update_editor(|e, cx| e.toggle_checkbox(&ToggleCheckbox, cx));
+    cx.assert_editor_state("- [x] «foo\n- bar\n* [ ] bazˇ»\n");
+
+    // Only the tables the cursor is in are formatted
+    cx.set_state("| a | bˇ |\n|-|-|\n| ccc | d |\n\n| e |\n|-|\n");
+    cx.update_editor(|e, cx| {
+        e.format_tables(&FormatTables, cx);
+        assert_eq!(
+            e.text(cx),
+            "| a   | b   |\n| --- | --- |\n| ccc | d   |\n\n| e |\n|-|\n"
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_auto_close_tags(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    let language = Arc::new(
+        Language::new(
+            LanguageConfig {
+                name: "HTML".into(),
+                block_comment: Some(("<!-- ".into(), " -->".into())),
+                auto_close_tags: true,
+                void_elements: vec!["br".into()],
+                opening_tag_node_kinds: vec!["start_tag".into()],
+                ..Default::default()
+            },
+            Some(tree_sitter_html::language()),
+        )
+        .with_override_query("(comment) @comment (quoted_attribute_value) @string")
+        .unwrap(),
+    );
+    cx.update_buffer(|buffer, cx| buffer.set_language(Some(language), cx));
+
+    // Completing an opening tag inserts the closing tag
+    cx.set_state("<div class=\"a\"ˇ");
+    cx.update_editor(|e, cx| e.handle_input(">", cx));
+    cx.assert_editor_state("<div class=\"a\">ˇ</div>");
+
+    // Self-closing tags and void elements are left alone
+    cx.set_state("<div/ˇ");
+    cx.update_editor(|e, cx| e.handle_input(">", cx));
+    cx.assert_editor_state("<div/>ˇ");
+    cx.set_state("<brˇ");
+    cx.update_editor(|e, cx| e.handle_input(">", cx));
+    cx.assert_editor_state("<br>ˇ");
+
+    // Tags that are already closed aren't closed again
+    cx.set_state("<pˇ</p>");
+    cx.update_editor(|e, cx| e.handle_input(">", cx));
+    cx.assert_editor_state("<p>ˇ</p>");
+
+    // `>` within an attribute value doesn't complete the tag
+    cx.set_state("<a title=\"ˇ");
+    cx.update_editor(|e, cx| e.handle_input(">", cx));
+    cx.assert_editor_state("<a title=\">ˇ");
+
+    // Editing an opening tag's name renames its closing tag
+    cx.set_state("<div><div></div></divˇ>");
+    cx.update_editor(|e, cx| {
+        e.handle_input("x", cx);
+        e.backspace(&Backspace, cx);
+        e.backspace(&Backspace, cx);
+    });
+    cx.assert_editor_state("<di><div></div></diˇ>");
+    cx.set_state("<«divˇ»>\n  <span></span>\n</div>");
+    cx.update_editor(|e, cx| e.handle_input("section", cx));
+    cx.assert_editor_state("<sectionˇ>\n  <span></span>\n</section>");
+
+    // Text that isn't typed, like pasted text, doesn't rename the matching tag
+    cx.set_state("<«divˇ»></div>");
+    cx.update_editor(|e, cx| e.insert("span", cx));
+    cx.assert_editor_state("<spanˇ></div>");
+}
+
+#[gpui::test]
+async fn test_auto_close_jsx_tags(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    let language = Arc::new(Language::new(
+        LanguageConfig {
+            name: "TSX".into(),
+            auto_close_tags: true,
+            opening_tag_node_kinds: vec!["jsx_opening_element".into()],
+            ..Default::default()
+        },
+        Some(tree_sitter_typescript::language_tsx()),
+    ));
+    cx.update_buffer(|buffer, cx| buffer.set_language(Some(language), cx));
+
+    cx.set_state("let a = <divˇ");
+    cx.update_editor(|e, cx| e.handle_input(">", cx));
+    cx.assert_editor_state("let a = <div>ˇ</div>");
+
+    // A `<` that the syntax tree treats as a comparison doesn't start a tag
+    cx.set_state("if (a <bˇ) {}");
+    cx.update_editor(|e, cx| e.handle_input(">", cx));
+    cx.assert_editor_state("if (a <b>ˇ) {}");
+}
+
 #[gpui::test]
 fn test_editing_disjoint_excerpts(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -7618,6 +9010,163 @@ fn test_highlighted_ranges(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+fn test_decoration_provider(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let editor = cx.add_window(|cx| {
+        let buffer = MultiBuffer::build_simple("one TODO\ntwo\nTODO three", cx);
+        build_editor(buffer.clone(), cx)
+    });
+
+    struct Todos;
+
+    let underline = UnderlineStyle {
+        color: Some(Hsla::red()),
+        thickness: px(1.),
+        wavy: true,
+    };
+    _ = editor.update(cx, |editor, cx| {
+        editor.register_decoration_provider::<Todos>(
+            move |buffer: &MultiBufferSnapshot, _: &AppContext| {
+                let text = buffer.text();
+                text.match_indices("TODO")
+                    .map(|(ix, todo)| Decoration {
+                        range: buffer.anchor_after(ix)..buffer.anchor_before(ix + todo.len()),
+                        style: DecorationStyle {
+                            underline: Some(underline),
+                            inlay: Some("!".into()),
+                            gutter_icon: Some(GutterIcon {
+                                icon: ui::IconName::Check,
+                                color: ui::Color::Muted,
+                            }),
+                            ..Default::default()
+                        },
+                    })
+                    .collect::<Vec<_>>()
+            },
+            cx,
+        );
+
+        let snapshot = editor.snapshot(cx);
+        assert_eq!(
+            editor.decoration_underlines_in_range(Anchor::min()..Anchor::max(), &snapshot),
+            &[
+                (
+                    DisplayPoint::new(DisplayRow(0), 4)..DisplayPoint::new(DisplayRow(0), 8),
+                    underline,
+                ),
+                (
+                    DisplayPoint::new(DisplayRow(2), 0)..DisplayPoint::new(DisplayRow(2), 4),
+                    underline,
+                ),
+            ]
+        );
+        assert_eq!(editor.display_text(cx), "one TODO!\ntwo\nTODO! three");
+        assert!(editor
+            .visible_gutter_columns(cx)
+            .iter()
+            .any(|column| column.name() == "decorations"));
+
+        editor.change_selections(None, cx, |s| {
+            s.select_ranges([Point::new(1, 0)..Point::new(1, 0)])
+        });
+        editor.insert("TODO ", cx);
+    });
+
+    // Decorations are refreshed once the buffer stops changing.
+    cx.executor()
+        .advance_clock(crate::decorations::DECORATION_REFRESH_DEBOUNCE);
+    cx.run_until_parked();
+    _ = editor.update(cx, |editor, cx| {
+        assert_eq!(editor.decorations::<Todos>().map(<[_]>::len), Some(3));
+        assert_eq!(editor.display_text(cx), "one TODO!\nTODO! two\nTODO! three");
+
+        editor.unregister_decoration_provider::<Todos>(cx);
+        assert_eq!(editor.decorations::<Todos>(), None);
+        assert_eq!(editor.display_text(cx), "one TODO\nTODO two\nTODO three");
+        assert!(editor.visible_gutter_columns(cx).is_empty());
+    });
+}
+
+#[gpui::test]
+fn test_gutter_columns(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let editor = cx.add_window(|cx| {
+        let buffer = MultiBuffer::build_simple("one\ntwo", cx);
+        build_editor(buffer.clone(), cx)
+    });
+
+    struct TestColumn {
+        name: &'static str,
+        width: f32,
+        order: i32,
+    }
+
+    impl GutterColumn for TestColumn {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        fn width(&self) -> f32 {
+            self.width
+        }
+
+        fn order(&self) -> i32 {
+            self.order
+        }
+
+        fn render_row(
+            &self,
+            _: MultiBufferRow,
+            _: &EditorSnapshot,
+            _: &mut WindowContext,
+        ) -> Option<AnyElement> {
+            Some(div().into_any_element())
+        }
+    }
+
+    fn visible_columns(editor: &Editor, cx: &AppContext) -> Vec<&'static str> {
+        editor
+            .visible_gutter_columns(cx)
+            .iter()
+            .map(|column| column.name())
+            .collect()
+    }
+
+    _ = editor.update(cx, |editor, cx| {
+        let column = |name, width, order| TestColumn { name, width, order };
+        editor.register_gutter_column(column("bookmarks", 1., 1), cx);
+        editor.register_gutter_column(column("coverage", 2., 0), cx);
+        // Registering a column again replaces it.
+        editor.register_gutter_column(column("bookmarks", 3., 1), cx);
+
+        assert_eq!(visible_columns(editor, cx), ["coverage", "bookmarks"]);
+        assert_eq!(editor.snapshot(cx).gutter_columns_width, 5.);
+    });
+
+    _ = cx.update(|cx| {
+        cx.update_global::<SettingsStore, _>(|settings, cx| {
+            settings.update_user_settings::<EditorSettings>(cx, |settings| {
+                settings.gutter = Some(GutterContent {
+                    columns: Some(HashMap::from_iter([("coverage".to_string(), false)])),
+                    ..Default::default()
+                });
+            });
+        })
+    });
+
+    _ = editor.update(cx, |editor, cx| {
+        assert_eq!(visible_columns(editor, cx), ["bookmarks"]);
+        assert_eq!(editor.snapshot(cx).gutter_columns_width, 3.);
+
+        editor.unregister_gutter_column("bookmarks", cx);
+        assert!(visible_columns(editor, cx).is_empty());
+        assert_eq!(editor.snapshot(cx).gutter_columns_width, 0.);
+    });
+}
+
 #[gpui::test]
 async fn test_following(cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
@@ -7641,6 +9190,7 @@ async fn test_following(cx: &mut gpui::TestAppContext) {
             },
             |cx| cx.new_view(|cx| build_editor(buffer.clone(), cx)),
         )
+        .unwrap()
     });
 
     let is_still_following = Rc::new(RefCell::new(true));
@@ -1,8 +1,8 @@
 use crate::{
     blame_entry_tooltip::{blame_entry_relative_timestamp, BlameEntryTooltip},
     display_map::{
-        BlockContext, BlockStyle, DisplaySnapshot, FoldStatus, HighlightedChunk, ToDisplayPoint,
-        TransformBlock,
+        BlockContext, BlockId, BlockStyle, DisplaySnapshot, FoldStatus, HighlightedChunk,
+        ToDisplayPoint, TransformBlock,
     },
     editor_settings::{
         CurrentLineHighlight, DoubleClickInMultibuffer, MultiCursorModifier, ShowScrollbar,
@@ -18,6 +18,7 @@ use crate::{
     items::BufferSearchHighlights,
     mouse_context_menu::{self, MouseContextMenu},
     scroll::scroll_amount::ScrollAmount,
+    view_blocks::view_block_height,
     CodeActionsMenu, CursorShape, DisplayPoint, DisplayRow, DocumentHighlightRead,
     DocumentHighlightWrite, Editor, EditorMode, EditorSettings, EditorSnapshot, EditorStyle,
     ExpandExcerpts, GutterDimensions, HalfPageDown, HalfPageUp, HoveredCursor, HunkToExpand,
@@ -35,8 +36,8 @@ use gpui::{
     GlobalElementId, Hitbox, Hsla, InteractiveElement, IntoElement, ModifiersChangedEvent,
     MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent, PaintQuad, ParentElement, Pixels,
     ScrollDelta, ScrollWheelEvent, ShapedLine, SharedString, Size, Stateful,
-    StatefulInteractiveElement, Style, Styled, TextRun, TextStyle, TextStyleRefinement, View,
-    ViewContext, WeakView, WindowContext,
+    StatefulInteractiveElement, Style, Styled, TextRun, TextStyle, TextStyleRefinement,
+    UnderlineStyle, View, ViewContext, WeakView, WindowContext,
 };
 use itertools::Itertools;
 use language::language_settings::ShowWhitespaceSetting;
@@ -136,6 +137,9 @@ impl SelectionLayout {
 pub struct EditorElement {
     editor: View<Editor>,
     style: EditorStyle,
+    /// Whether the editor has already been laid out again this frame, after its view blocks
+    /// were measured at different heights than the display map had for them.
+    relaid_out_for_resized_blocks: bool,
 }
 
 type DisplayRowDelta = u32;
@@ -147,6 +151,7 @@ impl EditorElement {
         Self {
             editor: editor.clone(),
             style,
+            relaid_out_for_resized_blocks: false,
         }
     }
 
@@ -176,6 +181,10 @@ impl EditorElement {
         register_action(view, cx, Editor::tab);
         register_action(view, cx, Editor::tab_prev);
         register_action(view, cx, Editor::indent);
+        register_action(view, cx, Editor::increment_number);
+        register_action(view, cx, Editor::decrement_number);
+        register_action(view, cx, Editor::go_to_next_cell);
+        register_action(view, cx, Editor::go_to_prev_cell);
         register_action(view, cx, Editor::outdent);
         register_action(view, cx, Editor::delete_line);
         register_action(view, cx, Editor::join_lines);
@@ -271,6 +280,9 @@ impl EditorElement {
             editor.select_previous(action, cx).log_err();
         });
         register_action(view, cx, Editor::toggle_comments);
+        register_action(view, cx, Editor::toggle_block_comments);
+        register_action(view, cx, Editor::toggle_checkbox);
+        register_action(view, cx, Editor::format_tables);
         register_action(view, cx, Editor::select_larger_syntax_node);
         register_action(view, cx, Editor::select_smaller_syntax_node);
         register_action(view, cx, Editor::move_to_enclosing_bracket);
@@ -307,8 +319,15 @@ impl EditorElement {
         });
         register_action(view, cx, Editor::open_url);
         register_action(view, cx, Editor::fold);
+        register_action(view, cx, Editor::fold_all);
+        register_action(view, cx, Editor::fold_recursive);
         register_action(view, cx, Editor::fold_at);
+        register_action(view, cx, Editor::fold_at_level);
+        register_action(view, cx, Editor::cycle_heading_visibility);
+        register_action(view, cx, Editor::promote_heading);
+        register_action(view, cx, Editor::demote_heading);
         register_action(view, cx, Editor::unfold_lines);
+        register_action(view, cx, Editor::unfold_all);
         register_action(view, cx, Editor::unfold_at);
         register_action(view, cx, Editor::fold_selected_ranges);
         register_action(view, cx, Editor::show_completions);
@@ -317,6 +336,7 @@ impl EditorElement {
         register_action(view, cx, Editor::open_excerpts_in_split);
         register_action(view, cx, Editor::toggle_soft_wrap);
         register_action(view, cx, Editor::toggle_line_numbers);
+        register_action(view, cx, Editor::toggle_redacted_values);
         register_action(view, cx, Editor::toggle_inlay_hints);
         register_action(view, cx, hover_popover::hover);
         register_action(view, cx, Editor::reveal_in_finder);
@@ -373,6 +393,13 @@ impl EditorElement {
                 cx.propagate();
             }
         });
+        register_action(view, cx, |editor, action, cx| {
+            if let Some(task) = editor.find_backlinks(action, cx) {
+                task.detach_and_log_err(cx);
+            } else {
+                cx.propagate();
+            }
+        });
         register_action(view, cx, Editor::next_inline_completion);
         register_action(view, cx, Editor::previous_inline_completion);
         register_action(view, cx, Editor::show_inline_completion);
@@ -384,6 +411,7 @@ impl EditorElement {
         register_action(view, cx, Editor::unique_lines_case_insensitive);
         register_action(view, cx, Editor::unique_lines_case_sensitive);
         register_action(view, cx, Editor::accept_partial_inline_completion);
+        register_action(view, cx, Editor::accept_inline_completion_line);
         register_action(view, cx, Editor::revert_selected_hunks);
         register_action(view, cx, Editor::open_active_item_in_terminal)
     }
@@ -451,6 +479,15 @@ impl EditorElement {
             return;
         }
 
+        if click_count == 2 && !modifiers.modified() {
+            let point_for_position =
+                position_map.point_for_position(text_hitbox.bounds, event.position);
+            if editor.insert_hint_at(&point_for_position, &position_map.snapshot, cx) {
+                cx.stop_propagation();
+                return;
+            }
+        }
+
         if click_count == 2 && !editor.buffer().read(cx).is_singleton() {
             match EditorSettings::get_global(cx).double_click_in_multibuffer {
                 DoubleClickInMultibuffer::Select => {
@@ -1419,6 +1456,70 @@ impl EditorElement {
         Some(shaped_lines)
     }
 
+    #[allow(clippy::too_many_arguments)]
+    fn layout_gutter_columns(
+        &self,
+        buffer_rows: &[Option<MultiBufferRow>],
+        em_width: Pixels,
+        scroll_position: gpui::Point<f32>,
+        line_height: Pixels,
+        gutter_dimensions: &GutterDimensions,
+        gutter_hitbox: &Hitbox,
+        snapshot: &EditorSnapshot,
+        cx: &mut WindowContext,
+    ) -> Vec<AnyElement> {
+        if gutter_dimensions.columns_width == Pixels::ZERO {
+            return Vec::new();
+        }
+
+        let columns = self.editor.read(cx).visible_gutter_columns(cx);
+        let scroll_top = scroll_position.y * line_height;
+        let mut start_x = gutter_dimensions
+            .git_blame_entries_width
+            .unwrap_or(Pixels::ZERO);
+        let mut elements = Vec::new();
+        cx.with_element_namespace("gutter_columns", |cx| {
+            for column in columns {
+                let width = em_width * column.width();
+                for (ix, row) in buffer_rows.iter().enumerate() {
+                    let Some(row) = *row else {
+                        continue;
+                    };
+                    let Some(contents) = column.render_row(row, snapshot, cx) else {
+                        continue;
+                    };
+
+                    let mut element = div()
+                        .id((column.name(), row.0 as usize))
+                        .w(width)
+                        .h(line_height)
+                        .flex()
+                        .items_center()
+                        .justify_center()
+                        .child(contents)
+                        .on_mouse_down(MouseButton::Left, |_, cx| cx.stop_propagation())
+                        .on_click(cx.listener_for(&self.editor, {
+                            let column = column.clone();
+                            move |editor, _, cx| column.on_click(row, editor, cx)
+                        }))
+                        .into_any_element();
+                    let start_y = ix as f32 * line_height - (scroll_top % line_height);
+                    element.prepaint_as_root(
+                        gutter_hitbox.origin + point(start_x, start_y),
+                        size(
+                            AvailableSpace::Definite(width),
+                            AvailableSpace::Definite(line_height),
+                        ),
+                        cx,
+                    );
+                    elements.push(element);
+                }
+                start_x += width;
+            }
+        });
+        elements
+    }
+
     fn layout_run_indicators(
         &self,
         line_height: Pixels,
@@ -1669,7 +1770,6 @@ impl EditorElement {
     fn layout_lines(
         &self,
         rows: Range<DisplayRow>,
-        line_number_layouts: &[Option<ShapedLine>],
         snapshot: &EditorSnapshot,
         cx: &WindowContext,
     ) -> Vec<LineWithInvisibles> {
@@ -1705,18 +1805,30 @@ impl EditorElement {
                         .log_err()
                 })
                 .map(|line| LineWithInvisibles {
+                    leading_whitespace_end: 0,
+                    trailing_whitespace_start: line.text.len(),
                     line,
                     invisibles: Vec::new(),
                 })
                 .collect()
         } else {
+            // Include the row after the last one, to tell whether the last line continues.
+            let max_row = snapshot.max_point().row();
+            let soft_wrapped_rows = (rows.start.0..=rows.end.0)
+                .map(DisplayRow)
+                .map(|row| {
+                    row <= max_row
+                        && !snapshot.is_block_line(row)
+                        && snapshot.soft_wrap_indent(row).is_some()
+                })
+                .collect::<Vec<_>>();
             let chunks = snapshot.highlighted_chunks(rows.clone(), true, &self.style);
             LineWithInvisibles::from_chunks(
                 chunks,
                 &self.style.text,
                 MAX_LINE_LEN,
                 rows.len(),
-                line_number_layouts,
+                &soft_wrapped_rows,
                 snapshot.mode,
                 cx,
             )
@@ -1737,8 +1849,15 @@ impl EditorElement {
         line_height: Pixels,
         line_layouts: &[LineWithInvisibles],
         cx: &mut WindowContext,
-    ) -> Vec<BlockLayout> {
+    ) -> (Vec<BlockLayout>, HashMap<BlockId, u8>) {
         let mut block_id = 0;
+        let mut resized_blocks = HashMap::default();
+        let as_view_block = |block: &TransformBlock, cx: &WindowContext| match block {
+            TransformBlock::Custom(block) if self.editor.read(cx).is_view_block(block.id()) => {
+                Some(block.id())
+            }
+            _ => None,
+        };
         let (fixed_blocks, non_fixed_blocks) = snapshot
             .blocks_in_range(rows.clone())
             .partition::<Vec<_>, _>(|(_, block)| match block {
@@ -1844,7 +1963,11 @@ impl EditorElement {
                         let mut filename = None;
                         let mut parent_path = None;
                         // Can't use .and_then() because `.file_name()` and `.parent()` return references :(
-                        if let Some(path) = path {
+                        if let Some(label) =
+                            self.editor.read(cx).buffer_header_label(buffer.remote_id())
+                        {
+                            filename = Some(label.to_string());
+                        } else if let Some(path) = path {
                             filename = path.file_name().map(|f| f.to_string_lossy().to_string());
                             parent_path = path
                                 .parent()
@@ -2019,12 +2142,27 @@ impl EditorElement {
         let mut fixed_block_max_width = Pixels::ZERO;
         let mut blocks = Vec::new();
         for (row, block) in fixed_blocks {
+            let view_block_id = as_view_block(block, cx);
             let available_space = size(
                 AvailableSpace::MinContent,
-                AvailableSpace::Definite(block.height() as f32 * line_height),
+                if view_block_id.is_some() {
+                    AvailableSpace::MinContent
+                } else {
+                    AvailableSpace::Definite(block.height() as f32 * line_height)
+                },
             );
             let (element, element_size) = render_block(block, available_space, block_id, row, cx);
             block_id += 1;
+            if let Some(id) = view_block_id {
+                let height = view_block_height(element_size.height, line_height);
+                if height != block.height() {
+                    resized_blocks.insert(id, height);
+                }
+            }
+            let available_space = size(
+                AvailableSpace::MinContent,
+                AvailableSpace::Definite(block.height() as f32 * line_height),
+            );
             fixed_block_max_width = fixed_block_max_width.max(element_size.width + em_width);
             blocks.push(BlockLayout {
                 row,
@@ -2047,12 +2185,27 @@ impl EditorElement {
                     .max(gutter_dimensions.width + *scroll_width),
                 BlockStyle::Fixed => unreachable!(),
             };
+            let view_block_id = as_view_block(block, cx);
             let available_space = size(
                 AvailableSpace::Definite(width),
-                AvailableSpace::Definite(block.height() as f32 * line_height),
+                if view_block_id.is_some() {
+                    AvailableSpace::MinContent
+                } else {
+                    AvailableSpace::Definite(block.height() as f32 * line_height)
+                },
             );
-            let (element, _) = render_block(block, available_space, block_id, row, cx);
+            let (element, element_size) = render_block(block, available_space, block_id, row, cx);
             block_id += 1;
+            if let Some(id) = view_block_id {
+                let height = view_block_height(element_size.height, line_height);
+                if height != block.height() {
+                    resized_blocks.insert(id, height);
+                }
+            }
+            let available_space = size(
+                AvailableSpace::Definite(width),
+                AvailableSpace::Definite(block.height() as f32 * line_height),
+            );
             blocks.push(BlockLayout {
                 row,
                 element,
@@ -2062,7 +2215,7 @@ impl EditorElement {
         }
 
         *scroll_width = (*scroll_width).max(fixed_block_max_width - gutter_dimensions.width);
-        blocks
+        (blocks, resized_blocks)
     }
 
     fn layout_blocks(
@@ -2471,6 +2624,10 @@ impl EditorElement {
                 }
             });
 
+            for column_row in layout.gutter_columns.iter_mut() {
+                column_row.paint(cx);
+            }
+
             for test_indicators in layout.test_indicators.iter_mut() {
                 test_indicators.paint(cx);
             }
@@ -2643,6 +2800,7 @@ impl EditorElement {
                 cx.with_element_namespace("folds", |cx| self.paint_folds(layout, cx));
                 let invisible_display_ranges = self.paint_highlights(layout, cx);
                 self.paint_lines(&invisible_display_ranges, layout, cx);
+                self.paint_decoration_underlines(layout, cx);
                 self.paint_redactions(layout, cx);
                 self.paint_cursors(layout, cx);
                 self.paint_inline_blame(layout, cx);
@@ -2718,6 +2876,51 @@ impl EditorElement {
         }
     }
 
+    fn paint_decoration_underlines(&mut self, layout: &EditorLayout, cx: &mut WindowContext) {
+        let start_row = layout.visible_display_row_range.start;
+        let end_row = layout.visible_display_row_range.end;
+        let line_height = layout.position_map.line_height;
+        let scroll_position = layout.position_map.scroll_pixel_position;
+
+        for (range, underline) in &layout.decoration_underlines {
+            let underline = UnderlineStyle {
+                color: underline.color.or(Some(self.style.text.color)),
+                ..*underline
+            };
+            let last_row = if range.end.column() == 0 && range.end.row() > range.start.row() {
+                range.end.row().0 - 1
+            } else {
+                range.end.row().0
+            };
+            for row in range.start.row().0.max(start_row.0)..(last_row + 1).min(end_row.0) {
+                let row = DisplayRow(row);
+                let line = &layout.position_map.line_layouts[row.minus(start_row) as usize].line;
+                let start_x = if row == range.start.row() {
+                    line.x_for_index(range.start.column() as usize)
+                } else {
+                    Pixels::ZERO
+                };
+                let end_x = if row == range.end.row() {
+                    line.x_for_index(range.end.column() as usize)
+                } else {
+                    line.width
+                };
+                if end_x <= start_x {
+                    continue;
+                }
+
+                // Match the position of the underlines of the text itself.
+                let padding_top = (line_height - line.ascent - line.descent) / 2.;
+                let y = row.as_f32() * line_height - scroll_position.y
+                    + padding_top
+                    + line.ascent
+                    + line.descent * 0.618;
+                let origin = layout.content_origin + point(start_x - scroll_position.x, y);
+                cx.paint_underline(origin, end_x - start_x, &underline);
+            }
+        }
+    }
+
     fn paint_redactions(&mut self, layout: &EditorLayout, cx: &mut WindowContext) {
         if layout.redacted_ranges.is_empty() {
             return;
@@ -3371,14 +3574,15 @@ fn prepaint_gutter_button(
     );
     let indicator_size = button.layout_as_root(available_space, cx);
 
-    let blame_width = gutter_dimensions
+    let leading_width = gutter_dimensions
         .git_blame_entries_width
-        .unwrap_or(Pixels::ZERO);
+        .unwrap_or(Pixels::ZERO)
+        + gutter_dimensions.columns_width;
 
-    let mut x = blame_width;
+    let mut x = leading_width;
     let available_width = gutter_dimensions.margin + gutter_dimensions.left_padding
         - indicator_size.width
-        - blame_width;
+        - leading_width;
     x += available_width / 2.;
 
     let mut y = row.as_f32() * line_height - scroll_pixel_position.y;
@@ -3530,6 +3734,12 @@ fn deploy_blame_entry_context_menu(
 pub(crate) struct LineWithInvisibles {
     pub line: ShapedLine,
     invisibles: Vec<Invisible>,
+    /// The offset at which the line's leading whitespace ends. This is zero for lines that
+    /// continue a soft-wrapped line.
+    leading_whitespace_end: usize,
+    /// The offset at which the line's trailing whitespace starts. This is the line's length
+    /// for lines that continue onto the next row due to soft wrapping.
+    trailing_whitespace_start: usize,
 }
 
 impl LineWithInvisibles {
@@ -3538,7 +3748,7 @@ impl LineWithInvisibles {
         text_style: &TextStyle,
         max_line_len: usize,
         max_line_count: usize,
-        line_number_layouts: &[Option<ShapedLine>],
+        soft_wrapped_rows: &[bool],
         editor_mode: EditorMode,
         cx: &WindowContext,
     ) -> Vec<Self> {
@@ -3562,9 +3772,21 @@ impl LineWithInvisibles {
                         .text_system()
                         .shape_line(line.clone().into(), font_size, &styles)
                         .unwrap();
+                    let is_soft_wrapped =
+                        |row: usize| soft_wrapped_rows.get(row).copied().unwrap_or(false);
                     layouts.push(Self {
                         line: shaped_line,
                         invisibles: std::mem::take(&mut invisibles),
+                        leading_whitespace_end: if is_soft_wrapped(row) {
+                            0
+                        } else {
+                            line.len() - line.trim_start().len()
+                        },
+                        trailing_whitespace_start: if is_soft_wrapped(row + 1) {
+                            line.len()
+                        } else {
+                            line.trim_end().len()
+                        },
                     });
 
                     line.clear();
@@ -3605,10 +3827,8 @@ impl LineWithInvisibles {
                     if editor_mode == EditorMode::Full {
                         // Line wrap pads its contents with fake whitespaces,
                         // avoid printing them
-                        let inside_wrapped_string = line_number_layouts
-                            .get(row)
-                            .and_then(|layout| layout.as_ref())
-                            .is_none();
+                        let inside_wrapped_string =
+                            soft_wrapped_rows.get(row).copied().unwrap_or(false);
                         if highlighted_chunk.is_tab {
                             if non_whitespace_added || !inside_wrapped_string {
                                 invisibles.push(Invisible::Tab {
@@ -3618,16 +3838,20 @@ impl LineWithInvisibles {
                         } else {
                             invisibles.extend(
                                 line_chunk
-                                    .chars()
-                                    .enumerate()
+                                    .char_indices()
                                     .filter(|(_, line_char)| {
                                         let is_whitespace = line_char.is_whitespace();
                                         non_whitespace_added |= !is_whitespace;
                                         is_whitespace
                                             && (non_whitespace_added || !inside_wrapped_string)
                                     })
-                                    .map(|(whitespace_index, _)| Invisible::Whitespace {
-                                        line_offset: line.len() + whitespace_index,
+                                    .map(|(whitespace_index, whitespace_char)| {
+                                        let line_offset = line.len() + whitespace_index;
+                                        if is_non_breaking_space(whitespace_char) {
+                                            Invisible::NonBreakingSpace { line_offset }
+                                        } else {
+                                            Invisible::Whitespace { line_offset }
+                                        }
                                     }),
                             )
                         }
@@ -3685,14 +3909,22 @@ impl LineWithInvisibles {
         let allowed_invisibles_regions = match whitespace_setting {
             ShowWhitespaceSetting::None => return,
             ShowWhitespaceSetting::Selection => Some(selection_ranges),
-            ShowWhitespaceSetting::All => None,
+            ShowWhitespaceSetting::All
+            | ShowWhitespaceSetting::Boundary
+            | ShowWhitespaceSetting::Trailing => None,
         };
 
         for invisible in &self.invisibles {
             let (&token_offset, invisible_symbol) = match invisible {
                 Invisible::Tab { line_start_offset } => (line_start_offset, &layout.tab_invisible),
                 Invisible::Whitespace { line_offset } => (line_offset, &layout.space_invisible),
+                Invisible::NonBreakingSpace { line_offset } => {
+                    (line_offset, &layout.non_breaking_space_invisible)
+                }
             };
+            if !self.is_invisible_shown(token_offset, whitespace_setting) {
+                continue;
+            }
 
             let x_offset = self.line.x_for_index(token_offset);
             let invisible_offset =
@@ -3715,12 +3947,30 @@ impl LineWithInvisibles {
             invisible_symbol.paint(origin, line_height, cx).log_err();
         }
     }
+
+    /// Whether the whitespace at the given offset is shown by the given setting, regardless of
+    /// the selections.
+    fn is_invisible_shown(&self, offset: usize, whitespace_setting: ShowWhitespaceSetting) -> bool {
+        match whitespace_setting {
+            ShowWhitespaceSetting::None => false,
+            ShowWhitespaceSetting::Selection | ShowWhitespaceSetting::All => true,
+            ShowWhitespaceSetting::Boundary => {
+                offset < self.leading_whitespace_end || offset >= self.trailing_whitespace_start
+            }
+            ShowWhitespaceSetting::Trailing => offset >= self.trailing_whitespace_start,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Invisible {
     Tab { line_start_offset: usize },
     Whitespace { line_offset: usize },
+    NonBreakingSpace { line_offset: usize },
+}
+
+fn is_non_breaking_space(c: char) -> bool {
+    matches!(c, '\u{a0}' | '\u{2007}' | '\u{202f}')
 }
 
 impl EditorElement {
@@ -3971,11 +4221,22 @@ impl Element for EditorElement {
                     let highlighted_rows = self
                         .editor
                         .update(cx, |editor, cx| editor.highlighted_display_rows(cx));
-                    let highlighted_ranges = self.editor.read(cx).background_highlights_in_range(
+                    // Decorations are painted below the editor's own highlights.
+                    let mut highlighted_ranges =
+                        self.editor.read(cx).decoration_backgrounds_in_range(
+                            start_anchor..end_anchor,
+                            &snapshot.display_snapshot,
+                        );
+                    highlighted_ranges.extend(self.editor.read(cx).background_highlights_in_range(
                         start_anchor..end_anchor,
                         &snapshot.display_snapshot,
                         cx.theme().colors(),
-                    );
+                    ));
+                    let decoration_underlines =
+                        self.editor.read(cx).decoration_underlines_in_range(
+                            start_anchor..end_anchor,
+                            &snapshot.display_snapshot,
+                        );
 
                     let redacted_ranges = self.editor.read(cx).redacted_ranges(
                         start_anchor..end_anchor,
@@ -4010,8 +4271,7 @@ impl Element for EditorElement {
                     );
 
                     let mut max_visible_line_width = Pixels::ZERO;
-                    let line_layouts =
-                        self.layout_lines(start_row..end_row, &line_numbers, &snapshot, cx);
+                    let line_layouts = self.layout_lines(start_row..end_row, &snapshot, cx);
                     for line_with_invisibles in &line_layouts {
                         if line_with_invisibles.line.width > max_visible_line_width {
                             max_visible_line_width = line_with_invisibles.line.width;
@@ -4025,7 +4285,7 @@ impl Element for EditorElement {
                     let mut scroll_width =
                         longest_line_width.max(max_visible_line_width) + overscroll.width;
 
-                    let mut blocks = cx.with_element_namespace("blocks", |cx| {
+                    let (mut blocks, resized_blocks) = cx.with_element_namespace("blocks", |cx| {
                         self.build_blocks(
                             start_row..end_row,
                             &snapshot,
@@ -4040,6 +4300,22 @@ impl Element for EditorElement {
                             cx,
                         )
                     });
+                    if !resized_blocks.is_empty() {
+                        // View blocks have been measured at a different height than the
+                        // display map has for them, so lay out the editor again. This happens
+                        // at most once per frame, and blocks that are resized again are laid
+                        // out at their new heights in the next frame.
+                        self.editor.update(cx, |editor, cx| {
+                            editor.resize_blocks(resized_blocks, None, cx);
+                            if self.relaid_out_for_resized_blocks {
+                                cx.notify();
+                            }
+                        });
+                        if !self.relaid_out_for_resized_blocks {
+                            self.relaid_out_for_resized_blocks = true;
+                            return self.prepaint(None, bounds, &mut (), cx);
+                        }
+                    }
 
                     let scroll_pixel_position = point(
                         scroll_position.x * em_width,
@@ -4064,6 +4340,17 @@ impl Element for EditorElement {
                         }
                     }
 
+                    let gutter_columns = self.layout_gutter_columns(
+                        &buffer_rows,
+                        em_width,
+                        scroll_position,
+                        line_height,
+                        &gutter_dimensions,
+                        &gutter_hitbox,
+                        &snapshot,
+                        cx,
+                    );
+
                     let blamed_display_rows = self.layout_blame_entries(
                         buffer_rows.into_iter(),
                         em_width,
@@ -4273,6 +4560,23 @@ impl Element for EditorElement {
                             }],
                         )
                         .unwrap();
+                    // Non-breaking spaces look like regular spaces but behave differently,
+                    // so they stand out.
+                    let non_breaking_space_invisible = cx
+                        .text_system()
+                        .shape_line(
+                            "°".into(),
+                            invisible_symbol_font_size,
+                            &[TextRun {
+                                len: "°".len(),
+                                font: self.style.text.font(),
+                                color: cx.theme().status().warning,
+                                background_color: None,
+                                underline: None,
+                                strikethrough: None,
+                            }],
+                        )
+                        .unwrap();
 
                     EditorLayout {
                         mode: snapshot.mode,
@@ -4297,10 +4601,12 @@ impl Element for EditorElement {
                         active_rows,
                         highlighted_rows,
                         highlighted_ranges,
+                        decoration_underlines,
                         redacted_ranges,
                         line_numbers,
                         display_hunks,
                         blamed_display_rows,
+                        gutter_columns,
                         inline_blame,
                         folds,
                         blocks,
@@ -4313,6 +4619,7 @@ impl Element for EditorElement {
                         fold_indicators,
                         tab_invisible,
                         space_invisible,
+                        non_breaking_space_invisible,
                     }
                 })
             })
@@ -4424,16 +4731,19 @@ pub struct EditorLayout {
     folds: Vec<FoldLayout>,
     blocks: Vec<BlockLayout>,
     highlighted_ranges: Vec<(Range<DisplayPoint>, Hsla)>,
+    decoration_underlines: Vec<(Range<DisplayPoint>, UnderlineStyle)>,
     redacted_ranges: Vec<Range<DisplayPoint>>,
     cursors: Vec<(DisplayPoint, Hsla)>,
     visible_cursors: Vec<CursorLayout>,
     selections: Vec<(PlayerColor, Vec<SelectionLayout>)>,
     code_actions_indicator: Option<AnyElement>,
     test_indicators: Vec<AnyElement>,
+    gutter_columns: Vec<AnyElement>,
     fold_indicators: Vec<Option<AnyElement>>,
     mouse_context_menu: Option<AnyElement>,
     tab_invisible: ShapedLine,
     space_invisible: ShapedLine,
+    non_breaking_space_invisible: ShapedLine,
 }
 
 impl EditorLayout {
@@ -5272,6 +5582,97 @@ mod tests {
         assert_eq!(expected_invisibles, actual_invisibles);
     }
 
+    #[gpui::test]
+    fn test_invisibles_by_whitespace_setting(cx: &mut TestAppContext) {
+        init_test(cx, |s| {
+            s.defaults.show_whitespaces = Some(ShowWhitespaceSetting::All);
+            s.defaults.tab_size = NonZeroU32::new(4);
+        });
+
+        let window = cx.add_window(|cx| {
+            let buffer = MultiBuffer::build_simple("  a b\u{a0}c  \n\tb ", cx);
+            Editor::new(EditorMode::Full, buffer, None, cx)
+        });
+        let cx = &mut VisualTestContext::from_window(*window, cx);
+        let editor = window.root(cx).unwrap();
+        let style = cx.update(|cx| editor.read(cx).style().unwrap().clone());
+        let (_, state) = cx.draw(point(px(500.), px(500.)), size(px(500.), px(500.)), |_| {
+            EditorElement::new(&editor, style)
+        });
+
+        let shown_invisibles = |whitespace_setting| {
+            state
+                .position_map
+                .line_layouts
+                .iter()
+                .map(|line| {
+                    line.invisibles
+                        .iter()
+                        .filter(|invisible| {
+                            let offset = match invisible {
+                                Invisible::Tab { line_start_offset } => *line_start_offset,
+                                Invisible::Whitespace { line_offset }
+                                | Invisible::NonBreakingSpace { line_offset } => *line_offset,
+                            };
+                            line.is_invisible_shown(offset, whitespace_setting)
+                        })
+                        .copied()
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            shown_invisibles(ShowWhitespaceSetting::All),
+            [
+                vec![
+                    Invisible::Whitespace { line_offset: 0 },
+                    Invisible::Whitespace { line_offset: 1 },
+                    Invisible::Whitespace { line_offset: 3 },
+                    Invisible::NonBreakingSpace { line_offset: 5 },
+                    Invisible::Whitespace { line_offset: 8 },
+                    Invisible::Whitespace { line_offset: 9 },
+                ],
+                vec![
+                    Invisible::Tab {
+                        line_start_offset: 0
+                    },
+                    Invisible::Whitespace { line_offset: 5 },
+                ],
+            ]
+        );
+        assert_eq!(
+            shown_invisibles(ShowWhitespaceSetting::Boundary),
+            [
+                vec![
+                    Invisible::Whitespace { line_offset: 0 },
+                    Invisible::Whitespace { line_offset: 1 },
+                    Invisible::Whitespace { line_offset: 8 },
+                    Invisible::Whitespace { line_offset: 9 },
+                ],
+                vec![
+                    Invisible::Tab {
+                        line_start_offset: 0
+                    },
+                    Invisible::Whitespace { line_offset: 5 },
+                ],
+            ]
+        );
+        assert_eq!(
+            shown_invisibles(ShowWhitespaceSetting::Trailing),
+            [
+                vec![
+                    Invisible::Whitespace { line_offset: 8 },
+                    Invisible::Whitespace { line_offset: 9 },
+                ],
+                vec![Invisible::Whitespace { line_offset: 5 }],
+            ]
+        );
+        assert!(shown_invisibles(ShowWhitespaceSetting::None)
+            .iter()
+            .all(Vec::is_empty));
+    }
+
     #[gpui::test]
     fn test_invisibles_dont_appear_in_certain_editors(cx: &mut TestAppContext) {
         init_test(cx, |s| {
@@ -5356,7 +5757,11 @@ mod tests {
                 match expected_invisibles.get(i) {
                     Some(expected_invisible) => match (expected_invisible, actual_invisible) {
                         (Invisible::Whitespace { .. }, Invisible::Whitespace { .. })
-                        | (Invisible::Tab { .. }, Invisible::Tab { .. }) => {}
+                        | (Invisible::Tab { .. }, Invisible::Tab { .. })
+                        | (
+                            Invisible::NonBreakingSpace { .. },
+                            Invisible::NonBreakingSpace { .. },
+                        ) => {}
                         _ => {
                             panic!("At index {i}, expected invisible {expected_invisible:?} does not match actual {actual_invisible:?} by kind. Actual invisibles: {actual_invisibles:?}")
                         }
@@ -5420,6 +5825,7 @@ pub fn register_action<T: Action>(
         let action = action.downcast_ref().unwrap();
         if phase == DispatchPhase::Bubble {
             view.update(cx, |editor, cx| {
+                editor.stream_action_dispatched(action.name(), cx);
                 listener(editor, action, cx);
             })
         }
@@ -0,0 +1,223 @@
+use crate::{Editor, EditorMode, EditorSettings};
+use collections::hash_map;
+use gpui::{
+    AppContext, Context, EntityId, EventEmitter, Global, Model, ModelContext, SharedString, Task,
+    ViewContext,
+};
+use language::Point;
+use settings::Settings;
+use std::{mem, ops::Range, path::Path, sync::Arc, time::Duration};
+use text::BufferId;
+
+/// Events beyond this many are dropped until the pending batch is reported.
+const MAX_PENDING_EVENTS: usize = 1024;
+
+/// Structured events about what happens in editors, for extensions and scripts that automate or
+/// analyze editing. Subscribe to [`EditorEventStream::global`] with `cx.subscribe`.
+///
+/// Nothing is reported unless the `event_stream.enabled` setting is on. Events are reported in
+/// batches, at most once per `event_stream.interval_ms`, and leave out buffer paths and edited
+/// text unless `event_stream.include_paths` and `event_stream.include_text` are on.
+pub struct EditorEventStream {
+    pending: Vec<EditorStreamEvent>,
+    dropped: usize,
+    flush_task: Option<Task<()>>,
+}
+
+struct GlobalEditorEventStream(Model<EditorEventStream>);
+
+impl Global for GlobalEditorEventStream {}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum EditorStreamEvent {
+    /// A buffer was first shown in an editor.
+    BufferOpened {
+        editor_id: EntityId,
+        buffer: StreamBuffer,
+    },
+    /// A buffer shown in an editor was edited, by the editor or elsewhere.
+    Edited {
+        editor_id: EntityId,
+        buffer: StreamBuffer,
+        edits: Vec<StreamEdit>,
+    },
+    /// An editor's selections changed. Only the latest selections of each editor in a batch
+    /// are reported.
+    SelectionsChanged {
+        editor_id: EntityId,
+        /// The selections, in the coordinates of the editor's multibuffer.
+        selections: Vec<Range<Point>>,
+    },
+    /// An action was dispatched to an editor.
+    ActionDispatched {
+        editor_id: EntityId,
+        action: SharedString,
+    },
+    /// This many events were dropped because too many were reported at once.
+    Dropped { count: usize },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct StreamBuffer {
+    pub id: BufferId,
+    /// Only set when the `event_stream.include_paths` setting is on.
+    pub path: Option<Arc<Path>>,
+    pub language: Option<Arc<str>>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct StreamEdit {
+    /// The range of the buffer containing the edit's new text, as of when it was reported.
+    pub range: Range<Point>,
+    /// Only set when the `event_stream.include_text` setting is on.
+    pub new_text: Option<String>,
+}
+
+impl EventEmitter<EditorStreamEvent> for EditorEventStream {}
+
+impl EditorEventStream {
+    pub(crate) fn init(cx: &mut AppContext) {
+        let stream = cx.new_model(|_| Self {
+            pending: Vec::new(),
+            dropped: 0,
+            flush_task: None,
+        });
+        cx.set_global(GlobalEditorEventStream(stream));
+    }
+
+    pub fn global(cx: &AppContext) -> Option<Model<Self>> {
+        cx.try_global::<GlobalEditorEventStream>()
+            .map(|stream| stream.0.clone())
+    }
+
+    fn push(&mut self, event: EditorStreamEvent, cx: &mut ModelContext<Self>) {
+        if let EditorStreamEvent::SelectionsChanged { editor_id, .. } = &event {
+            self.pending.retain(|pending| {
+                !matches!(
+                    pending,
+                    EditorStreamEvent::SelectionsChanged { editor_id: pending_id, .. }
+                        if pending_id == editor_id
+                )
+            });
+        }
+        if self.pending.len() < MAX_PENDING_EVENTS {
+            self.pending.push(event);
+        } else {
+            self.dropped += 1;
+        }
+
+        if self.flush_task.is_none() {
+            let interval =
+                Duration::from_millis(EditorSettings::get_global(cx).event_stream.interval_ms);
+            self.flush_task = Some(cx.spawn(|this, mut cx| async move {
+                cx.background_executor().timer(interval).await;
+                this.update(&mut cx, |this, cx| this.flush(cx)).ok();
+            }));
+        }
+    }
+
+    fn flush(&mut self, cx: &mut ModelContext<Self>) {
+        self.flush_task = None;
+        for event in mem::take(&mut self.pending) {
+            cx.emit(event);
+        }
+        let dropped = mem::take(&mut self.dropped);
+        if dropped > 0 {
+            cx.emit(EditorStreamEvent::Dropped { count: dropped });
+        }
+    }
+}
+
+impl Editor {
+    /// The stream to report this editor's events to, if they're reported at all.
+    fn event_stream(&self, cx: &AppContext) -> Option<Model<EditorEventStream>> {
+        if self.mode != EditorMode::Full || !EditorSettings::get_global(cx).event_stream.enabled {
+            return None;
+        }
+        EditorEventStream::global(cx)
+    }
+
+    /// Reports the buffers that were opened in this editor and the edits made to its buffers
+    /// since they were last reported.
+    pub(crate) fn stream_buffer_events(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(stream) = self.event_stream(cx) else {
+            return;
+        };
+        let settings = EditorSettings::get_global(cx).event_stream;
+        let editor_id = cx.entity_id();
+
+        let mut events = Vec::new();
+        for buffer in self.buffer.read(cx).all_buffers() {
+            let buffer = buffer.read(cx);
+            let stream_buffer = StreamBuffer {
+                id: buffer.remote_id(),
+                path: settings
+                    .include_paths
+                    .then(|| buffer.file().map(|file| file.path().clone()))
+                    .flatten(),
+                language: buffer.language().map(|language| language.name()),
+            };
+            match self.streamed_buffer_versions.entry(buffer.remote_id()) {
+                hash_map::Entry::Occupied(mut entry) => {
+                    let edits = buffer
+                        .edits_since::<Point>(entry.get())
+                        .map(|edit| StreamEdit {
+                            new_text: settings
+                                .include_text
+                                .then(|| buffer.text_for_range(edit.new.clone()).collect()),
+                            range: edit.new,
+                        })
+                        .collect::<Vec<_>>();
+                    entry.insert(buffer.version());
+                    if !edits.is_empty() {
+                        events.push(EditorStreamEvent::Edited {
+                            editor_id,
+                            buffer: stream_buffer,
+                            edits,
+                        });
+                    }
+                }
+                hash_map::Entry::Vacant(entry) => {
+                    entry.insert(buffer.version());
+                    events.push(EditorStreamEvent::BufferOpened {
+                        editor_id,
+                        buffer: stream_buffer,
+                    });
+                }
+            }
+        }
+
+        stream.update(cx, |stream, cx| {
+            for event in events {
+                stream.push(event, cx);
+            }
+        });
+    }
+
+    pub(crate) fn stream_selections_changed(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(stream) = self.event_stream(cx) else {
+            return;
+        };
+        let event = EditorStreamEvent::SelectionsChanged {
+            editor_id: cx.entity_id(),
+            selections: self
+                .selections
+                .all::<Point>(cx)
+                .into_iter()
+                .map(|selection| selection.range())
+                .collect(),
+        };
+        stream.update(cx, |stream, cx| stream.push(event, cx));
+    }
+
+    pub(crate) fn stream_action_dispatched(&self, action: &str, cx: &mut ViewContext<Self>) {
+        let Some(stream) = self.event_stream(cx) else {
+            return;
+        };
+        let event = EditorStreamEvent::ActionDispatched {
+            editor_id: cx.entity_id(),
+            action: SharedString::from(action.to_string()),
+        };
+        stream.update(cx, |stream, cx| stream.push(event, cx));
+    }
+}
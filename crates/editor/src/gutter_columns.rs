@@ -0,0 +1,68 @@
+use crate::{Editor, EditorSettings, EditorSnapshot};
+use gpui::{AnyElement, AppContext, ViewContext, WindowContext};
+use multi_buffer::MultiBufferRow;
+use settings::Settings;
+use std::sync::Arc;
+
+/// A column of the gutter supplied by a feature, such as to show bookmarks or test coverage.
+/// Columns are registered with [`Editor::register_gutter_column`], and can be hidden by name
+/// with the `gutter.columns` setting.
+pub trait GutterColumn: 'static {
+    /// The column's key in the `gutter.columns` setting. Registering a column replaces any
+    /// column with the same name.
+    fn name(&self) -> &'static str;
+
+    /// The column's width, in multiples of the editor's em width.
+    fn width(&self) -> f32;
+
+    /// Columns are laid out left to right in increasing order, after the git blame column
+    /// and before the code actions and line numbers.
+    fn order(&self) -> i32 {
+        0
+    }
+
+    /// Renders the contents of the column for a buffer row, which are centered in the row.
+    fn render_row(
+        &self,
+        row: MultiBufferRow,
+        snapshot: &EditorSnapshot,
+        cx: &mut WindowContext,
+    ) -> Option<AnyElement>;
+
+    /// Handles a click on a row for which the column rendered contents.
+    fn on_click(&self, _row: MultiBufferRow, _editor: &mut Editor, _cx: &mut ViewContext<Editor>) {}
+}
+
+impl Editor {
+    pub fn register_gutter_column(
+        &mut self,
+        column: impl GutterColumn,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.gutter_columns
+            .retain(|existing| existing.name() != column.name());
+        let ix = self
+            .gutter_columns
+            .partition_point(|existing| existing.order() <= column.order());
+        self.gutter_columns.insert(ix, Arc::new(column));
+        cx.notify();
+    }
+
+    pub fn unregister_gutter_column(&mut self, name: &str, cx: &mut ViewContext<Self>) {
+        let column_count = self.gutter_columns.len();
+        self.gutter_columns.retain(|column| column.name() != name);
+        if self.gutter_columns.len() != column_count {
+            cx.notify();
+        }
+    }
+
+    /// The registered gutter columns that aren't hidden by the settings, in display order.
+    pub(crate) fn visible_gutter_columns(&self, cx: &AppContext) -> Vec<Arc<dyn GutterColumn>> {
+        let settings = &EditorSettings::get_global(cx).gutter;
+        self.gutter_columns
+            .iter()
+            .filter(|column| settings.columns.get(column.name()).copied() != Some(false))
+            .cloned()
+            .collect()
+    }
+}
@@ -1,4 +1,6 @@
 use crate::{
+    display_map::{Inlay, InlayOffset},
+    document_links,
     hover_popover::{self, InlayHover},
     Anchor, Editor, EditorSnapshot, FindAllReferences, GoToDefinition, GoToTypeDefinition, InlayId,
     PointForPosition, SelectPhase,
@@ -144,6 +146,39 @@ impl Editor {
         self.clear_highlights::<HoveredLinkState>(cx);
     }
 
+    /// Inserts the text of the inlay hint at the given position into the buffer, including
+    /// the padding the language server asked for. Returns false if there is no hint there.
+    pub(crate) fn insert_hint_at(
+        &mut self,
+        point_for_position: &PointForPosition,
+        snapshot: &EditorSnapshot,
+        cx: &mut ViewContext<Self>,
+    ) -> bool {
+        let Some((hint, _, previous_valid_anchor)) =
+            hovered_inlay_hint(self, snapshot, point_for_position, cx)
+        else {
+            return false;
+        };
+        let Some(cached_hint) = self
+            .inlay_hint_cache()
+            .hint_by_id(previous_valid_anchor.excerpt_id, hint.id)
+        else {
+            return false;
+        };
+
+        let mut text = cached_hint.text();
+        if cached_hint.padding_right && !text.ends_with(' ') {
+            text.push(' ');
+        }
+        if cached_hint.padding_left && !text.starts_with(' ') {
+            text.insert(0, ' ');
+        }
+        self.transact(cx, |editor, cx| {
+            editor.edit([(hint.position..hint.position, text)], cx);
+        });
+        true
+    }
+
     pub(crate) fn handle_click_hovered_link(
         &mut self,
         point: PointForPosition,
@@ -240,163 +275,152 @@ pub fn update_inlay_link_and_hover_points(
     shift_held: bool,
     cx: &mut ViewContext<'_, Editor>,
 ) {
-    let hovered_offset = if point_for_position.column_overshoot_after_line_end == 0 {
-        Some(snapshot.display_point_to_inlay_offset(point_for_position.exact_unclipped, Bias::Left))
-    } else {
-        None
-    };
     let mut go_to_definition_updated = false;
     let mut hover_updated = false;
-    if let Some(hovered_offset) = hovered_offset {
-        let buffer_snapshot = editor.buffer().read(cx).snapshot(cx);
-        let previous_valid_anchor = buffer_snapshot.anchor_at(
-            point_for_position.previous_valid.to_point(snapshot),
-            Bias::Left,
-        );
-        let next_valid_anchor = buffer_snapshot.anchor_at(
-            point_for_position.next_valid.to_point(snapshot),
-            Bias::Right,
-        );
-        if let Some(hovered_hint) = editor
-            .visible_inlay_hints(cx)
-            .into_iter()
-            .skip_while(|hint| {
-                hint.position
-                    .cmp(&previous_valid_anchor, &buffer_snapshot)
-                    .is_lt()
-            })
-            .take_while(|hint| {
-                hint.position
-                    .cmp(&next_valid_anchor, &buffer_snapshot)
-                    .is_le()
-            })
-            .max_by_key(|hint| hint.id)
-        {
-            let inlay_hint_cache = editor.inlay_hint_cache();
-            let excerpt_id = previous_valid_anchor.excerpt_id;
-            if let Some(cached_hint) = inlay_hint_cache.hint_by_id(excerpt_id, hovered_hint.id) {
-                match cached_hint.resolve_state {
-                    ResolveState::CanResolve(_, _) => {
-                        if let Some(buffer_id) = previous_valid_anchor.buffer_id {
-                            inlay_hint_cache.spawn_hint_resolve(
-                                buffer_id,
-                                excerpt_id,
-                                hovered_hint.id,
-                                cx,
-                            );
-                        }
+    if let Some((hovered_hint, hovered_offset, previous_valid_anchor)) =
+        hovered_inlay_hint(editor, snapshot, &point_for_position, cx)
+    {
+        let inlay_hint_cache = editor.inlay_hint_cache();
+        let excerpt_id = previous_valid_anchor.excerpt_id;
+        if let Some(cached_hint) = inlay_hint_cache.hint_by_id(excerpt_id, hovered_hint.id) {
+            match cached_hint.resolve_state {
+                ResolveState::CanResolve(_, _) => {
+                    if let Some(buffer_id) = previous_valid_anchor.buffer_id {
+                        inlay_hint_cache.spawn_hint_resolve(
+                            buffer_id,
+                            excerpt_id,
+                            hovered_hint.id,
+                            cx,
+                        );
                     }
-                    ResolveState::Resolved => {
-                        let mut extra_shift_left = 0;
-                        let mut extra_shift_right = 0;
-                        if cached_hint.padding_left {
-                            extra_shift_left += 1;
-                            extra_shift_right += 1;
-                        }
-                        if cached_hint.padding_right {
-                            extra_shift_right += 1;
+                }
+                ResolveState::Resolved => {
+                    let display_options = inlay_hint_cache.display_options;
+                    let mut extra_shift_left = 0;
+                    let mut extra_shift_right = 0;
+                    if cached_hint.padding_left && display_options.show_padding {
+                        extra_shift_left += 1;
+                        extra_shift_right += 1;
+                    }
+                    if cached_hint.padding_right && display_options.show_padding {
+                        extra_shift_right += 1;
+                    }
+                    // Truncated hints are shown in full when hovered, unless the server
+                    // provided a tooltip for them.
+                    let full_text = display_options.truncates(&cached_hint).then(|| HoverBlock {
+                        text: cached_hint.text(),
+                        kind: HoverBlockKind::PlainText,
+                    });
+                    match cached_hint.label {
+                        project::InlayHintLabel::String(_) => {
+                            let tooltip = match cached_hint.tooltip {
+                                Some(InlayHintTooltip::String(text)) => Some(HoverBlock {
+                                    text,
+                                    kind: HoverBlockKind::PlainText,
+                                }),
+                                Some(InlayHintTooltip::MarkupContent(content)) => {
+                                    Some(HoverBlock {
+                                        text: content.value,
+                                        kind: content.kind,
+                                    })
+                                }
+                                None => full_text,
+                            };
+                            if let Some(tooltip) = tooltip {
+                                hover_popover::hover_at_inlay(
+                                    editor,
+                                    InlayHover {
+                                        excerpt: excerpt_id,
+                                        tooltip,
+                                        range: InlayHighlight {
+                                            inlay: hovered_hint.id,
+                                            inlay_position: hovered_hint.position,
+                                            range: extra_shift_left
+                                                ..hovered_hint.text.len() + extra_shift_right,
+                                        },
+                                    },
+                                    cx,
+                                );
+                                hover_updated = true;
+                            }
                         }
-                        match cached_hint.label {
-                            project::InlayHintLabel::String(_) => {
-                                if let Some(tooltip) = cached_hint.tooltip {
+                        project::InlayHintLabel::LabelParts(label_parts) => {
+                            let hint_start = snapshot.anchor_to_inlay_offset(hovered_hint.position);
+                            if let Some((hovered_hint_part, part_range)) =
+                                hover_popover::find_hovered_hint_part(
+                                    label_parts,
+                                    hint_start,
+                                    hovered_offset,
+                                )
+                            {
+                                let highlight_start =
+                                    (part_range.start - hint_start).0 + extra_shift_left;
+                                let highlight_end =
+                                    (part_range.end - hint_start).0 + extra_shift_right;
+                                let highlight = InlayHighlight {
+                                    inlay: hovered_hint.id,
+                                    inlay_position: hovered_hint.position,
+                                    range: highlight_start..highlight_end,
+                                };
+                                if let Some(tooltip) = hovered_hint_part.tooltip {
                                     hover_popover::hover_at_inlay(
                                         editor,
                                         InlayHover {
                                             excerpt: excerpt_id,
                                             tooltip: match tooltip {
-                                                InlayHintTooltip::String(text) => HoverBlock {
-                                                    text,
-                                                    kind: HoverBlockKind::PlainText,
-                                                },
-                                                InlayHintTooltip::MarkupContent(content) => {
+                                                InlayHintLabelPartTooltip::String(text) => {
                                                     HoverBlock {
-                                                        text: content.value,
-                                                        kind: content.kind,
+                                                        text,
+                                                        kind: HoverBlockKind::PlainText,
                                                     }
                                                 }
+                                                InlayHintLabelPartTooltip::MarkupContent(
+                                                    content,
+                                                ) => HoverBlock {
+                                                    text: content.value,
+                                                    kind: content.kind,
+                                                },
                                             },
-                                            range: InlayHighlight {
-                                                inlay: hovered_hint.id,
-                                                inlay_position: hovered_hint.position,
-                                                range: extra_shift_left
-                                                    ..hovered_hint.text.len() + extra_shift_right,
-                                            },
+                                            range: highlight.clone(),
+                                        },
+                                        cx,
+                                    );
+                                    hover_updated = true;
+                                } else if let Some(full_text) = full_text {
+                                    hover_popover::hover_at_inlay(
+                                        editor,
+                                        InlayHover {
+                                            excerpt: excerpt_id,
+                                            tooltip: full_text,
+                                            range: highlight.clone(),
                                         },
                                         cx,
                                     );
                                     hover_updated = true;
                                 }
-                            }
-                            project::InlayHintLabel::LabelParts(label_parts) => {
-                                let hint_start =
-                                    snapshot.anchor_to_inlay_offset(hovered_hint.position);
-                                if let Some((hovered_hint_part, part_range)) =
-                                    hover_popover::find_hovered_hint_part(
-                                        label_parts,
-                                        hint_start,
-                                        hovered_offset,
-                                    )
+                                if let Some((language_server_id, location)) =
+                                    hovered_hint_part.location
                                 {
-                                    let highlight_start =
-                                        (part_range.start - hint_start).0 + extra_shift_left;
-                                    let highlight_end =
-                                        (part_range.end - hint_start).0 + extra_shift_right;
-                                    let highlight = InlayHighlight {
-                                        inlay: hovered_hint.id,
-                                        inlay_position: hovered_hint.position,
-                                        range: highlight_start..highlight_end,
-                                    };
-                                    if let Some(tooltip) = hovered_hint_part.tooltip {
-                                        hover_popover::hover_at_inlay(
+                                    if secondary_held && !editor.has_pending_nonempty_selection() {
+                                        go_to_definition_updated = true;
+                                        show_link_definition(
+                                            shift_held,
                                             editor,
-                                            InlayHover {
-                                                excerpt: excerpt_id,
-                                                tooltip: match tooltip {
-                                                    InlayHintLabelPartTooltip::String(text) => {
-                                                        HoverBlock {
-                                                            text,
-                                                            kind: HoverBlockKind::PlainText,
-                                                        }
-                                                    }
-                                                    InlayHintLabelPartTooltip::MarkupContent(
-                                                        content,
-                                                    ) => HoverBlock {
-                                                        text: content.value,
-                                                        kind: content.kind,
-                                                    },
-                                                },
-                                                range: highlight.clone(),
-                                            },
+                                            TriggerPoint::InlayHint(
+                                                highlight,
+                                                location,
+                                                language_server_id,
+                                            ),
+                                            snapshot,
                                             cx,
                                         );
-                                        hover_updated = true;
-                                    }
-                                    if let Some((language_server_id, location)) =
-                                        hovered_hint_part.location
-                                    {
-                                        if secondary_held
-                                            && !editor.has_pending_nonempty_selection()
-                                        {
-                                            go_to_definition_updated = true;
-                                            show_link_definition(
-                                                shift_held,
-                                                editor,
-                                                TriggerPoint::InlayHint(
-                                                    highlight,
-                                                    location,
-                                                    language_server_id,
-                                                ),
-                                                snapshot,
-                                                cx,
-                                            );
-                                        }
                                     }
                                 }
                             }
-                        };
-                    }
-                    ResolveState::Resolving => {}
+                        }
+                    };
                 }
+                ResolveState::Resolving => {}
             }
         }
     }
@@ -409,6 +433,46 @@ pub fn update_inlay_link_and_hover_points(
     }
 }
 
+/// Finds the inlay hint displayed at the given position, along with the offset of the position
+/// within the inlays and the anchor of the closest valid position before it.
+fn hovered_inlay_hint(
+    editor: &Editor,
+    snapshot: &EditorSnapshot,
+    point_for_position: &PointForPosition,
+    cx: &ViewContext<'_, Editor>,
+) -> Option<(Inlay, InlayOffset, Anchor)> {
+    if point_for_position.column_overshoot_after_line_end != 0 {
+        return None;
+    }
+    let hovered_offset =
+        snapshot.display_point_to_inlay_offset(point_for_position.exact_unclipped, Bias::Left);
+
+    let buffer_snapshot = editor.buffer().read(cx).snapshot(cx);
+    let previous_valid_anchor = buffer_snapshot.anchor_at(
+        point_for_position.previous_valid.to_point(snapshot),
+        Bias::Left,
+    );
+    let next_valid_anchor = buffer_snapshot.anchor_at(
+        point_for_position.next_valid.to_point(snapshot),
+        Bias::Right,
+    );
+    let hovered_hint = editor
+        .visible_inlay_hints(cx)
+        .into_iter()
+        .skip_while(|hint| {
+            hint.position
+                .cmp(&previous_valid_anchor, &buffer_snapshot)
+                .is_lt()
+        })
+        .take_while(|hint| {
+            hint.position
+                .cmp(&next_valid_anchor, &buffer_snapshot)
+                .is_le()
+        })
+        .max_by_key(|hint| hint.id)?;
+    Some((hovered_hint, hovered_offset, previous_valid_anchor))
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum LinkDefinitionKind {
     Symbol,
@@ -503,6 +567,24 @@ pub fn show_link_definition(
                             (range, vec![HoverLink::Url(url)])
                         })
                         .ok()
+                    } else if let Some(link_definition) = project.as_ref().and_then(|project| {
+                        cx.update(|cx| {
+                            document_links::link_definition(project, &buffer, buffer_position, cx)
+                        })
+                        .ok()
+                        .flatten()
+                    }) {
+                        // Links between documents are followed without a language server.
+                        link_definition.await.log_err().map(|link| {
+                            let range = link.origin.as_ref().and_then(|origin| {
+                                let start =
+                                    snapshot.anchor_in_excerpt(excerpt_id, origin.range.start)?;
+                                let end =
+                                    snapshot.anchor_in_excerpt(excerpt_id, origin.range.end)?;
+                                Some(RangeInEditor::Text(start..end))
+                            });
+                            (range, vec![HoverLink::Text(link)])
+                        })
                     } else if let Some(project) = project {
                         // query the LSP for definition info
                         project
@@ -1029,6 +1111,8 @@ mod tests {
                 show_type_hints: true,
                 show_parameter_hints: true,
                 show_other_hints: true,
+                show_padding: true,
+                max_length: None,
             })
         });
 
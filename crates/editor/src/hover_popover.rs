@@ -1120,6 +1120,8 @@ mod tests {
                 show_type_hints: true,
                 show_parameter_hints: true,
                 show_other_hints: true,
+                show_padding: true,
+                max_length: None,
             })
         });
 
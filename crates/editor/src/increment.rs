@@ -0,0 +1,184 @@
+//! Incrementing and decrementing the numbers in a buffer, like vim's `ctrl-a` and `ctrl-x`.
+
+use std::ops::Range;
+
+use gpui::ViewContext;
+use multi_buffer::{MultiBufferSnapshot, ToOffset, ToPoint};
+use text::Point;
+
+use crate::{
+    actions::{DecrementNumber, IncrementNumber},
+    scroll::Autoscroll,
+    Editor,
+};
+
+impl Editor {
+    pub fn increment_number(&mut self, _: &IncrementNumber, cx: &mut ViewContext<Self>) {
+        self.add_to_numbers(1, cx)
+    }
+
+    pub fn decrement_number(&mut self, _: &DecrementNumber, cx: &mut ViewContext<Self>) {
+        self.add_to_numbers(-1, cx)
+    }
+
+    /// Adds `delta` to the first number at or after each cursor on its line, and to every number
+    /// in each non-empty selection.
+    fn add_to_numbers(&mut self, delta: i64, cx: &mut ViewContext<Self>) {
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let mut edits = Vec::new();
+        let mut new_selections = Vec::new();
+
+        for selection in self.selections.all::<Point>(cx) {
+            if selection.is_empty() {
+                // The cursor moves to the start of the number it changed.
+                let mut cursor = selection.start;
+                if let Some((range, num, radix)) = find_number(&snapshot, selection.start, true) {
+                    if let Some(text) = add_to_number(&num, radix, delta) {
+                        cursor = range.start;
+                        edits.push((range, text));
+                    }
+                }
+                let cursor = snapshot.anchor_before(cursor);
+                new_selections.push(cursor..cursor);
+                continue;
+            }
+
+            new_selections.push(
+                snapshot.anchor_before(selection.start)..snapshot.anchor_after(selection.end),
+            );
+            for row in selection.start.row..=selection.end.row {
+                let mut start = if row == selection.start.row {
+                    selection.start
+                } else {
+                    Point::new(row, 0)
+                };
+                // Only the first number can start before `start`; later ones are searched for
+                // from the end of the previous one, which may be followed by `-`, `b`, `o` or `x`.
+                let mut backtrack = true;
+                while let Some((range, num, radix)) = find_number(&snapshot, start, backtrack) {
+                    if range.start >= selection.end {
+                        break;
+                    }
+                    start = range.end;
+                    backtrack = false;
+                    if let Some(text) = add_to_number(&num, radix, delta) {
+                        edits.push((range, text));
+                    }
+                }
+            }
+        }
+
+        if edits.is_empty() {
+            return;
+        }
+        self.transact(cx, |this, cx| {
+            this.buffer
+                .update(cx, |buffer, cx| buffer.edit(edits, None, cx));
+            this.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                s.select_anchor_ranges(new_selections)
+            });
+        });
+    }
+}
+
+/// Finds the number that `start` is within, or the next number after it on the same line,
+/// returning its range, its digits (with any `-` sign but without a `0b`, `0o` or `0x` prefix)
+/// and its radix. Without `backtrack`, only numbers starting at or after `start` are found.
+pub fn find_number(
+    snapshot: &MultiBufferSnapshot,
+    start: Point,
+    backtrack: bool,
+) -> Option<(Range<Point>, String, u32)> {
+    let mut offset = start.to_offset(snapshot);
+
+    let ch0 = snapshot.chars_at(offset).next();
+    if backtrack
+        && (ch0.as_ref().is_some_and(char::is_ascii_digit)
+            || matches!(ch0, Some('-' | 'b' | 'o' | 'x')))
+    {
+        // go backwards to the start of any number the selection is within
+        for ch in snapshot.reversed_chars_at(offset) {
+            if ch.is_ascii_digit() || matches!(ch, '-' | 'b' | 'o' | 'x') {
+                offset -= ch.len_utf8();
+                continue;
+            }
+            break;
+        }
+    }
+
+    let mut begin = None;
+    let mut end = None;
+    let mut num = String::new();
+    let mut radix = 10;
+    // A `-` right after a digit separates two numbers rather than being a sign.
+    let mut prev = snapshot.reversed_chars_at(offset).next();
+
+    let mut chars = snapshot.chars_at(offset).peekable();
+    // find the next number on the line (may start after the original cursor position)
+    while let Some(ch) = chars.next() {
+        if num == "0" {
+            let prefixed_radix = match ch {
+                'b' => Some(2),
+                'o' => Some(8),
+                'x' => Some(16),
+                _ => None,
+            };
+            if let Some(prefixed_radix) = prefixed_radix
+                .filter(|radix| chars.peek().is_some_and(|next| next.is_digit(*radix)))
+            {
+                radix = prefixed_radix;
+                begin = None;
+                num = String::new();
+            }
+        }
+
+        if ch.is_digit(radix)
+            || (begin.is_none()
+                && ch == '-'
+                && !prev.as_ref().is_some_and(char::is_ascii_digit)
+                && chars.peek().is_some()
+                && chars.peek().unwrap().is_digit(radix))
+        {
+            if begin.is_none() {
+                begin = Some(offset);
+            }
+            num.push(ch);
+        } else {
+            if begin.is_some() {
+                end = Some(offset);
+                break;
+            } else if ch == '\n' {
+                break;
+            }
+        }
+        prev = Some(ch);
+        offset += ch.len_utf8();
+    }
+    if let Some(begin) = begin {
+        let end = end.unwrap_or(offset);
+        Some((begin.to_point(snapshot)..end.to_point(snapshot), num, radix))
+    } else {
+        None
+    }
+}
+
+/// Returns the digits of a number found by [`find_number`] after adding `delta` to it. Decimal
+/// numbers are signed, while binary, octal and hexadecimal numbers wrap around like unsigned
+/// ones, keep their leading zeros and keep the case of their digits, as in vim.
+pub fn add_to_number(num: &str, radix: u32, delta: i64) -> Option<String> {
+    if radix == 10 {
+        let value = num.parse::<i64>().ok()?;
+        return Some(value.saturating_add(delta).to_string());
+    }
+
+    let value = u64::from_str_radix(num, radix).ok()?;
+    let result = value.wrapping_add_signed(delta);
+    let digits = match radix {
+        2 => format!("{result:b}"),
+        8 => format!("{result:o}"),
+        16 if num.to_ascii_lowercase() == num => format!("{result:x}"),
+        16 => format!("{result:X}"),
+        _ => return None,
+    };
+    Some(format!("{digits:0>width$}", width = num.len()))
+}
@@ -14,7 +14,8 @@ use std::{
 };
 
 use crate::{
-    display_map::Inlay, Anchor, Editor, ExcerptId, InlayId, MultiBuffer, MultiBufferSnapshot,
+    display_map::{HintDisplayOptions, Inlay},
+    Anchor, Editor, ExcerptId, InlayId, MultiBuffer, MultiBufferSnapshot,
 };
 use anyhow::Context;
 use clock::Global;
@@ -36,6 +37,7 @@ pub struct InlayHintCache {
     allowed_hint_kinds: HashSet<Option<InlayHintKind>>,
     version: usize,
     pub(super) enabled: bool,
+    pub(super) display_options: HintDisplayOptions,
     update_tasks: HashMap<ExcerptId, TasksForRanges>,
     refresh_task: Option<Task<()>>,
     invalidate_debounce: Option<Duration>,
@@ -268,6 +270,7 @@ impl InlayHintCache {
         Self {
             allowed_hint_kinds: inlay_hint_settings.enabled_inlay_hint_kinds(),
             enabled: inlay_hint_settings.enabled,
+            display_options: HintDisplayOptions::from(&inlay_hint_settings),
             hints: HashMap::default(),
             update_tasks: HashMap::default(),
             refresh_task: None,
@@ -291,13 +294,24 @@ impl InlayHintCache {
         self.invalidate_debounce = debounce_value(new_hint_settings.edit_debounce_ms);
         self.append_debounce = debounce_value(new_hint_settings.scroll_debounce_ms);
         let new_allowed_hint_kinds = new_hint_settings.enabled_inlay_hint_kinds();
+        let new_display_options = HintDisplayOptions::from(&new_hint_settings);
         match (self.enabled, new_hint_settings.enabled) {
             (false, false) => {
                 self.allowed_hint_kinds = new_allowed_hint_kinds;
+                self.display_options = new_display_options;
                 ControlFlow::Break(None)
             }
             (true, true) => {
-                if new_allowed_hint_kinds == self.allowed_hint_kinds {
+                if new_display_options != self.display_options {
+                    self.version += 1;
+                    self.allowed_hint_kinds = new_allowed_hint_kinds;
+                    self.display_options = new_display_options;
+                    ControlFlow::Break(Some(self.redisplay_hints_splice(
+                        multi_buffer,
+                        &visible_hints,
+                        cx,
+                    )))
+                } else if new_allowed_hint_kinds == self.allowed_hint_kinds {
                     ControlFlow::Break(None)
                 } else {
                     let new_splice = self.new_allowed_hint_kinds_splice(
@@ -316,6 +330,7 @@ impl InlayHintCache {
             (true, false) => {
                 self.enabled = new_hint_settings.enabled;
                 self.allowed_hint_kinds = new_allowed_hint_kinds;
+                self.display_options = new_display_options;
                 if self.hints.is_empty() {
                     ControlFlow::Break(None)
                 } else {
@@ -329,6 +344,7 @@ impl InlayHintCache {
             (false, true) => {
                 self.enabled = new_hint_settings.enabled;
                 self.allowed_hint_kinds = new_allowed_hint_kinds;
+                self.display_options = new_display_options;
                 ControlFlow::Continue(())
             }
         }
@@ -402,6 +418,42 @@ impl InlayHintCache {
         }
     }
 
+    /// Replaces all visible hints with the cached hints of the allowed kinds, displayed with
+    /// the current display options.
+    fn redisplay_hints_splice(
+        &self,
+        multi_buffer: &Model<MultiBuffer>,
+        visible_hints: &[Inlay],
+        cx: &mut ViewContext<Editor>,
+    ) -> InlaySplice {
+        let multi_buffer_snapshot = multi_buffer.read(cx).snapshot(cx);
+        let mut to_insert = Vec::new();
+        for (excerpt_id, excerpt_cached_hints) in &self.hints {
+            let excerpt_cached_hints = excerpt_cached_hints.read();
+            for cached_hint_id in &excerpt_cached_hints.ordered_hints {
+                let cached_hint = &excerpt_cached_hints.hints_by_id[cached_hint_id];
+                if !self.allowed_hint_kinds.contains(&cached_hint.kind) {
+                    continue;
+                }
+                if let Some(anchor) =
+                    multi_buffer_snapshot.anchor_in_excerpt(*excerpt_id, cached_hint.position)
+                {
+                    to_insert.push(Inlay::hint(
+                        cached_hint_id.id(),
+                        anchor,
+                        cached_hint,
+                        self.display_options,
+                    ));
+                }
+            }
+        }
+
+        InlaySplice {
+            to_remove: visible_hints.iter().map(|inlay| inlay.id).collect(),
+            to_insert,
+        }
+    }
+
     fn new_allowed_hint_kinds_splice(
         &self,
         multi_buffer: &Model<MultiBuffer>,
@@ -467,6 +519,7 @@ impl InlayHintCache {
                                                 cached_hint_id.id(),
                                                 anchor,
                                                 &cached_hint,
+                                                self.display_options,
                                             ));
                                         }
                                     }
@@ -491,6 +544,7 @@ impl InlayHintCache {
                             cached_hint_id.id(),
                             anchor,
                             &maybe_missed_cached_hint,
+                            self.display_options,
                         ));
                     }
                 }
@@ -1207,9 +1261,12 @@ fn apply_hint_update(
                 if let Some(new_hint_position) =
                     multi_buffer_snapshot.anchor_in_excerpt(query.excerpt_id, new_hint.position)
                 {
-                    splice
-                        .to_insert
-                        .push(Inlay::hint(new_inlay_id, new_hint_position, &new_hint));
+                    splice.to_insert.push(Inlay::hint(
+                        new_inlay_id,
+                        new_hint_position,
+                        &new_hint,
+                        editor.inlay_hint_cache.display_options,
+                    ));
                 }
             }
             let new_id = InlayId::Hint(new_inlay_id);
@@ -1296,6 +1353,8 @@ pub mod tests {
                 show_type_hints: allowed_hint_kinds.contains(&Some(InlayHintKind::Type)),
                 show_parameter_hints: allowed_hint_kinds.contains(&Some(InlayHintKind::Parameter)),
                 show_other_hints: allowed_hint_kinds.contains(&None),
+                show_padding: true,
+                max_length: None,
             })
         });
 
@@ -1428,6 +1487,8 @@ pub mod tests {
                 show_type_hints: true,
                 show_parameter_hints: true,
                 show_other_hints: true,
+                show_padding: true,
+                max_length: None,
             })
         });
 
@@ -1547,6 +1608,8 @@ pub mod tests {
                 show_type_hints: true,
                 show_parameter_hints: true,
                 show_other_hints: true,
+                show_padding: true,
+                max_length: None,
             })
         });
 
@@ -1777,6 +1840,8 @@ pub mod tests {
                 show_type_hints: allowed_hint_kinds.contains(&Some(InlayHintKind::Type)),
                 show_parameter_hints: allowed_hint_kinds.contains(&Some(InlayHintKind::Parameter)),
                 show_other_hints: allowed_hint_kinds.contains(&None),
+                show_padding: true,
+                max_length: None,
             })
         });
 
@@ -1941,6 +2006,8 @@ pub mod tests {
                     show_parameter_hints: new_allowed_hint_kinds
                         .contains(&Some(InlayHintKind::Parameter)),
                     show_other_hints: new_allowed_hint_kinds.contains(&None),
+                    show_padding: true,
+                    max_length: None,
                 })
             });
             cx.executor().run_until_parked();
@@ -1987,6 +2054,8 @@ pub mod tests {
                 show_parameter_hints: another_allowed_hint_kinds
                     .contains(&Some(InlayHintKind::Parameter)),
                 show_other_hints: another_allowed_hint_kinds.contains(&None),
+                show_padding: true,
+                max_length: None,
             })
         });
         cx.executor().run_until_parked();
@@ -2047,6 +2116,8 @@ pub mod tests {
                 show_parameter_hints: final_allowed_hint_kinds
                     .contains(&Some(InlayHintKind::Parameter)),
                 show_other_hints: final_allowed_hint_kinds.contains(&None),
+                show_padding: true,
+                max_length: None,
             })
         });
         cx.executor().run_until_parked();
@@ -2122,6 +2193,8 @@ pub mod tests {
                 show_type_hints: true,
                 show_parameter_hints: true,
                 show_other_hints: true,
+                show_padding: true,
+                max_length: None,
             })
         });
 
@@ -2256,6 +2329,8 @@ pub mod tests {
                 show_type_hints: true,
                 show_parameter_hints: true,
                 show_other_hints: true,
+                show_padding: true,
+                max_length: None,
             })
         });
 
@@ -2551,6 +2626,8 @@ pub mod tests {
                 show_type_hints: true,
                 show_parameter_hints: true,
                 show_other_hints: true,
+                show_padding: true,
+                max_length: None,
             })
         });
 
@@ -2901,6 +2978,8 @@ pub mod tests {
                 show_type_hints: false,
                 show_parameter_hints: false,
                 show_other_hints: false,
+                show_padding: true,
+                max_length: None,
             })
         });
 
@@ -3095,6 +3174,8 @@ pub mod tests {
                 show_type_hints: true,
                 show_parameter_hints: true,
                 show_other_hints: true,
+                show_padding: true,
+                max_length: None,
             })
         });
         cx.executor().run_until_parked();
@@ -3130,6 +3211,8 @@ pub mod tests {
                 show_type_hints: true,
                 show_parameter_hints: true,
                 show_other_hints: true,
+                show_padding: true,
+                max_length: None,
             })
         });
 
@@ -3224,6 +3307,8 @@ pub mod tests {
                 show_type_hints: true,
                 show_parameter_hints: true,
                 show_other_hints: true,
+                show_padding: true,
+                max_length: None,
             })
         });
 
@@ -3304,6 +3389,8 @@ pub mod tests {
                 show_type_hints: true,
                 show_parameter_hints: true,
                 show_other_hints: true,
+                show_padding: true,
+                max_length: None,
             })
         });
         cx.executor().run_until_parked();
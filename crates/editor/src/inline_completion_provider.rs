@@ -2,7 +2,11 @@ use crate::Direction;
 use gpui::{AppContext, Model, ModelContext};
 use language::Buffer;
 
+/// A source of inline completions, which the editor shows as ghost text after the cursor.
+/// Several providers can be registered with an editor, and the suggestion of the one with the
+/// highest priority in the `inline_completions.providers` setting is shown.
 pub trait InlineCompletionProvider: 'static + Sized {
+    /// The provider's key in the `inline_completions.providers` setting.
     fn name() -> &'static str;
     fn is_enabled(
         &self,
@@ -35,6 +39,7 @@ pub trait InlineCompletionProvider: 'static + Sized {
 }
 
 pub trait InlineCompletionProviderHandle {
+    fn name(&self) -> &'static str;
     fn is_enabled(
         &self,
         buffer: &Model<Buffer>,
@@ -69,6 +74,10 @@ impl<T> InlineCompletionProviderHandle for Model<T>
 where
     T: InlineCompletionProvider,
 {
+    fn name(&self) -> &'static str {
+        T::name()
+    }
+
     fn is_enabled(
         &self,
         buffer: &Model<Buffer>,
@@ -1,10 +1,10 @@
 use crate::{
     editor_settings::SeedQuerySetting, persistence::DB, scroll::ScrollAnchor, Anchor, Autoscroll,
-    Editor, EditorEvent, EditorSettings, ExcerptId, ExcerptRange, MultiBuffer, MultiBufferSnapshot,
-    NavigationData, SearchWithinRange, ToPoint as _,
+    Editor, EditorEvent, EditorMode, EditorSettings, ExcerptId, ExcerptRange, MultiBuffer,
+    MultiBufferRow, MultiBufferSnapshot, NavigationData, SearchWithinRange, ToPoint as _,
 };
 use anyhow::{anyhow, Context as _, Result};
-use collections::HashSet;
+use collections::{FxHasher, HashSet};
 use futures::future::try_join_all;
 use git::repository::GitFileStatus;
 use gpui::{
@@ -19,6 +19,7 @@ use language::{
 use multi_buffer::AnchorRangeExt;
 use project::{search::SearchQuery, FormatTrigger, Item as _, Project, ProjectPath};
 use rpc::proto::{self, update_view, PeerId};
+use serde::{Deserialize, Serialize};
 use settings::Settings;
 use workspace::item::{ItemSettings, TabContentParams};
 
@@ -26,12 +27,14 @@ use std::{
     any::TypeId,
     borrow::Cow,
     cmp::{self, Ordering},
+    hash::Hasher,
     iter,
     ops::Range,
-    path::Path,
+    path::{Path, PathBuf},
     sync::Arc,
+    time::{Duration, SystemTime},
 };
-use text::{BufferId, Selection};
+use text::{BufferId, Selection, ToPoint as _};
 use theme::{Theme, ThemeSettings};
 use ui::{h_flex, prelude::*, Label};
 use util::{paths::PathExt, ResultExt, TryFutureExt};
@@ -39,11 +42,46 @@ use workspace::item::{BreadcrumbText, FollowEvent, FollowableItemHandle};
 use workspace::{
     item::{FollowableItem, Item, ItemEvent, ItemHandle, ProjectItem},
     searchable::{Direction, SearchEvent, SearchableItem, SearchableItemHandle},
-    ItemId, ItemNavHistory, Pane, ToolbarItemLocation, ViewId, Workspace, WorkspaceId,
+    ItemId, ItemNavHistory, NavigationLocation, Pane, ToolbarItemLocation, ViewId, Workspace,
+    WorkspaceId,
 };
 
 pub const MAX_TAB_TITLE_LEN: usize = 24;
 
+/// How long to wait after an editor's selections, folds or scroll position change before
+/// saving them, so that they can be restored when its file is opened again.
+const FILE_STATE_SERIALIZATION_DELAY: Duration = Duration::from_millis(500);
+
+/// How long to wait after an edit before recording where it happened, so that a burst of
+/// typing is recorded once.
+const EDIT_RECORDING_DELAY: Duration = Duration::from_millis(500);
+
+/// A file that was edited in a workspace, along with where and when it was last edited.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecentlyEditedFile {
+    pub abs_path: PathBuf,
+    pub position: Point,
+    pub edited_at: time::OffsetDateTime,
+}
+
+/// Returns the files most recently edited in the given workspace, from the latest edit.
+pub fn recently_edited_files(
+    workspace_id: WorkspaceId,
+    limit: usize,
+) -> Result<Vec<RecentlyEditedFile>> {
+    Ok(DB
+        .get_edited_files(workspace_id, limit)?
+        .into_iter()
+        .filter_map(|(abs_path, row, column, edited_at)| {
+            Some(RecentlyEditedFile {
+                abs_path,
+                position: Point::new(row, column),
+                edited_at: time::OffsetDateTime::from_unix_timestamp(edited_at).ok()?,
+            })
+        })
+        .collect())
+}
+
 impl FollowableItem for Editor {
     fn remote_id(&self) -> Option<ViewId> {
         self.remote_id
@@ -573,6 +611,51 @@ impl Item for Editor {
         }
     }
 
+    fn navigation_location(
+        &self,
+        data: &dyn std::any::Any,
+        cx: &AppContext,
+    ) -> Option<NavigationLocation> {
+        let data = data.downcast_ref::<NavigationData>()?;
+        let buffer = self.buffer.read(cx).read(cx);
+        let point = if buffer.can_resolve(&data.cursor_anchor) {
+            data.cursor_anchor.to_point(&buffer)
+        } else {
+            buffer.clip_point(data.cursor_position, Bias::Left)
+        };
+        let line_end = Point::new(point.row, buffer.line_len(MultiBufferRow(point.row)));
+        let line = buffer
+            .text_for_range(Point::new(point.row, 0)..line_end)
+            .collect::<String>();
+
+        Some(NavigationLocation {
+            row: point.row,
+            column: point.column,
+            line: line.trim().to_string().into(),
+        })
+    }
+
+    fn navigation_data(
+        &self,
+        row: u32,
+        column: u32,
+        cx: &AppContext,
+    ) -> Option<Box<dyn std::any::Any + Send>> {
+        let buffer = self.buffer.read(cx).read(cx);
+        let cursor_position = buffer.clip_point(Point::new(row, column), Bias::Left);
+        let cursor_anchor = buffer.anchor_before(cursor_position);
+
+        Some(Box::new(NavigationData {
+            cursor_anchor,
+            cursor_position,
+            scroll_anchor: ScrollAnchor {
+                offset: Default::default(),
+                anchor: cursor_anchor,
+            },
+            scroll_top_row: cursor_position.row,
+        }))
+    }
+
     fn tab_tooltip_text(&self, cx: &AppContext) -> Option<SharedString> {
         let file_path = self
             .buffer()
@@ -673,6 +756,7 @@ impl Item for Editor {
     fn deactivated(&mut self, cx: &mut ViewContext<Self>) {
         let selection = self.selections.newest_anchor();
         self.push_to_nav_history(selection.head(), None, cx);
+        self.serialize_file_state(cx).detach();
     }
 
     fn workspace_deactivated(&mut self, cx: &mut ViewContext<Self>) {
@@ -848,8 +932,23 @@ impl Item for Editor {
     fn added_to_workspace(&mut self, workspace: &mut Workspace, cx: &mut ViewContext<Self>) {
         let workspace_id = workspace.database_id();
         let item_id = cx.view().item_id().as_u64() as ItemId;
+        let first_added = self.workspace.is_none();
         self.workspace = Some((workspace.weak_handle(), workspace.database_id()));
 
+        if first_added {
+            self.restore_file_state(workspace_id, cx).detach();
+            cx.subscribe(&cx.view().clone(), |editor, _, event: &EditorEvent, cx| {
+                if let EditorEvent::SelectionsChanged { local: true }
+                | EditorEvent::ScrollPositionChanged { local: true, .. } = event
+                {
+                    editor.schedule_file_state_serialization(cx);
+                } else if let EditorEvent::Edited = event {
+                    editor.schedule_edit_recording(cx);
+                }
+            })
+            .detach();
+        }
+
         fn serialize(
             buffer: Model<Buffer>,
             workspace_id: WorkspaceId,
@@ -971,6 +1070,243 @@ impl Item for Editor {
     }
 }
 
+/// The state of an editor for a file, which is restored when the file is opened again.
+#[derive(Debug, Serialize, Deserialize)]
+struct SerializedFileState {
+    /// The file's modification time when the state was saved, or `None` if the buffer had
+    /// unsaved changes, in which case the offsets below may not match the file on disk.
+    mtime: Option<SystemTime>,
+    /// A hash of the file's contents when the state was saved, if it had no unsaved changes.
+    #[serde(default)]
+    content_hash: Option<u64>,
+    scroll_top_row: u32,
+    scroll_offset: (f32, f32),
+    /// The offsets of each selection, from its tail to its head.
+    selections: Vec<Range<usize>>,
+    folds: Vec<Range<usize>>,
+}
+
+impl Editor {
+    fn file_state_path(&self, cx: &AppContext) -> Option<PathBuf> {
+        if self.mode != EditorMode::Full {
+            return None;
+        }
+        let buffer = self.buffer().read(cx).as_singleton()?;
+        let file = buffer.read(cx).file()?.as_local()?;
+        Some(file.abs_path(cx))
+    }
+
+    pub(crate) fn schedule_file_state_serialization(&mut self, cx: &mut ViewContext<Self>) {
+        if self.workspace.is_none() {
+            return;
+        }
+
+        self.serialize_file_state_task = Some(cx.spawn(|editor, mut cx| async move {
+            cx.background_executor()
+                .timer(FILE_STATE_SERIALIZATION_DELAY)
+                .await;
+            editor
+                .update(&mut cx, |editor, cx| {
+                    editor.serialize_file_state(cx).detach()
+                })
+                .ok();
+        }));
+    }
+
+    fn schedule_edit_recording(&mut self, cx: &mut ViewContext<Self>) {
+        self.record_edit_task = Some(cx.spawn(|editor, mut cx| async move {
+            cx.background_executor().timer(EDIT_RECORDING_DELAY).await;
+            editor
+                .update(&mut cx, |editor, cx| editor.record_edit(cx).detach())
+                .ok();
+        }));
+    }
+
+    /// Records where the editor last edited its file, for listing recently edited files. Only
+    /// the editor's own edits are recorded, rather than reloads or collaborators' edits.
+    fn record_edit(&mut self, cx: &mut ViewContext<Self>) -> Task<()> {
+        self.record_edit_task.take();
+        let Some(last_edit) = self.last_edit.take() else {
+            return Task::ready(());
+        };
+        let Some((_, workspace_id)) = self.workspace.as_ref() else {
+            return Task::ready(());
+        };
+        let workspace_id = *workspace_id;
+        let Some(path) = self.file_state_path(cx) else {
+            return Task::ready(());
+        };
+        let Some(buffer) = self.buffer.read(cx).as_singleton() else {
+            return Task::ready(());
+        };
+
+        let position = last_edit.to_point(buffer.read(cx));
+        let edited_at = time::OffsetDateTime::now_utc().unix_timestamp();
+        cx.background_executor().spawn(async move {
+            DB.save_edited_file(workspace_id, path, position.row, position.column, edited_at)
+                .await
+                .log_err();
+        })
+    }
+
+    fn serialize_file_state(&mut self, cx: &mut ViewContext<Self>) -> Task<()> {
+        self.serialize_file_state_task.take();
+        let Some((_, workspace_id)) = self.workspace.as_ref() else {
+            return Task::ready(());
+        };
+        let workspace_id = *workspace_id;
+        let Some(path) = self.file_state_path(cx) else {
+            return Task::ready(());
+        };
+
+        let display_snapshot = self.display_map.update(cx, |map, cx| map.snapshot(cx));
+        let snapshot = &display_snapshot.buffer_snapshot;
+        let saved_buffer = self
+            .buffer()
+            .read(cx)
+            .as_singleton()
+            .map(|buffer| buffer.read(cx))
+            .filter(|buffer| !buffer.is_dirty());
+        let mtime = saved_buffer.and_then(|buffer| buffer.saved_mtime());
+        let saved_text = saved_buffer.map(|buffer| buffer.text_snapshot());
+        let scroll_anchor = self.scroll_manager.anchor();
+        let mut state = SerializedFileState {
+            mtime,
+            content_hash: None,
+            scroll_top_row: scroll_anchor.anchor.to_point(snapshot).row,
+            scroll_offset: (scroll_anchor.offset.x, scroll_anchor.offset.y),
+            selections: self
+                .selections
+                .all::<usize>(cx)
+                .into_iter()
+                .map(|selection| selection.tail()..selection.head())
+                .collect(),
+            folds: display_snapshot
+                .folds_in_range(0..snapshot.len())
+                .map(|fold| fold.range.to_offset(snapshot))
+                .collect(),
+        };
+
+        cx.background_executor().spawn(async move {
+            state.content_hash = saved_text.as_ref().map(content_hash);
+            if let Some(state) = serde_json::to_string(&state).log_err() {
+                DB.save_file_state(workspace_id, path, state)
+                    .await
+                    .log_err();
+            }
+        })
+    }
+
+    /// Returns whether the editor still shows the start of its file, without folds, as it
+    /// does when it's first opened.
+    fn has_initial_file_state(&mut self, cx: &mut ViewContext<Self>) -> bool {
+        let snapshot = self.display_map.update(cx, |map, cx| map.snapshot(cx));
+        self.selections.count() == 1
+            && self.selections.newest::<usize>(cx).head() == 0
+            && snapshot
+                .folds_in_range(0..snapshot.buffer_snapshot.len())
+                .next()
+                .is_none()
+    }
+
+    /// Restores the selections, folds and scroll position the file had when it was last
+    /// open in this workspace, unless the file has changed since. The state is loaded in the
+    /// background, and is discarded if the editor is moved or edited in the meantime.
+    fn restore_file_state(
+        &mut self,
+        workspace_id: WorkspaceId,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<()> {
+        let Some(path) = self.file_state_path(cx) else {
+            return Task::ready(());
+        };
+        // Leave editors that were already moved away from the start of the file as they are,
+        // such as those opened to show a particular location.
+        if !self.has_initial_file_state(cx) {
+            return Task::ready(());
+        }
+        let Some(buffer) = self.buffer().read(cx).as_singleton() else {
+            return Task::ready(());
+        };
+        let buffer = buffer.read(cx);
+        if buffer.is_dirty() {
+            return Task::ready(());
+        }
+        let mtime = buffer.saved_mtime();
+        let text = buffer.text_snapshot();
+        let version = buffer.version();
+
+        cx.spawn(|editor, mut cx| async move {
+            let state = cx
+                .background_executor()
+                .spawn(async move {
+                    let state = DB.get_file_state(workspace_id, path).await.log_err()??;
+                    let state = serde_json::from_str::<SerializedFileState>(&state).log_err()?;
+                    let unchanged = state.mtime.is_some()
+                        && state.mtime == mtime
+                        && state.content_hash == Some(content_hash(&text));
+                    unchanged.then_some(state)
+                })
+                .await;
+            let Some(state) = state else {
+                return;
+            };
+
+            editor
+                .update(&mut cx, |editor, cx| {
+                    let buffer_unchanged = editor
+                        .buffer()
+                        .read(cx)
+                        .as_singleton()
+                        .map_or(false, |buffer| buffer.read(cx).version() == version);
+                    if buffer_unchanged && editor.has_initial_file_state(cx) {
+                        editor.apply_file_state(state, cx);
+                    }
+                })
+                .ok();
+        })
+    }
+
+    fn apply_file_state(&mut self, state: SerializedFileState, cx: &mut ViewContext<Self>) {
+        let snapshot = self.buffer().read(cx).snapshot(cx);
+        let clip = |offset: usize| snapshot.clip_offset(offset.min(snapshot.len()), Bias::Left);
+        let folds = state
+            .folds
+            .iter()
+            .map(|fold| clip(fold.start)..clip(fold.end))
+            .filter(|fold| !fold.is_empty())
+            .collect::<Vec<_>>();
+        let selections = state
+            .selections
+            .iter()
+            .map(|selection| clip(selection.start)..clip(selection.end))
+            .collect::<Vec<_>>();
+
+        self.fold_ranges(folds, false, cx);
+        if !selections.is_empty() {
+            self.change_selections(None, cx, |s| s.select_ranges(selections));
+        }
+
+        let top_row = state.scroll_top_row.min(snapshot.max_point().row);
+        let scroll_anchor = ScrollAnchor {
+            anchor: snapshot.anchor_at(Point::new(top_row, 0), Bias::Left),
+            offset: point(state.scroll_offset.0, state.scroll_offset.1),
+        };
+        self.set_scroll_anchor(scroll_anchor, cx);
+    }
+}
+
+/// Hashes a file's contents, independently of how they're split into chunks.
+fn content_hash(text: &text::BufferSnapshot) -> u64 {
+    let mut hasher = FxHasher::default();
+    for chunk in text.as_rope().chunks() {
+        for byte in chunk.bytes() {
+            hasher.write_u8(byte);
+        }
+    }
+    hasher.finish()
+}
+
 impl ProjectItem for Editor {
     type Item = Buffer;
 
@@ -986,11 +1322,32 @@ impl ProjectItem for Editor {
 impl EventEmitter<SearchEvent> for Editor {}
 
 pub(crate) enum BufferSearchHighlights {}
+
+impl Editor {
+    /// The buffer search match that starts at the given position, if there is one. Matches are
+    /// selected collapsed to their start when `collapse_matches` is set, so this finds their end.
+    pub fn search_match_starting_at(
+        &self,
+        position: Anchor,
+        cx: &AppContext,
+    ) -> Option<Range<Anchor>> {
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let (_, matches) = self
+            .background_highlights
+            .get(&TypeId::of::<BufferSearchHighlights>())?;
+        matches
+            .iter()
+            .find(|range| range.start.cmp(&position, &snapshot).is_eq())
+            .cloned()
+    }
+}
+
 impl SearchableItem for Editor {
     type Match = Range<Anchor>;
 
     fn clear_matches(&mut self, cx: &mut ViewContext<Self>) {
         self.clear_background_highlights::<BufferSearchHighlights>(cx);
+        self.last_search = None;
     }
 
     fn update_matches(&mut self, matches: &[Range<Anchor>], cx: &mut ViewContext<Self>) {
@@ -1138,10 +1495,26 @@ impl SearchableItem for Editor {
                     .map(|range| range.to_offset(&buffer))
                     .collect::<Vec<_>>()
             });
-        cx.background_executor().spawn(async move {
+        // Searching again for the same query only searches the lines edited since, so that
+        // the matches stay up to date cheaply while typing.
+        let version = buffer
+            .as_singleton()
+            .filter(|_| search_within_ranges.is_none() && !query.is_multiline())
+            .map(|(_, _, excerpt_buffer)| excerpt_buffer.version().clone());
+        let last_search = self
+            .last_search
+            .take()
+            .filter(|last_search| version.is_some() && last_search.is_for(&query));
+        let search_query = query.clone();
+        let search = cx.background_executor().spawn(async move {
+            let query = search_query;
             let mut ranges = Vec::new();
             if let Some((_, _, excerpt_buffer)) = buffer.as_singleton() {
-                if let Some(search_within_ranges) = search_within_ranges {
+                if let Some(last_search) = last_search {
+                    ranges = last_search
+                        .search_edited_lines(&query, &buffer, excerpt_buffer)
+                        .await;
+                } else if let Some(search_within_ranges) = search_within_ranges {
                     for range in search_within_ranges {
                         let offset = range.start;
                         ranges.extend(
@@ -1182,6 +1555,21 @@ impl SearchableItem for Editor {
                 }
             }
             ranges
+        });
+        cx.spawn(|editor, mut cx| async move {
+            let matches = search.await;
+            if let Some(version) = version {
+                editor
+                    .update(&mut cx, |editor, _| {
+                        editor.last_search = Some(LastSearch {
+                            query,
+                            version,
+                            matches: matches.clone(),
+                        });
+                    })
+                    .ok();
+            }
+            matches
         })
     }
 
@@ -1202,6 +1590,69 @@ impl SearchableItem for Editor {
     }
 }
 
+/// The matches found by an editor's last search of a singleton buffer.
+pub(crate) struct LastSearch {
+    query: Arc<SearchQuery>,
+    version: clock::Global,
+    matches: Vec<Range<Anchor>>,
+}
+
+impl LastSearch {
+    fn is_for(&self, query: &SearchQuery) -> bool {
+        self.query.as_str() == query.as_str()
+            && self.query.is_regex() == query.is_regex()
+            && self.query.whole_word() == query.whole_word()
+            && self.query.case_sensitive() == query.case_sensitive()
+    }
+
+    /// Searches the lines edited since the last search, keeping its matches on other lines.
+    async fn search_edited_lines(
+        self,
+        query: &SearchQuery,
+        buffer: &MultiBufferSnapshot,
+        excerpt_buffer: &language::BufferSnapshot,
+    ) -> Vec<Range<Anchor>> {
+        let mut edited_ranges = Vec::<Range<usize>>::new();
+        for edit in excerpt_buffer.edits_since::<Point>(&self.version) {
+            let start = Point::new(edit.new.start.row, 0);
+            let end = Point::new(edit.new.end.row, excerpt_buffer.line_len(edit.new.end.row));
+            let range = excerpt_buffer.point_to_offset(start)..excerpt_buffer.point_to_offset(end);
+            match edited_ranges.last_mut() {
+                Some(last) if last.end >= range.start => last.end = last.end.max(range.end),
+                _ => edited_ranges.push(range),
+            }
+        }
+
+        let mut matches = Vec::new();
+        let mut old_matches = self.matches.into_iter().peekable();
+        for edited_range in edited_ranges {
+            while let Some(old_match) = old_matches.peek() {
+                let old_range = old_match.to_offset(buffer);
+                if old_range.end < edited_range.start {
+                    matches.extend(old_matches.next());
+                } else if old_range.start <= edited_range.end {
+                    old_matches.next();
+                } else {
+                    break;
+                }
+            }
+            let offset = edited_range.start;
+            matches.extend(
+                query
+                    .search(excerpt_buffer, Some(edited_range))
+                    .await
+                    .into_iter()
+                    .map(|range| {
+                        buffer.anchor_after(range.start + offset)
+                            ..buffer.anchor_before(range.end + offset)
+                    }),
+            );
+        }
+        matches.extend(old_matches);
+        matches
+    }
+}
+
 pub fn active_match_index(
     ranges: &[Range<Anchor>],
     cursor: &Anchor,
@@ -1300,8 +1751,11 @@ fn path_for_file<'a>(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use gpui::AppContext;
+    use crate::editor_tests::init_test;
+    use gpui::{AppContext, TestAppContext};
     use language::TestFile;
+    use project::FakeFs;
+    use serde_json::json;
     use std::path::Path;
 
     #[gpui::test]
@@ -1312,4 +1766,167 @@ mod tests {
         };
         assert_eq!(path_for_file(&file, 0, false, cx), None);
     }
+
+    #[gpui::test]
+    async fn test_file_state_persistence(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+        cx.executor().allow_parking();
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/root", json!({ "a.txt": "one\ntwo\nthree\nfour\nfive\n" }))
+            .await;
+        let project = Project::test(fs, ["/root".as_ref()], cx).await;
+        let workspace = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
+        let workspace_id = workspace::WORKSPACE_DB.next_id().await.unwrap();
+        let buffer = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer("/root/a.txt", cx)
+            })
+            .await
+            .unwrap();
+
+        let open_editor = |cx: &mut TestAppContext| {
+            workspace
+                .update(cx, |_, cx| {
+                    let weak_workspace = cx.view().downgrade();
+                    cx.new_view(|cx| {
+                        let mut editor =
+                            Editor::for_buffer(buffer.clone(), Some(project.clone()), cx);
+                        editor.workspace = Some((weak_workspace, workspace_id));
+                        editor
+                    })
+                })
+                .unwrap()
+        };
+        let restore_state = |editor: &View<Editor>, cx: &mut TestAppContext| {
+            workspace
+                .update(cx, |_, cx| {
+                    editor.update(cx, |editor, cx| editor.restore_file_state(workspace_id, cx))
+                })
+                .unwrap()
+        };
+        let selections_and_fold_count = |editor: &View<Editor>, cx: &mut TestAppContext| {
+            workspace
+                .update(cx, |_, cx| {
+                    editor.update(cx, |editor, cx| {
+                        let snapshot = editor.display_map.update(cx, |map, cx| map.snapshot(cx));
+                        (
+                            editor.selections.ranges::<usize>(cx),
+                            snapshot
+                                .folds_in_range(0..snapshot.buffer_snapshot.len())
+                                .count(),
+                        )
+                    })
+                })
+                .unwrap()
+        };
+
+        // The state of one editor is restored in the next editor opened for the file.
+        let editor = open_editor(cx);
+        workspace
+            .update(cx, |_, cx| {
+                editor.update(cx, |editor, cx| {
+                    editor.fold_ranges([Point::new(2, 0)..Point::new(3, 4)], false, cx);
+                    editor.change_selections(None, cx, |s| s.select_ranges([4..7]));
+                    editor.serialize_file_state(cx)
+                })
+            })
+            .unwrap()
+            .await;
+
+        let editor = open_editor(cx);
+        restore_state(&editor, cx).await;
+        assert_eq!(selections_and_fold_count(&editor, cx), (vec![4..7], 1));
+
+        // Once the file has changed, the saved state no longer applies to it.
+        buffer.update(cx, |buffer, cx| buffer.edit([(0..0, "zero\n")], None, cx));
+        project
+            .update(cx, |project, cx| project.save_buffer(buffer.clone(), cx))
+            .await
+            .unwrap();
+
+        let editor = open_editor(cx);
+        restore_state(&editor, cx).await;
+        assert_eq!(selections_and_fold_count(&editor, cx), (vec![0..0], 0));
+    }
+
+    #[gpui::test]
+    async fn test_recording_edits(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+        cx.executor().allow_parking();
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/root", json!({ "a.txt": "one\ntwo\nthree\nfour\n" }))
+            .await;
+        let project = Project::test(fs, ["/root".as_ref()], cx).await;
+        let workspace = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
+        let workspace_id = workspace::WORKSPACE_DB.next_id().await.unwrap();
+        let buffer = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer("/root/a.txt", cx)
+            })
+            .await
+            .unwrap();
+        let editor = workspace
+            .update(cx, |_, cx| {
+                let weak_workspace = cx.view().downgrade();
+                cx.new_view(|cx| {
+                    let mut editor = Editor::for_buffer(buffer.clone(), Some(project.clone()), cx);
+                    editor.workspace = Some((weak_workspace, workspace_id));
+                    editor
+                })
+            })
+            .unwrap();
+
+        let edit_in_editor = |position: Point, cx: &mut TestAppContext| {
+            workspace
+                .update(cx, |_, cx| {
+                    editor.update(cx, |editor, cx| {
+                        editor.transact(cx, |editor, cx| {
+                            editor.edit([(position..position, "!")], cx)
+                        });
+                    })
+                })
+                .unwrap()
+        };
+        let record_edit = |cx: &mut TestAppContext| {
+            workspace
+                .update(cx, |_, cx| {
+                    editor.update(cx, |editor, cx| editor.record_edit(cx))
+                })
+                .unwrap()
+        };
+        let edited_positions = || {
+            recently_edited_files(workspace_id, 10)
+                .unwrap()
+                .into_iter()
+                .map(|file| (file.abs_path, file.position))
+                .collect::<Vec<_>>()
+        };
+
+        // The editor's edits are recorded where they're made, rather than at the cursor.
+        edit_in_editor(Point::new(1, 3), cx);
+        record_edit(cx).await;
+        assert_eq!(
+            edited_positions(),
+            [(PathBuf::from("/root/a.txt"), Point::new(1, 3))]
+        );
+
+        // Edits the editor didn't make, like reloads or collaborators' edits, aren't recorded.
+        buffer.update(cx, |buffer, cx| buffer.edit([(0..0, "zero\n")], None, cx));
+        record_edit(cx).await;
+        assert_eq!(
+            edited_positions(),
+            [(PathBuf::from("/root/a.txt"), Point::new(1, 3))]
+        );
+
+        // An edit's position moves with the edits made before it's recorded.
+        edit_in_editor(Point::new(3, 0), cx);
+        buffer.update(cx, |buffer, cx| buffer.edit([(0..0, "top\n")], None, cx));
+        record_edit(cx).await;
+        assert_eq!(
+            edited_positions(),
+            [(PathBuf::from("/root/a.txt"), Point::new(4, 0))]
+        );
+    }
 }
@@ -0,0 +1,372 @@
+//! Editing assists for markup languages like HTML and JSX: inserting the closing tag when an
+//! opening tag is completed, and keeping the names of matching opening and closing tags in sync.
+
+use std::{ops::Range, sync::Arc};
+
+use multi_buffer::{MultiBufferSnapshot, ToOffset};
+use sum_tree::Bias;
+
+/// How far to look for the tag matching the one being edited, in bytes.
+const MAX_TAG_SEARCH_LEN: usize = 64 * 1024;
+
+/// How far to look back for the start of the tag being completed, in bytes.
+const MAX_TAG_LEN: usize = 1024;
+
+#[derive(Debug, PartialEq, Eq)]
+struct Tag {
+    /// The range of the tag's name, which is empty for fragments (`<>` and `</>`).
+    name_range: Range<usize>,
+    is_closing: bool,
+    is_self_closing: bool,
+}
+
+/// Returns whether tags at the given position should be automatically closed and renamed.
+fn tag_editing_enabled(snapshot: &MultiBufferSnapshot, offset: usize) -> bool {
+    snapshot.language_scope_at(offset).map_or(false, |scope| {
+        scope.auto_close_tags() && !matches!(scope.override_name(), Some("string" | "comment"))
+    })
+}
+
+/// Returns whether the `<` at the given position starts an opening tag in the syntax tree. Tags
+/// that are still being typed may also start a syntax error, as they lack their closing `>`.
+fn starts_opening_tag_node(snapshot: &MultiBufferSnapshot, offset: usize) -> bool {
+    let Some(scope) = snapshot.language_scope_at(offset) else {
+        return false;
+    };
+    let Some((buffer, offset)) = snapshot.point_to_buffer_offset(offset) else {
+        return false;
+    };
+    let Some(layer) = buffer.syntax_layer_at(offset) else {
+        return false;
+    };
+    let mut node = layer.node().descendant_for_byte_range(offset, offset + 1);
+    while let Some(ancestor) = node {
+        if ancestor.start_byte() != offset {
+            break;
+        }
+        if ancestor.is_error()
+            || scope
+                .opening_tag_node_kinds()
+                .iter()
+                .any(|kind| kind == ancestor.kind())
+        {
+            return true;
+        }
+        node = ancestor.parent();
+    }
+    false
+}
+
+fn is_tag_name_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '-' | '_' | '.' | ':')
+}
+
+/// Returns the closing tag that should be inserted after the cursor when `>` is typed at `offset`,
+/// if doing so completes an opening tag.
+pub(crate) fn closing_tag_for_input(
+    snapshot: &MultiBufferSnapshot,
+    offset: usize,
+) -> Option<String> {
+    let mut text_before = String::new();
+    for c in snapshot.reversed_chars_at(offset) {
+        if text_before.len() >= MAX_TAG_LEN {
+            return None;
+        }
+        text_before.insert(0, c);
+        if c == '<' {
+            break;
+        }
+    }
+    let tag_text = text_before.strip_prefix('<')?;
+
+    // The scope is checked at the start of the tag, as the cursor may be at the end of an
+    // attribute value.
+    let tag_start = offset - text_before.len();
+    if !tag_editing_enabled(snapshot, tag_start) {
+        return None;
+    }
+
+    // Avoid treating generic type arguments, like `Vec<T>`, as tags.
+    if snapshot
+        .reversed_chars_at(tag_start)
+        .next()
+        .map_or(false, |c| c.is_alphanumeric() || c == '_')
+    {
+        return None;
+    }
+
+    // Avoid treating comparisons, like `a <b`, as tags.
+    if !starts_opening_tag_node(snapshot, tag_start) {
+        return None;
+    }
+
+    let name_len = tag_text
+        .find(|c: char| !is_tag_name_char(c))
+        .unwrap_or(tag_text.len());
+    let (name, attributes) = tag_text.split_at(name_len);
+    if name.is_empty() {
+        // Only fragments (`<>`) may have empty names.
+        if !attributes.is_empty() {
+            return None;
+        }
+    } else if !name.starts_with(char::is_alphabetic)
+        || (!attributes.is_empty() && !attributes.starts_with(char::is_whitespace))
+        || attributes.trim_end().ends_with('/')
+    {
+        return None;
+    }
+
+    // The typed `>` must end the tag, rather than appearing in a quoted attribute value or
+    // an embedded expression.
+    let completed_tag = format!("{text_before}>");
+    if tag_end(&completed_tag, name_len + 1) != Some(text_before.len()) {
+        return None;
+    }
+
+    let is_void_element = snapshot
+        .language_scope_at(tag_start)
+        .map_or(false, |scope| {
+            scope
+                .void_elements()
+                .iter()
+                .any(|void_element| void_element.eq_ignore_ascii_case(name))
+        });
+    if is_void_element {
+        return None;
+    }
+
+    // When `>` is typed before an automatically-inserted `>`, the closing tag follows that one.
+    let closing_tag = format!("</{name}>");
+    let closing_tag_offset = offset
+        + snapshot
+            .chars_at(offset)
+            .next()
+            .filter(|c| *c == '>')
+            .map_or(0, char::len_utf8);
+    if snapshot.contains_str_at(closing_tag_offset, &closing_tag) {
+        return None;
+    }
+    Some(closing_tag)
+}
+
+/// Returns the edits that keep the names of matching tags in sync with the given edits, which
+/// have yet to be applied. Linked edits that would overlap any of the given edits are omitted.
+pub(crate) fn linked_tag_edits<T: ToOffset>(
+    snapshot: &MultiBufferSnapshot,
+    edits: &[(Range<T>, Arc<str>)],
+) -> Vec<(Range<usize>, Arc<str>)> {
+    let edits = edits
+        .iter()
+        .map(|(range, text)| {
+            (
+                range.start.to_offset(snapshot)..range.end.to_offset(snapshot),
+                text,
+            )
+        })
+        .collect::<Vec<_>>();
+    let mut linked_edits = Vec::<(Range<usize>, Arc<str>)>::new();
+    for (range, text) in &edits {
+        let Some(linked_range) = linked_tag_edit_range(snapshot, range.clone(), text) else {
+            continue;
+        };
+        let overlaps = |other: &Range<usize>| {
+            other.start <= linked_range.end && linked_range.start <= other.end
+        };
+        if edits.iter().any(|(range, _)| overlaps(range))
+            || linked_edits.iter().any(|(range, _)| overlaps(range))
+        {
+            continue;
+        }
+        linked_edits.push((linked_range, (*text).clone()));
+    }
+    linked_edits
+}
+
+/// Given a range that is about to be replaced with `new_text`, returns the range within the
+/// matching opening or closing tag that should receive the same edit, if the range lies within
+/// the name of a tag.
+fn linked_tag_edit_range(
+    snapshot: &MultiBufferSnapshot,
+    range: Range<usize>,
+    new_text: &str,
+) -> Option<Range<usize>> {
+    if !new_text.chars().all(is_tag_name_char) || !tag_editing_enabled(snapshot, range.start) {
+        return None;
+    }
+    if !snapshot
+        .text_for_range(range.clone())
+        .flat_map(str::chars)
+        .all(is_tag_name_char)
+    {
+        return None;
+    }
+
+    let name_start = range.start
+        - snapshot
+            .reversed_chars_at(range.start)
+            .take_while(|c| is_tag_name_char(*c))
+            .map(char::len_utf8)
+            .sum::<usize>();
+    let name_end = range.end
+        + snapshot
+            .chars_at(range.end)
+            .take_while(|c| is_tag_name_char(*c))
+            .map(char::len_utf8)
+            .sum::<usize>();
+
+    let mut preceding_chars = snapshot.reversed_chars_at(name_start);
+    let (is_closing, tag_start) = match (preceding_chars.next(), preceding_chars.next()) {
+        (Some('/'), Some('<')) => (true, name_start - 2),
+        (Some('<'), _) => (false, name_start - 1),
+        _ => return None,
+    };
+
+    let linked_name_range = if is_closing {
+        let search_start =
+            snapshot.clip_offset(tag_start.saturating_sub(MAX_TAG_SEARCH_LEN), Bias::Left);
+        let text = snapshot
+            .text_for_range(search_start..name_end)
+            .collect::<String>();
+        find_opening_tag(&text, tag_start - search_start)?
+            .name_range
+            .start
+            + search_start
+    } else {
+        let search_end = snapshot.clip_offset(
+            (name_end + MAX_TAG_SEARCH_LEN).min(snapshot.len()),
+            Bias::Right,
+        );
+        let text = snapshot
+            .text_for_range(tag_start..search_end)
+            .collect::<String>();
+        find_closing_tag(&text)?.name_range.start + tag_start
+    };
+
+    Some(
+        linked_name_range + (range.start - name_start)
+            ..linked_name_range + (range.end - name_start),
+    )
+}
+
+/// Parses the tag starting at `start`, which must be a `<`.
+fn parse_tag(text: &str, start: usize) -> Option<Tag> {
+    let rest = text[start..].strip_prefix('<')?;
+    let (is_closing, name_start) = match rest.strip_prefix('/') {
+        Some(_) => (true, start + 2),
+        None => (false, start + 1),
+    };
+    let name_len = text[name_start..]
+        .find(|c: char| !is_tag_name_char(c))
+        .unwrap_or(text.len() - name_start);
+    let name = &text[name_start..name_start + name_len];
+    let following_char = text[name_start + name_len..].chars().next();
+    if name.is_empty() {
+        // Only fragments may have empty names.
+        if following_char != Some('>') {
+            return None;
+        }
+    } else if !name.starts_with(char::is_alphabetic)
+        || following_char.map_or(false, |c| !c.is_whitespace() && c != '>' && c != '/')
+    {
+        return None;
+    }
+
+    let is_self_closing = !is_closing
+        && tag_end(text, name_start + name_len)
+            .map_or(false, |end| text[..end].trim_end().ends_with('/'));
+    Some(Tag {
+        name_range: name_start..name_start + name_len,
+        is_closing,
+        is_self_closing,
+    })
+}
+
+/// Returns the offset of the `>` ending the tag whose attributes start at `start`, skipping
+/// over quoted strings and braced expressions.
+fn tag_end(text: &str, start: usize) -> Option<usize> {
+    let mut quote = None;
+    let mut brace_depth = 0_usize;
+    for (ix, c) in text[start..].char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'' | '`') => quote = Some(c),
+            (None, '{') => brace_depth += 1,
+            (None, '}') => brace_depth = brace_depth.saturating_sub(1),
+            (None, '>') if brace_depth == 0 => return Some(start + ix),
+            (None, '<') if brace_depth == 0 => return None,
+            _ => {}
+        }
+    }
+    None
+}
+
+fn tags(text: &str) -> impl Iterator<Item = Tag> + '_ {
+    text.match_indices('<')
+        .filter_map(move |(ix, _)| parse_tag(text, ix))
+}
+
+/// Finds the tag closing the opening tag at the start of `text`.
+fn find_closing_tag(text: &str) -> Option<Tag> {
+    let opening_tag = parse_tag(text, 0)?;
+    let name = &text[opening_tag.name_range.clone()];
+    if opening_tag.is_closing || opening_tag.is_self_closing {
+        return None;
+    }
+
+    let mut depth = 0_usize;
+    for tag in tags(text).skip(1) {
+        if &text[tag.name_range.clone()] != name || tag.is_self_closing {
+            continue;
+        }
+        if !tag.is_closing {
+            depth += 1;
+        } else if depth == 0 {
+            return Some(tag);
+        } else {
+            depth -= 1;
+        }
+    }
+    None
+}
+
+/// Finds the tag opened by the closing tag at `closing_tag_start`, which must lie at the end
+/// of `text`.
+fn find_opening_tag(text: &str, closing_tag_start: usize) -> Option<Tag> {
+    let closing_tag = parse_tag(text, closing_tag_start)?;
+    let name = &text[closing_tag.name_range.clone()];
+    if !closing_tag.is_closing {
+        return None;
+    }
+
+    let mut open_tags = Vec::new();
+    for tag in tags(&text[..closing_tag_start]) {
+        if &text[tag.name_range.clone()] != name || tag.is_self_closing {
+            continue;
+        }
+        if tag.is_closing {
+            open_tags.pop();
+        } else {
+            open_tags.push(tag);
+        }
+    }
+    open_tags.pop()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_matching_tags() {
+        let text = "<div a=\"<b>\"><div/><div>x</div><p></p></div>";
+        let closing_tag = find_closing_tag(text).unwrap();
+        assert_eq!(closing_tag.name_range, 40..43);
+
+        let opening_tag = find_opening_tag(text, 38).unwrap();
+        assert_eq!(opening_tag.name_range, 1..4);
+
+        let fragment = "<><a></a></>";
+        assert_eq!(find_closing_tag(fragment).unwrap().name_range, 11..11);
+    }
+}
@@ -15,6 +15,20 @@ define_connection!(
     //   scroll_vertical_offset: f32,
     //   scroll_horizontal_offset: f32,
     // )
+    //
+    // editor_file_states(
+    //   workspace_id: usize,
+    //   path: PathBuf,
+    //   state: String, // JSON-encoded selections, folds and scroll position
+    // )
+    //
+    // edited_files(
+    //   workspace_id: usize,
+    //   path: PathBuf,
+    //   row: u32,
+    //   column: u32,
+    //   edited_at: i64, // Unix timestamp of the last edit
+    // )
     pub static ref DB: EditorDb<WorkspaceDb> =
         &[sql! (
             CREATE TABLE editors(
@@ -31,6 +45,30 @@ define_connection!(
             ALTER TABLE editors ADD COLUMN scroll_top_row INTEGER NOT NULL DEFAULT 0;
             ALTER TABLE editors ADD COLUMN scroll_horizontal_offset REAL NOT NULL DEFAULT 0;
             ALTER TABLE editors ADD COLUMN scroll_vertical_offset REAL NOT NULL DEFAULT 0;
+        ),
+        sql! (
+            CREATE TABLE editor_file_states(
+                workspace_id INTEGER NOT NULL,
+                path BLOB NOT NULL,
+                state TEXT NOT NULL,
+                PRIMARY KEY(workspace_id, path),
+                FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+                ON DELETE CASCADE
+                ON UPDATE CASCADE
+            ) STRICT;
+        ),
+        sql! (
+            CREATE TABLE edited_files(
+                workspace_id INTEGER NOT NULL,
+                path BLOB NOT NULL,
+                row INTEGER NOT NULL,
+                column INTEGER NOT NULL,
+                edited_at INTEGER NOT NULL,
+                PRIMARY KEY(workspace_id, path),
+                FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+                ON DELETE CASCADE
+                ON UPDATE CASCADE
+            ) STRICT;
         )];
 );
 
@@ -80,4 +118,46 @@ impl EditorDb {
             WHERE item_id = ?1 AND workspace_id = ?2
         }
     }
+
+    // Returns the state of the last editor for the given file
+    query! {
+        pub async fn get_file_state(workspace_id: WorkspaceId, path: PathBuf) -> Result<Option<String>> {
+            SELECT state FROM editor_file_states
+            WHERE workspace_id = ? AND path = ?
+        }
+    }
+
+    query! {
+        pub async fn save_file_state(workspace_id: WorkspaceId, path: PathBuf, state: String) -> Result<()> {
+            INSERT OR REPLACE INTO editor_file_states
+                (workspace_id, path, state)
+            VALUES
+                (?1, ?2, ?3)
+        }
+    }
+
+    // Returns the most recently edited files, with where and when they were last edited
+    query! {
+        pub fn get_edited_files(workspace_id: WorkspaceId, limit: usize) -> Result<Vec<(PathBuf, u32, u32, i64)>> {
+            SELECT path, row, column, edited_at FROM edited_files
+            WHERE workspace_id = ?
+            ORDER BY edited_at DESC
+            LIMIT ?
+        }
+    }
+
+    query! {
+        pub async fn save_edited_file(
+            workspace_id: WorkspaceId,
+            path: PathBuf,
+            row: u32,
+            column: u32,
+            edited_at: i64
+        ) -> Result<()> {
+            INSERT OR REPLACE INTO edited_files
+                (workspace_id, path, row, column, edited_at)
+            VALUES
+                (?1, ?2, ?3, ?4, ?5)
+        }
+    }
 }
@@ -0,0 +1,102 @@
+use crate::{
+    display_map::{BlockContext, BlockDisposition, BlockId, BlockProperties, BlockStyle},
+    scroll::Autoscroll,
+    Editor,
+};
+use gpui::{
+    div, AnyElement, AnyView, FocusHandle, InteractiveElement, IntoElement, ParentElement, Pixels,
+    Styled, ViewContext, WindowContext,
+};
+use multi_buffer::Anchor;
+
+/// A view embedded in an editor between its lines, such as the output of running code or a
+/// review comment. The block grows and shrinks with the view's contents, in whole lines, and
+/// follows its anchor as the buffer is edited.
+pub struct ViewBlock {
+    pub position: Anchor,
+    pub disposition: BlockDisposition,
+    pub style: BlockStyle,
+    pub view: AnyView,
+    /// Focused by [`Editor::focus_view_block`]. Focusable elements within the view also take
+    /// focus when clicked, without moving the editor's cursor.
+    pub focus_handle: Option<FocusHandle>,
+}
+
+pub(crate) struct ViewBlockState {
+    focus_handle: Option<FocusHandle>,
+}
+
+impl Editor {
+    pub fn insert_view_blocks(
+        &mut self,
+        blocks: impl IntoIterator<Item = ViewBlock>,
+        autoscroll: Option<Autoscroll>,
+        cx: &mut ViewContext<Self>,
+    ) -> Vec<BlockId> {
+        let mut focus_handles = Vec::new();
+        let blocks = blocks
+            .into_iter()
+            .map(|block| {
+                focus_handles.push(block.focus_handle);
+                let view = block.view;
+                BlockProperties {
+                    position: block.position,
+                    // Corrected when the view is first laid out.
+                    height: 1,
+                    style: block.style,
+                    disposition: block.disposition,
+                    render: Box::new(move |cx| render_view_block(&view, cx)),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let ids = self.insert_blocks(blocks, autoscroll, cx);
+        for (id, focus_handle) in ids.iter().zip(focus_handles) {
+            self.view_blocks
+                .insert(*id, ViewBlockState { focus_handle });
+        }
+        ids
+    }
+
+    /// Moves focus into the given view block. Returns false if it isn't a view block or its
+    /// view has no focus handle.
+    pub fn focus_view_block(&self, block_id: BlockId, cx: &mut WindowContext) -> bool {
+        let Some(focus_handle) = self
+            .view_blocks
+            .get(&block_id)
+            .and_then(|block| block.focus_handle.as_ref())
+        else {
+            return false;
+        };
+        cx.focus(focus_handle);
+        true
+    }
+
+    /// The view block containing the focused element, if any.
+    pub fn focused_view_block(&self, cx: &WindowContext) -> Option<BlockId> {
+        self.view_blocks.iter().find_map(|(id, block)| {
+            let focus_handle = block.focus_handle.as_ref()?;
+            focus_handle.contains_focused(cx).then_some(*id)
+        })
+    }
+
+    pub(crate) fn is_view_block(&self, block_id: BlockId) -> bool {
+        self.view_blocks.contains_key(&block_id)
+    }
+}
+
+fn render_view_block(view: &AnyView, cx: &mut BlockContext) -> AnyElement {
+    div()
+        .pl(cx.gutter_dimensions.width + cx.gutter_dimensions.margin)
+        // Clicks within the view shouldn't reach the editor and move its cursor.
+        .occlude()
+        .child(view.clone())
+        .into_any_element()
+}
+
+/// The number of lines needed to fit a view block's contents.
+pub(crate) fn view_block_height(content_height: Pixels, line_height: Pixels) -> u8 {
+    (content_height / line_height)
+        .ceil()
+        .clamp(1., u8::MAX as f32) as u8
+}
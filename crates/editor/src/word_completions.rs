@@ -0,0 +1,169 @@
+use crate::{Editor, EditorSettings};
+use collections::{HashMap, VecDeque};
+use gpui::{AppContext, Global, Model, Task, ViewContext};
+use language::{char_kind, Buffer, BufferSnapshot, CharKind, CodeLabel, Documentation, ToOffset};
+use lsp::LanguageServerId;
+use project::Completion;
+use settings::Settings;
+
+/// Stands in for a language server id on completions harvested from open buffers, which
+/// distinguishes them from the completions of real servers.
+pub(crate) const WORD_COMPLETION_SERVER_ID: LanguageServerId = LanguageServerId(usize::MAX);
+
+/// Shorter words are quicker to type than to pick from a menu.
+const MIN_WORD_LEN: usize = 3;
+/// Buffers larger than this aren't scanned for words, other than the one being edited.
+const MAX_SCANNED_BUFFER_LEN: usize = 1024 * 1024;
+const MAX_RECENT_WORDS: usize = 64;
+
+/// Words most recently inserted from word completions, newest first, which are offered before
+/// any other words.
+#[derive(Default)]
+struct RecentWordCompletions(VecDeque<String>);
+
+impl Global for RecentWordCompletions {}
+
+/// How highly a word is ranked, compared in order. Lower is better.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum WordRank {
+    /// Its position among the recently completed words.
+    Recent(usize),
+    /// Its distance from the cursor in the buffer being edited.
+    CurrentBuffer(usize),
+    /// The position of the first other buffer containing it.
+    OtherBuffer(usize),
+}
+
+impl Editor {
+    /// Completions for the word before the cursor, made from the words of all open buffers.
+    /// They're used when the completion provider has nothing to offer, such as for plain text
+    /// or when no language server is running.
+    pub(crate) fn word_completions(
+        &self,
+        buffer: &Model<Buffer>,
+        position: text::Anchor,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Vec<Completion>> {
+        if !EditorSettings::get_global(cx).word_completions {
+            return Task::ready(Vec::new());
+        }
+
+        let snapshot = buffer.read(cx).snapshot();
+        let open_buffers = match &self.project {
+            Some(project) => project.read(cx).opened_buffers(),
+            None => self.buffer.read(cx).all_buffers().into_iter().collect(),
+        };
+        let other_snapshots = open_buffers
+            .into_iter()
+            .filter(|open_buffer| open_buffer != buffer)
+            .map(|open_buffer| open_buffer.read(cx).snapshot())
+            .filter(|snapshot| snapshot.len() <= MAX_SCANNED_BUFFER_LEN)
+            .collect::<Vec<_>>();
+        let recent_words = cx
+            .try_global::<RecentWordCompletions>()
+            .map(|recent| recent.0.iter().cloned().collect::<Vec<_>>())
+            .unwrap_or_default();
+
+        cx.background_executor().spawn(async move {
+            build_word_completions(&snapshot, position, &other_snapshots, &recent_words)
+        })
+    }
+}
+
+/// Remembers a confirmed word completion, so that it's ranked first the next time it matches.
+pub(crate) fn record_word_completion(word: &str, cx: &mut AppContext) {
+    let recent = &mut cx.default_global::<RecentWordCompletions>().0;
+    recent.retain(|recent_word| recent_word != word);
+    recent.push_front(word.to_string());
+    recent.truncate(MAX_RECENT_WORDS);
+}
+
+fn build_word_completions(
+    snapshot: &BufferSnapshot,
+    position: text::Anchor,
+    other_snapshots: &[BufferSnapshot],
+    recent_words: &[String],
+) -> Vec<Completion> {
+    let cursor = position.to_offset(snapshot);
+    let (word_range, kind) = snapshot.surrounding_word(cursor);
+    if kind != Some(CharKind::Word) || word_range.start == cursor {
+        return Vec::new();
+    }
+
+    let mut ranks = HashMap::<String, WordRank>::default();
+    for (range, word) in words(&snapshot.text()) {
+        // Don't offer the word that's being typed.
+        if range.start <= cursor && cursor <= range.end {
+            continue;
+        }
+        let distance = if range.start > cursor {
+            range.start - cursor
+        } else {
+            cursor - range.end
+        };
+        let rank = WordRank::CurrentBuffer(distance);
+        ranks
+            .entry(word.to_string())
+            .and_modify(|existing| *existing = (*existing).min(rank))
+            .or_insert(rank);
+    }
+    for (ix, other_snapshot) in other_snapshots.iter().enumerate() {
+        for (_, word) in words(&other_snapshot.text()) {
+            ranks
+                .entry(word.to_string())
+                .or_insert(WordRank::OtherBuffer(ix));
+        }
+    }
+    for (ix, word) in recent_words.iter().enumerate() {
+        if let Some(rank) = ranks.get_mut(word) {
+            *rank = WordRank::Recent(ix);
+        }
+    }
+
+    let mut ranked_words = ranks.into_iter().collect::<Vec<_>>();
+    ranked_words.sort_by(|(word_a, rank_a), (word_b, rank_b)| {
+        rank_a.cmp(rank_b).then_with(|| word_a.cmp(word_b))
+    });
+
+    let old_range = snapshot.anchor_before(word_range.start)..snapshot.anchor_after(cursor);
+    ranked_words
+        .into_iter()
+        .enumerate()
+        .map(|(ix, (word, _))| Completion {
+            old_range: old_range.clone(),
+            new_text: word.clone(),
+            label: CodeLabel::plain(word.clone(), None),
+            server_id: WORD_COMPLETION_SERVER_ID,
+            // There's nothing to resolve.
+            documentation: Some(Documentation::Undocumented),
+            lsp_completion: lsp::CompletionItem {
+                label: word,
+                kind: Some(lsp::CompletionItemKind::TEXT),
+                sort_text: Some(format!("{ix:08}")),
+                ..Default::default()
+            },
+        })
+        .collect()
+}
+
+/// The words of the text worth completing, with their byte ranges.
+fn words(text: &str) -> impl Iterator<Item = (std::ops::Range<usize>, &str)> {
+    let mut chars = text.char_indices().peekable();
+    std::iter::from_fn(move || loop {
+        let (start, _) = *chars.peek()?;
+        let mut end = start;
+        let mut len = 0;
+        while let Some((ix, c)) = chars.next_if(|(_, c)| char_kind(&None, *c) == CharKind::Word) {
+            end = ix + c.len_utf8();
+            len += 1;
+        }
+        if len == 0 {
+            chars.next();
+            continue;
+        }
+        let word = &text[start..end];
+        if len >= MIN_WORD_LEN && !word.chars().all(|c| c.is_numeric()) {
+            return Some((start..end, word));
+        }
+    })
+}
@@ -16,6 +16,10 @@ pub struct ExtensionSettings {
     pub auto_install_extensions: HashMap<Arc<str>, bool>,
     #[serde(default)]
     pub auto_update_extensions: HashMap<Arc<str>, bool>,
+    /// Whether to reload dev extensions when their languages' configs and queries, or their
+    /// compiled grammars, change, so that open buffers are highlighted with the changes.
+    #[serde(default)]
+    pub watch_dev_extensions: bool,
 }
 
 impl ExtensionSettings {
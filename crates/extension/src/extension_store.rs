@@ -13,7 +13,7 @@ use anyhow::{anyhow, bail, Context as _, Result};
 use async_compression::futures::bufread::GzipDecoder;
 use async_tar::Archive;
 use client::{telemetry::Telemetry, Client, ExtensionMetadata, GetExtensionsResponse};
-use collections::{btree_map, BTreeMap, HashSet};
+use collections::{btree_map, BTreeMap, HashMap, HashSet};
 use extension_builder::{CompileExtensionOptions, ExtensionBuilder};
 use fs::{Fs, RemoveOptions};
 use futures::{
@@ -36,7 +36,7 @@ use language::{
 use node_runtime::NodeRuntime;
 use semantic_version::SemanticVersion;
 use serde::{Deserialize, Serialize};
-use settings::Settings;
+use settings::{Settings, SettingsStore};
 use std::ops::RangeInclusive;
 use std::str::FromStr;
 use std::{
@@ -106,6 +106,8 @@ pub struct ExtensionStore {
     modified_extensions: HashSet<Arc<str>>,
     wasm_host: Arc<WasmHost>,
     wasm_extensions: Vec<(Arc<ExtensionManifest>, WasmExtension)>,
+    /// The watchers of the source directories of dev extensions, by extension id.
+    dev_extension_watchers: HashMap<Arc<str>, Task<()>>,
     tasks: Vec<Task<()>>,
 }
 
@@ -223,7 +225,7 @@ impl ExtensionStore {
             extension_index: Default::default(),
             installed_dir,
             index_path,
-            builder: Arc::new(ExtensionBuilder::new(build_dir)),
+            builder: Arc::new(ExtensionBuilder::new(http_client.clone(), build_dir)),
             outstanding_operations: Default::default(),
             modified_extensions: Default::default(),
             reload_complete_senders: Vec::new(),
@@ -242,6 +244,7 @@ impl ExtensionStore {
             language_registry,
             theme_registry,
             reload_tx,
+            dev_extension_watchers: HashMap::default(),
             tasks: Vec::new(),
         };
 
@@ -359,9 +362,57 @@ impl ExtensionStore {
             }
         }));
 
+        cx.observe_global::<SettingsStore>(|this, cx| this.watch_dev_extensions(cx))
+            .detach();
+
         this
     }
 
+    /// Watches the source directories of dev extensions while `watch_dev_extensions` is on, so
+    /// that changes to their languages' queries and configs, and to their compiled grammars,
+    /// are reloaded into open buffers.
+    fn watch_dev_extensions(&mut self, cx: &mut ModelContext<Self>) {
+        let dev_extension_ids = if ExtensionSettings::get_global(cx).watch_dev_extensions {
+            self.dev_extensions()
+                .map(|manifest| manifest.id.clone())
+                .collect::<HashSet<_>>()
+        } else {
+            HashSet::default()
+        };
+        self.dev_extension_watchers
+            .retain(|extension_id, _| dev_extension_ids.contains(extension_id));
+
+        for extension_id in dev_extension_ids {
+            if self.dev_extension_watchers.contains_key(&extension_id) {
+                continue;
+            }
+            let fs = self.fs.clone();
+            let reload_tx = self.reload_tx.clone();
+            let extension_dir = self.installed_dir.join(extension_id.as_ref());
+            let watcher = cx.background_executor().spawn({
+                let extension_id = extension_id.clone();
+                async move {
+                    // Dev extensions are installed as links to their source directories, which
+                    // aren't watched along with the installed extensions.
+                    let Some(source_dir) = fs.canonicalize(&extension_dir).await.log_err() else {
+                        return;
+                    };
+                    let mut events = fs.watch(&source_dir, FS_WATCH_LATENCY).await;
+                    while let Some(paths) = events.next().await {
+                        let affects_languages = paths.iter().any(|path| {
+                            path.strip_prefix(&source_dir)
+                                .map_or(false, is_language_or_grammar_file)
+                        });
+                        if affects_languages {
+                            reload_tx.unbounded_send(Some(extension_id.clone())).ok();
+                        }
+                    }
+                }
+            });
+            self.dev_extension_watchers.insert(extension_id, watcher);
+        }
+    }
+
     fn reload(
         &mut self,
         modified_extension: Option<Arc<str>>,
@@ -1085,6 +1136,7 @@ impl ExtensionStore {
             .collect::<Vec<_>>();
 
         self.extension_index = new_index;
+        self.watch_dev_extensions(cx);
         cx.notify();
         cx.emit(Event::ExtensionsUpdated);
 
@@ -1344,3 +1396,16 @@ fn load_plugin_queries(root_path: &Path) -> LanguageQueries {
     }
     result
 }
+
+/// Whether the path, relative to an extension's directory, is of a language's config or queries,
+/// or of a compiled grammar.
+fn is_language_or_grammar_file(path: &Path) -> bool {
+    let extension = path.extension().and_then(|extension| extension.to_str());
+    match path.components().next() {
+        Some(path::Component::Normal(dir)) if dir == "languages" => {
+            extension == Some("scm") || path.file_name().map_or(false, |name| name == "config.toml")
+        }
+        Some(path::Component::Normal(dir)) if dir == "grammars" => extension == Some("wasm"),
+        _ => false,
+    }
+}
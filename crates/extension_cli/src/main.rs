@@ -58,7 +58,7 @@ async fn main() -> Result<()> {
     let mut manifest = ExtensionManifest::load(fs.clone(), &extension_path).await?;
 
     log::info!("compiling extension");
-    let builder = ExtensionBuilder::new(scratch_dir);
+    let builder = ExtensionBuilder::new(http::client(None, None), scratch_dir);
     builder
         .compile_extension(
             &extension_path,
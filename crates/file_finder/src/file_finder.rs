@@ -2,6 +2,7 @@
 mod file_finder_tests;
 
 mod new_path_prompt;
+mod recently_edited;
 
 use collections::{HashMap, HashSet};
 use editor::{scroll::Autoscroll, Bias, Editor};
@@ -15,6 +16,7 @@ use itertools::Itertools;
 use new_path_prompt::NewPathPrompt;
 use picker::{Picker, PickerDelegate};
 use project::{PathMatchCandidateSet, Project, ProjectPath, WorktreeId};
+use recently_edited::RecentlyEdited;
 use settings::Settings;
 use std::{
     cmp,
@@ -29,6 +31,8 @@ use ui::{prelude::*, HighlightedLabel, ListItem, ListItemSpacing};
 use util::{paths::PathLikeWithPosition, post_inc, ResultExt};
 use workspace::{item::PreviewTabsSettings, ModalView, Workspace};
 
+pub use recently_edited::ToggleRecentlyEdited;
+
 actions!(file_finder, [Toggle, SelectPrev]);
 
 impl ModalView for FileFinder {}
@@ -41,6 +45,7 @@ pub struct FileFinder {
 pub fn init(cx: &mut AppContext) {
     cx.observe_new_views(FileFinder::register).detach();
     cx.observe_new_views(NewPathPrompt::register).detach();
+    cx.observe_new_views(RecentlyEdited::register).detach();
 }
 
 impl FileFinder {
@@ -0,0 +1,255 @@
+use editor::{scroll::Autoscroll, Bias, Editor, RecentlyEditedFile};
+use fuzzy::{StringMatch, StringMatchCandidate};
+use gpui::{
+    actions, rems, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Render,
+    Task, View, ViewContext, VisualContext, WeakView,
+};
+use picker::{Picker, PickerDelegate};
+use std::sync::{atomic::AtomicBool, Arc};
+use time::OffsetDateTime;
+use time_format::TimestampFormat;
+use ui::{prelude::*, HighlightedLabel, ListItem, ListItemSpacing};
+use util::ResultExt;
+use workspace::{ModalView, Workspace};
+
+actions!(file_finder, [ToggleRecentlyEdited]);
+
+/// The most files that are listed, from the most recently edited one.
+const MAX_RECENTLY_EDITED_FILES: usize = 100;
+
+/// Lists the files of the workspace by when they were last edited, rather than opened, and
+/// jumps to where each of them was last edited.
+pub struct RecentlyEdited {
+    picker: View<Picker<RecentlyEditedDelegate>>,
+}
+
+impl ModalView for RecentlyEdited {}
+
+impl RecentlyEdited {
+    pub(crate) fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+        workspace.register_action(|workspace, _: &ToggleRecentlyEdited, cx| {
+            Self::open(workspace, cx);
+        });
+    }
+
+    fn open(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
+        let files =
+            editor::recently_edited_files(workspace.database_id(), MAX_RECENTLY_EDITED_FILES)
+                .log_err()
+                .unwrap_or_default();
+        let project = workspace.project().read(cx);
+        let entries = files
+            .into_iter()
+            .map(|file| {
+                let display_path = project
+                    .find_local_worktree(&file.abs_path, cx)
+                    .map(|(_, relative_path)| relative_path)
+                    .unwrap_or_else(|| file.abs_path.clone());
+                RecentlyEditedEntry {
+                    display_path: display_path.to_string_lossy().to_string(),
+                    file,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let weak_workspace = cx.view().downgrade();
+        workspace.toggle_modal(cx, |cx| {
+            let delegate =
+                RecentlyEditedDelegate::new(cx.view().downgrade(), weak_workspace, entries);
+            Self {
+                picker: cx.new_view(|cx| Picker::uniform_list(delegate, cx)),
+            }
+        });
+    }
+}
+
+impl EventEmitter<DismissEvent> for RecentlyEdited {}
+
+impl FocusableView for RecentlyEdited {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for RecentlyEdited {
+    fn render(&mut self, _: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("RecentlyEdited")
+            .w(rems(34.))
+            .child(self.picker.clone())
+    }
+}
+
+struct RecentlyEditedEntry {
+    file: RecentlyEditedFile,
+    /// The path of the file relative to its worktree, or its absolute path when it is outside
+    /// of the project.
+    display_path: String,
+}
+
+pub struct RecentlyEditedDelegate {
+    recently_edited: WeakView<RecentlyEdited>,
+    workspace: WeakView<Workspace>,
+    entries: Vec<RecentlyEditedEntry>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl RecentlyEditedDelegate {
+    fn new(
+        recently_edited: WeakView<RecentlyEdited>,
+        workspace: WeakView<Workspace>,
+        entries: Vec<RecentlyEditedEntry>,
+    ) -> Self {
+        Self {
+            recently_edited,
+            workspace,
+            entries,
+            matches: Vec::new(),
+            selected_index: 0,
+        }
+    }
+}
+
+impl PickerDelegate for RecentlyEditedDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _cx: &mut WindowContext) -> Arc<str> {
+        "Search recently edited files...".into()
+    }
+
+    fn no_matches_text(&self, _cx: &mut WindowContext) -> SharedString {
+        "No recently edited files".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(&mut self, query: String, cx: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        let candidates = self
+            .entries
+            .iter()
+            .enumerate()
+            .map(|(id, entry)| StringMatchCandidate::new(id, entry.display_path.clone()))
+            .collect::<Vec<_>>();
+        let executor = cx.background_executor().clone();
+        cx.spawn(move |picker, mut cx| async move {
+            let mut matches = fuzzy::match_strings(
+                &candidates,
+                &query,
+                false,
+                MAX_RECENTLY_EDITED_FILES,
+                &AtomicBool::default(),
+                executor,
+            )
+            .await;
+            // Keep the most recently edited files first, rather than the best matches.
+            matches.sort_unstable_by_key(|m| m.candidate_id);
+            picker
+                .update(&mut cx, |picker, cx| {
+                    let delegate = &mut picker.delegate;
+                    delegate.matches = matches;
+                    delegate.selected_index = 0;
+                    cx.notify();
+                })
+                .log_err();
+        })
+    }
+
+    fn confirm(&mut self, secondary: bool, cx: &mut ViewContext<Picker<Self>>) {
+        let Some(entry) = self
+            .matches
+            .get(self.selected_index)
+            .and_then(|m| self.entries.get(m.candidate_id))
+        else {
+            return;
+        };
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+
+        let abs_path = entry.file.abs_path.clone();
+        let position = entry.file.position;
+        let open_task = workspace.update(cx, |workspace, cx| {
+            if secondary {
+                workspace.split_abs_path(abs_path, true, cx)
+            } else {
+                workspace.open_abs_path(abs_path, true, cx)
+            }
+        });
+        let recently_edited = self.recently_edited.clone();
+        cx.spawn(|_, mut cx| async move {
+            let item = open_task.await.log_err()?;
+            if let Some(editor) = item.downcast::<Editor>() {
+                editor
+                    .update(&mut cx, |editor, cx| {
+                        let snapshot = editor.buffer().read(cx).snapshot(cx);
+                        let point = snapshot.clip_point(position, Bias::Left);
+                        editor.change_selections(Some(Autoscroll::center()), cx, |s| {
+                            s.select_ranges([point..point])
+                        });
+                    })
+                    .log_err();
+            }
+            recently_edited
+                .update(&mut cx, |_, cx| cx.emit(DismissEvent))
+                .ok()
+        })
+        .detach();
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        self.recently_edited
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        cx: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let string_match = self.matches.get(ix)?;
+        let entry = self.entries.get(string_match.candidate_id)?;
+        let edited_at = time_format::format_localized_timestamp(
+            entry.file.edited_at,
+            OffsetDateTime::now_utc(),
+            cx.local_timezone(),
+            TimestampFormat::Relative,
+        );
+
+        Some(
+            ListItem::new(ix)
+                .spacing(ListItemSpacing::Sparse)
+                .inset(true)
+                .selected(selected)
+                .child(
+                    h_flex()
+                        .gap_2()
+                        .child(HighlightedLabel::new(
+                            entry.display_path.clone(),
+                            string_match.positions.clone(),
+                        ))
+                        .child(
+                            Label::new(format!(":{}", entry.file.position.row + 1))
+                                .color(Color::Muted),
+                        ),
+                )
+                .end_slot(
+                    Label::new(edited_at)
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                ),
+        )
+    }
+}
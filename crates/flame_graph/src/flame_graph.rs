@@ -0,0 +1,58 @@
+mod flame_graph_view;
+mod profile;
+
+use gpui::{AppContext, Model, Task};
+use project::{Project, ProjectEntryId, ProjectPath};
+use std::path::PathBuf;
+
+pub use flame_graph_view::FlameGraphView;
+pub use profile::{Frame, Profile, SourceLocation};
+
+/// The suffixes of the files opened as flame graphs rather than in an editor.
+const PROFILE_SUFFIXES: &[&str] = &[".pprof", ".pb.gz", ".folded", ".collapsed", ".trace.json"];
+
+pub fn init(cx: &mut AppContext) {
+    workspace::register_project_item::<FlameGraphView>(cx);
+}
+
+pub struct ProfileItem {
+    abs_path: PathBuf,
+    project_path: ProjectPath,
+}
+
+impl project::Item for ProfileItem {
+    fn try_open(
+        project: &Model<Project>,
+        path: &ProjectPath,
+        cx: &mut AppContext,
+    ) -> Option<Task<gpui::Result<Model<Self>>>> {
+        let file_name = path.path.file_name()?.to_str()?;
+        if !PROFILE_SUFFIXES
+            .iter()
+            .any(|suffix| file_name.ends_with(suffix))
+        {
+            return None;
+        }
+
+        let path = path.clone();
+        let project = project.clone();
+        Some(cx.spawn(|mut cx| async move {
+            let abs_path = project
+                .read_with(&cx, |project, cx| project.absolute_path(&path, cx))?
+                .ok_or_else(|| anyhow::anyhow!("Failed to find the absolute path"))?;
+
+            cx.new_model(|_| ProfileItem {
+                abs_path,
+                project_path: path,
+            })
+        }))
+    }
+
+    fn entry_id(&self, _: &AppContext) -> Option<ProjectEntryId> {
+        None
+    }
+
+    fn project_path(&self, _: &AppContext) -> Option<ProjectPath> {
+        Some(self.project_path.clone())
+    }
+}
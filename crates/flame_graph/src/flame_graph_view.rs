@@ -0,0 +1,544 @@
+use crate::profile::{Profile, SourceLocation};
+use anyhow::Result;
+use async_compression::futures::bufread::GzipDecoder;
+use collections::HashMap;
+use editor::{scroll::Autoscroll, Editor, EditorEvent};
+use fs::Fs;
+use futures::AsyncReadExt as _;
+use gpui::{
+    hsla, AnyElement, AppContext, ClickEvent, EventEmitter, FocusHandle, FocusableView, Hsla,
+    Model, Subscription, Task, View, WeakView,
+};
+use language::Point;
+use project::{Project, ProjectPath};
+use std::{
+    hash::{Hash, Hasher},
+    io::Read as _,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use ui::{prelude::*, Tooltip};
+use util::ResultExt;
+use workspace::{
+    item::{Item, ProjectItem, TabContentParams},
+    Workspace,
+};
+
+const ROW_HEIGHT: Pixels = px(18.);
+
+/// Frames narrower than this fraction of the flame graph aren't drawn.
+const MIN_FRAME_WIDTH: f32 = 0.001;
+
+/// Frames narrower than this fraction of the flame graph aren't labeled.
+const MIN_LABELED_FRAME_WIDTH: f32 = 0.02;
+
+/// Shows a profile as an interactive flame graph, with the outermost frames at the top.
+/// Clicking a frame zooms into it, and double-clicking a frame whose function is defined
+/// in the project opens its source.
+pub struct FlameGraphView {
+    abs_path: PathBuf,
+    project: Model<Project>,
+    workspace: Option<WeakView<Workspace>>,
+    state: ProfileState,
+    zoomed_frame: usize,
+    /// The frames to draw, along with their offset and width relative to the flame graph.
+    visible_frames: Vec<(usize, f32, f32)>,
+    /// The project path of each frame's source, if it's defined in the project.
+    source_paths: Vec<Option<ProjectPath>>,
+    search_editor: View<Editor>,
+    matches: Vec<bool>,
+    matched_total: u64,
+    focus_handle: FocusHandle,
+    _load_profile: Task<()>,
+    _search_subscription: Subscription,
+}
+
+enum ProfileState {
+    Loading,
+    Loaded(Arc<Profile>),
+    Failed(SharedString),
+}
+
+impl FlameGraphView {
+    pub fn new(abs_path: PathBuf, project: Model<Project>, cx: &mut ViewContext<Self>) -> Self {
+        let fs = project.read(cx).fs().clone();
+        let load_profile = cx.spawn({
+            let abs_path = abs_path.clone();
+            |this, mut cx| async move {
+                let profile = cx
+                    .background_executor()
+                    .spawn(async move { load_profile(fs, &abs_path).await })
+                    .await;
+                this.update(&mut cx, |this, cx| {
+                    let state = match profile {
+                        Ok(profile) => ProfileState::Loaded(Arc::new(profile)),
+                        Err(error) => ProfileState::Failed(format!("{error:#}").into()),
+                    };
+                    this.set_state(state, cx);
+                })
+                .log_err();
+            }
+        });
+        Self::with_state(abs_path, project, ProfileState::Loading, load_profile, cx)
+    }
+
+    fn with_state(
+        abs_path: PathBuf,
+        project: Model<Project>,
+        state: ProfileState,
+        load_profile: Task<()>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let search_editor = cx.new_view(|cx| {
+            let mut editor = Editor::single_line(cx);
+            editor.set_placeholder_text("Search frames…", cx);
+            editor
+        });
+        let search_subscription =
+            cx.subscribe(&search_editor, |this, _, event: &EditorEvent, cx| {
+                if let EditorEvent::BufferEdited = event {
+                    this.update_matches(cx);
+                }
+            });
+
+        let mut this = Self {
+            abs_path,
+            project,
+            workspace: None,
+            state: ProfileState::Loading,
+            zoomed_frame: 0,
+            visible_frames: Vec::new(),
+            source_paths: Vec::new(),
+            search_editor,
+            matches: Vec::new(),
+            matched_total: 0,
+            focus_handle: cx.focus_handle(),
+            _load_profile: load_profile,
+            _search_subscription: search_subscription,
+        };
+        this.set_state(state, cx);
+        this
+    }
+
+    fn profile(&self) -> Option<&Arc<Profile>> {
+        match &self.state {
+            ProfileState::Loaded(profile) => Some(profile),
+            _ => None,
+        }
+    }
+
+    fn set_state(&mut self, state: ProfileState, cx: &mut ViewContext<Self>) {
+        self.state = state;
+        self.source_paths = match self.profile() {
+            Some(profile) => self.resolve_source_paths(profile, cx),
+            None => Vec::new(),
+        };
+        self.zoom(0, cx);
+        self.update_matches(cx);
+    }
+
+    fn resolve_source_paths(&self, profile: &Profile, cx: &AppContext) -> Vec<Option<ProjectPath>> {
+        let mut cache = HashMap::<&Path, Option<ProjectPath>>::default();
+        profile
+            .frames
+            .iter()
+            .map(|frame| {
+                let location = frame.location.as_ref()?;
+                cache
+                    .entry(location.path.as_path())
+                    .or_insert_with(|| self.resolve_source_path(location, cx))
+                    .clone()
+            })
+            .collect()
+    }
+
+    /// Finds the project file in which a frame's function is defined. Profilers report paths
+    /// that are either absolute or relative to the directory in which the program was built,
+    /// which is usually the root of a worktree.
+    fn resolve_source_path(
+        &self,
+        location: &SourceLocation,
+        cx: &AppContext,
+    ) -> Option<ProjectPath> {
+        self.project
+            .read(cx)
+            .visible_worktrees(cx)
+            .find_map(|worktree| {
+                let worktree = worktree.read(cx);
+                let path = if location.path.is_absolute() {
+                    location.path.strip_prefix(worktree.abs_path()).ok()?
+                } else {
+                    &location.path
+                };
+                worktree
+                    .entry_for_path(path)
+                    .filter(|entry| entry.is_file())
+                    .map(|entry| ProjectPath {
+                        worktree_id: worktree.id(),
+                        path: entry.path.clone(),
+                    })
+            })
+    }
+
+    fn zoom(&mut self, frame_ix: usize, cx: &mut ViewContext<Self>) {
+        self.visible_frames.clear();
+        self.zoomed_frame = 0;
+        let Some(profile) = self.profile().cloned() else {
+            cx.notify();
+            return;
+        };
+        let Some(zoomed) = profile.frames.get(frame_ix) else {
+            cx.notify();
+            return;
+        };
+        self.zoomed_frame = frame_ix;
+
+        // The zoomed frame's ancestors span the whole width.
+        let mut ancestor = zoomed.parent;
+        while let Some(ancestor_ix) = ancestor {
+            self.visible_frames.push((ancestor_ix, 0., 1.));
+            ancestor = profile.frames[ancestor_ix].parent;
+        }
+        self.visible_frames.reverse();
+
+        // Frames are in depth-first order, so the zoomed frame's descendants follow it.
+        let zoomed_total = zoomed.total.max(1) as f32;
+        for (ix, frame) in profile.frames.iter().enumerate().skip(frame_ix) {
+            if ix > frame_ix && frame.depth <= zoomed.depth {
+                break;
+            }
+            let width = frame.total as f32 / zoomed_total;
+            if width >= MIN_FRAME_WIDTH {
+                let left = (frame.start - zoomed.start) as f32 / zoomed_total;
+                self.visible_frames.push((ix, left, width));
+            }
+        }
+        cx.notify();
+    }
+
+    fn update_matches(&mut self, cx: &mut ViewContext<Self>) {
+        let query = self.search_editor.read(cx).text(cx);
+        let query = query.trim();
+        (self.matches, self.matched_total) = match self.profile() {
+            Some(profile) if !query.is_empty() => profile.search(query),
+            _ => (Vec::new(), 0),
+        };
+        cx.notify();
+    }
+
+    fn open_source(&mut self, frame_ix: usize, cx: &mut ViewContext<Self>) {
+        let Some(project_path) = self.source_paths.get(frame_ix).cloned().flatten() else {
+            return;
+        };
+        let row = self
+            .profile()
+            .and_then(|profile| profile.frames[frame_ix].location.as_ref()?.row);
+        let Some(workspace) = self
+            .workspace
+            .as_ref()
+            .and_then(|workspace| workspace.upgrade())
+        else {
+            return;
+        };
+
+        let open_task = workspace.update(cx, |workspace, cx| {
+            workspace.open_path(project_path, None, true, cx)
+        });
+        cx.spawn(|_, mut cx| async move {
+            let item = open_task.await?;
+            if let Some((editor, row)) = item.downcast::<Editor>().zip(row) {
+                editor.update(&mut cx, |editor, cx| {
+                    let point = Point::new(row, 0);
+                    editor.change_selections(Some(Autoscroll::center()), cx, |selections| {
+                        selections.select_ranges([point..point])
+                    });
+                })?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn render_frame(
+        &self,
+        profile: &Profile,
+        frame_ix: usize,
+        left: f32,
+        width: f32,
+        cx: &mut ViewContext<Self>,
+    ) -> impl IntoElement {
+        let frame = &profile.frames[frame_ix];
+        let searching = !self.matches.is_empty();
+        let matched = self.matches.get(frame_ix).copied().unwrap_or(false);
+        let mut color = if matched {
+            cx.theme().status().info
+        } else {
+            frame_color(&frame.name)
+        };
+        if searching && !matched {
+            color.fade_out(0.6);
+        }
+
+        let root_total = profile.root().total.max(1) as f64;
+        let mut meta = format!(
+            "{} {} ({:.2}%), {} self",
+            frame.total,
+            profile.unit,
+            frame.total as f64 / root_total * 100.,
+            frame.self_value,
+        );
+        if let Some(location) = &frame.location {
+            meta.push_str(&format!("\n{}", format_location(location)));
+            if self.source_paths[frame_ix].is_some() {
+                meta.push_str(" (double-click to open)");
+            }
+        }
+        let name = frame.name.clone();
+
+        div()
+            .id(frame_ix)
+            .absolute()
+            .top(ROW_HEIGHT * frame.depth as f32)
+            .left(relative(left))
+            .w(relative(width))
+            .h(ROW_HEIGHT)
+            .px_0p5()
+            .overflow_hidden()
+            .border_r_1()
+            .border_color(cx.theme().colors().editor_background)
+            .bg(color)
+            .when(frame_ix == self.zoomed_frame, |this| {
+                this.border_b_1()
+                    .border_color(cx.theme().colors().text_accent)
+            })
+            .when(width >= MIN_LABELED_FRAME_WIDTH, |this| {
+                this.child(
+                    Label::new(SharedString::from(name.to_string()))
+                        .size(LabelSize::Small)
+                        .color(Color::Custom(gpui::black()))
+                        .single_line(),
+                )
+            })
+            .tooltip(move |cx| Tooltip::with_meta(name.to_string(), None, meta.clone(), cx))
+            .on_click(cx.listener(move |this, event: &ClickEvent, cx| {
+                if event.up.click_count >= 2 {
+                    this.open_source(frame_ix, cx);
+                } else {
+                    this.zoom(frame_ix, cx);
+                }
+            }))
+    }
+
+    fn render_header(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let summary = self.profile().map(|profile| {
+            let root_total = profile.root().total;
+            if !self.matches.is_empty() {
+                format!(
+                    "Matched {:.2}% of {}",
+                    self.matched_total as f64 / root_total.max(1) as f64 * 100.,
+                    profile.unit
+                )
+            } else {
+                format!("{} {}", root_total, profile.unit)
+            }
+        });
+
+        h_flex()
+            .p_2()
+            .gap_2()
+            .justify_between()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(
+                        div()
+                            .w_64()
+                            .px_2()
+                            .py_1()
+                            .rounded_md()
+                            .border_1()
+                            .border_color(cx.theme().colors().border)
+                            .child(self.search_editor.clone()),
+                    )
+                    .children(summary.map(|summary| Label::new(summary).color(Color::Muted))),
+            )
+            .child(
+                Button::new("reset-zoom", "Reset Zoom")
+                    .disabled(self.zoomed_frame == 0)
+                    .on_click(cx.listener(|this, _, cx| this.zoom(0, cx))),
+            )
+    }
+}
+
+async fn load_profile(fs: Arc<dyn Fs>, path: &Path) -> Result<Profile> {
+    let mut bytes = Vec::new();
+    fs.open_sync(path).await?.read_to_end(&mut bytes)?;
+    if bytes.starts_with(&[0x1f, 0x8b]) {
+        let mut decompressed = Vec::new();
+        GzipDecoder::new(bytes.as_slice())
+            .read_to_end(&mut decompressed)
+            .await?;
+        bytes = decompressed;
+    }
+    Profile::parse(&bytes)
+}
+
+/// Picks a warm color for a frame, which stays the same across profiles.
+fn frame_color(name: &str) -> Hsla {
+    let mut hasher = collections::FxHasher::default();
+    name.hash(&mut hasher);
+    let hash = hasher.finish();
+    let hue = (hash % 1000) as f32 / 1000. * 0.1;
+    let lightness = 0.55 + ((hash / 1000) % 100) as f32 / 100. * 0.15;
+    hsla(hue, 0.8, lightness, 1.)
+}
+
+fn format_location(location: &SourceLocation) -> String {
+    match location.row {
+        Some(row) => format!("{}:{}", location.path.display(), row + 1),
+        None => location.path.display().to_string(),
+    }
+}
+
+impl Render for FlameGraphView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let body = match &self.state {
+            ProfileState::Loading => v_flex()
+                .flex_1()
+                .items_center()
+                .justify_center()
+                .child(Label::new("Loading profile…").color(Color::Muted))
+                .into_any_element(),
+            ProfileState::Failed(error) => v_flex()
+                .flex_1()
+                .items_center()
+                .justify_center()
+                .child(Label::new(error.clone()).color(Color::Error))
+                .into_any_element(),
+            ProfileState::Loaded(profile) => {
+                let profile = profile.clone();
+                let depth = self
+                    .visible_frames
+                    .iter()
+                    .map(|(ix, _, _)| profile.frames[*ix].depth + 1)
+                    .max()
+                    .unwrap_or(0);
+                let frames = self
+                    .visible_frames
+                    .iter()
+                    .map(|(ix, left, width)| {
+                        self.render_frame(&profile, *ix, *left, *width, cx)
+                            .into_any_element()
+                    })
+                    .collect::<Vec<_>>();
+
+                div()
+                    .id("flame-graph")
+                    .flex_1()
+                    .overflow_y_scroll()
+                    .child(
+                        div()
+                            .relative()
+                            .w_full()
+                            .h(ROW_HEIGHT * depth as f32)
+                            .children(frames),
+                    )
+                    .into_any_element()
+            }
+        };
+
+        v_flex()
+            .size_full()
+            .track_focus(&self.focus_handle)
+            .bg(cx.theme().colors().editor_background)
+            .child(self.render_header(cx))
+            .child(body)
+    }
+}
+
+impl EventEmitter<()> for FlameGraphView {}
+
+impl FocusableView for FlameGraphView {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for FlameGraphView {
+    type Event = ();
+
+    fn tab_content(&self, params: TabContentParams, _: &WindowContext) -> AnyElement {
+        let title = self
+            .abs_path
+            .file_name()
+            .unwrap_or_else(|| self.abs_path.as_os_str())
+            .to_string_lossy()
+            .to_string();
+        Label::new(title)
+            .single_line()
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .italic(params.preview)
+            .into_any_element()
+    }
+
+    fn tab_tooltip_text(&self, _: &AppContext) -> Option<SharedString> {
+        Some(self.abs_path.to_string_lossy().to_string().into())
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("flame graph opened")
+    }
+
+    fn added_to_workspace(&mut self, workspace: &mut Workspace, _: &mut ViewContext<Self>) {
+        self.workspace = Some(workspace.weak_handle());
+    }
+
+    fn clone_on_split(
+        &self,
+        _: workspace::WorkspaceId,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<View<Self>>
+    where
+        Self: Sized,
+    {
+        let state = match &self.state {
+            ProfileState::Loaded(profile) => ProfileState::Loaded(profile.clone()),
+            ProfileState::Failed(error) => ProfileState::Failed(error.clone()),
+            ProfileState::Loading => {
+                return Some(
+                    cx.new_view(|cx| Self::new(self.abs_path.clone(), self.project.clone(), cx)),
+                )
+            }
+        };
+        Some(cx.new_view(|cx| {
+            Self::with_state(
+                self.abs_path.clone(),
+                self.project.clone(),
+                state,
+                Task::ready(()),
+                cx,
+            )
+        }))
+    }
+}
+
+impl ProjectItem for FlameGraphView {
+    type Item = crate::ProfileItem;
+
+    fn for_project_item(
+        project: Model<Project>,
+        item: Model<Self::Item>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self
+    where
+        Self: Sized,
+    {
+        Self::new(item.read(cx).abs_path.clone(), project, cx)
+    }
+}
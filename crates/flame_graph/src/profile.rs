@@ -0,0 +1,711 @@
+use anyhow::{Context as _, Result};
+use collections::HashMap;
+use serde::Deserialize;
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// A profile of the call stacks of a program, arranged as a flame graph.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Profile {
+    /// Every frame of the flame graph in depth-first order, starting with a root frame that
+    /// spans every sample. The children of each frame are ordered by name.
+    pub frames: Vec<Frame>,
+    /// What the values of the frames measure, such as `samples` or `nanoseconds`.
+    pub unit: String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Frame {
+    pub name: Arc<str>,
+    pub location: Option<SourceLocation>,
+    pub parent: Option<usize>,
+    pub depth: usize,
+    /// Where the frame starts, as an offset from the start of the root frame.
+    pub start: u64,
+    /// The total value of the samples whose stacks contain this frame.
+    pub total: u64,
+    /// The total value of the samples for which this frame was the innermost.
+    pub self_value: u64,
+}
+
+impl Frame {
+    pub fn end(&self) -> u64 {
+        self.start + self.total
+    }
+}
+
+/// Where a frame's function is defined, as reported by the profiler.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SourceLocation {
+    /// The path of the source file, which may be relative to the directory in which the
+    /// program was built.
+    pub path: PathBuf,
+    /// The zero-based row on which the function is defined, if known.
+    pub row: Option<u32>,
+}
+
+impl Profile {
+    /// Parses a collapsed stack, Chrome trace or uncompressed pprof profile.
+    pub fn parse(bytes: &[u8]) -> Result<Self> {
+        if let Ok(text) = std::str::from_utf8(bytes) {
+            let trimmed = text.trim_start();
+            if trimmed.starts_with('{') || trimmed.starts_with('[') {
+                return parse_chrome_trace(text);
+            }
+            if let Ok(profile) = parse_collapsed(text) {
+                return Ok(profile);
+            }
+        }
+        parse_pprof(bytes)
+    }
+
+    pub fn root(&self) -> &Frame {
+        &self.frames[0]
+    }
+
+    pub fn max_depth(&self) -> usize {
+        self.frames
+            .iter()
+            .map(|frame| frame.depth)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Whether the frame at `ix` is `ancestor_ix` or one of its descendants.
+    pub fn is_descendant(&self, ix: usize, ancestor_ix: usize) -> bool {
+        let mut current = Some(ix);
+        while let Some(ix) = current {
+            if ix == ancestor_ix {
+                return true;
+            }
+            current = self.frames[ix].parent;
+        }
+        false
+    }
+
+    /// Returns which frames' names contain the given query, ignoring case, along with the
+    /// total value of the samples that contain at least one of those frames.
+    pub fn search(&self, query: &str) -> (Vec<bool>, u64) {
+        let query = query.to_lowercase();
+        let matches = self
+            .frames
+            .iter()
+            .map(|frame| !query.is_empty() && frame.name.to_lowercase().contains(&query))
+            .collect::<Vec<_>>();
+
+        // Avoid counting the samples of matching frames nested within another match twice.
+        let mut matched_total = 0;
+        for (ix, frame) in self.frames.iter().enumerate() {
+            let mut ancestor = frame.parent;
+            let mut nested = false;
+            while let Some(ancestor_ix) = ancestor {
+                if matches[ancestor_ix] {
+                    nested = true;
+                    break;
+                }
+                ancestor = self.frames[ancestor_ix].parent;
+            }
+            if matches[ix] && !nested {
+                matched_total += frame.total;
+            }
+        }
+
+        (matches, matched_total)
+    }
+}
+
+/// Parses stacks collapsed into lines such as `main;parse;lex 42`, as produced by
+/// `stackcollapse-*` scripts, `cargo flamegraph` and `py-spy`.
+pub fn parse_collapsed(text: &str) -> Result<Profile> {
+    let mut builder = ProfileBuilder::default();
+    for (ix, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (stack, count) = line
+            .rsplit_once(' ')
+            .with_context(|| format!("missing sample count on line {}", ix + 1))?;
+        let count = count
+            .parse::<f64>()
+            .with_context(|| format!("invalid sample count on line {}", ix + 1))?;
+        builder.add_stack(
+            stack
+                .split(';')
+                .filter(|frame| !frame.is_empty())
+                .map(parse_collapsed_frame),
+            count.round() as u64,
+        );
+    }
+    Ok(builder.build("samples"))
+}
+
+/// Splits the location off of frames such as `parse (src/parser.py:42)`.
+fn parse_collapsed_frame(frame: &str) -> (Arc<str>, Option<SourceLocation>) {
+    if let Some((name, location)) = frame
+        .strip_suffix(')')
+        .and_then(|frame| frame.rsplit_once(" ("))
+    {
+        if let Some(location) = parse_location(location) {
+            return (name.into(), Some(location));
+        }
+    }
+    (frame.into(), None)
+}
+
+/// Parses a location such as `src/parser.rs`, `src/parser.rs:42` or `src/parser.rs:42:7`.
+fn parse_location(location: &str) -> Option<SourceLocation> {
+    let mut path = location;
+    let mut numbers = Vec::new();
+    while let Some((prefix, suffix)) = path.rsplit_once(':') {
+        match suffix.parse::<u32>() {
+            Ok(number) if numbers.len() < 2 => {
+                numbers.push(number);
+                path = prefix;
+            }
+            _ => break,
+        }
+    }
+
+    if path.is_empty() || Path::new(path).extension().is_none() {
+        return None;
+    }
+    Some(SourceLocation {
+        path: path.into(),
+        row: numbers.last().map(|line| line.saturating_sub(1)),
+    })
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ChromeTrace {
+    Object {
+        #[serde(rename = "traceEvents")]
+        trace_events: Vec<TraceEvent>,
+    },
+    Array(Vec<TraceEvent>),
+}
+
+#[derive(Deserialize)]
+struct TraceEvent {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    ph: String,
+    /// The time at which the event occurred, in microseconds.
+    #[serde(default)]
+    ts: f64,
+    /// The duration of complete events, in microseconds.
+    dur: Option<f64>,
+    #[serde(default)]
+    pid: serde_json::Value,
+    #[serde(default)]
+    tid: serde_json::Value,
+    #[serde(default)]
+    args: serde_json::Value,
+}
+
+struct Span {
+    name: Arc<str>,
+    location: Option<SourceLocation>,
+    start: f64,
+    end: f64,
+}
+
+/// Parses the JSON trace event format, as produced by `chrome://tracing`, Perfetto and
+/// `tracing-chrome`. Durations are measured in nanoseconds.
+pub fn parse_chrome_trace(json: &str) -> Result<Profile> {
+    let events = match serde_json::from_str(json).context("invalid Chrome trace")? {
+        ChromeTrace::Object { trace_events } => trace_events,
+        ChromeTrace::Array(events) => events,
+    };
+
+    let mut thread_names = HashMap::default();
+    let mut threads = Vec::<(String, Vec<Span>)>::new();
+    let mut open_spans = HashMap::<String, Vec<Span>>::default();
+    for event in events {
+        let thread = format!("{}:{}", event.pid, event.tid);
+        let location = event
+            .args
+            .get("src_file")
+            .and_then(|file| file.as_str())
+            .and_then(parse_location);
+        let span = |start: f64, end: f64| Span {
+            name: event.name.as_str().into(),
+            location: location.clone(),
+            start,
+            end,
+        };
+
+        let span = match event.ph.as_str() {
+            "X" => span(event.ts, event.ts + event.dur.unwrap_or(0.)),
+            "B" => {
+                open_spans
+                    .entry(thread)
+                    .or_default()
+                    .push(span(event.ts, event.ts));
+                continue;
+            }
+            "E" => {
+                let Some(mut span) = open_spans.get_mut(&thread).and_then(|spans| spans.pop())
+                else {
+                    continue;
+                };
+                span.end = event.ts;
+                span
+            }
+            "M" if event.name == "thread_name" => {
+                if let Some(name) = event.args.get("name").and_then(|name| name.as_str()) {
+                    thread_names.insert(thread, name.to_string());
+                }
+                continue;
+            }
+            _ => continue,
+        };
+
+        match threads.iter_mut().find(|(name, _)| *name == thread) {
+            Some((_, spans)) => spans.push(span),
+            None => threads.push((thread, vec![span])),
+        }
+    }
+
+    let mut builder = ProfileBuilder::default();
+    let include_thread_frames = threads.len() > 1;
+    for (thread, spans) in threads {
+        let thread_frame = include_thread_frames.then(|| {
+            let name = thread_names
+                .remove(&thread)
+                .unwrap_or_else(|| format!("Thread {}", thread));
+            (Arc::<str>::from(name), None)
+        });
+
+        builder.add_spans(thread_frame, spans);
+    }
+    Ok(builder.build("nanoseconds"))
+}
+
+fn micros_to_nanos(micros: f64) -> u64 {
+    (micros * 1000.).max(0.).round() as u64
+}
+
+/// The subset of pprof's `profile.proto` needed to build a flame graph.
+mod pprof {
+    use prost::Message;
+
+    #[derive(Message)]
+    pub struct Profile {
+        #[prost(message, repeated, tag = "1")]
+        pub sample_type: Vec<ValueType>,
+        #[prost(message, repeated, tag = "2")]
+        pub sample: Vec<Sample>,
+        #[prost(message, repeated, tag = "4")]
+        pub location: Vec<Location>,
+        #[prost(message, repeated, tag = "5")]
+        pub function: Vec<Function>,
+        #[prost(string, repeated, tag = "6")]
+        pub string_table: Vec<String>,
+    }
+
+    #[derive(Message)]
+    pub struct ValueType {
+        #[prost(int64, tag = "1")]
+        pub r#type: i64,
+        #[prost(int64, tag = "2")]
+        pub unit: i64,
+    }
+
+    #[derive(Message)]
+    pub struct Sample {
+        /// The stack of the sample, innermost frame first.
+        #[prost(uint64, repeated, tag = "1")]
+        pub location_id: Vec<u64>,
+        #[prost(int64, repeated, tag = "2")]
+        pub value: Vec<i64>,
+    }
+
+    #[derive(Message)]
+    pub struct Location {
+        #[prost(uint64, tag = "1")]
+        pub id: u64,
+        #[prost(uint64, tag = "3")]
+        pub address: u64,
+        /// The functions at this location, starting with the innermost inlined function.
+        #[prost(message, repeated, tag = "4")]
+        pub line: Vec<Line>,
+    }
+
+    #[derive(Message)]
+    pub struct Line {
+        #[prost(uint64, tag = "1")]
+        pub function_id: u64,
+        #[prost(int64, tag = "2")]
+        pub line: i64,
+    }
+
+    #[derive(Message)]
+    pub struct Function {
+        #[prost(uint64, tag = "1")]
+        pub id: u64,
+        #[prost(int64, tag = "2")]
+        pub name: i64,
+        #[prost(int64, tag = "4")]
+        pub filename: i64,
+        #[prost(int64, tag = "5")]
+        pub start_line: i64,
+    }
+}
+
+/// Parses an uncompressed pprof profile, using the last of its sample types as the value of
+/// each sample.
+pub fn parse_pprof(bytes: &[u8]) -> Result<Profile> {
+    use prost::Message as _;
+
+    let profile = pprof::Profile::decode(bytes).context("invalid pprof profile")?;
+    let string = |ix: i64| {
+        usize::try_from(ix)
+            .ok()
+            .and_then(|ix| profile.string_table.get(ix))
+            .map_or("", String::as_str)
+    };
+    let functions = profile
+        .function
+        .iter()
+        .map(|function| (function.id, function))
+        .collect::<HashMap<_, _>>();
+    let locations = profile
+        .location
+        .iter()
+        .map(|location| (location.id, location))
+        .collect::<HashMap<_, _>>();
+
+    let sample_type = profile
+        .sample_type
+        .last()
+        .context("pprof profile has no sample types")?;
+    let value_ix = profile.sample_type.len() - 1;
+    let unit = match string(sample_type.unit) {
+        "" | "count" => string(sample_type.r#type),
+        unit => unit,
+    };
+
+    let mut builder = ProfileBuilder::default();
+    for sample in &profile.sample {
+        let value = sample.value.get(value_ix).copied().unwrap_or(0);
+        if value <= 0 {
+            continue;
+        }
+
+        let mut stack = Vec::<FrameKey>::new();
+        for location in sample
+            .location_id
+            .iter()
+            .rev()
+            .filter_map(|id| locations.get(id))
+        {
+            if location.line.is_empty() {
+                stack.push((format!("{:#x}", location.address).into(), None));
+            }
+            for line in location.line.iter().rev() {
+                let function = functions.get(&line.function_id);
+                let name = function.map_or("", |function| string(function.name));
+                let location = function
+                    .map(|function| string(function.filename))
+                    .filter(|path| !path.is_empty())
+                    .map(|path| SourceLocation {
+                        path: path.into(),
+                        row: u32::try_from(line.line - 1).ok(),
+                    });
+                stack.push((if name.is_empty() { "?" } else { name }.into(), location));
+            }
+        }
+        builder.add_stack(stack, value as u64);
+    }
+    Ok(builder.build(if unit.is_empty() { "samples" } else { unit }))
+}
+
+type FrameKey = (Arc<str>, Option<SourceLocation>);
+
+/// Merges stacks into a tree, which is then flattened into the frames of a flame graph.
+struct ProfileBuilder {
+    nodes: Vec<Node>,
+}
+
+struct Node {
+    key: FrameKey,
+    children: HashMap<FrameKey, usize>,
+    total: u64,
+    self_value: u64,
+}
+
+impl Node {
+    fn new(key: FrameKey) -> Self {
+        Self {
+            key,
+            children: HashMap::default(),
+            total: 0,
+            self_value: 0,
+        }
+    }
+}
+
+impl Default for ProfileBuilder {
+    fn default() -> Self {
+        Self {
+            nodes: vec![Node::new(("all".into(), None))],
+        }
+    }
+}
+
+impl ProfileBuilder {
+    /// Adds a sample with the given stack, starting with the outermost frame.
+    fn add_stack(&mut self, stack: impl IntoIterator<Item = FrameKey>, value: u64) {
+        if value == 0 {
+            return;
+        }
+
+        let mut node_ix = 0;
+        self.nodes[node_ix].total += value;
+        for key in stack {
+            node_ix = match self.nodes[node_ix].children.get(&key) {
+                Some(child_ix) => *child_ix,
+                None => {
+                    let child_ix = self.nodes.len();
+                    self.nodes.push(Node::new(key.clone()));
+                    self.nodes[node_ix].children.insert(key, child_ix);
+                    child_ix
+                }
+            };
+            self.nodes[node_ix].total += value;
+        }
+        self.nodes[node_ix].self_value += value;
+    }
+
+    /// Adds the spans recorded on a single thread, attributing to each span the time that
+    /// wasn't spent in any of the spans nested within it.
+    fn add_spans(&mut self, thread_frame: Option<FrameKey>, mut spans: Vec<Span>) {
+        // Order parents before their children, then walk the spans keeping track of the
+        // ones enclosing the current span.
+        spans.sort_by(|a, b| a.start.total_cmp(&b.start).then(b.end.total_cmp(&a.end)));
+        let mut stack = Vec::<(Span, f64)>::new();
+        for mut span in spans.into_iter().map(Some).chain([None]) {
+            while let Some((top, _)) = stack.last() {
+                if span.as_ref().map_or(false, |span| span.start < top.end) {
+                    break;
+                }
+
+                let (top, children_duration) = stack.pop().unwrap();
+                let duration = top.end - top.start;
+                let frames = thread_frame.iter().cloned().chain(
+                    stack
+                        .iter()
+                        .map(|(span, _)| span)
+                        .chain([&top])
+                        .map(|span| (span.name.clone(), span.location.clone())),
+                );
+                self.add_stack(frames, micros_to_nanos(duration - children_duration));
+                if let Some((_, parent_children_duration)) = stack.last_mut() {
+                    *parent_children_duration += duration;
+                }
+            }
+
+            if let Some(mut span) = span.take() {
+                if let Some((parent, _)) = stack.last() {
+                    span.end = span.end.min(parent.end);
+                }
+                stack.push((span, 0.));
+            }
+        }
+    }
+
+    fn build(self, unit: &str) -> Profile {
+        let mut frames = Vec::with_capacity(self.nodes.len());
+        let mut stack = vec![(0, None, 0, 0)];
+        while let Some((node_ix, parent, depth, start)) = stack.pop() {
+            let node = &self.nodes[node_ix];
+            let frame_ix = frames.len();
+            frames.push(Frame {
+                name: node.key.0.clone(),
+                location: node.key.1.clone(),
+                parent,
+                depth,
+                start,
+                total: node.total,
+                self_value: node.self_value,
+            });
+
+            let mut children = node.children.values().copied().collect::<Vec<_>>();
+            children.sort_by(|a, b| self.nodes[*a].key.0.cmp(&self.nodes[*b].key.0));
+            let mut child_start = start;
+            let children = children
+                .into_iter()
+                .map(|child_ix| {
+                    let entry = (child_ix, Some(frame_ix), depth + 1, child_start);
+                    child_start += self.nodes[child_ix].total;
+                    entry
+                })
+                .collect::<Vec<_>>();
+            stack.extend(children.into_iter().rev());
+        }
+
+        Profile {
+            frames,
+            unit: unit.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    fn summarize(profile: &Profile) -> Vec<(usize, &str, u64, u64, u64)> {
+        profile
+            .frames
+            .iter()
+            .map(|frame| {
+                (
+                    frame.depth,
+                    frame.name.as_ref(),
+                    frame.start,
+                    frame.total,
+                    frame.self_value,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_collapsed() {
+        let profile = Profile::parse(
+            indoc! {"
+                main;parse (src/parser.py:42);lex 3
+                main;render 5
+                main;parse (src/parser.py:42) 2
+            "}
+            .as_bytes(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            summarize(&profile),
+            [
+                (0, "all", 0, 10, 0),
+                (1, "main", 0, 10, 0),
+                (2, "parse", 0, 5, 2),
+                (3, "lex", 0, 3, 3),
+                (2, "render", 5, 5, 5),
+            ]
+        );
+        assert_eq!(
+            profile.frames[2].location,
+            Some(SourceLocation {
+                path: "src/parser.py".into(),
+                row: Some(41),
+            })
+        );
+        assert_eq!(profile.frames[2].parent, Some(1));
+
+        let (matches, matched_total) = profile.search("R");
+        assert_eq!(matches, [false, false, true, false, true]);
+        assert_eq!(matched_total, 10);
+    }
+
+    #[test]
+    fn test_parse_chrome_trace() {
+        let profile = Profile::parse(
+            indoc! {r#"
+                {
+                    "traceEvents": [
+                        {"name": "thread_name", "ph": "M", "pid": 1, "tid": 2, "args": {"name": "worker"}},
+                        {"name": "main", "ph": "X", "ts": 0, "dur": 10, "pid": 1, "tid": 1},
+                        {"name": "parse", "ph": "X", "ts": 1, "dur": 4, "pid": 1, "tid": 1,
+                         "args": {"src_file": "src/parser.rs"}},
+                        {"name": "render", "ph": "B", "ts": 6, "pid": 1, "tid": 1},
+                        {"name": "render", "ph": "E", "ts": 8, "pid": 1, "tid": 1},
+                        {"name": "work", "ph": "X", "ts": 0, "dur": 3, "pid": 1, "tid": 2}
+                    ]
+                }
+            "#}
+            .as_bytes(),
+        )
+        .unwrap();
+
+        assert_eq!(profile.unit, "nanoseconds");
+        assert_eq!(
+            summarize(&profile),
+            [
+                (0, "all", 0, 13000, 0),
+                (1, "Thread 1:1", 0, 10000, 0),
+                (2, "main", 0, 10000, 4000),
+                (3, "parse", 0, 4000, 4000),
+                (3, "render", 4000, 2000, 2000),
+                (1, "worker", 10000, 3000, 0),
+                (2, "work", 10000, 3000, 3000),
+            ]
+        );
+        assert_eq!(
+            profile.frames[3].location,
+            Some(SourceLocation {
+                path: "src/parser.rs".into(),
+                row: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_pprof() {
+        use prost::Message as _;
+
+        let strings = ["", "cpu", "nanoseconds", "main", "parse", "main.go"];
+        let function = |id: u64, name: i64| pprof::Function {
+            id,
+            name,
+            filename: 5,
+            start_line: 1,
+        };
+        let location = |id: u64, function_id: u64, line: i64| pprof::Location {
+            id,
+            address: 0,
+            line: vec![pprof::Line { function_id, line }],
+        };
+        let bytes = pprof::Profile {
+            sample_type: vec![pprof::ValueType { r#type: 1, unit: 2 }],
+            sample: vec![
+                pprof::Sample {
+                    location_id: vec![2, 1],
+                    value: vec![300],
+                },
+                pprof::Sample {
+                    location_id: vec![1],
+                    value: vec![100],
+                },
+            ],
+            location: vec![location(1, 1, 10), location(2, 2, 20)],
+            function: vec![function(1, 3), function(2, 4)],
+            string_table: strings.iter().map(|string| string.to_string()).collect(),
+        }
+        .encode_to_vec();
+
+        let profile = Profile::parse(&bytes).unwrap();
+        assert_eq!(profile.unit, "nanoseconds");
+        assert_eq!(
+            summarize(&profile),
+            [
+                (0, "all", 0, 400, 0),
+                (1, "main", 0, 400, 100),
+                (2, "parse", 0, 300, 300),
+            ]
+        );
+        assert_eq!(
+            profile.frames[2].location,
+            Some(SourceLocation {
+                path: "main.go".into(),
+                row: Some(19),
+            })
+        );
+    }
+}
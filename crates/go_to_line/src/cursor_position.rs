@@ -1,27 +1,70 @@
-use editor::{Editor, ToPoint};
+use editor::{Editor, EditorEvent, ToPoint};
 use gpui::{AppContext, Subscription, View, WeakView};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use settings::{Settings, SettingsSources};
 use std::fmt::Write;
-use text::{Point, Selection};
+use text::{Point, Selection, TextSummary};
 use ui::{
-    div, Button, ButtonCommon, Clickable, FluentBuilder, IntoElement, LabelSize, ParentElement,
+    h_flex, Button, ButtonCommon, Clickable, FluentBuilder, IntoElement, LabelSize, ParentElement,
     Render, Tooltip, ViewContext,
 };
 use util::paths::FILE_ROW_COLUMN_DELIMITER;
 use workspace::{item::ItemHandle, StatusItemView, Workspace};
 
-#[derive(Copy, Clone, Default, PartialOrd, PartialEq)]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
 struct SelectionStats {
     lines: usize,
+    /// The combined length of the selections in each of the units they can be counted in.
     characters: usize,
+    bytes: usize,
+    utf16_code_units: usize,
     selections: usize,
 }
 
+impl SelectionStats {
+    fn length(&self, unit: LengthUnit) -> usize {
+        match unit {
+            LengthUnit::Characters => self.characters,
+            LengthUnit::Bytes => self.bytes,
+            LengthUnit::Utf16CodeUnits => self.utf16_code_units,
+        }
+    }
+}
+
+/// The unit in which the length of the selections is shown. Counting UTF-16 code units
+/// helps with debugging protocols such as LSP, whose offsets are in UTF-16 by default.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+enum LengthUnit {
+    #[default]
+    Characters,
+    Bytes,
+    Utf16CodeUnits,
+}
+
+impl LengthUnit {
+    fn next(self) -> Self {
+        match self {
+            Self::Characters => Self::Bytes,
+            Self::Bytes => Self::Utf16CodeUnits,
+            Self::Utf16CodeUnits => Self::Characters,
+        }
+    }
+
+    /// The unit's name, in its long and short forms.
+    fn names(self) -> (&'static str, &'static str) {
+        match self {
+            Self::Characters => ("character", "c"),
+            Self::Bytes => ("byte", "b"),
+            Self::Utf16CodeUnits => ("UTF-16 unit", "u"),
+        }
+    }
+}
+
 pub struct CursorPosition {
     position: Option<Point>,
     selected_count: SelectionStats,
+    length_unit: LengthUnit,
     workspace: WeakView<Workspace>,
     _observe_active_editor: Option<Subscription>,
 }
@@ -31,6 +74,7 @@ impl CursorPosition {
         Self {
             position: None,
             selected_count: Default::default(),
+            length_unit: LengthUnit::default(),
             workspace: workspace.weak_handle(),
             _observe_active_editor: None,
         }
@@ -44,7 +88,13 @@ impl CursorPosition {
         self.selected_count.selections = editor.selections.count();
         let mut last_selection: Option<Selection<usize>> = None;
         for selection in editor.selections.all::<usize>(cx) {
-            self.selected_count.characters += selection.end - selection.start;
+            if !selection.is_empty() {
+                let summary =
+                    buffer.text_summary_for_range::<TextSummary, _>(selection.start..selection.end);
+                self.selected_count.bytes += summary.len;
+                self.selected_count.utf16_code_units += summary.len_utf16.0;
+                self.selected_count.characters += summary.chars;
+            }
             if last_selection
                 .as_ref()
                 .map_or(true, |last_selection| selection.id > last_selection.id)
@@ -65,58 +115,61 @@ impl CursorPosition {
         cx.notify();
     }
 
-    fn write_position(&self, text: &mut String, cx: &AppContext) {
-        if self.selected_count
-            <= (SelectionStats {
-                selections: 1,
-                ..Default::default()
-            })
-        {
-            // Do not write out anything if we have just one empty selection.
-            return;
-        }
-        let SelectionStats {
-            lines,
-            characters,
-            selections,
-        } = self.selected_count;
+    fn selection_stats_text(&self, cx: &AppContext) -> Option<String> {
         let format = LineIndicatorFormat::get(None, cx);
-        let is_short_format = format == &LineIndicatorFormat::Short;
-        let lines = (lines > 1).then_some((lines, "line"));
-        let selections = (selections > 1).then_some((selections, "selection"));
-        let characters = (characters > 0).then_some((characters, "character"));
-        if (None, None, None) == (characters, selections, lines) {
-            // Nothing to display.
-            return;
-        }
-        write!(text, " (").unwrap();
-        let mut wrote_once = false;
-        for (count, name) in [selections, lines, characters].into_iter().flatten() {
-            if wrote_once {
-                write!(text, ", ").unwrap();
-            }
-            let name = if is_short_format { &name[..1] } else { &name };
-            let plural_suffix = if count > 1 && !is_short_format {
-                "s"
-            } else {
-                ""
-            };
-            write!(text, "{count} {name}{plural_suffix}").unwrap();
-            wrote_once = true;
+        format_selection_stats(&self.selected_count, self.length_unit, *format)
+    }
+}
+
+/// Describes the selections, such as "(2 selections, 3 lines, 14 characters)". Returns `None`
+/// for a single empty selection.
+fn format_selection_stats(
+    stats: &SelectionStats,
+    length_unit: LengthUnit,
+    format: LineIndicatorFormat,
+) -> Option<String> {
+    let is_short_format = format == LineIndicatorFormat::Short;
+    let lines = (stats.lines > 1).then_some((stats.lines, ("line", "l")));
+    let selections = (stats.selections > 1).then_some((stats.selections, ("selection", "s")));
+    let length = stats.length(length_unit);
+    let length = (length > 0).then_some((length, length_unit.names()));
+    if (None, None, None) == (length, selections, lines) {
+        // Nothing to display.
+        return None;
+    }
+
+    let mut text = String::from("(");
+    let mut wrote_once = false;
+    for (count, (long_name, short_name)) in [selections, lines, length].into_iter().flatten() {
+        if wrote_once {
+            write!(text, ", ").unwrap();
         }
-        text.push(')');
+        let name = if is_short_format {
+            short_name
+        } else {
+            long_name
+        };
+        let plural_suffix = if count > 1 && !is_short_format {
+            "s"
+        } else {
+            ""
+        };
+        write!(text, "{count} {name}{plural_suffix}").unwrap();
+        wrote_once = true;
     }
+    text.push(')');
+    Some(text)
 }
 
 impl Render for CursorPosition {
     fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
-        div().when_some(self.position, |el, position| {
-            let mut text = format!(
+        let selection_stats = self.selection_stats_text(cx);
+        h_flex().when_some(self.position, |el, position| {
+            let text = format!(
                 "{}{FILE_ROW_COLUMN_DELIMITER}{}",
                 position.row + 1,
                 position.column + 1
             );
-            self.write_position(&mut text, cx);
 
             el.child(
                 Button::new("go-to-line-column", text)
@@ -136,6 +189,20 @@ impl Render for CursorPosition {
                     }))
                     .tooltip(|cx| Tooltip::for_action("Go to Line/Column", &crate::Toggle, cx)),
             )
+            .when_some(selection_stats, |el, selection_stats| {
+                let next_unit = self.length_unit.next().names().0;
+                el.child(
+                    Button::new("selection-stats", selection_stats)
+                        .label_size(LabelSize::Small)
+                        .on_click(cx.listener(|this, _, cx| {
+                            this.length_unit = this.length_unit.next();
+                            cx.notify();
+                        }))
+                        .tooltip(move |cx| {
+                            Tooltip::text(format!("Click to count {next_unit}s"), cx)
+                        }),
+                )
+            })
         })
     }
 }
@@ -147,7 +214,17 @@ impl StatusItemView for CursorPosition {
         cx: &mut ViewContext<Self>,
     ) {
         if let Some(editor) = active_pane_item.and_then(|item| item.act_as::<Editor>(cx)) {
-            self._observe_active_editor = Some(cx.observe(&editor, Self::update_position));
+            // Only update on the events that change the stats, rather than every time the
+            // editor is notified, since counting characters is linear in the selections' size.
+            self._observe_active_editor = Some(cx.subscribe(
+                &editor,
+                |this, editor, event: &EditorEvent, cx| match event {
+                    EditorEvent::SelectionsChanged { .. } | EditorEvent::BufferEdited => {
+                        this.update_position(editor, cx)
+                    }
+                    _ => {}
+                },
+            ));
             self.update_position(editor, cx);
         } else {
             self.position = None;
@@ -158,7 +235,7 @@ impl StatusItemView for CursorPosition {
     }
 }
 
-#[derive(Clone, Copy, Default, PartialEq, JsonSchema, Deserialize, Serialize)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, JsonSchema, Deserialize, Serialize)]
 #[serde(rename_all = "snake_case")]
 pub(crate) enum LineIndicatorFormat {
     Short,
@@ -191,3 +268,107 @@ impl Settings for LineIndicatorFormat {
         Ok(format.0)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{TestAppContext, VisualContext};
+    use project::{FakeFs, Project};
+    use workspace::AppState;
+
+    #[gpui::test]
+    async fn test_update_position_counts(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            AppState::test(cx);
+            language::init(cx);
+            editor::init(cx);
+            workspace::init_settings(cx);
+            Project::init_settings(cx);
+        });
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+        let cursor_position = workspace.update(cx, |workspace, cx| {
+            cx.new_view(|_| CursorPosition::new(workspace))
+        });
+
+        // Select "é😀\n" and "y".
+        let editor = cx.new_view(|cx| {
+            let mut editor = Editor::multi_line(cx);
+            editor.set_text("é😀\nxy", cx);
+            editor.change_selections(None, cx, |s| s.select_ranges([0..7, 8..9]));
+            editor
+        });
+        cursor_position.update(cx, |cursor_position, cx| {
+            cursor_position.update_position(editor, cx);
+            assert_eq!(
+                cursor_position.selected_count,
+                SelectionStats {
+                    lines: 2,
+                    characters: 4,
+                    bytes: 8,
+                    utf16_code_units: 5,
+                    selections: 2,
+                }
+            );
+        });
+    }
+
+    #[test]
+    fn test_format_selection_stats() {
+        let single_cursor = SelectionStats {
+            selections: 1,
+            ..Default::default()
+        };
+        assert_eq!(
+            format_selection_stats(
+                &single_cursor,
+                LengthUnit::Characters,
+                LineIndicatorFormat::Long
+            ),
+            None
+        );
+
+        // Two selections of "é😀\n" and "x".
+        let stats = SelectionStats {
+            lines: 2,
+            characters: 4,
+            bytes: 8,
+            utf16_code_units: 5,
+            selections: 2,
+        };
+        assert_eq!(
+            format_selection_stats(&stats, LengthUnit::Characters, LineIndicatorFormat::Long)
+                .as_deref(),
+            Some("(2 selections, 2 lines, 4 characters)")
+        );
+        assert_eq!(
+            format_selection_stats(&stats, LengthUnit::Bytes, LineIndicatorFormat::Long).as_deref(),
+            Some("(2 selections, 2 lines, 8 bytes)")
+        );
+        assert_eq!(
+            format_selection_stats(
+                &stats,
+                LengthUnit::Utf16CodeUnits,
+                LineIndicatorFormat::Short
+            )
+            .as_deref(),
+            Some("(2 s, 2 l, 5 u)")
+        );
+        assert_eq!(
+            format_selection_stats(
+                &SelectionStats {
+                    lines: 1,
+                    characters: 1,
+                    bytes: 1,
+                    utf16_code_units: 1,
+                    selections: 1,
+                },
+                LengthUnit::Characters,
+                LineIndicatorFormat::Long
+            )
+            .as_deref(),
+            Some("(1 character)")
+        );
+    }
+}
@@ -115,7 +115,7 @@ impl App {
         Self(AppContext::new(
             current_platform(),
             Arc::new(()),
-            http::client(None),
+            http::client(None, None),
         ))
     }
 
@@ -197,6 +197,7 @@ impl App {
 type Handler = Box<dyn FnMut(&mut AppContext) -> bool + 'static>;
 type Listener = Box<dyn FnMut(&dyn Any, &mut AppContext) -> bool + 'static>;
 type KeystrokeObserver = Box<dyn FnMut(&KeystrokeEvent, &mut WindowContext) + 'static>;
+type ActionObserver = Box<dyn FnMut(&dyn Action, &mut WindowContext) + 'static>;
 type QuitHandler = Box<dyn FnOnce(&mut AppContext) -> LocalBoxFuture<'static, ()> + 'static>;
 type ReleaseListener = Box<dyn FnOnce(&mut dyn Any, &mut AppContext) + 'static>;
 type NewViewListener = Box<dyn FnMut(AnyView, &mut WindowContext) + 'static>;
@@ -213,6 +214,8 @@ pub struct AppContext {
     pending_updates: usize,
     pub(crate) actions: Rc<ActionRegistry>,
     pub(crate) active_drag: Option<AnyDrag>,
+    /// The window that the active drag was last over, which is the only one that draws it.
+    pub(crate) drag_window: Option<AnyWindowHandle>,
     pub(crate) background_executor: BackgroundExecutor,
     pub(crate) foreground_executor: ForegroundExecutor,
     pub(crate) loading_assets: FxHashMap<(TypeId, u64), Box<dyn Any>>,
@@ -235,6 +238,7 @@ pub struct AppContext {
     // TypeId is the type of the event that the listener callback expects
     pub(crate) event_listeners: SubscriberSet<EntityId, (TypeId, Listener)>,
     pub(crate) keystroke_observers: SubscriberSet<(), KeystrokeObserver>,
+    pub(crate) action_observers: SubscriberSet<(), ActionObserver>,
     pub(crate) release_listeners: SubscriberSet<EntityId, ReleaseListener>,
     pub(crate) global_observers: SubscriberSet<TypeId, Handler>,
     pub(crate) quit_observers: SubscriberSet<(), QuitHandler>,
@@ -276,6 +280,7 @@ impl AppContext {
                 flushing_effects: false,
                 pending_updates: 0,
                 active_drag: None,
+                drag_window: None,
                 background_executor: executor,
                 foreground_executor,
                 svg_renderer: SvgRenderer::new(asset_source.clone()),
@@ -297,6 +302,7 @@ impl AppContext {
                 event_listeners: SubscriberSet::new(),
                 release_listeners: SubscriberSet::new(),
                 keystroke_observers: SubscriberSet::new(),
+                action_observers: SubscriberSet::new(),
                 global_observers: SubscriberSet::new(),
                 quit_observers: SubscriberSet::new(),
                 layout_id_buffer: Default::default(),
@@ -482,23 +488,36 @@ impl AppContext {
         self.platform.active_window()
     }
 
+    /// Returns the app's windows ordered by how they're stacked on screen, from front to back, or
+    /// `None` if the platform doesn't report their order.
+    pub fn window_stack(&self) -> Option<Vec<AnyWindowHandle>> {
+        self.platform.window_stack()
+    }
+
     /// Opens a new window with the given option and the root view returned by the given function.
     /// The function is invoked with a `WindowContext`, which can be used to interact with window-specific
-    /// functionality.
+    /// functionality. Fails if the platform can't create the window, such as when the GPU can't
+    /// be initialized.
     pub fn open_window<V: 'static + Render>(
         &mut self,
         options: crate::WindowOptions,
         build_root_view: impl FnOnce(&mut WindowContext) -> View<V>,
-    ) -> WindowHandle<V> {
+    ) -> Result<WindowHandle<V>> {
         self.update(|cx| {
             let id = cx.windows.insert(None);
             let handle = WindowHandle::new(id);
-            let mut window = Window::new(handle.into(), options, cx);
+            let mut window = match Window::new(handle.into(), options, cx) {
+                Ok(window) => window,
+                Err(error) => {
+                    cx.windows.remove(id);
+                    return Err(error);
+                }
+            };
             let root_view = build_root_view(&mut WindowContext::new(cx, &mut window));
             window.root_view.replace(root_view.into());
             cx.window_handles.insert(id, window.handle);
             cx.windows.get_mut(id).unwrap().replace(window);
-            handle
+            Ok(handle)
         })
     }
 
@@ -1049,6 +1068,18 @@ impl AppContext {
         inner(&mut self.keystroke_observers, Box::new(f))
     }
 
+    /// Register a callback to be invoked when an action is dispatched with
+    /// [`WindowContext::dispatch_action`] in any window, as when a button is clicked or a command
+    /// is picked from a menu, rather than by a keystroke.
+    pub fn observe_dispatched_actions(
+        &mut self,
+        f: impl FnMut(&dyn Action, &mut WindowContext) + 'static,
+    ) -> Subscription {
+        let (subscription, activate) = self.action_observers.insert((), Box::new(f));
+        activate();
+        subscription
+    }
+
     /// Register key bindings.
     pub fn bind_keys(&mut self, bindings: impl IntoIterator<Item = KeyBinding>) {
         self.keymap.borrow_mut().add_bindings(bindings);
@@ -1450,6 +1481,7 @@ pub struct KeystrokeEvent {
     /// The keystroke that occurred
     pub keystroke: Keystroke,
 
-    /// The action that was resolved for the keystroke, if any
+    /// The action that was resolved for the keystroke, if any. Keystrokes that begin a
+    /// multi-keystroke binding are observed without an action while it is pending.
     pub action: Option<Box<dyn Action>>,
 }
@@ -132,7 +132,9 @@ impl<E: IntoElement + 'static> Element for AnimationElement<E> {
             let element = self.element.take().expect("should only be called once");
             let mut element = (self.animator)(element, delta).into_any_element();
 
-            if !done {
+            // Repeating animations are paused while the window is inactive, and resume when it's
+            // activated, which refreshes it.
+            if !done && (self.animation.oneshot || cx.is_window_active()) {
                 let parent_id = cx.parent_view_id();
                 cx.on_next_frame(move |cx| {
                     if let Some(parent_id) = parent_id {
@@ -440,8 +440,10 @@ impl PlatformInput {
 mod test {
 
     use crate::{
-        self as gpui, div, FocusHandle, InteractiveElement, IntoElement, KeyBinding, Keystroke,
-        ParentElement, Render, TestAppContext, VisualContext,
+        self as gpui, div, point, px, size, Bounds, DevicePixels, Empty, FocusHandle,
+        InteractiveElement, IntoElement, KeyBinding, Keystroke, Modifiers, MouseButton,
+        ParentElement, Render, Styled, TestAppContext, VisualContext, VisualTestContext,
+        WindowBounds, WindowHandle, WindowOptions,
     };
 
     struct TestView {
@@ -486,6 +488,7 @@ mod test {
                     focus_handle: cx.focus_handle(),
                 })
             })
+            .unwrap()
         });
 
         cx.update(|cx| {
@@ -507,4 +510,98 @@ mod test {
             })
             .unwrap();
     }
+
+    #[derive(Clone)]
+    struct Dragged;
+
+    impl Render for Dragged {
+        fn render(&mut self, _: &mut gpui::ViewContext<Self>) -> impl IntoElement {
+            Empty
+        }
+    }
+
+    struct DragView {
+        drops: usize,
+    }
+
+    impl Render for DragView {
+        fn render(&mut self, cx: &mut gpui::ViewContext<Self>) -> impl IntoElement {
+            div()
+                .id("drag-view")
+                .size_full()
+                .on_drag(Dragged, |dragged, cx| cx.new_view(|_| dragged.clone()))
+                .on_drop(cx.listener(|this, _: &Dragged, _| this.drops += 1))
+        }
+    }
+
+    #[gpui::test]
+    fn test_drop_onto_other_window(cx: &mut TestAppContext) {
+        let mut open_window = |x, width| {
+            cx.update(|cx| {
+                let bounds = Bounds::new(
+                    point(DevicePixels(x), DevicePixels(0)),
+                    size(DevicePixels(width), DevicePixels(width)),
+                );
+                cx.open_window(
+                    WindowOptions {
+                        window_bounds: Some(WindowBounds::Windowed(bounds)),
+                        ..Default::default()
+                    },
+                    |cx| cx.new_view(|_| DragView { drops: 0 }),
+                )
+                .unwrap()
+            })
+        };
+        let source = open_window(0, 200);
+        // Two windows that overlap where the drag is dropped, with the second one in front.
+        let back = open_window(250, 600);
+        let front = open_window(260, 600);
+        cx.run_until_parked();
+
+        let drops = |window: WindowHandle<DragView>, cx: &mut TestAppContext| {
+            window.update(cx, |view, _| view.drops).unwrap()
+        };
+        let drag_to = |position, cx: &mut TestAppContext| {
+            let mut cx = VisualTestContext::from_window(*source, cx);
+            cx.simulate_mouse_down(
+                point(px(10.), px(10.)),
+                MouseButton::Left,
+                Modifiers::none(),
+            );
+            cx.simulate_mouse_move(
+                point(px(20.), px(20.)),
+                MouseButton::Left,
+                Modifiers::none(),
+            );
+            assert!(cx.update(|cx| cx.has_active_drag()));
+            cx.simulate_mouse_move(position, MouseButton::Left, Modifiers::none());
+            cx.simulate_mouse_up(position, MouseButton::Left, Modifiers::none());
+        };
+
+        // A drop outside of the source window goes to the frontmost window under the cursor.
+        drag_to(point(px(300.), px(50.)), cx);
+        assert_eq!(drops(front, cx), 1);
+        assert_eq!(drops(back, cx), 0);
+        assert_eq!(drops(source, cx), 0);
+        cx.update(|cx| {
+            assert!(!cx.has_active_drag());
+            assert!(cx.drag_window.is_none());
+        });
+
+        back.update(cx, |_, cx| cx.activate_window()).unwrap();
+        cx.run_until_parked();
+        drag_to(point(px(300.), px(50.)), cx);
+        assert_eq!(drops(front, cx), 1);
+        assert_eq!(drops(back, cx), 1);
+
+        // A drop where there's no window cancels the drag.
+        drag_to(point(px(150.), px(400.)), cx);
+        assert_eq!(drops(front, cx), 1);
+        assert_eq!(drops(back, cx), 1);
+        assert_eq!(drops(source, cx), 0);
+        cx.update(|cx| {
+            assert!(!cx.has_active_drag());
+            assert!(cx.drag_window.is_none());
+        });
+    }
 }
@@ -17,6 +17,8 @@ mod mac;
 
 #[cfg(any(target_os = "linux", target_os = "windows", feature = "macos-blade"))]
 mod blade;
+#[cfg(target_os = "linux")]
+pub use blade::use_software_rendering;
 
 #[cfg(any(test, feature = "test-support"))]
 mod test;
@@ -92,6 +94,15 @@ pub(crate) fn current_platform() -> Rc<dyn Platform> {
     Rc::new(WindowsPlatform::new())
 }
 
+/// Renders windows with a software rasterizer rather than the GPU, which is only supported on
+/// Linux.
+#[cfg(not(target_os = "linux"))]
+pub fn use_software_rendering() -> Result<()> {
+    Err(anyhow::anyhow!(
+        "software rendering is only supported on Linux"
+    ))
+}
+
 pub(crate) trait Platform: 'static {
     fn background_executor(&self) -> BackgroundExecutor;
     fn foreground_executor(&self) -> ForegroundExecutor;
@@ -108,6 +119,11 @@ pub(crate) trait Platform: 'static {
     fn displays(&self) -> Vec<Rc<dyn PlatformDisplay>>;
     fn primary_display(&self) -> Option<Rc<dyn PlatformDisplay>>;
     fn active_window(&self) -> Option<AnyWindowHandle>;
+    /// The app's windows, ordered from front to back, or `None` if the platform doesn't report
+    /// their order.
+    fn window_stack(&self) -> Option<Vec<AnyWindowHandle>> {
+        None
+    }
     fn can_open_windows(&self) -> anyhow::Result<()> {
         Ok(())
     }
@@ -115,7 +131,7 @@ pub(crate) trait Platform: 'static {
         &self,
         handle: AnyWindowHandle,
         options: WindowParams,
-    ) -> Box<dyn PlatformWindow>;
+    ) -> anyhow::Result<Box<dyn PlatformWindow>>;
 
     /// Returns the appearance of the application's windows.
     fn window_appearance(&self) -> WindowAppearance;
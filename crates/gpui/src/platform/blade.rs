@@ -1,8 +1,10 @@
 mod blade_atlas;
 mod blade_belt;
+mod blade_context;
 mod blade_renderer;
 
 pub(crate) use blade_atlas::*;
+pub(crate) use blade_context::*;
 pub(crate) use blade_renderer::*;
 
 use blade_belt::*;
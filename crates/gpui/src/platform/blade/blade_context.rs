@@ -0,0 +1,116 @@
+use anyhow::{anyhow, Result};
+use blade_graphics as gpu;
+use raw_window_handle as rwh;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Whether windows are rendered with a software rasterizer rather than the GPU.
+static SOFTWARE_RENDERING: AtomicBool = AtomicBool::new(false);
+
+/// Creates the GPU context that a window is rendered with. Fails when the GPU's driver can't be
+/// initialized, as with broken drivers, VMs and remote X sessions, for which software rendering
+/// can be used instead.
+pub(crate) unsafe fn init_gpu_context<W>(window: &W) -> Result<gpu::Context>
+where
+    W: rwh::HasWindowHandle + rwh::HasDisplayHandle,
+{
+    let desc = gpu::ContextDesc {
+        validation: false,
+        capture: false,
+        overlay: false,
+    };
+    gpu::Context::init_windowed(window, desc).map_err(|error| {
+        if SOFTWARE_RENDERING.load(Ordering::SeqCst) {
+            anyhow!("failed to initialize software rendering: {error:?}")
+        } else {
+            anyhow!("failed to initialize the GPU: {error:?}")
+        }
+    })
+}
+
+/// The directories that Vulkan drivers are installed in, with a JSON manifest for each.
+#[cfg(target_os = "linux")]
+const VULKAN_DRIVER_DIRS: &[&str] = &[
+    "/usr/share/vulkan/icd.d",
+    "/usr/local/share/vulkan/icd.d",
+    "/etc/vulkan/icd.d",
+];
+
+/// Renders windows with Mesa's software Vulkan driver, lavapipe, rather than the GPU, for
+/// machines whose GPU drivers are broken. The Vulkan loader is limited to the driver through the
+/// environment, so this must be called before any threads are spawned, and processes that are
+/// spawned later inherit it.
+#[cfg(target_os = "linux")]
+pub fn use_software_rendering() -> Result<()> {
+    use anyhow::Context as _;
+    use std::{env, path::Path};
+
+    let manifest = software_vulkan_driver_manifest(VULKAN_DRIVER_DIRS.iter().map(Path::new))
+        .context("no software Vulkan driver is installed (Mesa's lavapipe)")?;
+    log::info!("rendering with the software Vulkan driver at {manifest:?}");
+
+    // Newer loaders read the first variable, and older ones the second.
+    for variable in ["VK_DRIVER_FILES", "VK_ICD_FILENAMES"] {
+        env::set_var(variable, &manifest);
+    }
+    SOFTWARE_RENDERING.store(true, Ordering::SeqCst);
+    Ok(())
+}
+
+/// The manifest of lavapipe in the first of the driver directories that has one.
+#[cfg(target_os = "linux")]
+fn software_vulkan_driver_manifest<'a>(
+    driver_dirs: impl IntoIterator<Item = &'a std::path::Path>,
+) -> Option<std::path::PathBuf> {
+    driver_dirs.into_iter().find_map(|dir| {
+        let mut manifests = std::fs::read_dir(dir)
+            .ok()?
+            .filter_map(|entry| Some(entry.ok()?.path()))
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with("lvp_icd") && name.ends_with(".json"))
+            })
+            .collect::<Vec<_>>();
+        manifests.sort();
+        manifests.into_iter().next()
+    })
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use util::test::temp_tree;
+
+    #[test]
+    fn test_software_vulkan_driver_manifest() {
+        let dir = temp_tree(json!({
+            "usr": {
+                "radeon_icd.x86_64.json": "{}",
+            },
+            "local": {
+                "lvp_icd.x86_64.json": "{}",
+                "lvp_icd.i686.json": "{}",
+                "lvp_icd.x86_64.json.bak": "{}",
+            },
+            "etc": {
+                "lvp_icd.json": "{}",
+            },
+        }));
+        let root = dir.path();
+
+        assert_eq!(
+            software_vulkan_driver_manifest([
+                root.join("missing").as_path(),
+                root.join("usr").as_path(),
+                root.join("local").as_path(),
+                root.join("etc").as_path(),
+            ]),
+            Some(root.join("local/lvp_icd.i686.json"))
+        );
+        assert_eq!(
+            software_vulkan_driver_manifest([root.join("usr").as_path()]),
+            None
+        );
+    }
+}
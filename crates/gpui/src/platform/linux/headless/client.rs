@@ -76,8 +76,10 @@ impl LinuxClient for HeadlessClient {
         &self,
         _handle: AnyWindowHandle,
         params: WindowParams,
-    ) -> Box<dyn PlatformWindow> {
-        unimplemented!()
+    ) -> anyhow::Result<Box<dyn PlatformWindow>> {
+        Err(anyhow::anyhow!(
+            "neither DISPLAY, nor WAYLAND_DISPLAY found, so windows can't be opened"
+        ))
     }
 
     fn set_cursor_style(&self, _style: CursorStyle) {}
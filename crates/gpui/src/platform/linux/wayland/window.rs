@@ -22,7 +22,7 @@ use wayland_protocols::xdg::shell::client::xdg_surface;
 use wayland_protocols::xdg::shell::client::xdg_toplevel::{self, WmCapabilities};
 use wayland_protocols_plasma::blur::client::{org_kde_kwin_blur, org_kde_kwin_blur_manager};
 
-use crate::platform::blade::{BladeRenderer, BladeSurfaceConfig};
+use crate::platform::blade::{init_gpu_context, BladeRenderer, BladeSurfaceConfig};
 use crate::platform::linux::wayland::display::WaylandDisplay;
 use crate::platform::linux::wayland::serial::SerialKind;
 use crate::platform::{PlatformAtlas, PlatformInputHandler, PlatformWindow};
@@ -104,7 +104,7 @@ impl WaylandWindowState {
         client: WaylandClientStatePtr,
         globals: Globals,
         options: WindowParams,
-    ) -> Self {
+    ) -> anyhow::Result<Self> {
         let bounds = options.bounds.map(|p| p.0 as u32);
 
         let raw = RawWindow {
@@ -116,19 +116,21 @@ impl WaylandWindowState {
                 .display_ptr()
                 .cast::<c_void>(),
         };
-        let gpu = Arc::new(
-            unsafe {
-                gpu::Context::init_windowed(
-                    &raw,
-                    gpu::ContextDesc {
-                        validation: false,
-                        capture: false,
-                        overlay: false,
-                    },
-                )
+        let gpu = match unsafe { init_gpu_context(&raw) } {
+            Ok(gpu) => Arc::new(gpu),
+            Err(error) => {
+                if let Some(decoration) = &decoration {
+                    decoration.destroy();
+                }
+                toplevel.destroy();
+                if let Some(viewport) = &viewport {
+                    viewport.destroy();
+                }
+                xdg_surface.destroy();
+                surface.destroy();
+                return Err(error);
             }
-            .unwrap(),
-        );
+        };
         let config = BladeSurfaceConfig {
             size: gpu::Extent {
                 width: bounds.size.width,
@@ -138,7 +140,7 @@ impl WaylandWindowState {
             transparent: options.window_background != WindowBackgroundAppearance::Opaque,
         };
 
-        Self {
+        Ok(Self {
             xdg_surface,
             acknowledged_first_configure: false,
             surface,
@@ -158,7 +160,7 @@ impl WaylandWindowState {
             maximized: false,
             callbacks: Callbacks::default(),
             client,
-        }
+        })
     }
 }
 
@@ -215,7 +217,7 @@ impl WaylandWindow {
         globals: Globals,
         client: WaylandClientStatePtr,
         params: WindowParams,
-    ) -> (Self, ObjectId) {
+    ) -> anyhow::Result<(Self, ObjectId)> {
         let surface = globals.compositor.create_surface(&globals.qh, ());
         let xdg_surface = globals
             .wm_base
@@ -255,14 +257,14 @@ impl WaylandWindow {
                 client,
                 globals,
                 params,
-            ))),
+            )?)),
             callbacks: Rc::new(RefCell::new(Callbacks::default())),
         });
 
         // Kick things off
         surface.commit();
 
-        (this, surface.id())
+        Ok((this, surface.id()))
     }
 }
 
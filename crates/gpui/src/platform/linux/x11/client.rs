@@ -44,6 +44,7 @@ use crate::platform::linux::platform::DOUBLE_CLICK_INTERVAL;
 
 pub(crate) struct WindowRef {
     window: X11WindowStatePtr,
+    handle: AnyWindowHandle,
     refresh_event_token: RegistrationToken,
 }
 
@@ -756,11 +757,38 @@ impl LinuxClient for X11Client {
         )?))
     }
 
+    fn window_stack(&self) -> Option<Vec<AnyWindowHandle>> {
+        let state = self.0.borrow();
+        let root = state.xcb_connection.setup().roots[state.x_root_index].root;
+        let reply = state
+            .xcb_connection
+            .get_property(
+                false,
+                root,
+                state.atoms._NET_CLIENT_LIST_STACKING,
+                xproto::AtomEnum::WINDOW,
+                0,
+                u32::MAX,
+            )
+            .ok()?
+            .reply()
+            .ok()?;
+        // The window manager lists the windows from bottom to top.
+        let x_windows = reply.value32()?.collect::<Vec<_>>();
+        Some(
+            x_windows
+                .into_iter()
+                .rev()
+                .filter_map(|x_window| Some(state.windows.get(&x_window)?.handle))
+                .collect(),
+        )
+    }
+
     fn open_window(
         &self,
-        _handle: AnyWindowHandle,
+        handle: AnyWindowHandle,
         params: WindowParams,
-    ) -> Box<dyn PlatformWindow> {
+    ) -> anyhow::Result<Box<dyn PlatformWindow>> {
         let mut state = self.0.borrow_mut();
         let x_window = state.xcb_connection.generate_id().unwrap();
 
@@ -773,7 +801,7 @@ impl LinuxClient for X11Client {
             x_window,
             &state.atoms,
             state.scale_factor,
-        );
+        )?;
 
         let screen_resources = state
             .xcb_connection
@@ -837,11 +865,12 @@ impl LinuxClient for X11Client {
 
         let window_ref = WindowRef {
             window: window.0.clone(),
+            handle,
             refresh_event_token,
         };
 
         state.windows.insert(x_window, window_ref);
-        Box::new(window)
+        Ok(Box::new(window))
     }
 
     fn set_cursor_style(&self, style: CursorStyle) {
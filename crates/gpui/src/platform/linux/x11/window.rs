@@ -2,7 +2,7 @@
 #![allow(unused)]
 
 use crate::{
-    platform::blade::{BladeRenderer, BladeSurfaceConfig},
+    platform::blade::{init_gpu_context, BladeRenderer, BladeSurfaceConfig},
     size, Bounds, DevicePixels, ForegroundExecutor, Modifiers, Pixels, Platform, PlatformAtlas,
     PlatformDisplay, PlatformInput, PlatformInputHandler, PlatformWindow, Point, PromptLevel,
     Scene, Size, WindowAppearance, WindowBackgroundAppearance, WindowBounds, WindowOptions,
@@ -49,6 +49,7 @@ x11rb::atom_manager! {
         _NET_WM_STATE,
         _NET_WM_STATE_MAXIMIZED_VERT,
         _NET_WM_STATE_MAXIMIZED_HORZ,
+        _NET_CLIENT_LIST_STACKING,
     }
 }
 
@@ -213,7 +214,7 @@ impl X11WindowState {
         x_window: xproto::Window,
         atoms: &XcbAtoms,
         scale_factor: f32,
-    ) -> Self {
+    ) -> anyhow::Result<Self> {
         let x_screen_index = params
             .display_id
             .map_or(x_main_screen_index, |did| did.0 as usize);
@@ -333,19 +334,14 @@ impl X11WindowState {
             window_id: x_window,
             visual_id: visual.id,
         };
-        let gpu = Arc::new(
-            unsafe {
-                gpu::Context::init_windowed(
-                    &raw,
-                    gpu::ContextDesc {
-                        validation: false,
-                        capture: false,
-                        overlay: false,
-                    },
-                )
+        let gpu = match unsafe { init_gpu_context(&raw) } {
+            Ok(gpu) => Arc::new(gpu),
+            Err(error) => {
+                xcb_connection.destroy_window(x_window).log_err();
+                xcb_connection.flush().log_err();
+                return Err(error);
             }
-            .unwrap(),
-        );
+        };
 
         let config = BladeSurfaceConfig {
             // Note: this has to be done after the GPU init, or otherwise
@@ -354,7 +350,7 @@ impl X11WindowState {
             transparent: params.window_background != WindowBackgroundAppearance::Opaque,
         };
 
-        Self {
+        Ok(Self {
             client,
             executor,
             display: Rc::new(X11Display::new(xcb_connection, x_screen_index).unwrap()),
@@ -364,7 +360,7 @@ impl X11WindowState {
             renderer: BladeRenderer::new(gpu, config),
             atoms: *atoms,
             input_handler: None,
-        }
+        })
     }
 
     fn content_size(&self) -> Size<Pixels> {
@@ -414,8 +410,8 @@ impl X11Window {
         x_window: xproto::Window,
         atoms: &XcbAtoms,
         scale_factor: f32,
-    ) -> Self {
-        Self(X11WindowStatePtr {
+    ) -> anyhow::Result<Self> {
+        Ok(Self(X11WindowStatePtr {
             state: Rc::new(RefCell::new(X11WindowState::new(
                 client,
                 executor,
@@ -425,11 +421,11 @@ impl X11Window {
                 x_window,
                 atoms,
                 scale_factor,
-            ))),
+            )?)),
             callbacks: Rc::new(RefCell::new(Callbacks::default())),
             xcb_connection: xcb_connection.clone(),
             x_window,
-        })
+        }))
     }
 }
 
@@ -749,6 +749,22 @@ impl MacWindow {
             }
         }
     }
+
+    pub fn ordered_windows() -> Vec<AnyWindowHandle> {
+        unsafe {
+            let app = NSApplication::sharedApplication(nil);
+            let windows: id = msg_send![app, orderedWindows];
+            let count: NSUInteger = msg_send![windows, count];
+            let mut handles = Vec::new();
+            for i in 0..count {
+                let window: id = msg_send![windows, objectAtIndex: i];
+                if msg_send![window, isKindOfClass: WINDOW_CLASS] {
+                    handles.push(get_window_state(&*window).lock().handle);
+                }
+            }
+            handles
+        }
+    }
 }
 
 impl Drop for MacWindow {
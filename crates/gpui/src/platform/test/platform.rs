@@ -20,6 +20,9 @@ pub(crate) struct TestPlatform {
     foreground_executor: ForegroundExecutor,
 
     pub(crate) active_window: RefCell<Option<TestWindow>>,
+    /// The windows that have been opened, from front to back. A window comes to the front when
+    /// it's opened or activated.
+    window_stack: RefCell<Vec<AnyWindowHandle>>,
     active_display: Rc<dyn PlatformDisplay>,
     active_cursor: Mutex<CursorStyle>,
     current_clipboard_item: Mutex<Option<ClipboardItem>>,
@@ -45,6 +48,7 @@ impl TestPlatform {
             active_cursor: Default::default(),
             active_display: Rc::new(TestDisplay::new()),
             active_window: Default::default(),
+            window_stack: Default::default(),
             current_clipboard_item: Mutex::new(None),
             #[cfg(target_os = "linux")]
             current_primary_item: Mutex::new(None),
@@ -93,6 +97,9 @@ impl TestPlatform {
         let executor = self.foreground_executor().clone();
         let previous_window = self.active_window.borrow_mut().take();
         self.active_window.borrow_mut().clone_from(&window);
+        if let Some(window) = window.as_ref() {
+            self.bring_to_front(window.0.lock().handle);
+        }
 
         executor
             .spawn(async move {
@@ -111,6 +118,12 @@ impl TestPlatform {
             .detach();
     }
 
+    fn bring_to_front(&self, handle: AnyWindowHandle) {
+        let mut window_stack = self.window_stack.borrow_mut();
+        window_stack.retain(|window| *window != handle);
+        window_stack.insert(0, handle);
+    }
+
     pub(crate) fn did_prompt_for_new_path(&self) -> bool {
         self.prompts.borrow().new_path.len() > 0
     }
@@ -177,18 +190,23 @@ impl Platform for TestPlatform {
             .map(|window| window.0.lock().handle)
     }
 
+    fn window_stack(&self) -> Option<Vec<AnyWindowHandle>> {
+        Some(self.window_stack.borrow().clone())
+    }
+
     fn open_window(
         &self,
         handle: AnyWindowHandle,
         params: WindowParams,
-    ) -> Box<dyn crate::PlatformWindow> {
+    ) -> anyhow::Result<Box<dyn crate::PlatformWindow>> {
         let window = TestWindow::new(
             handle,
             params,
             self.weak.clone(),
             self.active_display.clone(),
         );
-        Box::new(window)
+        self.bring_to_front(handle);
+        Ok(Box::new(window))
     }
 
     fn window_appearance(&self) -> WindowAppearance {
@@ -283,11 +283,23 @@ impl Platform for WindowsPlatform {
             .map(|inner| inner.handle)
     }
 
+    fn window_stack(&self) -> Option<Vec<AnyWindowHandle>> {
+        let mut handles = Vec::new();
+        let mut hwnd = unsafe { GetTopWindow(HWND::default()) }.ok();
+        while let Some(current) = hwnd {
+            if let Some(inner) = self.try_get_windows_inner_from_hwnd(current) {
+                handles.push(inner.handle);
+            }
+            hwnd = unsafe { GetWindow(current, GW_HWNDNEXT) }.ok();
+        }
+        Some(handles)
+    }
+
     fn open_window(
         &self,
         handle: AnyWindowHandle,
         options: WindowParams,
-    ) -> Box<dyn PlatformWindow> {
+    ) -> Result<Box<dyn PlatformWindow>> {
         let lock = self.state.borrow();
         let window = WindowsWindow::new(
             handle,
@@ -297,10 +309,11 @@ impl Platform for WindowsPlatform {
             lock.current_cursor,
         );
         drop(lock);
+        let window = window?;
         let handle = window.get_raw_handle();
         self.raw_window_handles.write().push(handle);
 
-        Box::new(window)
+        Ok(Box::new(window))
     }
 
     // todo(windows)
@@ -66,7 +66,7 @@ impl WindowsWindowState {
         cs: &CREATESTRUCTW,
         current_cursor: HCURSOR,
         display: WindowsDisplay,
-    ) -> Self {
+    ) -> anyhow::Result<Self> {
         let origin = point(cs.x.into(), cs.y.into());
         let physical_size = size(cs.cx.into(), cs.cy.into());
         let fullscreen_restore_bounds = Bounds {
@@ -77,14 +77,14 @@ impl WindowsWindowState {
             let monitor_dpi = unsafe { GetDpiForWindow(hwnd) } as f32;
             monitor_dpi / USER_DEFAULT_SCREEN_DPI as f32
         };
-        let renderer = windows_renderer::windows_renderer(hwnd, transparent);
+        let renderer = windows_renderer::windows_renderer(hwnd, transparent)?;
         let callbacks = Callbacks::default();
         let input_handler = None;
         let click_state = ClickState::new();
         let system_settings = WindowsSystemSettings::new();
         let fullscreen = None;
 
-        Self {
+        Ok(Self {
             origin,
             physical_size,
             fullscreen_restore_bounds,
@@ -98,7 +98,7 @@ impl WindowsWindowState {
             display,
             fullscreen,
             hwnd,
-        }
+        })
     }
 
     #[inline]
@@ -190,22 +190,26 @@ impl WindowsWindowState {
 }
 
 impl WindowsWindowStatePtr {
-    fn new(context: &WindowCreateContext, hwnd: HWND, cs: &CREATESTRUCTW) -> Rc<Self> {
+    fn new(
+        context: &WindowCreateContext,
+        hwnd: HWND,
+        cs: &CREATESTRUCTW,
+    ) -> anyhow::Result<Rc<Self>> {
         let state = RefCell::new(WindowsWindowState::new(
             hwnd,
             context.transparent,
             cs,
             context.current_cursor,
             context.display,
-        ));
+        )?);
 
-        Rc::new(Self {
+        Ok(Rc::new(Self {
             state,
             hwnd,
             handle: context.handle,
             hide_title_bar: context.hide_title_bar,
             executor: context.executor.clone(),
-        })
+        }))
     }
 }
 
@@ -222,7 +226,8 @@ pub(crate) struct Callbacks {
 }
 
 struct WindowCreateContext {
-    inner: Option<Rc<WindowsWindowStatePtr>>,
+    /// The window's state, or why it couldn't be created, once it's been created.
+    inner: Option<anyhow::Result<Rc<WindowsWindowStatePtr>>>,
     handle: AnyWindowHandle,
     hide_title_bar: bool,
     display: WindowsDisplay,
@@ -238,7 +243,7 @@ impl WindowsWindow {
         icon: HICON,
         executor: ForegroundExecutor,
         current_cursor: HCURSOR,
-    ) -> Self {
+    ) -> anyhow::Result<Self> {
         let classname = register_wnd_class(icon);
         let hide_title_bar = params
             .titlebar
@@ -287,7 +292,10 @@ impl WindowsWindow {
                 lpparam,
             )
         };
-        let state_ptr = Rc::clone(context.inner.as_ref().unwrap());
+        let state_ptr = context
+            .inner
+            .take()
+            .context("the window wasn't created")??;
         register_drag_drop(state_ptr.clone());
         let wnd = Self(state_ptr);
 
@@ -311,7 +319,7 @@ impl WindowsWindow {
         }
         unsafe { ShowWindow(raw_hwnd, SW_SHOW).ok().log_err() };
 
-        wnd
+        Ok(wnd)
     }
 }
 
@@ -869,10 +877,17 @@ unsafe extern "system" fn wnd_proc(
         let cs = unsafe { &*cs };
         let ctx = cs.lpCreateParams as *mut WindowCreateContext;
         let ctx = unsafe { &mut *ctx };
-        let state_ptr = WindowsWindowStatePtr::new(ctx, hwnd, cs);
+        let state_ptr = match WindowsWindowStatePtr::new(ctx, hwnd, cs) {
+            Ok(state_ptr) => state_ptr,
+            Err(error) => {
+                // Returning zero stops the window from being created.
+                ctx.inner = Some(Err(error));
+                return LRESULT(0);
+            }
+        };
         let weak = Box::new(Rc::downgrade(&state_ptr));
         unsafe { set_window_long(hwnd, GWLP_USERDATA, Box::into_raw(weak) as isize) };
-        ctx.inner = Some(state_ptr);
+        ctx.inner = Some(Ok(state_ptr));
         return LRESULT(1);
     }
     let ptr = unsafe { get_window_long(hwnd, GWLP_USERDATA) } as *mut Weak<WindowsWindowStatePtr>;
@@ -949,30 +964,18 @@ mod windows_renderer {
 
     use crate::{
         get_window_long,
-        platform::blade::{BladeRenderer, BladeSurfaceConfig},
+        platform::blade::{init_gpu_context, BladeRenderer, BladeSurfaceConfig},
     };
 
-    pub(super) fn windows_renderer(hwnd: HWND, transparent: bool) -> BladeRenderer {
+    pub(super) fn windows_renderer(hwnd: HWND, transparent: bool) -> anyhow::Result<BladeRenderer> {
         let raw = RawWindow { hwnd: hwnd.0 };
-        let gpu: Arc<gpu::Context> = Arc::new(
-            unsafe {
-                gpu::Context::init_windowed(
-                    &raw,
-                    gpu::ContextDesc {
-                        validation: false,
-                        capture: false,
-                        overlay: false,
-                    },
-                )
-            }
-            .unwrap(),
-        );
+        let gpu: Arc<gpu::Context> = Arc::new(unsafe { init_gpu_context(&raw) }?);
         let config = BladeSurfaceConfig {
             size: gpu::Extent::default(),
             transparent,
         };
 
-        BladeRenderer::new(gpu, config)
+        Ok(BladeRenderer::new(gpu, config))
     }
 
     struct RawWindow {
@@ -580,6 +580,16 @@ pub(crate) struct ElementStateBox {
     pub(crate) type_name: &'static str,
 }
 
+/// How many of the units that window bounds are in make up a pixel. They're points on macOS, the
+/// same as pixels, and physical pixels elsewhere.
+fn window_bounds_scale(scale_factor: f32) -> f32 {
+    if cfg!(target_os = "macos") {
+        1.
+    } else {
+        scale_factor
+    }
+}
+
 fn default_bounds(display_id: Option<DisplayId>, cx: &mut AppContext) -> Bounds<DevicePixels> {
     const DEFAULT_WINDOW_OFFSET: Point<DevicePixels> = point(DevicePixels(0), DevicePixels(35));
 
@@ -604,7 +614,7 @@ impl Window {
         handle: AnyWindowHandle,
         options: WindowOptions,
         cx: &mut AppContext,
-    ) -> Self {
+    ) -> Result<Self> {
         let WindowOptions {
             window_bounds,
             titlebar,
@@ -632,7 +642,7 @@ impl Window {
                 display_id,
                 window_background,
             },
-        );
+        )?;
         let display_id = platform_window.display().id();
         let sprite_atlas = platform_window.sprite_atlas();
         let mouse_position = platform_window.mouse_position();
@@ -760,7 +770,7 @@ impl Window {
             platform_window.set_app_id(&app_id);
         }
 
-        Window {
+        Ok(Window {
             handle,
             removed: false,
             platform_window,
@@ -806,7 +816,7 @@ impl Window {
             focus_enabled: true,
             pending_input: None,
             prompt: None,
-        }
+        })
     }
     fn new_focus_listener(
         &mut self,
@@ -987,11 +997,19 @@ impl<'a> WindowContext<'a> {
                         .unwrap_or_else(|| cx.window.rendered_frame.dispatch_tree.root_node_id());
 
                     cx.dispatch_action_on_node(node_id, action.as_ref());
+                    cx.dispatch_action_observers(action.as_ref());
                 })
                 .log_err();
         })
     }
 
+    fn dispatch_action_observers(&mut self, action: &dyn Action) {
+        self.action_observers.clone().retain(&(), move |callback| {
+            (callback)(action, self);
+            true
+        });
+    }
+
     pub(crate) fn dispatch_keystroke_observers(
         &mut self,
         event: &dyn Any,
@@ -1417,7 +1435,13 @@ impl<'a> WindowContext<'a> {
             element.prepaint_as_root(Point::default(), self.window.viewport_size.into(), self);
             prompt_element = Some(element);
             self.window.prompt = Some(prompt);
-        } else if let Some(active_drag) = self.app.active_drag.take() {
+        } else if let Some(active_drag) = self
+            .app
+            .drag_window
+            .map_or(true, |window| window == self.window.handle)
+            .then(|| self.app.active_drag.take())
+            .flatten()
+        {
             let mut element = active_drag.view.clone().into_any();
             let offset = self.mouse_position() - active_drag.cursor_offset;
             element.prepaint_as_root(offset, AvailableSpace::min_size(), self);
@@ -2964,12 +2988,17 @@ impl<'a> WindowContext<'a> {
                 }
                 FileDropEvent::Exited => {
                     self.active_drag.take();
+                    self.app.drag_window = None;
                     PlatformInput::FileDrop(FileDropEvent::Exited)
                 }
             },
             PlatformInput::KeyDown(_) | PlatformInput::KeyUp(_) => event,
         };
 
+        if let Some(result) = self.forward_drag_event(&event) {
+            return result;
+        }
+
         if let Some(any_mouse_event) = event.mouse_event() {
             self.dispatch_mouse_event(any_mouse_event);
         } else if let Some(any_key_event) = event.keyboard_event() {
@@ -2982,6 +3011,72 @@ impl<'a> WindowContext<'a> {
         }
     }
 
+    /// Sends a mouse move or up event of a drag that has left this window to the window under the
+    /// cursor, so that what's dragged can be dropped onto another window. The platform keeps
+    /// sending the events of a drag to the window it started in.
+    fn forward_drag_event(&mut self, event: &PlatformInput) -> Option<DispatchEventResult> {
+        if !self.has_active_drag() {
+            return None;
+        }
+        let position = match event {
+            PlatformInput::MouseMove(event) => event.position,
+            PlatformInput::MouseUp(event) => event.position,
+            _ => return None,
+        };
+        if Bounds::new(Point::default(), self.viewport_size()).contains(&position) {
+            return None;
+        }
+
+        let handle = self.window.handle;
+        let origin = self.bounds().origin;
+        let scale = window_bounds_scale(self.scale_factor());
+        let screen_position = origin
+            + point(
+                DevicePixels((position.x.0 * scale) as i32),
+                DevicePixels((position.y.0 * scale) as i32),
+            );
+        // Where windows overlap, the event goes to the frontmost one.
+        let windows = self.app.window_stack().unwrap_or_else(|| {
+            let active_window = self.app.active_window();
+            let mut windows = self.app.windows();
+            windows.sort_by_key(|window| Some(*window) != active_window);
+            windows
+        });
+        let (target, position) = windows
+            .into_iter()
+            .filter(|window| *window != handle)
+            .find_map(|window| {
+                let (bounds, scale_factor) = window
+                    .update(self.app, |_, cx| (cx.bounds(), cx.scale_factor()))
+                    .ok()?;
+                // Where windows are isn't known on Wayland, where they're all at the origin.
+                if bounds.origin == origin || !bounds.contains(&screen_position) {
+                    return None;
+                }
+                let offset = screen_position - bounds.origin;
+                let scale = window_bounds_scale(scale_factor);
+                let position = point(px(offset.x.0 as f32 / scale), px(offset.y.0 as f32 / scale));
+                Some((window, position))
+            })?;
+
+        let event = match event {
+            PlatformInput::MouseMove(event) => PlatformInput::MouseMove(MouseMoveEvent {
+                position,
+                ..event.clone()
+            }),
+            PlatformInput::MouseUp(event) => PlatformInput::MouseUp(MouseUpEvent {
+                position,
+                ..event.clone()
+            }),
+            _ => unreachable!(),
+        };
+        // The drag is drawn in the window it's over, so it's removed from this one.
+        self.refresh();
+        target
+            .update(self.app, |_, cx| cx.dispatch_event(event))
+            .ok()
+    }
+
     fn dispatch_mouse_event(&mut self, event: &dyn Any) {
         let hit_test = self.window.rendered_frame.hit_test(self.mouse_position());
         if hit_test != self.window.mouse_hit_test {
@@ -3015,6 +3110,13 @@ impl<'a> WindowContext<'a> {
         self.window.rendered_frame.mouse_listeners = mouse_listeners;
 
         if self.has_active_drag() {
+            // The drag is only drawn in the window it was last over.
+            let handle = self.window.handle;
+            if let Some(previous) = self.app.drag_window.replace(handle) {
+                if previous != handle {
+                    previous.update(self.app, |_, cx| cx.refresh()).ok();
+                }
+            }
             if event.is::<MouseMoveEvent>() {
                 // If this was a mouse move event, redraw the window so that the
                 // active drag can follow the mouse cursor.
@@ -3026,6 +3128,10 @@ impl<'a> WindowContext<'a> {
                 self.refresh();
             }
         }
+        if event.is::<MouseUpEvent>() {
+            // The drag is over, whether it was dropped or cancelled.
+            self.app.drag_window = None;
+        }
     }
 
     fn dispatch_key_event(&mut self, event: &dyn Any) {
@@ -3084,6 +3190,7 @@ impl<'a> WindowContext<'a> {
                 }));
 
                 self.window.pending_input = Some(currently_pending);
+                self.dispatch_keystroke_observers(event, None);
 
                 self.propagate_event = false;
                 return;
@@ -3437,6 +3544,22 @@ impl<'a> WindowContext<'a> {
         actions
     }
 
+    /// Returns the key contexts of the focused element and the elements that contain it, from the
+    /// root of the window inwards.
+    pub fn context_stack(&self) -> Vec<KeyContext> {
+        let dispatch_tree = &self.window.rendered_frame.dispatch_tree;
+        let node_id = self
+            .window
+            .focus
+            .and_then(|focus_id| dispatch_tree.focusable_node_id(focus_id))
+            .unwrap_or_else(|| dispatch_tree.root_node_id());
+        dispatch_tree
+            .dispatch_path(node_id)
+            .into_iter()
+            .filter_map(|node_id| dispatch_tree.node(node_id).context.clone())
+            .collect()
+    }
+
     /// Returns key bindings that invoke the given action on the currently focused element.
     pub fn bindings_for_action(&self, action: &dyn Action) -> Vec<KeyBinding> {
         self.window
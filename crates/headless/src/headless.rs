@@ -1,3 +1,5 @@
+mod script;
+
 use anyhow::{anyhow, Result};
 use client::DevServerProjectId;
 use client::{user::UserStore, Client, ClientSettings};
@@ -12,6 +14,7 @@ use node_runtime::NodeRuntime;
 use postage::stream::Stream;
 use project::{Project, WorktreeSettings};
 use rpc::{proto, ErrorCode, TypedEnvelope};
+pub use script::{run_script, Script, ScriptOutcome, ScriptSeverity, ScriptStep};
 use settings::{Settings, SettingsStore};
 use std::{collections::HashMap, sync::Arc};
 use util::{ResultExt, TryFutureExt};
@@ -0,0 +1,589 @@
+use anyhow::{anyhow, bail, Context as _, Result};
+use client::Client;
+use collections::HashSet;
+use futures::StreamExt;
+use gpui::{AppContext, AsyncAppContext, Model, Task};
+use language::{Buffer, DiagnosticSeverity, Point, ToOffset};
+use project::{
+    search::SearchQuery, DiagnosticsExportFormat, FormatTrigger, Project, ProjectPath, SearchResult,
+};
+use serde::Deserialize;
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+use util::paths::PathMatcher;
+
+use crate::AppState;
+
+/// A script of batch edits run against a project without opening a window, such as a codemod
+/// run in CI. Scripts are JSON files with a list of steps, which run in order:
+///
+/// ```json
+/// {
+///   "steps": [
+///     { "action": "replace", "paths": ["src/**/*.rs"], "find": "foo\\(", "replace": "bar(", "regex": true },
+///     { "action": "format" },
+///     { "action": "save" },
+///     { "action": "diagnostics", "fail_on": "error" }
+///   ]
+/// }
+/// ```
+#[derive(Debug, Deserialize)]
+pub struct Script {
+    pub steps: Vec<ScriptStep>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum ScriptStep {
+    /// Opens the files matching the globs, which starts their language servers.
+    Open { paths: Vec<String> },
+    /// Replaces every match of `find` in the files matching the globs, or in every file of the
+    /// project when there are none. Edited files are opened.
+    Replace {
+        find: String,
+        replace: String,
+        #[serde(default)]
+        regex: bool,
+        #[serde(default)]
+        paths: Vec<String>,
+    },
+    /// Formats the open files matching the globs, or all open files when there are none, with
+    /// their configured formatters.
+    Format {
+        #[serde(default)]
+        paths: Vec<String>,
+    },
+    /// Saves every edited file.
+    Save,
+    /// Waits for language servers to report diagnostics of the open files, then prints them.
+    /// The script fails once it ends if any are at least as severe as `fail_on`.
+    Diagnostics {
+        #[serde(default)]
+        fail_on: Option<ScriptSeverity>,
+        #[serde(default = "default_diagnostics_timeout")]
+        timeout_secs: u64,
+    },
+    /// Waits for language servers to report diagnostics, then writes the diagnostics of every
+    /// file in the project to `output`, as SARIF or JSON. The format defaults to SARIF for
+    /// `.sarif` files and JSON otherwise.
+    ExportDiagnostics {
+        output: PathBuf,
+        #[serde(default)]
+        format: Option<DiagnosticsExportFormat>,
+        #[serde(default = "default_diagnostics_timeout")]
+        timeout_secs: u64,
+    },
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ScriptSeverity {
+    Error,
+    Warning,
+}
+
+fn default_diagnostics_timeout() -> u64 {
+    60
+}
+
+/// How a script finished, which becomes the process's exit status.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScriptOutcome {
+    Succeeded,
+    /// Every step ran, but diagnostics were found at a `fail_on` severity.
+    ChecksFailed,
+}
+
+impl ScriptOutcome {
+    pub fn exit_code(self) -> i32 {
+        match self {
+            ScriptOutcome::Succeeded => 0,
+            ScriptOutcome::ChecksFailed => 1,
+        }
+    }
+}
+
+impl Script {
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents =
+            std::fs::read_to_string(path).with_context(|| format!("reading script {path:?}"))?;
+        serde_json::from_str(&contents).with_context(|| format!("parsing script {path:?}"))
+    }
+}
+
+/// Loads the given directories into a project and runs the script against it, using the same
+/// language servers, formatters and settings as the editor.
+pub fn run_script(
+    script: Script,
+    project_paths: Vec<PathBuf>,
+    client: Arc<Client>,
+    app_state: AppState,
+    cx: &mut AppContext,
+) -> Task<Result<ScriptOutcome>> {
+    let project = Project::local(
+        client,
+        app_state.node_runtime,
+        app_state.user_store,
+        app_state.languages,
+        app_state.fs,
+        cx,
+    );
+    run_script_in_project(script, project, project_paths, cx)
+}
+
+fn run_script_in_project(
+    script: Script,
+    project: Model<Project>,
+    project_paths: Vec<PathBuf>,
+    cx: &mut AppContext,
+) -> Task<Result<ScriptOutcome>> {
+    cx.spawn(|mut cx| async move {
+        for path in project_paths {
+            let (worktree, _) = project
+                .update(&mut cx, |project, cx| {
+                    project.find_or_create_local_worktree(&path, true, cx)
+                })?
+                .await
+                .with_context(|| format!("opening {path:?}"))?;
+            let scan_complete = worktree.read_with(&cx, |worktree, _| {
+                worktree.as_local().map(|worktree| worktree.scan_complete())
+            })?;
+            if let Some(scan_complete) = scan_complete {
+                scan_complete.await;
+            }
+        }
+
+        let mut runner = ScriptRunner {
+            project,
+            buffers: Vec::new(),
+            outcome: ScriptOutcome::Succeeded,
+        };
+        for (ix, step) in script.steps.into_iter().enumerate() {
+            log::info!("running script step {}: {step:?}", ix + 1);
+            runner
+                .run_step(step, &mut cx)
+                .await
+                .with_context(|| format!("script step {}", ix + 1))?;
+        }
+        Ok(runner.outcome)
+    })
+}
+
+struct ScriptRunner {
+    project: Model<Project>,
+    /// The files opened by the script, in the order they were opened.
+    buffers: Vec<Model<Buffer>>,
+    outcome: ScriptOutcome,
+}
+
+impl ScriptRunner {
+    async fn run_step(&mut self, step: ScriptStep, cx: &mut AsyncAppContext) -> Result<()> {
+        match step {
+            ScriptStep::Open { paths } => {
+                let paths = cx.update(|cx| self.matching_paths(&paths, cx))??;
+                for path in paths {
+                    self.open_buffer(path, cx).await?;
+                }
+            }
+            ScriptStep::Replace {
+                find,
+                replace,
+                regex,
+                paths,
+            } => self.replace(find, replace, regex, paths, cx).await?,
+            ScriptStep::Format { paths } => {
+                let matchers = path_matchers(&paths)?;
+                let buffers = cx.update(|cx| {
+                    self.buffers
+                        .iter()
+                        .filter(|buffer| {
+                            matchers.is_empty()
+                                || buffer.read(cx).file().map_or(false, |file| {
+                                    matchers.iter().any(|matcher| matcher.is_match(file.path()))
+                                })
+                        })
+                        .cloned()
+                        .collect::<HashSet<_>>()
+                })?;
+                self.project
+                    .update(cx, |project, cx| {
+                        project.format(buffers, false, FormatTrigger::Manual, cx)
+                    })?
+                    .await?;
+            }
+            ScriptStep::Save => {
+                let saves = self.project.update(cx, |project, cx| {
+                    self.buffers
+                        .iter()
+                        .filter(|buffer| buffer.read(cx).is_dirty())
+                        .map(|buffer| project.save_buffer(buffer.clone(), cx))
+                        .collect::<Vec<_>>()
+                })?;
+                for save in saves {
+                    save.await?;
+                }
+            }
+            ScriptStep::Diagnostics {
+                fail_on,
+                timeout_secs,
+            } => {
+                self.project
+                    .update(cx, |project, cx| {
+                        project.diagnostics_settled(Duration::from_secs(timeout_secs), cx)
+                    })?
+                    .await;
+                let failed = cx.update(|cx| self.print_diagnostics(fail_on, cx))?;
+                if failed {
+                    self.outcome = ScriptOutcome::ChecksFailed;
+                }
+            }
+            ScriptStep::ExportDiagnostics {
+                format,
+                output,
+                timeout_secs,
+            } => {
+                self.project
+                    .update(cx, |project, cx| {
+                        project.diagnostics_settled(Duration::from_secs(timeout_secs), cx)
+                    })?
+                    .await;
+                let format = format.unwrap_or_else(|| DiagnosticsExportFormat::for_path(&output));
+                let exported = self
+                    .project
+                    .read_with(cx, |project, cx| project.export_diagnostics(format, cx))??;
+                std::fs::write(&output, exported)
+                    .with_context(|| format!("writing diagnostics to {output:?}"))?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn open_buffer(
+        &mut self,
+        path: ProjectPath,
+        cx: &mut AsyncAppContext,
+    ) -> Result<Model<Buffer>> {
+        let buffer = self
+            .project
+            .update(cx, |project, cx| project.open_buffer(path.clone(), cx))?
+            .await
+            .with_context(|| format!("opening {:?}", path.path))?;
+        if !self.buffers.contains(&buffer) {
+            self.buffers.push(buffer.clone());
+        }
+        Ok(buffer)
+    }
+
+    async fn replace(
+        &mut self,
+        find: String,
+        replace: String,
+        regex: bool,
+        paths: Vec<String>,
+        cx: &mut AsyncAppContext,
+    ) -> Result<()> {
+        let files_to_include = path_matchers(&paths)?;
+        let query = if regex {
+            SearchQuery::regex(&find, false, true, false, files_to_include, Vec::new())?
+        } else {
+            SearchQuery::text(&find, false, true, false, files_to_include, Vec::new())?
+        }
+        .with_replacement(replace);
+
+        let mut results = self
+            .project
+            .update(cx, |project, cx| project.search(query.clone(), cx))?;
+        while let Some(result) = results.next().await {
+            let (buffer, ranges) = match result {
+                SearchResult::Buffer { buffer, ranges } => (buffer, ranges),
+                SearchResult::SkippedFiles(_) => continue,
+                SearchResult::LimitReached => {
+                    bail!(
+                        "too many matches of {find:?}, limit the files it applies to with `paths`"
+                    )
+                }
+            };
+            buffer.update(cx, |buffer, cx| {
+                let snapshot = buffer.snapshot();
+                let edits = ranges
+                    .iter()
+                    .filter_map(|range| {
+                        let range =
+                            range.start.to_offset(&snapshot)..range.end.to_offset(&snapshot);
+                        let text = snapshot.text_for_range(range.clone()).collect::<String>();
+                        let replacement = query.replacement_for(&text)?.into_owned();
+                        Some((range, replacement))
+                    })
+                    .collect::<Vec<_>>();
+                buffer.edit(edits, None, cx);
+            })?;
+            if !self.buffers.contains(&buffer) {
+                self.buffers.push(buffer);
+            }
+        }
+        Ok(())
+    }
+
+    /// Prints the diagnostics of the open files, returning whether any are at least as severe
+    /// as `fail_on`.
+    fn print_diagnostics(&self, fail_on: Option<ScriptSeverity>, cx: &AppContext) -> bool {
+        let fail_on = fail_on.map(|severity| match severity {
+            ScriptSeverity::Error => DiagnosticSeverity::ERROR,
+            ScriptSeverity::Warning => DiagnosticSeverity::WARNING,
+        });
+        let mut failed = false;
+        for buffer in &self.buffers {
+            let buffer = buffer.read(cx);
+            let Some(file) = buffer.file() else {
+                continue;
+            };
+            let snapshot = buffer.snapshot();
+            for entry in snapshot.diagnostics_in_range::<_, Point>(0..snapshot.len(), false) {
+                let diagnostic = &entry.diagnostic;
+                if !diagnostic.is_primary {
+                    continue;
+                }
+                let severity = match diagnostic.severity {
+                    DiagnosticSeverity::ERROR => "error",
+                    DiagnosticSeverity::WARNING => "warning",
+                    DiagnosticSeverity::INFORMATION => "info",
+                    _ => "hint",
+                };
+                println!(
+                    "{}:{}:{}: {severity}: {}",
+                    file.path().display(),
+                    entry.range.start.row + 1,
+                    entry.range.start.column + 1,
+                    diagnostic.message
+                );
+                // Lower severities are more severe.
+                if fail_on.map_or(false, |fail_on| diagnostic.severity <= fail_on) {
+                    failed = true;
+                }
+            }
+        }
+        failed
+    }
+
+    /// The files of the project matching any of the globs.
+    fn matching_paths(&self, globs: &[String], cx: &AppContext) -> Result<Vec<ProjectPath>> {
+        let matchers = path_matchers(globs)?;
+        let mut paths = Vec::new();
+        for worktree in self.project.read(cx).visible_worktrees(cx) {
+            let worktree = worktree.read(cx);
+            for entry in worktree.files(false, 0) {
+                if matchers.iter().any(|matcher| matcher.is_match(&entry.path)) {
+                    paths.push(ProjectPath {
+                        worktree_id: worktree.id(),
+                        path: entry.path.clone(),
+                    });
+                }
+            }
+        }
+        if paths.is_empty() {
+            return Err(anyhow!("no files match {globs:?}"));
+        }
+        Ok(paths)
+    }
+}
+
+fn path_matchers(globs: &[String]) -> Result<Vec<PathMatcher>> {
+    globs
+        .iter()
+        .map(|glob| PathMatcher::new(glob).with_context(|| format!("invalid glob {glob:?}")))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fs::{FakeFs, Fs};
+    use gpui::TestAppContext;
+    use language::{Diagnostic, DiagnosticEntry, LanguageServerId, PointUtf16, Unclipped};
+    use serde_json::json;
+    use settings::SettingsStore;
+
+    #[test]
+    fn test_script_deserialization() {
+        let script: Script = serde_json::from_value(json!({
+            "steps": [
+                { "action": "open", "paths": ["src/*.rs"] },
+                { "action": "replace", "find": "foo", "replace": "bar" },
+                { "action": "format", "paths": ["src/*.rs"] },
+                { "action": "save" },
+                { "action": "diagnostics", "fail_on": "warning" },
+                { "action": "export_diagnostics", "output": "out.sarif", "format": "sarif" }
+            ]
+        }))
+        .unwrap();
+        assert_eq!(script.steps.len(), 6);
+        assert!(matches!(&script.steps[0], ScriptStep::Open { paths } if paths == &["src/*.rs"]));
+        assert!(matches!(
+            &script.steps[1],
+            ScriptStep::Replace { find, replace, regex: false, paths }
+                if find == "foo" && replace == "bar" && paths.is_empty()
+        ));
+        assert!(matches!(&script.steps[2], ScriptStep::Format { paths } if paths.len() == 1));
+        assert!(matches!(&script.steps[3], ScriptStep::Save));
+        assert!(matches!(
+            &script.steps[4],
+            ScriptStep::Diagnostics {
+                fail_on: Some(ScriptSeverity::Warning),
+                timeout_secs: 60
+            }
+        ));
+        assert!(matches!(
+            &script.steps[5],
+            ScriptStep::ExportDiagnostics {
+                output,
+                format: Some(DiagnosticsExportFormat::Sarif),
+                timeout_secs: 60
+            } if output == Path::new("out.sarif")
+        ));
+
+        // Steps with missing fields or unknown actions are rejected.
+        assert!(serde_json::from_value::<Script>(json!({
+            "steps": [{ "action": "replace", "find": "foo" }]
+        }))
+        .is_err());
+        assert!(serde_json::from_value::<Script>(json!({
+            "steps": [{ "action": "delete" }]
+        }))
+        .is_err());
+        assert!(serde_json::from_value::<Script>(json!({
+            "steps": [{ "action": "diagnostics", "fail_on": "hint" }]
+        }))
+        .is_err());
+    }
+
+    #[test]
+    fn test_script_outcome_exit_code() {
+        assert_eq!(ScriptOutcome::Succeeded.exit_code(), 0);
+        assert_eq!(ScriptOutcome::ChecksFailed.exit_code(), 1);
+    }
+
+    #[gpui::test]
+    async fn test_run_script(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/root",
+            json!({
+                "src": {
+                    "a.rs": "fn foo() {}\nfn main() { foo(); }\n",
+                    "b.rs": "fn other() { foo(); }\n",
+                },
+                "notes.txt": "foo\n",
+            }),
+        )
+        .await;
+        let project = Project::test(fs.clone(), ["/root".as_ref()], cx).await;
+
+        let script: Script = serde_json::from_value(json!({
+            "steps": [
+                { "action": "replace", "paths": ["src/*.rs"], "find": "foo\\(", "replace": "bar(", "regex": true },
+                { "action": "save" },
+                { "action": "diagnostics", "fail_on": "error" }
+            ]
+        }))
+        .unwrap();
+        let outcome = run_script_and_wait(script, project.clone(), cx).await;
+        assert_eq!(outcome.unwrap(), ScriptOutcome::Succeeded);
+        assert_eq!(
+            fs.load("/root/src/a.rs".as_ref()).await.unwrap(),
+            "fn bar() {}\nfn main() { bar(); }\n"
+        );
+        assert_eq!(
+            fs.load("/root/src/b.rs".as_ref()).await.unwrap(),
+            "fn other() { bar(); }\n"
+        );
+        assert_eq!(fs.load("/root/notes.txt".as_ref()).await.unwrap(), "foo\n");
+
+        // Diagnostics at the `fail_on` severity fail the script once every step has run.
+        project.update(cx, |project, cx| {
+            project
+                .update_diagnostic_entries(
+                    LanguageServerId(0),
+                    "/root/src/a.rs".into(),
+                    None,
+                    vec![DiagnosticEntry {
+                        range: Unclipped(PointUtf16::new(0, 3))..Unclipped(PointUtf16::new(0, 6)),
+                        diagnostic: Diagnostic {
+                            severity: DiagnosticSeverity::WARNING,
+                            is_primary: true,
+                            message: "unused function".to_string(),
+                            ..Default::default()
+                        },
+                    }],
+                    cx,
+                )
+                .unwrap();
+        });
+        let script: Script = serde_json::from_value(json!({
+            "steps": [
+                { "action": "open", "paths": ["src/a.rs"] },
+                { "action": "diagnostics", "fail_on": "error" }
+            ]
+        }))
+        .unwrap();
+        let outcome = run_script_and_wait(script, project.clone(), cx).await;
+        assert_eq!(outcome.unwrap(), ScriptOutcome::Succeeded);
+
+        let script: Script = serde_json::from_value(json!({
+            "steps": [
+                { "action": "open", "paths": ["src/a.rs"] },
+                { "action": "diagnostics", "fail_on": "warning" },
+                { "action": "replace", "find": "main", "replace": "start" },
+                { "action": "save" }
+            ]
+        }))
+        .unwrap();
+        let outcome = run_script_and_wait(script, project.clone(), cx).await;
+        assert_eq!(outcome.unwrap(), ScriptOutcome::ChecksFailed);
+        assert_eq!(
+            fs.load("/root/src/a.rs".as_ref()).await.unwrap(),
+            "fn bar() {}\nfn start() { bar(); }\n"
+        );
+
+        // A step that fails stops the script.
+        let script: Script = serde_json::from_value(json!({
+            "steps": [
+                { "action": "open", "paths": ["*.md"] },
+                { "action": "replace", "find": "bar", "replace": "baz" },
+                { "action": "save" }
+            ]
+        }))
+        .unwrap();
+        let error = run_script_and_wait(script, project.clone(), cx)
+            .await
+            .unwrap_err();
+        assert_eq!(error.to_string(), "script step 1");
+        assert_eq!(
+            fs.load("/root/src/b.rs".as_ref()).await.unwrap(),
+            "fn other() { bar(); }\n"
+        );
+    }
+
+    async fn run_script_and_wait(
+        script: Script,
+        project: Model<Project>,
+        cx: &mut TestAppContext,
+    ) -> Result<ScriptOutcome> {
+        let task = cx.update(|cx| run_script_in_project(script, project, vec!["/root".into()], cx));
+        // Let the diagnostics steps wait out their settle time.
+        cx.executor().advance_clock(Duration::from_secs(5));
+        task.await
+    }
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            language::init(cx);
+            Project::init_settings(cx);
+        });
+    }
+}
@@ -3,7 +3,7 @@ pub mod github;
 pub use anyhow::{anyhow, Result};
 use futures::future::BoxFuture;
 use futures_lite::FutureExt;
-use isahc::config::{Configurable, RedirectPolicy};
+use isahc::config::{CaCertificate, Configurable, RedirectPolicy};
 pub use isahc::{
     http::{Method, StatusCode, Uri},
     AsyncBody, Error, HttpClient as IsahcHttpClient, Request, Response,
@@ -11,7 +11,11 @@ pub use isahc::{
 #[cfg(feature = "test-support")]
 use std::fmt;
 use std::{
-    sync::{Arc, Mutex},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
     time::Duration,
 };
 pub use url::Url;
@@ -47,33 +51,63 @@ fn get_proxy(proxy: Option<String>) -> Option<isahc::http::Uri> {
         })
 }
 
+/// The file of CA certificates to trust instead of the system's, if one is given, or otherwise
+/// the one named by `SSL_CERT_FILE`, as OpenSSL and curl use.
+fn get_ca_certificates(ca_certificates: Option<PathBuf>) -> Option<PathBuf> {
+    ca_certificates.or_else(|| std::env::var_os("SSL_CERT_FILE").map(PathBuf::from))
+}
+
 /// An [`HttpClient`] that has a base URL.
 pub struct HttpClientWithUrl {
     base_url: Mutex<String>,
     client: Arc<dyn HttpClient>,
     proxy: Option<String>,
+    ca_certificates: Option<PathBuf>,
+    offline: AtomicBool,
 }
 
 impl HttpClientWithUrl {
-    /// Returns a new [`HttpClientWithUrl`] with the given base URL.
-    pub fn new(base_url: impl Into<String>, unparsed_proxy: Option<String>) -> Self {
+    /// Returns a new [`HttpClientWithUrl`] with the given base URL, which connects through the
+    /// given proxy and trusts the given CA certificates, or those set in the environment.
+    pub fn new(
+        base_url: impl Into<String>,
+        unparsed_proxy: Option<String>,
+        ca_certificates: Option<PathBuf>,
+    ) -> Self {
         let parsed_proxy = get_proxy(unparsed_proxy);
         let proxy_string = parsed_proxy.as_ref().map(|p| {
             // Map proxy settings from `http://localhost:10809` to `http://127.0.0.1:10809`
             // NodeRuntime without environment information can not parse `localhost`
-            // correctly.
+            // correctly. Only the host is changed, as credentials in the URL are case-sensitive.
             // TODO: map to `[::1]` if we are using ipv6
-            p.to_string()
-                .to_ascii_lowercase()
-                .replace("localhost", "127.0.0.1")
+            let proxy = p.to_string();
+            match p.host() {
+                Some(host) if host.eq_ignore_ascii_case("localhost") => {
+                    proxy.replacen(host, "127.0.0.1", 1)
+                }
+                _ => proxy,
+            }
         });
+        let ca_certificates = get_ca_certificates(ca_certificates);
         Self {
             base_url: Mutex::new(base_url.into()),
-            client: client(parsed_proxy),
+            client: client(parsed_proxy, ca_certificates.clone()),
             proxy: proxy_string,
+            ca_certificates,
+            offline: AtomicBool::new(false),
         }
     }
 
+    /// Whether requests are refused instead of sent, other than those to this machine, such as
+    /// to locally run models.
+    pub fn is_offline(&self) -> bool {
+        self.offline.load(Ordering::SeqCst)
+    }
+
+    pub fn set_offline(&self, offline: bool) {
+        self.offline.store(offline, Ordering::SeqCst);
+    }
+
     /// Returns the base URL.
     pub fn base_url(&self) -> String {
         self.base_url
@@ -119,12 +153,20 @@ impl HttpClient for Arc<HttpClientWithUrl> {
         &self,
         req: Request<AsyncBody>,
     ) -> BoxFuture<'static, Result<Response<AsyncBody>, Error>> {
-        self.client.send(req)
+        self.as_ref().send(req)
     }
 
     fn proxy(&self) -> Option<&str> {
         self.proxy.as_deref()
     }
+
+    fn ca_certificates(&self) -> Option<&Path> {
+        self.ca_certificates.as_deref()
+    }
+
+    fn is_offline(&self) -> bool {
+        self.as_ref().is_offline()
+    }
 }
 
 impl HttpClient for HttpClientWithUrl {
@@ -132,12 +174,41 @@ impl HttpClient for HttpClientWithUrl {
         &self,
         req: Request<AsyncBody>,
     ) -> BoxFuture<'static, Result<Response<AsyncBody>, Error>> {
+        if self.is_offline() && !is_loopback(req.uri()) {
+            let error = std::io::Error::new(
+                std::io::ErrorKind::NotConnected,
+                format!("not requesting {} in offline mode", req.uri()),
+            );
+            return async move { Err(error.into()) }.boxed();
+        }
         self.client.send(req)
     }
 
     fn proxy(&self) -> Option<&str> {
         self.proxy.as_deref()
     }
+
+    fn ca_certificates(&self) -> Option<&Path> {
+        self.ca_certificates.as_deref()
+    }
+
+    fn is_offline(&self) -> bool {
+        HttpClientWithUrl::is_offline(self)
+    }
+}
+
+/// Whether the URI is of this machine.
+fn is_loopback(uri: &Uri) -> bool {
+    match uri.host() {
+        Some(host) => {
+            let host = host.trim_start_matches('[').trim_end_matches(']');
+            host.eq_ignore_ascii_case("localhost")
+                || host
+                    .parse::<std::net::IpAddr>()
+                    .map_or(false, |address| address.is_loopback())
+        }
+        None => false,
+    }
 }
 
 pub trait HttpClient: Send + Sync {
@@ -184,17 +255,31 @@ pub trait HttpClient: Send + Sync {
     }
 
     fn proxy(&self) -> Option<&str>;
+
+    /// The file of CA certificates that servers' certificates are checked against, when it's not
+    /// the system's.
+    fn ca_certificates(&self) -> Option<&Path> {
+        None
+    }
+
+    /// Whether requests are refused instead of sent, other than those to this machine.
+    fn is_offline(&self) -> bool {
+        false
+    }
 }
 
-pub fn client(proxy: Option<isahc::http::Uri>) -> Arc<dyn HttpClient> {
-    Arc::new(
-        isahc::HttpClient::builder()
-            .connect_timeout(Duration::from_secs(5))
-            .low_speed_timeout(100, Duration::from_secs(5))
-            .proxy(proxy)
-            .build()
-            .unwrap(),
-    )
+pub fn client(
+    proxy: Option<isahc::http::Uri>,
+    ca_certificates: Option<PathBuf>,
+) -> Arc<dyn HttpClient> {
+    let mut builder = isahc::HttpClient::builder()
+        .connect_timeout(Duration::from_secs(5))
+        .low_speed_timeout(100, Duration::from_secs(5))
+        .proxy(proxy);
+    if let Some(ca_certificates) = ca_certificates {
+        builder = builder.ssl_ca_certificate(CaCertificate::file(ca_certificates));
+    }
+    Arc::new(builder.build().unwrap())
 }
 
 impl HttpClient for isahc::HttpClient {
@@ -237,6 +322,8 @@ impl FakeHttpClient {
                 handler: Box::new(move |req| Box::pin(handler(req))),
             }),
             proxy: None,
+            ca_certificates: None,
+            offline: AtomicBool::new(false),
         })
     }
 
@@ -280,3 +367,68 @@ impl HttpClient for FakeHttpClient {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    struct CountingClient(Arc<AtomicUsize>);
+
+    impl HttpClient for CountingClient {
+        fn send(
+            &self,
+            _: Request<AsyncBody>,
+        ) -> BoxFuture<'static, Result<Response<AsyncBody>, Error>> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            async move { Ok(Response::new(AsyncBody::default())) }.boxed()
+        }
+
+        fn proxy(&self) -> Option<&str> {
+            None
+        }
+    }
+
+    #[test]
+    fn test_offline_requests() {
+        let requests = Arc::new(AtomicUsize::new(0));
+        let client = HttpClientWithUrl {
+            base_url: Mutex::new("https://zed.dev".into()),
+            client: Arc::new(CountingClient(requests.clone())),
+            proxy: None,
+            ca_certificates: None,
+            offline: AtomicBool::new(false),
+        };
+        let get = |uri| {
+            futures_lite::future::block_on(client.get(uri, AsyncBody::default(), false)).is_ok()
+        };
+
+        client.set_offline(true);
+        assert!(HttpClient::is_offline(&client));
+        assert!(!get("https://zed.dev/api/releases"));
+        assert_eq!(requests.load(Ordering::SeqCst), 0);
+
+        // Models run on this machine can still be reached.
+        assert!(get("http://localhost:11434/api/tags"));
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+        client.set_offline(false);
+        assert!(get("https://zed.dev/api/releases"));
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_is_loopback() {
+        let is_loopback = |uri: &str| is_loopback(&uri.parse::<Uri>().unwrap());
+        assert!(is_loopback("http://localhost:1234/v1"));
+        assert!(is_loopback("http://LocalHost/"));
+        assert!(is_loopback("http://127.0.0.1:11434"));
+        assert!(is_loopback("http://127.1.2.3/"));
+        assert!(is_loopback("http://[::1]:8080/"));
+        assert!(!is_loopback("https://zed.dev/api"));
+        assert!(!is_loopback("http://localhost.example.com/"));
+        assert!(!is_loopback("http://10.0.0.1/"));
+        assert!(!is_loopback("http://[::2]/"));
+        assert!(!is_loopback("/relative/path"));
+    }
+}
@@ -0,0 +1,232 @@
+use crate::image_viewer_settings::{ImageOptimizer, ImageViewerSettings};
+use anyhow::{anyhow, Context, Result};
+use fs::Fs;
+use std::{
+    io::{Cursor, Read as _},
+    path::Path,
+    process::Stdio,
+};
+
+/// The size of an image, read from the header of its file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ImageMetadata {
+    pub width: u32,
+    pub height: u32,
+    pub file_size: u64,
+}
+
+impl ImageMetadata {
+    pub fn read(path: &Path) -> Result<Self> {
+        let file_size = std::fs::metadata(path)
+            .with_context(|| format!("reading metadata of {path:?}"))?
+            .len();
+        let (width, height) = image::image_dimensions(path)
+            .with_context(|| format!("reading dimensions of {path:?}"))?;
+        Ok(Self {
+            width,
+            height,
+            file_size,
+        })
+    }
+
+    /// Reads the metadata of the image through the file system, rather than from the disk
+    /// directly, so it works on whichever file system the image is in.
+    pub async fn load(fs: &dyn Fs, path: &Path) -> Result<Self> {
+        let mut bytes = Vec::new();
+        fs.open_sync(path)
+            .await
+            .with_context(|| format!("opening {path:?}"))?
+            .read_to_end(&mut bytes)
+            .with_context(|| format!("reading {path:?}"))?;
+        let (width, height) = image::io::Reader::new(Cursor::new(&bytes))
+            .with_guessed_format()?
+            .into_dimensions()
+            .with_context(|| format!("reading dimensions of {path:?}"))?;
+        Ok(Self {
+            width,
+            height,
+            file_size: bytes.len() as u64,
+        })
+    }
+
+    /// The dimensions and file size of the image, such as "1920×1080 · 1.2 MB".
+    pub fn description(&self) -> String {
+        format!(
+            "{}×{} · {}",
+            self.width,
+            self.height,
+            format_file_size(self.file_size)
+        )
+    }
+
+    /// The thresholds set in the settings that the image exceeds.
+    pub fn warnings(&self, settings: &ImageViewerSettings) -> Vec<String> {
+        let mut warnings = Vec::new();
+        if let Some(max_file_size) = settings.max_file_size {
+            if self.file_size > max_file_size {
+                warnings.push(format!(
+                    "The image is {}, which is larger than {}",
+                    format_file_size(self.file_size),
+                    format_file_size(max_file_size)
+                ));
+            }
+        }
+        if let Some(max_dimension) = settings.max_dimension {
+            if self.width.max(self.height) > max_dimension {
+                warnings.push(format!(
+                    "The image is {}×{} pixels, which is larger than {max_dimension} pixels",
+                    self.width, self.height
+                ));
+            }
+        }
+        warnings
+    }
+}
+
+/// Runs the optimizer on the image, which is expected to rewrite it in place.
+pub async fn optimize_image(optimizer: &ImageOptimizer, path: &Path) -> Result<()> {
+    let output = smol::process::Command::new(&optimizer.command)
+        .args(optimizer_arguments(optimizer, path))
+        .stdin(Stdio::null())
+        .output()
+        .await
+        .with_context(|| format!("running {}", optimizer.command))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(anyhow!(
+            "{} failed: {}",
+            optimizer.command,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+fn optimizer_arguments(optimizer: &ImageOptimizer, path: &Path) -> Vec<String> {
+    const IMAGE_PATH: &str = "{image_path}";
+
+    let path = path.to_string_lossy();
+    let mut arguments = optimizer
+        .arguments
+        .iter()
+        .map(|argument| argument.replace(IMAGE_PATH, &path))
+        .collect::<Vec<_>>();
+    if !optimizer
+        .arguments
+        .iter()
+        .any(|argument| argument.contains(IMAGE_PATH))
+    {
+        arguments.push(path.into_owned());
+    }
+    arguments
+}
+
+fn format_file_size(bytes: u64) -> String {
+    const UNITS: &[&str] = &["B", "KB", "MB", "GB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024. && unit + 1 < UNITS.len() {
+        size /= 1024.;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} {}", UNITS[unit])
+    } else {
+        format!("{size:.1} {}", UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fs::FakeFs;
+    use gpui::TestAppContext;
+
+    #[gpui::test]
+    async fn test_load(cx: &mut TestAppContext) {
+        let mut png = Vec::new();
+        image::DynamicImage::ImageRgba8(image::RgbaImage::new(3, 2))
+            .write_to(&mut png, image::ImageOutputFormat::Png)
+            .unwrap();
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_file("/images/pixel.png", png.clone()).await;
+        fs.insert_file("/images/broken.png", b"not an image".to_vec())
+            .await;
+
+        assert_eq!(
+            ImageMetadata::load(fs.as_ref(), Path::new("/images/pixel.png"))
+                .await
+                .unwrap(),
+            ImageMetadata {
+                width: 3,
+                height: 2,
+                file_size: png.len() as u64,
+            }
+        );
+        assert!(
+            ImageMetadata::load(fs.as_ref(), Path::new("/images/broken.png"))
+                .await
+                .is_err()
+        );
+        assert!(
+            ImageMetadata::load(fs.as_ref(), Path::new("/images/missing.png"))
+                .await
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_warnings() {
+        let metadata = ImageMetadata {
+            width: 4000,
+            height: 3000,
+            file_size: 3 * 1024 * 1024,
+        };
+        assert_eq!(metadata.description(), "4000×3000 · 3.0 MB");
+        assert!(metadata
+            .warnings(&ImageViewerSettings::default())
+            .is_empty());
+
+        let settings = ImageViewerSettings {
+            max_file_size: Some(1024 * 1024),
+            max_dimension: Some(2048),
+            optimizer: None,
+        };
+        assert_eq!(
+            metadata.warnings(&settings),
+            [
+                "The image is 3.0 MB, which is larger than 1.0 MB",
+                "The image is 4000×3000 pixels, which is larger than 2048 pixels",
+            ]
+        );
+
+        let small = ImageMetadata {
+            width: 16,
+            height: 16,
+            file_size: 512,
+        };
+        assert!(small.warnings(&settings).is_empty());
+    }
+
+    #[test]
+    fn test_optimizer_arguments() {
+        let path = Path::new("/project/logo.png");
+        let appended = ImageOptimizer {
+            command: "oxipng".into(),
+            arguments: vec!["-o".into(), "4".into()],
+        };
+        assert_eq!(
+            optimizer_arguments(&appended, path),
+            ["-o", "4", "/project/logo.png"]
+        );
+
+        let substituted = ImageOptimizer {
+            command: "cwebp".into(),
+            arguments: vec!["{image_path}".into(), "-o".into(), "{image_path}".into()],
+        };
+        assert_eq!(
+            optimizer_arguments(&substituted, path),
+            ["/project/logo.png", "-o", "/project/logo.png"]
+        );
+    }
+}
@@ -1,22 +1,40 @@
+mod image_metadata;
+mod image_viewer_settings;
+
 use gpui::{
-    canvas, div, fill, img, opaque_grey, point, size, AnyElement, AppContext, Bounds, Context,
-    EventEmitter, FocusHandle, FocusableView, Img, InteractiveElement, IntoElement, Model,
-    ObjectFit, ParentElement, Render, Styled, Task, View, ViewContext, VisualContext, WeakView,
-    WindowContext,
+    actions, canvas, div, fill, img, opaque_grey, point, size, AnyElement, AppContext, Bounds,
+    Context, EventEmitter, FocusHandle, FocusableView, Img, InteractiveElement, IntoElement, Model,
+    ObjectFit, ParentElement, Render, Styled, Subscription, Task, View, ViewContext, VisualContext,
+    WeakView, WindowContext,
 };
 use persistence::IMAGE_VIEWER;
+use settings::{Settings, SettingsStore};
 use ui::prelude::*;
 
 use project::{Project, ProjectEntryId, ProjectPath};
-use std::{ffi::OsStr, path::PathBuf};
+use std::{
+    ffi::OsStr,
+    path::{Path, PathBuf},
+};
 use util::ResultExt;
 use workspace::{
     item::{Item, ProjectItem, TabContentParams},
     ItemId, Pane, Workspace, WorkspaceId,
 };
 
+pub use image_metadata::ImageMetadata;
+pub use image_viewer_settings::{ImageOptimizer, ImageViewerSettings};
+
+actions!(image_viewer, [Optimize]);
+
 const IMAGE_VIEWER_KIND: &str = "ImageView";
 
+/// Whether the file at the path is a binary image that can be opened in the image viewer.
+pub fn is_image(path: &Path) -> bool {
+    let ext = path.extension().and_then(OsStr::to_str).unwrap_or_default();
+    Img::extensions().contains(&ext) && !ext.contains("svg")
+}
+
 pub struct ImageItem {
     path: PathBuf,
     project_path: ProjectPath,
@@ -31,15 +49,9 @@ impl project::Item for ImageItem {
         let path = path.clone();
         let project = project.clone();
 
-        let ext = path
-            .path
-            .extension()
-            .and_then(OsStr::to_str)
-            .unwrap_or_default();
-
         // Only open the item if it's a binary image (no SVGs, etc.)
         // Since we do not have a way to toggle to an editor
-        if Img::extensions().contains(&ext) && !ext.contains("svg") {
+        if is_image(&path.path) {
             Some(cx.spawn(|mut cx| async move {
                 let abs_path = project
                     .read_with(&cx, |project, cx| project.absolute_path(&path, cx))?
@@ -67,29 +79,160 @@ impl project::Item for ImageItem {
 pub struct ImageView {
     path: PathBuf,
     focus_handle: FocusHandle,
+    metadata: Option<ImageMetadata>,
+    optimization: Option<Task<()>>,
+    optimization_error: Option<SharedString>,
+    _settings_subscription: Subscription,
+}
+
+impl ImageView {
+    fn new(path: PathBuf, cx: &mut ViewContext<Self>) -> Self {
+        let mut this = Self {
+            path,
+            focus_handle: cx.focus_handle(),
+            metadata: None,
+            optimization: None,
+            optimization_error: None,
+            _settings_subscription: cx.observe_global::<SettingsStore>(|_, cx| cx.notify()),
+        };
+        this.reload_metadata(cx);
+        this
+    }
+
+    fn reload_metadata(&mut self, cx: &mut ViewContext<Self>) {
+        let path = self.path.clone();
+        let metadata = cx
+            .background_executor()
+            .spawn(async move { ImageMetadata::read(&path) });
+        cx.spawn(|this, mut cx| async move {
+            let metadata = metadata.await.log_err();
+            this.update(&mut cx, |this, cx| {
+                this.metadata = metadata;
+                cx.notify();
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// The thresholds in the settings that the image exceeds, once its metadata has been read.
+    fn warnings(&self, cx: &AppContext) -> Vec<String> {
+        self.metadata
+            .map(|metadata| metadata.warnings(ImageViewerSettings::get_global(cx)))
+            .unwrap_or_default()
+    }
+
+    fn optimize(&mut self, _: &Optimize, cx: &mut ViewContext<Self>) {
+        if self.optimization.is_some() {
+            return;
+        }
+        let Some(optimizer) = ImageViewerSettings::get_global(cx).optimizer.clone() else {
+            return;
+        };
+
+        let path = self.path.clone();
+        self.optimization_error = None;
+        self.optimization = Some(cx.spawn(|this, mut cx| async move {
+            let result = image_metadata::optimize_image(&optimizer, &path).await;
+            this.update(&mut cx, |this, cx| {
+                this.optimization = None;
+                this.optimization_error = result.err().map(|error| error.to_string().into());
+                this.reload_metadata(cx);
+                cx.notify();
+            })
+            .ok();
+        }));
+        cx.notify();
+    }
+
+    fn render_warnings(&self, cx: &mut ViewContext<Self>) -> Option<impl IntoElement> {
+        let warnings = self.warnings(cx);
+        if warnings.is_empty() && self.optimization_error.is_none() {
+            return None;
+        }
+
+        let has_optimizer = ImageViewerSettings::get_global(cx).optimizer.is_some();
+        let is_optimizing = self.optimization.is_some();
+        Some(
+            h_flex()
+                .absolute()
+                .top_0()
+                .left_0()
+                .w_full()
+                .gap_2()
+                .px_2()
+                .py_1()
+                .bg(cx.theme().colors().elevated_surface_background)
+                .border_b_1()
+                .border_color(cx.theme().colors().border)
+                .child(Icon::new(IconName::ExclamationTriangle).color(Color::Warning))
+                .child(
+                    v_flex()
+                        .flex_1()
+                        .children(warnings.into_iter().map(Label::new))
+                        .children(
+                            self.optimization_error
+                                .clone()
+                                .map(|error| Label::new(error).color(Color::Error)),
+                        ),
+                )
+                .when(has_optimizer, |this| {
+                    this.child(
+                        Button::new(
+                            "optimize-image",
+                            if is_optimizing {
+                                "Optimizing…"
+                            } else {
+                                "Optimize"
+                            },
+                        )
+                        .disabled(is_optimizing)
+                        .on_click(cx.listener(|this, _, cx| this.optimize(&Optimize, cx))),
+                    )
+                }),
+        )
+    }
 }
 
 impl Item for ImageView {
     type Event = ();
 
-    fn tab_content(&self, params: TabContentParams, _cx: &WindowContext) -> AnyElement {
+    fn tab_content(&self, params: TabContentParams, cx: &WindowContext) -> AnyElement {
         let title = self
             .path
             .file_name()
             .unwrap_or_else(|| self.path.as_os_str())
             .to_string_lossy()
             .to_string();
-        Label::new(title)
-            .single_line()
-            .color(if params.selected {
-                Color::Default
-            } else {
-                Color::Muted
+        h_flex()
+            .gap_1()
+            .when(!self.warnings(cx).is_empty(), |this| {
+                this.child(
+                    Icon::new(IconName::ExclamationTriangle)
+                        .size(IconSize::Small)
+                        .color(Color::Warning),
+                )
             })
-            .italic(params.preview)
+            .child(
+                Label::new(title)
+                    .single_line()
+                    .color(if params.selected {
+                        Color::Default
+                    } else {
+                        Color::Muted
+                    })
+                    .italic(params.preview),
+            )
             .into_any_element()
     }
 
+    fn tab_tooltip_text(&self, _: &AppContext) -> Option<SharedString> {
+        let path = self.path.to_string_lossy();
+        Some(match &self.metadata {
+            Some(metadata) => format!("{path}\n{}", metadata.description()).into(),
+            None => path.to_string().into(),
+        })
+    }
+
     fn added_to_workspace(&mut self, workspace: &mut Workspace, cx: &mut ViewContext<Self>) {
         let item_id = cx.entity_id().as_u64();
         let workspace_id = workspace.database_id();
@@ -124,10 +267,7 @@ impl Item for ImageView {
                 .get_image_path(item_id, workspace_id)?
                 .ok_or_else(|| anyhow::anyhow!("No image path found"))?;
 
-            cx.new_view(|cx| ImageView {
-                path: image_path,
-                focus_handle: cx.focus_handle(),
-            })
+            cx.new_view(|cx| ImageView::new(image_path, cx))
         })
     }
 
@@ -139,10 +279,7 @@ impl Item for ImageView {
     where
         Self: Sized,
     {
-        Some(cx.new_view(|cx| Self {
-            path: self.path.clone(),
-            focus_handle: cx.focus_handle(),
-        }))
+        Some(cx.new_view(|cx| Self::new(self.path.clone(), cx)))
     }
 }
 
@@ -199,7 +336,9 @@ impl Render for ImageView {
             .left_0();
 
         div()
+            .key_context("ImageViewer")
             .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::optimize))
             .size_full()
             .child(checkered_background)
             .child(
@@ -217,6 +356,7 @@ impl Render for ImageView {
                             .max_h_full(),
                     ),
             )
+            .children(self.render_warnings(cx))
     }
 }
 
@@ -231,14 +371,12 @@ impl ProjectItem for ImageView {
     where
         Self: Sized,
     {
-        Self {
-            path: item.read(cx).path.clone(),
-            focus_handle: cx.focus_handle(),
-        }
+        Self::new(item.read(cx).path.clone(), cx)
     }
 }
 
 pub fn init(cx: &mut AppContext) {
+    ImageViewerSettings::register(cx);
     workspace::register_project_item::<ImageView>(cx);
     workspace::register_deserializable_item::<ImageView>(cx)
 }
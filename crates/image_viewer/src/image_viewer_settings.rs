@@ -0,0 +1,45 @@
+use anyhow::Result;
+use gpui::AppContext;
+use schemars::JsonSchema;
+use serde_derive::{Deserialize, Serialize};
+use settings::{Settings, SettingsSources};
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, Debug)]
+#[serde(default)]
+pub struct ImageViewerSettings {
+    /// Warn about images whose files are larger than this many bytes.
+    ///
+    /// Default: null
+    pub max_file_size: Option<u64>,
+
+    /// Warn about images that are wider or taller than this many pixels.
+    ///
+    /// Default: null
+    pub max_dimension: Option<u32>,
+
+    /// The command that optimizes an image in place, which is offered for images that are
+    /// warned about.
+    ///
+    /// Default: null
+    pub optimizer: Option<ImageOptimizer>,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, Debug, PartialEq, Eq)]
+pub struct ImageOptimizer {
+    /// The program to run, such as `oxipng`.
+    pub command: String,
+    /// The arguments to pass to the program. `{image_path}` is replaced with the absolute path
+    /// of the image, which is appended to the arguments when none of them mention it.
+    #[serde(default)]
+    pub arguments: Vec<String>,
+}
+
+impl Settings for ImageViewerSettings {
+    const KEY: Option<&'static str> = Some("image_viewer");
+
+    type FileContent = Self;
+
+    fn load(sources: SettingsSources<Self::FileContent>, _: &mut AppContext) -> Result<Self> {
+        sources.json_merge()
+    }
+}
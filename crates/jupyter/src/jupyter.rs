@@ -0,0 +1,11 @@
+mod kernelspecs;
+
+use fs::Fs;
+use gpui::AppContext;
+use std::sync::Arc;
+
+pub use kernelspecs::{Kernelspec, Kernelspecs};
+
+pub fn init(fs: Arc<dyn Fs>, cx: &mut AppContext) {
+    Kernelspecs::init(fs, cx);
+}
@@ -0,0 +1,266 @@
+//! Discovers the Jupyter kernels installed on this machine, from the `kernel.json` files of the
+//! kernelspecs in each Jupyter data directory, the way `jupyter kernelspec list` does.
+
+use anyhow::{Context as _, Result};
+use collections::HashSet;
+use fs::Fs;
+use futures::StreamExt;
+use gpui::{AppContext, Context as _, Global, Model, ModelContext, Task};
+use serde::Deserialize;
+use std::{
+    env,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use util::{paths::HOME, ResultExt};
+
+/// An installed Jupyter kernel.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Kernelspec {
+    /// The name of the kernelspec's directory, which notebooks refer to it by.
+    pub name: String,
+    pub display_name: String,
+    pub language: String,
+    /// The command that launches the kernel, with `{connection_file}` in place of the path of
+    /// its connection file.
+    pub argv: Vec<String>,
+    /// The kernelspec's directory.
+    pub path: PathBuf,
+}
+
+#[derive(Deserialize)]
+struct KernelJson {
+    argv: Vec<String>,
+    display_name: String,
+    language: String,
+}
+
+struct GlobalKernelspecs(Model<Kernelspecs>);
+
+impl Global for GlobalKernelspecs {}
+
+/// The Jupyter kernels installed on this machine, which are discovered at startup.
+pub struct Kernelspecs {
+    kernelspecs: Vec<Kernelspec>,
+    _discover: Task<()>,
+}
+
+impl Kernelspecs {
+    pub(crate) fn init(fs: Arc<dyn Fs>, cx: &mut AppContext) {
+        let kernelspecs = cx.new_model(|cx| Self::new(fs, jupyter_data_dirs(), cx));
+        cx.set_global(GlobalKernelspecs(kernelspecs));
+    }
+
+    fn new(fs: Arc<dyn Fs>, data_dirs: Vec<PathBuf>, cx: &mut ModelContext<Self>) -> Self {
+        let discover = cx.spawn(|this, mut cx| async move {
+            let kernelspecs = discover_kernelspecs(fs.as_ref(), &data_dirs).await;
+            log::info!("found {} Jupyter kernels", kernelspecs.len());
+            this.update(&mut cx, |this, cx| {
+                this.kernelspecs = kernelspecs;
+                cx.notify();
+            })
+            .ok();
+        });
+        Self {
+            kernelspecs: Vec::new(),
+            _discover: discover,
+        }
+    }
+
+    pub fn try_global(cx: &AppContext) -> Option<Model<Self>> {
+        cx.try_global::<GlobalKernelspecs>()
+            .map(|kernelspecs| kernelspecs.0.clone())
+    }
+
+    pub fn kernelspecs(&self) -> &[Kernelspec] {
+        &self.kernelspecs
+    }
+
+    /// The kernel that a notebook or script should run with: the kernelspec it names, when
+    /// that's installed, or otherwise the first installed kernel for its language.
+    pub fn kernelspec_for(&self, name: Option<&str>, language: &str) -> Option<&Kernelspec> {
+        name.and_then(|name| {
+            self.kernelspecs
+                .iter()
+                .find(|kernelspec| kernelspec.name == name)
+        })
+        .or_else(|| {
+            self.kernelspecs
+                .iter()
+                .find(|kernelspec| kernelspec.language.eq_ignore_ascii_case(language))
+        })
+    }
+}
+
+/// The directories that Jupyter looks for data in, in order of precedence: those in
+/// `JUPYTER_PATH`, the user's data directory, the active conda environment's, and the
+/// system-wide ones.
+fn jupyter_data_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if let Some(jupyter_path) = env::var_os("JUPYTER_PATH") {
+        dirs.extend(env::split_paths(&jupyter_path));
+    }
+
+    if let Some(data_dir) = env::var_os("JUPYTER_DATA_DIR") {
+        dirs.push(PathBuf::from(data_dir));
+    } else if cfg!(target_os = "macos") {
+        dirs.push(HOME.join("Library/Jupyter"));
+    } else if cfg!(target_os = "windows") {
+        if let Some(app_data) = env::var_os("APPDATA") {
+            dirs.push(PathBuf::from(app_data).join("jupyter"));
+        }
+    } else {
+        let data_home = env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| HOME.join(".local/share"));
+        dirs.push(data_home.join("jupyter"));
+    }
+
+    if let Some(conda_prefix) = env::var_os("CONDA_PREFIX") {
+        dirs.push(PathBuf::from(conda_prefix).join("share/jupyter"));
+    }
+    for conda_dir in ["miniconda3", "anaconda3", "miniforge3"] {
+        dirs.push(HOME.join(conda_dir).join("share/jupyter"));
+    }
+
+    if cfg!(target_os = "windows") {
+        if let Some(program_data) = env::var_os("PROGRAMDATA") {
+            dirs.push(PathBuf::from(program_data).join("jupyter"));
+        }
+    } else {
+        dirs.push(PathBuf::from("/usr/local/share/jupyter"));
+        dirs.push(PathBuf::from("/usr/share/jupyter"));
+    }
+    dirs
+}
+
+/// Reads the kernelspecs in the `kernels` directory of each data directory. A kernelspec hides
+/// those of the same name in later directories.
+async fn discover_kernelspecs(fs: &dyn Fs, data_dirs: &[PathBuf]) -> Vec<Kernelspec> {
+    let mut kernelspecs = Vec::new();
+    let mut names = HashSet::default();
+    for data_dir in data_dirs {
+        let Ok(mut entries) = fs.read_dir(&data_dir.join("kernels")).await else {
+            continue;
+        };
+        let mut kernel_dirs = Vec::new();
+        while let Some(entry) = entries.next().await {
+            if let Some(entry) = entry.log_err() {
+                kernel_dirs.push(entry);
+            }
+        }
+        kernel_dirs.sort();
+
+        for kernel_dir in kernel_dirs {
+            let Some(name) = kernel_dir.file_name().and_then(|name| name.to_str()) else {
+                continue;
+            };
+            if names.contains(name) || !fs.is_dir(&kernel_dir).await {
+                continue;
+            }
+            if let Some(kernelspec) = read_kernelspec(fs, name, &kernel_dir).await.log_err() {
+                names.insert(kernelspec.name.clone());
+                kernelspecs.push(kernelspec);
+            }
+        }
+    }
+    kernelspecs
+}
+
+async fn read_kernelspec(fs: &dyn Fs, name: &str, kernel_dir: &Path) -> Result<Kernelspec> {
+    let kernel_json_path = kernel_dir.join("kernel.json");
+    let kernel_json = fs.load(&kernel_json_path).await?;
+    let kernel_json: KernelJson = serde_json::from_str(&kernel_json)
+        .with_context(|| format!("parsing {kernel_json_path:?}"))?;
+    Ok(Kernelspec {
+        name: name.to_string(),
+        display_name: kernel_json.display_name,
+        language: kernel_json.language,
+        argv: kernel_json.argv,
+        path: kernel_dir.to_path_buf(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fs::FakeFs;
+    use gpui::{Context as _, TestAppContext};
+    use serde_json::json;
+
+    #[gpui::test]
+    async fn test_discover_kernelspecs(cx: &mut TestAppContext) {
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/user/jupyter/kernels",
+            json!({
+                "python3": {
+                    "kernel.json": r#"{
+                        "argv": ["/venv/bin/python", "-m", "ipykernel_launcher", "-f", "{connection_file}"],
+                        "display_name": "Python 3 (venv)",
+                        "language": "python"
+                    }"#,
+                },
+                "broken": {
+                    "kernel.json": "{ not json",
+                },
+                "empty": {},
+            }),
+        )
+        .await;
+        fs.insert_tree(
+            "/usr/share/jupyter/kernels",
+            json!({
+                "ir": {
+                    "kernel.json": r#"{
+                        "argv": ["R", "--slave", "-e", "IRkernel::main()", "--args", "{connection_file}"],
+                        "display_name": "R",
+                        "language": "R"
+                    }"#,
+                },
+                "python3": {
+                    "kernel.json": r#"{
+                        "argv": ["python3", "-m", "ipykernel_launcher", "-f", "{connection_file}"],
+                        "display_name": "Python 3",
+                        "language": "python"
+                    }"#,
+                },
+            }),
+        )
+        .await;
+
+        let data_dirs = vec![
+            PathBuf::from("/missing/jupyter"),
+            PathBuf::from("/user/jupyter"),
+            PathBuf::from("/usr/share/jupyter"),
+        ];
+        let kernelspecs = cx.new_model(|cx| Kernelspecs::new(fs.clone(), data_dirs, cx));
+        cx.run_until_parked();
+
+        kernelspecs.read_with(cx, |kernelspecs, _| {
+            assert_eq!(
+                kernelspecs
+                    .kernelspecs()
+                    .iter()
+                    .map(|kernelspec| (kernelspec.name.as_str(), kernelspec.display_name.as_str()))
+                    .collect::<Vec<_>>(),
+                [("python3", "Python 3 (venv)"), ("ir", "R")]
+            );
+            let python = &kernelspecs.kernelspecs()[0];
+            assert_eq!(python.language, "python");
+            assert_eq!(python.argv[0], "/venv/bin/python");
+            assert_eq!(python.path, Path::new("/user/jupyter/kernels/python3"));
+
+            let kernel_name = |name, language| {
+                kernelspecs
+                    .kernelspec_for(name, language)
+                    .map(|kernelspec| kernelspec.name.as_str())
+            };
+            assert_eq!(kernel_name(Some("ir"), "python"), Some("ir"));
+            assert_eq!(kernel_name(Some("julia-1.10"), "python"), Some("python3"));
+            assert_eq!(kernel_name(None, "Python"), Some("python3"));
+            assert_eq!(kernel_name(None, "r"), Some("ir"));
+            assert_eq!(kernel_name(None, "julia"), None);
+        });
+    }
+}
@@ -14,7 +14,7 @@ use crate::{
         SyntaxSnapshot, ToTreeSitterPoint,
     },
     task_context::RunnableRange,
-    LanguageScope, Outline, RunnableTag,
+    LanguageScope, Outline, RunnableTag, TextObject,
 };
 use anyhow::{anyhow, Context, Result};
 pub use clock::ReplicaId;
@@ -99,6 +99,10 @@ pub struct Buffer {
     sync_parse_timeout: Duration,
     syntax_map: Mutex<SyntaxMap>,
     parsing_in_background: bool,
+    /// Whether edits are left unparsed until reparsing is resumed, such as while the buffer is
+    /// hidden and the app is idle.
+    reparsing_deferred: bool,
+    reparse_pending: bool,
     parse_count: usize,
     diagnostics: SmallVec<[(LanguageServerId, DiagnosticSet); 2]>,
     remote_selections: TreeMap<ReplicaId, SelectionSet>,
@@ -682,6 +686,8 @@ impl Buffer {
             capability,
             syntax_map: Mutex::new(SyntaxMap::new()),
             parsing_in_background: false,
+            reparsing_deferred: false,
+            reparse_pending: false,
             parse_count: 0,
             sync_parse_timeout: Duration::from_millis(1),
             autoindent_requests: Default::default(),
@@ -999,6 +1005,17 @@ impl Buffer {
         self.parsing_in_background
     }
 
+    /// Defers reparsing the buffer after it's edited until this is called
+    /// again with `false`, which reparses the buffer if it was edited in the
+    /// meantime. Until then, its syntax tree is only interpolated.
+    pub fn set_reparsing_deferred(&mut self, deferred: bool, cx: &mut ModelContext<Self>) {
+        self.reparsing_deferred = deferred;
+        if !deferred && self.reparse_pending {
+            self.reparse_pending = false;
+            self.reparse(cx);
+        }
+    }
+
     /// Indicates whether the buffer contains any regions that may be
     /// written in a language that hasn't been loaded yet.
     pub fn contains_unknown_injections(&self) -> bool {
@@ -1034,6 +1051,10 @@ impl Buffer {
     /// for the same buffer, we only initiate a new parse if we are not already
     /// parsing in the background.
     pub fn reparse(&mut self, cx: &mut ModelContext<Self>) {
+        if self.reparsing_deferred {
+            self.reparse_pending = true;
+            return;
+        }
         if self.parsing_in_background {
             return;
         }
@@ -2504,6 +2525,19 @@ impl BufferSnapshot {
         self.syntax.layers_for_range(0..self.len(), &self.text)
     }
 
+    /// Returns the ranges where other languages are injected, with the language injected in each.
+    /// Injections that are combined from several ranges, like the Ruby code of an ERB template,
+    /// are left out.
+    pub fn injections(&self) -> Vec<(Range<usize>, Arc<Language>)> {
+        self.syntax_layers()
+            .filter(|layer| layer.depth() > 0)
+            .filter_map(|layer| match layer.included_ranges().as_slice() {
+                [range] => Some((range.clone(), layer.language.clone())),
+                _ => None,
+            })
+            .collect()
+    }
+
     pub fn syntax_layer_at<D: ToOffset>(&self, position: D) -> Option<SyntaxLayer> {
         let offset = position.to_offset(self);
         self.syntax
@@ -2512,6 +2546,28 @@ impl BufferSnapshot {
             .last()
     }
 
+    /// Returns the names of the highlight captures of the nodes that contain the given position,
+    /// outermost first, for inspecting how the text there is highlighted.
+    pub fn highlight_captures_at<D: ToOffset>(&self, position: D) -> Vec<String> {
+        let offset = position.to_offset(self);
+        let end = self.clip_offset(offset + 1, Bias::Right).min(self.len());
+        let mut captures = self.syntax.captures(offset..end, &self.text, |grammar| {
+            grammar.highlights_query.as_ref()
+        });
+        let mut names = Vec::new();
+        while let Some(capture) = captures.peek() {
+            let node_range = capture.node.byte_range();
+            if node_range.start <= offset && (offset < node_range.end || offset == self.len()) {
+                let grammar = captures.grammars()[capture.grammar_index];
+                if let Some(query) = grammar.highlights_query.as_ref() {
+                    names.push(query.capture_names()[capture.index as usize].to_string());
+                }
+            }
+            captures.advance();
+        }
+        names
+    }
+
     /// Returns the main [Language]
     pub fn language(&self) -> Option<&Arc<Language>> {
         self.language.as_ref()
@@ -3054,6 +3110,61 @@ impl BufferSnapshot {
         })
     }
 
+    /// Returns the ranges of the text objects, such as functions and their arguments, that the
+    /// language's `textobjects.scm` query finds in the given range. When a match captures an
+    /// object more than once, such as an argument and the comma after it, the ranges are joined.
+    pub fn text_object_ranges<T: ToOffset>(
+        &self,
+        range: Range<T>,
+    ) -> impl Iterator<Item = (Range<usize>, TextObject)> + '_ {
+        let offset_range = range.start.to_offset(self)..range.end.to_offset(self);
+
+        let mut syntax_matches = self.syntax.matches(offset_range, self, |grammar| {
+            grammar
+                .text_object_config
+                .as_ref()
+                .map(|config| &config.query)
+        });
+
+        let text_object_configs = syntax_matches
+            .grammars()
+            .iter()
+            .map(|grammar| grammar.text_object_config.as_ref())
+            .collect::<Vec<_>>();
+
+        let mut captures = SmallVec::<[(Range<usize>, TextObject); 4]>::new();
+        iter::from_fn(move || loop {
+            if let Some(capture) = captures.pop() {
+                return Some(capture);
+            }
+
+            let mat = syntax_matches.peek()?;
+            if let Some(config) = text_object_configs[mat.grammar_index] {
+                for capture in mat.captures {
+                    let Some(text_object) = config
+                        .text_objects_by_capture_ix
+                        .iter()
+                        .find(|(ix, _)| *ix == capture.index)
+                        .map(|(_, text_object)| *text_object)
+                    else {
+                        continue;
+                    };
+                    let range = capture.node.byte_range();
+                    if let Some((existing, _)) = captures
+                        .iter_mut()
+                        .find(|(_, existing)| *existing == text_object)
+                    {
+                        existing.start = existing.start.min(range.start);
+                        existing.end = existing.end.max(range.end);
+                    } else {
+                        captures.push((range, text_object));
+                    }
+                }
+            }
+            syntax_matches.advance();
+        })
+    }
+
     /// Returns selections for remote peers intersecting the given range.
     #[allow(clippy::type_complexity)]
     pub fn remote_selections_in_range(
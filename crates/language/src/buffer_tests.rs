@@ -577,6 +577,41 @@ async fn test_reparse(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_deferred_reparsing(cx: &mut gpui::TestAppContext) {
+    let text = "fn a() {}";
+    let buffer =
+        cx.new_model(|cx| Buffer::local(text, cx).with_language(Arc::new(rust_lang()), cx));
+    cx.executor().run_until_parked();
+
+    buffer.update(cx, |buffer, cx| {
+        buffer.set_reparsing_deferred(true, cx);
+        let offset = buffer.text().find(')').unwrap();
+        buffer.edit([(offset..offset, "b: C")], None, cx);
+        assert!(!buffer.is_parsing());
+    });
+    cx.executor().run_until_parked();
+    assert_eq!(
+        get_tree_sexp(&buffer, cx),
+        concat!(
+            "(source_file (function_item name: (identifier) ",
+            "parameters: (parameters) ",
+            "body: (block)))"
+        )
+    );
+
+    buffer.update(cx, |buffer, cx| buffer.set_reparsing_deferred(false, cx));
+    cx.executor().run_until_parked();
+    assert_eq!(
+        get_tree_sexp(&buffer, cx),
+        concat!(
+            "(source_file (function_item name: (identifier) ",
+            "parameters: (parameters (parameter pattern: (identifier) type: (type_identifier))) ",
+            "body: (block)))"
+        )
+    );
+}
+
 #[gpui::test]
 async fn test_resetting_language(cx: &mut gpui::TestAppContext) {
     let buffer = cx.new_model(|cx| {
@@ -599,6 +634,43 @@ async fn test_resetting_language(cx: &mut gpui::TestAppContext) {
     assert_eq!(get_tree_sexp(&buffer, cx), "(document (object))");
 }
 
+#[gpui::test]
+fn test_text_object_ranges(cx: &mut AppContext) {
+    let language = rust_lang()
+        .with_text_object_query(
+            r#"
+            (function_item
+                body: (_
+                    "{"
+                    (_)* @function.inside
+                    "}")) @function.around
+
+            (parameters
+                (_) @parameter.inside)
+            "#,
+        )
+        .unwrap();
+    let text = "fn a(b: u32, c: u32) { x(); y(); }";
+    let buffer = cx.new_model(|cx| Buffer::local(text, cx).with_language(Arc::new(language), cx));
+    let snapshot = buffer.read(cx).snapshot();
+
+    let mut ranges = snapshot
+        .text_object_ranges(0..text.len())
+        .map(|(range, text_object)| (&text[range], text_object))
+        .collect::<Vec<_>>();
+    ranges.sort_by_key(|(text, _)| *text);
+    assert_eq!(
+        ranges,
+        &[
+            ("b: u32", TextObject::InsideArgument),
+            ("c: u32", TextObject::InsideArgument),
+            (text, TextObject::AroundFunction),
+            // The statements in the body are joined into one range.
+            ("x(); y();", TextObject::InsideFunction),
+        ]
+    );
+}
+
 #[gpui::test]
 async fn test_outline(cx: &mut gpui::TestAppContext) {
     let text = r#"
@@ -1048,6 +1120,41 @@ fn test_range_for_syntax_ancestor(cx: &mut AppContext) {
     }
 }
 
+#[gpui::test]
+fn test_highlight_captures_at(cx: &mut AppContext) {
+    let language = Language::new(
+        LanguageConfig {
+            name: "Rust".into(),
+            ..Default::default()
+        },
+        Some(tree_sitter_rust::language()),
+    )
+    .with_highlights_query(
+        r#"
+        (function_item) @item
+        (function_item name: (identifier) @function)
+        "#,
+    )
+    .unwrap();
+
+    cx.new_model(|cx| {
+        let text = "fn a() {}";
+        let buffer = Buffer::local(text, cx).with_language(Arc::new(language), cx);
+        let snapshot = buffer.snapshot();
+
+        assert_eq!(
+            snapshot.highlight_captures_at(text.find('a').unwrap()),
+            ["item", "function"]
+        );
+        assert_eq!(
+            snapshot.highlight_captures_at(text.find('{').unwrap()),
+            ["item"]
+        );
+
+        buffer
+    });
+}
+
 #[gpui::test]
 fn test_autoindent_with_soft_tabs(cx: &mut AppContext) {
     init_settings(cx, |_| {});
@@ -2011,6 +2118,55 @@ fn test_language_scope_at_with_combined_injections(cx: &mut AppContext) {
     });
 }
 
+#[gpui::test]
+fn test_injections(cx: &mut AppContext) {
+    init_settings(cx, |_| {});
+
+    cx.new_model(|cx| {
+        let text = r#"
+            <div>
+            <script>
+            let a = 1;
+            </script>
+            </div>
+        "#
+        .unindent();
+
+        let language_registry = Arc::new(LanguageRegistry::test(cx.background_executor().clone()));
+        language_registry.add(Arc::new(html_lang()));
+        language_registry.add(Arc::new(javascript_lang()));
+
+        let mut buffer = Buffer::local(text, cx);
+        buffer.set_language_registry(language_registry.clone());
+        buffer.set_language(
+            language_registry
+                .language_for_name("HTML")
+                .now_or_never()
+                .unwrap()
+                .ok(),
+            cx,
+        );
+
+        let snapshot = buffer.snapshot();
+        let injections = snapshot
+            .injections()
+            .into_iter()
+            .map(|(range, language)| {
+                (
+                    snapshot.text_for_range(range).collect::<String>(),
+                    language.name(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            injections,
+            [("\nlet a = 1;\n".to_string(), Arc::from("JavaScript"))]
+        );
+
+        buffer
+    });
+}
+
 #[gpui::test]
 fn test_serialization(cx: &mut gpui::AppContext) {
     let mut now = Instant::now();
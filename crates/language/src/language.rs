@@ -272,6 +272,11 @@ pub trait LspAdapterDelegate: Send + Sync {
     fn worktree_id(&self) -> u64;
     fn worktree_root_path(&self) -> &Path;
     fn update_status(&self, language: LanguageServerName, status: LanguageServerBinaryStatus);
+    /// Whether the downloaded version of the language server is to be kept, rather than checking
+    /// for a newer one.
+    fn is_pinned(&self, server_name: &LanguageServerName) -> bool;
+    /// The binary that's set to be run for the language server instead of a downloaded one.
+    fn configured_binary(&self, server_name: &LanguageServerName) -> Option<LanguageServerBinary>;
 
     async fn which(&self, command: &OsStr) -> Option<PathBuf>;
     async fn shell_env(&self) -> HashMap<String, String>;
@@ -312,10 +317,36 @@ pub trait LspAdapter: 'static + Send + Sync {
                 return Ok(binary);
             }
 
+            // Adapters that don't look for the binary in the settings themselves still run the one
+            // that's set there.
+            if let Some(binary) = delegate.configured_binary(&self.name()) {
+                log::info!(
+                    "using configured language server for {}. path: {:?}",
+                    language.name(),
+                    binary.path
+                );
+                return Ok(binary);
+            }
+
             if let Some(cached_binary) = cached_binary.as_ref() {
                 return Ok(cached_binary.clone());
             }
 
+            if delegate.is_pinned(&self.name()) {
+                if let Some(pinned_binary) = self
+                    .cached_server_binary(container_dir.to_path_buf(), delegate.as_ref())
+                    .await
+                {
+                    log::info!(
+                        "using pinned version of language server {:?}. path: {:?}",
+                        self.name(),
+                        pinned_binary.path
+                    );
+                    *cached_binary = Some(pinned_binary.clone());
+                    return Ok(pinned_binary);
+                }
+            }
+
             if !container_dir.exists() {
                 smol::fs::create_dir_all(&container_dir)
                     .await
@@ -592,6 +623,33 @@ pub struct LanguageConfig {
     /// Starting and closing characters of a block comment.
     #[serde(default)]
     pub block_comment: Option<(Arc<str>, Arc<str>)>,
+    /// A prefix that is inserted after the indentation of each new line typed within a block
+    /// comment, e.g. `"* "` for `/* */` comments. Block comments are not continued if unset.
+    #[serde(default)]
+    pub block_comment_continuation: Option<Arc<str>>,
+    /// Whether to insert a closing tag when an opening tag is typed, and to keep the names
+    /// of matching opening and closing tags in sync, as in HTML or JSX.
+    #[serde(default)]
+    pub auto_close_tags: bool,
+    /// Names of elements that never have a closing tag, like `br` or `img` in HTML.
+    #[serde(default)]
+    pub void_elements: Vec<String>,
+    /// Kinds of syntax nodes that are opening tags, like `start_tag` in HTML. Closing tags are
+    /// only inserted after a `>` that completes one of these nodes.
+    #[serde(default)]
+    pub opening_tag_node_kinds: Vec<String>,
+    /// Whether to start new lines typed in Markdown list items and block quotes with the same
+    /// markers, and to end the list or quote when a new line is typed in an empty one.
+    #[serde(default)]
+    pub continue_lists: bool,
+    /// The character that's repeated at the start of a line to make it a heading of that level,
+    /// like `#` in Markdown or `*` in Org, so that the sections below headings can be folded.
+    #[serde(default)]
+    pub heading_marker: Option<char>,
+    /// Whether documents link to each other with Markdown's `[text](path#heading)` links and
+    /// `[[name]]` wiki-links, which can then be followed, completed and listed as backlinks.
+    #[serde(default)]
+    pub document_links: bool,
     /// A list of language servers that are allowed to run on subranges of a given language.
     #[serde(default)]
     pub scope_opt_in_language_servers: Vec<String>,
@@ -690,6 +748,13 @@ impl Default for LanguageConfig {
             autoclose_before: Default::default(),
             line_comments: Default::default(),
             block_comment: Default::default(),
+            block_comment_continuation: Default::default(),
+            auto_close_tags: Default::default(),
+            void_elements: Default::default(),
+            opening_tag_node_kinds: Default::default(),
+            continue_lists: Default::default(),
+            heading_marker: Default::default(),
+            document_links: Default::default(),
             scope_opt_in_language_servers: Default::default(),
             overrides: Default::default(),
             word_characters: Default::default(),
@@ -838,6 +903,7 @@ pub struct Grammar {
     pub(crate) brackets_config: Option<BracketConfig>,
     pub(crate) redactions_config: Option<RedactionConfig>,
     pub(crate) runnable_config: Option<RunnableConfig>,
+    pub(crate) text_object_config: Option<TextObjectConfig>,
     pub(crate) indents_config: Option<IndentConfig>,
     pub outline_config: Option<OutlineConfig>,
     pub embedding_config: Option<EmbeddingConfig>,
@@ -892,6 +958,53 @@ struct RunnableConfig {
     pub run_capture_ix: u32,
 }
 
+struct TextObjectConfig {
+    query: Query,
+    text_objects_by_capture_ix: Vec<(u32, TextObject)>,
+}
+
+/// A kind of range that can be selected as a whole, as captured by a language's
+/// `textobjects.scm` query with names like `@function.around` and `@function.inside`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TextObject {
+    InsideFunction,
+    AroundFunction,
+    InsideClass,
+    AroundClass,
+    InsideArgument,
+    AroundArgument,
+    InsideComment,
+    AroundComment,
+}
+
+impl TextObject {
+    pub fn from_capture_name(name: &str) -> Option<TextObject> {
+        match name {
+            "function.inside" => Some(TextObject::InsideFunction),
+            "function.around" => Some(TextObject::AroundFunction),
+            "class.inside" => Some(TextObject::InsideClass),
+            "class.around" => Some(TextObject::AroundClass),
+            "parameter.inside" => Some(TextObject::InsideArgument),
+            "parameter.around" => Some(TextObject::AroundArgument),
+            "comment.inside" => Some(TextObject::InsideComment),
+            "comment.around" => Some(TextObject::AroundComment),
+            _ => None,
+        }
+    }
+
+    /// The text object that includes this one's surroundings, such as a function's signature
+    /// around its body.
+    pub fn around(self) -> TextObject {
+        match self {
+            TextObject::InsideFunction => TextObject::AroundFunction,
+            TextObject::InsideClass => TextObject::AroundClass,
+            TextObject::InsideArgument => TextObject::AroundArgument,
+            TextObject::InsideComment => TextObject::AroundComment,
+            _ => self,
+        }
+    }
+}
+
 struct OverrideConfig {
     query: Query,
     values: HashMap<u32, (String, LanguageConfigOverride)>,
@@ -934,6 +1047,7 @@ impl Language {
                     override_config: None,
                     redactions_config: None,
                     runnable_config: None,
+                    text_object_config: None,
                     error_query: Query::new(&ts_language, "(ERROR) @error").unwrap(),
                     ts_language,
                     highlight_map: Default::default(),
@@ -994,6 +1108,11 @@ impl Language {
                 .with_runnable_query(query.as_ref())
                 .context("Error loading tests query")?;
         }
+        if let Some(query) = queries.text_objects {
+            self = self
+                .with_text_object_query(query.as_ref())
+                .context("Error loading textobject query")?;
+        }
         Ok(self)
     }
 
@@ -1032,6 +1151,27 @@ impl Language {
         Ok(self)
     }
 
+    pub fn with_text_object_query(mut self, source: &str) -> Result<Self> {
+        let grammar = self
+            .grammar_mut()
+            .ok_or_else(|| anyhow!("cannot mutate grammar"))?;
+
+        let query = Query::new(&grammar.ts_language, source)?;
+        let text_objects_by_capture_ix = query
+            .capture_names()
+            .iter()
+            .enumerate()
+            .filter_map(|(ix, name)| Some((ix as u32, TextObject::from_capture_name(name)?)))
+            .collect::<Vec<_>>();
+        if !text_objects_by_capture_ix.is_empty() {
+            grammar.text_object_config = Some(TextObjectConfig {
+                query,
+                text_objects_by_capture_ix,
+            });
+        }
+        Ok(self)
+    }
+
     pub fn with_outline_query(mut self, source: &str) -> Result<Self> {
         let grammar = self
             .grammar_mut()
@@ -1397,6 +1537,43 @@ impl LanguageScope {
         .map(|e| (&e.0, &e.1))
     }
 
+    /// Returns the prefix that is inserted on new lines typed within a block comment.
+    pub fn block_comment_continuation(&self) -> Option<&Arc<str>> {
+        self.block_comment_delimiters()?;
+        self.language.config.block_comment_continuation.as_ref()
+    }
+
+    /// Returns whether opening tags typed in this scope should be closed automatically, and
+    /// matching tags renamed together.
+    pub fn auto_close_tags(&self) -> bool {
+        self.language.config.auto_close_tags
+    }
+
+    /// Returns the names of elements that have no closing tag.
+    pub fn void_elements(&self) -> &[String] {
+        &self.language.config.void_elements
+    }
+
+    /// Returns the kinds of syntax nodes that are opening tags.
+    pub fn opening_tag_node_kinds(&self) -> &[String] {
+        &self.language.config.opening_tag_node_kinds
+    }
+
+    /// Returns whether Markdown list items and block quotes are continued on new lines.
+    pub fn continue_lists(&self) -> bool {
+        self.language.config.continue_lists
+    }
+
+    /// Returns the character that's repeated at the start of headings, if the language has them.
+    pub fn heading_marker(&self) -> Option<char> {
+        self.language.config.heading_marker
+    }
+
+    /// Returns whether the language's documents link to each other, as Markdown's do.
+    pub fn document_links(&self) -> bool {
+        self.language.config.document_links
+    }
+
     /// Returns a list of language-specific word characters.
     ///
     /// By default, Zed treats alphanumeric characters (and '_') as word characters for
@@ -1451,6 +1628,14 @@ impl LanguageScope {
         }
     }
 
+    /// Returns the name of the override (e.g. `"comment"` or `"string"`) that applies to this scope, if any.
+    pub fn override_name(&self) -> Option<&str> {
+        let id = self.override_id?;
+        let grammar = self.language.grammar.as_ref()?;
+        let override_config = grammar.override_config.as_ref()?;
+        override_config.values.get(&id).map(|e| e.0.as_str())
+    }
+
     fn config_override(&self) -> Option<&LanguageConfigOverride> {
         let id = self.override_id?;
         let grammar = self.language.grammar.as_ref()?;
@@ -126,6 +126,7 @@ pub const QUERY_FILENAME_PREFIXES: &[(
     ("overrides", |q| &mut q.overrides),
     ("redactions", |q| &mut q.redactions),
     ("runnables", |q| &mut q.runnables),
+    ("textobjects", |q| &mut q.text_objects),
 ];
 
 /// Tree-sitter language queries for a given language.
@@ -140,6 +141,7 @@ pub struct LanguageQueries {
     pub overrides: Option<Cow<'static, str>>,
     pub redactions: Option<Cow<'static, str>>,
     pub runnables: Option<Cow<'static, str>>,
+    pub text_objects: Option<Cow<'static, str>>,
 }
 
 #[derive(Clone, Default)]
@@ -446,6 +448,11 @@ impl LanguageRegistry {
         self.language_server_download_dir = Some(path.into());
     }
 
+    /// The directory that language servers are downloaded into, each to a directory named after it.
+    pub fn language_server_download_dir(&self) -> Option<Arc<Path>> {
+        self.language_server_download_dir.clone()
+    }
+
     pub fn language_for_name(
         self: &Arc<Self>,
         name: &str,
@@ -862,7 +869,7 @@ impl LanguageRegistry {
 
     pub fn delete_server_container(
         &self,
-        adapter: Arc<CachedLspAdapter>,
+        server_name: LanguageServerName,
         cx: &mut AppContext,
     ) -> Task<()> {
         log::info!("deleting server container");
@@ -873,7 +880,7 @@ impl LanguageRegistry {
             .expect("language server download directory has not been assigned before deleting server container");
 
         cx.spawn(|_| async move {
-            let container_dir = download_dir.join(adapter.name.0.as_ref());
+            let container_dir = download_dir.join(server_name.0.as_ref());
             smol::fs::remove_dir_all(container_dir)
                 .await
                 .context("server container removal")
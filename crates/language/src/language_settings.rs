@@ -13,7 +13,7 @@ use schemars::{
 use serde::{Deserialize, Serialize};
 use settings::{Settings, SettingsLocation, SettingsSources};
 use std::{num::NonZeroU32, path::Path, sync::Arc};
-use util::serde::default_true;
+use util::{paths::PathMatcher, serde::default_true, ResultExt};
 
 impl<'a> Into<SettingsLocation<'a>> for &'a dyn File {
     fn into(self) -> SettingsLocation<'a> {
@@ -48,6 +48,17 @@ pub fn all_language_settings<'a>(
     AllLanguageSettings::get(location, cx)
 }
 
+/// Returns whether the contents of the provided file, in the specified language, may be attached
+/// to the assistant's context.
+pub fn assistant_context_enabled(
+    language: Option<&Arc<Language>>,
+    file: Option<&Arc<dyn File>>,
+    cx: &AppContext,
+) -> bool {
+    all_language_settings(file, cx)
+        .assistant_context_enabled(language, file.map(|file| file.path().as_ref()))
+}
+
 /// The settings for all languages.
 #[derive(Debug, Clone)]
 pub struct AllLanguageSettings {
@@ -56,6 +67,10 @@ pub struct AllLanguageSettings {
     defaults: LanguageSettings,
     languages: HashMap<Arc<str>, LanguageSettings>,
     pub(crate) file_types: HashMap<Arc<str>, GlobSet>,
+    /// The files that aren't formatted or cleaned up when they're saved.
+    pub format_on_save_exclusions: Vec<PathMatcher>,
+    /// The files whose contents are never attached to the assistant's context.
+    pub assistant_context_exclusions: Vec<PathMatcher>,
 }
 
 /// The settings for a particular language.
@@ -86,6 +101,8 @@ pub struct LanguageSettings {
     /// Whether or not to ensure there's a single newline at the end of a buffer
     /// when saving it.
     pub ensure_final_newline_on_save: bool,
+    /// Whether or not to align the columns of Markdown tables when saving a buffer.
+    pub format_tables_on_save: bool,
     /// How to perform a buffer format.
     pub formatter: Formatter,
     /// Zed's Prettier integration settings.
@@ -102,6 +119,9 @@ pub struct LanguageSettings {
     /// Controls whether inline completions are shown immediately (true)
     /// or manually by triggering `editor::ShowInlineCompletion` (false).
     pub show_inline_completions: bool,
+    /// Whether the contents of files in this language may be attached to the
+    /// assistant's context.
+    pub enable_assistant_context: bool,
     /// Whether to show tabs and spaces in the editor.
     pub show_whitespaces: ShowWhitespaceSetting,
     /// Whether to start a new line with a comment when a previous line is a comment as well.
@@ -173,6 +193,17 @@ pub enum InlineCompletionProvider {
     Supermaven,
 }
 
+impl InlineCompletionProvider {
+    /// The name identifying the provider in the `providers` inline completion settings.
+    pub fn name(&self) -> Option<&'static str> {
+        match self {
+            InlineCompletionProvider::None => None,
+            InlineCompletionProvider::Copilot => Some("copilot"),
+            InlineCompletionProvider::Supermaven => Some("supermaven"),
+        }
+    }
+}
+
 /// The settings for inline completions, such as [GitHub Copilot](https://github.com/features/copilot)
 /// or [Supermaven](https://supermaven.com).
 #[derive(Clone, Debug, Default)]
@@ -181,6 +212,44 @@ pub struct InlineCompletionSettings {
     pub provider: InlineCompletionProvider,
     /// A list of globs representing files that inline completions should be disabled for.
     pub disabled_globs: Vec<GlobMatcher>,
+    /// Settings for individual inline completion providers, keyed by provider name.
+    pub providers: HashMap<String, InlineCompletionProviderSettings>,
+}
+
+impl InlineCompletionSettings {
+    /// Returns whether the provider with the given name may supply inline completions.
+    /// The provider selected by `features.inline_completion_provider` is enabled unless
+    /// it's disabled in `providers`, and other providers must be enabled there.
+    pub fn is_provider_enabled(&self, name: &str) -> bool {
+        self.providers
+            .get(name)
+            .and_then(|settings| settings.enabled)
+            .unwrap_or_else(|| self.provider.name() == Some(name))
+    }
+
+    /// Returns the priority of the provider with the given name. When several providers
+    /// have inline completions, the one with the highest priority is shown.
+    pub fn provider_priority(&self, name: &str) -> i32 {
+        self.providers
+            .get(name)
+            .and_then(|settings| settings.priority)
+            .unwrap_or(0)
+    }
+}
+
+/// The settings for an individual inline completion provider.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct InlineCompletionProviderSettings {
+    /// Whether the provider supplies inline completions.
+    ///
+    /// Default: true for the provider selected by `features.inline_completion_provider`,
+    /// false for others
+    pub enabled: Option<bool>,
+    /// When several providers have inline completions, the one with the highest priority
+    /// is shown.
+    ///
+    /// Default: 0
+    pub priority: Option<i32>,
 }
 
 /// The settings for all languages.
@@ -202,6 +271,20 @@ pub struct AllLanguageSettingsContent {
     /// with languages.
     #[serde(default)]
     pub file_types: HashMap<Arc<str>, Vec<String>>,
+    /// Globs matching the files that aren't formatted, and don't have trailing whitespace
+    /// removed or a final newline added, when they're saved, such as vendored or generated
+    /// code. They still are when formatted explicitly.
+    ///
+    /// Default: []
+    #[serde(default)]
+    pub format_on_save_exclusions: Option<Vec<String>>,
+    /// Globs matching the files whose contents are never attached to the assistant's context,
+    /// whether by the assistant itself or by its tools, such as files with credentials or code
+    /// that mustn't leave the machine.
+    ///
+    /// Default: []
+    #[serde(default)]
+    pub assistant_context_exclusions: Option<Vec<String>>,
 }
 
 /// The settings for a particular language.
@@ -259,6 +342,11 @@ pub struct LanguageSettingsContent {
     /// Default: true
     #[serde(default)]
     pub ensure_final_newline_on_save: Option<bool>,
+    /// Whether or not to align the columns of Markdown tables when saving a buffer.
+    ///
+    /// Default: false
+    #[serde(default)]
+    pub format_tables_on_save: Option<bool>,
     /// How to perform a buffer format.
     ///
     /// Default: auto
@@ -292,6 +380,12 @@ pub struct LanguageSettingsContent {
     /// Default: true
     #[serde(default, alias = "show_copilot_suggestions")]
     pub show_inline_completions: Option<bool>,
+    /// Whether the contents of files in this language may be attached to the
+    /// assistant's context.
+    ///
+    /// Default: true
+    #[serde(default)]
+    pub enable_assistant_context: Option<bool>,
     /// Whether to show tabs and spaces in the editor.
     #[serde(default)]
     pub show_whitespaces: Option<ShowWhitespaceSetting>,
@@ -329,6 +423,9 @@ pub struct InlineCompletionSettingsContent {
     /// A list of globs representing files that inline completions should be disabled for.
     #[serde(default)]
     pub disabled_globs: Option<Vec<String>>,
+    /// Settings for individual inline completion providers, keyed by provider name.
+    #[serde(default)]
+    pub providers: Option<HashMap<String, InlineCompletionProviderSettings>>,
 }
 
 /// The settings for enabling/disabling features.
@@ -386,6 +483,10 @@ pub enum ShowWhitespaceSetting {
     None,
     /// Draw all invisible symbols.
     All,
+    /// Draw whitespace only at the start and end of lines.
+    Boundary,
+    /// Draw whitespace only at the end of lines.
+    Trailing,
 }
 
 /// Controls which formatter should be used when formatting code.
@@ -448,6 +549,17 @@ pub struct InlayHintSettings {
     /// Default: 50
     #[serde(default = "scroll_debounce_ms")]
     pub scroll_debounce_ms: u64,
+    /// Whether to pad hints with spaces where the language server asks for it.
+    ///
+    /// Default: true
+    #[serde(default = "default_true")]
+    pub show_padding: bool,
+    /// The maximum number of characters to show for each hint. Longer hints are
+    /// truncated with an ellipsis, and shown in full when hovered.
+    ///
+    /// Default: null
+    #[serde(default)]
+    pub max_length: Option<usize>,
 }
 
 fn edit_debounce_ms() -> u64 {
@@ -495,6 +607,14 @@ impl AllLanguageSettings {
             .any(|glob| glob.is_match(path))
     }
 
+    /// Returns whether the file at the given path is formatted and cleaned up when it's saved.
+    pub fn format_on_save_enabled_for_path(&self, path: &Path) -> bool {
+        !self
+            .format_on_save_exclusions
+            .iter()
+            .any(|matcher| matcher.is_match(path))
+    }
+
     /// Returns whether inline completions are enabled for the given language and path.
     pub fn inline_completions_enabled(
         &self,
@@ -510,6 +630,32 @@ impl AllLanguageSettings {
         self.language(language.map(|l| l.name()).as_deref())
             .show_inline_completions
     }
+
+    /// Returns whether the contents of the file at the given path may be attached to the
+    /// assistant's context.
+    pub fn assistant_context_enabled_for_path(&self, path: &Path) -> bool {
+        !self
+            .assistant_context_exclusions
+            .iter()
+            .any(|matcher| matcher.is_match(path))
+    }
+
+    /// Returns whether the contents of files in the given language, at the given path, may be
+    /// attached to the assistant's context.
+    pub fn assistant_context_enabled(
+        &self,
+        language: Option<&Arc<Language>>,
+        path: Option<&Path>,
+    ) -> bool {
+        if let Some(path) = path {
+            if !self.assistant_context_enabled_for_path(path) {
+                return false;
+            }
+        }
+
+        self.language(language.map(|l| l.name()).as_deref())
+            .enable_assistant_context
+    }
 }
 
 /// The kind of an inlay hint.
@@ -572,6 +718,19 @@ impl settings::Settings for AllLanguageSettings {
             .as_ref()
             .and_then(|c| c.disabled_globs.as_ref())
             .ok_or_else(Self::missing_default)?;
+        let mut completion_providers = default_value
+            .inline_completions
+            .as_ref()
+            .and_then(|c| c.providers.clone())
+            .unwrap_or_default();
+        let mut format_on_save_exclusions = default_value
+            .format_on_save_exclusions
+            .as_ref()
+            .ok_or_else(Self::missing_default)?;
+        let mut assistant_context_exclusions = default_value
+            .assistant_context_exclusions
+            .as_ref()
+            .ok_or_else(Self::missing_default)?;
 
         let mut file_types: HashMap<Arc<str>, GlobSet> = HashMap::default();
         for user_settings in sources.customizations() {
@@ -592,6 +751,23 @@ impl settings::Settings for AllLanguageSettings {
             {
                 completion_globs = globs;
             }
+            if let Some(exclusions) = user_settings.format_on_save_exclusions.as_ref() {
+                format_on_save_exclusions = exclusions;
+            }
+            if let Some(exclusions) = user_settings.assistant_context_exclusions.as_ref() {
+                assistant_context_exclusions = exclusions;
+            }
+            if let Some(providers) = user_settings
+                .inline_completions
+                .as_ref()
+                .and_then(|f| f.providers.as_ref())
+            {
+                for (name, provider_settings) in providers {
+                    let merged = completion_providers.entry(name.clone()).or_default();
+                    merged.enabled = provider_settings.enabled.or(merged.enabled);
+                    merged.priority = provider_settings.priority.or(merged.priority);
+                }
+            }
 
             // A user's global settings override the default global settings and
             // all default language-specific settings.
@@ -634,10 +810,19 @@ impl settings::Settings for AllLanguageSettings {
                     .iter()
                     .filter_map(|g| Some(globset::Glob::new(g).ok()?.compile_matcher()))
                     .collect(),
+                providers: completion_providers,
             },
             defaults,
             languages,
             file_types,
+            format_on_save_exclusions: format_on_save_exclusions
+                .iter()
+                .filter_map(|glob| PathMatcher::new(glob).log_err())
+                .collect(),
+            assistant_context_exclusions: assistant_context_exclusions
+                .iter()
+                .filter_map(|glob| PathMatcher::new(glob).log_err())
+                .collect(),
         })
     }
 
@@ -735,6 +920,10 @@ fn merge_settings(settings: &mut LanguageSettings, src: &LanguageSettingsContent
         &mut settings.ensure_final_newline_on_save,
         src.ensure_final_newline_on_save,
     );
+    merge(
+        &mut settings.format_tables_on_save,
+        src.format_tables_on_save,
+    );
     merge(
         &mut settings.enable_language_server,
         src.enable_language_server,
@@ -744,6 +933,10 @@ fn merge_settings(settings: &mut LanguageSettings, src: &LanguageSettingsContent
         &mut settings.show_inline_completions,
         src.show_inline_completions,
     );
+    merge(
+        &mut settings.enable_assistant_context,
+        src.enable_assistant_context,
+    );
     merge(&mut settings.show_whitespaces, src.show_whitespaces);
     merge(
         &mut settings.extend_comment_on_newline,
@@ -5,7 +5,7 @@ use std::{ops::Range, path::PathBuf};
 
 use crate::{HighlightId, Language, LanguageRegistry};
 use gpui::{px, FontStyle, FontWeight, HighlightStyle, StrikethroughStyle, UnderlineStyle};
-use pulldown_cmark::{CodeBlockKind, Event, Parser, Tag, TagEnd};
+use pulldown_cmark::{Alignment, CodeBlockKind, Event, Options, Parser, Tag, TagEnd};
 
 /// Parsed Markdown content.
 #[derive(Debug, Clone, Default)]
@@ -361,8 +361,243 @@ pub fn new_paragraph(text: &mut String, list_stack: &mut Vec<(Option<u64>, bool)
     }
 }
 
+/// The markers that start a line of a Markdown list item or block quote, like `> - [x] `.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineMarkers {
+    /// The length of the markers, including the indentation before them.
+    pub len: usize,
+    /// The markers that start the next line of the item, after its indentation: the next number
+    /// of an ordered list, and an unchecked task box for a task list item.
+    pub continuation: String,
+    /// The offset of the character in the item's task box, which is a space when unchecked.
+    pub checkbox: Option<usize>,
+}
+
+/// Returns the list item and block quote markers that the line starts with, if any.
+pub fn line_markers(line: &str) -> Option<LineMarkers> {
+    let mut rest = line.trim_start();
+    let mut continuation = String::new();
+    let mut has_markers = false;
+    let mut is_list_item = false;
+    while let Some(quoted) = rest.strip_prefix('>') {
+        let quoted = quoted.trim_start_matches(' ');
+        continuation.push_str(&rest[..rest.len() - quoted.len()]);
+        if !continuation.ends_with(' ') {
+            continuation.push(' ');
+        }
+        rest = quoted;
+        has_markers = true;
+    }
+
+    let digits_len = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    if let Some(item) = ["- ", "* ", "+ "]
+        .iter()
+        .find_map(|bullet| rest.strip_prefix(bullet))
+    {
+        continuation.push_str(&rest[..2]);
+        rest = item;
+        has_markers = true;
+        is_list_item = true;
+    } else if (1..=9).contains(&digits_len) {
+        let delimiter = &rest[digits_len..];
+        if delimiter.starts_with(". ") || delimiter.starts_with(") ") {
+            let number = rest[..digits_len].parse::<u32>().ok()?;
+            continuation.push_str(&format!("{}{}", number + 1, &delimiter[..2]));
+            rest = &delimiter[2..];
+            has_markers = true;
+            is_list_item = true;
+        }
+    }
+
+    let mut checkbox = None;
+    if is_list_item {
+        for task in ["[ ]", "[x]", "[X]"] {
+            if let Some(item) = rest.strip_prefix(task) {
+                if item.is_empty() || item.starts_with(' ') {
+                    checkbox = Some(line.len() - rest.len() + 1);
+                    continuation.push_str("[ ] ");
+                    rest = item.strip_prefix(' ').unwrap_or(item);
+                    break;
+                }
+            }
+        }
+    }
+
+    has_markers.then(|| LineMarkers {
+        len: line.len() - rest.len(),
+        continuation,
+        checkbox,
+    })
+}
+
+/// Returns the edits that align the columns of the Markdown tables in the text, padding their
+/// cells to the widths of their columns. Tables in block quotes are left as they are.
+pub fn align_tables(text: &str) -> Vec<(Range<usize>, String)> {
+    let mut edits = Vec::new();
+    let mut table: Option<(usize, Vec<Alignment>, Vec<Vec<&str>>)> = None;
+    for (event, range) in Parser::new_ext(text, Options::ENABLE_TABLES).into_offset_iter() {
+        match event {
+            Event::Start(Tag::Table(alignments)) => {
+                table = Some((range.start, alignments, Vec::new()));
+            }
+            Event::Start(Tag::TableHead | Tag::TableRow) => {
+                if let Some((_, _, rows)) = &mut table {
+                    rows.push(Vec::new());
+                }
+            }
+            Event::Start(Tag::TableCell) => {
+                if let Some(row) = table.as_mut().and_then(|(_, _, rows)| rows.last_mut()) {
+                    row.push(text[range].trim());
+                }
+            }
+            Event::End(TagEnd::Table) => {
+                if let Some((start, alignments, rows)) = table.take() {
+                    edits.extend(aligned_table(text, start..range.end, &alignments, &rows));
+                }
+            }
+            _ => {}
+        }
+    }
+    edits
+}
+
+fn aligned_table(
+    text: &str,
+    range: Range<usize>,
+    alignments: &[Alignment],
+    rows: &[Vec<&str>],
+) -> Option<(Range<usize>, String)> {
+    let line_start = text[..range.start].rfind('\n').map_or(0, |ix| ix + 1);
+    let indent = &text[line_start..range.start];
+    let range = range.start..range.start + text[range].trim_end().len();
+    let old_text = &text[range.clone()];
+    if !indent.chars().all(char::is_whitespace)
+        || old_text
+            .lines()
+            .any(|line| line.trim_start().starts_with('>'))
+    {
+        return None;
+    }
+
+    let widths = (0..alignments.len())
+        .map(|column| {
+            rows.iter()
+                .filter_map(|row| row.get(column))
+                .map(|cell| cell.chars().count())
+                .max()
+                .unwrap_or_default()
+                .max(3)
+        })
+        .collect::<Vec<_>>();
+    let mut lines = Vec::with_capacity(rows.len() + 1);
+    for (ix, row) in rows.iter().enumerate() {
+        let cells =
+            alignments
+                .iter()
+                .zip(&widths)
+                .enumerate()
+                .map(|(column, (alignment, width))| {
+                    pad_cell(
+                        row.get(column).copied().unwrap_or_default(),
+                        *width,
+                        *alignment,
+                    )
+                });
+        lines.push(format!("| {} |", cells.collect::<Vec<_>>().join(" | ")));
+        if ix == 0 {
+            let delimiters =
+                alignments
+                    .iter()
+                    .zip(&widths)
+                    .map(|(alignment, width)| match alignment {
+                        Alignment::None => "-".repeat(*width),
+                        Alignment::Left => format!(":{}", "-".repeat(width - 1)),
+                        Alignment::Right => format!("{}:", "-".repeat(width - 1)),
+                        Alignment::Center => format!(":{}:", "-".repeat(width - 2)),
+                    });
+            lines.push(format!(
+                "| {} |",
+                delimiters.collect::<Vec<_>>().join(" | ")
+            ));
+        }
+    }
+    let new_text = lines.join(&format!("\n{indent}"));
+
+    // Cells that a row has beyond the table's columns aren't part of the table, so a table
+    // with any of those isn't aligned, to not lose their text.
+    let content = |text: &str| {
+        let mut chars = text
+            .chars()
+            .filter(|c| !c.is_whitespace() && !matches!(c, '|' | '-' | ':'))
+            .collect::<Vec<_>>();
+        chars.sort_unstable();
+        chars
+    };
+    (new_text != old_text && content(&new_text) == content(old_text)).then(|| (range, new_text))
+}
+
+fn pad_cell(cell: &str, width: usize, alignment: Alignment) -> String {
+    let padding = width.saturating_sub(cell.chars().count());
+    match alignment {
+        Alignment::Right => format!("{}{cell}", " ".repeat(padding)),
+        Alignment::Center => format!(
+            "{}{cell}{}",
+            " ".repeat(padding / 2),
+            " ".repeat(padding - padding / 2)
+        ),
+        Alignment::None | Alignment::Left => format!("{cell}{}", " ".repeat(padding)),
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_markers() {
+        let markers = |line: &str| {
+            line_markers(line).map(|markers| (markers.len, markers.continuation, markers.checkbox))
+        };
+        assert_eq!(markers("- item"), Some((2, "- ".into(), None)));
+        assert_eq!(markers("  * item"), Some((4, "* ".into(), None)));
+        assert_eq!(markers("9. item"), Some((3, "10. ".into(), None)));
+        assert_eq!(markers("1) item"), Some((3, "2) ".into(), None)));
+        assert_eq!(markers("> quote"), Some((2, "> ".into(), None)));
+        assert_eq!(
+            markers("> - [x] done"),
+            Some((8, "> - [ ] ".into(), Some(5)))
+        );
+        assert_eq!(markers("- [ ]"), Some((5, "- [ ] ".into(), Some(3))));
+        assert_eq!(markers("- [link](url)"), Some((2, "- ".into(), None)));
+        assert_eq!(markers("-item"), None);
+        assert_eq!(markers("**bold**"), None);
+        assert_eq!(
+            markers("2024. was a year"),
+            Some((6, "2025. ".into(), None))
+        );
+    }
+
+    #[test]
+    fn test_align_tables() {
+        let text = "# Table\n\n| Name | Count |  Notes |\n|:-|-:|:-:|\n| a | 1 | x |\n| longer name | 100 |\n\n```\n|a|b|\n|-|-|\n```\n";
+        let edits = align_tables(text);
+        assert_eq!(edits.len(), 1);
+        let (range, new_text) = &edits[0];
+        assert_eq!(
+            &text[range.clone()],
+            "| Name | Count |  Notes |\n|:-|-:|:-:|\n| a | 1 | x |\n| longer name | 100 |"
+        );
+        assert_eq!(
+            new_text,
+            "| Name        | Count | Notes |\n\
+             | :---------- | ----: | :---: |\n\
+             | a           |     1 |   x   |\n\
+             | longer name |   100 |       |"
+        );
+
+        // Rows with more cells than the table has columns are left as they are.
+        assert_eq!(align_tables("| a |\n|-|\n| b | c |\n"), Vec::new());
+    }
 
     #[test]
     fn test_dividers() {
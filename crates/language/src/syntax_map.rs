@@ -1482,6 +1482,20 @@ impl<'a> SyntaxLayer<'a> {
             .root_node_with_offset(self.offset.0, self.offset.1)
     }
 
+    /// How deeply this layer is nested in injections, which is zero for the buffer's language.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Returns the byte ranges of the buffer that were parsed for this layer.
+    pub fn included_ranges(&self) -> Vec<Range<usize>> {
+        self.tree
+            .included_ranges()
+            .into_iter()
+            .map(|range| self.offset.0 + range.start_byte..self.offset.0 + range.end_byte)
+            .collect()
+    }
+
     pub(crate) fn override_id(&self, offset: usize, text: &text::BufferSnapshot) -> Option<u32> {
         let text = TextProvider(text.as_rope());
         let config = self.language.grammar.as_ref()?.override_config.as_ref()?;
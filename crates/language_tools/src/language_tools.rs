@@ -1,4 +1,5 @@
 mod lsp_log;
+mod server_binaries;
 mod syntax_tree_view;
 
 #[cfg(test)]
@@ -7,9 +8,11 @@ mod lsp_log_tests;
 use gpui::AppContext;
 
 pub use lsp_log::{LogStore, LspLogToolbarItemView, LspLogView};
+pub use server_binaries::LanguageServerBinaries;
 pub use syntax_tree_view::{SyntaxTreeToolbarItemView, SyntaxTreeView};
 
 pub fn init(cx: &mut AppContext) {
     lsp_log::init(cx);
+    server_binaries::init(cx);
     syntax_tree_view::init(cx);
 }
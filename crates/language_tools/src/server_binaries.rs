@@ -0,0 +1,460 @@
+//! A view of the language servers that have been downloaded, with the versions, size and path of
+//! each, and how each is run: pinned to the version that's downloaded, or replaced by a binary
+//! that's installed on the system.
+
+use fs::Fs;
+use gpui::{
+    actions, AnyElement, AppContext, EventEmitter, FocusHandle, FocusableView, Model,
+    PathPromptOptions, Render, Task, View, ViewContext, VisualContext, WindowContext,
+};
+use language::LanguageServerName;
+use project::{
+    project_settings::{BinarySettings, ProjectSettings},
+    Project,
+};
+use settings::{Settings, SettingsStore};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use ui::{prelude::*, Headline, HeadlineSize, Tooltip};
+use workspace::{
+    item::{Item, TabContentParams},
+    Workspace, WorkspaceId,
+};
+
+actions!(language_server_binaries, [Open]);
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(|workspace, _: &Open, cx| {
+            if let Some(binaries) = workspace.item_of_type::<LanguageServerBinaries>(cx) {
+                workspace.activate_item(&binaries, cx);
+                return;
+            }
+            let project = workspace.project().clone();
+            let fs = workspace.app_state().fs.clone();
+            let binaries = cx.new_view(|cx| LanguageServerBinaries::new(project, fs, cx));
+            workspace.add_item_to_active_pane(Box::new(binaries), None, cx);
+        });
+    })
+    .detach();
+}
+
+/// A language server's directory in the download directory, named after it.
+struct DownloadedServer {
+    name: LanguageServerName,
+    path: PathBuf,
+    versions: Vec<String>,
+    size: u64,
+}
+
+pub struct LanguageServerBinaries {
+    /// The downloaded language servers, by name.
+    servers: Vec<DownloadedServer>,
+    project: Model<Project>,
+    fs: Arc<dyn Fs>,
+    focus_handle: FocusHandle,
+    _reload: Task<()>,
+    _settings_subscription: gpui::Subscription,
+}
+
+impl LanguageServerBinaries {
+    fn new(project: Model<Project>, fs: Arc<dyn Fs>, cx: &mut ViewContext<Self>) -> Self {
+        let mut this = Self {
+            servers: Vec::new(),
+            project,
+            fs,
+            focus_handle: cx.focus_handle(),
+            _reload: Task::ready(()),
+            _settings_subscription: cx.observe_global::<SettingsStore>(|_, cx| cx.notify()),
+        };
+        this.reload(cx);
+        this
+    }
+
+    /// Reads what's in the download directory again.
+    fn reload(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(download_dir) = self
+            .project
+            .read(cx)
+            .languages()
+            .language_server_download_dir()
+        else {
+            return;
+        };
+        let servers = cx
+            .background_executor()
+            .spawn(async move { downloaded_servers(&download_dir) });
+        self._reload = cx.spawn(|this, mut cx| async move {
+            let servers = servers.await;
+            this.update(&mut cx, |this, cx| {
+                this.servers = servers;
+                cx.notify();
+            })
+            .ok();
+        });
+    }
+
+    fn update_lsp_settings(
+        &self,
+        server_name: &LanguageServerName,
+        cx: &mut ViewContext<Self>,
+        update: impl 'static + Send + FnOnce(&mut project::project_settings::LspSettings),
+    ) {
+        let server_name = server_name.0.clone();
+        settings::update_settings_file::<ProjectSettings>(self.fs.clone(), cx, move |settings| {
+            update(settings.lsp.entry(server_name).or_default());
+        });
+    }
+
+    fn toggle_pinned(&mut self, server_name: &LanguageServerName, cx: &mut ViewContext<Self>) {
+        let pinned = !is_pinned(server_name, cx);
+        self.update_lsp_settings(server_name, cx, move |settings| {
+            settings.pinned = pinned.then_some(true);
+        });
+    }
+
+    /// Asks for a binary on the system to run instead of the downloaded one.
+    fn use_system_binary(&mut self, server_name: &LanguageServerName, cx: &mut ViewContext<Self>) {
+        let paths = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+        });
+        let server_name = server_name.clone();
+        cx.spawn(|this, mut cx| async move {
+            let Some(path) = paths
+                .await
+                .ok()
+                .flatten()
+                .and_then(|paths| paths.into_iter().next())
+            else {
+                return;
+            };
+            this.update(&mut cx, |this, cx| {
+                this.update_lsp_settings(&server_name, cx, move |settings| {
+                    settings.binary = Some(BinarySettings {
+                        path: Some(path.to_string_lossy().into_owned()),
+                        arguments: None,
+                    });
+                });
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn use_downloaded_binary(
+        &mut self,
+        server_name: &LanguageServerName,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.update_lsp_settings(server_name, cx, |settings| settings.binary = None);
+    }
+
+    fn redownload(&mut self, server_name: &LanguageServerName, cx: &mut ViewContext<Self>) {
+        let task = self.project.update(cx, |project, cx| {
+            project.reinstall_language_server_binary(server_name, cx)
+        });
+        cx.spawn(|this, mut cx| async move {
+            task.await;
+            this.update(&mut cx, |this, cx| this.reload(cx)).ok();
+        })
+        .detach();
+    }
+
+    fn delete(&mut self, server_name: &LanguageServerName, cx: &mut ViewContext<Self>) {
+        let task = self.project.update(cx, |project, cx| {
+            project
+                .languages()
+                .delete_server_container(server_name.clone(), cx)
+        });
+        cx.spawn(|this, mut cx| async move {
+            task.await;
+            this.update(&mut cx, |this, cx| this.reload(cx)).ok();
+        })
+        .detach();
+    }
+
+    fn render_server(
+        &self,
+        ix: usize,
+        server: &DownloadedServer,
+        cx: &mut ViewContext<Self>,
+    ) -> impl IntoElement {
+        let name = server.name.clone();
+        let pinned = is_pinned(&name, cx);
+        let system_binary = ProjectSettings::get_global(cx)
+            .lsp
+            .get(&name.0)
+            .and_then(|settings| settings.binary.as_ref()?.path.clone());
+        let running = self.project.read(cx).is_running_language_server(&name);
+        let versions = if server.versions.is_empty() {
+            "Unknown version".to_string()
+        } else {
+            server.versions.join(", ")
+        };
+
+        v_flex()
+            .gap_1()
+            .py_2()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(Label::new(name.0.clone()))
+                    .child(Label::new(versions).color(Color::Muted))
+                    .child(
+                        Label::new(format_size(server.size))
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .when(running, |this| {
+                        this.child(
+                            Label::new("running")
+                                .size(LabelSize::Small)
+                                .color(Color::Success),
+                        )
+                    }),
+            )
+            .child(
+                Label::new(match &system_binary {
+                    Some(path) => format!("Runs {path} instead of {}", server.path.display()),
+                    None => server.path.display().to_string(),
+                })
+                .size(LabelSize::Small)
+                .color(Color::Muted),
+            )
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(
+                        Button::new(("pin", ix), if pinned { "Unpin" } else { "Pin Version" })
+                            .tooltip(|cx| {
+                                Tooltip::text(
+                                    "Keep the downloaded version, without updating it",
+                                    cx,
+                                )
+                            })
+                            .on_click(cx.listener({
+                                let name = name.clone();
+                                move |this, _, cx| this.toggle_pinned(&name, cx)
+                            })),
+                    )
+                    .child(match system_binary {
+                        Some(_) => Button::new(("binary", ix), "Use Downloaded Binary").on_click(
+                            cx.listener({
+                                let name = name.clone();
+                                move |this, _, cx| this.use_downloaded_binary(&name, cx)
+                            }),
+                        ),
+                        None => Button::new(("binary", ix), "Use System Binary…").on_click(
+                            cx.listener({
+                                let name = name.clone();
+                                move |this, _, cx| this.use_system_binary(&name, cx)
+                            }),
+                        ),
+                    })
+                    .child(
+                        Button::new(("redownload", ix), "Re-download").on_click(cx.listener({
+                            let name = name.clone();
+                            move |this, _, cx| this.redownload(&name, cx)
+                        })),
+                    )
+                    .child(
+                        Button::new(("delete", ix), "Delete")
+                            .disabled(running)
+                            .when(running, |this| {
+                                this.tooltip(|cx| {
+                                    Tooltip::text("Can't delete a running language server", cx)
+                                })
+                            })
+                            .on_click(cx.listener(move |this, _, cx| this.delete(&name, cx))),
+                    ),
+            )
+    }
+}
+
+fn is_pinned(server_name: &LanguageServerName, cx: &AppContext) -> bool {
+    ProjectSettings::get_global(cx)
+        .lsp
+        .get(&server_name.0)
+        .and_then(|settings| settings.pinned)
+        .unwrap_or(false)
+}
+
+fn downloaded_servers(download_dir: &Path) -> Vec<DownloadedServer> {
+    let Ok(entries) = std::fs::read_dir(download_dir) else {
+        return Vec::new();
+    };
+    let mut servers = entries
+        .filter_map(Result::ok)
+        .filter(|entry| {
+            entry
+                .file_type()
+                .map_or(false, |file_type| file_type.is_dir())
+        })
+        .map(|entry| {
+            let path = entry.path();
+            DownloadedServer {
+                name: LanguageServerName(entry.file_name().to_string_lossy().into()),
+                versions: installed_versions(&path),
+                size: size_of_path(&path),
+                path,
+            }
+        })
+        .collect::<Vec<_>>();
+    servers.sort_by(|a, b| a.name.0.cmp(&b.name.0));
+    servers
+}
+
+/// The versions of a language server that are in its directory.
+fn installed_versions(server_dir: &Path) -> Vec<String> {
+    // Servers that are installed with npm have the versions of their packages in `package.json`.
+    if let Some(dependencies) = std::fs::read_to_string(server_dir.join("package.json"))
+        .ok()
+        .and_then(|package| serde_json::from_str::<serde_json::Value>(&package).ok())
+        .and_then(|package| package.get("dependencies")?.as_object().cloned())
+    {
+        return dependencies
+            .iter()
+            .map(|(name, version)| format!("{name} {}", version.as_str().unwrap_or_default()))
+            .collect();
+    }
+
+    // Others are downloaded to files or directories that are named after their versions.
+    let Ok(entries) = std::fs::read_dir(server_dir) else {
+        return Vec::new();
+    };
+    let mut versions = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| version_in_file_name(&entry.file_name().to_string_lossy()))
+        .collect::<Vec<_>>();
+    versions.sort();
+    versions
+}
+
+/// The version in a name like "gopls_v0.15.3" or "rust-analyzer-2024-05-27".
+fn version_in_file_name(file_name: &str) -> Option<String> {
+    file_name
+        .match_indices(|c: char| c == '-' || c == '_')
+        .map(|(ix, _)| &file_name[ix + 1..])
+        .find(|rest| {
+            rest.trim_start_matches('v')
+                .starts_with(|c: char| c.is_ascii_digit())
+        })
+        .map(|version| version.to_string())
+}
+
+fn size_of_path(path: &Path) -> u64 {
+    let Ok(metadata) = std::fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if !metadata.is_dir() {
+        return metadata.len();
+    }
+    std::fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .map(|entry| size_of_path(&entry.path()))
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
+fn format_size(bytes: u64) -> String {
+    const UNITS: &[&str] = &["B", "KB", "MB", "GB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024. && unit + 1 < UNITS.len() {
+        size /= 1024.;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} {}", UNITS[unit])
+    } else {
+        format!("{size:.1} {}", UNITS[unit])
+    }
+}
+
+impl Render for LanguageServerBinaries {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let servers = self
+            .servers
+            .iter()
+            .enumerate()
+            .map(|(ix, server)| self.render_server(ix, server, cx).into_any_element())
+            .collect::<Vec<_>>();
+
+        v_flex()
+            .id("language-server-binaries")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .overflow_y_scroll()
+            .p_4()
+            .gap_2()
+            .bg(cx.theme().colors().editor_background)
+            .child(Headline::new("Language Server Binaries").size(HeadlineSize::Small))
+            .when(servers.is_empty(), |this| {
+                this.child(
+                    Label::new("No language servers have been downloaded").color(Color::Muted),
+                )
+            })
+            .children(servers)
+    }
+}
+
+impl EventEmitter<()> for LanguageServerBinaries {}
+
+impl FocusableView for LanguageServerBinaries {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for LanguageServerBinaries {
+    type Event = ();
+
+    fn tab_content(&self, params: TabContentParams, _: &WindowContext) -> AnyElement {
+        Label::new("Language Server Binaries")
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn show_toolbar(&self) -> bool {
+        false
+    }
+
+    fn clone_on_split(&self, _: WorkspaceId, cx: &mut ViewContext<Self>) -> Option<View<Self>> {
+        Some(cx.new_view(|cx| Self::new(self.project.clone(), self.fs.clone(), cx)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_version_in_file_name() {
+        assert_eq!(
+            version_in_file_name("gopls_v0.15.3").as_deref(),
+            Some("v0.15.3")
+        );
+        assert_eq!(
+            version_in_file_name("rust-analyzer-2024-05-27").as_deref(),
+            Some("2024-05-27")
+        );
+        assert_eq!(
+            version_in_file_name("elixir-ls_0.20.0").as_deref(),
+            Some("0.20.0")
+        );
+        assert_eq!(version_in_file_name("node_modules"), None);
+    }
+}
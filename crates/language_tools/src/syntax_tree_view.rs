@@ -12,10 +12,14 @@ use tree_sitter::{Node, TreeCursor};
 use ui::{h_flex, popover_menu, ButtonLike, Color, ContextMenu, Label, LabelCommon, PopoverMenu};
 use workspace::{
     item::{Item, ItemHandle, TabContentParams},
-    SplitDirection, ToolbarItemEvent, ToolbarItemLocation, ToolbarItemView, Workspace,
+    notifications::NotificationId,
+    SplitDirection, Toast, ToolbarItemEvent, ToolbarItemLocation, ToolbarItemView, Workspace,
 };
 
-actions!(debug, [OpenSyntaxTreeView]);
+actions!(debug, [OpenSyntaxTreeView, ShowSyntaxScopesAtCursor]);
+
+/// The toast that shows the syntax scopes at the cursor.
+enum SyntaxScopes {}
 
 pub fn init(cx: &mut AppContext) {
     cx.observe_new_views(|workspace: &mut Workspace, _| {
@@ -26,10 +30,49 @@ pub fn init(cx: &mut AppContext) {
                 cx.new_view(|cx| SyntaxTreeView::new(workspace_handle, active_item, cx));
             workspace.split_item(SplitDirection::Right, Box::new(syntax_tree_view), cx)
         });
+        workspace.register_action(|workspace, _: &ShowSyntaxScopesAtCursor, cx| {
+            let Some(editor) = workspace.active_item_as::<Editor>(cx) else {
+                return;
+            };
+            let message = syntax_scopes_at_cursor(editor.read(cx), cx)
+                .unwrap_or_else(|| "There's no syntax tree at the cursor".to_string());
+            workspace.show_toast(
+                Toast::new(NotificationId::unique::<SyntaxScopes>(), message),
+                cx,
+            );
+        });
     })
     .detach();
 }
 
+/// Describes the syntax at the newest cursor: the kinds of the nodes that contain it, outermost
+/// first, and the names of the highlight captures that apply to it.
+fn syntax_scopes_at_cursor(editor: &Editor, cx: &AppContext) -> Option<String> {
+    let cursor = editor.selections.newest::<usize>(cx).head();
+    let snapshot = editor.buffer().read(cx).snapshot(cx);
+    let (buffer, offset) = snapshot.point_to_buffer_offset(cursor)?;
+    let layer = buffer.syntax_layer_at(offset)?;
+    let mut node = layer.node().descendant_for_byte_range(offset, offset)?;
+    let mut kinds = vec![node.kind()];
+    while let Some(parent) = node.parent() {
+        kinds.push(parent.kind());
+        node = parent;
+    }
+    kinds.reverse();
+
+    let captures = buffer.highlight_captures_at(offset);
+    Some(format!(
+        "{}: {}\nHighlights: {}",
+        layer.language.name(),
+        kinds.join(" > "),
+        if captures.is_empty() {
+            "none".to_string()
+        } else {
+            captures.join(", ")
+        }
+    ))
+}
+
 pub struct SyntaxTreeView {
     workspace_handle: WeakView<Workspace>,
     editor: Option<EditorState>,
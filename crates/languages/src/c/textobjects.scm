@@ -0,0 +1,33 @@
+(function_definition
+    body: (_
+        "{"
+        (_)* @function.inside
+        "}")) @function.around
+
+(struct_specifier
+    body: (_
+        "{"
+        (_)* @class.inside
+        "}")) @class.around
+
+(union_specifier
+    body: (_
+        "{"
+        (_)* @class.inside
+        "}")) @class.around
+
+(enum_specifier
+    body: (_
+        "{"
+        (_)* @class.inside
+        "}")) @class.around
+
+(parameter_list
+    (_) @parameter.inside)
+
+(argument_list
+    (_) @parameter.inside)
+
+(comment) @comment.inside
+
+(comment)+ @comment.around
@@ -0,0 +1,53 @@
+(function_declaration
+    body: (_
+        "{"
+        (_)* @function.inside
+        "}")) @function.around
+
+(generator_function_declaration
+    body: (_
+        "{"
+        (_)* @function.inside
+        "}")) @function.around
+
+(function
+    body: (_
+        "{"
+        (_)* @function.inside
+        "}")) @function.around
+
+(method_definition
+    body: (_
+        "{"
+        (_)* @function.inside
+        "}")) @function.around
+
+(arrow_function
+    body: (statement_block
+        "{"
+        (_)* @function.inside
+        "}")) @function.around
+
+(arrow_function) @function.around
+
+(class_declaration
+    body: (_
+        "{"
+        (_)* @class.inside
+        "}")) @class.around
+
+(class
+    body: (_
+        "{"
+        (_)* @class.inside
+        "}")) @class.around
+
+(formal_parameters
+    (_) @parameter.inside)
+
+(arguments
+    (_) @parameter.inside)
+
+(comment) @comment.inside
+
+(comment)+ @comment.around
@@ -0,0 +1,2 @@
+(fenced_code_block) @code
+(indented_code_block) @code
@@ -0,0 +1,249 @@
+use editor::Editor;
+use gpui::{
+    actions, div, prelude::*, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView,
+    Render, SharedString, Styled, Subscription, View, ViewContext, VisualContext, WeakView,
+    WindowContext,
+};
+use regex::Regex;
+use theme::ActiveTheme;
+use ui::{h_flex, prelude::*, v_flex, IconButton, IconName, Label, Tooltip};
+use workspace::ModalView;
+
+actions!(
+    line_filter,
+    [KeepLinesMatching, DeleteLinesMatching, ToggleInvert]
+);
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(LineFilter::register).detach();
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FilterMode {
+    /// Keep the lines matching the pattern, delete all others.
+    Keep,
+    /// Delete the lines matching the pattern, keep all others.
+    Delete,
+}
+
+/// A modal prompting for a regular expression, used to keep or delete the
+/// selected lines (or all lines in the buffer) that match it.
+pub struct LineFilter {
+    query_editor: View<Editor>,
+    active_editor: View<Editor>,
+    mode: FilterMode,
+    invert: bool,
+    query_error: Option<SharedString>,
+    /// The number of lines matching the query, out of the number of lines being filtered.
+    match_counts: Option<(usize, usize)>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl ModalView for LineFilter {}
+
+impl FocusableView for LineFilter {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.query_editor.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for LineFilter {}
+
+impl LineFilter {
+    fn register(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+        let handle = cx.view().downgrade();
+        editor
+            .register_action({
+                let handle = handle.clone();
+                move |_: &KeepLinesMatching, cx| Self::toggle(&handle, FilterMode::Keep, cx)
+            })
+            .register_action(move |_: &DeleteLinesMatching, cx| {
+                Self::toggle(&handle, FilterMode::Delete, cx)
+            });
+    }
+
+    fn toggle(handle: &WeakView<Editor>, mode: FilterMode, cx: &mut WindowContext) {
+        let Some(editor) = handle.upgrade() else {
+            return;
+        };
+        if editor.read(cx).read_only(cx) {
+            return;
+        }
+        let Some(workspace) = editor.read(cx).workspace() else {
+            return;
+        };
+        workspace.update(cx, |workspace, cx| {
+            workspace.toggle_modal(cx, move |cx| LineFilter::new(editor, mode, cx));
+        })
+    }
+
+    pub fn new(active_editor: View<Editor>, mode: FilterMode, cx: &mut ViewContext<Self>) -> Self {
+        let query_editor = cx.new_view(|cx| {
+            let mut editor = Editor::single_line(cx);
+            editor.set_placeholder_text("Regular expression…", cx);
+            editor
+        });
+        let query_editor_change = cx.subscribe(&query_editor, Self::on_query_editor_event);
+
+        Self {
+            query_editor,
+            active_editor,
+            mode,
+            invert: false,
+            query_error: None,
+            match_counts: None,
+            _subscriptions: vec![query_editor_change],
+        }
+    }
+
+    fn on_query_editor_event(
+        &mut self,
+        _: View<Editor>,
+        event: &editor::EditorEvent,
+        cx: &mut ViewContext<Self>,
+    ) {
+        match event {
+            editor::EditorEvent::Blurred => cx.emit(DismissEvent),
+            editor::EditorEvent::BufferEdited { .. } => self.update_match_counts(cx),
+            _ => {}
+        }
+    }
+
+    fn regex_from_query(&mut self, cx: &mut ViewContext<Self>) -> Option<Regex> {
+        let query = self.query_editor.read(cx).text(cx);
+        if query.is_empty() {
+            self.query_error = None;
+            return None;
+        }
+        match Regex::new(&query) {
+            Ok(regex) => {
+                self.query_error = None;
+                Some(regex)
+            }
+            Err(error) => {
+                self.query_error = Some(error.to_string().into());
+                None
+            }
+        }
+    }
+
+    fn update_match_counts(&mut self, cx: &mut ViewContext<Self>) {
+        let invert = self.invert;
+        let match_counts = self.regex_from_query(cx).map(|regex| {
+            self.active_editor.update(cx, |editor, cx| {
+                editor.count_retained_lines(|line| regex.is_match(line) != invert, cx)
+            })
+        });
+        self.match_counts = match_counts;
+        cx.notify();
+    }
+
+    fn toggle_invert(&mut self, _: &ToggleInvert, cx: &mut ViewContext<Self>) {
+        self.invert = !self.invert;
+        self.update_match_counts(cx);
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, cx: &mut ViewContext<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, cx: &mut ViewContext<Self>) {
+        let Some(regex) = self.regex_from_query(cx) else {
+            cx.notify();
+            return;
+        };
+
+        let invert = self.invert;
+        let keep_matching = self.mode == FilterMode::Keep;
+        self.active_editor.update(cx, |editor, cx| {
+            editor.retain_lines(|line| (regex.is_match(line) != invert) == keep_matching, cx);
+            editor.focus(cx);
+        });
+        cx.emit(DismissEvent);
+    }
+
+    fn help_text(&self) -> SharedString {
+        if let Some(error) = &self.query_error {
+            return error.clone();
+        }
+        let Some((matching, total)) = self.match_counts else {
+            return match self.mode {
+                FilterMode::Keep => "Keep lines matching a pattern".into(),
+                FilterMode::Delete => "Delete lines matching a pattern".into(),
+            };
+        };
+        let removed = match self.mode {
+            FilterMode::Keep => total - matching,
+            FilterMode::Delete => matching,
+        };
+        let line_or_lines = |count: usize| if count == 1 { "line" } else { "lines" };
+        format!(
+            "{matching} of {total} {} {}match, {removed} {} will be deleted",
+            line_or_lines(total),
+            if self.invert { "don't " } else { "" },
+            line_or_lines(removed),
+        )
+        .into()
+    }
+}
+
+impl Render for LineFilter {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let help_color = if self.query_error.is_some() {
+            Color::Error
+        } else {
+            Color::Muted
+        };
+
+        div()
+            .elevation_2(cx)
+            .key_context("LineFilter")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::toggle_invert))
+            .w_96()
+            .child(
+                v_flex()
+                    .px_1()
+                    .pt_0p5()
+                    .gap_px()
+                    .child(
+                        h_flex()
+                            .py_0p5()
+                            .px_1()
+                            .gap_1()
+                            .child(
+                                div()
+                                    .flex_1()
+                                    .px_1()
+                                    .py_0p5()
+                                    .child(self.query_editor.clone()),
+                            )
+                            .child(
+                                IconButton::new("invert-match", IconName::Filter)
+                                    .style(ButtonStyle::Subtle)
+                                    .selected(self.invert)
+                                    .on_click(cx.listener(|this, _, cx| {
+                                        this.toggle_invert(&ToggleInvert, cx)
+                                    }))
+                                    .tooltip(|cx| {
+                                        Tooltip::for_action("Invert Match", &ToggleInvert, cx)
+                                    }),
+                            ),
+                    )
+                    .child(
+                        div()
+                            .h_px()
+                            .w_full()
+                            .bg(cx.theme().colors().element_background),
+                    )
+                    .child(
+                        h_flex()
+                            .justify_between()
+                            .px_2()
+                            .py_1()
+                            .child(Label::new(self.help_text()).color(help_color)),
+                    ),
+            )
+    }
+}
@@ -254,10 +254,42 @@ public func LKLocalAudioTrackCreateTrack() -> UnsafeMutableRawPointer {
 }
 
 
+@_cdecl("LKAudioInputDevices")
+public func LKAudioInputDevices(data: UnsafeRawPointer, callback: @escaping @convention(c) (UnsafeRawPointer, CFString, CFString) -> Void) {
+    for device in Room.audioDeviceModule.inputDevices {
+        callback(data, device.deviceId as CFString, device.name as CFString)
+    }
+}
+
+@_cdecl("LKAudioInputDevice")
+public func LKAudioInputDevice() -> CFString? {
+    return Room.audioDeviceModule.inputDevice?.deviceId as CFString?
+}
+
+@_cdecl("LKSetAudioInputDevice")
+public func LKSetAudioInputDevice(id: CFString) -> Bool {
+    let id = id as String
+    guard let device = Room.audioDeviceModule.inputDevices.first(where: { $0.deviceId == id }) else {
+        return false
+    }
+    Room.audioDeviceModule.inputDevice = device
+    return true
+}
+
+@_cdecl("LKRoomLocalAudioLevel")
+public func LKRoomLocalAudioLevel(room: UnsafeRawPointer) -> Float {
+    let room = Unmanaged<Room>.fromOpaque(room).takeUnretainedValue()
+    return room.localParticipant?.audioLevel ?? 0
+}
+
 @_cdecl("LKCreateScreenShareTrackForDisplay")
-public func LKCreateScreenShareTrackForDisplay(display: UnsafeMutableRawPointer) -> UnsafeMutableRawPointer {
+public func LKCreateScreenShareTrackForDisplay(display: UnsafeMutableRawPointer, maxWidth: UInt32, maxHeight: UInt32, maxFrameRate: UInt32) -> UnsafeMutableRawPointer {
     let display = Unmanaged<MacOSDisplay>.fromOpaque(display).takeUnretainedValue()
-    let track = LocalVideoTrack.createMacOSScreenShareTrack(source: display, preferredMethod: .legacy)
+    let options = ScreenShareCaptureOptions(
+        dimensions: Dimensions(width: Int32(maxWidth), height: Int32(maxHeight)),
+        fps: Int(maxFrameRate)
+    )
+    let track = LocalVideoTrack.createMacOSScreenShareTrack(source: display, options: options, preferredMethod: .legacy)
     return Unmanaged.passRetained(track).toOpaque()
 }
 
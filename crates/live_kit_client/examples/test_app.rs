@@ -129,7 +129,8 @@ fn main() {
             let displays = room_a.display_sources().await.unwrap();
             let display = displays.into_iter().next().unwrap();
 
-            let local_video_track = LocalVideoTrack::screen_share_for_display(&display);
+            let local_video_track =
+                LocalVideoTrack::screen_share_for_display(&display, Default::default());
             let local_video_track_publication =
                 room_a.publish_video_track(local_video_track).await.unwrap();
 
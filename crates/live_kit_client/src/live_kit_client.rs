@@ -20,6 +20,31 @@ pub enum ConnectionState {
     Connected { url: String, token: String },
 }
 
+/// Limits applied to the video captured when sharing a display.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ScreenCaptureOptions {
+    pub max_width: u32,
+    pub max_height: u32,
+    pub max_frame_rate: u32,
+}
+
+impl Default for ScreenCaptureOptions {
+    fn default() -> Self {
+        Self {
+            max_width: 1920,
+            max_height: 1080,
+            max_frame_rate: 15,
+        }
+    }
+}
+
+/// An audio device available for capturing the microphone.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AudioDevice {
+    pub id: String,
+    pub name: String,
+}
+
 #[derive(Clone)]
 pub enum RoomUpdate {
     ActiveSpeakersChanged { speakers: Vec<Sid> },
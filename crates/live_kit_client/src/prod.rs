@@ -1,4 +1,4 @@
-use crate::{ConnectionState, RoomUpdate, Sid};
+use crate::{AudioDevice, ConnectionState, RoomUpdate, ScreenCaptureOptions, Sid};
 use anyhow::{anyhow, Context, Result};
 use core_foundation::{
     array::{CFArray, CFArrayRef},
@@ -145,9 +145,22 @@ extern "C" {
             error: CFStringRef,
         ),
     );
-    fn LKCreateScreenShareTrackForDisplay(display: swift::MacOSDisplay) -> swift::LocalVideoTrack;
+    fn LKCreateScreenShareTrackForDisplay(
+        display: swift::MacOSDisplay,
+        max_width: u32,
+        max_height: u32,
+        max_frame_rate: u32,
+    ) -> swift::LocalVideoTrack;
     fn LKLocalAudioTrackCreateTrack() -> swift::LocalAudioTrack;
 
+    fn LKAudioInputDevices(
+        callback_data: *mut c_void,
+        callback: extern "C" fn(callback_data: *mut c_void, id: CFStringRef, name: CFStringRef),
+    );
+    fn LKAudioInputDevice() -> CFStringRef;
+    fn LKSetAudioInputDevice(id: CFStringRef) -> bool;
+    fn LKRoomLocalAudioLevel(room: swift::Room) -> f32;
+
     fn LKLocalTrackPublicationSetMute(
         publication: swift::LocalTrackPublication,
         muted: bool,
@@ -400,6 +413,49 @@ impl Room {
         }
     }
 
+    pub fn audio_input_devices(&self) -> Vec<AudioDevice> {
+        extern "C" fn callback(devices: *mut c_void, id: CFStringRef, name: CFStringRef) {
+            unsafe {
+                let devices = &mut *(devices as *mut Vec<AudioDevice>);
+                devices.push(AudioDevice {
+                    id: CFString::wrap_under_get_rule(id).to_string(),
+                    name: CFString::wrap_under_get_rule(name).to_string(),
+                });
+            }
+        }
+
+        let mut devices = Vec::new();
+        unsafe {
+            LKAudioInputDevices(&mut devices as *mut Vec<AudioDevice> as *mut _, callback);
+        }
+        devices
+    }
+
+    pub fn audio_input_device(&self) -> Option<String> {
+        unsafe {
+            let id = LKAudioInputDevice();
+            if id.is_null() {
+                None
+            } else {
+                Some(CFString::wrap_under_get_rule(id).to_string())
+            }
+        }
+    }
+
+    pub fn set_audio_input_device(&self, id: &str) -> Result<()> {
+        let id = CFString::new(id);
+        if unsafe { LKSetAudioInputDevice(id.as_concrete_TypeRef()) } {
+            Ok(())
+        } else {
+            Err(anyhow!("no audio input device with id {id}"))
+        }
+    }
+
+    /// The level of the audio captured by the local microphone, between 0 and 1.
+    pub fn local_audio_level(&self) -> f32 {
+        unsafe { LKRoomLocalAudioLevel(self.native_room) }
+    }
+
     pub fn updates(&self) -> mpsc::UnboundedReceiver<RoomUpdate> {
         let (tx, rx) = mpsc::unbounded();
         self.update_subscribers.lock().push(tx);
@@ -706,8 +762,15 @@ impl Drop for LocalAudioTrack {
 pub struct LocalVideoTrack(swift::LocalVideoTrack);
 
 impl LocalVideoTrack {
-    pub fn screen_share_for_display(display: &MacOSDisplay) -> Self {
-        Self(unsafe { LKCreateScreenShareTrackForDisplay(display.0) })
+    pub fn screen_share_for_display(display: &MacOSDisplay, options: ScreenCaptureOptions) -> Self {
+        Self(unsafe {
+            LKCreateScreenShareTrackForDisplay(
+                display.0,
+                options.max_width,
+                options.max_height,
+                options.max_frame_rate,
+            )
+        })
     }
 }
 
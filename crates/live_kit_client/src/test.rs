@@ -1,4 +1,4 @@
-use crate::{ConnectionState, RoomUpdate, Sid};
+use crate::{AudioDevice, ConnectionState, RoomUpdate, ScreenCaptureOptions, Sid};
 use anyhow::{anyhow, Context, Result};
 use async_trait::async_trait;
 use collections::{BTreeMap, HashMap, HashSet};
@@ -249,6 +249,7 @@ impl TestServer {
             sid: sid.clone(),
             publisher_id: identity.clone(),
             frames_rx: local_track.frames_rx.clone(),
+            capture_options: local_track.options,
         });
 
         room.video_tracks.push(track.clone());
@@ -439,6 +440,7 @@ struct TestServerVideoTrack {
     sid: Sid,
     publisher_id: Sid,
     frames_rx: async_broadcast::Receiver<Frame>,
+    capture_options: ScreenCaptureOptions,
 }
 
 #[derive(Debug)]
@@ -518,6 +520,9 @@ struct RoomState {
         watch::Receiver<ConnectionState>,
     ),
     display_sources: Vec<MacOSDisplay>,
+    audio_input_devices: Vec<AudioDevice>,
+    audio_input_device: Option<String>,
+    local_audio_level: f32,
     paused_audio_tracks: HashSet<Sid>,
     updates_tx: async_broadcast::Sender<RoomUpdate>,
     updates_rx: async_broadcast::Receiver<RoomUpdate>,
@@ -531,6 +536,9 @@ impl Room {
         Arc::new(Self(Mutex::new(RoomState {
             connection: watch::channel_with(ConnectionState::Disconnected),
             display_sources: Default::default(),
+            audio_input_devices: Default::default(),
+            audio_input_device: None,
+            local_audio_level: 0.,
             paused_audio_tracks: Default::default(),
             updates_tx,
             updates_rx,
@@ -651,6 +659,32 @@ impl Room {
             .collect()
     }
 
+    pub fn audio_input_devices(&self) -> Vec<AudioDevice> {
+        self.0.lock().audio_input_devices.clone()
+    }
+
+    pub fn audio_input_device(&self) -> Option<String> {
+        self.0.lock().audio_input_device.clone()
+    }
+
+    pub fn set_audio_input_device(&self, id: &str) -> Result<()> {
+        let mut state = self.0.lock();
+        if state
+            .audio_input_devices
+            .iter()
+            .any(|device| device.id == id)
+        {
+            state.audio_input_device = Some(id.to_string());
+            Ok(())
+        } else {
+            Err(anyhow!("no audio input device with id {id}"))
+        }
+    }
+
+    pub fn local_audio_level(&self) -> f32 {
+        self.0.lock().local_audio_level
+    }
+
     pub fn updates(&self) -> impl Stream<Item = RoomUpdate> {
         self.0.lock().updates_rx.clone()
     }
@@ -659,6 +693,14 @@ impl Room {
         self.0.lock().display_sources = sources;
     }
 
+    pub fn set_audio_input_devices(&self, devices: Vec<AudioDevice>) {
+        self.0.lock().audio_input_devices = devices;
+    }
+
+    pub fn set_local_audio_level(&self, level: f32) {
+        self.0.lock().local_audio_level = level;
+    }
+
     fn test_server(&self) -> Arc<TestServer> {
         match self.0.lock().connection.1.borrow().clone() {
             ConnectionState::Disconnected => panic!("must be connected to call this method"),
@@ -751,12 +793,14 @@ impl RemoteTrackPublication {
 #[derive(Clone)]
 pub struct LocalVideoTrack {
     frames_rx: async_broadcast::Receiver<Frame>,
+    options: ScreenCaptureOptions,
 }
 
 impl LocalVideoTrack {
-    pub fn screen_share_for_display(display: &MacOSDisplay) -> Self {
+    pub fn screen_share_for_display(display: &MacOSDisplay, options: ScreenCaptureOptions) -> Self {
         Self {
             frames_rx: display.frames.1.clone(),
+            options,
         }
     }
 }
@@ -787,6 +831,10 @@ impl RemoteVideoTrack {
     pub fn frames(&self) -> async_broadcast::Receiver<Frame> {
         self.server_track.frames_rx.clone()
     }
+
+    pub fn capture_options(&self) -> ScreenCaptureOptions {
+        self.server_track.capture_options
+    }
 }
 
 #[derive(Debug)]
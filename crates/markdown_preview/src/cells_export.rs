@@ -0,0 +1,246 @@
+//! Exporting the cells of a source file, which start at `# %%` markers, or of a notebook, as a
+//! Markdown or a standalone HTML document, for sharing them outside of the editor.
+
+use editor::{
+    actions::{ExportCellsAsHtml, ExportCellsAsMarkdown},
+    Editor, MultiBuffer,
+};
+use gpui::{Context, ViewContext, VisualContext};
+use notebook::{CellOutput, CellType, Notebook, NotebookEditor};
+use pulldown_cmark::{Options, Parser};
+use std::path::Path;
+use workspace::{notifications::NotificationId, Toast, Workspace};
+
+#[derive(Clone, Copy)]
+enum ExportFormat {
+    Markdown,
+    Html,
+}
+
+/// The toast that's shown when there are no cells to export.
+enum NoCellsToExport {}
+
+pub(crate) fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+    workspace.register_action(|workspace, _: &ExportCellsAsMarkdown, cx| {
+        export_cells(workspace, ExportFormat::Markdown, cx);
+    });
+    workspace.register_action(|workspace, _: &ExportCellsAsHtml, cx| {
+        export_cells(workspace, ExportFormat::Html, cx);
+    });
+}
+
+/// Opens the export of the active editor's or notebook's cells in a new buffer, from which it
+/// can be saved.
+fn export_cells(workspace: &mut Workspace, format: ExportFormat, cx: &mut ViewContext<Workspace>) {
+    let Some(item) = workspace.active_item(cx) else {
+        return;
+    };
+    let (markdown, title) = if let Some(notebook_editor) = item.downcast::<NotebookEditor>() {
+        let title = item
+            .project_path(cx)
+            .and_then(|path| Some(path.path.file_stem()?.to_string_lossy().into_owned()))
+            .unwrap_or_else(|| "notebook".to_string());
+        let notebook = notebook_editor.read(cx).notebook(cx);
+        (
+            notebook.and_then(|notebook| notebook_markdown(&notebook)),
+            title,
+        )
+    } else if let Some(editor) = item.downcast::<Editor>() {
+        let editor = editor.read(cx);
+        let title = editor.buffer().read(cx).title(cx);
+        let title = Path::new(title.as_ref()).file_stem().map_or_else(
+            || title.to_string(),
+            |stem| stem.to_string_lossy().into_owned(),
+        );
+        (editor.cells_markdown(cx), title)
+    } else {
+        return;
+    };
+    let Some(markdown) = markdown else {
+        workspace.show_toast(
+            Toast::new(
+                NotificationId::unique::<NoCellsToExport>(),
+                "There are no cells to export. Cells start at lines with a `# %%` or `// %%` marker.",
+            ),
+            cx,
+        );
+        return;
+    };
+
+    let (text, language_name, title) = match format {
+        ExportFormat::Markdown => (markdown, "Markdown", format!("{title}.md")),
+        ExportFormat::Html => (
+            standalone_html(&title, &markdown),
+            "HTML",
+            format!("{title}.html"),
+        ),
+    };
+    let language = workspace
+        .app_state()
+        .languages
+        .language_for_name(language_name);
+    cx.spawn(|workspace, mut cx| async move {
+        let language = language.await.ok();
+        workspace
+            .update(&mut cx, |workspace, cx| {
+                workspace.with_local_workspace(cx, move |workspace, cx| {
+                    let project = workspace.project().clone();
+                    let buffer = project.update(cx, |project, cx| {
+                        project.create_local_buffer(&text, language, cx)
+                    });
+                    let buffer =
+                        cx.new_model(|cx| MultiBuffer::singleton(buffer, cx).with_title(title));
+                    workspace.add_item_to_active_pane(
+                        Box::new(
+                            cx.new_view(|cx| Editor::for_multibuffer(buffer, Some(project), cx)),
+                        ),
+                        None,
+                        cx,
+                    );
+                })
+            })?
+            .await
+    })
+    .detach_and_log_err(cx);
+}
+
+/// The notebook's cells as Markdown, with the code of each in a fenced code block followed by
+/// its outputs, or `None` if it has no cells. Images are embedded as data URIs, so that the
+/// export doesn't need any other files.
+fn notebook_markdown(notebook: &Notebook) -> Option<String> {
+    let fence_name = notebook.language_name().to_lowercase();
+    let blocks = notebook
+        .cells
+        .iter()
+        .flat_map(|cell| {
+            let source = cell.source.trim_end();
+            let mut blocks = Vec::new();
+            if !source.is_empty() {
+                blocks.push(match cell.cell_type {
+                    CellType::Code => format!("```{fence_name}\n{source}\n```"),
+                    CellType::Markdown | CellType::Raw => source.to_string(),
+                });
+            }
+            blocks.extend(
+                cell.display_outputs()
+                    .into_iter()
+                    .map(|output| match output {
+                        CellOutput::Text(text) => format!("```\n{text}\n```"),
+                        CellOutput::Png(data) => format!("![output](data:image/png;base64,{data})"),
+                    }),
+            );
+            blocks
+        })
+        .collect::<Vec<_>>();
+    if blocks.is_empty() {
+        return None;
+    }
+    Some(blocks.join("\n\n") + "\n")
+}
+
+/// An HTML document of the Markdown that doesn't need anything else to be shown.
+fn standalone_html(title: &str, markdown: &str) -> String {
+    let mut body = String::new();
+    pulldown_cmark::html::push_html(&mut body, Parser::new_ext(markdown, Options::all()));
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>{}</title>
+<style>
+body {{ max-width: 48rem; margin: 2rem auto; padding: 0 1rem; font-family: system-ui, sans-serif; line-height: 1.5; }}
+pre {{ padding: 1rem; overflow-x: auto; background: #f6f8fa; border-radius: 6px; }}
+</style>
+</head>
+<body>
+{body}</body>
+</html>
+"#,
+        escape_html(title)
+    )
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_standalone_html() {
+        let html = standalone_html("a <b>", "# Results\n\n```python\na = 1\n```\n");
+        assert!(html.contains("<title>a &lt;b&gt;</title>"));
+        assert!(html.contains("<h1>Results</h1>"));
+        assert!(html.contains("<pre><code class=\"language-python\">a = 1\n</code></pre>"));
+    }
+
+    #[test]
+    fn test_notebook_markdown() {
+        let notebook = Notebook::from_json(
+            r##"{
+                "cells": [
+                    {"cell_type": "markdown", "metadata": {}, "source": ["# Results\n"]},
+                    {
+                        "cell_type": "code",
+                        "execution_count": 1,
+                        "metadata": {},
+                        "outputs": [
+                            {"name": "stdout", "output_type": "stream", "text": ["a\n", "b\n"]},
+                            {
+                                "data": {
+                                    "image/png": "iVBORw0K\nGgo=\n",
+                                    "text/plain": ["<Figure size 640x480>"]
+                                },
+                                "metadata": {},
+                                "output_type": "display_data"
+                            },
+                            {
+                                "data": {"text/plain": ["2"]},
+                                "execution_count": 1,
+                                "metadata": {},
+                                "output_type": "execute_result"
+                            },
+                            {
+                                "ename": "ValueError",
+                                "evalue": "bad",
+                                "output_type": "error",
+                                "traceback": []
+                            }
+                        ],
+                        "source": ["plot()"]
+                    },
+                    {
+                        "cell_type": "code",
+                        "execution_count": null,
+                        "metadata": {},
+                        "outputs": [],
+                        "source": []
+                    }
+                ],
+                "metadata": {"kernelspec": {"language": "python"}},
+                "nbformat": 4,
+                "nbformat_minor": 5
+            }"##,
+        )
+        .unwrap();
+
+        let markdown = notebook_markdown(&notebook).unwrap();
+        assert_eq!(
+            markdown,
+            "# Results\n\n\
+            ```python\nplot()\n```\n\n\
+            ```\na\nb\n```\n\n\
+            ![output](data:image/png;base64,iVBORw0KGgo=)\n\n\
+            ```\n2\n```\n\n\
+            ```\nValueError: bad\n```\n"
+        );
+        let html = standalone_html("plots", &markdown);
+        assert!(html.contains("<img src=\"data:image/png;base64,iVBORw0KGgo=\" alt=\"output\" />"));
+    }
+}
@@ -1,6 +1,7 @@
 use gpui::{actions, AppContext};
 use workspace::Workspace;
 
+mod cells_export;
 pub mod markdown_elements;
 pub mod markdown_parser;
 pub mod markdown_preview_view;
@@ -11,6 +12,7 @@ actions!(markdown, [OpenPreview, OpenPreviewToTheSide]);
 pub fn init(cx: &mut AppContext) {
     cx.observe_new_views(|workspace: &mut Workspace, cx| {
         markdown_preview_view::MarkdownPreviewView::register(workspace, cx);
+        cells_export::register(workspace, cx);
     })
     .detach();
 }
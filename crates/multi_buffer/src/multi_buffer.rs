@@ -1555,6 +1555,19 @@ impl MultiBuffer {
             return true;
         }
 
+        // The targets of links between documents are completed as they're started.
+        if scope.as_ref().map_or(false, |scope| scope.document_links())
+            && matches!(char, '(' | '[' | '/' | '#')
+        {
+            return true;
+        }
+
+        // The trigger characters are the host language's servers', which aren't used within an
+        // injected language that has its language servers turned off.
+        if !snapshot.settings_at(position, cx).enable_language_server {
+            return false;
+        }
+
         let anchor = snapshot.anchor_before(position);
         anchor
             .buffer_id
@@ -269,6 +269,9 @@ impl NodeRuntime for RealNodeRuntime {
             if let Some(proxy) = self.http.proxy() {
                 command.args(["--proxy", proxy]);
             }
+            if let Some(ca_certificates) = self.http.ca_certificates() {
+                command.arg("--cafile").arg(ca_certificates);
+            }
 
             #[cfg(windows)]
             {
@@ -310,6 +313,10 @@ impl NodeRuntime for RealNodeRuntime {
     }
 
     async fn npm_package_latest_version(&self, name: &str) -> Result<String> {
+        if self.http.is_offline() {
+            return Err(anyhow!("not querying npm for {name} in offline mode"));
+        }
+
         let output = self
             .run_npm_subcommand(
                 None,
@@ -369,6 +376,10 @@ impl NodeRuntime for RealNodeRuntime {
         directory: &Path,
         packages: &[(&str, &str)],
     ) -> Result<()> {
+        if self.http.is_offline() {
+            return Err(anyhow!("not installing npm packages in offline mode"));
+        }
+
         let packages: Vec<_> = packages
             .into_iter()
             .map(|(name, version)| format!("{name}@{version}"))
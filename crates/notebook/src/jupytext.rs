@@ -0,0 +1,301 @@
+//! Pairing of notebooks with scripts in Jupytext's percent format, in which each cell starts with
+//! a `# %%` marker line. A notebook is paired with the script next to it that has the same name
+//! and the extension of its language, and saving either side rewrites the other.
+
+use crate::nbformat::{CellType, Notebook};
+use editor::{
+    cells::{self, uncomment},
+    MultiBufferSnapshot,
+};
+use language::Point;
+use multi_buffer::MultiBufferRow;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+pub(crate) const NOTEBOOK_EXTENSION: &str = "ipynb";
+
+/// What follows the marker of a raw cell, as in `# %% [raw]`.
+const RAW_CELL_TAG: &str = "[raw]";
+
+/// The extension of scripts in the language, and the prefix of their line comments.
+fn script_format(language: &str) -> Option<(&'static str, &'static str)> {
+    Some(match language.to_lowercase().as_str() {
+        "python" => ("py", "#"),
+        "r" => ("R", "#"),
+        "julia" => ("jl", "#"),
+        "bash" | "sh" => ("sh", "#"),
+        "ruby" => ("rb", "#"),
+        "rust" => ("rs", "//"),
+        "javascript" => ("js", "//"),
+        "typescript" => ("ts", "//"),
+        "go" => ("go", "//"),
+        "scala" => ("scala", "//"),
+        "c++" | "cpp" => ("cpp", "//"),
+        _ => return None,
+    })
+}
+
+/// The script that the notebook at the path is paired with, or `None` if scripts can't be
+/// written in the notebook's language.
+pub fn paired_script_path(notebook_path: &Path, notebook: &Notebook) -> Option<PathBuf> {
+    let (extension, _) = script_format(notebook.language_name())?;
+    Some(notebook_path.with_extension(extension))
+}
+
+/// The notebook that the script at the path would be paired with.
+pub fn paired_notebook_path(script_path: &Path) -> Option<PathBuf> {
+    let extension = script_path.extension()?;
+    if extension == NOTEBOOK_EXTENSION {
+        return None;
+    }
+    Some(script_path.with_extension(NOTEBOOK_EXTENSION))
+}
+
+/// Whether the notebook's metadata asks for it to be paired with a script, as Jupytext records
+/// in `jupytext.formats`, such as `"ipynb,py:percent"`.
+pub fn is_paired(notebook: &Notebook) -> bool {
+    notebook
+        .metadata
+        .get("jupytext")
+        .and_then(|jupytext| jupytext.get("formats"))
+        .and_then(Value::as_str)
+        .map_or(false, |formats| {
+            formats
+                .split(',')
+                .any(|format| format.ends_with(":percent"))
+        })
+}
+
+/// The notebook's cells as a script in the percent format, or `None` if scripts can't be
+/// written in the notebook's language.
+pub fn notebook_to_script(notebook: &Notebook) -> Option<String> {
+    let (_, prefix) = script_format(notebook.language_name())?;
+    let cells = notebook
+        .cells
+        .iter()
+        .map(|cell| {
+            let (mut text, body) = match cell.cell_type {
+                CellType::Code => (format!("{prefix} %%"), cell.source.clone()),
+                CellType::Markdown => (
+                    format!("{prefix} %% [markdown]"),
+                    comment(&cell.source, prefix),
+                ),
+                CellType::Raw => (
+                    format!("{prefix} %% {RAW_CELL_TAG}"),
+                    comment(&cell.source, prefix),
+                ),
+            };
+            let body = body.trim_end();
+            if !body.is_empty() {
+                text.push('\n');
+                text.push_str(body);
+            }
+            text
+        })
+        .collect::<Vec<_>>();
+    Some(cells.join("\n\n") + "\n")
+}
+
+/// Replaces the notebook's cells with those of the script. Cells whose type and source are
+/// unchanged are kept as they were, along with their outputs and metadata.
+pub fn update_notebook_from_script(notebook: &mut Notebook, script: &MultiBufferSnapshot) {
+    let mut old_cells = std::mem::take(&mut notebook.cells)
+        .into_iter()
+        .map(Some)
+        .collect::<Vec<_>>();
+    for cell in cells::cells(script) {
+        let (cell_type, source) = if let Some(markdown) = cell.markdown(script) {
+            (CellType::Markdown, markdown)
+        } else if let Some(prefix) = raw_comment_prefix(&cell, script) {
+            (CellType::Raw, uncomment(&cell.code(script), prefix))
+        } else {
+            (CellType::Code, cell.code(script))
+        };
+
+        let old_cell = old_cells.iter_mut().find(|old_cell| {
+            old_cell.as_ref().map_or(false, |old_cell| {
+                old_cell.cell_type == cell_type && old_cell.source.trim_end() == source
+            })
+        });
+        let cell = match old_cell.and_then(Option::take) {
+            Some(old_cell) => old_cell,
+            None => notebook.new_cell(cell_type, source),
+        };
+        notebook.cells.push(cell);
+    }
+}
+
+/// The prefix of the comments that a raw cell is written in, or `None` if it isn't raw.
+fn raw_comment_prefix(cell: &cells::Cell, script: &MultiBufferSnapshot) -> Option<&'static str> {
+    let row = cell.marker_row?;
+    let range = Point::new(row, 0)..Point::new(row, script.line_len(MultiBufferRow(row)));
+    let marker = script.text_for_range(range).collect::<String>();
+    let marker = marker.trim_start();
+    if !marker.contains(RAW_CELL_TAG) {
+        return None;
+    }
+    Some(if marker.starts_with("//") { "//" } else { "#" })
+}
+
+fn comment(text: &str, prefix: &str) -> String {
+    text.lines()
+        .map(|line| {
+            if line.is_empty() {
+                prefix.to_string()
+            } else {
+                format!("{prefix} {line}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use editor::MultiBuffer;
+    use gpui::TestAppContext;
+    use indoc::indoc;
+
+    const NOTEBOOK: &str = indoc! {r##"
+        {
+         "cells": [
+          {
+           "cell_type": "markdown",
+           "id": "a1",
+           "metadata": {},
+           "source": [
+            "# Title\n",
+            "\n",
+            "Some *text*."
+           ]
+          },
+          {
+           "cell_type": "code",
+           "execution_count": 1,
+           "id": "b2",
+           "metadata": {},
+           "outputs": [
+            {
+             "name": "stdout",
+             "output_type": "stream",
+             "text": [
+              "hello\n"
+             ]
+            }
+           ],
+           "source": [
+            "print(\"hello\")"
+           ]
+          },
+          {
+           "cell_type": "code",
+           "execution_count": 2,
+           "id": "c3",
+           "metadata": {},
+           "outputs": [
+            {
+             "name": "stdout",
+             "output_type": "stream",
+             "text": [
+              "2\n"
+             ]
+            }
+           ],
+           "source": [
+            "print(1 + 1)"
+           ]
+          }
+         ],
+         "metadata": {
+          "jupytext": {
+           "formats": "ipynb,py:percent"
+          },
+          "kernelspec": {
+           "display_name": "Python 3",
+           "language": "python",
+           "name": "python3"
+          }
+         },
+         "nbformat": 4,
+         "nbformat_minor": 5
+        }
+    "##};
+
+    const SCRIPT: &str = indoc! {r#"
+        # %% [markdown]
+        # # Title
+        #
+        # Some *text*.
+
+        # %%
+        print("hello")
+
+        # %%
+        print(1 + 1)
+    "#};
+
+    #[test]
+    fn test_notebook_to_script() {
+        let notebook = Notebook::from_json(NOTEBOOK).unwrap();
+        assert!(is_paired(&notebook));
+        assert_eq!(notebook_to_script(&notebook).unwrap(), SCRIPT);
+        assert_eq!(
+            paired_script_path(Path::new("/dir/analysis.ipynb"), &notebook),
+            Some(PathBuf::from("/dir/analysis.py"))
+        );
+        assert_eq!(
+            paired_notebook_path(Path::new("/dir/analysis.py")),
+            Some(PathBuf::from("/dir/analysis.ipynb"))
+        );
+        assert_eq!(paired_notebook_path(Path::new("/dir/analysis.ipynb")), None);
+    }
+
+    #[gpui::test]
+    fn test_update_notebook_from_script(cx: &mut TestAppContext) {
+        let mut notebook = Notebook::from_json(NOTEBOOK).unwrap();
+
+        // A script that's unchanged keeps every cell as it was.
+        update_notebook_from_script(&mut notebook, &script_snapshot(SCRIPT, cx));
+        assert_eq!(notebook.to_json().unwrap(), NOTEBOOK);
+
+        // Edited and new cells lose their outputs, but the others keep theirs.
+        let script = indoc! {r#"
+            # %%
+            print(1 + 1)
+
+            # %%
+            print("hello, world")
+
+            # %% [raw]
+            # raw text
+
+            # %% [markdown]
+            # # Title
+            #
+            # Some *text*.
+        "#};
+        update_notebook_from_script(&mut notebook, &script_snapshot(script, cx));
+        let cells = notebook
+            .cells
+            .iter()
+            .map(|cell| (cell.cell_type, cell.source.as_str(), cell.outputs.len()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            cells,
+            [
+                (CellType::Code, "print(1 + 1)", 1),
+                (CellType::Code, "print(\"hello, world\")", 0),
+                (CellType::Raw, "raw text", 0),
+                (CellType::Markdown, "# Title\n\nSome *text*.", 0),
+            ]
+        );
+        assert_eq!(notebook.cells[0].fields["id"], "c3");
+        assert_eq!(notebook.cells[3].fields["id"], "a1");
+        assert_eq!(notebook_to_script(&notebook).unwrap(), script);
+    }
+
+    fn script_snapshot(text: &str, cx: &mut TestAppContext) -> MultiBufferSnapshot {
+        cx.update(|cx| MultiBuffer::build_simple(text, cx).read(cx).snapshot(cx))
+    }
+}
@@ -0,0 +1,444 @@
+//! Reading and writing Jupyter notebooks in version 4 of the nbformat JSON schema. Whatever this
+//! crate doesn't interpret, such as the metadata and outputs of cells, is kept as it was read, so
+//! that saving a notebook only changes the cells that were edited.
+
+use anyhow::{anyhow, Context as _, Result};
+use serde::Serialize as _;
+use serde_json::{Map, Value};
+
+/// The language of a notebook's code cells when its metadata doesn't name one.
+const DEFAULT_LANGUAGE: &str = "python";
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Notebook {
+    pub cells: Vec<Cell>,
+    pub metadata: Map<String, Value>,
+    pub nbformat: u64,
+    pub nbformat_minor: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CellType {
+    Code,
+    Markdown,
+    Raw,
+}
+
+impl CellType {
+    fn from_name(cell_type: &str) -> Option<Self> {
+        match cell_type {
+            "code" => Some(Self::Code),
+            "markdown" => Some(Self::Markdown),
+            "raw" => Some(Self::Raw),
+            _ => None,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Code => "code",
+            Self::Markdown => "markdown",
+            Self::Raw => "raw",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Cell {
+    pub cell_type: CellType,
+    pub source: String,
+    /// The number of the cell's last execution, for code cells that have run.
+    pub execution_count: Option<u64>,
+    /// The outputs of a code cell, as they were read.
+    pub outputs: Vec<Value>,
+    /// The cell's other fields, such as its `id` and `metadata`.
+    pub fields: Map<String, Value>,
+}
+
+/// An output of a code cell, in the richest form of it that can be shown outside of Jupyter.
+#[derive(Clone, Debug, PartialEq)]
+pub enum CellOutput {
+    Text(String),
+    /// A PNG image, as the base64 that notebooks store it in.
+    Png(String),
+}
+
+impl Cell {
+    fn new(cell_type: CellType, source: String) -> Self {
+        let mut fields = Map::new();
+        fields.insert("metadata".into(), Value::Object(Map::new()));
+        Self {
+            cell_type,
+            source,
+            execution_count: None,
+            outputs: Vec::new(),
+            fields,
+        }
+    }
+
+    fn from_json(value: Value) -> Result<Self> {
+        let Value::Object(mut fields) = value else {
+            return Err(anyhow!("a cell isn't an object"));
+        };
+        let cell_type = fields
+            .remove("cell_type")
+            .and_then(|cell_type| CellType::from_name(cell_type.as_str()?))
+            .context("a cell has no valid cell_type")?;
+        let source = fields
+            .remove("source")
+            .map(multiline_string)
+            .transpose()?
+            .unwrap_or_default();
+        let execution_count = fields
+            .remove("execution_count")
+            .and_then(|count| count.as_u64());
+        let outputs = match fields.remove("outputs") {
+            Some(Value::Array(outputs)) => outputs,
+            _ => Vec::new(),
+        };
+        Ok(Self {
+            cell_type,
+            source,
+            execution_count,
+            outputs,
+            fields,
+        })
+    }
+
+    fn to_json(&self) -> Value {
+        let mut cell = self.fields.clone();
+        cell.insert("cell_type".into(), self.cell_type.name().into());
+        cell.insert("source".into(), source_lines(&self.source));
+        if self.cell_type == CellType::Code {
+            cell.insert("execution_count".into(), self.execution_count.into());
+            cell.insert("outputs".into(), Value::Array(self.outputs.clone()));
+        }
+        Value::Object(cell)
+    }
+
+    /// The cell's outputs, with images in place of the text of rich outputs that have one.
+    pub fn display_outputs(&self) -> Vec<CellOutput> {
+        self.outputs
+            .iter()
+            .filter_map(|output| {
+                let output = match output["output_type"].as_str()? {
+                    "stream" => CellOutput::Text(multiline_string(output["text"].clone()).ok()?),
+                    "execute_result" | "display_data" => {
+                        let data = &output["data"];
+                        let png = multiline_string(data["image/png"].clone())
+                            .ok()
+                            .map(|png| png.split_whitespace().collect::<String>())
+                            .filter(|png| !png.is_empty());
+                        match png {
+                            Some(png) => CellOutput::Png(png),
+                            None => {
+                                CellOutput::Text(multiline_string(data["text/plain"].clone()).ok()?)
+                            }
+                        }
+                    }
+                    "error" => CellOutput::Text(format!(
+                        "{}: {}",
+                        output["ename"].as_str().unwrap_or("Error"),
+                        output["evalue"].as_str().unwrap_or_default()
+                    )),
+                    _ => return None,
+                };
+                match output {
+                    CellOutput::Text(text) => {
+                        let text = text.trim_end();
+                        (!text.is_empty()).then(|| CellOutput::Text(text.to_string()))
+                    }
+                    png => Some(png),
+                }
+            })
+            .collect()
+    }
+
+    /// The text of the cell's outputs, without any of their rich representations.
+    pub fn output_text(&self) -> String {
+        let mut text = String::new();
+        for output in &self.outputs {
+            let output_text = match output["output_type"].as_str() {
+                Some("stream") => multiline_string(output["text"].clone()).ok(),
+                Some("execute_result") | Some("display_data") => {
+                    let data = &output["data"];
+                    multiline_string(data["text/plain"].clone())
+                        .ok()
+                        .filter(|text| !text.is_empty())
+                        .or_else(|| {
+                            let mime_type = data.as_object()?.keys().next()?;
+                            Some(format!("<{mime_type} output>"))
+                        })
+                }
+                Some("error") => Some(format!(
+                    "{}: {}",
+                    output["ename"].as_str().unwrap_or("Error"),
+                    output["evalue"].as_str().unwrap_or_default()
+                )),
+                _ => None,
+            };
+            if let Some(output_text) = output_text {
+                text.push_str(output_text.trim_end_matches('\n'));
+                text.push('\n');
+            }
+        }
+        text.truncate(text.trim_end().len());
+        text
+    }
+}
+
+impl Notebook {
+    pub fn from_json(text: &str) -> Result<Self> {
+        let value: Value = serde_json::from_str(text).context("parsing the notebook's JSON")?;
+        let Value::Object(mut notebook) = value else {
+            return Err(anyhow!("the notebook isn't a JSON object"));
+        };
+        let nbformat = notebook
+            .get("nbformat")
+            .and_then(Value::as_u64)
+            .context("the notebook has no nbformat version")?;
+        if nbformat != 4 {
+            return Err(anyhow!(
+                "nbformat {nbformat} notebooks aren't supported, only nbformat 4"
+            ));
+        }
+        let nbformat_minor = notebook
+            .get("nbformat_minor")
+            .and_then(Value::as_u64)
+            .unwrap_or_default();
+        let metadata = match notebook.remove("metadata") {
+            Some(Value::Object(metadata)) => metadata,
+            _ => Map::new(),
+        };
+        let cells = match notebook.remove("cells") {
+            Some(Value::Array(cells)) => cells
+                .into_iter()
+                .map(Cell::from_json)
+                .collect::<Result<Vec<_>>>()?,
+            _ => Vec::new(),
+        };
+        Ok(Self {
+            cells,
+            metadata,
+            nbformat,
+            nbformat_minor,
+        })
+    }
+
+    /// Writes the notebook the way Jupyter does, with sorted keys and an indent of one space, so
+    /// that saving it doesn't reformat the whole file.
+    pub fn to_json(&self) -> Result<String> {
+        let mut notebook = Map::new();
+        notebook.insert(
+            "cells".into(),
+            self.cells.iter().map(Cell::to_json).collect(),
+        );
+        notebook.insert("metadata".into(), Value::Object(self.metadata.clone()));
+        notebook.insert("nbformat".into(), self.nbformat.into());
+        notebook.insert("nbformat_minor".into(), self.nbformat_minor.into());
+
+        let mut text = Vec::new();
+        let formatter = serde_json::ser::PrettyFormatter::with_indent(b" ");
+        let mut serializer = serde_json::Serializer::with_formatter(&mut text, formatter);
+        sort_keys(Value::Object(notebook)).serialize(&mut serializer)?;
+        text.push(b'\n');
+        Ok(String::from_utf8(text)?)
+    }
+
+    /// A cell that's yet to be added to the notebook, with an ID if its version of nbformat
+    /// requires one.
+    pub fn new_cell(&self, cell_type: CellType, source: String) -> Cell {
+        let mut cell = Cell::new(cell_type, source);
+        if self.nbformat_minor >= 5 {
+            let id = uuid::Uuid::new_v4().simple().to_string();
+            cell.fields.insert("id".into(), id[..8].into());
+        }
+        cell
+    }
+
+    /// The name of the kernelspec that the notebook was last run with.
+    pub fn kernelspec_name(&self) -> Option<&str> {
+        self.metadata
+            .get("kernelspec")
+            .and_then(|kernelspec| kernelspec.get("name"))
+            .and_then(Value::as_str)
+    }
+
+    /// The name of the kernel that the notebook was last run with.
+    pub fn kernel_name(&self) -> &str {
+        self.metadata
+            .get("kernelspec")
+            .and_then(|kernelspec| kernelspec.get("display_name"))
+            .and_then(Value::as_str)
+            .unwrap_or_else(|| self.language_name())
+    }
+
+    /// The name of the language that the notebook's code cells are written in.
+    pub fn language_name(&self) -> &str {
+        self.metadata
+            .get("kernelspec")
+            .and_then(|kernelspec| kernelspec.get("language"))
+            .or_else(|| {
+                self.metadata
+                    .get("language_info")
+                    .and_then(|info| info.get("name"))
+            })
+            .and_then(Value::as_str)
+            .unwrap_or(DEFAULT_LANGUAGE)
+    }
+}
+
+/// Reads a string that's either written whole or as an array of its lines.
+fn multiline_string(value: Value) -> Result<String> {
+    match value {
+        Value::String(text) => Ok(text),
+        Value::Array(lines) => lines
+            .into_iter()
+            .map(|line| match line {
+                Value::String(line) => Ok(line),
+                _ => Err(anyhow!("a line of a multiline string isn't a string")),
+            })
+            .collect(),
+        Value::Null => Ok(String::new()),
+        _ => Err(anyhow!("expected a string or an array of lines")),
+    }
+}
+
+/// Splits the source into lines that keep their newlines, which is how Jupyter writes it.
+fn source_lines(source: &str) -> Value {
+    source
+        .split_inclusive('\n')
+        .map(|line| Value::String(line.to_string()))
+        .collect()
+}
+
+fn sort_keys(value: Value) -> Value {
+    match value {
+        Value::Object(object) => {
+            let mut entries = object.into_iter().collect::<Vec<_>>();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key, sort_keys(value)))
+                    .collect(),
+            )
+        }
+        Value::Array(values) => Value::Array(values.into_iter().map(sort_keys).collect()),
+        value => value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    const NOTEBOOK: &str = indoc! {r##"
+        {
+         "cells": [
+          {
+           "cell_type": "markdown",
+           "id": "a1",
+           "metadata": {},
+           "source": [
+            "# Title\n",
+            "Some *text*."
+           ]
+          },
+          {
+           "cell_type": "code",
+           "execution_count": 2,
+           "id": "b2",
+           "metadata": {
+            "tags": [
+             "setup"
+            ]
+           },
+           "outputs": [
+            {
+             "name": "stdout",
+             "output_type": "stream",
+             "text": [
+              "hello\n"
+             ]
+            },
+            {
+             "data": {
+              "image/png": "iVBORw0KGgo=",
+              "text/plain": [
+               "<Figure size 640x480>"
+              ]
+             },
+             "metadata": {},
+             "output_type": "display_data"
+            }
+           ],
+           "source": [
+            "import sys\n",
+            "print(\"hello\")"
+           ]
+          }
+         ],
+         "metadata": {
+          "kernelspec": {
+           "display_name": "Python 3",
+           "language": "python",
+           "name": "python3"
+          }
+         },
+         "nbformat": 4,
+         "nbformat_minor": 5
+        }
+    "##};
+
+    #[test]
+    fn test_round_trip() {
+        let notebook = Notebook::from_json(NOTEBOOK).unwrap();
+        assert_eq!(notebook.cells.len(), 2);
+        assert_eq!(notebook.cells[0].cell_type, CellType::Markdown);
+        assert_eq!(notebook.cells[0].source, "# Title\nSome *text*.");
+        assert_eq!(notebook.cells[1].cell_type, CellType::Code);
+        assert_eq!(notebook.cells[1].execution_count, Some(2));
+        assert_eq!(notebook.language_name(), "python");
+        assert_eq!(notebook.kernel_name(), "Python 3");
+        assert_eq!(notebook.kernelspec_name(), Some("python3"));
+        assert_eq!(notebook.to_json().unwrap(), NOTEBOOK);
+    }
+
+    #[test]
+    fn test_edited_cells() {
+        let mut notebook = Notebook::from_json(NOTEBOOK).unwrap();
+        notebook.cells.swap(0, 1);
+        notebook.cells[1].source = "Edited\n".into();
+        let cell = notebook.new_cell(CellType::Code, String::new());
+        assert_eq!(cell.fields["id"].as_str().map(str::len), Some(8));
+        notebook.cells.push(cell);
+
+        let notebook = Notebook::from_json(&notebook.to_json().unwrap()).unwrap();
+        assert_eq!(notebook.cells[0].fields["id"], "b2");
+        assert_eq!(notebook.cells[0].outputs.len(), 2);
+        assert_eq!(notebook.cells[1].source, "Edited\n");
+        assert_eq!(notebook.cells[2].source, "");
+        assert_eq!(notebook.cells[2].execution_count, None);
+    }
+
+    #[test]
+    fn test_output_text() {
+        let notebook = Notebook::from_json(NOTEBOOK).unwrap();
+        assert_eq!(notebook.cells[0].output_text(), "");
+        assert_eq!(
+            notebook.cells[1].output_text(),
+            "hello\n<Figure size 640x480>"
+        );
+    }
+
+    #[test]
+    fn test_unsupported_notebooks() {
+        assert!(Notebook::from_json("[]").is_err());
+        assert!(Notebook::from_json(r#"{"nbformat": 3, "worksheets": []}"#).is_err());
+        assert!(
+            Notebook::from_json(r#"{"nbformat": 4, "nbformat_minor": 0, "cells": [{}]}"#).is_err()
+        );
+    }
+}
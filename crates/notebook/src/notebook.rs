@@ -0,0 +1,178 @@
+mod jupytext;
+mod nbformat;
+mod notebook_editor;
+
+use editor::{cells, Editor, EditorMode};
+use gpui::{actions, AppContext, Context as _, Model, Task, ViewContext};
+use jupytext::{is_paired, paired_notebook_path, update_notebook_from_script, NOTEBOOK_EXTENSION};
+use project::{Fs, Project, ProjectEntryId, ProjectPath};
+use std::{ffi::OsStr, path::PathBuf, sync::Arc};
+use workspace::{SaveIntent, Workspace};
+
+pub use nbformat::{Cell, CellOutput, CellType, Notebook};
+pub use notebook_editor::NotebookEditor;
+
+actions!(
+    notebook,
+    [
+        InsertCodeCell,
+        InsertMarkdownCell,
+        DeleteCell,
+        MoveCellUp,
+        MoveCellDown,
+        TogglePairedScript
+    ]
+);
+
+pub fn init(fs: Arc<dyn Fs>, cx: &mut AppContext) {
+    workspace::register_project_item::<NotebookEditor>(cx);
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(toggle_paired_script);
+    })
+    .detach();
+    cx.observe_new_views(move |editor: &mut Editor, cx| register_editor(editor, fs.clone(), cx))
+        .detach();
+}
+
+/// Replaces the active notebook with the script it's paired with, pairing it with one if it
+/// isn't yet, or the active script with the notebook it's paired with.
+fn toggle_paired_script(
+    workspace: &mut Workspace,
+    _: &TogglePairedScript,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let Some(item) = workspace.active_item(cx) else {
+        return;
+    };
+    let (target_path, write) = if let Some(notebook_editor) = item.downcast::<NotebookEditor>() {
+        let Some(paired) = notebook_editor.update(cx, |editor, cx| editor.pair_with_script(cx))
+        else {
+            return;
+        };
+        paired
+    } else {
+        let Some(project_path) = item.project_path(cx) else {
+            return;
+        };
+        let Some(notebook_path) = paired_notebook_path(&project_path.path) else {
+            return;
+        };
+        let notebook_path = ProjectPath {
+            worktree_id: project_path.worktree_id,
+            path: notebook_path.into(),
+        };
+        if workspace
+            .project()
+            .read(cx)
+            .entry_for_path(&notebook_path, cx)
+            .is_none()
+        {
+            return;
+        }
+        (notebook_path, Task::ready(Ok(())))
+    };
+
+    let pane = workspace.active_pane().downgrade();
+    let item_id = item.item_id();
+    cx.spawn(|workspace, mut cx| async move {
+        write.await?;
+        workspace
+            .update(&mut cx, |workspace, cx| {
+                workspace.open_path(target_path, Some(pane.clone()), true, cx)
+            })?
+            .await?;
+        pane.update(&mut cx, |pane, cx| {
+            pane.close_item_by_id(item_id, SaveIntent::Close, cx)
+        })?
+        .await
+    })
+    .detach_and_log_err(cx);
+}
+
+fn register_editor(editor: &mut Editor, fs: Arc<dyn Fs>, cx: &mut ViewContext<Editor>) {
+    if editor.mode() != EditorMode::Full {
+        return;
+    }
+    cx.subscribe(&editor.buffer().clone(), move |editor, _, event, cx| {
+        if matches!(event, multi_buffer::Event::Saved) {
+            sync_paired_notebook(editor, fs.clone(), cx);
+        }
+    })
+    .detach();
+}
+
+/// Rewrites the cells of the notebook that a saved script is paired with.
+fn sync_paired_notebook(editor: &mut Editor, fs: Arc<dyn Fs>, cx: &mut ViewContext<Editor>) {
+    let buffer = editor.buffer().read(cx);
+    let Some(script_path) = buffer
+        .as_singleton()
+        .and_then(|buffer| Some(buffer.read(cx).file()?.as_local()?.abs_path(cx)))
+    else {
+        return;
+    };
+    let Some(notebook_path) = paired_notebook_path(&script_path) else {
+        return;
+    };
+    let script = buffer.snapshot(cx);
+    if cells::cells(&script).is_empty() {
+        return;
+    }
+    cx.background_executor()
+        .spawn(async move {
+            if !fs.is_file(&notebook_path).await {
+                return Ok(());
+            }
+            let mut notebook = Notebook::from_json(&fs.load(&notebook_path).await?)?;
+            if !is_paired(&notebook) {
+                return Ok(());
+            }
+            update_notebook_from_script(&mut notebook, &script);
+            fs.atomic_write(notebook_path, notebook.to_json()?).await
+        })
+        .detach_and_log_err(cx);
+}
+
+pub struct NotebookItem {
+    abs_path: PathBuf,
+    project_path: ProjectPath,
+    entry_id: Option<ProjectEntryId>,
+}
+
+impl project::Item for NotebookItem {
+    fn try_open(
+        project: &Model<Project>,
+        path: &ProjectPath,
+        cx: &mut AppContext,
+    ) -> Option<Task<gpui::Result<Model<Self>>>> {
+        if path.path.extension().and_then(OsStr::to_str) != Some(NOTEBOOK_EXTENSION) {
+            return None;
+        }
+
+        let path = path.clone();
+        let project = project.clone();
+        Some(cx.spawn(|mut cx| async move {
+            let (abs_path, entry_id) = project.read_with(&cx, |project, cx| {
+                (
+                    project.absolute_path(&path, cx),
+                    project.entry_for_path(&path, cx).map(|entry| entry.id),
+                )
+            })?;
+            let abs_path =
+                abs_path.ok_or_else(|| anyhow::anyhow!("Failed to find the absolute path"))?;
+
+            cx.new_model(|_| NotebookItem {
+                abs_path,
+                project_path: path,
+                entry_id,
+            })
+        }))
+    }
+
+    fn entry_id(&self, _: &AppContext) -> Option<ProjectEntryId> {
+        self.entry_id
+    }
+
+    fn project_path(&self, _: &AppContext) -> Option<ProjectPath> {
+        Some(self.project_path.clone())
+    }
+}
@@ -0,0 +1,946 @@
+use crate::{
+    jupytext::{is_paired, notebook_to_script, paired_script_path},
+    nbformat::{Cell, CellType, Notebook},
+    DeleteCell, InsertCodeCell, InsertMarkdownCell, MoveCellDown, MoveCellUp, NotebookItem,
+    TogglePairedScript,
+};
+use anyhow::{anyhow, Result};
+use collections::HashSet;
+use editor::{
+    display_map::{BlockContext, BlockDisposition, BlockId, BlockProperties, BlockStyle},
+    scroll::Autoscroll,
+    Editor, EditorEvent, ExcerptId, ExcerptRange, MultiBuffer,
+};
+use gpui::{
+    Action, AnyElement, AppContext, EventEmitter, FocusHandle, FocusableView, Model, Subscription,
+    Task, View,
+};
+use jupyter::Kernelspecs;
+use language::{Buffer, Capability, Language};
+use project::{Project, ProjectPath};
+use serde_json::{Map, Value};
+use std::{mem, path::PathBuf, sync::Arc};
+use ui::{prelude::*, Tooltip};
+use workspace::item::{Item, ItemEvent, ProjectItem, TabContentParams};
+
+/// The most lines of a cell's outputs that are shown below it.
+const MAX_OUTPUT_LINES: usize = 20;
+
+/// Shows the cells of a Jupyter notebook in an editor, each as an excerpt of its own buffer, and
+/// saves them back to the notebook along with the outputs and metadata that were read from it.
+pub struct NotebookEditor {
+    item: Model<NotebookItem>,
+    project: Model<Project>,
+    state: NotebookState,
+    editor: View<Editor>,
+    /// The notebook's cells, in the order they're shown.
+    cells: Vec<CellState>,
+    code_language: Option<Arc<Language>>,
+    markdown_language: Option<Arc<Language>>,
+    output_blocks: HashSet<BlockId>,
+    /// Whether cells have been added, deleted or moved since the notebook was saved.
+    cells_changed: bool,
+    _load_notebook: Task<Result<()>>,
+    _editor_subscription: Subscription,
+    _kernelspecs_subscription: Option<Subscription>,
+}
+
+enum NotebookState {
+    Loading,
+    /// The notebook as it was last loaded, without its cells, which are edited in buffers of
+    /// their own.
+    Loaded(Notebook),
+    Failed(SharedString),
+}
+
+struct CellState {
+    cell: Cell,
+    buffer: Model<Buffer>,
+    excerpt_id: ExcerptId,
+}
+
+impl CellState {
+    fn to_cell(&self, cx: &AppContext) -> Cell {
+        let mut cell = self.cell.clone();
+        cell.source = self.buffer.read(cx).text();
+        cell
+    }
+}
+
+impl NotebookEditor {
+    pub fn new(
+        item: Model<NotebookItem>,
+        project: Model<Project>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let replica_id = project.read(cx).replica_id();
+        let multibuffer = cx.new_model(|_| MultiBuffer::new(replica_id, Capability::ReadWrite));
+        let editor = cx.new_view(|cx| {
+            let mut editor = Editor::for_multibuffer(multibuffer, Some(project.clone()), cx);
+            editor.set_show_inline_completions(false);
+            editor
+        });
+        let editor_subscription = cx.subscribe(&editor, |_, _, event: &EditorEvent, cx| {
+            cx.emit(event.clone())
+        });
+        let kernelspecs_subscription = Kernelspecs::try_global(cx)
+            .map(|kernelspecs| cx.observe(&kernelspecs, |_, _, cx| cx.notify()));
+
+        let mut this = Self {
+            item,
+            project,
+            state: NotebookState::Loading,
+            editor,
+            cells: Vec::new(),
+            code_language: None,
+            markdown_language: None,
+            output_blocks: HashSet::default(),
+            cells_changed: false,
+            _load_notebook: Task::ready(Ok(())),
+            _editor_subscription: editor_subscription,
+            _kernelspecs_subscription: kernelspecs_subscription,
+        };
+        this._load_notebook = this.load_notebook(cx);
+        this
+    }
+
+    fn load_notebook(&mut self, cx: &mut ViewContext<Self>) -> Task<Result<()>> {
+        let abs_path = self.item.read(cx).abs_path.clone();
+        let fs = self.project.read(cx).fs().clone();
+        let languages = self.project.read(cx).languages().clone();
+        cx.spawn(|this, mut cx| async move {
+            let notebook = cx
+                .background_executor()
+                .spawn(async move { Notebook::from_json(&fs.load(&abs_path).await?) })
+                .await;
+            let notebook = match notebook {
+                Ok(notebook) => notebook,
+                Err(error) => {
+                    this.update(&mut cx, |this, cx| {
+                        this.state = NotebookState::Failed(format!("{error:#}").into());
+                        cx.notify();
+                    })?;
+                    return Err(error);
+                }
+            };
+
+            let code_language = languages
+                .language_for_name_or_extension(notebook.language_name())
+                .await
+                .ok();
+            let markdown_language = languages.language_for_name("Markdown").await.ok();
+            this.update(&mut cx, |this, cx| {
+                this.code_language = code_language;
+                this.markdown_language = markdown_language;
+                this.set_notebook(notebook, cx);
+            })
+        })
+    }
+
+    fn set_notebook(&mut self, mut notebook: Notebook, cx: &mut ViewContext<Self>) {
+        let cells = mem::take(&mut notebook.cells);
+        self.editor.update(cx, |editor, cx| {
+            editor
+                .buffer()
+                .update(cx, |multibuffer, cx| multibuffer.clear(cx));
+        });
+        self.cells.clear();
+        for (ix, cell) in cells.into_iter().enumerate() {
+            self.insert_cell(ix, cell, cx);
+        }
+        self.state = NotebookState::Loaded(notebook);
+        self.cells_changed = false;
+        self.refresh_output_blocks(cx);
+        cx.emit(EditorEvent::DirtyChanged);
+        cx.notify();
+    }
+
+    /// Shows the cell at the index, in a buffer of its own.
+    fn insert_cell(&mut self, ix: usize, cell: Cell, cx: &mut ViewContext<Self>) {
+        let language = match cell.cell_type {
+            CellType::Code => self.code_language.clone(),
+            CellType::Markdown => self.markdown_language.clone(),
+            CellType::Raw => None,
+        };
+        let languages = self.project.read(cx).languages().clone();
+        let buffer = cx.new_model(|cx| {
+            let mut buffer = Buffer::local(cell.source.clone(), cx);
+            buffer.set_language_registry(languages);
+            buffer.set_language(language, cx);
+            buffer
+        });
+
+        let prev_excerpt_id = ix
+            .checked_sub(1)
+            .map_or(ExcerptId::min(), |prev_ix| self.cells[prev_ix].excerpt_id);
+        let excerpt_id = self.insert_excerpt(prev_excerpt_id, &buffer, cx);
+        let buffer_id = buffer.read(cx).remote_id();
+        let label = cell_label(&cell);
+        self.editor.update(cx, |editor, cx| {
+            editor.set_buffer_header_label(buffer_id, Some(label), cx);
+        });
+        self.cells.insert(
+            ix,
+            CellState {
+                cell,
+                buffer,
+                excerpt_id,
+            },
+        );
+    }
+
+    fn insert_excerpt(
+        &mut self,
+        prev_excerpt_id: ExcerptId,
+        buffer: &Model<Buffer>,
+        cx: &mut ViewContext<Self>,
+    ) -> ExcerptId {
+        let len = buffer.read(cx).len();
+        self.editor.update(cx, |editor, cx| {
+            editor.buffer().update(cx, |multibuffer, cx| {
+                multibuffer.insert_excerpts_after(
+                    prev_excerpt_id,
+                    buffer.clone(),
+                    [ExcerptRange {
+                        context: 0..len,
+                        primary: None,
+                    }],
+                    cx,
+                )[0]
+            })
+        })
+    }
+
+    fn remove_excerpt(&mut self, excerpt_id: ExcerptId, cx: &mut ViewContext<Self>) {
+        self.editor.update(cx, |editor, cx| {
+            editor.buffer().update(cx, |multibuffer, cx| {
+                multibuffer.remove_excerpts([excerpt_id], cx);
+            });
+        });
+    }
+
+    /// The index of the cell that contains the newest cursor.
+    fn active_cell_ix(&self, cx: &AppContext) -> Option<usize> {
+        let head = self.editor.read(cx).selections.newest_anchor().head();
+        self.cells
+            .iter()
+            .position(|cell| cell.excerpt_id == head.excerpt_id)
+    }
+
+    fn select_cell(&mut self, ix: usize, cx: &mut ViewContext<Self>) {
+        let Some(cell) = self.cells.get(ix) else {
+            return;
+        };
+        let excerpt_id = cell.excerpt_id;
+        let start = cell.buffer.read(cx).anchor_before(0);
+        self.editor.update(cx, |editor, cx| {
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            let Some(start) = snapshot.anchor_in_excerpt(excerpt_id, start) else {
+                return;
+            };
+            editor.change_selections(Some(Autoscroll::fit()), cx, |selections| {
+                selections.select_anchor_ranges([start..start])
+            });
+        });
+    }
+
+    fn cells_did_change(&mut self, cx: &mut ViewContext<Self>) {
+        self.cells_changed = true;
+        self.refresh_output_blocks(cx);
+        cx.emit(EditorEvent::DirtyChanged);
+        cx.notify();
+    }
+
+    fn insert_code_cell(&mut self, _: &InsertCodeCell, cx: &mut ViewContext<Self>) {
+        self.insert_new_cell(CellType::Code, cx);
+    }
+
+    fn insert_markdown_cell(&mut self, _: &InsertMarkdownCell, cx: &mut ViewContext<Self>) {
+        self.insert_new_cell(CellType::Markdown, cx);
+    }
+
+    /// Adds an empty cell after the active one, or at the end of the notebook.
+    fn insert_new_cell(&mut self, cell_type: CellType, cx: &mut ViewContext<Self>) {
+        let NotebookState::Loaded(notebook) = &self.state else {
+            return;
+        };
+        let cell = notebook.new_cell(cell_type, String::new());
+        let ix = self
+            .active_cell_ix(cx)
+            .map_or(self.cells.len(), |ix| ix + 1);
+        self.insert_cell(ix, cell, cx);
+        self.cells_did_change(cx);
+        self.select_cell(ix, cx);
+    }
+
+    fn delete_cell(&mut self, _: &DeleteCell, cx: &mut ViewContext<Self>) {
+        let Some(ix) = self.active_cell_ix(cx) else {
+            return;
+        };
+        let cell = self.cells.remove(ix);
+        let buffer_id = cell.buffer.read(cx).remote_id();
+        self.remove_excerpt(cell.excerpt_id, cx);
+        self.editor.update(cx, |editor, cx| {
+            editor.set_buffer_header_label(buffer_id, None, cx);
+        });
+
+        // Keep a cell to type into, since an editor without excerpts can't be edited.
+        if self.cells.is_empty() {
+            if let NotebookState::Loaded(notebook) = &self.state {
+                let cell = notebook.new_cell(CellType::Code, String::new());
+                self.insert_cell(0, cell, cx);
+            }
+        }
+        self.cells_did_change(cx);
+        self.select_cell(ix.min(self.cells.len().saturating_sub(1)), cx);
+    }
+
+    fn move_cell_up(&mut self, _: &MoveCellUp, cx: &mut ViewContext<Self>) {
+        if let Some(ix) = self.active_cell_ix(cx).filter(|ix| *ix > 0) {
+            self.move_cell(ix, ix - 1, cx);
+        }
+    }
+
+    fn move_cell_down(&mut self, _: &MoveCellDown, cx: &mut ViewContext<Self>) {
+        if let Some(ix) = self
+            .active_cell_ix(cx)
+            .filter(|ix| ix + 1 < self.cells.len())
+        {
+            self.move_cell(ix, ix + 1, cx);
+        }
+    }
+
+    /// Moves a cell by replacing its excerpt with one at its new position, keeping its buffer.
+    fn move_cell(&mut self, ix: usize, new_ix: usize, cx: &mut ViewContext<Self>) {
+        let cell = self.cells.remove(ix);
+        self.remove_excerpt(cell.excerpt_id, cx);
+        let prev_excerpt_id = new_ix
+            .checked_sub(1)
+            .map_or(ExcerptId::min(), |prev_ix| self.cells[prev_ix].excerpt_id);
+        let excerpt_id = self.insert_excerpt(prev_excerpt_id, &cell.buffer, cx);
+        self.cells.insert(new_ix, CellState { excerpt_id, ..cell });
+        self.cells_did_change(cx);
+        self.select_cell(new_ix, cx);
+    }
+
+    /// Shows the text of each code cell's outputs below it.
+    fn refresh_output_blocks(&mut self, cx: &mut ViewContext<Self>) {
+        let outputs = self
+            .cells
+            .iter()
+            .filter_map(|cell| {
+                let lines = output_lines(&cell.cell.output_text());
+                (!lines.is_empty()).then_some((cell.excerpt_id, lines))
+            })
+            .collect::<Vec<_>>();
+        self.editor.update(cx, |editor, cx| {
+            editor.remove_blocks(mem::take(&mut self.output_blocks), None, cx);
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            let blocks = outputs
+                .into_iter()
+                .filter_map(|(excerpt_id, lines)| {
+                    Some(BlockProperties {
+                        position: snapshot.anchor_in_excerpt(excerpt_id, language::Anchor::MAX)?,
+                        height: lines.len() as u8,
+                        style: BlockStyle::Fixed,
+                        disposition: BlockDisposition::Below,
+                        render: Box::new(move |cx| render_output(&lines, cx)),
+                    })
+                })
+                .collect::<Vec<_>>();
+            self.output_blocks = editor.insert_blocks(blocks, None, cx).into_iter().collect();
+        });
+    }
+
+    /// Pairs the notebook with a script in Jupytext's percent format, recording the pairing in
+    /// its metadata as Jupytext does, and writes both. Returns the path of the script, or `None`
+    /// if scripts can't be written in the notebook's language.
+    pub(crate) fn pair_with_script(
+        &mut self,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<(ProjectPath, Task<Result<()>>)> {
+        let NotebookState::Loaded(notebook) = &mut self.state else {
+            return None;
+        };
+        let project_path = self.item.read(cx).project_path.clone();
+        let script_path = paired_script_path(&project_path.path, notebook)?;
+        if !is_paired(notebook) {
+            let extension = script_path.extension()?.to_string_lossy();
+            let jupytext = notebook
+                .metadata
+                .entry("jupytext")
+                .or_insert_with(|| Value::Object(Map::new()));
+            if let Value::Object(jupytext) = jupytext {
+                jupytext.insert(
+                    "formats".into(),
+                    format!("ipynb,{extension}:percent").into(),
+                );
+            }
+        }
+        let abs_path = self.item.read(cx).abs_path.clone();
+        let write = self.write_notebook(abs_path, cx);
+        Some((
+            ProjectPath {
+                worktree_id: project_path.worktree_id,
+                path: script_path.into(),
+            },
+            write,
+        ))
+    }
+
+    /// The notebook with its cells as they're shown, or `None` if it hasn't loaded.
+    pub fn notebook(&self, cx: &AppContext) -> Option<Notebook> {
+        let NotebookState::Loaded(notebook) = &self.state else {
+            return None;
+        };
+        let mut notebook = notebook.clone();
+        notebook.cells = self.cells.iter().map(|cell| cell.to_cell(cx)).collect();
+        Some(notebook)
+    }
+
+    /// Writes the notebook, with the cells as they're shown, to the path, along with the script
+    /// it's paired with.
+    fn write_notebook(
+        &mut self,
+        abs_path: PathBuf,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        let Some(notebook) = self.notebook(cx) else {
+            return Task::ready(Err(anyhow!("the notebook hasn't loaded")));
+        };
+        let text = match notebook.to_json() {
+            Ok(text) => text,
+            Err(error) => return Task::ready(Err(error)),
+        };
+        let script = if is_paired(&notebook) {
+            paired_script_path(&abs_path, &notebook).zip(notebook_to_script(&notebook))
+        } else {
+            None
+        };
+        let versions = self
+            .cells
+            .iter()
+            .map(|cell| (cell.buffer.clone(), cell.buffer.read(cx).version()))
+            .collect::<Vec<_>>();
+        let fs = self.project.read(cx).fs().clone();
+        cx.spawn(|this, mut cx| async move {
+            fs.atomic_write(abs_path, text).await?;
+            if let Some((script_path, script)) = script {
+                fs.atomic_write(script_path, script).await?;
+            }
+            this.update(&mut cx, |this, cx| {
+                for (buffer, version) in versions {
+                    buffer.update(cx, |buffer, cx| buffer.did_save(version, None, cx));
+                }
+                this.cells_changed = false;
+                cx.emit(EditorEvent::Saved);
+                cx.emit(EditorEvent::DirtyChanged);
+            })
+        })
+    }
+
+    fn render_button(
+        &self,
+        id: &'static str,
+        icon: IconName,
+        tooltip: &'static str,
+        action: &dyn Action,
+        cx: &mut ViewContext<Self>,
+    ) -> IconButton {
+        let tooltip_action = action.boxed_clone();
+        let action = action.boxed_clone();
+        IconButton::new(id, icon)
+            .icon_size(IconSize::Small)
+            .disabled(!matches!(self.state, NotebookState::Loaded(_)))
+            .tooltip(move |cx| Tooltip::for_action(tooltip, tooltip_action.as_ref(), cx))
+            .on_click(cx.listener(move |this, _, cx| {
+                // The cell actions apply to the cell with the editor's cursor.
+                cx.focus_view(&this.editor);
+                cx.dispatch_action(action.boxed_clone());
+            }))
+    }
+
+    fn render_toolbar(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let kernel = match &self.state {
+            NotebookState::Loaded(notebook) => Some(kernel_label(notebook, cx)),
+            _ => None,
+        };
+        h_flex()
+            .px_2()
+            .py_1()
+            .gap_1()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .child(self.render_button(
+                "insert-code-cell",
+                IconName::Code,
+                "Add Code Cell",
+                &InsertCodeCell,
+                cx,
+            ))
+            .child(self.render_button(
+                "insert-markdown-cell",
+                IconName::FileDoc,
+                "Add Markdown Cell",
+                &InsertMarkdownCell,
+                cx,
+            ))
+            .child(self.render_button(
+                "move-cell-up",
+                IconName::ArrowUp,
+                "Move Cell Up",
+                &MoveCellUp,
+                cx,
+            ))
+            .child(self.render_button(
+                "move-cell-down",
+                IconName::ArrowDown,
+                "Move Cell Down",
+                &MoveCellDown,
+                cx,
+            ))
+            .child(self.render_button(
+                "delete-cell",
+                IconName::Trash,
+                "Delete Cell",
+                &DeleteCell,
+                cx,
+            ))
+            .child(self.render_button(
+                "toggle-paired-script",
+                IconName::FileGeneric,
+                "Open Paired Script",
+                &TogglePairedScript,
+                cx,
+            ))
+            .child(div().flex_1())
+            .children(
+                kernel
+                    .map(|(kernel, color)| Label::new(kernel).size(LabelSize::Small).color(color)),
+            )
+    }
+}
+
+/// The installed kernel that the notebook would run with, or the kernel it was last run with
+/// when none is installed for it.
+fn kernel_label(notebook: &Notebook, cx: &AppContext) -> (SharedString, Color) {
+    let kernelspec = Kernelspecs::try_global(cx).and_then(|kernelspecs| {
+        kernelspecs
+            .read(cx)
+            .kernelspec_for(notebook.kernelspec_name(), notebook.language_name())
+            .map(|kernelspec| kernelspec.display_name.clone())
+    });
+    match kernelspec {
+        Some(display_name) => (display_name.into(), Color::Muted),
+        None => (
+            format!("{} (not installed)", notebook.kernel_name()).into(),
+            Color::Warning,
+        ),
+    }
+}
+
+/// The label shown in the header of a cell, which for code cells is the number of their last
+/// execution, as in Jupyter.
+fn cell_label(cell: &Cell) -> SharedString {
+    match cell.cell_type {
+        CellType::Code => match cell.execution_count {
+            Some(count) => format!("In [{count}]").into(),
+            None => "In [ ]".into(),
+        },
+        CellType::Markdown => "Markdown".into(),
+        CellType::Raw => "Raw".into(),
+    }
+}
+
+fn output_lines(text: &str) -> Vec<SharedString> {
+    let mut lines = text
+        .lines()
+        .map(|line| SharedString::from(line.to_string()))
+        .collect::<Vec<_>>();
+    if lines.len() > MAX_OUTPUT_LINES {
+        let hidden_count = lines.len() - MAX_OUTPUT_LINES + 1;
+        lines.truncate(MAX_OUTPUT_LINES - 1);
+        lines.push(format!("… {hidden_count} more lines").into());
+    }
+    lines
+}
+
+fn render_output(lines: &[SharedString], cx: &mut BlockContext) -> AnyElement {
+    let line_height = cx.line_height;
+    h_flex()
+        .w_full()
+        .items_start()
+        .child(
+            h_flex()
+                .justify_center()
+                .w(cx.gutter_dimensions.width)
+                .h(line_height)
+                .child(Label::new("Out").size(LabelSize::Small).color(Color::Muted)),
+        )
+        .child(
+            v_flex()
+                .font(cx.editor_style.text.font())
+                .text_color(cx.theme().colors().text_muted)
+                .children(
+                    lines
+                        .iter()
+                        .map(|line| div().h(line_height).child(line.clone())),
+                ),
+        )
+        .into_any_element()
+}
+
+impl Render for NotebookEditor {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let body = match &self.state {
+            NotebookState::Loading => v_flex()
+                .flex_1()
+                .items_center()
+                .justify_center()
+                .child(Label::new("Loading notebook…").color(Color::Muted))
+                .into_any_element(),
+            NotebookState::Failed(error) => v_flex()
+                .flex_1()
+                .items_center()
+                .justify_center()
+                .child(Label::new(error.clone()).color(Color::Error))
+                .into_any_element(),
+            NotebookState::Loaded(_) => div()
+                .flex_1()
+                .w_full()
+                .child(self.editor.clone())
+                .into_any_element(),
+        };
+
+        v_flex()
+            .key_context("NotebookEditor")
+            .on_action(cx.listener(Self::insert_code_cell))
+            .on_action(cx.listener(Self::insert_markdown_cell))
+            .on_action(cx.listener(Self::delete_cell))
+            .on_action(cx.listener(Self::move_cell_up))
+            .on_action(cx.listener(Self::move_cell_down))
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(self.render_toolbar(cx))
+            .child(body)
+    }
+}
+
+impl EventEmitter<EditorEvent> for NotebookEditor {}
+
+impl FocusableView for NotebookEditor {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.editor.focus_handle(cx)
+    }
+}
+
+impl Item for NotebookEditor {
+    type Event = EditorEvent;
+
+    fn to_item_events(event: &EditorEvent, f: impl FnMut(ItemEvent)) {
+        Editor::to_item_events(event, f)
+    }
+
+    fn tab_content(&self, params: TabContentParams, cx: &WindowContext) -> AnyElement {
+        let abs_path = &self.item.read(cx).abs_path;
+        let title = abs_path
+            .file_name()
+            .unwrap_or_else(|| abs_path.as_os_str())
+            .to_string_lossy()
+            .to_string();
+        Label::new(title)
+            .single_line()
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .italic(params.preview)
+            .into_any_element()
+    }
+
+    fn tab_tooltip_text(&self, cx: &AppContext) -> Option<SharedString> {
+        Some(
+            self.item
+                .read(cx)
+                .abs_path
+                .to_string_lossy()
+                .to_string()
+                .into(),
+        )
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("notebook opened")
+    }
+
+    fn for_each_project_item(
+        &self,
+        cx: &AppContext,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::Item),
+    ) {
+        f(self.item.entity_id(), self.item.read(cx))
+    }
+
+    fn is_singleton(&self, _: &AppContext) -> bool {
+        true
+    }
+
+    fn is_dirty(&self, cx: &AppContext) -> bool {
+        self.cells_changed
+            || self
+                .cells
+                .iter()
+                .any(|cell| cell.buffer.read(cx).is_dirty())
+    }
+
+    fn can_save(&self, _: &AppContext) -> bool {
+        matches!(self.state, NotebookState::Loaded(_))
+    }
+
+    fn save(
+        &mut self,
+        _format: bool,
+        _project: Model<Project>,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        let abs_path = self.item.read(cx).abs_path.clone();
+        self.write_notebook(abs_path, cx)
+    }
+
+    fn save_as(
+        &mut self,
+        project: Model<Project>,
+        path: ProjectPath,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        let Some(abs_path) = project.read(cx).absolute_path(&path, cx) else {
+            return Task::ready(Err(anyhow!("Failed to find the absolute path")));
+        };
+        let write = self.write_notebook(abs_path.clone(), cx);
+        let item = self.item.clone();
+        cx.spawn(|this, mut cx| async move {
+            write.await?;
+            item.update(&mut cx, |item, cx| {
+                item.entry_id = project.read(cx).entry_for_path(&path, cx).map(|e| e.id);
+                item.abs_path = abs_path;
+                item.project_path = path;
+            })?;
+            this.update(&mut cx, |_, cx| cx.emit(EditorEvent::TitleChanged))
+        })
+    }
+
+    fn reload(&mut self, _project: Model<Project>, cx: &mut ViewContext<Self>) -> Task<Result<()>> {
+        self.load_notebook(cx)
+    }
+}
+
+impl ProjectItem for NotebookEditor {
+    type Item = NotebookItem;
+
+    fn for_project_item(
+        project: Model<Project>,
+        item: Model<Self::Item>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self
+    where
+        Self: Sized,
+    {
+        Self::new(item, project, cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{TestAppContext, VisualTestContext};
+    use indoc::indoc;
+    use project::{FakeFs, Fs as _};
+    use serde_json::json;
+    use std::path::Path;
+    use workspace::AppState;
+
+    const NOTEBOOK: &str = indoc! {r##"
+        {
+         "cells": [
+          {
+           "cell_type": "markdown",
+           "id": "a1",
+           "metadata": {},
+           "source": [
+            "# Title"
+           ]
+          },
+          {
+           "cell_type": "code",
+           "execution_count": 1,
+           "id": "b2",
+           "metadata": {},
+           "outputs": [
+            {
+             "name": "stdout",
+             "output_type": "stream",
+             "text": [
+              "hello\n"
+             ]
+            }
+           ],
+           "source": [
+            "print(\"hello\")"
+           ]
+          }
+         ],
+         "metadata": {
+          "kernelspec": {
+           "display_name": "Python 3",
+           "language": "python",
+           "name": "python3"
+          }
+         },
+         "nbformat": 4,
+         "nbformat_minor": 5
+        }
+    "##};
+
+    #[gpui::test]
+    async fn test_edit_and_save_cells(cx: &mut TestAppContext) {
+        init_test(cx);
+        let (notebook_editor, project, fs, cx) = open_notebook(NOTEBOOK, cx).await;
+        let original = Notebook::from_json(NOTEBOOK).unwrap();
+
+        notebook_editor.update(cx, |notebook_editor, cx| {
+            assert_eq!(
+                cell_sources(notebook_editor, cx),
+                ["# Title", "print(\"hello\")"]
+            );
+            assert!(!notebook_editor.is_dirty(cx));
+
+            // A new cell goes after the one with the cursor, and is selected.
+            notebook_editor.insert_code_cell(&InsertCodeCell, cx);
+            notebook_editor
+                .editor
+                .update(cx, |editor, cx| editor.insert("x = 1", cx));
+            assert_eq!(
+                cell_sources(notebook_editor, cx),
+                ["# Title", "x = 1", "print(\"hello\")"]
+            );
+            assert!(notebook_editor.is_dirty(cx));
+
+            notebook_editor.move_cell_down(&MoveCellDown, cx);
+            assert_eq!(
+                cell_sources(notebook_editor, cx),
+                ["# Title", "print(\"hello\")", "x = 1"]
+            );
+            assert_eq!(notebook_editor.active_cell_ix(cx), Some(2));
+            notebook_editor.move_cell_down(&MoveCellDown, cx);
+            assert_eq!(notebook_editor.active_cell_ix(cx), Some(2));
+
+            notebook_editor.select_cell(0, cx);
+            notebook_editor.delete_cell(&DeleteCell, cx);
+            assert_eq!(
+                cell_sources(notebook_editor, cx),
+                ["print(\"hello\")", "x = 1"]
+            );
+            assert_eq!(notebook_editor.active_cell_ix(cx), Some(0));
+        });
+
+        let save = notebook_editor.update(cx, |notebook_editor, cx| {
+            notebook_editor.save(false, project.clone(), cx)
+        });
+        save.await.unwrap();
+        notebook_editor.update(cx, |notebook_editor, cx| {
+            assert!(!notebook_editor.is_dirty(cx));
+        });
+
+        // The saved notebook keeps the outputs and metadata of what wasn't edited.
+        let saved = fs.load(Path::new("/root/analysis.ipynb")).await.unwrap();
+        let notebook = Notebook::from_json(&saved).unwrap();
+        assert_eq!(notebook.to_json().unwrap(), saved);
+        assert_eq!(notebook.metadata, original.metadata);
+        assert_eq!(notebook.cells.len(), 2);
+        assert_eq!(notebook.cells[0], original.cells[1]);
+        assert_eq!(notebook.cells[1].cell_type, CellType::Code);
+        assert_eq!(notebook.cells[1].source, "x = 1");
+        assert!(notebook.cells[1].outputs.is_empty());
+        assert!(notebook.cells[1].fields.contains_key("id"));
+    }
+
+    #[gpui::test]
+    async fn test_delete_last_cell(cx: &mut TestAppContext) {
+        init_test(cx);
+        let notebook = indoc! {r#"
+            {
+             "cells": [
+              {
+               "cell_type": "markdown",
+               "metadata": {},
+               "source": [
+                "Only cell"
+               ]
+              }
+             ],
+             "metadata": {},
+             "nbformat": 4,
+             "nbformat_minor": 4
+            }
+        "#};
+        let (notebook_editor, _, _, cx) = open_notebook(notebook, cx).await;
+
+        // An empty code cell replaces the last cell, so that there's somewhere to type.
+        notebook_editor.update(cx, |notebook_editor, cx| {
+            notebook_editor.delete_cell(&DeleteCell, cx);
+            assert_eq!(cell_sources(notebook_editor, cx), [""]);
+            assert_eq!(notebook_editor.cells[0].cell.cell_type, CellType::Code);
+        });
+    }
+
+    /// Opens the notebook's text as `/root/analysis.ipynb`.
+    async fn open_notebook<'a>(
+        text: &str,
+        cx: &'a mut TestAppContext,
+    ) -> (
+        View<NotebookEditor>,
+        Model<Project>,
+        Arc<FakeFs>,
+        &'a mut VisualTestContext,
+    ) {
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/root", json!({ "analysis.ipynb": text }))
+            .await;
+        let project = Project::test(fs.clone(), ["/root".as_ref()], cx).await;
+        let worktree_id = project.read_with(cx, |project, cx| {
+            project.worktrees().next().unwrap().read(cx).id()
+        });
+        let project_path = ProjectPath {
+            worktree_id,
+            path: Path::new("analysis.ipynb").into(),
+        };
+        let item = cx
+            .update(|cx| <NotebookItem as project::Item>::try_open(&project, &project_path, cx))
+            .unwrap()
+            .await
+            .unwrap();
+        let (notebook_editor, cx) =
+            cx.add_window_view(|cx| NotebookEditor::new(item, project.clone(), cx));
+        cx.run_until_parked();
+        (notebook_editor, project, fs, cx)
+    }
+
+    fn cell_sources(notebook_editor: &NotebookEditor, cx: &AppContext) -> Vec<String> {
+        notebook_editor
+            .cells
+            .iter()
+            .map(|cell| cell.buffer.read(cx).text())
+            .collect()
+    }
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            AppState::test(cx);
+            language::init(cx);
+            editor::init(cx);
+            workspace::init_settings(cx);
+            Project::init_settings(cx);
+        });
+    }
+}
@@ -123,7 +123,7 @@ pub enum ToolDefinition {
     Function { function: FunctionDefinition },
 }
 
-#[derive(Serialize, Deserialize, Debug, Eq, PartialEq)]
+#[derive(Clone, Serialize, Deserialize, Debug, Eq, PartialEq)]
 #[serde(tag = "role", rename_all = "lowercase")]
 pub enum RequestMessage {
     Assistant {
@@ -143,20 +143,20 @@ pub enum RequestMessage {
     },
 }
 
-#[derive(Serialize, Deserialize, Debug, Eq, PartialEq)]
+#[derive(Clone, Serialize, Deserialize, Debug, Eq, PartialEq)]
 pub struct ToolCall {
     pub id: String,
     #[serde(flatten)]
     pub content: ToolCallContent,
 }
 
-#[derive(Serialize, Deserialize, Debug, Eq, PartialEq)]
+#[derive(Clone, Serialize, Deserialize, Debug, Eq, PartialEq)]
 #[serde(tag = "type", rename_all = "lowercase")]
 pub enum ToolCallContent {
     Function { function: FunctionContent },
 }
 
-#[derive(Serialize, Deserialize, Debug, Eq, PartialEq)]
+#[derive(Clone, Serialize, Deserialize, Debug, Eq, PartialEq)]
 pub struct FunctionContent {
     pub name: String,
     pub arguments: String,
@@ -216,18 +216,38 @@ pub async fn stream_completion(
     request: Request,
     low_speed_timeout: Option<Duration>,
 ) -> Result<BoxStream<'static, Result<ResponseStreamEvent>>> {
-    let uri = format!("{api_url}/chat/completions");
+    stream_compatible_completion(
+        client,
+        &format!("{api_url}/chat/completions"),
+        Some(("Authorization", &format!("Bearer {}", api_key))),
+        &request,
+        low_speed_timeout,
+    )
+    .await
+}
+
+/// Streams a completion from an API that's compatible with OpenAI's, like Azure OpenAI's or
+/// Ollama's, which differ in their URLs, how they're authenticated, and the names of their models.
+pub async fn stream_compatible_completion(
+    client: &dyn HttpClient,
+    uri: &str,
+    auth_header: Option<(&str, &str)>,
+    request: &impl Serialize,
+    low_speed_timeout: Option<Duration>,
+) -> Result<BoxStream<'static, Result<ResponseStreamEvent>>> {
     let mut request_builder = HttpRequest::builder()
         .method(Method::POST)
         .uri(uri)
-        .header("Content-Type", "application/json")
-        .header("Authorization", format!("Bearer {}", api_key));
+        .header("Content-Type", "application/json");
+    if let Some((name, value)) = auth_header {
+        request_builder = request_builder.header(name, value);
+    }
 
     if let Some(low_speed_timeout) = low_speed_timeout {
         request_builder = request_builder.low_speed_timeout(100, low_speed_timeout);
     };
 
-    let request = request_builder.body(AsyncBody::from(serde_json::to_string(&request)?))?;
+    let request = request_builder.body(AsyncBody::from(serde_json::to_string(request)?))?;
     let mut response = client.send(request).await?;
     if response.status().is_success() {
         let reader = BufReader::new(response.into_body());
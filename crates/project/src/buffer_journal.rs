@@ -0,0 +1,559 @@
+use std::{
+    collections::HashMap,
+    fs,
+    io::Write as _,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use anyhow::{Context as _, Result};
+use collections::HashSet;
+use futures::{channel::mpsc, StreamExt};
+use gpui::{
+    AppContext, Context, EntityId, Global, Model, ModelContext, Subscription, Task, WeakModel,
+};
+use language::{Buffer, Event as BufferEvent, LocalFile};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use text::LineEnding;
+
+/// How long edits can go unjournaled after a buffer is edited.
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A write-ahead journal of the unsaved edits to local buffers, so that they can be recovered
+/// after a crash or power loss. Each dirty buffer gets a journal file, which starts with a
+/// [`JournalHeader`] identifying the file on disk the edits apply to, followed by a
+/// [`JournalEntry`] for every flush of edits since. Journals are deleted once their buffer is
+/// saved, reloaded, or closed, so any journals left behind by a previous session hold edits
+/// that were lost.
+pub struct BufferJournal {
+    dir: PathBuf,
+    /// Identifies the journals written by this session, so they aren't offered for recovery.
+    session_id: String,
+    buffers: HashMap<EntityId, JournaledBuffer>,
+    writes: mpsc::UnboundedSender<JournalWrite>,
+    _writer: Task<()>,
+    _quit_subscription: Subscription,
+}
+
+struct GlobalBufferJournal(Model<BufferJournal>);
+
+impl Global for GlobalBufferJournal {}
+
+struct JournaledBuffer {
+    buffer: WeakModel<Buffer>,
+    abs_path: PathBuf,
+    journal_path: PathBuf,
+    /// The hash of the file's contents on disk, which the journaled edits apply to.
+    base_hash: String,
+    /// The version of the buffer matching the file on disk.
+    base_version: clock::Global,
+    /// The version of the buffer the journal has been written up to.
+    flushed_version: clock::Global,
+    /// Whether the journal's header has been written.
+    started: bool,
+    flush_task: Option<Task<()>>,
+    _subscriptions: [Subscription; 2],
+}
+
+enum JournalWrite {
+    Append { path: PathBuf, lines: String },
+    Delete { path: PathBuf },
+}
+
+#[derive(Serialize, Deserialize)]
+struct JournalHeader {
+    path: PathBuf,
+    base_hash: String,
+}
+
+/// The edits made to a buffer between two flushes, as `(start, end, new_text)`, where the
+/// offsets are in the text as of the previous flush.
+#[derive(Serialize, Deserialize)]
+struct JournalEntry {
+    edits: Vec<(usize, usize, String)>,
+}
+
+/// The unsaved contents of a file, recovered from the journal of a previous session.
+#[derive(Clone, Debug)]
+pub struct RecoveredBuffer {
+    pub abs_path: PathBuf,
+    pub journal_path: PathBuf,
+    /// The file's contents on disk, with normalized line endings.
+    pub disk_text: String,
+    /// The buffer's contents when its journal was last written.
+    pub recovered_text: String,
+}
+
+impl RecoveredBuffer {
+    /// A unified diff of the changes recovering the buffer would make to the file.
+    pub fn diff(&self) -> String {
+        similar::TextDiff::from_lines(&self.disk_text, &self.recovered_text)
+            .unified_diff()
+            .context_radius(2)
+            .header(
+                &self.abs_path.to_string_lossy(),
+                &format!("{} (recovered)", self.abs_path.to_string_lossy()),
+            )
+            .to_string()
+    }
+}
+
+impl BufferJournal {
+    /// Starts journaling the buffers of local projects into the given directory.
+    pub fn init(dir: PathBuf, cx: &mut AppContext) {
+        let journal = cx.new_model(|cx| Self::new(dir, cx));
+        cx.set_global(GlobalBufferJournal(journal));
+    }
+
+    pub fn global(cx: &AppContext) -> Option<Model<Self>> {
+        cx.try_global::<GlobalBufferJournal>()
+            .map(|journal| journal.0.clone())
+    }
+
+    fn new(dir: PathBuf, cx: &mut ModelContext<Self>) -> Self {
+        let session_id = format!(
+            "{}-{:x}",
+            SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map_or(0, |duration| duration.as_millis()),
+            rand::random::<u32>()
+        );
+
+        // Journals are written by a single background task, so that writes to the same journal
+        // happen in order.
+        let (writes, mut rx) = mpsc::unbounded();
+        let writer = cx.background_executor().spawn({
+            let dir = dir.clone();
+            async move {
+                while let Some(write) = rx.next().await {
+                    if let Err(error) = apply_write(&dir, write) {
+                        log::error!("failed to write buffer journal: {error:?}");
+                    }
+                }
+            }
+        });
+
+        // Quitting means the user decided what to do with their unsaved edits.
+        let quit_subscription = cx.on_app_quit(|this, _| {
+            for buffer in this.buffers.values() {
+                if buffer.started {
+                    fs::remove_file(&buffer.journal_path).ok();
+                }
+            }
+            async {}
+        });
+
+        Self {
+            dir,
+            session_id,
+            buffers: HashMap::default(),
+            writes,
+            _writer: writer,
+            _quit_subscription: quit_subscription,
+        }
+    }
+
+    /// Journals the unsaved edits of the given buffer, which must be backed by a local file.
+    pub fn track(&mut self, buffer_handle: &Model<Buffer>, cx: &mut ModelContext<Self>) {
+        let buffer = buffer_handle.read(cx);
+        // Without the contents of the file on disk, the edits couldn't be replayed.
+        if buffer.is_dirty() {
+            return;
+        }
+        let Some(file) = buffer.file().and_then(|file| file.as_local()) else {
+            return;
+        };
+        let abs_path = file.abs_path(cx);
+        let journal_path = self.journal_path(&abs_path);
+        let base_hash = text_hash(&buffer.text());
+        let version = buffer.version();
+
+        let id = buffer_handle.entity_id();
+        let subscriptions = [
+            cx.subscribe(buffer_handle, Self::on_buffer_event),
+            cx.observe_release(buffer_handle, move |this, _, _| {
+                if let Some(journaled) = this.buffers.remove(&id) {
+                    this.delete_journal(&journaled);
+                }
+            }),
+        ];
+        self.buffers.insert(
+            id,
+            JournaledBuffer {
+                buffer: buffer_handle.downgrade(),
+                abs_path,
+                journal_path,
+                base_hash,
+                base_version: version.clone(),
+                flushed_version: version,
+                started: false,
+                flush_task: None,
+                _subscriptions: subscriptions,
+            },
+        );
+    }
+
+    fn on_buffer_event(
+        &mut self,
+        buffer: Model<Buffer>,
+        event: &BufferEvent,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let id = buffer.entity_id();
+        match event {
+            BufferEvent::Edited => {
+                let Some(journaled) = self.buffers.get_mut(&id) else {
+                    return;
+                };
+                if journaled.flush_task.is_none() {
+                    journaled.flush_task = Some(cx.spawn(|this, mut cx| async move {
+                        cx.background_executor().timer(FLUSH_INTERVAL).await;
+                        this.update(&mut cx, |this, cx| this.flush(id, cx)).ok();
+                    }));
+                }
+            }
+            BufferEvent::Saved | BufferEvent::Reloaded => self.reset(&buffer, cx),
+            BufferEvent::DirtyChanged if !buffer.read(cx).is_dirty() => self.reset(&buffer, cx),
+            BufferEvent::FileHandleChanged => {
+                let Some(abs_path) = buffer
+                    .read(cx)
+                    .file()
+                    .and_then(|file| file.as_local())
+                    .map(|file| file.abs_path(cx))
+                else {
+                    return;
+                };
+                let journal_path = self.journal_path(&abs_path);
+                let Some(journaled) = self.buffers.get_mut(&id) else {
+                    return;
+                };
+                if journaled.abs_path != abs_path {
+                    // A moved file keeps its contents, so the edits since it was last saved can
+                    // be journaled again under its new path.
+                    if journaled.started {
+                        self.writes
+                            .unbounded_send(JournalWrite::Delete {
+                                path: journaled.journal_path.clone(),
+                            })
+                            .ok();
+                    }
+                    journaled.abs_path = abs_path;
+                    journaled.journal_path = journal_path;
+                    journaled.flushed_version = journaled.base_version.clone();
+                    journaled.started = false;
+                    self.flush(id, cx);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Discards the journal of a buffer that matches its file on disk again.
+    fn reset(&mut self, buffer: &Model<Buffer>, cx: &mut ModelContext<Self>) {
+        let Some(journaled) = self.buffers.get_mut(&buffer.entity_id()) else {
+            return;
+        };
+        let buffer = buffer.read(cx);
+        journaled.base_hash = text_hash(&buffer.text());
+        journaled.base_version = buffer.version();
+        journaled.flushed_version = buffer.version();
+        journaled.flush_task = None;
+        if journaled.started {
+            journaled.started = false;
+            self.writes
+                .unbounded_send(JournalWrite::Delete {
+                    path: journaled.journal_path.clone(),
+                })
+                .ok();
+        }
+    }
+
+    /// Appends the edits made to a buffer since it was last flushed to its journal.
+    fn flush(&mut self, id: EntityId, cx: &mut ModelContext<Self>) {
+        let Some(journaled) = self.buffers.get_mut(&id) else {
+            return;
+        };
+        journaled.flush_task = None;
+        let Some(buffer) = journaled.buffer.upgrade() else {
+            return;
+        };
+        let buffer = buffer.read(cx);
+        if !buffer.is_dirty() {
+            return;
+        }
+
+        let entry = JournalEntry {
+            edits: buffer
+                .edits_since::<usize>(&journaled.flushed_version)
+                .map(|edit| {
+                    let new_text = buffer.text_for_range(edit.new.clone()).collect();
+                    (edit.old.start, edit.old.end, new_text)
+                })
+                .collect(),
+        };
+        journaled.flushed_version = buffer.version();
+        if entry.edits.is_empty() {
+            return;
+        }
+
+        let mut lines = String::new();
+        if !journaled.started {
+            let header = JournalHeader {
+                path: journaled.abs_path.clone(),
+                base_hash: journaled.base_hash.clone(),
+            };
+            let Ok(header) = serde_json::to_string(&header) else {
+                return;
+            };
+            lines.push_str(&header);
+            lines.push('\n');
+            journaled.started = true;
+        }
+        let Ok(entry) = serde_json::to_string(&entry) else {
+            return;
+        };
+        lines.push_str(&entry);
+        lines.push('\n');
+        self.writes
+            .unbounded_send(JournalWrite::Append {
+                path: journaled.journal_path.clone(),
+                lines,
+            })
+            .ok();
+    }
+
+    fn delete_journal(&self, journaled: &JournaledBuffer) {
+        if journaled.started {
+            self.writes
+                .unbounded_send(JournalWrite::Delete {
+                    path: journaled.journal_path.clone(),
+                })
+                .ok();
+        }
+    }
+
+    fn journal_path(&self, abs_path: &Path) -> PathBuf {
+        let mut hasher = Sha256::new();
+        hasher.update(abs_path.to_string_lossy().as_bytes());
+        let path_hash = hasher
+            .finalize()
+            .iter()
+            .take(8)
+            .map(|byte| format!("{byte:02x}"))
+            .collect::<String>();
+        self.dir
+            .join(format!("{path_hash}.{}.jsonl", self.session_id))
+    }
+
+    /// The buffers with unsaved edits that were journaled by previous sessions and are still
+    /// recoverable, because their files haven't changed since.
+    pub fn recoverable(&self, cx: &AppContext) -> Task<Vec<RecoveredBuffer>> {
+        let dir = self.dir.clone();
+        let session_suffix = format!(".{}.jsonl", self.session_id);
+        cx.background_executor().spawn(async move {
+            let Ok(entries) = fs::read_dir(&dir) else {
+                return Vec::new();
+            };
+            let mut journals = entries
+                .filter_map(|entry| {
+                    let entry = entry.ok()?;
+                    let path = entry.path();
+                    let name = path.file_name()?.to_str()?;
+                    if !name.ends_with(".jsonl") || name.ends_with(&session_suffix) {
+                        return None;
+                    }
+                    let modified = entry.metadata().ok()?.modified().ok()?;
+                    Some((modified, path))
+                })
+                .collect::<Vec<_>>();
+            // When several sessions left journals for the same file, recover the latest.
+            journals.sort_by(|(a, _), (b, _)| b.cmp(a));
+
+            let mut recovered_paths = HashSet::default();
+            let mut recovered = Vec::new();
+            for (_, journal_path) in journals {
+                match recover(&journal_path) {
+                    Ok(Some(buffer)) => {
+                        if recovered_paths.insert(buffer.abs_path.clone()) {
+                            recovered.push(buffer);
+                        } else {
+                            fs::remove_file(&journal_path).ok();
+                        }
+                    }
+                    Ok(None) => {
+                        fs::remove_file(&journal_path).ok();
+                    }
+                    Err(error) => {
+                        log::error!("failed to read buffer journal {journal_path:?}: {error:?}")
+                    }
+                }
+            }
+            recovered.sort_by(|a, b| a.abs_path.cmp(&b.abs_path));
+            recovered
+        })
+    }
+
+    /// Deletes the journals of recovered buffers, once they have been restored or discarded.
+    pub fn discard(&self, buffers: &[RecoveredBuffer]) {
+        for buffer in buffers {
+            self.writes
+                .unbounded_send(JournalWrite::Delete {
+                    path: buffer.journal_path.clone(),
+                })
+                .ok();
+        }
+    }
+}
+
+fn apply_write(dir: &Path, write: JournalWrite) -> Result<()> {
+    match write {
+        JournalWrite::Append { path, lines } => {
+            fs::create_dir_all(dir)?;
+            let mut file = fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&path)
+                .with_context(|| format!("opening {path:?}"))?;
+            file.write_all(lines.as_bytes())?;
+            file.sync_data()?;
+        }
+        JournalWrite::Delete { path } => {
+            if let Err(error) = fs::remove_file(&path) {
+                if error.kind() != std::io::ErrorKind::NotFound {
+                    Err(error).with_context(|| format!("deleting {path:?}"))?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Replays a journal against the file it was written for. Returns `None` when the file has
+/// changed since, or the journal holds no edits.
+fn recover(journal_path: &Path) -> Result<Option<RecoveredBuffer>> {
+    let journal = fs::read_to_string(journal_path)?;
+    let mut lines = journal.lines();
+    let Some(header) = lines.next() else {
+        return Ok(None);
+    };
+    let header: JournalHeader = serde_json::from_str(header)?;
+    let Ok(mut disk_text) = fs::read_to_string(&header.path) else {
+        return Ok(None);
+    };
+    LineEnding::normalize(&mut disk_text);
+    if text_hash(&disk_text) != header.base_hash {
+        return Ok(None);
+    }
+
+    // A crash can leave the last entry partially written, in which case the edits before it
+    // are still recovered.
+    let entries = lines.map_while(|line| serde_json::from_str::<JournalEntry>(line).ok());
+    let recovered_text = replay(&disk_text, entries);
+    if recovered_text == disk_text {
+        return Ok(None);
+    }
+    Ok(Some(RecoveredBuffer {
+        abs_path: header.path,
+        journal_path: journal_path.to_path_buf(),
+        disk_text,
+        recovered_text,
+    }))
+}
+
+fn replay(base_text: &str, entries: impl IntoIterator<Item = JournalEntry>) -> String {
+    let mut text = base_text.to_string();
+    for entry in entries {
+        // Edits are sorted and their offsets are in the text before the entry, so applying them
+        // from last to first keeps the offsets of the rest valid.
+        for (start, end, new_text) in entry.edits.iter().rev() {
+            if start > end || !text.is_char_boundary(*start) || !text.is_char_boundary(*end) {
+                return text;
+            }
+            text.replace_range(*start..*end, new_text);
+        }
+    }
+    text
+}
+
+fn text_hash(text: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(text.as_bytes());
+    hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use fs::RealFs;
+    use gpui::TestAppContext;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use util::test::temp_tree;
+
+    use super::*;
+    use crate::Project;
+
+    #[gpui::test]
+    async fn test_recovering_journaled_edits(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            release_channel::init("0.0.0", cx);
+            language::init(cx);
+            Project::init_settings(cx);
+        });
+
+        let dir = temp_tree(json!({
+            "root": {
+                "a.txt": "one\ntwo\nthree\n",
+            },
+        }));
+        let journal_dir = dir.path().join("journal");
+        cx.update(|cx| BufferJournal::init(journal_dir.clone(), cx));
+
+        let root = dir.path().join("root");
+        let project = Project::test(Arc::new(RealFs::default()), [root.as_ref()], cx).await;
+        let buffer = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer(root.join("a.txt"), cx)
+            })
+            .await
+            .unwrap();
+
+        buffer.update(cx, |buffer, cx| buffer.edit([(0..3, "ONE")], None, cx));
+        cx.executor().advance_clock(FLUSH_INTERVAL);
+        cx.run_until_parked();
+        buffer.update(cx, |buffer, cx| {
+            buffer.edit([(4..7, ""), (14..14, "four\n")], None, cx)
+        });
+        cx.executor().advance_clock(FLUSH_INTERVAL);
+        cx.run_until_parked();
+
+        // The next session recovers the edits journaled by this one.
+        let next_session = cx.new_model(|cx| BufferJournal::new(journal_dir.clone(), cx));
+        let recovered = next_session
+            .update(cx, |journal, cx| journal.recoverable(cx))
+            .await;
+        assert_eq!(recovered.len(), 1);
+        assert_eq!(recovered[0].abs_path, root.join("a.txt"));
+        assert_eq!(recovered[0].recovered_text, "ONE\n\nthree\nfour\n");
+
+        // Saving the buffer leaves nothing to recover.
+        project
+            .update(cx, |project, cx| project.save_buffer(buffer.clone(), cx))
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        let recovered = next_session
+            .update(cx, |journal, cx| journal.recoverable(cx))
+            .await;
+        assert!(recovered.is_empty());
+    }
+}
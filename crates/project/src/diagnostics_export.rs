@@ -0,0 +1,286 @@
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, Result};
+use futures::{channel::mpsc, FutureExt, StreamExt};
+use gpui::{AppContext, ModelContext, Task};
+use language::{DiagnosticEntry, DiagnosticSeverity, PointUtf16, Unclipped};
+use lsp::Url;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::{Event, Project};
+
+/// How long language servers must stay quiet before their diagnostics are considered complete.
+const DIAGNOSTICS_SETTLE_TIME: Duration = Duration::from_secs(2);
+
+/// The formats that [`Project::export_diagnostics`] can write diagnostics in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticsExportFormat {
+    /// A JSON array with an object for each diagnostic.
+    Json,
+    /// A [SARIF 2.1.0](https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html) log,
+    /// with a run for each language server.
+    Sarif,
+}
+
+impl DiagnosticsExportFormat {
+    /// The format of a file to export to, based on its extension.
+    pub fn for_path(path: &Path) -> Self {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("sarif") => Self::Sarif,
+            _ => Self::Json,
+        }
+    }
+}
+
+impl FromStr for DiagnosticsExportFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(format: &str) -> Result<Self> {
+        match format {
+            "json" => Ok(Self::Json),
+            "sarif" => Ok(Self::Sarif),
+            _ => Err(anyhow!(
+                "unknown diagnostics format {format:?}, expected json or sarif"
+            )),
+        }
+    }
+}
+
+/// A diagnostic as exported in the JSON format. Lines and columns start at 1, and columns
+/// count UTF-16 code units, as they do in the language server protocol.
+#[derive(Serialize)]
+struct ExportedDiagnostic {
+    path: PathBuf,
+    line: u32,
+    column: u32,
+    end_line: u32,
+    end_column: u32,
+    severity: &'static str,
+    message: String,
+    source: Option<String>,
+    code: Option<String>,
+    language_server: String,
+}
+
+struct PathDiagnostics {
+    root_name: String,
+    root_path: PathBuf,
+    path: PathBuf,
+    language_server: String,
+    entries: Vec<DiagnosticEntry<Unclipped<PointUtf16>>>,
+}
+
+impl Project {
+    /// Resolves once language servers have stopped updating diagnostics for a while, or the
+    /// timeout passes.
+    pub fn diagnostics_settled(
+        &mut self,
+        timeout: Duration,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<()> {
+        let (tx, mut rx) = mpsc::unbounded();
+        let this = cx.handle();
+        let subscription = cx.subscribe(&this, move |_, _, event, _| {
+            if matches!(
+                event,
+                Event::LanguageServerAdded(_)
+                    | Event::DiagnosticsUpdated { .. }
+                    | Event::DiskBasedDiagnosticsStarted { .. }
+                    | Event::DiskBasedDiagnosticsFinished { .. }
+            ) {
+                tx.unbounded_send(()).ok();
+            }
+        });
+
+        cx.spawn(|this, mut cx| async move {
+            let _subscription = subscription;
+            let deadline = Instant::now() + timeout;
+            loop {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                if remaining.is_zero() {
+                    log::warn!("timed out waiting for diagnostics");
+                    return;
+                }
+                let mut settled = cx
+                    .background_executor()
+                    .timer(DIAGNOSTICS_SETTLE_TIME.min(remaining))
+                    .fuse();
+                futures::select_biased! {
+                    _ = rx.next() => continue,
+                    _ = settled => {}
+                }
+                let pending = this
+                    .update(&mut cx, |project, _| {
+                        project
+                            .language_servers_running_disk_based_diagnostics()
+                            .next()
+                            .is_some()
+                    })
+                    .unwrap_or(false);
+                if !pending {
+                    return;
+                }
+            }
+        })
+    }
+
+    /// The diagnostics of every file in the project's local worktrees, including files that
+    /// aren't open, serialized in the given format.
+    pub fn export_diagnostics(
+        &self,
+        format: DiagnosticsExportFormat,
+        cx: &AppContext,
+    ) -> Result<String> {
+        let mut diagnostics = Vec::new();
+        for worktree in self.visible_worktrees(cx) {
+            let worktree = worktree.read(cx);
+            let Some(local_worktree) = worktree.as_local() else {
+                continue;
+            };
+            let mut paths = worktree
+                .diagnostic_summaries()
+                .map(|(path, _, _)| path)
+                .collect::<Vec<_>>();
+            paths.sort();
+            paths.dedup();
+            for path in paths {
+                for (server_id, entries) in local_worktree.diagnostics_for_path(&path) {
+                    let language_server = self
+                        .language_server_statuses
+                        .get(&server_id)
+                        .map_or_else(|| server_id.to_string(), |status| status.name.clone());
+                    diagnostics.push(PathDiagnostics {
+                        root_name: worktree.root_name().to_string(),
+                        root_path: local_worktree.abs_path().to_path_buf(),
+                        path: path.to_path_buf(),
+                        language_server,
+                        entries,
+                    });
+                }
+            }
+        }
+
+        let exported = match format {
+            DiagnosticsExportFormat::Json => json_diagnostics(&diagnostics),
+            DiagnosticsExportFormat::Sarif => sarif_diagnostics(&diagnostics),
+        };
+        Ok(serde_json::to_string_pretty(&exported)?)
+    }
+}
+
+fn json_diagnostics(diagnostics: &[PathDiagnostics]) -> serde_json::Value {
+    let exported = diagnostics
+        .iter()
+        .flat_map(|path_diagnostics| {
+            path_diagnostics.entries.iter().map(|entry| {
+                let start = entry.range.start.0;
+                let end = entry.range.end.0;
+                ExportedDiagnostic {
+                    path: path_diagnostics.root_path.join(&path_diagnostics.path),
+                    line: start.row + 1,
+                    column: start.column + 1,
+                    end_line: end.row + 1,
+                    end_column: end.column + 1,
+                    severity: severity_name(entry.diagnostic.severity),
+                    message: entry.diagnostic.message.clone(),
+                    source: entry.diagnostic.source.clone(),
+                    code: entry.diagnostic.code.clone(),
+                    language_server: path_diagnostics.language_server.clone(),
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    json!(exported)
+}
+
+fn sarif_diagnostics(diagnostics: &[PathDiagnostics]) -> serde_json::Value {
+    let mut language_servers = diagnostics
+        .iter()
+        .map(|path_diagnostics| path_diagnostics.language_server.as_str())
+        .collect::<Vec<_>>();
+    language_servers.sort();
+    language_servers.dedup();
+
+    let runs = language_servers
+        .into_iter()
+        .map(|language_server| {
+            let diagnostics = diagnostics
+                .iter()
+                .filter(|path_diagnostics| path_diagnostics.language_server == language_server);
+
+            // Paths are relative to the root of their worktree, which is named by its base id.
+            let mut base_ids = serde_json::Map::new();
+            let mut results = Vec::new();
+            for path_diagnostics in diagnostics {
+                if let Ok(root_uri) = Url::from_directory_path(&path_diagnostics.root_path) {
+                    base_ids.insert(
+                        path_diagnostics.root_name.clone(),
+                        json!({ "uri": root_uri.as_str() }),
+                    );
+                }
+                for entry in &path_diagnostics.entries {
+                    let start = entry.range.start.0;
+                    let end = entry.range.end.0;
+                    let mut result = json!({
+                        "level": sarif_level(entry.diagnostic.severity),
+                        "message": { "text": entry.diagnostic.message },
+                        "locations": [{
+                            "physicalLocation": {
+                                "artifactLocation": {
+                                    "uri": path_diagnostics.path.to_string_lossy().replace('\\', "/"),
+                                    "uriBaseId": path_diagnostics.root_name,
+                                },
+                                "region": {
+                                    "startLine": start.row + 1,
+                                    "startColumn": start.column + 1,
+                                    "endLine": end.row + 1,
+                                    "endColumn": end.column + 1,
+                                },
+                            },
+                        }],
+                    });
+                    if let Some(code) = &entry.diagnostic.code {
+                        result["ruleId"] = json!(code);
+                    }
+                    results.push(result);
+                }
+            }
+
+            json!({
+                "tool": { "driver": { "name": language_server } },
+                "originalUriBaseIds": base_ids,
+                "columnKind": "utf16CodeUnits",
+                "results": results,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": runs,
+    })
+}
+
+fn severity_name(severity: DiagnosticSeverity) -> &'static str {
+    match severity {
+        DiagnosticSeverity::ERROR => "error",
+        DiagnosticSeverity::WARNING => "warning",
+        DiagnosticSeverity::INFORMATION => "information",
+        _ => "hint",
+    }
+}
+
+fn sarif_level(severity: DiagnosticSeverity) -> &'static str {
+    match severity {
+        DiagnosticSeverity::ERROR => "error",
+        DiagnosticSeverity::WARNING => "warning",
+        _ => "note",
+    }
+}
@@ -0,0 +1,216 @@
+//! Formatting the regions of a buffer where another language is injected, like a fenced code
+//! block in Markdown or a SQL query in a string, with the external formatter that's set for the
+//! injected language. Language servers and Prettier only see the whole buffer, in its own
+//! language, so they aren't used for injections.
+
+use std::{ops::Range, path::Path, sync::Arc};
+
+use anyhow::Result;
+use gpui::{AsyncAppContext, Model};
+use language::{
+    language_settings::{language_settings, FormatOnSave, Formatter, LanguageSettings},
+    Anchor, Buffer, BufferSnapshot, Point, ToOffset,
+};
+
+use crate::{FormatTrigger, Project};
+
+/// An injected region, of whole lines, and the formatter of its language.
+struct Injection {
+    range: Range<Anchor>,
+    text: String,
+    command: Arc<str>,
+    arguments: Arc<[String]>,
+}
+
+/// Formats the injected regions of the buffer whose languages have an external formatter,
+/// returning the version of the buffer that was formatted and the edits to apply to it.
+pub(crate) async fn format_injections(
+    buffer: &Model<Buffer>,
+    buffer_abs_path: &Path,
+    trigger: FormatTrigger,
+    cx: &mut AsyncAppContext,
+) -> Result<(clock::Global, Vec<(Range<Anchor>, String)>)> {
+    let (version, injections, working_dir_path) = buffer.read_with(cx, |buffer, cx| {
+        let snapshot = buffer.snapshot();
+        let injections = snapshot
+            .injections()
+            .into_iter()
+            .filter(|(_, language)| {
+                snapshot
+                    .language()
+                    .map_or(true, |host_language| !Arc::ptr_eq(host_language, language))
+            })
+            .filter_map(|(range, language)| {
+                let settings = language_settings(Some(&language), snapshot.file(), cx);
+                let (command, arguments) = external_formatter(settings, trigger)?;
+                Some((whole_lines(&snapshot, range)?, command, arguments))
+            })
+            .collect::<Vec<_>>();
+
+        // Injections that are nested in another one are formatted with the one containing them.
+        let mut formatted_ranges: Vec<Range<usize>> = Vec::new();
+        let injections = injections
+            .into_iter()
+            .filter(|(range, _, _)| {
+                let overlaps = formatted_ranges
+                    .iter()
+                    .any(|formatted| formatted.start < range.end && range.start < formatted.end);
+                if !overlaps {
+                    formatted_ranges.push(range.clone());
+                }
+                !overlaps
+            })
+            .map(|(range, command, arguments)| Injection {
+                text: snapshot.text_for_range(range.clone()).collect(),
+                range: snapshot.anchor_after(range.start)..snapshot.anchor_before(range.end),
+                command,
+                arguments,
+            })
+            .collect::<Vec<_>>();
+        (
+            snapshot.version().clone(),
+            injections,
+            Project::formatter_working_dir(buffer, cx),
+        )
+    })?;
+    let Some(working_dir_path) = working_dir_path else {
+        return Ok((version, Vec::new()));
+    };
+
+    let mut edits = Vec::new();
+    for injection in injections {
+        if injection.text.trim().is_empty() {
+            continue;
+        }
+        let (indent, text) = dedent(&injection.text);
+        let formatted = Project::run_external_formatter(
+            &injection.command,
+            &injection.arguments,
+            buffer_abs_path,
+            &working_dir_path,
+            std::iter::once(text.as_str()),
+        )
+        .await?;
+        let formatted = indent_lines(&formatted, &indent, injection.text.ends_with('\n'));
+        if formatted != injection.text {
+            edits.push((injection.range, formatted));
+        }
+    }
+    Ok((version, edits))
+}
+
+/// The external formatter that's used for the language when formatting is triggered, if any.
+fn external_formatter(
+    settings: &LanguageSettings,
+    trigger: FormatTrigger,
+) -> Option<(Arc<str>, Arc<[String]>)> {
+    match (&settings.formatter, &settings.format_on_save) {
+        (_, FormatOnSave::Off) if trigger == FormatTrigger::Save => None,
+        (Formatter::External { command, arguments }, FormatOnSave::On | FormatOnSave::Off)
+        | (_, FormatOnSave::External { command, arguments }) => {
+            Some((command.clone(), arguments.clone()))
+        }
+        _ => None,
+    }
+}
+
+/// Extends the range of an injection to whole lines, when it only shares its first and last lines
+/// with the host language's text by way of whitespace, as a fenced code block or a query in a
+/// multiline string does. Injections within a line aren't formatted.
+fn whole_lines(snapshot: &BufferSnapshot, range: Range<usize>) -> Option<Range<usize>> {
+    let max_point = snapshot.max_point();
+    let is_blank = |range: Range<Point>| snapshot.chars_for_range(range).all(|c| c.is_whitespace());
+    let line_end = |row: u32| Point::new(row, snapshot.line_len(row));
+    let next_line_start = |row: u32| {
+        if row < max_point.row {
+            Point::new(row + 1, 0)
+        } else {
+            max_point
+        }
+    };
+
+    let start = snapshot.offset_to_point(range.start);
+    let start = if is_blank(Point::new(start.row, 0)..start) {
+        Point::new(start.row, 0)
+    } else if is_blank(start..line_end(start.row)) {
+        next_line_start(start.row)
+    } else {
+        return None;
+    };
+    let end = snapshot.offset_to_point(range.end);
+    let end = if is_blank(Point::new(end.row, 0)..end) {
+        Point::new(end.row, 0)
+    } else if is_blank(end..line_end(end.row)) {
+        next_line_start(end.row)
+    } else {
+        return None;
+    };
+    (start < end).then(|| start.to_offset(snapshot)..end.to_offset(snapshot))
+}
+
+/// Removes the indentation that all the non-blank lines of the text share, returning it and the
+/// text without it.
+fn dedent(text: &str) -> (String, String) {
+    let mut indent: Option<&str> = None;
+    for line in text.lines().filter(|line| !line.trim().is_empty()) {
+        let line_indent = &line[..line.len() - line.trim_start().len()];
+        indent = Some(match indent {
+            Some(indent) => {
+                let common_len = indent
+                    .char_indices()
+                    .zip(line_indent.chars())
+                    .take_while(|((_, a), b)| a == b)
+                    .last()
+                    .map_or(0, |((ix, c), _)| ix + c.len_utf8());
+                &indent[..common_len]
+            }
+            None => line_indent,
+        });
+    }
+    let indent = indent.unwrap_or_default();
+    let text = text
+        .split_inclusive('\n')
+        .map(|line| {
+            line.strip_prefix(indent)
+                .unwrap_or_else(|| line.trim_start_matches(|c| c == ' ' || c == '\t'))
+        })
+        .collect();
+    (indent.to_string(), text)
+}
+
+/// Indents the non-blank lines of the formatted text, ending it with a newline only when the
+/// text it replaces did.
+fn indent_lines(text: &str, indent: &str, trailing_newline: bool) -> String {
+    let mut indented = String::with_capacity(text.len());
+    for line in text.trim_end_matches('\n').split('\n') {
+        if !line.trim().is_empty() {
+            indented.push_str(indent);
+            indented.push_str(line);
+        }
+        indented.push('\n');
+    }
+    if !trailing_newline {
+        indented.pop();
+    }
+    indented
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dedent_and_indent_lines() {
+        let text = "    SELECT *\n      FROM users\n\n    WHERE id = 1\n";
+        let (indent, dedented) = dedent(text);
+        assert_eq!(indent, "    ");
+        assert_eq!(dedented, "SELECT *\n  FROM users\n\nWHERE id = 1\n");
+
+        let formatted = "SELECT\n  *\nFROM\n  users\n\nWHERE\n  id = 1\n";
+        assert_eq!(
+            indent_lines(formatted, &indent, true),
+            "    SELECT\n      *\n    FROM\n      users\n\n    WHERE\n      id = 1\n"
+        );
+        assert_eq!(indent_lines("a = 1\n", "  ", false), "  a = 1");
+    }
+}
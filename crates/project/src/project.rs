@@ -1,5 +1,8 @@
+mod buffer_journal;
 pub mod connection_manager;
 pub mod debounced_delay;
+mod diagnostics_export;
+mod injection_formatting;
 pub mod lsp_command;
 pub mod lsp_ext_command;
 mod prettier_support;
@@ -7,6 +10,7 @@ pub mod project_settings;
 pub mod search;
 mod task_inventory;
 pub mod terminals;
+mod virtual_files;
 
 #[cfg(test)]
 mod project_tests;
@@ -40,7 +44,9 @@ use gpui::{
 };
 use itertools::Itertools;
 use language::{
-    language_settings::{language_settings, FormatOnSave, Formatter, InlayHintKind},
+    language_settings::{
+        all_language_settings, language_settings, FormatOnSave, Formatter, InlayHintKind,
+    },
     markdown, point_to_lsp, prepare_completion_documentation,
     proto::{
         deserialize_anchor, deserialize_line_ending, deserialize_version, serialize_anchor,
@@ -84,7 +90,8 @@ use std::{
     env,
     ffi::OsStr,
     hash::Hash,
-    io, iter, mem,
+    io::{self, Read as _},
+    iter, mem,
     num::NonZeroU32,
     ops::Range,
     path::{self, Component, Path, PathBuf},
@@ -108,15 +115,18 @@ use util::{
 };
 use worktree::{Snapshot, Traversal};
 
+pub use buffer_journal::{BufferJournal, RecoveredBuffer};
+pub use diagnostics_export::DiagnosticsExportFormat;
 pub use fs::*;
 pub use language::Location;
 #[cfg(any(test, feature = "test-support"))]
 pub use prettier::FORMAT_SUFFIX as TEST_PRETTIER_FORMAT_SUFFIX;
 pub use task_inventory::{Inventory, TaskSourceKind};
+pub use virtual_files::{VirtualEntry, VirtualFile};
 pub use worktree::{
-    DiagnosticSummary, Entry, EntryKind, File, LocalWorktree, PathChange, ProjectEntryId,
-    RepositoryEntry, UpdatedEntriesSet, UpdatedGitRepositoriesSet, Worktree, WorktreeId,
-    WorktreeSettings, FS_WATCH_LATENCY,
+    DiagnosticSummary, Entry, EntryKind, File, FollowSymlinks, LocalWorktree, PathChange,
+    ProjectEntryId, RepositoryEntry, UpdatedEntriesSet, UpdatedGitRepositoriesSet, Worktree,
+    WorktreeId, WorktreeSettings, FS_WATCH_LATENCY,
 };
 
 const MAX_SERVER_REINSTALL_ATTEMPT_COUNT: u64 = 4;
@@ -125,6 +135,9 @@ const SERVER_LAUNCHING_BEFORE_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5
 pub const SERVER_PROGRESS_DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(100);
 
 const MAX_PROJECT_SEARCH_HISTORY_SIZE: usize = 500;
+/// How many created files are read to find the files that deleted buffers' files were renamed
+/// to, when a rename isn't seen as one.
+const MAX_RECREATED_FILE_CANDIDATES: usize = 64;
 
 pub trait Item {
     fn try_open(
@@ -152,6 +165,7 @@ pub enum OpenedBufferEvent {
 /// Can be either local (for the project opened on the same host) or remote.(for collab projects, browsed by multiple remote users).
 pub struct Project {
     worktrees: Vec<WorktreeHandle>,
+    virtual_entries: Vec<VirtualEntry>,
     active_entry: Option<ProjectEntryId>,
     buffer_ordered_messages_tx: mpsc::UnboundedSender<BufferOrderedMessage>,
     pending_language_server_update: Option<BufferOrderedMessage>,
@@ -332,6 +346,7 @@ pub enum Event {
     CollaboratorLeft(proto::PeerId),
     RefreshInlayHints,
     RevealInProjectPanel(ProjectEntryId),
+    VirtualEntriesChanged,
 }
 
 pub enum LanguageServerState {
@@ -605,6 +620,8 @@ pub enum SearchResult {
         buffer: Model<Buffer>,
         ranges: Vec<Range<Anchor>>,
     },
+    /// How many files on disk weren't searched because they were too large or looked binary.
+    SkippedFiles(usize),
     LimitReached,
 }
 
@@ -686,6 +703,7 @@ impl Project {
 
             Self {
                 worktrees: Vec::new(),
+                virtual_entries: Vec::new(),
                 buffer_ordered_messages_tx: tx,
                 flush_language_server_update: None,
                 pending_language_server_update: None,
@@ -818,6 +836,7 @@ impl Project {
                 .detach();
             let mut this = Self {
                 worktrees: Vec::new(),
+                virtual_entries: Vec::new(),
                 buffer_ordered_messages_tx: tx,
                 pending_language_server_update: None,
                 flush_language_server_update: None,
@@ -1972,6 +1991,74 @@ impl Project {
         buffer
     }
 
+    /// Adds a read-only [`VirtualFile`] under the virtual root with the given name, and returns
+    /// the buffer holding its contents. If the file was already added, its contents are
+    /// replaced.
+    pub fn add_virtual_file(
+        &mut self,
+        root_name: impl Into<Arc<str>>,
+        path: impl Into<Arc<Path>>,
+        text: String,
+        cx: &mut ModelContext<Self>,
+    ) -> Model<Buffer> {
+        let root_name = root_name.into();
+        let path = path.into();
+        if let Some(entry) = self
+            .virtual_entries
+            .iter()
+            .find(|entry| entry.root_name == root_name && entry.path == path)
+        {
+            let buffer = entry.buffer.clone();
+            buffer.update(cx, |buffer, cx| {
+                buffer.set_text(text, cx);
+                buffer.did_save(buffer.version(), None, cx);
+            });
+            return buffer;
+        }
+
+        let file = Arc::new(VirtualFile::new(root_name.clone(), path.clone()));
+        let buffer = cx.new_model(|cx| {
+            Buffer::build(
+                text::Buffer::new(0, cx.entity_id().as_non_zero_u64().into(), text),
+                None,
+                Some(file),
+                Capability::ReadOnly,
+            )
+        });
+        // Buffer ids in remote projects are assigned by the host, so virtual buffers aren't
+        // registered with them.
+        if self.is_local() {
+            self.register_buffer(&buffer, cx).log_err();
+        } else {
+            buffer.update(cx, |buffer, _| {
+                buffer.set_language_registry(self.languages.clone())
+            });
+            self.detect_language_for_buffer(&buffer, cx);
+        }
+        self.virtual_entries.push(VirtualEntry {
+            root_name,
+            path,
+            buffer: buffer.clone(),
+        });
+        cx.emit(Event::VirtualEntriesChanged);
+        buffer
+    }
+
+    /// Removes the virtual files under the virtual root with the given name, such as once the
+    /// feature that added them no longer needs them.
+    pub fn remove_virtual_root(&mut self, root_name: &str, cx: &mut ModelContext<Self>) {
+        let entry_count = self.virtual_entries.len();
+        self.virtual_entries
+            .retain(|entry| entry.root_name.as_ref() != root_name);
+        if self.virtual_entries.len() != entry_count {
+            cx.emit(Event::VirtualEntriesChanged);
+        }
+    }
+
+    pub fn virtual_entries(&self) -> &[VirtualEntry] {
+        &self.virtual_entries
+    }
+
     pub fn open_path(
         &mut self,
         path: ProjectPath,
@@ -2325,6 +2412,10 @@ impl Project {
                     self.local_buffer_ids_by_entry_id
                         .insert(entry_id, remote_id);
                 }
+
+                if let Some(journal) = BufferJournal::global(cx) {
+                    journal.update(cx, |journal, cx| journal.track(buffer, cx));
+                }
             }
         }
 
@@ -3266,6 +3357,40 @@ impl Project {
         self.language_server_ids.insert(key, server_id);
     }
 
+    /// Whether a language server of this name is running in the project.
+    pub fn is_running_language_server(&self, server_name: &LanguageServerName) -> bool {
+        self.language_servers.values().any(|state| {
+            matches!(state, LanguageServerState::Running { adapter, .. } if adapter.name == *server_name)
+        })
+    }
+
+    /// Deletes the downloaded binaries of the language server, and restarts it if it's running,
+    /// which downloads it again.
+    pub fn reinstall_language_server_binary(
+        &mut self,
+        server_name: &LanguageServerName,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<()> {
+        let running_server =
+            self.language_servers
+                .iter()
+                .find_map(|(server_id, state)| match state {
+                    LanguageServerState::Running {
+                        language, adapter, ..
+                    } if adapter.name == *server_name => {
+                        Some((*server_id, language.clone(), adapter.clone()))
+                    }
+                    _ => None,
+                });
+        if let Some((server_id, language, adapter)) = running_server {
+            if let Some(task) = self.reinstall_language_server(language, adapter, server_id, cx) {
+                return task;
+            }
+        }
+        self.languages
+            .delete_server_container(server_name.clone(), cx)
+    }
+
     fn reinstall_language_server(
         &mut self,
         language: Arc<Language>,
@@ -3298,7 +3423,8 @@ impl Project {
             // to be very confused and messed up
             let Some(task) = this
                 .update(&mut cx, |this, cx| {
-                    this.languages.delete_server_container(adapter.clone(), cx)
+                    this.languages
+                        .delete_server_container(adapter.name.clone(), cx)
                 })
                 .log_err()
             else {
@@ -4683,6 +4809,116 @@ impl Project {
         }
     }
 
+    /// Formats only the given ranges of each buffer, such as the parts of it that a multibuffer
+    /// shows, with range formatting from its primary language server. Buffers that are formatted
+    /// some other way, or whose language server can't format ranges, are formatted in full, as
+    /// are all of the buffers of remote projects.
+    pub fn format_ranges(
+        &mut self,
+        buffer_ranges: HashMap<Model<Buffer>, Vec<Range<Anchor>>>,
+        push_to_history: bool,
+        cx: &mut ModelContext<Project>,
+    ) -> Task<anyhow::Result<ProjectTransaction>> {
+        if !self.is_local() {
+            let buffers = buffer_ranges.into_keys().collect();
+            return self.format(buffers, push_to_history, FormatTrigger::Manual, cx);
+        }
+
+        let mut whole_buffers = HashSet::default();
+        let mut ranged_buffers = Vec::new();
+        for (buffer_handle, ranges) in buffer_ranges {
+            let buffer = buffer_handle.read(cx);
+            let buffer_id = buffer.remote_id();
+            let settings = language_settings(buffer.language(), buffer.file(), cx);
+            let formats_with_language_server = match settings.formatter {
+                Formatter::LanguageServer => true,
+                Formatter::Auto => !settings.prettier.allowed,
+                _ => false,
+            };
+            let tab_size = settings.tab_size;
+            let language_server = self
+                .primary_language_server_for_buffer(buffer, cx)
+                .map(|(_, server)| server.clone())
+                .filter(|server| {
+                    matches!(
+                        server.capabilities().document_range_formatting_provider,
+                        Some(ref provider) if *provider != OneOf::Left(false)
+                    )
+                });
+            let abs_path =
+                File::from_dyn(buffer.file()).and_then(|file| Some(file.as_local()?.abs_path(cx)));
+            match language_server.zip(abs_path) {
+                Some((language_server, abs_path))
+                    if formats_with_language_server
+                        && self.buffers_being_formatted.insert(buffer_id) =>
+                {
+                    ranged_buffers.push((
+                        buffer_handle,
+                        abs_path,
+                        language_server,
+                        tab_size,
+                        ranges,
+                    ));
+                }
+                _ => {
+                    whole_buffers.insert(buffer_handle);
+                }
+            }
+        }
+
+        let format_whole_buffers =
+            self.format(whole_buffers, push_to_history, FormatTrigger::Manual, cx);
+        cx.spawn(move |project, mut cx| async move {
+            let _cleanup = defer({
+                let project = project.clone();
+                let mut cx = cx.clone();
+                let buffers = ranged_buffers
+                    .iter()
+                    .map(|(buffer, ..)| buffer.clone())
+                    .collect::<Vec<_>>();
+                move || {
+                    project
+                        .update(&mut cx, |project, cx| {
+                            for buffer in buffers {
+                                project
+                                    .buffers_being_formatted
+                                    .remove(&buffer.read(cx).remote_id());
+                            }
+                        })
+                        .ok();
+                }
+            });
+
+            let mut project_transaction = format_whole_buffers.await?;
+            for (buffer, abs_path, language_server, tab_size, ranges) in &ranged_buffers {
+                let edits = Self::format_ranges_via_lsp(
+                    &project,
+                    buffer,
+                    abs_path,
+                    ranges,
+                    language_server,
+                    *tab_size,
+                    &mut cx,
+                )
+                .await
+                .context("failed to format ranges via language server")?;
+                buffer.update(&mut cx, |b, cx| {
+                    b.finalize_last_transaction();
+                    b.start_transaction();
+                    b.edit(edits, None, cx);
+                    b.end_transaction(cx);
+                    if let Some(transaction) = b.finalize_last_transaction().cloned() {
+                        if !push_to_history {
+                            b.forget_transaction(transaction.id);
+                        }
+                        project_transaction.0.insert(buffer.clone(), transaction);
+                    }
+                })?;
+            }
+            Ok(project_transaction)
+        })
+    }
+
     async fn format_locally(
         project: WeakModel<Project>,
         mut buffers_with_paths: Vec<(Model<Buffer>, Option<PathBuf>)>,
@@ -4690,9 +4926,19 @@ impl Project {
         trigger: FormatTrigger,
         mut cx: AsyncAppContext,
     ) -> anyhow::Result<ProjectTransaction> {
-        // Do not allow multiple concurrent formatting requests for the
-        // same buffer.
         project.update(&mut cx, |this, cx| {
+            // Files excluded from formatting on save are saved as they are.
+            if trigger == FormatTrigger::Save {
+                buffers_with_paths.retain(|(buffer, _)| {
+                    buffer.read(cx).file().map_or(true, |file| {
+                        all_language_settings(Some(file), cx)
+                            .format_on_save_enabled_for_path(file.path())
+                    })
+                });
+            }
+
+            // Do not allow multiple concurrent formatting requests for the
+            // same buffer.
             buffers_with_paths.retain(|(buffer, _)| {
                 this.buffers_being_formatted
                     .insert(buffer.read(cx).remote_id())
@@ -4738,6 +4984,7 @@ impl Project {
 
             let remove_trailing_whitespace = settings.remove_trailing_whitespace_on_save;
             let ensure_final_newline = settings.ensure_final_newline_on_save;
+            let format_tables = settings.format_tables_on_save;
             let tab_size = settings.tab_size;
 
             // First, format buffer's whitespace according to the settings.
@@ -4756,6 +5003,10 @@ impl Project {
                 if let Some(diff) = trailing_whitespace_diff {
                     buffer.apply_diff(diff, cx);
                 }
+                if format_tables {
+                    let edits = language::markdown::align_tables(&buffer.text());
+                    buffer.edit(edits, None, cx);
+                }
                 if ensure_final_newline {
                     buffer.ensure_final_newline(cx);
                 }
@@ -4892,7 +5143,7 @@ impl Project {
                 }
             };
 
-            buffer.update(&mut cx, |b, cx| {
+            let formatting_transaction_id = buffer.update(&mut cx, |b, cx| {
                 // If the buffer had its whitespace formatted and was edited while the language-specific
                 // formatting was being computed, avoid applying the language-specific formatting, because
                 // it can't be grouped with the whitespace formatting in the undo history.
@@ -4906,6 +5157,7 @@ impl Project {
 
                 // Apply any language-specific formatting, and group the two formatting operations
                 // in the buffer's undo history.
+                let last_transaction_id = b.peek_undo_stack().map(|e| e.transaction_id());
                 if let Some(operation) = format_operation {
                     match operation {
                         FormatOperation::Lsp(edits) => {
@@ -4926,6 +5178,37 @@ impl Project {
                     }
                 }
 
+                whitespace_transaction_id
+                    .or_else(|| project_transaction.0.get(buffer).map(|t| t.id))
+                    .or_else(|| {
+                        b.peek_undo_stack()
+                            .map(|e| e.transaction_id())
+                            .filter(|id| Some(*id) != last_transaction_id)
+                    })
+            })?;
+
+            // Format the regions where other languages are injected with their own formatters,
+            // in the same transaction as the rest of the formatting.
+            if let Some(buffer_abs_path) = buffer_abs_path {
+                let (version, edits) = injection_formatting::format_injections(
+                    buffer,
+                    buffer_abs_path,
+                    trigger,
+                    &mut cx,
+                )
+                .await
+                .context("failed to format injected languages")?;
+                buffer.update(&mut cx, |b, cx| {
+                    if !edits.is_empty() && !b.version().changed_since(&version) {
+                        b.edit(edits, None, cx);
+                        if let Some(transaction_id) = formatting_transaction_id {
+                            b.group_until_transaction(transaction_id);
+                        }
+                    }
+                })?;
+            }
+
+            buffer.update(&mut cx, |b, cx| {
                 if let Some(transaction) = b.finalize_last_transaction().cloned() {
                     if !push_to_history {
                         b.forget_transaction(transaction.id);
@@ -4988,6 +5271,82 @@ impl Project {
         }
     }
 
+    /// Requests range formatting for each of the ranges, after merging those that overlap.
+    async fn format_ranges_via_lsp(
+        this: &WeakModel<Self>,
+        buffer: &Model<Buffer>,
+        abs_path: &Path,
+        ranges: &[Range<Anchor>],
+        language_server: &Arc<LanguageServer>,
+        tab_size: NonZeroU32,
+        cx: &mut AsyncAppContext,
+    ) -> Result<Vec<(Range<Anchor>, String)>> {
+        let uri = lsp::Url::from_file_path(abs_path)
+            .map_err(|_| anyhow!("failed to convert abs path to uri"))?;
+        let lsp_ranges = buffer.update(cx, |buffer, _| {
+            let mut ranges = ranges
+                .iter()
+                .map(|range| range.start.to_point_utf16(buffer)..range.end.to_point_utf16(buffer))
+                .collect::<Vec<_>>();
+            ranges.sort_unstable_by_key(|range| range.start);
+            let mut merged_ranges: Vec<Range<PointUtf16>> = Vec::new();
+            for range in ranges {
+                match merged_ranges.last_mut() {
+                    Some(last_range) if last_range.end >= range.start => {
+                        last_range.end = last_range.end.max(range.end);
+                    }
+                    _ => merged_ranges.push(range),
+                }
+            }
+            merged_ranges
+                .into_iter()
+                .map(|range| lsp::Range::new(point_to_lsp(range.start), point_to_lsp(range.end)))
+                .collect::<Vec<_>>()
+        })?;
+
+        let mut edits = Vec::new();
+        for range in lsp_ranges {
+            let lsp_edits = language_server
+                .request::<lsp::request::RangeFormatting>(lsp::DocumentRangeFormattingParams {
+                    text_document: lsp::TextDocumentIdentifier::new(uri.clone()),
+                    range,
+                    options: lsp_command::lsp_formatting_options(tab_size.get()),
+                    work_done_progress_params: Default::default(),
+                })
+                .await?;
+            if let Some(lsp_edits) = lsp_edits {
+                edits.extend(
+                    this.update(cx, |this, cx| {
+                        this.edits_from_lsp(
+                            buffer,
+                            lsp_edits,
+                            language_server.server_id(),
+                            None,
+                            cx,
+                        )
+                    })?
+                    .await?,
+                );
+            }
+        }
+
+        // Every response is relative to the same unedited buffer, but servers may return edits
+        // outside of the requested range, so edits from separate responses can overlap.
+        buffer.update(cx, |buffer, _| {
+            edits.sort_by(|(a, _), (b, _)| {
+                a.start
+                    .cmp(&b.start, buffer)
+                    .then_with(|| a.end.cmp(&b.end, buffer))
+            });
+            edits.dedup_by(|(range, _), (previous_range, _)| {
+                range.start.cmp(&previous_range.end, buffer).is_lt()
+                    || (range.start.cmp(&previous_range.start, buffer).is_eq()
+                        && range.end.cmp(&previous_range.end, buffer).is_eq())
+            });
+        })?;
+        Ok(edits)
+    }
+
     async fn format_via_external_command(
         buffer: &Model<Buffer>,
         buffer_abs_path: &Path,
@@ -4995,48 +5354,19 @@ impl Project {
         arguments: &[String],
         cx: &mut AsyncAppContext,
     ) -> Result<Option<Diff>> {
-        let working_dir_path = buffer.update(cx, |buffer, cx| {
-            let file = File::from_dyn(buffer.file())?;
-            let worktree = file.worktree.read(cx).as_local()?;
-            let mut worktree_path = worktree.abs_path().to_path_buf();
-            if worktree.root_entry()?.is_file() {
-                worktree_path.pop();
-            }
-            Some(worktree_path)
-        })?;
+        let working_dir_path =
+            buffer.update(cx, |buffer, cx| Self::formatter_working_dir(buffer, cx))?;
 
         if let Some(working_dir_path) = working_dir_path {
-            let mut child =
-                smol::process::Command::new(command)
-                    .args(arguments.iter().map(|arg| {
-                        arg.replace("{buffer_path}", &buffer_abs_path.to_string_lossy())
-                    }))
-                    .current_dir(&working_dir_path)
-                    .stdin(smol::process::Stdio::piped())
-                    .stdout(smol::process::Stdio::piped())
-                    .stderr(smol::process::Stdio::piped())
-                    .spawn()?;
-            let stdin = child
-                .stdin
-                .as_mut()
-                .ok_or_else(|| anyhow!("failed to acquire stdin"))?;
             let text = buffer.update(cx, |buffer, _| buffer.as_rope().clone())?;
-            for chunk in text.chunks() {
-                stdin.write_all(chunk.as_bytes()).await?;
-            }
-            stdin.flush().await?;
-
-            let output = child.output().await?;
-            if !output.status.success() {
-                return Err(anyhow!(
-                    "command failed with exit code {:?}:\nstdout: {}\nstderr: {}",
-                    output.status.code(),
-                    String::from_utf8_lossy(&output.stdout),
-                    String::from_utf8_lossy(&output.stderr),
-                ));
-            }
-
-            let stdout = String::from_utf8(output.stdout)?;
+            let stdout = Self::run_external_formatter(
+                command,
+                arguments,
+                buffer_abs_path,
+                &working_dir_path,
+                text.chunks(),
+            )
+            .await?;
             Ok(Some(
                 buffer
                     .update(cx, |buffer, cx| buffer.diff(stdout, cx))?
@@ -5047,6 +5377,59 @@ impl Project {
         }
     }
 
+    /// The directory that external formatters run in, which is the root of the buffer's worktree.
+    fn formatter_working_dir(buffer: &Buffer, cx: &AppContext) -> Option<PathBuf> {
+        let file = File::from_dyn(buffer.file())?;
+        let worktree = file.worktree.read(cx).as_local()?;
+        let mut worktree_path = worktree.abs_path().to_path_buf();
+        if worktree.root_entry()?.is_file() {
+            worktree_path.pop();
+        }
+        Some(worktree_path)
+    }
+
+    /// Runs an external formatter with the text on its standard input, returning the formatted
+    /// text it writes to its standard output.
+    async fn run_external_formatter<'a>(
+        command: &str,
+        arguments: &[String],
+        buffer_abs_path: &Path,
+        working_dir_path: &Path,
+        text: impl Iterator<Item = &'a str>,
+    ) -> Result<String> {
+        let mut child = smol::process::Command::new(command)
+            .args(
+                arguments
+                    .iter()
+                    .map(|arg| arg.replace("{buffer_path}", &buffer_abs_path.to_string_lossy())),
+            )
+            .current_dir(working_dir_path)
+            .stdin(smol::process::Stdio::piped())
+            .stdout(smol::process::Stdio::piped())
+            .stderr(smol::process::Stdio::piped())
+            .spawn()?;
+        let stdin = child
+            .stdin
+            .as_mut()
+            .ok_or_else(|| anyhow!("failed to acquire stdin"))?;
+        for chunk in text {
+            stdin.write_all(chunk.as_bytes()).await?;
+        }
+        stdin.flush().await?;
+
+        let output = child.output().await?;
+        if !output.status.success() {
+            return Err(anyhow!(
+                "command failed with exit code {:?}:\nstdout: {}\nstderr: {}",
+                output.status.code(),
+                String::from_utf8_lossy(&output.stdout),
+                String::from_utf8_lossy(&output.stderr),
+            ));
+        }
+
+        Ok(String::from_utf8(output.stdout)?)
+    }
+
     #[inline(never)]
     fn definition_impl(
         &self,
@@ -5493,6 +5876,13 @@ impl Project {
             let offset = position.to_offset(&snapshot);
             let scope = snapshot.language_scope_at(offset);
             let language = snapshot.language().cloned();
+            // Within an injected language, the servers are still the host language's, which
+            // aren't asked when the injected language has its language servers turned off.
+            if !language_settings(snapshot.language_at(offset), snapshot.file(), cx)
+                .enable_language_server
+            {
+                return Task::ready(Ok(Vec::new()));
+            }
 
             let server_ids: Vec<_> = self
                 .language_servers_for_buffer(buffer.read(cx), cx)
@@ -6598,6 +6988,11 @@ impl Project {
                     let _ = tx.send(SearchResult::Buffer { buffer, ranges }).await;
                 }
 
+                if response.skipped_files > 0 {
+                    let skipped_files = response.skipped_files as usize;
+                    let _ = tx.send(SearchResult::SkippedFiles(skipped_files)).await;
+                }
+
                 if response.limit_reached {
                     let _ = tx.send(SearchResult::LimitReached).await;
                 }
@@ -6670,6 +7065,12 @@ impl Project {
         }
         let workers = background.num_cpus().min(path_count);
         let (matching_paths_tx, matching_paths_rx) = smol::channel::bounded(1024);
+        let max_file_size = (!query.include_large_files()).then(|| {
+            ProjectSettings::get_global(cx)
+                .project_search
+                .max_file_size()
+        });
+        let skipped_files = Arc::new(AtomicUsize::new(0));
         let mut unnamed_files = vec![];
         let opened_buffers = self
             .opened_buffers
@@ -6714,6 +7115,8 @@ impl Project {
                 include_root,
                 path_count,
                 snapshots,
+                max_file_size,
+                skipped_files.clone(),
                 matching_paths_tx,
             ))
             .detach();
@@ -6735,6 +7138,7 @@ impl Project {
                 false
             };
             matching_paths.sort_by_key(|candidate| (candidate.is_ignored(), candidate.path()));
+            let skipped_files = skipped_files.load(SeqCst);
 
             let mut range_count = 0;
             let query = Arc::new(query);
@@ -6794,6 +7198,11 @@ impl Project {
                 }
             }
 
+            if skipped_files > 0 {
+                result_tx
+                    .send(SearchResult::SkippedFiles(skipped_files))
+                    .await?;
+            }
             if limit_reached {
                 result_tx.send(SearchResult::LimitReached).await?;
             }
@@ -6817,9 +7226,12 @@ impl Project {
         include_root: bool,
         path_count: usize,
         snapshots: Vec<LocalSnapshot>,
+        max_file_size: Option<u64>,
+        skipped_files: Arc<AtomicUsize>,
         matching_paths_tx: Sender<SearchMatchCandidate>,
     ) {
         let fs = &fs;
+        let skipped_files = &*skipped_files;
         let query = &query;
         let matching_paths_tx = &matching_paths_tx;
         let snapshots = &snapshots;
@@ -6865,6 +7277,8 @@ impl Project {
                                 &opened_buffers,
                                 include_root,
                                 fs,
+                                max_file_size,
+                                skipped_files,
                             )
                             .await;
                         }
@@ -6877,11 +7291,13 @@ impl Project {
                             let limiter = Arc::clone(&max_concurrent_workers);
                             scope.spawn(async move {
                                 let _guard = limiter.acquire().await;
-                                search_ignored_entry(
+                                search_unloaded_entry(
                                     snapshot,
                                     ignored_entry,
                                     fs,
                                     query,
+                                    max_file_size,
+                                    skipped_files,
                                     matching_paths_tx,
                                 )
                                 .await;
@@ -6889,6 +7305,30 @@ impl Project {
                         }
                     }
                 }
+
+                for snapshot in snapshots {
+                    let sparse_entries = snapshot.entries(false).filter(|entry| {
+                        entry.kind == EntryKind::UnloadedDir
+                            && !entry.is_external
+                            && !snapshot.is_in_sparse_scan(&entry.path)
+                    });
+                    for sparse_entry in sparse_entries {
+                        let limiter = Arc::clone(&max_concurrent_workers);
+                        scope.spawn(async move {
+                            let _guard = limiter.acquire().await;
+                            search_unloaded_entry(
+                                snapshot,
+                                sparse_entry,
+                                fs,
+                                query,
+                                max_file_size,
+                                skipped_files,
+                                matching_paths_tx,
+                            )
+                            .await;
+                        });
+                    }
+                }
             })
             .await;
     }
@@ -7306,6 +7746,7 @@ impl Project {
         let snapshot = worktree_handle.read(cx).snapshot();
 
         let mut renamed_buffers = Vec::new();
+        let mut orphaned_buffers = Vec::new();
         for (path, entry_id, _) in changes {
             let worktree_id = worktree_handle.read(cx).id();
             let project_path = ProjectPath {
@@ -7374,6 +7815,10 @@ impl Project {
                         }
                     };
 
+                    if new_file.is_deleted && !old_file.is_deleted && !buffer.is_dirty() {
+                        orphaned_buffers.push(cx.handle());
+                    }
+
                     let old_path = old_file.abs_path(cx);
                     if new_file.abs_path(cx) != old_path {
                         renamed_buffers.push((cx.handle(), old_file.clone()));
@@ -7417,6 +7862,136 @@ impl Project {
             self.detect_language_for_buffer(&buffer, cx);
             self.register_buffer_with_language_servers(&buffer, cx);
         }
+
+        if !orphaned_buffers.is_empty() {
+            self.follow_recreated_files(worktree_handle, orphaned_buffers, changes, cx);
+        }
+    }
+
+    /// Moves open buffers whose files were deleted to files with the same contents that were
+    /// created in the same batch of changes. A renamed file keeps its inode, so its buffer
+    /// follows its entry, but some tools, like `git checkout`, delete files and write new ones.
+    fn follow_recreated_files(
+        &mut self,
+        worktree_handle: &Model<Worktree>,
+        orphaned_buffers: Vec<Model<Buffer>>,
+        changes: &[(Arc<Path>, ProjectEntryId, PathChange)],
+        cx: &mut ModelContext<Self>,
+    ) {
+        let worktree = worktree_handle.read(cx);
+        let worktree_abs_path = worktree.abs_path();
+        let mut extensions = HashSet::default();
+        let orphaned_buffers = orphaned_buffers
+            .into_iter()
+            .map(|buffer| {
+                let buffer_ref = buffer.read(cx);
+                if let Some(file) = buffer_ref.file() {
+                    extensions.insert(file.path().extension().map(OsStr::to_owned));
+                }
+                let hash = content_hash(buffer_ref.as_rope().chunks());
+                (buffer, hash)
+            })
+            .collect::<Vec<_>>();
+
+        // Only files that were likely renamed from one of the buffers' files are read, so that
+        // many files aren't read when many are created at once.
+        let added_paths = changes
+            .iter()
+            .filter(|(path, _, change)| {
+                matches!(change, PathChange::Added | PathChange::AddedOrUpdated)
+                    && extensions.contains(&path.extension().map(OsStr::to_owned))
+                    && worktree
+                        .entry_for_path(path)
+                        .map_or(false, |entry| entry.is_file())
+            })
+            .map(|(path, _, _)| path.clone())
+            .take(MAX_RECREATED_FILE_CANDIDATES)
+            .collect::<Vec<_>>();
+        if added_paths.is_empty() {
+            return;
+        }
+
+        let fs = self.fs.clone();
+        let worktree_handle = worktree_handle.clone();
+        cx.spawn(move |this, mut cx| async move {
+            let mut paths_by_hash = HashMap::default();
+            for path in added_paths {
+                let Ok(mut text) = fs.load(&worktree_abs_path.join(&path)).await else {
+                    continue;
+                };
+                LineEnding::normalize(&mut text);
+                paths_by_hash
+                    .entry(content_hash([text.as_str()]))
+                    .or_insert(path);
+            }
+
+            this.update(&mut cx, |this, cx| {
+                for (buffer, hash) in orphaned_buffers {
+                    if let Some(path) = paths_by_hash.remove(&hash) {
+                        this.move_buffer_to_path(&worktree_handle, &buffer, path, cx);
+                    }
+                }
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Points a buffer whose file was deleted at the file at another path in the same worktree.
+    fn move_buffer_to_path(
+        &mut self,
+        worktree_handle: &Model<Worktree>,
+        buffer: &Model<Buffer>,
+        path: Arc<Path>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let Some(old_file) = File::from_dyn(buffer.read(cx).file()).cloned() else {
+            return;
+        };
+        // The buffer may have been edited, or given another file, in the meantime.
+        if !old_file.is_deleted || buffer.read(cx).is_dirty() {
+            return;
+        }
+        let Some(entry) = worktree_handle.read(cx).entry_for_path(&path) else {
+            return;
+        };
+        let new_file = File {
+            is_local: true,
+            entry_id: Some(entry.id),
+            mtime: entry.mtime,
+            path: entry.path.clone(),
+            worktree: worktree_handle.clone(),
+            is_deleted: false,
+            is_private: entry.is_private,
+        };
+
+        let worktree_id = worktree_handle.read(cx).id();
+        let buffer_id = buffer.read(cx).remote_id();
+        self.local_buffer_ids_by_path.remove(&ProjectPath {
+            worktree_id,
+            path: old_file.path.clone(),
+        });
+        self.local_buffer_ids_by_path
+            .insert(ProjectPath { worktree_id, path }, buffer_id);
+        if let Some(entry_id) = old_file.entry_id {
+            self.local_buffer_ids_by_entry_id.remove(&entry_id);
+        }
+        self.local_buffer_ids_by_entry_id
+            .insert(entry.id, buffer_id);
+
+        if let Some(project_id) = self.remote_id() {
+            self.client
+                .send(proto::UpdateBufferFile {
+                    project_id,
+                    buffer_id: buffer_id.into(),
+                    file: Some(new_file.to_proto()),
+                })
+                .log_err();
+        }
+        buffer.update(cx, |buffer, cx| buffer.file_updated(Arc::new(new_file), cx));
+
+        self.unregister_buffer_from_language_servers(buffer, &old_file, cx);
+        self.detect_language_for_buffer(buffer, cx);
+        self.register_buffer_with_language_servers(buffer, cx);
     }
 
     fn update_local_worktree_language_servers(
@@ -9383,6 +9958,7 @@ impl Project {
         cx.spawn(move |mut cx| async move {
             let mut locations = Vec::new();
             let mut limit_reached = false;
+            let mut skipped_files = 0;
             while let Some(result) = result.next().await {
                 match result {
                     SearchResult::Buffer { buffer, ranges } => {
@@ -9399,12 +9975,14 @@ impl Project {
                             });
                         }
                     }
+                    SearchResult::SkippedFiles(count) => skipped_files += count,
                     SearchResult::LimitReached => limit_reached = true,
                 }
             }
             Ok(proto::SearchProjectResponse {
                 locations,
                 limit_reached,
+                skipped_files: skipped_files as u64,
             })
         })
         .await
@@ -9473,7 +10051,7 @@ impl Project {
         hasher.update(project_path.worktree_id.to_proto().to_be_bytes());
         hasher.update(project_path.path.to_string_lossy().as_bytes());
         hasher.update(self.nonce.to_be_bytes());
-        hasher.finalize().as_slice().try_into().unwrap()
+        hasher.finalize().into()
     }
 
     async fn handle_open_buffer_by_id(
@@ -10336,6 +10914,8 @@ async fn search_snapshots(
     opened_buffers: &HashMap<Arc<Path>, (Model<Buffer>, BufferSnapshot)>,
     include_root: bool,
     fs: &Arc<dyn Fs>,
+    max_file_size: Option<u64>,
+    skipped_files: &AtomicUsize,
 ) {
     let mut snapshot_start_ix = 0;
     let mut abs_path = PathBuf::new();
@@ -10380,7 +10960,7 @@ async fn search_snapshots(
                     abs_path.push(&snapshot.abs_path());
                     abs_path.push(&entry.path);
                     if let Some(file) = fs.open_sync(&abs_path).await.log_err() {
-                        query.detect(file).unwrap_or(false)
+                        detect_in_file(file, query, max_file_size, skipped_files)
                     } else {
                         false
                     }
@@ -10405,52 +10985,56 @@ async fn search_snapshots(
     }
 }
 
-async fn search_ignored_entry(
+/// Searches a directory that isn't loaded in the worktree, because it's ignored, or outside
+/// of a sparse worktree's scan paths, by reading it from disk.
+#[allow(clippy::too_many_arguments)]
+async fn search_unloaded_entry(
     snapshot: &LocalSnapshot,
-    ignored_entry: &Entry,
+    unloaded_entry: &Entry,
     fs: &Arc<dyn Fs>,
     query: &SearchQuery,
+    max_file_size: Option<u64>,
+    skipped_files: &AtomicUsize,
     counter_tx: &Sender<SearchMatchCandidate>,
 ) {
-    let mut ignored_paths_to_process =
-        VecDeque::from([snapshot.abs_path().join(&ignored_entry.path)]);
+    let mut paths_to_process = VecDeque::from([snapshot.abs_path().join(&unloaded_entry.path)]);
 
-    while let Some(ignored_abs_path) = ignored_paths_to_process.pop_front() {
+    while let Some(abs_path) = paths_to_process.pop_front() {
         let metadata = fs
-            .metadata(&ignored_abs_path)
+            .metadata(&abs_path)
             .await
-            .with_context(|| format!("fetching fs metadata for {ignored_abs_path:?}"))
+            .with_context(|| format!("fetching fs metadata for {abs_path:?}"))
             .log_err()
             .flatten();
 
         if let Some(fs_metadata) = metadata {
             if fs_metadata.is_dir {
                 let files = fs
-                    .read_dir(&ignored_abs_path)
+                    .read_dir(&abs_path)
                     .await
-                    .with_context(|| format!("listing ignored path {ignored_abs_path:?}"))
+                    .with_context(|| format!("listing unloaded path {abs_path:?}"))
                     .log_err();
 
                 if let Some(mut subfiles) = files {
                     while let Some(subfile) = subfiles.next().await {
                         if let Some(subfile) = subfile.log_err() {
-                            ignored_paths_to_process.push_back(subfile);
+                            paths_to_process.push_back(subfile);
                         }
                     }
                 }
             } else if !fs_metadata.is_symlink {
-                if !query.file_matches(Some(&ignored_abs_path))
-                    || snapshot.is_path_excluded(ignored_entry.path.to_path_buf())
+                if !query.file_matches(Some(&abs_path))
+                    || snapshot.is_path_excluded(unloaded_entry.path.to_path_buf())
                 {
                     continue;
                 }
                 let matches = if let Some(file) = fs
-                    .open_sync(&ignored_abs_path)
+                    .open_sync(&abs_path)
                     .await
-                    .with_context(|| format!("Opening ignored path {ignored_abs_path:?}"))
+                    .with_context(|| format!("Opening unloaded path {abs_path:?}"))
                     .log_err()
                 {
-                    query.detect(file).unwrap_or(false)
+                    detect_in_file(file, query, max_file_size, skipped_files)
                 } else {
                     false
                 };
@@ -10459,11 +11043,11 @@ async fn search_ignored_entry(
                     let project_path = SearchMatchCandidate::Path {
                         worktree_id: snapshot.id(),
                         path: Arc::from(
-                            ignored_abs_path
+                            abs_path
                                 .strip_prefix(snapshot.abs_path())
                                 .expect("scanning worktree-related files"),
                         ),
-                        is_ignored: true,
+                        is_ignored: unloaded_entry.is_ignored,
                     };
                     if counter_tx.send(project_path).await.is_err() {
                         return;
@@ -10474,6 +11058,39 @@ async fn search_ignored_entry(
     }
 }
 
+/// How much of a file is checked for null bytes, which binary files have and text files
+/// don't, as git decides.
+const BINARY_DETECTION_LEN: usize = 8000;
+
+/// Whether a file on disk contains a match for a search query. Files larger than
+/// `max_file_size`, and files that look binary, are counted as skipped instead, unless
+/// there's no maximum.
+fn detect_in_file(
+    file: impl io::Read,
+    query: &SearchQuery,
+    max_file_size: Option<u64>,
+    skipped_files: &AtomicUsize,
+) -> bool {
+    let Some(max_file_size) = max_file_size else {
+        return query.detect(file).unwrap_or(false);
+    };
+    let mut contents = Vec::new();
+    if file
+        .take(max_file_size + 1)
+        .read_to_end(&mut contents)
+        .log_err()
+        .is_none()
+    {
+        return false;
+    }
+    let head = &contents[..contents.len().min(BINARY_DETECTION_LEN)];
+    if contents.len() as u64 > max_file_size || head.contains(&0) {
+        skipped_files.fetch_add(1, SeqCst);
+        return false;
+    }
+    query.detect(contents.as_slice()).unwrap_or(false)
+}
+
 fn glob_literal_prefix(glob: &str) -> &str {
     let mut literal_end = 0;
     for (i, part) in glob.split(path::MAIN_SEPARATOR).enumerate() {
@@ -10608,6 +11225,7 @@ struct ProjectLspAdapterDelegate {
     http_client: Arc<dyn HttpClient>,
     language_registry: Arc<LanguageRegistry>,
     shell_env: Mutex<Option<HashMap<String, String>>>,
+    lsp_settings: HashMap<Arc<str>, LspSettings>,
 }
 
 impl ProjectLspAdapterDelegate {
@@ -10619,6 +11237,7 @@ impl ProjectLspAdapterDelegate {
             http_client: project.client.http_client(),
             language_registry: project.languages.clone(),
             shell_env: Default::default(),
+            lsp_settings: ProjectSettings::get_global(cx).lsp.clone(),
         })
     }
 
@@ -10689,6 +11308,27 @@ impl LspAdapterDelegate for ProjectLspAdapterDelegate {
             .update_lsp_status(server_name, status);
     }
 
+    fn is_pinned(&self, server_name: &LanguageServerName) -> bool {
+        self.lsp_settings
+            .get(&server_name.0)
+            .and_then(|settings| settings.pinned)
+            .unwrap_or(false)
+    }
+
+    fn configured_binary(&self, server_name: &LanguageServerName) -> Option<LanguageServerBinary> {
+        let binary = self.lsp_settings.get(&server_name.0)?.binary.clone()?;
+        Some(LanguageServerBinary {
+            path: binary.path?.into(),
+            arguments: binary
+                .arguments
+                .unwrap_or_default()
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            env: None,
+        })
+    }
+
     async fn read_text_file(&self, path: PathBuf) -> Result<String> {
         if self.worktree.entry_for_path(&path).is_none() {
             return Err(anyhow!("no such path {path:?}"));
@@ -10719,6 +11359,15 @@ fn serialize_symbol(symbol: &Symbol) -> proto::Symbol {
     }
 }
 
+/// A hash of text read in chunks, used to find files with the same contents.
+fn content_hash<'a>(chunks: impl IntoIterator<Item = &'a str>) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    for chunk in chunks {
+        hasher.update(chunk.as_bytes());
+    }
+    hasher.finalize().into()
+}
+
 fn relativize_path(base: &Path, path: &Path) -> PathBuf {
     let mut path_components = path.components();
     let mut base_components = base.components();
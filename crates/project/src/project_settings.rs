@@ -20,6 +20,10 @@ pub struct ProjectSettings {
     /// Configuration for Git-related features
     #[serde(default)]
     pub git: GitSettings,
+
+    /// Configuration for project search.
+    #[serde(default)]
+    pub project_search: ProjectSearchSettings,
 }
 
 #[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
@@ -55,6 +59,21 @@ impl GitSettings {
     }
 }
 
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct ProjectSearchSettings {
+    /// Files larger than this many bytes are skipped by project search, along with
+    /// files that look binary, unless large and binary files are included in the search.
+    ///
+    /// Default: 1048576
+    pub max_file_size: Option<u64>,
+}
+
+impl ProjectSearchSettings {
+    pub fn max_file_size(&self) -> u64 {
+        self.max_file_size.unwrap_or(1024 * 1024)
+    }
+}
+
 #[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum GitGutterSetting {
@@ -101,6 +120,11 @@ pub struct LspSettings {
     pub binary: Option<BinarySettings>,
     pub initialization_options: Option<serde_json::Value>,
     pub settings: Option<serde_json::Value>,
+    /// Whether to keep running the downloaded version of the language server, rather than
+    /// checking for a newer one.
+    ///
+    /// Default: false
+    pub pinned: Option<bool>,
 }
 
 impl Settings for ProjectSettings {
@@ -973,6 +973,88 @@ async fn test_single_file_worktrees_diagnostics(cx: &mut gpui::TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_export_diagnostics(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "a.rs": "let a = 1;",
+            "b.rs": "let b = 2;"
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+
+    // Diagnostics of files that aren't open are exported too.
+    project.update(cx, |project, cx| {
+        project
+            .update_diagnostics(
+                LanguageServerId(0),
+                lsp::PublishDiagnosticsParams {
+                    uri: Url::from_file_path("/dir/b.rs").unwrap(),
+                    version: None,
+                    diagnostics: vec![lsp::Diagnostic {
+                        range: lsp::Range::new(lsp::Position::new(0, 4), lsp::Position::new(0, 5)),
+                        severity: Some(lsp::DiagnosticSeverity::WARNING),
+                        code: Some(lsp::NumberOrString::String("unused".to_string())),
+                        message: "unused variable".to_string(),
+                        ..Default::default()
+                    }],
+                },
+                &[],
+                cx,
+            )
+            .unwrap();
+    });
+
+    let exported = project.read_with(cx, |project, cx| {
+        project
+            .export_diagnostics(DiagnosticsExportFormat::Json, cx)
+            .unwrap()
+    });
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&exported).unwrap(),
+        json!([{
+            "path": "/dir/b.rs",
+            "line": 1,
+            "column": 5,
+            "end_line": 1,
+            "end_column": 6,
+            "severity": "warning",
+            "message": "unused variable",
+            "source": null,
+            "code": "unused",
+            "language_server": "0",
+        }])
+    );
+
+    let exported = project.read_with(cx, |project, cx| {
+        project
+            .export_diagnostics(DiagnosticsExportFormat::Sarif, cx)
+            .unwrap()
+    });
+    let sarif = serde_json::from_str::<serde_json::Value>(&exported).unwrap();
+    assert_eq!(sarif["version"], "2.1.0");
+    assert_eq!(
+        sarif["runs"][0]["results"],
+        json!([{
+            "level": "warning",
+            "ruleId": "unused",
+            "message": { "text": "unused variable" },
+            "locations": [{
+                "physicalLocation": {
+                    "artifactLocation": { "uri": "b.rs", "uriBaseId": "dir" },
+                    "region": { "startLine": 1, "startColumn": 5, "endLine": 1, "endColumn": 6 },
+                },
+            }],
+        }])
+    );
+}
+
 #[gpui::test]
 async fn test_omitted_diagnostics(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -3156,6 +3238,114 @@ async fn test_buffer_identity_across_renames(cx: &mut gpui::TestAppContext) {
     buffer.update(cx, |buffer, _| assert!(!buffer.is_dirty()));
 }
 
+#[gpui::test]
+async fn test_buffer_follows_recreated_file(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "old.txt": "the contents\n",
+            "other.txt": "other contents\n",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    let buffer = project
+        .update(cx, |p, cx| p.open_local_buffer("/dir/old.txt", cx))
+        .await
+        .unwrap();
+
+    // The file is deleted and written to a new path, with a new inode, in one batch of events,
+    // along with an unrelated new file.
+    fs.pause_events();
+    fs.remove_file("/dir/old.txt".as_ref(), Default::default())
+        .await
+        .unwrap();
+    fs.insert_file("/dir/new.txt", b"the contents\n".to_vec())
+        .await;
+    fs.insert_file("/dir/unrelated.txt", b"unrelated\n".to_vec())
+        .await;
+    fs.flush_events(fs.buffered_event_count());
+    cx.executor().run_until_parked();
+
+    buffer.update(cx, |buffer, _| {
+        let file = buffer.file().unwrap();
+        assert_eq!(file.path().as_ref(), Path::new("new.txt"));
+        assert!(!file.is_deleted());
+        assert!(!buffer.is_dirty());
+    });
+    let reopened = project
+        .update(cx, |p, cx| p.open_local_buffer("/dir/new.txt", cx))
+        .await
+        .unwrap();
+    assert_eq!(reopened.entity_id(), buffer.entity_id());
+}
+
+#[gpui::test]
+async fn test_virtual_files(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/dir", json!({})).await;
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    let languages = project.update(cx, |project, _| project.languages().clone());
+    languages.add(rust_lang());
+
+    let buffer = project.update(cx, |project, cx| {
+        project.add_virtual_file("HEAD", Path::new("src/main.rs"), "fn a() {}".into(), cx)
+    });
+    cx.executor().run_until_parked();
+    buffer.update(cx, |buffer, cx| {
+        assert_eq!(buffer.text(), "fn a() {}");
+        assert!(buffer.read_only());
+        assert!(!buffer.is_dirty());
+        assert_eq!(buffer.language().unwrap().name().as_ref(), "Rust");
+        assert_eq!(
+            buffer.file().unwrap().full_path(cx),
+            Path::new("HEAD/src/main.rs")
+        );
+    });
+
+    // Adding the same file again replaces its contents.
+    let same_buffer = project.update(cx, |project, cx| {
+        project.add_virtual_file("HEAD", Path::new("src/main.rs"), "fn b() {}".into(), cx)
+    });
+    assert_eq!(same_buffer.entity_id(), buffer.entity_id());
+    buffer.update(cx, |buffer, _| {
+        assert_eq!(buffer.text(), "fn b() {}");
+        assert!(!buffer.is_dirty());
+    });
+
+    project.update(cx, |project, cx| {
+        project.add_virtual_file("HEAD~1", Path::new("src/main.rs"), "".into(), cx);
+        assert_eq!(
+            project
+                .virtual_entries()
+                .iter()
+                .map(|entry| (entry.root_name.as_ref(), entry.path.as_ref()))
+                .collect::<Vec<_>>(),
+            [
+                ("HEAD", Path::new("src/main.rs")),
+                ("HEAD~1", Path::new("src/main.rs"))
+            ]
+        );
+
+        project.remove_virtual_root("HEAD", cx);
+        assert_eq!(
+            project
+                .virtual_entries()
+                .iter()
+                .map(|entry| entry.root_name.as_ref())
+                .collect::<Vec<_>>(),
+            ["HEAD~1"]
+        );
+    });
+}
+
 #[gpui::test]
 async fn test_buffer_deduping(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -3918,6 +4108,55 @@ async fn test_search(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_search_skips_large_and_binary_files(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+    cx.update(|cx| {
+        SettingsStore::update_global(cx, |store, cx| {
+            store.update_user_settings::<project_settings::ProjectSettings>(cx, |settings| {
+                settings.project_search.max_file_size = Some(32);
+            });
+        });
+    });
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "one.rs": "const ONE: usize = 1;",
+            "two.rs": "const TWO: usize = one::ONE + one::ONE;",
+            "data.bin": "ONE\0\0\0",
+        }),
+    )
+    .await;
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    let query = SearchQuery::text("ONE", false, true, false, Vec::new(), Vec::new()).unwrap();
+
+    let mut search_rx = project.update(cx, |project, cx| project.search(query.clone(), cx));
+    let mut skipped_files = 0;
+    while let Some(result) = search_rx.next().await {
+        if let SearchResult::SkippedFiles(count) = result {
+            skipped_files += count;
+        }
+    }
+    assert_eq!(skipped_files, 2);
+    assert_eq!(
+        search(&project, query.clone(), cx).await.unwrap(),
+        HashMap::from_iter([("dir/one.rs".to_string(), vec![6..9])])
+    );
+
+    assert_eq!(
+        search(&project, query.with_large_files(true), cx)
+            .await
+            .unwrap(),
+        HashMap::from_iter([
+            ("dir/one.rs".to_string(), vec![6..9]),
+            ("dir/two.rs".to_string(), vec![26..29, 37..40]),
+            ("dir/data.bin".to_string(), vec![0..3]),
+        ])
+    );
+}
+
 #[gpui::test]
 async fn test_search_with_inclusions(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -4684,6 +4923,65 @@ async fn test_multiple_language_server_hovers(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_format_on_save_exclusions(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "gen": { "a.txt": "one  \ntwo" },
+            "b.txt": "one  \ntwo",
+        }),
+    )
+    .await;
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+    cx.update(|cx| {
+        SettingsStore::update_global(cx, |settings, cx| {
+            settings.update_user_settings::<AllLanguageSettings>(cx, |settings| {
+                settings.format_on_save_exclusions = Some(vec!["gen/**".to_string()]);
+            });
+        })
+    });
+
+    let excluded_buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/gen/a.txt", cx)
+        })
+        .await
+        .unwrap();
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/b.txt", cx)
+        })
+        .await
+        .unwrap();
+
+    // Saving leaves the excluded file as it is.
+    project
+        .update(cx, |project, cx| {
+            let buffers = [excluded_buffer.clone(), buffer.clone()]
+                .into_iter()
+                .collect();
+            project.format(buffers, true, FormatTrigger::Save, cx)
+        })
+        .await
+        .unwrap();
+    excluded_buffer.read_with(cx, |buffer, _| assert_eq!(buffer.text(), "one  \ntwo"));
+    buffer.read_with(cx, |buffer, _| assert_eq!(buffer.text(), "one\ntwo\n"));
+
+    // Formatting it explicitly still cleans it up.
+    project
+        .update(cx, |project, cx| {
+            let buffers = [excluded_buffer.clone()].into_iter().collect();
+            project.format(buffers, true, FormatTrigger::Manual, cx)
+        })
+        .await
+        .unwrap();
+    excluded_buffer.read_with(cx, |buffer, _| assert_eq!(buffer.text(), "one\ntwo\n"));
+}
+
 #[gpui::test]
 async fn test_hovers_with_empty_parts(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -4921,7 +5219,7 @@ async fn search(
             SearchResult::Buffer { buffer, ranges } => {
                 results.entry(buffer).or_insert(ranges);
             }
-            SearchResult::LimitReached => {}
+            SearchResult::SkippedFiles(_) | SearchResult::LimitReached => {}
         }
     }
     Ok(results
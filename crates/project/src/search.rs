@@ -1,7 +1,7 @@
 use aho_corasick::{AhoCorasick, AhoCorasickBuilder};
 use anyhow::{Context, Result};
 use client::proto;
-use itertools::Itertools;
+use itertools::{Either, Itertools};
 use language::{char_kind, BufferSnapshot};
 use regex::{Captures, Regex, RegexBuilder};
 use smol::future::yield_now;
@@ -16,11 +16,16 @@ use util::paths::PathMatcher;
 
 static TEXT_REPLACEMENT_SPECIAL_CHARACTERS_REGEX: OnceLock<Regex> = OnceLock::new();
 
+/// How many steps the backtracking engine may take looking for a match at one position, so
+/// that catastrophic patterns like `(a+)+b` give up rather than hang the search.
+const BACKTRACK_LIMIT: usize = 1_000_000;
+
 #[derive(Clone, Debug)]
 pub struct SearchInputs {
     query: Arc<str>,
     files_to_include: Vec<PathMatcher>,
     files_to_exclude: Vec<PathMatcher>,
+    include_large_files: bool,
 }
 
 impl SearchInputs {
@@ -46,7 +51,7 @@ pub enum SearchQuery {
     },
 
     Regex {
-        regex: Regex,
+        regex: SearchRegex,
         replacement: Option<String>,
         multiline: bool,
         whole_word: bool,
@@ -73,6 +78,7 @@ impl SearchQuery {
             query: query.into(),
             files_to_exclude,
             files_to_include,
+            include_large_files: false,
         };
         Ok(Self::Text {
             search: Arc::new(search),
@@ -102,15 +108,13 @@ impl SearchQuery {
             query = word_query
         }
 
-        let multiline = query.contains('\n') || query.contains("\\n");
-        let regex = RegexBuilder::new(&query)
-            .case_insensitive(!case_sensitive)
-            .multi_line(multiline)
-            .build()?;
+        let multiline = is_multiline_pattern(&query);
+        let regex = SearchRegex::new(&query, case_sensitive, multiline)?;
         let inner = SearchInputs {
             query: initial_query,
             files_to_exclude,
             files_to_include,
+            include_large_files: false,
         };
         Ok(Self::Regex {
             regex,
@@ -124,7 +128,7 @@ impl SearchQuery {
     }
 
     pub fn from_proto(message: proto::SearchProject) -> Result<Self> {
-        if message.regex {
+        let query = if message.regex {
             Self::regex(
                 message.query,
                 message.whole_word,
@@ -132,7 +136,7 @@ impl SearchQuery {
                 message.include_ignored,
                 deserialize_path_matches(&message.files_to_include)?,
                 deserialize_path_matches(&message.files_to_exclude)?,
-            )
+            )?
         } else {
             Self::text(
                 message.query,
@@ -141,9 +145,22 @@ impl SearchQuery {
                 message.include_ignored,
                 deserialize_path_matches(&message.files_to_include)?,
                 deserialize_path_matches(&message.files_to_exclude)?,
-            )
+            )?
+        };
+        Ok(query.with_large_files(message.include_large_files))
+    }
+
+    /// Sets whether files on disk that are larger than the project search `max_file_size`
+    /// setting, or that look binary, are searched. They're skipped by default.
+    pub fn with_large_files(mut self, include_large_files: bool) -> Self {
+        match self {
+            Self::Text { ref mut inner, .. } | Self::Regex { ref mut inner, .. } => {
+                inner.include_large_files = include_large_files;
+                self
+            }
         }
     }
+
     pub fn with_replacement(mut self, new_replacement: String) -> Self {
         match self {
             Self::Text {
@@ -167,6 +184,7 @@ impl SearchQuery {
             whole_word: self.whole_word(),
             case_sensitive: self.case_sensitive(),
             include_ignored: self.include_ignored(),
+            include_large_files: self.include_large_files(),
             files_to_include: self
                 .files_to_include()
                 .iter()
@@ -203,12 +221,12 @@ impl SearchQuery {
                     if let Err(err) = reader.read_to_string(&mut text) {
                         Err(err.into())
                     } else {
-                        Ok(regex.find(&text).is_some())
+                        regex.is_match(&text)
                     }
                 } else {
                     for line in reader.lines() {
                         let line = line?;
-                        if regex.find(&line).is_some() {
+                        if regex.is_match(&line)? {
                             return Ok(true);
                         }
                     }
@@ -243,7 +261,7 @@ impl SearchQuery {
                                 x => unreachable!("Unexpected escape sequence: {}", x),
                             }
                         });
-                    Some(regex.replace(text, replacement))
+                    Some(regex.replace(text, &replacement))
                 } else {
                     None
                 }
@@ -309,7 +327,13 @@ impl SearchQuery {
                             yield_now().await;
                         }
 
-                        matches.push(mat.start()..mat.end());
+                        match mat {
+                            Ok(range) => matches.push(range),
+                            Err(error) => {
+                                log::warn!("stopped searching buffer: {error}");
+                                break;
+                            }
+                        }
                     }
                 } else {
                     let mut line = String::new();
@@ -322,9 +346,15 @@ impl SearchQuery {
                         for (newline_ix, text) in chunk.split('\n').enumerate() {
                             if newline_ix > 0 {
                                 for mat in regex.find_iter(&line) {
-                                    let start = line_offset + mat.start();
-                                    let end = line_offset + mat.end();
-                                    matches.push(start..end);
+                                    match mat {
+                                        Ok(range) => matches.push(
+                                            line_offset + range.start..line_offset + range.end,
+                                        ),
+                                        Err(error) => {
+                                            log::warn!("stopped searching buffer: {error}");
+                                            return matches;
+                                        }
+                                    }
                                 }
 
                                 line_offset += line.len() + 1;
@@ -373,10 +403,22 @@ impl SearchQuery {
         }
     }
 
+    pub fn include_large_files(&self) -> bool {
+        self.as_inner().include_large_files
+    }
+
     pub fn is_regex(&self) -> bool {
         matches!(self, Self::Regex { .. })
     }
 
+    /// Whether the query can match text spanning more than one line.
+    pub fn is_multiline(&self) -> bool {
+        match self {
+            Self::Text { inner, .. } => inner.as_str().contains('\n'),
+            Self::Regex { multiline, .. } => *multiline,
+        }
+    }
+
     pub fn files_to_include(&self) -> &[PathMatcher] {
         self.as_inner().files_to_include()
     }
@@ -418,6 +460,93 @@ impl SearchQuery {
     }
 }
 
+/// A compiled regex query. Patterns run on the `regex` crate's engine, which always finishes in
+/// linear time, unless they use lookaround or backreferences, which only `fancy-regex`'s
+/// backtracking engine supports.
+#[derive(Clone, Debug)]
+pub enum SearchRegex {
+    Linear(Regex),
+    Backtracking(fancy_regex::Regex),
+}
+
+impl SearchRegex {
+    fn new(pattern: &str, case_sensitive: bool, multiline: bool) -> Result<Self> {
+        let error = match RegexBuilder::new(pattern)
+            .case_insensitive(!case_sensitive)
+            .multi_line(multiline)
+            .build()
+        {
+            Ok(regex) => return Ok(Self::Linear(regex)),
+            Err(error) => error,
+        };
+
+        let flags = match (case_sensitive, multiline) {
+            (true, false) => "",
+            (true, true) => "(?m)",
+            (false, false) => "(?i)",
+            (false, true) => "(?im)",
+        };
+        // Patterns that neither engine supports are reported with the `regex` crate's error,
+        // which is the more descriptive of the two.
+        fancy_regex::RegexBuilder::new(&format!("{flags}{pattern}"))
+            .backtrack_limit(BACKTRACK_LIMIT)
+            .build()
+            .map(Self::Backtracking)
+            .map_err(|_| error.into())
+    }
+
+    /// Whether the pattern matches the text, which fails if the backtracking engine gives up.
+    pub fn is_match(&self, text: &str) -> Result<bool> {
+        match self {
+            Self::Linear(regex) => Ok(regex.is_match(text)),
+            Self::Backtracking(regex) => Ok(regex.is_match(text)?),
+        }
+    }
+
+    /// The ranges of the matches in the text, ending with an error if the backtracking engine
+    /// gives up.
+    pub fn find_iter<'a>(
+        &'a self,
+        text: &'a str,
+    ) -> impl Iterator<Item = Result<Range<usize>>> + 'a {
+        match self {
+            Self::Linear(regex) => Either::Left(regex.find_iter(text).map(|mat| Ok(mat.range()))),
+            Self::Backtracking(regex) => {
+                Either::Right(regex.find_iter(text).map(|mat| -> Result<Range<usize>> {
+                    let mat = mat?;
+                    Ok(mat.start()..mat.end())
+                }))
+            }
+        }
+    }
+
+    /// Replaces the first match in the text, expanding `$1`-style references to its groups.
+    pub fn replace<'a>(&self, text: &'a str, replacement: &str) -> Cow<'a, str> {
+        match self {
+            Self::Linear(regex) => regex.replace(text, replacement),
+            Self::Backtracking(regex) => regex.replace(text, replacement),
+        }
+    }
+}
+
+/// Whether a pattern should be matched against whole files rather than line by line, because
+/// it contains a newline or a `\n` or `\r` escape.
+fn is_multiline_pattern(pattern: &str) -> bool {
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\n' => return true,
+            '\\' => {
+                if matches!(chars.next(), Some('n' | 'r')) {
+                    return true;
+                }
+            }
+            _ => {}
+        }
+    }
+    false
+}
+
 fn deserialize_path_matches(glob_set: &str) -> anyhow::Result<Vec<PathMatcher>> {
     glob_set
         .split(',')
@@ -455,6 +584,37 @@ mod tests {
         }
     }
 
+    #[test]
+    fn regex_queries_with_lookaround() {
+        let query =
+            SearchQuery::regex("foo(?=bar)", false, true, false, Vec::new(), Vec::new()).unwrap();
+        assert!(query.detect("a foobar".as_bytes()).unwrap());
+        assert!(!query.detect("a foobaz".as_bytes()).unwrap());
+
+        let query =
+            SearchQuery::regex("(?<=x)FOO", false, false, false, Vec::new(), Vec::new()).unwrap();
+        assert!(query.detect("xfoo".as_bytes()).unwrap());
+        assert!(!query.detect("yfoo".as_bytes()).unwrap());
+
+        assert!(
+            SearchQuery::regex("(?=unclosed", false, true, false, Vec::new(), Vec::new()).is_err()
+        );
+    }
+
+    #[test]
+    fn multiline_regex_queries() {
+        assert!(is_multiline_pattern("one\\ntwo"));
+        assert!(is_multiline_pattern("one\ntwo"));
+        assert!(is_multiline_pattern("one\\r?\\ntwo"));
+        assert!(!is_multiline_pattern("one\\\\ntwo"));
+        assert!(!is_multiline_pattern("one\\s+two"));
+
+        let query = SearchQuery::regex("one\\n\\s*two", false, true, false, Vec::new(), Vec::new())
+            .unwrap();
+        assert!(query.detect("one\n    two".as_bytes()).unwrap());
+        assert!(!query.detect("one two".as_bytes()).unwrap());
+    }
+
     #[test]
     fn path_matcher_creation_for_globs() {
         for invalid_glob in ["dir/[].txt", "dir/[a-z.txt", "dir/{file"] {
@@ -0,0 +1,92 @@
+use std::{
+    any::Any,
+    ffi::OsStr,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::SystemTime,
+};
+
+use gpui::{AppContext, Model};
+use language::{Buffer, LocalFile};
+use rpc::proto;
+
+/// A read-only file that isn't in any worktree, such as a decompiled source or a file at a git
+/// revision. Virtual files are contributed by features, and shown in the project panel under a
+/// virtual root named after the feature that contributed them.
+pub struct VirtualFile {
+    root_name: Arc<str>,
+    path: Arc<Path>,
+}
+
+/// A virtual file that's been added to a project, along with the buffer holding its contents.
+#[derive(Clone)]
+pub struct VirtualEntry {
+    pub root_name: Arc<str>,
+    pub path: Arc<Path>,
+    pub buffer: Model<Buffer>,
+}
+
+impl VirtualFile {
+    pub(crate) fn new(root_name: Arc<str>, path: Arc<Path>) -> Self {
+        Self { root_name, path }
+    }
+}
+
+impl language::File for VirtualFile {
+    fn as_local(&self) -> Option<&dyn LocalFile> {
+        None
+    }
+
+    fn mtime(&self) -> Option<SystemTime> {
+        None
+    }
+
+    fn path(&self) -> &Arc<Path> {
+        &self.path
+    }
+
+    fn full_path(&self, _: &AppContext) -> PathBuf {
+        Path::new(self.root_name.as_ref()).join(&self.path)
+    }
+
+    fn file_name<'a>(&'a self, _: &'a AppContext) -> &'a OsStr {
+        self.path
+            .file_name()
+            .unwrap_or_else(|| OsStr::new(self.root_name.as_ref()))
+    }
+
+    /// Virtual files don't belong to a worktree, so they only get the user's settings.
+    fn worktree_id(&self) -> usize {
+        usize::MAX
+    }
+
+    fn is_deleted(&self) -> bool {
+        false
+    }
+
+    /// Virtual files have no mtime, but aren't new files waiting to be saved.
+    fn is_created(&self) -> bool {
+        true
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn to_proto(&self) -> proto::File {
+        proto::File {
+            worktree_id: u64::MAX,
+            entry_id: None,
+            path: Path::new(self.root_name.as_ref())
+                .join(&self.path)
+                .to_string_lossy()
+                .into(),
+            mtime: None,
+            is_deleted: false,
+        }
+    }
+
+    fn is_private(&self) -> bool {
+        false
+    }
+}
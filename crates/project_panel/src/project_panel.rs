@@ -10,14 +10,17 @@ use anyhow::{anyhow, Result};
 use collections::{hash_map, HashMap};
 use git::repository::GitFileStatus;
 use gpui::{
-    actions, anchored, deferred, div, impl_actions, px, uniform_list, Action, AppContext,
+    actions, anchored, deferred, div, impl_actions, px, uniform_list, Action, AnyView, AppContext,
     AssetSource, AsyncWindowContext, ClipboardItem, DismissEvent, Div, EventEmitter, FocusHandle,
     FocusableView, InteractiveElement, KeyContext, Model, MouseButton, MouseDownEvent,
     ParentElement, Pixels, Point, PromptLevel, Render, Stateful, Styled, Subscription, Task,
     UniformListScrollHandle, View, ViewContext, VisualContext as _, WeakView, WindowContext,
 };
+use image_viewer::{ImageMetadata, ImageViewerSettings};
 use menu::{Confirm, SelectFirst, SelectLast, SelectNext, SelectPrev};
-use project::{Entry, EntryKind, Fs, Project, ProjectEntryId, ProjectPath, Worktree, WorktreeId};
+use project::{
+    Entry, EntryKind, Fs, Project, ProjectEntryId, ProjectPath, VirtualEntry, Worktree, WorktreeId,
+};
 use project_panel_settings::{ProjectPanelDockPosition, ProjectPanelSettings};
 use serde::{Deserialize, Serialize};
 use std::{
@@ -27,9 +30,10 @@ use std::{
     ops::Range,
     path::{Path, PathBuf},
     sync::Arc,
+    time::SystemTime,
 };
 use theme::ThemeSettings;
-use ui::{prelude::*, v_flex, ContextMenu, Icon, KeyBinding, Label, ListItem};
+use ui::{prelude::*, v_flex, ContextMenu, Icon, KeyBinding, Label, ListItem, Tooltip};
 use unicase::UniCase;
 use util::{maybe, NumericPrefixWithSuffix, ResultExt, TryFutureExt};
 use workspace::{
@@ -50,6 +54,7 @@ pub struct ProjectPanel {
     last_worktree_root_id: Option<ProjectEntryId>,
     expanded_dir_ids: HashMap<WorktreeId, Vec<ProjectEntryId>>,
     unfolded_dir_ids: HashSet<ProjectEntryId>,
+    collapsed_virtual_roots: HashSet<Arc<str>>,
     selection: Option<Selection>,
     context_menu: Option<(View<ContextMenu>, Point<Pixels>, Subscription)>,
     edit_state: Option<EditState>,
@@ -59,6 +64,21 @@ pub struct ProjectPanel {
     workspace: WeakView<Workspace>,
     width: Option<Pixels>,
     pending_serialization: Task<Option<()>>,
+    /// The metadata of the images that have been hovered, which is read once per version of
+    /// each image.
+    image_metadata: HashMap<ProjectEntryId, CachedImageMetadata>,
+}
+
+struct CachedImageMetadata {
+    mtime: Option<SystemTime>,
+    state: ImageMetadataState,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ImageMetadataState {
+    Loading,
+    Loaded(ImageMetadata),
+    Failed,
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -103,6 +123,10 @@ pub struct EntryDetails {
     is_cut: bool,
     git_status: Option<GitFileStatus>,
     is_private: bool,
+    /// The absolute path of the entry when it's an image in a local worktree, whose dimensions
+    /// and file size are shown when it's hovered.
+    image_abs_path: Option<PathBuf>,
+    mtime: Option<SystemTime>,
 }
 
 #[derive(PartialEq, Clone, Default, Debug, Deserialize)]
@@ -213,6 +237,7 @@ impl ProjectPanel {
                     this.update_visible_entries(None, cx);
                     cx.notify();
                 }
+                project::Event::VirtualEntriesChanged => cx.notify(),
                 _ => {}
             })
             .detach();
@@ -262,6 +287,7 @@ impl ProjectPanel {
                 last_worktree_root_id: Default::default(),
                 expanded_dir_ids: Default::default(),
                 unfolded_dir_ids: Default::default(),
+                collapsed_virtual_roots: Default::default(),
                 selection: None,
                 edit_state: None,
                 context_menu: None,
@@ -271,6 +297,7 @@ impl ProjectPanel {
                 workspace: workspace.weak_handle(),
                 width: None,
                 pending_serialization: Task::ready(None),
+                image_metadata: HashMap::default(),
             };
             this.update_visible_entries(None, cx);
 
@@ -1525,6 +1552,7 @@ impl ProjectPanel {
                 )
             };
             if let Some(worktree) = self.project.read(cx).worktree_for_id(*worktree_id, cx) {
+                let is_local = worktree.read(cx).is_local();
                 let snapshot = worktree.read(cx).snapshot();
                 let root_name = OsStr::new(snapshot.root_name());
                 let expanded_entry_ids = self
@@ -1593,6 +1621,12 @@ impl ProjectPanel {
                             .map_or(false, |e| e.is_cut() && e.entry_id() == entry.id),
                         git_status: status,
                         is_private: entry.is_private,
+                        image_abs_path: (is_local
+                            && entry.is_file()
+                            && image_viewer::is_image(&entry.path))
+                        .then(|| snapshot.absolutize(&entry.path).ok())
+                        .flatten(),
+                        mtime: entry.mtime,
                     };
 
                     if let Some(edit_state) = &self.edit_state {
@@ -1662,6 +1696,52 @@ impl ProjectPanel {
         (depth, difference)
     }
 
+    /// Reads the image's metadata in the background, unless it's been read since the image last
+    /// changed.
+    fn load_image_metadata(
+        &mut self,
+        entry_id: ProjectEntryId,
+        abs_path: PathBuf,
+        mtime: Option<SystemTime>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if self
+            .image_metadata
+            .get(&entry_id)
+            .map_or(false, |cached| cached.mtime == mtime)
+        {
+            return;
+        }
+
+        self.image_metadata.insert(
+            entry_id,
+            CachedImageMetadata {
+                mtime,
+                state: ImageMetadataState::Loading,
+            },
+        );
+        let fs = self.fs.clone();
+        let metadata = cx
+            .background_executor()
+            .spawn(async move { ImageMetadata::load(fs.as_ref(), &abs_path).await });
+        cx.spawn(|this, mut cx| async move {
+            let state = match metadata.await.log_err() {
+                Some(metadata) => ImageMetadataState::Loaded(metadata),
+                None => ImageMetadataState::Failed,
+            };
+            this.update(&mut cx, |this, cx| {
+                if let Some(cached) = this.image_metadata.get_mut(&entry_id) {
+                    if cached.mtime == mtime {
+                        cached.state = state;
+                        cx.notify();
+                    }
+                }
+            })
+            .ok();
+        })
+        .detach();
+    }
+
     fn render_entry(
         &self,
         entry_id: ProjectEntryId,
@@ -1680,8 +1760,25 @@ impl ProjectPanel {
         let file_name = details.filename.clone();
         let icon = details.icon.clone();
         let depth = details.depth;
+        let image_abs_path = details.image_abs_path.clone();
+        let mtime = details.mtime;
+        let project_panel = cx.view().downgrade();
         div()
             .id(entry_id.to_proto() as usize)
+            .when_some(image_abs_path, |this, image_abs_path| {
+                this.on_hover(cx.listener(move |this, hovered, cx| {
+                    if *hovered {
+                        this.load_image_metadata(entry_id, image_abs_path.clone(), mtime, cx);
+                    }
+                }))
+                .tooltip(move |cx| {
+                    let state = project_panel.upgrade().and_then(|project_panel| {
+                        let cached = project_panel.read(cx).image_metadata.get(&entry_id)?;
+                        Some(cached.state)
+                    });
+                    image_tooltip(state, cx)
+                })
+            })
             .on_drag(entry_id, move |entry_id, cx| {
                 cx.new_view(|_| DraggedProjectEntryView {
                     details: details.clone(),
@@ -1753,6 +1850,102 @@ impl ProjectPanel {
             )
     }
 
+    /// Renders the virtual files added by features below the worktrees, grouped under their
+    /// virtual roots.
+    fn render_virtual_roots(&self, cx: &mut ViewContext<Self>) -> Option<Div> {
+        let mut roots: Vec<(Arc<str>, Vec<VirtualEntry>)> = Vec::new();
+        for entry in self.project.read(cx).virtual_entries() {
+            match roots.iter_mut().find(|(name, _)| *name == entry.root_name) {
+                Some((_, entries)) => entries.push(entry.clone()),
+                None => roots.push((entry.root_name.clone(), vec![entry.clone()])),
+            }
+        }
+        if roots.is_empty() {
+            return None;
+        }
+
+        let settings = ProjectPanelSettings::get_global(cx);
+        let (indent_size, show_file_icons, show_folder_icons) = (
+            settings.indent_size,
+            settings.file_icons,
+            settings.folder_icons,
+        );
+        let mut list = v_flex()
+            .flex_none()
+            .border_t_1()
+            .border_color(cx.theme().colors().border_variant);
+        let mut file_ix = 0;
+        for (root_ix, (root_name, entries)) in roots.into_iter().enumerate() {
+            let is_expanded = !self.collapsed_virtual_roots.contains(&root_name);
+            let icon = if show_folder_icons {
+                FileIcons::get_folder_icon(is_expanded, cx)
+            } else {
+                FileIcons::get_chevron_icon(is_expanded, cx)
+            };
+            list = list.child(
+                ListItem::new(("virtual-root", root_ix))
+                    .indent_step_size(px(indent_size))
+                    .child(render_icon(icon))
+                    .child(
+                        h_flex()
+                            .h_6()
+                            .ml_1()
+                            .child(Label::new(root_name.to_string()).single_line()),
+                    )
+                    .on_click(cx.listener(move |this, _, cx| {
+                        if !this.collapsed_virtual_roots.remove(&root_name) {
+                            this.collapsed_virtual_roots.insert(root_name.clone());
+                        }
+                        cx.notify();
+                    })),
+            );
+            if !is_expanded {
+                continue;
+            }
+
+            for entry in entries {
+                let icon = show_file_icons
+                    .then(|| FileIcons::get_icon(&entry.path, cx))
+                    .flatten();
+                list = list.child(
+                    ListItem::new(("virtual-file", file_ix))
+                        .indent_level(1)
+                        .indent_step_size(px(indent_size))
+                        .child(render_icon(icon))
+                        .child(
+                            h_flex().h_6().ml_1().child(
+                                Label::new(entry.path.to_string_lossy().to_string())
+                                    .single_line()
+                                    .color(Color::Muted),
+                            ),
+                        )
+                        .on_click(cx.listener(move |this, _, cx| {
+                            this.open_virtual_entry(&entry, cx);
+                        })),
+                );
+                file_ix += 1;
+            }
+        }
+        Some(list)
+    }
+
+    /// Opens a virtual file in an editor, or activates the editor it's already open in.
+    fn open_virtual_entry(&mut self, entry: &VirtualEntry, cx: &mut ViewContext<Self>) {
+        self.workspace
+            .update(cx, |workspace, cx| {
+                let existing = workspace.items_of_type::<Editor>(cx).find(|editor| {
+                    editor.read(cx).buffer().read(cx).as_singleton().as_ref() == Some(&entry.buffer)
+                });
+                if let Some(editor) = existing {
+                    workspace.activate_item(&editor, cx);
+                } else {
+                    let pane = workspace.active_pane().clone();
+                    workspace.open_project_item::<Editor>(pane, entry.buffer.clone(), cx);
+                }
+            })
+            .ok();
+    }
+
     fn dispatch_context(&self, cx: &ViewContext<Self>) -> KeyContext {
         let mut dispatch_context = KeyContext::new_with_defaults();
         dispatch_context.add("ProjectPanel");
@@ -1800,7 +1993,7 @@ impl Render for ProjectPanel {
         let project = self.project.read(cx);
 
         if has_worktree {
-            div()
+            v_flex()
                 .id("project-panel")
                 .size_full()
                 .relative()
@@ -1868,6 +2061,7 @@ impl Render for ProjectPanel {
                     .size_full()
                     .track_scroll(self.scroll_handle.clone()),
                 )
+                .children(self.render_virtual_roots(cx))
                 .children(self.context_menu.as_ref().map(|(menu, position, _)| {
                     deferred(
                         anchored()
@@ -1898,6 +2092,32 @@ impl Render for ProjectPanel {
     }
 }
 
+fn image_tooltip(state: Option<ImageMetadataState>, cx: &mut WindowContext) -> AnyView {
+    match state {
+        Some(ImageMetadataState::Loaded(metadata)) => {
+            let warnings = metadata.warnings(ImageViewerSettings::get_global(cx));
+            if warnings.is_empty() {
+                Tooltip::text(metadata.description(), cx)
+            } else {
+                Tooltip::with_meta(metadata.description(), None, warnings.join("\n"), cx)
+            }
+        }
+        Some(ImageMetadataState::Failed) => Tooltip::text("Couldn't read the image", cx),
+        Some(ImageMetadataState::Loading) | None => Tooltip::text("Reading the image…", cx),
+    }
+}
+
+fn render_icon(icon: Option<Arc<str>>) -> Div {
+    if let Some(icon) = icon {
+        h_flex().child(Icon::from_path(icon.to_string()).color(Color::Muted))
+    } else {
+        h_flex()
+            .size(IconSize::default().rems())
+            .invisible()
+            .flex_none()
+    }
+}
+
 impl Render for DraggedProjectEntryView {
     fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
         let settings = ProjectPanelSettings::get_global(cx);
@@ -3887,6 +4107,87 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_open_virtual_entry(cx: &mut gpui::TestAppContext) {
+        init_test_with_editor(cx);
+
+        let fs = FakeFs::new(cx.executor().clone());
+        fs.insert_tree("/src", json!({ "a.rs": "" })).await;
+
+        let project = Project::test(fs.clone(), ["/src".as_ref()], cx).await;
+        let workspace = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
+        let cx = &mut VisualTestContext::from_window(*workspace, cx);
+        let panel = workspace
+            .update(cx, |workspace, cx| ProjectPanel::new(workspace, cx))
+            .unwrap();
+
+        let buffer = project.update(cx, |project, cx| {
+            project.add_virtual_file("HEAD", Path::new("a.rs"), "// a".into(), cx);
+            project.virtual_entries()[0].buffer.clone()
+        });
+        for _ in 0..2 {
+            panel.update(cx, |panel, cx| {
+                let entry = panel.project.read(cx).virtual_entries()[0].clone();
+                panel.open_virtual_entry(&entry, cx);
+            });
+            cx.executor().run_until_parked();
+        }
+
+        // The virtual file is opened read-only, in a single editor.
+        workspace
+            .update(cx, |workspace, cx| {
+                let editors = workspace.items_of_type::<Editor>(cx).collect::<Vec<_>>();
+                assert_eq!(editors.len(), 1);
+                let editor = editors[0].read(cx);
+                assert!(editor.read_only(cx));
+                assert_eq!(
+                    editor.buffer().read(cx).as_singleton().as_ref(),
+                    Some(&buffer)
+                );
+            })
+            .unwrap();
+    }
+
+    #[gpui::test]
+    async fn test_image_metadata_cache(cx: &mut gpui::TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor().clone());
+        fs.insert_tree("/src", json!({ "logo.png": "not an image" }))
+            .await;
+
+        let project = Project::test(fs.clone(), ["/src".as_ref()], cx).await;
+        let workspace = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
+        let cx = &mut VisualTestContext::from_window(*workspace, cx);
+        let panel = workspace
+            .update(cx, |workspace, cx| ProjectPanel::new(workspace, cx))
+            .unwrap();
+        let entry_id = find_project_entry(&panel, "src/logo.png", cx).unwrap();
+
+        let load = |mtime, cx: &mut VisualTestContext| {
+            panel.update(cx, |panel, cx| {
+                panel.load_image_metadata(entry_id, PathBuf::from("/src/logo.png"), mtime, cx);
+                panel.image_metadata[&entry_id].state
+            })
+        };
+        let state = |cx: &mut VisualTestContext| {
+            panel.update(cx, |panel, _| panel.image_metadata[&entry_id].state)
+        };
+
+        // The image is read in the background.
+        let mtime = Some(SystemTime::UNIX_EPOCH);
+        assert_eq!(load(mtime, cx), ImageMetadataState::Loading);
+        cx.executor().run_until_parked();
+        assert_eq!(state(cx), ImageMetadataState::Failed);
+
+        // It isn't read again until it changes.
+        assert_eq!(load(mtime, cx), ImageMetadataState::Failed);
+        let mtime = Some(SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1));
+        assert_eq!(load(mtime, cx), ImageMetadataState::Loading);
+        cx.executor().run_until_parked();
+        assert_eq!(state(cx), ImageMetadataState::Failed);
+    }
+
     fn toggle_expand_dir(
         panel: &View<ProjectPanel>,
         path: impl AsRef<Path>,
@@ -1055,6 +1055,8 @@ pub struct TextSummary {
     pub len: usize,
     /// Length in UTF-16 code units
     pub len_utf16: OffsetUtf16,
+    /// Length in `char`s
+    pub chars: usize,
     /// A point representing the number of lines and the length of the last line
     pub lines: Point,
     /// How many `char`s are in the first line
@@ -1081,6 +1083,7 @@ impl TextSummary {
 impl<'a> From<&'a str> for TextSummary {
     fn from(text: &'a str) -> Self {
         let mut len_utf16 = OffsetUtf16(0);
+        let mut chars = 0;
         let mut lines = Point::new(0, 0);
         let mut first_line_chars = 0;
         let mut last_line_chars = 0;
@@ -1089,6 +1092,7 @@ impl<'a> From<&'a str> for TextSummary {
         let mut longest_row_chars = 0;
         for c in text.chars() {
             len_utf16.0 += c.len_utf16();
+            chars += 1;
 
             if c == '\n' {
                 lines += Point::new(1, 0);
@@ -1113,6 +1117,7 @@ impl<'a> From<&'a str> for TextSummary {
         TextSummary {
             len: text.len(),
             len_utf16,
+            chars,
             lines,
             first_line_chars,
             last_line_chars,
@@ -1166,6 +1171,7 @@ impl<'a> std::ops::AddAssign<&'a Self> for TextSummary {
 
         self.len += other.len;
         self.len_utf16 += other.len_utf16;
+        self.chars += other.chars;
         self.lines += other.lines;
     }
 }
@@ -267,6 +267,7 @@ messages!(
     (RespondToContactRequest, Foreground),
     (RoomUpdated, Foreground),
     (SaveBuffer, Foreground),
+    (SetChannelDefaultRole, Foreground),
     (SetChannelMemberRole, Foreground),
     (SetChannelVisibility, Foreground),
     (SearchProject, Background),
@@ -410,6 +411,7 @@ request_messages!(
     (SaveBuffer, BufferSaved),
     (SearchProject, SearchProjectResponse),
     (SendChannelMessage, SendChannelMessageResponse),
+    (SetChannelDefaultRole, Ack),
     (SetChannelMemberRole, Ack),
     (SetChannelVisibility, Ack),
     (ShareProject, ShareProjectResponse),
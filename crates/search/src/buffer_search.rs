@@ -9,7 +9,7 @@ use any_vec::AnyVec;
 use collections::HashMap;
 use editor::{
     actions::{Tab, TabPrev},
-    DisplayPoint, Editor, EditorElement, EditorStyle,
+    DisplayPoint, Editor, EditorElement, EditorSettings, EditorStyle,
 };
 use futures::channel::oneshot;
 use gpui::{
@@ -24,7 +24,7 @@ use project::{
 };
 use serde::Deserialize;
 use settings::Settings;
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 use theme::ThemeSettings;
 
 use ui::{h_flex, prelude::*, IconButton, IconName, Tooltip, BASE_REM_SIZE_IN_PX};
@@ -41,6 +41,8 @@ use registrar::{ForDeployed, ForDismissed, SearchActionsRegistrar, WithResults};
 const MIN_INPUT_WIDTH_REMS: f32 = 10.;
 const MAX_INPUT_WIDTH_REMS: f32 = 30.;
 const MAX_BUFFER_SEARCH_HISTORY_SIZE: usize = 50;
+/// How long the match count is highlighted after moving to the next match wraps around.
+const WRAP_FLASH_DURATION: Duration = Duration::from_millis(400);
 
 const fn true_value() -> bool {
     true
@@ -97,6 +99,8 @@ pub struct BufferSearchBar {
     scroll_handle: ScrollHandle,
     editor_scroll_handle: ScrollHandle,
     editor_needed_width: Pixels,
+    /// Highlights the match count until it completes, after the active match wrapped around.
+    wrap_flash: Option<Task<()>>,
 }
 
 impl BufferSearchBar {
@@ -178,13 +182,13 @@ impl Render for BufferSearchBar {
                     .map(AnyVec::len)
                     .unwrap_or(0);
                 if let Some(match_ix) = self.active_match_index {
-                    Some(format!("{}/{}", match_ix + 1, matches_count))
+                    Some(format!("{} of {}", match_ix + 1, matches_count))
                 } else {
                     text_color = Color::Error; // No matches found
                     None
                 }
             })
-            .unwrap_or_else(|| "0/0".to_string());
+            .unwrap_or_else(|| "0 of 0".to_string());
         let should_show_replace_input = self.replace_enabled && supported_options.replacement;
         let in_replace = self.replacement_editor.focus_handle(cx).is_focused(cx);
 
@@ -280,7 +284,9 @@ impl Render for BufferSearchBar {
                     ))
                     .when(!narrow_mode, |this| {
                         this.child(h_flex().min_w(rems_from_px(40.)).child(
-                            Label::new(match_text).color(if self.active_match_index.is_some() {
+                            Label::new(match_text).color(if self.wrap_flash.is_some() {
+                                Color::Accent
+                            } else if self.active_match_index.is_some() {
                                 Color::Default
                             } else {
                                 Color::Disabled
@@ -504,6 +510,7 @@ impl BufferSearchBar {
             scroll_handle: ScrollHandle::new(),
             editor_scroll_handle: ScrollHandle::new(),
             editor_needed_width: px(0.),
+            wrap_flash: None,
         }
     }
 
@@ -700,6 +707,10 @@ impl BufferSearchBar {
         }
     }
 
+    pub fn search_options(&self) -> SearchOptions {
+        self.search_options
+    }
+
     pub fn set_search_options(
         &mut self,
         search_options: SearchOptions,
@@ -740,6 +751,16 @@ impl BufferSearchBar {
                 {
                     let new_match_index = searchable_item
                         .match_index_for_direction(matches, index, direction, count, cx);
+                    let wrapped = match direction {
+                        Direction::Next => new_match_index < index,
+                        Direction::Prev => new_match_index > index,
+                    };
+                    if wrapped {
+                        self.flash_wrap(cx);
+                        if !EditorSettings::get_global(cx).search_wrap {
+                            return;
+                        }
+                    }
 
                     searchable_item.update_matches(matches, cx);
                     searchable_item.activate_match(new_match_index, matches, cx);
@@ -748,6 +769,20 @@ impl BufferSearchBar {
         }
     }
 
+    /// Briefly highlights the match count, to show that moving to the next match went past the
+    /// end of the buffer, or would have if the `search_wrap` setting allowed it.
+    fn flash_wrap(&mut self, cx: &mut ViewContext<Self>) {
+        self.wrap_flash = Some(cx.spawn(|this, mut cx| async move {
+            cx.background_executor().timer(WRAP_FLASH_DURATION).await;
+            this.update(&mut cx, |this, cx| {
+                this.wrap_flash = None;
+                cx.notify();
+            })
+            .ok();
+        }));
+        cx.notify();
+    }
+
     pub fn select_last_match(&mut self, cx: &mut ViewContext<Self>) {
         if let Some(searchable_item) = self.active_searchable_item.as_ref() {
             if let Some(matches) = self
@@ -1142,6 +1177,78 @@ mod tests {
         (editor, search_bar, cx)
     }
 
+    #[gpui::test]
+    async fn test_search_wrap(cx: &mut TestAppContext) {
+        let (editor, search_bar, cx) = init_test(cx);
+        search_bar
+            .update(cx, |search_bar, cx| search_bar.search("us", None, cx))
+            .await
+            .unwrap();
+        editor.update(cx, |editor, cx| {
+            editor.change_selections(None, cx, |s| {
+                s.select_display_ranges([
+                    DisplayPoint::new(DisplayRow(0), 0)..DisplayPoint::new(DisplayRow(0), 0)
+                ])
+            });
+        });
+        let select_next_match = |cx: &mut VisualTestContext| {
+            search_bar.update(cx, |search_bar, cx| {
+                search_bar.select_next_match(&SelectNextMatch, cx);
+            });
+            let selections = editor.update(cx, |editor, cx| editor.selections.display_ranges(cx));
+            let flashed = search_bar.update(cx, |search_bar, _| search_bar.wrap_flash.is_some());
+            (selections, flashed)
+        };
+        let first_match =
+            DisplayPoint::new(DisplayRow(2), 17)..DisplayPoint::new(DisplayRow(2), 19);
+        let second_match =
+            DisplayPoint::new(DisplayRow(2), 43)..DisplayPoint::new(DisplayRow(2), 45);
+
+        assert_eq!(select_next_match(cx), (vec![first_match.clone()], false));
+        assert_eq!(select_next_match(cx), (vec![second_match.clone()], false));
+        assert_eq!(select_next_match(cx), (vec![first_match.clone()], true));
+        cx.executor().advance_clock(WRAP_FLASH_DURATION);
+
+        cx.update(|cx| {
+            cx.update_global(|store: &mut settings::SettingsStore, cx| {
+                store.update_user_settings::<EditorSettings>(cx, |settings| {
+                    settings.search_wrap = Some(false);
+                });
+            });
+        });
+        cx.run_until_parked();
+        assert_eq!(select_next_match(cx), (vec![second_match.clone()], false));
+        assert_eq!(select_next_match(cx), (vec![second_match], true));
+    }
+
+    #[gpui::test]
+    async fn test_matches_follow_edits(cx: &mut TestAppContext) {
+        let (editor, search_bar, cx) = init_test(cx);
+        search_bar
+            .update(cx, |search_bar, cx| search_bar.search("us", None, cx))
+            .await
+            .unwrap();
+        let match_count = |cx: &mut VisualTestContext| {
+            cx.run_until_parked();
+            editor.update(cx, |editor, cx| {
+                editor.all_text_background_highlights(cx).len()
+            })
+        };
+        assert_eq!(match_count(cx), 2);
+
+        editor.update(cx, |editor, cx| editor.edit([(0..0, "us ")], cx));
+        assert_eq!(match_count(cx), 3);
+
+        // Splitting a match removes it, and joining it back together finds it again.
+        editor.update(cx, |editor, cx| editor.edit([(1..1, "x")], cx));
+        assert_eq!(match_count(cx), 2);
+        editor.update(cx, |editor, cx| editor.edit([(1..2, "")], cx));
+        assert_eq!(match_count(cx), 3);
+
+        editor.update(cx, |editor, cx| editor.undo(&editor::actions::Undo, cx));
+        assert_eq!(match_count(cx), 2);
+    }
+
     #[gpui::test]
     async fn test_search_simple(cx: &mut TestAppContext) {
         let (editor, search_bar, cx) = init_test(cx);
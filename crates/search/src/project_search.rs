@@ -1,7 +1,7 @@
 use crate::{
     FocusSearch, NextHistoryQuery, PreviousHistoryQuery, ReplaceAll, ReplaceNext, SearchOptions,
-    SelectNextMatch, SelectPrevMatch, ToggleCaseSensitive, ToggleIncludeIgnored, ToggleRegex,
-    ToggleReplace, ToggleWholeWord,
+    SelectNextMatch, SelectPrevMatch, ToggleCaseSensitive, ToggleIncludeIgnored,
+    ToggleIncludeLargeFiles, ToggleRegex, ToggleReplace, ToggleWholeWord,
 };
 use anyhow::Context as _;
 use collections::{HashMap, HashSet};
@@ -124,6 +124,8 @@ struct ProjectSearch {
     search_id: usize,
     no_results: Option<bool>,
     limit_reached: bool,
+    /// How many files were too large or looked binary to be searched.
+    skipped_files: usize,
     search_history_cursor: SearchHistoryCursor,
 }
 
@@ -178,6 +180,7 @@ impl ProjectSearch {
             search_id: 0,
             no_results: None,
             limit_reached: false,
+            skipped_files: 0,
             search_history_cursor: Default::default(),
         }
     }
@@ -195,6 +198,7 @@ impl ProjectSearch {
             search_id: self.search_id,
             no_results: self.no_results,
             limit_reached: self.limit_reached,
+            skipped_files: self.skipped_files,
             search_history_cursor: self.search_history_cursor.clone(),
         })
     }
@@ -218,6 +222,7 @@ impl ProjectSearch {
                 this.excerpts.update(cx, |this, cx| this.clear(cx));
                 this.no_results = Some(true);
                 this.limit_reached = false;
+                this.skipped_files = 0;
             })
             .ok()?;
 
@@ -245,6 +250,13 @@ impl ProjectSearch {
                         }
                         this.update(&mut cx, |_, cx| cx.notify()).ok()?;
                     }
+                    project::SearchResult::SkippedFiles(count) => {
+                        this.update(&mut cx, |this, cx| {
+                            this.skipped_files += count;
+                            cx.notify();
+                        })
+                        .ok()?;
+                    }
                     project::SearchResult::LimitReached => {
                         limit_reached = true;
                     }
@@ -898,6 +910,9 @@ impl ProjectSearchView {
                 }
             };
 
+        let include_large_files = self
+            .search_options
+            .contains(SearchOptions::INCLUDE_LARGE_FILES);
         let query = if self.search_options.contains(SearchOptions::REGEX) {
             match SearchQuery::regex(
                 text,
@@ -906,7 +921,9 @@ impl ProjectSearchView {
                 self.search_options.contains(SearchOptions::INCLUDE_IGNORED),
                 included_files,
                 excluded_files,
-            ) {
+            )
+            .map(|query| query.with_large_files(include_large_files))
+            {
                 Ok(query) => {
                     let should_unmark_error = self.panels_with_errors.remove(&InputPanel::Query);
                     if should_unmark_error {
@@ -932,7 +949,9 @@ impl ProjectSearchView {
                 self.search_options.contains(SearchOptions::INCLUDE_IGNORED),
                 included_files,
                 excluded_files,
-            ) {
+            )
+            .map(|query| query.with_large_files(include_large_files))
+            {
                 Ok(query) => {
                     let should_unmark_error = self.panels_with_errors.remove(&InputPanel::Query);
                     if should_unmark_error {
@@ -1421,6 +1440,7 @@ impl Render for ProjectSearchBar {
             .unwrap_or_else(|| "0/0".to_string());
 
         let limit_reached = search.model.read(cx).limit_reached;
+        let skipped_files = search.model.read(cx).skipped_files;
 
         let matches_column = h_flex()
             .child(
@@ -1466,6 +1486,14 @@ impl Render for ProjectSearchBar {
                         .child(Label::new("Search limit reached").color(Color::Warning))
                         .ml_2(),
                 )
+            })
+            .when(skipped_files > 0, |this| {
+                let label = if skipped_files == 1 {
+                    "1 large or binary file skipped".to_string()
+                } else {
+                    format!("{skipped_files} large or binary files skipped")
+                };
+                this.child(div().child(Label::new(label).color(Color::Muted)).ml_2())
             });
 
         let search_line = h_flex()
@@ -1541,6 +1569,19 @@ impl Render for ProjectSearchBar {
                                     this.toggle_search_option(SearchOptions::INCLUDE_IGNORED, cx);
                                 }),
                             ),
+                        )
+                        .child(
+                            SearchOptions::INCLUDE_LARGE_FILES.as_button(
+                                search
+                                    .search_options
+                                    .contains(SearchOptions::INCLUDE_LARGE_FILES),
+                                cx.listener(|this, _, cx| {
+                                    this.toggle_search_option(
+                                        SearchOptions::INCLUDE_LARGE_FILES,
+                                        cx,
+                                    );
+                                }),
+                            ),
                         ),
                 )
                 .child(
@@ -1600,6 +1641,11 @@ impl Render for ProjectSearchBar {
                 this.on_action(cx.listener(|this, _: &ToggleIncludeIgnored, cx| {
                     this.toggle_search_option(SearchOptions::INCLUDE_IGNORED, cx);
                 }))
+                .on_action(cx.listener(
+                    |this, _: &ToggleIncludeLargeFiles, cx| {
+                        this.toggle_search_option(SearchOptions::INCLUDE_LARGE_FILES, cx);
+                    },
+                ))
             })
             .on_action(cx.listener(Self::select_next_match))
             .on_action(cx.listener(Self::select_prev_match))
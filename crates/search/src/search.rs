@@ -23,6 +23,7 @@ actions!(
         ToggleWholeWord,
         ToggleCaseSensitive,
         ToggleIncludeIgnored,
+        ToggleIncludeLargeFiles,
         ToggleRegex,
         ToggleReplace,
         SelectNextMatch,
@@ -43,6 +44,7 @@ bitflags! {
         const CASE_SENSITIVE = 0b010;
         const INCLUDE_IGNORED = 0b100;
         const REGEX = 0b1000;
+        const INCLUDE_LARGE_FILES = 0b10000;
     }
 }
 
@@ -53,6 +55,7 @@ impl SearchOptions {
             SearchOptions::CASE_SENSITIVE => "match case",
             SearchOptions::INCLUDE_IGNORED => "include Ignored",
             SearchOptions::REGEX => "regular expression",
+            SearchOptions::INCLUDE_LARGE_FILES => "include large and binary files",
             _ => panic!("{:?} is not a named SearchOption", self),
         }
     }
@@ -63,6 +66,7 @@ impl SearchOptions {
             SearchOptions::CASE_SENSITIVE => ui::IconName::CaseSensitive,
             SearchOptions::INCLUDE_IGNORED => ui::IconName::FileGit,
             SearchOptions::REGEX => ui::IconName::Regex,
+            SearchOptions::INCLUDE_LARGE_FILES => ui::IconName::FileGeneric,
             _ => panic!("{:?} is not a named SearchOption", self),
         }
     }
@@ -73,6 +77,7 @@ impl SearchOptions {
             SearchOptions::CASE_SENSITIVE => Box::new(ToggleCaseSensitive),
             SearchOptions::INCLUDE_IGNORED => Box::new(ToggleIncludeIgnored),
             SearchOptions::REGEX => Box::new(ToggleRegex),
+            SearchOptions::INCLUDE_LARGE_FILES => Box::new(ToggleIncludeLargeFiles),
             _ => panic!("{:?} is not a named SearchOption", self),
         }
     }
@@ -87,6 +92,10 @@ impl SearchOptions {
         options.set(SearchOptions::CASE_SENSITIVE, query.case_sensitive());
         options.set(SearchOptions::INCLUDE_IGNORED, query.include_ignored());
         options.set(SearchOptions::REGEX, query.is_regex());
+        options.set(
+            SearchOptions::INCLUDE_LARGE_FILES,
+            query.include_large_files(),
+        );
         options
     }
 
@@ -30,6 +30,7 @@ use std::{
     time::{Duration, SystemTime},
 };
 use util::ResultExt;
+use workspace::ActivityTracker;
 use worktree::LocalSnapshot;
 
 pub use project_index_debug_view::ProjectIndexDebugView;
@@ -547,10 +548,13 @@ impl WorktreeIndex {
         updated_entries: channel::Receiver<UpdatedEntriesSet>,
         mut cx: AsyncAppContext,
     ) -> Result<()> {
+        // Indexing waits while the app is idle or in the background, to save battery.
+        cx.update(ActivityTracker::active)?.await;
         let index = this.update(&mut cx, |this, cx| this.index_entries_changed_on_disk(cx))?;
         index.await.log_err();
 
         while let Ok(updated_entries) = updated_entries.recv().await {
+            cx.update(ActivityTracker::active)?.await;
             let index = this.update(&mut cx, |this, cx| {
                 this.index_updated_entries(updated_entries, cx)
             })?;
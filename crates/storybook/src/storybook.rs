@@ -84,18 +84,20 @@ fn main() {
 
         let size = size(px(1500.), px(780.));
         let bounds = Bounds::centered(None, size, cx);
-        let _window = cx.open_window(
-            WindowOptions {
-                window_bounds: Some(WindowBounds::Windowed(bounds)),
-                ..Default::default()
-            },
-            move |cx| {
-                let ui_font_size = ThemeSettings::get_global(cx).ui_font_size;
-                cx.set_rem_size(ui_font_size);
-
-                cx.new_view(|cx| StoryWrapper::new(selector.story(cx)))
-            },
-        );
+        let _window = cx
+            .open_window(
+                WindowOptions {
+                    window_bounds: Some(WindowBounds::Windowed(bounds)),
+                    ..Default::default()
+                },
+                move |cx| {
+                    let ui_font_size = ThemeSettings::get_global(cx).ui_font_size;
+                    cx.set_rem_size(ui_font_size);
+
+                    cx.new_view(|cx| StoryWrapper::new(selector.story(cx)))
+                },
+            )
+            .unwrap();
 
         cx.activate(true);
     });
@@ -29,16 +29,20 @@ pub fn init(client: Arc<Client>, cx: &mut AppContext) {
     let supermaven = cx.new_model(|_| Supermaven::Starting);
     Supermaven::set_global(supermaven.clone(), cx);
 
-    let mut provider = all_language_settings(None, cx).inline_completions.provider;
-    if provider == language::language_settings::InlineCompletionProvider::Supermaven {
+    let mut enabled = all_language_settings(None, cx)
+        .inline_completions
+        .is_provider_enabled("supermaven");
+    if enabled {
         supermaven.update(cx, |supermaven, cx| supermaven.start(client.clone(), cx));
     }
 
     cx.observe_global::<SettingsStore>(move |cx| {
-        let new_provider = all_language_settings(None, cx).inline_completions.provider;
-        if new_provider != provider {
-            provider = new_provider;
-            if provider == language::language_settings::InlineCompletionProvider::Supermaven {
+        let new_enabled = all_language_settings(None, cx)
+            .inline_completions
+            .is_provider_enabled("supermaven");
+        if new_enabled != enabled {
+            enabled = new_enabled;
+            if enabled {
                 supermaven.update(cx, |supermaven, cx| supermaven.start(client.clone(), cx));
             } else {
                 supermaven.update(cx, |supermaven, _cx| supermaven.stop());
@@ -112,6 +116,14 @@ impl Supermaven {
         if let Self::Spawned(agent) = self {
             let buffer_id = buffer.entity_id();
             let buffer = buffer.read(cx);
+            // The buffer's contents are never sent when inline completions are disabled for it.
+            let file = buffer.file();
+            if !all_language_settings(file, cx).inline_completions_enabled(
+                buffer.language(),
+                file.map(|file| file.path().as_ref()),
+            ) {
+                return None;
+            }
             let path = buffer
                 .file()
                 .and_then(|file| Some(file.as_local()?.abs_path(cx)))
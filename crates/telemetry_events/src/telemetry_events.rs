@@ -1,3 +1,4 @@
+use schemars::JsonSchema;
 use semantic_version::SemanticVersion;
 use serde::{Deserialize, Serialize};
 use std::{fmt::Display, sync::Arc, time::Duration};
@@ -66,6 +67,84 @@ pub enum Event {
     Action(ActionEvent),
 }
 
+impl Event {
+    pub fn category(&self) -> EventCategory {
+        match self {
+            Event::Editor(_) => EventCategory::Editor,
+            Event::Copilot(_) | Event::InlineCompletion(_) => EventCategory::InlineCompletion,
+            Event::Call(_) => EventCategory::Call,
+            Event::Assistant(_) => EventCategory::Assistant,
+            Event::Cpu(_) => EventCategory::Cpu,
+            Event::Memory(_) => EventCategory::Memory,
+            Event::App(_) => EventCategory::App,
+            Event::Setting(_) => EventCategory::Setting,
+            Event::Extension(_) => EventCategory::Extension,
+            Event::Edit(_) => EventCategory::Edit,
+            Event::Action(_) => EventCategory::Action,
+        }
+    }
+}
+
+/// The kinds of events that can be opted out of individually.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EventCategory {
+    /// Opening and saving files, with their extension.
+    Editor,
+    /// Accepting or discarding inline completions.
+    InlineCompletion,
+    /// Joining and leaving calls.
+    Call,
+    /// Using the assistant, with the model used.
+    Assistant,
+    /// Zed's CPU usage.
+    Cpu,
+    /// Zed's memory usage.
+    Memory,
+    /// Opening and closing Zed.
+    App,
+    /// Changing some settings, like the theme.
+    Setting,
+    /// Installing extensions.
+    Extension,
+    /// How long was spent editing.
+    Edit,
+    /// Running some actions, like opening the command palette.
+    Action,
+}
+
+impl EventCategory {
+    pub const ALL: [EventCategory; 11] = [
+        EventCategory::Editor,
+        EventCategory::InlineCompletion,
+        EventCategory::Call,
+        EventCategory::Assistant,
+        EventCategory::Cpu,
+        EventCategory::Memory,
+        EventCategory::App,
+        EventCategory::Setting,
+        EventCategory::Extension,
+        EventCategory::Edit,
+        EventCategory::Action,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            EventCategory::Editor => "editor",
+            EventCategory::InlineCompletion => "inline_completion",
+            EventCategory::Call => "call",
+            EventCategory::Assistant => "assistant",
+            EventCategory::Cpu => "cpu",
+            EventCategory::Memory => "memory",
+            EventCategory::App => "app",
+            EventCategory::Setting => "setting",
+            EventCategory::Extension => "extension",
+            EventCategory::Edit => "edit",
+            EventCategory::Action => "action",
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct EditorEvent {
     pub operation: String,
@@ -92,6 +92,8 @@ pub enum Event {
     SelectionsChanged,
     NewNavigationTarget(Option<MaybeNavigationTarget>),
     Open(MaybeNavigationTarget),
+    /// The task running in the terminal has exited, and its summary was printed.
+    TaskFinished,
 }
 
 #[derive(Clone, Debug)]
@@ -961,6 +963,14 @@ impl Terminal {
         }
     }
 
+    /// Returns all of the terminal's text, including its scrollback.
+    pub fn text(&self) -> String {
+        let term = self.term.lock();
+        let start = AlacPoint::new(term.topmost_line(), Column(0));
+        let end = AlacPoint::new(term.bottommost_line(), term.last_column());
+        term.bounds_to_string(start, end)
+    }
+
     pub fn select_all(&mut self) {
         let term = self.term.lock();
         let start = AlacPoint::new(term.topmost_line(), Column(0));
@@ -1467,6 +1477,7 @@ impl Terminal {
         // when Zed task finishes and no more output is made.
         // After the task summary is output once, no more text is appended to the terminal.
         unsafe { append_text_to_term(&mut self.term.lock(), &[&task_line, &command_line]) };
+        cx.emit(Event::TaskFinished);
     }
 }
 
@@ -3,11 +3,16 @@ use std::{ops::ControlFlow, path::PathBuf, sync::Arc};
 use crate::TerminalView;
 use collections::{HashMap, HashSet};
 use db::kvp::KEY_VALUE_STORE;
+use editor::{
+    actions::{RunAllAbove, RunAllBelow, RunCell},
+    cells::CellsToRun,
+    Editor,
+};
 use futures::future::join_all;
 use gpui::{
     actions, Action, AppContext, AsyncWindowContext, DismissEvent, Entity, EventEmitter,
-    ExternalPaths, FocusHandle, FocusableView, IntoElement, ParentElement, Pixels, Render, Styled,
-    Subscription, Task, View, ViewContext, VisualContext, WeakView, WindowContext,
+    ExternalPaths, FocusHandle, FocusableView, IntoElement, Model, ParentElement, Pixels, Render,
+    Styled, Subscription, Task, View, ViewContext, VisualContext, WeakView, WindowContext,
 };
 use itertools::Itertools;
 use project::{Fs, ProjectEntryId};
@@ -15,7 +20,10 @@ use search::{buffer_search::DivRegistrar, BufferSearchBar};
 use serde::{Deserialize, Serialize};
 use settings::Settings;
 use task::{RevealStrategy, SpawnInTerminal, TaskId};
-use terminal::terminal_settings::{Shell, TerminalDockPosition, TerminalSettings};
+use terminal::{
+    terminal_settings::{Shell, TerminalDockPosition, TerminalSettings},
+    Terminal,
+};
 use ui::{
     h_flex, ButtonCommon, Clickable, ContextMenu, FluentBuilder, IconButton, IconSize, Selectable,
     Tooltip,
@@ -29,7 +37,7 @@ use workspace::{
     DraggedTab, NewTerminal, Pane, ToggleZoom, Workspace,
 };
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 
 const TERMINAL_PANEL_KEY: &str = "TerminalPanel";
 
@@ -40,6 +48,15 @@ pub fn init(cx: &mut AppContext) {
         |workspace: &mut Workspace, _: &mut ViewContext<Workspace>| {
             workspace.register_action(TerminalPanel::new_terminal);
             workspace.register_action(TerminalPanel::open_terminal);
+            workspace.register_action(|workspace, _: &RunCell, cx| {
+                TerminalPanel::run_cells(workspace, CellsToRun::Current, cx);
+            });
+            workspace.register_action(|workspace, _: &RunAllAbove, cx| {
+                TerminalPanel::run_cells(workspace, CellsToRun::Above, cx);
+            });
+            workspace.register_action(|workspace, _: &RunAllBelow, cx| {
+                TerminalPanel::run_cells(workspace, CellsToRun::Below, cx);
+            });
             workspace.register_action(|workspace, _: &ToggleFocus, cx| {
                 workspace.toggle_panel_focus::<TerminalPanel>(cx);
             });
@@ -320,25 +337,43 @@ impl TerminalPanel {
         };
 
         terminal_panel.update(cx, |panel, cx| {
-            panel.add_terminal(
-                Some(action.working_directory.clone()),
-                None,
-                RevealStrategy::Always,
-                cx,
-            )
+            panel
+                .add_terminal(
+                    Some(action.working_directory.clone()),
+                    None,
+                    RevealStrategy::Always,
+                    cx,
+                )
+                .detach_and_log_err(cx)
         });
     }
 
-    fn spawn_task(&mut self, spawn_in_terminal: &SpawnInTerminal, cx: &mut ViewContext<Self>) {
+    /// Runs a shell command in a new terminal of the panel, and resolves to the terminal once
+    /// it's been created, so that its output can be read when the command finishes.
+    pub fn run_command(
+        &mut self,
+        spawn_in_terminal: &SpawnInTerminal,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<Model<Terminal>>> {
+        let Some(spawn_task) = Self::spawn_in_shell(spawn_in_terminal, cx) else {
+            return Task::ready(Err(anyhow!("no shell to run the command in")));
+        };
+        let reveal = spawn_task.reveal;
+        self.add_terminal(spawn_in_terminal.cwd.clone(), Some(spawn_task), reveal, cx)
+    }
+
+    /// Wraps the task's command in the shell from the settings, since tasks are always spawned
+    /// inside of a shell.
+    fn spawn_in_shell(
+        spawn_in_terminal: &SpawnInTerminal,
+        cx: &AppContext,
+    ) -> Option<SpawnInTerminal> {
         let mut spawn_task = spawn_in_terminal.clone();
-        // Set up shell args unconditionally, as tasks are always spawned inside of a shell.
-        let Some((shell, mut user_args)) = (match TerminalSettings::get_global(cx).shell.clone() {
+        let (shell, mut user_args) = match TerminalSettings::get_global(cx).shell.clone() {
             Shell::System => std::env::var("SHELL").ok().map(|shell| (shell, Vec::new())),
             Shell::Program(shell) => Some((shell, Vec::new())),
             Shell::WithArguments { program, args } => Some((program, args)),
-        }) else {
-            return;
-        };
+        }?;
 
         spawn_task.command_label = format!("{shell} -i -c `{}`", spawn_task.command_label);
         let task_command = std::mem::replace(&mut spawn_task.command, shell);
@@ -352,7 +387,13 @@ impl TerminalPanel {
             });
         user_args.extend(["-i".to_owned(), "-c".to_owned(), combined_command]);
         spawn_task.args = user_args;
-        let spawn_task = spawn_task;
+        Some(spawn_task)
+    }
+
+    fn spawn_task(&mut self, spawn_in_terminal: &SpawnInTerminal, cx: &mut ViewContext<Self>) {
+        let Some(spawn_task) = Self::spawn_in_shell(spawn_in_terminal, cx) else {
+            return;
+        };
 
         let reveal = spawn_task.reveal;
         let working_directory = spawn_in_terminal.cwd.clone();
@@ -435,7 +476,8 @@ impl TerminalPanel {
         cx: &mut ViewContext<Self>,
     ) {
         let reveal = spawn_task.reveal;
-        self.add_terminal(working_directory, Some(spawn_task), reveal, cx);
+        self.add_terminal(working_directory, Some(spawn_task), reveal, cx)
+            .detach_and_log_err(cx);
     }
 
     /// Create a new Terminal in the current working directory or the user's home directory
@@ -450,7 +492,44 @@ impl TerminalPanel {
 
         terminal_panel.update(cx, |this, cx| {
             this.add_terminal(None, None, RevealStrategy::Always, cx)
+                .detach_and_log_err(cx)
+        });
+    }
+
+    /// Runs cells of the active editor in the active terminal, by pasting their code into the
+    /// REPL that's expected to be running there, such as IPython.
+    fn run_cells(
+        workspace: &mut Workspace,
+        cells_to_run: CellsToRun,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let Some(editor) = workspace.active_item_as::<Editor>(cx) else {
+            return;
+        };
+        let Some(code) = editor.read(cx).cell_code(cells_to_run, cx) else {
+            return;
+        };
+        let Some(terminal_panel) = workspace.panel::<Self>(cx) else {
+            return;
+        };
+        let Some(terminal_view) = terminal_panel
+            .read(cx)
+            .pane
+            .read(cx)
+            .active_item()
+            .and_then(|item| item.downcast::<TerminalView>())
+        else {
+            workspace.show_error(&anyhow!("Open a terminal with a REPL to run cells in"), cx);
+            return;
+        };
+
+        terminal_view.update(cx, |terminal_view, cx| {
+            terminal_view.terminal().update(cx, |terminal, _| {
+                terminal.paste(&code);
+                terminal.input("\r".to_string());
+            });
         });
+        workspace.open_panel::<Self>(cx);
     }
 
     fn terminals_for_task(
@@ -486,13 +565,13 @@ impl TerminalPanel {
         spawn_task: Option<SpawnInTerminal>,
         reveal_strategy: RevealStrategy,
         cx: &mut ViewContext<Self>,
-    ) {
+    ) -> Task<Result<Model<Terminal>>> {
         let workspace = self.workspace.clone();
         self.pending_terminals_to_add += 1;
 
         cx.spawn(|terminal_panel, mut cx| async move {
             let pane = terminal_panel.update(&mut cx, |this, _| this.pane.clone())?;
-            workspace.update(&mut cx, |workspace, cx| {
+            let terminal = workspace.update(&mut cx, |workspace, cx| {
                 let working_directory = if let Some(working_directory) = working_directory {
                     Some(working_directory)
                 } else {
@@ -502,14 +581,15 @@ impl TerminalPanel {
                 };
 
                 let window = cx.window_handle();
-                if let Some(terminal) = workspace.project().update(cx, |project, cx| {
+                let terminal = workspace.project().update(cx, |project, cx| {
                     project
                         .create_terminal(working_directory, spawn_task, window, cx)
                         .log_err()
-                }) {
-                    let terminal = Box::new(cx.new_view(|cx| {
+                });
+                if let Some(terminal) = &terminal {
+                    let terminal_view = Box::new(cx.new_view(|cx| {
                         TerminalView::new(
-                            terminal,
+                            terminal.clone(),
                             workspace.weak_handle(),
                             workspace.database_id(),
                             cx,
@@ -517,20 +597,20 @@ impl TerminalPanel {
                     }));
                     pane.update(cx, |pane, cx| {
                         let focus = pane.has_focus(cx);
-                        pane.add_item(terminal, true, focus, None, cx);
+                        pane.add_item(terminal_view, true, focus, None, cx);
                     });
                 }
                 if reveal_strategy == RevealStrategy::Always {
                     workspace.focus_panel::<Self>(cx);
                 }
+                terminal
             })?;
             terminal_panel.update(&mut cx, |this, cx| {
                 this.pending_terminals_to_add = this.pending_terminals_to_add.saturating_sub(1);
                 this.serialize(cx)
             })?;
-            anyhow::Ok(())
+            terminal.ok_or_else(|| anyhow!("failed to create a terminal"))
         })
-        .detach_and_log_err(cx);
     }
 
     fn serialize(&mut self, cx: &mut ViewContext<Self>) {
@@ -738,7 +818,8 @@ impl Panel for TerminalPanel {
 
     fn set_active(&mut self, active: bool, cx: &mut ViewContext<Self>) {
         if active && self.has_no_terminals(cx) {
-            self.add_terminal(None, None, RevealStrategy::Never, cx);
+            self.add_terminal(None, None, RevealStrategy::Never, cx)
+                .detach_and_log_err(cx);
         }
     }
 
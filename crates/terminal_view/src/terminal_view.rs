@@ -305,6 +305,10 @@ impl TerminalView {
         &self.terminal
     }
 
+    pub fn workspace(&self) -> &WeakView<Workspace> {
+        &self.workspace
+    }
+
     fn next_blink_epoch(&mut self) -> usize {
         self.blink_epoch += 1;
         self.blink_epoch
@@ -589,6 +593,7 @@ fn subscribe_for_terminal_events(
             Event::BreadcrumbsChanged => cx.emit(ItemEvent::UpdateBreadcrumbs),
             Event::CloseTerminal => cx.emit(ItemEvent::CloseItem),
             Event::SelectionsChanged => cx.emit(SearchEvent::ActiveMatchChanged),
+            Event::TaskFinished => cx.emit(Event::TaskFinished),
         });
     vec![terminal_subscription, terminal_events_subscription]
 }
@@ -266,6 +266,7 @@ fn test_text_summary_for_range() {
         TextSummary {
             len: 2,
             len_utf16: OffsetUtf16(2),
+            chars: 2,
             lines: Point::new(1, 0),
             first_line_chars: 1,
             last_line_chars: 0,
@@ -279,6 +280,7 @@ fn test_text_summary_for_range() {
         TextSummary {
             len: 11,
             len_utf16: OffsetUtf16(11),
+            chars: 11,
             lines: Point::new(3, 0),
             first_line_chars: 1,
             last_line_chars: 0,
@@ -292,6 +294,7 @@ fn test_text_summary_for_range() {
         TextSummary {
             len: 20,
             len_utf16: OffsetUtf16(20),
+            chars: 20,
             lines: Point::new(4, 1),
             first_line_chars: 2,
             last_line_chars: 1,
@@ -305,6 +308,7 @@ fn test_text_summary_for_range() {
         TextSummary {
             len: 22,
             len_utf16: OffsetUtf16(22),
+            chars: 22,
             lines: Point::new(4, 3),
             first_line_chars: 2,
             last_line_chars: 3,
@@ -318,6 +322,7 @@ fn test_text_summary_for_range() {
         TextSummary {
             len: 15,
             len_utf16: OffsetUtf16(15),
+            chars: 15,
             lines: Point::new(2, 3),
             first_line_chars: 4,
             last_line_chars: 3,
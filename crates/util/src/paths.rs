@@ -60,6 +60,7 @@ lazy_static::lazy_static! {
     pub static ref SUPERMAVEN_DIR: PathBuf = SUPPORT_DIR.join("supermaven");
     pub static ref DEFAULT_PRETTIER_DIR: PathBuf = SUPPORT_DIR.join("prettier");
     pub static ref DB_DIR: PathBuf = SUPPORT_DIR.join("db");
+    pub static ref JOURNAL_DIR: PathBuf = SUPPORT_DIR.join("journal");
     pub static ref CRASHES_DIR: Option<PathBuf> = cfg!(target_os = "macos")
         .then_some(HOME.join("Library/Logs/DiagnosticReports"));
     pub static ref CRASHES_RETIRED_DIR: Option<PathBuf> = CRASHES_DIR
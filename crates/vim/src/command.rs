@@ -1,17 +1,29 @@
-use command_palette_hooks::CommandInterceptResult;
-use editor::actions::{SortLinesCaseInsensitive, SortLinesCaseSensitive};
-use gpui::{impl_actions, Action, AppContext, ViewContext};
+use std::{ops::Range, path::Path};
+
+use command_palette::CommandPalette;
+use command_palette_hooks::{CommandCompletion, CommandInterceptResult};
+use editor::{
+    actions::{SortLinesCaseInsensitive, SortLinesCaseSensitive},
+    Editor, EditorSettings,
+};
+use gpui::{actions, impl_actions, Action, AppContext, View, ViewContext};
+use language::{language_settings::SoftWrap, Point, ToPoint as _};
+use multi_buffer::MultiBufferRow;
 use serde_derive::Deserialize;
+use settings::Settings;
 use workspace::{SaveIntent, Workspace};
 
 use crate::{
+    global::{is_global, GlobalCommand},
     motion::{EndOfDocument, Motion, StartOfDocument},
     normal::{
         move_cursor,
-        search::{range_regex, FindCommand, ReplaceCommand},
+        search::{FindCommand, ReplaceCommand},
         JoinLines,
     },
+    registers::ShowRegisters,
     state::Mode,
+    window::{MoveTab, ResizeIntent, ResizePane},
     Vim,
 };
 
@@ -20,31 +32,495 @@ pub struct GoToLine {
     pub line: u32,
 }
 
-impl_actions!(vim, [GoToLine]);
+/// A line given as one end of an ex command's range, such as `10`, `.`, `$` or `'a`, moved by
+/// the offset typed after it, as in `.+2`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub enum Position {
+    Line { row: u32, offset: i32 },
+    Mark { name: char, offset: i32 },
+    LastLine { offset: i32 },
+    CurrentLine { offset: i32 },
+}
+
+/// The lines an ex command applies to, typed before the command: `%` for every line, a single
+/// line, or the first and last lines separated by a comma.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct CommandRange {
+    start: Position,
+    end: Option<Position>,
+}
+
+/// Runs an ex command, such as `:d` or `:sort`, on the lines of a range, or moves to the last
+/// line of the range when there is no command.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct WithRange {
+    range: CommandRange,
+    command: String,
+}
+
+/// Opens a file, with `:e {path}`. Relative paths are in the project's first folder that
+/// contains them.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct EditFile {
+    pub path: String,
+}
+
+/// Switches to an open buffer, with `:b {name}`, where the name is all or part of its path.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SwitchToBuffer {
+    pub name: String,
+}
+
+/// Sets an option, with `:set {option}`, for the options in [`OPTIONS`].
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SetOption {
+    pub option: String,
+}
+
+actions!(vim, [VisualCommand]);
+impl_actions!(
+    vim,
+    [GoToLine, WithRange, EditFile, SwitchToBuffer, SetOption]
+);
+
+/// The commands completed by name, as they're written in full.
+const COMMANDS: &[&str] = &[
+    "buffer",
+    "close",
+    "cnext",
+    "cprevious",
+    "delete",
+    "edit",
+    "exit",
+    "join",
+    "new",
+    "only",
+    "qall",
+    "quit",
+    "registers",
+    "resize",
+    "set",
+    "sort",
+    "split",
+    "tabclose",
+    "tabedit",
+    "tabfirst",
+    "tablast",
+    "tabmove",
+    "tabnew",
+    "tabnext",
+    "tabonly",
+    "tabprevious",
+    "update",
+    "vertical",
+    "vnew",
+    "vsplit",
+    "wall",
+    "wq",
+    "wqall",
+    "write",
+    "xall",
+];
+
+/// The options that `:set` supports.
+const OPTIONS: &[&str] = &["nonumber", "nowrap", "number", "wrap"];
+
+/// The most completions that are offered for a command, as there can be many files.
+const MAX_COMPLETIONS: usize = 50;
 
 pub fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
     workspace.register_action(|_: &mut Workspace, action: &GoToLine, cx| {
         Vim::update(cx, |vim, cx| {
             vim.switch_mode(Mode::Normal, false, cx);
+            vim.push_active_editor_to_jump_list(None, cx);
             move_cursor(vim, Motion::StartOfDocument, Some(action.line as usize), cx);
         });
     });
+    workspace.register_action(|workspace: &mut Workspace, _: &VisualCommand, cx| {
+        CommandPalette::toggle_command_line(workspace, "'<,'>", cx);
+    });
+    workspace.register_action(with_range);
+    workspace.register_action(edit_file);
+    workspace.register_action(switch_to_buffer);
+    workspace.register_action(set_option);
+}
+
+fn edit_file(workspace: &mut Workspace, action: &EditFile, cx: &mut ViewContext<Workspace>) {
+    let path = Path::new(&action.path);
+    let abs_path = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        let project = workspace.project().read(cx);
+        let worktrees = project.visible_worktrees(cx).collect::<Vec<_>>();
+        let Some(worktree) = worktrees
+            .iter()
+            .find(|worktree| worktree.read(cx).entry_for_path(path).is_some())
+            .or(worktrees.first())
+        else {
+            return;
+        };
+        worktree.read(cx).abs_path().join(path)
+    };
+    workspace
+        .open_abs_path(abs_path, true, cx)
+        .detach_and_log_err(cx);
+}
+
+fn switch_to_buffer(
+    workspace: &mut Workspace,
+    action: &SwitchToBuffer,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let items = workspace
+        .items(cx)
+        .filter_map(|item| Some((buffer_name(item.project_path(cx)?.path.as_ref()), item)))
+        .collect::<Vec<_>>();
+    // As in vim, an exact name is preferred, and otherwise a partial name must be unique.
+    let item = match items.iter().find(|(name, _)| *name == action.name) {
+        Some((_, item)) => Some(item),
+        None => {
+            let mut matches = items
+                .iter()
+                .filter(|(name, _)| name.contains(action.name.as_str()));
+            matches
+                .next()
+                .filter(|_| matches.next().is_none())
+                .map(|(_, item)| item)
+        }
+    };
+    if let Some(item) = item.map(|item| item.boxed_clone()) {
+        workspace.activate_item(item.as_ref(), cx);
+    }
+}
+
+fn set_option(_: &mut Workspace, action: &SetOption, cx: &mut ViewContext<Workspace>) {
+    match action.option.as_str() {
+        "number" | "nonumber" => {
+            let mut editor_settings = EditorSettings::get_global(cx).clone();
+            editor_settings.gutter.line_numbers = action.option == "number";
+            EditorSettings::override_global(editor_settings, cx);
+        }
+        "wrap" | "nowrap" => Vim::update(cx, |vim, cx| {
+            vim.update_active_editor(cx, |_, editor, cx| {
+                let soft_wrap = if action.option == "wrap" {
+                    SoftWrap::EditorWidth
+                } else {
+                    SoftWrap::None
+                };
+                editor.set_soft_wrap_mode(soft_wrap, cx);
+            });
+        }),
+        _ => {}
+    }
+}
+
+/// The name of a buffer, as `:b` matches it and completes it.
+fn buffer_name(path: &Path) -> String {
+    path.to_string_lossy().into_owned()
+}
+
+/// The workspace of the editor vim is active in.
+fn active_workspace(cx: &AppContext) -> Option<View<Workspace>> {
+    let editor = cx.global::<Vim>().active_editor.clone()?.upgrade()?;
+    editor.read(cx).workspace()
+}
+
+/// Completes the name of a command, or its argument: a file for `:e`, an open buffer for `:b`
+/// or an option for `:set`.
+pub fn command_completions(query: &str, cx: &AppContext) -> Vec<CommandCompletion> {
+    let command = query.trim_start_matches(':');
+    let prefix = &query[..query.len() - command.len()];
+
+    let Some((name, argument)) = command.split_once(' ') else {
+        if command.is_empty() {
+            return Vec::new();
+        }
+        return COMMANDS
+            .iter()
+            .filter(|name| name.starts_with(command))
+            .map(|name| CommandCompletion {
+                label: format!(":{name}"),
+                query: format!("{prefix}{name}"),
+            })
+            .collect();
+    };
+
+    let argument = argument.trim_start();
+    let candidates = match name {
+        "e" | "ed" | "edi" | "edit" => file_completions(argument, cx),
+        "b" | "bu" | "buf" | "buff" | "buffe" | "buffer" => buffer_completions(argument, cx),
+        "se" | "set" => OPTIONS
+            .iter()
+            .filter(|option| option.starts_with(argument))
+            .map(|option| option.to_string())
+            .collect(),
+        _ => Vec::new(),
+    };
+    candidates
+        .into_iter()
+        .take(MAX_COMPLETIONS)
+        .map(|candidate| CommandCompletion {
+            query: format!("{prefix}{name} {candidate}"),
+            label: candidate,
+        })
+        .collect()
+}
+
+/// The files and folders in the project's folders whose paths start with the argument, one
+/// level at a time like vim. Folders end with a `/`, so that completing goes into them.
+fn file_completions(argument: &str, cx: &AppContext) -> Vec<String> {
+    let Some(workspace) = active_workspace(cx) else {
+        return Vec::new();
+    };
+    let (directory, file_prefix) = match argument.rsplit_once('/') {
+        Some((directory, file_prefix)) => (directory, file_prefix),
+        None => ("", argument),
+    };
+
+    let mut completions = Vec::new();
+    for worktree in workspace.read(cx).project().read(cx).visible_worktrees(cx) {
+        for entry in worktree.read(cx).child_entries(Path::new(directory)) {
+            let Some(file_name) = entry.path.file_name().and_then(|name| name.to_str()) else {
+                continue;
+            };
+            if !file_name.starts_with(file_prefix) {
+                continue;
+            }
+            let mut completion = entry.path.to_string_lossy().into_owned();
+            if entry.is_dir() {
+                completion.push('/');
+            }
+            completions.push(completion);
+        }
+    }
+    completions.sort();
+    completions.dedup();
+    completions
+}
+
+/// The names of the open buffers that contain the argument.
+fn buffer_completions(argument: &str, cx: &AppContext) -> Vec<String> {
+    let Some(workspace) = active_workspace(cx) else {
+        return Vec::new();
+    };
+    let mut completions = workspace
+        .read(cx)
+        .items(cx)
+        .filter_map(|item| Some(buffer_name(item.project_path(cx)?.path.as_ref())))
+        .filter(|name| name.contains(argument))
+        .collect::<Vec<_>>();
+    completions.sort();
+    completions.dedup();
+    completions
+}
+
+fn with_range(_: &mut Workspace, action: &WithRange, cx: &mut ViewContext<Workspace>) {
+    let command = match action.command.trim() {
+        "" => None,
+        command => match command_interceptor(command, cx) {
+            Some(result) => Some(result.action),
+            None => return,
+        },
+    };
+    Vim::update(cx, |vim, cx| {
+        let Some(range) = vim
+            .update_active_editor(cx, |vim, editor, cx| {
+                action.range.buffer_range(vim, editor, cx)
+            })
+            .flatten()
+        else {
+            return;
+        };
+        if command.is_none() {
+            vim.switch_mode(Mode::Normal, false, cx);
+            let line = range.end.row as usize + 1;
+            vim.push_active_editor_to_jump_list(None, cx);
+            move_cursor(vim, Motion::StartOfDocument, Some(line), cx);
+            return;
+        }
+        // Line-wise commands apply to the selected lines.
+        vim.switch_mode(Mode::VisualLine, true, cx);
+        vim.update_active_editor(cx, |_, editor, cx| {
+            editor.change_selections(None, cx, |s| s.select_ranges([range]))
+        });
+    });
+    if let Some(command) = command {
+        cx.dispatch_action(command);
+        cx.defer(|_, cx| {
+            Vim::update(cx, |vim, cx| {
+                if vim.state().mode.is_visual() {
+                    vim.switch_mode(Mode::Normal, false, cx)
+                }
+            })
+        });
+    }
+}
+
+impl Position {
+    /// The row of the active editor's buffer at this position, clipped to the buffer.
+    fn buffer_row(
+        &self,
+        vim: &Vim,
+        editor: &mut Editor,
+        cx: &mut ViewContext<Editor>,
+    ) -> Option<u32> {
+        let snapshot = editor.buffer().read(cx).snapshot(cx);
+        let (row, offset) = match self {
+            Position::Line { row, offset } => (row.saturating_sub(1), offset),
+            Position::Mark { name, offset } => {
+                let anchor = vim.state().marks.get(&name.to_string())?.first()?;
+                (anchor.to_point(&snapshot).row, offset)
+            }
+            Position::LastLine { offset } => (snapshot.max_point().row, offset),
+            Position::CurrentLine { offset } => {
+                let head = editor.selections.newest_anchor().head();
+                (head.to_point(&snapshot).row, offset)
+            }
+        };
+        let row = (row as i64 + *offset as i64).clamp(0, snapshot.max_point().row as i64);
+        Some(row as u32)
+    }
+}
+
+impl CommandRange {
+    /// The current line, which commands apply to when no range is given.
+    pub(crate) fn current_line() -> Self {
+        Self {
+            start: Position::CurrentLine { offset: 0 },
+            end: None,
+        }
+    }
+
+    /// Every line, as `%`, which `:g` applies to when no range is given.
+    pub(crate) fn all_lines() -> Self {
+        Self {
+            start: Position::Line { row: 1, offset: 0 },
+            end: Some(Position::LastLine { offset: 0 }),
+        }
+    }
+
+    /// The whole lines of the active editor's buffer that the range covers, or `None` if it
+    /// refers to a mark that isn't set.
+    pub(crate) fn buffer_range(
+        &self,
+        vim: &Vim,
+        editor: &mut Editor,
+        cx: &mut ViewContext<Editor>,
+    ) -> Option<Range<Point>> {
+        let start = self.start.buffer_row(vim, editor, cx)?;
+        let end = match &self.end {
+            Some(end) => end.buffer_row(vim, editor, cx)?,
+            None => start,
+        };
+        let (start, end) = if end < start {
+            (end, start)
+        } else {
+            (start, end)
+        };
+        let snapshot = editor.buffer().read(cx).snapshot(cx);
+        Some(Point::new(start, 0)..Point::new(end, snapshot.line_len(MultiBufferRow(end))))
+    }
+}
+
+/// Parses one end of a range, returning it and the rest of the query.
+fn parse_position(query: &str) -> Option<(Position, &str)> {
+    let digits = query
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(query.len());
+    let (mut position, mut rest) = if digits > 0 {
+        let row = query[..digits].parse().ok()?;
+        (Position::Line { row, offset: 0 }, &query[digits..])
+    } else if let Some(rest) = query.strip_prefix('.') {
+        (Position::CurrentLine { offset: 0 }, rest)
+    } else if let Some(rest) = query.strip_prefix('$') {
+        (Position::LastLine { offset: 0 }, rest)
+    } else if let Some(rest) = query.strip_prefix('\'') {
+        let name = rest.chars().next()?;
+        (Position::Mark { name, offset: 0 }, &rest[name.len_utf8()..])
+    } else if query.starts_with(['+', '-']) {
+        (Position::CurrentLine { offset: 0 }, query)
+    } else {
+        return None;
+    };
+
+    // Offsets like `+2` or `-` (which means `-1`) can follow the line, any number of times.
+    while let Some(sign) = rest.chars().next().filter(|c| matches!(c, '+' | '-')) {
+        rest = &rest[1..];
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let amount = if digits > 0 {
+            rest[..digits].parse::<i32>().ok()?
+        } else {
+            1
+        };
+        rest = &rest[digits..];
+        let delta = if sign == '+' { amount } else { -amount };
+        match &mut position {
+            Position::Line { offset, .. }
+            | Position::Mark { offset, .. }
+            | Position::LastLine { offset }
+            | Position::CurrentLine { offset } => *offset += delta,
+        }
+    }
+    Some((position, rest))
+}
+
+/// Parses the range at the start of an ex command, returning it and the rest of the command.
+pub(crate) fn parse_range(query: &str) -> (Option<CommandRange>, &str) {
+    if let Some(rest) = query.strip_prefix('%') {
+        return (Some(CommandRange::all_lines()), rest);
+    }
+    let Some((start, rest)) = parse_position(query) else {
+        return (None, query);
+    };
+    let Some(rest) = rest.strip_prefix(',') else {
+        return (Some(CommandRange { start, end: None }), rest);
+    };
+    // A missing end, as in `:3,`, is the current line.
+    let (end, rest) = parse_position(rest).unwrap_or((Position::CurrentLine { offset: 0 }, rest));
+    (
+        Some(CommandRange {
+            start,
+            end: Some(end),
+        }),
+        rest,
+    )
+}
+
+/// Whether an ex command is `:s`, followed by the delimiter of its pattern.
+pub(crate) fn is_substitute(command: &str) -> bool {
+    let mut chars = command.chars();
+    chars.next() == Some('s') && chars.next().map_or(false, is_delimiter)
+}
+
+/// Whether a character can delimit the pattern of a command like `:s` or `:g`.
+pub(crate) fn is_delimiter(c: char) -> bool {
+    !c.is_alphanumeric() && !c.is_whitespace() && !matches!(c, '"' | '|' | '\\')
+}
+
+/// Whether a command, as it's named in the command palette, applies to lines and so can be
+/// given a range.
+fn accepts_range(name: &str) -> bool {
+    matches!(name, ":join" | ":delete" | ":sort" | ":sort i")
 }
 
 pub fn command_interceptor(mut query: &str, cx: &AppContext) -> Option<CommandInterceptResult> {
     // Note: this is a very poor simulation of vim's command palette.
-    // In the future we should adjust it to handle parsing range syntax,
-    // and then calling the appropriate commands with/without ranges.
     //
     // We also need to support passing arguments to commands like :w
     // (ideally with filename autocompletion).
     //
-    // For now, you can only do a replace on the % range, and you can
-    // only use a specific line number range to "go to line"
+    // For now, ranges can only be given to :s and to the commands that
+    // modify lines, or on their own to go to a line.
     while query.starts_with(':') {
         query = &query[1..];
     }
 
+    if let Some(result) = intercept_with_argument(query) {
+        return Some(result);
+    }
+
     let (name, action) = match query {
         // save and quit
         "w" | "wr" | "wri" | "writ" | "write" => (
@@ -194,6 +670,16 @@ pub fn command_interceptor(mut query: &str, cx: &AppContext) -> Option<CommandIn
         "tabN" | "tabNe" | "tabNex" | "tabNext" => {
             ("tabNext", workspace::ActivatePrevItem.boxed_clone())
         }
+        "tabr" | "tabre" | "tabrew" | "tabrewi" | "tabrewin" | "tabrewind" => {
+            ("tabrewind", workspace::ActivateItem(0).boxed_clone())
+        }
+        "tabfir" | "tabfirs" | "tabfirst" => ("tabfirst", workspace::ActivateItem(0).boxed_clone()),
+        "tabl" | "tabla" | "tablas" | "tablast" => {
+            ("tablast", workspace::ActivateLastItem.boxed_clone())
+        }
+        "tabm" | "tabmo" | "tabmov" | "tabmove" => {
+            ("tabmove", MoveTab::To(usize::MAX).boxed_clone())
+        }
         "tabc" | "tabcl" | "tabclo" | "tabclos" | "tabclose" => (
             "tabclose",
             workspace::CloseActiveItem {
@@ -215,6 +701,13 @@ pub fn command_interceptor(mut query: &str, cx: &AppContext) -> Option<CommandIn
             }
             .boxed_clone(),
         ),
+        "clo" | "clos" | "close" => (
+            "close",
+            workspace::CloseAllItems {
+                save_intent: Some(SaveIntent::Close),
+            }
+            .boxed_clone(),
+        ),
         "on" | "onl" | "only" => (
             "only",
             workspace::CloseInactiveTabsAndPanes {
@@ -230,6 +723,12 @@ pub fn command_interceptor(mut query: &str, cx: &AppContext) -> Option<CommandIn
             .boxed_clone(),
         ),
 
+        // registers
+        "reg" | "regi" | "regis" | "regist" | "registe" | "register" | "registers" | "di"
+        | "dis" | "disp" | "displ" | "displa" | "display" => {
+            ("registers", ShowRegisters.boxed_clone())
+        }
+
         // quickfix / loclist (merged together for now)
         "cl" | "cli" | "clis" | "clist" => (
             "clist",
@@ -330,26 +829,45 @@ pub fn command_interceptor(mut query: &str, cx: &AppContext) -> Option<CommandIn
                     }
                     .boxed_clone(),
                 )
-            } else if query.starts_with('%') {
-                (
-                    query,
-                    ReplaceCommand {
-                        query: query.to_string(),
-                    }
-                    .boxed_clone(),
-                )
             } else if let Ok(line) = query.parse::<u32>() {
                 (query, GoToLine { line }.boxed_clone())
-            } else if range_regex().is_match(query) {
-                (
-                    query,
-                    ReplaceCommand {
-                        query: query.to_string(),
-                    }
-                    .boxed_clone(),
-                )
             } else {
-                return None;
+                let (range, command) = parse_range(query);
+                if is_global(query) {
+                    (
+                        query,
+                        GlobalCommand {
+                            query: query.to_string(),
+                        }
+                        .boxed_clone(),
+                    )
+                } else if is_substitute(command) {
+                    (
+                        query,
+                        ReplaceCommand {
+                            query: query.to_string(),
+                        }
+                        .boxed_clone(),
+                    )
+                } else if let Some(range) = range {
+                    let command = command.trim();
+                    if !command.is_empty()
+                        && !command_interceptor(command, cx)
+                            .map_or(false, |result| accepts_range(&result.string))
+                    {
+                        return None;
+                    }
+                    (
+                        query,
+                        WithRange {
+                            range,
+                            command: command.to_string(),
+                        }
+                        .boxed_clone(),
+                    )
+                } else {
+                    return None;
+                }
             }
         }
     };
@@ -364,6 +882,101 @@ pub fn command_interceptor(mut query: &str, cx: &AppContext) -> Option<CommandIn
     })
 }
 
+/// Intercepts the commands that take an argument, like `:e {path}`.
+fn intercept_with_argument(query: &str) -> Option<CommandInterceptResult> {
+    let (name, argument) = query.split_once(' ')?;
+    let argument = argument.trim();
+    if argument.is_empty() {
+        return None;
+    }
+    let (name, action) = match name {
+        "e" | "ed" | "edi" | "edit" => (
+            "edit",
+            EditFile {
+                path: argument.to_string(),
+            }
+            .boxed_clone(),
+        ),
+        "b" | "bu" | "buf" | "buff" | "buffe" | "buffer" => (
+            "buffer",
+            SwitchToBuffer {
+                name: argument.to_string(),
+            }
+            .boxed_clone(),
+        ),
+        "se" | "set" if OPTIONS.contains(&argument) => (
+            "set",
+            SetOption {
+                option: argument.to_string(),
+            }
+            .boxed_clone(),
+        ),
+        "tabn" | "tabne" | "tabnex" | "tabnext" => {
+            let index = argument.parse::<usize>().ok()?;
+            (
+                "tabnext",
+                workspace::ActivateItem(index.saturating_sub(1)).boxed_clone(),
+            )
+        }
+        "tabm" | "tabmo" | "tabmov" | "tabmove" => {
+            let action = if argument.starts_with(['+', '-']) {
+                MoveTab::By(argument.parse().ok()?)
+            } else {
+                MoveTab::To(argument.parse().ok()?)
+            };
+            ("tabmove", action.boxed_clone())
+        }
+        "res" | "resi" | "resiz" | "resize" => {
+            ("resize", resize_action(argument, ResizeIntent::Lengthen)?)
+        }
+        "vert" | "verti" | "vertic" | "vertica" | "vertical" => {
+            let (command, argument) = argument.split_once(' ')?;
+            if !matches!(command, "res" | "resi" | "resiz" | "resize") {
+                return None;
+            }
+            let argument = argument.trim();
+            let string = format!(":vertical resize {argument}");
+            return Some(CommandInterceptResult {
+                action: resize_action(argument, ResizeIntent::Widen)?,
+                positions: generate_positions(&string, query),
+                string,
+            });
+        }
+        _ => return None,
+    };
+
+    let string = format!(":{name} {argument}");
+    let positions = generate_positions(&string, query);
+    Some(CommandInterceptResult {
+        action,
+        string,
+        positions,
+    })
+}
+
+/// The action for `:resize` or `:vertical resize`, which grow the active pane by a number of lines
+/// or columns, such as `+5`, or shrink it, such as `-5`.
+fn resize_action(argument: &str, grow: ResizeIntent) -> Option<Box<dyn Action>> {
+    let shrink = match grow {
+        ResizeIntent::Widen => ResizeIntent::Narrow,
+        _ => ResizeIntent::Shorten,
+    };
+    let (intent, amount) = if let Some(amount) = argument.strip_prefix('+') {
+        (grow, amount)
+    } else if let Some(amount) = argument.strip_prefix('-') {
+        (shrink, amount)
+    } else {
+        return None;
+    };
+    Some(
+        ResizePane {
+            intent,
+            amount: Some(amount.parse().ok()?),
+        }
+        .boxed_clone(),
+    )
+}
+
 fn generate_positions(string: &str, query: &str) -> Vec<usize> {
     let mut positions = Vec::new();
     let mut chars = query.chars();
@@ -390,9 +1003,14 @@ fn generate_positions(string: &str, query: &str) -> Vec<usize> {
 mod test {
     use std::path::Path;
 
-    use crate::test::{NeovimBackedTestContext, VimTestContext};
+    use crate::{
+        state::Mode,
+        test::{NeovimBackedTestContext, VimTestContext},
+    };
+    use editor::EditorSettings;
     use gpui::TestAppContext;
     use indoc::indoc;
+    use settings::Settings;
 
     #[gpui::test]
     async fn test_command_basics(cx: &mut TestAppContext) {
@@ -452,6 +1070,219 @@ mod test {
             ˇcc"});
     }
 
+    #[gpui::test]
+    async fn test_command_ranges(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state(
+            indoc! {"
+            ˇa
+            b
+            c
+            d"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes(": 2 , 3 d enter");
+        cx.assert_state(
+            indoc! {"
+            a
+            ˇd"},
+            Mode::Normal,
+        );
+
+        cx.set_state(
+            indoc! {"
+            ˇa
+            b
+            c
+            d"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes(": . , $ - 1 enter");
+        cx.assert_state(
+            indoc! {"
+            a
+            b
+            ˇc
+            d"},
+            Mode::Normal,
+        );
+
+        cx.set_state(
+            indoc! {"
+            a a
+            ˇa a
+            a a
+            a a"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("shift-v j :");
+        cx.simulate_keystrokes("s / a / b enter");
+        cx.assert_state(
+            indoc! {"
+            a a
+            b a
+            ˇb a
+            a a"},
+            Mode::Normal,
+        );
+    }
+
+    #[gpui::test]
+    async fn test_command_replace_flags(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state(
+            indoc! {"
+            ˇa A a
+            A a A"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes(": % s / a / b / g enter");
+        cx.assert_state(
+            indoc! {"
+            b A b
+            ˇA b A"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes(": % s / a / c / g i enter");
+        cx.assert_state(
+            indoc! {"
+            b c b
+            ˇc b c"},
+            Mode::Normal,
+        );
+    }
+
+    #[gpui::test]
+    async fn test_command_replace_confirm(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state(
+            indoc! {"
+            ˇa a
+            a a
+            a a"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes(": % s / a / b / g c enter");
+        cx.assert_state(
+            indoc! {"
+            ˇa a
+            a a
+            a a"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("y n");
+        cx.assert_state(
+            indoc! {"
+            b a
+            ˇa a
+            a a"},
+            Mode::Normal,
+        );
+        // Other keys are ignored until the prompt is answered.
+        cx.simulate_keystrokes("x");
+        cx.simulate_keystrokes("a");
+        cx.assert_state(
+            indoc! {"
+            b a
+            b b
+            ˇb b"},
+            Mode::Normal,
+        );
+
+        // The replacements are undone together.
+        cx.simulate_keystrokes("u");
+        cx.assert_state(
+            indoc! {"
+            ˇa a
+            a a
+            a a"},
+            Mode::Normal,
+        );
+
+        cx.simulate_keystrokes(": % s / a / b / g c enter");
+        cx.simulate_keystrokes("n escape");
+        cx.assert_state(
+            indoc! {"
+            a ˇa
+            a a
+            a a"},
+            Mode::Normal,
+        );
+    }
+
+    #[gpui::test]
+    async fn test_command_global(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state(
+            indoc! {"
+            ˇone
+            two
+            three
+            four"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes(": g / o / d enter");
+        cx.assert_state(
+            indoc! {"
+            ˇthree"},
+            Mode::Normal,
+        );
+        // Every line's change is undone together.
+        cx.simulate_keystrokes("u");
+        cx.assert_state(
+            indoc! {"
+            ˇone
+            two
+            three
+            four"},
+            Mode::Normal,
+        );
+
+        cx.simulate_keystrokes(": v / o / d enter");
+        cx.assert_state(
+            indoc! {"
+            one
+            two
+            ˇfour"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("u");
+
+        cx.simulate_keystrokes(": g / t / s / / T / enter");
+        cx.assert_state(
+            indoc! {"
+            one
+            Two
+            ˇThree
+            four"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("u");
+
+        cx.simulate_keystrokes(": 2 , 3 g ! / e / n o r m a l space A ! enter");
+        cx.assert_state(
+            indoc! {"
+            one
+            twoˇ!
+            three
+            four"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("u");
+        cx.assert_state(
+            indoc! {"
+            ˇone
+            two
+            three
+            four"},
+            Mode::Normal,
+        );
+    }
+
     #[gpui::test]
     async fn test_command_search(cx: &mut TestAppContext) {
         let mut cx = NeovimBackedTestContext::new(cx).await;
@@ -516,4 +1347,21 @@ mod test {
         cx.simulate_keystrokes(": q a enter");
         cx.workspace(|workspace, cx| assert_eq!(workspace.items(cx).count(), 0));
     }
+
+    #[gpui::test]
+    async fn test_command_completion_and_history(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        let line_numbers = |cx: &mut VimTestContext| {
+            cx.update(|cx| EditorSettings::get_global(cx).gutter.line_numbers)
+        };
+
+        cx.simulate_keystrokes(": s e t space n o n tab enter");
+        assert!(!line_numbers(&mut cx));
+        cx.simulate_keystrokes(": s e t space n u tab enter");
+        assert!(line_numbers(&mut cx));
+
+        // Up goes back through the commands run before.
+        cx.simulate_keystrokes(": up up enter");
+        assert!(!line_numbers(&mut cx));
+    }
 }
@@ -0,0 +1,169 @@
+use std::{collections::VecDeque, ops::Range, sync::Arc};
+
+use collections::HashSet;
+use editor::{
+    display_map::{BlockContext, BlockDisposition, BlockId, BlockProperties, BlockStyle},
+    scroll::Autoscroll,
+    Anchor, Editor,
+};
+use gpui::{AnyElement, SharedString, ViewContext, WindowContext};
+use language::{ToPoint as _, TransactionId};
+use ui::prelude::*;
+
+use crate::{normal::search::move_to_first_non_whitespace, state::Operator, Vim};
+
+/// A `:s` with the `c` flag, which asks whether to replace each match in turn, in a prompt
+/// shown below the match.
+#[derive(Clone)]
+pub struct ConfirmReplace {
+    /// The matches that haven't been answered yet, with the text to replace each with.
+    matches: VecDeque<(Range<Anchor>, String)>,
+    prompt: Option<BlockId>,
+    /// The first replacement, which later replacements are grouped with so that they're undone
+    /// together.
+    transaction: Option<TransactionId>,
+    last_replaced: Option<Anchor>,
+}
+
+impl Vim {
+    pub(crate) fn start_confirm_replace(
+        &mut self,
+        matches: Vec<(Range<Anchor>, String)>,
+        cx: &mut WindowContext,
+    ) {
+        let confirm = ConfirmReplace {
+            matches: matches.into(),
+            prompt: None,
+            transaction: None,
+            last_replaced: None,
+        };
+        self.update_state(|state| state.confirm_replace = Some(confirm));
+        self.push_operator(Operator::ConfirmReplace, cx);
+        self.show_next_replace(cx);
+    }
+
+    /// Answers the prompt for the current match: `y` replaces it, `n` skips it, `a` replaces
+    /// it and every match after it, `l` replaces it and stops, and `q` stops. Other keys are
+    /// ignored, as they are by vim.
+    pub(crate) fn answer_confirm_replace(&mut self, text: Arc<str>, cx: &mut WindowContext) {
+        let Some(mut confirm) = self.state().confirm_replace.clone() else {
+            self.clear_operator(cx);
+            return;
+        };
+        self.update_active_editor(cx, |_, editor, cx| match &*text {
+            "y" => confirm.replace_next(editor, cx),
+            "n" => {
+                confirm.matches.pop_front();
+            }
+            "a" => {
+                while !confirm.matches.is_empty() {
+                    confirm.replace_next(editor, cx);
+                }
+            }
+            "l" => {
+                confirm.replace_next(editor, cx);
+                confirm.matches.clear();
+            }
+            "q" => confirm.matches.clear(),
+            _ => {}
+        });
+        self.update_state(|state| state.confirm_replace = Some(confirm));
+        self.show_next_replace(cx);
+    }
+
+    /// Stops asking about the remaining matches, such as when escape is pressed.
+    pub(crate) fn stop_confirm_replace(&mut self, cx: &mut WindowContext) {
+        self.update_state(|state| {
+            if let Some(confirm) = &mut state.confirm_replace {
+                confirm.matches.clear();
+            }
+        });
+        self.show_next_replace(cx);
+    }
+
+    /// Prompts for the next match, or finishes once every match has been answered.
+    fn show_next_replace(&mut self, cx: &mut WindowContext) {
+        let Some(mut confirm) = self.state().confirm_replace.clone() else {
+            return;
+        };
+        self.update_active_editor(cx, |_, editor, cx| {
+            if let Some(prompt) = confirm.prompt.take() {
+                editor.remove_blocks(HashSet::from_iter([prompt]), None, cx);
+            }
+            if let Some((range, replacement)) = confirm.matches.front() {
+                editor.highlight_background::<ConfirmReplace>(
+                    &[range.clone()],
+                    |colors| colors.search_match_background,
+                    cx,
+                );
+                editor.change_selections(Some(Autoscroll::center()), cx, |s| {
+                    s.select_anchor_ranges([range.start..range.start])
+                });
+                let replacement = SharedString::from(replacement.clone());
+                confirm.prompt = editor
+                    .insert_blocks(
+                        [BlockProperties {
+                            position: range.start,
+                            height: 1,
+                            style: BlockStyle::Sticky,
+                            disposition: BlockDisposition::Below,
+                            render: Box::new(move |cx| render_prompt(&replacement, cx)),
+                        }],
+                        None,
+                        cx,
+                    )
+                    .pop();
+            } else {
+                editor.clear_background_highlights::<ConfirmReplace>(cx);
+                if let Some(transaction) = confirm.transaction {
+                    editor.group_until_transaction(transaction, cx);
+                }
+                if let Some(last_replaced) = confirm.last_replaced {
+                    let snapshot = editor.buffer().read(cx).snapshot(cx);
+                    let row = last_replaced.to_point(&snapshot).row;
+                    move_to_first_non_whitespace(editor, row, cx);
+                }
+            }
+        });
+
+        if confirm.matches.is_empty() {
+            self.update_state(|state| {
+                state.confirm_replace = None;
+                state
+                    .operator_stack
+                    .retain(|operator| *operator != Operator::ConfirmReplace);
+            });
+            self.sync_vim_settings(cx);
+        } else {
+            self.update_state(|state| state.confirm_replace = Some(confirm));
+        }
+    }
+}
+
+impl ConfirmReplace {
+    fn replace_next(&mut self, editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+        let Some((range, text)) = self.matches.pop_front() else {
+            return;
+        };
+        let transaction = editor.transact(cx, |editor, cx| {
+            editor.edit([(range.clone(), text)], cx);
+        });
+        if self.transaction.is_none() {
+            self.transaction = transaction;
+        }
+        self.last_replaced = Some(range.start);
+    }
+}
+
+fn render_prompt(replacement: &SharedString, cx: &mut BlockContext) -> AnyElement {
+    h_flex()
+        .pl(cx.anchor_x)
+        .gap_2()
+        .child(Label::new(format!("replace with {replacement:?}?")).size(LabelSize::Small))
+        .child(
+            Label::new("(y)es (n)o (a)ll (q)uit (l)ast")
+                .size(LabelSize::Small)
+                .color(Color::Muted),
+        )
+        .into_any_element()
+}
@@ -1,4 +1,4 @@
-use crate::{insert::NormalBefore, Vim, VimModeSetting};
+use crate::{insert::NormalBefore, jump_list::editor_switched, Vim, VimModeSetting};
 use editor::{Editor, EditorEvent};
 use gpui::{Action, AppContext, Entity, EntityId, UpdateGlobal, View, ViewContext, WindowContext};
 use settings::{Settings, SettingsStore};
@@ -34,6 +34,13 @@ fn focused(editor: View<Editor>, cx: &mut WindowContext) {
         if !vim.enabled {
             return;
         }
+        if let Some(previous) = vim
+            .active_editor
+            .clone()
+            .and_then(|editor| editor.upgrade())
+        {
+            editor_switched(vim, previous, &editor, cx);
+        }
         vim.activate_editor(editor.clone(), cx);
     });
 }
@@ -44,7 +51,7 @@ fn blurred(editor: View<Editor>, cx: &mut WindowContext) {
             return;
         }
         if let Some(previous_editor) = vim.active_editor.clone() {
-            vim.stop_recording_immediately(NormalBefore.boxed_clone());
+            vim.stop_recording_immediately(NormalBefore.boxed_clone(), cx);
             if previous_editor
                 .upgrade()
                 .is_some_and(|previous| previous == editor.clone())
@@ -0,0 +1,289 @@
+use std::time::Instant;
+
+use editor::{Anchor, Editor};
+use gpui::{impl_actions, Keystroke, Modifiers, ViewContext, WindowContext};
+use language::{Point, ToOffset as _, ToPoint as _};
+use multi_buffer::{MultiBufferRow, MultiBufferSnapshot};
+use regex::Regex;
+use serde_derive::Deserialize;
+use workspace::Workspace;
+
+use crate::{
+    command::{is_delimiter, is_substitute, parse_range, CommandRange},
+    normal::search::{move_to_first_non_whitespace, parse_pattern, substitute},
+    state::Mode,
+    Vim,
+};
+
+/// Runs an ex command on each line that matches a pattern, with `:g/pattern/command`, or that
+/// doesn't match it, with `:v/pattern/command` or `:g!/pattern/command`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct GlobalCommand {
+    pub query: String,
+}
+
+impl_actions!(vim, [GlobalCommand]);
+
+pub(crate) fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+    workspace.register_action(global_command);
+}
+
+#[derive(Debug)]
+struct Global {
+    range: CommandRange,
+    pattern: String,
+    /// Whether the command runs on the lines that don't match, as for `:v`.
+    invert: bool,
+    command: String,
+}
+
+/// The commands that `:g` can run on each line.
+#[derive(Debug)]
+enum LineCommand {
+    Delete,
+    /// A `:s` command, which applies to the line unless it has a range.
+    Substitute(String),
+    /// Keystrokes typed in normal mode, as for `:normal`.
+    Normal(Vec<Keystroke>),
+}
+
+/// A line matched by `:g`, which is tracked as earlier lines are edited.
+struct MarkedLine {
+    start: Anchor,
+    end: Anchor,
+    was_empty: bool,
+}
+
+fn global_command(_: &mut Workspace, action: &GlobalCommand, cx: &mut ViewContext<Workspace>) {
+    let Some(global) = parse_global(&action.query) else {
+        return;
+    };
+    let Some(command) = LineCommand::parse(&global.command) else {
+        return;
+    };
+    let Ok(regex) = Regex::new(&global.pattern) else {
+        return;
+    };
+
+    // Every line is marked before the command runs on any of them, as the command may
+    // change which lines match.
+    let lines = Vim::update(cx, |vim, cx| {
+        let lines = vim
+            .update_active_editor(cx, |vim, editor, cx| {
+                let range = global.range.buffer_range(vim, editor, cx)?;
+                let snapshot = editor.buffer().read(cx).snapshot(cx);
+                let lines = (range.start.row..=range.end.row)
+                    .filter(|row| {
+                        let row = MultiBufferRow(*row);
+                        let line = snapshot
+                            .text_for_range(
+                                Point::new(row.0, 0)..Point::new(row.0, snapshot.line_len(row)),
+                            )
+                            .collect::<String>();
+                        regex.is_match(&line) != global.invert
+                    })
+                    .map(|row| MarkedLine::new(row, &snapshot))
+                    .collect::<Vec<_>>();
+                Some(lines)
+            })
+            .flatten()?;
+        vim.switch_mode(Mode::Normal, true, cx);
+        Some(lines)
+    });
+    let Some(lines) = lines.filter(|lines| !lines.is_empty()) else {
+        return;
+    };
+
+    // Keystrokes are dispatched once the command palette has returned focus to the editor.
+    cx.defer(move |_, cx| run_on_lines(&lines, &command, &global.pattern, cx));
+}
+
+/// Runs the command on each of the lines that still exist, as a single transaction so that
+/// it's undone in one step.
+fn run_on_lines(
+    lines: &[MarkedLine],
+    command: &LineCommand,
+    pattern: &str,
+    cx: &mut WindowContext,
+) {
+    Vim::update(cx, |vim, cx| {
+        vim.update_active_editor(cx, |_, editor, cx| {
+            editor.start_transaction_at(Instant::now(), cx);
+        });
+    });
+
+    let mut last_row = None;
+    for line in lines {
+        match command {
+            LineCommand::Delete => Vim::update(cx, |vim, cx| {
+                vim.update_active_editor(cx, |_, editor, cx| {
+                    let Some(row) = line.row(editor, cx) else {
+                        return;
+                    };
+                    delete_line(editor, row, cx);
+                    last_row = Some(row);
+                });
+            }),
+            LineCommand::Substitute(query) => Vim::update(cx, |vim, cx| {
+                vim.update_active_editor(cx, |vim, editor, cx| {
+                    let Some(row) = line.row(editor, cx) else {
+                        return;
+                    };
+                    move_to_row(editor, row, cx);
+                    if let Some(row) = substitute(vim, editor, query, pattern, cx) {
+                        last_row = Some(row);
+                    }
+                });
+            }),
+            LineCommand::Normal(keystrokes) => {
+                let row = Vim::update(cx, |vim, cx| {
+                    vim.update_active_editor(cx, |_, editor, cx| {
+                        let row = line.row(editor, cx)?;
+                        move_to_row(editor, row, cx);
+                        Some(row)
+                    })
+                    .flatten()
+                });
+                if row.is_none() {
+                    continue;
+                }
+                for keystroke in keystrokes {
+                    cx.dispatch_keystroke(keystroke.clone());
+                }
+                // Like `:normal`, an incomplete command is abandoned.
+                Vim::update(cx, |vim, cx| {
+                    if vim.state().mode != Mode::Normal || vim.active_operator().is_some() {
+                        vim.switch_mode(Mode::Normal, false, cx);
+                    }
+                });
+            }
+        }
+    }
+
+    Vim::update(cx, |vim, cx| {
+        vim.update_active_editor(cx, |_, editor, cx| {
+            editor.end_transaction_at(Instant::now(), cx);
+            if let Some(row) = last_row {
+                let max_row = editor.buffer().read(cx).snapshot(cx).max_point().row;
+                move_to_first_non_whitespace(editor, row.min(max_row), cx);
+            }
+        });
+    });
+}
+
+impl MarkedLine {
+    fn new(row: u32, snapshot: &MultiBufferSnapshot) -> Self {
+        let start = Point::new(row, 0);
+        // The line's newline is included, so that the line is known to be deleted once its
+        // start and end meet.
+        let end = if row < snapshot.max_point().row {
+            Point::new(row + 1, 0)
+        } else {
+            snapshot.max_point()
+        };
+        Self {
+            start: snapshot.anchor_before(start),
+            end: snapshot.anchor_after(end),
+            was_empty: start == end,
+        }
+    }
+
+    /// The line's current row, or `None` if it was deleted.
+    fn row(&self, editor: &Editor, cx: &mut ViewContext<Editor>) -> Option<u32> {
+        let snapshot = editor.buffer().read(cx).snapshot(cx);
+        let start = self.start.to_offset(&snapshot);
+        if !self.was_empty && start == self.end.to_offset(&snapshot) {
+            return None;
+        }
+        Some(self.start.to_point(&snapshot).row)
+    }
+}
+
+fn move_to_row(editor: &mut Editor, row: u32, cx: &mut ViewContext<Editor>) {
+    let point = Point::new(row, 0);
+    editor.change_selections(None, cx, |s| s.select_ranges([point..point]));
+}
+
+/// Deletes a line along with its newline, or the newline before it if it's the last line.
+fn delete_line(editor: &mut Editor, row: u32, cx: &mut ViewContext<Editor>) {
+    let snapshot = editor.buffer().read(cx).snapshot(cx);
+    let max_row = snapshot.max_point().row;
+    let range = if row < max_row {
+        Point::new(row, 0)..Point::new(row + 1, 0)
+    } else if row > 0 {
+        let previous_row = MultiBufferRow(row - 1);
+        Point::new(row - 1, snapshot.line_len(previous_row))..snapshot.max_point()
+    } else {
+        Point::zero()..snapshot.max_point()
+    };
+    editor.edit([(range, "")], cx);
+}
+
+/// Parses a `:g` or `:v` command, with an optional range, which defaults to every line.
+fn parse_global(query: &str) -> Option<Global> {
+    let (range, command) = parse_range(query);
+    let (invert, rest) = [
+        ("global!", true),
+        ("vglobal", true),
+        ("global", false),
+        ("g!", true),
+        ("v", true),
+        ("g", false),
+    ]
+    .into_iter()
+    .find_map(|(name, invert)| Some((invert, command.strip_prefix(name)?)))
+    .filter(|(_, rest)| rest.chars().next().map_or(false, is_delimiter))?;
+
+    let mut chars = rest.chars();
+    let delimiter = chars.next()?;
+    let pattern = parse_pattern(&mut chars, delimiter);
+    Some(Global {
+        range: range.unwrap_or_else(CommandRange::all_lines),
+        pattern,
+        invert,
+        command: chars.collect(),
+    })
+}
+
+/// Whether a command is `:g` or `:v`, followed by the delimiter of its pattern.
+pub(crate) fn is_global(command: &str) -> bool {
+    parse_global(command).is_some()
+}
+
+impl LineCommand {
+    fn parse(command: &str) -> Option<Self> {
+        let command = command.trim_start();
+        if is_substitute(command) {
+            return Some(Self::Substitute(command.to_string()));
+        }
+        let (name, args) = command
+            .split_once(char::is_whitespace)
+            .unwrap_or((command, ""));
+        match name.trim_end_matches('!') {
+            "d" | "de" | "del" | "dele" | "delet" | "delete" => Some(Self::Delete),
+            "norm" | "norma" | "normal" => Some(Self::Normal(typed_keystrokes(args))),
+            _ => None,
+        }
+    }
+}
+
+/// The keystrokes that type the text, as they're given to `:normal`.
+fn typed_keystrokes(text: &str) -> Vec<Keystroke> {
+    text.chars()
+        .map(|c| {
+            let key = if c == ' ' {
+                "space".to_string()
+            } else {
+                c.to_lowercase().to_string()
+            };
+            Keystroke {
+                modifiers: Modifiers {
+                    shift: c.is_uppercase(),
+                    ..Default::default()
+                },
+                key,
+                ime_key: Some(c.to_string()),
+            }
+        })
+        .collect()
+}
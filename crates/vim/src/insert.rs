@@ -13,7 +13,7 @@ pub fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
 fn normal_before(_: &mut Workspace, action: &NormalBefore, cx: &mut ViewContext<Workspace>) {
     let should_repeat = Vim::update(cx, |vim, cx| {
         let count = vim.take_count(cx).unwrap_or(1);
-        vim.stop_recording_immediately(action.boxed_clone());
+        vim.stop_recording_immediately(action.boxed_clone(), cx);
         if count <= 1 || vim.workspace_state.replaying {
             vim.update_active_editor(cx, |_, editor, cx| {
                 editor.dismiss_menus_and_popups(false, cx);
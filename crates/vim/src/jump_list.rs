@@ -0,0 +1,231 @@
+use editor::{scroll::Autoscroll, Anchor, Direction, Editor};
+use gpui::{actions, AppContext, UpdateGlobal, View};
+use ui::{ViewContext, WindowContext};
+use workspace::Workspace;
+
+use crate::{
+    normal::mark::{anchor_in_editor, global_mark_at, open_global_mark},
+    state::{GlobalMark, JumpList},
+    Vim,
+};
+
+actions!(vim, [JumpOlder, JumpNewer]);
+
+/// The most entries a jump list keeps, as in vim.
+const MAX_JUMPS: usize = 100;
+
+pub(crate) fn register(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
+    workspace.register_action(|_, _: &JumpOlder, cx| {
+        Vim::update(cx, |vim, cx| move_to_jump(vim, Direction::Prev, cx))
+    });
+    workspace.register_action(|_, _: &JumpNewer, cx| {
+        Vim::update(cx, |vim, cx| move_to_jump(vim, Direction::Next, cx))
+    });
+
+    cx.on_release(|_, window, cx| {
+        Vim::update_global(cx, |vim, _| {
+            vim.workspace_state.jump_lists.remove(&window);
+        })
+    })
+    .detach();
+}
+
+/// Remembers where the cursor is in an editor, or the given position in it, before it jumps
+/// elsewhere.
+pub(crate) fn push_to_jump_list(
+    vim: &mut Vim,
+    editor: View<Editor>,
+    position: Option<Anchor>,
+    cx: &mut WindowContext,
+) {
+    let editor = editor.read(cx);
+    let position = position.unwrap_or_else(|| editor.selections.newest_anchor().head());
+    let Some(mark) = global_mark_at(editor, position, cx) else {
+        return;
+    };
+    let window = cx.window_handle();
+    vim.workspace_state
+        .jump_lists
+        .entry(window)
+        .or_default()
+        .push(mark, cx);
+}
+
+impl Vim {
+    pub(crate) fn push_active_editor_to_jump_list(
+        &mut self,
+        position: Option<Anchor>,
+        cx: &mut WindowContext,
+    ) {
+        if let Some(editor) = self
+            .active_editor
+            .clone()
+            .and_then(|editor| editor.upgrade())
+        {
+            push_to_jump_list(self, editor, position, cx);
+        }
+    }
+}
+
+/// Moving to another editor in the window is a jump, as switching buffers is in vim, unless it's
+/// to move to an entry in the jump list.
+pub(crate) fn editor_switched(
+    vim: &mut Vim,
+    previous: View<Editor>,
+    editor: &View<Editor>,
+    cx: &mut WindowContext,
+) {
+    if previous == *editor || !editor.read(cx).use_modal_editing() {
+        return;
+    }
+    let workspace = editor.read(cx).workspace();
+    if workspace.is_none() || previous.read(cx).workspace() != workspace {
+        return;
+    }
+    let window = cx.window_handle();
+    if let Some(jump_list) = vim.workspace_state.jump_lists.get_mut(&window) {
+        if std::mem::take(&mut jump_list.navigating) {
+            return;
+        }
+    }
+    push_to_jump_list(vim, previous, None, cx);
+}
+
+fn move_to_jump(vim: &mut Vim, direction: Direction, cx: &mut WindowContext) {
+    let count = vim.take_count(cx).unwrap_or(1);
+    let Some(editor) = vim
+        .active_editor
+        .clone()
+        .and_then(|editor| editor.upgrade())
+    else {
+        return;
+    };
+    let current = {
+        let editor = editor.read(cx);
+        let head = editor.selections.newest_anchor().head();
+        global_mark_at(editor, head, cx)
+    };
+    let window = cx.window_handle();
+    let jump_list = vim.workspace_state.jump_lists.entry(window).or_default();
+    let Some(mark) = jump_list.step(direction, count, current, cx) else {
+        return;
+    };
+
+    if let Some(anchor) = anchor_in_editor(&mark, editor.read(cx), cx) {
+        editor.update(cx, |editor, cx| {
+            editor.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                s.select_anchor_ranges([anchor..anchor])
+            })
+        });
+        return;
+    }
+
+    let Some(workspace) = editor.read(cx).workspace() else {
+        return;
+    };
+    jump_list.navigating = true;
+    let open = open_global_mark(&mark, false, workspace, cx);
+    cx.spawn(|mut cx| async move {
+        if let Err(error) = open.await {
+            cx.update(|cx| {
+                Vim::update(cx, |vim, cx| {
+                    if let Some(jump_list) =
+                        vim.workspace_state.jump_lists.get_mut(&cx.window_handle())
+                    {
+                        jump_list.navigating = false;
+                    }
+                })
+            })?;
+            return Err(error);
+        }
+        anyhow::Ok(())
+    })
+    .detach_and_log_err(cx);
+}
+
+impl JumpList {
+    /// Adds a position to the end of the list, replacing any entry on the same line of the
+    /// same file.
+    fn push(&mut self, mark: GlobalMark, cx: &AppContext) {
+        let row = mark.point(cx).row;
+        self.entries
+            .retain(|entry| entry.path != mark.path || entry.point(cx).row != row);
+        self.entries.push(mark);
+        if self.entries.len() > MAX_JUMPS {
+            self.entries.remove(0);
+        }
+        self.position = self.entries.len();
+    }
+
+    /// Moves `count` entries through the list, returning the entry that was moved to.
+    fn step(
+        &mut self,
+        direction: Direction,
+        count: usize,
+        current: Option<GlobalMark>,
+        cx: &AppContext,
+    ) -> Option<GlobalMark> {
+        let target = match direction {
+            Direction::Prev => {
+                // Going back from the newest jump remembers where the cursor is, so that
+                // `ctrl-i` can return to it.
+                if self.position == self.entries.len() {
+                    if let Some(current) = current {
+                        self.push(current, cx);
+                        self.position = self.entries.len() - 1;
+                    }
+                }
+                self.position.checked_sub(count)?
+            }
+            Direction::Next => {
+                let target = self.position + count;
+                if target >= self.entries.len() {
+                    return None;
+                }
+                target
+            }
+        };
+        self.position = target;
+        self.entries.get(target).cloned()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{state::Mode, test::VimTestContext};
+
+    #[gpui::test]
+    async fn test_jump_list(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇone\ntwo\nthree\nfour", Mode::Normal);
+        cx.simulate_keystrokes("shift-g k g g");
+        cx.assert_state("ˇone\ntwo\nthree\nfour", Mode::Normal);
+
+        cx.simulate_keystrokes("ctrl-o");
+        cx.assert_state("one\ntwo\nˇthree\nfour", Mode::Normal);
+
+        // The first jump was from the line the cursor was on when going back, which replaced it.
+        cx.simulate_keystrokes("ctrl-o");
+        cx.assert_state("one\ntwo\nˇthree\nfour", Mode::Normal);
+
+        cx.simulate_keystrokes("ctrl-i");
+        cx.assert_state("ˇone\ntwo\nthree\nfour", Mode::Normal);
+        cx.simulate_keystrokes("ctrl-i");
+        cx.assert_state("ˇone\ntwo\nthree\nfour", Mode::Normal);
+    }
+
+    #[gpui::test]
+    async fn test_jump_list_ignores_motions(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇone\ntwo\nthree\nfour", Mode::Normal);
+        cx.simulate_keystrokes("j j l l ctrl-o");
+        cx.assert_state("one\ntwo\nthˇree\nfour", Mode::Normal);
+
+        cx.simulate_keystrokes("2 shift-g");
+        cx.assert_state("one\nˇtwo\nthree\nfour", Mode::Normal);
+        cx.simulate_keystrokes("ctrl-o");
+        cx.assert_state("one\ntwo\nthˇree\nfour", Mode::Normal);
+    }
+}
@@ -0,0 +1,170 @@
+use std::sync::Arc;
+
+use gpui::{actions, Keystroke, ViewContext, WindowContext};
+use workspace::Workspace;
+
+use crate::{state::Operator, Vim};
+
+actions!(vim, [ToggleRecord]);
+
+pub(crate) fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+    workspace.register_action(|_: &mut Workspace, _: &ToggleRecord, cx| {
+        Vim::update(cx, |vim, cx| {
+            if vim.workspace_state.recording_register.take().is_none() {
+                vim.push_operator(Operator::RecordRegister, cx);
+            }
+        })
+    });
+}
+
+/// The single register name typed after `q` or `@`.
+fn register_name(text: &str) -> Option<char> {
+    let mut chars = text.chars();
+    match (chars.next(), chars.next()) {
+        (Some(name), None) => Some(name),
+        _ => None,
+    }
+}
+
+impl Vim {
+    /// Starts recording keystrokes into the register typed after `q`. Uppercase registers append
+    /// to the keystrokes already recorded into their lowercase register.
+    pub(crate) fn start_recording_macro(&mut self, text: Arc<str>, cx: &mut WindowContext) {
+        let Some(name) = register_name(&text).filter(|name| name.is_ascii_alphanumeric()) else {
+            self.clear_operator(cx);
+            return;
+        };
+        self.pop_operator(cx);
+        let register = name.to_ascii_lowercase();
+        if !name.is_ascii_uppercase() {
+            self.workspace_state.macros.remove(&register);
+        }
+        self.workspace_state.recording_register = Some(register);
+    }
+
+    /// Records a keystroke into the register being recorded, unless it's being replayed from
+    /// another register.
+    pub(crate) fn record_keystroke(&mut self, keystroke: &Keystroke) {
+        if self.workspace_state.replaying_macro {
+            return;
+        }
+        if let Some(register) = self.workspace_state.recording_register {
+            self.workspace_state
+                .macros
+                .entry(register)
+                .or_default()
+                .push(keystroke.clone());
+        }
+    }
+
+    /// Replays the keystrokes recorded into the register typed after `@`, or into the register
+    /// replayed last for `@@`, as many times as the count.
+    pub(crate) fn replay_macro(&mut self, text: Arc<str>, cx: &mut WindowContext) {
+        let count = self.take_count(cx).unwrap_or(1);
+        self.pop_operator(cx);
+        // Replaying a register from itself would never end.
+        if self.workspace_state.replaying_macro {
+            return;
+        }
+        let register = match register_name(&text) {
+            Some('@') => self.workspace_state.last_replayed_register,
+            name => name.map(|name| name.to_ascii_lowercase()),
+        };
+        let Some(register) = register else {
+            return;
+        };
+        let Some(keystrokes) = self.workspace_state.macros.get(&register).cloned() else {
+            return;
+        };
+        self.workspace_state.last_replayed_register = Some(register);
+        self.workspace_state.replaying_macro = true;
+
+        // Replay through the usual keystroke dispatch, so that the keystrokes are handled by
+        // whatever mode and operators they put vim in.
+        cx.defer(move |cx| {
+            for _ in 0..count {
+                for keystroke in &keystrokes {
+                    cx.dispatch_keystroke(keystroke.clone());
+                }
+            }
+            Vim::update(cx, |vim, _| vim.workspace_state.replaying_macro = false);
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::indoc;
+
+    use crate::{state::Mode, test::VimTestContext};
+
+    #[gpui::test]
+    async fn test_record_and_replay_macro(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state(
+            indoc! {"
+                ˇone
+                two
+                three
+                four
+                five"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("q a shift-a ! escape j q");
+        cx.assert_state(
+            indoc! {"
+                one!
+                twˇo
+                three
+                four
+                five"},
+            Mode::Normal,
+        );
+
+        cx.simulate_keystrokes("@ a");
+        cx.assert_state(
+            indoc! {"
+                one!
+                two!
+                thrˇee
+                four
+                five"},
+            Mode::Normal,
+        );
+
+        cx.simulate_keystrokes("2 @ @");
+        cx.assert_state(
+            indoc! {"
+                one!
+                two!
+                three!
+                four!
+                fivˇe"},
+            Mode::Normal,
+        );
+    }
+
+    #[gpui::test]
+    async fn test_record_multi_keystroke_bindings(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state(
+            indoc! {"
+                one
+                two
+                thrˇee"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("q b g g d d q");
+        cx.assert_state(
+            indoc! {"
+                ˇtwo
+                three"},
+            Mode::Normal,
+        );
+
+        cx.simulate_keystrokes("j @ b");
+        cx.assert_state("ˇthree", Mode::Normal);
+    }
+}
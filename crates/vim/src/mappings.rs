@@ -0,0 +1,215 @@
+//! Key mappings defined in the `vim_mappings` setting, like vim's `map` and `noremap` commands.
+//!
+//! Each mapping becomes a key binding in the context of its mode. A recursive mapping sends its
+//! keystrokes with `workspace::SendKeystrokes`, so that other mappings apply to them. A
+//! non-recursive one sends them with the `VimNoremap` context set, which no mapping matches.
+
+use anyhow::Result;
+use gpui::{impl_actions, Action, AppContext, KeyBinding, Keystroke, ViewContext, WindowContext};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsSources};
+use util::ResultExt;
+use workspace::{SendKeystrokes, Workspace};
+
+use crate::Vim;
+
+/// The key mappings to add to vim mode.
+///
+/// Default: []
+#[derive(Clone, Debug, PartialEq)]
+pub struct VimMappingsSetting(pub Vec<VimMapping>);
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct VimMapping {
+    /// The mode that the mapping applies in.
+    pub mode: MappingMode,
+    /// The keystrokes to map, written as in a keymap, like "j k".
+    pub from: String,
+    /// The keystrokes to send instead.
+    pub to: String,
+    /// Whether other mappings apply to the keystrokes sent, as with `map` rather than `noremap`.
+    ///
+    /// Default: false
+    #[serde(default)]
+    pub remap: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MappingMode {
+    Normal,
+    Visual,
+    Insert,
+    /// Normal mode while an operator is waiting for its motion, as with `omap`.
+    OperatorPending,
+}
+
+impl MappingMode {
+    fn context(&self) -> &'static str {
+        match self {
+            MappingMode::Normal => {
+                "Editor && vim_mode == normal && vim_operator == none && !VimWaiting"
+            }
+            MappingMode::Visual => "Editor && vim_mode == visual && !VimWaiting",
+            MappingMode::Insert => "Editor && vim_mode == insert",
+            MappingMode::OperatorPending => {
+                "Editor && vim_mode == normal && vim_operator != none && !VimWaiting"
+            }
+        }
+    }
+}
+
+impl Settings for VimMappingsSetting {
+    const KEY: Option<&'static str> = Some("vim_mappings");
+
+    type FileContent = Option<Vec<VimMapping>>;
+
+    fn load(sources: SettingsSources<Self::FileContent>, _: &mut AppContext) -> Result<Self> {
+        Ok(Self(sources.user.cloned().flatten().unwrap_or(
+            sources.default.clone().ok_or_else(Self::missing_default)?,
+        )))
+    }
+}
+
+/// Sends keystrokes that no mapping applies to, for a non-recursive mapping.
+#[derive(Clone, Deserialize, PartialEq)]
+pub struct SendKeystrokesNoremap(pub String);
+
+impl_actions!(vim, [SendKeystrokesNoremap]);
+
+pub(crate) fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+    workspace.register_action(|_: &mut Workspace, action: &SendKeystrokesNoremap, cx| {
+        send_keystrokes_noremap(action, cx)
+    });
+}
+
+/// The key bindings for the mappings in the `vim_mappings` setting. They're added after the rest
+/// of the keymap, so that they take precedence over vim's own bindings.
+pub fn mapping_bindings(cx: &AppContext) -> Vec<KeyBinding> {
+    VimMappingsSetting::get_global(cx)
+        .0
+        .iter()
+        .filter_map(|mapping| {
+            let action: Box<dyn Action> = if mapping.remap {
+                Box::new(SendKeystrokes(mapping.to.clone()))
+            } else {
+                Box::new(SendKeystrokesNoremap(mapping.to.clone()))
+            };
+            let context = format!("{} && !VimNoremap", mapping.mode.context());
+            KeyBinding::load(&mapping.from, action, Some(&context)).log_err()
+        })
+        .collect()
+}
+
+fn send_keystrokes_noremap(action: &SendKeystrokesNoremap, cx: &mut WindowContext) {
+    let keystrokes = action
+        .0
+        .split_whitespace()
+        .flat_map(|keystroke| Keystroke::parse(keystroke).log_err())
+        .collect::<Vec<_>>();
+    Vim::update(cx, |vim, cx| {
+        vim.workspace_state.noremap = true;
+        vim.sync_vim_settings(cx);
+    });
+
+    // The keystrokes are sent once the mapping's own keystrokes have been handled.
+    cx.defer(move |cx| {
+        for keystroke in keystrokes {
+            let focused = cx.focused();
+            cx.dispatch_keystroke(keystroke);
+            // The next keystroke goes to the newly focused view only once it's drawn.
+            if cx.focused() != focused {
+                cx.draw();
+            }
+        }
+        Vim::update(cx, |vim, cx| {
+            vim.workspace_state.noremap = false;
+            vim.sync_vim_settings(cx);
+        });
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use settings::SettingsStore;
+
+    use super::{mapping_bindings, MappingMode, VimMapping, VimMappingsSetting};
+    use crate::{state::Mode, test::VimTestContext};
+
+    fn set_mappings(cx: &mut VimTestContext, mappings: &[(MappingMode, &str, &str, bool)]) {
+        cx.update_global(|store: &mut SettingsStore, cx| {
+            store.update_user_settings::<VimMappingsSetting>(cx, |s| {
+                *s = Some(
+                    mappings
+                        .iter()
+                        .map(|(mode, from, to, remap)| VimMapping {
+                            mode: *mode,
+                            from: from.to_string(),
+                            to: to.to_string(),
+                            remap: *remap,
+                        })
+                        .collect(),
+                );
+            });
+        });
+        cx.update(|cx| cx.bind_keys(mapping_bindings(cx)));
+    }
+
+    #[gpui::test]
+    async fn test_insert_mode_mapping(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        set_mappings(&mut cx, &[(MappingMode::Insert, "j k", "escape", false)]);
+
+        cx.set_state("ˇone", Mode::Normal);
+        cx.simulate_keystrokes("i x j k");
+        cx.assert_state("ˇxone", Mode::Normal);
+
+        // keystrokes that don't complete the mapping are typed
+        cx.simulate_keystrokes("a j x");
+        cx.assert_state("xjxˇone", Mode::Insert);
+    }
+
+    #[gpui::test]
+    async fn test_recursive_mappings(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        set_mappings(
+            &mut cx,
+            &[
+                (MappingMode::Normal, "l", "w", false),
+                (MappingMode::Normal, "g a", "l", true),
+                (MappingMode::Normal, "g b", "l", false),
+            ],
+        );
+
+        cx.set_state("ˇone two three four", Mode::Normal);
+        cx.simulate_keystrokes("l");
+        cx.assert_state("one ˇtwo three four", Mode::Normal);
+
+        // a recursive mapping's keystrokes are mapped again
+        cx.simulate_keystrokes("g a");
+        cx.assert_state("one two ˇthree four", Mode::Normal);
+
+        // a non-recursive mapping's aren't
+        cx.simulate_keystrokes("g b");
+        cx.assert_state("one two tˇhree four", Mode::Normal);
+    }
+
+    #[gpui::test]
+    async fn test_operator_pending_mapping(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        set_mappings(
+            &mut cx,
+            &[(MappingMode::OperatorPending, "p", "i (", false)],
+        );
+
+        cx.set_state("f(aˇb, c) d", Mode::Normal);
+        cx.simulate_keystrokes("d p");
+        cx.assert_state("f(ˇ) d", Mode::Normal);
+
+        // the mapping doesn't apply without an operator
+        cx.set_state("ˇab", Mode::Normal);
+        cx.simulate_keystrokes("y l p");
+        cx.assert_state("aˇab", Mode::Normal);
+    }
+}
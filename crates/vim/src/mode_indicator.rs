@@ -38,12 +38,24 @@ impl ModeIndicator {
     }
 
     fn current_operators_description(&self, vim: &Vim) -> String {
-        vim.state()
+        let register = vim
+            .workspace_state
+            .selected_register
+            .map(|register| format!("\"{register}"))
+            .unwrap_or_default();
+        let operators = vim
+            .state()
             .operator_stack
             .iter()
             .map(|item| item.id())
             .collect::<Vec<_>>()
-            .join("")
+            .join("");
+        let recording = vim
+            .workspace_state
+            .recording_register
+            .map(|register| format!("recording @{register} "))
+            .unwrap_or_default();
+        recording + &register + &operators
     }
 }
 
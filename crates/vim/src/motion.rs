@@ -398,6 +398,11 @@ pub(crate) fn search_motion(m: Motion, cx: &mut WindowContext) {
         prior_selections, ..
     } = &m
     {
+        if let Some(prior) = prior_selections.last() {
+            Vim::update(cx, |vim, cx| {
+                vim.push_active_editor_to_jump_list(Some(prior.start), cx)
+            });
+        }
         match Vim::read(cx).state().mode {
             Mode::Visual | Mode::VisualLine | Mode::VisualBlock => {
                 if !prior_selections.is_empty() {
@@ -428,7 +433,12 @@ pub(crate) fn motion(motion: Motion, cx: &mut WindowContext) {
         Vim::update(cx, |vim, cx| vim.pop_operator(cx));
     }
 
-    let count = Vim::update(cx, |vim, cx| vim.take_count(cx));
+    let count = Vim::update(cx, |vim, cx| {
+        if motion.is_jump() && vim.state().mode != Mode::Insert {
+            vim.push_active_editor_to_jump_list(None, cx);
+        }
+        vim.take_count(cx)
+    });
     let active_operator = Vim::read(cx).active_operator();
     let mut waiting_operator: Option<Operator> = None;
     match Vim::read(cx).state().mode {
@@ -543,6 +553,50 @@ impl Motion {
         }
     }
 
+    /// Whether the motion is a jump, which remembers where the cursor was in the jump list.
+    /// Searches are remembered by `search_motion`, as the search has moved the cursor already.
+    pub fn is_jump(&self) -> bool {
+        use Motion::*;
+        match self {
+            StartOfDocument
+            | EndOfDocument
+            | Matching
+            | StartOfParagraph
+            | EndOfParagraph
+            | WindowTop
+            | WindowMiddle
+            | WindowBottom
+            | Jump { .. } => true,
+            Down { .. }
+            | Up { .. }
+            | CurrentLine
+            | EndOfLine { .. }
+            | FindForward { .. }
+            | RepeatFind { .. }
+            | Left
+            | Backspace
+            | Right
+            | Space
+            | StartOfLine { .. }
+            | StartOfLineDownward
+            | EndOfLineDownward
+            | GoToColumn
+            | NextWordStart { .. }
+            | NextWordEnd { .. }
+            | PreviousWordStart { .. }
+            | PreviousWordEnd { .. }
+            | NextSubwordStart { .. }
+            | NextSubwordEnd { .. }
+            | PreviousSubwordStart { .. }
+            | PreviousSubwordEnd { .. }
+            | FirstNonWhitespace { .. }
+            | FindBackward { .. }
+            | RepeatFindReversed { .. }
+            | NextLineStart
+            | ZedSearchResult { .. } => false,
+        }
+    }
+
     pub fn inclusive(&self) -> bool {
         use Motion::*;
         match self {
@@ -1,9 +1,10 @@
 mod case;
 mod change;
 mod delete;
+pub(crate) mod fold;
 mod increment;
 pub(crate) mod mark;
-mod paste;
+pub(crate) mod paste;
 pub(crate) mod repeat;
 mod scroll;
 pub(crate) mod search;
@@ -32,6 +33,7 @@ use self::{
     case::{change_case, convert_to_lower_case, convert_to_upper_case},
     change::{change_motion, change_object},
     delete::{delete_motion, delete_object},
+    fold::{fold_motion, fold_object},
     yank::{yank_motion, yank_object},
 };
 
@@ -167,6 +169,7 @@ pub(crate) fn register(workspace: &mut Workspace, cx: &mut ViewContext<Workspace
     search::register(workspace, cx);
     substitute::register(workspace, cx);
     increment::register(workspace, cx);
+    fold::register(workspace, cx);
 }
 
 pub fn normal_motion(
@@ -181,6 +184,7 @@ pub fn normal_motion(
             Some(Operator::Change) => change_motion(vim, motion, times, cx),
             Some(Operator::Delete) => delete_motion(vim, motion, times, cx),
             Some(Operator::Yank) => yank_motion(vim, motion, times, cx),
+            Some(Operator::Fold) => fold_motion(vim, motion, times, cx),
             Some(Operator::AddSurrounds { target: None }) => {}
             Some(operator) => {
                 // Can't do anything for text objects, Ignoring
@@ -198,6 +202,7 @@ pub fn normal_object(object: Object, cx: &mut WindowContext) {
                 Some(Operator::Change) => change_object(vim, object, around, cx),
                 Some(Operator::Delete) => delete_object(vim, object, around, cx),
                 Some(Operator::Yank) => yank_object(vim, object, around, cx),
+                Some(Operator::Fold) => fold_object(vim, object, around, cx),
                 Some(Operator::AddSurrounds { target: None }) => {
                     waiting_operator = Some(Operator::AddSurrounds {
                         target: Some(SurroundsType::Object(object)),
@@ -112,7 +112,8 @@ pub fn delete_object(vim: &mut Vim, object: Object, around: bool, cx: &mut Windo
                     // Does post-processing for the trailing newline and EOF
                     // when not cancelled.
                     let cancelled = around && selection.start == selection.end;
-                    if object == Object::Paragraph && !cancelled {
+                    if matches!(object, Object::Paragraph | Object::IndentObj { .. }) && !cancelled
+                    {
                         // EOF check should be done before including a trailing newline.
                         if ends_at_eof(map, selection) {
                             move_selection_start_to_previous_line(map, selection);
@@ -0,0 +1,471 @@
+use std::ops::{Range, RangeInclusive};
+
+use editor::{
+    actions::{FoldAll, UnfoldLines},
+    display_map::DisplaySnapshot,
+    scroll::Autoscroll,
+    Editor,
+};
+use gpui::{actions, ViewContext, WindowContext};
+use language::Point;
+use multi_buffer::{MultiBufferRow, ToPoint as _};
+use workspace::Workspace;
+
+use crate::{motion::Motion, object::Object, state::Mode, Vim};
+
+actions!(
+    vim,
+    [
+        ToggleFold,
+        OpenFold,
+        CloseFold,
+        CloseAllFolds,
+        CreateFold,
+        FoldLines,
+        DeleteFold,
+        DeleteAllFolds,
+        NextFoldStart,
+        PreviousFoldEnd
+    ]
+);
+
+pub(crate) fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+    workspace.register_action(|_: &mut Workspace, _: &ToggleFold, cx| {
+        Vim::update(cx, |vim, cx| {
+            let count = vim.take_count(cx).unwrap_or(1);
+            vim.update_active_editor(cx, |vim, editor, cx| toggle_folds(vim, editor, count, cx));
+        })
+    });
+    workspace.register_action(|_: &mut Workspace, _: &OpenFold, cx| {
+        Vim::update(cx, |vim, cx| {
+            vim.take_count(cx);
+            vim.update_active_editor(cx, |_, editor, cx| editor.unfold_lines(&UnfoldLines, cx));
+        })
+    });
+    workspace.register_action(|_: &mut Workspace, _: &CloseFold, cx| {
+        Vim::update(cx, |vim, cx| {
+            let count = vim.take_count(cx).unwrap_or(1);
+            vim.update_active_editor(cx, |vim, editor, cx| {
+                let map = editor.snapshot(cx).display_snapshot;
+                let ranges = cursor_rows(editor, cx)
+                    .into_iter()
+                    .flat_map(|row| open_folds_at(vim, &map, row, count))
+                    .collect::<Vec<_>>();
+                close_folds(editor, ranges, cx);
+            });
+        })
+    });
+    workspace.register_action(|_: &mut Workspace, _: &CloseAllFolds, cx| {
+        Vim::update(cx, |vim, cx| {
+            vim.take_count(cx);
+            vim.update_active_editor(cx, |vim, editor, cx| {
+                editor.fold_all(&FoldAll, cx);
+                let map = editor.snapshot(cx).display_snapshot;
+                let ranges = manual_folds(vim, &map).collect::<Vec<_>>();
+                close_folds(editor, ranges, cx);
+            });
+        })
+    });
+    workspace.register_action(|_: &mut Workspace, _: &CreateFold, cx| {
+        Vim::update(cx, |vim, cx| {
+            let rows = vim
+                .update_active_editor(cx, |_, editor, cx| {
+                    editor
+                        .selections
+                        .all::<Point>(cx)
+                        .into_iter()
+                        .map(|selection| line_rows(selection.start, selection.end))
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default();
+            vim.switch_mode(Mode::Normal, true, cx);
+            vim.update_active_editor(cx, |vim, editor, cx| create_folds(vim, editor, rows, cx));
+        })
+    });
+    workspace.register_action(|_: &mut Workspace, _: &FoldLines, cx| {
+        Vim::update(cx, |vim, cx| {
+            let count = vim.take_count(cx).unwrap_or(1) as u32;
+            vim.update_active_editor(cx, |vim, editor, cx| {
+                let max_row = editor.buffer().read(cx).snapshot(cx).max_point().row;
+                let rows = cursor_rows(editor, cx)
+                    .into_iter()
+                    .map(|row| row..=(row + count - 1).min(max_row))
+                    .collect();
+                create_folds(vim, editor, rows, cx);
+            });
+        })
+    });
+    workspace.register_action(|_: &mut Workspace, _: &DeleteFold, cx| {
+        Vim::update(cx, |vim, cx| {
+            vim.take_count(cx);
+            vim.update_active_editor(cx, |vim, editor, cx| delete_folds(vim, editor, false, cx));
+        })
+    });
+    workspace.register_action(|_: &mut Workspace, _: &DeleteAllFolds, cx| {
+        Vim::update(cx, |vim, cx| {
+            vim.take_count(cx);
+            vim.update_active_editor(cx, |vim, editor, cx| delete_folds(vim, editor, true, cx));
+        })
+    });
+    workspace.register_action(|_: &mut Workspace, _: &NextFoldStart, cx| {
+        Vim::update(cx, |vim, cx| {
+            let count = vim.take_count(cx).unwrap_or(1);
+            vim.update_active_editor(cx, |vim, editor, cx| {
+                move_to_fold(vim, editor, count, true, cx)
+            });
+        })
+    });
+    workspace.register_action(|_: &mut Workspace, _: &PreviousFoldEnd, cx| {
+        Vim::update(cx, |vim, cx| {
+            let count = vim.take_count(cx).unwrap_or(1);
+            vim.update_active_editor(cx, |vim, editor, cx| {
+                move_to_fold(vim, editor, count, false, cx)
+            });
+        })
+    });
+}
+
+/// Creates a manual fold over the lines a motion moves over, as `zf{motion}`.
+pub(crate) fn fold_motion(
+    vim: &mut Vim,
+    motion: Motion,
+    times: Option<usize>,
+    cx: &mut WindowContext,
+) {
+    vim.update_active_editor(cx, |vim, editor, cx| {
+        let text_layout_details = editor.text_layout_details(cx);
+        let mut rows = Vec::new();
+        editor.change_selections(None, cx, |s| {
+            s.move_with(|map, selection| {
+                let mut range = selection.clone();
+                motion.expand_selection(map, &mut range, times, true, &text_layout_details);
+                rows.push(line_rows(
+                    range.start.to_point(map),
+                    range.end.to_point(map),
+                ));
+            });
+        });
+        create_folds(vim, editor, rows, cx);
+    });
+}
+
+/// Creates a manual fold over the lines of a text object, as `zf{object}`.
+pub(crate) fn fold_object(vim: &mut Vim, object: Object, around: bool, cx: &mut WindowContext) {
+    vim.update_active_editor(cx, |vim, editor, cx| {
+        let mut rows = Vec::new();
+        editor.change_selections(None, cx, |s| {
+            s.move_with(|map, selection| {
+                let mut range = selection.clone();
+                if object.expand_selection(map, &mut range, around) {
+                    rows.push(line_rows(
+                        range.start.to_point(map),
+                        range.end.to_point(map),
+                    ));
+                }
+            });
+        });
+        create_folds(vim, editor, rows, cx);
+    });
+}
+
+/// The rows of the lines a range covers, leaving out the line it ends at the start of, as a
+/// line-wise range ends after the newline of its last line.
+fn line_rows(start: Point, end: Point) -> RangeInclusive<u32> {
+    if end.row > start.row && end.column == 0 {
+        start.row..=end.row - 1
+    } else {
+        start.row..=end.row
+    }
+}
+
+fn cursor_rows(editor: &Editor, cx: &mut ViewContext<Editor>) -> Vec<u32> {
+    editor
+        .selections
+        .all::<Point>(cx)
+        .into_iter()
+        .map(|selection| selection.head().row)
+        .collect()
+}
+
+/// The manual folds made with `zf`, as the whole lines they cover.
+fn manual_folds<'a>(
+    vim: &'a Vim,
+    map: &'a DisplaySnapshot,
+) -> impl Iterator<Item = Range<Point>> + 'a {
+    let snapshot = &map.buffer_snapshot;
+    vim.state().manual_folds.iter().map(move |range| {
+        let end = range.end.to_point(snapshot);
+        Point::new(range.start.to_point(snapshot).row, 0)
+            ..Point::new(end.row, snapshot.line_len(MultiBufferRow(end.row)))
+    })
+}
+
+/// Up to `count` of the open folds that the row is in, innermost first: the ranges that the
+/// syntax or indentation make foldable, and the manual folds.
+fn open_folds_at(vim: &Vim, map: &DisplaySnapshot, row: u32, count: usize) -> Vec<Range<Point>> {
+    let mut folds = (0..=row)
+        .rev()
+        .filter_map(|start_row| map.foldable_range(MultiBufferRow(start_row)))
+        .filter(|range| range.end.row >= row)
+        .take(count)
+        .collect::<Vec<_>>();
+    folds.extend(manual_folds(vim, map).filter(|range| {
+        range.start.row <= row
+            && row <= range.end.row
+            && !map.is_line_folded(MultiBufferRow(range.start.row))
+    }));
+    folds.sort_by_key(|range| range.end.row - range.start.row);
+    folds.dedup();
+    folds.truncate(count);
+    folds
+}
+
+/// Opens the folds on the cursors' lines that are closed, and closes `count` folds around the
+/// others, as `za`.
+fn toggle_folds(vim: &Vim, editor: &mut Editor, count: usize, cx: &mut ViewContext<Editor>) {
+    let map = editor.snapshot(cx).display_snapshot;
+    let (folded, unfolded): (Vec<_>, Vec<_>) = cursor_rows(editor, cx)
+        .into_iter()
+        .partition(|row| map.is_line_folded(MultiBufferRow(*row)));
+
+    let lines = folded
+        .into_iter()
+        .map(|row| {
+            Point::new(row, 0)..Point::new(row, map.buffer_snapshot.line_len(MultiBufferRow(row)))
+        })
+        .collect::<Vec<_>>();
+    editor.unfold_ranges(lines, true, true, cx);
+
+    let ranges = unfolded
+        .into_iter()
+        .flat_map(|row| open_folds_at(vim, &map, row, count))
+        .collect::<Vec<_>>();
+    close_folds(editor, ranges, cx);
+}
+
+/// Folds the ranges, and moves the cursors that they hide to the line the fold is shown on,
+/// as vim does when a fold closes over the cursor.
+fn close_folds(editor: &mut Editor, ranges: Vec<Range<Point>>, cx: &mut ViewContext<Editor>) {
+    if !ranges.is_empty() {
+        editor.fold_ranges(ranges, true, cx);
+    }
+
+    let map = editor.snapshot(cx).display_snapshot;
+    let mut moved = false;
+    let cursors = editor
+        .selections
+        .all::<Point>(cx)
+        .into_iter()
+        .map(|selection| {
+            let head = selection.head();
+            match fold_start_before(&map, head) {
+                Some(start) => {
+                    moved = true;
+                    let cursor = Point::new(start.row, head.column.min(start.column));
+                    cursor..cursor
+                }
+                None => selection.range(),
+            }
+        })
+        .collect::<Vec<_>>();
+    if moved {
+        editor.change_selections(Some(Autoscroll::fit()), cx, |s| s.select_ranges(cursors));
+    }
+}
+
+/// The start of the closed fold that hides the point, if any.
+fn fold_start_before(map: &DisplaySnapshot, point: Point) -> Option<Point> {
+    map.folds_in_range(point..point)
+        .map(|fold| fold.range.start.to_point(&map.buffer_snapshot))
+        .filter(|start| *start < point)
+        .min()
+}
+
+fn create_folds(
+    vim: &mut Vim,
+    editor: &mut Editor,
+    rows: Vec<RangeInclusive<u32>>,
+    cx: &mut ViewContext<Editor>,
+) {
+    let snapshot = editor.buffer().read(cx).snapshot(cx);
+    let ranges = rows
+        .into_iter()
+        .map(|rows| {
+            Point::new(*rows.start(), 0)
+                ..Point::new(*rows.end(), snapshot.line_len(MultiBufferRow(*rows.end())))
+        })
+        .collect::<Vec<_>>();
+    vim.update_state(|state| {
+        state.manual_folds.extend(
+            ranges
+                .iter()
+                .map(|range| snapshot.anchor_before(range.start)..snapshot.anchor_after(range.end)),
+        )
+    });
+    close_folds(editor, ranges, cx);
+}
+
+/// Forgets the innermost manual fold on each cursor's line, as `zd`, or every manual fold, as
+/// `zE`, opening them.
+fn delete_folds(vim: &mut Vim, editor: &mut Editor, all: bool, cx: &mut ViewContext<Editor>) {
+    let map = editor.snapshot(cx).display_snapshot;
+    let folds = manual_folds(vim, &map).collect::<Vec<_>>();
+    let mut deleted = Vec::new();
+    if all {
+        deleted.extend(0..folds.len());
+    } else {
+        for row in cursor_rows(editor, cx) {
+            let innermost = folds
+                .iter()
+                .enumerate()
+                .filter(|(_, range)| range.start.row <= row && row <= range.end.row)
+                .min_by_key(|(_, range)| range.end.row - range.start.row);
+            if let Some((ix, _)) = innermost {
+                deleted.push(ix);
+            }
+        }
+    }
+    if deleted.is_empty() {
+        return;
+    }
+
+    editor.unfold_ranges(deleted.iter().map(|ix| folds[*ix].clone()), false, true, cx);
+    vim.update_state(|state| {
+        let mut ix = 0;
+        state.manual_folds.retain(|_| {
+            ix += 1;
+            !deleted.contains(&(ix - 1))
+        });
+    });
+}
+
+/// Moves `count` times to the start of the next fold, as `zj`, or to the end of the previous
+/// fold, as `zk`. Folds that are open count too.
+fn move_to_fold(
+    vim: &Vim,
+    editor: &mut Editor,
+    count: usize,
+    forward: bool,
+    cx: &mut ViewContext<Editor>,
+) {
+    let map = editor.snapshot(cx).display_snapshot;
+    let snapshot = &map.buffer_snapshot;
+    let max_row = snapshot.max_point().row;
+    let manual = manual_folds(vim, &map).collect::<Vec<_>>();
+    let is_hidden = |row: u32| fold_start_before(&map, Point::new(row, 0)).is_some();
+
+    let Some(mut row) = cursor_rows(editor, cx).last().copied() else {
+        return;
+    };
+    for _ in 0..count {
+        let next = if forward {
+            (row + 1..=max_row).find(|row| {
+                !is_hidden(*row)
+                    && (map.is_foldable(MultiBufferRow(*row))
+                        || manual.iter().any(|range| range.start.row == *row))
+            })
+        } else {
+            (0..row)
+                .filter_map(|start_row| map.foldable_range(MultiBufferRow(start_row)))
+                .map(|range| range.end.row)
+                .chain(manual.iter().map(|range| range.end.row))
+                .filter(|end_row| *end_row < row && !is_hidden(*end_row))
+                .max()
+        };
+        match next {
+            Some(next) => row = next,
+            None => break,
+        }
+    }
+
+    let column = snapshot.indent_size_for_line(MultiBufferRow(row)).len;
+    let cursor = Point::new(row, column);
+    editor.change_selections(Some(Autoscroll::fit()), cx, |s| {
+        s.select_ranges([cursor..cursor])
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use multi_buffer::MultiBufferRow;
+
+    use crate::{state::Mode, test::VimTestContext};
+
+    fn is_folded(cx: &mut VimTestContext, row: u32) -> bool {
+        cx.update_editor(|editor, cx| {
+            editor
+                .snapshot(cx)
+                .display_snapshot
+                .is_line_folded(MultiBufferRow(row))
+        })
+    }
+
+    #[gpui::test]
+    async fn test_toggle_folds(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("fn a() {\n    ˇone\n    two\n}\nb", Mode::Normal);
+        cx.simulate_keystrokes("z c");
+        cx.assert_state("fn aˇ() {\n    one\n    two\n}\nb", Mode::Normal);
+        assert!(is_folded(&mut cx, 1));
+
+        cx.simulate_keystrokes("z o");
+        assert!(!is_folded(&mut cx, 1));
+
+        cx.simulate_keystrokes("z a");
+        assert!(is_folded(&mut cx, 1));
+        cx.simulate_keystrokes("z a");
+        assert!(!is_folded(&mut cx, 1));
+    }
+
+    #[gpui::test]
+    async fn test_manual_folds(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("a\nˇb\nc\nd", Mode::Normal);
+        cx.simulate_keystrokes("z f j");
+        cx.assert_state("a\nˇb\nc\nd", Mode::Normal);
+        assert!(is_folded(&mut cx, 1));
+
+        // A manual fold is remembered after it's opened.
+        cx.simulate_keystrokes("z o");
+        assert!(!is_folded(&mut cx, 1));
+        cx.simulate_keystrokes("j z c");
+        cx.assert_state("a\nˇb\nc\nd", Mode::Normal);
+        assert!(is_folded(&mut cx, 1));
+
+        cx.simulate_keystrokes("z d");
+        assert!(!is_folded(&mut cx, 1));
+        cx.simulate_keystrokes("z c");
+        assert!(!is_folded(&mut cx, 1));
+
+        cx.simulate_keystrokes("g g 3 z shift-f");
+        assert!(is_folded(&mut cx, 2));
+        assert!(!is_folded(&mut cx, 3));
+    }
+
+    #[gpui::test]
+    async fn test_move_to_folds(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state(
+            "ˇa\nfn b() {\n    c\n}\nd\nfn e() {\n    f\n}",
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("z j");
+        cx.assert_state(
+            "a\nˇfn b() {\n    c\n}\nd\nfn e() {\n    f\n}",
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("z j");
+        cx.assert_state(
+            "a\nfn b() {\n    c\n}\nd\nˇfn e() {\n    f\n}",
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("g g 2 z j");
+        cx.assert_state(
+            "a\nfn b() {\n    c\n}\nd\nˇfn e() {\n    f\n}",
+            Mode::Normal,
+        );
+    }
+}
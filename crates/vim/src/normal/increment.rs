@@ -1,6 +1,8 @@
-use std::ops::Range;
-
-use editor::{scroll::Autoscroll, MultiBufferSnapshot, ToOffset, ToPoint};
+use editor::{
+    increment::{add_to_number, find_number},
+    scroll::Autoscroll,
+    ToPoint,
+};
 use gpui::{impl_actions, ViewContext, WindowContext};
 use language::{Bias, Point};
 use serde::Deserialize;
@@ -30,7 +32,7 @@ pub fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
             vim.record_current_action(cx);
             let count = vim.take_count(cx).unwrap_or(1);
             let step = if action.step { 1 } else { 0 };
-            increment(vim, count as i32, step, cx)
+            increment(vim, count as i64, step, cx)
         })
     });
     workspace.register_action(|_: &mut Workspace, action: &Decrement, cx| {
@@ -38,12 +40,12 @@ pub fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
             vim.record_current_action(cx);
             let count = vim.take_count(cx).unwrap_or(1);
             let step = if action.step { -1 } else { 0 };
-            increment(vim, count as i32 * -1, step, cx)
+            increment(vim, count as i64 * -1, step, cx)
         })
     });
 }
 
-fn increment(vim: &mut Vim, mut delta: i32, step: i32, cx: &mut WindowContext) {
+fn increment(vim: &mut Vim, mut delta: i64, step: i64, cx: &mut WindowContext) {
     vim.update_active_editor(cx, |vim, editor, cx| {
         let mut edits = Vec::new();
         let mut new_anchors = Vec::new();
@@ -62,22 +64,9 @@ fn increment(vim: &mut Vim, mut delta: i32, step: i32, cx: &mut WindowContext) {
                     Point::new(row, 0)
                 };
 
-                if let Some((range, num, radix)) = find_number(&snapshot, start) {
-                    if let Ok(val) = i32::from_str_radix(&num, radix) {
-                        let result = val + delta;
+                if let Some((range, num, radix)) = find_number(&snapshot, start, true) {
+                    if let Some(replace) = add_to_number(&num, radix, delta) {
                         delta += step;
-                        let replace = match radix {
-                            10 => format!("{}", result),
-                            16 => {
-                                if num.to_ascii_lowercase() == num {
-                                    format!("{:x}", result)
-                                } else {
-                                    format!("{:X}", result)
-                                }
-                            }
-                            2 => format!("{:b}", result),
-                            _ => unreachable!(),
-                        };
                         edits.push((range.clone(), replace));
                     }
                     if selection.is_empty() {
@@ -111,76 +100,14 @@ fn increment(vim: &mut Vim, mut delta: i32, step: i32, cx: &mut WindowContext) {
     vim.switch_mode(Mode::Normal, true, cx)
 }
 
-fn find_number(
-    snapshot: &MultiBufferSnapshot,
-    start: Point,
-) -> Option<(Range<Point>, String, u32)> {
-    let mut offset = start.to_offset(snapshot);
-
-    let ch0 = snapshot.chars_at(offset).next();
-    if ch0.as_ref().is_some_and(char::is_ascii_digit) || matches!(ch0, Some('-' | 'b' | 'x')) {
-        // go backwards to the start of any number the selection is within
-        for ch in snapshot.reversed_chars_at(offset) {
-            if ch.is_ascii_digit() || ch == '-' || ch == 'b' || ch == 'x' {
-                offset -= ch.len_utf8();
-                continue;
-            }
-            break;
-        }
-    }
-
-    let mut begin = None;
-    let mut end = None;
-    let mut num = String::new();
-    let mut radix = 10;
-
-    let mut chars = snapshot.chars_at(offset).peekable();
-    // find the next number on the line (may start after the original cursor position)
-    while let Some(ch) = chars.next() {
-        if num == "0" && ch == 'b' && chars.peek().is_some() && chars.peek().unwrap().is_digit(2) {
-            radix = 2;
-            begin = None;
-            num = String::new();
-        }
-        if num == "0" && ch == 'x' && chars.peek().is_some() && chars.peek().unwrap().is_digit(16) {
-            radix = 16;
-            begin = None;
-            num = String::new();
-        }
-
-        if ch.is_digit(radix)
-            || (begin.is_none()
-                && ch == '-'
-                && chars.peek().is_some()
-                && chars.peek().unwrap().is_digit(radix))
-        {
-            if begin.is_none() {
-                begin = Some(offset);
-            }
-            num.push(ch);
-        } else {
-            if begin.is_some() {
-                end = Some(offset);
-                break;
-            } else if ch == '\n' {
-                break;
-            }
-        }
-        offset += ch.len_utf8();
-    }
-    if let Some(begin) = begin {
-        let end = end.unwrap_or(offset);
-        Some((begin.to_point(snapshot)..end.to_point(snapshot), num, radix))
-    } else {
-        None
-    }
-}
-
 #[cfg(test)]
 mod test {
     use indoc::indoc;
 
-    use crate::test::NeovimBackedTestContext;
+    use crate::{
+        state::Mode,
+        test::{NeovimBackedTestContext, VimTestContext},
+    };
 
     #[gpui::test]
     async fn test_increment(cx: &mut gpui::TestAppContext) {
@@ -270,6 +197,23 @@ mod test {
         cx.simulate("ctrl-a", "banˇana").await.assert_matches();
     }
 
+    #[gpui::test]
+    async fn test_increment_octal_and_wide_numbers(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇ0o17", Mode::Normal);
+        cx.simulate_keystrokes("ctrl-a");
+        cx.assert_state("0o2ˇ0", Mode::Normal);
+
+        cx.set_state("ˇ4294967295", Mode::Normal);
+        cx.simulate_keystrokes("ctrl-a");
+        cx.assert_state("429496729ˇ6", Mode::Normal);
+
+        cx.set_state("ˇ0x00ff", Mode::Normal);
+        cx.simulate_keystrokes("1 0 ctrl-x");
+        cx.assert_state("0x00fˇ5", Mode::Normal);
+    }
+
     #[gpui::test]
     async fn test_increment_steps(cx: &mut gpui::TestAppContext) {
         let mut cx = NeovimBackedTestContext::new(cx).await;
@@ -1,20 +1,39 @@
 use std::{ops::Range, sync::Arc};
 
+use anyhow::Result;
+use collections::HashMap;
 use editor::{
     display_map::{DisplaySnapshot, ToDisplayPoint},
     movement,
     scroll::Autoscroll,
-    Anchor, Bias, DisplayPoint,
+    Anchor, Bias, DisplayPoint, Editor,
 };
-use gpui::WindowContext;
-use language::SelectionGoal;
+use gpui::{AppContext, Model, Task, View, WindowContext};
+use language::{Buffer, Point, SelectionGoal, ToPoint as _};
+use util::ResultExt;
+use workspace::Workspace;
 
 use crate::{
     motion::{self, Motion},
+    persistence::DB,
+    state::GlobalMark,
     Vim,
 };
 
+/// Marks named by uppercase letters are global, and can be jumped to from any file.
+fn global_mark_name(text: &str) -> Option<char> {
+    let mut chars = text.chars();
+    match (chars.next(), chars.next()) {
+        (Some(name), None) if name.is_ascii_uppercase() => Some(name),
+        _ => None,
+    }
+}
+
 pub fn create_mark(vim: &mut Vim, text: Arc<str>, tail: bool, cx: &mut WindowContext) {
+    if let Some(name) = global_mark_name(&text) {
+        create_global_mark(vim, name, cx);
+        return;
+    }
     let Some(anchors) = vim.update_active_editor(cx, |_, editor, _| {
         editor
             .selections
@@ -29,6 +48,90 @@ pub fn create_mark(vim: &mut Vim, text: Arc<str>, tail: bool, cx: &mut WindowCon
     vim.clear_operator(cx);
 }
 
+fn create_global_mark(vim: &mut Vim, name: char, cx: &mut WindowContext) {
+    let mark = vim
+        .update_active_editor(cx, |_, editor, cx| {
+            let head = editor.selections.newest_anchor().head();
+            global_mark_at(editor, head, cx)
+        })
+        .flatten();
+    if let Some(mark) = mark {
+        save_global_mark(name, &mark, cx);
+        vim.workspace_state.global_marks.insert(name, mark);
+    }
+    vim.clear_operator(cx);
+}
+
+/// A global mark at a position in an editor, if the position is in a file on disk.
+pub(crate) fn global_mark_at(
+    editor: &Editor,
+    position: Anchor,
+    cx: &AppContext,
+) -> Option<GlobalMark> {
+    let (buffer, anchor) = editor
+        .buffer()
+        .read(cx)
+        .text_anchor_for_position(position, cx)?;
+    let path = buffer.read(cx).file()?.as_local()?.abs_path(cx);
+    let point = anchor.to_point(&buffer.read(cx));
+    Some(GlobalMark {
+        path: path.into(),
+        anchor: Some((buffer, anchor)),
+        point,
+    })
+}
+
+fn save_global_mark(name: char, mark: &GlobalMark, cx: &AppContext) {
+    let path = mark.path.to_path_buf();
+    let point = mark.point(cx);
+    cx.background_executor()
+        .spawn(DB.save_global_mark(name.to_string(), path, point.row, point.column))
+        .detach_and_log_err(cx);
+}
+
+pub(crate) fn load_global_marks() -> HashMap<char, GlobalMark> {
+    DB.global_marks()
+        .log_err()
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|(name, path, row, column)| {
+            let mark = GlobalMark {
+                path: path.into(),
+                anchor: None,
+                point: Point::new(row, column),
+            };
+            Some((global_mark_name(&name)?, mark))
+        })
+        .collect()
+}
+
+/// Saves where global marks have moved to with the edits made since they were set.
+pub(crate) fn save_global_marks_on_quit(cx: &mut AppContext) {
+    cx.on_app_quit(|cx| {
+        let saves = cx
+            .global::<Vim>()
+            .workspace_state
+            .global_marks
+            .iter()
+            .map(|(name, mark)| {
+                let point = mark.point(cx);
+                DB.save_global_mark(
+                    name.to_string(),
+                    mark.path.to_path_buf(),
+                    point.row,
+                    point.column,
+                )
+            })
+            .collect::<Vec<_>>();
+        async move {
+            for save in saves {
+                save.await.log_err();
+            }
+        }
+    })
+    .detach();
+}
+
 pub fn create_mark_after(vim: &mut Vim, text: Arc<str>, cx: &mut WindowContext) {
     let Some(anchors) = vim.update_active_editor(cx, |_, editor, cx| {
         let (map, selections) = editor.selections.all_display(cx);
@@ -68,6 +171,11 @@ pub fn create_mark_before(vim: &mut Vim, text: Arc<str>, cx: &mut WindowContext)
 }
 
 pub fn jump(text: Arc<str>, line: bool, cx: &mut WindowContext) {
+    if let Some(name) = global_mark_name(&text) {
+        jump_to_global_mark(name, line, cx);
+        return;
+    }
+
     let anchors = Vim::update(cx, |vim, cx| {
         vim.pop_operator(cx);
 
@@ -93,7 +201,106 @@ pub fn jump(text: Arc<str>, line: bool, cx: &mut WindowContext) {
     });
 
     let Some(anchors) = anchors else { return };
+    jump_to_anchors(anchors, line, cx);
+}
+
+fn jump_to_global_mark(name: char, line: bool, cx: &mut WindowContext) {
+    let Some((mark, anchor, editor)) = Vim::update(cx, |vim, cx| {
+        vim.pop_operator(cx);
+        let mark = vim.workspace_state.global_marks.get(&name).cloned()?;
+        let editor = vim.active_editor.clone()?.upgrade()?;
+        let anchor = anchor_in_editor(&mark, editor.read(cx), cx);
+        Some((mark, anchor, editor))
+    }) else {
+        return;
+    };
+
+    if let Some(anchor) = anchor {
+        jump_to_anchors(vec![anchor], line, cx);
+        return;
+    }
+
+    // Operators can't apply across files.
+    if Vim::read(cx).state().active_operator().is_some() {
+        Vim::update(cx, |vim, cx| vim.clear_operator(cx));
+        return;
+    }
+    let Some(workspace) = editor.read(cx).workspace() else {
+        return;
+    };
+    Vim::update(cx, |vim, cx| vim.push_active_editor_to_jump_list(None, cx));
+    let open = open_global_mark(&mark, line, workspace, cx);
+    cx.spawn(|mut cx| async move {
+        // Follow edits to the file from now on.
+        if let Some(anchor) = open.await? {
+            cx.update(|cx| {
+                Vim::update(cx, |vim, _| {
+                    if let Some(mark) = vim.workspace_state.global_marks.get_mut(&name) {
+                        mark.anchor = Some(anchor);
+                    }
+                })
+            })?;
+        }
+        anyhow::Ok(())
+    })
+    .detach_and_log_err(cx);
+}
+
+/// Where a global mark is in an editor, if the editor is editing the mark's buffer.
+pub(crate) fn anchor_in_editor(
+    mark: &GlobalMark,
+    editor: &Editor,
+    cx: &AppContext,
+) -> Option<Anchor> {
+    let (buffer, text_anchor) = mark.anchor.as_ref()?;
+    let snapshot = editor.buffer().read(cx).snapshot(cx);
+    let (excerpt_id, buffer_id, _) = snapshot.as_singleton()?;
+    if buffer_id != buffer.read(cx).remote_id() {
+        return None;
+    }
+    snapshot.anchor_in_excerpt(*excerpt_id, *text_anchor)
+}
+
+/// Opens a global mark's file and moves the cursor to the mark, resolving to the anchor that
+/// tracks the mark's position in the file's buffer.
+pub(crate) fn open_global_mark(
+    mark: &GlobalMark,
+    line: bool,
+    workspace: View<Workspace>,
+    cx: &mut WindowContext,
+) -> Task<Result<Option<(Model<Buffer>, language::Anchor)>>> {
+    let point = mark.point(cx);
+    let open = workspace.update(cx, |workspace, cx| {
+        workspace.open_abs_path(mark.path.to_path_buf(), true, cx)
+    });
+    cx.spawn(|mut cx| async move {
+        let Some(editor) = open.await?.downcast::<Editor>() else {
+            return Ok(None);
+        };
+        cx.update(|cx| {
+            editor.update(cx, |editor, cx| {
+                let buffer = editor.buffer().read(cx).as_singleton()?;
+                let anchor = buffer
+                    .read(cx)
+                    .anchor_before(buffer.read(cx).clip_point(point, Bias::Left));
+                let map = editor.snapshot(cx).display_snapshot;
+                let mut display_point = map
+                    .buffer_snapshot
+                    .clip_point(point, Bias::Left)
+                    .to_display_point(&map);
+                if line {
+                    display_point = motion::first_non_whitespace(&map, false, display_point);
+                }
+                editor.change_selections(Some(Autoscroll::center()), cx, |s| {
+                    s.select_display_ranges([display_point..display_point])
+                });
+                Some((buffer, anchor))
+            })
+        })
+    })
+}
 
+fn jump_to_anchors(anchors: Vec<Anchor>, line: bool, cx: &mut WindowContext) {
     let is_active_operator = Vim::read(cx).state().active_operator().is_some();
     if is_active_operator {
         if let Some(anchor) = anchors.last() {
@@ -108,6 +315,7 @@ pub fn jump(text: Arc<str>, line: bool, cx: &mut WindowContext) {
         return;
     } else {
         Vim::update(cx, |vim, cx| {
+            vim.push_active_editor_to_jump_list(None, cx);
             vim.update_active_editor(cx, |_, editor, cx| {
                 let map = editor.snapshot(cx);
                 let mut ranges: Vec<Range<Anchor>> = Vec::new();
@@ -12,9 +12,9 @@ use workspace::Workspace;
 
 use crate::{state::Mode, utils::copy_selections_content, UseSystemClipboard, Vim, VimSettings};
 
-#[derive(Clone, Deserialize, PartialEq)]
+#[derive(Clone, Default, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
-struct Paste {
+pub(crate) struct Paste {
     #[serde(default)]
     before: bool,
     #[serde(default)]
@@ -37,27 +37,31 @@ fn system_clipboard_is_newer(vim: &Vim, cx: &mut AppContext) -> bool {
     })
 }
 
-fn paste(_: &mut Workspace, action: &Paste, cx: &mut ViewContext<Workspace>) {
+pub(crate) fn paste(_: &mut Workspace, action: &Paste, cx: &mut ViewContext<Workspace>) {
     Vim::update(cx, |vim, cx| {
         vim.record_current_action(cx);
         let count = vim.take_count(cx).unwrap_or(1);
+        let selected_register = vim.workspace_state.selected_register.take();
         vim.update_active_editor(cx, |vim, editor, cx| {
             let text_layout_details = editor.text_layout_details(cx);
             editor.transact(cx, |editor, cx| {
                 editor.set_clip_at_line_ends(false, cx);
 
+                let read_from_clipboard = match selected_register {
+                    Some('+' | '*') => true,
+                    Some(_) => false,
+                    None => {
+                        let setting = VimSettings::get_global(cx).use_system_clipboard;
+                        setting == UseSystemClipboard::Always
+                            || setting == UseSystemClipboard::OnYank
+                                && system_clipboard_is_newer(vim, cx)
+                    }
+                };
                 let (clipboard_text, clipboard_selections): (String, Option<_>) =
-                    if VimSettings::get_global(cx).use_system_clipboard == UseSystemClipboard::Never
-                        || VimSettings::get_global(cx).use_system_clipboard
-                            == UseSystemClipboard::OnYank
-                            && !system_clipboard_is_newer(vim, cx)
-                    {
+                    if !read_from_clipboard {
                         (
-                            vim.workspace_state
-                                .registers
-                                .get("\"")
-                                .cloned()
-                                .unwrap_or_else(|| "".to_string()),
+                            vim.read_register(selected_register.unwrap_or('"'), cx)
+                                .unwrap_or_default(),
                             None,
                         )
                     } else {
@@ -232,9 +236,13 @@ mod test {
         test::{NeovimBackedTestContext, VimTestContext},
         UseSystemClipboard, VimSettings,
     };
+    use editor::EditorSettings;
     use gpui::ClipboardItem;
     use indoc::indoc;
-    use settings::SettingsStore;
+    use language::{Language, LanguageConfig};
+    use project::WorktreeSettings;
+    use settings::{Settings, SettingsStore};
+    use std::sync::Arc;
 
     #[gpui::test]
     async fn test_paste(cx: &mut gpui::TestAppContext) {
@@ -413,6 +421,77 @@ mod test {
         );
     }
 
+    #[gpui::test]
+    async fn test_yank_redacted_values(cx: &mut gpui::TestAppContext) {
+        VimTestContext::init(cx);
+        // Files are private depending on the settings when their worktree is scanned.
+        cx.update(|cx| {
+            WorktreeSettings::register(cx);
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings::<WorktreeSettings>(cx, |s| {
+                    s.private_files = Some(vec!["**/*.rs".into()])
+                });
+            });
+        });
+        let mut cx = VimTestContext::new(cx, true).await;
+        cx.update_global(|store: &mut SettingsStore, cx| {
+            store.update_user_settings::<VimSettings>(cx, |s| {
+                s.use_system_clipboard = Some(UseSystemClipboard::Always)
+            });
+            store.update_user_settings::<EditorSettings>(cx, |s| {
+                s.redact_private_values = Some(true)
+            });
+        });
+        let language = Language::new(
+            LanguageConfig {
+                name: "Rust".into(),
+                ..Default::default()
+            },
+            Some(tree_sitter_rust::language()),
+        )
+        .with_redaction_query("(string_literal) @redact")
+        .unwrap();
+        cx.update_buffer(|buffer, cx| buffer.set_language(Some(Arc::new(language)), cx));
+        cx.set_state(
+            indoc! {r#"
+                let ˇtoken = "secret";
+                let name = 1;
+                done"#},
+            Mode::Normal,
+        );
+        cx.run_until_parked();
+
+        // Yanking a redacted value asks before putting it on the clipboard.
+        cx.simulate_keystrokes("y y");
+        assert!(cx.has_pending_prompt());
+        cx.simulate_prompt_answer(1);
+        cx.run_until_parked();
+        assert_eq!(cx.read_from_clipboard(), None);
+
+        // The line is deleted right away, and copied once that's confirmed.
+        cx.simulate_keystrokes("d d");
+        cx.assert_state(
+            indoc! {"
+                ˇlet name = 1;
+                done"},
+            Mode::Normal,
+        );
+        assert!(cx.has_pending_prompt());
+        cx.simulate_prompt_answer(0);
+        cx.run_until_parked();
+        assert_eq!(
+            cx.read_from_clipboard().map(|item| item.text().clone()),
+            Some("let token = \"secret\";\n".into())
+        );
+
+        cx.simulate_keystrokes("y y");
+        assert!(!cx.has_pending_prompt());
+        assert_eq!(
+            cx.read_from_clipboard().map(|item| item.text().clone()),
+            Some("let name = 1;\n".into())
+        );
+    }
+
     #[gpui::test]
     async fn test_paste_visual(cx: &mut gpui::TestAppContext) {
         let mut cx = NeovimBackedTestContext::new(cx).await;
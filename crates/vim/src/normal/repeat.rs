@@ -52,8 +52,13 @@ pub(crate) fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>
 
 pub(crate) fn repeat(cx: &mut WindowContext, from_insert_mode: bool) {
     let Some((mut actions, editor, selection)) = Vim::update(cx, |vim, cx| {
-        let actions = vim.workspace_state.recorded_actions.clone();
-        if actions.is_empty() {
+        // An operator that was started but never completed isn't part of the change.
+        if !from_insert_mode {
+            vim.cancel_recording();
+        }
+
+        let change = vim.workspace_state.last_change.clone();
+        if change.actions.is_empty() {
             return None;
         }
 
@@ -62,28 +67,30 @@ pub(crate) fn repeat(cx: &mut WindowContext, from_insert_mode: bool) {
         };
         let count = vim.take_count(cx);
 
-        let selection = vim.workspace_state.recorded_selection.clone();
-        match selection {
+        match change.selection {
             RecordedSelection::SingleLine { .. } | RecordedSelection::Visual { .. } => {
-                vim.workspace_state.recorded_count = None;
+                vim.workspace_state.replayed_count = None;
                 vim.switch_mode(Mode::Visual, false, cx)
             }
             RecordedSelection::VisualLine { .. } => {
-                vim.workspace_state.recorded_count = None;
+                vim.workspace_state.replayed_count = None;
                 vim.switch_mode(Mode::VisualLine, false, cx)
             }
             RecordedSelection::VisualBlock { .. } => {
-                vim.workspace_state.recorded_count = None;
+                vim.workspace_state.replayed_count = None;
                 vim.switch_mode(Mode::VisualBlock, false, cx)
             }
             RecordedSelection::None => {
+                // A count given to `.` replaces the change's count when it's repeated again.
                 if let Some(count) = count {
-                    vim.workspace_state.recorded_count = Some(count);
+                    vim.workspace_state.last_change.count = Some(count);
                 }
+                vim.workspace_state.replayed_count = count.or(change.count);
             }
         }
+        vim.workspace_state.selected_register = change.register;
 
-        Some((actions, editor, selection))
+        Some((change.actions, editor, change.selection))
     }) else {
         return;
     };
@@ -150,7 +157,7 @@ pub(crate) fn repeat(cx: &mut WindowContext, from_insert_mode: bool) {
         let mut new_actions = actions.clone();
         actions[0] = ReplayableAction::Action(to_repeat.boxed_clone());
 
-        let mut count = Vim::read(cx).workspace_state.recorded_count.unwrap_or(1);
+        let mut count = Vim::read(cx).workspace_state.replayed_count.unwrap_or(1);
 
         // if we came from insert mode we're just doing repetitions 2 onwards.
         if from_insert_mode {
@@ -497,4 +504,51 @@ mod test {
         cx.simulate_shared_keystrokes(".").await;
         cx.shared_state().await.assert_eq("ˇx hello\n");
     }
+
+    #[gpui::test]
+    async fn test_repeat_after_cancelled_operator(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇone two three", Mode::Normal);
+        cx.simulate_keystrokes("d w");
+        cx.assert_state("ˇtwo three", Mode::Normal);
+        cx.simulate_keystrokes("c escape");
+        cx.simulate_keystrokes(".");
+        cx.assert_state("ˇthree", Mode::Normal);
+    }
+
+    #[gpui::test]
+    async fn test_repeat_insert_after_cursor_movement(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇone\ntwo", Mode::Normal);
+        cx.simulate_keystrokes("i a b left c escape");
+        cx.assert_state("aˇcbone\ntwo", Mode::Normal);
+        // Only the text inserted after moving is repeated.
+        cx.simulate_keystrokes("j .");
+        cx.assert_state("acbone\ntˇcwo", Mode::Normal);
+    }
+
+    #[gpui::test]
+    async fn test_repeat_replace_mode(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇabc def", Mode::Normal);
+        cx.simulate_keystrokes("shift-r x y escape");
+        cx.assert_state("xˇyc def", Mode::Normal);
+        cx.simulate_keystrokes("w .");
+        cx.assert_state("xyc xˇyf", Mode::Normal);
+    }
+
+    #[gpui::test]
+    async fn test_repeat_with_register(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇab", Mode::Normal);
+        cx.simulate_keystrokes("\" a y l l y l");
+        cx.simulate_keystrokes("\" a p");
+        cx.assert_state("abˇa", Mode::Normal);
+        cx.simulate_keystrokes(".");
+        cx.assert_state("abaˇa", Mode::Normal);
+    }
 }
@@ -1,17 +1,20 @@
-use std::{ops::Range, sync::OnceLock, time::Duration};
+use std::ops::Range;
 
-use gpui::{actions, impl_actions, ViewContext};
-use language::Point;
-use regex::Regex;
+use editor::{scroll::Autoscroll, Anchor, Editor, MultiBufferSnapshot};
+use gpui::{actions, impl_actions, AppContext, ViewContext, WindowContext};
+use language::{Point, ToOffset as _, ToPoint as _};
+use multi_buffer::{AnchorRangeExt as _, MultiBufferRow};
+use regex::{Regex, RegexBuilder};
 use search::{buffer_search, BufferSearchBar, SearchOptions};
 use serde_derive::Deserialize;
+use settings::Settings;
 use workspace::{searchable::Direction, Workspace};
 
 use crate::{
+    command::{parse_range, CommandRange},
     motion::{search_motion, Motion},
-    normal::move_cursor,
-    state::{Mode, SearchState},
-    Vim,
+    state::{Mode, SearchOffset, SearchState},
+    Vim, VimSettings,
 };
 
 #[derive(Clone, Deserialize, PartialEq)]
@@ -45,13 +48,20 @@ pub struct ReplaceCommand {
     pub query: String,
 }
 
-#[derive(Debug, Default)]
+/// A `:s/pattern/replacement/flags` command, with the pattern and replacement converted to the
+/// syntax of the `regex` crate.
+#[derive(Debug)]
 struct Replacement {
     search: String,
     replacement: String,
-    should_replace_all: bool,
+    range: CommandRange,
+    /// Whether every match on a line is replaced, rather than only the first.
+    global: bool,
     is_case_sensitive: bool,
-    range: Option<Range<usize>>,
+    /// Whether to ask before replacing each match.
+    confirm: bool,
+    /// Whether to count the matches rather than replacing them.
+    count_only: bool,
 }
 
 actions!(vim, [SearchSubmit, MoveToNextMatch, MoveToPrevMatch]);
@@ -60,11 +70,6 @@ impl_actions!(
     [FindCommand, ReplaceCommand, Search, MoveToPrev, MoveToNext]
 );
 
-static RANGE_REGEX: OnceLock<Regex> = OnceLock::new();
-pub(crate) fn range_regex() -> &'static Regex {
-    RANGE_REGEX.get_or_init(|| Regex::new(r"^(\d+),(\d+)s(.*)").unwrap())
-}
-
 pub(crate) fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
     workspace.register_action(move_to_next);
     workspace.register_action(move_to_prev);
@@ -134,6 +139,7 @@ fn search(workspace: &mut Workspace, action: &Search, cx: &mut ViewContext<Works
                         prior_selections,
                         prior_operator: vim.active_operator(),
                         prior_mode: vim.state().mode,
+                        offset: vim.workspace_state.search.offset,
                     };
                 });
             }
@@ -148,6 +154,55 @@ fn search_deploy(_: &mut Workspace, _: &buffer_search::Deploy, cx: &mut ViewCont
 }
 
 fn search_submit(workspace: &mut Workspace, _: &SearchSubmit, cx: &mut ViewContext<Workspace>) {
+    let pane = workspace.active_pane().clone();
+    let Some(search_bar) = pane
+        .read(cx)
+        .toolbar()
+        .read(cx)
+        .item_of_type::<BufferSearchBar>()
+    else {
+        return;
+    };
+
+    // A query with an offset, as `/pattern/e`, is searched for again without it.
+    let search = Vim::update(cx, |vim, cx| {
+        search_bar.update(cx, |search_bar, cx| {
+            let state = &mut vim.workspace_state.search;
+            let delimiter = match state.direction {
+                Direction::Next => '/',
+                Direction::Prev => '?',
+            };
+            let query = search_bar.query(cx);
+            let Some((pattern, offset)) = split_search_offset(&query, delimiter) else {
+                // Searching again for the last query, as `/<enter>` does, keeps its offset.
+                if query != state.initial_query {
+                    state.offset = None;
+                }
+                return None;
+            };
+            state.offset = offset;
+            let pattern = if pattern.is_empty() {
+                state.initial_query.clone()
+            } else {
+                pattern.to_string()
+            };
+            let options = search_bar.search_options();
+            Some(search_bar.search(&pattern, Some(options), cx))
+        })
+    });
+
+    match search {
+        Some(search) => cx
+            .spawn(|workspace, mut cx| async move {
+                search.await?;
+                workspace.update(&mut cx, |workspace, cx| finish_search_submit(workspace, cx))
+            })
+            .detach_and_log_err(cx),
+        None => finish_search_submit(workspace, cx),
+    }
+}
+
+fn finish_search_submit(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
     let mut motion = None;
     Vim::update(cx, |vim, cx| {
         let pane = workspace.active_pane().clone();
@@ -173,6 +228,7 @@ fn search_submit(workspace: &mut Workspace, _: &SearchSubmit, cx: &mut ViewConte
                     let prior_mode = state.prior_mode;
                     let prior_operator = state.prior_operator.take();
                     let new_selections = vim.editor_selections(cx);
+                    let new_selections = apply_search_offset(vim, new_selections, cx);
 
                     // If the active editor has changed during a search, don't panic.
                     if prior_selections.iter().any(|s| {
@@ -224,6 +280,7 @@ pub fn move_to_match_internal(
                     search_bar.select_match(direction, count, cx);
 
                     let new_selections = vim.editor_selections(cx);
+                    let new_selections = apply_search_offset(vim, new_selections, cx);
                     motion = Some(Motion::ZedSearchResult {
                         prior_selections,
                         new_selections,
@@ -247,11 +304,11 @@ pub fn move_to_internal(
         let pane = workspace.active_pane().clone();
         let count = vim.take_count(cx).unwrap_or(1);
         let prior_selections = vim.editor_selections(cx);
+        vim.workspace_state.search.offset = None;
 
         pane.update(cx, |pane, cx| {
             if let Some(search_bar) = pane.toolbar().read(cx).item_of_type::<BufferSearchBar>() {
                 let search = search_bar.update(cx, |search_bar, cx| {
-                    let options = SearchOptions::CASE_SENSITIVE | SearchOptions::REGEX;
                     if !search_bar.show(cx) {
                         return None;
                     }
@@ -260,6 +317,12 @@ pub fn move_to_internal(
                         let _ = search_bar.search("", None, cx);
                         return None;
                     };
+                    let mut options = SearchOptions::REGEX;
+                    if !VimSettings::get_global(cx).use_smartcase_star_search
+                        || query.chars().any(char::is_uppercase)
+                    {
+                        options |= SearchOptions::CASE_SENSITIVE;
+                    }
                     let mut query = regex::escape(&query);
                     if whole_word {
                         query = format!(r"\<{}\>", query);
@@ -340,174 +403,371 @@ fn replace_command(
     action: &ReplaceCommand,
     cx: &mut ViewContext<Workspace>,
 ) {
-    let replacement = parse_replace_all(&action.query);
+    let Some(replacement) = parse_replace_all(&action.query) else {
+        return;
+    };
     let pane = workspace.active_pane().clone();
-    let mut editor = Vim::read(cx)
-        .active_editor
-        .as_ref()
-        .and_then(|editor| editor.upgrade());
-    if let Some(range) = &replacement.range {
-        if let Some(editor) = editor.as_mut() {
-            editor.update(cx, |editor, cx| {
-                let snapshot = &editor.snapshot(cx).buffer_snapshot;
-                let range = snapshot
-                    .anchor_before(Point::new(range.start.saturating_sub(1) as u32, 0))
-                    ..snapshot.anchor_before(Point::new(range.end as u32, 0));
-
-                editor.set_search_within_ranges(&[range], cx)
-            })
-        }
-    }
-    pane.update(cx, |pane, cx| {
-        let Some(search_bar) = pane.toolbar().read(cx).item_of_type::<BufferSearchBar>() else {
+    let search_bar = pane
+        .read(cx)
+        .toolbar()
+        .read(cx)
+        .item_of_type::<BufferSearchBar>();
+    // An empty pattern repeats the last search.
+    let search = if replacement.search.is_empty() {
+        let Some(search_bar) = &search_bar else {
             return;
         };
-        let search = search_bar.update(cx, |search_bar, cx| {
+        search_bar.read(cx).query(cx)
+    } else {
+        replacement.search.clone()
+    };
+
+    // With the `n` flag, the matches are only counted, which the search bar shows.
+    if replacement.count_only {
+        let Some(search_bar) = search_bar else {
+            return;
+        };
+        search_bar.update(cx, |search_bar, cx| {
             if !search_bar.show(cx) {
-                return None;
+                return;
             }
-
             let mut options = SearchOptions::REGEX;
-            if replacement.is_case_sensitive {
-                options.set(SearchOptions::CASE_SENSITIVE, true)
-            }
-            let search = if replacement.search == "" {
-                search_bar.query(cx)
-            } else {
-                replacement.search
-            };
+            options.set(SearchOptions::CASE_SENSITIVE, replacement.is_case_sensitive);
+            search_bar
+                .search(&search, Some(options), cx)
+                .detach_and_log_err(cx);
+        });
+        return;
+    }
 
-            search_bar.set_replacement(Some(&replacement.replacement), cx);
-            Some(search_bar.search(&search, Some(options), cx))
+    let Ok(regex) = RegexBuilder::new(&search)
+        .case_insensitive(!replacement.is_case_sensitive)
+        .multi_line(true)
+        .build()
+    else {
+        return;
+    };
+    Vim::update(cx, |vim, cx| {
+        let Some(matches) = vim
+            .update_active_editor(cx, |vim, editor, cx| {
+                let range = replacement.range.buffer_range(vim, editor, cx)?;
+                Some(substitution_matches(
+                    editor,
+                    &regex,
+                    &replacement,
+                    range,
+                    cx,
+                ))
+            })
+            .flatten()
+        else {
+            return;
+        };
+        if matches.is_empty() {
+            return;
+        }
+        vim.switch_mode(Mode::Normal, true, cx);
+        if replacement.confirm {
+            vim.start_confirm_replace(matches, cx);
+            return;
+        }
+        vim.update_active_editor(cx, |_, editor, cx| {
+            let last_row = {
+                let snapshot = editor.buffer().read(cx).snapshot(cx);
+                matches
+                    .last()
+                    .map(|(range, _)| range.start.to_point(&snapshot).row)
+            };
+            editor.transact(cx, |editor, cx| editor.edit(matches, cx));
+            if let Some(row) = last_row {
+                move_to_first_non_whitespace(editor, row, cx);
+            }
         });
-        let Some(search) = search else { return };
-        let search_bar = search_bar.downgrade();
-        cx.spawn(|_, mut cx| async move {
-            search.await?;
-            search_bar.update(&mut cx, |search_bar, cx| {
-                if replacement.should_replace_all {
-                    search_bar.select_last_match(cx);
-                    search_bar.replace_all(&Default::default(), cx);
-                    if let Some(editor) = editor {
-                        cx.spawn(|_, mut cx| async move {
-                            cx.background_executor()
-                                .timer(Duration::from_millis(200))
-                                .await;
-                            editor
-                                .update(&mut cx, |editor, cx| {
-                                    editor.set_search_within_ranges(&[], cx)
-                                })
-                                .ok();
-                        })
-                        .detach();
+    })
+}
+
+/// Runs a `:s` command on the lines of its range in an editor, such as for each line matched by
+/// a `:g` command, returning the row of the last replacement. An empty pattern is replaced by
+/// `default_pattern`, and the `c` and `n` flags are ignored.
+pub(crate) fn substitute(
+    vim: &Vim,
+    editor: &mut Editor,
+    query: &str,
+    default_pattern: &str,
+    cx: &mut ViewContext<Editor>,
+) -> Option<u32> {
+    let replacement = parse_replace_all(query)?;
+    let search = if replacement.search.is_empty() {
+        default_pattern
+    } else {
+        &replacement.search
+    };
+    let regex = RegexBuilder::new(search)
+        .case_insensitive(!replacement.is_case_sensitive)
+        .multi_line(true)
+        .build()
+        .ok()?;
+    let range = replacement.range.buffer_range(vim, editor, cx)?;
+    let matches = substitution_matches(editor, &regex, &replacement, range, cx);
+    let snapshot = editor.buffer().read(cx).snapshot(cx);
+    let last_row = matches.last()?.0.start.to_point(&snapshot).row;
+    editor.edit(matches, cx);
+    Some(last_row)
+}
+
+/// Moves the cursor to the first non-whitespace character of a row, where it's left after
+/// replacing text on that row.
+/// Splits a search query into its pattern and the offset after the first unescaped delimiter, as
+/// in `/pattern/e+1`. Returns `None` when the query has no offset, or one that can't be parsed.
+fn split_search_offset(query: &str, delimiter: char) -> Option<(&str, Option<SearchOffset>)> {
+    let mut escaped = false;
+    for (ix, ch) in query.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if ch == '\\' {
+            escaped = true;
+        } else if ch == delimiter {
+            let (pattern, offset) = (&query[..ix], &query[ix + 1..]);
+            if offset.is_empty() {
+                return Some((pattern, None));
+            }
+            return parse_search_offset(offset).map(|offset| (pattern, Some(offset)));
+        }
+    }
+    None
+}
+
+/// Parses a search offset: `[+-]N` lines, `e[+-N]` characters from the end of the match, or
+/// `s[+-N]` or `b[+-N]` characters from its start. A sign without a number means one.
+fn parse_search_offset(offset: &str) -> Option<SearchOffset> {
+    let (kind, number): (fn(i64) -> SearchOffset, _) = match offset.chars().next()? {
+        'e' => (SearchOffset::End, &offset[1..]),
+        's' | 'b' => (SearchOffset::Start, &offset[1..]),
+        _ => (SearchOffset::Lines, offset),
+    };
+    let delta = match number {
+        "" => 0,
+        "+" => 1,
+        "-" => -1,
+        number => number.parse().ok()?,
+    };
+    Some(kind(delta))
+}
+
+/// Moves the cursors from the start of the matches they're at as the last search's offset says.
+/// A cursor moved relative to the end of a match also selects the character it's on, so that an
+/// operator includes it, as the `e` offset is inclusive in vim.
+fn apply_search_offset(
+    vim: &mut Vim,
+    selections: Vec<Range<Anchor>>,
+    cx: &mut WindowContext,
+) -> Vec<Range<Anchor>> {
+    let Some(offset) = vim.workspace_state.search.offset else {
+        return selections;
+    };
+    vim.update_active_editor(cx, |_, editor, cx| {
+        let snapshot = editor.buffer().read(cx).snapshot(cx);
+        selections
+            .iter()
+            .map(|selection| {
+                let range = editor
+                    .search_match_starting_at(selection.start, cx)
+                    .unwrap_or_else(|| selection.start..selection.start)
+                    .to_offset(&snapshot);
+                match offset {
+                    SearchOffset::Lines(lines) => {
+                        let row = snapshot.offset_to_point(range.start).row as i64 + lines;
+                        let row = row.clamp(0, snapshot.max_point().row as i64) as u32;
+                        let column = snapshot.indent_size_for_line(MultiBufferRow(row)).len;
+                        let anchor = snapshot.anchor_before(Point::new(row, column));
+                        anchor..anchor
+                    }
+                    SearchOffset::Start(chars) => {
+                        let anchor =
+                            snapshot.anchor_before(offset_by_chars(&snapshot, range.start, chars));
+                        anchor..anchor
+                    }
+                    SearchOffset::End(chars) => {
+                        let last = offset_by_chars(&snapshot, range.end, chars - 1);
+                        snapshot.anchor_before(last)
+                            ..snapshot.anchor_before(offset_by_chars(&snapshot, last, 1))
                     }
-                    Vim::update(cx, |vim, cx| {
-                        move_cursor(
-                            vim,
-                            Motion::StartOfLine {
-                                display_lines: false,
-                            },
-                            None,
-                            cx,
-                        )
-                    })
                 }
-            })?;
-            anyhow::Ok(())
-        })
-        .detach_and_log_err(cx);
+            })
+            .collect()
     })
+    .unwrap_or(selections)
+}
+
+fn offset_by_chars(snapshot: &MultiBufferSnapshot, offset: usize, chars: i64) -> usize {
+    if chars >= 0 {
+        let chars = snapshot.chars_at(offset).take(chars as usize);
+        offset + chars.map(char::len_utf8).sum::<usize>()
+    } else {
+        let chars = snapshot
+            .reversed_chars_at(offset)
+            .take(chars.unsigned_abs() as usize);
+        offset - chars.map(char::len_utf8).sum::<usize>()
+    }
+}
+
+pub(crate) fn move_to_first_non_whitespace(
+    editor: &mut Editor,
+    row: u32,
+    cx: &mut ViewContext<Editor>,
+) {
+    let snapshot = editor.buffer().read(cx).snapshot(cx);
+    let column = snapshot.indent_size_for_line(MultiBufferRow(row)).len;
+    let point = Point::new(row, column);
+    editor.change_selections(Some(Autoscroll::fit()), cx, |s| {
+        s.select_ranges([point..point])
+    });
+}
+
+/// The matches of the pattern in the lines of the range, with the text to replace each with.
+/// Without the `g` flag, only the first match on each line is replaced.
+fn substitution_matches(
+    editor: &Editor,
+    regex: &Regex,
+    replacement: &Replacement,
+    range: Range<Point>,
+    cx: &AppContext,
+) -> Vec<(Range<Anchor>, String)> {
+    let snapshot = editor.buffer().read(cx).snapshot(cx);
+    let start_offset = range.start.to_offset(&snapshot);
+    let text = snapshot.text_for_range(range).collect::<String>();
+
+    let mut matches = Vec::new();
+    let mut last_row = None;
+    for captures in regex.captures_iter(&text) {
+        let Some(found) = captures.get(0) else {
+            continue;
+        };
+        let start = start_offset + found.start();
+        let end = start_offset + found.end();
+        let row = snapshot.offset_to_point(start).row;
+        if !replacement.global && last_row == Some(row) {
+            continue;
+        }
+        last_row = Some(row);
+
+        let mut new_text = String::new();
+        captures.expand(&replacement.replacement, &mut new_text);
+        // The anchors stay on the matched text as earlier matches are replaced.
+        let range = if start == end {
+            snapshot.anchor_after(start)..snapshot.anchor_after(end)
+        } else {
+            snapshot.anchor_after(start)..snapshot.anchor_before(end)
+        };
+        matches.push((range, new_text));
+    }
+    matches
+}
+
+/// Reads a vim pattern up to its closing delimiter, which is consumed, converting it to the
+/// syntax of the `regex` crate: `\(` and `\)` are flipped with `(` and `)`, and an escaped
+/// delimiter matches the delimiter itself.
+pub(crate) fn parse_pattern(chars: &mut impl Iterator<Item = char>, delimiter: char) -> String {
+    let mut pattern = String::new();
+    let mut escaped = false;
+    for c in chars.by_ref() {
+        if escaped {
+            escaped = false;
+            if c == delimiter {
+                pattern.push_str(&regex::escape(&c.to_string()));
+            } else {
+                // unescape escaped parens
+                if c != '(' && c != ')' {
+                    pattern.push('\\');
+                }
+                pattern.push(c);
+            }
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == delimiter {
+            break;
+        } else {
+            // escape unescaped parens
+            if c == '(' || c == ')' {
+                pattern.push('\\');
+            }
+            pattern.push(c);
+        }
+    }
+    pattern
 }
 
 // convert a vim query into something more usable by zed.
 // we don't attempt to fully convert between the two regex syntaxes,
 // but we do flip \( and \) to ( and ) (and vice-versa) in the pattern,
-// and convert \0..\9 to $0..$9 in the replacement so that common idioms work.
-fn parse_replace_all(query: &str) -> Replacement {
-    let mut chars = query.chars();
-    let mut range = None;
-    let maybe_line_range_and_rest: Option<(Range<usize>, &str)> =
-        range_regex().captures(query).map(|captures| {
-            (
-                captures.get(1).unwrap().as_str().parse().unwrap()
-                    ..captures.get(2).unwrap().as_str().parse().unwrap(),
-                captures.get(3).unwrap().as_str(),
-            )
-        });
-    if maybe_line_range_and_rest.is_some() {
-        let (line_range, rest) = maybe_line_range_and_rest.unwrap();
-        range = Some(line_range);
-        chars = rest.chars();
-    } else if Some('%') != chars.next() || Some('s') != chars.next() {
-        return Replacement::default();
-    }
-
-    let Some(delimiter) = chars.next() else {
-        return Replacement::default();
-    };
+// and convert \0..\9 and & to ${0}..${9} in the replacement so that common idioms work.
+fn parse_replace_all(query: &str) -> Option<Replacement> {
+    let (range, command) = parse_range(query);
+    let mut chars = command.strip_prefix('s')?.chars();
+    let delimiter = chars.next()?;
 
-    let mut search = String::new();
+    let search = parse_pattern(&mut chars, delimiter);
     let mut replacement = String::new();
     let mut flags = String::new();
 
-    let mut buffer = &mut search;
-
     let mut escaped = false;
-    // 0 - parsing search
     // 1 - parsing replacement
     // 2 - parsing flags
-    let mut phase = 0;
+    let mut phase = 1;
 
     for c in chars {
-        if escaped {
+        if phase == 2 {
+            flags.push(c);
+        } else if escaped {
             escaped = false;
-            if phase == 1 && c.is_digit(10) {
-                buffer.push('$')
-            // unescape escaped parens
-            } else if phase == 0 && c == '(' || c == ')' {
-            } else if c != delimiter {
-                buffer.push('\\')
+            if c == delimiter {
+                // an escaped delimiter is the delimiter itself
+                replacement.push(c);
+            } else {
+                match c {
+                    '0'..='9' => replacement.push_str(&format!("${{{c}}}")),
+                    'r' | 'n' => replacement.push('\n'),
+                    't' => replacement.push('\t'),
+                    '&' => replacement.push('&'),
+                    '$' => replacement.push_str("$$"),
+                    _ => replacement.push(c),
+                }
             }
-            buffer.push(c)
         } else if c == '\\' {
             escaped = true;
         } else if c == delimiter {
-            if phase == 0 {
-                buffer = &mut replacement;
-                phase = 1;
-            } else if phase == 1 {
-                buffer = &mut flags;
-                phase = 2;
-            } else {
-                break;
-            }
+            phase += 1;
         } else {
-            // escape unescaped parens
-            if phase == 0 && c == '(' || c == ')' {
-                buffer.push('\\')
+            match c {
+                '&' => replacement.push_str("${0}"),
+                '$' => replacement.push_str("$$"),
+                _ => replacement.push(c),
             }
-            buffer.push(c)
         }
     }
 
     let mut replacement = Replacement {
         search,
         replacement,
-        should_replace_all: true,
+        range: range.unwrap_or_else(CommandRange::current_line),
+        global: false,
         is_case_sensitive: true,
-        range,
+        confirm: false,
+        count_only: false,
     };
 
     for c in flags.chars() {
         match c {
-            'g' | 'I' => {}
-            'c' | 'n' => replacement.should_replace_all = false,
+            'g' => replacement.global = true,
+            'c' => replacement.confirm = true,
+            'n' => replacement.count_only = true,
             'i' => replacement.is_case_sensitive = false,
+            'I' => replacement.is_case_sensitive = true,
             _ => {}
         }
     }
 
-    replacement
+    Some(replacement)
 }
 
 #[cfg(test)]
@@ -515,10 +775,13 @@ mod test {
     use editor::{display_map::DisplayRow, DisplayPoint};
     use indoc::indoc;
     use search::BufferSearchBar;
+    use settings::SettingsStore;
 
+    use super::split_search_offset;
     use crate::{
-        state::Mode,
+        state::{Mode, SearchOffset},
         test::{NeovimBackedTestContext, VimTestContext},
+        VimSettings,
     };
 
     #[gpui::test]
@@ -637,6 +900,78 @@ mod test {
         cx.assert_state("one two ˇone", Mode::Normal);
     }
 
+    #[gpui::test]
+    async fn test_smartcase_star_search(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        cx.update_global(|store: &mut SettingsStore, cx| {
+            store.update_user_settings::<VimSettings>(cx, |s| {
+                s.use_smartcase_star_search = Some(true);
+            });
+        });
+
+        cx.set_state("ˇhi Hi hi", Mode::Normal);
+        cx.simulate_keystrokes("*");
+        cx.run_until_parked();
+        cx.assert_state("hi ˇHi hi", Mode::Normal);
+
+        // a word with an uppercase letter matches only itself
+        cx.simulate_keystrokes("*");
+        cx.run_until_parked();
+        cx.assert_state("hi ˇHi hi", Mode::Normal);
+    }
+
+    #[gpui::test]
+    async fn test_search_offset(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇone two\nthree two\nfour", Mode::Normal);
+        cx.simulate_keystrokes("/ t w o / e enter");
+        cx.run_until_parked();
+        cx.assert_state("one twˇo\nthree two\nfour", Mode::Normal);
+
+        // `n` keeps the offset
+        cx.simulate_keystrokes("n");
+        cx.assert_state("one two\nthree twˇo\nfour", Mode::Normal);
+
+        cx.simulate_keystrokes("g g / t h r / 1 enter");
+        cx.run_until_parked();
+        cx.assert_state("one two\nthree two\nˇfour", Mode::Normal);
+
+        cx.simulate_keystrokes("g g / t w o / s 1 enter");
+        cx.run_until_parked();
+        cx.assert_state("one tˇwo\nthree two\nfour", Mode::Normal);
+
+        // `*` forgets it
+        cx.simulate_keystrokes("0 * n");
+        cx.run_until_parked();
+        cx.assert_state("ˇone two\nthree two\nfour", Mode::Normal);
+
+        // the `e` offset is inclusive
+        cx.set_state("ˇone two three", Mode::Normal);
+        cx.simulate_keystrokes("d / t w o / e enter");
+        cx.run_until_parked();
+        cx.assert_state("ˇ three", Mode::Normal);
+    }
+
+    #[test]
+    fn test_split_search_offset() {
+        assert_eq!(split_search_offset("foo", '/'), None);
+        assert_eq!(split_search_offset("foo/", '/'), Some(("foo", None)));
+        assert_eq!(
+            split_search_offset("foo/e", '/'),
+            Some(("foo", Some(SearchOffset::End(0))))
+        );
+        assert_eq!(
+            split_search_offset("foo?b-2", '?'),
+            Some(("foo", Some(SearchOffset::Start(-2))))
+        );
+        assert_eq!(
+            split_search_offset("a\\/b/+", '/'),
+            Some(("a\\/b", Some(SearchOffset::Lines(1))))
+        );
+        assert_eq!(split_search_offset("foo/bar", '/'), None);
+    }
+
     #[gpui::test]
     async fn test_non_vim_search(cx: &mut gpui::TestAppContext) {
         let mut cx = VimTestContext::new(cx, false).await;
@@ -13,7 +13,7 @@ use editor::{
 use itertools::Itertools;
 
 use gpui::{actions, impl_actions, ViewContext, WindowContext};
-use language::{char_kind, BufferSnapshot, CharKind, Point, Selection};
+use language::{char_kind, BufferSnapshot, CharKind, Point, Selection, TextObject};
 use multi_buffer::MultiBufferRow;
 use serde::Deserialize;
 use workspace::Workspace;
@@ -33,6 +33,10 @@ pub enum Object {
     AngleBrackets,
     Argument,
     Tag,
+    Method,
+    Class,
+    Comment,
+    IndentObj { include_below: bool },
 }
 
 #[derive(Clone, Deserialize, PartialEq)]
@@ -42,7 +46,16 @@ struct Word {
     ignore_punctuation: bool,
 }
 
-impl_actions!(vim, [Word]);
+/// The lines indented at least as much as the cursor's line, as `ii`. `ai` includes the line
+/// above them, and `aI` the lines above and below them.
+#[derive(Clone, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct IndentObj {
+    #[serde(default)]
+    include_below: bool,
+}
+
+impl_actions!(vim, [Word, IndentObj]);
 
 actions!(
     vim,
@@ -58,7 +71,10 @@ actions!(
         CurlyBrackets,
         AngleBrackets,
         Argument,
-        Tag
+        Tag,
+        Method,
+        Class,
+        Comment
     ]
 );
 
@@ -96,6 +112,14 @@ pub fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
     });
     workspace
         .register_action(|_: &mut Workspace, _: &Argument, cx: _| object(Object::Argument, cx));
+    workspace.register_action(|_: &mut Workspace, _: &Method, cx: _| object(Object::Method, cx));
+    workspace.register_action(|_: &mut Workspace, _: &Class, cx: _| object(Object::Class, cx));
+    workspace.register_action(|_: &mut Workspace, _: &Comment, cx: _| object(Object::Comment, cx));
+    workspace.register_action(
+        |_: &mut Workspace, &IndentObj { include_below }: &IndentObj, cx: _| {
+            object(Object::IndentObj { include_below }, cx)
+        },
+    );
 }
 
 fn object(object: Object, cx: &mut WindowContext) {
@@ -123,13 +147,24 @@ impl Object {
             | Object::AngleBrackets
             | Object::CurlyBrackets
             | Object::SquareBrackets
-            | Object::Argument => true,
+            | Object::Argument
+            | Object::Method
+            | Object::Class
+            | Object::Comment
+            | Object::IndentObj { .. } => true,
         }
     }
 
     pub fn always_expands_both_ways(self) -> bool {
         match self {
-            Object::Word { .. } | Object::Sentence | Object::Paragraph | Object::Argument => false,
+            Object::Word { .. }
+            | Object::Sentence
+            | Object::Paragraph
+            | Object::Argument
+            | Object::Method
+            | Object::Class
+            | Object::Comment
+            | Object::IndentObj { .. } => false,
             Object::Quotes
             | Object::BackQuotes
             | Object::DoubleQuotes
@@ -161,8 +196,11 @@ impl Object {
             | Object::AngleBrackets
             | Object::VerticalBars
             | Object::Tag
-            | Object::Argument => Mode::Visual,
-            Object::Paragraph => Mode::VisualLine,
+            | Object::Argument
+            | Object::Method
+            | Object::Class
+            | Object::Comment => Mode::Visual,
+            Object::Paragraph | Object::IndentObj { .. } => Mode::VisualLine,
         }
     }
 
@@ -209,6 +247,34 @@ impl Object {
                 surrounding_markers(map, relative_to, around, self.is_multiline(), '<', '>')
             }
             Object::Argument => argument(map, relative_to, around),
+            Object::Method => text_object(
+                map,
+                relative_to,
+                if around {
+                    TextObject::AroundFunction
+                } else {
+                    TextObject::InsideFunction
+                },
+            ),
+            Object::Class => text_object(
+                map,
+                relative_to,
+                if around {
+                    TextObject::AroundClass
+                } else {
+                    TextObject::InsideClass
+                },
+            ),
+            Object::Comment => text_object(
+                map,
+                relative_to,
+                if around {
+                    TextObject::AroundComment
+                } else {
+                    TextObject::InsideComment
+                },
+            ),
+            Object::IndentObj { include_below } => indent(map, relative_to, around, include_below),
         }
     }
 
@@ -411,6 +477,74 @@ fn around_next_word(
     Some(start..end)
 }
 
+/// The ranges of the text objects of a kind that the language's `textobjects.scm` query finds
+/// around an offset in a buffer.
+fn text_object_ranges_at(
+    buffer: &BufferSnapshot,
+    offset: usize,
+    target: TextObject,
+) -> Vec<Range<usize>> {
+    let query_range = offset..(offset + 1).min(buffer.len());
+    buffer
+        .text_object_ranges(query_range)
+        .filter(|(range, text_object)| {
+            *text_object == target && range.start <= offset && offset < range.end
+        })
+        .map(|(range, _)| range)
+        .collect()
+}
+
+/// Returns the range of the innermost function, class or comment containing `relative_to`,
+/// as found by the language's `textobjects.scm` query. When the cursor isn't inside the object,
+/// such as when it's on a function's signature, the inside of the enclosing object is used, or
+/// the whole object if the query doesn't capture its inside.
+fn text_object(
+    map: &DisplaySnapshot,
+    relative_to: DisplayPoint,
+    target: TextObject,
+) -> Option<Range<DisplayPoint>> {
+    let snapshot = &map.buffer_snapshot;
+    let offset = relative_to.to_offset(map, Bias::Left);
+
+    // Text objects come from the syntax tree, so we operate at the buffer level and map back to the display level
+    let excerpt = snapshot.excerpt_containing(offset..offset)?;
+    let buffer = excerpt.buffer();
+    let offset = excerpt.map_offset_to_buffer(offset);
+
+    let innermost = |ranges: Vec<Range<usize>>| {
+        // Consecutive comments are one object, so the whole run is selected.
+        if target == TextObject::AroundComment {
+            ranges.into_iter().max_by_key(|range| range.len())
+        } else {
+            ranges.into_iter().min_by_key(|range| range.len())
+        }
+    };
+    let range = innermost(text_object_ranges_at(buffer, offset, target)).or_else(|| {
+        let around = target.around();
+        if around == target {
+            return None;
+        }
+        let around_range = innermost(text_object_ranges_at(buffer, offset, around))?;
+        buffer
+            .text_object_ranges(around_range.clone())
+            .filter(|(range, text_object)| {
+                *text_object == target
+                    && around_range.start <= range.start
+                    && range.end <= around_range.end
+            })
+            .map(|(range, _)| range)
+            .max_by_key(|range| range.len())
+            .or(Some(around_range))
+    })?;
+
+    if excerpt.contains_buffer_range(range.clone()) {
+        let range = excerpt.map_range_from_buffer(range);
+        Some(range.start.to_display_point(map)..range.end.to_display_point(map))
+    } else {
+        None
+    }
+}
+
 fn argument(
     map: &DisplaySnapshot,
     relative_to: DisplayPoint,
@@ -423,6 +557,55 @@ fn argument(
     let excerpt = snapshot.excerpt_containing(offset..offset)?;
     let buffer = excerpt.buffer();
 
+    /// The argument at the offset as found by the language's `textobjects.scm` query, if it
+    /// has one. Unless the query captures the argument's surroundings, the around range
+    /// includes the comma after the argument, or before it if it's the last one.
+    fn text_object_argument_at(
+        buffer: &BufferSnapshot,
+        offset: usize,
+        around: bool,
+    ) -> Option<Range<usize>> {
+        let innermost = |target| {
+            text_object_ranges_at(buffer, offset, target)
+                .into_iter()
+                .min_by_key(|range| range.len())
+        };
+        if around {
+            if let Some(range) = innermost(TextObject::AroundArgument) {
+                return Some(range);
+            }
+        }
+        let mut range = innermost(TextObject::InsideArgument)?;
+        if !around {
+            return Some(range);
+        }
+
+        let whitespace_before_comma = buffer
+            .chars_at(range.end)
+            .take_while(|c| c.is_whitespace())
+            .map(char::len_utf8)
+            .sum::<usize>();
+        if buffer.chars_at(range.end + whitespace_before_comma).next() == Some(',') {
+            range.end += whitespace_before_comma + 1;
+            range.end += buffer
+                .chars_at(range.end)
+                .take_while(|c| c.is_whitespace())
+                .map(char::len_utf8)
+                .sum::<usize>();
+        } else {
+            let whitespace_after_comma = buffer
+                .reversed_chars_at(range.start)
+                .take_while(|c| c.is_whitespace())
+                .map(char::len_utf8)
+                .sum::<usize>();
+            let comma = range.start - whitespace_after_comma;
+            if buffer.reversed_chars_at(comma).next() == Some(',') {
+                range.start = comma - 1;
+            }
+        }
+        Some(range)
+    }
+
     fn comma_delimited_range_at(
         buffer: &BufferSnapshot,
         mut offset: usize,
@@ -552,7 +735,13 @@ fn argument(
         Some(start..end)
     }
 
-    let result = comma_delimited_range_at(buffer, excerpt.map_offset_to_buffer(offset), around)?;
+    // Arguments found by the query, such as in languages without brackets around them, are
+    // used along with arguments between brackets, such as in tuples, and the innermost wins.
+    let offset = excerpt.map_offset_to_buffer(offset);
+    let result = text_object_argument_at(buffer, offset, around)
+        .into_iter()
+        .chain(comma_delimited_range_at(buffer, offset, around))
+        .min_by_key(|range| range.len())?;
 
     if excerpt.contains_buffer_range(result.clone()) {
         let result = excerpt.map_range_from_buffer(result);
@@ -714,6 +903,56 @@ fn expand_to_include_whitespace(
 /// - If `around` and if the current paragraph is the last paragraph of the
 ///   file and is not blank, then the returned range starts at the start of the
 ///   previous paragraph, if it exists.
+/// Returns the lines around `relative_to` that are indented at least as much as its line, or as
+/// the next non-blank line when it's on a blank line. Blank lines within them are included, but
+/// not blank lines at either end. When `around`, the line above them is included, which
+/// usually starts the block they're in, and with `include_below` the line below them too.
+fn indent(
+    map: &DisplaySnapshot,
+    relative_to: DisplayPoint,
+    around: bool,
+    include_below: bool,
+) -> Option<Range<DisplayPoint>> {
+    let snapshot = &map.buffer_snapshot;
+    let max_row = snapshot.max_point().row;
+    let row = relative_to.to_point(map).row;
+    let is_blank = |row: u32| snapshot.is_line_blank(MultiBufferRow(row));
+
+    let row = (row..=max_row)
+        .chain((0..row).rev())
+        .find(|row| !is_blank(*row))?;
+    let indent = snapshot.indent_size_for_line(MultiBufferRow(row)).len;
+    let in_block = |row: u32| {
+        is_blank(row) || snapshot.indent_size_for_line(MultiBufferRow(row)).len >= indent
+    };
+
+    let mut start = row;
+    while start > 0 && in_block(start - 1) {
+        start -= 1;
+    }
+    while is_blank(start) {
+        start += 1;
+    }
+    let mut end = row;
+    while end < max_row && in_block(end + 1) {
+        end += 1;
+    }
+    while is_blank(end) {
+        end -= 1;
+    }
+
+    if around {
+        start = start.saturating_sub(1);
+        if include_below && end < max_row {
+            end += 1;
+        }
+    }
+
+    let start = Point::new(start, 0).to_display_point(map);
+    let end = Point::new(end, snapshot.line_len(MultiBufferRow(end))).to_display_point(map);
+    Some(start..end)
+}
+
 fn paragraph(
     map: &DisplaySnapshot,
     relative_to: DisplayPoint,
@@ -1537,4 +1776,127 @@ mod test {
             Mode::Visual,
         );
     }
+
+    #[gpui::test]
+    async fn test_syntax_text_objects(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        // Functions
+        cx.set_state(
+            indoc! {"
+                fn boop(a: u32) {
+                    let x = 1;
+                    ˇlet y = 2;
+                }
+            "},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("v i f");
+        cx.assert_state(
+            indoc! {"
+                fn boop(a: u32) {
+                    «let x = 1;
+                    let y = 2;ˇ»
+                }
+            "},
+            Mode::Visual,
+        );
+
+        cx.set_state(
+            indoc! {"
+                fn boop(a: u32) {
+                    ˇlet x = 1;
+                }
+            "},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("v a f");
+        cx.assert_state(
+            indoc! {"
+                «fn boop(a: u32) {
+                    let x = 1;
+                }ˇ»
+            "},
+            Mode::Visual,
+        );
+
+        // The body of the function is selected from its signature
+        cx.set_state(
+            indoc! {"
+                fn bˇoop(a: u32) {
+                    let x = 1;
+                }
+            "},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("v i f");
+        cx.assert_state(
+            indoc! {"
+                fn boop(a: u32) {
+                    «let x = 1;ˇ»
+                }
+            "},
+            Mode::Visual,
+        );
+
+        // Classes
+        cx.set_state(
+            indoc! {"
+                struct Foo {
+                    ˇa: u32,
+                }
+            "},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("v a c");
+        cx.assert_state(
+            indoc! {"
+                «struct Foo {
+                    a: u32,
+                }ˇ»
+            "},
+            Mode::Visual,
+        );
+
+        // Comments
+        cx.set_state("fn boop(/* a ˇcomment */ a: u32) {}", Mode::Normal);
+        cx.simulate_keystrokes("v a g c");
+        cx.assert_state("fn boop(«/* a comment */ˇ» a: u32) {}", Mode::Visual);
+
+        // Arguments found by the query
+        cx.set_state("fn boop(a: u32, ˇb: u32) {}", Mode::Normal);
+        cx.simulate_keystrokes("d a a");
+        cx.assert_state("fn boop(a: u32ˇ) {}", Mode::Normal);
+    }
+
+    #[gpui::test]
+    async fn test_indent_objects(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        const EXAMPLE: &str = indoc! {"
+            fn boop() {
+                let a = 1;
+                ˇlet b = 2;
+
+                let c = 3;
+            }
+            boop();"};
+
+        cx.set_state(EXAMPLE, Mode::Normal);
+        cx.simulate_keystrokes("d i i");
+        cx.assert_state("fn boop() {\nˇ}\nboop();", Mode::Normal);
+
+        cx.set_state(EXAMPLE, Mode::Normal);
+        cx.simulate_keystrokes("d a i");
+        cx.assert_state("ˇ}\nboop();", Mode::Normal);
+
+        cx.set_state(EXAMPLE, Mode::Normal);
+        cx.simulate_keystrokes("d a shift-i");
+        cx.assert_state("ˇboop();", Mode::Normal);
+
+        // On a blank line, the block is the one the next line is in.
+        cx.set_state("a\nˇ\n    b\n    c\nd", Mode::Normal);
+        cx.simulate_keystrokes("d i i");
+        cx.assert_state("a\n\nˇd", Mode::Normal);
+    }
 }
@@ -0,0 +1,32 @@
+use std::path::PathBuf;
+
+use db::sqlez_macros::sql;
+use db::{define_connection, query};
+
+define_connection!(
+    // Global marks, set with `m{A-Z}`, are shared by all workspaces like they are in vim.
+    pub static ref DB: VimDb<()> =
+        &[sql! (
+            CREATE TABLE vim_global_marks(
+                name TEXT PRIMARY KEY,
+                path BLOB NOT NULL,
+                row INTEGER NOT NULL,
+                column INTEGER NOT NULL
+            ) STRICT;
+        )];
+);
+
+impl VimDb {
+    query! {
+        pub fn global_marks() -> Result<Vec<(String, PathBuf, u32, u32)>> {
+            SELECT name, path, row, column FROM vim_global_marks
+        }
+    }
+
+    query! {
+        pub async fn save_global_mark(name: String, path: PathBuf, row: u32, column: u32) -> Result<()> {
+            INSERT OR REPLACE INTO vim_global_marks(name, path, row, column)
+            VALUES (?, ?, ?, ?)
+        }
+    }
+}
@@ -0,0 +1,321 @@
+use std::sync::Arc;
+
+use gpui::{
+    actions, AppContext, DismissEvent, EventEmitter, FocusableView, Render, Task, View,
+    ViewContext, VisualContext, WeakView, WindowContext,
+};
+use picker::{Picker, PickerDelegate};
+use ui::{prelude::*, ListItem, ListItemSpacing};
+use util::ResultExt;
+use workspace::{ModalView, Workspace};
+
+use crate::{
+    normal::paste::{paste, Paste},
+    Vim,
+};
+
+actions!(vim, [ShowRegisters]);
+
+/// The order registers are listed in by [`ShowRegisters`].
+const REGISTER_ORDER: &str = "\"0123456789abcdefghijklmnopqrstuvwxyz-";
+
+pub(crate) fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+    workspace.register_action(|workspace, _: &ShowRegisters, cx| {
+        let workspace_handle = cx.view().downgrade();
+        workspace.toggle_modal(cx, |cx| RegisterPicker::new(workspace_handle, cx));
+    });
+}
+
+/// Whether text can be yanked into or pasted from the register with this name. Uppercase
+/// letters append to the register of the lowercase letter, `_` discards the text, and `+` and
+/// `*` are the system clipboard.
+fn is_register(name: char) -> bool {
+    name.is_ascii_alphanumeric() || matches!(name, '"' | '-' | '_' | '+' | '*')
+}
+
+fn is_system_clipboard(name: char) -> bool {
+    matches!(name, '+' | '*')
+}
+
+impl Vim {
+    /// Selects the register for the next yank, delete or paste, as typed after `"`.
+    pub(crate) fn select_register(&mut self, text: Arc<str>, cx: &mut WindowContext) {
+        let mut chars = text.chars();
+        match (chars.next(), chars.next()) {
+            (Some(name), None) if is_register(name) => {
+                self.pop_operator(cx);
+                self.workspace_state.selected_register = Some(name);
+            }
+            _ => self.clear_operator(cx),
+        }
+    }
+
+    /// Stores yanked or deleted text. Without a selected register the text goes to the unnamed
+    /// register and to `0` when yanked, or to the numbered registers when a line or more was
+    /// deleted and to `-` when less was.
+    pub(crate) fn write_registers(
+        &mut self,
+        text: String,
+        register: Option<char>,
+        is_yank: bool,
+        linewise: bool,
+    ) {
+        let registers = &mut self.workspace_state.registers;
+        match register {
+            Some('_') => return,
+            Some(name) if is_system_clipboard(name) => {}
+            Some(name) if name != '"' => {
+                let key = name.to_ascii_lowercase().to_string();
+                let text = if name.is_ascii_uppercase() {
+                    let mut contents = registers.get(&key).cloned().unwrap_or_default();
+                    if linewise && !contents.is_empty() && !contents.ends_with('\n') {
+                        contents.push('\n');
+                    }
+                    contents + &text
+                } else {
+                    text
+                };
+                registers.insert(key, text.clone());
+                registers.insert("\"".to_string(), text);
+                return;
+            }
+            _ if is_yank => {
+                registers.insert("0".to_string(), text.clone());
+            }
+            _ if linewise || text.contains('\n') => {
+                for ix in (1..9).rev() {
+                    match registers.remove(&ix.to_string()) {
+                        Some(contents) => registers.insert((ix + 1).to_string(), contents),
+                        None => registers.remove(&(ix + 1).to_string()),
+                    };
+                }
+                registers.insert("1".to_string(), text.clone());
+            }
+            _ => {
+                registers.insert("-".to_string(), text.clone());
+            }
+        }
+        registers.insert("\"".to_string(), text);
+    }
+
+    pub(crate) fn read_register(&self, name: char, cx: &AppContext) -> Option<String> {
+        if is_system_clipboard(name) {
+            return cx.read_from_clipboard().map(|item| item.text().clone());
+        }
+        self.workspace_state
+            .registers
+            .get(&name.to_ascii_lowercase().to_string())
+            .cloned()
+    }
+}
+
+pub struct RegisterPicker {
+    picker: View<Picker<RegisterPickerDelegate>>,
+}
+
+impl RegisterPicker {
+    fn new(workspace: WeakView<Workspace>, cx: &mut ViewContext<Self>) -> Self {
+        let mut registers = Vim::read(cx)
+            .workspace_state
+            .registers
+            .iter()
+            .filter_map(|(name, contents)| {
+                let mut chars = name.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(name), None) => Some((name, contents.clone())),
+                    _ => None,
+                }
+            })
+            .collect::<Vec<_>>();
+        registers.sort_by_key(|(name, _)| REGISTER_ORDER.find(*name));
+
+        let delegate = RegisterPickerDelegate {
+            picker: cx.view().downgrade(),
+            workspace,
+            matches: (0..registers.len()).collect(),
+            registers,
+            selected_index: 0,
+        };
+        let picker = cx.new_view(|cx| Picker::uniform_list(delegate, cx));
+        Self { picker }
+    }
+}
+
+impl FocusableView for RegisterPicker {
+    fn focus_handle(&self, cx: &AppContext) -> gpui::FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for RegisterPicker {}
+impl ModalView for RegisterPicker {}
+
+impl Render for RegisterPicker {
+    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+pub struct RegisterPickerDelegate {
+    picker: WeakView<RegisterPicker>,
+    workspace: WeakView<Workspace>,
+    registers: Vec<(char, String)>,
+    matches: Vec<usize>,
+    selected_index: usize,
+}
+
+impl PickerDelegate for RegisterPickerDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _cx: &mut WindowContext) -> Arc<str> {
+        "Paste from register...".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(&mut self, query: String, _: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        let query = query.to_lowercase();
+        self.matches = self
+            .registers
+            .iter()
+            .enumerate()
+            .filter(|(_, (name, contents))| {
+                query.is_empty()
+                    || query == name.to_string()
+                    || contents.to_lowercase().contains(&query)
+            })
+            .map(|(ix, _)| ix)
+            .collect();
+        self.selected_index = self
+            .selected_index
+            .min(self.matches.len().saturating_sub(1));
+        Task::ready(())
+    }
+
+    fn confirm(&mut self, _: bool, cx: &mut ViewContext<Picker<Self>>) {
+        let register = self
+            .matches
+            .get(self.selected_index)
+            .map(|&ix| self.registers[ix].0);
+        self.dismissed(cx);
+
+        let Some(register) = register else {
+            return;
+        };
+        self.workspace
+            .update(cx, |workspace, cx| {
+                Vim::update(cx, |vim, _| {
+                    vim.workspace_state.selected_register = Some(register)
+                });
+                paste(workspace, &Paste::default(), cx);
+            })
+            .log_err();
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        self.picker
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let (name, contents) = &self.registers[*self.matches.get(ix)?];
+        // Like vim's `:registers`, show newlines and tabs rather than breaking the line.
+        let contents = contents.replace('\n', "^J").replace('\t', "^I");
+
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .child(
+                    h_flex()
+                        .gap_2()
+                        .child(Label::new(format!("\"{name}")))
+                        .child(Label::new(contents).color(Color::Muted).single_line()),
+                ),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::indoc;
+
+    use crate::{state::Mode, test::VimTestContext, Vim};
+
+    fn register(cx: &mut VimTestContext, name: &str) -> Option<String> {
+        cx.read(|cx| {
+            cx.global::<Vim>()
+                .workspace_state
+                .registers
+                .get(name)
+                .cloned()
+        })
+    }
+
+    #[gpui::test]
+    async fn test_registers(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state(
+            indoc! {"
+                The quick brown
+                fox jˇumps over
+                the lazy dog"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("\" a y i w");
+        assert_eq!(register(&mut cx, "a").as_deref(), Some("jumps"));
+        assert_eq!(register(&mut cx, "\"").as_deref(), Some("jumps"));
+
+        // The black hole register keeps deleted text out of every register.
+        cx.simulate_keystrokes("\" _ d i w");
+        cx.assert_state(
+            indoc! {"
+                The quick brown
+                fox ˇ over
+                the lazy dog"},
+            Mode::Normal,
+        );
+        assert_eq!(register(&mut cx, "\"").as_deref(), Some("jumps"));
+        assert_eq!(register(&mut cx, "-"), None);
+
+        // Uppercase registers append to their lowercase register.
+        cx.simulate_keystrokes("k \" shift-a y i w");
+        assert_eq!(register(&mut cx, "a").as_deref(), Some("jumpsquick"));
+
+        cx.simulate_keystrokes("\" a p");
+        cx.assert_state(
+            indoc! {"
+                The qjumpsquicˇkuick brown
+                fox  over
+                the lazy dog"},
+            Mode::Normal,
+        );
+
+        // Deleted lines shift through the numbered registers.
+        cx.simulate_keystrokes("d d d d");
+        assert_eq!(register(&mut cx, "1").as_deref(), Some("fox  over\n"));
+        assert_eq!(
+            register(&mut cx, "2").as_deref(),
+            Some("The qjumpsquickuick brown\n")
+        );
+        assert_eq!(register(&mut cx, "0"), None);
+    }
+}
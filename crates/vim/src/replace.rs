@@ -3,22 +3,27 @@ use crate::{
     state::Mode,
     Vim,
 };
-use editor::{display_map::ToDisplayPoint, Bias, ToPoint};
+use editor::{
+    display_map::{DisplaySnapshot, ToDisplayPoint},
+    Bias, ToPoint,
+};
 use gpui::{actions, ViewContext, WindowContext};
 use language::{AutoindentMode, Point};
 use std::ops::Range;
 use std::sync::Arc;
 use workspace::Workspace;
 
-actions!(vim, [ToggleReplace, UndoReplace]);
+actions!(vim, [ToggleReplace, ToggleVirtualReplace, UndoReplace]);
 
 pub fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
     workspace.register_action(|_, _: &ToggleReplace, cx: &mut ViewContext<Workspace>| {
-        Vim::update(cx, |vim, cx| {
-            vim.update_state(|state| state.replacements = vec![]);
-            vim.switch_mode(Mode::Replace, false, cx);
-        });
+        Vim::update(cx, |vim, cx| start_replace(vim, false, cx));
     });
+    workspace.register_action(
+        |_, _: &ToggleVirtualReplace, cx: &mut ViewContext<Workspace>| {
+            Vim::update(cx, |vim, cx| start_replace(vim, true, cx));
+        },
+    );
 
     workspace.register_action(|_, _: &UndoReplace, cx: &mut ViewContext<Workspace>| {
         Vim::update(cx, |vim, cx| {
@@ -31,6 +36,15 @@ pub fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
     });
 }
 
+fn start_replace(vim: &mut Vim, virtual_replace: bool, cx: &mut WindowContext) {
+    vim.start_recording(cx);
+    vim.update_state(|state| {
+        state.replacements = vec![];
+        state.virtual_replace = virtual_replace;
+    });
+    vim.switch_mode(Mode::Replace, false, cx);
+}
+
 pub(crate) fn multi_replace(text: Arc<str>, cx: &mut WindowContext) {
     Vim::update(cx, |vim, cx| {
         vim.update_active_editor(cx, |vim, editor, cx| {
@@ -38,6 +52,7 @@ pub(crate) fn multi_replace(text: Arc<str>, cx: &mut WindowContext) {
                 editor.set_clip_at_line_ends(false, cx);
                 let map = editor.snapshot(cx);
                 let display_selections = editor.selections.all::<Point>(cx);
+                let virtual_replace = vim.state().virtual_replace;
 
                 // Handles all string that require manipulation, including inserts and replaces
                 let edits = display_selections
@@ -47,7 +62,7 @@ pub(crate) fn multi_replace(text: Arc<str>, cx: &mut WindowContext) {
                         let mut range = selection.range();
                         // "\n" need to be handled separately, because when a "\n" is typing,
                         // we don't do a replace, we need insert a "\n"
-                        if !is_new_line {
+                        if !is_new_line && !(virtual_replace && fills_tab(&map, range.start)) {
                             range.end.column += 1;
                             range.end = map.buffer_snapshot.clip_point(range.end, Bias::Right);
                         }
@@ -85,6 +100,17 @@ pub(crate) fn multi_replace(text: Arc<str>, cx: &mut WindowContext) {
     });
 }
 
+/// Whether typing at the point would only fill part of a tab, rather than replace it, because
+/// the tab spans more than one column.
+fn fills_tab(map: &DisplaySnapshot, point: Point) -> bool {
+    if map.buffer_snapshot.chars_at(point).next() != Some('\t') {
+        return false;
+    }
+    let start = point.to_display_point(map);
+    let end = Point::new(point.row, point.column + 1).to_display_point(map);
+    end.column() - start.column() > 1
+}
+
 fn undo_replace(vim: &mut Vim, maybe_times: Option<usize>, cx: &mut WindowContext) {
     vim.update_active_editor(cx, |vim, editor, cx| {
         editor.transact(cx, |editor, cx| {
@@ -288,6 +314,36 @@ mod test {
         cx.assert_state("ˇabˇcabcabc", Mode::Replace);
     }
 
+    #[gpui::test]
+    async fn test_replace_repeat(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇabc abc", Mode::Normal);
+        cx.simulate_keystrokes("shift-r x y escape w .");
+        cx.assert_state("xyc xˇyc", Mode::Normal);
+    }
+
+    #[gpui::test]
+    async fn test_virtual_replace(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        // Typing over a tab fills its columns before replacing it.
+        cx.set_state("ˇ\tx", Mode::Normal);
+        cx.simulate_keystrokes("g shift-r a b c");
+        cx.assert_state("abcˇ\tx", Mode::Replace);
+        cx.simulate_keystrokes("d");
+        cx.assert_state("abcdˇx", Mode::Replace);
+
+        cx.simulate_keystrokes("backspace");
+        cx.assert_state("abcˇ\tx", Mode::Replace);
+        cx.simulate_keystrokes("backspace backspace backspace");
+        cx.assert_state("ˇ\tx", Mode::Replace);
+
+        // Plain replace mode replaces the tab straight away.
+        cx.simulate_keystrokes("escape shift-r a");
+        cx.assert_state("aˇx", Mode::Replace);
+    }
+
     #[gpui::test]
     async fn test_replace_undo(cx: &mut gpui::TestAppContext) {
         let mut cx = VimTestContext::new(cx, true).await;
@@ -1,11 +1,12 @@
-use std::{fmt::Display, ops::Range, sync::Arc};
+use std::{fmt::Display, ops::Range, path::Path, sync::Arc};
 
-use crate::surrounds::SurroundsType;
+use crate::confirm_replace::ConfirmReplace;
+use crate::surrounds::{SurroundsType, TagSurround};
 use crate::{motion::Motion, object::Object};
 use collections::HashMap;
 use editor::Anchor;
-use gpui::{Action, KeyContext};
-use language::{CursorShape, Selection, TransactionId};
+use gpui::{Action, AnyWindowHandle, AppContext, KeyContext, Keystroke, Model};
+use language::{Buffer, CursorShape, Point, Selection, ToPoint as _, TransactionId};
 use serde::{Deserialize, Serialize};
 use workspace::searchable::Direction;
 
@@ -52,6 +53,7 @@ pub enum Operator {
     Change,
     Delete,
     Yank,
+    Fold,
     Replace,
     Object { around: bool },
     FindForward { before: bool },
@@ -59,8 +61,13 @@ pub enum Operator {
     AddSurrounds { target: Option<SurroundsType> },
     ChangeSurrounds { target: Option<Object> },
     DeleteSurrounds,
+    SurroundTag { surround: TagSurround, tag: String },
     Mark,
     Jump { line: bool },
+    Register,
+    RecordRegister,
+    ReplayRegister,
+    ConfirmReplace,
 }
 
 #[derive(Default, Clone)]
@@ -75,14 +82,22 @@ pub struct EditorState {
 
     pub operator_stack: Vec<Operator>,
     pub replacements: Vec<(Range<editor::Anchor>, String)>,
+    /// Whether replace mode was entered with `gR`, which replaces screen columns rather than
+    /// characters, so that typing over a tab only replaces it once the tab is filled.
+    pub virtual_replace: bool,
 
     pub marks: HashMap<String, Vec<Anchor>>,
     pub change_list: Vec<Vec<Anchor>>,
     pub change_list_position: Option<usize>,
+    /// The folds made with `zf`, which are kept when they're opened so that they can be
+    /// closed again.
+    pub manual_folds: Vec<Range<Anchor>>,
 
     pub current_tx: Option<TransactionId>,
     pub current_anchor: Option<Selection<Anchor>>,
     pub undo_modes: HashMap<TransactionId, Mode>,
+
+    pub confirm_replace: Option<ConfirmReplace>,
 }
 
 #[derive(Default, Clone, Debug)]
@@ -105,6 +120,21 @@ pub enum RecordedSelection {
     },
 }
 
+/// A change as it's recorded for `.` to repeat: the actions and insertions that made it, and
+/// the selection, count and register they applied to.
+#[derive(Default, Clone)]
+pub struct RecordedChange {
+    pub actions: Vec<ReplayableAction>,
+    pub selection: RecordedSelection,
+    pub count: Option<usize>,
+    pub register: Option<char>,
+    /// Whether insert mode was entered, or the buffer was edited, while recording, so that an
+    /// operator that's cancelled doesn't replace the last change.
+    pub changed: bool,
+    /// The edit count of the active editor's buffer when recording started.
+    pub edit_count: Option<usize>,
+}
+
 #[derive(Default, Clone)]
 pub struct WorkspaceState {
     pub search: SearchState,
@@ -113,11 +143,66 @@ pub struct WorkspaceState {
     pub recording: bool,
     pub stop_recording_after_next_action: bool,
     pub replaying: bool,
-    pub recorded_count: Option<usize>,
-    pub recorded_actions: Vec<ReplayableAction>,
-    pub recorded_selection: RecordedSelection,
+    /// The change being recorded, which becomes the last change once it's complete.
+    pub recorded_change: RecordedChange,
+    /// The last complete change, which `.` repeats.
+    pub last_change: RecordedChange,
+    /// The count of the change being repeated, which is the count given to `.` if there was one.
+    pub replayed_count: Option<usize>,
 
     pub registers: HashMap<String, String>,
+    /// The register chosen with `"` for the next yank, delete or paste.
+    pub selected_register: Option<char>,
+
+    /// The register that keystrokes are being recorded into with `q`.
+    pub recording_register: Option<char>,
+    /// The keystrokes recorded into each register, by lowercase register name.
+    pub macros: HashMap<char, Vec<Keystroke>>,
+    pub last_replayed_register: Option<char>,
+    pub replaying_macro: bool,
+    /// Whether the keystrokes of a non-recursive mapping are being sent, which mappings don't
+    /// apply to.
+    pub noremap: bool,
+
+    pub global_marks: HashMap<char, GlobalMark>,
+    /// Each window's jump list, which is kept until the window is closed.
+    pub jump_lists: HashMap<AnyWindowHandle, JumpList>,
+}
+
+/// A mark set with `m{A-Z}`, which can be jumped to from any editor. It remembers a position in
+/// a file rather than in an editor, so that it can be jumped to after the file was closed, or
+/// after restarting. Jump list entries are remembered the same way.
+#[derive(Clone)]
+pub struct GlobalMark {
+    pub path: Arc<Path>,
+    /// Tracks the position through edits, unless the mark was loaded from the database and its
+    /// file hasn't been opened since.
+    pub anchor: Option<(Model<Buffer>, language::Anchor)>,
+    /// The position when the mark was set or loaded.
+    pub point: Point,
+}
+
+impl GlobalMark {
+    pub fn point(&self, cx: &AppContext) -> Point {
+        match &self.anchor {
+            Some((buffer, anchor)) => anchor.to_point(&buffer.read(cx)),
+            None => self.point,
+        }
+    }
+}
+
+/// The positions jumped away from in a window, which `ctrl-o` and `ctrl-i` move through.
+/// Unlike a pane's navigation history, which the editor adds to whenever the cursor moves far,
+/// only vim's jumps are added to it, and it's shared by every pane in the window.
+#[derive(Default, Clone)]
+pub struct JumpList {
+    pub entries: Vec<GlobalMark>,
+    /// The entry that was last moved to, or the number of entries if the list hasn't been moved
+    /// through since the last jump.
+    pub position: usize,
+    /// Whether a file is being opened to move to an entry, so that leaving the current editor
+    /// isn't recorded as a jump.
+    pub navigating: bool,
 }
 
 #[derive(Debug)]
@@ -153,6 +238,20 @@ pub struct SearchState {
     pub prior_selections: Vec<Range<Anchor>>,
     pub prior_operator: Option<Operator>,
     pub prior_mode: Mode,
+
+    /// The offset given after the pattern of the last search, which `n` and `N` reuse.
+    pub offset: Option<SearchOffset>,
+}
+
+/// Where a search leaves the cursor relative to the match, as in `/pattern/e+1`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SearchOffset {
+    /// Some lines below or above the match, at the first non-blank character.
+    Lines(i64),
+    /// Some characters after or before the start of the match.
+    Start(i64),
+    /// Some characters after or before the last character of the match.
+    End(i64),
 }
 
 impl EditorState {
@@ -255,6 +354,7 @@ impl Operator {
             Operator::Change => "c",
             Operator::Delete => "d",
             Operator::Yank => "y",
+            Operator::Fold => "zf",
             Operator::Replace => "r",
             Operator::FindForward { before: false } => "f",
             Operator::FindForward { before: true } => "t",
@@ -263,9 +363,14 @@ impl Operator {
             Operator::AddSurrounds { .. } => "ys",
             Operator::ChangeSurrounds { .. } => "cs",
             Operator::DeleteSurrounds => "ds",
+            Operator::SurroundTag { .. } => "<",
             Operator::Mark => "m",
             Operator::Jump { line: true } => "'",
             Operator::Jump { line: false } => "`",
+            Operator::Register => "\"",
+            Operator::RecordRegister => "q",
+            Operator::ReplayRegister => "@",
+            Operator::ConfirmReplace => ":s",
         }
     }
 
@@ -275,11 +380,16 @@ impl Operator {
             Operator::FindForward { .. }
             | Operator::Mark
             | Operator::Jump { .. }
+            | Operator::Register
+            | Operator::RecordRegister
+            | Operator::ReplayRegister
+            | Operator::ConfirmReplace
             | Operator::FindBackward { .. }
             | Operator::Replace
             | Operator::AddSurrounds { target: Some(_) }
             | Operator::ChangeSurrounds { .. }
-            | Operator::DeleteSurrounds => &["VimWaiting"],
+            | Operator::DeleteSurrounds
+            | Operator::SurroundTag { .. } => &["VimWaiting"],
             _ => &[],
         }
     }
@@ -4,17 +4,31 @@ use crate::{
     state::Mode,
     Vim,
 };
-use editor::{movement, scroll::Autoscroll, Bias};
+use editor::{display_map::DisplaySnapshot, movement, scroll::Autoscroll, Bias, DisplayPoint};
 use gpui::WindowContext;
-use language::BracketPair;
+use language::{BracketPair, Selection};
 use serde::Deserialize;
-use std::sync::Arc;
+use std::{ops::Range, sync::Arc};
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum SurroundsType {
     Motion(Motion),
     Object(Object),
 }
 
+/// What a tag, typed after `t` or `<` and ended by `>` or enter, is surrounded with.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub enum TagSurround {
+    /// Adds the tag around a motion or object, as with `ysiwt`.
+    Add(SurroundsType),
+    /// Replaces the surrounding pair with the tag, as with `cs(t`.
+    Change(Object),
+}
+
+/// Whether the text typed as the new surround for `ys` or `cs` starts typing a tag.
+pub fn starts_tag(text: &str) -> bool {
+    text == "t" || text == "<"
+}
+
 // This exists so that we can have Deserialize on Operators, but not on Motions.
 impl<'de> Deserialize<'de> for SurroundsType {
     fn deserialize<D>(_: D) -> Result<Self, D::Error>
@@ -34,16 +48,7 @@ pub fn add_surrounds(text: Arc<str>, target: SurroundsType, cx: &mut WindowConte
             editor.transact(cx, |editor, cx| {
                 editor.set_clip_at_line_ends(false, cx);
 
-                let pair = match find_surround_pair(&all_support_surround_pair(), &text) {
-                    Some(pair) => pair.clone(),
-                    None => BracketPair {
-                        start: text.to_string(),
-                        end: text.to_string(),
-                        close: true,
-                        newline: false,
-                    },
-                };
-                let surround = pair.end != *text;
+                let (pair, surround) = surround_pair(&text);
                 let (display_map, display_selections) = editor.selections.all_adjusted_display(cx);
                 let mut edits = Vec::new();
                 let mut anchors = Vec::new();
@@ -122,6 +127,11 @@ pub fn delete_surrounds(text: Arc<str>, cx: &mut WindowContext) {
     Vim::update(cx, |vim, cx| {
         vim.stop_recording();
 
+        if &*text == "t" {
+            delete_tag_surrounds(vim, cx);
+            return;
+        }
+
         // only legitimate surrounds can be removed
         let pair = match find_surround_pair(&all_support_surround_pair(), &text) {
             Some(pair) => pair.clone(),
@@ -214,6 +224,10 @@ pub fn delete_surrounds(text: Arc<str>, cx: &mut WindowContext) {
 }
 
 pub fn change_surrounds(text: Arc<str>, target: Object, cx: &mut WindowContext) {
+    if target == Object::Tag {
+        change_tag_surrounds(text, cx);
+        return;
+    }
     if let Some(will_replace_pair) = object_to_bracket_pair(target) {
         Vim::update(cx, |vim, cx| {
             vim.stop_recording();
@@ -221,16 +235,7 @@ pub fn change_surrounds(text: Arc<str>, target: Object, cx: &mut WindowContext)
                 editor.transact(cx, |editor, cx| {
                     editor.set_clip_at_line_ends(false, cx);
 
-                    let pair = match find_surround_pair(&all_support_surround_pair(), &text) {
-                        Some(pair) => pair.clone(),
-                        None => BracketPair {
-                            start: text.to_string(),
-                            end: text.to_string(),
-                            close: true,
-                            newline: false,
-                        },
-                    };
-                    let surround = pair.end != *text;
+                    let (pair, surround) = surround_pair(&text);
                     let (display_map, selections) = editor.selections.all_adjusted_display(cx);
                     let mut edits = Vec::new();
                     let mut anchors = Vec::new();
@@ -334,6 +339,28 @@ pub fn check_and_move_to_valid_bracket_pair(
     cx: &mut WindowContext,
 ) -> bool {
     let mut valid = false;
+    if object == Object::Tag {
+        vim.update_active_editor(cx, |_, editor, cx| {
+            let (display_map, selections) = editor.selections.all_adjusted_display(cx);
+            let anchors = selections
+                .into_iter()
+                .map(|selection| {
+                    let start = match tag_ranges(&display_map, selection.clone()) {
+                        Some((open, _)) => {
+                            valid = true;
+                            open.start
+                        }
+                        None => selection.start.to_offset(&display_map, Bias::Left),
+                    };
+                    start..start
+                })
+                .collect::<Vec<_>>();
+            editor.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                s.select_ranges(anchors);
+            });
+        });
+        return valid;
+    }
     if let Some(pair) = object_to_bracket_pair(object) {
         vim.update_active_editor(cx, |_, editor, cx| {
             editor.transact(cx, |editor, cx| {
@@ -378,6 +405,125 @@ pub fn check_and_move_to_valid_bracket_pair(
     return valid;
 }
 
+/// Deletes the tags around each cursor, leaving what they contain.
+fn delete_tag_surrounds(vim: &mut Vim, cx: &mut WindowContext) {
+    vim.update_active_editor(cx, |_, editor, cx| {
+        editor.transact(cx, |editor, cx| {
+            let (display_map, selections) = editor.selections.all_display(cx);
+            let mut edits = Vec::new();
+            let mut anchors = Vec::new();
+
+            for selection in selections {
+                let start = selection.start.to_offset(&display_map, Bias::Left);
+                match tag_ranges(&display_map, selection) {
+                    Some((open, close)) => {
+                        anchors.push(open.start..open.start);
+                        edits.push((open, String::new()));
+                        edits.push((close, String::new()));
+                    }
+                    None => anchors.push(start..start),
+                }
+            }
+
+            editor.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                s.select_ranges(anchors);
+            });
+            edits.sort_by_key(|(range, _)| range.start);
+            editor.buffer().update(cx, |buffer, cx| {
+                buffer.edit(edits, None, cx);
+            });
+        });
+    });
+}
+
+/// Replaces the tags around each cursor, which has been moved to the start of the opening tag,
+/// with the typed pair or tag.
+fn change_tag_surrounds(text: Arc<str>, cx: &mut WindowContext) {
+    Vim::update(cx, |vim, cx| {
+        vim.stop_recording();
+        vim.update_active_editor(cx, |_, editor, cx| {
+            editor.transact(cx, |editor, cx| {
+                let (pair, surround) = surround_pair(&text);
+                let (open_str, close_str) = if surround {
+                    (format!("{} ", pair.start), format!(" {}", pair.end))
+                } else {
+                    (pair.start, pair.end)
+                };
+                let (display_map, selections) = editor.selections.all_display(cx);
+                let mut edits = Vec::new();
+                for selection in selections {
+                    if let Some((open, close)) = tag_ranges(&display_map, selection) {
+                        edits.push((open, open_str.clone()));
+                        edits.push((close, close_str.clone()));
+                    }
+                }
+
+                let stable_anchors = editor
+                    .selections
+                    .disjoint_anchors()
+                    .into_iter()
+                    .map(|selection| {
+                        let start = selection.start.bias_left(&display_map.buffer_snapshot);
+                        start..start
+                    })
+                    .collect::<Vec<_>>();
+                edits.sort_by_key(|(range, _)| range.start);
+                editor.buffer().update(cx, |buffer, cx| {
+                    buffer.edit(edits, None, cx);
+                });
+                editor.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                    s.select_anchor_ranges(stable_anchors);
+                });
+            });
+        });
+    });
+}
+
+/// The offsets of the opening and closing tags around a selection. Tags are found in the
+/// syntax tree, so this works for languages like HTML and JSX.
+fn tag_ranges(
+    map: &DisplaySnapshot,
+    selection: Selection<DisplayPoint>,
+) -> Option<(Range<usize>, Range<usize>)> {
+    let outer = Object::Tag.range(map, selection.clone(), true)?;
+    let inner = Object::Tag.range(map, selection, false)?;
+    Some((
+        outer.start.to_offset(map, Bias::Left)..inner.start.to_offset(map, Bias::Left),
+        inner.end.to_offset(map, Bias::Left)..outer.end.to_offset(map, Bias::Left),
+    ))
+}
+
+/// The pair to surround text with when `text` is typed, and whether it's padded with spaces,
+/// as it is when the opening bracket is typed. A tag, like `<a href="#">`, is closed with
+/// its name, and other text that isn't a bracket or quote surrounds text on both sides.
+fn surround_pair(text: &str) -> (BracketPair, bool) {
+    let tag_name = text
+        .strip_prefix('<')
+        .and_then(|tag| tag.strip_suffix('>'))
+        .and_then(|tag| tag.split_whitespace().next());
+    if let Some(tag_name) = tag_name {
+        let pair = BracketPair {
+            start: text.to_string(),
+            end: format!("</{tag_name}>"),
+            close: true,
+            newline: false,
+        };
+        return (pair, false);
+    }
+    match find_surround_pair(&all_support_surround_pair(), text) {
+        Some(pair) => (pair.clone(), pair.end != text),
+        None => (
+            BracketPair {
+                start: text.to_string(),
+                end: text.to_string(),
+                close: true,
+                newline: false,
+            },
+            false,
+        ),
+    }
+}
+
 fn find_surround_pair<'a>(pairs: &'a [BracketPair], ch: &str) -> Option<&'a BracketPair> {
     pairs.iter().find(|pair| pair.start == ch || pair.end == ch)
 }
@@ -984,4 +1130,50 @@ mod test {
             Mode::Normal,
         );
     }
+
+    #[gpui::test]
+    async fn test_surrounds_repeat(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇone two three", Mode::Normal);
+        cx.simulate_keystrokes("y s i w )");
+        cx.assert_state("ˇ(one) two three", Mode::Normal);
+        cx.simulate_keystrokes("f t .");
+        cx.assert_state("(one) ˇ(two) three", Mode::Normal);
+
+        cx.simulate_keystrokes("c s ( ]");
+        cx.assert_state("(one) ˇ[two] three", Mode::Normal);
+        cx.simulate_keystrokes("0 .");
+        cx.assert_state("ˇ[one] [two] three", Mode::Normal);
+
+        cx.simulate_keystrokes("d s ]");
+        cx.assert_state("ˇone [two] three", Mode::Normal);
+        cx.simulate_keystrokes("f t .");
+        cx.assert_state("one ˇtwo three", Mode::Normal);
+    }
+
+    #[gpui::test]
+    async fn test_surround_tags(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new_html(cx).await;
+
+        cx.set_state("<p>ˇhello world</p>", Mode::Normal);
+        cx.simulate_keystrokes("y s i w t e m >");
+        cx.assert_state("<p>ˇ<em>hello</em> world</p>", Mode::Normal);
+        cx.simulate_keystrokes("f w .");
+        cx.assert_state("<p><em>hello</em> ˇ<em>world</em></p>", Mode::Normal);
+
+        // Attributes are only added to the opening tag.
+        cx.simulate_keystrokes("c s t < a space h r e f = \" # \" enter");
+        cx.assert_state(
+            "<p><em>hello</em> ˇ<a href=\"#\">world</a></p>",
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("c s t (");
+        cx.assert_state("<p><em>hello</em> ˇ( world )</p>", Mode::Normal);
+
+        cx.simulate_keystrokes("0 f h d s t");
+        cx.assert_state("<p>ˇhello ( world )</p>", Mode::Normal);
+        cx.simulate_keystrokes("u");
+        cx.assert_state("<p><em>ˇhello</em> ( world )</p>", Mode::Normal);
+    }
 }
@@ -1039,6 +1039,26 @@ async fn test_lowercase_marks(cx: &mut TestAppContext) {
         .assert_eq("line one\nˇtwo\nline three");
 }
 
+#[gpui::test]
+async fn test_global_marks(cx: &mut TestAppContext) {
+    let mut cx = VimTestContext::new(cx, true).await;
+
+    cx.set_state("line one\nline ˇtwo\nline three", Mode::Normal);
+    cx.simulate_keystrokes("m shift-a g g");
+    cx.read(|cx| {
+        let marks = &cx.global::<crate::Vim>().workspace_state.global_marks;
+        assert_eq!(marks[&'A'].point, language::Point::new(1, 5));
+    });
+
+    // Global marks follow edits to their file.
+    cx.simulate_keystrokes("shift-o n e w escape");
+    cx.simulate_keystrokes("` shift-a");
+    cx.assert_state("new\nline one\nline ˇtwo\nline three", Mode::Normal);
+
+    cx.simulate_keystrokes("g g ' shift-a");
+    cx.assert_state("new\nline one\nˇline two\nline three", Mode::Normal);
+}
+
 #[gpui::test]
 async fn test_lt_gt_marks(cx: &mut TestAppContext) {
     let mut cx = NeovimBackedTestContext::new(cx).await;
@@ -40,6 +40,7 @@ fn copy_selections_content_internal(
     let mut text = String::new();
     let mut clipboard_selections = Vec::with_capacity(selections.len());
     let mut ranges_to_highlight = Vec::new();
+    let mut copied_ranges = Vec::with_capacity(selections.len());
 
     vim.update_state(|state| {
         state.marks.insert(
@@ -87,6 +88,7 @@ fn copy_selections_content_internal(
             let start_anchor = buffer.anchor_after(start);
             let end_anchor = buffer.anchor_before(end);
             ranges_to_highlight.push(start_anchor..end_anchor);
+            copied_ranges.push(start..end);
 
             for chunk in buffer.text_for_range(start..end) {
                 text.push_str(chunk);
@@ -102,9 +104,27 @@ fn copy_selections_content_internal(
         }
     }
 
+    let selected_register = vim.workspace_state.selected_register.take();
+    if selected_register == Some('_') {
+        return;
+    }
+
     let setting = VimSettings::get_global(cx).use_system_clipboard;
-    if setting == UseSystemClipboard::Always || setting == UseSystemClipboard::OnYank && is_yank {
-        cx.write_to_clipboard(ClipboardItem::new(text.clone()).with_metadata(clipboard_selections));
+    let write_to_clipboard = match selected_register {
+        Some('+' | '*') => true,
+        Some(_) => false,
+        None => {
+            setting == UseSystemClipboard::Always
+                || setting == UseSystemClipboard::OnYank && is_yank
+        }
+    };
+    if write_to_clipboard {
+        let item = ClipboardItem::new(text.clone()).with_metadata(clipboard_selections);
+        editor.confirm_copying_redacted_values(
+            &copied_ranges,
+            move |_, cx| cx.write_to_clipboard(item),
+            cx,
+        );
         vim.workspace_state
             .registers
             .insert(".system.".to_string(), text.clone());
@@ -116,7 +136,7 @@ fn copy_selections_content_internal(
                 .unwrap_or_default(),
         );
     }
-    vim.workspace_state.registers.insert("\"".to_string(), text);
+    vim.write_registers(text, selected_register, is_yank, linewise);
     if !is_yank || vim.state().mode == Mode::Visual {
         return;
     }
@@ -5,22 +5,32 @@ mod test;
 
 mod change_list;
 mod command;
+mod confirm_replace;
 mod editor_events;
+mod global;
 mod insert;
+mod jump_list;
+mod macros;
+mod mappings;
 mod mode_indicator;
 mod motion;
 mod normal;
 mod object;
+mod persistence;
+mod registers;
 mod replace;
 mod state;
 mod surrounds;
 mod utils;
 mod visual;
+mod window;
 
 use anyhow::Result;
 use change_list::push_to_change_list;
 use collections::HashMap;
-use command_palette_hooks::{CommandPaletteFilter, CommandPaletteInterceptor};
+use command_palette_hooks::{
+    CommandPaletteCompleter, CommandPaletteFilter, CommandPaletteInterceptor,
+};
 use editor::{
     movement::{self, FindRange},
     Anchor, Bias, Editor, EditorEvent, EditorMode, ToPoint,
@@ -30,20 +40,21 @@ use gpui::{
     Subscription, UpdateGlobal, View, ViewContext, WeakView, WindowContext,
 };
 use language::{CursorShape, Point, SelectionGoal, TransactionId};
+pub use mappings::{mapping_bindings, VimMappingsSetting};
 pub use mode_indicator::ModeIndicator;
 use motion::Motion;
 use normal::{
     mark::{create_mark, create_mark_after, create_mark_before},
-    normal_replace,
+    normal_replace, InsertBefore,
 };
 use replace::multi_replace;
 use schemars::JsonSchema;
 use serde::Deserialize;
 use serde_derive::Serialize;
 use settings::{update_settings_file, Settings, SettingsSources, SettingsStore};
-use state::{EditorState, Mode, Operator, RecordedSelection, WorkspaceState};
+use state::{EditorState, Mode, Operator, RecordedChange, RecordedSelection, WorkspaceState};
 use std::{ops::Range, sync::Arc};
-use surrounds::{add_surrounds, change_surrounds, delete_surrounds};
+use surrounds::{add_surrounds, change_surrounds, delete_surrounds, starts_tag, TagSurround};
 use ui::BorrowAppContext;
 use visual::{visual_block_motion, visual_replace};
 use workspace::{self, Workspace};
@@ -93,9 +104,11 @@ pub fn init(cx: &mut AppContext) {
     cx.set_global(Vim::default());
     VimModeSetting::register(cx);
     VimSettings::register(cx);
+    VimMappingsSetting::register(cx);
 
     cx.observe_keystrokes(observe_keystrokes).detach();
     editor_events::init(cx);
+    normal::mark::save_global_marks_on_quit(cx);
 
     cx.observe_new_views(|workspace: &mut Workspace, cx| register(workspace, cx))
         .detach();
@@ -158,32 +171,39 @@ fn register(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
     insert::register(workspace, cx);
     motion::register(workspace, cx);
     command::register(workspace, cx);
+    global::register(workspace, cx);
     replace::register(workspace, cx);
     object::register(workspace, cx);
     visual::register(workspace, cx);
     change_list::register(workspace, cx);
+    jump_list::register(workspace, cx);
+    registers::register(workspace, cx);
+    macros::register(workspace, cx);
+    mappings::register(workspace, cx);
+    window::register(workspace, cx);
+}
+
+/// Whether an editor action that's bound in normal or visual mode, like `g c c`, changes the
+/// buffer, so that `.` repeats it.
+fn is_repeatable_editor_action(action: &dyn Action) -> bool {
+    action.as_any().is::<editor::actions::ToggleComments>()
 }
 
 /// Called whenever an keystroke is typed so vim can observe all actions
 /// and keystrokes accordingly.
 fn observe_keystrokes(keystroke_event: &KeystrokeEvent, cx: &mut WindowContext) {
+    if Vim::read(cx).workspace_state.recording_register.is_some() {
+        Vim::update(cx, |vim, _| {
+            vim.record_keystroke(&keystroke_event.keystroke)
+        });
+    }
+
     if let Some(action) = keystroke_event
         .action
         .as_ref()
         .map(|action| action.boxed_clone())
     {
-        Vim::update(cx, |vim, _| {
-            if vim.workspace_state.recording {
-                vim.workspace_state
-                    .recorded_actions
-                    .push(ReplayableAction::Action(action.boxed_clone()));
-
-                if vim.workspace_state.stop_recording_after_next_action {
-                    vim.workspace_state.recording = false;
-                    vim.workspace_state.stop_recording_after_next_action = false;
-                }
-            }
-        });
+        Vim::update(cx, |vim, cx| vim.record_action(action.boxed_clone(), cx));
 
         // Keystroke is handled by the vim system, so continue forward
         if action.name().starts_with("vim::") {
@@ -201,8 +221,13 @@ fn observe_keystrokes(keystroke_event: &KeystrokeEvent, cx: &mut WindowContext)
             | Operator::AddSurrounds { .. }
             | Operator::ChangeSurrounds { .. }
             | Operator::DeleteSurrounds
+            | Operator::SurroundTag { .. }
             | Operator::Mark
-            | Operator::Jump { .. },
+            | Operator::Jump { .. }
+            | Operator::Register
+            | Operator::RecordRegister
+            | Operator::ReplayRegister
+            | Operator::ConfirmReplace,
         ) => {}
         Some(_) => {
             vim.clear_operator(cx);
@@ -292,22 +317,70 @@ impl Vim {
         range_to_replace: Option<Range<isize>>,
         cx: &mut WindowContext,
     ) {
-        Vim::update(cx, |vim, _| {
+        Vim::update(cx, |vim, cx| {
             if vim.workspace_state.recording {
-                vim.workspace_state
-                    .recorded_actions
-                    .push(ReplayableAction::Insertion {
-                        text: text.clone(),
-                        utf16_range_to_replace: range_to_replace,
-                    });
+                let change = &mut vim.workspace_state.recorded_change;
+                change.actions.push(ReplayableAction::Insertion {
+                    text: text.clone(),
+                    utf16_range_to_replace: range_to_replace,
+                });
+                change.changed = true;
                 if vim.workspace_state.stop_recording_after_next_action {
-                    vim.workspace_state.recording = false;
-                    vim.workspace_state.stop_recording_after_next_action = false;
+                    vim.finish_recording(cx);
                 }
             }
         });
     }
 
+    /// Records an action dispatched by a keystroke as part of the change being recorded.
+    fn record_action(&mut self, action: Box<dyn Action>, cx: &mut WindowContext) {
+        let mode = self.state().mode;
+        if is_repeatable_editor_action(&*action)
+            && !self.workspace_state.replaying
+            && mode != Mode::Insert
+            && mode != Mode::Replace
+        {
+            // The action has already edited the buffer, so it's recorded as a change itself.
+            self.start_recording(cx);
+            self.workspace_state.recorded_change.changed = true;
+            self.stop_recording();
+        }
+
+        if !self.workspace_state.recording {
+            return;
+        }
+        // Like in vim, moving the cursor in insert mode starts a new insertion, and only
+        // the text inserted after the last movement is repeated, as if it was typed after `i`.
+        if mode == Mode::Insert && self.is_cursor_movement(&*action, cx) {
+            self.workspace_state.recorded_change = RecordedChange {
+                actions: vec![ReplayableAction::Action(InsertBefore.boxed_clone())],
+                changed: true,
+                ..Default::default()
+            };
+            return;
+        }
+        self.workspace_state
+            .recorded_change
+            .actions
+            .push(ReplayableAction::Action(action));
+        if self.workspace_state.stop_recording_after_next_action {
+            self.finish_recording(cx);
+        }
+    }
+
+    fn is_cursor_movement(&self, action: &dyn Action, cx: &WindowContext) -> bool {
+        let name = action.name();
+        if !name.starts_with("editor::Move") || name.starts_with("editor::MoveLine") {
+            return false;
+        }
+        // Moving through the completions menu doesn't move the cursor.
+        !self
+            .active_editor
+            .as_ref()
+            .and_then(|editor| editor.upgrade())
+            .is_some_and(|editor| editor.read(cx).context_menu_visible())
+    }
+
     fn update_active_editor<S>(
         &mut self,
         cx: &mut WindowContext,
@@ -334,8 +407,11 @@ impl Vim {
     pub fn start_recording(&mut self, cx: &mut WindowContext) {
         if !self.workspace_state.replaying {
             self.workspace_state.recording = true;
-            self.workspace_state.recorded_actions = Default::default();
-            self.workspace_state.recorded_count = None;
+            self.workspace_state.recorded_change = RecordedChange {
+                register: self.workspace_state.selected_register,
+                edit_count: self.active_editor_edit_count(cx),
+                ..Default::default()
+            };
 
             let selections = self
                 .active_editor
@@ -350,7 +426,7 @@ impl Vim {
                 });
 
             if let Some((oldest, newest)) = selections {
-                self.workspace_state.recorded_selection = match self.state().mode {
+                self.workspace_state.recorded_change.selection = match self.state().mode {
                     Mode::Visual if newest.end.row == newest.start.row => {
                         RecordedSelection::SingleLine {
                             cols: newest.end.column - newest.start.column,
@@ -370,7 +446,7 @@ impl Vim {
                     _ => RecordedSelection::None,
                 }
             } else {
-                self.workspace_state.recorded_selection = RecordedSelection::None;
+                self.workspace_state.recorded_change.selection = RecordedSelection::None;
             }
         }
     }
@@ -392,23 +468,59 @@ impl Vim {
     /// next action to stop recording.
     ///
     /// This doesn't include the current action.
-    pub fn stop_recording_immediately(&mut self, action: Box<dyn Action>) {
+    pub fn stop_recording_immediately(&mut self, action: Box<dyn Action>, cx: &mut WindowContext) {
         if self.workspace_state.recording {
             self.workspace_state
-                .recorded_actions
+                .recorded_change
+                .actions
                 .push(ReplayableAction::Action(action.boxed_clone()));
-            self.workspace_state.recording = false;
-            self.workspace_state.stop_recording_after_next_action = false;
+            self.finish_recording(cx);
         }
     }
 
+    /// Stops recording, and keeps the recorded change for `.` to repeat, unless it was an
+    /// operator that was cancelled before it changed anything.
+    fn finish_recording(&mut self, cx: &mut WindowContext) {
+        self.workspace_state.recording = false;
+        self.workspace_state.stop_recording_after_next_action = false;
+        let mut change = std::mem::take(&mut self.workspace_state.recorded_change);
+        // Editor events arrive after the keystroke that caused them is observed, so edits are
+        // detected by the buffer's edit count instead.
+        change.changed |= self.active_editor_edit_count(cx) != change.edit_count;
+        if change.changed {
+            self.workspace_state.last_change = change;
+        }
+    }
+
+    fn active_editor_edit_count(&self, cx: &mut WindowContext) -> Option<usize> {
+        let editor = self.active_editor.as_ref()?.upgrade()?;
+        let buffer = editor.read(cx).buffer().clone();
+        Some(buffer.read(cx).snapshot(cx).edit_count())
+    }
+
+    /// Discards the change being recorded, such as an operator that was never completed.
+    pub fn cancel_recording(&mut self) {
+        self.workspace_state.recording = false;
+        self.workspace_state.stop_recording_after_next_action = false;
+        self.workspace_state.recorded_change = RecordedChange::default();
+    }
+
     /// Explicitly record one action (equivalents to start_recording and stop_recording)
     pub fn record_current_action(&mut self, cx: &mut WindowContext) {
+        // Actions in insert mode, like `ctrl-t`, are part of the insertion being recorded.
+        if self.workspace_state.recording
+            && matches!(self.state().mode, Mode::Insert | Mode::Replace)
+        {
+            return;
+        }
         self.start_recording(cx);
         self.stop_recording();
     }
 
     fn switch_mode(&mut self, mode: Mode, leave_selections: bool, cx: &mut WindowContext) {
+        if self.state().confirm_replace.is_some() {
+            self.stop_confirm_replace(cx);
+        }
         let state = self.state();
         let last_mode = state.mode;
         let prior_mode = state.last_mode;
@@ -422,6 +534,10 @@ impl Vim {
         });
         if mode != Mode::Insert {
             self.take_count(cx);
+            self.workspace_state.selected_register.take();
+        }
+        if self.workspace_state.recording && matches!(mode, Mode::Insert | Mode::Replace) {
+            self.workspace_state.recorded_change.changed = true;
         }
 
         // Sync editor settings like clip mode
@@ -514,7 +630,7 @@ impl Vim {
 
     fn take_count(&mut self, cx: &mut WindowContext) -> Option<usize> {
         if self.workspace_state.replaying {
-            return self.workspace_state.recorded_count;
+            return self.workspace_state.replayed_count;
         }
 
         let count = if self.state().post_count == None && self.state().pre_count == None {
@@ -525,7 +641,7 @@ impl Vim {
             }))
         };
         if self.workspace_state.recording {
-            self.workspace_state.recorded_count = count;
+            self.workspace_state.recorded_change.count = count;
         }
         self.sync_vim_settings(cx);
         count
@@ -534,7 +650,12 @@ impl Vim {
     fn push_operator(&mut self, operator: Operator, cx: &mut WindowContext) {
         if matches!(
             operator,
-            Operator::Change | Operator::Delete | Operator::Replace
+            Operator::Change
+                | Operator::Delete
+                | Operator::Replace
+                | Operator::AddSurrounds { target: None }
+                | Operator::ChangeSurrounds { target: None }
+                | Operator::DeleteSurrounds
         ) {
             self.start_recording(cx)
         };
@@ -566,6 +687,7 @@ impl Vim {
 
     fn clear_operator(&mut self, cx: &mut WindowContext) {
         self.take_count(cx);
+        self.workspace_state.selected_register.take();
         self.update_state(|state| state.operator_stack.clear());
         self.sync_vim_settings(cx);
     }
@@ -709,6 +831,19 @@ impl Vim {
             Some(Operator::AddSurrounds { target }) => match Vim::read(cx).state().mode {
                 Mode::Normal => {
                     if let Some(target) = target {
+                        if starts_tag(&text) {
+                            Vim::update(cx, |vim, cx| {
+                                vim.pop_operator(cx);
+                                vim.push_operator(
+                                    Operator::SurroundTag {
+                                        surround: TagSurround::Add(target),
+                                        tag: String::new(),
+                                    },
+                                    cx,
+                                )
+                            });
+                            return;
+                        }
                         add_surrounds(text, target, cx);
                         Vim::update(cx, |vim, cx| vim.clear_operator(cx));
                     }
@@ -718,6 +853,19 @@ impl Vim {
             Some(Operator::ChangeSurrounds { target }) => match Vim::read(cx).state().mode {
                 Mode::Normal => {
                     if let Some(target) = target {
+                        if starts_tag(&text) {
+                            Vim::update(cx, |vim, cx| {
+                                vim.pop_operator(cx);
+                                vim.push_operator(
+                                    Operator::SurroundTag {
+                                        surround: TagSurround::Change(target),
+                                        tag: String::new(),
+                                    },
+                                    cx,
+                                )
+                            });
+                            return;
+                        }
                         change_surrounds(text, target, cx);
                         Vim::update(cx, |vim, cx| vim.clear_operator(cx));
                     }
@@ -731,10 +879,41 @@ impl Vim {
                 }
                 _ => Vim::update(cx, |vim, cx| vim.clear_operator(cx)),
             },
+            Some(Operator::SurroundTag { surround, tag }) => {
+                // The tag is typed until it's closed with `>` or enter.
+                if &*text != ">" && &*text != "\n" {
+                    Vim::update(cx, |vim, _| {
+                        vim.update_state(|state| {
+                            if let Some(Operator::SurroundTag { tag, .. }) =
+                                state.operator_stack.last_mut()
+                            {
+                                tag.push_str(&text);
+                            }
+                        })
+                    });
+                    return;
+                }
+                if !tag.trim().is_empty() {
+                    let text: Arc<str> = format!("<{}>", tag.trim()).into();
+                    match surround {
+                        TagSurround::Add(target) => add_surrounds(text, target, cx),
+                        TagSurround::Change(target) => change_surrounds(text, target, cx),
+                    }
+                }
+                Vim::update(cx, |vim, cx| vim.clear_operator(cx));
+            }
             Some(Operator::Mark) => Vim::update(cx, |vim, cx| {
                 normal::mark::create_mark(vim, text, false, cx)
             }),
             Some(Operator::Jump { line }) => normal::mark::jump(text, line, cx),
+            Some(Operator::Register) => Vim::update(cx, |vim, cx| vim.select_register(text, cx)),
+            Some(Operator::RecordRegister) => {
+                Vim::update(cx, |vim, cx| vim.start_recording_macro(text, cx))
+            }
+            Some(Operator::ReplayRegister) => Vim::update(cx, |vim, cx| vim.replay_macro(text, cx)),
+            Some(Operator::ConfirmReplace) => {
+                Vim::update(cx, |vim, cx| vim.answer_confirm_replace(text, cx))
+            }
             _ => match Vim::read(cx).state().mode {
                 Mode::Replace => multi_replace(text, cx),
                 _ => {}
@@ -750,6 +929,9 @@ impl Vim {
             CommandPaletteInterceptor::update_global(cx, |interceptor, _| {
                 interceptor.clear();
             });
+            CommandPaletteCompleter::update_global(cx, |completer, _| {
+                completer.clear();
+            });
             CommandPaletteFilter::update_global(cx, |filter, _| {
                 filter.hide_namespace(Self::NAMESPACE);
             });
@@ -758,12 +940,16 @@ impl Vim {
         }
 
         self.enabled = true;
+        self.workspace_state.global_marks = normal::mark::load_global_marks();
         CommandPaletteFilter::update_global(cx, |filter, _| {
             filter.show_namespace(Self::NAMESPACE);
         });
         CommandPaletteInterceptor::update_global(cx, |interceptor, _| {
             interceptor.set(Box::new(command::command_interceptor));
         });
+        CommandPaletteCompleter::update_global(cx, |completer, _| {
+            completer.set(Box::new(command::command_completions));
+        });
 
         if let Some(active_window) = cx
             .active_window()
@@ -814,7 +1000,11 @@ impl Vim {
             editor.set_autoindent(state.should_autoindent());
             editor.selections.line_mode = matches!(state.mode, Mode::VisualLine);
             if editor.is_focused(cx) || editor.mouse_menu_is_focused(cx) {
-                editor.set_keymap_context_layer::<Self>(state.keymap_context_layer(), cx);
+                let mut context = state.keymap_context_layer();
+                if vim.workspace_state.noremap {
+                    context.add("VimNoremap");
+                }
+                editor.set_keymap_context_layer::<Self>(context, cx);
                 // disable vim mode if a sub-editor (inline assist, rename, etc.) is focused
             } else if editor.focus_handle(cx).contains_focused(cx) {
                 editor.remove_keymap_context_layer::<Self>(cx);
@@ -867,6 +1057,7 @@ struct VimSettings {
     pub use_system_clipboard: UseSystemClipboard,
     pub use_multiline_find: bool,
     pub use_smartcase_find: bool,
+    pub use_smartcase_star_search: bool,
 }
 
 #[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
@@ -874,6 +1065,7 @@ struct VimSettingsContent {
     pub use_system_clipboard: Option<UseSystemClipboard>,
     pub use_multiline_find: Option<bool>,
     pub use_smartcase_find: Option<bool>,
+    pub use_smartcase_star_search: Option<bool>,
 }
 
 impl Settings for VimSettings {
@@ -2,7 +2,7 @@ use std::sync::Arc;
 
 use collections::HashMap;
 use editor::{
-    display_map::{DisplaySnapshot, ToDisplayPoint},
+    display_map::{DisplayRow, DisplaySnapshot, ToDisplayPoint},
     movement,
     scroll::Autoscroll,
     Bias, DisplayPoint, Editor,
@@ -36,6 +36,8 @@ actions!(
         SelectPrevious,
         SelectNextMatch,
         SelectPreviousMatch,
+        VisualInsert,
+        VisualAppend,
     ]
 );
 
@@ -64,6 +66,9 @@ pub fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
         });
     });
 
+    workspace.register_action(visual_insert);
+    workspace.register_action(visual_append);
+
     workspace.register_action(select_next);
     workspace.register_action(select_previous);
     workspace.register_action(|workspace, _: &SelectNextMatch, cx| {
@@ -305,14 +310,16 @@ pub fn visual_object(object: Object, cx: &mut WindowContext) {
                                 }
                             }
 
-                            // In the visual selection result of a paragraph object, the cursor is
-                            // placed at the start of the last line. And in the visual mode, the
-                            // selection end is located after the end character. So, adjustment of
-                            // selection end is needed.
+                            // In the visual selection result of a paragraph or indent object, the
+                            // cursor is placed at the start of the last line. And in the visual
+                            // mode, the selection end is located after the end character. So,
+                            // adjustment of selection end is needed.
                             //
                             // We don't do this adjustment for a one-line blank paragraph since the
                             // trailing newline is included in its selection from the beginning.
-                            if object == Object::Paragraph && range.start != range.end {
+                            if matches!(object, Object::Paragraph | Object::IndentObj { .. })
+                                && range.start != range.end
+                            {
                                 let row_of_selection_end_line = selection.end.to_point(map).row;
                                 let new_selection_end = if map
                                     .buffer_snapshot
@@ -459,6 +466,106 @@ pub(crate) fn visual_replace(text: Arc<str>, cx: &mut WindowContext) {
     });
 }
 
+/// Inserts before the selection, or on every line of the block in visual block mode.
+fn visual_insert(_: &mut Workspace, _: &VisualInsert, cx: &mut ViewContext<Workspace>) {
+    Vim::update(cx, |vim, cx| {
+        vim.start_recording(cx);
+        let mode = vim.state().mode;
+        vim.switch_mode(Mode::Insert, true, cx);
+        vim.update_active_editor(cx, |_, editor, cx| {
+            let (map, selections) = editor.selections.all_display(cx);
+            // Lines that end before the block starts are left alone.
+            let block_start = selections
+                .iter()
+                .filter(|selection| !selection.is_empty())
+                .map(|selection| selection.start.column())
+                .min()
+                .unwrap_or(0);
+            let starts = selections
+                .iter()
+                .filter(|selection| {
+                    mode != Mode::VisualBlock || selection.start.column() >= block_start
+                })
+                .map(|selection| match mode {
+                    Mode::VisualLine => start_of_line(&map, false, selection.start),
+                    _ => selection.start,
+                })
+                .map(|start| start.to_point(&map))
+                .collect::<Vec<_>>();
+            editor.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                s.select_ranges(starts.into_iter().map(|start| start..start))
+            });
+        });
+    });
+}
+
+/// Appends after the selection, or on every line of the block in visual block mode.
+fn visual_append(_: &mut Workspace, _: &VisualAppend, cx: &mut ViewContext<Workspace>) {
+    Vim::update(cx, |vim, cx| {
+        vim.start_recording(cx);
+        let mode = vim.state().mode;
+        // The block's padding is inserted in insert mode, so that it's undone along with the
+        // text typed after it.
+        vim.switch_mode(Mode::Insert, true, cx);
+        vim.update_active_editor(cx, |_, editor, cx| {
+            if mode == Mode::VisualBlock {
+                append_to_block(editor, cx);
+                return;
+            }
+            editor.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                s.move_with(|map, selection| {
+                    let point = if mode == Mode::VisualLine {
+                        let mut row = selection.end.row();
+                        if selection.end.column() == 0 && row > selection.start.row() {
+                            row.0 -= 1;
+                        }
+                        DisplayPoint::new(row, map.line_len(row))
+                    } else {
+                        selection.end
+                    };
+                    selection.collapse_to(point, SelectionGoal::None)
+                })
+            })
+        });
+    });
+}
+
+/// Puts a cursor after the block on each of its lines, padding lines that end before the block
+/// does with spaces, as vim does. If the block extends to the end of every line, as it does
+/// after `$`, the cursors go at the end of each line instead.
+fn append_to_block(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+    let (map, selections) = editor.selections.all_display(cx);
+    let (Some(first), Some(last)) = (selections.first(), selections.last()) else {
+        return;
+    };
+    let rows = first.start.row().0..=last.end.row().0;
+    let to_line_ends = selections
+        .iter()
+        .all(|selection| selection.end.column() == map.line_len(selection.end.row()));
+    let end_column = selections
+        .iter()
+        .map(|selection| selection.end.column())
+        .max()
+        .unwrap_or(0);
+
+    let mut edits = Vec::new();
+    let mut cursors = Vec::new();
+    for row in rows.map(DisplayRow) {
+        let line_len = map.line_len(row);
+        let column = if to_line_ends { line_len } else { end_column };
+        let point = DisplayPoint::new(row, column.min(line_len)).to_point(&map);
+        cursors.push(map.buffer_snapshot.anchor_after(point));
+        if column > line_len {
+            edits.push((point..point, " ".repeat((column - line_len) as usize)));
+        }
+    }
+
+    editor.edit(edits, cx);
+    editor.change_selections(Some(Autoscroll::fit()), cx, |s| {
+        s.select_anchor_ranges(cursors.into_iter().map(|cursor| cursor..cursor))
+    });
+}
+
 pub fn select_next(_: &mut Workspace, _: &SelectNext, cx: &mut ViewContext<Workspace>) {
     Vim::update(cx, |vim, cx| {
         let count =
@@ -1081,6 +1188,28 @@ mod test {
         });
     }
 
+    #[gpui::test]
+    async fn test_visual_block_insert_skips_short_lines(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("The ˇquick brown\nfox\nthe lazy dog", Mode::Normal);
+        cx.simulate_keystrokes("ctrl-v l j j shift-i X escape");
+        cx.assert_state("The ˇXquick brown\nfox\nthe Xlazy dog", Mode::Normal);
+    }
+
+    #[gpui::test]
+    async fn test_visual_block_append(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("The ˇquick brown\nfox\nthe lazy dog", Mode::Normal);
+        cx.simulate_keystrokes("ctrl-v l l j j shift-a X escape");
+        cx.assert_state("The quiˇXck brown\nfox    X\nthe lazXy dog", Mode::Normal);
+
+        cx.set_state("ˇone\nthree", Mode::Normal);
+        cx.simulate_keystrokes("ctrl-v j $ shift-a ! escape");
+        cx.assert_state("oneˇ!\nthree!", Mode::Normal);
+    }
+
     #[gpui::test]
     async fn test_visual_object(cx: &mut gpui::TestAppContext) {
         let mut cx = NeovimBackedTestContext::new(cx).await;
@@ -0,0 +1,186 @@
+//! The `ctrl-w` commands and `:tab` commands that vim's windows and tab pages don't already map
+//! onto, acting on the workspace's panes and their tabs.
+
+use gpui::{actions, impl_actions, Axis, Pixels, ViewContext};
+use serde::Deserialize;
+use workspace::Workspace;
+
+use crate::Vim;
+
+/// Which way `ctrl-w +`, `-`, `>` and `<` resize the active pane.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum ResizeIntent {
+    Lengthen,
+    Shorten,
+    Widen,
+    Narrow,
+}
+
+/// Resizes the active pane by a number of lines or columns, or by the count if none is given.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ResizePane {
+    pub intent: ResizeIntent,
+    #[serde(default)]
+    pub amount: Option<usize>,
+}
+
+/// Moves the active tab among the tabs of its pane, as `:tabmove`.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum MoveTab {
+    /// To after the tab with the given number, counting from one, or to the first position for
+    /// zero and the last position for a number past the end.
+    To(usize),
+    /// By a number of places to the right, or to the left if it's negative.
+    By(isize),
+}
+
+actions!(
+    vim,
+    [
+        ResetPaneSizes,
+        MaximizePaneHeight,
+        MaximizePaneWidth,
+        RotatePanesDownwards,
+        RotatePanesUpwards,
+        ExchangePane
+    ]
+);
+
+impl_actions!(vim, [ResizePane, MoveTab]);
+
+pub(crate) fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+    workspace.register_action(|workspace, action: &ResizePane, cx| {
+        let count = Vim::update(cx, |vim, cx| vim.take_count(cx)).unwrap_or(1);
+        let amount = action.amount.unwrap_or(count) as f32;
+        let (line_height, column_width) = cell_size(cx);
+        let (axis, amount) = match action.intent {
+            ResizeIntent::Lengthen => (Axis::Vertical, line_height * amount),
+            ResizeIntent::Shorten => (Axis::Vertical, -line_height * amount),
+            ResizeIntent::Widen => (Axis::Horizontal, column_width * amount),
+            ResizeIntent::Narrow => (Axis::Horizontal, -column_width * amount),
+        };
+        workspace.resize_active_pane(axis, amount, cx);
+    });
+    workspace.register_action(|workspace, _: &ResetPaneSizes, cx| {
+        Vim::update(cx, |vim, cx| vim.take_count(cx));
+        workspace.reset_pane_sizes(cx);
+    });
+    // A pane can't be smaller than its minimum size, so growing it by more than the size of the
+    // window makes it as large as it can be.
+    workspace.register_action(|workspace, _: &MaximizePaneHeight, cx| {
+        Vim::update(cx, |vim, cx| vim.take_count(cx));
+        let amount = cx.viewport_size().height;
+        workspace.resize_active_pane(Axis::Vertical, amount, cx);
+    });
+    workspace.register_action(|workspace, _: &MaximizePaneWidth, cx| {
+        Vim::update(cx, |vim, cx| vim.take_count(cx));
+        let amount = cx.viewport_size().width;
+        workspace.resize_active_pane(Axis::Horizontal, amount, cx);
+    });
+    workspace.register_action(|workspace, _: &RotatePanesDownwards, cx| {
+        let count = Vim::update(cx, |vim, cx| vim.take_count(cx)).unwrap_or(1);
+        for _ in 0..count {
+            workspace.rotate_panes(true, cx);
+        }
+    });
+    workspace.register_action(|workspace, _: &RotatePanesUpwards, cx| {
+        let count = Vim::update(cx, |vim, cx| vim.take_count(cx)).unwrap_or(1);
+        for _ in 0..count {
+            workspace.rotate_panes(false, cx);
+        }
+    });
+    workspace.register_action(|workspace, _: &ExchangePane, cx| {
+        Vim::update(cx, |vim, cx| vim.take_count(cx));
+        workspace.exchange_active_pane(cx);
+    });
+    workspace.register_action(|workspace, action: &MoveTab, cx| {
+        let pane = workspace.active_pane().clone();
+        let Some(item) = pane.read(cx).active_item() else {
+            return;
+        };
+        let index = pane.read(cx).active_item_index();
+        let last = pane.read(cx).items_len() - 1;
+        // The tab numbers are those before the move, when the tab is still counted.
+        let destination = match action {
+            MoveTab::To(number) if *number > index => number - 1,
+            MoveTab::To(number) => *number,
+            MoveTab::By(places) => index.saturating_add_signed(*places),
+        };
+        let item_id = item.item_id();
+        let destination = destination.min(last);
+        workspace.move_item(pane.clone(), pane, item_id, destination, cx);
+    });
+}
+
+/// The height of a line and the width of a column in the active editor, which resizing is
+/// measured in.
+fn cell_size(cx: &mut ViewContext<Workspace>) -> (Pixels, Pixels) {
+    let fallback = (cx.line_height(), cx.rem_size() / 2.);
+    Vim::update(cx, |vim, cx| {
+        vim.update_active_editor(cx, |_, editor, cx| {
+            let Some(style) = editor.style() else {
+                return fallback;
+            };
+            let font_id = cx.text_system().resolve_font(&style.text.font());
+            let font_size = style.text.font_size.to_pixels(cx.rem_size());
+            let column_width = cx
+                .text_system()
+                .advance(font_id, font_size, 'm')
+                .map_or(fallback.1, |size| size.width);
+            (
+                style.text.line_height_in_pixels(cx.rem_size()),
+                column_width,
+            )
+        })
+    })
+    .unwrap_or(fallback)
+}
+
+#[cfg(test)]
+mod test {
+    use gpui::EntityId;
+
+    use crate::test::VimTestContext;
+
+    /// The index of the active tab and the id of its item.
+    fn active_tab(cx: &mut VimTestContext) -> (usize, EntityId) {
+        cx.workspace(|workspace, cx| {
+            let pane = workspace.active_pane().read(cx);
+            (
+                pane.active_item_index(),
+                pane.active_item().unwrap().item_id(),
+            )
+        })
+    }
+
+    #[gpui::test]
+    async fn test_tab_commands(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.simulate_keystrokes(": t a b n e w enter");
+        cx.simulate_keystrokes(": t a b n e w enter");
+        cx.simulate_keystrokes(": t a b n e w enter");
+        assert_eq!(active_tab(&mut cx).0, 3);
+
+        cx.simulate_keystrokes(": t a b f i r s t enter");
+        assert_eq!(active_tab(&mut cx).0, 0);
+        cx.simulate_keystrokes(": t a b l a s t enter");
+        assert_eq!(active_tab(&mut cx).0, 3);
+        cx.simulate_keystrokes(": t a b n space 2 enter");
+        let (index, item_id) = active_tab(&mut cx);
+        assert_eq!(index, 1);
+
+        cx.simulate_keystrokes(": t a b m enter");
+        assert_eq!(active_tab(&mut cx), (3, item_id));
+        cx.simulate_keystrokes(": t a b m space - 1 enter");
+        assert_eq!(active_tab(&mut cx), (2, item_id));
+        cx.simulate_keystrokes(": t a b m space 0 enter");
+        assert_eq!(active_tab(&mut cx), (0, item_id));
+        // after the tab that was second, which is now first
+        cx.simulate_keystrokes(": t a b m space 2 enter");
+        assert_eq!(active_tab(&mut cx), (1, item_id));
+    }
+}
@@ -0,0 +1,337 @@
+use crate::wat::{Disassembly, OutlineEntry, OutlineEntryKind};
+use anyhow::Result;
+use editor::{scroll::Autoscroll, Editor, EditorEvent};
+use fs::Fs;
+use gpui::{
+    uniform_list, AnyElement, AppContext, EventEmitter, FocusHandle, FocusableView, Model,
+    Subscription, Task, View,
+};
+use language::Point;
+use project::Project;
+use std::{
+    io::Read as _,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use ui::{prelude::*, ListItem};
+use util::ResultExt;
+use workspace::item::{Item, ProjectItem, TabContentParams};
+
+/// Shows a WebAssembly module or component as its read-only text format, next to an outline of
+/// its sections and named fields, which scrolls the disassembly to the entry that's clicked.
+pub struct WasmView {
+    abs_path: PathBuf,
+    project: Model<Project>,
+    state: DisassemblyState,
+    editor: View<Editor>,
+    filter_editor: View<Editor>,
+    /// The indices of the outline entries that match the filter.
+    visible_entries: Vec<usize>,
+    selected_entry: Option<usize>,
+    _load_disassembly: Task<()>,
+    _filter_subscription: Subscription,
+}
+
+enum DisassemblyState {
+    Loading,
+    Loaded {
+        is_component: bool,
+        outline: Arc<[OutlineEntry]>,
+    },
+    Failed(SharedString),
+}
+
+impl WasmView {
+    pub fn new(abs_path: PathBuf, project: Model<Project>, cx: &mut ViewContext<Self>) -> Self {
+        let editor = cx.new_view(|cx| {
+            let mut editor = Editor::multi_line(cx);
+            editor.set_read_only(true);
+            editor.set_show_inline_completions(false);
+            editor
+        });
+        let filter_editor = cx.new_view(|cx| {
+            let mut editor = Editor::single_line(cx);
+            editor.set_placeholder_text("Filter symbols…", cx);
+            editor
+        });
+        let filter_subscription =
+            cx.subscribe(&filter_editor, |this, _, event: &EditorEvent, cx| {
+                if let EditorEvent::BufferEdited = event {
+                    this.update_visible_entries(cx);
+                }
+            });
+
+        let fs = project.read(cx).fs().clone();
+        let load_disassembly = cx.spawn({
+            let abs_path = abs_path.clone();
+            |this, mut cx| async move {
+                let disassembly = cx
+                    .background_executor()
+                    .spawn(async move { load_disassembly(fs, &abs_path).await })
+                    .await;
+                this.update(&mut cx, |this, cx| match disassembly {
+                    Ok(disassembly) => {
+                        this.editor.update(cx, |editor, cx| {
+                            editor.set_text(disassembly.text, cx);
+                        });
+                        this.state = DisassemblyState::Loaded {
+                            is_component: disassembly.is_component,
+                            outline: disassembly.outline.into(),
+                        };
+                        this.update_visible_entries(cx);
+                    }
+                    Err(error) => {
+                        this.state = DisassemblyState::Failed(format!("{error:#}").into());
+                        cx.notify();
+                    }
+                })
+                .log_err();
+            }
+        });
+
+        Self {
+            abs_path,
+            project,
+            state: DisassemblyState::Loading,
+            editor,
+            filter_editor,
+            visible_entries: Vec::new(),
+            selected_entry: None,
+            _load_disassembly: load_disassembly,
+            _filter_subscription: filter_subscription,
+        }
+    }
+
+    fn outline(&self) -> Option<&Arc<[OutlineEntry]>> {
+        match &self.state {
+            DisassemblyState::Loaded { outline, .. } => Some(outline),
+            _ => None,
+        }
+    }
+
+    /// Lists every entry of the outline, or only the modules and symbols whose name contains the
+    /// filter.
+    fn update_visible_entries(&mut self, cx: &mut ViewContext<Self>) {
+        let query = self.filter_editor.read(cx).text(cx).trim().to_lowercase();
+        self.visible_entries = self
+            .outline()
+            .map(|outline| {
+                outline
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, entry)| {
+                        query.is_empty()
+                            || (!matches!(entry.kind, OutlineEntryKind::Section { .. })
+                                && entry.label.to_lowercase().contains(&query))
+                    })
+                    .map(|(ix, _)| ix)
+                    .collect()
+            })
+            .unwrap_or_default();
+        cx.notify();
+    }
+
+    fn jump_to_entry(&mut self, entry_ix: usize, cx: &mut ViewContext<Self>) {
+        let Some(entry) = self.outline().and_then(|outline| outline.get(entry_ix)) else {
+            return;
+        };
+        let point = Point::new(entry.row, 0);
+        self.selected_entry = Some(entry_ix);
+        self.editor.update(cx, |editor, cx| {
+            editor.change_selections(Some(Autoscroll::top_relative(0)), cx, |selections| {
+                selections.select_ranges([point..point])
+            });
+        });
+        cx.focus_view(&self.editor);
+        cx.notify();
+    }
+
+    fn render_outline_entry(&self, entry_ix: usize, cx: &mut ViewContext<Self>) -> ListItem {
+        let entry = &self
+            .outline()
+            .expect("the outline is only rendered once loaded")[entry_ix];
+        let label = Label::new(entry.label.clone()).single_line();
+        let label = match entry.kind {
+            OutlineEntryKind::Symbol => label.color(Color::Muted),
+            OutlineEntryKind::Module | OutlineEntryKind::Section { .. } => label,
+        };
+        let icon = (entry.kind == OutlineEntryKind::Module).then(|| {
+            Icon::new(IconName::Code)
+                .size(IconSize::Small)
+                .color(Color::Muted)
+        });
+        let count = match entry.kind {
+            OutlineEntryKind::Section { count } => Some(
+                Label::new(count.to_string())
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            ),
+            _ => None,
+        };
+
+        ListItem::new(entry_ix)
+            .inset(true)
+            .indent_level(entry.depth)
+            .indent_step_size(px(12.))
+            .selected(self.selected_entry == Some(entry_ix))
+            .start_slot(icon)
+            .child(label)
+            .end_slot(count)
+            .on_click(cx.listener(move |this, _, cx| this.jump_to_entry(entry_ix, cx)))
+    }
+
+    fn render_outline(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let kind = match &self.state {
+            DisassemblyState::Loaded { is_component, .. } => {
+                if *is_component {
+                    "WebAssembly Component"
+                } else {
+                    "WebAssembly Module"
+                }
+            }
+            _ => "WebAssembly",
+        };
+
+        v_flex()
+            .w(px(260.))
+            .h_full()
+            .border_r_1()
+            .border_color(cx.theme().colors().border)
+            .child(
+                v_flex()
+                    .p_2()
+                    .gap_2()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(
+                        h_flex().justify_between().child(Label::new(kind)).child(
+                            Label::new("Read-only")
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        ),
+                    )
+                    .child(
+                        div()
+                            .px_2()
+                            .py_1()
+                            .rounded_md()
+                            .border_1()
+                            .border_color(cx.theme().colors().border)
+                            .child(self.filter_editor.clone()),
+                    ),
+            )
+            .child(
+                uniform_list(
+                    cx.view().clone(),
+                    "wasm-outline",
+                    self.visible_entries.len(),
+                    |this, range, cx| {
+                        range
+                            .map(|ix| this.render_outline_entry(this.visible_entries[ix], cx))
+                            .collect()
+                    },
+                )
+                .flex_1(),
+            )
+    }
+}
+
+async fn load_disassembly(fs: Arc<dyn Fs>, path: &Path) -> Result<Disassembly> {
+    let mut bytes = Vec::new();
+    fs.open_sync(path).await?.read_to_end(&mut bytes)?;
+    Disassembly::new(&bytes)
+}
+
+impl Render for WasmView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let body = match &self.state {
+            DisassemblyState::Loading => v_flex()
+                .flex_1()
+                .items_center()
+                .justify_center()
+                .child(Label::new("Disassembling…").color(Color::Muted))
+                .into_any_element(),
+            DisassemblyState::Failed(error) => v_flex()
+                .flex_1()
+                .items_center()
+                .justify_center()
+                .child(Label::new(error.clone()).color(Color::Error))
+                .into_any_element(),
+            DisassemblyState::Loaded { .. } => div()
+                .flex_1()
+                .h_full()
+                .child(self.editor.clone())
+                .into_any_element(),
+        };
+
+        h_flex()
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(self.render_outline(cx))
+            .child(body)
+    }
+}
+
+impl EventEmitter<()> for WasmView {}
+
+impl FocusableView for WasmView {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.editor.focus_handle(cx)
+    }
+}
+
+impl Item for WasmView {
+    type Event = ();
+
+    fn tab_content(&self, params: TabContentParams, _: &WindowContext) -> AnyElement {
+        let title = self
+            .abs_path
+            .file_name()
+            .unwrap_or_else(|| self.abs_path.as_os_str())
+            .to_string_lossy()
+            .to_string();
+        Label::new(title)
+            .single_line()
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .italic(params.preview)
+            .into_any_element()
+    }
+
+    fn tab_tooltip_text(&self, _: &AppContext) -> Option<SharedString> {
+        Some(self.abs_path.to_string_lossy().to_string().into())
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("wasm disassembly opened")
+    }
+
+    fn clone_on_split(
+        &self,
+        _: workspace::WorkspaceId,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<View<Self>>
+    where
+        Self: Sized,
+    {
+        Some(cx.new_view(|cx| Self::new(self.abs_path.clone(), self.project.clone(), cx)))
+    }
+}
+
+impl ProjectItem for WasmView {
+    type Item = crate::WasmItem;
+
+    fn for_project_item(
+        project: Model<Project>,
+        item: Model<Self::Item>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self
+    where
+        Self: Sized,
+    {
+        Self::new(item.read(cx).abs_path.clone(), project, cx)
+    }
+}
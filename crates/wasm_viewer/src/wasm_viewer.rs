@@ -0,0 +1,51 @@
+mod wasm_view;
+mod wat;
+
+use gpui::{AppContext, Context as _, Model, Task};
+use project::{Project, ProjectEntryId, ProjectPath};
+use std::{ffi::OsStr, path::PathBuf};
+
+pub use wasm_view::WasmView;
+pub use wat::{Disassembly, OutlineEntry, OutlineEntryKind};
+
+pub fn init(cx: &mut AppContext) {
+    workspace::register_project_item::<WasmView>(cx);
+}
+
+pub struct WasmItem {
+    abs_path: PathBuf,
+    project_path: ProjectPath,
+}
+
+impl project::Item for WasmItem {
+    fn try_open(
+        project: &Model<Project>,
+        path: &ProjectPath,
+        cx: &mut AppContext,
+    ) -> Option<Task<gpui::Result<Model<Self>>>> {
+        if path.path.extension().and_then(OsStr::to_str) != Some("wasm") {
+            return None;
+        }
+
+        let path = path.clone();
+        let project = project.clone();
+        Some(cx.spawn(|mut cx| async move {
+            let abs_path = project
+                .read_with(&cx, |project, cx| project.absolute_path(&path, cx))?
+                .ok_or_else(|| anyhow::anyhow!("Failed to find the absolute path"))?;
+
+            cx.new_model(|_| WasmItem {
+                abs_path,
+                project_path: path,
+            })
+        }))
+    }
+
+    fn entry_id(&self, _: &AppContext) -> Option<ProjectEntryId> {
+        None
+    }
+
+    fn project_path(&self, _: &AppContext) -> Option<ProjectPath> {
+        Some(self.project_path.clone())
+    }
+}
@@ -0,0 +1,312 @@
+use anyhow::{Context as _, Result};
+
+/// How many spaces the disassembler indents each level of nesting by.
+const INDENT: usize = 2;
+
+/// A WebAssembly binary, disassembled into the text format.
+pub struct Disassembly {
+    pub text: String,
+    pub is_component: bool,
+    pub outline: Vec<OutlineEntry>,
+}
+
+impl Disassembly {
+    pub fn new(bytes: &[u8]) -> Result<Self> {
+        let text = wasmprinter::print_bytes(bytes).context("disassembling WebAssembly")?;
+        Ok(Self {
+            is_component: text.starts_with("(component"),
+            outline: outline(&text),
+            text,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OutlineEntry {
+    /// How deeply the entry is nested in the outline.
+    pub depth: usize,
+    pub kind: OutlineEntryKind,
+    pub label: String,
+    /// The row of the disassembly at which the entry starts.
+    pub row: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutlineEntryKind {
+    /// A module or component that's nested in a component, whose own entries follow it.
+    Module,
+    /// A run of fields of the same kind, such as the functions of a module.
+    Section { count: usize },
+    /// A field that has a name, such as a function, an import or an export.
+    Symbol,
+}
+
+/// A field of a module or component, which is a line of the disassembly that's indented one
+/// level deeper than the module that contains it.
+#[derive(Debug)]
+struct Field {
+    /// How many nested modules or components contain the field.
+    depth: usize,
+    kind: String,
+    name: Option<String>,
+    index: Option<String>,
+    row: u32,
+}
+
+impl Field {
+    fn is_module(&self) -> bool {
+        matches!(self.kind.as_str(), "module" | "component" | "core module")
+    }
+}
+
+/// Lists the sections of each module and component in the disassembly, along with the fields
+/// that have a name.
+pub fn outline(text: &str) -> Vec<OutlineEntry> {
+    let fields = fields(text);
+    let mut entries = Vec::new();
+    let mut ix = 0;
+    while ix < fields.len() {
+        let first = &fields[ix];
+        if first.is_module() {
+            let mut label = first.kind.clone();
+            if let Some(name) = first.name.as_ref().or(first.index.as_ref()) {
+                label.push(' ');
+                label.push_str(name);
+            }
+            entries.push(OutlineEntry {
+                depth: first.depth,
+                kind: OutlineEntryKind::Module,
+                label,
+                row: first.row,
+            });
+            ix += 1;
+            continue;
+        }
+
+        let section = fields[ix..]
+            .iter()
+            .take_while(|field| {
+                !field.is_module() && field.depth == first.depth && field.kind == first.kind
+            })
+            .collect::<Vec<_>>();
+        entries.push(OutlineEntry {
+            depth: first.depth,
+            kind: OutlineEntryKind::Section {
+                count: section.len(),
+            },
+            label: first.kind.clone(),
+            row: first.row,
+        });
+        entries.extend(section.iter().filter_map(|field| {
+            Some(OutlineEntry {
+                depth: field.depth + 1,
+                kind: OutlineEntryKind::Symbol,
+                label: field.name.clone()?,
+                row: field.row,
+            })
+        }));
+        ix += section.len();
+    }
+    entries
+}
+
+fn fields(text: &str) -> Vec<Field> {
+    let mut fields = Vec::new();
+    // The indentation of the modules and components that contain the current line.
+    let mut modules = Vec::<usize>::new();
+    for (row, line) in text.lines().enumerate() {
+        let trimmed = line.trim_start();
+        let indent = (line.len() - trimmed.len()) / INDENT;
+        let Some(field) = trimmed.strip_prefix('(') else {
+            continue;
+        };
+        if field.starts_with(';') {
+            continue;
+        }
+
+        while modules.last().map_or(false, |&module| module >= indent) {
+            modules.pop();
+        }
+        let (kind, rest) = field_kind(field);
+        let Some(&module) = modules.last() else {
+            if indent == 0 && matches!(kind.as_str(), "module" | "component") {
+                modules.push(0);
+            }
+            continue;
+        };
+        if module + 1 != indent {
+            continue;
+        }
+
+        let (name, index) = field_name(rest);
+        let field = Field {
+            depth: modules.len() - 1,
+            kind,
+            name,
+            index,
+            row: row as u32,
+        };
+        if field.is_module() {
+            modules.push(indent);
+        }
+        fields.push(field);
+    }
+    fields
+}
+
+/// Splits the kind of a field, such as `func` or `core module`, from the rest of its line.
+fn field_kind(field: &str) -> (String, &str) {
+    let end_of_word = |text: &str| {
+        text.find(|c: char| c.is_whitespace() || c == '(' || c == ')')
+            .unwrap_or(text.len())
+    };
+    let end = end_of_word(field);
+    let (kind, rest) = field.split_at(end);
+    if kind == "core" {
+        let rest = rest.trim_start();
+        let end = end_of_word(rest);
+        (format!("core {}", &rest[..end]), &rest[end..])
+    } else {
+        (kind.to_string(), rest)
+    }
+}
+
+/// Reads the identifier, or else the names in quotes, and the index comment from the start of a
+/// field, up to its first nested expression.
+fn field_name(mut rest: &str) -> (Option<String>, Option<String>) {
+    let mut id = None;
+    let mut strings = Vec::new();
+    let mut index = None;
+    loop {
+        rest = rest.trim_start();
+        if let Some(comment) = rest.strip_prefix("(;") {
+            let Some((comment, after)) = comment.split_once(";)") else {
+                break;
+            };
+            index.get_or_insert_with(|| comment.to_string());
+            rest = after;
+        } else if rest.is_empty() || rest.starts_with('(') || rest.starts_with(')') {
+            break;
+        } else if let Some(string) = rest.strip_prefix('"') {
+            let mut escaped = false;
+            let Some(end) = string.find(|c: char| {
+                let is_end = c == '"' && !escaped;
+                escaped = c == '\\' && !escaped;
+                is_end
+            }) else {
+                break;
+            };
+            strings.push(&string[..end]);
+            rest = &string[end + 1..];
+        } else {
+            let end = rest
+                .find(|c: char| c.is_whitespace() || c == '(' || c == ')')
+                .unwrap_or(rest.len());
+            let (token, after) = rest.split_at(end);
+            if token.starts_with('$') && id.is_none() {
+                id = Some(token.to_string());
+            }
+            rest = after;
+        }
+    }
+    let name = id.or_else(|| (!strings.is_empty()).then(|| strings.join(".")));
+    (name, index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    fn entry(depth: usize, kind: OutlineEntryKind, label: &str, row: u32) -> OutlineEntry {
+        OutlineEntry {
+            depth,
+            kind,
+            label: label.to_string(),
+            row,
+        }
+    }
+
+    #[test]
+    fn test_module_outline() {
+        let text = indoc! {r#"
+            (module $example
+              (type (;0;) (func (param i32)))
+              (type (;1;) (func (param i32 i32) (result i32)))
+              (import "env" "log" (func $log (;0;) (type 0)))
+              (func $add (;1;) (type 1) (param i32 i32) (result i32)
+                (local i32)
+                local.get 0
+                local.get 1
+                i32.add
+              )
+              (func (;2;) (type 0) (param i32))
+              (memory (;0;) 1)
+              (export "add" (func $add))
+              (export "memory" (memory 0))
+              (data (;0;) (i32.const 1024) "(func \"not a field\")")
+            )
+        "#};
+        use OutlineEntryKind::*;
+        assert_eq!(
+            outline(text),
+            [
+                entry(0, Section { count: 2 }, "type", 1),
+                entry(0, Section { count: 1 }, "import", 3),
+                entry(1, Symbol, "env.log", 3),
+                entry(0, Section { count: 2 }, "func", 4),
+                entry(1, Symbol, "$add", 4),
+                entry(0, Section { count: 1 }, "memory", 11),
+                entry(0, Section { count: 2 }, "export", 12),
+                entry(1, Symbol, "add", 12),
+                entry(1, Symbol, "memory", 13),
+                entry(0, Section { count: 1 }, "data", 14),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_component_outline() {
+        let text = indoc! {r#"
+            (component
+              (core module (;0;)
+                (func $run (;0;))
+                (export "run" (func $run))
+              )
+              (core module $shim (;1;)
+                (table (;0;) 1 1 funcref)
+              )
+              (core instance (;0;) (instantiate 0))
+              (type (;0;) (func))
+              (export (;1;) "run" (func 0))
+            )
+        "#};
+        use OutlineEntryKind::*;
+        assert_eq!(
+            outline(text),
+            [
+                entry(0, Module, "core module 0", 1),
+                entry(1, Section { count: 1 }, "func", 2),
+                entry(2, Symbol, "$run", 2),
+                entry(1, Section { count: 1 }, "export", 3),
+                entry(2, Symbol, "run", 3),
+                entry(0, Module, "core module $shim", 5),
+                entry(1, Section { count: 1 }, "table", 6),
+                entry(0, Section { count: 1 }, "core instance", 8),
+                entry(0, Section { count: 1 }, "type", 9),
+                entry(0, Section { count: 1 }, "export", 10),
+                entry(1, Symbol, "run", 10),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_disassemble() {
+        let disassembly = Disassembly::new(b"\0asm\x01\0\0\0").unwrap();
+        assert!(!disassembly.is_component);
+        assert!(disassembly.text.starts_with("(module"));
+        assert!(disassembly.outline.is_empty());
+
+        assert!(Disassembly::new(b"not wasm").is_err());
+    }
+}
@@ -0,0 +1,231 @@
+//! What the welcome page shows besides getting started: recent and pinned projects, walkthroughs
+//! of Zed's features and a list of tips.
+
+use anyhow::Result;
+use db::kvp::KEY_VALUE_STORE;
+use std::path::PathBuf;
+use util::ResultExt;
+use workspace::{SerializedWorkspaceLocation, WORKSPACE_DB};
+
+const PINNED_PROJECTS_KEY: &str = "pinned_projects";
+
+/// The most recent projects shown, not counting pinned ones.
+const MAX_RECENT_PROJECTS: usize = 8;
+
+/// A project, as the paths that are opened for it.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct HomeProject {
+    pub paths: Vec<PathBuf>,
+    pub pinned: bool,
+}
+
+/// The pinned projects, followed by the most recently opened local projects that aren't pinned.
+pub(crate) async fn load_projects() -> Vec<HomeProject> {
+    let mut projects = pinned_projects()
+        .into_iter()
+        .map(|paths| HomeProject {
+            paths,
+            pinned: true,
+        })
+        .collect::<Vec<_>>();
+    let recent = WORKSPACE_DB
+        .recent_workspaces_on_disk()
+        .await
+        .log_err()
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|(_, location)| match location {
+            SerializedWorkspaceLocation::Local(paths) => Some(paths.paths().to_vec()),
+            SerializedWorkspaceLocation::DevServer(_) => None,
+        })
+        .filter(|paths| !projects.iter().any(|project| project.paths == *paths))
+        .take(MAX_RECENT_PROJECTS)
+        .map(|paths| HomeProject {
+            paths,
+            pinned: false,
+        })
+        .collect::<Vec<_>>();
+    projects.extend(recent);
+    projects
+}
+
+fn pinned_projects() -> Vec<Vec<PathBuf>> {
+    KEY_VALUE_STORE
+        .read_kvp(PINNED_PROJECTS_KEY)
+        .log_err()
+        .flatten()
+        .and_then(|pinned| serde_json::from_str(&pinned).log_err())
+        .unwrap_or_default()
+}
+
+/// Pins the project if it isn't pinned already, or unpins it.
+pub(crate) async fn toggle_pinned(paths: Vec<PathBuf>) -> Result<()> {
+    let mut pinned = pinned_projects();
+    if let Some(ix) = pinned.iter().position(|pinned| *pinned == paths) {
+        pinned.remove(ix);
+    } else {
+        pinned.push(paths);
+    }
+    KEY_VALUE_STORE
+        .write_kvp(
+            PINNED_PROJECTS_KEY.to_string(),
+            serde_json::to_string(&pinned)?,
+        )
+        .await
+}
+
+pub(crate) struct Walkthrough {
+    pub title: &'static str,
+    pub steps: &'static [WalkthroughStep],
+}
+
+/// A step of a walkthrough, which is done by running its action, whether from the welcome page
+/// or with the key binding shown for it.
+pub(crate) struct WalkthroughStep {
+    pub description: &'static str,
+    pub action: &'static str,
+}
+
+pub(crate) const WALKTHROUGHS: &[Walkthrough] = &[
+    Walkthrough {
+        title: "Tour the keymap",
+        steps: &[
+            WalkthroughStep {
+                description: "Run any command from the command palette",
+                action: "command_palette::Toggle",
+            },
+            WalkthroughStep {
+                description: "Find a file in the project",
+                action: "file_finder::Toggle",
+            },
+            WalkthroughStep {
+                description: "Search the whole project",
+                action: "pane::DeploySearch",
+            },
+            WalkthroughStep {
+                description: "Jump to a symbol in the current file",
+                action: "outline::Toggle",
+            },
+            WalkthroughStep {
+                description: "Show or hide the project panel",
+                action: "workspace::ToggleLeftDock",
+            },
+            WalkthroughStep {
+                description: "Open a terminal",
+                action: "terminal_panel::ToggleFocus",
+            },
+            WalkthroughStep {
+                description: "Use the key bindings of another editor",
+                action: "welcome::ToggleBaseKeymapSelector",
+            },
+            WalkthroughStep {
+                description: "Change any key binding in your keymap",
+                action: "zed::OpenKeymap",
+            },
+        ],
+    },
+    Walkthrough {
+        title: "Set up collaboration",
+        steps: &[
+            WalkthroughStep {
+                description: "Sign in with GitHub",
+                action: "client::SignIn",
+            },
+            WalkthroughStep {
+                description: "Open the collaboration panel to add contacts and join channels",
+                action: "collab_panel::ToggleFocus",
+            },
+            WalkthroughStep {
+                description: "Share the project with the people in your call",
+                action: "collab::ShareProject",
+            },
+        ],
+    },
+];
+
+pub(crate) const TIPS: &[&str] = &[
+    "Add a cursor on the next match of the selection with cmd-d on macOS or ctrl-d on Linux.",
+    "Hold shift and alt while dragging to select a column of text.",
+    "Split a pane by dragging a tab to one of its edges.",
+    "Open a file from the command line with `zed path/to/file:line:column`.",
+    "Edit the results of a project search in place, as one multibuffer.",
+    "Rename a symbol across the project with the rename command.",
+    "Go back to where you were with the navigation history, using ctrl-- on macOS or ctrl-alt-- on Linux.",
+    "Set `format_on_save` in your settings to format files whenever they're saved.",
+    "Settings can be overridden for a project in its `.zed/settings.json`.",
+    "Each language can have its own settings, under `languages` in your settings.",
+    "Zoom into the active pane to give it the whole window.",
+    "Install extensions for more languages and themes from the extensions page.",
+    "Turn on vim mode from the welcome page or with the `vim_mode` setting.",
+];
+
+/// The tips that contain every word of the query, ignoring case.
+pub(crate) fn matching_tips(query: &str) -> Vec<usize> {
+    let words = query
+        .split_whitespace()
+        .map(|word| word.to_lowercase())
+        .collect::<Vec<_>>();
+    TIPS.iter()
+        .enumerate()
+        .filter(|(_, tip)| {
+            let tip = tip.to_lowercase();
+            words.iter().all(|word| tip.contains(word))
+        })
+        .map(|(ix, _)| ix)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matching_tips() {
+        assert_eq!(matching_tips("").len(), TIPS.len());
+        assert_eq!(
+            matching_tips("  PANE  drag ")
+                .into_iter()
+                .map(|ix| TIPS[ix])
+                .collect::<Vec<_>>(),
+            ["Split a pane by dragging a tab to one of its edges."]
+        );
+        assert!(matching_tips("pane macro").is_empty());
+    }
+
+    #[gpui::test]
+    async fn test_toggle_pinned() {
+        let zed = vec![PathBuf::from("/code/zed")];
+        let notes = vec![PathBuf::from("/notes"), PathBuf::from("/code/notes")];
+        toggle_pinned(zed.clone()).await.unwrap();
+        toggle_pinned(notes.clone()).await.unwrap();
+        let projects = load_projects().await;
+        assert_eq!(
+            projects[..2],
+            [
+                HomeProject {
+                    paths: zed.clone(),
+                    pinned: true,
+                },
+                HomeProject {
+                    paths: notes.clone(),
+                    pinned: true,
+                },
+            ]
+        );
+
+        // Toggling a pinned project unpins it.
+        toggle_pinned(zed.clone()).await.unwrap();
+        let projects = load_projects().await;
+        assert!(!projects.iter().any(|project| project.paths == zed));
+        assert_eq!(
+            projects[0],
+            HomeProject {
+                paths: notes.clone(),
+                pinned: true,
+            }
+        );
+
+        toggle_pinned(notes.clone()).await.unwrap();
+        assert!(!load_projects().await.iter().any(|project| project.pinned));
+    }
+}
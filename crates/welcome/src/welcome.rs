@@ -1,16 +1,21 @@
 mod base_keymap_picker;
 mod base_keymap_setting;
+mod home;
 
 use client::{telemetry::Telemetry, TelemetrySettings};
+use collections::HashSet;
 use db::kvp::KEY_VALUE_STORE;
+use editor::{Editor, EditorEvent};
 use gpui::{
     svg, AnyElement, AppContext, EventEmitter, FocusHandle, FocusableView, InteractiveElement,
     ParentElement, Render, Styled, Subscription, View, ViewContext, VisualContext, WeakView,
     WindowContext,
 };
+use home::{matching_tips, HomeProject, TIPS, WALKTHROUGHS};
 use settings::{Settings, SettingsStore};
 use std::sync::Arc;
-use ui::{prelude::*, CheckboxWithLabel};
+use ui::{prelude::*, CheckboxWithLabel, KeyBinding, ListHeader, ListItem, ListItemSpacing};
+use util::{paths::PathExt, ResultExt};
 use vim::VimModeSetting;
 use workspace::{
     dock::DockPosition,
@@ -27,8 +32,7 @@ pub fn init(cx: &mut AppContext) {
 
     cx.observe_new_views(|workspace: &mut Workspace, _cx| {
         workspace.register_action(|workspace, _: &Welcome, cx| {
-            let welcome_page = WelcomePage::new(workspace, cx);
-            workspace.add_item_to_active_pane(Box::new(welcome_page), None, cx)
+            open_welcome_page(workspace, cx);
         });
     })
     .detach();
@@ -36,6 +40,12 @@ pub fn init(cx: &mut AppContext) {
     base_keymap_picker::init(cx);
 }
 
+/// Opens the welcome page in the active pane, as in a new window without a project.
+pub fn open_welcome_page(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
+    let welcome_page = WelcomePage::new(workspace, cx);
+    workspace.add_item_to_active_pane(Box::new(welcome_page), None, cx)
+}
+
 pub fn show_welcome_view(app_state: Arc<AppState>, cx: &mut AppContext) {
     open_new(app_state, cx, |workspace, cx| {
         workspace.toggle_dock(DockPosition::Left, cx);
@@ -55,20 +65,45 @@ pub struct WelcomePage {
     workspace: WeakView<Workspace>,
     focus_handle: FocusHandle,
     telemetry: Arc<Telemetry>,
+    projects: Vec<HomeProject>,
+    open_walkthrough: Option<usize>,
+    completed_steps: HashSet<(usize, usize)>,
+    tips_query: View<Editor>,
+    selected_entry: Option<Entry>,
     _settings_subscription: Subscription,
+    _tips_query_subscription: Subscription,
+}
+
+/// Something on the welcome page that can be selected with the keyboard.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Entry {
+    Project(usize),
+    Walkthrough(usize),
+    WalkthroughStep(usize, usize),
+    Tip(usize),
 }
 
 impl Render for WelcomePage {
     fn render(&mut self, cx: &mut gpui::ViewContext<Self>) -> impl IntoElement {
         h_flex()
+            .id("welcome-page")
+            .key_context("WelcomePage")
             .size_full()
+            .items_start()
+            .justify_center()
+            .gap_8()
+            .py_8()
+            .overflow_y_scroll()
             .bg(cx.theme().colors().editor_background)
             .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_prev))
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::secondary_confirm))
             .child(
                 v_flex()
                     .w_96()
                     .gap_4()
-                    .mx_auto()
                     .child(
                         svg()
                             .path("icons/logo_96.svg")
@@ -236,6 +271,14 @@ impl Render for WelcomePage {
                             )),
                     ),
             )
+            .child(
+                v_flex()
+                    .w_96()
+                    .gap_4()
+                    .child(self.render_projects(cx))
+                    .child(self.render_walkthroughs(cx))
+                    .child(self.render_tips(cx)),
+            )
     }
 }
 
@@ -248,18 +291,329 @@ impl WelcomePage {
             })
             .detach();
 
-            WelcomePage {
-                focus_handle: cx.focus_handle(),
-                workspace: workspace.weak_handle(),
-                telemetry: workspace.client().telemetry().clone(),
-                _settings_subscription: cx
-                    .observe_global::<SettingsStore>(move |_, cx| cx.notify()),
-            }
+            Self::build(
+                workspace.weak_handle(),
+                workspace.client().telemetry().clone(),
+                cx,
+            )
         });
 
         this
     }
 
+    fn build(
+        workspace: WeakView<Workspace>,
+        telemetry: Arc<Telemetry>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let tips_query = cx.new_view(|cx| {
+            let mut editor = Editor::single_line(cx);
+            editor.set_placeholder_text("Search tips…", cx);
+            editor
+        });
+        let tips_query_subscription = cx.subscribe(&tips_query, |this, _, event, cx| {
+            if let EditorEvent::BufferEdited = event {
+                if let Some(Entry::Tip(_)) = this.selected_entry {
+                    this.selected_entry = None;
+                }
+                cx.notify();
+            }
+        });
+        cx.spawn(|this, mut cx| async move {
+            let projects = home::load_projects().await;
+            this.update(&mut cx, |this, cx| {
+                this.projects = projects;
+                cx.notify();
+            })
+        })
+        .detach_and_log_err(cx);
+
+        WelcomePage {
+            focus_handle: cx.focus_handle(),
+            workspace,
+            telemetry,
+            projects: Vec::new(),
+            open_walkthrough: None,
+            completed_steps: HashSet::default(),
+            tips_query,
+            selected_entry: None,
+            _settings_subscription: cx.observe_global::<SettingsStore>(move |_, cx| cx.notify()),
+            _tips_query_subscription: tips_query_subscription,
+        }
+    }
+
+    /// The entries in the order they're shown, which is the order they're selected in.
+    fn entries(&self, cx: &AppContext) -> Vec<Entry> {
+        let mut entries = (0..self.projects.len())
+            .map(Entry::Project)
+            .collect::<Vec<_>>();
+        for (ix, walkthrough) in WALKTHROUGHS.iter().enumerate() {
+            entries.push(Entry::Walkthrough(ix));
+            if self.open_walkthrough == Some(ix) {
+                entries.extend(
+                    (0..walkthrough.steps.len()).map(|step| Entry::WalkthroughStep(ix, step)),
+                );
+            }
+        }
+        let query = self.tips_query.read(cx).text(cx);
+        entries.extend(matching_tips(&query).into_iter().map(Entry::Tip));
+        entries
+    }
+
+    fn select_next(&mut self, _: &menu::SelectNext, cx: &mut ViewContext<Self>) {
+        let entries = self.entries(cx);
+        let ix = self
+            .selected_entry
+            .and_then(|selected| entries.iter().position(|entry| *entry == selected))
+            .map_or(0, |ix| (ix + 1).min(entries.len().saturating_sub(1)));
+        self.selected_entry = entries.get(ix).copied();
+        cx.notify();
+    }
+
+    fn select_prev(&mut self, _: &menu::SelectPrev, cx: &mut ViewContext<Self>) {
+        let entries = self.entries(cx);
+        let ix = self
+            .selected_entry
+            .and_then(|selected| entries.iter().position(|entry| *entry == selected))
+            .map_or(entries.len().saturating_sub(1), |ix| ix.saturating_sub(1));
+        self.selected_entry = entries.get(ix).copied();
+        cx.notify();
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, cx: &mut ViewContext<Self>) {
+        if let Some(entry) = self.selected_entry {
+            self.confirm_entry(entry, cx);
+        }
+    }
+
+    /// Pins or unpins the selected project.
+    fn secondary_confirm(&mut self, _: &menu::SecondaryConfirm, cx: &mut ViewContext<Self>) {
+        if let Some(Entry::Project(ix)) = self.selected_entry {
+            self.toggle_pinned(ix, cx);
+        }
+    }
+
+    fn confirm_entry(&mut self, entry: Entry, cx: &mut ViewContext<Self>) {
+        self.selected_entry = Some(entry);
+        match entry {
+            Entry::Project(ix) => {
+                let Some(project) = self.projects.get(ix) else {
+                    return;
+                };
+                self.telemetry
+                    .report_app_event("welcome page: open project".to_string());
+                let paths = project.paths.clone();
+                self.workspace
+                    .update(cx, |workspace, cx| {
+                        workspace
+                            .open_workspace_for_paths(false, paths, cx)
+                            .detach_and_log_err(cx)
+                    })
+                    .ok();
+            }
+            Entry::Walkthrough(ix) => {
+                if self.open_walkthrough == Some(ix) {
+                    self.open_walkthrough = None;
+                } else {
+                    self.telemetry.report_app_event(format!(
+                        "welcome page: start walkthrough {}",
+                        WALKTHROUGHS[ix].title
+                    ));
+                    self.open_walkthrough = Some(ix);
+                }
+            }
+            Entry::WalkthroughStep(walkthrough, step) => {
+                let name = WALKTHROUGHS[walkthrough].steps[step].action;
+                if let Some(action) = cx.build_action(name, None).log_err() {
+                    self.completed_steps.insert((walkthrough, step));
+                    cx.dispatch_action(action);
+                }
+            }
+            Entry::Tip(_) => {}
+        }
+        cx.notify();
+    }
+
+    fn toggle_pinned(&mut self, ix: usize, cx: &mut ViewContext<Self>) {
+        let Some(project) = self.projects.get(ix) else {
+            return;
+        };
+        let paths = project.paths.clone();
+        cx.spawn(|this, mut cx| async move {
+            home::toggle_pinned(paths.clone()).await?;
+            let projects = home::load_projects().await;
+            this.update(&mut cx, |this, cx| {
+                // Keep the project selected where it's moved to.
+                if this.selected_entry == Some(Entry::Project(ix)) {
+                    this.selected_entry = projects
+                        .iter()
+                        .position(|project| project.paths == paths)
+                        .map(Entry::Project);
+                }
+                this.projects = projects;
+                cx.notify();
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn render_projects(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let (pinned, recent): (Vec<_>, Vec<_>) = self
+            .projects
+            .iter()
+            .enumerate()
+            .partition(|(_, project)| project.pinned);
+        let mut list = v_flex();
+        for (title, projects) in [("Pinned Projects", pinned), ("Recent Projects", recent)] {
+            if projects.is_empty() {
+                continue;
+            }
+            list = list.child(ListHeader::new(title)).children(
+                projects
+                    .into_iter()
+                    .map(|(ix, project)| self.render_project(ix, project, cx)),
+            );
+        }
+        list
+    }
+
+    fn render_project(
+        &self,
+        ix: usize,
+        project: &HomeProject,
+        cx: &mut ViewContext<Self>,
+    ) -> impl IntoElement {
+        let name = project
+            .paths
+            .iter()
+            .filter_map(|path| path.file_name())
+            .map(|name| name.to_string_lossy())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let paths = project
+            .paths
+            .iter()
+            .map(|path| path.compact().to_string_lossy().into_owned())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let pin_label = if project.pinned { "Unpin" } else { "Pin" };
+        ListItem::new(("project", ix))
+            .inset(true)
+            .spacing(ListItemSpacing::Sparse)
+            .selected(self.selected_entry == Some(Entry::Project(ix)))
+            .start_slot(Icon::new(IconName::Folder).color(Color::Muted))
+            .child(
+                v_flex()
+                    .child(Label::new(name))
+                    .child(Label::new(paths).size(LabelSize::Small).color(Color::Muted)),
+            )
+            .end_hover_slot(
+                Button::new(("pin-project", ix), pin_label)
+                    .label_size(LabelSize::Small)
+                    .on_click(cx.listener(move |this, _, cx| this.toggle_pinned(ix, cx))),
+            )
+            .on_click(cx.listener(move |this, _, cx| this.confirm_entry(Entry::Project(ix), cx)))
+    }
+
+    fn render_walkthroughs(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let mut list = v_flex().child(ListHeader::new("Walkthroughs"));
+        for (ix, walkthrough) in WALKTHROUGHS.iter().enumerate() {
+            let is_open = self.open_walkthrough == Some(ix);
+            let completed = (0..walkthrough.steps.len())
+                .filter(|step| self.completed_steps.contains(&(ix, *step)))
+                .count();
+            list = list.child(
+                ListItem::new(("walkthrough", ix))
+                    .inset(true)
+                    .spacing(ListItemSpacing::Sparse)
+                    .selected(self.selected_entry == Some(Entry::Walkthrough(ix)))
+                    .toggle(is_open)
+                    .child(Label::new(walkthrough.title))
+                    .end_slot(
+                        Label::new(format!("{completed}/{}", walkthrough.steps.len()))
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .on_click(cx.listener(move |this, _, cx| {
+                        this.confirm_entry(Entry::Walkthrough(ix), cx)
+                    })),
+            );
+            if is_open {
+                list =
+                    list.children(walkthrough.steps.iter().enumerate().map(|(step_ix, step)| {
+                        let entry = Entry::WalkthroughStep(ix, step_ix);
+                        let is_completed = self.completed_steps.contains(&(ix, step_ix));
+                        let key_binding = cx
+                            .build_action(step.action, None)
+                            .ok()
+                            .and_then(|action| KeyBinding::for_action(&*action, cx));
+                        ListItem::new(ElementId::NamedInteger(
+                            format!("walkthrough-step-{ix}").into(),
+                            step_ix,
+                        ))
+                        .inset(true)
+                        .indent_level(1)
+                        .spacing(ListItemSpacing::Sparse)
+                        .selected(self.selected_entry == Some(entry))
+                        .start_slot(
+                            Icon::new(if is_completed {
+                                IconName::Check
+                            } else {
+                                IconName::ChevronRight
+                            })
+                            .size(IconSize::Small)
+                            .color(if is_completed {
+                                Color::Success
+                            } else {
+                                Color::Muted
+                            }),
+                        )
+                        .child(Label::new(step.description))
+                        .end_slot(key_binding)
+                        .on_click(cx.listener(move |this, _, cx| this.confirm_entry(entry, cx)))
+                    }));
+            }
+        }
+        list
+    }
+
+    fn render_tips(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let query = self.tips_query.read(cx).text(cx);
+        let tips = matching_tips(&query);
+        v_flex()
+            .child(ListHeader::new("Tips"))
+            .child(
+                h_flex()
+                    .mx_2()
+                    .mb_1()
+                    .px_2()
+                    .py_1()
+                    .gap_2()
+                    .border_1()
+                    .border_color(cx.theme().colors().border)
+                    .rounded_md()
+                    .child(Icon::new(IconName::MagnifyingGlass).color(Color::Muted))
+                    .child(self.tips_query.clone()),
+            )
+            .when(tips.is_empty(), |list| {
+                list.child(
+                    h_flex()
+                        .px_3()
+                        .child(Label::new("No tips match").color(Color::Muted)),
+                )
+            })
+            .children(tips.into_iter().map(|ix| {
+                ListItem::new(("tip", ix))
+                    .inset(true)
+                    .spacing(ListItemSpacing::Sparse)
+                    .selected(self.selected_entry == Some(Entry::Tip(ix)))
+                    .child(Label::new(TIPS[ix]))
+                    .on_click(
+                        cx.listener(move |this, _, cx| this.confirm_entry(Entry::Tip(ix), cx)),
+                    )
+            }))
+    }
+
     fn update_settings<T: Settings>(
         &mut self,
         selection: &Selection,
@@ -316,15 +670,104 @@ impl Item for WelcomePage {
         _workspace_id: WorkspaceId,
         cx: &mut ViewContext<Self>,
     ) -> Option<View<Self>> {
-        Some(cx.new_view(|cx| WelcomePage {
-            focus_handle: cx.focus_handle(),
-            workspace: self.workspace.clone(),
-            telemetry: self.telemetry.clone(),
-            _settings_subscription: cx.observe_global::<SettingsStore>(move |_, cx| cx.notify()),
-        }))
+        Some(
+            cx.new_view(|cx| {
+                WelcomePage::build(self.workspace.clone(), self.telemetry.clone(), cx)
+            }),
+        )
     }
 
     fn to_item_events(event: &Self::Event, mut f: impl FnMut(workspace::item::ItemEvent)) {
         f(*event)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{TestAppContext, VisualTestContext};
+    use project::Project;
+    use std::path::PathBuf;
+
+    #[gpui::test]
+    async fn test_select_entries(cx: &mut TestAppContext) {
+        let app_state = init_test(cx);
+        let project = Project::test(app_state.fs.clone(), [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+        let welcome_page = workspace.update(cx, |workspace, cx| WelcomePage::new(workspace, cx));
+        cx.run_until_parked();
+
+        welcome_page.update(cx, |page, cx| {
+            page.projects = vec![HomeProject {
+                paths: vec![PathBuf::from("/code/zed")],
+                pinned: true,
+            }];
+
+            // Selection starts at the top and stops at either end.
+            page.select_next(&menu::SelectNext, cx);
+            assert_eq!(page.selected_entry, Some(Entry::Project(0)));
+            page.select_prev(&menu::SelectPrev, cx);
+            assert_eq!(page.selected_entry, Some(Entry::Project(0)));
+            page.select_next(&menu::SelectNext, cx);
+            assert_eq!(page.selected_entry, Some(Entry::Walkthrough(0)));
+
+            // Opening a walkthrough puts its steps after it.
+            page.confirm(&menu::Confirm, cx);
+            assert_eq!(page.open_walkthrough, Some(0));
+            page.select_next(&menu::SelectNext, cx);
+            assert_eq!(page.selected_entry, Some(Entry::WalkthroughStep(0, 0)));
+
+            // Running a step's action completes it.
+            let step = WALKTHROUGHS[0]
+                .steps
+                .iter()
+                .position(|step| step.action == "welcome::ToggleBaseKeymapSelector")
+                .unwrap();
+            page.confirm_entry(Entry::WalkthroughStep(0, step), cx);
+            assert!(page.completed_steps.contains(&(0, step)));
+
+            // Confirming the walkthrough again closes it.
+            page.confirm_entry(Entry::Walkthrough(0), cx);
+            assert_eq!(page.open_walkthrough, None);
+            assert!(!page
+                .entries(cx)
+                .iter()
+                .any(|entry| matches!(entry, Entry::WalkthroughStep(..))));
+
+            // Selecting upwards from nothing starts at the last tip.
+            page.selected_entry = None;
+            page.select_prev(&menu::SelectPrev, cx);
+            assert_eq!(page.selected_entry, Some(Entry::Tip(TIPS.len() - 1)));
+        });
+
+        // Filtering the tips clears the selected tip and leaves only those that match.
+        let tips_query = welcome_page.update(cx, |page, _| page.tips_query.clone());
+        tips_query.update(cx, |editor, cx| editor.set_text("pane", cx));
+        welcome_page.update(cx, |page, cx| {
+            assert_eq!(page.selected_entry, None);
+            let tips = page
+                .entries(cx)
+                .into_iter()
+                .filter_map(|entry| match entry {
+                    Entry::Tip(ix) => Some(ix),
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(tips, matching_tips("pane"));
+            assert!(!tips.is_empty() && tips.len() < TIPS.len());
+        });
+    }
+
+    fn init_test(cx: &mut TestAppContext) -> Arc<AppState> {
+        cx.update(|cx| {
+            let app_state = AppState::test(cx);
+            language::init(cx);
+            editor::init(cx);
+            workspace::init_settings(cx);
+            Project::init_settings(cx);
+            BaseKeymap::register(cx);
+            VimModeSetting::register(cx);
+            app_state
+        })
+    }
+}
@@ -0,0 +1,187 @@
+use std::time::Duration;
+
+use collections::HashSet;
+use futures::{channel::oneshot, Future};
+use gpui::{
+    AnyWindowHandle, AppContext, Context as _, Global, Model, ModelContext, Task, WindowContext,
+};
+use settings::Settings;
+
+use crate::WorkspaceSettings;
+
+/// How long after the last window is deactivated before the app is considered to be in the
+/// background, so that moving between windows doesn't throttle anything.
+const BACKGROUND_DELAY: Duration = Duration::from_secs(1);
+
+/// Whether the user is using the app.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Activity {
+    /// A window is active and has had input recently.
+    Active,
+    /// A window is active, but hasn't had input for the `idle_timeout_seconds` setting.
+    Idle,
+    /// No window is active.
+    Background,
+}
+
+struct GlobalActivityTracker(Model<ActivityTracker>);
+
+impl Global for GlobalActivityTracker {}
+
+/// Tracks whether the user is using the app, so that non-essential work, such as blinking
+/// cursors, indexing and highlighting hidden buffers, can be throttled while they aren't, and
+/// resumed as soon as they're back. Observers are notified when the activity changes.
+pub struct ActivityTracker {
+    activity: Activity,
+    active_windows: HashSet<AnyWindowHandle>,
+    /// Whether there was input since the idle timer last checked.
+    had_input: bool,
+    idle_timer: Option<Task<()>>,
+    background_timer: Option<Task<()>>,
+    resumed: Vec<oneshot::Sender<()>>,
+}
+
+impl ActivityTracker {
+    /// Starts tracking activity. Until this is called, such as in tests, work is never
+    /// throttled.
+    pub fn init(cx: &mut AppContext) {
+        let tracker = cx.new_model(|cx| {
+            let mut tracker = Self {
+                activity: Activity::Active,
+                active_windows: HashSet::default(),
+                had_input: false,
+                idle_timer: None,
+                background_timer: None,
+                resumed: Vec::new(),
+            };
+            tracker.start_idle_timer(cx);
+            tracker
+        });
+        cx.set_global(GlobalActivityTracker(tracker));
+        cx.observe_keystrokes(|_, cx| Self::record_input(cx))
+            .detach();
+    }
+
+    pub fn global(cx: &AppContext) -> Option<Model<Self>> {
+        cx.try_global::<GlobalActivityTracker>()
+            .map(|tracker| tracker.0.clone())
+    }
+
+    pub fn activity(&self) -> Activity {
+        self.activity
+    }
+
+    pub fn is_throttled(&self) -> bool {
+        self.activity != Activity::Active
+    }
+
+    /// Resolves once the app is active, or immediately if it already is.
+    pub fn active(cx: &mut AppContext) -> impl Future<Output = ()> {
+        let resumed = Self::global(cx).and_then(|tracker| {
+            tracker.update(cx, |tracker, _| {
+                tracker.is_throttled().then(|| {
+                    let (tx, rx) = oneshot::channel();
+                    tracker.resumed.push(tx);
+                    rx
+                })
+            })
+        });
+        async move {
+            if let Some(resumed) = resumed {
+                resumed.await.ok();
+            }
+        }
+    }
+
+    /// Records that the user interacted with the app, which resumes it if it was idle.
+    pub fn record_input(cx: &mut AppContext) {
+        let Some(tracker) = Self::global(cx) else {
+            return;
+        };
+        tracker.update(cx, |tracker, cx| {
+            tracker.had_input = true;
+            if tracker.activity == Activity::Idle {
+                tracker.set_activity(Activity::Active, cx);
+            }
+        });
+    }
+
+    pub(crate) fn window_activation_changed(cx: &mut WindowContext) {
+        let Some(tracker) = Self::global(cx) else {
+            return;
+        };
+        let window = cx.window_handle();
+        let active = cx.is_window_active();
+        tracker.update(cx, |tracker, cx| {
+            if active {
+                tracker.active_windows.insert(window);
+                tracker.background_timer = None;
+                tracker.had_input = true;
+                tracker.set_activity(Activity::Active, cx);
+            } else {
+                // Closed windows aren't always deactivated first.
+                let windows = cx.windows();
+                tracker.active_windows.retain(|active_window| {
+                    *active_window != window && windows.contains(active_window)
+                });
+                if tracker.active_windows.is_empty() {
+                    tracker.background_timer = Some(cx.spawn(|this, mut cx| async move {
+                        cx.background_executor().timer(BACKGROUND_DELAY).await;
+                        this.update(&mut cx, |this, cx| {
+                            if this.active_windows.is_empty() {
+                                this.set_activity(Activity::Background, cx);
+                            }
+                        })
+                        .ok();
+                    }));
+                }
+            }
+        });
+    }
+
+    fn set_activity(&mut self, activity: Activity, cx: &mut ModelContext<Self>) {
+        if self.activity == activity {
+            return;
+        }
+        log::debug!("activity changed from {:?} to {activity:?}", self.activity);
+        self.activity = activity;
+        if activity == Activity::Active {
+            for resumed in self.resumed.drain(..) {
+                resumed.send(()).ok();
+            }
+            self.start_idle_timer(cx);
+        }
+        cx.notify();
+    }
+
+    /// Checks for input every `idle_timeout_seconds` while the app is active, becoming idle
+    /// once there was none.
+    fn start_idle_timer(&mut self, cx: &mut ModelContext<Self>) {
+        let timeout = WorkspaceSettings::get_global(cx).idle_timeout_seconds;
+        if timeout == 0 {
+            self.idle_timer = None;
+            return;
+        }
+        self.had_input = false;
+        self.idle_timer = Some(cx.spawn(|this, mut cx| async move {
+            loop {
+                cx.background_executor()
+                    .timer(Duration::from_secs(timeout))
+                    .await;
+                let done = this.update(&mut cx, |this, cx| {
+                    if this.activity != Activity::Active {
+                        return true;
+                    }
+                    if std::mem::take(&mut this.had_input) {
+                        return false;
+                    }
+                    this.set_activity(Activity::Idle, cx);
+                    true
+                });
+                if done.unwrap_or(true) {
+                    break;
+                }
+            }
+        }));
+    }
+}
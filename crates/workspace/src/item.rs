@@ -1,5 +1,5 @@
 use crate::{
-    pane::{self, Pane},
+    pane::{self, NavigationLocation, Pane},
     persistence::model::ItemId,
     searchable::SearchableItemHandle,
     workspace_settings::{AutosaveSetting, WorkspaceSettings},
@@ -148,6 +148,20 @@ pub trait Item: FocusableView + EventEmitter<Self::Event> {
     fn navigate(&mut self, _: Box<dyn Any>, _: &mut ViewContext<Self>) -> bool {
         false
     }
+    /// Describes the location that some navigation data that was pushed by this item refers to.
+    fn navigation_location(&self, _: &dyn Any, _: &AppContext) -> Option<NavigationLocation> {
+        None
+    }
+    /// Creates navigation data for a location that was described by `navigation_location`, so
+    /// that the navigation history can be restored.
+    fn navigation_data(
+        &self,
+        _row: u32,
+        _column: u32,
+        _: &AppContext,
+    ) -> Option<Box<dyn Any + Send>> {
+        None
+    }
     fn tab_tooltip_text(&self, _: &AppContext) -> Option<SharedString> {
         None
     }
@@ -299,6 +313,13 @@ pub trait ItemHandle: 'static + Send {
     fn deactivated(&self, cx: &mut WindowContext);
     fn workspace_deactivated(&self, cx: &mut WindowContext);
     fn navigate(&self, data: Box<dyn Any>, cx: &mut WindowContext) -> bool;
+    fn navigation_location(&self, data: &dyn Any, cx: &AppContext) -> Option<NavigationLocation>;
+    fn navigation_data(
+        &self,
+        row: u32,
+        column: u32,
+        cx: &AppContext,
+    ) -> Option<Box<dyn Any + Send>>;
     fn item_id(&self) -> EntityId;
     fn to_any(&self) -> AnyView;
     fn is_dirty(&self, cx: &AppContext) -> bool;
@@ -620,6 +641,19 @@ impl<T: Item> ItemHandle for View<T> {
         self.update(cx, |this, cx| this.navigate(data, cx))
     }
 
+    fn navigation_location(&self, data: &dyn Any, cx: &AppContext) -> Option<NavigationLocation> {
+        self.read(cx).navigation_location(data, cx)
+    }
+
+    fn navigation_data(
+        &self,
+        row: u32,
+        column: u32,
+        cx: &AppContext,
+    ) -> Option<Box<dyn Any + Send>> {
+        self.read(cx).navigation_data(row, column, cx)
+    }
+
     fn item_id(&self) -> EntityId {
         self.entity_id()
     }
@@ -1001,7 +1035,7 @@ pub mod test {
 
         fn push_to_nav_history(&mut self, cx: &mut ViewContext<Self>) {
             if let Some(history) = &mut self.nav_history {
-                history.push(Some(Box::new(self.state.clone())), cx);
+                history.push(Some(self.state.clone()), cx);
             }
         }
     }
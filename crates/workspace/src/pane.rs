@@ -3,6 +3,7 @@ use crate::{
         ClosePosition, Item, ItemHandle, ItemSettings, PreviewTabsSettings, TabContentParams,
         WeakItemHandle,
     },
+    persistence::model::{SerializedNavHistory, SerializedNavigationEntry},
     toolbar::Toolbar,
     workspace_settings::{AutosaveSetting, TabBarSettings, WorkspaceSettings},
     CloseWindow, NewCenterTerminal, NewFile, NewSearch, OpenInTerminal, OpenTerminal, OpenVisible,
@@ -13,16 +14,17 @@ use collections::{HashMap, HashSet, VecDeque};
 use futures::{stream::FuturesUnordered, StreamExt};
 use gpui::{
     actions, anchored, deferred, impl_actions, prelude::*, Action, AnchorCorner, AnyElement,
-    AppContext, AsyncWindowContext, ClickEvent, DismissEvent, Div, DragMoveEvent, EntityId,
-    EventEmitter, ExternalPaths, FocusHandle, FocusableView, KeyContext, Model, MouseButton,
-    MouseDownEvent, NavigationDirection, Pixels, Point, PromptLevel, Render, ScrollHandle,
-    Subscription, Task, View, ViewContext, VisualContext, WeakFocusHandle, WeakView, WindowContext,
+    AnyWindowHandle, AppContext, AsyncWindowContext, ClickEvent, DismissEvent, Div, DragMoveEvent,
+    EntityId, EventEmitter, ExternalPaths, FocusHandle, FocusableView, KeyContext, Model,
+    MouseButton, MouseDownEvent, NavigationDirection, Pixels, Point, PromptLevel, Render,
+    ScrollHandle, Subscription, Task, View, ViewContext, VisualContext, WeakFocusHandle, WeakView,
+    WindowContext,
 };
 use itertools::Itertools;
 use parking_lot::Mutex;
 use project::{Project, ProjectEntryId, ProjectPath};
 use serde::Deserialize;
-use settings::{Settings, SettingsStore};
+use settings::{Settings, SettingsLocation, SettingsStore};
 use std::{
     any::Any,
     cmp, fmt, mem,
@@ -41,7 +43,10 @@ use ui::{
     IconSize, Indicator, Label, Tab, TabBar, TabPosition, Tooltip,
 };
 use ui::{v_flex, ContextMenu};
-use util::{debug_panic, maybe, truncate_and_remove_front, ResultExt};
+use util::{
+    debug_panic, maybe, paths::PathMatcher, truncate_and_remove_front, truncate_and_trailoff,
+    ResultExt,
+};
 
 #[derive(PartialEq, Clone, Copy, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
@@ -139,6 +144,9 @@ impl DeploySearch {
 }
 
 const MAX_NAVIGATION_HISTORY_LEN: usize = 1024;
+/// The most entries of the navigation history that are listed when the back or forward button
+/// is right-clicked.
+const MAX_LISTED_NAVIGATION_ENTRIES: usize = 20;
 
 pub enum Event {
     AddItem { item: Box<dyn ItemHandle> },
@@ -268,9 +276,30 @@ pub struct NavigationEntry {
     pub is_preview: bool,
 }
 
+/// Where an entry of the navigation history is in its item.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NavigationLocation {
+    /// The zero-based row of the location.
+    pub row: u32,
+    /// The zero-based column of the location.
+    pub column: u32,
+    /// The text of the line that the location is on.
+    pub line: SharedString,
+}
+
+/// An entry of the navigation history, described for listing it.
+#[derive(Clone, Debug)]
+pub struct NavigationHistoryEntry {
+    pub timestamp: usize,
+    pub title: SharedString,
+    pub location: Option<NavigationLocation>,
+}
+
 #[derive(Clone)]
 pub struct DraggedTab {
     pub pane: View<Pane>,
+    /// The window of the pane that the tab is dragged from.
+    pub window: AnyWindowHandle,
     pub item: Box<dyn ItemHandle>,
     pub ix: usize,
     pub detail: usize,
@@ -608,6 +637,24 @@ impl Pane {
         }
     }
 
+    fn navigate_to_history_entry(
+        &mut self,
+        mode: NavigationMode,
+        timestamp: usize,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if let Some(workspace) = self.workspace.upgrade() {
+            let pane = cx.view().downgrade();
+            cx.window_context().defer(move |cx| {
+                workspace.update(cx, |workspace, cx| {
+                    workspace
+                        .navigate_history_to(pane, mode, timestamp, cx)
+                        .detach_and_log_err(cx)
+                })
+            })
+        }
+    }
+
     fn history_updated(&mut self, cx: &mut ViewContext<Self>) {
         self.toolbar.update(cx, |_, cx| cx.notify());
     }
@@ -1433,7 +1480,28 @@ impl Pane {
 
     fn can_autosave_item(item: &dyn ItemHandle, cx: &AppContext) -> bool {
         let is_deleted = item.project_entry_ids(cx).is_empty();
-        item.is_dirty(cx) && !item.has_conflict(cx) && item.can_save(cx) && !is_deleted
+        item.is_dirty(cx)
+            && !item.has_conflict(cx)
+            && item.can_save(cx)
+            && !is_deleted
+            && !Self::is_excluded_from_autosave(item, cx)
+    }
+
+    /// Whether the item's file matches the `autosave_exclusions` setting, which a project's
+    /// settings can override.
+    fn is_excluded_from_autosave(item: &dyn ItemHandle, cx: &AppContext) -> bool {
+        let Some(project_path) = item.project_path(cx) else {
+            return false;
+        };
+        let location = SettingsLocation {
+            worktree_id: project_path.worktree_id.to_usize(),
+            path: &project_path.path,
+        };
+        WorkspaceSettings::get(Some(location), cx)
+            .autosave_exclusions
+            .iter()
+            .filter_map(|glob| PathMatcher::new(glob).log_err())
+            .any(|matcher| matcher.is_match(&project_path.path))
     }
 
     pub fn autosave_item(
@@ -1585,6 +1653,7 @@ impl Pane {
                 DraggedTab {
                     item: item.boxed_clone(),
                     pane: cx.view().clone(),
+                    window: cx.window_handle(),
                     detail,
                     is_active,
                     ix,
@@ -1760,6 +1829,46 @@ impl Pane {
         })
     }
 
+    /// Lists the locations that going back or forward would navigate to, so that several steps
+    /// can be taken at once.
+    fn build_nav_history_menu(
+        pane: WeakView<Pane>,
+        mode: NavigationMode,
+        cx: &mut WindowContext,
+    ) -> View<ContextMenu> {
+        ContextMenu::build(cx, move |mut menu, cx| {
+            let Some(pane) = pane.upgrade() else {
+                return menu;
+            };
+            let entries = pane.read(cx).nav_history.entries(mode, cx);
+            for entry in entries.into_iter().take(MAX_LISTED_NAVIGATION_ENTRIES) {
+                let title = match &entry.location {
+                    Some(location) => format!("{}:{}", entry.title, location.row + 1),
+                    None => entry.title.to_string(),
+                };
+                let line = entry
+                    .location
+                    .as_ref()
+                    .map(|location| truncate_and_trailoff(&location.line, 40));
+                menu = menu.custom_entry(
+                    move |_| {
+                        h_flex()
+                            .gap_2()
+                            .child(Label::new(title.clone()))
+                            .children(line.clone().map(|line| {
+                                Label::new(line).size(LabelSize::Small).color(Color::Muted)
+                            }))
+                            .into_any_element()
+                    },
+                    cx.handler_for(&pane, move |pane, cx| {
+                        pane.navigate_to_history_entry(mode, entry.timestamp, cx)
+                    }),
+                );
+            }
+            menu
+        })
+    }
+
     fn render_tab_bar(&mut self, cx: &mut ViewContext<'_, Pane>) -> impl IntoElement {
         let navigate_backward = IconButton::new("navigate_backward", IconName::ArrowLeft)
             .shape(IconButtonShape::Square)
@@ -1781,6 +1890,19 @@ impl Pane {
             .disabled(!self.can_navigate_forward())
             .tooltip(|cx| Tooltip::for_action("Go Forward", &GoForward, cx));
 
+        let pane = cx.view().downgrade();
+        let navigate_backward = right_click_menu("navigate_backward_history")
+            .trigger(navigate_backward)
+            .menu({
+                let pane = pane.clone();
+                move |cx| Self::build_nav_history_menu(pane.clone(), NavigationMode::GoingBack, cx)
+            });
+        let navigate_forward = right_click_menu("navigate_forward_history")
+            .trigger(navigate_forward)
+            .menu(move |cx| {
+                Self::build_nav_history_menu(pane.clone(), NavigationMode::GoingForward, cx)
+            });
+
         TabBar::new("tab_bar")
             .track_scroll(self.tab_bar_scroll_handle.clone())
             .when(
@@ -1909,6 +2031,10 @@ impl Pane {
                 return;
             }
         }
+        if dragged_tab.window != cx.window_handle() {
+            self.handle_tab_drop_from_other_window(dragged_tab, cx);
+            return;
+        }
         let mut to_pane = cx.view().clone();
         let split_direction = self.drag_split_direction;
         let item_id = dragged_tab.item.item_id();
@@ -1931,6 +2057,72 @@ impl Pane {
             .log_err();
     }
 
+    /// A view is only ever shown in one window, so a tab that's dragged from another window is
+    /// moved by opening its file in this pane and closing it in the other window. It's left open
+    /// there if it has unsaved changes, which would be lost otherwise.
+    fn handle_tab_drop_from_other_window(
+        &mut self,
+        dragged_tab: &DraggedTab,
+        cx: &mut ViewContext<'_, Self>,
+    ) {
+        let from_project = dragged_tab.pane.read(cx).project.clone();
+        let Some(abs_path) = dragged_tab
+            .item
+            .project_path(cx)
+            .and_then(|project_path| from_project.read(cx).absolute_path(&project_path, cx))
+        else {
+            self.workspace
+                .update(cx, |workspace, cx| {
+                    workspace.show_error(
+                        &anyhow::anyhow!("Only tabs of files can be moved to another window"),
+                        cx,
+                    );
+                })
+                .log_err();
+            return;
+        };
+
+        let mut to_pane = cx.view().clone();
+        let split_direction = self.drag_split_direction;
+        let from_pane = dragged_tab.pane.clone();
+        let from_window = dragged_tab.window;
+        let item = dragged_tab.item.boxed_clone();
+        self.workspace
+            .update(cx, |_, cx| {
+                cx.spawn(|workspace, mut cx| async move {
+                    let open_task = workspace.update(&mut cx, |workspace, cx| {
+                        if let Some(split_direction) = split_direction {
+                            to_pane = workspace.split_pane(to_pane, split_direction, cx);
+                        }
+                        workspace.open_paths(
+                            vec![abs_path],
+                            OpenVisible::None,
+                            Some(to_pane.downgrade()),
+                            cx,
+                        )
+                    })?;
+                    let opened = open_task
+                        .await
+                        .into_iter()
+                        .flatten()
+                        .any(|item| item.is_ok());
+                    if opened {
+                        from_window.update(&mut cx, |_, cx| {
+                            if !item.is_dirty(cx) {
+                                from_pane.update(cx, |pane, cx| {
+                                    pane.close_item_by_id(item.item_id(), SaveIntent::Skip, cx)
+                                        .detach_and_log_err(cx);
+                                });
+                            }
+                        })?;
+                    }
+                    anyhow::Ok(())
+                })
+                .detach_and_log_err(cx);
+            })
+            .log_err();
+    }
+
     fn handle_project_entry_drop(
         &mut self,
         project_entry_id: &ProjectEntryId,
@@ -2409,6 +2601,112 @@ impl NavHistory {
     pub fn path_for_item(&self, item_id: EntityId) -> Option<(ProjectPath, Option<PathBuf>)> {
         self.0.lock().paths_by_item.get(&item_id).cloned()
     }
+
+    /// Lists the entries that going back or forward would navigate to, from the nearest one.
+    pub fn entries(&self, mode: NavigationMode, cx: &AppContext) -> Vec<NavigationHistoryEntry> {
+        let state = self.0.lock();
+        let stack = match mode {
+            NavigationMode::GoingBack => &state.backward_stack,
+            NavigationMode::GoingForward => &state.forward_stack,
+            _ => return Vec::new(),
+        };
+        stack
+            .iter()
+            .rev()
+            .map(|entry| {
+                let item = entry.item.upgrade();
+                let title = item
+                    .as_ref()
+                    .and_then(|item| item.project_path(cx))
+                    .or_else(|| {
+                        let (project_path, _) = state.paths_by_item.get(&entry.item.id())?;
+                        Some(project_path.clone())
+                    })
+                    .and_then(|project_path| {
+                        Some(project_path.path.file_name()?.to_string_lossy().to_string())
+                    })
+                    .or_else(|| Some(item.as_ref()?.tab_description(0, cx)?.to_string()))
+                    .unwrap_or_else(|| "untitled".to_string());
+                let location = item
+                    .zip(entry.data.as_ref())
+                    .and_then(|(item, data)| item.navigation_location(data.as_ref(), cx));
+                NavigationHistoryEntry {
+                    timestamp: entry.timestamp,
+                    title: title.into(),
+                    location,
+                }
+            })
+            .collect()
+    }
+
+    /// Whether going back or forward would still reach the entry with the given timestamp.
+    pub fn contains(&self, mode: NavigationMode, timestamp: usize) -> bool {
+        let state = self.0.lock();
+        let stack = match mode {
+            NavigationMode::GoingBack => &state.backward_stack,
+            NavigationMode::GoingForward => &state.forward_stack,
+            NavigationMode::ReopeningClosedItem => &state.closed_stack,
+            _ => return false,
+        };
+        stack.iter().any(|entry| entry.timestamp == timestamp)
+    }
+
+    /// Serializes the entries of the given items, which are referred to by their position so
+    /// that the history can be restored along with them.
+    pub(crate) fn serialize(&self, item_ids: &[EntityId], cx: &AppContext) -> SerializedNavHistory {
+        let state = self.0.lock();
+        let serialize_stack = |stack: &VecDeque<NavigationEntry>| {
+            stack
+                .iter()
+                .filter_map(|entry| {
+                    let item = item_ids.iter().position(|id| *id == entry.item.id())?;
+                    let location = match (&entry.data, entry.item.upgrade()) {
+                        (Some(data), Some(item)) => {
+                            let location = item.navigation_location(data.as_ref(), cx)?;
+                            Some((location.row, location.column))
+                        }
+                        _ => None,
+                    };
+                    Some(SerializedNavigationEntry { item, location })
+                })
+                .collect()
+        };
+        SerializedNavHistory {
+            backward: serialize_stack(&state.backward_stack),
+            forward: serialize_stack(&state.forward_stack),
+        }
+    }
+
+    /// Replaces the history with one that was serialized along with the given items.
+    pub(crate) fn restore(
+        &mut self,
+        serialized: &SerializedNavHistory,
+        items: &[Option<Box<dyn ItemHandle>>],
+        cx: &AppContext,
+    ) {
+        let mut state = self.0.lock();
+        let next_timestamp = state.next_timestamp.clone();
+        let restore_stack = |entries: &[SerializedNavigationEntry]| {
+            entries
+                .iter()
+                .filter_map(|entry| {
+                    let item = items.get(entry.item)?.as_ref()?;
+                    let data = match entry.location {
+                        Some((row, column)) => Some(item.navigation_data(row, column, cx)?),
+                        None => None,
+                    };
+                    Some(NavigationEntry {
+                        item: Arc::from(item.downgrade_item()),
+                        data,
+                        timestamp: next_timestamp.fetch_add(1, Ordering::SeqCst),
+                        is_preview: false,
+                    })
+                })
+                .collect::<VecDeque<_>>()
+        };
+        state.backward_stack = restore_stack(&serialized.backward);
+        state.forward_stack = restore_stack(&serialized.forward);
+    }
 }
 
 impl NavHistoryState {
@@ -2485,9 +2783,16 @@ pub fn render_item_indicator(item: Box<dyn ItemHandle>, cx: &WindowContext) -> O
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::item::test::{TestItem, TestProjectItem};
-    use gpui::{TestAppContext, VisualTestContext};
+    use crate::{
+        item::{
+            test::{TestItem, TestProjectItem},
+            ProjectItem,
+        },
+        register_project_item,
+    };
+    use gpui::{Empty, TestAppContext, VisualTestContext, WindowHandle};
     use project::FakeFs;
+    use serde_json::json;
     use settings::SettingsStore;
     use theme::LoadThemes;
 
@@ -2935,6 +3240,166 @@ mod tests {
         assert_item_labels(&pane, [], cx);
     }
 
+    #[gpui::test]
+    async fn test_drop_tab_from_other_window(cx: &mut TestAppContext) {
+        init_test(cx);
+        cx.update(register_project_item::<TestFileView>);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/root", json!({ "one.txt": "", "two.txt": "" }))
+            .await;
+        let project = Project::test(fs, ["/root".as_ref()], cx).await;
+        let source = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
+        let target = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
+
+        let one = open_test_file(source, "/root/one.txt", false, cx).await;
+        let two = open_test_file(source, "/root/two.txt", true, cx).await;
+        let source_pane = source
+            .update(cx, |workspace, _| workspace.active_pane().clone())
+            .unwrap();
+        let target_pane = target
+            .update(cx, |workspace, _| workspace.active_pane().clone())
+            .unwrap();
+
+        let drop_tab = |item: &Box<dyn ItemHandle>, cx: &mut TestAppContext| {
+            let dragged_tab = DraggedTab {
+                pane: source_pane.clone(),
+                window: source.into(),
+                item: item.boxed_clone(),
+                ix: 0,
+                detail: 0,
+                is_active: true,
+            };
+            target
+                .update(cx, |_, cx| {
+                    target_pane.update(cx, |pane, cx| pane.handle_tab_drop(&dragged_tab, 0, cx))
+                })
+                .unwrap();
+            cx.run_until_parked();
+        };
+        let paths = |pane: &View<Pane>, cx: &mut TestAppContext| {
+            cx.read(|cx| {
+                pane.read(cx)
+                    .items()
+                    .map(|item| item.project_path(cx).unwrap().path.to_path_buf())
+                    .collect::<Vec<_>>()
+            })
+        };
+
+        // The tab is opened in the other window and closed in its own.
+        drop_tab(&one, cx);
+        assert_eq!(paths(&target_pane, cx), [PathBuf::from("one.txt")]);
+        assert_eq!(paths(&source_pane, cx), [PathBuf::from("two.txt")]);
+
+        // A tab with unsaved changes is left open in its own window too.
+        drop_tab(&two, cx);
+        assert_eq!(
+            paths(&target_pane, cx),
+            [PathBuf::from("one.txt"), PathBuf::from("two.txt")]
+        );
+        assert_eq!(paths(&source_pane, cx), [PathBuf::from("two.txt")]);
+    }
+
+    async fn open_test_file(
+        workspace: WindowHandle<Workspace>,
+        path: &str,
+        is_dirty: bool,
+        cx: &mut TestAppContext,
+    ) -> Box<dyn ItemHandle> {
+        let item = workspace
+            .update(cx, |workspace, cx| {
+                workspace.open_abs_path(PathBuf::from(path), true, cx)
+            })
+            .unwrap()
+            .await
+            .unwrap();
+        workspace
+            .update(cx, |_, cx| {
+                let view = item.act_as::<TestFileView>(cx).unwrap();
+                view.update(cx, |view, _| view.is_dirty = is_dirty);
+            })
+            .unwrap();
+        item
+    }
+
+    struct TestFile {
+        project_path: ProjectPath,
+    }
+
+    impl project::Item for TestFile {
+        fn try_open(
+            _: &Model<Project>,
+            path: &ProjectPath,
+            cx: &mut AppContext,
+        ) -> Option<Task<Result<Model<Self>>>> {
+            let project_path = path.clone();
+            Some(Task::ready(Ok(cx.new_model(|_| Self { project_path }))))
+        }
+
+        fn entry_id(&self, _: &AppContext) -> Option<ProjectEntryId> {
+            None
+        }
+
+        fn project_path(&self, _: &AppContext) -> Option<ProjectPath> {
+            Some(self.project_path.clone())
+        }
+    }
+
+    struct TestFileView {
+        file: Model<TestFile>,
+        is_dirty: bool,
+        focus_handle: FocusHandle,
+    }
+
+    impl Item for TestFileView {
+        type Event = ();
+
+        fn for_each_project_item(
+            &self,
+            cx: &AppContext,
+            f: &mut dyn FnMut(EntityId, &dyn project::Item),
+        ) {
+            f(self.file.entity_id(), self.file.read(cx))
+        }
+
+        fn is_singleton(&self, _: &AppContext) -> bool {
+            true
+        }
+
+        fn is_dirty(&self, _: &AppContext) -> bool {
+            self.is_dirty
+        }
+    }
+
+    impl EventEmitter<()> for TestFileView {}
+
+    impl FocusableView for TestFileView {
+        fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+            self.focus_handle.clone()
+        }
+    }
+
+    impl Render for TestFileView {
+        fn render(&mut self, _: &mut ViewContext<Self>) -> impl IntoElement {
+            Empty
+        }
+    }
+
+    impl ProjectItem for TestFileView {
+        type Item = TestFile;
+
+        fn for_project_item(
+            _: Model<Project>,
+            file: Model<TestFile>,
+            cx: &mut ViewContext<Self>,
+        ) -> Self {
+            Self {
+                file,
+                is_dirty: false,
+                focus_handle: cx.focus_handle(),
+            }
+        }
+    }
+
     fn init_test(cx: &mut TestAppContext) {
         cx.update(|cx| {
             let settings_store = SettingsStore::test(cx);
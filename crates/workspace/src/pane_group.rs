@@ -91,6 +91,38 @@ impl PaneGroup {
         };
     }
 
+    /// Grows the pane along the axis by `amount`, or shrinks it when the amount is negative,
+    /// resizing the innermost split along that axis which contains it.
+    pub fn resize(&mut self, pane: &View<Pane>, axis: Axis, amount: Pixels) {
+        if let Member::Axis(root) = &self.root {
+            root.resize(pane, axis, amount);
+        }
+    }
+
+    /// Gives the members of every split an equal share of its space.
+    pub fn reset_pane_sizes(&mut self) {
+        if let Member::Axis(root) = &self.root {
+            root.reset_pane_sizes();
+        }
+    }
+
+    /// Moves each member of the split that directly contains the pane to the next position, or
+    /// to the previous one when not `forward`, moving the last to the first.
+    pub fn rotate(&mut self, pane: &View<Pane>, forward: bool) {
+        if let Member::Axis(root) = &mut self.root {
+            root.rotate(pane, forward);
+        }
+    }
+
+    /// The pane after the given one in the split that directly contains it, or the one before it
+    /// when it's the last. A split member that is itself split has no single pane to return.
+    pub fn sibling_pane(&self, pane: &View<Pane>) -> Option<View<Pane>> {
+        match &self.root {
+            Member::Pane(_) => None,
+            Member::Axis(root) => root.sibling_pane(pane),
+        }
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub(crate) fn render(
         &self,
@@ -440,6 +472,114 @@ impl PaneAxis {
         }
     }
 
+    fn resize(&self, pane: &View<Pane>, axis: Axis, amount: Pixels) -> bool {
+        let Some(ix) = self.members.iter().position(|member| member.contains(pane)) else {
+            return false;
+        };
+        if let Member::Axis(member) = &self.members[ix] {
+            if member.resize(pane, axis, amount) {
+                return true;
+            }
+        }
+        if self.axis != axis {
+            return false;
+        }
+
+        // Sizes are flexes, which add up to the number of members, so the amount is converted
+        // using the size the split was last rendered at.
+        let size = self
+            .bounding_boxes
+            .lock()
+            .iter()
+            .flatten()
+            .fold(px(0.), |size, bounds| size + bounds.size.along(axis));
+        if size <= px(0.) {
+            return true;
+        }
+        let min_size = match axis {
+            Axis::Horizontal => px(HORIZONTAL_MIN_SIZE),
+            Axis::Vertical => px(VERTICAL_MIN_SIZE),
+        };
+        let len = self.members.len() as f32;
+        let min_flex = min_size / size * len;
+        let max_flex = (len - min_flex * (len - 1.)).max(min_flex);
+
+        // The other members share the rest of the space in the proportions they had.
+        let mut flexes = self.flexes.lock();
+        let new_flex = (flexes[ix] + amount / size * len).clamp(min_flex, max_flex);
+        let rest = len - flexes[ix];
+        let new_rest = len - new_flex;
+        for (member_ix, flex) in flexes.iter_mut().enumerate() {
+            if member_ix == ix {
+                *flex = new_flex;
+            } else if rest > 0. {
+                *flex *= new_rest / rest;
+            } else {
+                *flex = new_rest / (len - 1.);
+            }
+        }
+        true
+    }
+
+    fn reset_pane_sizes(&self) {
+        *self.flexes.lock() = vec![1.; self.members.len()];
+        for member in &self.members {
+            if let Member::Axis(axis) = member {
+                axis.reset_pane_sizes();
+            }
+        }
+    }
+
+    fn rotate(&mut self, pane: &View<Pane>, forward: bool) -> bool {
+        let contains_pane = self
+            .members
+            .iter()
+            .any(|member| matches!(member, Member::Pane(member) if member == pane));
+        if contains_pane {
+            let mut flexes = self.flexes.lock();
+            if forward {
+                self.members.rotate_right(1);
+                flexes.rotate_right(1);
+            } else {
+                self.members.rotate_left(1);
+                flexes.rotate_left(1);
+            }
+            return true;
+        }
+
+        for member in &mut self.members {
+            if let Member::Axis(axis) = member {
+                if axis.rotate(pane, forward) {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    fn sibling_pane(&self, pane: &View<Pane>) -> Option<View<Pane>> {
+        let ix = self
+            .members
+            .iter()
+            .position(|member| matches!(member, Member::Pane(member) if member == pane));
+        let Some(ix) = ix else {
+            return self.members.iter().find_map(|member| match member {
+                Member::Axis(axis) => axis.sibling_pane(pane),
+                Member::Pane(_) => None,
+            });
+        };
+
+        let sibling = if ix + 1 < self.members.len() {
+            &self.members[ix + 1]
+        } else {
+            &self.members[ix.checked_sub(1)?]
+        };
+        match sibling {
+            Member::Pane(sibling) => Some(sibling.clone()),
+            Member::Axis(_) => None,
+        }
+    }
+
     fn bounding_box_for_pane(&self, pane: &View<Pane>) -> Option<Bounds<Pixels>> {
         debug_assert!(self.members.len() == self.bounding_boxes.lock().len());
 
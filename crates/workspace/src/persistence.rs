@@ -360,6 +360,9 @@ define_connection! {
         ALTER TABLE workspaces DROP COLUMN remote_project_id;
         ALTER TABLE workspaces ADD COLUMN dev_server_project_id INTEGER;
     ),
+    sql!(
+        ALTER TABLE panes ADD COLUMN nav_history TEXT;
+    ),
     ];
 }
 
@@ -687,12 +690,7 @@ impl WorkspaceDb {
             .get_pane_group(workspace_id, None)?
             .into_iter()
             .next()
-            .unwrap_or_else(|| {
-                SerializedPaneGroup::Pane(SerializedPane {
-                    active: true,
-                    children: vec![],
-                })
-            }))
+            .unwrap_or_else(|| SerializedPaneGroup::Pane(SerializedPane::new(vec![], true))))
     }
 
     fn get_pane_group(
@@ -707,9 +705,10 @@ impl WorkspaceDb {
             Option<PaneId>,
             Option<bool>,
             Option<String>,
+            Option<String>,
         );
         self.select_bound::<GroupKey, GroupOrPane>(sql!(
-            SELECT group_id, axis, pane_id, active, flexes
+            SELECT group_id, axis, pane_id, active, flexes, nav_history
                 FROM (SELECT
                         group_id,
                         axis,
@@ -718,7 +717,8 @@ impl WorkspaceDb {
                         position,
                         parent_group_id,
                         workspace_id,
-                        flexes
+                        flexes,
+                        NULL as nav_history
                       FROM pane_groups
                     UNION
                       SELECT
@@ -729,14 +729,15 @@ impl WorkspaceDb {
                         position,
                         parent_group_id,
                         panes.workspace_id as workspace_id,
-                        NULL
+                        NULL,
+                        panes.nav_history as nav_history
                       FROM center_panes
                       JOIN panes ON center_panes.pane_id = panes.pane_id)
                 WHERE parent_group_id IS ? AND workspace_id = ?
                 ORDER BY position
         ))?((group_id, workspace_id))?
         .into_iter()
-        .map(|(group_id, axis, pane_id, active, flexes)| {
+        .map(|(group_id, axis, pane_id, active, flexes, nav_history)| {
             if let Some((group_id, axis)) = group_id.zip(axis) {
                 let flexes = flexes
                     .map(|flexes: String| serde_json::from_str::<Vec<f32>>(&flexes))
@@ -748,10 +749,15 @@ impl WorkspaceDb {
                     flexes,
                 })
             } else if let Some((pane_id, active)) = pane_id.zip(active) {
-                Ok(SerializedPaneGroup::Pane(SerializedPane::new(
-                    self.get_items(pane_id)?,
-                    active,
-                )))
+                let nav_history = nav_history
+                    .map(|nav_history: String| serde_json::from_str(&nav_history))
+                    .transpose()?
+                    .unwrap_or_default();
+
+                Ok(SerializedPaneGroup::Pane(
+                    SerializedPane::new(self.get_items(pane_id)?, active)
+                        .with_nav_history(nav_history),
+                ))
             } else {
                 bail!("Pane Group Child was neither a pane group or a pane");
             }
@@ -821,11 +827,17 @@ impl WorkspaceDb {
         pane: &SerializedPane,
         parent: Option<(GroupId, usize)>,
     ) -> Result<PaneId> {
+        let nav_history = if pane.nav_history.is_empty() {
+            None
+        } else {
+            Some(serde_json::to_string(&pane.nav_history)?)
+        };
+
         let pane_id = conn.select_row_bound::<_, i64>(sql!(
-            INSERT INTO panes(workspace_id, active)
-            VALUES (?, ?)
+            INSERT INTO panes(workspace_id, active, nav_history)
+            VALUES (?, ?, ?)
             RETURNING pane_id
-        ))?((workspace_id, pane.active))?
+        ))?((workspace_id, pane.active, nav_history))?
         .ok_or_else(|| anyhow!("Could not retrieve inserted pane_id"))?;
 
         let (parent_id, order) = parent.unzip();
@@ -898,6 +910,7 @@ mod tests {
     use super::*;
     use db::open_test_db;
     use gpui;
+    use model::{SerializedNavHistory, SerializedNavigationEntry};
 
     #[gpui::test]
     async fn test_next_id_stability() {
@@ -1251,6 +1264,48 @@ mod tests {
         assert_eq!(workspace.center_group, new_workspace.center_group);
     }
 
+    #[gpui::test]
+    async fn test_pane_nav_history() {
+        env_logger::try_init().ok();
+
+        let db = WorkspaceDb(open_test_db("test_pane_nav_history").await);
+
+        let nav_history = SerializedNavHistory {
+            backward: vec![
+                SerializedNavigationEntry {
+                    item: 0,
+                    location: Some((10, 4)),
+                },
+                SerializedNavigationEntry {
+                    item: 1,
+                    location: None,
+                },
+            ],
+            forward: vec![SerializedNavigationEntry {
+                item: 0,
+                location: Some((2, 0)),
+            }],
+        };
+        let center_pane = SerializedPaneGroup::Pane(
+            SerializedPane::new(
+                vec![
+                    SerializedItem::new("Terminal", 1, false, false),
+                    SerializedItem::new("Terminal", 2, true, false),
+                ],
+                true,
+            )
+            .with_nav_history(nav_history),
+        );
+
+        let workspace = default_workspace(&["/tmp"], &center_pane);
+
+        db.save_workspace(workspace.clone()).await;
+
+        let new_workspace = db.workspace_for_roots(&["/tmp"]).unwrap();
+
+        assert_eq!(workspace.center_group, new_workspace.center_group);
+    }
+
     #[gpui::test]
     async fn test_cleanup_panes() {
         env_logger::try_init().ok();
@@ -191,10 +191,7 @@ pub(crate) enum SerializedPaneGroup {
 #[cfg(test)]
 impl Default for SerializedPaneGroup {
     fn default() -> Self {
-        Self::Pane(SerializedPane {
-            children: vec![SerializedItem::default()],
-            active: false,
-        })
+        Self::Pane(SerializedPane::new(vec![SerializedItem::default()], false))
     }
 }
 
@@ -270,11 +267,21 @@ impl SerializedPaneGroup {
 pub struct SerializedPane {
     pub(crate) active: bool,
     pub(crate) children: Vec<SerializedItem>,
+    pub(crate) nav_history: SerializedNavHistory,
 }
 
 impl SerializedPane {
     pub fn new(children: Vec<SerializedItem>, active: bool) -> Self {
-        SerializedPane { children, active }
+        SerializedPane {
+            children,
+            active,
+            nav_history: SerializedNavHistory::default(),
+        }
+    }
+
+    pub fn with_nav_history(mut self, nav_history: SerializedNavHistory) -> Self {
+        self.nav_history = nav_history;
+        self
     }
 
     pub async fn deserialize_to(
@@ -334,10 +341,37 @@ impl SerializedPane {
             })?;
         }
 
+        // Adding the items pushed them to the history, which is replaced with the saved one.
+        pane.update(cx, |pane, cx| {
+            pane.nav_history_mut()
+                .restore(&self.nav_history, &items, cx);
+        })?;
+
         anyhow::Ok(items)
     }
 }
 
+/// The navigation history of a pane, whose entries refer to the pane's items by position.
+#[derive(Debug, PartialEq, Eq, Default, Clone, Serialize, Deserialize)]
+pub struct SerializedNavHistory {
+    pub(crate) backward: Vec<SerializedNavigationEntry>,
+    pub(crate) forward: Vec<SerializedNavigationEntry>,
+}
+
+impl SerializedNavHistory {
+    pub fn is_empty(&self) -> bool {
+        self.backward.is_empty() && self.forward.is_empty()
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct SerializedNavigationEntry {
+    /// The position of the entry's item in the pane.
+    pub(crate) item: usize,
+    /// The zero-based row and column of the entry, for items that report them.
+    pub(crate) location: Option<(u32, u32)>,
+}
+
 pub type GroupId = i64;
 pub type PaneId = i64;
 pub type ItemId = u64;
@@ -0,0 +1,254 @@
+use crate::{item::ItemHandle, notifications::NotificationId, Workspace};
+use collections::HashSet;
+use gpui::{DismissEvent, EventEmitter, SharedString, ViewContext, WeakView};
+use ui::{prelude::*, Tooltip};
+use util::ResultExt;
+
+/// Where saving one of the files that were saved together has got to.
+#[derive(Clone, Debug, PartialEq)]
+pub enum FileSaveStatus {
+    Saving,
+    Saved,
+    /// The file couldn't be formatted, so it was saved as it was.
+    SavedWithoutFormatting(SharedString),
+    Failed(SharedString),
+    /// The file can't be saved without asking about it, so it has to be saved on its own.
+    NotSaved(SharedString),
+}
+
+impl FileSaveStatus {
+    fn is_done(&self) -> bool {
+        *self != Self::Saving
+    }
+
+    fn needs_attention(&self) -> bool {
+        !matches!(self, Self::Saving | Self::Saved)
+    }
+}
+
+struct FileSave {
+    name: SharedString,
+    item: Box<dyn ItemHandle>,
+    status: FileSaveStatus,
+}
+
+/// Shows the progress of saving all the dirty files of a workspace, followed by a report of the
+/// ones that couldn't be saved as usual, each of which can be clicked to go to it.
+pub struct SaveAllReport {
+    workspace: WeakView<Workspace>,
+    files: Vec<FileSave>,
+}
+
+impl EventEmitter<DismissEvent> for SaveAllReport {}
+
+impl SaveAllReport {
+    pub fn statuses(&self) -> impl Iterator<Item = (&SharedString, &FileSaveStatus)> {
+        self.files.iter().map(|file| (&file.name, &file.status))
+    }
+
+    fn set_status(&mut self, ix: usize, status: FileSaveStatus, cx: &mut ViewContext<Self>) {
+        if let Some(file) = self.files.get_mut(ix) {
+            file.status = status;
+        }
+        // Only a report of files that need attention is left once they're all done.
+        if self.files.iter().all(|file| file.status.is_done())
+            && !self.files.iter().any(|file| file.status.needs_attention())
+        {
+            cx.emit(DismissEvent);
+        }
+        cx.notify();
+    }
+
+    fn render_file(
+        &self,
+        ix: usize,
+        file: &FileSave,
+        cx: &mut ViewContext<Self>,
+    ) -> impl IntoElement {
+        let (icon, color, detail) = match &file.status {
+            FileSaveStatus::Saving => (IconName::ArrowCircle, Color::Muted, None),
+            FileSaveStatus::Saved => (IconName::Check, Color::Success, None),
+            FileSaveStatus::SavedWithoutFormatting(error) => (
+                IconName::ExclamationTriangle,
+                Color::Warning,
+                Some(format!("Saved without formatting: {error}")),
+            ),
+            FileSaveStatus::Failed(error) => (
+                IconName::XCircle,
+                Color::Error,
+                Some(format!("Failed to save: {error}")),
+            ),
+            FileSaveStatus::NotSaved(reason) => (
+                IconName::ExclamationTriangle,
+                Color::Warning,
+                Some(reason.to_string()),
+            ),
+        };
+
+        h_flex()
+            .id(("save-all-file", ix))
+            .gap_2()
+            .px_1()
+            .rounded_md()
+            .cursor_pointer()
+            .hover(|style| style.bg(cx.theme().colors().element_hover))
+            .child(Icon::new(icon).size(IconSize::Small).color(color))
+            .child(Label::new(file.name.clone()))
+            .children(detail.map(|detail| {
+                Label::new(detail)
+                    .size(LabelSize::Small)
+                    .color(Color::Muted)
+            }))
+            .tooltip(|cx| Tooltip::text("Go to File", cx))
+            .on_click(cx.listener(move |this, _, cx| {
+                let item = this.files[ix].item.boxed_clone();
+                this.workspace
+                    .update(cx, |workspace, cx| {
+                        workspace.activate_item(item.as_ref(), cx)
+                    })
+                    .log_err();
+            }))
+    }
+}
+
+impl Render for SaveAllReport {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let done_count = self
+            .files
+            .iter()
+            .filter(|file| file.status.is_done())
+            .count();
+        let problem_count = self
+            .files
+            .iter()
+            .filter(|file| file.status.needs_attention())
+            .count();
+        let title = if done_count < self.files.len() {
+            format!("Saved {done_count} of {} files…", self.files.len())
+        } else if problem_count == 1 {
+            "1 file needs attention".to_string()
+        } else {
+            format!("{problem_count} files need attention")
+        };
+
+        let files = self
+            .files
+            .iter()
+            .enumerate()
+            .filter(|(_, file)| file.status != FileSaveStatus::Saved)
+            .map(|(ix, file)| self.render_file(ix, file, cx).into_any_element())
+            .collect::<Vec<_>>();
+
+        v_flex()
+            .elevation_3(cx)
+            .p_4()
+            .gap_2()
+            .child(
+                h_flex().justify_between().child(Label::new(title)).child(
+                    IconButton::new("dismiss", IconName::Close)
+                        .on_click(cx.listener(|_, _, cx| cx.emit(DismissEvent))),
+                ),
+            )
+            .child(
+                v_flex()
+                    .id("save-all-files")
+                    .max_h(rems(16.))
+                    .overflow_y_scroll()
+                    .children(files),
+            )
+    }
+}
+
+impl Workspace {
+    /// Saves all the dirty items at once, showing the progress of each of them. Items that can't
+    /// be formatted are saved without formatting, and the ones that can't be saved without asking,
+    /// like untitled or conflicting ones, are left for the user, who's told about all of them.
+    pub(crate) fn save_all_with_details(
+        &mut self,
+        _: &crate::SaveAllWithDetails,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if self.project.read(cx).is_disconnected() {
+            return;
+        }
+
+        let mut seen_items = HashSet::default();
+        let dirty_items = self
+            .panes
+            .iter()
+            .flat_map(|pane| pane.read(cx).items())
+            .filter(|item| item.is_dirty(cx) && seen_items.insert(item.item_id()))
+            .map(|item| item.boxed_clone())
+            .collect::<Vec<_>>();
+        if dirty_items.is_empty() {
+            return;
+        }
+
+        let files = dirty_items
+            .into_iter()
+            .map(|item| {
+                let name = item
+                    .project_path(cx)
+                    .and_then(|project_path| {
+                        Some(project_path.path.file_name()?.to_string_lossy().to_string())
+                    })
+                    .or_else(|| item.tab_description(0, cx).map(|name| name.to_string()))
+                    .unwrap_or_else(|| "untitled".to_string());
+                let status = if !item.can_save(cx) {
+                    FileSaveStatus::NotSaved("Has no file to save to yet".into())
+                } else if item.has_conflict(cx) {
+                    FileSaveStatus::NotSaved("Changed on disk since it was edited".into())
+                } else {
+                    FileSaveStatus::Saving
+                };
+                FileSave {
+                    name: name.into(),
+                    item,
+                    status,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let saves = files
+            .iter()
+            .enumerate()
+            .filter(|(_, file)| file.status == FileSaveStatus::Saving)
+            .map(|(ix, file)| (ix, file.item.boxed_clone()))
+            .collect::<Vec<_>>();
+
+        let workspace = cx.view().downgrade();
+        let report = cx.new_view(|_| SaveAllReport { workspace, files });
+        self.show_notification(NotificationId::unique::<SaveAllReport>(), cx, |_| {
+            report.clone()
+        });
+
+        let project = self.project.clone();
+        for (ix, item) in saves {
+            let save = item.save(true, project.clone(), cx);
+            let report = report.downgrade();
+            let project = project.clone();
+            cx.spawn(|_, mut cx| async move {
+                let status = match save.await {
+                    Ok(()) => FileSaveStatus::Saved,
+                    Err(format_error) => {
+                        // The error may only be the formatter's, so try again without formatting
+                        // rather than leaving the file unsaved.
+                        let save = cx.update(|cx| item.save(false, project, cx))?;
+                        match save.await {
+                            Ok(()) => {
+                                FileSaveStatus::SavedWithoutFormatting(error_message(&format_error))
+                            }
+                            Err(error) => FileSaveStatus::Failed(error_message(&error)),
+                        }
+                    }
+                };
+                report.update(&mut cx, |report, cx| report.set_status(ix, status, cx))
+            })
+            .detach_and_log_err(cx);
+        }
+    }
+}
+
+fn error_message(error: &anyhow::Error) -> SharedString {
+    error.to_string().into()
+}
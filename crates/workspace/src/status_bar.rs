@@ -1,10 +1,11 @@
-use crate::{ItemHandle, Pane};
+use crate::{workspace_settings::StatusBarSettings, ItemHandle, Pane, Workspace};
 use gpui::{
-    AnyView, IntoElement, ParentElement, Render, Styled, Subscription, View, ViewContext,
-    WindowContext,
+    AnchorCorner, AnyElement, AnyView, AppContext, ClickEvent, ElementId, Global, IntoElement,
+    ParentElement, Render, Styled, Subscription, View, ViewContext, WindowContext,
 };
-use std::any::TypeId;
-use ui::{h_flex, prelude::*};
+use settings::{Settings, SettingsStore};
+use std::{any::TypeId, rc::Rc, sync::Arc};
+use ui::{h_flex, popover_menu, prelude::*, ContextMenu};
 use util::ResultExt;
 
 pub trait StatusItemView: Render {
@@ -13,6 +14,11 @@ pub trait StatusItemView: Render {
         active_pane_item: Option<&dyn crate::ItemHandle>,
         cx: &mut ViewContext<Self>,
     );
+
+    /// Builds the menu to open when the item is clicked, if it has one.
+    fn click_menu(&mut self, _cx: &mut ViewContext<Self>) -> Option<View<ContextMenu>> {
+        None
+    }
 }
 
 trait StatusItemViewHandle: Send {
@@ -22,14 +28,98 @@ trait StatusItemViewHandle: Send {
         active_pane_item: Option<&dyn ItemHandle>,
         cx: &mut WindowContext,
     );
+    fn click_menu(&self, cx: &mut WindowContext) -> Option<View<ContextMenu>>;
     fn item_type(&self) -> TypeId;
 }
 
+/// The end of the status bar that an item is placed at.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StatusItemPosition {
+    Left,
+    Right,
+}
+
+struct StatusItem {
+    /// The item's key in the `status_bar.items` setting, if it can be hidden.
+    name: Option<&'static str>,
+    /// Items with a higher priority are placed closer to their end of the status bar.
+    priority: i32,
+    handle: Arc<dyn StatusItemViewHandle>,
+}
+
+#[derive(Default)]
+struct StatusItemRegistry(Vec<StatusItemRegistration>);
+
+impl Global for StatusItemRegistry {}
+
+struct StatusItemRegistration {
+    name: &'static str,
+    position: StatusItemPosition,
+    priority: i32,
+    build: Rc<dyn Fn(&mut Workspace, &mut ViewContext<Workspace>) -> Arc<dyn StatusItemViewHandle>>,
+}
+
+/// Registers an item to add to the status bar of every workspace opened afterwards, which
+/// `build` creates for each workspace. Registering an item replaces any item with the same
+/// name. Items can be hidden by name with the `status_bar.items` setting.
+pub fn register_status_item<T: StatusItemView>(
+    name: &'static str,
+    position: StatusItemPosition,
+    priority: i32,
+    build: impl Fn(&mut Workspace, &mut ViewContext<Workspace>) -> View<T> + 'static,
+    cx: &mut AppContext,
+) {
+    let registry = &mut cx.default_global::<StatusItemRegistry>().0;
+    registry.retain(|registration| registration.name != name);
+    registry.push(StatusItemRegistration {
+        name,
+        position,
+        priority,
+        build: Rc::new(move |workspace, cx| Arc::new(build(workspace, cx))),
+    });
+}
+
+pub(crate) fn add_registered_status_items(
+    workspace: &mut Workspace,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let Some(registry) = cx.try_global::<StatusItemRegistry>() else {
+        return;
+    };
+    let registrations = registry
+        .0
+        .iter()
+        .map(|registration| {
+            (
+                registration.name,
+                registration.position,
+                registration.priority,
+                registration.build.clone(),
+            )
+        })
+        .collect::<Vec<_>>();
+    for (name, position, priority, build) in registrations {
+        let handle = build(workspace, cx);
+        workspace.status_bar().update(cx, |status_bar, cx| {
+            status_bar.insert_item(
+                StatusItem {
+                    name: Some(name),
+                    priority,
+                    handle,
+                },
+                position,
+                cx,
+            )
+        });
+    }
+}
+
 pub struct StatusBar {
-    left_items: Vec<Box<dyn StatusItemViewHandle>>,
-    right_items: Vec<Box<dyn StatusItemViewHandle>>,
+    left_items: Vec<StatusItem>,
+    right_items: Vec<StatusItem>,
     active_pane: View<Pane>,
     _observe_active_pane: Subscription,
+    _observe_settings: Subscription,
 }
 
 impl Render for StatusBar {
@@ -52,13 +142,81 @@ impl StatusBar {
         h_flex()
             .gap(Spacing::Large.rems(cx))
             .overflow_x_hidden()
-            .children(self.left_items.iter().map(|item| item.to_any()))
+            .children(
+                self.visible_items(&self.left_items, cx)
+                    .map(|item| render_item(item, AnchorCorner::BottomLeft)),
+            )
     }
 
     fn render_right_tools(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
-        h_flex()
-            .gap(Spacing::Large.rems(cx))
-            .children(self.right_items.iter().rev().map(|item| item.to_any()))
+        h_flex().gap(Spacing::Large.rems(cx)).children(
+            self.visible_items(&self.right_items, cx)
+                .rev()
+                .map(|item| render_item(item, AnchorCorner::BottomRight)),
+        )
+    }
+
+    fn visible_items<'a>(
+        &self,
+        items: &'a [StatusItem],
+        cx: &AppContext,
+    ) -> impl DoubleEndedIterator<Item = &'a StatusItem> {
+        let settings = &StatusBarSettings::get_global(cx).items;
+        let hidden = items
+            .iter()
+            .map(|item| {
+                item.name
+                    .map_or(false, |name| settings.get(name) == Some(&false))
+            })
+            .collect::<Vec<_>>();
+        items
+            .iter()
+            .zip(hidden)
+            .filter_map(|(item, hidden)| (!hidden).then_some(item))
+    }
+}
+
+fn render_item(item: &StatusItem, anchor: AnchorCorner) -> AnyElement {
+    let view = item.handle.to_any();
+    let handle = item.handle.clone();
+    popover_menu(ElementId::View(view.entity_id()))
+        .menu(move |cx| handle.click_menu(cx))
+        .anchor(anchor)
+        .trigger(StatusItemTrigger {
+            view,
+            on_click: None,
+        })
+        .into_any_element()
+}
+
+/// Wraps a status bar item, so that clicking it opens the item's click menu.
+#[derive(IntoElement)]
+struct StatusItemTrigger {
+    view: AnyView,
+    on_click: Option<Box<dyn Fn(&ClickEvent, &mut WindowContext)>>,
+}
+
+impl Clickable for StatusItemTrigger {
+    fn on_click(mut self, handler: impl Fn(&ClickEvent, &mut WindowContext) + 'static) -> Self {
+        self.on_click = Some(Box::new(handler));
+        self
+    }
+}
+
+impl Selectable for StatusItemTrigger {
+    fn selected(self, _selected: bool) -> Self {
+        self
+    }
+}
+
+impl RenderOnce for StatusItemTrigger {
+    fn render(self, _cx: &mut WindowContext) -> impl IntoElement {
+        div()
+            .id("status-item")
+            .child(self.view)
+            .when_some(self.on_click, |this, on_click| {
+                this.on_click(move |event, cx| on_click(event, cx))
+            })
     }
 }
 
@@ -70,6 +228,7 @@ impl StatusBar {
             active_pane: active_pane.clone(),
             _observe_active_pane: cx
                 .observe(active_pane, |this, _, cx| this.update_active_pane_item(cx)),
+            _observe_settings: cx.observe_global::<SettingsStore>(|_, cx| cx.notify()),
         };
         this.update_active_pane_item(cx);
         this
@@ -79,10 +238,57 @@ impl StatusBar {
     where
         T: 'static + StatusItemView,
     {
+        self.insert_item(
+            StatusItem {
+                name: None,
+                priority: 0,
+                handle: Arc::new(item),
+            },
+            StatusItemPosition::Left,
+            cx,
+        );
+    }
+
+    /// Adds an item that can be hidden by name with the `status_bar.items` setting. Items with
+    /// a higher priority are placed closer to their end of the status bar, and items with the
+    /// same priority in the order they're added, from the end inwards.
+    pub fn add_item<T>(
+        &mut self,
+        name: &'static str,
+        position: StatusItemPosition,
+        priority: i32,
+        item: View<T>,
+        cx: &mut ViewContext<Self>,
+    ) where
+        T: 'static + StatusItemView,
+    {
+        self.insert_item(
+            StatusItem {
+                name: Some(name),
+                priority,
+                handle: Arc::new(item),
+            },
+            position,
+            cx,
+        );
+    }
+
+    fn insert_item(
+        &mut self,
+        item: StatusItem,
+        position: StatusItemPosition,
+        cx: &mut ViewContext<Self>,
+    ) {
         let active_pane_item = self.active_pane.read(cx).active_item();
-        item.set_active_pane_item(active_pane_item.as_deref(), cx);
+        item.handle
+            .set_active_pane_item(active_pane_item.as_deref(), cx);
 
-        self.left_items.push(Box::new(item));
+        let items = match position {
+            StatusItemPosition::Left => &mut self.left_items,
+            StatusItemPosition::Right => &mut self.right_items,
+        };
+        let ix = items.partition_point(|existing| existing.priority >= item.priority);
+        items.insert(ix, item);
         cx.notify();
     }
 
@@ -90,7 +296,7 @@ impl StatusBar {
         self.left_items
             .iter()
             .chain(self.right_items.iter())
-            .find_map(|item| item.to_any().clone().downcast().log_err())
+            .find_map(|item| item.handle.to_any().clone().downcast().log_err())
     }
 
     pub fn position_of_item<T>(&self) -> Option<usize>
@@ -98,12 +304,12 @@ impl StatusBar {
         T: StatusItemView,
     {
         for (index, item) in self.left_items.iter().enumerate() {
-            if item.item_type() == TypeId::of::<T>() {
+            if item.handle.item_type() == TypeId::of::<T>() {
                 return Some(index);
             }
         }
         for (index, item) in self.right_items.iter().enumerate() {
-            if item.item_type() == TypeId::of::<T>() {
+            if item.handle.item_type() == TypeId::of::<T>() {
                 return Some(index + self.left_items.len());
             }
         }
@@ -121,12 +327,18 @@ impl StatusBar {
         let active_pane_item = self.active_pane.read(cx).active_item();
         item.set_active_pane_item(active_pane_item.as_deref(), cx);
 
-        if position < self.left_items.len() {
-            self.left_items.insert(position + 1, Box::new(item))
+        // Take the priority of the preceding item, to keep the items ordered by priority.
+        let (items, ix) = if position < self.left_items.len() {
+            (&mut self.left_items, position)
         } else {
-            self.right_items
-                .insert(position + 1 - self.left_items.len(), Box::new(item))
-        }
+            (&mut self.right_items, position - self.left_items.len())
+        };
+        let item = StatusItem {
+            name: None,
+            priority: items[ix].priority,
+            handle: Arc::new(item),
+        };
+        items.insert(ix + 1, item);
         cx.notify()
     }
 
@@ -143,11 +355,15 @@ impl StatusBar {
     where
         T: 'static + StatusItemView,
     {
-        let active_pane_item = self.active_pane.read(cx).active_item();
-        item.set_active_pane_item(active_pane_item.as_deref(), cx);
-
-        self.right_items.push(Box::new(item));
-        cx.notify();
+        self.insert_item(
+            StatusItem {
+                name: None,
+                priority: 0,
+                handle: Arc::new(item),
+            },
+            StatusItemPosition::Right,
+            cx,
+        );
     }
 
     pub fn set_active_pane(&mut self, active_pane: &View<Pane>, cx: &mut ViewContext<Self>) {
@@ -160,7 +376,8 @@ impl StatusBar {
     fn update_active_pane_item(&mut self, cx: &mut ViewContext<Self>) {
         let active_pane_item = self.active_pane.read(cx).active_item();
         for item in self.left_items.iter().chain(&self.right_items) {
-            item.set_active_pane_item(active_pane_item.as_deref(), cx);
+            item.handle
+                .set_active_pane_item(active_pane_item.as_deref(), cx);
         }
     }
 }
@@ -180,6 +397,10 @@ impl<T: StatusItemView> StatusItemViewHandle for View<T> {
         });
     }
 
+    fn click_menu(&self, cx: &mut WindowContext) -> Option<View<ContextMenu>> {
+        self.update(cx, |this, cx| this.click_menu(cx))
+    }
+
     fn item_type(&self) -> TypeId {
         TypeId::of::<T>()
     }
@@ -1,3 +1,4 @@
+mod activity;
 pub mod dock;
 pub mod item;
 mod modal_layer;
@@ -5,6 +6,7 @@ pub mod notifications;
 pub mod pane;
 pub mod pane_group;
 mod persistence;
+mod save_all;
 pub mod searchable;
 pub mod shared_screen;
 mod status_bar;
@@ -12,6 +14,7 @@ pub mod tasks;
 mod toolbar;
 mod workspace_settings;
 
+pub use activity::{Activity, ActivityTracker};
 use anyhow::{anyhow, Context as _, Result};
 use call::{call_settings::CallSettings, ActiveCall};
 use client::{
@@ -28,7 +31,7 @@ use futures::{
 };
 use gpui::{
     actions, canvas, impl_actions, point, relative, size, Action, AnyElement, AnyView, AnyWeakView,
-    AppContext, AsyncAppContext, AsyncWindowContext, Bounds, DevicePixels, DragMoveEvent,
+    AppContext, AsyncAppContext, AsyncWindowContext, Axis, Bounds, DevicePixels, DragMoveEvent,
     ElementId, Entity as _, EntityId, EventEmitter, FocusHandle, FocusableView, Global,
     GlobalElementId, KeyContext, Keystroke, LayoutId, ManagedView, Model, ModelContext,
     PathPromptOptions, Point, PromptLevel, Render, Size, Subscription, Task, View, WeakView,
@@ -61,7 +64,7 @@ use sqlez::{
     statement::Statement,
 };
 use status_bar::StatusBar;
-pub use status_bar::StatusItemView;
+pub use status_bar::{register_status_item, StatusItemPosition, StatusItemView};
 use std::{
     any::TypeId,
     borrow::Cow,
@@ -87,7 +90,8 @@ use ui::{
 use util::{maybe, ResultExt};
 use uuid::Uuid;
 pub use workspace_settings::{
-    AutosaveSetting, RestoreOnStartupBehaviour, TabBarSettings, WorkspaceSettings,
+    AutosaveSetting, RestoreOnStartupBehaviour, StatusBarSettings, TabBarSettings,
+    WorkspaceSettings,
 };
 
 use crate::notifications::NotificationId;
@@ -122,6 +126,7 @@ actions!(
         Unfollow,
         SaveAs,
         SaveWithoutFormat,
+        SaveAllWithDetails,
         ReloadActiveItem,
         ActivatePreviousPane,
         ActivateNextPane,
@@ -275,6 +280,7 @@ pub fn init_settings(cx: &mut AppContext) {
     ItemSettings::register(cx);
     PreviewTabsSettings::register(cx);
     TabBarSettings::register(cx);
+    StatusBarSettings::register(cx);
 }
 
 pub fn init(app_state: Arc<AppState>, cx: &mut AppContext) {
@@ -600,6 +606,8 @@ pub struct Workspace {
     centered_layout: bool,
     bounds_save_task_queued: Option<Task<()>>,
     on_prompt_for_new_path: Option<PromptForNewPath>,
+    /// Whether reparsing the project's hidden buffers is deferred while the app is throttled.
+    reparsing_deferred: bool,
 }
 
 impl EventEmitter<Event> for Workspace {}
@@ -771,6 +779,7 @@ impl Workspace {
             status_bar.add_right_item(bottom_dock_buttons, cx);
             status_bar
         });
+        cx.defer(status_bar::add_registered_status_items);
 
         let modal_layer = cx.new_view(|_| ModalLayer::new());
 
@@ -781,7 +790,7 @@ impl Workspace {
             active_call = Some((call, subscriptions));
         }
 
-        let subscriptions = vec![
+        let mut subscriptions = vec![
             cx.observe_window_activation(Self::on_window_activation_changed),
             cx.observe_window_bounds(move |this, cx| {
                 if this.bounds_save_task_queued.is_some() {
@@ -836,6 +845,9 @@ impl Workspace {
                 })
             }),
         ];
+        if let Some(tracker) = ActivityTracker::global(cx) {
+            subscriptions.push(cx.observe(&tracker, Self::on_activity_changed));
+        }
 
         cx.defer(|this, cx| {
             this.update_window_title(cx);
@@ -876,6 +888,7 @@ impl Workspace {
             bounds: Default::default(),
             centered_layout: false,
             bounds_save_task_queued: None,
+            reparsing_deferred: false,
             on_prompt_for_new_path: None,
         }
     }
@@ -1205,6 +1218,30 @@ impl Workspace {
         self.navigate_history(pane, NavigationMode::GoingForward, cx)
     }
 
+    /// Goes back or forward until the entry of the pane's history with the given timestamp has
+    /// been navigated to, so that several steps can be taken at once.
+    pub fn navigate_history_to(
+        &mut self,
+        pane: WeakView<Pane>,
+        mode: NavigationMode,
+        timestamp: usize,
+        cx: &mut ViewContext<Workspace>,
+    ) -> Task<Result<()>> {
+        cx.spawn(|workspace, mut cx| async move {
+            // Each step takes at least one entry from the history, so this ends.
+            while pane.update(&mut cx, |pane, _| {
+                pane.nav_history().contains(mode, timestamp)
+            })? {
+                workspace
+                    .update(&mut cx, |workspace, cx| {
+                        workspace.navigate_history(pane.clone(), mode, cx)
+                    })?
+                    .await?;
+            }
+            Ok(())
+        })
+    }
+
     pub fn reopen_closed_item(&mut self, cx: &mut ViewContext<Workspace>) -> Task<Result<()>> {
         self.navigate_history(
             self.active_pane().downgrade(),
@@ -2481,6 +2518,35 @@ impl Workspace {
         }
     }
 
+    /// Grows the active pane along the axis by `amount`, or shrinks it when it's negative.
+    pub fn resize_active_pane(&mut self, axis: Axis, amount: Pixels, cx: &mut ViewContext<Self>) {
+        self.center.resize(&self.active_pane, axis, amount);
+        cx.notify();
+    }
+
+    pub fn reset_pane_sizes(&mut self, cx: &mut ViewContext<Self>) {
+        self.center.reset_pane_sizes();
+        cx.notify();
+    }
+
+    /// Rotates the panes in the split that contains the active pane, as vim's `ctrl-w r` and
+    /// `ctrl-w R`.
+    pub fn rotate_panes(&mut self, forward: bool, cx: &mut ViewContext<Self>) {
+        self.center.rotate(&self.active_pane, forward);
+        cx.notify();
+    }
+
+    /// Exchanges the active pane with the next one in its split, or the previous one if it's the
+    /// last, and activates the pane that takes its place, as vim's `ctrl-w x`.
+    pub fn exchange_active_pane(&mut self, cx: &mut ViewContext<Self>) {
+        let active_pane = self.active_pane.clone();
+        if let Some(sibling) = self.center.sibling_pane(&active_pane) {
+            self.center.swap(&active_pane, &sibling);
+            cx.focus_view(&sibling);
+            cx.notify();
+        }
+    }
+
     fn handle_pane_focused(&mut self, pane: View<Pane>, cx: &mut ViewContext<Self>) {
         // This is explicitly hoisted out of the following check for pane identity as
         // terminal panel panes are not registered as a center panes.
@@ -3439,7 +3505,31 @@ impl Workspace {
         Some(cx.new_view(|cx| SharedScreen::new(&track, peer_id, user.clone(), cx)))
     }
 
+    /// Defers reparsing buffers that aren't shown while the app is throttled, and catches
+    /// them up once it's active again.
+    fn on_activity_changed(&mut self, tracker: Model<ActivityTracker>, cx: &mut ViewContext<Self>) {
+        let throttled = tracker.read(cx).is_throttled();
+        if throttled == self.reparsing_deferred {
+            return;
+        }
+        self.reparsing_deferred = throttled;
+
+        let mut visible_buffers = HashSet::default();
+        if throttled {
+            for pane in &self.panes {
+                if let Some(item) = pane.read(cx).active_item() {
+                    visible_buffers.extend(item.project_item_model_ids(cx));
+                }
+            }
+        }
+        for buffer in self.project.read(cx).opened_buffers() {
+            let deferred = throttled && !visible_buffers.contains(&buffer.entity_id());
+            buffer.update(cx, |buffer, cx| buffer.set_reparsing_deferred(deferred, cx));
+        }
+    }
+
     pub fn on_window_activation_changed(&mut self, cx: &mut ViewContext<Self>) {
+        ActivityTracker::window_activation_changed(cx);
         if cx.is_window_active() {
             self.update_active_view_for_followers(cx);
             cx.background_executor()
@@ -3545,25 +3635,30 @@ impl Workspace {
 
     fn serialize_workspace_internal(&self, cx: &mut WindowContext) -> Task<()> {
         fn serialize_pane_handle(pane_handle: &View<Pane>, cx: &WindowContext) -> SerializedPane {
-            let (items, active) = {
-                let pane = pane_handle.read(cx);
-                let active_item_id = pane.active_item().map(|item| item.item_id());
-                (
-                    pane.items()
-                        .filter_map(|item_handle| {
-                            Some(SerializedItem {
-                                kind: Arc::from(item_handle.serialized_item_kind()?),
-                                item_id: item_handle.item_id().as_u64(),
-                                active: Some(item_handle.item_id()) == active_item_id,
-                                preview: pane.is_active_preview_item(item_handle.item_id()),
-                            })
-                        })
-                        .collect::<Vec<_>>(),
-                    pane.has_focus(cx),
-                )
-            };
+            let pane = pane_handle.read(cx);
+            let active_item_id = pane.active_item().map(|item| item.item_id());
+            let serialized_items = pane
+                .items()
+                .filter(|item_handle| item_handle.serialized_item_kind().is_some())
+                .collect::<Vec<_>>();
+            let items = serialized_items
+                .iter()
+                .filter_map(|item_handle| {
+                    Some(SerializedItem {
+                        kind: Arc::from(item_handle.serialized_item_kind()?),
+                        item_id: item_handle.item_id().as_u64(),
+                        active: Some(item_handle.item_id()) == active_item_id,
+                        preview: pane.is_active_preview_item(item_handle.item_id()),
+                    })
+                })
+                .collect::<Vec<_>>();
+            let item_ids = serialized_items
+                .iter()
+                .map(|item_handle| item_handle.item_id())
+                .collect::<Vec<_>>();
 
-            SerializedPane::new(items, active)
+            SerializedPane::new(items, pane.has_focus(cx))
+                .with_nav_history(pane.nav_history().serialize(&item_ids, cx))
         }
 
         fn build_serialized_pane_group(
@@ -3779,6 +3874,7 @@ impl Workspace {
             .on_action(cx.listener(Self::close_inactive_items_and_panes))
             .on_action(cx.listener(Self::close_all_items_and_panes))
             .on_action(cx.listener(Self::save_all))
+            .on_action(cx.listener(Self::save_all_with_details))
             .on_action(cx.listener(Self::send_keystrokes))
             .on_action(cx.listener(Self::add_folder_to_project))
             .on_action(cx.listener(Self::follow_next_collaborator))
@@ -3888,6 +3984,7 @@ impl Workspace {
             .on_action(cx.listener(Self::close_all_items_and_panes))
             .on_action(cx.listener(Self::add_folder_to_project))
             .on_action(cx.listener(Self::save_all))
+            .on_action(cx.listener(Self::save_all_with_details))
             .on_action(cx.listener(Self::open));
         for action in self.workspace_actions.iter() {
             div = (action)(div, cx)
@@ -4104,6 +4201,7 @@ impl Render for Workspace {
 
         self.actions(div(), cx)
             .key_context(context)
+            .on_mouse_move(|_, cx| ActivityTracker::record_input(cx))
             .relative()
             .size_full()
             .flex()
@@ -4875,7 +4973,7 @@ pub fn join_hosted_project(
                         Workspace::new(Default::default(), project, app_state.clone(), cx)
                     })
                 })
-            })?
+            })??
         };
 
         workspace.update(&mut cx, |_, cx| {
@@ -4940,7 +5038,7 @@ pub fn join_dev_server_project(
                             Workspace::new(Default::default(), project, app_state.clone(), cx)
                         })
                     })
-                })?
+                })??
             }
         };
 
@@ -5003,7 +5101,7 @@ pub fn join_in_room_project(
                         Workspace::new(Default::default(), project, app_state.clone(), cx)
                     })
                 })
-            })?
+            })??
         };
 
         workspace.update(&mut cx, |workspace, cx| {
@@ -5331,6 +5429,75 @@ mod tests {
         assert_eq!(cx.window_title().as_deref(), Some("one.txt — root2"));
     }
 
+    #[gpui::test]
+    async fn test_save_all_with_details(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, None, cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+
+        let clean = cx.new_view(|cx| {
+            TestItem::new(cx).with_project_items(&[TestProjectItem::new(1, "1.txt", cx)])
+        });
+        let dirty = cx.new_view(|cx| {
+            TestItem::new(cx)
+                .with_dirty(true)
+                .with_project_items(&[TestProjectItem::new(2, "2.txt", cx)])
+        });
+        let conflicted = cx.new_view(|cx| {
+            TestItem::new(cx)
+                .with_dirty(true)
+                .with_conflict(true)
+                .with_project_items(&[TestProjectItem::new(3, "3.txt", cx)])
+        });
+        let untitled = cx.new_view(|cx| {
+            TestItem::new(cx)
+                .with_dirty(true)
+                .with_project_items(&[TestProjectItem::new_untitled(cx)])
+        });
+        workspace.update(cx, |workspace, cx| {
+            workspace.add_item_to_active_pane(Box::new(clean.clone()), None, cx);
+            workspace.add_item_to_active_pane(Box::new(dirty.clone()), None, cx);
+            workspace.add_item_to_active_pane(Box::new(conflicted.clone()), None, cx);
+            workspace.add_item_to_active_pane(Box::new(untitled.clone()), None, cx);
+            workspace.save_all_with_details(&SaveAllWithDetails, cx);
+        });
+        cx.run_until_parked();
+
+        // Only the dirty file that can be saved without asking about it is saved, and the others
+        // are reported rather than skipped.
+        clean.update(cx, |item, _| assert_eq!(item.save_count, 0));
+        dirty.update(cx, |item, _| assert_eq!(item.save_count, 1));
+        conflicted.update(cx, |item, _| assert_eq!(item.save_count, 0));
+        untitled.update(cx, |item, _| assert_eq!(item.save_count, 0));
+
+        let report = workspace.update(cx, |workspace, _| {
+            workspace
+                .notifications
+                .iter()
+                .find(|(id, _)| *id == NotificationId::unique::<save_all::SaveAllReport>())
+                .map(|(_, notification)| notification.to_any())
+                .and_then(|notification| notification.downcast::<save_all::SaveAllReport>().ok())
+                .unwrap()
+        });
+        report.update(cx, |report, _| {
+            assert_eq!(
+                report
+                    .statuses()
+                    .map(|(_, status)| status.clone())
+                    .collect::<Vec<_>>(),
+                [
+                    save_all::FileSaveStatus::Saved,
+                    save_all::FileSaveStatus::NotSaved(
+                        "Changed on disk since it was edited".into()
+                    ),
+                    save_all::FileSaveStatus::NotSaved("Has no file to save to yet".into()),
+                ]
+            );
+        });
+    }
+
     #[gpui::test]
     async fn test_close_window(cx: &mut TestAppContext) {
         init_test(cx);
@@ -5587,6 +5754,60 @@ mod tests {
         });
     }
 
+    #[gpui::test]
+    async fn test_activity_tracking(cx: &mut gpui::TestAppContext) {
+        init_test(cx);
+        cx.update(ActivityTracker::init);
+        let tracker = cx.update(|cx| ActivityTracker::global(cx).unwrap());
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (_workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+        cx.update(|cx| cx.activate_window());
+        cx.executor().run_until_parked();
+        tracker.update(cx, |tracker, _| {
+            assert_eq!(tracker.activity(), Activity::Active)
+        });
+
+        // Deactivating the window only puts the app in the background after a delay.
+        cx.deactivate_window();
+        tracker.update(cx, |tracker, _| {
+            assert_eq!(tracker.activity(), Activity::Active)
+        });
+        cx.executor().advance_clock(Duration::from_secs(2));
+        tracker.update(cx, |tracker, _| {
+            assert_eq!(tracker.activity(), Activity::Background)
+        });
+
+        // Activating it resumes immediately.
+        cx.update(|cx| cx.activate_window());
+        cx.executor().run_until_parked();
+        tracker.update(cx, |tracker, _| {
+            assert_eq!(tracker.activity(), Activity::Active)
+        });
+
+        // The app becomes idle once there's no input for the idle timeout.
+        let timeout = Duration::from_secs(
+            cx.update(|cx| WorkspaceSettings::get_global(cx).idle_timeout_seconds),
+        );
+        cx.executor().advance_clock(timeout / 2);
+        cx.update(|cx| ActivityTracker::record_input(cx));
+        cx.executor().advance_clock(timeout / 2);
+        tracker.update(cx, |tracker, _| {
+            assert_eq!(tracker.activity(), Activity::Active)
+        });
+        cx.executor().advance_clock(timeout);
+        tracker.update(cx, |tracker, _| {
+            assert_eq!(tracker.activity(), Activity::Idle)
+        });
+        let active = cx.update(|cx| ActivityTracker::active(cx));
+        cx.update(|cx| ActivityTracker::record_input(cx));
+        active.await;
+        tracker.update(cx, |tracker, _| {
+            assert_eq!(tracker.activity(), Activity::Active)
+        });
+    }
+
     #[gpui::test]
     async fn test_autosave(cx: &mut gpui::TestAppContext) {
         init_test(cx);
@@ -5707,6 +5928,51 @@ mod tests {
         item.update(cx, |item, _| assert_eq!(item.save_count, 5));
     }
 
+    #[gpui::test]
+    async fn test_autosave_exclusions(cx: &mut gpui::TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+        let pane = workspace.update(cx, |workspace, _| workspace.active_pane().clone());
+
+        let excluded_item = cx.new_view(|cx| {
+            TestItem::new(cx).with_project_items(&[TestProjectItem::new(1, "vendor/lib.rs", cx)])
+        });
+        let item = cx.new_view(|cx| {
+            TestItem::new(cx).with_project_items(&[TestProjectItem::new(2, "src/main.rs", cx)])
+        });
+        workspace.update(cx, |workspace, cx| {
+            workspace.add_item_to_active_pane(Box::new(excluded_item.clone()), None, cx);
+            workspace.add_item_to_active_pane(Box::new(item.clone()), None, cx);
+        });
+        cx.update(|cx| {
+            SettingsStore::update_global(cx, |settings, cx| {
+                settings.update_user_settings::<WorkspaceSettings>(cx, |settings| {
+                    settings.autosave = Some(AutosaveSetting::OnWindowChange);
+                    settings.autosave_exclusions = Some(vec!["vendor/**".to_string()]);
+                })
+            });
+        });
+        excluded_item.update(cx, |item, _| item.is_dirty = true);
+        item.update(cx, |item, _| item.is_dirty = true);
+
+        // Only the file that isn't excluded is saved.
+        cx.deactivate_window();
+        excluded_item.update(cx, |item, _| assert_eq!(item.save_count, 0));
+        item.update(cx, |item, _| assert_eq!(item.save_count, 1));
+
+        // Closing the excluded file asks whether to save it.
+        let excluded_item_id = excluded_item.entity_id();
+        let _close_items = pane.update(cx, |pane, cx| {
+            pane.close_items(cx, SaveIntent::Close, move |id| id == excluded_item_id)
+        });
+        cx.run_until_parked();
+        assert!(cx.has_pending_prompt());
+        excluded_item.update(cx, |item, _| assert_eq!(item.save_count, 0));
+    }
+
     #[gpui::test]
     async fn test_pane_navigation(cx: &mut gpui::TestAppContext) {
         init_test(cx);
@@ -5761,6 +6027,104 @@ mod tests {
         });
     }
 
+    #[gpui::test]
+    async fn test_navigate_history_to(cx: &mut gpui::TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+
+        let item = cx.new_view(|cx| {
+            TestItem::new(cx).with_project_items(&[TestProjectItem::new(1, "1.txt", cx)])
+        });
+        let pane = workspace.update(cx, |workspace, _| workspace.active_pane().clone());
+        workspace.update(cx, |workspace, cx| {
+            workspace.add_item_to_active_pane(Box::new(item.clone()), None, cx);
+        });
+        item.update(cx, |item, cx| {
+            item.set_state("one".to_string(), cx);
+            item.set_state("two".to_string(), cx);
+            item.set_state("three".to_string(), cx);
+        });
+
+        let entries = pane.update(cx, |pane, cx| {
+            pane.nav_history().entries(NavigationMode::GoingBack, cx)
+        });
+        assert_eq!(entries.len(), 3);
+
+        // Going back to the second entry takes two steps at once.
+        workspace
+            .update(cx, |workspace, cx| {
+                workspace.navigate_history_to(
+                    pane.downgrade(),
+                    NavigationMode::GoingBack,
+                    entries[1].timestamp,
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+
+        pane.update(cx, |pane, cx| {
+            let history = pane.nav_history();
+            assert_eq!(history.entries(NavigationMode::GoingBack, cx).len(), 1);
+            assert_eq!(history.entries(NavigationMode::GoingForward, cx).len(), 2);
+            assert!(!history.contains(NavigationMode::GoingBack, entries[1].timestamp));
+        });
+    }
+
+    #[gpui::test]
+    async fn test_rotate_and_exchange_panes(cx: &mut gpui::TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+
+        let (left, middle, right) = workspace.update(cx, |workspace, cx| {
+            let left = workspace.active_pane().clone();
+            let middle = workspace.split_pane(left.clone(), SplitDirection::Right, cx);
+            let right = workspace.split_pane(middle.clone(), SplitDirection::Right, cx);
+            (left, middle, right)
+        });
+        let center_panes = |workspace: &View<Workspace>, cx: &mut VisualTestContext| {
+            workspace.update(cx, |workspace, _| {
+                workspace
+                    .center
+                    .panes()
+                    .into_iter()
+                    .cloned()
+                    .collect::<Vec<_>>()
+            })
+        };
+        assert_eq!(
+            center_panes(&workspace, cx),
+            [left.clone(), middle.clone(), right.clone()]
+        );
+
+        workspace.update(cx, |workspace, cx| workspace.rotate_panes(true, cx));
+        assert_eq!(
+            center_panes(&workspace, cx),
+            [right.clone(), left.clone(), middle.clone()]
+        );
+        workspace.update(cx, |workspace, cx| workspace.rotate_panes(false, cx));
+        assert_eq!(
+            center_panes(&workspace, cx),
+            [left.clone(), middle.clone(), right.clone()]
+        );
+
+        // The last pane is exchanged with the one before it, which then becomes active.
+        workspace.update(cx, |workspace, cx| workspace.exchange_active_pane(cx));
+        assert_eq!(
+            center_panes(&workspace, cx),
+            [left.clone(), right.clone(), middle.clone()]
+        );
+        workspace.update(cx, |workspace, _| {
+            assert_eq!(workspace.active_pane(), &middle);
+        });
+    }
+
     #[gpui::test]
     async fn test_toggle_docks_and_panels(cx: &mut gpui::TestAppContext) {
         init_test(cx);
@@ -6161,6 +6525,57 @@ mod tests {
         });
     }
 
+    #[gpui::test]
+    async fn test_register_status_items(cx: &mut TestAppContext) {
+        init_test(cx);
+        cx.update(|cx| {
+            register_status_item(
+                "low",
+                StatusItemPosition::Right,
+                0,
+                |_, cx| cx.new_view(|_| TestStatusItem::<false>),
+                cx,
+            );
+            register_status_item(
+                "high",
+                StatusItemPosition::Right,
+                1,
+                |_, cx| cx.new_view(|_| TestStatusItem::<true>),
+                cx,
+            );
+        });
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+        cx.run_until_parked();
+
+        workspace.update(cx, |workspace, cx| {
+            let status_bar = workspace.status_bar().read(cx);
+            let high = status_bar
+                .position_of_item::<TestStatusItem<true>>()
+                .unwrap();
+            let low = status_bar
+                .position_of_item::<TestStatusItem<false>>()
+                .unwrap();
+            // Though registered later, the item with the higher priority is placed closer to
+            // the end of the status bar.
+            assert!(high < low);
+        });
+    }
+
+    struct TestStatusItem<const HIGH: bool>;
+
+    impl<const HIGH: bool> Render for TestStatusItem<HIGH> {
+        fn render(&mut self, _: &mut ViewContext<Self>) -> impl IntoElement {
+            Empty
+        }
+    }
+
+    impl<const HIGH: bool> StatusItemView for TestStatusItem<HIGH> {
+        fn set_active_pane_item(&mut self, _: Option<&dyn ItemHandle>, _: &mut ViewContext<Self>) {}
+    }
+
     mod register_project_item_tests {
         use ui::Context as _;
 
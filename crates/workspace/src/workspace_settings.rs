@@ -1,4 +1,5 @@
 use anyhow::Result;
+use collections::HashMap;
 use gpui::AppContext;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -11,9 +12,11 @@ pub struct WorkspaceSettings {
     pub confirm_quit: bool,
     pub show_call_status_icon: bool,
     pub autosave: AutosaveSetting,
+    pub autosave_exclusions: Vec<String>,
     pub restore_on_startup: RestoreOnStartupBehaviour,
     pub drop_target_size: f32,
     pub when_closing_with_no_tabs: CloseWindowWhenNoItems,
+    pub idle_timeout_seconds: u64,
 }
 
 #[derive(Copy, Clone, Default, Serialize, Deserialize, JsonSchema)]
@@ -41,7 +44,7 @@ impl CloseWindowWhenNoItems {
 #[derive(Copy, Clone, Default, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum RestoreOnStartupBehaviour {
-    /// Always start with an empty editor
+    /// Always start with the welcome page, which lists recent projects
     None,
     /// Restore the workspace that was closed last.
     #[default]
@@ -70,6 +73,11 @@ pub struct WorkspaceSettingsContent {
     ///
     /// Default: off
     pub autosave: Option<AutosaveSetting>,
+    /// Globs matching the files that aren't saved automatically, such as vendored or generated
+    /// code. Closing them with unsaved changes asks whether to save them.
+    ///
+    /// Default: []
+    pub autosave_exclusions: Option<Vec<String>>,
     /// Controls previous session restoration in freshly launched Zed instance.
     /// Values: none, last_workspace
     /// Default: last_workspace
@@ -83,6 +91,12 @@ pub struct WorkspaceSettingsContent {
     ///
     /// Default: auto ("on" on macOS, "off" otherwise)
     pub when_closing_with_no_tabs: Option<CloseWindowWhenNoItems>,
+    /// How long without input before Zed is considered idle, and throttles work that
+    /// isn't essential, such as blinking the cursor and indexing. It's also throttled
+    /// while no Zed window is focused. Set to 0 to only throttle while unfocused.
+    ///
+    /// Default: 300
+    pub idle_timeout_seconds: Option<u64>,
 }
 
 #[derive(Deserialize)]
@@ -103,6 +117,20 @@ pub struct TabBarSettingsContent {
     pub show_nav_history_buttons: Option<bool>,
 }
 
+#[derive(Deserialize)]
+pub struct StatusBarSettings {
+    pub items: HashMap<String, bool>,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct StatusBarSettingsContent {
+    /// Whether to show each status bar item, keyed by item name. Items that
+    /// aren't listed are shown.
+    ///
+    /// Default: {}
+    pub items: Option<HashMap<String, bool>>,
+}
+
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum AutosaveSetting {
@@ -150,3 +178,13 @@ impl Settings for TabBarSettings {
         sources.json_merge()
     }
 }
+
+impl Settings for StatusBarSettings {
+    const KEY: Option<&'static str> = Some("status_bar");
+
+    type FileContent = StatusBarSettingsContent;
+
+    fn load(sources: SettingsSources<Self::FileContent>, _: &mut AppContext) -> Result<Self> {
+        sources.json_merge()
+    }
+}
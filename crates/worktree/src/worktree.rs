@@ -69,13 +69,23 @@ use util::{
     ResultExt,
 };
 
-pub use worktree_settings::WorktreeSettings;
+pub use worktree_settings::{FollowSymlinks, WorktreeSettings};
 
 #[cfg(feature = "test-support")]
 pub const FS_WATCH_LATENCY: Duration = Duration::from_millis(100);
 #[cfg(not(feature = "test-support"))]
 pub const FS_WATCH_LATENCY: Duration = Duration::from_millis(100);
 
+/// How many entries the scanner reads before adding them to the snapshot.
+const POPULATE_BATCH_SIZE: usize = 1024;
+/// How many entries' metadata each scanner worker reads at once.
+const METADATA_CONCURRENCY: usize = 16;
+/// How many changed paths in one batch of FS events, such as from a build or a `git checkout`,
+/// make the scanner wait for the rest of the changes before processing them.
+const EVENT_STORM_THRESHOLD: usize = 256;
+/// The longest the scanner waits for an event storm to settle.
+const MAX_EVENT_STORM_DELAY: Duration = Duration::from_millis(500);
+
 #[derive(Copy, Clone, PartialEq, Eq, Debug, Hash, PartialOrd, Ord)]
 pub struct WorktreeId(usize);
 
@@ -285,6 +295,10 @@ pub struct LocalSnapshot {
     git_repositories: TreeMap<ProjectEntryId, LocalRepositoryEntry>,
     file_scan_exclusions: Vec<PathMatcher>,
     private_files: Vec<PathMatcher>,
+    follow_symlinks: FollowSymlinks,
+    /// The only directories that are scanned and watched, unless they're loaded on demand, or
+    /// empty if the whole worktree is.
+    sparse_scan_paths: Vec<Arc<Path>>,
 }
 
 struct BackgroundScannerState {
@@ -299,6 +313,22 @@ struct BackgroundScannerState {
     removed_entry_ids: HashMap<u64, ProjectEntryId>,
     changed_paths: Vec<Arc<Path>>,
     prev_snapshot: Snapshot,
+    /// Directories that have been scanned, but whose entries haven't been added to the
+    /// snapshot yet.
+    pending_dirs: Vec<ScannedDir>,
+    pending_entry_count: usize,
+    /// In a sparse worktree, the directories that are watched: the sparse scan paths, and the
+    /// directories outside of them that were loaded on demand.
+    watched_dirs: Vec<Arc<Path>>,
+    /// Directories that were loaded on demand in a sparse worktree, and aren't watched yet.
+    dirs_to_watch: Vec<Arc<Path>>,
+}
+
+/// A directory's entries, as read by the scanner.
+struct ScannedDir {
+    path: Arc<Path>,
+    entries: Vec<Entry>,
+    ignore: Option<Arc<Gitignore>>,
 }
 
 #[derive(Debug, Clone)]
@@ -400,12 +430,25 @@ impl Worktree {
                         }), cx).private_files.as_deref(),
                         "private_files",
                     );
+                    let new_follow_symlinks = WorktreeSettings::get_global(cx)
+                        .follow_symlinks
+                        .unwrap_or_default();
+                    let new_sparse_scan_paths = sparse_scan_paths(
+                        WorktreeSettings::get(Some(settings::SettingsLocation {
+                            worktree_id: cx.handle().entity_id().as_u64() as usize,
+                            path: Path::new("")
+                        }), cx).sparse_scan_paths.as_deref(),
+                    );
 
                     if new_file_scan_exclusions != this.snapshot.file_scan_exclusions
                         || new_private_files != this.snapshot.private_files
+                        || new_follow_symlinks != this.snapshot.follow_symlinks
+                        || new_sparse_scan_paths != this.snapshot.sparse_scan_paths
                     {
                         this.snapshot.file_scan_exclusions = new_file_scan_exclusions;
                         this.snapshot.private_files = new_private_files;
+                        this.snapshot.follow_symlinks = new_follow_symlinks;
+                        this.snapshot.sparse_scan_paths = new_sparse_scan_paths;
 
                         log::info!(
                             "Re-scanning directories, new scan exclude files: {:?}, new dotenv files: {:?}",
@@ -459,6 +502,20 @@ impl Worktree {
                     }), cx).private_files.as_deref(),
                     "private_files",
                 ),
+                follow_symlinks: WorktreeSettings::get_global(cx)
+                    .follow_symlinks
+                    .unwrap_or_default(),
+                sparse_scan_paths: sparse_scan_paths(
+                    WorktreeSettings::get(
+                        Some(SettingsLocation {
+                            worktree_id: cx.handle().entity_id().as_u64() as usize,
+                            path: Path::new(""),
+                        }),
+                        cx,
+                    )
+                    .sparse_scan_paths
+                    .as_deref(),
+                ),
                 ignores_by_parent_abs_path: Default::default(),
                 git_repositories: Default::default(),
                 snapshot: Snapshot {
@@ -715,7 +772,7 @@ fn start_background_scan_tasks(
         };
         let background = cx.background_executor().clone();
         async move {
-            let events = fs.watch(&abs_path, FS_WATCH_LATENCY).await;
+            let events = watch_worktree(fs.as_ref(), &abs_path, &snapshot.sparse_scan_paths).await;
             let case_sensitive = fs.is_case_sensitive().await.unwrap_or_else(|e| {
                 log::error!(
                     "Failed to determine whether filesystem is case sensitive (falling back to true) due to error: {e:#}"
@@ -764,6 +821,32 @@ fn start_background_scan_tasks(
     vec![background_scanner, scan_state_updater]
 }
 
+/// Watches the worktree's root, or in a sparse worktree, only its sparse scan paths.
+async fn watch_worktree(
+    fs: &dyn Fs,
+    abs_path: &Path,
+    sparse_scan_paths: &[Arc<Path>],
+) -> Pin<Box<dyn Send + Stream<Item = Vec<PathBuf>>>> {
+    if sparse_scan_paths.is_empty() {
+        return fs.watch(abs_path, FS_WATCH_LATENCY).await;
+    }
+    let mut watches = Vec::with_capacity(sparse_scan_paths.len());
+    for path in sparse_scan_paths {
+        watches.push(fs.watch(&abs_path.join(path), FS_WATCH_LATENCY).await);
+    }
+    futures::stream::select_all(watches).boxed()
+}
+
+fn sparse_scan_paths(values: Option<&[String]>) -> Vec<Arc<Path>> {
+    values
+        .unwrap_or(&[])
+        .iter()
+        .map(|path| Arc::from(Path::new(path.trim_matches('/'))))
+        .sorted()
+        .dedup()
+        .collect()
+}
+
 fn path_matchers(values: Option<&[String]>, context: &'static str) -> Vec<PathMatcher> {
     values
         .unwrap_or(&[])
@@ -2524,6 +2607,16 @@ impl LocalSnapshot {
         })
     }
 
+    /// Whether the directory is scanned along with the rest of the worktree, because it's
+    /// within one of the sparse scan paths, or contains one of them.
+    pub fn is_in_sparse_scan(&self, path: &Path) -> bool {
+        self.sparse_scan_paths.is_empty()
+            || self
+                .sparse_scan_paths
+                .iter()
+                .any(|sparse_path| path.starts_with(sparse_path) || sparse_path.starts_with(path))
+    }
+
     pub fn is_path_excluded(&self, mut path: PathBuf) -> bool {
         loop {
             if self
@@ -2542,7 +2635,12 @@ impl LocalSnapshot {
 
 impl BackgroundScannerState {
     fn should_scan_directory(&self, entry: &Entry) -> bool {
-        (!entry.is_external && !entry.is_ignored)
+        if !self.may_follow(entry) {
+            return false;
+        }
+        (!entry.is_ignored
+            && (!entry.is_external || self.snapshot.follow_symlinks == FollowSymlinks::Always)
+            && self.snapshot.is_in_sparse_scan(&entry.path))
             || entry.path.file_name() == Some(*DOT_GIT)
             || self.scanned_dirs.contains(&entry.id) // If we've ever scanned it, keep scanning
             || self
@@ -2555,6 +2653,27 @@ impl BackgroundScannerState {
                 .any(|p| entry.path.starts_with(p))
     }
 
+    /// Whether the directory may ever be scanned, which symlinked directories can't be when the
+    /// `follow_symlinks` setting is `never`. The root is always scanned, even if it's a symlink.
+    fn may_follow(&self, entry: &Entry) -> bool {
+        !entry.is_symlink
+            || entry.path.as_ref() == Path::new("")
+            || self.snapshot.follow_symlinks != FollowSymlinks::Never
+    }
+
+    /// Records that a directory loaded on demand in a sparse worktree needs to be watched, unless
+    /// it's already within a watched directory.
+    fn watch_loaded_dir(&mut self, path: &Path) {
+        if self.snapshot.sparse_scan_paths.is_empty()
+            || self.watched_dirs.iter().any(|dir| path.starts_with(dir))
+        {
+            return;
+        }
+        let path: Arc<Path> = path.into();
+        self.watched_dirs.push(path.clone());
+        self.dirs_to_watch.push(path);
+    }
+
     fn enqueue_scan_dir(&self, abs_path: Arc<Path>, entry: &Entry, scan_job_tx: &Sender<ScanJob>) {
         let path = entry.path.clone();
         let ignore_stack = self.snapshot.ignore_stack_for_abs_path(&abs_path, true);
@@ -2610,62 +2729,92 @@ impl BackgroundScannerState {
         entry
     }
 
+    /// Queues a scanned directory's entries to be added to the snapshot. They're added in
+    /// batches, so that the snapshot's trees are edited once for many directories.
     fn populate_dir(
         &mut self,
         parent_path: &Arc<Path>,
-        entries: impl IntoIterator<Item = Entry>,
+        entries: Vec<Entry>,
         ignore: Option<Arc<Gitignore>>,
     ) {
-        let mut parent_entry = if let Some(parent_entry) = self
-            .snapshot
-            .entries_by_path
-            .get(&PathKey(parent_path.clone()), &())
-        {
-            parent_entry.clone()
-        } else {
-            log::warn!(
-                "populating a directory {:?} that has been removed",
-                parent_path
-            );
-            return;
-        };
-
-        match parent_entry.kind {
-            EntryKind::PendingDir | EntryKind::UnloadedDir => parent_entry.kind = EntryKind::Dir,
-            EntryKind::Dir => {}
-            _ => return,
+        self.pending_entry_count += entries.len() + 1;
+        self.pending_dirs.push(ScannedDir {
+            path: parent_path.clone(),
+            entries,
+            ignore,
+        });
+        if self.pending_entry_count >= POPULATE_BATCH_SIZE {
+            self.flush_populated_dirs();
         }
+    }
 
-        if let Some(ignore) = ignore {
-            let abs_parent_path = self.snapshot.abs_path.join(&parent_path).into();
-            self.snapshot
-                .ignores_by_parent_abs_path
-                .insert(abs_parent_path, (ignore, false));
+    /// Adds the entries of the directories scanned since the last flush to the snapshot. A
+    /// directory may be populated in the same batch as its parent, so parents are populated
+    /// first.
+    fn flush_populated_dirs(&mut self) {
+        if self.pending_dirs.is_empty() {
+            return;
         }
+        let mut dirs = mem::take(&mut self.pending_dirs);
+        self.pending_entry_count = 0;
+        dirs.sort_by(|a, b| a.path.cmp(&b.path));
 
-        let parent_entry_id = parent_entry.id;
-        self.scanned_dirs.insert(parent_entry_id);
-        let mut entries_by_path_edits = vec![Edit::Insert(parent_entry)];
+        let mut new_entries = HashMap::<Arc<Path>, Entry>::default();
         let mut entries_by_id_edits = Vec::new();
+        let mut changed_paths = Vec::new();
+        for dir in dirs {
+            if !new_entries.contains_key(&dir.path) {
+                let Some(parent_entry) = self
+                    .snapshot
+                    .entries_by_path
+                    .get(&PathKey(dir.path.clone()), &())
+                else {
+                    log::warn!(
+                        "populating a directory {:?} that has been removed",
+                        dir.path
+                    );
+                    continue;
+                };
+                new_entries.insert(dir.path.clone(), parent_entry.clone());
+            }
+            let parent_entry = new_entries.get_mut(&dir.path).unwrap();
 
-        for entry in entries {
-            entries_by_id_edits.push(Edit::Insert(PathEntry {
-                id: entry.id,
-                path: entry.path.clone(),
-                is_ignored: entry.is_ignored,
-                scan_id: self.snapshot.scan_id,
-            }));
-            entries_by_path_edits.push(Edit::Insert(entry));
+            match parent_entry.kind {
+                EntryKind::PendingDir | EntryKind::UnloadedDir => {
+                    parent_entry.kind = EntryKind::Dir
+                }
+                EntryKind::Dir => {}
+                _ => continue,
+            }
+
+            if let Some(ignore) = dir.ignore {
+                let abs_parent_path = self.snapshot.abs_path.join(&dir.path).into();
+                self.snapshot
+                    .ignores_by_parent_abs_path
+                    .insert(abs_parent_path, (ignore, false));
+            }
+
+            self.scanned_dirs.insert(parent_entry.id);
+            for entry in dir.entries {
+                entries_by_id_edits.push(Edit::Insert(PathEntry {
+                    id: entry.id,
+                    path: entry.path.clone(),
+                    is_ignored: entry.is_ignored,
+                    scan_id: self.snapshot.scan_id,
+                }));
+                new_entries.insert(entry.path.clone(), entry);
+            }
+            changed_paths.push(dir.path);
         }
 
         self.snapshot
             .entries_by_path
-            .edit(entries_by_path_edits, &());
+            .edit(new_entries.into_values().map(Edit::Insert).collect(), &());
         self.snapshot.entries_by_id.edit(entries_by_id_edits, &());
 
-        if let Err(ix) = self.changed_paths.binary_search(parent_path) {
-            self.changed_paths.insert(ix, parent_path.clone());
-        }
+        self.changed_paths.extend(changed_paths);
+        self.changed_paths.sort_unstable();
+        self.changed_paths.dedup();
 
         #[cfg(test)]
         self.snapshot.check_invariants(false);
@@ -3484,6 +3633,7 @@ impl BackgroundScanner {
         scan_requests_rx: channel::Receiver<ScanRequest>,
         path_prefixes_to_scan_rx: channel::Receiver<Arc<Path>>,
     ) -> Self {
+        let snapshot_sparse_scan_paths = snapshot.sparse_scan_paths.clone();
         Self {
             fs,
             fs_case_sensitive,
@@ -3500,6 +3650,10 @@ impl BackgroundScanner {
                 paths_to_scan: Default::default(),
                 removed_entry_ids: Default::default(),
                 changed_paths: Default::default(),
+                pending_dirs: Default::default(),
+                pending_entry_count: 0,
+                watched_dirs: snapshot_sparse_scan_paths,
+                dirs_to_watch: Default::default(),
             }),
             phase: BackgroundScannerPhase::InitialScan,
         }
@@ -3616,6 +3770,7 @@ impl BackgroundScanner {
                     if !self.process_scan_request(request, false).await {
                         return;
                     }
+                    self.watch_loaded_dirs(&mut fs_events_rx).await;
                 }
 
                 path_prefix = self.path_prefixes_to_scan_rx.recv().fuse() => {
@@ -3634,6 +3789,7 @@ impl BackgroundScanner {
                         if let Some(abs_path) = self.fs.canonicalize(&abs_path).await.log_err() {
                             self.process_events(vec![abs_path]).await;
                         }
+                        self.watch_loaded_dirs(&mut fs_events_rx).await;
                     }
                 }
 
@@ -3642,15 +3798,68 @@ impl BackgroundScanner {
                     while let Poll::Ready(Some(more_paths)) = futures::poll!(fs_events_rx.next()) {
                         paths.extend(more_paths);
                     }
+                    self.coalesce_event_storm(&mut paths, &mut fs_events_rx).await;
                     self.process_events(paths.clone()).await;
                 }
             }
         }
     }
 
+    /// Starts watching the directories of a sparse worktree that were loaded on demand.
+    async fn watch_loaded_dirs(
+        &self,
+        fs_events_rx: &mut Pin<Box<dyn Send + Stream<Item = Vec<PathBuf>>>>,
+    ) {
+        let (root_path, dirs_to_watch) = {
+            let mut state = self.state.lock();
+            (
+                state.snapshot.abs_path.clone(),
+                mem::take(&mut state.dirs_to_watch),
+            )
+        };
+        for path in dirs_to_watch {
+            log::debug!("watching directory {path:?} loaded on demand");
+            let events = self
+                .fs
+                .watch(&root_path.join(&path), FS_WATCH_LATENCY)
+                .await;
+            let previous_events = mem::replace(fs_events_rx, futures::stream::empty().boxed());
+            *fs_events_rx = select(previous_events, events).boxed();
+        }
+    }
+
+    /// Waits for a storm of FS events to settle, so that its changes are processed, and sent to
+    /// the worktree, as one batch, and a file that is deleted and recreated in the storm is seen
+    /// as changed rather than as deleted.
+    async fn coalesce_event_storm(
+        &self,
+        paths: &mut Vec<PathBuf>,
+        fs_events_rx: &mut Pin<Box<dyn Send + Stream<Item = Vec<PathBuf>>>>,
+    ) {
+        if paths.len() < EVENT_STORM_THRESHOLD {
+            return;
+        }
+        log::debug!("coalescing a storm of {} fs events", paths.len());
+        let max_delay = self.executor.timer(MAX_EVENT_STORM_DELAY).fuse();
+        futures::pin_mut!(max_delay);
+        loop {
+            select_biased! {
+                more_paths = fs_events_rx.next().fuse() => {
+                    let Some(more_paths) = more_paths else { break };
+                    paths.extend(more_paths);
+                }
+                _ = self.executor.timer(FS_WATCH_LATENCY).fuse() => break,
+                _ = max_delay => break,
+            }
+        }
+    }
+
     async fn process_scan_request(&self, mut request: ScanRequest, scanning: bool) -> bool {
         log::debug!("rescanning paths {:?}", request.relative_paths);
 
+        // Other workers' scanned directories are added first, so that the paths are reloaded
+        // from an up-to-date snapshot.
+        self.state.lock().flush_populated_dirs();
         request.relative_paths.sort_unstable();
         self.forcibly_load_paths(&request.relative_paths).await;
 
@@ -3805,9 +4014,13 @@ impl BackgroundScanner {
                 for ancestor in path.ancestors() {
                     if let Some(entry) = state.snapshot.entry_for_path(ancestor) {
                         if entry.kind == EntryKind::UnloadedDir {
+                            if !state.may_follow(entry) {
+                                break;
+                            }
                             let abs_path = root_path.join(ancestor);
                             state.enqueue_scan_dir(abs_path.into(), entry, &scan_job_tx);
                             state.paths_to_scan.insert(path.clone());
+                            state.watch_loaded_dir(ancestor);
                             break;
                         }
                     }
@@ -3818,6 +4031,7 @@ impl BackgroundScanner {
         while let Some(job) = scan_job_rx.next().await {
             self.scan_dir(&job).await.log_err();
         }
+        self.state.lock().flush_populated_dirs();
 
         mem::take(&mut self.state.lock().paths_to_scan).len() > 0
     }
@@ -3893,10 +4107,13 @@ impl BackgroundScanner {
                 }
             })
             .await;
+
+        self.state.lock().flush_populated_dirs();
     }
 
     fn send_status_update(&self, scanning: bool, barrier: Option<barrier::Sender>) -> bool {
         let mut state = self.state.lock();
+        state.flush_populated_dirs();
         if state.changed_paths.is_empty() && scanning {
             return true;
         }
@@ -3918,8 +4135,6 @@ impl BackgroundScanner {
 
     async fn scan_dir(&self, job: &ScanJob) -> Result<()> {
         let root_abs_path;
-        let mut ignore_stack;
-        let mut new_ignore;
         let root_char_bag;
         let next_entry_id;
         {
@@ -3931,83 +4146,82 @@ impl BackgroundScanner {
                 return Ok(());
             }
             log::debug!("scanning directory {:?}", job.path);
-            ignore_stack = job.ignore_stack.clone();
-            new_ignore = None;
             root_char_bag = snapshot.root_char_bag;
             next_entry_id = self.next_entry_id.clone();
             drop(state);
         }
 
-        let mut dotgit_path = None;
-        let mut root_canonical_path = None;
-        let mut new_entries: Vec<Entry> = Vec::new();
-        let mut new_jobs: Vec<Option<ScanJob>> = Vec::new();
-        let mut child_paths = self.fs.read_dir(&job.abs_path).await?;
-        while let Some(child_abs_path) = child_paths.next().await {
-            let child_abs_path: Arc<Path> = match child_abs_path {
-                Ok(child_abs_path) => child_abs_path.into(),
-                Err(error) => {
-                    log::error!("error processing entry {:?}", error);
-                    continue;
-                }
-            };
-            let child_name = child_abs_path.file_name().unwrap();
-            let child_path: Arc<Path> = job.path.join(child_name).into();
-            // If we find a .gitignore, add it to the stack of ignores used to determine which paths are ignored
-            if child_name == *GITIGNORE {
-                match build_gitignore(&child_abs_path, self.fs.as_ref()).await {
-                    Ok(ignore) => {
-                        let ignore = Arc::new(ignore);
-                        ignore_stack = ignore_stack.append(job.abs_path.clone(), ignore.clone());
-                        new_ignore = Some(ignore);
-                    }
-                    Err(error) => {
-                        log::error!(
-                            "error loading .gitignore file {:?} - {:?}",
-                            child_name,
-                            error
-                        );
-                    }
-                }
-
-                // Update ignore status of any child entries we've already processed to reflect the
-                // ignore file in the current directory. Because `.gitignore` starts with a `.`,
-                // there should rarely be too numerous. Update the ignore stack associated with any
-                // new jobs as well.
-                let mut new_jobs = new_jobs.iter_mut();
-                for entry in &mut new_entries {
-                    let entry_abs_path = root_abs_path.join(&entry.path);
-                    entry.is_ignored =
-                        ignore_stack.is_abs_path_ignored(&entry_abs_path, entry.is_dir());
-
-                    if entry.is_dir() {
-                        if let Some(job) = new_jobs.next().expect("missing scan job for entry") {
-                            job.ignore_stack = if entry.is_ignored {
-                                IgnoreStack::all()
-                            } else {
-                                ignore_stack.clone()
-                            };
-                        }
-                    }
-                }
-            }
-            // If we find a .git, we'll need to load the repository.
-            else if child_name == *DOT_GIT {
-                dotgit_path = Some(child_path.clone());
+        let mut child_abs_paths = Vec::new();
+        let mut read_dir = self.fs.read_dir(&job.abs_path).await?;
+        while let Some(child_abs_path) = read_dir.next().await {
+            match child_abs_path {
+                Ok(child_abs_path) => child_abs_paths.push(Arc::<Path>::from(child_abs_path)),
+                Err(error) => log::error!("error processing entry {:?}", error),
             }
+        }
 
-            {
-                let relative_path = job.path.join(child_name);
-                let mut state = self.state.lock();
-                if state.snapshot.is_path_excluded(relative_path.clone()) {
-                    log::debug!("skipping excluded child entry {relative_path:?}");
-                    state.remove_path(&relative_path);
-                    continue;
+        // If there's a .gitignore, add it to the stack of ignores before looking at any other
+        // entries, so that ignored subdirectories are never descended into.
+        let mut ignore_stack = job.ignore_stack.clone();
+        let mut new_ignore = None;
+        if let Some(gitignore_abs_path) = child_abs_paths
+            .iter()
+            .find(|child_abs_path| child_abs_path.file_name() == Some(*GITIGNORE))
+        {
+            match build_gitignore(gitignore_abs_path, self.fs.as_ref()).await {
+                Ok(ignore) => {
+                    let ignore = Arc::new(ignore);
+                    ignore_stack = ignore_stack.append(job.abs_path.clone(), ignore.clone());
+                    new_ignore = Some(ignore);
+                }
+                Err(error) => {
+                    log::error!(
+                        "error loading .gitignore file {:?} - {:?}",
+                        gitignore_abs_path,
+                        error
+                    );
                 }
-                drop(state);
             }
+        }
+
+        // If we find a .git, we'll need to load the repository, even though it's excluded.
+        let dotgit_path = child_abs_paths
+            .iter()
+            .any(|child_abs_path| child_abs_path.file_name() == Some(*DOT_GIT))
+            .then(|| Arc::<Path>::from(job.path.join(*DOT_GIT)));
 
-            let child_metadata = match self.fs.metadata(&child_abs_path).await {
+        let children = {
+            let mut state = self.state.lock();
+            child_abs_paths
+                .into_iter()
+                .filter_map(|child_abs_path| {
+                    let child_path: Arc<Path> = job.path.join(child_abs_path.file_name()?).into();
+                    if state.snapshot.is_path_excluded(child_path.to_path_buf()) {
+                        log::debug!("skipping excluded child entry {child_path:?}");
+                        state.remove_path(&child_path);
+                        return None;
+                    }
+                    let is_private = state.snapshot.is_path_private(&child_path);
+                    if is_private {
+                        log::debug!("detected private file: {child_path:?}");
+                    }
+                    Some((child_abs_path, child_path, is_private))
+                })
+                .collect::<Vec<_>>()
+        };
+        let children_metadata = futures::stream::iter(&children)
+            .map(|(child_abs_path, _, _)| self.fs.metadata(child_abs_path))
+            .buffered(METADATA_CONCURRENCY)
+            .collect::<Vec<_>>()
+            .await;
+
+        let mut root_canonical_path = None;
+        let mut new_entries: Vec<Entry> = Vec::new();
+        let mut new_jobs: Vec<Option<ScanJob>> = Vec::new();
+        for ((child_abs_path, child_path, is_private), child_metadata) in
+            children.into_iter().zip(children_metadata)
+        {
+            let child_metadata = match child_metadata {
                 Ok(Some(metadata)) => metadata,
                 Ok(None) => continue,
                 Err(err) => {
@@ -4061,6 +4275,7 @@ impl BackgroundScanner {
 
                 // Avoid recursing until crash in the case of a recursive symlink
                 if job.ancestor_inodes.contains(&child_entry.inode) {
+                    log::debug!("not scanning symlink cycle at {:?}", child_entry.path);
                     new_jobs.push(None);
                 } else {
                     let mut ancestor_inodes = job.ancestor_inodes.clone();
@@ -4099,16 +4314,7 @@ impl BackgroundScanner {
                 }
             }
 
-            {
-                let relative_path = job.path.join(child_name);
-                let state = self.state.lock();
-                if state.snapshot.is_path_private(&relative_path) {
-                    log::debug!("detected private file: {relative_path:?}");
-                    child_entry.is_private = true;
-                }
-                drop(state)
-            }
-
+            child_entry.is_private = is_private;
             new_entries.push(child_entry);
         }
 
@@ -4131,8 +4337,11 @@ impl BackgroundScanner {
 
         state.populate_dir(&job.path, new_entries, new_ignore);
 
-        let repository =
-            dotgit_path.and_then(|path| state.build_git_repository(path, self.fs.as_ref()));
+        // The repository's working directory has to be in the snapshot before it's built.
+        let repository = dotgit_path.and_then(|path| {
+            state.flush_populated_dirs();
+            state.build_git_repository(path, self.fs.as_ref())
+        });
 
         for mut new_job in new_jobs.into_iter().flatten() {
             if let Some(containing_repository) = &repository {
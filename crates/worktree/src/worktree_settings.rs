@@ -23,6 +23,33 @@ pub struct WorktreeSettings {
     /// Treat the files matching these globs as `.env` files.
     /// Default: [ "**/.env*" ]
     pub private_files: Option<Vec<String>>,
+
+    /// The directories of a gigantic monorepo, relative to its root, to scan and watch. When
+    /// set, other directories are only loaded when they're expanded in the project panel or
+    /// searched, which keeps the memory used, and the number of directories watched, bounded.
+    /// Changes outside of the watched directories, such as new top-level directories, are
+    /// noticed once their parent is expanded again.
+    ///
+    /// Default: [] (the whole worktree is scanned)
+    pub sparse_scan_paths: Option<Vec<String>>,
+
+    /// How symlinked directories are scanned.
+    ///
+    /// Default: lazily
+    pub follow_symlinks: Option<FollowSymlinks>,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FollowSymlinks {
+    /// Scan the symlinked directories inside the worktree, and those outside of it once
+    /// they're expanded.
+    #[default]
+    Lazily,
+    /// Scan every symlinked directory, including those outside the worktree.
+    Always,
+    /// Never scan symlinked directories, so they can't be expanded.
+    Never,
 }
 
 impl Settings for WorktreeSettings {
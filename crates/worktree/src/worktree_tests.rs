@@ -1,6 +1,6 @@
 use crate::{
-    worktree_settings::WorktreeSettings, Entry, EntryKind, Event, PathChange, Snapshot, Worktree,
-    WorktreeModelHandle,
+    worktree_settings::{FollowSymlinks, WorktreeSettings},
+    Entry, EntryKind, Event, PathChange, Snapshot, Worktree, WorktreeModelHandle,
 };
 use anyhow::Result;
 use client::Client;
@@ -80,6 +80,60 @@ async fn test_traversal(cx: &mut TestAppContext) {
     })
 }
 
+#[gpui::test]
+async fn test_scanning_many_directories(cx: &mut TestAppContext) {
+    init_test(cx);
+    let fs = FakeFs::new(cx.background_executor.clone());
+
+    // Enough entries that they're added to the snapshot in several batches.
+    let mut root = serde_json::Map::new();
+    for dir_ix in 0..20 {
+        let mut dir = serde_json::Map::new();
+        for subdir_ix in 0..10 {
+            let files = (0..10)
+                .map(|file_ix| (format!("file-{file_ix}.txt"), json!("")))
+                .collect::<serde_json::Map<_, _>>();
+            dir.insert(format!("subdir-{subdir_ix}"), files.into());
+        }
+        root.insert(format!("dir-{dir_ix}"), dir.into());
+    }
+    root.insert("target".into(), json!({ "debug": { "build": "" } }));
+    root.insert(".gitignore".into(), json!("target\n"));
+    fs.insert_tree("/root", root.into()).await;
+
+    let tree = Worktree::local(
+        build_client(cx),
+        Path::new("/root"),
+        true,
+        fs,
+        Default::default(),
+        &mut cx.to_async(),
+    )
+    .await
+    .unwrap();
+    cx.read(|cx| tree.read(cx).as_local().unwrap().scan_complete())
+        .await;
+
+    tree.read_with(cx, |tree, _| {
+        tree.as_local().unwrap().snapshot().check_invariants(true);
+        assert_eq!(tree.file_count(), 20 * 10 * 10 + 1);
+        assert_eq!(
+            tree.entry_for_path("dir-19/subdir-9").unwrap().kind,
+            EntryKind::Dir
+        );
+        assert!(tree
+            .entry_for_path("dir-19/subdir-9/file-9.txt")
+            .unwrap()
+            .is_file());
+
+        // The ignored directory isn't descended into.
+        let target = tree.entry_for_path("target").unwrap();
+        assert!(target.is_ignored);
+        assert_eq!(target.kind, EntryKind::UnloadedDir);
+        assert!(tree.entry_for_path("target/debug").is_none());
+    });
+}
+
 #[gpui::test]
 async fn test_descendent_entries(cx: &mut TestAppContext) {
     init_test(cx);
@@ -450,6 +504,216 @@ async fn test_symlinks_pointing_outside(cx: &mut TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_follow_symlinks_setting(cx: &mut TestAppContext) {
+    init_test(cx);
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree(
+        "/root",
+        json!({
+            "dir1": {
+                "deps": {},
+                "src": {
+                    "a.rs": "",
+                },
+            },
+            "dir2": {
+                "src": {
+                    "b.rs": "",
+                }
+            },
+        }),
+    )
+    .await;
+    fs.create_symlink("/root/dir1/deps/dep-dir2".as_ref(), "../../dir2".into())
+        .await
+        .unwrap();
+    fs.create_symlink("/root/dir1/deps/src".as_ref(), "../src".into())
+        .await
+        .unwrap();
+
+    // Symlinked directories outside of the worktree are scanned too.
+    cx.update(|cx| {
+        cx.update_global::<SettingsStore, _>(|store, cx| {
+            store.update_user_settings::<WorktreeSettings>(cx, |settings| {
+                settings.follow_symlinks = Some(FollowSymlinks::Always);
+            });
+        });
+    });
+    let tree = Worktree::local(
+        build_client(cx),
+        Path::new("/root/dir1"),
+        true,
+        fs.clone(),
+        Default::default(),
+        &mut cx.to_async(),
+    )
+    .await
+    .unwrap();
+    cx.read(|cx| tree.read(cx).as_local().unwrap().scan_complete())
+        .await;
+    tree.read_with(cx, |tree, _| {
+        assert_eq!(
+            tree.entries(true)
+                .map(|entry| (entry.path.as_ref(), entry.is_external))
+                .collect::<Vec<_>>(),
+            vec![
+                (Path::new(""), false),
+                (Path::new("deps"), false),
+                (Path::new("deps/dep-dir2"), true),
+                (Path::new("deps/dep-dir2/src"), true),
+                (Path::new("deps/dep-dir2/src/b.rs"), true),
+                (Path::new("deps/src"), false),
+                (Path::new("deps/src/a.rs"), false),
+                (Path::new("src"), false),
+                (Path::new("src/a.rs"), false),
+            ]
+        );
+    });
+
+    // No symlinked directories are scanned, even when they're expanded.
+    cx.update(|cx| {
+        cx.update_global::<SettingsStore, _>(|store, cx| {
+            store.update_user_settings::<WorktreeSettings>(cx, |settings| {
+                settings.follow_symlinks = Some(FollowSymlinks::Never);
+            });
+        });
+    });
+    let tree = Worktree::local(
+        build_client(cx),
+        Path::new("/root/dir1"),
+        true,
+        fs.clone(),
+        Default::default(),
+        &mut cx.to_async(),
+    )
+    .await
+    .unwrap();
+    cx.read(|cx| tree.read(cx).as_local().unwrap().scan_complete())
+        .await;
+    tree.read_with(cx, |tree, _| {
+        tree.as_local()
+            .unwrap()
+            .refresh_entries_for_paths(vec![Path::new("deps/src").into()])
+    })
+    .recv()
+    .await;
+    tree.read_with(cx, |tree, _| {
+        assert_eq!(
+            tree.entries(true)
+                .map(|entry| entry.path.as_ref())
+                .collect::<Vec<_>>(),
+            vec![
+                Path::new(""),
+                Path::new("deps"),
+                Path::new("deps/dep-dir2"),
+                Path::new("deps/src"),
+                Path::new("src"),
+                Path::new("src/a.rs"),
+            ]
+        );
+        assert_eq!(
+            tree.entry_for_path("deps/src").unwrap().kind,
+            EntryKind::UnloadedDir
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_sparse_scan_paths(cx: &mut TestAppContext) {
+    init_test(cx);
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree(
+        "/root",
+        json!({
+            "libs": {
+                "common": {
+                    "a.rs": "",
+                },
+                "other": {
+                    "b.rs": "",
+                },
+            },
+            "services": {
+                "api": {
+                    "c.rs": "",
+                },
+                "d.rs": "",
+            },
+            "README.md": "",
+        }),
+    )
+    .await;
+    cx.update(|cx| {
+        cx.update_global::<SettingsStore, _>(|store, cx| {
+            store.update_user_settings::<WorktreeSettings>(cx, |settings| {
+                settings.sparse_scan_paths = Some(vec!["libs/common".to_string()]);
+            });
+        });
+    });
+
+    let tree = Worktree::local(
+        build_client(cx),
+        Path::new("/root"),
+        true,
+        fs.clone(),
+        Default::default(),
+        &mut cx.to_async(),
+    )
+    .await
+    .unwrap();
+    cx.read(|cx| tree.read(cx).as_local().unwrap().scan_complete())
+        .await;
+
+    // Only the sparse scan path, and the directories containing it, are scanned.
+    tree.read_with(cx, |tree, _| {
+        assert_eq!(
+            tree.entries(true)
+                .map(|entry| (entry.path.as_ref(), entry.kind.is_unloaded()))
+                .collect::<Vec<_>>(),
+            vec![
+                (Path::new(""), false),
+                (Path::new("README.md"), false),
+                (Path::new("libs"), false),
+                (Path::new("libs/common"), false),
+                (Path::new("libs/common/a.rs"), false),
+                (Path::new("libs/other"), true),
+                (Path::new("services"), true),
+            ]
+        );
+    });
+
+    // Other directories are loaded when they're expanded, and watched from then on.
+    tree.read_with(cx, |tree, _| {
+        tree.as_local()
+            .unwrap()
+            .refresh_entries_for_paths(vec![Path::new("services").into()])
+    })
+    .recv()
+    .await;
+    fs.insert_file("/root/services/e.rs", Vec::new()).await;
+    cx.executor().run_until_parked();
+    tree.read_with(cx, |tree, _| {
+        assert_eq!(
+            tree.entries(true)
+                .map(|entry| (entry.path.as_ref(), entry.kind.is_unloaded()))
+                .collect::<Vec<_>>(),
+            vec![
+                (Path::new(""), false),
+                (Path::new("README.md"), false),
+                (Path::new("libs"), false),
+                (Path::new("libs/common"), false),
+                (Path::new("libs/common/a.rs"), false),
+                (Path::new("libs/other"), true),
+                (Path::new("services"), false),
+                (Path::new("services/api"), true),
+                (Path::new("services/d.rs"), false),
+                (Path::new("services/e.rs"), false),
+            ]
+        );
+    });
+}
+
 #[cfg(target_os = "macos")]
 #[gpui::test]
 async fn test_renaming_case_only(cx: &mut TestAppContext) {
@@ -7,12 +7,11 @@ mod reliability;
 mod zed;
 
 use anyhow::{anyhow, Context as _, Result};
-use clap::{command, Parser};
-use cli::FORCE_CLI_MODE_ENV_VAR_NAME;
+use clap::{command, Parser, Subcommand};
+use cli::{FORCE_CLI_MODE_ENV_VAR_NAME, SOFTWARE_RENDERING_ENV_VAR_NAME};
 use client::{parse_zed_link, Client, DevServerToken, UserStore};
 use collab_ui::channel_view::ChannelView;
 use db::kvp::KEY_VALUE_STORE;
-use editor::Editor;
 use env_logger::Builder;
 use fs::RealFs;
 use futures::{future, StreamExt};
@@ -25,6 +24,7 @@ use log::LevelFilter;
 use assets::Assets;
 use node_runtime::RealNodeRuntime;
 use parking_lot::Mutex;
+use project::{BufferJournal, DiagnosticsExportFormat};
 use release_channel::AppCommitSha;
 use settings::{handle_settings_file_changes, watch_config_file, Settings, SettingsStore};
 use simplelog::ConfigBuilder;
@@ -33,7 +33,7 @@ use std::{
     env,
     fs::OpenOptions,
     io::{IsTerminal, Write},
-    path::Path,
+    path::{Path, PathBuf},
     process,
     sync::Arc,
 };
@@ -45,10 +45,11 @@ use util::{
 };
 use uuid::Uuid;
 use welcome::{show_welcome_view, BaseKeymap, FIRST_OPEN};
-use workspace::{AppState, WorkspaceSettings, WorkspaceStore};
+use workspace::{ActivityTracker, AppState, WorkspaceSettings, WorkspaceStore};
 use zed::{
-    app_menus, build_window_options, handle_cli_connection, handle_keymap_file_changes,
-    initialize_workspace, open_paths_with_positions, OpenListener, OpenRequest,
+    app_menus, buffer_recovery, build_window_options, handle_cli_connection,
+    handle_keymap_file_changes, initialize_workspace, open_paths_with_positions, OpenListener,
+    OpenRequest,
 };
 
 use crate::zed::inline_completion_registry;
@@ -59,7 +60,14 @@ static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
 
 fn fail_to_launch(e: anyhow::Error) {
     App::new().run(move |cx| {
-        let window = cx.open_window(gpui::WindowOptions::default(), |cx| cx.new_view(|_| gpui::Empty));
+        let window = match cx.open_window(gpui::WindowOptions::default(), |cx| cx.new_view(|_| gpui::Empty)) {
+            Ok(window) => window,
+            Err(window_error) => {
+                eprintln!("Zed failed to launch: {e:?}\n\nIt couldn't show this error either: {window_error:?}");
+                cx.quit();
+                return;
+            }
+        };
         window.update(cx, |_, cx| {
             let response = cx.prompt(gpui::PromptLevel::Critical, "Zed failed to launch", Some(&format!("{}\n\nFor help resolving this, please open an issue on https://github.com/zed-industries/zed", e)), &["Exit"]);
 
@@ -110,6 +118,71 @@ fn init_headless(
     )
 }
 
+/// Runs a headless command against a project without opening any windows, then exits with its
+/// status.
+fn run_headless_command(command: HeadlessCommand, app_state: Arc<AppState>, cx: &mut AppContext) {
+    let (script, project_paths) = match command {
+        HeadlessCommand::RunScript { script, paths } => (headless::Script::load(&script), paths),
+        HeadlessCommand::ExportDiagnostics {
+            output,
+            format,
+            open,
+            timeout_secs,
+            paths,
+        } => {
+            let mut steps = Vec::new();
+            if !open.is_empty() {
+                steps.push(headless::ScriptStep::Open { paths: open });
+            }
+            steps.push(headless::ScriptStep::ExportDiagnostics {
+                output,
+                format,
+                timeout_secs,
+            });
+            (Ok(headless::Script { steps }), paths)
+        }
+    };
+    let project_paths = if project_paths.is_empty() {
+        vec![PathBuf::from(".")]
+    } else {
+        project_paths
+    }
+    .into_iter()
+    .map(|path| {
+        std::fs::canonicalize(&path).with_context(|| format!("opening project path {path:?}"))
+    })
+    .collect::<Result<Vec<_>>>();
+    let task = match (script, project_paths) {
+        (Ok(script), Ok(project_paths)) => headless::run_script(
+            script,
+            project_paths,
+            app_state.client.clone(),
+            headless::AppState {
+                languages: app_state.languages.clone(),
+                user_store: app_state.user_store.clone(),
+                fs: app_state.fs.clone(),
+                node_runtime: app_state.node_runtime.clone(),
+            },
+            cx,
+        ),
+        (Err(error), _) | (_, Err(error)) => Task::ready(Err(error)),
+    };
+
+    cx.spawn(|cx| async move {
+        let exit_code = match task.await {
+            Ok(outcome) => outcome.exit_code(),
+            Err(error) => {
+                eprintln!("zed: {error:?}");
+                2
+            }
+        };
+        // Give language servers a chance to shut down before exiting.
+        cx.update(|cx| cx.shutdown()).log_err();
+        process::exit(exit_code);
+    })
+    .detach();
+}
+
 fn init_ui(app_state: Arc<AppState>, cx: &mut AppContext) -> Result<()> {
     match cx.try_global::<AppMode>() {
         Some(AppMode::Headless(_)) => {
@@ -136,13 +209,22 @@ fn init_ui(app_state: Arc<AppState>, cx: &mut AppContext) -> Result<()> {
     command_palette::init(cx);
     editor::init(cx);
     image_viewer::init(cx);
+    wasm_viewer::init(cx);
+    jupyter::init(app_state.fs.clone(), cx);
+    notebook::init(app_state.fs.clone(), cx);
     diagnostics::init(cx);
+    coverage::init(app_state.fs.clone(), cx);
+    benchmarks::init(cx);
+    flame_graph::init(cx);
 
     audio::init(Assets, cx);
     workspace::init(app_state.clone(), cx);
+    ActivityTracker::init(cx);
     recent_projects::init(cx);
 
     go_to_line::init(cx);
+    cheat_sheet::init(cx);
+    line_filter::init(cx);
     file_finder::init(cx);
     tab_switcher::init(cx);
     outline::init(cx);
@@ -195,13 +277,25 @@ fn init_ui(app_state: Arc<AppState>, cx: &mut AppContext) -> Result<()> {
                     .ok();
             }
             languages.set_theme(cx.theme().clone());
-            let new_host = &client::ClientSettings::get_global(cx).server_url;
+            let client_settings = client::ClientSettings::get_global(cx);
+            let new_host = &client_settings.server_url;
             if &http.base_url() != new_host {
                 http.set_base_url(new_host);
                 if client.status().borrow().is_connected() {
                     client.reconnect(&cx.to_async());
                 }
             }
+            let offline = client_settings.offline;
+            if http.is_offline() != offline {
+                http.set_offline(offline);
+                if offline {
+                    client.disconnect(&cx.to_async());
+                } else {
+                    let client = client.clone();
+                    cx.spawn(|cx| async move { authenticate(client, &cx).await })
+                        .detach_and_log_err(cx);
+                }
+            }
         }
     })
     .detach();
@@ -242,6 +336,12 @@ fn main() {
     menu::init();
     zed_actions::init();
 
+    let args = Args::parse();
+    if args.command.is_some() != args.headless {
+        eprintln!("zed: --headless must be used with a command, such as `zed --headless run-script <SCRIPT>`");
+        process::exit(2);
+    }
+
     if let Err(e) = init_paths() {
         fail_to_launch(e);
         return;
@@ -250,6 +350,16 @@ fn main() {
     init_logger();
 
     log::info!("========== starting zed ==========");
+    // The variable is only meant for this process, not the terminals and tasks it runs. Both
+    // it and the renderer are changed before any threads are spawned.
+    let software_rendering =
+        args.software_rendering || env::var_os(SOFTWARE_RENDERING_ENV_VAR_NAME).is_some();
+    env::remove_var(SOFTWARE_RENDERING_ENV_VAR_NAME);
+    if software_rendering {
+        gpui::use_software_rendering()
+            .context("failed to enable software rendering")
+            .log_err();
+    }
     let app = App::new().with_assets(Assets);
 
     let (installation_id, existing_installation_id_found) = app
@@ -264,15 +374,16 @@ fn main() {
     let listener = Arc::new(listener);
     let open_listener = listener.clone();
 
+    // Headless commands run alongside any other instance.
     #[cfg(target_os = "linux")]
-    {
+    if !args.headless {
         if crate::zed::listen_for_cli_connections(listener.clone()).is_err() {
             println!("zed is already running");
             return;
         }
     }
     #[cfg(not(target_os = "linux"))]
-    {
+    if !args.headless {
         use zed::only_instance::*;
         if ensure_only_instance() != IsOnlyInstance::Yes {
             println!("zed is already running");
@@ -388,7 +499,11 @@ fn main() {
 
         reliability::init(client.http_client(), installation_id, cx);
 
-        let args = Args::parse();
+        if let Some(command) = args.command {
+            run_headless_command(command, app_state, cx);
+            return;
+        }
+
         let urls: Vec<_> = args
             .paths_or_urls
             .iter()
@@ -423,9 +538,13 @@ fn main() {
                     .detach();
                 } else {
                     init_ui(app_state.clone(), cx).unwrap();
+                    BufferJournal::init(paths::JOURNAL_DIR.clone(), cx);
                     cx.spawn({
                         let app_state = app_state.clone();
-                        |cx| async move { restore_or_create_workspace(app_state, cx).await }
+                        |cx| async move {
+                            restore_or_create_workspace(app_state, cx.clone()).await;
+                            buffer_recovery::recover_unsaved_buffers(cx).await.log_err();
+                        }
                     })
                     .detach();
                 }
@@ -592,7 +711,7 @@ async fn restore_or_create_workspace(app_state: Arc<AppState>, cx: AsyncAppConte
         } else {
             cx.update(|cx| {
                 workspace::open_new(app_state, cx, |workspace, cx| {
-                    Editor::new_file(workspace, &Default::default(), cx)
+                    welcome::open_welcome_page(workspace, cx)
                 })
                 .detach();
             })?;
@@ -806,6 +925,49 @@ struct Args {
     /// Instructs zed to run as a dev server on this machine. (not implemented)
     #[arg(long)]
     dev_server_token: Option<String>,
+
+    /// Runs zed without opening any windows. Must be used with a command.
+    #[arg(long)]
+    headless: bool,
+
+    /// Renders with a software rasterizer instead of the GPU, for machines whose GPU drivers are
+    /// broken, or that have none, as in VMs and remote X sessions. Only supported on Linux.
+    #[arg(long)]
+    software_rendering: bool,
+
+    #[command(subcommand)]
+    command: Option<HeadlessCommand>,
+}
+
+#[derive(Subcommand, Debug)]
+enum HeadlessCommand {
+    /// Runs a script of batch edits, such as a codemod, against a project. Exits with status 0
+    /// when it succeeds, 1 when its diagnostics checks fail, and 2 when it can't be run.
+    RunScript {
+        /// The JSON script to run.
+        script: PathBuf,
+        /// The directories to load into the project. Defaults to the current directory.
+        paths: Vec<PathBuf>,
+    },
+    /// Waits for language servers to report diagnostics for a project, then writes them all to
+    /// a file, so they can be used by code review tools.
+    ExportDiagnostics {
+        /// The file to write the diagnostics to.
+        #[arg(long)]
+        output: PathBuf,
+        /// `sarif` or `json`. Defaults to SARIF for `.sarif` files and JSON otherwise.
+        #[arg(long)]
+        format: Option<DiagnosticsExportFormat>,
+        /// Globs of files to open first. Language servers only start once a file of their
+        /// language is open.
+        #[arg(long)]
+        open: Vec<String>,
+        /// How long to wait for language servers to finish reporting diagnostics.
+        #[arg(long, default_value_t = 60)]
+        timeout_secs: u64,
+        /// The directories to load into the project. Defaults to the current directory.
+        paths: Vec<PathBuf>,
+    },
 }
 
 fn parse_url_arg(arg: &str, cx: &AppContext) -> Result<String> {
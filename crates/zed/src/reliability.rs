@@ -181,7 +181,7 @@ pub fn monitor_main_thread_hangs(
 
     let foreground_executor = cx.foreground_executor();
     let background_executor = cx.background_executor();
-    let telemetry_settings = *client::TelemetrySettings::get_global(cx);
+    let telemetry_settings = client::TelemetrySettings::get_global(cx).clone();
     let metadata = cx.app_metadata();
 
     // Initialize SIGUSR2 handler to send a backrace to a channel.
@@ -351,13 +351,14 @@ fn upload_panics_and_crashes(
     installation_id: Option<String>,
     cx: &mut AppContext,
 ) {
-    let telemetry_settings = *client::TelemetrySettings::get_global(cx);
+    let telemetry_settings = client::TelemetrySettings::get_global(cx).clone();
     cx.background_executor()
         .spawn(async move {
-            let most_recent_panic = upload_previous_panics(http.clone(), telemetry_settings)
-                .await
-                .log_err()
-                .flatten();
+            let most_recent_panic =
+                upload_previous_panics(http.clone(), telemetry_settings.clone())
+                    .await
+                    .log_err()
+                    .flatten();
             upload_previous_crashes(http, most_recent_panic, installation_id, telemetry_settings)
                 .await
                 .log_err()
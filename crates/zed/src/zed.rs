@@ -1,8 +1,10 @@
 mod app_menus;
+pub mod buffer_recovery;
 pub mod inline_completion_registry;
 #[cfg(not(target_os = "linux"))]
 pub(crate) mod only_instance;
 mod open_listener;
+pub mod telemetry_inspector;
 
 pub use app_menus::*;
 use breadcrumbs::Breadcrumbs;
@@ -40,14 +42,15 @@ use util::{
     ResultExt,
 };
 use uuid::Uuid;
-use vim::VimModeSetting;
+use vim::{VimMappingsSetting, VimModeSetting};
 use welcome::BaseKeymap;
 use workspace::{
     create_and_open_local_file, notifications::simple_message_notification::MessageNotification,
-    open_new, AppState, NewFile, NewWindow, OpenLog, Toast, Workspace, WorkspaceSettings,
+    open_new, AppState, NewFile, NewWindow, OpenLog, StatusItemPosition, Toast, Workspace,
+    WorkspaceSettings,
 };
 use workspace::{notifications::DetachAndPromptErr, Pane};
-use zed_actions::{OpenBrowser, OpenSettings, OpenZedUrl, Quit};
+use zed_actions::{OpenBrowser, OpenSettings, OpenZedUrl, Quit, ToggleOfflineMode};
 
 actions!(
     zed,
@@ -66,6 +69,7 @@ actions!(
         OpenLocalSettings,
         OpenLocalTasks,
         OpenTasks,
+        OpenTelemetryInspector,
         OpenTelemetryLog,
         ResetBufferFontSize,
         ResetDatabase,
@@ -141,12 +145,48 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
         let cursor_position =
             cx.new_view(|_| go_to_line::cursor_position::CursorPosition::new(workspace));
         workspace.status_bar().update(cx, |status_bar, cx| {
-            status_bar.add_left_item(diagnostic_summary, cx);
-            status_bar.add_left_item(activity_indicator, cx);
-            status_bar.add_right_item(inline_completion_button, cx);
-            status_bar.add_right_item(active_buffer_language, cx);
-            status_bar.add_right_item(vim_mode_indicator, cx);
-            status_bar.add_right_item(cursor_position, cx);
+            status_bar.add_item(
+                "diagnostics",
+                StatusItemPosition::Left,
+                0,
+                diagnostic_summary,
+                cx,
+            );
+            status_bar.add_item(
+                "activity",
+                StatusItemPosition::Left,
+                0,
+                activity_indicator,
+                cx,
+            );
+            status_bar.add_item(
+                "inline_completions",
+                StatusItemPosition::Right,
+                0,
+                inline_completion_button,
+                cx,
+            );
+            status_bar.add_item(
+                "language",
+                StatusItemPosition::Right,
+                0,
+                active_buffer_language,
+                cx,
+            );
+            status_bar.add_item(
+                "vim_mode",
+                StatusItemPosition::Right,
+                0,
+                vim_mode_indicator,
+                cx,
+            );
+            status_bar.add_item(
+                "cursor_position",
+                StatusItemPosition::Right,
+                0,
+                cursor_position,
+                cx,
+            );
         });
 
         auto_update::notify_of_any_new_update(cx);
@@ -325,6 +365,19 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
                     cx,
                 );
             })
+            .register_action(
+                |workspace: &mut Workspace, _: &OpenTelemetryInspector, cx| {
+                    telemetry_inspector::open(workspace, cx);
+                },
+            )
+            .register_action(|workspace, _: &ToggleOfflineMode, cx| {
+                let offline = !client::ClientSettings::get_global(cx).offline;
+                settings::update_settings_file::<client::ClientSettings>(
+                    workspace.app_state().fs.clone(),
+                    cx,
+                    move |settings| settings.offline = Some(offline),
+                );
+            })
             .register_action(
                 move |workspace: &mut Workspace,
                       _: &OpenTelemetryLog,
@@ -424,7 +477,7 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
                 move |_, _: &NewWindow, cx| {
                     if let Some(app_state) = app_state.upgrade() {
                         open_new(app_state, cx, |workspace, cx| {
-                            Editor::new_file(workspace, &Default::default(), cx)
+                            welcome::open_welcome_page(workspace, cx)
                         })
                         .detach();
                     }
@@ -626,17 +679,24 @@ pub fn handle_keymap_file_changes(
 ) {
     BaseKeymap::register(cx);
     VimModeSetting::register(cx);
+    VimMappingsSetting::register(cx);
 
     let (base_keymap_tx, mut base_keymap_rx) = mpsc::unbounded();
     let mut old_base_keymap = *BaseKeymap::get_global(cx);
     let mut old_vim_enabled = VimModeSetting::get_global(cx).0;
+    let mut old_vim_mappings = VimMappingsSetting::get_global(cx).clone();
     cx.observe_global::<SettingsStore>(move |cx| {
         let new_base_keymap = *BaseKeymap::get_global(cx);
         let new_vim_enabled = VimModeSetting::get_global(cx).0;
+        let new_vim_mappings = VimMappingsSetting::get_global(cx);
 
-        if new_base_keymap != old_base_keymap || new_vim_enabled != old_vim_enabled {
+        if new_base_keymap != old_base_keymap
+            || new_vim_enabled != old_vim_enabled
+            || *new_vim_mappings != old_vim_mappings
+        {
             old_base_keymap = new_base_keymap;
             old_vim_enabled = new_vim_enabled;
+            old_vim_mappings = new_vim_mappings.clone();
             base_keymap_tx.unbounded_send(()).unwrap();
         }
     })
@@ -686,6 +746,10 @@ pub fn load_default_keymap(cx: &mut AppContext) {
     if let Some(asset_path) = base_keymap.asset_path() {
         KeymapFile::load_asset(asset_path, cx).unwrap();
     }
+
+    if VimModeSetting::get_global(cx).0 {
+        cx.bind_keys(vim::mapping_bindings(cx));
+    }
 }
 
 fn open_local_settings_file(
@@ -127,6 +127,7 @@ pub fn app_menus() -> Vec<Menu<'static>> {
                 MenuItem::action("Terminal Panel", terminal_panel::ToggleFocus),
                 MenuItem::separator(),
                 MenuItem::action("Diagnostics", diagnostics::Deploy),
+                MenuItem::action("Test Coverage", coverage::OpenSummary),
                 MenuItem::separator(),
             ],
         },
@@ -139,6 +140,10 @@ pub fn app_menus() -> Vec<Menu<'static>> {
                 MenuItem::action("Command Palette...", command_palette::Toggle),
                 MenuItem::separator(),
                 MenuItem::action("Go to File...", file_finder::Toggle),
+                MenuItem::action(
+                    "Go to Recently Edited File...",
+                    file_finder::ToggleRecentlyEdited,
+                ),
                 // MenuItem::action("Go to Symbol in Project", project_symbols::Toggle),
                 MenuItem::action("Go to Symbol in Editor...", outline::Toggle),
                 MenuItem::action("Go to Line/Column...", go_to_line::Toggle),
@@ -163,6 +168,7 @@ pub fn app_menus() -> Vec<Menu<'static>> {
             name: "Help",
             items: vec![
                 MenuItem::action("View Telemetry", super::OpenTelemetryLog),
+                MenuItem::action("Inspect Telemetry", super::OpenTelemetryInspector),
                 MenuItem::action("View Dependency Licenses", super::OpenLicenses),
                 MenuItem::action("Show Welcome", workspace::Welcome),
                 MenuItem::action("Give Feedback...", feedback::GiveFeedback),
@@ -0,0 +1,113 @@
+use anyhow::Result;
+use gpui::{AsyncAppContext, PromptLevel, WindowHandle};
+use project::{BufferJournal, RecoveredBuffer};
+use util::ResultExt;
+use workspace::Workspace;
+
+/// How many lines of each file's diff are shown when offering to recover it.
+const MAX_DIFF_LINES: usize = 20;
+/// How many files' diffs are shown when offering to recover them.
+const MAX_DIFFS: usize = 5;
+
+/// Offers to restore the unsaved edits that a previous session journaled but never saved or
+/// discarded, because it crashed or lost power. Edits that are recovered are applied to their
+/// files' buffers, which are left unsaved for the user to review.
+pub async fn recover_unsaved_buffers(mut cx: AsyncAppContext) -> Result<()> {
+    let Some(journal) = cx.update(BufferJournal::global)? else {
+        return Ok(());
+    };
+    let recovered = journal
+        .update(&mut cx, |journal, cx| journal.recoverable(cx))?
+        .await;
+    if recovered.is_empty() {
+        return Ok(());
+    }
+    let Some(workspace) = cx.update(|cx| {
+        cx.windows()
+            .into_iter()
+            .find_map(|window| window.downcast::<Workspace>())
+    })?
+    else {
+        return Ok(());
+    };
+
+    let message = if recovered.len() == 1 {
+        "Zed didn't quit properly. Recover the unsaved changes to 1 file?".to_string()
+    } else {
+        format!(
+            "Zed didn't quit properly. Recover the unsaved changes to {} files?",
+            recovered.len()
+        )
+    };
+    let detail = recovery_detail(&recovered);
+    let answer = workspace
+        .update(&mut cx, |_, cx| {
+            cx.prompt(
+                PromptLevel::Warning,
+                &message,
+                Some(&detail),
+                &["Recover", "Discard", "Later"],
+            )
+        })?
+        .await?;
+
+    match answer {
+        0 => {
+            for buffer in &recovered {
+                restore_buffer(workspace, buffer, &mut cx).await.log_err();
+            }
+            journal.update(&mut cx, |journal, _| journal.discard(&recovered))?;
+        }
+        1 => journal.update(&mut cx, |journal, _| journal.discard(&recovered))?,
+        // The journals are kept, so recovering them is offered again next time.
+        _ => {}
+    }
+    Ok(())
+}
+
+async fn restore_buffer(
+    workspace: WindowHandle<Workspace>,
+    recovered: &RecoveredBuffer,
+    cx: &mut AsyncAppContext,
+) -> Result<()> {
+    workspace
+        .update(cx, |workspace, cx| {
+            workspace.open_abs_path(recovered.abs_path.clone(), true, cx)
+        })?
+        .await?;
+    let buffer = workspace
+        .update(cx, |workspace, cx| {
+            workspace.project().update(cx, |project, cx| {
+                project.open_local_buffer(&recovered.abs_path, cx)
+            })
+        })?
+        .await?;
+    let diff = buffer
+        .update(cx, |buffer, cx| {
+            buffer.diff(recovered.recovered_text.clone(), cx)
+        })?
+        .await;
+    buffer.update(cx, |buffer, cx| buffer.apply_diff(diff, cx))?;
+    Ok(())
+}
+
+/// The diffs of the recovered files, shortened to fit in a prompt.
+fn recovery_detail(recovered: &[RecoveredBuffer]) -> String {
+    let mut detail = String::new();
+    for buffer in recovered.iter().take(MAX_DIFFS) {
+        let diff = buffer.diff();
+        let line_count = diff.lines().count();
+        for line in diff.lines().take(MAX_DIFF_LINES) {
+            detail.push_str(line);
+            detail.push('\n');
+        }
+        if line_count > MAX_DIFF_LINES {
+            detail.push_str(&format!("… {} more lines\n", line_count - MAX_DIFF_LINES));
+        }
+        detail.push('\n');
+    }
+    if recovered.len() > MAX_DIFFS {
+        detail.push_str(&format!("… and {} more files", recovered.len() - MAX_DIFFS));
+    }
+    detail.trim_end().to_string()
+}
@@ -5,7 +5,7 @@ use collections::HashMap;
 use copilot::{Copilot, CopilotCompletionProvider};
 use editor::{Editor, EditorMode};
 use gpui::{AnyWindowHandle, AppContext, Context, ViewContext, WeakView};
-use language::language_settings::all_language_settings;
+use language::language_settings::{all_language_settings, InlineCompletionProvider};
 use settings::SettingsStore;
 use supermaven::{Supermaven, SupermavenCompletionProvider};
 
@@ -33,29 +33,29 @@ pub fn init(telemetry: Arc<Telemetry>, cx: &mut AppContext) {
             editors
                 .borrow_mut()
                 .insert(editor_handle, cx.window_handle());
-            let provider = all_language_settings(None, cx).inline_completions.provider;
-            assign_inline_completion_provider(editor, provider, &telemetry, cx);
+            let providers = enabled_inline_completion_providers(cx);
+            assign_inline_completion_providers(editor, &providers, &telemetry, cx);
         }
     })
     .detach();
 
-    let mut provider = all_language_settings(None, cx).inline_completions.provider;
+    let mut providers = enabled_inline_completion_providers(cx);
     for (editor, window) in editors.borrow().iter() {
         _ = window.update(cx, |_window, cx| {
             _ = editor.update(cx, |editor, cx| {
-                assign_inline_completion_provider(editor, provider, &telemetry, cx);
+                assign_inline_completion_providers(editor, &providers, &telemetry, cx);
             })
         });
     }
 
     cx.observe_global::<SettingsStore>(move |cx| {
-        let new_provider = all_language_settings(None, cx).inline_completions.provider;
-        if new_provider != provider {
-            provider = new_provider;
+        let new_providers = enabled_inline_completion_providers(cx);
+        if new_providers != providers {
+            providers = new_providers;
             for (editor, window) in editors.borrow().iter() {
                 _ = window.update(cx, |_window, cx| {
                     _ = editor.update(cx, |editor, cx| {
-                        assign_inline_completion_provider(editor, provider, &telemetry, cx);
+                        assign_inline_completion_providers(editor, &providers, &telemetry, cx);
                     })
                 });
             }
@@ -93,35 +93,55 @@ fn register_backward_compatible_actions(editor: &mut Editor, cx: &mut ViewContex
         ));
 }
 
-fn assign_inline_completion_provider(
+/// The inline completion providers enabled in the settings.
+fn enabled_inline_completion_providers(cx: &AppContext) -> Vec<InlineCompletionProvider> {
+    let settings = &all_language_settings(None, cx).inline_completions;
+    [
+        InlineCompletionProvider::Copilot,
+        InlineCompletionProvider::Supermaven,
+    ]
+    .into_iter()
+    .filter(|provider| {
+        provider
+            .name()
+            .map_or(false, |name| settings.is_provider_enabled(name))
+    })
+    .collect()
+}
+
+fn assign_inline_completion_providers(
     editor: &mut Editor,
-    provider: language::language_settings::InlineCompletionProvider,
+    providers: &[InlineCompletionProvider],
     telemetry: &Arc<Telemetry>,
     cx: &mut ViewContext<Editor>,
 ) {
-    match provider {
-        language::language_settings::InlineCompletionProvider::None => {}
-        language::language_settings::InlineCompletionProvider::Copilot => {
-            if let Some(copilot) = Copilot::global(cx) {
-                if let Some(buffer) = editor.buffer().read(cx).as_singleton() {
-                    if buffer.read(cx).file().is_some() {
-                        copilot.update(cx, |copilot, cx| {
-                            copilot.register_buffer(&buffer, cx);
-                        });
+    editor.clear_inline_completion_providers(cx);
+    for provider in providers {
+        match provider {
+            InlineCompletionProvider::None => {}
+            InlineCompletionProvider::Copilot => {
+                if let Some(copilot) = Copilot::global(cx) {
+                    if let Some(buffer) = editor.buffer().read(cx).as_singleton() {
+                        if buffer.read(cx).file().is_some() {
+                            copilot.update(cx, |copilot, cx| {
+                                copilot.register_buffer(&buffer, cx);
+                            });
+                        }
                     }
+                    let provider = cx.new_model(|_| {
+                        CopilotCompletionProvider::new(copilot).with_telemetry(telemetry.clone())
+                    });
+                    editor.register_inline_completion_provider(provider, cx);
                 }
-                let provider = cx.new_model(|_| {
-                    CopilotCompletionProvider::new(copilot).with_telemetry(telemetry.clone())
-                });
-                editor.set_inline_completion_provider(Some(provider), cx);
             }
-        }
-        language::language_settings::InlineCompletionProvider::Supermaven => {
-            if let Some(supermaven) = Supermaven::global(cx) {
-                let provider = cx.new_model(|_| {
-                    SupermavenCompletionProvider::new(supermaven).with_telemetry(telemetry.clone())
-                });
-                editor.set_inline_completion_provider(Some(provider), cx);
+            InlineCompletionProvider::Supermaven => {
+                if let Some(supermaven) = Supermaven::global(cx) {
+                    let provider = cx.new_model(|_| {
+                        SupermavenCompletionProvider::new(supermaven)
+                            .with_telemetry(telemetry.clone())
+                    });
+                    editor.register_inline_completion_provider(provider, cx);
+                }
             }
         }
     }
@@ -0,0 +1,248 @@
+//! A live view of the telemetry events Zed reports, with the payload of each and whether it's
+//! sent, and switches for which kinds of events are sent.
+
+use chrono::{DateTime, Local};
+use client::{
+    telemetry::{ReportedEvent, Telemetry},
+    TelemetrySettings, TelemetrySettingsContent,
+};
+use fs::Fs;
+use futures::StreamExt;
+use gpui::{
+    AnyElement, AppContext, EventEmitter, FocusHandle, FocusableView, Render, Task, View,
+    ViewContext, VisualContext, WindowContext,
+};
+use settings::Settings;
+use std::{collections::VecDeque, sync::Arc};
+use telemetry_events::EventCategory;
+use theme::ThemeSettings;
+use ui::{prelude::*, CheckboxWithLabel, Headline, HeadlineSize, Selection};
+use workspace::{
+    item::{Item, TabContentParams},
+    Workspace, WorkspaceId,
+};
+
+/// How many of the latest events are kept.
+const MAX_EVENTS: usize = 500;
+
+struct InspectedEvent {
+    reported_at: DateTime<Local>,
+    category: EventCategory,
+    payload: SharedString,
+    sent: bool,
+}
+
+pub struct TelemetryInspector {
+    /// The events reported since the inspector was opened, newest first.
+    events: VecDeque<InspectedEvent>,
+    telemetry: Arc<Telemetry>,
+    fs: Arc<dyn Fs>,
+    focus_handle: FocusHandle,
+    _observe_events: Task<()>,
+}
+
+/// Activates the workspace's telemetry inspector, opening one if there isn't one yet.
+pub fn open(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
+    if let Some(inspector) = workspace.item_of_type::<TelemetryInspector>(cx) {
+        workspace.activate_item(&inspector, cx);
+        return;
+    }
+    let telemetry = workspace.client().telemetry().clone();
+    let fs = workspace.app_state().fs.clone();
+    let inspector = cx.new_view(|cx| TelemetryInspector::new(telemetry, fs, cx));
+    workspace.add_item_to_active_pane(Box::new(inspector), None, cx);
+}
+
+impl TelemetryInspector {
+    fn new(telemetry: Arc<Telemetry>, fs: Arc<dyn Fs>, cx: &mut ViewContext<Self>) -> Self {
+        let mut events = telemetry.observe_events();
+        let observe_events = cx.spawn(|this, mut cx| async move {
+            while let Some(event) = events.next().await {
+                if this
+                    .update(&mut cx, |this, cx| this.push_event(event, cx))
+                    .is_err()
+                {
+                    break;
+                }
+            }
+        });
+
+        Self {
+            events: VecDeque::new(),
+            telemetry,
+            fs,
+            focus_handle: cx.focus_handle(),
+            _observe_events: observe_events,
+        }
+    }
+
+    fn push_event(&mut self, reported: ReportedEvent, cx: &mut ViewContext<Self>) {
+        let payload = serde_json::to_string_pretty(&reported.event).unwrap_or_default();
+        self.events.push_front(InspectedEvent {
+            reported_at: Local::now(),
+            category: reported.event.category(),
+            payload: payload.into(),
+            sent: reported.sent,
+        });
+        self.events.truncate(MAX_EVENTS);
+        cx.notify();
+    }
+
+    fn update_settings(
+        &self,
+        cx: &mut ViewContext<Self>,
+        update: impl 'static + Send + FnOnce(&mut TelemetrySettingsContent),
+    ) {
+        settings::update_settings_file::<TelemetrySettings>(self.fs.clone(), cx, update);
+    }
+
+    fn render_switches(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let settings = TelemetrySettings::get_global(cx).clone();
+        let selection = |selected: bool| {
+            if selected {
+                Selection::Selected
+            } else {
+                Selection::Unselected
+            }
+        };
+
+        v_flex()
+            .gap_1()
+            .child(CheckboxWithLabel::new(
+                "metrics",
+                Label::new("Send anonymous usage data"),
+                selection(settings.metrics),
+                cx.listener(|this, selection, cx| {
+                    let metrics = *selection == Selection::Selected;
+                    this.update_settings(cx, move |settings| settings.metrics = Some(metrics));
+                }),
+            ))
+            .child(h_flex().flex_wrap().gap_x_4().pl_4().children(
+                EventCategory::ALL.into_iter().map(|category| {
+                    CheckboxWithLabel::new(
+                        category.name(),
+                        Label::new(category.name()).color(if settings.metrics {
+                            Color::Default
+                        } else {
+                            Color::Muted
+                        }),
+                        selection(!settings.disabled_categories.contains(&category)),
+                        cx.listener(move |this, selection, cx| {
+                            let enabled = *selection == Selection::Selected;
+                            this.update_settings(cx, move |settings| {
+                                let disabled =
+                                    settings.disabled_categories.get_or_insert_with(Vec::new);
+                                disabled.retain(|disabled| *disabled != category);
+                                if !enabled {
+                                    disabled.push(category);
+                                }
+                            });
+                        }),
+                    )
+                }),
+            ))
+    }
+
+    fn render_event(&self, event: &InspectedEvent, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let buffer_font = ThemeSettings::get_global(cx).buffer_font.family.clone();
+        let colors = cx.theme().colors();
+
+        v_flex()
+            .gap_1()
+            .py_2()
+            .border_b_1()
+            .border_color(colors.border_variant)
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(
+                        Label::new(event.reported_at.format("%H:%M:%S").to_string())
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .child(Label::new(event.category.name()))
+                    .child(
+                        Label::new(if event.sent { "sent" } else { "not sent" })
+                            .size(LabelSize::Small)
+                            .color(if event.sent {
+                                Color::Success
+                            } else {
+                                Color::Muted
+                            }),
+                    ),
+            )
+            .child(
+                div()
+                    .font_family(buffer_font)
+                    .text_xs()
+                    .text_color(if event.sent {
+                        colors.text
+                    } else {
+                        colors.text_muted
+                    })
+                    .child(event.payload.clone()),
+            )
+    }
+}
+
+impl Render for TelemetryInspector {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let events = self
+            .events
+            .iter()
+            .map(|event| self.render_event(event, cx).into_any_element())
+            .collect::<Vec<_>>();
+
+        v_flex()
+            .id("telemetry-inspector")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .overflow_y_scroll()
+            .p_4()
+            .gap_4()
+            .bg(cx.theme().colors().editor_background)
+            .child(Headline::new("Telemetry Inspector").size(HeadlineSize::Small))
+            .child(
+                Label::new(
+                    "Events are shown as they're reported, with exactly what's sent for each. \
+                     Events of the kinds that are turned off are shown, but not sent.",
+                )
+                .color(Color::Muted),
+            )
+            .child(self.render_switches(cx))
+            .when(events.is_empty(), |this| {
+                this.child(Label::new("No events have been reported yet").color(Color::Muted))
+            })
+            .children(events)
+    }
+}
+
+impl EventEmitter<()> for TelemetryInspector {}
+
+impl FocusableView for TelemetryInspector {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for TelemetryInspector {
+    type Event = ();
+
+    fn tab_content(&self, params: TabContentParams, _: &WindowContext) -> AnyElement {
+        Label::new("Telemetry Inspector")
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn show_toolbar(&self) -> bool {
+        false
+    }
+
+    fn clone_on_split(&self, _: WorkspaceId, cx: &mut ViewContext<Self>) -> Option<View<Self>> {
+        Some(cx.new_view(|cx| Self::new(self.telemetry.clone(), self.fs.clone(), cx)))
+    }
+}
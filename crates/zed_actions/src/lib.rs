@@ -22,4 +22,4 @@ pub struct OpenZedUrl {
 
 impl_actions!(zed, [OpenBrowser, OpenZedUrl]);
 
-actions!(zed, [OpenSettings, Quit]);
+actions!(zed, [OpenSettings, Quit, ToggleOfflineMode]);